@@ -5,12 +5,14 @@ use tracing::{debug, error, instrument};
 
 use hyperlane_base::db::DbError;
 use hyperlane_core::{
-    accumulator::{incremental::IncrementalMerkle, merkle::Proof},
+    accumulator::{
+        incremental::IncrementalMerkle,
+        merkle::Proof,
+        prover::{Prover, ProverError},
+    },
     ChainCommunicationError, H256,
 };
 
-use crate::prover::{Prover, ProverError};
-
 /// Struct to sync prover.
 #[derive(Debug)]
 pub struct MerkleTreeBuilder {
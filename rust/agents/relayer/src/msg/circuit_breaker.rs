@@ -0,0 +1,222 @@
+use std::{collections::VecDeque, time::Instant};
+
+use prometheus::{IntCounter, IntGauge};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::settings::CircuitBreakerConf;
+
+/// Whether submissions to a destination are let through.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Submit as usual.
+    #[default]
+    Closed,
+    /// A single submission probes whether the destination recovered.
+    HalfOpen,
+    /// Submissions are paused until the cooldown elapsed.
+    Open,
+}
+
+impl CircuitState {
+    /// The value of the state in the `circuit_breaker_state` metric
+    fn metric_value(self) -> i64 {
+        match self {
+            Self::Closed => 0,
+            Self::HalfOpen => 1,
+            Self::Open => 2,
+        }
+    }
+}
+
+/// Pauses the submissions to a destination once too many of the recent ones
+/// failed, and resumes them once a probe submission succeeds.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    conf: CircuitBreakerConf,
+    /// Whether each of the most recent submissions failed, oldest first
+    outcomes: VecDeque<bool>,
+    state: CircuitState,
+    opened_at: Option<Instant>,
+    /// Whether the probe of the half-open breaker was submitted
+    probe_submitted: bool,
+    state_gauge: IntGauge,
+    trips: IntCounter,
+}
+
+impl CircuitBreaker {
+    pub fn new(conf: CircuitBreakerConf, state_gauge: IntGauge, trips: IntCounter) -> Self {
+        state_gauge.set(CircuitState::Closed.metric_value());
+        Self {
+            conf,
+            outcomes: VecDeque::new(),
+            state: CircuitState::Closed,
+            opened_at: None,
+            probe_submitted: false,
+            state_gauge,
+            trips,
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.state
+    }
+
+    /// The share of failed submissions among the recent ones
+    pub fn failure_rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        let failures = self.outcomes.iter().filter(|failed| **failed).count();
+        failures as f64 / self.outcomes.len() as f64
+    }
+
+    /// How many operations may be submitted at `now`, or `None` if there is
+    /// no limit. An open breaker lets a probe through once its cooldown
+    /// elapsed.
+    pub fn submission_limit(&mut self, now: Instant) -> Option<usize> {
+        if self.state == CircuitState::Open
+            && self
+                .opened_at
+                .map_or(true, |at| now.duration_since(at) >= self.conf.cooldown)
+        {
+            info!("Probing destination after circuit breaker cooldown");
+            self.probe_submitted = false;
+            self.set_state(CircuitState::HalfOpen);
+        }
+        match self.state {
+            CircuitState::Closed => None,
+            CircuitState::HalfOpen if !self.probe_submitted => Some(1),
+            CircuitState::HalfOpen | CircuitState::Open => Some(0),
+        }
+    }
+
+    /// Record that `count` operations were submitted
+    pub fn on_submitted(&mut self, count: usize) {
+        if self.state == CircuitState::HalfOpen && count > 0 {
+            self.probe_submitted = true;
+        }
+    }
+
+    /// Record whether a submitted operation was delivered. The breaker trips
+    /// once the failure rate over a full window reaches the configured one,
+    /// or if the probe failed.
+    pub fn record(&mut self, succeeded: bool, now: Instant) {
+        match self.state {
+            CircuitState::Closed => {
+                self.outcomes.push_back(!succeeded);
+                while self.outcomes.len() > self.conf.window {
+                    self.outcomes.pop_front();
+                }
+                if self.outcomes.len() >= self.conf.window
+                    && self.failure_rate() >= self.conf.failure_rate
+                {
+                    warn!(
+                        failure_rate = self.failure_rate(),
+                        cooldown=?self.conf.cooldown,
+                        "Too many submissions failed, pausing submissions to destination"
+                    );
+                    self.trips.inc();
+                    self.open(now);
+                }
+            }
+            CircuitState::HalfOpen if succeeded => {
+                info!("Probe submission succeeded, resuming submissions to destination");
+                self.close();
+            }
+            CircuitState::HalfOpen => {
+                warn!("Probe submission failed, pausing submissions to destination again");
+                self.open(now);
+            }
+            // Operations submitted before the breaker tripped
+            CircuitState::Open => {}
+        }
+    }
+
+    /// Resume submissions, e.g. when requested through the admin API
+    pub fn close(&mut self) {
+        self.outcomes.clear();
+        self.opened_at = None;
+        self.set_state(CircuitState::Closed);
+    }
+
+    fn open(&mut self, now: Instant) {
+        self.opened_at = Some(now);
+        self.set_state(CircuitState::Open);
+    }
+
+    fn set_state(&mut self, state: CircuitState) {
+        self.state = state;
+        self.state_gauge.set(state.metric_value());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_secs(60);
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new(
+            CircuitBreakerConf {
+                failure_rate: 0.5,
+                window: 4,
+                cooldown: COOLDOWN,
+            },
+            IntGauge::new("circuit_breaker_state", "help string").unwrap(),
+            IntCounter::new("circuit_breaker_trips", "help string").unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_trips_on_failure_rate() {
+        let mut breaker = breaker();
+        let now = Instant::now();
+
+        // Failures don't trip the breaker before the window is full
+        breaker.record(false, now);
+        breaker.record(false, now);
+        breaker.record(true, now);
+        assert_eq!(breaker.submission_limit(now), None);
+
+        breaker.record(true, now);
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert_eq!(breaker.submission_limit(now), Some(0));
+        assert_eq!(breaker.trips.get(), 1);
+        assert_eq!(breaker.state_gauge.get(), 2);
+    }
+
+    #[test]
+    fn test_probes_after_cooldown() {
+        let mut breaker = breaker();
+        let now = Instant::now();
+        for _ in 0..4 {
+            breaker.record(false, now);
+        }
+        assert_eq!(breaker.submission_limit(now + COOLDOWN / 2), Some(0));
+
+        // A single probe is let through
+        let later = now + COOLDOWN;
+        assert_eq!(breaker.submission_limit(later), Some(1));
+        breaker.on_submitted(1);
+        assert_eq!(breaker.submission_limit(later), Some(0));
+
+        // A failed probe pauses submissions for another cooldown
+        breaker.record(false, later);
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert_eq!(breaker.submission_limit(later + COOLDOWN / 2), Some(0));
+
+        // A successful probe resumes them
+        let even_later = later + COOLDOWN;
+        assert_eq!(breaker.submission_limit(even_later), Some(1));
+        breaker.on_submitted(1);
+        breaker.record(true, even_later);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(breaker.submission_limit(even_later), None);
+        assert_eq!(breaker.trips.get(), 1);
+    }
+}
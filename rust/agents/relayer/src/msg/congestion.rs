@@ -0,0 +1,238 @@
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use hyperlane_core::{Mailbox, MempoolCongestion};
+use tracing::{info, warn};
+
+/// How often the congestion signals of a destination are refreshed.
+const CONGESTION_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+/// The minimum time between new submissions while a destination is congested.
+const CONGESTED_SUBMISSION_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Our transactions are submitted one at a time and awaited, so any pending
+/// transaction between submissions is one that did not get included.
+const CONGESTED_PENDING_TXS: u64 = 1;
+const SATURATED_PENDING_TXS: u64 = 3;
+/// EIP-1559 raises the base fee by at most 12.5% per block, when blocks are full.
+const CONGESTED_BASE_FEE_SLOPE: f64 = 0.05;
+const SATURATED_BASE_FEE_SLOPE: f64 = 0.1;
+const CONGESTED_EXPIRED_TXS: u64 = 1;
+const SATURATED_EXPIRED_TXS: u64 = 3;
+
+/// How congested the mempool of a destination is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CongestionLevel {
+    /// Submit as usual.
+    #[default]
+    Clear,
+    /// Keep replacing our own stuck transactions, but slow down new submissions.
+    Congested,
+    /// Only replace our own stuck transactions, don't submit new ones.
+    Saturated,
+}
+
+impl CongestionLevel {
+    /// The most severe level indicated by any of the signals.
+    pub fn from_signals(signals: &MempoolCongestion) -> Self {
+        let level = |value: Option<f64>, congested: f64, saturated: f64| match value {
+            Some(v) if v >= saturated => Self::Saturated,
+            Some(v) if v >= congested => Self::Congested,
+            _ => Self::Clear,
+        };
+        [
+            level(
+                signals.pending_txs.map(|n| n as f64),
+                CONGESTED_PENDING_TXS as f64,
+                SATURATED_PENDING_TXS as f64,
+            ),
+            level(
+                signals.base_fee_slope,
+                CONGESTED_BASE_FEE_SLOPE,
+                SATURATED_BASE_FEE_SLOPE,
+            ),
+            level(
+                signals.expired_txs.map(|n| n as f64),
+                CONGESTED_EXPIRED_TXS as f64,
+                SATURATED_EXPIRED_TXS as f64,
+            ),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or_default()
+    }
+}
+
+/// Applies back-pressure to the submissions to a destination based on the
+/// congestion signals of its mempool, so that we don't keep pushing
+/// transactions into a mempool that is already saturated.
+pub struct CongestionMonitor {
+    mailbox: Arc<dyn Mailbox>,
+    level: CongestionLevel,
+    last_checked_at: Option<Instant>,
+    next_new_submission_at: Option<Instant>,
+}
+
+impl Debug for CongestionMonitor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // intentionally leaves out the mailbox
+        f.debug_struct("CongestionMonitor")
+            .field("level", &self.level)
+            .field("last_checked_at", &self.last_checked_at)
+            .finish()
+    }
+}
+
+impl CongestionMonitor {
+    pub fn new(mailbox: Arc<dyn Mailbox>) -> Self {
+        Self {
+            mailbox,
+            level: CongestionLevel::default(),
+            last_checked_at: None,
+            next_new_submission_at: None,
+        }
+    }
+
+    pub fn level(&self) -> CongestionLevel {
+        self.level
+    }
+
+    /// Re-read the congestion signals of the destination if they are stale.
+    /// On failure the previous level is kept.
+    pub async fn refresh(&mut self) {
+        let now = Instant::now();
+        if self
+            .last_checked_at
+            .map(|at| now.duration_since(at) < CONGESTION_CHECK_INTERVAL)
+            .unwrap_or(false)
+        {
+            return;
+        }
+        self.last_checked_at = Some(now);
+
+        match self.mailbox.mempool_congestion().await {
+            Ok(signals) => {
+                let level = CongestionLevel::from_signals(&signals);
+                if level != self.level {
+                    info!(?signals, ?level, previous_level=?self.level, "Destination congestion level changed");
+                }
+                self.level = level;
+            }
+            Err(err) => {
+                warn!(error=?err, level=?self.level, "Failed to check destination mempool congestion, keeping previous level");
+            }
+        }
+    }
+
+    /// Split `ops` into the ones to submit now and the ones to defer. Replacements
+    /// of our own transactions always go first and are never deferred.
+    pub fn admit<T>(
+        &mut self,
+        ops: Vec<T>,
+        is_replacement: impl Fn(&T) -> bool,
+        now: Instant,
+    ) -> (Vec<T>, Vec<T>) {
+        if self.level == CongestionLevel::Clear {
+            return (ops, vec![]);
+        }
+        let (mut admitted, mut new_ops): (Vec<_>, Vec<_>) =
+            ops.into_iter().partition(|op| is_replacement(op));
+        let may_submit_new = self.level == CongestionLevel::Congested
+            && self
+                .next_new_submission_at
+                .map(|at| now >= at)
+                .unwrap_or(true);
+        if may_submit_new && !new_ops.is_empty() {
+            admitted.push(new_ops.remove(0));
+            self.next_new_submission_at = Some(now + CONGESTED_SUBMISSION_INTERVAL);
+        }
+        (admitted, new_ops)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_test::mocks::MockMailboxContract;
+
+    use super::*;
+
+    fn monitor(level: CongestionLevel) -> CongestionMonitor {
+        let mut monitor = CongestionMonitor::new(Arc::new(MockMailboxContract::default()));
+        monitor.level = level;
+        monitor
+    }
+
+    /// Ops are `(id, is_replacement)`
+    fn admit(
+        monitor: &mut CongestionMonitor,
+        ops: &[(u32, bool)],
+        now: Instant,
+    ) -> (Vec<u32>, Vec<u32>) {
+        let (admitted, deferred) = monitor.admit(ops.to_vec(), |(_, r)| *r, now);
+        (
+            admitted.into_iter().map(|(id, _)| id).collect(),
+            deferred.into_iter().map(|(id, _)| id).collect(),
+        )
+    }
+
+    #[test]
+    fn test_level_from_signals() {
+        assert_eq!(
+            CongestionLevel::from_signals(&MempoolCongestion::default()),
+            CongestionLevel::Clear
+        );
+        assert_eq!(
+            CongestionLevel::from_signals(&MempoolCongestion {
+                pending_txs: Some(0),
+                base_fee_slope: Some(0.06),
+                expired_txs: None,
+            }),
+            CongestionLevel::Congested
+        );
+        assert_eq!(
+            CongestionLevel::from_signals(&MempoolCongestion {
+                pending_txs: Some(1),
+                base_fee_slope: Some(-0.1),
+                expired_txs: Some(5),
+            }),
+            CongestionLevel::Saturated
+        );
+    }
+
+    #[test]
+    fn test_clear_admits_everything() {
+        let mut monitor = monitor(CongestionLevel::Clear);
+        let ops = [(1, false), (2, true), (3, false)];
+        assert_eq!(
+            admit(&mut monitor, &ops, Instant::now()),
+            (vec![1, 2, 3], vec![])
+        );
+    }
+
+    #[test]
+    fn test_congested_paces_new_submissions() {
+        let mut monitor = monitor(CongestionLevel::Congested);
+        let now = Instant::now();
+        let ops = [(1, false), (2, true), (3, false)];
+
+        assert_eq!(admit(&mut monitor, &ops, now), (vec![2, 1], vec![3]));
+        // Replacements still go through before the interval has passed
+        assert_eq!(admit(&mut monitor, &ops, now), (vec![2], vec![1, 3]));
+        assert_eq!(
+            admit(&mut monitor, &ops, now + CONGESTED_SUBMISSION_INTERVAL),
+            (vec![2, 1], vec![3])
+        );
+    }
+
+    #[test]
+    fn test_saturated_only_admits_replacements() {
+        let mut monitor = monitor(CongestionLevel::Saturated);
+        let ops = [(1, false), (2, true), (3, false)];
+        assert_eq!(
+            admit(&mut monitor, &ops, Instant::now()),
+            (vec![2], vec![1, 3])
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use std::{fmt::Debug, time::Duration};
+
+use async_trait::async_trait;
+use eyre::Result;
+use hyperlane_core::H256;
+use sea_orm::{ConnectionTrait, Database, DbBackend, DbConn, Statement};
+use tracing::{info, instrument};
+
+use crate::settings::CoordinationConf;
+
+const CREATE_CLAIMS_TABLE: &str = "CREATE TABLE IF NOT EXISTS relayer_message_claims (
+    message_id BYTEA PRIMARY KEY,
+    instance_id TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+)";
+
+/// Inserts a claim, or takes over an existing one if it's held by the same
+/// instance or expired. Returns a row iff the claim is held afterwards.
+const CLAIM_MESSAGE: &str =
+    "INSERT INTO relayer_message_claims (message_id, instance_id, expires_at)
+    VALUES ($1, $2, now() + make_interval(secs => $3))
+    ON CONFLICT (message_id) DO UPDATE
+        SET instance_id = EXCLUDED.instance_id, expires_at = EXCLUDED.expires_at
+        WHERE relayer_message_claims.instance_id = EXCLUDED.instance_id
+            OR relayer_message_claims.expires_at < now()
+    RETURNING message_id";
+
+/// Deletes the claim on a message that no longer needs to be claimed
+const RELEASE_MESSAGE: &str = "DELETE FROM relayer_message_claims WHERE message_id = $1";
+
+/// Deletes the claims left behind by instances that stopped before releasing them
+const DELETE_EXPIRED_CLAIMS: &str = "DELETE FROM relayer_message_claims WHERE expires_at < now()";
+
+/// Leases on the messages that relayer instances claim before preparing
+/// and submitting them, so that instances running active-active don't submit
+/// the same messages.
+#[async_trait]
+pub trait MessageClaims: Debug + Send + Sync {
+    /// Claim the message for this instance, or renew its claim. Returns
+    /// false if another instance holds an unexpired claim on it.
+    async fn claim(&self, message_id: H256) -> Result<bool>;
+
+    /// Release the claim on a message once it was delivered or dropped,
+    /// whichever instance holds it.
+    async fn release(&self, message_id: H256) -> Result<()>;
+
+    /// How long a claim lasts unless it's renewed
+    fn lease(&self) -> Duration;
+}
+
+/// Claims stored in a Postgres database shared by the relayer instances
+#[derive(Debug)]
+pub struct PostgresMessageClaims {
+    db: DbConn,
+    instance_id: String,
+    lease: Duration,
+}
+
+impl PostgresMessageClaims {
+    #[instrument(skip_all, fields(instance_id = %conf.instance_id))]
+    pub async fn connect(conf: &CoordinationConf) -> Result<Self> {
+        let db = Database::connect(&conf.database_url).await?;
+        db.execute(Statement::from_string(
+            DbBackend::Postgres,
+            CREATE_CLAIMS_TABLE.to_owned(),
+        ))
+        .await?;
+        let expired = db
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                DELETE_EXPIRED_CLAIMS.to_owned(),
+            ))
+            .await?
+            .rows_affected();
+        info!(lease=?conf.lease, expired, "Coordinating with other relayer instances");
+        Ok(Self {
+            db,
+            instance_id: conf.instance_id.clone(),
+            lease: conf.lease,
+        })
+    }
+}
+
+#[async_trait]
+impl MessageClaims for PostgresMessageClaims {
+    async fn claim(&self, message_id: H256) -> Result<bool> {
+        let claimed = self
+            .db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                CLAIM_MESSAGE,
+                [
+                    message_id.as_bytes().to_vec().into(),
+                    self.instance_id.clone().into(),
+                    self.lease.as_secs_f64().into(),
+                ],
+            ))
+            .await?;
+        Ok(claimed.is_some())
+    }
+
+    async fn release(&self, message_id: H256) -> Result<()> {
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                RELEASE_MESSAGE,
+                [message_id.as_bytes().to_vec().into()],
+            ))
+            .await?;
+        Ok(())
+    }
+
+    fn lease(&self) -> Duration {
+        self.lease
+    }
+}
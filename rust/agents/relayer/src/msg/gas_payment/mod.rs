@@ -1,15 +1,16 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
 use eyre::Result;
 use hyperlane_base::db::HyperlaneRocksDB;
 use hyperlane_core::{
     FixedPointNumber, GasPaymentKey, HyperlaneMessage, InterchainGasExpenditure,
-    InterchainGasPayment, TxCostEstimate, TxOutcome, U256,
+    InterchainGasPaymaster, InterchainGasPayment, TxCostEstimate, TxOutcome, U256,
 };
+use tokio::sync::RwLock;
 use tracing::{debug, error, trace};
 
-use self::policies::{GasPaymentPolicyMinimum, GasPaymentPolicyNone};
+use self::policies::{GasPaymentPolicyIgpQuote, GasPaymentPolicyMinimum, GasPaymentPolicyNone};
 use crate::{
     msg::gas_payment::policies::GasPaymentPolicyOnChainFeeQuoting,
     settings::{
@@ -41,38 +42,63 @@ pub struct GasPaymentEnforcer {
     /// use a wild-card white list to ensure all messages fall into one
     /// policy or another. If a message matches multiple policies'
     /// whitelists, then whichever is first in the list will be used.
-    policies: Vec<(Box<dyn GasPaymentPolicy>, MatchingList)>,
+    /// They can be replaced while the relayer is running.
+    policies: RwLock<Vec<(Box<dyn GasPaymentPolicy>, MatchingList)>>,
+    origin_igp: Option<Arc<dyn InterchainGasPaymaster>>,
     db: HyperlaneRocksDB,
 }
 
 impl GasPaymentEnforcer {
     /// Note that `policy_configs` should not be empty. In the settings,
     /// a default of vec![GasPaymentEnforcementConf::default()] is used.
+    /// `origin_igp` is only needed by policies that quote payments.
     pub fn new(
         policy_configs: impl IntoIterator<Item = GasPaymentEnforcementConf>,
+        origin_igp: Option<Arc<dyn InterchainGasPaymaster>>,
         db: HyperlaneRocksDB,
     ) -> Self {
-        let policies = policy_configs
-            .into_iter()
-            .map(|cfg| {
-                let p: Box<dyn GasPaymentPolicy> = match cfg.policy {
-                    GasPaymentEnforcementPolicy::None => Box::new(GasPaymentPolicyNone),
-                    GasPaymentEnforcementPolicy::Minimum { payment } => {
-                        Box::new(GasPaymentPolicyMinimum::new(payment))
-                    }
-                    GasPaymentEnforcementPolicy::OnChainFeeQuoting {
-                        gas_fraction_numerator: n,
-                        gas_fraction_denominator: d,
-                    } => Box::new(GasPaymentPolicyOnChainFeeQuoting::new(n, d)),
-                };
-                (p, cfg.matching_list)
-            })
-            .collect();
-
-        Self { policies, db }
+        let policies = build_policies(policy_configs, &origin_igp);
+        Self {
+            policies: RwLock::new(policies),
+            origin_igp,
+            db,
+        }
+    }
+
+    /// Replaces the policies, e.g. when the config is reloaded
+    pub async fn set_policies(
+        &self,
+        policy_configs: impl IntoIterator<Item = GasPaymentEnforcementConf>,
+    ) {
+        *self.policies.write().await = build_policies(policy_configs, &self.origin_igp);
     }
 }
 
+fn build_policies(
+    policy_configs: impl IntoIterator<Item = GasPaymentEnforcementConf>,
+    origin_igp: &Option<Arc<dyn InterchainGasPaymaster>>,
+) -> Vec<(Box<dyn GasPaymentPolicy>, MatchingList)> {
+    policy_configs
+        .into_iter()
+        .map(|cfg| {
+            let p: Box<dyn GasPaymentPolicy> = match cfg.policy {
+                GasPaymentEnforcementPolicy::None => Box::new(GasPaymentPolicyNone),
+                GasPaymentEnforcementPolicy::Minimum { payment } => {
+                    Box::new(GasPaymentPolicyMinimum::new(payment))
+                }
+                GasPaymentEnforcementPolicy::OnChainFeeQuoting {
+                    gas_fraction_numerator: n,
+                    gas_fraction_denominator: d,
+                } => Box::new(GasPaymentPolicyOnChainFeeQuoting::new(n, d)),
+                GasPaymentEnforcementPolicy::IgpQuote { margin_bps } => Box::new(
+                    GasPaymentPolicyIgpQuote::new(origin_igp.clone(), margin_bps),
+                ),
+            };
+            (p, cfg.matching_list)
+        })
+        .collect()
+}
+
 impl GasPaymentEnforcer {
     /// Returns Some(gas_limit) if the enforcer has approved the transaction or
     /// None if the transaction is not approved.
@@ -91,7 +117,8 @@ impl GasPaymentEnforcer {
             .retrieve_gas_payment_by_gas_payment_key(gas_payment_key)?;
         let current_expenditure = self.db.retrieve_gas_expenditure_by_message_id(msg_id)?;
 
-        for (policy, whitelist) in &self.policies {
+        let policies = self.policies.read().await;
+        for (policy, whitelist) in policies.iter() {
             if !whitelist.msg_matches(message, true) {
                 trace!(
                     msg=%message,
@@ -127,7 +154,7 @@ impl GasPaymentEnforcer {
 
         error!(
             msg=%message,
-            policies=?self.policies,
+            policies=?*policies,
             "No gas payment policy matched for message; consider adding a default policy to the end of the policies array which uses a wildcard whitelist."
         );
         Ok(None)
@@ -182,6 +209,7 @@ mod test {
                     },
                     matching_list: Default::default(),
                 }],
+                None,
                 hyperlane_db,
             );
 
@@ -201,6 +229,41 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_replaced_policies() {
+        test_utils::run_test_db(|db| async move {
+            let hyperlane_db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("test_replaced_policies"),
+                db,
+            );
+            let enforcer = GasPaymentEnforcer::new(
+                // Require a payment
+                vec![GasPaymentEnforcementConf {
+                    policy: GasPaymentEnforcementPolicy::Minimum {
+                        payment: U256::one(),
+                    },
+                    matching_list: Default::default(),
+                }],
+                None,
+                hyperlane_db,
+            );
+            enforcer
+                .set_policies(vec![GasPaymentEnforcementConf::default()])
+                .await;
+
+            // The unpaid message meets the requirement of the new policy
+            assert!(enforcer
+                .message_meets_gas_payment_requirement(
+                    &HyperlaneMessage::default(),
+                    &TxCostEstimate::default(),
+                )
+                .await
+                .unwrap()
+                .is_some());
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn test_no_match() {
         #[allow(unused_must_use)]
@@ -214,6 +277,7 @@ mod test {
                     policy: GasPaymentEnforcementPolicy::None,
                     matching_list,
                 }],
+                None,
                 hyperlane_db,
             );
 
@@ -250,6 +314,7 @@ mod test {
                     },
                     matching_list: MatchingList::default(),
                 }],
+                None,
                 hyperlane_db.clone(),
             );
 
@@ -307,6 +372,7 @@ mod test {
                     },
                     matching_list: MatchingList::default(),
                 }],
+                None,
                 hyperlane_db.clone(),
             );
 
@@ -369,6 +435,7 @@ mod test {
                         matching_list: MatchingList::default(),
                     },
                 ],
+                None,
                 hyperlane_db,
             );
 
@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use eyre::{eyre, Result};
+
+use hyperlane_core::{
+    HyperlaneMessage, InterchainGasExpenditure, InterchainGasPaymaster, InterchainGasPayment,
+    TxCostEstimate, U256,
+};
+use tracing::debug;
+
+use crate::msg::gas_payment::GasPaymentPolicy;
+
+/// Requires the payment to cover what the IGP of the origin currently quotes
+/// for the gas of the message, which follows the gas oracle of the
+/// destination.
+#[derive(Debug)]
+pub struct GasPaymentPolicyIgpQuote {
+    /// The IGP of the origin, if it could be built
+    igp: Option<Arc<dyn InterchainGasPaymaster>>,
+    /// Basis points added to the quote before comparing it with the payment
+    margin_bps: u32,
+}
+
+impl GasPaymentPolicyIgpQuote {
+    pub fn new(igp: Option<Arc<dyn InterchainGasPaymaster>>, margin_bps: u32) -> Self {
+        Self { igp, margin_bps }
+    }
+}
+
+#[async_trait]
+impl GasPaymentPolicy for GasPaymentPolicyIgpQuote {
+    async fn message_meets_gas_payment_requirement(
+        &self,
+        message: &HyperlaneMessage,
+        current_payment: &InterchainGasPayment,
+        current_expenditure: &InterchainGasExpenditure,
+        tx_cost_estimate: &TxCostEstimate,
+    ) -> Result<Option<U256>> {
+        let igp = self
+            .igp
+            .as_ref()
+            .ok_or_else(|| eyre!("No interchain gas paymaster to quote gas payments with"))?;
+        // Gas spent by earlier attempts has to be paid for as well
+        let gas_amount = tx_cost_estimate
+            .enforceable_gas_limit()?
+            .saturating_add(current_expenditure.gas_used);
+        let quote = igp
+            .quote_gas_payment(message.destination, gas_amount)
+            .await?;
+        let required = quote.saturating_mul(U256::from(10_000 + self.margin_bps as u64)) / 10_000;
+        debug!(
+            ?gas_amount,
+            ?quote,
+            ?required,
+            payment = ?current_payment.payment,
+            "Compared gas payment with IGP quote"
+        );
+        if current_payment.payment >= required {
+            Ok(Some(tx_cost_estimate.gas_limit))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{
+        ChainResult, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneProvider, H256,
+    };
+
+    use super::*;
+
+    const DESTINATION: u32 = 42;
+
+    /// Quotes 2 tokens per gas
+    #[derive(Debug)]
+    struct MockIgp;
+
+    impl HyperlaneContract for MockIgp {
+        fn address(&self) -> H256 {
+            H256::zero()
+        }
+    }
+
+    impl HyperlaneChain for MockIgp {
+        fn domain(&self) -> &HyperlaneDomain {
+            unimplemented!()
+        }
+
+        fn provider(&self) -> Box<dyn HyperlaneProvider> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl InterchainGasPaymaster for MockIgp {
+        async fn quote_gas_payment(
+            &self,
+            destination_domain: u32,
+            gas_amount: U256,
+        ) -> ChainResult<U256> {
+            assert_eq!(destination_domain, DESTINATION);
+            Ok(gas_amount * 2)
+        }
+    }
+
+    fn payment(payment: u32) -> InterchainGasPayment {
+        InterchainGasPayment {
+            message_id: H256::zero(),
+            destination: 0,
+            payment: payment.into(),
+            gas_amount: U256::zero(),
+        }
+    }
+
+    fn expenditure(gas_used: u32) -> InterchainGasExpenditure {
+        InterchainGasExpenditure {
+            message_id: H256::zero(),
+            gas_used: gas_used.into(),
+            tokens_used: U256::zero(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_payment_covers_quote() {
+        let policy = GasPaymentPolicyIgpQuote::new(Some(Arc::new(MockIgp)), 1_000);
+        let message = HyperlaneMessage {
+            destination: DESTINATION,
+            ..Default::default()
+        };
+        let estimate = TxCostEstimate {
+            gas_limit: 1000.into(),
+            ..Default::default()
+        };
+        let meets = |payment_amount, gas_used| {
+            let (payment, expenditure) = (payment(payment_amount), expenditure(gas_used));
+            let (policy, message, estimate) = (&policy, &message, &estimate);
+            async move {
+                policy
+                    .message_meets_gas_payment_requirement(
+                        message,
+                        &payment,
+                        &expenditure,
+                        estimate,
+                    )
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // The quote of 2000 plus a 10% margin
+        assert_eq!(meets(2199, 0).await, None);
+        assert_eq!(meets(2200, 0).await, Some(estimate.gas_limit));
+        // Gas that was already spent is quoted as well
+        assert_eq!(meets(2200, 100).await, None);
+        assert_eq!(meets(2420, 100).await, Some(estimate.gas_limit));
+    }
+
+    #[tokio::test]
+    async fn test_missing_igp() {
+        let policy = GasPaymentPolicyIgpQuote::new(None, 0);
+        assert!(policy
+            .message_meets_gas_payment_requirement(
+                &HyperlaneMessage::default(),
+                &payment(0),
+                &expenditure(0),
+                &TxCostEstimate::default(),
+            )
+            .await
+            .is_err());
+    }
+}
@@ -45,7 +45,7 @@ impl GasPaymentPolicy for GasPaymentPolicyOnChainFeeQuoting {
         current_expenditure: &InterchainGasExpenditure,
         tx_cost_estimate: &TxCostEstimate,
     ) -> Result<Option<U256>> {
-        let fractional_gas_estimate = (tx_cost_estimate.enforceable_gas_limit()
+        let fractional_gas_estimate = (tx_cost_estimate.enforceable_gas_limit()?
             * self.fractional_numerator)
             / self.fractional_denominator;
         let gas_amount = current_payment
@@ -63,7 +63,7 @@ impl GasPaymentPolicy for GasPaymentPolicyOnChainFeeQuoting {
 
 #[cfg(test)]
 mod test {
-    use hyperlane_core::H256;
+    use hyperlane_core::{ResourceCost, TxResource, H256};
     use once_cell::sync::Lazy;
 
     use super::*;
@@ -90,6 +90,7 @@ mod test {
         gas_limit: U256([2000, 0, 0, 0]), // MIN * 2
         gas_price: U256([100001, 0, 0, 0]).try_into().unwrap(),
         l2_gas_limit: None,
+        resources: vec![],
     });
 
     #[test]
@@ -206,6 +207,7 @@ mod test {
             gas_limit: MIN * 100, // Large gas limit
             gas_price: COST_ESTIMATE.gas_price.clone(),
             l2_gas_limit: Some(MIN * 2),
+            resources: vec![],
         };
 
         // First ensure that if l2_gas_limit is None, because of the high gas limit,
@@ -239,4 +241,53 @@ mod test {
             Some(tx_cost_estimate.gas_limit),
         );
     }
+
+    #[tokio::test]
+    async fn test_multi_dimensional_cost() {
+        let policy = GasPaymentPolicyOnChainFeeQuoting::default();
+        let message = HyperlaneMessage::default();
+
+        // Costs 1000 gas of computation and 1000 gas worth of storage, half
+        // of which is rebated, despite the large gas limit
+        let tx_cost_estimate = TxCostEstimate {
+            gas_limit: MIN * 100,
+            gas_price: 1000u32.into(),
+            l2_gas_limit: None,
+            resources: vec![
+                ResourceCost::new(TxResource::Computation, 1000, 1000u32),
+                ResourceCost::new(TxResource::Storage, 1_000_000, 1u32),
+                ResourceCost::new(TxResource::StorageRebate, 500_000, 1u32),
+            ],
+        };
+        assert_eq!(
+            tx_cost_estimate.enforceable_gas_limit().unwrap(),
+            U256::from(1500u32)
+        );
+
+        assert_eq!(
+            policy
+                .message_meets_gas_payment_requirement(
+                    &message,
+                    &current_payment(749),
+                    &current_expenditure(0),
+                    &tx_cost_estimate,
+                )
+                .await
+                .unwrap(),
+            None
+        );
+        // The transaction is still submitted with the full gas limit
+        assert_eq!(
+            policy
+                .message_meets_gas_payment_requirement(
+                    &message,
+                    &current_payment(750),
+                    &current_expenditure(0),
+                    &tx_cost_estimate,
+                )
+                .await
+                .unwrap(),
+            Some(tx_cost_estimate.gas_limit),
+        );
+    }
 }
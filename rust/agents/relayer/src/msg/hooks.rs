@@ -0,0 +1,241 @@
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use hyperlane_core::{HyperlaneMessage, H256};
+
+use crate::settings::{matching_list::MatchingList, MessageHookConf};
+
+/// What a hook decided about a message that's about to be prepared
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookVerdict {
+    /// Prepare the message as usual
+    Proceed,
+    /// Prepare the message, noting why it's of interest
+    Annotate(String),
+    /// Prepare the message once this much time elapsed
+    Defer(Duration),
+    /// Park the message, which is only prepared again once it's retried
+    /// through the API
+    Veto(String),
+}
+
+/// Checks messages before their metadata is built, e.g. to enforce rate
+/// limits of apps or compliance filters. Hooks run in the order they're
+/// configured, until one defers or vetoes the message.
+#[async_trait]
+pub trait MessageHook: Debug + Send + Sync {
+    /// Identifies the hook in logs
+    fn name(&self) -> &str;
+
+    /// Check `message`, which may be checked again each time it's prepared
+    async fn check(&self, message: &HyperlaneMessage) -> HookVerdict;
+}
+
+/// Build the hooks configured in the relayer settings, in order
+pub fn build_message_hooks(confs: &[MessageHookConf]) -> Vec<Arc<dyn MessageHook>> {
+    confs
+        .iter()
+        .map(|conf| -> Arc<dyn MessageHook> {
+            match conf.clone() {
+                MessageHookConf::Deny {
+                    matching_list,
+                    reason,
+                } => Arc::new(DenyHook {
+                    matching_list,
+                    reason,
+                }),
+                MessageHookConf::RateLimit {
+                    matching_list,
+                    max_messages,
+                    window,
+                } => Arc::new(RateLimitHook::new(matching_list, max_messages, window)),
+                MessageHookConf::Annotate {
+                    matching_list,
+                    note,
+                } => Arc::new(AnnotateHook {
+                    matching_list,
+                    note,
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Vetoes the messages that match, e.g. ones from sanctioned senders
+#[derive(Debug)]
+pub struct DenyHook {
+    matching_list: MatchingList,
+    reason: String,
+}
+
+#[async_trait]
+impl MessageHook for DenyHook {
+    fn name(&self) -> &str {
+        "deny"
+    }
+
+    async fn check(&self, message: &HyperlaneMessage) -> HookVerdict {
+        if self.matching_list.msg_matches(message, false) {
+            HookVerdict::Veto(self.reason.clone())
+        } else {
+            HookVerdict::Proceed
+        }
+    }
+}
+
+/// Defers the messages that match once `max_messages` of them were let
+/// through within the sliding `window`
+#[derive(Debug)]
+pub struct RateLimitHook {
+    matching_list: MatchingList,
+    max_messages: u32,
+    window: Duration,
+    /// The messages let through within the window, oldest first
+    let_through: Mutex<VecDeque<(Instant, H256)>>,
+}
+
+impl RateLimitHook {
+    pub fn new(matching_list: MatchingList, max_messages: u32, window: Duration) -> Self {
+        Self {
+            matching_list,
+            max_messages,
+            window,
+            let_through: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn check_at(&self, message: &HyperlaneMessage, now: Instant) -> HookVerdict {
+        if !self.matching_list.msg_matches(message, false) {
+            return HookVerdict::Proceed;
+        }
+        let mut let_through = self.let_through.lock().expect("rate limit lock poisoned");
+        while let_through
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) >= self.window)
+        {
+            let_through.pop_front();
+        }
+        // Messages prepared again aren't counted twice
+        let id = message.id();
+        if let_through
+            .iter()
+            .any(|(_, let_through_id)| *let_through_id == id)
+        {
+            return HookVerdict::Proceed;
+        }
+        if let_through.len() < self.max_messages as usize {
+            let_through.push_back((now, id));
+            return HookVerdict::Proceed;
+        }
+        let oldest = let_through.front().map_or(now, |(at, _)| *at);
+        HookVerdict::Defer(self.window.saturating_sub(now.duration_since(oldest)))
+    }
+}
+
+#[async_trait]
+impl MessageHook for RateLimitHook {
+    fn name(&self) -> &str {
+        "rateLimit"
+    }
+
+    async fn check(&self, message: &HyperlaneMessage) -> HookVerdict {
+        self.check_at(message, Instant::now())
+    }
+}
+
+/// Notes why the messages that match are of interest, e.g. to audit them
+#[derive(Debug)]
+pub struct AnnotateHook {
+    matching_list: MatchingList,
+    note: String,
+}
+
+#[async_trait]
+impl MessageHook for AnnotateHook {
+    fn name(&self) -> &str {
+        "annotate"
+    }
+
+    async fn check(&self, message: &HyperlaneMessage) -> HookVerdict {
+        if self.matching_list.msg_matches(message, false) {
+            HookVerdict::Annotate(self.note.clone())
+        } else {
+            HookVerdict::Proceed
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sender_list(sender: H256) -> MatchingList {
+        serde_json::from_str(&format!(r#"[{{"senderaddress": "{sender:?}"}}]"#)).unwrap()
+    }
+
+    fn message(nonce: u32, sender: H256) -> HyperlaneMessage {
+        HyperlaneMessage {
+            nonce,
+            sender,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deny_hook() {
+        let denied = H256::repeat_byte(1);
+        let hook = DenyHook {
+            matching_list: sender_list(denied),
+            reason: "sanctioned sender".to_owned(),
+        };
+        assert_eq!(
+            hook.check(&message(0, denied)).await,
+            HookVerdict::Veto("sanctioned sender".to_owned())
+        );
+        assert_eq!(
+            hook.check(&message(0, H256::repeat_byte(2))).await,
+            HookVerdict::Proceed
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_hook() {
+        let sender = H256::repeat_byte(1);
+        let window = Duration::from_secs(60);
+        let hook = RateLimitHook::new(sender_list(sender), 2, window);
+        let now = Instant::now();
+
+        assert_eq!(
+            hook.check_at(&message(0, sender), now),
+            HookVerdict::Proceed
+        );
+        assert_eq!(
+            hook.check_at(&message(1, sender), now + window / 2),
+            HookVerdict::Proceed
+        );
+        // Preparing a message again doesn't count against the limit
+        assert_eq!(
+            hook.check_at(&message(1, sender), now + window / 2),
+            HookVerdict::Proceed
+        );
+        assert_eq!(
+            hook.check_at(&message(2, sender), now + window / 2),
+            HookVerdict::Defer(window / 2)
+        );
+        // Other apps aren't limited
+        assert_eq!(
+            hook.check_at(&message(2, H256::repeat_byte(2)), now + window / 2),
+            HookVerdict::Proceed
+        );
+        // The oldest message leaves the window
+        assert_eq!(
+            hook.check_at(&message(2, sender), now + window),
+            HookVerdict::Proceed
+        );
+    }
+}
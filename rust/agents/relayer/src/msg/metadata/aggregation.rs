@@ -1,15 +1,17 @@
+use std::future::Future;
+use std::time::Duration;
+
 use async_trait::async_trait;
 use derive_more::Deref;
-use futures_util::future::join_all;
+use futures_util::stream::{FuturesUnordered, StreamExt};
 
 use derive_new::new;
 use eyre::Context;
-use itertools::{Either, Itertools};
 use tracing::{info, instrument};
 
-use hyperlane_core::{HyperlaneMessage, InterchainSecurityModule, ModuleType, H256, U256};
+use hyperlane_core::{HyperlaneMessage, ModuleType, H256, U256};
 
-use super::{MessageMetadataBuilder, MetadataBuilder};
+use super::{MessageMetadataBuilder, MetadataBuilder, MetadataBuilderError};
 
 /// Bytes used to store one member of the (start, end) range tuple
 /// Copied from `AggregationIsmMetadata.sol`
@@ -28,19 +30,38 @@ struct SubModuleMetadata {
     metadata: Vec<u8>,
 }
 
+/// An ISM for which no valid metadata could be built, along with its type if
+/// it could be fetched.
+type ErrIsm = (H256, Option<ModuleType>);
+
+/// Why no valid metadata could be built for a sub-module
 #[derive(Debug)]
-struct IsmAndMetadata {
-    ism: Box<dyn InterchainSecurityModule>,
-    meta: SubModuleMetadata,
+enum SubModuleError {
+    /// The metadata can't be built or doesn't verify
+    Invalid(ErrIsm),
+    /// The sub-module is an optimistic ISM that the message has to be
+    /// pre-verified with, or whose fraud window it's in
+    FraudWindow(MetadataBuilderError),
 }
 
-impl IsmAndMetadata {
-    fn new(ism: Box<dyn InterchainSecurityModule>, index: usize, metadata: Vec<u8>) -> Self {
-        Self {
-            ism,
-            meta: SubModuleMetadata::new(index, metadata),
+/// Polls `futures` concurrently until `threshold` of them succeed, dropping
+/// the ones that are still pending at that point. Returns the successes and
+/// the errors seen so far.
+async fn collect_until_threshold<T, E>(
+    futures: impl IntoIterator<Item = impl Future<Output = Result<T, E>>>,
+    threshold: usize,
+) -> (Vec<T>, Vec<E>) {
+    let mut pending: FuturesUnordered<_> = futures.into_iter().collect();
+    let mut oks = Vec::with_capacity(threshold);
+    let mut errs = vec![];
+    while oks.len() < threshold {
+        match pending.next().await {
+            Some(Ok(ok)) => oks.push(ok),
+            Some(Err(err)) => errs.push(err),
+            None => break,
         }
     }
+    (oks, errs)
 }
 
 impl AggregationIsmMetadataBuilder {
@@ -87,31 +108,85 @@ impl AggregationIsmMetadataBuilder {
         cheapest.into_iter().map(|(meta, _)| meta).collect()
     }
 
-    async fn cheapest_valid_metas(
-        sub_modules: Vec<IsmAndMetadata>,
+    /// Build the metadata of the sub-module at `index` and estimate the gas
+    /// to verify it. Fails if the metadata can't be built or doesn't verify.
+    async fn valid_meta_and_gas(
+        &self,
+        index: usize,
+        ism_address: H256,
         message: &HyperlaneMessage,
+    ) -> Result<(SubModuleMetadata, U256), SubModuleError> {
+        let sub_module = self
+            .base
+            .build_ism_and_metadata(ism_address, message)
+            .await
+            .map_err(|err| match err.downcast::<MetadataBuilderError>() {
+                Ok(
+                    err @ (MetadataBuilderError::FraudWindowPending(_)
+                    | MetadataBuilderError::PreVerificationRequired { .. }),
+                ) => SubModuleError::FraudWindow(err),
+                _ => SubModuleError::Invalid((ism_address, None)),
+            })?;
+        let err_ism = SubModuleError::Invalid((ism_address, Some(sub_module.module_type)));
+        let Some(metadata) = sub_module.metadata else {
+            return Err(err_ism);
+        };
+        match sub_module.ism.dry_run_verify(message, &metadata).await {
+            Ok(Some(gas)) => Ok((SubModuleMetadata::new(index, metadata), gas)),
+            _ => Err(err_ism),
+        }
+    }
+
+    /// The error to fail with if enough sub-modules would be valid once the
+    /// message is pre-verified with, or past the fraud window of, the
+    /// optimistic ones among them. Pre-verifications come first, then the
+    /// fraud window that elapses first.
+    fn fraud_window_error(
+        valid_count: usize,
+        errs: Vec<SubModuleError>,
         threshold: usize,
-        err_isms: Vec<(H256, Option<ModuleType>)>,
-    ) -> Option<Vec<SubModuleMetadata>> {
-        let gas_cost_results: Vec<_> = join_all(
-            sub_modules
+    ) -> Option<MetadataBuilderError> {
+        let pending: Vec<_> = errs
+            .into_iter()
+            .filter_map(|err| match err {
+                SubModuleError::FraudWindow(err) => Some(err),
+                SubModuleError::Invalid(_) => None,
+            })
+            .collect();
+        if valid_count + pending.len() < threshold {
+            return None;
+        }
+        pending.into_iter().min_by_key(|err| match err {
+            MetadataBuilderError::FraudWindowPending(remaining) => *remaining,
+            _ => Duration::ZERO,
+        })
+    }
+
+    /// Build and verify the metadata of all sub-modules concurrently, stopping
+    /// once `threshold` of them are valid. Fails if the threshold is only
+    /// reached once the fraud windows of optimistic sub-modules elapsed.
+    async fn first_valid_metas(
+        &self,
+        ism_addresses: &[H256],
+        message: &HyperlaneMessage,
+        threshold: usize,
+    ) -> Result<Option<Vec<SubModuleMetadata>>, MetadataBuilderError> {
+        let (metas_and_gas, err_isms) = collect_until_threshold(
+            ism_addresses
                 .iter()
-                .map(|module| module.ism.dry_run_verify(message, &(module.meta.metadata))),
+                .enumerate()
+                .map(|(index, ism_address)| self.valid_meta_and_gas(index, *ism_address, message)),
+            threshold,
         )
         .await;
-        // Filter out the ISMs with a gas cost estimate
-        let metas_and_gas: Vec<_> = sub_modules
-            .into_iter()
-            .zip(gas_cost_results.into_iter())
-            .filter_map(|(module, gas_cost)| gas_cost.ok().flatten().map(|gc| (module.meta, gc)))
-            .collect();
 
         let metas_and_gas_count = metas_and_gas.len();
         if metas_and_gas_count < threshold {
             info!(?err_isms, %metas_and_gas_count, %threshold, message_id=?message.id(), "Could not fetch all metadata, ISM metadata count did not reach aggregation threshold");
-            return None;
+            return Self::fraud_window_error(metas_and_gas_count, err_isms, threshold)
+                .map_or(Ok(None), Err);
         }
-        Some(Self::n_cheapest_metas(metas_and_gas, threshold))
+        Ok(Some(Self::n_cheapest_metas(metas_and_gas, threshold)))
     }
 }
 
@@ -125,38 +200,21 @@ impl MetadataBuilder for AggregationIsmMetadataBuilder {
     ) -> eyre::Result<Option<Vec<u8>>> {
         const CTX: &str = "When fetching AggregationIsm metadata";
         let ism = self.build_aggregation_ism(ism_address).await.context(CTX)?;
-        let (ism_addresses, threshold) = ism.modules_and_threshold(message).await.context(CTX)?;
+        let (ism_addresses, threshold) = self
+            .ism_config_cache()
+            .modules_and_threshold(
+                ism_address,
+                message.origin,
+                ism.modules_and_threshold(message),
+            )
+            .await
+            .context(CTX)?;
         let threshold = threshold as usize;
 
-        let sub_modules_and_metas = join_all(
-            ism_addresses
-                .iter()
-                .map(|ism_address| self.base.build_ism_and_metadata(*ism_address, message)),
-        )
-        .await;
-
-        // Partitions things into
-        // 1. ok_sub_modules: ISMs with metadata with valid metadata
-        // 2. err_sub_modules: ISMs with invalid metadata
-        let (ok_sub_modules, err_sub_modules): (Vec<_>, Vec<_>) = sub_modules_and_metas
-            .into_iter()
-            .zip(ism_addresses.iter())
-            .enumerate()
-            .partition_map(|(index, (result, ism_address))| match result {
-                Ok(sub_module_and_meta) => match sub_module_and_meta.metadata {
-                    Some(metadata) => Either::Left(IsmAndMetadata::new(
-                        sub_module_and_meta.ism,
-                        index,
-                        metadata,
-                    )),
-                    None => Either::Right((*ism_address, Some(sub_module_and_meta.module_type))),
-                },
-                Err(_) => Either::Right((*ism_address, None)),
-            });
-        let maybe_aggregation_metadata =
-            Self::cheapest_valid_metas(ok_sub_modules, message, threshold, err_sub_modules)
-                .await
-                .map(|mut metas| Self::format_metadata(&mut metas, ism_addresses.len()));
+        let maybe_aggregation_metadata = self
+            .first_valid_metas(&ism_addresses, message, threshold)
+            .await?
+            .map(|mut metas| Self::format_metadata(&mut metas, ism_addresses.len()));
         Ok(maybe_aggregation_metadata)
     }
 }
@@ -259,4 +317,60 @@ mod test {
             ]
         )
     }
+
+    #[tokio::test]
+    async fn test_collect_until_threshold_exits_early() {
+        // The sub-module that never finishes doesn't hold up the others
+        let futures: Vec<std::pin::Pin<Box<dyn Future<Output = Result<u32, u32>>>>> = vec![
+            Box::pin(futures::future::pending()),
+            Box::pin(async { Ok(2) }),
+            Box::pin(async { Ok(3) }),
+        ];
+        let (mut oks, _) = collect_until_threshold(futures, 2).await;
+        oks.sort();
+        assert_eq!(oks, vec![2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_collect_until_threshold_not_reached() {
+        let futures = vec![async { Ok::<u32, u32>(1) }, async { Err(2) }];
+        let (oks, errs) = collect_until_threshold(futures, 3).await;
+        assert_eq!(oks, vec![1]);
+        assert_eq!(errs, vec![2]);
+    }
+
+    #[test]
+    fn test_fraud_window_error() {
+        let errs = || {
+            vec![
+                SubModuleError::Invalid((H256::zero(), None)),
+                SubModuleError::FraudWindow(MetadataBuilderError::FraudWindowPending(
+                    Duration::from_secs(20),
+                )),
+                SubModuleError::FraudWindow(MetadataBuilderError::FraudWindowPending(
+                    Duration::from_secs(10),
+                )),
+            ]
+        };
+        // The fraud window that elapses first
+        assert!(matches!(
+            AggregationIsmMetadataBuilder::fraud_window_error(1, errs(), 3),
+            Some(MetadataBuilderError::FraudWindowPending(remaining)) if remaining == Duration::from_secs(10)
+        ));
+        // The threshold can't be reached even once the fraud windows elapsed
+        assert!(AggregationIsmMetadataBuilder::fraud_window_error(0, errs(), 3).is_none());
+
+        // Pre-verifications come first
+        let mut errs = errs();
+        errs.push(SubModuleError::FraudWindow(
+            MetadataBuilderError::PreVerificationRequired {
+                ism: H256::zero(),
+                metadata: vec![],
+            },
+        ));
+        assert!(matches!(
+            AggregationIsmMetadataBuilder::fraud_window_error(1, errs, 3),
+            Some(MetadataBuilderError::PreVerificationRequired { .. })
+        ));
+    }
 }
@@ -1,9 +1,9 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::Debug,
     ops::Deref,
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 
@@ -11,8 +11,8 @@ use crate::{
     merkle_tree::builder::MerkleTreeBuilder,
     msg::metadata::{
         multisig::{MerkleRootMultisigMetadataBuilder, MessageIdMultisigMetadataBuilder},
-        AggregationIsmMetadataBuilder, CcipReadIsmMetadataBuilder, NullMetadataBuilder,
-        RoutingIsmMetadataBuilder,
+        AggregationIsmMetadataBuilder, CcipReadIsmMetadataBuilder, IsmConfigCache,
+        NullMetadataBuilder, OptimisticIsmMetadataBuilder, RoutingIsmMetadataBuilder,
     },
     settings::matching_list::MatchingList,
 };
@@ -22,12 +22,12 @@ use eyre::{Context, Result};
 use hyperlane_base::db::HyperlaneRocksDB;
 use hyperlane_base::{
     settings::{ChainConf, CheckpointSyncerConf},
-    CheckpointSyncer, CoreMetrics, MultisigCheckpointSyncer,
+    CheckpointSyncer, CoreMetrics, FallbackCheckpointSyncer, MultisigCheckpointSyncer,
 };
 use hyperlane_core::{
     accumulator::merkle::Proof, AggregationIsm, CcipReadIsm, Checkpoint, HyperlaneDomain,
-    HyperlaneMessage, InterchainSecurityModule, Mailbox, ModuleType, MultisigIsm, RoutingIsm,
-    ValidatorAnnounce, H160, H256,
+    HyperlaneMessage, InterchainSecurityModule, Mailbox, ModuleType, MultisigIsm, OptimisticIsm,
+    RoutingIsm, ValidatorAnnounce, H160, H256,
 };
 
 use tokio::sync::RwLock;
@@ -39,6 +39,12 @@ pub enum MetadataBuilderError {
     UnsupportedModuleType(ModuleType),
     #[error("Exceeded max depth when building metadata ({0})")]
     MaxDepthExceeded(u32),
+    #[error("Message is in the fraud window of an optimistic ISM for {0:?}")]
+    FraudWindowPending(Duration),
+    /// The message has to be pre-verified with the optimistic ISM `ism`,
+    /// using the `metadata` of its submodule, before it can be delivered
+    #[error("Message has to be pre-verified with the optimistic ISM {ism:?}")]
+    PreVerificationRequired { ism: H256, metadata: Vec<u8> },
 }
 
 #[derive(Debug)]
@@ -236,8 +242,9 @@ impl MessageMetadataBuilder {
             .await
             .context("When building ISM")?;
 
-        let module_type = ism
-            .module_type()
+        let module_type = self
+            .ism_config_cache
+            .module_type(ism_address, message.origin, ism.module_type())
             .await
             .context("When fetching module type")?;
         let cloned = self.clone_with_incremented_depth()?;
@@ -253,6 +260,7 @@ impl MessageMetadataBuilder {
             ModuleType::Aggregation => Box::new(AggregationIsmMetadataBuilder::new(cloned)),
             ModuleType::Null => Box::new(NullMetadataBuilder::new()),
             ModuleType::CcipRead => Box::new(CcipReadIsmMetadataBuilder::new(cloned)),
+            ModuleType::Optimistic => Box::new(OptimisticIsmMetadataBuilder::new(cloned)),
             _ => return Err(MetadataBuilderError::UnsupportedModuleType(module_type).into()),
         };
         let meta = metadata_builder
@@ -276,10 +284,19 @@ pub struct BaseMetadataBuilder {
     origin_prover_sync: Arc<RwLock<MerkleTreeBuilder>>,
     origin_validator_announce: Arc<dyn ValidatorAnnounce>,
     allow_local_checkpoint_syncers: bool,
+    /// The only validators whose checkpoints are used for multisig metadata,
+    /// if restricted
+    allowed_validators: Option<HashSet<H256>>,
     metrics: Arc<CoreMetrics>,
     db: HyperlaneRocksDB,
     max_depth: u32,
     app_context_classifier: IsmAwareAppContextClassifier,
+    /// The configuration of the ISMs on the destination
+    ism_config_cache: IsmConfigCache,
+    /// When the messages in the fraud window of an optimistic ISM can be
+    /// delivered, by message id
+    #[new(default)]
+    fraud_window_messages: Mutex<HashMap<H256, Instant>>,
 }
 
 impl Debug for BaseMetadataBuilder {
@@ -301,6 +318,23 @@ impl BaseMetadataBuilder {
         &self.destination_chain_setup.domain
     }
 
+    pub fn ism_config_cache(&self) -> &IsmConfigCache {
+        &self.ism_config_cache
+    }
+
+    /// The validators of a multisig ISM whose checkpoints can be used, which
+    /// are all of them unless they're restricted to a subset
+    pub fn usable_validators(&self, validators: &[H256]) -> Vec<H256> {
+        match &self.allowed_validators {
+            Some(allowed) => validators
+                .iter()
+                .filter(|validator| allowed.contains(validator))
+                .copied()
+                .collect(),
+            None => validators.to_vec(),
+        }
+    }
+
     pub async fn get_proof(&self, leaf_index: u32, checkpoint: Checkpoint) -> Result<Proof> {
         const CTX: &str = "When fetching message proof";
         let proof = self
@@ -361,6 +395,28 @@ impl BaseMetadataBuilder {
             .await
     }
 
+    pub async fn build_optimistic_ism(&self, address: H256) -> Result<Box<dyn OptimisticIsm>> {
+        self.destination_chain_setup
+            .build_optimistic_ism(address, &self.metrics)
+            .await
+    }
+
+    /// Record that the message `id` can be delivered at `ready_at`, and
+    /// update the number of messages still in a fraud window
+    pub fn track_fraud_window(&self, id: H256, ready_at: Instant) {
+        let mut messages = self
+            .fraud_window_messages
+            .lock()
+            .expect("fraud window lock poisoned");
+        messages.insert(id, ready_at);
+        let now = Instant::now();
+        messages.retain(|_, ready_at| *ready_at > now);
+        self.metrics
+            .messages_in_fraud_window()
+            .with_label_values(&[self.origin_domain.name(), self.destination_domain().name()])
+            .set(messages.len() as i64);
+    }
+
     pub async fn build_checkpoint_syncer(
         &self,
         validators: &[H256],
@@ -371,9 +427,11 @@ impl BaseMetadataBuilder {
             .get_announced_storage_locations(validators)
             .await?;
 
-        // Only use the most recently announced location for now.
+        // Validators that write their checkpoints to several locations are
+        // read from all of them, preferring the most recently announced one.
         let mut checkpoint_syncers: HashMap<H160, Arc<dyn CheckpointSyncer>> = HashMap::new();
         for (&validator, validator_storage_locations) in validators.iter().zip(storage_locations) {
+            let mut validator_syncers: Vec<Arc<dyn CheckpointSyncer>> = Vec::new();
             for storage_location in validator_storage_locations.iter().rev() {
                 let Ok(config) = CheckpointSyncerConf::from_str(storage_location) else {
                     debug!(
@@ -398,9 +456,14 @@ impl BaseMetadataBuilder {
 
                 match config.build(None).await {
                     Ok(checkpoint_syncer) => {
-                        // found the syncer for this validator
-                        checkpoint_syncers.insert(validator.into(), checkpoint_syncer.into());
-                        break;
+                        if validator_syncers.iter().any(|syncer| {
+                            syncer.announcement_location()
+                                == checkpoint_syncer.announcement_location()
+                        }) {
+                            // announced more than once
+                            continue;
+                        }
+                        validator_syncers.push(checkpoint_syncer.into());
                     }
                     Err(err) => {
                         debug!(
@@ -412,6 +475,18 @@ impl BaseMetadataBuilder {
                     }
                 }
             }
+            match validator_syncers.len() {
+                0 => {}
+                1 => {
+                    checkpoint_syncers.insert(validator.into(), validator_syncers.remove(0));
+                }
+                _ => {
+                    checkpoint_syncers.insert(
+                        validator.into(),
+                        Arc::new(FallbackCheckpointSyncer::new(validator_syncers)),
+                    );
+                }
+            }
             if checkpoint_syncers.get(&validator.into()).is_none() {
                 if validator_storage_locations.is_empty() {
                     warn!(?validator, "Validator has not announced any storage locations; see https://docs.hyperlane.xyz/docs/operators/validators/announcing-your-validator");
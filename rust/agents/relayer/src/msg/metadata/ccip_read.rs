@@ -1,15 +1,15 @@
 use async_trait::async_trait;
 use derive_more::Deref;
 use derive_new::new;
-use ethers::{abi::AbiDecode, core::utils::hex::decode as hex_decode};
+use ethers::core::utils::hex::decode as hex_decode;
 use eyre::Context;
-use hyperlane_core::{utils::bytes_to_hex, HyperlaneMessage, RawHyperlaneMessage, H256};
-use hyperlane_ethereum::OffchainLookup;
-use regex::Regex;
+use hyperlane_core::{
+    utils::bytes_to_hex, HyperlaneMessage, OffchainLookup, RawHyperlaneMessage, H160, H256,
+};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use tracing::{info, instrument};
+use serde_json::{json, Value};
+use tracing::{info, instrument, warn};
 
 use super::{base::MessageMetadataBuilder, MetadataBuilder};
 
@@ -18,11 +18,89 @@ struct OffchainResponse {
     data: String,
 }
 
+/// A request to an ERC-3668 gateway
+#[derive(Debug, PartialEq)]
+enum GatewayRequest {
+    /// Urls with a `{data}` parameter are queried with a GET
+    Get(String),
+    /// Other urls are sent the sender and the data in a POST
+    Post(String, Value),
+}
+
+impl GatewayRequest {
+    fn new(url: &str, lookup: &OffchainLookup) -> Self {
+        // Need to explicitly convert the sender H160 the hex because the `ToString` implementation
+        // for `H160` truncates the output. (e.g. `0xc66a…7b6f` instead of returning
+        // the full address)
+        let sender = bytes_to_hex(H160::from(lookup.sender).as_bytes());
+        let data = bytes_to_hex(&lookup.call_data);
+        let interpolated_url = url.replace("{sender}", &sender).replace("{data}", &data);
+        if url.contains("{data}") {
+            Self::Get(interpolated_url)
+        } else {
+            Self::Post(
+                interpolated_url,
+                json!({
+                    "sender": sender,
+                    "data": data
+                }),
+            )
+        }
+    }
+}
+
 #[derive(Clone, Debug, new, Deref)]
 pub struct CcipReadIsmMetadataBuilder {
     base: MessageMetadataBuilder,
 }
 
+impl CcipReadIsmMetadataBuilder {
+    /// Query the gateways of `lookup` in order until one responds, following
+    /// ERC-3668: a client error fails the lookup, while server errors and
+    /// malformed responses move on to the next gateway.
+    async fn fetch_offchain_data(&self, lookup: &OffchainLookup) -> eyre::Result<Option<Vec<u8>>> {
+        let client = Client::new();
+        for url in lookup.urls.iter() {
+            let request = match GatewayRequest::new(url, lookup) {
+                GatewayRequest::Get(url) => client.get(url),
+                GatewayRequest::Post(url, body) => client
+                    .post(url)
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            };
+            let res = match request.send().await {
+                Ok(res) => res,
+                Err(err) => {
+                    warn!(%url, error = %err, "Offchain lookup gateway unreachable");
+                    continue;
+                }
+            };
+            if res.status().is_client_error() {
+                info!(%url, status = %res.status(), "Offchain lookup rejected by gateway");
+                return Ok(None);
+            }
+            let json: Result<OffchainResponse, reqwest::Error> = res.json().await;
+            match json {
+                Ok(result) => {
+                    let data = result.data.strip_prefix("0x").unwrap_or(&result.data);
+                    match hex_decode(data) {
+                        Ok(data) => return Ok(Some(data)),
+                        Err(err) => {
+                            warn!(%url, error = %err, "Invalid offchain lookup gateway response")
+                        }
+                    }
+                }
+                Err(_err) => {
+                    // try the next URL
+                }
+            }
+        }
+
+        // No metadata endpoints or endpoints down
+        Ok(None)
+    }
+}
+
 #[async_trait]
 impl MetadataBuilder for CcipReadIsmMetadataBuilder {
     #[instrument(err, skip(self))]
@@ -34,64 +112,54 @@ impl MetadataBuilder for CcipReadIsmMetadataBuilder {
         const CTX: &str = "When fetching CcipRead metadata";
         let ism = self.build_ccip_read_ism(ism_address).await.context(CTX)?;
 
-        let response = ism
+        let Some(lookup) = ism
             .get_offchain_verify_info(RawHyperlaneMessage::from(message).to_vec())
-            .await;
-        let info: OffchainLookup = match response {
-            Ok(_) => {
-                info!("incorrectly configured getOffchainVerifyInfo, expected revert");
-                return Ok(None);
-            }
-            Err(raw_error) => {
-                let matching_regex = Regex::new(r"0x[[:xdigit:]]+")?;
-                if let Some(matching) = &matching_regex.captures(&raw_error.to_string()) {
-                    OffchainLookup::decode(hex_decode(&matching[0][2..])?)?
-                } else {
-                    info!("unable to parse custom error out of revert");
-                    return Ok(None);
-                }
-            }
+            .await
+            .context(CTX)?
+        else {
+            info!("incorrectly configured getOffchainVerifyInfo, expected revert");
+            return Ok(None);
         };
+        // Lookups of other contracts, e.g. one the ISM calls, can't be
+        // trusted to be the ISM's
+        if lookup.sender != ism_address {
+            info!(sender=?lookup.sender, "offchain lookup sender is not the ISM");
+            return Ok(None);
+        }
 
-        for url in info.urls.iter() {
-            // Need to explicitly convert the sender H160 the hex because the `ToString` implementation
-            // for `H160` truncates the output. (e.g. `0xc66a…7b6f` instead of returning
-            // the full address)
-            let sender_as_bytes = &bytes_to_hex(info.sender.as_bytes());
-            let data_as_bytes = &info.call_data.to_string();
-            let interpolated_url = url
-                .replace("{sender}", sender_as_bytes)
-                .replace("{data}", data_as_bytes);
-            let res = if !url.contains("{data}") {
-                let body = json!({
-                    "sender": sender_as_bytes,
-                    "data": data_as_bytes
-                });
-                Client::new()
-                    .post(interpolated_url)
-                    .header("Content-Type", "application/json")
-                    .json(&body)
-                    .send()
-                    .await?
-            } else {
-                reqwest::get(interpolated_url).await?
-            };
+        // The callback of Hyperlane CCIP read ISMs is the mailbox's
+        // `process`, whose metadata is the response of the gateway
+        self.fetch_offchain_data(&lookup).await
+    }
+}
 
-            let json: Result<OffchainResponse, reqwest::Error> = res.json().await;
+#[cfg(test)]
+mod test {
+    use super::*;
 
-            match json {
-                Ok(result) => {
-                    // remove leading 0x which hex_decode doesn't like
-                    let metadata = hex_decode(&result.data[2..])?;
-                    return Ok(Some(metadata));
-                }
-                Err(_err) => {
-                    // try the next URL
-                }
-            }
+    fn lookup() -> OffchainLookup {
+        OffchainLookup {
+            sender: H160::repeat_byte(0xaa).into(),
+            urls: vec![],
+            call_data: vec![0x12, 0x34],
+            callback_function: [0; 4],
+            extra_data: vec![],
         }
+    }
 
-        // No metadata endpoints or endpoints down
-        Ok(None)
+    #[test]
+    fn test_gateway_request() {
+        let sender = format!("0x{}", "aa".repeat(20));
+        assert_eq!(
+            GatewayRequest::new("https://gateway.xyz/{sender}/{data}.json", &lookup()),
+            GatewayRequest::Get(format!("https://gateway.xyz/{sender}/0x1234.json"))
+        );
+        assert_eq!(
+            GatewayRequest::new("https://gateway.xyz", &lookup()),
+            GatewayRequest::Post(
+                "https://gateway.xyz".to_owned(),
+                json!({ "sender": sender, "data": "0x1234" })
+            )
+        );
     }
 }
@@ -0,0 +1,222 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use hyperlane_core::{ChainResult, ModuleType, H256};
+
+/// The address of an ISM and the origin domain of the messages it verifies
+type IsmKey = (H256, u32);
+
+/// Values that expire a while after they were inserted
+#[derive(Debug)]
+struct TtlMap<K, V> {
+    entries: Mutex<HashMap<K, (V, Instant)>>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlMap<K, V> {
+    fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, key: &K, ttl: Duration) -> Option<V> {
+        let entries = self.entries.lock().expect("ISM config cache lock poisoned");
+        entries
+            .get(key)
+            .filter(|(_, inserted_at)| inserted_at.elapsed() < ttl)
+            .map(|(value, _)| value.clone())
+    }
+
+    fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().expect("ISM config cache lock poisoned");
+        entries.insert(key, (value, Instant::now()));
+    }
+
+    fn retain(&self, keep: impl Fn(&K) -> bool) {
+        let mut entries = self.entries.lock().expect("ISM config cache lock poisoned");
+        entries.retain(|key, _| keep(key));
+    }
+}
+
+/// Caches the configuration of ISMs, such as their validator sets,
+/// thresholds and routes, so that it isn't fetched again for every message.
+///
+/// Entries are keyed by the ISM address and the origin domain of the
+/// message, so the configuration of an ISM is assumed to only depend on the
+/// origin of the messages it verifies. Nothing is cached without a TTL.
+#[derive(Debug)]
+pub struct IsmConfigCache {
+    ttl: Option<Duration>,
+    module_types: TtlMap<IsmKey, ModuleType>,
+    validators_and_thresholds: TtlMap<IsmKey, (Vec<H256>, u8)>,
+    modules_and_thresholds: TtlMap<IsmKey, (Vec<H256>, u8)>,
+    routes: TtlMap<IsmKey, H256>,
+}
+
+impl IsmConfigCache {
+    pub fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            ttl,
+            module_types: TtlMap::new(),
+            validators_and_thresholds: TtlMap::new(),
+            modules_and_thresholds: TtlMap::new(),
+            routes: TtlMap::new(),
+        }
+    }
+
+    async fn get_or_fetch<V: Clone>(
+        &self,
+        map: &TtlMap<IsmKey, V>,
+        key: IsmKey,
+        fetch: impl Future<Output = ChainResult<V>>,
+    ) -> ChainResult<V> {
+        let Some(ttl) = self.ttl else {
+            return fetch.await;
+        };
+        if let Some(value) = map.get(&key, ttl) {
+            return Ok(value);
+        }
+        let value = fetch.await?;
+        map.insert(key, value.clone());
+        Ok(value)
+    }
+
+    /// The module type of the ISM at `ism`, fetched with `fetch` unless it's
+    /// cached
+    pub async fn module_type(
+        &self,
+        ism: H256,
+        origin: u32,
+        fetch: impl Future<Output = ChainResult<ModuleType>>,
+    ) -> ChainResult<ModuleType> {
+        self.get_or_fetch(&self.module_types, (ism, origin), fetch)
+            .await
+    }
+
+    /// The validators and threshold of the multisig ISM at `ism`, fetched
+    /// with `fetch` unless they're cached
+    pub async fn validators_and_threshold(
+        &self,
+        ism: H256,
+        origin: u32,
+        fetch: impl Future<Output = ChainResult<(Vec<H256>, u8)>>,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        self.get_or_fetch(&self.validators_and_thresholds, (ism, origin), fetch)
+            .await
+    }
+
+    /// The modules and threshold of the aggregation ISM at `ism`, fetched
+    /// with `fetch` unless they're cached
+    pub async fn modules_and_threshold(
+        &self,
+        ism: H256,
+        origin: u32,
+        fetch: impl Future<Output = ChainResult<(Vec<H256>, u8)>>,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        self.get_or_fetch(&self.modules_and_thresholds, (ism, origin), fetch)
+            .await
+    }
+
+    /// The module the routing ISM at `ism` routes to, fetched with `fetch`
+    /// unless it's cached
+    pub async fn route(
+        &self,
+        ism: H256,
+        origin: u32,
+        fetch: impl Future<Output = ChainResult<H256>>,
+    ) -> ChainResult<H256> {
+        self.get_or_fetch(&self.routes, (ism, origin), fetch).await
+    }
+
+    /// Drop the configuration cached for the messages from `origin`, e.g.
+    /// because verifying one of them failed after an ISM was reconfigured.
+    pub fn invalidate(&self, origin: u32) {
+        let other_origin = |(_, domain): &IsmKey| *domain != origin;
+        self.module_types.retain(other_origin);
+        self.validators_and_thresholds.retain(other_origin);
+        self.modules_and_thresholds.retain(other_origin);
+        self.routes.retain(other_origin);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ISM: H256 = H256::repeat_byte(1);
+
+    #[tokio::test]
+    async fn test_cached_until_invalidated() {
+        let cache = IsmConfigCache::new(Some(Duration::from_secs(60)));
+        let route = |module: u8| async move { Ok(H256::repeat_byte(module)) };
+
+        assert_eq!(
+            cache.route(ISM, 1, route(2)).await.unwrap(),
+            H256::repeat_byte(2)
+        );
+        // Cached values aren't fetched again
+        assert_eq!(
+            cache.route(ISM, 1, route(3)).await.unwrap(),
+            H256::repeat_byte(2)
+        );
+        // Other origins are cached separately
+        assert_eq!(
+            cache.route(ISM, 2, route(3)).await.unwrap(),
+            H256::repeat_byte(3)
+        );
+
+        cache.invalidate(1);
+        assert_eq!(
+            cache.route(ISM, 1, route(4)).await.unwrap(),
+            H256::repeat_byte(4)
+        );
+        assert_eq!(
+            cache.route(ISM, 2, route(4)).await.unwrap(),
+            H256::repeat_byte(3)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_caching_without_ttl() {
+        let cache = IsmConfigCache::new(None);
+        let fetch = |threshold: u8| async move { Ok((vec![ISM], threshold)) };
+
+        assert_eq!(
+            cache
+                .validators_and_threshold(ISM, 1, fetch(1))
+                .await
+                .unwrap(),
+            (vec![ISM], 1)
+        );
+        assert_eq!(
+            cache
+                .validators_and_threshold(ISM, 1, fetch(2))
+                .await
+                .unwrap(),
+            (vec![ISM], 2)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_values_are_fetched_again() {
+        let cache = IsmConfigCache::new(Some(Duration::ZERO));
+        let fetch = |module_type| async move { Ok(module_type) };
+
+        cache
+            .module_type(ISM, 1, fetch(ModuleType::Routing))
+            .await
+            .unwrap();
+        assert_eq!(
+            cache
+                .module_type(ISM, 1, fetch(ModuleType::Aggregation))
+                .await
+                .unwrap(),
+            ModuleType::Aggregation
+        );
+    }
+}
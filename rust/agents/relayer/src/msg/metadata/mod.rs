@@ -1,15 +1,20 @@
 mod aggregation;
 mod base;
 mod ccip_read;
+mod ism_config_cache;
 mod multisig;
 mod null_metadata;
+mod optimistic;
 mod routing;
 
 use aggregation::AggregationIsmMetadataBuilder;
 pub(crate) use base::MetadataBuilder;
 pub(crate) use base::{
-    AppContextClassifier, BaseMetadataBuilder, IsmAwareAppContextClassifier, MessageMetadataBuilder,
+    AppContextClassifier, BaseMetadataBuilder, IsmAwareAppContextClassifier,
+    MessageMetadataBuilder, MetadataBuilderError,
 };
 use ccip_read::CcipReadIsmMetadataBuilder;
+pub(crate) use ism_config_cache::IsmConfigCache;
 use null_metadata::NullMetadataBuilder;
+use optimistic::OptimisticIsmMetadataBuilder;
 use routing::RoutingIsmMetadataBuilder;
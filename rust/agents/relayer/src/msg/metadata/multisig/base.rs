@@ -105,8 +105,14 @@ impl<T: MultisigIsmMetadataBuilder> MetadataBuilder for T {
             .await
             .context(CTX)?;
 
-        let (validators, threshold) = multisig_ism
-            .validators_and_threshold(message)
+        let (validators, threshold) = self
+            .as_ref()
+            .ism_config_cache()
+            .validators_and_threshold(
+                ism_address,
+                message.origin,
+                multisig_ism.validators_and_threshold(message),
+            )
             .await
             .context(CTX)?;
 
@@ -115,9 +121,22 @@ impl<T: MultisigIsmMetadataBuilder> MetadataBuilder for T {
             return Ok(None);
         }
 
+        // Signatures are still ordered by the full validator set, but only
+        // the usable validators are asked for them
+        let usable_validators = self.as_ref().usable_validators(&validators);
+        if usable_validators.len() < threshold as usize {
+            info!(
+                ?validators,
+                ?usable_validators,
+                threshold,
+                "Could not fetch metadata: Not enough allowed validators to reach quorum"
+            );
+            return Ok(None);
+        }
+
         let checkpoint_syncer = self
             .as_ref()
-            .build_checkpoint_syncer(&validators, self.as_ref().app_context.clone())
+            .build_checkpoint_syncer(&usable_validators, self.as_ref().app_context.clone())
             .await
             .context(CTX)?;
 
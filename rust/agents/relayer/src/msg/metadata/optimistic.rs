@@ -0,0 +1,59 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use derive_more::Deref;
+use derive_new::new;
+use eyre::Context;
+use hyperlane_core::{HyperlaneMessage, H256};
+use tracing::{info, instrument};
+
+use super::{MessageMetadataBuilder, MetadataBuilder, MetadataBuilderError};
+
+#[derive(Clone, Debug, new, Deref)]
+pub struct OptimisticIsmMetadataBuilder {
+    base: MessageMetadataBuilder,
+}
+
+#[async_trait]
+impl MetadataBuilder for OptimisticIsmMetadataBuilder {
+    /// Fails with the metadata of the submodule if the message wasn't
+    /// pre-verified yet, so that it's pre-verified when submitted, and with
+    /// the time left in the fraud window until it elapsed, so that the
+    /// message is processed once it did. Doesn't send any transaction.
+    #[instrument(err, skip(self))]
+    async fn build(
+        &self,
+        ism_address: H256,
+        message: &HyperlaneMessage,
+    ) -> eyre::Result<Option<Vec<u8>>> {
+        const CTX: &str = "When fetching Optimistic metadata";
+        let ism = self.build_optimistic_ism(ism_address).await.context(CTX)?;
+        let fraud_window = ism.fraud_window().await.context(CTX)?;
+
+        let pre_verified_at = match ism.pre_verified_at(message).await.context(CTX)? {
+            Some(timestamp) => UNIX_EPOCH + Duration::from_secs(timestamp),
+            None => {
+                let submodule = ism.submodule(message).await.context(CTX)?;
+                let Some(metadata) = self.base.build(submodule, message).await.context(CTX)? else {
+                    info!(?submodule, "Could not fetch metadata of the submodule");
+                    return Ok(None);
+                };
+                return Err(MetadataBuilderError::PreVerificationRequired {
+                    ism: ism_address,
+                    metadata,
+                }
+                .into());
+            }
+        };
+
+        let remaining = (pre_verified_at + fraud_window)
+            .duration_since(SystemTime::now())
+            .unwrap_or_default();
+        self.track_fraud_window(message.id(), Instant::now() + remaining);
+        if !remaining.is_zero() {
+            return Err(MetadataBuilderError::FraudWindowPending(remaining).into());
+        }
+        // Pre-verified messages are verified without metadata
+        Ok(Some(vec![]))
+    }
+}
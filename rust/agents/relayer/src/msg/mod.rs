@@ -25,7 +25,11 @@
 //!   - FallbackProviderSubmitter (Serialized, but if some RPC provider sucks,
 //!   switch everyone to new one)
 
+pub(crate) mod circuit_breaker;
+pub(crate) mod congestion;
+pub(crate) mod coordination;
 pub(crate) mod gas_payment;
+pub(crate) mod hooks;
 pub(crate) mod metadata;
 pub(crate) mod op_queue;
 pub(crate) mod op_submitter;
@@ -1,4 +1,9 @@
-use std::{cmp::Reverse, collections::BinaryHeap, sync::Arc};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    sync::Arc,
+    time::Instant,
+};
 
 use derive_new::new;
 use hyperlane_core::{PendingOperation, QueueOperation};
@@ -6,7 +11,7 @@ use prometheus::{IntGauge, IntGaugeVec};
 use tokio::sync::{broadcast::Receiver, Mutex};
 use tracing::{debug, info, instrument};
 
-use crate::server::MessageRetryRequest;
+use crate::server::{OperationSummary, QueueRequest};
 
 /// Queue of generic operations that can be submitted to a destination chain.
 /// Includes logic for maintaining queue metrics by the destination and `app_context` of an operation
@@ -14,12 +19,45 @@ use crate::server::MessageRetryRequest;
 pub struct OpQueue {
     metrics: IntGaugeVec,
     queue_metrics_label: String,
-    retry_rx: Arc<Mutex<Receiver<MessageRetryRequest>>>,
+    retry_rx: Arc<Mutex<Receiver<QueueRequest>>>,
     #[new(default)]
     queue: Arc<Mutex<BinaryHeap<Reverse<QueueOperation>>>>,
+    /// The most operations of an app context popped at once
+    #[new(default)]
+    app_concurrency_limits: Arc<HashMap<String, usize>>,
 }
 
 impl OpQueue {
+    /// Limit how many operations of each app context are popped at once
+    pub fn with_app_concurrency_limits(mut self, limits: HashMap<String, usize>) -> Self {
+        self.app_concurrency_limits = Arc::new(limits);
+        self
+    }
+
+    /// Whether another operation can be popped along with `popped_per_app`,
+    /// counting it if so
+    fn within_app_limit(
+        &self,
+        op: &QueueOperation,
+        popped_per_app: &mut HashMap<String, usize>,
+    ) -> bool {
+        if self.app_concurrency_limits.is_empty() {
+            return true;
+        }
+        let Some(app_context) = op.app_context() else {
+            return true;
+        };
+        let Some(limit) = self.app_concurrency_limits.get(&app_context) else {
+            return true;
+        };
+        let popped = popped_per_app.entry(app_context).or_default();
+        if *popped >= *limit {
+            return false;
+        }
+        *popped += 1;
+        true
+    }
+
     /// Push an element onto the queue and update metrics
     #[instrument(skip(self), ret, fields(queue_label=%self.queue_metrics_label), level = "debug")]
     pub async fn push(&self, op: QueueOperation) {
@@ -29,6 +67,11 @@ impl OpQueue {
         self.queue.lock().await.push(Reverse(op));
     }
 
+    /// The number of operations in the queue
+    pub async fn len(&self) -> usize {
+        self.queue.lock().await.len()
+    }
+
     /// Pop an element from the queue and update metrics
     #[instrument(skip(self), ret, fields(queue_label=%self.queue_metrics_label), level = "debug")]
     pub async fn pop(&mut self) -> Option<QueueOperation> {
@@ -36,20 +79,49 @@ impl OpQueue {
         pop_attempt.into_iter().next()
     }
 
-    /// Pop multiple elements at once from the queue and update metrics
+    /// Pop multiple elements at once from the queue and update metrics.
+    /// Operations that are due are popped first, in order of their rank.
+    /// Operations beyond the concurrency limit of their app context are left
+    /// in the queue.
     #[instrument(skip(self), fields(queue_label=%self.queue_metrics_label), level = "debug")]
     pub async fn pop_many(&mut self, limit: usize) -> Vec<QueueOperation> {
         self.process_retry_requests().await;
         let mut queue = self.queue.lock().await;
+        let now = Instant::now();
+        let mut due = vec![];
+        while let Some(Reverse(op)) = queue.peek() {
+            if op.next_attempt_after().map_or(false, |at| at > now) {
+                break;
+            }
+            due.extend(queue.pop().map(|Reverse(op)| op));
+        }
+        // The sort is stable, so operations of the same rank keep their queue order
+        due.sort_by_cached_key(|op| op.rank());
         let mut popped = vec![];
-        while let Some(Reverse(op)) = queue.pop() {
+        let mut skipped = vec![];
+        let mut popped_per_app = HashMap::new();
+        for op in due {
+            if popped.len() < limit && self.within_app_limit(&op, &mut popped_per_app) {
+                popped.push(op);
+            } else {
+                skipped.push(op);
+            }
+        }
+        while popped.len() < limit {
+            let Some(Reverse(op)) = queue.pop() else {
+                break;
+            };
+            if self.within_app_limit(&op, &mut popped_per_app) {
+                popped.push(op);
+            } else {
+                skipped.push(op);
+            }
+        }
+        queue.extend(skipped.into_iter().map(Reverse));
+        for op in &popped {
             // even if the metric is decremented here, the operation may fail to process and be re-added to the queue.
             // in those cases, the queue length will look like it has spikes whose sizes are at most `limit`
             self.get_operation_metric(op.as_ref()).dec();
-            popped.push(op);
-            if popped.len() >= limit {
-                break;
-            }
         }
         // This function is called very often by the op_submitter tasks, so only log when there are operations to pop
         // to avoid spamming the logs
@@ -68,33 +140,80 @@ impl OpQueue {
         // be very low.
         // The other consideration is whether to put the channel receiver in the OpQueue or in a dedicated task
         // that also holds an Arc to the Mutex. For simplicity, we'll put it in the OpQueue for now.
-        let mut message_retry_requests = vec![];
-        while let Ok(message_id) = self.retry_rx.lock().await.try_recv() {
-            message_retry_requests.push(message_id);
+        let mut queue_requests = vec![];
+        while let Ok(request) = self.retry_rx.lock().await.try_recv() {
+            queue_requests.push(request);
         }
-        if message_retry_requests.is_empty() {
+        if queue_requests.is_empty() {
             return;
         }
         let mut queue = self.queue.lock().await;
         let mut reprioritized_queue: BinaryHeap<_> = queue
             .drain()
-            .map(|Reverse(mut op)| {
+            .filter_map(|Reverse(mut op)| {
                 // Can check for equality here because of the PartialEq implementation for MessageRetryRequest,
                 // but can't use `contains` because the types are different
-                if message_retry_requests.iter().any(|r| r == op) {
-                    info!(
-                        operation = %op,
-                        queue_label = %self.queue_metrics_label,
-                        "Retrying OpQueue operation"
-                    );
-                    op.reset_attempts()
+                let matching_requests: Vec<_> = queue_requests
+                    .iter()
+                    .filter(|r| r.selector() == op)
+                    .collect();
+                for request in matching_requests {
+                    match request {
+                        QueueRequest::Retry(_) => {
+                            info!(
+                                operation = %op,
+                                queue_label = %self.queue_metrics_label,
+                                "Retrying OpQueue operation"
+                            );
+                            op.reset_attempts()
+                        }
+                        QueueRequest::Reestimate(_) => {
+                            info!(
+                                operation = %op,
+                                queue_label = %self.queue_metrics_label,
+                                "Re-estimating OpQueue operation"
+                            );
+                            op.discard_preparation();
+                            op.reset_attempts()
+                        }
+                        QueueRequest::Drop(_) => {
+                            info!(
+                                operation = %op,
+                                queue_label = %self.queue_metrics_label,
+                                "Dropping OpQueue operation"
+                            );
+                            self.get_operation_metric(op.as_ref()).dec();
+                            return None;
+                        }
+                    }
                 }
-                Reverse(op)
+                Some(Reverse(op))
             })
             .collect();
         queue.append(&mut reprioritized_queue);
     }
 
+    /// Summarize the operations in the queue
+    pub async fn summaries(&self) -> Vec<OperationSummary> {
+        let now = Instant::now();
+        self.queue
+            .lock()
+            .await
+            .iter()
+            .map(|Reverse(op)| OperationSummary {
+                id: op.id(),
+                queue: self.queue_metrics_label.clone(),
+                destination_domain: op.destination_domain().id(),
+                num_retries: op.num_retries(),
+                dead_lettered: op.is_dead_lettered(),
+                next_attempt_in_secs: op
+                    .next_attempt_after()
+                    .map(|at| at.saturating_duration_since(now).as_secs()),
+                last_error: op.last_error(),
+            })
+            .collect()
+    }
+
     /// Get the metric associated with this operation
     fn get_operation_metric(&self, operation: &dyn PendingOperation) -> IntGauge {
         let (destination, app_context) = operation.get_operation_labels();
@@ -106,6 +225,7 @@ impl OpQueue {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::server::MessageRetryRequest;
     use hyperlane_core::{
         HyperlaneDomain, HyperlaneMessage, KnownHyperlaneDomain, PendingOperationResult,
         TryBatchAs, TxOutcome, H256, U256,
@@ -121,6 +241,9 @@ mod test {
         id: H256,
         seconds_to_next_attempt: u64,
         destination_domain: HyperlaneDomain,
+        rank: u64,
+        due: bool,
+        app_context: Option<String>,
     }
 
     impl MockPendingOperation {
@@ -129,6 +252,17 @@ mod test {
                 id: H256::random(),
                 seconds_to_next_attempt,
                 destination_domain,
+                rank: 0,
+                due: false,
+                app_context: None,
+            }
+        }
+
+        fn due(rank: u64, destination_domain: HyperlaneDomain) -> Self {
+            Self {
+                rank,
+                due: true,
+                ..Self::new(0, destination_domain)
             }
         }
     }
@@ -145,10 +279,18 @@ mod test {
             self.seconds_to_next_attempt = 0;
         }
 
+        fn num_retries(&self) -> u32 {
+            0
+        }
+
         fn priority(&self) -> u32 {
             todo!()
         }
 
+        fn rank(&self) -> u64 {
+            self.rank
+        }
+
         fn get_operation_labels(&self) -> (String, String) {
             Default::default()
         }
@@ -162,7 +304,7 @@ mod test {
         }
 
         fn app_context(&self) -> Option<String> {
-            todo!()
+            self.app_context.clone()
         }
 
         async fn prepare(&mut self) -> PendingOperationResult {
@@ -183,6 +325,12 @@ mod test {
             todo!()
         }
 
+        fn discard_preparation(&mut self) {}
+
+        fn has_been_submitted(&self) -> bool {
+            false
+        }
+
         /// This will be called after the operation has been submitted and is
         /// responsible for checking if the operation has reached a point at
         /// which we consider it safe from reorgs.
@@ -199,6 +347,9 @@ mod test {
         }
 
         fn next_attempt_after(&self) -> Option<Instant> {
+            if self.due {
+                return Instant::now().checked_sub(Duration::from_secs(1));
+            }
             Some(
                 Instant::now()
                     .checked_add(Duration::from_secs(self.seconds_to_next_attempt))
@@ -266,10 +417,10 @@ mod test {
 
         // Retry by message ids
         broadcaster
-            .send(MessageRetryRequest::MessageId(op_ids[1]))
+            .send(MessageRetryRequest::MessageId(op_ids[1]).into())
             .unwrap();
         broadcaster
-            .send(MessageRetryRequest::MessageId(op_ids[2]))
+            .send(MessageRetryRequest::MessageId(op_ids[2]).into())
             .unwrap();
 
         // Pop elements from queue 1
@@ -325,9 +476,7 @@ mod test {
 
         // Retry by domain
         broadcaster
-            .send(MessageRetryRequest::DestinationDomain(
-                destination_domain_2.id(),
-            ))
+            .send(MessageRetryRequest::DestinationDomain(destination_domain_2.id()).into())
             .unwrap();
 
         // Pop elements from queue
@@ -345,4 +494,91 @@ mod test {
         assert_eq!(popped[3], op_ids[0]);
         assert_eq!(popped[4], op_ids[1]);
     }
+
+    #[tokio::test]
+    async fn test_due_operations_popped_by_rank() {
+        let (metrics, queue_metrics_label) = dummy_metrics_and_label();
+        let broadcaster = sync::broadcast::Sender::new(100);
+        let mut op_queue = OpQueue::new(
+            metrics,
+            queue_metrics_label,
+            Arc::new(Mutex::new(broadcaster.subscribe())),
+        );
+
+        let destination_domain: HyperlaneDomain = KnownHyperlaneDomain::Injective.into();
+        let ops = vec![
+            Box::new(MockPendingOperation::new(10, destination_domain.clone())) as QueueOperation,
+            Box::new(MockPendingOperation::due(3, destination_domain.clone())) as QueueOperation,
+            Box::new(MockPendingOperation::due(1, destination_domain.clone())) as QueueOperation,
+            Box::new(MockPendingOperation::due(2, destination_domain.clone())) as QueueOperation,
+        ];
+        let op_ids: Vec<_> = ops.iter().map(|op| op.id()).collect();
+        for op in ops {
+            op_queue.push(op).await;
+        }
+
+        let popped: Vec<_> = op_queue
+            .pop_many(2)
+            .await
+            .iter()
+            .map(|op| op.id())
+            .collect();
+        assert_eq!(popped, vec![op_ids[2], op_ids[3]]);
+
+        // The operation that isn't due comes last, despite its lower rank
+        let popped: Vec<_> = op_queue
+            .pop_many(5)
+            .await
+            .iter()
+            .map(|op| op.id())
+            .collect();
+        assert_eq!(popped, vec![op_ids[1], op_ids[0]]);
+    }
+
+    #[tokio::test]
+    async fn test_app_concurrency_limits() {
+        let (metrics, queue_metrics_label) = dummy_metrics_and_label();
+        let broadcaster = sync::broadcast::Sender::new(100);
+        let mut op_queue = OpQueue::new(
+            metrics,
+            queue_metrics_label,
+            Arc::new(Mutex::new(broadcaster.subscribe())),
+        )
+        .with_app_concurrency_limits([("limited".to_owned(), 1)].into_iter().collect());
+
+        let destination_domain: HyperlaneDomain = KnownHyperlaneDomain::Injective.into();
+        let op = |rank, app_context: Option<&str>| {
+            Box::new(MockPendingOperation {
+                app_context: app_context.map(str::to_owned),
+                ..MockPendingOperation::due(rank, destination_domain.clone())
+            }) as QueueOperation
+        };
+        let ops = vec![
+            op(1, Some("limited")),
+            op(2, Some("limited")),
+            op(3, Some("other")),
+            op(4, None),
+        ];
+        let op_ids: Vec<_> = ops.iter().map(|op| op.id()).collect();
+        for op in ops {
+            op_queue.push(op).await;
+        }
+
+        let popped: Vec<_> = op_queue
+            .pop_many(3)
+            .await
+            .iter()
+            .map(|op| op.id())
+            .collect();
+        assert_eq!(popped, vec![op_ids[0], op_ids[2], op_ids[3]]);
+
+        // The operation left behind is popped with the next batch
+        let popped: Vec<_> = op_queue
+            .pop_many(3)
+            .await
+            .iter()
+            .map(|op| op.id())
+            .collect();
+        assert_eq!(popped, vec![op_ids[1]]);
+    }
 }
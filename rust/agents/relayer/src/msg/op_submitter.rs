@@ -1,15 +1,15 @@
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use derive_new::new;
 use futures::future::join_all;
-use futures_util::future::try_join_all;
 use hyperlane_core::total_estimated_cost;
-use prometheus::{IntCounter, IntGaugeVec};
+use prometheus::{IntCounter, IntGauge, IntGaugeVec};
 use tokio::sync::broadcast::Sender;
 use tokio::sync::mpsc;
 use tokio::sync::Mutex;
-use tokio::task::JoinHandle;
+use tokio::task::{JoinHandle, JoinSet};
 use tokio::time::sleep;
 use tokio_metrics::TaskMonitor;
 use tracing::{debug, info_span, instrument, instrument::Instrumented, trace, Instrument};
@@ -17,15 +17,32 @@ use tracing::{info, warn};
 
 use hyperlane_base::CoreMetrics;
 use hyperlane_core::{
-    BatchItem, ChainCommunicationError, ChainResult, HyperlaneDomain, HyperlaneDomainProtocol,
-    HyperlaneMessage, PendingOperationResult, QueueOperation, TxOutcome,
+    BatchItem, BatchResult, ChainCommunicationError, ChainResult, HyperlaneDomain,
+    HyperlaneDomainProtocol, HyperlaneMessage, Mailbox, PendingOperationResult, QueueOperation,
 };
 
+use crate::msg::circuit_breaker::CircuitBreaker;
+use crate::msg::congestion::CongestionMonitor;
 use crate::msg::pending_message::CONFIRM_DELAY;
-use crate::server::MessageRetryRequest;
+use crate::server::QueueRequest;
+use crate::settings::CircuitBreakerConf;
 
 use super::op_queue::OpQueue;
 
+/// How long to wait when all operations ready for submission were deferred
+/// because the destination is congested.
+const CONGESTION_BACKOFF: Duration = Duration::from_secs(1);
+
+/// How long to wait when the most operations that can be in flight were
+/// submitted.
+const IN_FLIGHT_BACKOFF: Duration = Duration::from_millis(200);
+
+/// How long to wait when submissions are paused by the circuit breaker.
+const CIRCUIT_OPEN_BACKOFF: Duration = Duration::from_secs(1);
+
+/// How often to check whether a batch filled up while its window is open.
+const BATCH_WINDOW_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 /// SerialSubmitter accepts operations over a channel. It is responsible for
 /// executing the right strategy to deliver those messages to the destination
 /// chain. It is designed to be used in a scenario allowing only one
@@ -73,23 +90,114 @@ use super::op_queue::OpQueue;
 /// eligible for submission, we should be working on it within reason. This
 /// must be balanced with the cost of making RPCs that will almost certainly
 /// fail and potentially block new messages from being sent immediately.
-#[derive(Debug, new)]
+///
+/// 4. Don't make a congested destination worse. While its mempool is
+/// congested, new submissions are slowed down or paused, but replacements of
+/// our own reverted, dropped or stuck transactions still go out first.
+///
+/// 5. Amortize the overhead of a transaction on expensive destinations. With
+/// a batch window configured, operations that are ready are held back until
+/// a full batch is ready or the window elapses, and are then submitted
+/// together.
+///
+/// 6. Don't keep burning retries on a destination that rejects everything.
+/// With a circuit breaker configured, submissions are paused once too many
+/// of the recent ones failed, until a probe submission succeeds.
+#[derive(Debug)]
 pub struct SerialSubmitter {
     /// Domain this submitter delivers to.
     domain: HyperlaneDomain,
+    /// Mailbox on the destination, used to sense mempool congestion.
+    mailbox: Arc<dyn Mailbox>,
     /// Receiver for new messages to submit.
     rx: mpsc::UnboundedReceiver<QueueOperation>,
-    /// Receiver for retry requests.
-    retry_tx: Sender<MessageRetryRequest>,
     /// Metrics for serial submitter.
     metrics: SerialSubmitterMetrics,
     /// Max batch size for submitting messages
     max_batch_size: u32,
+    /// How long to wait for a full batch before submitting a partial one
+    batch_window: Option<Duration>,
+    /// The most operations that can be submitted and wait to be confirmed
+    max_in_flight: Option<u32>,
+    /// Pauses submissions that keep failing, if configured
+    circuit_breaker: Option<Arc<Mutex<CircuitBreaker>>>,
     /// tokio task monitor
     task_monitor: TaskMonitor,
+    /// Operations waiting to be prepared.
+    prepare_queue: OpQueue,
+    /// Operations waiting to be submitted.
+    submit_queue: OpQueue,
+    /// Operations waiting to be confirmed.
+    confirm_queue: OpQueue,
 }
 
 impl SerialSubmitter {
+    /// Create a submitter whose queues act on the requests sent over
+    /// `retry_tx`. At most `app_concurrency_limits` operations of each app
+    /// context are prepared at once, ready operations wait up to
+    /// `batch_window` for a full batch, no more than `max_in_flight`
+    /// operations wait to be confirmed, and submissions are paused by a
+    /// `circuit_breaker` if one is configured.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        domain: HyperlaneDomain,
+        mailbox: Arc<dyn Mailbox>,
+        rx: mpsc::UnboundedReceiver<QueueOperation>,
+        retry_tx: Sender<QueueRequest>,
+        metrics: SerialSubmitterMetrics,
+        max_batch_size: u32,
+        batch_window: Option<Duration>,
+        max_in_flight: Option<u32>,
+        circuit_breaker: Option<CircuitBreakerConf>,
+        app_concurrency_limits: HashMap<String, usize>,
+        task_monitor: TaskMonitor,
+    ) -> Self {
+        let circuit_breaker = circuit_breaker.map(|conf| {
+            Arc::new(Mutex::new(CircuitBreaker::new(
+                conf,
+                metrics.circuit_breaker_state.clone(),
+                metrics.circuit_breaker_trips.clone(),
+            )))
+        });
+        let queue = |label: &str| {
+            OpQueue::new(
+                metrics.submitter_queue_length.clone(),
+                label.to_string(),
+                Arc::new(Mutex::new(retry_tx.subscribe())),
+            )
+        };
+        Self {
+            prepare_queue: queue("prepare_queue")
+                .with_app_concurrency_limits(app_concurrency_limits),
+            submit_queue: queue("submit_queue"),
+            confirm_queue: queue("confirm_queue"),
+            domain,
+            mailbox,
+            rx,
+            metrics,
+            max_batch_size,
+            batch_window,
+            max_in_flight,
+            circuit_breaker,
+            task_monitor,
+        }
+    }
+
+    /// The queues of the submitter, which are shared with its tasks
+    pub fn queues(&self) -> Vec<OpQueue> {
+        vec![
+            self.prepare_queue.clone(),
+            self.submit_queue.clone(),
+            self.confirm_queue.clone(),
+        ]
+    }
+
+    /// The circuit breaker of the submitter, if configured, which is shared
+    /// with its tasks
+    pub fn circuit_breaker(&self) -> Option<Arc<Mutex<CircuitBreaker>>> {
+        self.circuit_breaker.clone()
+    }
+
     pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
         let span = info_span!("SerialSubmitter", destination=%self.domain);
         let task_monitor = self.task_monitor.clone();
@@ -102,72 +210,74 @@ impl SerialSubmitter {
     async fn run(self) {
         let Self {
             domain,
+            mailbox,
             metrics,
             rx: rx_prepare,
-            retry_tx,
             max_batch_size,
+            batch_window,
+            max_in_flight,
+            circuit_breaker,
             task_monitor,
+            prepare_queue,
+            submit_queue,
+            confirm_queue,
         } = self;
-        let prepare_queue = OpQueue::new(
-            metrics.submitter_queue_length.clone(),
-            "prepare_queue".to_string(),
-            Arc::new(Mutex::new(retry_tx.subscribe())),
-        );
-        let submit_queue = OpQueue::new(
-            metrics.submitter_queue_length.clone(),
-            "submit_queue".to_string(),
-            Arc::new(Mutex::new(retry_tx.subscribe())),
-        );
-        let confirm_queue = OpQueue::new(
-            metrics.submitter_queue_length.clone(),
-            "confirm_queue".to_string(),
-            Arc::new(Mutex::new(retry_tx.subscribe())),
-        );
-
-        let tasks = [
-            tokio::spawn(TaskMonitor::instrument(
-                &task_monitor,
-                receive_task(domain.clone(), rx_prepare, prepare_queue.clone()),
-            )),
-            tokio::spawn(TaskMonitor::instrument(
-                &task_monitor,
-                prepare_task(
-                    domain.clone(),
-                    prepare_queue.clone(),
-                    submit_queue.clone(),
-                    confirm_queue.clone(),
-                    max_batch_size,
-                    metrics.clone(),
-                ),
-            )),
-            tokio::spawn(TaskMonitor::instrument(
-                &task_monitor,
-                submit_task(
-                    domain.clone(),
-                    submit_queue,
-                    confirm_queue.clone(),
-                    max_batch_size,
-                    metrics.clone(),
-                ),
-            )),
-            tokio::spawn(TaskMonitor::instrument(
-                &task_monitor,
-                confirm_task(
-                    domain.clone(),
-                    prepare_queue,
-                    confirm_queue,
-                    max_batch_size,
-                    metrics,
-                ),
-            )),
-        ];
-
-        if let Err(err) = try_join_all(tasks).await {
-            tracing::error!(
-                error=?err,
-                ?domain,
-                "SerialSubmitter task panicked for domain"
-            );
+
+        // Dropping the set aborts the tasks, so they stop along with the
+        // submitter
+        let mut tasks = JoinSet::new();
+        tasks.spawn(TaskMonitor::instrument(
+            &task_monitor,
+            receive_task(domain.clone(), rx_prepare, prepare_queue.clone()),
+        ));
+        tasks.spawn(TaskMonitor::instrument(
+            &task_monitor,
+            prepare_task(
+                domain.clone(),
+                prepare_queue.clone(),
+                submit_queue.clone(),
+                confirm_queue.clone(),
+                max_batch_size,
+                metrics.clone(),
+            ),
+        ));
+        tasks.spawn(TaskMonitor::instrument(
+            &task_monitor,
+            submit_task(
+                domain.clone(),
+                CongestionMonitor::new(mailbox),
+                prepare_queue.clone(),
+                submit_queue,
+                confirm_queue.clone(),
+                max_batch_size,
+                batch_window.map(BatchWindow::new),
+                max_in_flight,
+                circuit_breaker.clone(),
+                metrics.clone(),
+            ),
+        ));
+        tasks.spawn(TaskMonitor::instrument(
+            &task_monitor,
+            confirm_task(
+                domain.clone(),
+                prepare_queue,
+                confirm_queue,
+                max_batch_size,
+                circuit_breaker,
+                metrics,
+            ),
+        ));
+
+        while let Some(result) = tasks.join_next().await {
+            if let Err(err) = result {
+                tracing::error!(
+                    error=?err,
+                    ?domain,
+                    "SerialSubmitter task panicked for domain"
+                );
+                // The other tasks keep running
+                tasks.detach_all();
+            }
         }
     }
 }
@@ -256,18 +366,107 @@ async fn prepare_task(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 #[instrument(skip_all, fields(%domain))]
 async fn submit_task(
     domain: HyperlaneDomain,
+    mut congestion: CongestionMonitor,
+    mut prepare_queue: OpQueue,
     mut submit_queue: OpQueue,
     mut confirm_queue: OpQueue,
     max_batch_size: u32,
+    mut batch_window: Option<BatchWindow>,
+    max_in_flight: Option<u32>,
+    circuit_breaker: Option<Arc<Mutex<CircuitBreaker>>>,
     metrics: SerialSubmitterMetrics,
 ) {
-    let recv_limit = max_batch_size as usize;
     loop {
-        let mut batch = submit_queue.pop_many(recv_limit).await;
+        let breaker_limit = match &circuit_breaker {
+            Some(breaker) => breaker.lock().await.submission_limit(Instant::now()),
+            None => None,
+        };
+        if breaker_limit == Some(0) {
+            trace!("Submissions are paused by the circuit breaker");
+            sleep(CIRCUIT_OPEN_BACKOFF).await;
+            continue;
+        }
+        congestion.refresh().await;
+        let recv_limit = match max_in_flight {
+            Some(max) => {
+                let in_flight = confirm_queue.len().await;
+                (max as usize)
+                    .saturating_sub(in_flight)
+                    .min(max_batch_size as usize)
+            }
+            None => max_batch_size as usize,
+        };
+        let recv_limit = breaker_limit.map_or(recv_limit, |limit| recv_limit.min(limit));
+        if recv_limit == 0 {
+            trace!(
+                "Most operations that can be in flight were submitted, waiting for confirmations"
+            );
+            sleep(IN_FLIGHT_BACKOFF).await;
+            continue;
+        }
+        let batch = submit_queue.pop_many(recv_limit).await;
+        // Operations whose preparation was discarded, e.g. to re-estimate
+        // them, are prepared again
+        let (batch, unprepared): (Vec<_>, Vec<_>) = batch
+            .into_iter()
+            .partition(|op| op.get_tx_cost_estimate().is_some());
+        for op in unprepared {
+            prepare_queue.push(op).await;
+        }
+        let popped = batch.len();
+        let (mut batch, deferred) =
+            congestion.admit(batch, |op| op.has_been_submitted(), Instant::now());
+        if !deferred.is_empty() {
+            debug!(
+                level=?congestion.level(),
+                deferred=deferred.len(),
+                "Destination is congested, deferring new submissions"
+            );
+            for op in deferred {
+                submit_queue.push(op).await;
+            }
+        }
+        if popped > 0 && batch.is_empty() {
+            // Everything was deferred, so wait before checking congestion again
+            sleep(CONGESTION_BACKOFF).await;
+            continue;
+        }
+        if let Some(window) = batch_window.as_mut() {
+            // Replacements of our own transactions aren't held back
+            let replacing = batch.iter().any(|op| op.has_been_submitted());
+            if !batch.is_empty()
+                && !replacing
+                && !window.is_closed(batch.len(), recv_limit, Instant::now())
+            {
+                for op in batch {
+                    submit_queue.push(op).await;
+                }
+                sleep(BATCH_WINDOW_POLL_INTERVAL).await;
+                continue;
+            }
+            window.reset();
+        }
+
+        // Leases may have expired while the operations waited to be submitted
+        let mut leased = Vec::with_capacity(batch.len());
+        for mut op in batch {
+            if op.renew_lease().await {
+                leased.push(op);
+            } else {
+                debug!(?op, "Operation lost its lease, preparing it again");
+                op.discard_preparation();
+                prepare_queue.push(op).await;
+            }
+        }
+        let mut batch = leased;
 
+        if let Some(breaker) = &circuit_breaker {
+            breaker.lock().await.on_submitted(batch.len());
+        }
         match batch.len().cmp(&1) {
             std::cmp::Ordering::Less => {
                 // The queue is empty, so give some time before checking again to prevent burning CPU
@@ -280,7 +479,12 @@ async fn submit_task(
             }
             std::cmp::Ordering::Greater => {
                 OperationBatch::new(batch, domain.clone())
-                    .submit(&mut confirm_queue, &metrics)
+                    .submit(
+                        &mut prepare_queue,
+                        &mut confirm_queue,
+                        circuit_breaker.as_deref(),
+                        &metrics,
+                    )
                     .await;
             }
         }
@@ -317,6 +521,7 @@ async fn confirm_task(
     prepare_queue: OpQueue,
     mut confirm_queue: OpQueue,
     max_batch_size: u32,
+    circuit_breaker: Option<Arc<Mutex<CircuitBreaker>>>,
     metrics: SerialSubmitterMetrics,
 ) {
     let recv_limit = max_batch_size as usize;
@@ -336,6 +541,7 @@ async fn confirm_task(
                 domain.clone(),
                 prepare_queue.clone(),
                 confirm_queue.clone(),
+                circuit_breaker.clone(),
                 metrics.clone(),
             )
         });
@@ -358,12 +564,21 @@ async fn confirm_operation(
     domain: HyperlaneDomain,
     prepare_queue: OpQueue,
     confirm_queue: OpQueue,
+    circuit_breaker: Option<Arc<Mutex<CircuitBreaker>>>,
     metrics: SerialSubmitterMetrics,
 ) -> PendingOperationResult {
     trace!(?op, "Confirming operation");
     debug_assert_eq!(*op.destination_domain(), domain);
 
     let operation_result = op.confirm().await;
+    // Only submissions that were delivered or failed are recorded
+    if let Some(breaker) = &circuit_breaker {
+        match operation_result {
+            PendingOperationResult::Success => breaker.lock().await.record(true, Instant::now()),
+            PendingOperationResult::Reprepare => breaker.lock().await.record(false, Instant::now()),
+            _ => {}
+        }
+    }
     match operation_result {
         PendingOperationResult::Success => {
             debug!(?op, "Operation confirmed");
@@ -392,6 +607,8 @@ pub struct SerialSubmitterMetrics {
     ops_confirmed: IntCounter,
     ops_failed: IntCounter,
     ops_dropped: IntCounter,
+    circuit_breaker_state: IntGauge,
+    circuit_breaker_trips: IntCounter,
 }
 
 impl SerialSubmitterMetrics {
@@ -414,6 +631,12 @@ impl SerialSubmitterMetrics {
             ops_dropped: metrics
                 .operations_processed_count()
                 .with_label_values(&["dropped", destination]),
+            circuit_breaker_state: metrics
+                .circuit_breaker_state()
+                .with_label_values(&[destination]),
+            circuit_breaker_trips: metrics
+                .circuit_breaker_trips()
+                .with_label_values(&[destination]),
         }
     }
 }
@@ -426,15 +649,56 @@ struct OperationBatch {
 }
 
 impl OperationBatch {
-    async fn submit(self, confirm_queue: &mut OpQueue, metrics: &SerialSubmitterMetrics) {
+    async fn submit(
+        self,
+        prepare_queue: &mut OpQueue,
+        confirm_queue: &mut OpQueue,
+        circuit_breaker: Option<&Mutex<CircuitBreaker>>,
+        metrics: &SerialSubmitterMetrics,
+    ) {
         match self.try_submit_as_batch(metrics).await {
-            Ok(outcome) => {
-                info!(outcome=?outcome, batch_size=self.operations.len(), batch=?self.operations, "Submitted transaction batch");
+            Ok(result) if !result.outcome.executed => {
+                // Nothing was processed, so each operation is charged its
+                // share of the gas and submitted on its own instead
+                warn!(outcome=?result.outcome, batch=?self.operations, "Batch transaction reverted. Falling back to serial submission.");
                 let total_estimated_cost = total_estimated_cost(&self.operations);
-                for mut op in self.operations {
-                    op.set_operation_outcome(outcome.clone(), total_estimated_cost);
-                    op.set_next_attempt_after(CONFIRM_DELAY);
-                    confirm_queue.push(op).await;
+                let mut operations = self.operations;
+                for op in operations.iter_mut() {
+                    op.set_operation_outcome(result.outcome.clone(), total_estimated_cost);
+                }
+                return Self::new(operations, self.domain)
+                    .submit_serially(confirm_queue, metrics)
+                    .await;
+            }
+            Ok(result) => {
+                info!(outcome=?result.outcome, batch_size=self.operations.len(), batch=?self.operations, "Submitted transaction batch");
+                let total_estimated_cost = total_estimated_cost(&self.operations);
+                for (index, mut op) in self.operations.into_iter().enumerate() {
+                    // Gas is attributed to each item by the mailbox, so the
+                    // item is charged as if it was submitted on its own
+                    let Some(outcome) = result.item_outcome(index) else {
+                        op.set_operation_outcome(result.outcome.clone(), total_estimated_cost);
+                        op.set_next_attempt_after(CONFIRM_DELAY);
+                        confirm_queue.push(op).await;
+                        continue;
+                    };
+                    let executed = outcome.executed;
+                    op.set_operation_outcome(
+                        outcome,
+                        op.get_tx_cost_estimate().unwrap_or_default(),
+                    );
+                    if executed {
+                        op.set_next_attempt_after(CONFIRM_DELAY);
+                        confirm_queue.push(op).await;
+                    } else {
+                        // Only the items that reverted are prepared again
+                        debug!(?op, "Operation reverted in batch, repreparing it");
+                        metrics.ops_failed.inc();
+                        if let Some(breaker) = circuit_breaker {
+                            breaker.lock().await.record(false, Instant::now());
+                        }
+                        prepare_queue.push(op).await;
+                    }
                 }
                 return;
             }
@@ -449,7 +713,7 @@ impl OperationBatch {
     async fn try_submit_as_batch(
         &self,
         metrics: &SerialSubmitterMetrics,
-    ) -> ChainResult<TxOutcome> {
+    ) -> ChainResult<BatchResult> {
         let batch = self
             .operations
             .iter()
@@ -462,9 +726,9 @@ impl OperationBatch {
             return Err(ChainCommunicationError::BatchIsEmpty);
         };
 
-        let outcome = first_item.mailbox.process_batch(&batch).await?;
+        let result = first_item.mailbox.process_batch(&batch).await?;
         metrics.ops_submitted.inc_by(self.operations.len() as u64);
-        Ok(outcome)
+        Ok(result)
     }
 
     async fn submit_serially(self, confirm_queue: &mut OpQueue, metrics: &SerialSubmitterMetrics) {
@@ -473,3 +737,60 @@ impl OperationBatch {
         }
     }
 }
+
+/// Window during which operations that are ready are held back so that they
+/// can be submitted in a single batch.
+#[derive(Debug, Clone, Copy)]
+struct BatchWindow {
+    duration: Duration,
+    /// When the first operation of the next batch became ready
+    opened_at: Option<Instant>,
+}
+
+impl BatchWindow {
+    fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            opened_at: None,
+        }
+    }
+
+    /// Whether `ready` operations should be submitted at `now`, either
+    /// because they fill a batch or because the window elapsed. The window
+    /// opens the first time it is checked.
+    fn is_closed(&mut self, ready: usize, max_batch_size: usize, now: Instant) -> bool {
+        let opened_at = *self.opened_at.get_or_insert(now);
+        ready >= max_batch_size || now.duration_since(opened_at) >= self.duration
+    }
+
+    /// Open a new window for the next batch
+    fn reset(&mut self) {
+        self.opened_at = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_batch_window() {
+        let mut window = BatchWindow::new(Duration::from_millis(500));
+        let start = Instant::now();
+
+        // Partial batches wait for the window to elapse
+        assert!(!window.is_closed(1, 4, start));
+        assert!(!window.is_closed(3, 4, start + Duration::from_millis(499)));
+        assert!(window.is_closed(3, 4, start + Duration::from_millis(500)));
+
+        // Full batches are submitted right away
+        window.reset();
+        let later = start + Duration::from_secs(1);
+        assert!(window.is_closed(4, 4, later));
+
+        // The window restarts once it was reset
+        window.reset();
+        assert!(!window.is_closed(1, 4, later + Duration::from_millis(100)));
+        assert!(window.is_closed(1, 4, later + Duration::from_millis(600)));
+    }
+}
@@ -1,24 +1,38 @@
 use std::{
     fmt::{Debug, Formatter},
-    sync::Arc,
-    time::{Duration, Instant},
+    sync::{Arc, RwLock},
+    time::{Duration, Instant, SystemTime},
 };
 
 use async_trait::async_trait;
 use derive_new::new;
 use eyre::Result;
-use hyperlane_base::{db::HyperlaneRocksDB, CoreMetrics};
+use hyperlane_base::{
+    db::HyperlaneRocksDB, settings::link_to_message, CoreMetrics, GasLimitPolicyConf,
+    RetryPolicyConf,
+};
 use hyperlane_core::{
     gas_used_by_operation, make_op_try, BatchItem, ChainCommunicationError, ChainResult,
-    HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox, MessageSubmissionData,
-    PendingOperation, PendingOperationResult, TryBatchAs, TxOutcome, H256, U256,
+    DeliveryReceipt, DeliveryStatus, GasPaymentKey, HyperlaneChain, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneSigner, HyperlaneSignerExt, InterchainGasPaymaster, Mailbox,
+    MessageProfit, MessageSubmissionData, PendingMessageState, PendingOperation,
+    PendingOperationResult, ProcessRevert, ProcessSimulation, TryBatchAs, TxCostEstimate,
+    TxOutcome, H256, U256,
 };
-use prometheus::{IntCounter, IntGauge};
-use tracing::{debug, error, info, instrument, trace, warn};
+use hyperlane_ethereum::SingletonSignerHandle;
+use prometheus::{Counter, IntCounter, IntGauge};
+use tokio::sync::Semaphore;
+use tracing::{debug, error, info, info_span, instrument, trace, warn, Instrument, Span};
+
+use crate::settings::{matching_list::MatchingList, DeadLetterConf, PrioritizationPolicy};
 
 use super::{
+    coordination::MessageClaims,
     gas_payment::GasPaymentEnforcer,
-    metadata::{BaseMetadataBuilder, MessageMetadataBuilder, MetadataBuilder},
+    hooks::{HookVerdict, MessageHook},
+    metadata::{
+        BaseMetadataBuilder, MessageMetadataBuilder, MetadataBuilder, MetadataBuilderError,
+    },
 };
 
 pub const CONFIRM_DELAY: Duration = if cfg!(any(test, feature = "test-utils")) {
@@ -29,6 +43,9 @@ pub const CONFIRM_DELAY: Duration = if cfg!(any(test, feature = "test-utils")) {
     Duration::from_secs(60)
 };
 
+/// How often parked messages are checked for being resurrected
+const DEAD_LETTER_RECHECK: Duration = Duration::from_secs(60 * 60);
+
 /// The message context contains the links needed to submit a message. Each
 /// instance is for a unique origin -> destination pairing.
 pub struct MessageContext {
@@ -46,6 +63,51 @@ pub struct MessageContext {
     /// destination.
     pub transaction_gas_limit: Option<U256>,
     pub metrics: MessageSubmissionMetrics,
+    /// Signs receipts of confirmed deliveries, if enabled.
+    pub receipt_signer: Option<SingletonSignerHandle>,
+    /// The order in which messages that are due are submitted.
+    pub prioritization: PrioritizationPolicy,
+    /// When to park messages as undeliverable.
+    pub dead_letter: DeadLetterConf,
+    /// How messages to the destination are retried, if not with the default
+    /// schedule. It's replaced when the config is reloaded.
+    pub retry_policy: RwLock<Option<RetryPolicyConf>>,
+    /// How the estimated gas limits of messages to the destination are
+    /// adjusted, if at all
+    pub gas_limit_policy: Option<GasLimitPolicyConf>,
+    /// How the estimated gas limits of the messages of apps are adjusted, by
+    /// the first policy whose matching list matches them, taking precedence
+    /// over `gas_limit_policy`
+    pub app_gas_limit_policies: Arc<Vec<(MatchingList, GasLimitPolicyConf)>>,
+    /// The IGP of the origin, used to convert the cost of deliveries to the
+    /// origin native token.
+    pub origin_igp: Option<Arc<dyn InterchainGasPaymaster>>,
+    /// Limits how many messages from the origin have their metadata built
+    /// at once, across all destinations.
+    pub metadata_permits: Option<Arc<Semaphore>>,
+    /// Claims messages before they're prepared, if relayer instances
+    /// coordinate so that only one of them submits each message.
+    pub message_claims: Option<Arc<dyn MessageClaims>>,
+    /// Check messages before their metadata is built, in order
+    pub message_hooks: Arc<Vec<Arc<dyn MessageHook>>>,
+}
+
+impl MessageContext {
+    /// Whether to park a message whose last of `num_retries` attempts failed
+    /// with `error`. The max attempts of the retry policy take precedence
+    /// over the dead letter config's max retries.
+    pub fn should_park(&self, num_retries: u32, error: &str) -> bool {
+        let max_attempts = self
+            .retry_policy
+            .read()
+            .expect("poisoned lock")
+            .as_ref()
+            .and_then(|policy| policy.max_attempts);
+        match max_attempts {
+            Some(max) => num_retries >= max || self.dead_letter.parks_on(error),
+            None => self.dead_letter.should_park(num_retries, error),
+        }
+    }
 }
 
 /// A message that the submitter can and should try to submit.
@@ -57,7 +119,17 @@ pub struct PendingMessage {
     #[new(default)]
     submitted: bool,
     #[new(default)]
+    submission_attempted: bool,
+    #[new(default)]
     submission_data: Option<Box<MessageSubmissionData>>,
+    /// The optimistic ISM the message is pre-verified with when submitted,
+    /// instead of being delivered, using the metadata of the submission data
+    #[new(default)]
+    pre_verify_ism: Option<H256>,
+    /// Submission data persisted before a restart, reused for the first
+    /// preparation if it still simulates successfully
+    #[new(default)]
+    persisted_submission_data: Option<Box<MessageSubmissionData>>,
     #[new(default)]
     num_retries: u32,
     #[new(value = "Instant::now()")]
@@ -66,6 +138,23 @@ pub struct PendingMessage {
     next_attempt_after: Option<Instant>,
     #[new(default)]
     submission_outcome: Option<TxOutcome>,
+    /// When the relayer first saw the message
+    #[new(value = "SystemTime::now()")]
+    first_seen: SystemTime,
+    /// The gas paid for the message per gas of its last cost estimate, in
+    /// basis points
+    #[new(default)]
+    gas_payment_ratio_bps: Option<u64>,
+    /// Why the last attempt failed
+    #[new(default)]
+    last_error: Option<String>,
+    /// Whether the message is parked as undeliverable
+    #[new(default)]
+    dead_lettered: bool,
+    /// The span that preparing, submitting and confirming the message is
+    /// recorded in, which is part of the trace of the message
+    #[new(value = "Span::none()")]
+    span: Span,
 }
 
 impl Debug for PendingMessage {
@@ -100,6 +189,10 @@ impl Eq for PendingMessage {}
 
 impl TryBatchAs<HyperlaneMessage> for PendingMessage {
     fn try_batch(&self) -> ChainResult<BatchItem<HyperlaneMessage>> {
+        if self.pre_verify_ism.is_some() {
+            debug!("Cannot batch the pre-verification of a message, returning BatchingFailed");
+            return Err(ChainCommunicationError::BatchingFailed);
+        }
         match self.submission_data.as_ref() {
             None => {
                 warn!("Cannot batch message without submission data, returning BatchingFailed");
@@ -124,6 +217,24 @@ impl PendingOperation for PendingMessage {
         self.message.nonce
     }
 
+    fn rank(&self) -> u64 {
+        match &self.ctx.prioritization {
+            PrioritizationPolicy::Nonce => 0,
+            // Messages that haven't been estimated yet are ranked first, so
+            // that their ratio is known as soon as possible
+            PrioritizationPolicy::GasPaymentRatio => self
+                .gas_payment_ratio_bps
+                .map(|ratio| u64::MAX - ratio)
+                .unwrap_or(0),
+            PrioritizationPolicy::Age => self.first_seen_millis(),
+            PrioritizationPolicy::AppPriority(lists) => lists
+                .iter()
+                .position(|list| list.msg_matches(&self.message, false))
+                .unwrap_or(lists.len())
+                as u64,
+        }
+    }
+
     fn origin_domain_id(&self) -> u32 {
         self.message.origin
     }
@@ -136,45 +247,100 @@ impl PendingOperation for PendingMessage {
         self.app_context.clone()
     }
 
-    #[instrument(skip(self), ret, fields(id=?self.id()), level = "debug")]
+    #[instrument(parent = &self.span, skip(self), ret(level = "debug"), fields(id=?self.id()))]
     async fn prepare(&mut self) -> PendingOperationResult {
-        make_op_try!(|| self.on_reprepare());
+        make_op_try!(|err| self.on_reprepare(err));
+
+        if self.dead_lettered {
+            trace!("Message is parked as undeliverable");
+            self.set_next_attempt_after(DEAD_LETTER_RECHECK);
+            return PendingOperationResult::NotReady;
+        }
 
         if !self.is_ready() {
             trace!("Message is not ready to be submitted yet");
             return PendingOperationResult::NotReady;
         }
 
-        // If the message has already been processed, e.g. due to another relayer having
-        // already processed, then mark it as already-processed, and move on to
-        // the next tick.
-        let is_already_delivered = op_try!(
+        let delivery_status = op_try!(
             self.ctx
                 .destination_mailbox
-                .delivered(self.message.id())
+                .delivery_status(&self.message)
                 .await,
             "checking message delivery status"
         );
-        if is_already_delivered {
-            debug!("Message has already been delivered, marking as submitted.");
-            self.submitted = true;
-            self.set_next_attempt_after(CONFIRM_DELAY);
-            return PendingOperationResult::Confirm;
+        match delivery_status {
+            // If the message has already been processed, e.g. due to another relayer having
+            // already processed, then mark it as already-processed, and move on to
+            // the next tick.
+            DeliveryStatus::Delivered(delivery) => {
+                debug!(
+                    ?delivery,
+                    "Message has already been delivered, marking as submitted."
+                );
+                self.submitted = true;
+                self.set_next_attempt_after(CONFIRM_DELAY);
+                return PendingOperationResult::Confirm;
+            }
+            DeliveryStatus::Undeliverable { reason } => {
+                info!(
+                    recipient=?self.message.recipient,
+                    reason,
+                    "Dropping message because it can never be delivered"
+                );
+                self.release_claim().await;
+                return PendingOperationResult::Drop;
+            }
+            DeliveryStatus::Pending => {}
         }
 
-        let provider = self.ctx.destination_mailbox.provider();
-
-        // We cannot deliver to an address that is not a contract so check and drop if it isn't.
-        let is_contract = op_try!(
-            provider.is_contract(&self.message.recipient).await,
-            "checking if message recipient is a contract"
-        );
-        if !is_contract {
-            info!(
-                recipient=?self.message.recipient,
-                "Dropping message because recipient is not a contract"
+        if let Some(claims) = self.ctx.message_claims.clone() {
+            let claimed = op_try!(
+                claims.claim(self.message.id()).await,
+                "claiming message for this relayer instance"
             );
-            return PendingOperationResult::Drop;
+            if !claimed {
+                debug!("Message is claimed by another relayer instance");
+                self.set_next_attempt_after(claims.lease());
+                return PendingOperationResult::NotReady;
+            }
+        }
+
+        if let Some(result) = self.run_message_hooks().await {
+            return result;
+        }
+
+        self.pre_verify_ism = None;
+        if let Some(data) = self.persisted_submission_data.take() {
+            // Only metadata that simulates successfully is reused, since it
+            // can't be checked on chains that can't simulate. Its costs are
+            // estimated again to apply the current gas payment policies.
+            match self
+                .ctx
+                .destination_mailbox
+                .simulate_process(&self.message, &data.metadata)
+                .await
+            {
+                Ok(ProcessSimulation::Succeeded) => {
+                    debug!("Reusing the metadata persisted before the restart");
+                    let tx_cost_estimate = op_try!(
+                        self.ctx
+                            .destination_mailbox
+                            .process_estimate_costs(&self.message, &data.metadata)
+                            .await,
+                        "estimating costs for process call"
+                    );
+                    return self
+                        .prepare_submission(data.metadata, tx_cost_estimate)
+                        .await;
+                }
+                result => {
+                    debug!(
+                        ?result,
+                        "Submission data persisted before the restart can't be reused, preparing again"
+                    );
+                }
+            }
         }
 
         let ism_address = op_try!(
@@ -185,6 +351,11 @@ impl PendingOperation for PendingMessage {
             "fetching ISM address. Potentially malformed recipient ISM address."
         );
 
+        // Held until the metadata is built
+        let metadata_permit = match &self.ctx.metadata_permits {
+            Some(permits) => permits.acquire().await.ok(),
+            None => None,
+        };
         let message_metadata_builder = op_try!(
             MessageMetadataBuilder::new(
                 ism_address,
@@ -195,62 +366,85 @@ impl PendingOperation for PendingMessage {
             "getting the message metadata builder"
         );
 
-        let Some(metadata) = op_try!(
-            message_metadata_builder
-                .build(ism_address, &self.message)
-                .await,
-            "building metadata"
-        ) else {
+        let metadata = message_metadata_builder
+            .build(ism_address, &self.message)
+            .instrument(info_span!("build_metadata", ?ism_address))
+            .await;
+        drop(metadata_permit);
+        match metadata
+            .as_ref()
+            .err()
+            .and_then(|err| err.downcast_ref::<MetadataBuilderError>())
+        {
+            // Messages in the fraud window of an optimistic ISM are prepared
+            // again once it elapsed, rather than backing off
+            Some(MetadataBuilderError::FraudWindowPending(remaining)) => {
+                debug!(
+                    ?remaining,
+                    "Message is in the fraud window of an optimistic ISM"
+                );
+                self.set_next_attempt_after(*remaining);
+                return PendingOperationResult::NotReady;
+            }
+            Some(MetadataBuilderError::PreVerificationRequired { ism, metadata }) => {
+                return self.prepare_pre_verification(*ism, metadata.clone()).await;
+            }
+            _ => {}
+        }
+        let Some(metadata) = op_try!(metadata, "building metadata") else {
             info!("Could not fetch metadata");
-            return self.on_reprepare();
+            return self.on_reprepare("Could not fetch metadata");
         };
 
+        // Simulate the process call first, so that messages that would revert
+        // don't cost gas and are retried or parked depending on why
+        if let ProcessSimulation::Reverted(revert) = op_try!(
+            self.ctx
+                .destination_mailbox
+                .simulate_process(&self.message, &metadata)
+                .await,
+            "simulating process call"
+        ) {
+            return self.on_simulated_revert(revert);
+        }
+
         // Estimate transaction costs for the process call. If there are issues, it's
         // likely that gas estimation has failed because the message is
         // reverting. This is defined behavior, so we just log the error and
         // move onto the next tick.
-        let tx_cost_estimate = op_try!(
-            self.ctx
-                .destination_mailbox
-                .process_estimate_costs(&self.message, &metadata)
-                .await,
-            "estimating costs for process call"
-        );
+        let tx_cost_estimate = self
+            .ctx
+            .destination_mailbox
+            .process_estimate_costs(&self.message, &metadata)
+            .await;
+        if tx_cost_estimate.is_err() {
+            // The metadata may have been built for an ISM configuration that
+            // changed since it was cached
+            self.invalidate_ism_config_cache();
+        }
+        let tx_cost_estimate = op_try!(tx_cost_estimate, "estimating costs for process call");
+        self.prepare_submission(metadata, tx_cost_estimate).await
+    }
 
-        // If the gas payment requirement hasn't been met, move to the next tick.
-        let Some(gas_limit) = op_try!(
-            self.ctx
-                .origin_gas_payment_enforcer
-                .message_meets_gas_payment_requirement(&self.message, &tx_cost_estimate)
-                .await,
-            "checking if message meets gas payment requirement"
-        ) else {
-            warn!(?tx_cost_estimate, "Gas payment requirement not met yet");
-            return self.on_reprepare();
+    async fn renew_lease(&mut self) -> bool {
+        let Some(claims) = self.ctx.message_claims.clone() else {
+            return true;
         };
-
-        // Go ahead and attempt processing of message to destination chain.
-        debug!(
-            ?gas_limit,
-            ?tx_cost_estimate,
-            "Gas payment requirement met, ready to process message"
-        );
-
-        if let Some(max_limit) = self.ctx.transaction_gas_limit {
-            if gas_limit > max_limit {
-                info!("Message delivery estimated gas exceeds max gas limit");
-                return self.on_reprepare();
+        match claims.claim(self.message.id()).await {
+            Ok(true) => true,
+            Ok(false) => {
+                debug!("Message was claimed by another relayer instance before it was submitted");
+                false
+            }
+            Err(err) => {
+                // Don't risk submitting a message another instance may have claimed
+                warn!(error=?err, "Failed to renew the claim on the message");
+                false
             }
         }
-
-        self.submission_data = Some(Box::new(MessageSubmissionData {
-            metadata,
-            gas_limit,
-        }));
-        PendingOperationResult::Success
     }
 
-    #[instrument]
+    #[instrument(parent = &self.span)]
     async fn submit(&mut self) {
         if self.submitted {
             // this message has already been submitted, possibly not by us
@@ -261,14 +455,19 @@ impl PendingOperation for PendingMessage {
             .submission_data
             .clone()
             .expect("Pending message must be prepared before it can be submitted");
+        self.submission_attempted = true;
 
-        // We use the estimated gas limit from the prior call to
-        // `process_estimate_costs` to avoid a second gas estimation.
-        let tx_outcome = self
-            .ctx
-            .destination_mailbox
-            .process(&self.message, &state.metadata, Some(state.gas_limit))
-            .await;
+        // We use the gas limit estimated when preparing to avoid a second gas
+        // estimation.
+        let tx_outcome = match self.pre_verify_ism {
+            Some(ism_address) => self.pre_verify(ism_address, &state).await,
+            None => self
+                .ctx
+                .destination_mailbox
+                .process(&self.message, &state.metadata, Some(state.gas_limit))
+                .await
+                .map_err(Into::into),
+        };
         match tx_outcome {
             Ok(outcome) => {
                 self.set_operation_outcome(outcome, state.gas_limit);
@@ -287,10 +486,25 @@ impl PendingOperation for PendingMessage {
         self.submission_data.as_ref().map(|d| d.gas_limit)
     }
 
+    fn discard_preparation(&mut self) {
+        self.submission_data = None;
+        // The next submission is of a newly prepared transaction rather than
+        // a replacement
+        self.submission_attempted = false;
+        self.submission_outcome = None;
+    }
+
+    fn has_been_submitted(&self) -> bool {
+        // Batched submissions only record the outcome
+        self.submission_attempted || self.submission_outcome.is_some()
+    }
+
+    #[instrument(parent = &self.span, skip(self), fields(id=?self.id()))]
     async fn confirm(&mut self) -> PendingOperationResult {
-        make_op_try!(|| {
+        make_op_try!(|err| {
             // Provider error; just try again later
             // Note: this means that we are using `NotReady` for a retryable error case
+            self.last_error = Some(err);
             self.inc_attempts();
             PendingOperationResult::NotReady
         });
@@ -299,6 +513,25 @@ impl PendingOperation for PendingMessage {
             return PendingOperationResult::NotReady;
         }
 
+        // A pre-verified message is prepared again, which waits for the
+        // fraud window it's now in to elapse
+        if self.pre_verify_ism.take().is_some() {
+            // The message itself wasn't submitted yet
+            let outcome = self.submission_outcome.take();
+            self.discard_preparation();
+            return match outcome {
+                Some(outcome) if outcome.executed => {
+                    info!(?outcome, "Message pre-verified with an optimistic ISM");
+                    self.submitted = false;
+                    PendingOperationResult::Reprepare
+                }
+                outcome => {
+                    warn!(tx_outcome=?outcome, "Pre-verification either reverted or failed");
+                    self.on_reprepare("Pre-verification either reverted or failed")
+                }
+            };
+        }
+
         let is_delivered = op_try!(
             self.ctx
                 .destination_mailbox
@@ -311,6 +544,11 @@ impl PendingOperation for PendingMessage {
                 critical: self.record_message_process_success(),
                 "recording message process success"
             );
+            if let Some(signer) = &self.ctx.receipt_signer {
+                self.store_delivery_receipt(signer).await;
+            }
+            self.record_profit().await;
+            self.release_claim().await;
             info!(
                 submission=?self.submission_outcome,
                 "Message successfully processed"
@@ -322,7 +560,8 @@ impl PendingOperation for PendingMessage {
                 message_id=?self.message.id(),
                 "Transaction attempting to process message either reverted or was reorged"
             );
-            self.on_reprepare()
+            self.invalidate_ism_config_cache();
+            self.on_reprepare("Transaction either reverted or was reorged")
         }
     }
 
@@ -376,19 +615,33 @@ impl PendingOperation for PendingMessage {
 
     fn set_next_attempt_after(&mut self, delay: Duration) {
         self.next_attempt_after = Some(Instant::now() + delay);
+        self.persist_state();
     }
 
     fn reset_attempts(&mut self) {
         self.reset_attempts();
     }
 
+    fn num_retries(&self) -> u32 {
+        self.num_retries
+    }
+
+    fn last_error(&self) -> Option<String> {
+        self.last_error.clone()
+    }
+
+    fn is_dead_lettered(&self) -> bool {
+        self.dead_lettered
+    }
+
     fn set_retries(&mut self, retries: u32) {
         self.set_retries(retries);
     }
 }
 
 impl PendingMessage {
-    /// Constructor that tries reading the retry count from the HyperlaneDB in order to recompute the `next_attempt_after`.
+    /// Constructor that tries restoring the state persisted in the HyperlaneDB before a restart,
+    /// falling back to reading the retry count in order to recompute the `next_attempt_after`.
     /// In case of failure, behaves like `Self::new(...)`.
     pub fn from_persisted_retries(
         message: HyperlaneMessage,
@@ -396,30 +649,317 @@ impl PendingMessage {
         app_context: Option<String>,
     ) -> Self {
         let mut pm = Self::new(message, ctx, app_context);
-        match pm
+        pm.span = info_span!(
+            "message",
+            id = ?pm.message.id(),
+            origin = pm.message.origin,
+            destination = pm.message.destination,
+            nonce = pm.message.nonce
+        );
+        link_to_message(&pm.span, pm.message.id());
+        let persisted_state = pm
+            .ctx
+            .origin_db
+            .retrieve_pending_message_state_by_message_id(&pm.message.id());
+        match persisted_state {
+            Ok(Some(state)) => pm.restore_state(state),
+            r => {
+                trace!(message_id = ?pm.message.id(), result = ?r, "Failed to read pending message state from HyperlaneDB for message.");
+                pm.restore_retries();
+            }
+        }
+        if let Ok(Some(true)) = pm
+            .ctx
+            .origin_db
+            .retrieve_dead_lettered_by_message_id(&pm.message.id())
+        {
+            pm.dead_lettered = true;
+            pm.ctx.metrics.dead_lettered.inc();
+        }
+        debug!(parent: &pm.span, num_retries = pm.num_retries, "Message enqueued");
+        pm
+    }
+
+    /// Restore the backoff schedule, submission ordering and prepared
+    /// submission persisted before a restart
+    fn restore_state(&mut self, state: PendingMessageState) {
+        self.num_retries = state.num_retries;
+        self.next_attempt_after = (state.next_attempt_at > 0).then(|| {
+            let remaining = state.next_attempt_at.saturating_sub(unix_timestamp());
+            Instant::now() + Duration::from_secs(remaining)
+        });
+        self.first_seen = SystemTime::UNIX_EPOCH + Duration::from_millis(state.first_seen);
+        self.submitted = state.submitted;
+        self.persisted_submission_data = state.submission_data.map(Box::new);
+    }
+
+    /// Recompute the `next_attempt_after` from the persisted retry count,
+    /// for messages whose state was persisted before it was tracked in full
+    fn restore_retries(&mut self) {
+        match self
             .ctx
             .origin_db
-            .retrieve_pending_message_retry_count_by_message_id(&pm.message.id())
+            .retrieve_pending_message_retry_count_by_message_id(&self.message.id())
         {
             Ok(Some(num_retries)) => {
-                let next_attempt_after = PendingMessage::calculate_msg_backoff(num_retries)
-                    .map(|dur| Instant::now() + dur);
-                pm.num_retries = num_retries;
-                pm.next_attempt_after = next_attempt_after;
+                self.num_retries = num_retries;
+                self.next_attempt_after = self.backoff(num_retries).map(|dur| Instant::now() + dur);
             }
             r => {
-                trace!(message_id = ?pm.message.id(), result = ?r, "Failed to read retry count from HyperlaneDB for message.")
+                trace!(message_id = ?self.message.id(), result = ?r, "Failed to read retry count from HyperlaneDB for message.")
             }
         }
-        pm
     }
 
-    fn on_reprepare(&mut self) -> PendingOperationResult {
-        self.inc_attempts();
+    /// When the relayer first saw the message, in milliseconds since the
+    /// unix epoch
+    fn first_seen_millis(&self) -> u64 {
+        self.first_seen
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+
+    /// The gas paid for the message per gas of `tx_cost_estimate`, in basis
+    /// points
+    fn gas_payment_ratio_bps(&self, tx_cost_estimate: &TxCostEstimate) -> Result<Option<u64>> {
+        let payment =
+            self.ctx
+                .origin_db
+                .retrieve_gas_payment_by_gas_payment_key(GasPaymentKey {
+                    message_id: self.message.id(),
+                    destination: self.message.destination,
+                })?;
+        let estimated_gas = tx_cost_estimate.enforceable_gas_limit()?;
+        if estimated_gas.is_zero() {
+            return Ok(None);
+        }
+        let ratio = payment.gas_amount.saturating_mul(10_000.into()) / estimated_gas;
+        Ok(Some(ratio.min(u64::MAX.into()).as_u64()))
+    }
+
+    /// Whether the message outlived the TTL of the dead letter expiry rule
+    /// that matches it
+    fn is_expired(&self) -> bool {
+        let age = self.first_seen.elapsed().unwrap_or_default();
+        self.ctx.dead_letter.is_expired(&self.message, age)
+    }
+
+    /// The policy adjusting the estimated gas limit of the message, if any
+    fn gas_limit_policy(&self) -> Option<&GasLimitPolicyConf> {
+        self.ctx
+            .app_gas_limit_policies
+            .iter()
+            .find(|(matching_list, _)| matching_list.msg_matches(&self.message, false))
+            .map(|(_, policy)| policy)
+            .or(self.ctx.gas_limit_policy.as_ref())
+    }
+
+    /// Drop the ISM configuration cached for the origin of the message, so
+    /// that it is fetched again when the message is next prepared
+    fn invalidate_ism_config_cache(&self) {
+        self.ctx
+            .metadata_builder
+            .ism_config_cache()
+            .invalidate(self.message.origin);
+    }
+
+    /// Route a message whose simulated delivery reverted: delivered messages
+    /// are confirmed, ISM failures are retried with a fresh ISM configuration,
+    /// and recipient reverts are parked if their reason is configured to be.
+    fn on_simulated_revert(&mut self, revert: ProcessRevert) -> PendingOperationResult {
+        self.ctx.metrics.avoided_failed_transactions(&revert).inc();
+        match revert {
+            ProcessRevert::AlreadyDelivered => {
+                debug!("Simulated delivery shows the message was already delivered");
+                self.submitted = true;
+                self.set_next_attempt_after(CONFIRM_DELAY);
+                PendingOperationResult::Confirm
+            }
+            ProcessRevert::IsmVerificationFailed { reason } => {
+                warn!(reason, "Simulated delivery fails ISM verification");
+                self.invalidate_ism_config_cache();
+                self.on_reprepare(format!("ISM verification failed: {reason}"))
+            }
+            ProcessRevert::RecipientReverted { reason } => {
+                warn!(reason, "Simulated delivery reverts in the recipient");
+                self.on_reprepare(format!("Recipient reverted: {reason}"))
+            }
+        }
+    }
+
+    /// Run the message hooks, returning early with the result of the
+    /// preparation if one of them defers or vetoes the message
+    async fn run_message_hooks(&mut self) -> Option<PendingOperationResult> {
+        for hook in self.ctx.message_hooks.clone().iter() {
+            match hook.check(&self.message).await {
+                HookVerdict::Proceed => {}
+                HookVerdict::Annotate(note) => {
+                    info!(hook = hook.name(), note, "Message annotated by hook");
+                }
+                HookVerdict::Defer(delay) => {
+                    debug!(hook = hook.name(), ?delay, "Message deferred by hook");
+                    self.set_next_attempt_after(delay);
+                    return Some(PendingOperationResult::NotReady);
+                }
+                HookVerdict::Veto(reason) => {
+                    warn!(hook = hook.name(), reason, "Message vetoed by hook");
+                    self.last_error = Some(format!("Vetoed by {} hook: {reason}", hook.name()));
+                    self.set_dead_lettered(true);
+                    self.set_next_attempt_after(DEAD_LETTER_RECHECK);
+                    return Some(PendingOperationResult::NotReady);
+                }
+            }
+        }
+        None
+    }
+
+    /// Check that the gas payment of the message covers `tx_cost_estimate`,
+    /// and if it does, prepare submitting it with `metadata`
+    async fn prepare_submission(
+        &mut self,
+        metadata: Vec<u8>,
+        mut tx_cost_estimate: TxCostEstimate,
+    ) -> PendingOperationResult {
+        make_op_try!(|err| self.on_reprepare(err));
+
+        if let Some(policy) = self.gas_limit_policy() {
+            let gas_limit = policy.apply(tx_cost_estimate.gas_limit);
+            debug!(
+                estimated_gas_limit = ?tx_cost_estimate.gas_limit,
+                ?gas_limit,
+                ?policy,
+                "Adjusted estimated gas limit"
+            );
+            tx_cost_estimate.gas_limit = gas_limit;
+        }
+        self.gas_payment_ratio_bps = op_try!(
+            self.gas_payment_ratio_bps(&tx_cost_estimate),
+            "computing the gas payment ratio"
+        );
+
+        // If the gas payment requirement hasn't been met, move to the next tick.
+        let Some(gas_limit) = op_try!(
+            self.ctx
+                .origin_gas_payment_enforcer
+                .message_meets_gas_payment_requirement(&self.message, &tx_cost_estimate)
+                .await,
+            "checking if message meets gas payment requirement"
+        ) else {
+            warn!(?tx_cost_estimate, "Gas payment requirement not met yet");
+            return self.on_reprepare("Gas payment requirement not met yet");
+        };
+
+        // Go ahead and attempt processing of message to destination chain.
+        debug!(
+            ?gas_limit,
+            ?tx_cost_estimate,
+            "Gas payment requirement met, ready to process message"
+        );
+
+        if let Some(max_limit) = self.ctx.transaction_gas_limit {
+            if gas_limit > max_limit {
+                info!("Message delivery estimated gas exceeds max gas limit");
+                return self.on_reprepare("Message delivery estimated gas exceeds max gas limit");
+            }
+        }
+
+        self.submission_data = Some(Box::new(MessageSubmissionData {
+            metadata,
+            gas_limit,
+        }));
+        self.persist_state();
+        PendingOperationResult::Success
+    }
+
+    /// Prepare pre-verifying the message with the optimistic ISM at
+    /// `ism_address`, which starts its fraud window, using the `metadata` of
+    /// its submodule. Like deliveries, pre-verifications have to be paid for.
+    async fn prepare_pre_verification(
+        &mut self,
+        ism_address: H256,
+        metadata: Vec<u8>,
+    ) -> PendingOperationResult {
+        make_op_try!(|err| self.on_reprepare(err));
+
+        let ism = op_try!(
+            self.ctx
+                .metadata_builder
+                .build_optimistic_ism(ism_address)
+                .await,
+            "building optimistic ISM"
+        );
+        let tx_cost_estimate = op_try!(
+            ism.pre_verify_estimate_costs(&self.message, &metadata)
+                .await,
+            "estimating costs for pre-verification"
+        );
+        debug!(
+            ?ism_address,
+            "Message has to be pre-verified with an optimistic ISM"
+        );
+        self.pre_verify_ism = Some(ism_address);
+        self.prepare_submission(metadata, tx_cost_estimate).await
+    }
+
+    /// Pre-verify the message with the optimistic ISM at `ism_address`
+    async fn pre_verify(
+        &self,
+        ism_address: H256,
+        state: &MessageSubmissionData,
+    ) -> Result<TxOutcome> {
+        let ism = self
+            .ctx
+            .metadata_builder
+            .build_optimistic_ism(ism_address)
+            .await?;
+        let outcome = ism
+            .pre_verify(&self.message, &state.metadata, Some(state.gas_limit))
+            .await?;
+        Ok(outcome)
+    }
+
+    fn on_reprepare(&mut self, reason: impl Into<String>) -> PendingOperationResult {
+        let reason = reason.into();
         self.submitted = false;
+        self.discard_preparation();
+        self.inc_attempts();
+        if self.ctx.should_park(self.num_retries, &reason) {
+            self.set_dead_lettered(true);
+        } else if self.is_expired() {
+            info!(first_seen=?self.first_seen, "Message expired without being delivered");
+            self.set_dead_lettered(true);
+        }
+        self.last_error = Some(reason);
         PendingOperationResult::Reprepare
     }
 
+    /// Park the message as undeliverable, or resurrect it. The state is
+    /// persisted so that it survives restarts.
+    fn set_dead_lettered(&mut self, dead_lettered: bool) {
+        if self.dead_lettered == dead_lettered {
+            return;
+        }
+        if dead_lettered {
+            warn!(
+                num_retries = self.num_retries,
+                "Parking message as undeliverable until it's retried"
+            );
+            self.ctx.metrics.dead_lettered.inc();
+        } else {
+            info!("Resurrecting message that was parked as undeliverable");
+            self.ctx.metrics.dead_lettered.dec();
+        }
+        self.dead_lettered = dead_lettered;
+        if let Err(e) = self
+            .ctx
+            .origin_db
+            .store_dead_lettered_by_message_id(&self.message.id(), &dead_lettered)
+        {
+            warn!(message_id = ?self.message.id(), err = %e, "Persisting the dead letter state failed for message");
+        }
+    }
+
     fn is_ready(&self) -> bool {
         self.next_attempt_after
             .map(|a| Instant::now() >= a)
@@ -442,31 +982,168 @@ impl PendingMessage {
         Ok(())
     }
 
+    /// Sign and store a receipt of the delivery of the message by our
+    /// submission. Failing to do so is logged but doesn't fail the operation,
+    /// as the delivery itself is final.
+    async fn store_delivery_receipt(&self, signer: &SingletonSignerHandle) {
+        // The message may have been delivered by someone else, in which case
+        // our submission reverted.
+        let Some(outcome) = self.submission_outcome.as_ref().filter(|o| o.executed) else {
+            debug!("Message was not delivered by our submission, not storing a delivery receipt");
+            return;
+        };
+        let receipt = DeliveryReceipt {
+            message_id: self.message.id(),
+            destination_domain: self.message.destination,
+            transaction_id: outcome.transaction_id,
+            timestamp: unix_timestamp(),
+            relayer: signer.eth_address(),
+        };
+        let signed = match signer.sign(receipt).await {
+            Ok(signed) => signed,
+            Err(err) => {
+                warn!(error=?err, ?receipt, "Failed to sign delivery receipt");
+                return;
+            }
+        };
+        if let Err(err) = self
+            .ctx
+            .origin_db
+            .store_delivery_receipt_by_message_id(&receipt.message_id, &signed)
+        {
+            warn!(error=?err, ?receipt, "Failed to store delivery receipt");
+        }
+    }
+
+    /// Record how the gas payment for the message compares with what was
+    /// spent processing it. Failing to do so is logged but doesn't fail the
+    /// operation.
+    async fn record_profit(&self) {
+        let message_id = self.message.id();
+        let db = &self.ctx.origin_db;
+        let payment = db.retrieve_gas_payment_by_gas_payment_key(GasPaymentKey {
+            message_id,
+            destination: self.message.destination,
+        });
+        let expenditure = db.retrieve_gas_expenditure_by_message_id(message_id);
+        let (payment, expenditure) = match (payment, expenditure) {
+            (Ok(payment), Ok(expenditure)) => (payment, expenditure),
+            (Err(err), _) | (_, Err(err)) => {
+                warn!(error=?err, "Failed to retrieve gas payment and expenditure of message");
+                return;
+            }
+        };
+        let token_exchange_rate = match &self.ctx.origin_igp {
+            Some(igp) => igp
+                .token_exchange_rate(self.message.destination)
+                .await
+                .unwrap_or_else(|err| {
+                    warn!(error=?err, "Failed to fetch the token exchange rate of the gas oracle");
+                    U256::zero()
+                }),
+            None => U256::zero(),
+        };
+        let profit = MessageProfit {
+            message_id,
+            nonce: self.message.nonce,
+            origin_domain: self.message.origin,
+            destination_domain: self.message.destination,
+            payment: payment.payment,
+            gas_used: expenditure.gas_used,
+            tokens_used: expenditure.tokens_used,
+            token_exchange_rate,
+            timestamp: unix_timestamp(),
+        };
+        if let Some(cost) = profit.cost() {
+            self.ctx
+                .metrics
+                .gas_payments
+                .inc_by(profit.payment.to_f64_lossy());
+            self.ctx.metrics.gas_costs.inc_by(cost.to_f64_lossy());
+        }
+        if let Err(err) = db.store_message_profit_by_nonce(&self.message.nonce, &profit) {
+            warn!(error=?err, ?profit, "Failed to store message profit");
+        }
+    }
+
+    /// Release the claim on a message that won't be submitted again, so that
+    /// claims don't pile up. Claims that fail to be released are deleted once
+    /// they expired, the next time a relayer instance starts.
+    async fn release_claim(&self) {
+        if let Some(claims) = &self.ctx.message_claims {
+            if let Err(err) = claims.release(self.message.id()).await {
+                warn!(error=?err, "Failed to release the claim on the message");
+            }
+        }
+    }
+
     fn reset_attempts(&mut self) {
-        self.set_retries(0);
+        self.set_dead_lettered(false);
+        self.num_retries = 0;
         self.next_attempt_after = None;
         self.last_attempted_at = Instant::now();
+        self.persist_state();
     }
 
     fn inc_attempts(&mut self) {
-        self.set_retries(self.num_retries + 1);
+        self.num_retries += 1;
         self.last_attempted_at = Instant::now();
-        self.next_attempt_after = PendingMessage::calculate_msg_backoff(self.num_retries)
+        self.next_attempt_after = self
+            .backoff(self.num_retries)
             .map(|dur| self.last_attempted_at + dur);
+        self.persist_state();
     }
 
     fn set_retries(&mut self, retries: u32) {
         self.num_retries = retries;
-        self.persist_retries();
+        self.persist_state();
     }
 
-    fn persist_retries(&self) {
+    /// Persist the retry count, backoff deadline, first-seen time and
+    /// prepared submission of the message, so that they're restored after a
+    /// restart
+    fn persist_state(&self) {
+        let id = self.message.id();
+        if let Err(e) = self
+            .ctx
+            .origin_db
+            .store_pending_message_retry_count_by_message_id(&id, &self.num_retries)
+        {
+            warn!(message_id = ?id, err = %e, "Persisting the `num_retries` failed for message");
+        }
+        let now = Instant::now();
+        let state = PendingMessageState {
+            num_retries: self.num_retries,
+            next_attempt_at: self
+                .next_attempt_after
+                .map(|at| unix_timestamp() + at.saturating_duration_since(now).as_secs())
+                .unwrap_or(0),
+            first_seen: self.first_seen_millis(),
+            submitted: self.submitted,
+            // The metadata of a pre-verification can't be used to deliver
+            // the message
+            submission_data: self
+                .submission_data
+                .as_deref()
+                .filter(|_| self.pre_verify_ism.is_none())
+                .cloned(),
+        };
         if let Err(e) = self
             .ctx
             .origin_db
-            .store_pending_message_retry_count_by_message_id(&self.message.id(), &self.num_retries)
+            .store_pending_message_state_by_message_id(&id, &state)
         {
-            warn!(message_id = ?self.message.id(), err = %e, "Persisting the `num_retries` failed for message");
+            warn!(message_id = ?id, err = %e, "Persisting the pending message state failed for message");
+        }
+    }
+
+    /// Get duration we should wait before re-attempting to deliver this
+    /// message given the number of retries, following the retry policy of
+    /// the destination if it has one.
+    fn backoff(&self, num_retries: u32) -> Option<Duration> {
+        match &*self.ctx.retry_policy.read().expect("poisoned lock") {
+            Some(policy) => policy.backoff(num_retries, self.message.id().to_low_u64_be()),
+            None => Self::calculate_msg_backoff(num_retries),
         }
     }
 
@@ -490,11 +1167,25 @@ impl PendingMessage {
     }
 }
 
+/// The current time in seconds since the unix epoch
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
 #[derive(Debug)]
 pub struct MessageSubmissionMetrics {
     // Fields are public for testing purposes
     pub last_known_nonce: IntGauge,
     pub messages_processed: IntCounter,
+    pub dead_lettered: IntGauge,
+    pub gas_payments: Counter,
+    pub gas_costs: Counter,
+    pub avoided_already_delivered: IntCounter,
+    pub avoided_ism_verification_failures: IntCounter,
+    pub avoided_recipient_reverts: IntCounter,
 }
 
 impl MessageSubmissionMetrics {
@@ -505,6 +1196,11 @@ impl MessageSubmissionMetrics {
     ) -> Self {
         let origin = origin.name();
         let destination = destination.name();
+        let avoided_failed_transactions = |reason| {
+            metrics
+                .avoided_failed_transactions()
+                .with_label_values(&[origin, destination, reason])
+        };
         Self {
             last_known_nonce: metrics.last_known_message_nonce().with_label_values(&[
                 "message_processed",
@@ -514,6 +1210,30 @@ impl MessageSubmissionMetrics {
             messages_processed: metrics
                 .messages_processed_count()
                 .with_label_values(&[origin, destination]),
+            dead_lettered: metrics
+                .dead_lettered_messages()
+                .with_label_values(&[origin, destination]),
+            gas_payments: metrics
+                .message_gas_payments()
+                .with_label_values(&[origin, destination]),
+            gas_costs: metrics
+                .message_gas_costs()
+                .with_label_values(&[origin, destination]),
+            avoided_already_delivered: avoided_failed_transactions("already_delivered"),
+            avoided_ism_verification_failures: avoided_failed_transactions(
+                "ism_verification_failed",
+            ),
+            avoided_recipient_reverts: avoided_failed_transactions("recipient_reverted"),
+        }
+    }
+
+    /// The counter of transactions avoided because their simulation
+    /// reverted with `revert`
+    fn avoided_failed_transactions(&self, revert: &ProcessRevert) -> &IntCounter {
+        match revert {
+            ProcessRevert::AlreadyDelivered => &self.avoided_already_delivered,
+            ProcessRevert::IsmVerificationFailed { .. } => &self.avoided_ism_verification_failures,
+            ProcessRevert::RecipientReverted { .. } => &self.avoided_recipient_reverts,
         }
     }
 
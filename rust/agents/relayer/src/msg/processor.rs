@@ -1,6 +1,6 @@
 use std::{
     cmp::max,
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     fmt::{Debug, Formatter},
     sync::Arc,
     time::Duration,
@@ -13,41 +13,79 @@ use hyperlane_base::{
     db::{HyperlaneRocksDB, ProcessMessage},
     CoreMetrics,
 };
-use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, QueueOperation};
+use hyperlane_core::{cursors::SequenceGaps, HyperlaneDomain, HyperlaneMessage, QueueOperation};
 use prometheus::IntGauge;
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::{mpsc::UnboundedSender, RwLock};
 use tracing::{debug, instrument, trace};
 
 use super::{metadata::AppContextClassifier, pending_message::*};
 use crate::{processor::ProcessorExt, settings::matching_list::MatchingList};
 
+/// The lists messages are filtered by before they are relayed. They are shared
+/// by the processors of all origins, and can be replaced while the relayer is
+/// running.
+#[derive(Debug, Default, Clone)]
+pub struct MessageFilter {
+    /// Only messages that match are relayed
+    pub whitelist: MatchingList,
+    /// Messages that match are not relayed
+    pub blacklist: MatchingList,
+}
+
+/// Where the messages from an origin to a destination are sent
+#[derive(Clone, new)]
+pub struct DestinationRoute {
+    /// channel of the destination chain to send operations (i.e. message
+    /// submissions) to
+    pub send_channel: UnboundedSender<QueueOperation>,
+    /// Needed context to send a message to the destination chain
+    pub ctx: Arc<MessageContext>,
+}
+
+/// The routes of an origin by destination domain. They are shared with the
+/// processor of the origin, so that destinations can be added and removed
+/// while the relayer is running.
+pub type DestinationRoutes = Arc<RwLock<HashMap<u32, DestinationRoute>>>;
+
 /// Finds unprocessed messages from an origin and submits then through a channel
 /// for to the appropriate destination.
 #[allow(clippy::too_many_arguments)]
 pub struct MessageProcessor {
-    whitelist: Arc<MatchingList>,
-    blacklist: Arc<MatchingList>,
+    message_filter: Arc<RwLock<MessageFilter>>,
     metrics: MessageProcessorMetrics,
-    /// channel for each destination chain to send operations (i.e. message
-    /// submissions) to
-    send_channels: HashMap<u32, UnboundedSender<QueueOperation>>,
-    /// Needed context to send a message for each destination chain
-    destination_ctxs: HashMap<u32, Arc<MessageContext>>,
+    /// Route of each destination chain
+    routes: DestinationRoutes,
     metric_app_contexts: Vec<(MatchingList, String)>,
     nonce_iterator: ForwardBackwardIterator,
 }
 
-#[derive(Debug)]
 struct ForwardBackwardIterator {
     low_nonce_iter: DirectionalNonceIterator,
     high_nonce_iter: DirectionalNonceIterator,
+    /// The nonces the message sync skipped. The high iterator moves past
+    /// them instead of waiting for them to be indexed.
+    sequence_gaps: Option<Arc<SequenceGaps>>,
+    /// The skipped nonces the high iterator moved past, which are processed
+    /// once they're backfilled
+    skipped_nonces: BTreeSet<u32>,
+    db: Arc<dyn ProcessMessage>,
     // here for debugging purposes
     _domain: String,
 }
 
+impl Debug for ForwardBackwardIterator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ForwardBackwardIterator {{ low_nonce_iter: {:?}, high_nonce_iter: {:?}, skipped_nonces: {:?}, domain: {:?} }}",
+            self.low_nonce_iter, self.high_nonce_iter, self.skipped_nonces, self._domain
+        )
+    }
+}
+
 impl ForwardBackwardIterator {
-    #[instrument(skip(db), ret)]
-    fn new(db: Arc<dyn ProcessMessage>) -> Self {
+    #[instrument(skip(db, sequence_gaps), ret)]
+    fn new(db: Arc<dyn ProcessMessage>, sequence_gaps: Option<Arc<SequenceGaps>>) -> Self {
         let high_nonce = db.retrieve_highest_seen_message_nonce().ok().flatten();
         let domain = db.domain().name().to_owned();
         let high_nonce_iter = DirectionalNonceIterator::new(
@@ -57,8 +95,12 @@ impl ForwardBackwardIterator {
             db.clone(),
             domain.clone(),
         );
-        let mut low_nonce_iter =
-            DirectionalNonceIterator::new(high_nonce, NonceDirection::Low, db, domain.clone());
+        let mut low_nonce_iter = DirectionalNonceIterator::new(
+            high_nonce,
+            NonceDirection::Low,
+            db.clone(),
+            domain.clone(),
+        );
         // Decrement the low nonce to avoid processing the same message twice, which causes double counts in metrics
         low_nonce_iter.iterate();
         debug!(
@@ -70,6 +112,9 @@ impl ForwardBackwardIterator {
         Self {
             low_nonce_iter,
             high_nonce_iter,
+            sequence_gaps,
+            skipped_nonces: BTreeSet::new(),
+            db,
             _domain: domain,
         }
     }
@@ -78,6 +123,9 @@ impl ForwardBackwardIterator {
         &mut self,
         metrics: &MessageProcessorMetrics,
     ) -> Result<Option<HyperlaneMessage>> {
+        if let Some(message) = self.try_get_backfilled_message()? {
+            return Ok(Some(message));
+        }
         loop {
             let high_nonce_message_status = self.high_nonce_iter.try_get_next_nonce(metrics)?;
             let low_nonce_message_status = self.low_nonce_iter.try_get_next_nonce(metrics)?;
@@ -99,6 +147,16 @@ impl ForwardBackwardIterator {
                     self.low_nonce_iter.iterate();
                     return Ok(Some(low_nonce_message));
                 }
+                // Move past nonces the message sync skipped
+                (MessageStatus::Unindexed, MessageStatus::Unindexed)
+                    if self.is_skipped(self.high_nonce_iter.nonce) =>
+                {
+                    if let Some(nonce) = self.high_nonce_iter.nonce {
+                        debug!(nonce, "Moving past nonce skipped by the message sync");
+                        self.skipped_nonces.insert(nonce);
+                    }
+                    self.high_nonce_iter.iterate();
+                }
                 (MessageStatus::Unindexed, MessageStatus::Unindexed) => return Ok(None),
             }
             // This loop may iterate through millions of processed messages, blocking the runtime.
@@ -107,6 +165,29 @@ impl ForwardBackwardIterator {
             tokio::task::yield_now().await;
         }
     }
+
+    fn is_skipped(&self, nonce: Option<u32>) -> bool {
+        match (nonce, &self.sequence_gaps) {
+            (Some(nonce), Some(gaps)) => gaps.contains(nonce),
+            _ => false,
+        }
+    }
+
+    /// The first message the high iterator moved past that was backfilled
+    /// since and isn't processed yet
+    fn try_get_backfilled_message(&mut self) -> Result<Option<HyperlaneMessage>> {
+        for nonce in self.skipped_nonces.clone() {
+            let Some(message) = self.db.retrieve_message_by_nonce(nonce)? else {
+                continue;
+            };
+            self.skipped_nonces.remove(&nonce);
+            if !self.db.retrieve_processed_by_nonce(nonce)?.unwrap_or(false) {
+                debug!(?message, "Found backfilled message");
+                return Ok(Some(message));
+            }
+        }
+        Ok(None)
+    }
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
@@ -217,8 +298,8 @@ impl Debug for MessageProcessor {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "MessageProcessor {{ whitelist: {:?}, blacklist: {:?}, nonce_iterator: {:?}}}",
-            self.whitelist, self.blacklist, self.nonce_iterator
+            "MessageProcessor {{ message_filter: {:?}, nonce_iterator: {:?}}}",
+            self.message_filter, self.nonce_iterator
         )
     }
 }
@@ -246,17 +327,19 @@ impl ProcessorExt for MessageProcessor {
             );
             let destination = msg.destination;
 
+            let filter = self.message_filter.read().await;
             // Skip if not whitelisted.
-            if !self.whitelist.msg_matches(&msg, true) {
-                debug!(?msg, whitelist=?self.whitelist, "Message not whitelisted, skipping");
+            if !filter.whitelist.msg_matches(&msg, true) {
+                debug!(?msg, whitelist=?filter.whitelist, "Message not whitelisted, skipping");
                 return Ok(());
             }
 
             // Skip if the message is blacklisted
-            if self.blacklist.msg_matches(&msg, false) {
-                debug!(?msg, blacklist=?self.blacklist, "Message blacklisted, skipping");
+            if filter.blacklist.msg_matches(&msg, false) {
+                debug!(?msg, blacklist=?filter.blacklist, "Message blacklisted, skipping");
                 return Ok(());
             }
+            drop(filter);
 
             // Skip if the message is intended for this origin
             if destination == self.domain().id() {
@@ -265,10 +348,10 @@ impl ProcessorExt for MessageProcessor {
             }
 
             // Skip if the message is intended for a destination we do not service
-            if !self.send_channels.contains_key(&destination) {
+            let Some(route) = self.routes.read().await.get(&destination).cloned() else {
                 debug!(?msg, "Message destined for unknown domain, skipping");
                 return Ok(());
-            }
+            };
 
             debug!(%msg, "Sending message to submitter");
 
@@ -277,12 +360,10 @@ impl ProcessorExt for MessageProcessor {
 
             let app_context = app_context_classifier.get_app_context(&msg).await?;
             // Finally, build the submit arg and dispatch it to the submitter.
-            let pending_msg = PendingMessage::from_persisted_retries(
-                msg,
-                self.destination_ctxs[&destination].clone(),
-                app_context,
-            );
-            self.send_channels[&destination].send(Box::new(pending_msg) as QueueOperation)?;
+            let pending_msg = PendingMessage::from_persisted_retries(msg, route.ctx, app_context);
+            route
+                .send_channel
+                .send(Box::new(pending_msg) as QueueOperation)?;
         } else {
             tokio::time::sleep(Duration::from_secs(1)).await;
         }
@@ -293,21 +374,21 @@ impl ProcessorExt for MessageProcessor {
 impl MessageProcessor {
     pub fn new(
         db: HyperlaneRocksDB,
-        whitelist: Arc<MatchingList>,
-        blacklist: Arc<MatchingList>,
+        message_filter: Arc<RwLock<MessageFilter>>,
         metrics: MessageProcessorMetrics,
-        send_channels: HashMap<u32, UnboundedSender<QueueOperation>>,
-        destination_ctxs: HashMap<u32, Arc<MessageContext>>,
+        routes: DestinationRoutes,
         metric_app_contexts: Vec<(MatchingList, String)>,
+        sequence_gaps: Option<Arc<SequenceGaps>>,
     ) -> Self {
         Self {
-            whitelist,
-            blacklist,
+            message_filter,
             metrics,
-            send_channels,
-            destination_ctxs,
+            routes,
             metric_app_contexts,
-            nonce_iterator: ForwardBackwardIterator::new(Arc::new(db) as Arc<dyn ProcessMessage>),
+            nonce_iterator: ForwardBackwardIterator::new(
+                Arc::new(db) as Arc<dyn ProcessMessage>,
+                sequence_gaps,
+            ),
         }
     }
 
@@ -368,7 +449,7 @@ mod test {
         merkle_tree::builder::MerkleTreeBuilder,
         msg::{
             gas_payment::GasPaymentEnforcer,
-            metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
+            metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier, IsmConfigCache},
         },
         processor::Processor,
     };
@@ -379,7 +460,7 @@ mod test {
         settings::{ChainConf, ChainConnectionConf, Settings},
     };
     use hyperlane_test::mocks::{MockMailboxContract, MockValidatorAnnounceContract};
-    use prometheus::{IntCounter, Registry};
+    use prometheus::{Counter, IntCounter, Registry};
     use tokio::{
         sync::{
             mpsc::{self, UnboundedReceiver},
@@ -407,14 +488,23 @@ mod test {
         MessageSubmissionMetrics {
             last_known_nonce: IntGauge::new("last_known_nonce_gauge", "help string").unwrap(),
             messages_processed: IntCounter::new("message_processed_gauge", "help string").unwrap(),
+            dead_lettered: IntGauge::new("dead_lettered_gauge", "help string").unwrap(),
+            gas_payments: Counter::new("gas_payments_counter", "help string").unwrap(),
+            gas_costs: Counter::new("gas_costs_counter", "help string").unwrap(),
+            avoided_already_delivered: IntCounter::new("avoided_counter", "help string").unwrap(),
+            avoided_ism_verification_failures: IntCounter::new("avoided_counter", "help string")
+                .unwrap(),
+            avoided_recipient_reverts: IntCounter::new("avoided_counter", "help string").unwrap(),
         }
     }
 
     fn dummy_chain_conf(domain: &HyperlaneDomain) -> ChainConf {
         ChainConf {
             domain: domain.clone(),
+            chain_id: None,
             signer: Default::default(),
             reorg_period: Default::default(),
+            finality: Default::default(),
             addresses: Default::default(),
             connection: ChainConnectionConf::Ethereum(hyperlane_ethereum::ConnectionConf {
                 rpc_connection: hyperlane_ethereum::RpcConnectionConf::Http {
@@ -422,9 +512,14 @@ mod test {
                 },
                 transaction_overrides: Default::default(),
                 operation_batch: Default::default(),
+                quirks: Default::default(),
+                light_client_url: None,
             }),
             metrics_conf: Default::default(),
             index: Default::default(),
+            min_signer_balance: None,
+            retry_policy: Default::default(),
+            gas_limit_policy: None,
         }
     }
 
@@ -450,10 +545,12 @@ mod test {
             Arc::new(RwLock::new(MerkleTreeBuilder::new())),
             Arc::new(MockValidatorAnnounceContract::default()),
             false,
+            None,
             Arc::new(core_metrics),
             db.clone(),
             5,
             IsmAwareAppContextClassifier::new(Arc::new(MockMailboxContract::default()), vec![]),
+            IsmConfigCache::new(None),
         )
     }
 
@@ -467,9 +564,19 @@ mod test {
             destination_mailbox: Arc::new(MockMailboxContract::default()),
             origin_db: db.clone(),
             metadata_builder: Arc::new(base_metadata_builder),
-            origin_gas_payment_enforcer: Arc::new(GasPaymentEnforcer::new([], db.clone())),
+            origin_gas_payment_enforcer: Arc::new(GasPaymentEnforcer::new([], None, db.clone())),
             transaction_gas_limit: Default::default(),
             metrics: dummy_submission_metrics(),
+            receipt_signer: None,
+            prioritization: Default::default(),
+            dead_letter: Default::default(),
+            retry_policy: Default::default(),
+            gas_limit_policy: None,
+            app_gas_limit_policies: Default::default(),
+            origin_igp: None,
+            metadata_permits: None,
+            message_claims: None,
+            message_hooks: Default::default(),
         });
 
         let (send_channel, receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
@@ -477,11 +584,13 @@ mod test {
             MessageProcessor::new(
                 db.clone(),
                 Default::default(),
-                Default::default(),
                 dummy_processor_metrics(origin_domain.id()),
-                HashMap::from([(destination_domain.id(), send_channel)]),
-                HashMap::from([(destination_domain.id(), message_context)]),
+                Arc::new(RwLock::new(HashMap::from([(
+                    destination_domain.id(),
+                    DestinationRoute::new(send_channel, message_context),
+                )]))),
                 vec![],
+                None,
             ),
             receive_channel,
         )
@@ -680,7 +789,7 @@ mod test {
         let dummy_metrics = dummy_processor_metrics(0);
         let db = Arc::new(mock_db);
 
-        let mut forward_backward_iterator = ForwardBackwardIterator::new(db.clone());
+        let mut forward_backward_iterator = ForwardBackwardIterator::new(db.clone(), None);
 
         let mut messages = vec![];
         while let Some(msg) = forward_backward_iterator
@@ -1,31 +1,35 @@
 use std::{
     collections::{HashMap, HashSet},
     fmt::{Debug, Formatter},
-    sync::Arc,
+    iter,
+    sync::{Arc, RwLock as StdRwLock},
+    time::Duration,
 };
 
 use async_trait::async_trait;
 use derive_more::AsRef;
 use eyre::Result;
-use futures_util::future::try_join_all;
+use futures_util::{stream::FuturesUnordered, StreamExt};
 use hyperlane_base::{
     db::{HyperlaneRocksDB, DB},
-    metrics::{AgentMetrics, MetricsUpdater},
-    settings::ChainConf,
-    BaseAgent, ChainMetrics, ContractSyncMetrics, ContractSyncer, CoreMetrics, HyperlaneAgentCore,
-    SyncOptions,
+    metrics::{AgentMetrics, BalanceMonitor, DbMetricsUpdater, MetricsUpdater},
+    settings::{ChainConf, ConfigReloader, GasLimitPolicyConf},
+    AgentHealth, BaseAgent, ChainMetrics, ContractSyncMetrics, ContractSyncer, CoreMetrics,
+    HyperlaneAgentCore, LoadableFromSettings, ObjectWriter, SyncOptions, TaskSupervisor,
 };
 use hyperlane_core::{
-    HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, MerkleTreeInsertion, QueueOperation,
-    H512, U256,
+    HyperlaneDomain, HyperlaneMessage, InterchainGasPaymaster, InterchainGasPayment, Mailbox,
+    MerkleTreeInsertion, QueueOperation, ValidatorAnnounce, H256, H512, U256,
 };
+use hyperlane_ethereum::{SingletonSigner, SingletonSignerHandle};
 use tokio::{
     sync::{
-        broadcast::{Receiver, Sender},
-        mpsc::{self, UnboundedReceiver, UnboundedSender},
-        RwLock,
+        broadcast::Sender,
+        mpsc::{self, UnboundedSender},
+        Mutex, RwLock, Semaphore,
     },
-    task::JoinHandle,
+    task::{AbortHandle, JoinHandle},
+    time::{interval_at, Instant, Interval},
 };
 use tokio_metrics::TaskMonitor;
 use tracing::{error, info, info_span, instrument::Instrumented, warn, Instrument};
@@ -33,14 +37,24 @@ use tracing::{error, info, info_span, instrument::Instrumented, warn, Instrument
 use crate::{
     merkle_tree::builder::MerkleTreeBuilder,
     msg::{
+        circuit_breaker::CircuitBreaker,
+        coordination::{MessageClaims, PostgresMessageClaims},
         gas_payment::GasPaymentEnforcer,
-        metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
+        hooks::{build_message_hooks, MessageHook},
+        metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier, IsmConfigCache},
+        op_queue::OpQueue,
         op_submitter::{SerialSubmitter, SerialSubmitterMetrics},
         pending_message::{MessageContext, MessageSubmissionMetrics},
-        processor::{MessageProcessor, MessageProcessorMetrics},
+        processor::{
+            DestinationRoute, DestinationRoutes, MessageFilter, MessageProcessor,
+            MessageProcessorMetrics,
+        },
+    },
+    server::{self as relayer_server, QueueRequest},
+    settings::{
+        matching_list::MatchingList, CircuitBreakerConf, DeadLetterConf, GasPaymentEnforcementConf,
+        PrioritizationPolicy, RelayerSettings,
     },
-    server::{self as relayer_server, MessageRetryRequest},
-    settings::{matching_list::MatchingList, RelayerSettings},
 };
 use crate::{
     merkle_tree::processor::{MerkleTreeProcessor, MerkleTreeProcessorMetrics},
@@ -67,15 +81,59 @@ pub struct Relayer {
     /// Context data for each (origin, destination) chain pair a message can be
     /// sent between
     msg_ctxs: HashMap<ContextKey, Arc<MessageContext>>,
+    /// Mailboxes of the destination chains
+    mailboxes: HashMap<HyperlaneDomain, Arc<dyn Mailbox>>,
+    validator_announces: HashMap<HyperlaneDomain, Arc<dyn ValidatorAnnounce>>,
+    /// IGPs of the origin chains whose protocol supports them
+    interchain_gas_paymasters: HashMap<HyperlaneDomain, Arc<dyn InterchainGasPaymaster>>,
+    gas_payment_enforcers: HashMap<HyperlaneDomain, Arc<GasPaymentEnforcer>>,
+    /// Limits how many messages of each origin have their metadata built at
+    /// once, if configured
+    metadata_permits: HashMap<HyperlaneDomain, Arc<Semaphore>>,
     prover_syncs: HashMap<HyperlaneDomain, Arc<RwLock<MerkleTreeBuilder>>>,
     merkle_tree_hook_syncs: HashMap<HyperlaneDomain, Arc<dyn ContractSyncer<MerkleTreeInsertion>>>,
     dbs: HashMap<HyperlaneDomain, HyperlaneRocksDB>,
-    whitelist: Arc<MatchingList>,
-    blacklist: Arc<MatchingList>,
+    /// The database the databases of origins that are added are scoped from
+    db: DB,
+    contract_sync_metrics: Arc<ContractSyncMetrics>,
+    /// Whitelist and blacklist, which the admin API can replace
+    message_filter: Arc<RwLock<MessageFilter>>,
     transaction_gas_limit: Option<U256>,
     skip_transaction_gas_limit_for: HashSet<u32>,
     allow_local_checkpoint_syncers: bool,
+    /// The only validators whose checkpoints are used, if restricted
+    allowed_validators: Option<HashSet<H256>>,
     metric_app_contexts: Vec<(MatchingList, String)>,
+    /// The most operations of each app context prepared at once for a
+    /// destination
+    app_concurrency_limits: HashMap<String, usize>,
+    /// The most operations submitted to each destination that wait to be
+    /// confirmed
+    max_in_flight_per_destination: Option<u32>,
+    /// How the estimated gas limits of the messages of apps are adjusted
+    app_gas_limit_policies: Arc<Vec<(MatchingList, GasLimitPolicyConf)>>,
+    gas_payment_enforcement: Vec<GasPaymentEnforcementConf>,
+    prioritization: PrioritizationPolicy,
+    dead_letter: DeadLetterConf,
+    ism_cache_ttl: Option<Duration>,
+    max_metadata_tasks_per_origin: Option<u32>,
+    /// How often the chains are reloaded from the settings, if at all
+    chain_refresh_interval: Option<Duration>,
+    /// Restarts the contract syncs of origins that stall or panic
+    sync_supervisor: TaskSupervisor,
+    circuit_breaker: Option<CircuitBreakerConf>,
+    /// Claims of the messages, if coordinating with other relayer instances
+    message_claims: Option<Arc<dyn MessageClaims>>,
+    /// Check messages before their metadata is built, shared by all routes
+    /// so that rate limits span destinations
+    message_hooks: Arc<Vec<Arc<dyn MessageHook>>>,
+    /// Signer of delivery receipts, if enabled
+    receipt_signer_instance: Option<Box<SingletonSigner>>,
+    receipt_signer: Option<SingletonSignerHandle>,
+    /// Key of the admin APIs, which are only served if set
+    admin_api_key: Option<String>,
+    /// Where the database snapshots taken through the admin API are written
+    db_snapshot_writer: Option<Arc<dyn ObjectWriter>>,
     core_metrics: Arc<CoreMetrics>,
     // TODO: decide whether to consolidate `agent_metrics` and `chain_metrics` into a single struct
     // or move them in `core_metrics`, like the validator metrics
@@ -83,17 +141,27 @@ pub struct Relayer {
     chain_metrics: ChainMetrics,
     /// Tokio console server
     pub tokio_console_server: Option<console_subscriber::Server>,
+    /// Channels of the running destination submitters
+    send_channels: HashMap<HyperlaneDomain, UnboundedSender<QueueOperation>>,
+    /// Routes of the running origin processors
+    origin_routes: HashMap<HyperlaneDomain, DestinationRoutes>,
+    /// Circuit breakers of the running destination submitters
+    circuit_breakers: HashMap<HyperlaneDomain, Arc<Mutex<CircuitBreaker>>>,
+    /// Tasks of the running origins, which are aborted when they're removed
+    origin_tasks: HashMap<HyperlaneDomain, Vec<AbortHandle>>,
+    /// Tasks of the running destinations, which are aborted when they're
+    /// removed
+    destination_tasks: HashMap<HyperlaneDomain, Vec<AbortHandle>>,
 }
 
 impl Debug for Relayer {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Relayer {{ origin_chains: {:?}, destination_chains: {:?}, whitelist: {:?}, blacklist: {:?}, transaction_gas_limit: {:?}, skip_transaction_gas_limit_for: {:?}, allow_local_checkpoint_syncers: {:?} }}",
+            "Relayer {{ origin_chains: {:?}, destination_chains: {:?}, message_filter: {:?}, transaction_gas_limit: {:?}, skip_transaction_gas_limit_for: {:?}, allow_local_checkpoint_syncers: {:?} }}",
             self.origin_chains,
             self.destination_chains,
-            self.whitelist,
-            self.blacklist,
+            self.message_filter,
             self.transaction_gas_limit,
             self.skip_transaction_gas_limit_for,
             self.allow_local_checkpoint_syncers
@@ -119,13 +187,37 @@ impl BaseAgent for Relayer {
         Self: Sized,
     {
         let core = settings.build_hyperlane_core(core_metrics.clone());
-        let db = DB::from_path(&settings.db)?;
+        let db = DB::open(
+            settings.db_backend,
+            settings.db_tuning,
+            &settings.db,
+            settings.db_restore_from.as_deref(),
+        )?;
+        AgentHealth::global().pass("db");
         let dbs = settings
             .origin_chains
             .iter()
             .map(|origin| (origin.clone(), HyperlaneRocksDB::new(origin, db.clone())))
             .collect::<HashMap<_, _>>();
 
+        // Verified before the contract syncs build their cursors, which then
+        // index the invalidated sequences again
+        if let Some(conf) = &settings.db_integrity_check {
+            for (origin, db) in &dbs {
+                let chain_conf = settings.chain_setup(origin)?;
+                let messages = chain_conf.build_message_indexer(&core_metrics).await?;
+                let insertions = chain_conf
+                    .build_merkle_tree_hook_indexer(&core_metrics)
+                    .await?;
+                if let Err(err) = db
+                    .verify_against_chain(conf, Some(messages.as_ref()), Some(insertions.as_ref()))
+                    .await
+                {
+                    warn!(%origin, ?err, "Failed to verify the database against the chain");
+                }
+            }
+        }
+
         let mailboxes = settings
             .build_mailboxes(settings.destination_chains.iter(), &core_metrics)
             .await?;
@@ -134,6 +226,7 @@ impl BaseAgent for Relayer {
             .await?;
 
         let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&core_metrics));
+        let sync_supervisor = TaskSupervisor::new(settings.sync_supervisor, &core_metrics)?;
 
         let message_syncs: HashMap<_, Arc<dyn ContractSyncer<HyperlaneMessage>>> = settings
             .contract_syncs::<HyperlaneMessage, _>(
@@ -177,8 +270,8 @@ impl BaseAgent for Relayer {
             .map(|(k, v)| (k, v as _))
             .collect();
 
-        let whitelist = Arc::new(settings.whitelist);
-        let blacklist = Arc::new(settings.blacklist);
+        let whitelist = settings.whitelist;
+        let blacklist = settings.blacklist;
         let skip_transaction_gas_limit_for = settings.skip_transaction_gas_limit_for;
         let transaction_gas_limit = settings.transaction_gas_limit;
 
@@ -204,6 +297,24 @@ impl BaseAgent for Relayer {
 
         info!(gas_enforcement_policies=?settings.gas_payment_enforcement, "Gas enforcement configuration");
 
+        // IGPs quote gas payments and convert the cost of deliveries, where
+        // the protocol supports them
+        let mut interchain_gas_paymasters: HashMap<_, Arc<dyn InterchainGasPaymaster>> =
+            HashMap::new();
+        for origin in &settings.origin_chains {
+            match settings
+                .build_interchain_gas_paymaster(origin, &core_metrics)
+                .await
+            {
+                Ok(igp) => {
+                    interchain_gas_paymasters.insert(origin.clone(), Arc::from(igp));
+                }
+                Err(err) => {
+                    warn!(%origin, error=?err, "Failed to build interchain gas paymaster, gas payments can't be quoted")
+                }
+            }
+        }
+
         // need one of these per origin chain due to the database scoping even though
         // the config itself is the same
         let gas_payment_enforcers: HashMap<_, _> = settings
@@ -214,79 +325,138 @@ impl BaseAgent for Relayer {
                     domain.clone(),
                     Arc::new(GasPaymentEnforcer::new(
                         settings.gas_payment_enforcement.clone(),
+                        interchain_gas_paymasters.get(domain).cloned(),
                         dbs.get(domain).unwrap().clone(),
                     )),
                 )
             })
             .collect();
 
-        let mut msg_ctxs = HashMap::new();
-        let mut destination_chains = HashMap::new();
-        for destination in &settings.destination_chains {
-            let destination_chain_setup = core.settings.chain_setup(destination).unwrap().clone();
-            destination_chains.insert(destination.clone(), destination_chain_setup.clone());
-            let transaction_gas_limit: Option<U256> =
-                if skip_transaction_gas_limit_for.contains(&destination.id()) {
-                    None
-                } else {
-                    transaction_gas_limit
-                };
-
-            for origin in &settings.origin_chains {
-                let db = dbs.get(origin).unwrap().clone();
-                let metadata_builder = BaseMetadataBuilder::new(
-                    origin.clone(),
-                    destination_chain_setup.clone(),
-                    prover_syncs[origin].clone(),
-                    validator_announces[origin].clone(),
-                    settings.allow_local_checkpoint_syncers,
-                    core.metrics.clone(),
-                    db,
-                    5,
-                    IsmAwareAppContextClassifier::new(
-                        mailboxes[destination].clone(),
-                        settings.metric_app_contexts.clone(),
-                    ),
-                );
-
-                msg_ctxs.insert(
-                    ContextKey {
-                        origin: origin.id(),
-                        destination: destination.id(),
-                    },
-                    Arc::new(MessageContext {
-                        destination_mailbox: mailboxes[destination].clone(),
-                        origin_db: dbs.get(origin).unwrap().clone(),
-                        metadata_builder: Arc::new(metadata_builder),
-                        origin_gas_payment_enforcer: gas_payment_enforcers[origin].clone(),
-                        transaction_gas_limit,
-                        metrics: MessageSubmissionMetrics::new(&core_metrics, origin, destination),
-                    }),
-                );
+        // Intentionally using hyperlane_ethereum for the receipt signer
+        let (receipt_signer_instance, receipt_signer) = match &settings.receipt_signer {
+            Some(conf) => {
+                let (instance, handle) = SingletonSigner::new(conf.build().await?);
+                (Some(Box::new(instance)), Some(handle))
             }
-        }
+            None => (None, None),
+        };
+
+        let message_claims: Option<Arc<dyn MessageClaims>> = match &settings.coordination {
+            Some(conf) => Some(Arc::new(PostgresMessageClaims::connect(conf).await?)),
+            None => None,
+        };
+
+        let db_snapshot_writer: Option<Arc<dyn ObjectWriter>> = match &settings.db_snapshot_location
+        {
+            Some(location) => Some(location.build_object_writer().await?.into()),
+            None => None,
+        };
+
+        // Shared by all destinations, so that a burst of messages from one
+        // origin doesn't starve the others
+        let metadata_permits: HashMap<_, _> = settings
+            .max_metadata_tasks_per_origin
+            .map(|max| {
+                settings
+                    .origin_chains
+                    .iter()
+                    .map(|origin| (origin.clone(), Arc::new(Semaphore::new(max as usize))))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let destination_chains = settings
+            .destination_chains
+            .iter()
+            .map(|destination| {
+                let destination_chain_setup =
+                    core.settings.chain_setup(destination).unwrap().clone();
+                (destination.clone(), destination_chain_setup)
+            })
+            .collect();
 
-        Ok(Self {
+        let mut relayer = Self {
             dbs,
+            db,
             origin_chains: settings.origin_chains,
             destination_chains,
-            msg_ctxs,
+            msg_ctxs: HashMap::new(),
+            mailboxes,
+            validator_announces,
+            interchain_gas_paymasters,
+            gas_payment_enforcers,
+            metadata_permits,
+            contract_sync_metrics,
             core,
             message_syncs,
             interchain_gas_payment_syncs,
             prover_syncs,
             merkle_tree_hook_syncs,
-            whitelist,
-            blacklist,
+            message_filter: Arc::new(RwLock::new(MessageFilter {
+                whitelist,
+                blacklist,
+            })),
             transaction_gas_limit,
             skip_transaction_gas_limit_for,
             allow_local_checkpoint_syncers: settings.allow_local_checkpoint_syncers,
+            allowed_validators: settings.allowed_validators,
             metric_app_contexts: settings.metric_app_contexts,
+            app_concurrency_limits: settings
+                .app_contexts
+                .iter()
+                .filter_map(|app| {
+                    app.max_concurrency
+                        .map(|limit| (app.name.clone(), limit as usize))
+                })
+                .collect(),
+            max_in_flight_per_destination: settings.max_in_flight_per_destination,
+            app_gas_limit_policies: Arc::new(
+                settings
+                    .app_contexts
+                    .iter()
+                    .filter_map(|app| {
+                        app.gas_limit_policy
+                            .clone()
+                            .map(|policy| (app.matching_list.clone(), policy))
+                    })
+                    .collect(),
+            ),
+            gas_payment_enforcement: settings.gas_payment_enforcement,
+            prioritization: settings.prioritization,
+            dead_letter: settings.dead_letter,
+            ism_cache_ttl: settings.ism_cache_ttl,
+            max_metadata_tasks_per_origin: settings.max_metadata_tasks_per_origin,
+            chain_refresh_interval: settings.chain_refresh_interval,
+            sync_supervisor,
+            circuit_breaker: settings.circuit_breaker,
+            message_claims,
+            message_hooks: Arc::new(build_message_hooks(&settings.message_hooks)),
+            receipt_signer_instance,
+            receipt_signer,
+            admin_api_key: settings.admin_api_key,
+            db_snapshot_writer,
             core_metrics,
             agent_metrics,
             chain_metrics,
             tokio_console_server: Some(tokio_console_server),
-        })
+            send_channels: HashMap::new(),
+            circuit_breakers: HashMap::new(),
+            origin_routes: HashMap::new(),
+            origin_tasks: HashMap::new(),
+            destination_tasks: HashMap::new(),
+        };
+        let origins: Vec<_> = relayer.origin_chains.iter().cloned().collect();
+        let destinations: Vec<_> = relayer.destination_chains.keys().cloned().collect();
+        relayer.insert_msg_ctxs(&origins, &destinations);
+        Ok(relayer)
+    }
+
+    fn preflight_chains(settings: &Self::Settings) -> Vec<HyperlaneDomain> {
+        settings
+            .origin_chains
+            .union(&settings.destination_chains)
+            .cloned()
+            .collect()
     }
 
     #[allow(clippy::async_yields_async)]
@@ -305,10 +475,55 @@ impl BaseAgent for Relayer {
             tasks.push(console_server.instrument(info_span!("Tokio console server")));
         }
 
-        // run server
-        let sender = Sender::<MessageRetryRequest>::new(ENDPOINT_MESSAGES_QUEUE_SIZE);
-        let custom_routes = relayer_server::routes(sender.clone());
+        let sender = Sender::<QueueRequest>::new(ENDPOINT_MESSAGES_QUEUE_SIZE);
+
+        if let Some(signer_instance) = self.receipt_signer_instance.take() {
+            tasks.push(
+                tokio::spawn(async move {
+                    signer_instance.run().await;
+                })
+                .instrument(info_span!("SingletonSigner")),
+            );
+        }
 
+        let balance_monitor = BalanceMonitor::new(
+            &self.core.settings,
+            &self.core_metrics,
+            self.agent_metrics.clone(),
+            Self::AGENT_NAME.to_string(),
+        )
+        .await;
+        tasks.push(balance_monitor.spawn());
+
+        match DbMetricsUpdater::new(self.db.clone(), &self.core_metrics) {
+            Ok(db_metrics_updater) => tasks.push(db_metrics_updater.spawn()),
+            Err(err) => warn!(?err, "Not reporting database metrics"),
+        }
+
+        let mut op_queues = vec![];
+        let destinations: Vec<_> = self.destination_chains.keys().cloned().collect();
+        for destination in &destinations {
+            let (queues, destination_tasks) = self
+                .start_destination(destination, &sender, &task_monitor)
+                .await;
+            op_queues.extend(queues);
+            tasks.extend(destination_tasks);
+        }
+
+        // run server
+        let custom_routes = relayer_server::routes(
+            sender.clone(),
+            self.dbs.values().cloned().collect(),
+            op_queues,
+            self.message_filter.clone(),
+            self.circuit_breakers
+                .iter()
+                .map(|(destination, breaker)| (destination.id(), breaker.clone()))
+                .collect(),
+            self.admin_api_key.clone(),
+            self.db.clone(),
+            self.db_snapshot_writer.clone(),
+        );
         let server = self
             .core
             .settings
@@ -319,82 +534,61 @@ impl BaseAgent for Relayer {
             .instrument(info_span!("Relayer server"));
         tasks.push(server_task);
 
-        // send channels by destination chain
-        let mut send_channels = HashMap::with_capacity(self.destination_chains.len());
-        for (dest_domain, dest_conf) in &self.destination_chains {
-            let (send_channel, receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
-            send_channels.insert(dest_domain.id(), send_channel);
-
-            tasks.push(
-                self.run_destination_submitter(
-                    dest_domain,
-                    receive_channel,
-                    sender.clone(),
-                    // Default to submitting one message at a time if there is no batch config
-                    self.core.settings.chains[dest_domain.name()]
-                        .connection
-                        .operation_batch_config()
-                        .map(|c| c.max_batch_size)
-                        .unwrap_or(1),
-                    task_monitor.clone(),
-                ),
-            );
-
-            let metrics_updater = MetricsUpdater::new(
-                dest_conf,
-                self.core_metrics.clone(),
-                self.agent_metrics.clone(),
-                self.chain_metrics.clone(),
-                Self::AGENT_NAME.to_string(),
-            )
-            .await
-            .unwrap();
-            tasks.push(metrics_updater.spawn());
+        let origins: Vec<_> = self.origin_chains.iter().cloned().collect();
+        for origin in &origins {
+            tasks.extend(self.start_origin(origin, &task_monitor).await);
         }
 
-        for origin in &self.origin_chains {
-            let maybe_broadcaster = self
-                .message_syncs
-                .get(origin)
-                .and_then(|sync| sync.get_broadcaster());
-            tasks.push(self.run_message_sync(origin, task_monitor.clone()).await);
-            tasks.push(
-                self.run_interchain_gas_payment_sync(
-                    origin,
-                    maybe_broadcaster.clone().map(|b| b.subscribe()),
-                    task_monitor.clone(),
-                )
-                .await,
-            );
-            tasks.push(
-                self.run_merkle_tree_hook_syncs(
-                    origin,
-                    maybe_broadcaster.map(|b| b.subscribe()),
-                    task_monitor.clone(),
-                )
-                .await,
-            );
-        }
-
-        // each message process attempts to send messages from a chain
-        for origin in &self.origin_chains {
-            tasks.push(self.run_message_processor(
-                origin,
-                send_channels.clone(),
-                task_monitor.clone(),
-            ));
-            tasks.push(self.run_merkle_tree_processor(origin, task_monitor.clone()));
+        let mut tasks: FuturesUnordered<_> = tasks.into_iter().collect();
+        let mut chain_refresh = self
+            .chain_refresh_interval
+            .map(|period| interval_at(Instant::now() + period, period));
+        let mut config_reloader = ConfigReloader::new(self.core.settings.config_watch_interval)
+            .expect("Failed to start config reloader");
+        loop {
+            tokio::select! {
+                result = tasks.next() => match result {
+                    // The tasks of removed chains are aborted
+                    Some(Err(err)) if err.is_cancelled() => {}
+                    Some(Err(err)) => {
+                        tracing::error!(
+                            error=?err,
+                            "Relayer task panicked"
+                        );
+                        break;
+                    }
+                    Some(Ok(())) => {}
+                    None => break,
+                },
+                _ = next_tick(&mut chain_refresh) => {
+                    tasks.extend(self.refresh_chains(&sender, &task_monitor).await);
+                }
+                settings = config_reloader.reload::<RelayerSettings>() => {
+                    self.apply_reloaded(settings).await;
+                }
+            }
         }
+    }
+}
 
-        if let Err(err) = try_join_all(tasks).await {
-            tracing::error!(
-                error=?err,
-                "Relayer task panicked"
-            );
+/// Waits for the next tick of `interval`, or forever if there is none
+async fn next_tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
         }
+        None => futures_util::future::pending().await,
     }
 }
 
+/// Handles to abort `tasks` with
+fn abort_handles(tasks: &[Instrumented<JoinHandle<()>>]) -> Vec<AbortHandle> {
+    tasks
+        .iter()
+        .map(|task| task.inner().abort_handle())
+        .collect()
+}
+
 impl Relayer {
     async fn run_message_sync(
         &self,
@@ -402,21 +596,32 @@ impl Relayer {
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let index_settings = self.as_ref().settings.chains[origin.name()].index_settings();
+        let skip_gaps = index_settings.skip_gaps_after_rewinds.is_some();
         let contract_sync = self.message_syncs.get(origin).unwrap().clone();
-        let cursor = contract_sync.cursor(index_settings).await;
-        tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
-            contract_sync
-                .clone()
-                .sync("dispatched_messages", cursor.into())
-                .await
-        }))
-        .instrument(info_span!("MessageSync"))
+        // The cursor is built again on every restart, resuming from the db
+        let sync = self
+            .sync_supervisor
+            .supervise("dispatched_messages", origin, move || {
+                let contract_sync = contract_sync.clone();
+                let index_settings = index_settings.clone();
+                async move {
+                    let cursor = contract_sync.cursor_skipping_gaps(index_settings).await;
+                    let sync = contract_sync.sync("dispatched_messages", cursor.into());
+                    if skip_gaps {
+                        tokio::join!(sync, contract_sync.backfill_gaps("dispatched_messages"));
+                    } else {
+                        sync.await
+                    }
+                }
+            });
+        tokio::spawn(TaskMonitor::instrument(&task_monitor, sync))
+            .instrument(info_span!("MessageSync"))
     }
 
     async fn run_interchain_gas_payment_sync(
         &self,
         origin: &HyperlaneDomain,
-        tx_id_receiver: Option<Receiver<H512>>,
+        broadcaster: Option<Sender<H512>>,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let index_settings = self.as_ref().settings.chains[origin.name()].index_settings();
@@ -425,44 +630,61 @@ impl Relayer {
             .get(origin)
             .unwrap()
             .clone();
-        let cursor = contract_sync.cursor(index_settings).await;
-        tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
-            contract_sync
-                .clone()
-                .sync(
-                    "gas_payments",
-                    SyncOptions::new(Some(cursor), tx_id_receiver),
-                )
-                .await
-        }))
-        .instrument(info_span!("IgpSync"))
+        let sync = self
+            .sync_supervisor
+            .supervise("gas_payments", origin, move || {
+                let contract_sync = contract_sync.clone();
+                let index_settings = index_settings.clone();
+                let tx_id_receiver = broadcaster.as_ref().map(Sender::subscribe);
+                async move {
+                    let cursor = contract_sync.cursor(index_settings).await;
+                    contract_sync
+                        .sync(
+                            "gas_payments",
+                            SyncOptions::new(Some(cursor), tx_id_receiver),
+                        )
+                        .await
+                }
+            });
+        tokio::spawn(TaskMonitor::instrument(&task_monitor, sync)).instrument(info_span!("IgpSync"))
     }
 
     async fn run_merkle_tree_hook_syncs(
         &self,
         origin: &HyperlaneDomain,
-        tx_id_receiver: Option<Receiver<H512>>,
+        broadcaster: Option<Sender<H512>>,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let index_settings = self.as_ref().settings.chains[origin.name()].index.clone();
+        let skip_gaps = index_settings.skip_gaps_after_rewinds.is_some();
         let contract_sync = self.merkle_tree_hook_syncs.get(origin).unwrap().clone();
-        let cursor = contract_sync.cursor(index_settings).await;
-        tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
-            contract_sync
-                .clone()
-                .sync(
-                    "merkle_tree_hook",
-                    SyncOptions::new(Some(cursor), tx_id_receiver),
-                )
-                .await
-        }))
-        .instrument(info_span!("MerkleTreeHookSync"))
+        let sync = self
+            .sync_supervisor
+            .supervise("merkle_tree_hook", origin, move || {
+                let contract_sync = contract_sync.clone();
+                let index_settings = index_settings.clone();
+                let tx_id_receiver = broadcaster.as_ref().map(Sender::subscribe);
+                async move {
+                    let cursor = contract_sync.cursor_skipping_gaps(index_settings).await;
+                    let sync = contract_sync.sync(
+                        "merkle_tree_hook",
+                        SyncOptions::new(Some(cursor), tx_id_receiver),
+                    );
+                    if skip_gaps {
+                        tokio::join!(sync, contract_sync.backfill_gaps("merkle_tree_hook"));
+                    } else {
+                        sync.await
+                    }
+                }
+            });
+        tokio::spawn(TaskMonitor::instrument(&task_monitor, sync))
+            .instrument(info_span!("MerkleTreeHookSync"))
     }
 
     fn run_message_processor(
         &self,
         origin: &HyperlaneDomain,
-        send_channels: HashMap<u32, UnboundedSender<QueueOperation>>,
+        routes: DestinationRoutes,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let metrics = MessageProcessorMetrics::new(
@@ -470,30 +692,16 @@ impl Relayer {
             origin,
             self.destination_chains.keys(),
         );
-        let destination_ctxs: HashMap<_, _> = self
-            .destination_chains
-            .keys()
-            .filter(|&destination| destination != origin)
-            .map(|destination| {
-                (
-                    destination.id(),
-                    self.msg_ctxs[&ContextKey {
-                        origin: origin.id(),
-                        destination: destination.id(),
-                    }]
-                        .clone(),
-                )
-            })
-            .collect();
 
         let message_processor = MessageProcessor::new(
             self.dbs.get(origin).unwrap().clone(),
-            self.whitelist.clone(),
-            self.blacklist.clone(),
+            self.message_filter.clone(),
             metrics,
-            send_channels,
-            destination_ctxs,
+            routes,
             self.metric_app_contexts.clone(),
+            self.message_syncs
+                .get(origin)
+                .and_then(|sync| sync.sequence_gaps()),
         );
 
         let span = info_span!("MessageProcessor", origin=%message_processor.domain());
@@ -519,37 +727,473 @@ impl Relayer {
         processor.spawn().instrument(span)
     }
 
-    #[allow(clippy::too_many_arguments)]
-    #[tracing::instrument(skip(self, receiver))]
+    #[tracing::instrument(skip(self, submitter))]
     fn run_destination_submitter(
         &self,
         destination: &HyperlaneDomain,
-        receiver: UnboundedReceiver<QueueOperation>,
-        retry_receiver_channel: Sender<MessageRetryRequest>,
-        batch_size: u32,
+        submitter: Instrumented<JoinHandle<()>>,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
-        let serial_submitter = SerialSubmitter::new(
-            destination.clone(),
-            receiver,
-            retry_receiver_channel,
-            SerialSubmitterMetrics::new(&self.core.metrics, destination),
-            batch_size,
-            task_monitor.clone(),
-        );
         let span = info_span!("SerialSubmitter", destination=%destination);
         let destination = destination.clone();
         tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
-            // Propagate task panics
-            serial_submitter.spawn().await.unwrap_or_else(|err| {
-                panic!(
+            // Propagate task panics, but not the abort of a removed destination
+            match submitter.await {
+                Err(err) if !err.is_cancelled() => panic!(
                     "destination submitter panicked for destination {}: {:?}",
                     destination, err
-                )
-            });
+                ),
+                _ => {}
+            }
         }))
         .instrument(span)
     }
+
+    /// Build the context of the messages sent from `origin` to `destination`
+    fn build_msg_ctx(
+        &self,
+        origin: &HyperlaneDomain,
+        destination: &HyperlaneDomain,
+    ) -> Arc<MessageContext> {
+        let destination_chain_setup = &self.destination_chains[destination];
+        let transaction_gas_limit: Option<U256> = if self
+            .skip_transaction_gas_limit_for
+            .contains(&destination.id())
+        {
+            None
+        } else {
+            self.transaction_gas_limit
+        };
+        let db = self.dbs[origin].clone();
+        let metadata_builder = BaseMetadataBuilder::new(
+            origin.clone(),
+            destination_chain_setup.clone(),
+            self.prover_syncs[origin].clone(),
+            self.validator_announces[origin].clone(),
+            self.allow_local_checkpoint_syncers,
+            self.allowed_validators.clone(),
+            self.core.metrics.clone(),
+            db.clone(),
+            5,
+            IsmAwareAppContextClassifier::new(
+                self.mailboxes[destination].clone(),
+                self.metric_app_contexts.clone(),
+            ),
+            IsmConfigCache::new(self.ism_cache_ttl),
+        );
+
+        Arc::new(MessageContext {
+            destination_mailbox: self.mailboxes[destination].clone(),
+            origin_db: db,
+            metadata_builder: Arc::new(metadata_builder),
+            origin_gas_payment_enforcer: self.gas_payment_enforcers[origin].clone(),
+            transaction_gas_limit,
+            metrics: MessageSubmissionMetrics::new(&self.core_metrics, origin, destination),
+            receipt_signer: self.receipt_signer.clone(),
+            prioritization: self.prioritization.clone(),
+            dead_letter: self.dead_letter.clone(),
+            retry_policy: StdRwLock::new(destination_chain_setup.retry_policy.clone()),
+            gas_limit_policy: destination_chain_setup.gas_limit_policy.clone(),
+            app_gas_limit_policies: self.app_gas_limit_policies.clone(),
+            origin_igp: self.interchain_gas_paymasters.get(origin).cloned(),
+            metadata_permits: self.metadata_permits.get(origin).cloned(),
+            message_claims: self.message_claims.clone(),
+            message_hooks: self.message_hooks.clone(),
+        })
+    }
+
+    /// Build the contexts of the messages sent from each of `origins` to
+    /// each of `destinations`
+    fn insert_msg_ctxs(&mut self, origins: &[HyperlaneDomain], destinations: &[HyperlaneDomain]) {
+        for origin in origins {
+            for destination in destinations {
+                let ctx = self.build_msg_ctx(origin, destination);
+                self.msg_ctxs.insert(
+                    ContextKey {
+                        origin: origin.id(),
+                        destination: destination.id(),
+                    },
+                    ctx,
+                );
+            }
+        }
+    }
+
+    /// Start the submitter of `destination`, and route the messages of the
+    /// running origins to it. Returns the queues of the submitter along with
+    /// its tasks.
+    async fn start_destination(
+        &mut self,
+        destination: &HyperlaneDomain,
+        sender: &Sender<QueueRequest>,
+        task_monitor: &TaskMonitor,
+    ) -> (Vec<OpQueue>, Vec<Instrumented<JoinHandle<()>>>) {
+        let (send_channel, receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
+
+        let batch_config = self.core.settings.chains[destination.name()]
+            .connection
+            .operation_batch_config();
+        let serial_submitter = SerialSubmitter::new(
+            destination.clone(),
+            self.mailboxes[destination].clone(),
+            receive_channel,
+            sender.clone(),
+            SerialSubmitterMetrics::new(&self.core.metrics, destination),
+            // Default to submitting one message at a time if there is no batch config
+            batch_config.map(|c| c.max_batch_size).unwrap_or(1),
+            batch_config.and_then(|c| c.batch_window),
+            self.max_in_flight_per_destination,
+            self.circuit_breaker.clone(),
+            self.app_concurrency_limits.clone(),
+            task_monitor.clone(),
+        );
+        let op_queues = serial_submitter.queues();
+        if let Some(breaker) = serial_submitter.circuit_breaker() {
+            self.circuit_breakers.insert(destination.clone(), breaker);
+        }
+        let submitter = serial_submitter.spawn();
+        let mut handles = abort_handles(std::slice::from_ref(&submitter));
+        let mut tasks =
+            vec![self.run_destination_submitter(destination, submitter, task_monitor.clone())];
+
+        let metrics_updater = MetricsUpdater::new(
+            &self.destination_chains[destination],
+            self.core_metrics.clone(),
+            self.chain_metrics.clone(),
+            Self::AGENT_NAME.to_string(),
+        )
+        .await
+        .unwrap();
+        tasks.push(metrics_updater.spawn());
+
+        for (origin, routes) in &self.origin_routes {
+            if origin == destination {
+                continue;
+            }
+            let ctx = self.msg_ctxs[&ContextKey {
+                origin: origin.id(),
+                destination: destination.id(),
+            }]
+                .clone();
+            routes.write().await.insert(
+                destination.id(),
+                DestinationRoute::new(send_channel.clone(), ctx),
+            );
+        }
+        self.send_channels.insert(destination.clone(), send_channel);
+        handles.extend(abort_handles(&tasks));
+        self.destination_tasks.insert(destination.clone(), handles);
+        (op_queues, tasks)
+    }
+
+    /// Start syncing `origin` and processing its messages, routing them to
+    /// the running destinations
+    async fn start_origin(
+        &mut self,
+        origin: &HyperlaneDomain,
+        task_monitor: &TaskMonitor,
+    ) -> Vec<Instrumented<JoinHandle<()>>> {
+        let maybe_broadcaster = self
+            .message_syncs
+            .get(origin)
+            .and_then(|sync| sync.get_broadcaster());
+        let mut tasks = vec![
+            self.run_message_sync(origin, task_monitor.clone()).await,
+            self.run_interchain_gas_payment_sync(
+                origin,
+                maybe_broadcaster.clone(),
+                task_monitor.clone(),
+            )
+            .await,
+            self.run_merkle_tree_hook_syncs(origin, maybe_broadcaster, task_monitor.clone())
+                .await,
+        ];
+
+        let routes: HashMap<_, _> = self
+            .send_channels
+            .iter()
+            .filter(|(destination, _)| *destination != origin)
+            .map(|(destination, send_channel)| {
+                let ctx = self.msg_ctxs[&ContextKey {
+                    origin: origin.id(),
+                    destination: destination.id(),
+                }]
+                    .clone();
+                (
+                    destination.id(),
+                    DestinationRoute::new(send_channel.clone(), ctx),
+                )
+            })
+            .collect();
+        let routes = Arc::new(RwLock::new(routes));
+        // each message process attempts to send messages from a chain
+        tasks.push(self.run_message_processor(origin, routes.clone(), task_monitor.clone()));
+        tasks.push(self.run_merkle_tree_processor(origin, task_monitor.clone()));
+
+        self.origin_routes.insert(origin.clone(), routes);
+        self.origin_tasks
+            .insert(origin.clone(), abort_handles(&tasks));
+        tasks
+    }
+
+    /// Build what the routes from `origin` need, with the reloaded `settings`
+    async fn add_origin(
+        &mut self,
+        settings: &RelayerSettings,
+        origin: &HyperlaneDomain,
+    ) -> Result<()> {
+        let chain_setup = settings.chain_setup(origin)?.clone();
+        let db = HyperlaneRocksDB::new(origin, self.db.clone());
+        let dbs = HashMap::from([(origin.clone(), Arc::new(db.clone()))]);
+        let validator_announce = settings
+            .build_validator_announce(origin, &self.core_metrics)
+            .await?;
+        let message_syncs = settings
+            .contract_syncs::<HyperlaneMessage, _>(
+                iter::once(origin),
+                &self.core_metrics,
+                &self.contract_sync_metrics,
+                dbs.clone(),
+            )
+            .await?;
+        let interchain_gas_payment_syncs = settings
+            .contract_syncs::<InterchainGasPayment, _>(
+                iter::once(origin),
+                &self.core_metrics,
+                &self.contract_sync_metrics,
+                dbs.clone(),
+            )
+            .await?;
+        let merkle_tree_hook_syncs = settings
+            .contract_syncs::<MerkleTreeInsertion, _>(
+                iter::once(origin),
+                &self.core_metrics,
+                &self.contract_sync_metrics,
+                dbs,
+            )
+            .await?;
+        let igp: Option<Arc<dyn InterchainGasPaymaster>> = match settings
+            .build_interchain_gas_paymaster(origin, &self.core_metrics)
+            .await
+        {
+            Ok(igp) => Some(Arc::from(igp)),
+            Err(err) => {
+                warn!(%origin, error=?err, "Failed to build interchain gas paymaster, gas payments can't be quoted");
+                None
+            }
+        };
+
+        self.core
+            .settings
+            .chains
+            .insert(origin.name().to_owned(), chain_setup);
+        self.message_syncs.extend(message_syncs);
+        self.interchain_gas_payment_syncs
+            .extend(interchain_gas_payment_syncs);
+        self.merkle_tree_hook_syncs.extend(merkle_tree_hook_syncs);
+        self.validator_announces
+            .insert(origin.clone(), Arc::from(validator_announce));
+        self.prover_syncs.insert(
+            origin.clone(),
+            Arc::new(RwLock::new(MerkleTreeBuilder::new())),
+        );
+        self.gas_payment_enforcers.insert(
+            origin.clone(),
+            Arc::new(GasPaymentEnforcer::new(
+                self.gas_payment_enforcement.clone(),
+                igp.clone(),
+                db.clone(),
+            )),
+        );
+        if let Some(igp) = igp {
+            self.interchain_gas_paymasters.insert(origin.clone(), igp);
+        }
+        if let Some(max) = self.max_metadata_tasks_per_origin {
+            self.metadata_permits
+                .insert(origin.clone(), Arc::new(Semaphore::new(max as usize)));
+        }
+        self.dbs.insert(origin.clone(), db);
+        self.origin_chains.insert(origin.clone());
+
+        let destinations: Vec<_> = self.destination_chains.keys().cloned().collect();
+        self.insert_msg_ctxs(&[origin.clone()], &destinations);
+        Ok(())
+    }
+
+    /// Build what the routes to `destination` need, with the reloaded
+    /// `settings`
+    async fn add_destination(
+        &mut self,
+        settings: &RelayerSettings,
+        destination: &HyperlaneDomain,
+    ) -> Result<()> {
+        let chain_setup = settings.chain_setup(destination)?.clone();
+        let mailbox = settings
+            .build_mailbox(destination, &self.core_metrics)
+            .await?;
+
+        self.core
+            .settings
+            .chains
+            .insert(destination.name().to_owned(), chain_setup.clone());
+        self.mailboxes
+            .insert(destination.clone(), Arc::from(mailbox));
+        self.destination_chains
+            .insert(destination.clone(), chain_setup);
+
+        let origins: Vec<_> = self.origin_chains.iter().cloned().collect();
+        self.insert_msg_ctxs(&origins, &[destination.clone()]);
+        Ok(())
+    }
+
+    /// Stop processing the messages of `origin` and syncing it
+    fn stop_origin(&mut self, origin: &HyperlaneDomain) {
+        for task in self.origin_tasks.remove(origin).into_iter().flatten() {
+            task.abort();
+        }
+        self.origin_routes.remove(origin);
+        self.msg_ctxs.retain(|key, _| key.origin != origin.id());
+        self.origin_chains.remove(origin);
+        self.message_syncs.remove(origin);
+        self.interchain_gas_payment_syncs.remove(origin);
+        self.merkle_tree_hook_syncs.remove(origin);
+        self.validator_announces.remove(origin);
+        self.prover_syncs.remove(origin);
+        self.gas_payment_enforcers.remove(origin);
+        self.interchain_gas_paymasters.remove(origin);
+        self.metadata_permits.remove(origin);
+        self.dbs.remove(origin);
+    }
+
+    /// Stop routing messages to `destination`, then abort its submitter.
+    /// Messages that weren't delivered are relayed again once the chain is
+    /// added back and the relayer restarts.
+    async fn stop_destination(&mut self, destination: &HyperlaneDomain) {
+        for routes in self.origin_routes.values() {
+            routes.write().await.remove(&destination.id());
+        }
+        self.send_channels.remove(destination);
+        for task in self
+            .destination_tasks
+            .remove(destination)
+            .into_iter()
+            .flatten()
+        {
+            task.abort();
+        }
+        self.msg_ctxs
+            .retain(|key, _| key.destination != destination.id());
+        self.destination_chains.remove(destination);
+        self.mailboxes.remove(destination);
+        self.circuit_breakers.remove(destination);
+    }
+
+    /// Reload the settings, and start or stop relaying for the chains that
+    /// were added to or removed from them. Returns the tasks that were
+    /// started.
+    async fn refresh_chains(
+        &mut self,
+        sender: &Sender<QueueRequest>,
+        task_monitor: &TaskMonitor,
+    ) -> Vec<Instrumented<JoinHandle<()>>> {
+        let settings = match RelayerSettings::load() {
+            Ok(settings) => settings,
+            Err(err) => {
+                warn!(error=?err, "Failed to reload settings, keeping the current chains");
+                return vec![];
+            }
+        };
+
+        let removed_destinations: Vec<_> = self
+            .destination_chains
+            .keys()
+            .filter(|destination| !settings.destination_chains.contains(*destination))
+            .cloned()
+            .collect();
+        for destination in &removed_destinations {
+            info!(%destination, "Destination chain was removed, stopping its submitter");
+            self.stop_destination(destination).await;
+        }
+        let removed_origins: Vec<_> = self
+            .origin_chains
+            .difference(&settings.origin_chains)
+            .cloned()
+            .collect();
+        for origin in &removed_origins {
+            info!(%origin, "Origin chain was removed, stopping its processor");
+            self.stop_origin(origin);
+        }
+
+        let mut tasks = vec![];
+        let added_origins: Vec<_> = settings
+            .origin_chains
+            .difference(&self.origin_chains)
+            .cloned()
+            .collect();
+        for origin in &added_origins {
+            if let Err(err) = self.add_origin(&settings, origin).await {
+                warn!(%origin, error=?err, "Failed to add origin chain");
+                continue;
+            }
+            info!(%origin, "Origin chain was added, starting its processor");
+            tasks.extend(self.start_origin(origin, task_monitor).await);
+        }
+        let added_destinations: Vec<_> = settings
+            .destination_chains
+            .iter()
+            .filter(|destination| !self.destination_chains.contains_key(*destination))
+            .cloned()
+            .collect();
+        for destination in &added_destinations {
+            if let Err(err) = self.add_destination(&settings, destination).await {
+                warn!(%destination, error=?err, "Failed to add destination chain");
+                continue;
+            }
+            info!(%destination, "Destination chain was added, starting its submitter");
+            // The queues of the submitter aren't served by the API until
+            // the relayer restarts
+            let (_, destination_tasks) = self
+                .start_destination(destination, sender, task_monitor)
+                .await;
+            tasks.extend(destination_tasks);
+        }
+        tasks
+    }
+
+    /// Apply the settings that can change while the relayer is running: the
+    /// whitelist and blacklist, the gas payment policies and the retry
+    /// policies, on top of the ones of every agent. Chains are only added or
+    /// removed by `refresh_chains`.
+    async fn apply_reloaded(&mut self, settings: RelayerSettings) {
+        self.core.settings.apply_reloaded(&settings);
+
+        for (destination, chain_setup) in self.destination_chains.iter_mut() {
+            if let Some(reloaded) = settings.chains.get(destination.name()) {
+                chain_setup.retry_policy = reloaded.retry_policy.clone();
+            }
+        }
+        for (key, ctx) in &self.msg_ctxs {
+            let retry_policy = self
+                .destination_chains
+                .iter()
+                .find(|(destination, _)| destination.id() == key.destination)
+                .and_then(|(_, chain_setup)| chain_setup.retry_policy.clone());
+            *ctx.retry_policy.write().expect("poisoned lock") = retry_policy;
+        }
+
+        // Replaces the lists set through the admin API as well
+        *self.message_filter.write().await = MessageFilter {
+            whitelist: settings.whitelist,
+            blacklist: settings.blacklist,
+        };
+        for enforcer in self.gas_payment_enforcers.values() {
+            enforcer
+                .set_policies(settings.gas_payment_enforcement.clone())
+                .await;
+        }
+        self.gas_payment_enforcement = settings.gas_payment_enforcement;
+        info!("Applied reloaded relayer settings");
+    }
 }
 
 #[cfg(test)]
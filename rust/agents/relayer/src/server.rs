@@ -1,22 +1,74 @@
 use axum::{
     extract::{Query, State},
-    routing, Router,
+    http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing, Json, Router,
 };
+use convert_case::Case;
 use derive_new::new;
-use hyperlane_core::{ChainCommunicationError, QueueOperation, H256};
-use serde::Deserialize;
-use std::str::FromStr;
-use tokio::sync::broadcast::Sender;
+use hyperlane_base::{
+    db::{DbError, HyperlaneRocksDB, DB},
+    server::authorize,
+    settings::parser::recase_json_value,
+    DbSnapshotApi, LogFilterApi, ObjectWriter,
+};
+use hyperlane_core::{
+    ChainCommunicationError, MessageProfit, QueueOperation, SignedDeliveryReceipt, H256,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+use tokio::sync::{broadcast::Sender, Mutex, RwLock};
+use tracing::info;
+
+use crate::{
+    msg::{
+        circuit_breaker::{CircuitBreaker, CircuitState},
+        op_queue::OpQueue,
+        processor::MessageFilter,
+    },
+    settings::matching_list::MatchingList,
+};
 
 const MESSAGE_RETRY_API_BASE: &str = "/message_retry";
+const DELIVERY_RECEIPT_API_BASE: &str = "/delivery_receipt";
+const OPERATION_ADMIN_API_BASE: &str = "/admin/operations";
+const MESSAGE_FILTER_ADMIN_API_BASE: &str = "/admin/message_filter";
+const PROFITABILITY_ADMIN_API_BASE: &str = "/admin/profitability";
+const CIRCUIT_BREAKER_ADMIN_API_BASE: &str = "/admin/circuit_breakers";
+/// The most records returned by a single profitability export
+const MAX_PROFITABILITY_RECORDS: usize = 10_000;
 pub const ENDPOINT_MESSAGES_QUEUE_SIZE: usize = 1_000;
 
 /// Returns a vector of agent-specific endpoint routes to be served.
 /// Can be extended with additional routes and feature flags to enable/disable individually.
-pub fn routes(tx: Sender<MessageRetryRequest>) -> Vec<(&'static str, Router)> {
-    let message_retry_api = MessageRetryApi::new(tx);
+/// The admin APIs are only served if an api key is configured.
+pub fn routes(
+    tx: Sender<QueueRequest>,
+    origin_dbs: Vec<HyperlaneRocksDB>,
+    queues: Vec<OpQueue>,
+    message_filter: Arc<RwLock<MessageFilter>>,
+    circuit_breakers: HashMap<u32, Arc<Mutex<CircuitBreaker>>>,
+    admin_api_key: Option<String>,
+    db: DB,
+    db_snapshot_writer: Option<Arc<dyn ObjectWriter>>,
+) -> Vec<(&'static str, Router)> {
+    let message_retry_api = MessageRetryApi::new(tx.clone());
+    let delivery_receipt_api = DeliveryReceiptApi::new(origin_dbs.clone());
 
-    vec![message_retry_api.get_route()]
+    let mut routes = vec![
+        message_retry_api.get_route(),
+        delivery_receipt_api.get_route(),
+    ];
+    if let Some(api_key) = admin_api_key {
+        routes.push(OperationAdminApi::new(api_key.clone(), queues, tx).get_route());
+        routes.push(MessageFilterAdminApi::new(api_key.clone(), message_filter).get_route());
+        routes.push(ProfitabilityAdminApi::new(api_key.clone(), origin_dbs).get_route());
+        routes.push(CircuitBreakerAdminApi::new(api_key.clone(), circuit_breakers).get_route());
+        routes.extend(LogFilterApi::global(api_key.clone()).map(|api| api.get_route()));
+        routes.push(DbSnapshotApi::new(api_key, "relayer", db, db_snapshot_writer).get_route());
+    }
+    routes
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -25,6 +77,34 @@ pub enum MessageRetryRequest {
     DestinationDomain(u32),
 }
 
+/// A request to act on the matching operations in the submitter queues
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QueueRequest {
+    /// Attempt the operations again immediately, resurrecting them if they
+    /// were parked as undeliverable
+    Retry(MessageRetryRequest),
+    /// Prepare the operations again before submitting them, which
+    /// re-estimates their cost
+    Reestimate(MessageRetryRequest),
+    /// Remove the operations from the queues until the relayer restarts
+    Drop(MessageRetryRequest),
+}
+
+impl QueueRequest {
+    /// The operations the request applies to
+    pub fn selector(&self) -> &MessageRetryRequest {
+        match self {
+            Self::Retry(selector) | Self::Reestimate(selector) | Self::Drop(selector) => selector,
+        }
+    }
+}
+
+impl From<MessageRetryRequest> for QueueRequest {
+    fn from(request: MessageRetryRequest) -> Self {
+        Self::Retry(request)
+    }
+}
+
 impl PartialEq<QueueOperation> for &MessageRetryRequest {
     fn eq(&self, other: &QueueOperation) -> bool {
         match self {
@@ -38,7 +118,7 @@ impl PartialEq<QueueOperation> for &MessageRetryRequest {
 
 #[derive(new, Clone)]
 pub struct MessageRetryApi {
-    tx: Sender<MessageRetryRequest>,
+    tx: Sender<QueueRequest>,
 }
 
 #[derive(Deserialize)]
@@ -63,7 +143,7 @@ impl TryFrom<RawMessageRetryRequest> for Vec<MessageRetryRequest> {
 }
 
 async fn retry_message(
-    State(tx): State<Sender<MessageRetryRequest>>,
+    State(tx): State<Sender<QueueRequest>>,
     Query(request): Query<RawMessageRetryRequest>,
 ) -> String {
     let retry_requests: Vec<MessageRetryRequest> = match request.try_into() {
@@ -81,7 +161,7 @@ async fn retry_message(
 
     if let Err(err) = retry_requests
         .into_iter()
-        .map(|req| tx.send(req))
+        .map(|req| tx.send(req.into()))
         .collect::<Result<Vec<_>, _>>()
     {
         return format!("Failed to send retry request to the queue: {}", err);
@@ -102,16 +182,514 @@ impl MessageRetryApi {
     }
 }
 
+/// Serves the signed receipts of the messages we delivered.
+#[derive(new, Clone)]
+pub struct DeliveryReceiptApi {
+    origin_dbs: Vec<HyperlaneRocksDB>,
+}
+
+#[derive(Deserialize)]
+struct RawDeliveryReceiptRequest {
+    message_id: String,
+}
+
+async fn delivery_receipt(
+    State(origin_dbs): State<Vec<HyperlaneRocksDB>>,
+    Query(request): Query<RawDeliveryReceiptRequest>,
+) -> Result<Json<SignedDeliveryReceipt>, (StatusCode, String)> {
+    let message_id = H256::from_str(&request.message_id).map_err(|err| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Failed to parse message id: {}", err),
+        )
+    })?;
+    // Receipts are stored in the db of the origin of the message
+    for db in &origin_dbs {
+        match db.retrieve_delivery_receipt_by_message_id(&message_id) {
+            Ok(Some(receipt)) => return Ok(Json(receipt)),
+            Ok(None) => {}
+            Err(err) => {
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to retrieve delivery receipt: {}", err),
+                ))
+            }
+        }
+    }
+    Err((
+        StatusCode::NOT_FOUND,
+        "No delivery receipt found for this message".to_string(),
+    ))
+}
+
+impl DeliveryReceiptApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(delivery_receipt))
+            .with_state(self.origin_dbs.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (DELIVERY_RECEIPT_API_BASE, self.router())
+    }
+}
+
+/// A summary of an operation waiting in a submitter queue. Operations that
+/// are being prepared, submitted or confirmed at the time aren't in a queue.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationSummary {
+    pub id: H256,
+    pub queue: String,
+    pub destination_domain: u32,
+    pub num_retries: u32,
+    /// Whether the operation is parked as undeliverable until it's retried
+    pub dead_lettered: bool,
+    /// Seconds until the operation is attempted again, if it's backing off
+    pub next_attempt_in_secs: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+/// Lists the operations in the submitter queues, and retries, re-estimates
+/// or drops them by message id. Requests must carry the api key as a bearer
+/// token.
+#[derive(new, Clone)]
+pub struct OperationAdminApi {
+    api_key: String,
+    queues: Vec<OpQueue>,
+    tx: Sender<QueueRequest>,
+}
+
+#[derive(Deserialize)]
+struct RawOperationListRequest {
+    destination_domain: Option<u32>,
+    dead_lettered: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct RawOperationRequest {
+    message_id: String,
+}
+
+async fn list_operations(
+    State(api): State<OperationAdminApi>,
+    headers: HeaderMap,
+    Query(request): Query<RawOperationListRequest>,
+) -> Result<Json<Vec<OperationSummary>>, (StatusCode, String)> {
+    authorize(&api.api_key, &headers)?;
+    let mut summaries = vec![];
+    for queue in &api.queues {
+        summaries.extend(queue.summaries().await);
+    }
+    if let Some(destination_domain) = request.destination_domain {
+        summaries.retain(|summary| summary.destination_domain == destination_domain);
+    }
+    if let Some(dead_lettered) = request.dead_lettered {
+        summaries.retain(|summary| summary.dead_lettered == dead_lettered);
+    }
+    Ok(Json(summaries))
+}
+
+async fn send_operation_request(
+    api: OperationAdminApi,
+    headers: HeaderMap,
+    request: RawOperationRequest,
+    to_request: fn(MessageRetryRequest) -> QueueRequest,
+) -> Result<String, (StatusCode, String)> {
+    authorize(&api.api_key, &headers)?;
+    let message_id = H256::from_str(&request.message_id).map_err(|err| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Failed to parse message id: {}", err),
+        )
+    })?;
+    api.tx
+        .send(to_request(MessageRetryRequest::MessageId(message_id)))
+        .map_err(|err| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to send request to the queues: {}", err),
+            )
+        })?;
+    Ok("Sent request to the queues".to_string())
+}
+
+async fn retry_operation(
+    State(api): State<OperationAdminApi>,
+    headers: HeaderMap,
+    Query(request): Query<RawOperationRequest>,
+) -> Result<String, (StatusCode, String)> {
+    send_operation_request(api, headers, request, QueueRequest::Retry).await
+}
+
+async fn reestimate_operation(
+    State(api): State<OperationAdminApi>,
+    headers: HeaderMap,
+    Query(request): Query<RawOperationRequest>,
+) -> Result<String, (StatusCode, String)> {
+    send_operation_request(api, headers, request, QueueRequest::Reestimate).await
+}
+
+async fn drop_operation(
+    State(api): State<OperationAdminApi>,
+    headers: HeaderMap,
+    Query(request): Query<RawOperationRequest>,
+) -> Result<String, (StatusCode, String)> {
+    send_operation_request(api, headers, request, QueueRequest::Drop).await
+}
+
+impl OperationAdminApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(list_operations))
+            .route("/retry", routing::post(retry_operation))
+            .route("/reestimate", routing::post(reestimate_operation))
+            .route("/drop", routing::post(drop_operation))
+            .with_state(self.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (OPERATION_ADMIN_API_BASE, self.router())
+    }
+}
+
+/// Shows and replaces the whitelist and blacklist of the relayer without
+/// restarting it. Requests must carry the api key as a bearer token.
+#[derive(new, Clone)]
+pub struct MessageFilterAdminApi {
+    api_key: String,
+    message_filter: Arc<RwLock<MessageFilter>>,
+}
+
+/// The lists of the message filter, as shown by the admin API
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageFilterSummary {
+    pub whitelist: String,
+    pub blacklist: String,
+}
+
+impl From<&MessageFilter> for MessageFilterSummary {
+    fn from(filter: &MessageFilter) -> Self {
+        Self {
+            whitelist: filter.whitelist.to_string(),
+            blacklist: filter.blacklist.to_string(),
+        }
+    }
+}
+
+/// Lists to replace, in the same format as the settings. Lists that are
+/// left out are kept.
+#[derive(Deserialize)]
+struct RawMessageFilterUpdate {
+    whitelist: Option<MatchingList>,
+    blacklist: Option<MatchingList>,
+}
+
+async fn get_message_filter(
+    State(api): State<MessageFilterAdminApi>,
+    headers: HeaderMap,
+) -> Result<Json<MessageFilterSummary>, (StatusCode, String)> {
+    authorize(&api.api_key, &headers)?;
+    let filter = api.message_filter.read().await;
+    Ok(Json(MessageFilterSummary::from(&*filter)))
+}
+
+async fn update_message_filter(
+    State(api): State<MessageFilterAdminApi>,
+    headers: HeaderMap,
+    Json(update): Json<Value>,
+) -> Result<Json<MessageFilterSummary>, (StatusCode, String)> {
+    authorize(&api.api_key, &headers)?;
+    // Matching lists are parsed from flat case keys, like in the settings
+    let update: RawMessageFilterUpdate =
+        serde_json::from_value(recase_json_value(update, Case::Flat)).map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to parse matching lists: {}", err),
+            )
+        })?;
+    // Both lists are swapped under the same lock, so no message is filtered
+    // by a mix of old and new lists
+    let mut filter = api.message_filter.write().await;
+    if let Some(whitelist) = update.whitelist {
+        filter.whitelist = whitelist;
+    }
+    if let Some(blacklist) = update.blacklist {
+        filter.blacklist = blacklist;
+    }
+    info!(whitelist=%filter.whitelist, blacklist=%filter.blacklist, "Replaced message filter");
+    Ok(Json(MessageFilterSummary::from(&*filter)))
+}
+
+impl MessageFilterAdminApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route(
+                "/",
+                routing::get(get_message_filter).post(update_message_filter),
+            )
+            .with_state(self.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (MESSAGE_FILTER_ADMIN_API_BASE, self.router())
+    }
+}
+
+/// Exports how the gas payments for the messages we processed compare with
+/// what we spent processing them, as JSON or CSV. Requests must carry the api
+/// key as a bearer token.
+#[derive(new, Clone)]
+pub struct ProfitabilityAdminApi {
+    api_key: String,
+    origin_dbs: Vec<HyperlaneRocksDB>,
+}
+
+/// The profitability of a processed message. Token amounts are decimal
+/// strings in the smallest unit of the respective native token.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfitabilityRecord {
+    pub message_id: H256,
+    pub nonce: u32,
+    pub origin_domain: u32,
+    pub destination_domain: u32,
+    /// Received on the origin, in origin tokens
+    pub payment: String,
+    pub gas_used: String,
+    /// Spent on the destination, in destination tokens
+    pub tokens_used: String,
+    pub token_exchange_rate: String,
+    /// `tokens_used` in origin tokens, if the exchange rate is known
+    pub cost: Option<String>,
+    /// `payment` minus `cost`, negative for losses
+    pub profit: Option<String>,
+    pub timestamp: u64,
+}
+
+impl From<&MessageProfit> for ProfitabilityRecord {
+    fn from(profit: &MessageProfit) -> Self {
+        let cost = profit.cost();
+        Self {
+            message_id: profit.message_id,
+            nonce: profit.nonce,
+            origin_domain: profit.origin_domain,
+            destination_domain: profit.destination_domain,
+            payment: profit.payment.to_string(),
+            gas_used: profit.gas_used.to_string(),
+            tokens_used: profit.tokens_used.to_string(),
+            token_exchange_rate: profit.token_exchange_rate.to_string(),
+            cost: cost.map(|cost| cost.to_string()),
+            profit: cost.map(|cost| {
+                if profit.payment >= cost {
+                    (profit.payment - cost).to_string()
+                } else {
+                    format!("-{}", cost - profit.payment)
+                }
+            }),
+            timestamp: profit.timestamp,
+        }
+    }
+}
+
+impl ProfitabilityRecord {
+    const CSV_HEADER: &str = "message_id,nonce,origin_domain,destination_domain,payment,gas_used,tokens_used,token_exchange_rate,cost,profit,timestamp";
+
+    fn to_csv_row(&self) -> String {
+        format!(
+            "{:?},{},{},{},{},{},{},{},{},{},{}",
+            self.message_id,
+            self.nonce,
+            self.origin_domain,
+            self.destination_domain,
+            self.payment,
+            self.gas_used,
+            self.tokens_used,
+            self.token_exchange_rate,
+            self.cost.as_deref().unwrap_or_default(),
+            self.profit.as_deref().unwrap_or_default(),
+            self.timestamp,
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct RawProfitabilityRequest {
+    origin_domain: Option<u32>,
+    from_nonce: Option<u32>,
+    limit: Option<usize>,
+    format: Option<String>,
+}
+
+async fn export_profitability(
+    State(api): State<ProfitabilityAdminApi>,
+    headers: HeaderMap,
+    Query(request): Query<RawProfitabilityRequest>,
+) -> Result<Response, (StatusCode, String)> {
+    authorize(&api.api_key, &headers)?;
+    let csv = match request.format.as_deref() {
+        Some("json") | None => false,
+        Some("csv") => true,
+        Some(format) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Unknown export format `{format}`, expected `json` or `csv`"),
+            ))
+        }
+    };
+    let limit = request
+        .limit
+        .unwrap_or(MAX_PROFITABILITY_RECORDS)
+        .min(MAX_PROFITABILITY_RECORDS);
+    let db_err = |err: DbError| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to retrieve message profits: {}", err),
+        )
+    };
+
+    let mut records = vec![];
+    for db in &api.origin_dbs {
+        if request
+            .origin_domain
+            .map_or(false, |domain| domain != db.domain().id())
+        {
+            continue;
+        }
+        let Some(highest_nonce) = db.retrieve_highest_seen_message_nonce().map_err(db_err)? else {
+            continue;
+        };
+        // Only messages we processed have a profit stored
+        for nonce in request.from_nonce.unwrap_or(0)..=highest_nonce {
+            if records.len() >= limit {
+                break;
+            }
+            if let Some(profit) = db
+                .retrieve_message_profit_by_nonce(&nonce)
+                .map_err(db_err)?
+            {
+                records.push(ProfitabilityRecord::from(&profit));
+            }
+        }
+    }
+
+    if !csv {
+        return Ok(Json(records).into_response());
+    }
+    let mut body = format!("{}\n", ProfitabilityRecord::CSV_HEADER);
+    for record in &records {
+        body.push_str(&record.to_csv_row());
+        body.push('\n');
+    }
+    Ok(([(CONTENT_TYPE, "text/csv")], body).into_response())
+}
+
+impl ProfitabilityAdminApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(export_profitability))
+            .with_state(self.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (PROFITABILITY_ADMIN_API_BASE, self.router())
+    }
+}
+
+/// Shows the circuit breakers of the destinations, and closes them to resume
+/// the submissions they paused. Requests must carry the api key as a bearer
+/// token.
+#[derive(new, Clone)]
+pub struct CircuitBreakerAdminApi {
+    api_key: String,
+    circuit_breakers: HashMap<u32, Arc<Mutex<CircuitBreaker>>>,
+}
+
+/// The state of the circuit breaker of a destination
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CircuitBreakerSummary {
+    pub destination_domain: u32,
+    pub state: CircuitState,
+    /// The share of failed submissions among the recent ones
+    pub failure_rate: f64,
+}
+
+#[derive(Deserialize)]
+struct RawCircuitBreakerRequest {
+    destination_domain: u32,
+}
+
+async fn list_circuit_breakers(
+    State(api): State<CircuitBreakerAdminApi>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<CircuitBreakerSummary>>, (StatusCode, String)> {
+    authorize(&api.api_key, &headers)?;
+    let mut summaries = vec![];
+    for (&destination_domain, breaker) in &api.circuit_breakers {
+        let breaker = breaker.lock().await;
+        summaries.push(CircuitBreakerSummary {
+            destination_domain,
+            state: breaker.state(),
+            failure_rate: breaker.failure_rate(),
+        });
+    }
+    summaries.sort_by_key(|summary| summary.destination_domain);
+    Ok(Json(summaries))
+}
+
+async fn close_circuit_breaker(
+    State(api): State<CircuitBreakerAdminApi>,
+    headers: HeaderMap,
+    Query(request): Query<RawCircuitBreakerRequest>,
+) -> Result<String, (StatusCode, String)> {
+    authorize(&api.api_key, &headers)?;
+    let breaker = api
+        .circuit_breakers
+        .get(&request.destination_domain)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                "No circuit breaker for this destination".to_string(),
+            )
+        })?;
+    breaker.lock().await.close();
+    info!(
+        destination_domain = request.destination_domain,
+        "Closed circuit breaker"
+    );
+    Ok("Resumed submissions to the destination".to_string())
+}
+
+impl CircuitBreakerAdminApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(list_circuit_breakers))
+            .route("/close", routing::post(close_circuit_breaker))
+            .with_state(self.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (CIRCUIT_BREAKER_ADMIN_API_BASE, self.router())
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::settings::CircuitBreakerConf;
     use axum::http::StatusCode;
     use ethers::utils::hex::ToHex;
-    use std::net::SocketAddr;
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::{
+        DeliveryReceipt, HyperlaneDomain, HyperlaneMessage, KnownHyperlaneDomain, Signature, H160,
+        H512,
+    };
+    use prometheus::{IntCounter, IntGauge};
+    use std::{
+        net::SocketAddr,
+        time::{Duration, Instant},
+    };
     use tokio::sync::broadcast::{Receiver, Sender};
 
-    fn setup_test_server() -> (SocketAddr, Receiver<MessageRetryRequest>) {
-        let broadcast_tx = Sender::<MessageRetryRequest>::new(ENDPOINT_MESSAGES_QUEUE_SIZE);
+    fn setup_test_server() -> (SocketAddr, Receiver<QueueRequest>) {
+        let broadcast_tx = Sender::<QueueRequest>::new(ENDPOINT_MESSAGES_QUEUE_SIZE);
         let message_retry_api = MessageRetryApi::new(broadcast_tx.clone());
         let (path, retry_router) = message_retry_api.get_route();
         let app = Router::new().nest(path, retry_router);
@@ -147,7 +725,7 @@ mod tests {
 
         assert_eq!(
             rx.try_recv().unwrap(),
-            MessageRetryRequest::MessageId(message_id)
+            QueueRequest::Retry(MessageRetryRequest::MessageId(message_id))
         );
     }
 
@@ -171,7 +749,297 @@ mod tests {
 
         assert_eq!(
             rx.try_recv().unwrap(),
-            MessageRetryRequest::DestinationDomain(destination_domain)
+            QueueRequest::Retry(MessageRetryRequest::DestinationDomain(destination_domain))
         );
     }
+
+    #[tokio::test]
+    async fn test_delivery_receipt() {
+        test_utils::run_test_db(|db| async move {
+            let db =
+                HyperlaneRocksDB::new(&HyperlaneDomain::Known(KnownHyperlaneDomain::Test1), db);
+            let receipt = SignedDeliveryReceipt {
+                value: DeliveryReceipt {
+                    message_id: H256::random(),
+                    destination_domain: 42,
+                    transaction_id: H512::random(),
+                    timestamp: 1_700_000_000,
+                    relayer: H160::random(),
+                },
+                signature: Signature {
+                    r: 1.into(),
+                    s: 2.into(),
+                    v: 27,
+                },
+            };
+            db.store_delivery_receipt_by_message_id(&receipt.value.message_id, &receipt)
+                .unwrap();
+
+            let (path, router) = DeliveryReceiptApi::new(vec![db]).get_route();
+            let app = Router::new().nest(path, router);
+            let server =
+                axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+            let addr = server.local_addr();
+            tokio::spawn(server);
+
+            let url = |message_id: H256| {
+                format!(
+                    "http://{}{}?message_id={}",
+                    addr,
+                    DELIVERY_RECEIPT_API_BASE,
+                    message_id.encode_hex::<String>()
+                )
+            };
+
+            let response = reqwest::get(url(receipt.value.message_id)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.json::<SignedDeliveryReceipt>().await.unwrap(),
+                receipt
+            );
+
+            let response = reqwest::get(url(H256::random())).await.unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_operation_admin_api() {
+        let broadcast_tx = Sender::<QueueRequest>::new(ENDPOINT_MESSAGES_QUEUE_SIZE);
+        let mut rx = broadcast_tx.subscribe();
+        let (path, router) =
+            OperationAdminApi::new("secret".to_owned(), vec![], broadcast_tx).get_route();
+        let app = Router::new().nest(path, router);
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let client = reqwest::Client::new();
+        let message_id = H256::random();
+        let drop_url = format!(
+            "http://{}{}/drop?message_id={}",
+            addr,
+            OPERATION_ADMIN_API_BASE,
+            message_id.encode_hex::<String>()
+        );
+
+        // Requests without the api key are rejected
+        let response = client.post(&drop_url).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = client
+            .post(&drop_url)
+            .bearer_auth("wrong")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(rx.try_recv().is_err());
+
+        let response = client
+            .post(&drop_url)
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            QueueRequest::Drop(MessageRetryRequest::MessageId(message_id))
+        );
+
+        let response = client
+            .get(format!("http://{}{}", addr, OPERATION_ADMIN_API_BASE))
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.json::<Vec<OperationSummary>>().await.unwrap(),
+            vec![]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_message_filter_admin_api() {
+        let message_filter = Arc::new(RwLock::new(MessageFilter::default()));
+        let (path, router) =
+            MessageFilterAdminApi::new("secret".to_owned(), message_filter.clone()).get_route();
+        let app = Router::new().nest(path, router);
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let client = reqwest::Client::new();
+        let url = format!("http://{}{}", addr, MESSAGE_FILTER_ADMIN_API_BASE);
+        let update = serde_json::json!({
+            "blacklist": [{ "originDomain": 42 }],
+        });
+
+        let response = client.post(&url).json(&update).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = client
+            .post(&url)
+            .bearer_auth("secret")
+            .json(&update)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let message = HyperlaneMessage {
+            origin: 42,
+            ..Default::default()
+        };
+        let filter = message_filter.read().await;
+        assert!(filter.blacklist.msg_matches(&message, false));
+        // The whitelist was left out, so it is kept
+        assert!(filter.whitelist.msg_matches(&message, true));
+    }
+
+    #[tokio::test]
+    async fn test_profitability_admin_api() {
+        test_utils::run_test_db(|db| async move {
+            let db =
+                HyperlaneRocksDB::new(&HyperlaneDomain::Known(KnownHyperlaneDomain::Test1), db);
+            let profit = |nonce: u32, payment: u32| MessageProfit {
+                message_id: H256::from_low_u64_be(nonce as u64),
+                nonce,
+                origin_domain: 13371,
+                destination_domain: 42,
+                payment: payment.into(),
+                gas_used: 100.into(),
+                tokens_used: 1_000.into(),
+                // Destination tokens are worth twice as much
+                token_exchange_rate: 20_000_000_000u64.into(),
+                timestamp: 1_700_000_000,
+            };
+            // The message with nonce 1 wasn't processed by us
+            db.store_message_profit_by_nonce(&0, &profit(0, 3_000))
+                .unwrap();
+            db.store_message_profit_by_nonce(&2, &profit(2, 1_500))
+                .unwrap();
+            db.try_update_max_seen_message_nonce(2).unwrap();
+
+            let (path, router) =
+                ProfitabilityAdminApi::new("secret".to_owned(), vec![db]).get_route();
+            let app = Router::new().nest(path, router);
+            let server =
+                axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+            let addr = server.local_addr();
+            tokio::spawn(server);
+
+            let client = reqwest::Client::new();
+            let url = format!("http://{}{}", addr, PROFITABILITY_ADMIN_API_BASE);
+
+            let response = client.get(&url).send().await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+            let records = client
+                .get(&url)
+                .bearer_auth("secret")
+                .send()
+                .await
+                .unwrap()
+                .json::<Vec<ProfitabilityRecord>>()
+                .await
+                .unwrap();
+            let profits: Vec<_> = records
+                .iter()
+                .map(|record| (record.nonce, record.cost.clone(), record.profit.clone()))
+                .collect();
+            assert_eq!(
+                profits,
+                vec![
+                    (0, Some("2000".to_owned()), Some("1000".to_owned())),
+                    (2, Some("2000".to_owned()), Some("-500".to_owned())),
+                ]
+            );
+
+            let csv = client
+                .get(format!("{url}?format=csv&from_nonce=1"))
+                .bearer_auth("secret")
+                .send()
+                .await
+                .unwrap()
+                .text()
+                .await
+                .unwrap();
+            let lines: Vec<_> = csv.lines().collect();
+            assert_eq!(lines.len(), 2);
+            assert_eq!(lines[0], ProfitabilityRecord::CSV_HEADER);
+            assert!(lines[1].ends_with(",2000,-500,1700000000"));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_admin_api() {
+        let mut breaker = CircuitBreaker::new(
+            CircuitBreakerConf {
+                failure_rate: 0.5,
+                window: 1,
+                cooldown: Duration::from_secs(60),
+            },
+            IntGauge::new("circuit_breaker_state", "help string").unwrap(),
+            IntCounter::new("circuit_breaker_trips", "help string").unwrap(),
+        );
+        breaker.record(false, Instant::now());
+        let breaker = Arc::new(Mutex::new(breaker));
+        let (path, router) = CircuitBreakerAdminApi::new(
+            "secret".to_owned(),
+            HashMap::from([(42, breaker.clone())]),
+        )
+        .get_route();
+        let app = Router::new().nest(path, router);
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let client = reqwest::Client::new();
+        let url = format!("http://{}{}", addr, CIRCUIT_BREAKER_ADMIN_API_BASE);
+
+        let response = client.get(&url).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let summaries = client
+            .get(&url)
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap()
+            .json::<Vec<CircuitBreakerSummary>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            summaries,
+            vec![CircuitBreakerSummary {
+                destination_domain: 42,
+                state: CircuitState::Open,
+                failure_rate: 1.0,
+            }]
+        );
+
+        let response = client
+            .post(format!("{url}/close?destination_domain=43"))
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = client
+            .post(format!("{url}/close?destination_domain=42"))
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(breaker.lock().await.state(), CircuitState::Closed);
+    }
 }
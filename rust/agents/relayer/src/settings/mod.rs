@@ -4,26 +4,33 @@
 //! and validations it defines are not applied here, we should mirror them.
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
-use std::{collections::HashSet, path::PathBuf};
+use std::{collections::HashSet, path::PathBuf, str::FromStr, time::Duration};
 
-use convert_case::Case;
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
 use eyre::{eyre, Context};
 use hyperlane_base::{
+    db::{DbBackend, IntegrityCheckConf, RocksDbTuning},
     impl_loadable_from_settings,
     settings::{
-        parser::{recase_json_value, RawAgentConf, ValueParser},
-        Settings,
+        parser::{
+            parse_db_integrity_check, parse_gas_limit_policy, parse_json_array,
+            parse_matching_list, parse_supervisor, RawAgentConf, RawAgentSignerConf, ValueParser,
+        },
+        CheckpointSyncerConf, GasLimitPolicyConf, Settings, SignerConf,
     },
+    SupervisorConf,
+};
+use hyperlane_core::{
+    cfg_unwrap_all, config::*, utils::hex_or_base58_to_h256, HyperlaneDomain, HyperlaneMessage,
+    H256, U256,
 };
-use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain, U256};
 use itertools::Itertools;
 use serde::Deserialize;
 use serde_json::Value;
 
 use crate::settings::matching_list::MatchingList;
 
-pub mod matching_list;
+pub use hyperlane_base::settings::matching_list;
 
 /// Settings for `Relayer`
 #[derive(Debug, AsRef, AsMut, Deref, DerefMut)]
@@ -36,6 +43,16 @@ pub struct RelayerSettings {
 
     /// Database path
     pub db: PathBuf,
+    /// The key-value store the database is kept in
+    pub db_backend: DbBackend,
+    /// How RocksDB trades memory for throughput
+    pub db_tuning: RocksDbTuning,
+    /// A snapshot file to restore into the database if it's new
+    pub db_restore_from: Option<PathBuf>,
+    /// Where the snapshots taken through the admin API are written to
+    pub db_snapshot_location: Option<CheckpointSyncerConf>,
+    /// If set, the stored sequences are verified against the chain on startup
+    pub db_integrity_check: Option<IntegrityCheckConf>,
     /// The chain to relay messages from
     pub origin_chains: HashSet<HyperlaneDomain>,
     /// Chains to relay messages to
@@ -54,8 +71,69 @@ pub struct RelayerSettings {
     /// If true, allows local storage based checkpoint syncers.
     /// Not intended for production use.
     pub allow_local_checkpoint_syncers: bool,
+    /// If set, only the checkpoints of these validators are used to build
+    /// multisig metadata, e.g. the operator's own validators along with
+    /// public ones. Messages whose ISM can't reach quorum with them aren't
+    /// relayed.
+    pub allowed_validators: Option<HashSet<H256>>,
     /// App contexts used for metrics.
     pub metric_app_contexts: Vec<(MatchingList, String)>,
+    /// If set, a receipt signed with this signer is stored for every
+    /// confirmed delivery.
+    pub receipt_signer: Option<SignerConf>,
+    /// The order in which operations that are due are submitted.
+    pub prioritization: PrioritizationPolicy,
+    /// If set, the admin APIs are served, authenticating requests with this
+    /// key.
+    pub admin_api_key: Option<String>,
+    /// When to park messages as undeliverable.
+    pub dead_letter: DeadLetterConf,
+    /// Apps with their own gas payment enforcement and submission settings
+    pub app_contexts: Vec<AppContextConf>,
+    /// How long the configuration of ISMs is cached for. It is fetched for
+    /// every message if unset.
+    pub ism_cache_ttl: Option<Duration>,
+    /// The most messages from each origin whose metadata is built at once
+    pub max_metadata_tasks_per_origin: Option<u32>,
+    /// The most operations to each destination that were submitted and
+    /// wait to be confirmed
+    pub max_in_flight_per_destination: Option<u32>,
+    /// How often the settings are reloaded to start relaying to and from the
+    /// chains that were added, and stop for the ones that were removed. The
+    /// chains are only loaded on startup if unset.
+    pub chain_refresh_interval: Option<Duration>,
+    /// Restarts the contract syncs of origins that stall or panic
+    pub sync_supervisor: SupervisorConf,
+    /// Pauses the submissions to each destination that keep failing, if set
+    pub circuit_breaker: Option<CircuitBreakerConf>,
+    /// Coordinates with other relayer instances that relay the same
+    /// messages, if set
+    pub coordination: Option<CoordinationConf>,
+    /// The hooks that check messages before their metadata is built, in the
+    /// order they run
+    pub message_hooks: Vec<MessageHookConf>,
+}
+
+/// Config for the messages of an app, e.g. of a set of senders or
+/// recipients
+#[derive(Debug, Clone)]
+pub struct AppContextConf {
+    /// The name of the app, which labels its messages in metrics
+    pub name: String,
+    /// The messages of the app
+    pub matching_list: MatchingList,
+    /// The gas payment enforcement policy of the app, if not the chain-wide
+    /// one
+    pub gas_payment_enforcement: Option<GasPaymentEnforcementPolicy>,
+    /// The rank of the app when operations are prioritized by app, lower
+    /// ranks being submitted first
+    pub priority: Option<u32>,
+    /// The most operations of the app that are prepared at once for a
+    /// destination
+    pub max_concurrency: Option<u32>,
+    /// How the estimated gas limits of the operations of the app are
+    /// adjusted, if not with the policy of their destination
+    pub gas_limit_policy: Option<GasLimitPolicyConf>,
 }
 
 /// Config for gas payment enforcement
@@ -82,6 +160,121 @@ pub enum GasPaymentEnforcementPolicy {
         gas_fraction_numerator: u64,
         gas_fraction_denominator: u64,
     },
+    /// The payment covers what the IGP of the origin currently quotes for
+    /// the estimated gas, plus a margin in basis points.
+    IgpQuote { margin_bps: u32 },
+}
+
+/// Config for parking messages that will likely never be delivered, rather
+/// than retrying them forever. Parked messages are only attempted again once
+/// they are retried through the API.
+#[derive(Debug, Clone, Default)]
+pub struct DeadLetterConf {
+    /// Messages are parked after this many failed attempts
+    pub max_retries: Option<u32>,
+    /// Messages are parked as soon as an attempt fails with an error that
+    /// contains one of these, e.g. a revert reason of the recipient
+    pub revert_reasons: Vec<String>,
+    /// Messages that match a list are parked once an attempt fails after
+    /// the relayer first saw them longer ago than its TTL, by the first list
+    /// that matches them
+    pub expiries: Vec<(MatchingList, Duration)>,
+}
+
+impl DeadLetterConf {
+    /// Whether a message that failed `num_retries` times, the last time with
+    /// `error`, should be parked
+    pub fn should_park(&self, num_retries: u32, error: &str) -> bool {
+        self.max_retries.map_or(false, |max| num_retries >= max) || self.parks_on(error)
+    }
+
+    /// Whether a message is parked as soon as an attempt fails with `error`
+    pub fn parks_on(&self, error: &str) -> bool {
+        self.revert_reasons
+            .iter()
+            .any(|reason| error.contains(reason.as_str()))
+    }
+
+    /// Whether a message that was first seen `age` ago has outlived the TTL
+    /// of its first matching expiry rule
+    pub fn is_expired(&self, message: &HyperlaneMessage, age: Duration) -> bool {
+        self.expiries
+            .iter()
+            .find(|(matching_list, _)| matching_list.msg_matches(message, false))
+            .map_or(false, |(_, ttl)| age >= *ttl)
+    }
+}
+
+/// Config for pausing the submissions to a destination that keep failing,
+/// e.g. because its RPC is down or its mailbox is paused, rather than burning
+/// retries on them.
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConf {
+    /// The share of failed submissions among the recent ones that pauses the
+    /// submissions, between 0 and 1
+    pub failure_rate: f64,
+    /// How many of the most recent submissions the failure rate is computed
+    /// over. Submissions aren't paused before this many finished.
+    pub window: usize,
+    /// How long submissions are paused before a single one is let through
+    /// to probe the destination
+    pub cooldown: Duration,
+}
+
+/// Config for running several relayer instances active-active. Instances
+/// claim messages in a shared Postgres database before preparing them, so
+/// that each message is only submitted by one of them.
+#[derive(Debug, Clone)]
+pub struct CoordinationConf {
+    /// The URL of the Postgres database the claims are stored in
+    pub database_url: String,
+    /// Identifies this instance among the ones sharing the database
+    pub instance_id: String,
+    /// How long a claim lasts unless it's renewed, after which other
+    /// instances may claim the message, e.g. because this one crashed
+    pub lease: Duration,
+}
+
+/// Config for a built-in hook that checks messages before their metadata is
+/// built
+#[derive(Debug, Clone)]
+pub enum MessageHookConf {
+    /// Park the messages that match, e.g. to enforce compliance filters
+    Deny {
+        matching_list: MatchingList,
+        reason: String,
+    },
+    /// Delay the messages that match once `max_messages` of them were
+    /// prepared within the sliding `window`
+    RateLimit {
+        matching_list: MatchingList,
+        max_messages: u32,
+        window: Duration,
+    },
+    /// Note why the messages that match are of interest in the logs
+    Annotate {
+        matching_list: MatchingList,
+        note: String,
+    },
+}
+
+/// Config for the order in which the relayer submits operations that are due.
+/// Operations that are backing off are always ordered by when they are next
+/// attempted.
+#[derive(Debug, Clone, Default)]
+pub enum PrioritizationPolicy {
+    /// Messages from the same origin are submitted in order of nonce
+    #[default]
+    Nonce,
+    /// Messages that paid for the most gas relative to their estimated cost
+    /// are submitted first
+    GasPaymentRatio,
+    /// Messages that the relayer has known about the longest are submitted
+    /// first
+    Age,
+    /// Messages are submitted in the order of the first of the matching lists
+    /// they match, followed by messages that match none of them
+    AppPriority(Vec<MatchingList>),
 }
 
 #[derive(Debug, Deserialize)]
@@ -120,6 +313,40 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .parse_from_str("Expected database path")
             .unwrap_or_else(|| std::env::current_dir().unwrap().join("hyperlane_db"));
 
+        let db_backend = p
+            .chain(&mut err)
+            .get_opt_key("dbBackend")
+            .parse_from_str("Expected a database backend")
+            .unwrap_or_default();
+
+        let db_tuning = p
+            .chain(&mut err)
+            .get_opt_key("dbTuning")
+            .parse_from_str("Expected a RocksDB tuning")
+            .unwrap_or_default();
+
+        let db_restore_from = p
+            .chain(&mut err)
+            .get_opt_key("dbRestoreFrom")
+            .parse_from_str("Expected a database snapshot path")
+            .end();
+
+        let db_snapshot_location = p
+            .chain(&mut err)
+            .get_opt_key("dbSnapshotLocation")
+            .parse_string()
+            .end()
+            .and_then(|location| {
+                CheckpointSyncerConf::from_str(location)
+                    .take_err(&mut err, || &p.cwp + "db_snapshot_location")
+            });
+
+        let db_integrity_check = p
+            .chain(&mut err)
+            .get_opt_key("dbIntegrityCheck")
+            .and_then(parse_db_integrity_check)
+            .end();
+
         let (raw_gas_payment_enforcement_path, raw_gas_payment_enforcement) = p
             .get_opt_key("gasPaymentEnforcement")
             .take_config_err_flat(&mut err)
@@ -130,56 +357,87 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             raw_gas_payment_enforcement_path,
             &raw_gas_payment_enforcement,
         );
-        let mut gas_payment_enforcement = gas_payment_enforcement_parser.into_array_iter().map(|itr| {
-            itr.filter_map(|policy| {
-                let policy_type = policy.chain(&mut err).get_opt_key("type").parse_string().end();
-                let minimum_is_defined = matches!(policy.get_opt_key("minimum"), Ok(Some(_)));
-
-                let matching_list = policy.chain(&mut err).get_opt_key("matchingList").and_then(parse_matching_list).unwrap_or_default();
-
-                let parse_minimum = |p| GasPaymentEnforcementPolicy::Minimum { payment: p };
-                match policy_type {
-                    Some("minimum") => policy.chain(&mut err).get_opt_key("payment").parse_u256().end().map(parse_minimum),
-                    None if minimum_is_defined => policy.chain(&mut err).get_opt_key("payment").parse_u256().end().map(parse_minimum),
-                    Some("none") | None => Some(GasPaymentEnforcementPolicy::None),
-                    Some("onChainFeeQuoting") => {
-                        let gas_fraction = policy.chain(&mut err)
-                            .get_opt_key("gasFraction")
-                            .parse_string()
-                            .map(|v| v.replace(' ', ""))
-                            .unwrap_or_else(|| "1/2".to_owned());
-                        let (numerator, denominator) = gas_fraction
-                            .split_once('/')
-                            .ok_or_else(|| eyre!("Invalid `gas_fraction` for OnChainFeeQuoting gas payment enforcement policy; expected `numerator / denominator`"))
-                            .take_err(&mut err, || &policy.cwp + "gas_fraction")
-                            .unwrap_or(("1", "1"));
-
-                        Some(GasPaymentEnforcementPolicy::OnChainFeeQuoting {
-                            gas_fraction_numerator: numerator
-                                .parse()
-                                .context("Error parsing gas fraction numerator")
-                                .take_err(&mut err, || &policy.cwp + "gas_fraction")
-                                .unwrap_or(1),
-                            gas_fraction_denominator: denominator
-                                .parse()
-                                .context("Error parsing gas fraction denominator")
-                                .take_err(&mut err, || &policy.cwp + "gas_fraction")
-                                .unwrap_or(1),
-                        })
-                    }
-                    Some(pt) => Err(eyre!("Unknown gas payment enforcement policy type `{pt}`"))
-                        .take_err(&mut err, || cwp + "type"),
-                }.map(|policy| GasPaymentEnforcementConf {
-                    policy,
-                    matching_list,
+        let mut gas_payment_enforcement = gas_payment_enforcement_parser
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|policy| {
+                    let matching_list = policy
+                        .chain(&mut err)
+                        .get_opt_key("matchingList")
+                        .and_then(parse_matching_list)
+                        .unwrap_or_default();
+                    parse_gas_payment_enforcement_policy(&policy, &mut err).map(|policy| {
+                        GasPaymentEnforcementConf {
+                            policy,
+                            matching_list,
+                        }
+                    })
                 })
-            }).collect_vec()
-        }).unwrap_or_default();
+                .collect_vec()
+            })
+            .unwrap_or_default();
 
         if gas_payment_enforcement.is_empty() {
             gas_payment_enforcement.push(GasPaymentEnforcementConf::default());
         }
 
+        let (raw_app_contexts_path, raw_app_contexts) = p
+            .get_opt_key("appContexts")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "app_contexts", Value::Array(vec![])));
+        let app_contexts = ValueParser::new(raw_app_contexts_path, &raw_app_contexts)
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|app| {
+                    let name = app.chain(&mut err).get_key("name").parse_string().end();
+                    let matching_list = app
+                        .chain(&mut err)
+                        .get_key("matchingList")
+                        .and_then(parse_matching_list)
+                        .unwrap_or_default();
+                    let gas_payment_enforcement = app
+                        .get_opt_key("gasPaymentEnforcement")
+                        .take_err(&mut err, || &app.cwp + "gas_payment_enforcement")
+                        .flatten()
+                        .and_then(|policy| parse_gas_payment_enforcement_policy(&policy, &mut err));
+                    let priority = app
+                        .chain(&mut err)
+                        .get_opt_key("priority")
+                        .parse_u32()
+                        .end();
+                    let max_concurrency = app
+                        .chain(&mut err)
+                        .get_opt_key("maxConcurrency")
+                        .parse_u32()
+                        .end();
+                    let gas_limit_policy = parse_gas_limit_policy(&app, &mut err);
+                    name.map(|name| AppContextConf {
+                        name: name.to_owned(),
+                        matching_list,
+                        gas_payment_enforcement,
+                        priority,
+                        max_concurrency,
+                        gas_limit_policy,
+                    })
+                })
+                .collect_vec()
+            })
+            .unwrap_or_default();
+
+        // The policies of app contexts take precedence over the chain-wide ones
+        gas_payment_enforcement.splice(
+            0..0,
+            app_contexts.iter().filter_map(|app| {
+                app.gas_payment_enforcement
+                    .clone()
+                    .map(|policy| GasPaymentEnforcementConf {
+                        policy,
+                        matching_list: app.matching_list.clone(),
+                    })
+            }),
+        );
+
         let whitelist = p
             .chain(&mut err)
             .get_opt_key("whitelist")
@@ -210,6 +468,233 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .parse_bool()
             .unwrap_or(false);
 
+        let allowed_validators = p
+            .chain(&mut err)
+            .get_opt_key("allowedValidators")
+            .parse_string()
+            .end()
+            .map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|validator| !validator.is_empty())
+                    .filter_map(|validator| {
+                        hex_or_base58_to_h256(validator)
+                            .context("Expected a valid validator address in `allowedValidators`")
+                            .into_config_result(|| cwp + "allowed_validators")
+                            .take_config_err(&mut err)
+                    })
+                    .collect()
+            });
+
+        let receipt_signer = p
+            .chain(&mut err)
+            .get_opt_key("receiptSigner")
+            .parse_from_raw_config::<SignerConf, RawAgentSignerConf, NoFilter>(
+                (),
+                "Expected valid receipt signer configuration",
+            )
+            .end();
+
+        let prioritization_type = p
+            .chain(&mut err)
+            .get_opt_key("queuePrioritization")
+            .parse_string()
+            .end();
+        let prioritization = match prioritization_type {
+            Some("nonce") | None => Some(PrioritizationPolicy::Nonce),
+            Some("gasPaymentRatio") => Some(PrioritizationPolicy::GasPaymentRatio),
+            Some("age") => Some(PrioritizationPolicy::Age),
+            Some("appPriority") => {
+                let (raw_priority_lists_path, raw_priority_lists) = p
+                    .get_opt_key("priorityMatchingLists")
+                    .take_config_err_flat(&mut err)
+                    .and_then(parse_json_array)
+                    .unwrap_or_else(|| (&p.cwp + "priority_matching_lists", Value::Array(vec![])));
+                let mut priority_lists =
+                    ValueParser::new(raw_priority_lists_path, &raw_priority_lists)
+                        .into_array_iter()
+                        .map(|itr| {
+                            itr.filter_map(|list| {
+                                parse_matching_list(list).take_config_err(&mut err)
+                            })
+                            .collect_vec()
+                        })
+                        .unwrap_or_default();
+                // Without explicit lists, app contexts are ranked by their priority
+                if priority_lists.is_empty() {
+                    priority_lists = app_contexts
+                        .iter()
+                        .filter_map(|app| app.priority.map(|priority| (priority, app)))
+                        .sorted_by_key(|(priority, _)| *priority)
+                        .map(|(_, app)| app.matching_list.clone())
+                        .collect();
+                }
+                Some(PrioritizationPolicy::AppPriority(priority_lists))
+            }
+            Some(pt) => Err(eyre!("Unknown queue prioritization policy `{pt}`"))
+                .take_err(&mut err, || cwp + "queue_prioritization"),
+        }
+        .unwrap_or_default();
+
+        let admin_api_key = p
+            .chain(&mut err)
+            .get_opt_key("adminApiKey")
+            .parse_string()
+            .end()
+            .map(|v| v.to_owned());
+
+        let (raw_expiries_path, raw_expiries) = p
+            .get_opt_key("deadLetterExpiries")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "dead_letter_expiries", Value::Array(vec![])));
+        let expiries = ValueParser::new(raw_expiries_path, &raw_expiries)
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|expiry| {
+                    let matching_list = expiry
+                        .chain(&mut err)
+                        .get_key("matchingList")
+                        .and_then(parse_matching_list)
+                        .unwrap_or_default();
+                    let ttl = expiry
+                        .chain(&mut err)
+                        .get_key("ttlSecs")
+                        .parse_u64()
+                        .end()
+                        .map(Duration::from_secs);
+                    ttl.map(|ttl| (matching_list, ttl))
+                })
+                .collect_vec()
+            })
+            .unwrap_or_default();
+
+        let dead_letter = DeadLetterConf {
+            max_retries: p
+                .chain(&mut err)
+                .get_opt_key("deadLetterAfterRetries")
+                .parse_u32()
+                .end(),
+            revert_reasons: p
+                .chain(&mut err)
+                .get_opt_key("deadLetterRevertReasons")
+                .parse_string()
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|reason| !reason.is_empty())
+                        .map(str::to_owned)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            expiries,
+        };
+
+        let ism_cache_ttl = p
+            .chain(&mut err)
+            .get_opt_key("ismCacheTtlSecs")
+            .parse_u64()
+            .end()
+            .map(Duration::from_secs);
+
+        let max_metadata_tasks_per_origin = p
+            .chain(&mut err)
+            .get_opt_key("maxMetadataTasksPerOrigin")
+            .parse_u32()
+            .end();
+
+        let max_in_flight_per_destination = p
+            .chain(&mut err)
+            .get_opt_key("maxInFlightPerDestination")
+            .parse_u32()
+            .end();
+
+        let chain_refresh_interval = p
+            .chain(&mut err)
+            .get_opt_key("chainRefreshIntervalSecs")
+            .parse_u64()
+            .end()
+            .map(Duration::from_secs);
+
+        let sync_supervisor = p
+            .chain(&mut err)
+            .get_opt_key("syncSupervisor")
+            .and_then(parse_supervisor)
+            .unwrap_or_default();
+
+        let circuit_breaker_window = p
+            .chain(&mut err)
+            .get_opt_key("circuitBreakerWindow")
+            .parse_u32()
+            .unwrap_or(20) as usize;
+        let circuit_breaker_cooldown = p
+            .chain(&mut err)
+            .get_opt_key("circuitBreakerCooldownSecs")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(60));
+        let circuit_breaker = p
+            .chain(&mut err)
+            .get_opt_key("circuitBreakerFailureRate")
+            .parse_f64()
+            .end()
+            .and_then(|failure_rate| {
+                if failure_rate > 0.0 && failure_rate <= 1.0 {
+                    Some(failure_rate)
+                } else {
+                    Err(eyre!("Expected a failure rate above 0 and at most 1"))
+                        .take_err(&mut err, || cwp + "circuit_breaker_failure_rate")
+                }
+            })
+            .map(|failure_rate| CircuitBreakerConf {
+                failure_rate,
+                window: circuit_breaker_window.max(1),
+                cooldown: circuit_breaker_cooldown,
+            });
+
+        let coordination_lease = p
+            .chain(&mut err)
+            .get_opt_key("coordinationLeaseSecs")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(600));
+        let coordination_instance_id = p
+            .chain(&mut err)
+            .get_opt_key("coordinationInstanceId")
+            .parse_string()
+            .end();
+        let coordination = p
+            .chain(&mut err)
+            .get_opt_key("coordinationDatabaseUrl")
+            .parse_string()
+            .end()
+            .and_then(|database_url| {
+                let Some(instance_id) = coordination_instance_id else {
+                    return Err(eyre!(
+                        "Expected an instance id to coordinate with other relayers"
+                    ))
+                    .take_err(&mut err, || cwp + "coordination_instance_id");
+                };
+                Some(CoordinationConf {
+                    database_url: database_url.to_owned(),
+                    instance_id: instance_id.to_owned(),
+                    lease: coordination_lease,
+                })
+            });
+
+        let (raw_message_hooks_path, raw_message_hooks) = p
+            .get_opt_key("messageHooks")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "message_hooks", Value::Array(vec![])));
+        let message_hooks = ValueParser::new(raw_message_hooks_path, &raw_message_hooks)
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|hook| parse_message_hook(&hook, &mut err))
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+
         cfg_unwrap_all!(cwp, err: [base]);
 
         let skip_transaction_gas_limit_for = skip_transaction_gas_limit_for_names
@@ -242,7 +727,7 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
 
         let metric_app_contexts_parser =
             ValueParser::new(raw_metric_app_contexts_path, &raw_metric_app_contexts);
-        let metric_app_contexts = metric_app_contexts_parser
+        let mut metric_app_contexts = metric_app_contexts_parser
             .into_array_iter()
             .map(|itr| {
                 itr.filter_map(|policy| {
@@ -259,10 +744,22 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
                 .collect_vec()
             })
             .unwrap_or_default();
+        // Messages are labelled with the app context they belong to
+        metric_app_contexts.splice(
+            0..0,
+            app_contexts
+                .iter()
+                .map(|app| (app.matching_list.clone(), app.name.clone())),
+        );
 
         err.into_result(RelayerSettings {
             base,
             db,
+            db_backend,
+            db_tuning,
+            db_restore_from,
+            db_snapshot_location,
+            db_integrity_check,
             origin_chains: relay_chains.clone(),
             destination_chains: relay_chains,
             gas_payment_enforcement,
@@ -271,43 +768,130 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             transaction_gas_limit,
             skip_transaction_gas_limit_for,
             allow_local_checkpoint_syncers,
+            allowed_validators,
             metric_app_contexts,
+            receipt_signer,
+            prioritization,
+            admin_api_key,
+            dead_letter,
+            app_contexts,
+            ism_cache_ttl,
+            max_metadata_tasks_per_origin,
+            max_in_flight_per_destination,
+            chain_refresh_interval,
+            sync_supervisor,
+            circuit_breaker,
+            coordination,
+            message_hooks,
         })
     }
 }
 
-fn parse_json_array(p: ValueParser) -> Option<(ConfigPath, Value)> {
-    let mut err = ConfigParsingError::default();
-
-    match p {
-        ValueParser {
-            val: Value::String(array_str),
-            cwp,
-        } => serde_json::from_str::<Value>(array_str)
-            .context("Expected JSON string")
-            .take_err(&mut err, || cwp.clone())
-            .map(|v| (cwp, recase_json_value(v, Case::Flat))),
-        ValueParser {
-            val: value @ Value::Array(_),
-            cwp,
-        } => Some((cwp, value.clone())),
-        _ => Err(eyre!("Expected JSON array or stringified JSON"))
-            .take_err(&mut err, || p.cwp.clone()),
+/// Expects a message hook, i.e. an item of `messageHooks`
+fn parse_message_hook(hook: &ValueParser, err: &mut ConfigParsingError) -> Option<MessageHookConf> {
+    let matching_list = hook
+        .chain(err)
+        .get_key("matchingList")
+        .and_then(parse_matching_list)
+        .unwrap_or_default();
+    match hook.chain(err).get_key("type").parse_string().end()? {
+        "deny" => Some(MessageHookConf::Deny {
+            matching_list,
+            reason: hook
+                .chain(err)
+                .get_opt_key("reason")
+                .parse_string()
+                .unwrap_or("Denied by message hook")
+                .to_owned(),
+        }),
+        "rateLimit" => {
+            let max_messages = hook.chain(err).get_key("maxMessages").parse_u32().end()?;
+            let window = hook
+                .chain(err)
+                .get_key("windowSecs")
+                .parse_u64()
+                .end()
+                .map(Duration::from_secs)?;
+            Some(MessageHookConf::RateLimit {
+                matching_list,
+                max_messages,
+                window,
+            })
+        }
+        "annotate" => Some(MessageHookConf::Annotate {
+            matching_list,
+            note: hook
+                .chain(err)
+                .get_key("note")
+                .parse_string()
+                .end()?
+                .to_owned(),
+        }),
+        _ => Err(eyre!(
+            "Unknown message hook type; expected `deny`, `rateLimit` or `annotate`"
+        ))
+        .take_err(err, || &hook.cwp + "type"),
     }
 }
 
-fn parse_matching_list(p: ValueParser) -> ConfigResult<MatchingList> {
-    let mut err = ConfigParsingError::default();
-
-    let raw_list = parse_json_array(p.clone()).map(|(_, v)| v);
-    let Some(raw_list) = raw_list else {
-        return err.into_result(MatchingList::default());
-    };
-    let p = ValueParser::new(p.cwp.clone(), &raw_list);
-    let ml = p
-        .parse_value::<MatchingList>("Expected matching list")
-        .take_config_err(&mut err)
-        .unwrap_or_default();
-
-    err.into_result(ml)
+/// Expects a gas payment enforcement policy, e.g. an item of
+/// `gasPaymentEnforcement`
+fn parse_gas_payment_enforcement_policy(
+    policy: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<GasPaymentEnforcementPolicy> {
+    let policy_type = policy.chain(err).get_opt_key("type").parse_string().end();
+    let minimum_is_defined = matches!(policy.get_opt_key("minimum"), Ok(Some(_)));
+
+    let parse_minimum = |p| GasPaymentEnforcementPolicy::Minimum { payment: p };
+    match policy_type {
+        Some("minimum") => policy
+            .chain(err)
+            .get_opt_key("payment")
+            .parse_u256()
+            .end()
+            .map(parse_minimum),
+        None if minimum_is_defined => policy
+            .chain(err)
+            .get_opt_key("payment")
+            .parse_u256()
+            .end()
+            .map(parse_minimum),
+        Some("none") | None => Some(GasPaymentEnforcementPolicy::None),
+        Some("onChainFeeQuoting") => {
+            let gas_fraction = policy
+                .chain(err)
+                .get_opt_key("gasFraction")
+                .parse_string()
+                .map(|v| v.replace(' ', ""))
+                .unwrap_or_else(|| "1/2".to_owned());
+            let (numerator, denominator) = gas_fraction
+                .split_once('/')
+                .ok_or_else(|| eyre!("Invalid `gas_fraction` for OnChainFeeQuoting gas payment enforcement policy; expected `numerator / denominator`"))
+                .take_err(err, || &policy.cwp + "gas_fraction")
+                .unwrap_or(("1", "1"));
+
+            Some(GasPaymentEnforcementPolicy::OnChainFeeQuoting {
+                gas_fraction_numerator: numerator
+                    .parse()
+                    .context("Error parsing gas fraction numerator")
+                    .take_err(err, || &policy.cwp + "gas_fraction")
+                    .unwrap_or(1),
+                gas_fraction_denominator: denominator
+                    .parse()
+                    .context("Error parsing gas fraction denominator")
+                    .take_err(err, || &policy.cwp + "gas_fraction")
+                    .unwrap_or(1),
+            })
+        }
+        Some("igpQuote") => Some(GasPaymentEnforcementPolicy::IgpQuote {
+            margin_bps: policy
+                .chain(err)
+                .get_opt_key("marginBps")
+                .parse_u32()
+                .unwrap_or(0),
+        }),
+        Some(pt) => Err(eyre!("Unknown gas payment enforcement policy type `{pt}`"))
+            .take_err(err, || &policy.cwp + "type"),
+    }
 }
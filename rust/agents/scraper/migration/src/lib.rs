@@ -13,6 +13,14 @@ mod m20230309_000003_create_table_transaction;
 mod m20230309_000004_create_table_delivered_message;
 mod m20230309_000004_create_table_gas_payment;
 mod m20230309_000005_create_table_message;
+mod m20261016_000006_create_table_delivery_ism;
+mod m20261016_000006_create_table_delivery_signature;
+mod m20261016_000007_create_view_message_delivery;
+mod m20261016_000008_create_view_message_gas_payment;
+mod m20261016_000009_create_table_backfill_shard;
+mod m20261016_000010_add_column_time_orphaned;
+mod m20261016_000011_exclude_orphans_from_views;
+mod m20261016_000012_create_table_export_cursor;
 
 pub struct Migrator;
 
@@ -30,6 +38,14 @@ impl MigratorTrait for Migrator {
             Box::new(m20230309_000004_create_table_gas_payment::Migration),
             Box::new(m20230309_000004_create_table_delivered_message::Migration),
             Box::new(m20230309_000005_create_table_message::Migration),
+            Box::new(m20261016_000006_create_table_delivery_ism::Migration),
+            Box::new(m20261016_000006_create_table_delivery_signature::Migration),
+            Box::new(m20261016_000007_create_view_message_delivery::Migration),
+            Box::new(m20261016_000008_create_view_message_gas_payment::Migration),
+            Box::new(m20261016_000009_create_table_backfill_shard::Migration),
+            Box::new(m20261016_000010_add_column_time_orphaned::Migration),
+            Box::new(m20261016_000011_exclude_orphans_from_views::Migration),
+            Box::new(m20261016_000012_create_table_export_cursor::Migration),
         ]
     }
 }
@@ -97,4 +97,6 @@ pub enum Block {
     Height,
     /// Time the block was created at
     Timestamp,
+    /// Time the block was orphaned by a reorg, `None` while it is canonical
+    TimeOrphaned,
 }
@@ -105,4 +105,6 @@ pub enum DeliveredMessage {
     DestinationMailbox,
     /// Transaction the delivery was included in
     DestinationTxId,
+    /// Time the delivery was orphaned by a reorg, `None` while it is canonical
+    TimeOrphaned,
 }
@@ -136,6 +136,8 @@ pub enum GasPayment {
     /// Used to disambiguate duplicate payments from multiple payments made in
     /// same transaction.
     LogIndex,
+    /// Time the payment was orphaned by a reorg, `None` while it is canonical
+    TimeOrphaned,
 }
 
 #[derive(Iden)]
@@ -283,4 +283,6 @@ pub enum Message {
     OriginMailbox,
     /// Transaction this message was dispatched in on the origin chain.
     OriginTxId,
+    /// Time the dispatch was orphaned by a reorg, `None` while it is canonical
+    TimeOrphaned,
 }
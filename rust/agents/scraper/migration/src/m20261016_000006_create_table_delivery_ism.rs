@@ -0,0 +1,95 @@
+use sea_orm_migration::prelude::*;
+
+use crate::l20230309_types::*;
+use crate::m20230309_000001_create_table_domain::Domain;
+use crate::m20230309_000004_create_table_delivered_message::DeliveredMessage;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DeliveryIsm::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DeliveryIsm::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DeliveryIsm::TimeCreated)
+                            .timestamp()
+                            .not_null()
+                            .default("NOW()"),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(DeliveryIsm::MsgId, Hash)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(DeliveryIsm::Domain).unsigned().not_null())
+                    .col(ColumnDef::new_with_type(DeliveryIsm::Ism, Address).not_null())
+                    .col(ColumnDef::new(DeliveryIsm::ModuleType).small_integer())
+                    .col(ColumnDef::new(DeliveryIsm::Metadata).binary().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(DeliveryIsm::MsgId)
+                            .to(DeliveredMessage::Table, DeliveredMessage::MsgId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(DeliveryIsm::Domain)
+                            .to(Domain::Table, Domain::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .table(DeliveryIsm::Table)
+                    .name("delivery_ism_ism_idx")
+                    .col(DeliveryIsm::Domain)
+                    .col(DeliveryIsm::Ism)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DeliveryIsm::Table).to_owned())
+            .await
+    }
+}
+
+/// The ISM a message was verified by when it was delivered, and the metadata
+/// it was verified with. Only recorded for deliveries whose transaction could
+/// be decoded.
+///
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum DeliveryIsm {
+    Table,
+    /// Unique database ID
+    Id,
+    /// Time of record creation
+    TimeCreated,
+    /// Unique id of the delivered message
+    MsgId,
+    /// Domain the message was received on
+    Domain,
+    /// Address of the ISM of the recipient
+    Ism,
+    /// Module type of the ISM, `None` if it could not be queried
+    ModuleType,
+    /// Metadata passed to the ISM
+    Metadata,
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+use crate::l20230309_types::*;
+use crate::m20230309_000004_create_table_delivered_message::DeliveredMessage;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DeliverySignature::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DeliverySignature::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DeliverySignature::TimeCreated)
+                            .timestamp()
+                            .not_null()
+                            .default("NOW()"),
+                    )
+                    .col(ColumnDef::new_with_type(DeliverySignature::MsgId, Hash).not_null())
+                    .col(ColumnDef::new_with_type(DeliverySignature::Validator, Address).not_null())
+                    .col(
+                        ColumnDef::new(DeliverySignature::Signature)
+                            .binary()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(DeliverySignature::MsgId)
+                            .to(DeliveredMessage::Table, DeliveredMessage::MsgId),
+                    )
+                    .index(
+                        Index::create()
+                            .col(DeliverySignature::MsgId)
+                            .col(DeliverySignature::Validator)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .table(DeliverySignature::Table)
+                    .name("delivery_signature_validator_idx")
+                    .col(DeliverySignature::Validator)
+                    .index_type(IndexType::Hash)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DeliverySignature::Table).to_owned())
+            .await
+    }
+}
+
+/// A validator signature a message was verified with when it was delivered.
+///
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum DeliverySignature {
+    Table,
+    /// Unique database ID
+    Id,
+    /// Time of record creation
+    TimeCreated,
+    /// Unique id of the delivered message
+    MsgId,
+    /// Address of the validator the signature was recovered to
+    Validator,
+    /// The 65 byte signature
+    Signature,
+}
@@ -0,0 +1,121 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000001_create_table_domain::Domain;
+use crate::m20230309_000002_create_table_block::Block;
+use crate::m20230309_000003_create_table_transaction::Transaction;
+use crate::m20230309_000004_create_table_delivered_message::DeliveredMessage;
+use crate::m20230309_000005_create_table_message::Message;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Unlike `message_view`, which starts from dispatches, this starts from
+        // deliveries, so it also answers for messages whose dispatch wasn't
+        // scraped, e.g. because their origin isn't scraped.
+        let sql = format!(
+            r#"
+            CREATE VIEW "{view}" AS
+            SELECT
+                "dmsg"."{dmsg_mid}" AS "msg_id",
+
+                "msg"."{msg_id}" IS NOT NULL AS "is_dispatch_scraped",
+                "msg"."{msg_nonce}" AS "nonce",
+                "msg"."{msg_origin}" AS "origin_domain_id",
+                "origin_domain"."{domain_name}" AS "origin_domain",
+                "dmsg"."{dmsg_domain}" AS "destination_domain_id",
+                "dest_domain"."{domain_name}" AS "destination_domain",
+                "msg"."{msg_sender}" AS "sender",
+                "msg"."{msg_recipient}" AS "recipient",
+                "msg"."{msg_origin_mb}" AS "origin_mailbox",
+                "dmsg"."{dmsg_dest_mb}" AS "destination_mailbox",
+
+                "origin_tx"."{tx_hash}" AS "origin_tx_hash",
+                "origin_block"."{block_height}" AS "origin_block_height",
+                "origin_block"."{block_timestamp}" AS "send_occurred_at",
+
+                "dest_tx"."{tx_hash}" AS "destination_tx_hash",
+                "dest_tx"."{tx_sender}" AS "destination_tx_sender",
+                "dest_tx"."{tx_gas_used}" AS "destination_tx_gas_used",
+                "dest_block"."{block_height}" AS "destination_block_height",
+                "dest_block"."{block_timestamp}" AS "delivery_occurred_at",
+                "dest_block"."{block_timestamp}" - "origin_block"."{block_timestamp}" AS "delivery_latency"
+            FROM "{dmsg_table}" AS "dmsg"
+                LEFT JOIN "{msg_table}"
+                    AS "msg"
+                    ON "msg"."{msg_mid}" = "dmsg"."{dmsg_mid}"
+                LEFT JOIN "{domain_table}"
+                    AS "origin_domain"
+                    ON "origin_domain"."{domain_id}" = "msg"."{msg_origin}"
+                LEFT JOIN "{domain_table}"
+                    AS "dest_domain"
+                    ON "dest_domain"."{domain_id}" = "dmsg"."{dmsg_domain}"
+                LEFT JOIN "{tx_table}"
+                    AS "origin_tx"
+                    ON "origin_tx"."{tx_id}" = "msg"."{msg_oti}"
+                LEFT JOIN "{block_table}"
+                    AS "origin_block"
+                    ON "origin_block"."{block_id}" = "origin_tx"."{tx_block_id}"
+                LEFT JOIN "{tx_table}"
+                    AS "dest_tx"
+                    ON "dest_tx"."{tx_id}" = "dmsg"."{dmsg_dti}"
+                LEFT JOIN "{block_table}"
+                    AS "dest_block"
+                    ON "dest_block"."{block_id}" = "dest_tx"."{tx_block_id}"
+            "#,
+            view = MessageDelivery::Table.to_string(),
+            msg_table = Message::Table.to_string(),
+            msg_id = Message::Id.to_string(),
+            msg_mid = Message::MsgId.to_string(),
+            msg_origin = Message::Origin.to_string(),
+            msg_nonce = Message::Nonce.to_string(),
+            msg_sender = Message::Sender.to_string(),
+            msg_recipient = Message::Recipient.to_string(),
+            msg_origin_mb = Message::OriginMailbox.to_string(),
+            msg_oti = Message::OriginTxId.to_string(),
+            domain_table = Domain::Table.to_string(),
+            domain_id = Domain::Id.to_string(),
+            domain_name = Domain::Name.to_string(),
+            tx_table = Transaction::Table.to_string(),
+            tx_id = Transaction::Id.to_string(),
+            tx_hash = Transaction::Hash.to_string(),
+            tx_block_id = Transaction::BlockId.to_string(),
+            tx_sender = Transaction::Sender.to_string(),
+            tx_gas_used = Transaction::GasUsed.to_string(),
+            block_table = Block::Table.to_string(),
+            block_id = Block::Id.to_string(),
+            block_height = Block::Height.to_string(),
+            block_timestamp = Block::Timestamp.to_string(),
+            dmsg_table = DeliveredMessage::Table.to_string(),
+            dmsg_mid = DeliveredMessage::MsgId.to_string(),
+            dmsg_domain = DeliveredMessage::Domain.to_string(),
+            dmsg_dest_mb = DeliveredMessage::DestinationMailbox.to_string(),
+            dmsg_dti = DeliveredMessage::DestinationTxId.to_string(),
+        );
+
+        manager.get_connection().execute_unprepared(&sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"DROP VIEW IF EXISTS "{}""#,
+                MessageDelivery::Table.to_string()
+            ))
+            .await?;
+        Ok(())
+    }
+}
+
+/// Each delivered message along with its dispatch, if it was scraped.
+///
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum MessageDelivery {
+    Table,
+}
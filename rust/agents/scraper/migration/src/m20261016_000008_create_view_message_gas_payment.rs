@@ -0,0 +1,102 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000003_create_table_transaction::Transaction;
+use crate::m20230309_000004_create_table_delivered_message::DeliveredMessage;
+use crate::m20230309_000004_create_table_gas_payment::TotalGasPayment;
+use crate::m20230309_000005_create_table_message::Message;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The gas used by the delivery transaction is an upper bound of the
+        // gas used to deliver the message, since transactions may deliver
+        // several messages or do more than deliver.
+        let sql = format!(
+            r#"
+            CREATE VIEW "{view}" AS
+            SELECT
+                "msg"."{msg_mid}" AS "msg_id",
+                "msg"."{msg_origin}" AS "origin_domain_id",
+                "msg"."{msg_dest}" AS "destination_domain_id",
+                "msg"."{msg_nonce}" AS "nonce",
+                "msg"."{msg_sender}" AS "sender",
+                "msg"."{msg_recipient}" AS "recipient",
+
+                COALESCE("tgp"."{tgp_num_payments}", '0') AS "num_payments",
+                COALESCE("tgp"."{tgp_payment}", '0') AS "total_payment",
+                COALESCE("tgp"."{tgp_gas_amount}", '0') AS "total_gas_amount",
+
+                "dmsg"."{dmsg_id}" IS NOT NULL AS "is_delivered",
+                "dest_tx"."{tx_gas_used}" AS "destination_tx_gas_used",
+                "dest_tx"."{tx_egp}" AS "destination_tx_effective_gas_price",
+                COALESCE("tgp"."{tgp_gas_amount}", '0') - "dest_tx"."{tx_gas_used}" AS "gas_amount_surplus",
+                CASE
+                    WHEN "tgp"."{tgp_mid}" IS NULL THEN 'unpaid'
+                    WHEN "dest_tx"."{tx_id}" IS NULL THEN 'paid'
+                    WHEN "tgp"."{tgp_gas_amount}" < "dest_tx"."{tx_gas_used}" THEN 'underpaid'
+                    ELSE 'overpaid'
+                END AS "payment_status"
+            FROM "{msg_table}" AS "msg"
+                LEFT JOIN "{tgp_table}"
+                    AS "tgp"
+                    ON "tgp"."{tgp_mid}" = "msg"."{msg_mid}"
+                LEFT JOIN "{dmsg_table}"
+                    AS "dmsg"
+                    ON "dmsg"."{dmsg_mid}" = "msg"."{msg_mid}"
+                LEFT JOIN "{tx_table}"
+                    AS "dest_tx"
+                    ON "dest_tx"."{tx_id}" = "dmsg"."{dmsg_dti}"
+            "#,
+            view = MessageGasPayment::Table.to_string(),
+            msg_table = Message::Table.to_string(),
+            msg_mid = Message::MsgId.to_string(),
+            msg_origin = Message::Origin.to_string(),
+            msg_dest = Message::Destination.to_string(),
+            msg_nonce = Message::Nonce.to_string(),
+            msg_sender = Message::Sender.to_string(),
+            msg_recipient = Message::Recipient.to_string(),
+            tx_table = Transaction::Table.to_string(),
+            tx_id = Transaction::Id.to_string(),
+            tx_egp = Transaction::EffectiveGasPrice.to_string(),
+            tx_gas_used = Transaction::GasUsed.to_string(),
+            tgp_table = TotalGasPayment::Table.to_string(),
+            tgp_mid = TotalGasPayment::MsgId.to_string(),
+            tgp_num_payments = TotalGasPayment::NumPayments.to_string(),
+            tgp_payment = TotalGasPayment::TotalPayment.to_string(),
+            tgp_gas_amount = TotalGasPayment::TotalGasAmount.to_string(),
+            dmsg_table = DeliveredMessage::Table.to_string(),
+            dmsg_id = DeliveredMessage::Id.to_string(),
+            dmsg_mid = DeliveredMessage::MsgId.to_string(),
+            dmsg_dti = DeliveredMessage::DestinationTxId.to_string(),
+        );
+
+        manager.get_connection().execute_unprepared(&sql).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"DROP VIEW IF EXISTS "{}""#,
+                MessageGasPayment::Table.to_string()
+            ))
+            .await?;
+        Ok(())
+    }
+}
+
+/// The gas payments of each dispatched message, aggregated by message ID,
+/// next to the gas its delivery used, to find underpaid and overpaid
+/// messages. Payment statuses are `unpaid`, `paid` (not delivered yet),
+/// `underpaid` and `overpaid`.
+///
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum MessageGasPayment {
+    Table,
+}
@@ -0,0 +1,88 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000001_create_table_domain::Domain;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BackfillShard::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BackfillShard::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(BackfillShard::TimeCreated)
+                            .timestamp()
+                            .not_null()
+                            .default("NOW()"),
+                    )
+                    .col(ColumnDef::new(BackfillShard::Domain).unsigned().not_null())
+                    .col(
+                        ColumnDef::new(BackfillShard::FromBlock)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BackfillShard::ToBlock)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BackfillShard::NextBlock)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(BackfillShard::Domain)
+                            .to(Domain::Table, Domain::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .unique()
+                            .col(BackfillShard::Domain)
+                            .col(BackfillShard::FromBlock),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BackfillShard::Table).to_owned())
+            .await
+    }
+}
+
+/// A range of historical blocks scraped by a backfill worker, along with how
+/// far it got.
+///
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum BackfillShard {
+    Table,
+    /// Unique database ID
+    Id,
+    /// Time of record creation
+    TimeCreated,
+    /// Hyperlane domain ID the shard is for
+    Domain,
+    /// First block of the shard
+    FromBlock,
+    /// Last block of the shard, inclusive
+    ToBlock,
+    /// The next block to scrape. The shard is complete once it is past
+    /// `to_block`.
+    NextBlock,
+}
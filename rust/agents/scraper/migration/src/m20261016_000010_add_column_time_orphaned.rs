@@ -0,0 +1,122 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000002_create_table_block::Block;
+use crate::m20230309_000004_create_table_delivered_message::DeliveredMessage;
+use crate::m20230309_000004_create_table_gas_payment::GasPayment;
+use crate::m20230309_000005_create_table_message::Message;
+
+/// The name Postgres gave the unique constraint on the domain and height of
+/// blocks, which was created without a name
+const BLOCK_DOMAIN_HEIGHT_CONSTRAINT: &str = "block_domain_height_key";
+const BLOCK_CANONICAL_DOMAIN_HEIGHT_IDX: &str = "block_canonical_domain_height_idx";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Block::Table)
+                    .add_column(ColumnDef::new(Block::TimeOrphaned).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .add_column(ColumnDef::new(Message::TimeOrphaned).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DeliveredMessage::Table)
+                    .add_column(ColumnDef::new(DeliveredMessage::TimeOrphaned).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GasPayment::Table)
+                    .add_column(ColumnDef::new(GasPayment::TimeOrphaned).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Orphaned blocks are kept, so only canonical blocks are unique by
+        // height. Otherwise the block replacing an orphaned one can't be
+        // stored.
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"
+                ALTER TABLE "{block_table}" DROP CONSTRAINT IF EXISTS "{BLOCK_DOMAIN_HEIGHT_CONSTRAINT}";
+                CREATE UNIQUE INDEX "{BLOCK_CANONICAL_DOMAIN_HEIGHT_IDX}"
+                    ON "{block_table}" ("{block_domain}", "{block_height}")
+                    WHERE "{block_time_orphaned}" IS NULL;
+                "#,
+                block_table = Block::Table.to_string(),
+                block_domain = Block::Domain.to_string(),
+                block_height = Block::Height.to_string(),
+                block_time_orphaned = Block::TimeOrphaned.to_string(),
+            ))
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Fails while orphaned blocks share a height with canonical ones
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"
+                DROP INDEX IF EXISTS "{BLOCK_CANONICAL_DOMAIN_HEIGHT_IDX}";
+                ALTER TABLE "{block_table}"
+                    ADD CONSTRAINT "{BLOCK_DOMAIN_HEIGHT_CONSTRAINT}" UNIQUE ("{block_domain}", "{block_height}");
+                "#,
+                block_table = Block::Table.to_string(),
+                block_domain = Block::Domain.to_string(),
+                block_height = Block::Height.to_string(),
+            ))
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GasPayment::Table)
+                    .drop_column(GasPayment::TimeOrphaned)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DeliveredMessage::Table)
+                    .drop_column(DeliveredMessage::TimeOrphaned)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .drop_column(Message::TimeOrphaned)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Block::Table)
+                    .drop_column(Block::TimeOrphaned)
+                    .to_owned(),
+            )
+            .await
+    }
+}
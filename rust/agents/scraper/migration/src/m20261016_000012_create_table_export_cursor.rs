@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExportCursor::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ExportCursor::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ExportCursor::TimeUpdated)
+                            .timestamp()
+                            .not_null()
+                            .default("NOW()"),
+                    )
+                    .col(
+                        ColumnDef::new(ExportCursor::Dataset)
+                            .text()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ExportCursor::LastId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ExportCursor::Table).to_owned())
+            .await
+    }
+}
+
+/// How far each dataset was exported to object storage.
+///
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum ExportCursor {
+    Table,
+    /// Unique database ID
+    Id,
+    /// Time of the last update
+    TimeUpdated,
+    /// Name of the exported dataset, e.g. `messages`
+    Dataset,
+    /// Database ID of the last row of the dataset that was exported
+    LastId,
+}
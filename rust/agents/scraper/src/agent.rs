@@ -4,17 +4,28 @@ use async_trait::async_trait;
 use derive_more::AsRef;
 use futures::future::try_join_all;
 use hyperlane_base::{
-    metrics::AgentMetrics, settings::IndexSettings, BaseAgent, ChainMetrics, ContractSyncMetrics,
-    ContractSyncer, CoreMetrics, HyperlaneAgentCore, MetricsUpdater, SyncOptions,
+    metrics::{AgentMetrics, BalanceMonitor},
+    settings::{ConfigReloader, IndexSettings},
+    AgentHealth, BaseAgent, ChainMetrics, ContractSyncMetrics, ContractSyncer, CoreMetrics,
+    HyperlaneAgentCore, MetricsUpdater, SyncOptions,
 };
 use hyperlane_core::{Delivery, HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, H512};
 use tokio::{
     sync::broadcast::{Receiver, Sender},
     task::JoinHandle,
 };
-use tracing::{info_span, instrument::Instrumented, trace, Instrument};
+use tracing::{info_span, instrument::Instrumented, trace, warn, Instrument};
 
-use crate::{chain_scraper::HyperlaneSqlDb, db::ScraperDb, settings::ScraperSettings};
+use crate::{
+    backfill::{Backfill, BackfillMetrics},
+    chain_scraper::{HyperlaneSqlDb, IsmMetadataResolver},
+    db::ScraperDb,
+    export::Exporter,
+    notify::Notifier,
+    reorg::{ReorgDetector, ReorgMetrics},
+    server as scraper_server,
+    settings::ScraperSettings,
+};
 
 /// A message explorer scraper agent
 #[derive(Debug, AsRef)]
@@ -22,7 +33,12 @@ use crate::{chain_scraper::HyperlaneSqlDb, db::ScraperDb, settings::ScraperSetti
 pub struct Scraper {
     #[as_ref]
     core: HyperlaneAgentCore,
+    db: ScraperDb,
     contract_sync_metrics: Arc<ContractSyncMetrics>,
+    backfill_metrics: Option<BackfillMetrics>,
+    reorg_metrics: ReorgMetrics,
+    exporter: Option<Exporter>,
+    notifier: Option<Notifier>,
     scrapers: HashMap<u32, ChainScraper>,
     settings: ScraperSettings,
     core_metrics: Arc<CoreMetrics>,
@@ -53,9 +69,24 @@ impl BaseAgent for Scraper {
         Self: Sized,
     {
         let db = ScraperDb::connect(&settings.db).await?;
+        AgentHealth::global().pass("db");
         let core = settings.build_hyperlane_core(metrics.clone());
 
         let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&metrics));
+        let backfill_metrics = settings
+            .backfill
+            .as_ref()
+            .map(|_| BackfillMetrics::new(&metrics))
+            .transpose()?;
+        let reorg_metrics = ReorgMetrics::new(&metrics)?;
+        let exporter = match &settings.export {
+            Some(conf) => Some(Exporter::new(conf, db.clone(), &metrics).await?),
+            None => None,
+        };
+        let notifier = match &settings.notifications {
+            Some(conf) => Some(Notifier::new(conf, db.clone(), &metrics).await?),
+            None => None,
+        };
         let mut scrapers: HashMap<u32, ChainScraper> = HashMap::new();
 
         for domain in settings.chains_to_scrape.iter() {
@@ -68,6 +99,7 @@ impl BaseAgent for Scraper {
                     .build_provider(domain, &metrics.clone())
                     .await?
                     .into(),
+                Arc::new(IsmMetadataResolver::new(chain_setup.clone(), metrics.clone()).await?),
                 &chain_setup.index.clone(),
             )
             .await?;
@@ -85,7 +117,12 @@ impl BaseAgent for Scraper {
 
         Ok(Self {
             core,
+            db,
             contract_sync_metrics,
+            backfill_metrics,
+            reorg_metrics,
+            exporter,
+            notifier,
             scrapers,
             settings,
             core_metrics: metrics,
@@ -94,8 +131,12 @@ impl BaseAgent for Scraper {
         })
     }
 
+    fn preflight_chains(settings: &Self::Settings) -> Vec<HyperlaneDomain> {
+        settings.chains_to_scrape.clone()
+    }
+
     #[allow(clippy::async_yields_async)]
-    async fn run(self) {
+    async fn run(mut self) {
         let mut tasks = Vec::with_capacity(self.scrapers.len());
 
         // running http server
@@ -104,9 +145,32 @@ impl BaseAgent for Scraper {
             .settings
             .server(self.core_metrics.clone())
             .expect("Failed to create server");
-        let server_task = server.run().instrument(info_span!("Relayer server"));
+        let custom_routes = scraper_server::routes(self.db.clone());
+        let server_task = server
+            .run_with_custom_routes(custom_routes)
+            .instrument(info_span!("Scraper server"));
         tasks.push(server_task);
 
+        let config_reloader = ConfigReloader::new(self.settings.config_watch_interval)
+            .expect("Failed to start config reloader");
+        tasks.push(config_reloader.spawn::<ScraperSettings>(&self.settings));
+
+        if let Some(exporter) = self.exporter.take() {
+            tasks.push(tokio::spawn(exporter.run()).instrument(info_span!("Exporter")));
+        }
+        if let Some(notifier) = self.notifier.take() {
+            tasks.push(tokio::spawn(notifier.run()).instrument(info_span!("Notifier")));
+        }
+
+        let balance_monitor = BalanceMonitor::new(
+            &self.settings,
+            &self.core_metrics,
+            self.agent_metrics.clone(),
+            Self::AGENT_NAME.to_string(),
+        )
+        .await;
+        tasks.push(balance_monitor.spawn());
+
         for (domain, scraper) in self.scrapers.iter() {
             tasks.push(self.scrape(*domain).await);
 
@@ -114,7 +178,6 @@ impl BaseAgent for Scraper {
             let metrics_updater = MetricsUpdater::new(
                 chain_conf,
                 self.core_metrics.clone(),
-                self.agent_metrics.clone(),
                 self.chain_metrics.clone(),
                 Self::AGENT_NAME.to_string(),
             )
@@ -138,6 +201,14 @@ impl Scraper {
         let domain = scraper.domain.clone();
 
         let mut tasks = Vec::with_capacity(2);
+        // The backfill is planned before the indexers' cursors are created, so
+        // that they start after the backfilled blocks
+        if let Some(backfill) = self.build_backfill(domain.clone(), db.clone()).await {
+            tasks.push(backfill);
+        }
+        if let Some(reorg_detector) = self.build_reorg_detector(domain.clone(), db.clone()).await {
+            tasks.push(reorg_detector);
+        }
         let (message_indexer, maybe_broadcaster) = self
             .build_message_indexer(
                 domain.clone(),
@@ -159,8 +230,8 @@ impl Scraper {
             )
             .await,
         );
-        tasks.push(
-            self.build_interchain_gas_payment_indexer(
+        if let Some(gas_payment_indexer) = self
+            .build_interchain_gas_payment_indexer(
                 domain,
                 self.core_metrics.clone(),
                 self.contract_sync_metrics.clone(),
@@ -168,8 +239,10 @@ impl Scraper {
                 index_settings.clone(),
                 maybe_broadcaster.map(|b| b.subscribe()),
             )
-            .await,
-        );
+            .await
+        {
+            tasks.push(gas_payment_indexer);
+        }
 
         tokio::spawn(async move {
             // If any of the tasks panic, we want to propagate it, so we unwrap
@@ -180,6 +253,78 @@ impl Scraper {
 }
 
 impl Scraper {
+    async fn build_backfill(
+        &self,
+        domain: HyperlaneDomain,
+        db: HyperlaneSqlDb,
+    ) -> Option<Instrumented<JoinHandle<()>>> {
+        let (Some(conf), Some(backfill_metrics)) =
+            (&self.settings.backfill, &self.backfill_metrics)
+        else {
+            return None;
+        };
+        let chain_conf = self.settings.chain_setup(&domain).ok()?;
+        let backfill = match Backfill::new(
+            conf,
+            chain_conf,
+            self.db.clone(),
+            db,
+            &self.core_metrics,
+            backfill_metrics,
+        )
+        .await
+        {
+            Ok(backfill) => backfill,
+            Err(err) => {
+                warn!(?err, chain = domain.name(), "Not backfilling");
+                return None;
+            }
+        };
+        let shards = match backfill.plan().await {
+            Ok(shards) => shards,
+            Err(err) => {
+                warn!(
+                    ?err,
+                    chain = domain.name(),
+                    "Failed to plan backfill, scraping serially"
+                );
+                return None;
+            }
+        };
+        Some(
+            tokio::spawn(async move { backfill.run(shards).await })
+                .instrument(info_span!("Backfill", chain=%domain.name())),
+        )
+    }
+
+    async fn build_reorg_detector(
+        &self,
+        domain: HyperlaneDomain,
+        db: HyperlaneSqlDb,
+    ) -> Option<Instrumented<JoinHandle<()>>> {
+        let chain_conf = self.settings.chain_setup(&domain).ok()?;
+        let detector = match ReorgDetector::new(
+            &self.settings.reorg_check,
+            chain_conf,
+            self.db.clone(),
+            db,
+            &self.core_metrics,
+            &self.reorg_metrics,
+        )
+        .await
+        {
+            Ok(detector) => detector,
+            Err(err) => {
+                warn!(?err, chain = domain.name(), "Not detecting reorgs");
+                return None;
+            }
+        };
+        Some(
+            tokio::spawn(async move { detector.run().await })
+                .instrument(info_span!("ReorgDetector", chain=%domain.name())),
+        )
+    }
+
     async fn build_message_indexer(
         &self,
         domain: HyperlaneDomain,
@@ -246,8 +391,10 @@ impl Scraper {
         db: HyperlaneSqlDb,
         index_settings: IndexSettings,
         tx_id_receiver: Option<Receiver<H512>>,
-    ) -> Instrumented<JoinHandle<()>> {
-        let sync = self
+    ) -> Option<Instrumented<JoinHandle<()>>> {
+        // Not all chains have an interchain gas paymaster indexer, their
+        // messages and deliveries are scraped regardless
+        let sync = match self
             .as_ref()
             .settings
             .watermark_contract_sync::<InterchainGasPayment, _>(
@@ -257,14 +404,22 @@ impl Scraper {
                 Arc::new(db.clone()),
             )
             .await
-            .unwrap();
+        {
+            Ok(sync) => sync,
+            Err(err) => {
+                warn!(?err, chain = domain.name(), "Not scraping gas payments");
+                return None;
+            }
+        };
 
         let label = "gas_payment";
         let cursor = sync.cursor(index_settings.clone()).await;
-        tokio::spawn(async move {
-            sync.sync(label, SyncOptions::new(Some(cursor), tx_id_receiver))
-                .await
-        })
-        .instrument(info_span!("ChainContractSync", chain=%domain.name(), event=label))
+        Some(
+            tokio::spawn(async move {
+                sync.sync(label, SyncOptions::new(Some(cursor), tx_id_receiver))
+                    .await
+            })
+            .instrument(info_span!("ChainContractSync", chain=%domain.name(), event=label)),
+        )
     }
 }
@@ -0,0 +1,298 @@
+//! Scrapes the history of a chain with parallel workers, instead of serially
+//! from the indexing start block.
+//!
+//! On the first run for a chain, the blocks from the cursor up to the tip are
+//! split into shards, and the cursor is moved to the tip so that the live
+//! indexers only follow new blocks. Workers then scrape the shards
+//! concurrently, each with its own persisted cursor, so that a restart resumes
+//! the shards where they left off. Storing logs is idempotent, so blocks that
+//! are scraped twice, e.g. around a restart, don't lead to duplicates.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::Hash,
+    ops::RangeInclusive,
+    sync::Arc,
+    time::Duration,
+};
+
+use eyre::{bail, Result};
+use futures::future::join_all;
+use hyperlane_base::{settings::ChainConf, CoreMetrics};
+use hyperlane_core::{
+    Delivery, HyperlaneLogStore, HyperlaneMessage, IndexMode, Indexer, InterchainGasPayment,
+    SequenceAwareIndexer,
+};
+use prometheus::{IntGauge, IntGaugeVec};
+use tokio::{sync::Mutex, time::sleep};
+use tracing::{debug, info, info_span, warn, Instrument};
+
+use crate::{
+    chain_scraper::HyperlaneSqlDb,
+    db::{BackfillShard, ScraperDb},
+};
+
+/// How long a worker waits before retrying a range it failed to scrape
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Settings for backfilling the history of chains with parallel workers
+#[derive(Debug, Clone)]
+pub struct BackfillConf {
+    /// How many shards of each chain are scraped at once
+    pub workers: u32,
+    /// How many blocks each shard spans
+    pub shard_size: u32,
+}
+
+/// Progress of the backfills of all chains
+#[derive(Debug, Clone)]
+pub struct BackfillMetrics {
+    remaining_blocks: IntGaugeVec,
+    remaining_shards: IntGaugeVec,
+}
+
+impl BackfillMetrics {
+    pub fn new(metrics: &CoreMetrics) -> Result<Self> {
+        Ok(Self {
+            remaining_blocks: metrics.new_int_gauge(
+                "scraper_backfill_remaining_blocks",
+                "Historical blocks that weren't backfilled yet",
+                &["chain"],
+            )?,
+            remaining_shards: metrics.new_int_gauge(
+                "scraper_backfill_remaining_shards",
+                "Backfill shards that weren't completed yet",
+                &["chain"],
+            )?,
+        })
+    }
+}
+
+/// Backfills the history of a single chain
+#[derive(Debug)]
+pub struct Backfill {
+    workers: u32,
+    shard_size: u32,
+    chunk_size: u32,
+    db: ScraperDb,
+    sql_db: HyperlaneSqlDb,
+    message_indexer: Arc<dyn SequenceAwareIndexer<HyperlaneMessage>>,
+    delivery_indexer: Arc<dyn SequenceAwareIndexer<Delivery>>,
+    gas_payment_indexer: Option<Arc<dyn SequenceAwareIndexer<InterchainGasPayment>>>,
+    remaining_blocks: IntGauge,
+    remaining_shards: IntGauge,
+}
+
+impl Backfill {
+    pub async fn new(
+        conf: &BackfillConf,
+        chain_conf: &ChainConf,
+        db: ScraperDb,
+        sql_db: HyperlaneSqlDb,
+        metrics: &CoreMetrics,
+        backfill_metrics: &BackfillMetrics,
+    ) -> Result<Self> {
+        let chain = sql_db.domain().name();
+        // The shards are block ranges, which chains indexed by sequence can't
+        // be queried by
+        if !matches!(chain_conf.index.mode, IndexMode::Block) {
+            bail!("Backfilling is only supported for chains indexed by block");
+        }
+        // The history is scraped from the archival RPCs if there are any, so
+        // that the live indexers aren't slowed down by deep queries
+        let archive_setup = chain_conf.archive_setup();
+        if archive_setup.is_some() {
+            info!(chain, "Backfilling from the archival RPCs");
+        }
+        let setup = archive_setup.as_ref().unwrap_or(chain_conf);
+        let message_indexer = setup.build_message_indexer(metrics).await?.into();
+        let delivery_indexer = setup.build_delivery_indexer(metrics).await?.into();
+        // Like for the live indexers, not all chains have an interchain gas
+        // paymaster indexer
+        let gas_payment_indexer = match setup.build_interchain_gas_payment_indexer(metrics).await {
+            Ok(indexer) => Some(indexer.into()),
+            Err(err) => {
+                warn!(?err, chain, "Not backfilling gas payments");
+                None
+            }
+        };
+        Ok(Self {
+            workers: conf.workers,
+            shard_size: conf.shard_size,
+            chunk_size: chain_conf.index.chunk_size.max(1),
+            remaining_blocks: backfill_metrics
+                .remaining_blocks
+                .with_label_values(&[chain]),
+            remaining_shards: backfill_metrics
+                .remaining_shards
+                .with_label_values(&[chain]),
+            db,
+            sql_db,
+            message_indexer,
+            delivery_indexer,
+            gas_payment_indexer,
+        })
+    }
+
+    /// Splits the blocks from the cursor up to the tip into shards on the
+    /// first run, and moves the cursor past them. Later runs resume the
+    /// stored shards. Must be called before the live indexers' cursors are
+    /// created, so that they start from the tip.
+    pub async fn plan(&self) -> Result<Vec<BackfillShard>> {
+        let domain = self.sql_db.domain().id();
+        let shards = self.db.retrieve_backfill_shards(domain).await?;
+        if !shards.is_empty() {
+            return Ok(shards);
+        }
+        let from = self.sql_db.cursor_height().await as u32;
+        let tip = self.message_indexer.get_finalized_block_number().await?;
+        let ranges = shard_ranges(from, tip, self.shard_size);
+        if ranges.is_empty() {
+            return Ok(vec![]);
+        }
+        info!(from, tip, shards = ranges.len(), "Planned backfill");
+        // The shards are stored first, so that if the cursor can't be moved,
+        // the blocks are only scraped twice rather than not at all
+        self.db.store_backfill_shards(domain, &ranges).await?;
+        self.sql_db.skip_cursor_to(tip as u64).await?;
+        self.db.retrieve_backfill_shards(domain).await
+    }
+
+    /// Scrapes the incomplete shards with `workers` workers, until all are
+    /// complete.
+    pub async fn run(self, shards: Vec<BackfillShard>) {
+        let pending: VecDeque<_> = shards
+            .into_iter()
+            .filter(|shard| !shard.is_complete())
+            .collect();
+        self.remaining_shards.set(pending.len() as i64);
+        self.remaining_blocks.set(
+            pending
+                .iter()
+                .map(BackfillShard::remaining_blocks)
+                .sum::<u64>() as i64,
+        );
+        if pending.is_empty() {
+            return;
+        }
+        info!(
+            shards = pending.len(),
+            workers = self.workers,
+            "Backfilling"
+        );
+
+        let backfill = Arc::new(self);
+        let pending = Arc::new(Mutex::new(pending));
+        let workers = (0..backfill.workers).map(|worker| {
+            let backfill = backfill.clone();
+            let pending = pending.clone();
+            tokio::spawn(async move { backfill.work(&pending).await })
+                .instrument(info_span!("BackfillWorker", worker))
+        });
+        for result in join_all(workers).await {
+            if let Err(err) = result {
+                warn!(?err, "Backfill worker panicked");
+            }
+        }
+        info!("Backfill complete");
+    }
+
+    async fn work(&self, pending: &Mutex<VecDeque<BackfillShard>>) {
+        loop {
+            let Some(mut shard) = pending.lock().await.pop_front() else {
+                return;
+            };
+            debug!(?shard, "Backfilling shard");
+            while !shard.is_complete() {
+                let range = shard.next_block
+                    ..=shard
+                        .to_block
+                        .min(shard.next_block.saturating_add(self.chunk_size - 1));
+                if let Err(err) = self.scrape_range(range.clone()).await {
+                    warn!(?err, ?range, "Failed to backfill range, retrying");
+                    sleep(RETRY_DELAY).await;
+                    continue;
+                }
+                let next_block = range.end().saturating_add(1);
+                // If the progress isn't persisted, the range is only scraped
+                // again after a restart
+                if let Err(err) = self.db.update_backfill_shard(shard.id, next_block).await {
+                    warn!(?err, ?shard, "Failed to store backfill progress");
+                }
+                self.remaining_blocks
+                    .sub((next_block - shard.next_block) as i64);
+                shard.next_block = next_block;
+            }
+            self.remaining_shards.dec();
+            debug!(?shard, "Backfilled shard");
+        }
+    }
+
+    async fn scrape_range(&self, range: RangeInclusive<u32>) -> Result<()> {
+        scrape_logs(&*self.message_indexer, &self.sql_db, range.clone()).await?;
+        scrape_logs(&*self.delivery_indexer, &self.sql_db, range.clone()).await?;
+        if let Some(indexer) = &self.gas_payment_indexer {
+            scrape_logs(&**indexer, &self.sql_db, range).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn scrape_logs<T, I>(
+    indexer: &I,
+    db: &HyperlaneSqlDb,
+    range: RangeInclusive<u32>,
+) -> Result<()>
+where
+    T: Hash + Eq,
+    I: Indexer<T> + ?Sized,
+    HyperlaneSqlDb: HyperlaneLogStore<T>,
+{
+    let logs = indexer.fetch_logs_in_range(range).await?;
+    let logs = Vec::from_iter(HashSet::<_>::from_iter(logs));
+    db.store_logs(&logs).await?;
+    Ok(())
+}
+
+/// Splits the blocks from `from` to `to`, inclusive, into ranges of at most
+/// `shard_size` blocks
+fn shard_ranges(from: u32, to: u32, shard_size: u32) -> Vec<RangeInclusive<u32>> {
+    (from as u64..=to as u64)
+        .step_by(shard_size as usize)
+        .map(|start| start as u32..=(start + shard_size as u64 - 1).min(to as u64) as u32)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shard_ranges() {
+        assert_eq!(shard_ranges(0, 9, 5), vec![0..=4, 5..=9]);
+        assert_eq!(shard_ranges(10, 22, 5), vec![10..=14, 15..=19, 20..=22]);
+        assert_eq!(shard_ranges(7, 7, 5), vec![7..=7]);
+        assert!(shard_ranges(8, 7, 5).is_empty());
+        assert_eq!(
+            shard_ranges(u32::MAX - 1, u32::MAX, 5),
+            vec![u32::MAX - 1..=u32::MAX]
+        );
+    }
+
+    #[test]
+    fn test_shard_progress() {
+        let mut shard = BackfillShard {
+            id: 1,
+            from_block: 10,
+            to_block: 19,
+            next_block: 10,
+        };
+        assert_eq!(shard.remaining_blocks(), 10);
+        shard.next_block = 15;
+        assert_eq!(shard.remaining_blocks(), 5);
+        assert!(!shard.is_complete());
+        shard.next_block = 20;
+        assert_eq!(shard.remaining_blocks(), 0);
+        assert!(shard.is_complete());
+    }
+}
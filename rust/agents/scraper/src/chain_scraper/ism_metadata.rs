@@ -0,0 +1,347 @@
+//! Recovers which ISM a message was delivered with and, when the delivery
+//! transaction can be decoded, the metadata and validator signatures it was
+//! verified with.
+
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+use ethers::{
+    abi::{decode, ParamType, Token},
+    utils::id,
+};
+use eyre::Result;
+use hyperlane_base::{settings::ChainConf, CoreMetrics};
+use hyperlane_core::{
+    accumulator::{merkle::merkle_root_from_branch, TREE_DEPTH},
+    Checkpoint, CheckpointWithMessageId, Decode, HyperlaneMessage, Mailbox, ModuleType, Signature,
+    SignedType, TxnInfo, H160, H256,
+};
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// The mailbox entrypoint delivering a message.
+const PROCESS_SIGNATURE: &str = "process(bytes,bytes)";
+/// Multicall3 entrypoint the relayer batches deliveries with.
+const AGGREGATE3_SIGNATURE: &str = "aggregate3((address,bool,bytes)[])";
+const SIGNATURE_LEN: usize = 65;
+
+/// The ISM a message was delivered with.
+#[derive(Debug, Clone)]
+pub struct DeliveryIsm {
+    pub message_id: H256,
+    pub ism: H256,
+    /// `None` if the module type could not be queried
+    pub module_type: Option<ModuleType>,
+    pub metadata: Vec<u8>,
+    /// Validator signatures contained in the metadata, with the validator each
+    /// one was recovered to. Only decoded for multisig ISMs.
+    pub signatures: Vec<(H160, Signature)>,
+}
+
+/// Looks up the ISM of the recipients of delivered messages.
+pub struct IsmMetadataResolver {
+    chain: ChainConf,
+    metrics: Arc<CoreMetrics>,
+    mailbox: Box<dyn Mailbox>,
+    module_types: RwLock<HashMap<H256, ModuleType>>,
+}
+
+impl Debug for IsmMetadataResolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // intentionally leaves out the chain config and metrics
+        f.debug_struct("IsmMetadataResolver")
+            .field("mailbox", &self.mailbox.address())
+            .finish()
+    }
+}
+
+impl IsmMetadataResolver {
+    pub async fn new(chain: ChainConf, metrics: Arc<CoreMetrics>) -> Result<Self> {
+        let mailbox = chain.build_mailbox(&metrics).await?;
+        Ok(Self {
+            chain,
+            metrics,
+            mailbox,
+            module_types: Default::default(),
+        })
+    }
+
+    /// Returns `None` if the delivery transaction could not be decoded.
+    ///
+    /// Note that the ISM is the recipient's ISM at the time of scraping, which
+    /// can differ from the one at delivery if the recipient changed it since.
+    pub async fn resolve(&self, message_id: H256, txn: &TxnInfo) -> Result<Option<DeliveryIsm>> {
+        let Some((message, metadata)) = txn
+            .raw_input_data
+            .as_deref()
+            .and_then(|input| decode_process_calldata(input, message_id))
+        else {
+            return Ok(None);
+        };
+        let ism = self.mailbox.recipient_ism(message.recipient).await?;
+        let module_type = self.module_type(ism).await;
+        let signatures = module_type
+            .map(|module_type| recover_signatures(module_type, &message, &metadata))
+            .unwrap_or_default();
+        Ok(Some(DeliveryIsm {
+            message_id,
+            ism,
+            module_type,
+            metadata,
+            signatures,
+        }))
+    }
+
+    async fn module_type(&self, ism: H256) -> Option<ModuleType> {
+        if let Some(module_type) = self.module_types.read().await.get(&ism) {
+            return Some(*module_type);
+        }
+        let module_type = match self.chain.build_ism(ism, &self.metrics).await {
+            Ok(contract) => contract.module_type().await.map_err(Into::into),
+            Err(err) => Err(err),
+        };
+        match module_type {
+            Ok(module_type) => {
+                self.module_types.write().await.insert(ism, module_type);
+                Some(module_type)
+            }
+            Err(err) => {
+                warn!(error=?err, ?ism, "Failed to query ISM module type");
+                None
+            }
+        }
+    }
+}
+
+/// Finds the `process` call delivering `message_id` in the input of a
+/// transaction, either as a direct call to the mailbox or batched in a
+/// Multicall3 `aggregate3` call. Returns the message and its metadata.
+fn decode_process_calldata(input: &[u8], message_id: H256) -> Option<(HyperlaneMessage, Vec<u8>)> {
+    let (selector, args) = (input.get(..4)?, input.get(4..)?);
+    if selector == id(PROCESS_SIGNATURE) {
+        let tokens = decode(&[ParamType::Bytes, ParamType::Bytes], args).ok()?;
+        let [Token::Bytes(metadata), Token::Bytes(message)] = tokens.as_slice() else {
+            return None;
+        };
+        let message = HyperlaneMessage::from(message);
+        (message.id() == message_id).then(|| (message, metadata.clone()))
+    } else if selector == id(AGGREGATE3_SIGNATURE) {
+        let call = ParamType::Tuple(vec![ParamType::Address, ParamType::Bool, ParamType::Bytes]);
+        let tokens = decode(&[ParamType::Array(Box::new(call))], args).ok()?;
+        let [Token::Array(calls)] = tokens.as_slice() else {
+            return None;
+        };
+        calls.iter().find_map(|call| match call {
+            Token::Tuple(fields) => match fields.as_slice() {
+                [_, _, Token::Bytes(data)] => decode_process_calldata(data, message_id),
+                _ => None,
+            },
+            _ => None,
+        })
+    } else {
+        None
+    }
+}
+
+/// Recovers the validators that signed the checkpoint in the metadata of a
+/// multisig ISM. Signatures that cannot be recovered are skipped.
+fn recover_signatures(
+    module_type: ModuleType,
+    message: &HyperlaneMessage,
+    metadata: &[u8],
+) -> Vec<(H160, Signature)> {
+    let Some((checkpoint, signatures)) = signed_checkpoint(module_type, message, metadata) else {
+        return vec![];
+    };
+    signatures
+        .chunks_exact(SIGNATURE_LEN)
+        .filter_map(|bytes| {
+            let signature = Signature::read_from(&mut &bytes[..]).ok()?;
+            let signed = SignedType {
+                value: checkpoint,
+                signature,
+            };
+            Some((signed.recover().ok()?, signature))
+        })
+        .collect()
+}
+
+/// Decodes the checkpoint signed by the validators and the concatenated
+/// signatures from the metadata of a multisig ISM.
+fn signed_checkpoint<'a>(
+    module_type: ModuleType,
+    message: &HyperlaneMessage,
+    metadata: &'a [u8],
+) -> Option<(CheckpointWithMessageId, &'a [u8])> {
+    match module_type {
+        // merkle tree hook (32) | root (32) | index (4) | signatures
+        ModuleType::MessageIdMultisig => Some((
+            CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: read_h256(metadata, 0)?,
+                    mailbox_domain: message.origin,
+                    root: read_h256(metadata, 32)?,
+                    index: read_u32(metadata, 64)?,
+                },
+                message_id: message.id(),
+            },
+            metadata.get(68..)?,
+        )),
+        // merkle tree hook (32) | message index (4) | signed message id (32) |
+        // proof (32 * 32) | signed index (4) | signatures
+        ModuleType::MerkleRootMultisig => {
+            let proof_end = 68 + 32 * TREE_DEPTH;
+            let proof: Vec<H256> = metadata
+                .get(68..proof_end)?
+                .chunks_exact(32)
+                .map(H256::from_slice)
+                .collect();
+            let message_index = read_u32(metadata, 32)?;
+            Some((
+                CheckpointWithMessageId {
+                    checkpoint: Checkpoint {
+                        merkle_tree_hook_address: read_h256(metadata, 0)?,
+                        mailbox_domain: message.origin,
+                        root: merkle_root_from_branch(
+                            message.id(),
+                            &proof,
+                            TREE_DEPTH,
+                            message_index as usize,
+                        ),
+                        index: read_u32(metadata, proof_end)?,
+                    },
+                    message_id: read_h256(metadata, 36)?,
+                },
+                metadata.get(proof_end + 4..)?,
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn read_h256(bytes: &[u8], offset: usize) -> Option<H256> {
+    bytes.get(offset..offset + 32).map(H256::from_slice)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::{
+        abi::encode,
+        signers::{LocalWallet, Signer},
+    };
+    use hyperlane_core::{RawHyperlaneMessage, Signable};
+
+    use super::*;
+
+    fn message() -> HyperlaneMessage {
+        HyperlaneMessage {
+            origin: 1000,
+            destination: 2000,
+            nonce: 3,
+            body: vec![1, 2, 3],
+            ..Default::default()
+        }
+    }
+
+    fn process_calldata(metadata: &[u8], message: &HyperlaneMessage) -> Vec<u8> {
+        [
+            id(PROCESS_SIGNATURE).to_vec(),
+            encode(&[
+                Token::Bytes(metadata.to_vec()),
+                Token::Bytes(RawHyperlaneMessage::from(message)),
+            ]),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_decode_process_calldata() {
+        let message = message();
+        let calldata = process_calldata(&[4, 5], &message);
+
+        assert_eq!(
+            decode_process_calldata(&calldata, message.id()),
+            Some((message.clone(), vec![4, 5]))
+        );
+        assert_eq!(decode_process_calldata(&calldata, H256::zero()), None);
+        assert_eq!(decode_process_calldata(&calldata[..10], message.id()), None);
+    }
+
+    #[test]
+    fn test_decode_batched_process_calldata() {
+        let message = message();
+        let other = HyperlaneMessage {
+            nonce: 4,
+            ..message.clone()
+        };
+        let call = |data: Vec<u8>| {
+            Token::Tuple(vec![
+                Token::Address(Default::default()),
+                Token::Bool(true),
+                Token::Bytes(data),
+            ])
+        };
+        let calldata = [
+            id(AGGREGATE3_SIGNATURE).to_vec(),
+            encode(&[Token::Array(vec![
+                call(process_calldata(&[6], &other)),
+                call(process_calldata(&[4, 5], &message)),
+            ])]),
+        ]
+        .concat();
+
+        assert_eq!(
+            decode_process_calldata(&calldata, message.id()),
+            Some((message, vec![4, 5]))
+        );
+    }
+
+    #[test]
+    fn test_recover_message_id_multisig_signatures() {
+        let message = message();
+        let checkpoint = CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: H256::repeat_byte(1),
+                mailbox_domain: message.origin,
+                root: H256::repeat_byte(2),
+                index: 3,
+            },
+            message_id: message.id(),
+        };
+        let wallet: LocalWallet =
+            "4f3edf983ac636a65a842ce7c78d9aa706d3b113bce9c46f30d7d21715b23b1d"
+                .parse()
+                .unwrap();
+        let signature: Signature = wallet
+            .sign_hash(checkpoint.eth_signed_message_hash().into())
+            .unwrap()
+            .into();
+        let metadata = [
+            checkpoint.merkle_tree_hook_address.as_bytes(),
+            checkpoint.root.as_bytes(),
+            &checkpoint.index.to_be_bytes(),
+            &signature.to_vec(),
+        ]
+        .concat();
+
+        assert_eq!(
+            recover_signatures(ModuleType::MessageIdMultisig, &message, &metadata),
+            vec![(H160::from(wallet.address().0), signature)]
+        );
+        // Truncated metadata
+        assert!(
+            recover_signatures(ModuleType::MessageIdMultisig, &message, &metadata[..60]).is_empty()
+        );
+        // Not a multisig ISM
+        assert!(recover_signatures(ModuleType::Routing, &message, &metadata).is_empty());
+    }
+}
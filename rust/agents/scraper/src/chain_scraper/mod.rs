@@ -9,16 +9,20 @@ use hyperlane_base::settings::IndexSettings;
 use hyperlane_core::{
     unwrap_or_none_result, BlockInfo, Delivery, HyperlaneDomain, HyperlaneLogStore,
     HyperlaneMessage, HyperlaneProvider, HyperlaneSequenceAwareIndexerStoreReader,
-    HyperlaneWatermarkedLogStore, Indexed, InterchainGasPayment, LogMeta, H256,
+    HyperlaneWatermarkedLogStore, Indexed, InterchainGasPayment, LogMeta, TxnInfo, H256,
 };
 use itertools::Itertools;
-use tracing::trace;
+use tracing::{trace, warn};
 
 use crate::db::{
     BasicBlock, BlockCursor, ScraperDb, StorableDelivery, StorableMessage, StorablePayment,
     StorableTxn,
 };
 
+pub use ism_metadata::{DeliveryIsm, IsmMetadataResolver};
+
+mod ism_metadata;
+
 /// Maximum number of records to query at a time. This came about because when a
 /// lot of messages are sent in a short period of time we were ending up with a
 /// lot of data to query from the node provider between points when we would
@@ -33,6 +37,7 @@ pub struct HyperlaneSqlDb {
     domain: HyperlaneDomain,
     db: ScraperDb,
     provider: Arc<dyn HyperlaneProvider>,
+    ism_resolver: Arc<IsmMetadataResolver>,
     cursor: Arc<BlockCursor>,
 }
 
@@ -43,6 +48,7 @@ impl HyperlaneSqlDb {
         mailbox_address: H256,
         domain: HyperlaneDomain,
         provider: Arc<dyn HyperlaneProvider>,
+        ism_resolver: Arc<IsmMetadataResolver>,
         index_settings: &IndexSettings,
     ) -> Result<Self> {
         let cursor = Arc::new(
@@ -53,6 +59,7 @@ impl HyperlaneSqlDb {
             db,
             domain,
             provider,
+            ism_resolver,
             mailbox_address,
             cursor,
         })
@@ -62,6 +69,17 @@ impl HyperlaneSqlDb {
         &self.domain
     }
 
+    /// The block the watermarked indexers resume from
+    pub async fn cursor_height(&self) -> u64 {
+        self.cursor.height().await
+    }
+
+    /// Moves the block the watermarked indexers resume from forward to
+    /// `height`, e.g. because the blocks before it are backfilled.
+    pub async fn skip_cursor_to(&self, height: u64) -> Result<()> {
+        self.cursor.skip_to(height).await
+    }
+
     pub async fn last_message_nonce(&self) -> Result<Option<u32>> {
         self.db
             .last_message_nonce(self.domain.id(), &self.mailbox_address)
@@ -112,6 +130,47 @@ impl HyperlaneSqlDb {
         Ok(txns_with_ids.map(move |TxnWithId { hash, id: txn_id }| TxnWithId { hash, id: txn_id }))
     }
 
+    /// Records the ISM and metadata each message was delivered with. This is
+    /// best effort, failures are logged and never fail the indexing of the
+    /// deliveries themselves.
+    async fn store_delivery_isms(&self, deliveries: &[(Indexed<Delivery>, LogMeta)]) {
+        let mut txns: HashMap<H256, TxnInfo> = HashMap::new();
+        let mut isms = Vec::with_capacity(deliveries.len());
+        for (message_id, meta) in deliveries {
+            let txn_hash: H256 = meta
+                .transaction_id
+                .try_into()
+                .expect("256-bit transaction ids are the maximum supported at this time");
+            if !txns.contains_key(&txn_hash) {
+                match self.provider.get_txn_by_hash(&txn_hash).await {
+                    Ok(info) => {
+                        txns.insert(txn_hash, info);
+                    }
+                    Err(err) => {
+                        warn!(error=?err, ?txn_hash, "Failed to fetch delivery transaction");
+                        continue;
+                    }
+                }
+            }
+            match self
+                .ism_resolver
+                .resolve(*message_id.inner(), &txns[&txn_hash])
+                .await
+            {
+                Ok(Some(ism)) => isms.push(ism),
+                Ok(None) => trace!(
+                    ?message_id,
+                    ?txn_hash,
+                    "Could not decode delivery transaction"
+                ),
+                Err(err) => warn!(error=?err, ?message_id, "Failed to resolve delivery ISM"),
+            }
+        }
+        if let Err(err) = self.db.store_delivery_isms(self.domain.id(), &isms).await {
+            warn!(error=?err, "Failed to store delivery ISMs");
+        }
+    }
+
     /// Takes a list of transaction hashes and the block id the transaction is
     /// in. if it is in the database already:
     ///     Fetches its associated database id
@@ -332,6 +391,7 @@ impl HyperlaneLogStore<Delivery> for HyperlaneSqlDb {
             .db
             .store_deliveries(self.domain().id(), self.mailbox_address, storable)
             .await?;
+        self.store_delivery_isms(deliveries).await;
         Ok(stored as u32)
     }
 }
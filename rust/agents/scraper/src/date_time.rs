@@ -9,6 +9,11 @@ pub fn from_unix_timestamp_s(timestamp: u64) -> TimeDateTime {
     TimeDateTime::new(offset.date(), offset.time())
 }
 
+/// Convert from a TimeDateTime object to a unix timestamp in seconds.
+pub fn to_unix_timestamp_s(date_time: TimeDateTime) -> i64 {
+    date_time.assume_utc().unix_timestamp()
+}
+
 /// Get the current time as a sql date time object
 pub fn now() -> TimeDateTime {
     let offset = OffsetDateTime::now_utc();
@@ -0,0 +1,115 @@
+use std::ops::RangeInclusive;
+
+use eyre::{Context, Result};
+use itertools::Itertools;
+use sea_orm::{prelude::*, ActiveValue::*, Insert, QueryOrder};
+use tracing::{instrument, trace};
+
+use migration::OnConflict;
+
+use crate::db::ScraperDb;
+
+use super::generated::backfill_shard;
+
+/// A range of historical blocks scraped by a backfill worker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackfillShard {
+    pub id: i64,
+    pub from_block: u32,
+    pub to_block: u32,
+    /// The next block to scrape
+    pub next_block: u32,
+}
+
+impl BackfillShard {
+    pub fn is_complete(&self) -> bool {
+        self.next_block > self.to_block
+    }
+
+    /// The blocks of the shard that weren't scraped yet
+    pub fn remaining_blocks(&self) -> u64 {
+        (self.to_block as u64 + 1).saturating_sub(self.next_block as u64)
+    }
+}
+
+impl From<backfill_shard::Model> for BackfillShard {
+    fn from(model: backfill_shard::Model) -> Self {
+        Self {
+            id: model.id,
+            from_block: model.from_block as u32,
+            to_block: model.to_block as u32,
+            next_block: model.next_block as u32,
+        }
+    }
+}
+
+impl ScraperDb {
+    /// Get the backfill shards of a domain, complete or not, ordered by their
+    /// first block.
+    #[instrument(skip(self))]
+    pub async fn retrieve_backfill_shards(&self, domain: u32) -> Result<Vec<BackfillShard>> {
+        Ok(backfill_shard::Entity::find()
+            .filter(backfill_shard::Column::Domain.eq(domain))
+            .order_by_asc(backfill_shard::Column::FromBlock)
+            .all(&self.0)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Store new backfill shards for the given block ranges. Shards that
+    /// already exist are left untouched, so that their progress isn't lost.
+    #[instrument(skip(self))]
+    pub async fn store_backfill_shards(
+        &self,
+        domain: u32,
+        ranges: &[RangeInclusive<u32>],
+    ) -> Result<()> {
+        if ranges.is_empty() {
+            return Ok(());
+        }
+        let models = ranges
+            .iter()
+            .map(|range| backfill_shard::ActiveModel {
+                id: NotSet,
+                time_created: NotSet,
+                domain: Set(domain as i32),
+                from_block: Set(*range.start() as i64),
+                to_block: Set(*range.end() as i64),
+                next_block: Set(*range.start() as i64),
+            })
+            .collect_vec();
+        trace!(?models, "Writing backfill shards to database");
+
+        match Insert::many(models)
+            .on_conflict(
+                OnConflict::columns([
+                    backfill_shard::Column::Domain,
+                    backfill_shard::Column::FromBlock,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec(&self.0)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(DbErr::RecordNotInserted) => Ok(()),
+            Err(e) => Err(e).context("When inserting backfill shards"),
+        }
+    }
+
+    /// Record that a backfill shard was scraped up to `next_block`, exclusive.
+    #[instrument(skip(self))]
+    pub async fn update_backfill_shard(&self, id: i64, next_block: u32) -> Result<()> {
+        backfill_shard::ActiveModel {
+            id: Unchanged(id),
+            next_block: Set(next_block as i64),
+            ..Default::default()
+        }
+        .update(&self.0)
+        .await?;
+        Ok(())
+    }
+}
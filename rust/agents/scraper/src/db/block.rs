@@ -1,9 +1,13 @@
+use std::ops::RangeInclusive;
+
 use eyre::{Context, Result};
 use sea_orm::{
-    prelude::*, ActiveValue::*, DbErr, EntityTrait, FromQueryResult, Insert, QueryResult,
-    QuerySelect,
+    prelude::*,
+    sea_query::{Expr, Query},
+    ActiveValue::*,
+    DbErr, EntityTrait, FromQueryResult, Insert, QueryResult, QuerySelect, TransactionTrait,
 };
-use tracing::{debug, trace};
+use tracing::{debug, instrument, trace};
 
 use hyperlane_core::{BlockInfo, H256};
 use migration::OnConflict;
@@ -12,7 +16,16 @@ use crate::conversions::{address_to_bytes, h256_to_bytes};
 use crate::date_time;
 use crate::db::ScraperDb;
 
-use super::generated::block;
+use super::generated::{block, delivered_message, gas_payment, message, transaction};
+
+/// A block as it's stored in the database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredBlock {
+    /// the database id of this block
+    pub id: i64,
+    pub hash: H256,
+    pub height: u64,
+}
 
 /// A stripped down block model. This is so we can get just the information
 /// needed if the block is present in the Db already to inject into other
@@ -63,6 +76,7 @@ impl ScraperDb {
         // check database to see which blocks we already know and fetch their IDs
         let blocks = block::Entity::find()
             .filter(block::Column::Hash.is_in(hashes.map(h256_to_bytes)))
+            .filter(block::Column::TimeOrphaned.is_null())
             .select_only()
             // these must align with the custom impl of FromQueryResult
             .column_as(block::Column::Id, "id")
@@ -90,6 +104,7 @@ impl ScraperDb {
                 domain: Unchanged(domain as i32),
                 height: Unchanged(info.number as i64),
                 timestamp: Set(date_time::from_unix_timestamp_s(info.timestamp)),
+                time_orphaned: Set(None),
             })
             .collect::<Vec<_>>();
 
@@ -98,8 +113,10 @@ impl ScraperDb {
         trace!(?models, "Writing blocks to database");
         match Insert::many(models)
             .on_conflict(
+                // An orphaned block may become canonical again after another
+                // reorg
                 OnConflict::column(block::Column::Hash)
-                    .do_nothing()
+                    .update_column(block::Column::TimeOrphaned)
                     .to_owned(),
             )
             .exec(&self.0)
@@ -110,4 +127,75 @@ impl ScraperDb {
             Err(e) => Err(e).context("When inserting blocks"),
         }
     }
+
+    /// Get the canonical blocks of a domain with heights in the range
+    pub async fn retrieve_canonical_blocks(
+        &self,
+        domain: u32,
+        heights: RangeInclusive<u64>,
+    ) -> Result<Vec<StoredBlock>> {
+        let blocks = block::Entity::find()
+            .filter(block::Column::Domain.eq(domain))
+            .filter(block::Column::Height.between(*heights.start() as i64, *heights.end() as i64))
+            .filter(block::Column::TimeOrphaned.is_null())
+            .all(&self.0)
+            .await
+            .context("When querying canonical blocks")?
+            .into_iter()
+            .map(|block| StoredBlock {
+                id: block.id,
+                hash: H256::from_slice(&block.hash),
+                height: block.height as u64,
+            })
+            .collect();
+        Ok(blocks)
+    }
+
+    /// Mark blocks as orphaned by a reorg, along with the dispatches,
+    /// deliveries and gas payments in them. They're kept, but excluded from
+    /// the views, until they're scraped again from the canonical chain.
+    #[instrument(skip(self))]
+    pub async fn orphan_blocks(&self, block_ids: &[i64]) -> Result<()> {
+        if block_ids.is_empty() {
+            return Ok(());
+        }
+        let now = date_time::now();
+        let txns_in_blocks = Query::select()
+            .column(transaction::Column::Id)
+            .from(transaction::Entity)
+            .and_where(transaction::Column::BlockId.is_in(block_ids.iter().copied()))
+            .to_owned();
+
+        let db_txn = self.0.begin().await?;
+        let messages = message::Entity::update_many()
+            .col_expr(message::Column::TimeOrphaned, Expr::value(now))
+            .filter(message::Column::OriginTxId.in_subquery(txns_in_blocks.clone()))
+            .exec(&db_txn)
+            .await?;
+        let deliveries = delivered_message::Entity::update_many()
+            .col_expr(delivered_message::Column::TimeOrphaned, Expr::value(now))
+            .filter(delivered_message::Column::DestinationTxId.in_subquery(txns_in_blocks.clone()))
+            .exec(&db_txn)
+            .await?;
+        let payments = gas_payment::Entity::update_many()
+            .col_expr(gas_payment::Column::TimeOrphaned, Expr::value(now))
+            .filter(gas_payment::Column::TxId.in_subquery(txns_in_blocks))
+            .exec(&db_txn)
+            .await?;
+        block::Entity::update_many()
+            .col_expr(block::Column::TimeOrphaned, Expr::value(now))
+            .filter(block::Column::Id.is_in(block_ids.iter().copied()))
+            .exec(&db_txn)
+            .await?;
+        db_txn.commit().await?;
+
+        debug!(
+            blocks = block_ids.len(),
+            messages = messages.rows_affected,
+            deliveries = deliveries.rows_affected,
+            payments = payments.rows_affected,
+            "Orphaned blocks"
+        );
+        Ok(())
+    }
 }
@@ -83,20 +83,40 @@ impl BlockCursor {
             inner.last_saved_at = now;
             // prevent any more writes to the inner struct until the write is complete.
             let inner = inner.downgrade();
-            let model = cursor::ActiveModel {
-                id: ActiveValue::NotSet,
-                domain: ActiveValue::Set(self.domain as i32),
-                time_created: ActiveValue::NotSet,
-                height: ActiveValue::Set(height as i64),
-            };
-            debug!(?model, "Inserting cursor");
-            if let Err(e) = Insert::one(model).exec(&self.db).await {
+            if let Err(e) = self.save(height).await {
                 warn!(error = ?e, "Failed to update database with new cursor. When you just started this, ensure that the migrations included this domain.")
             } else {
                 debug!(cursor = ?*inner, "Updated cursor")
             }
         }
     }
+
+    /// Moves the cursor forward to `height` and saves it right away, e.g.
+    /// when the blocks before it are scraped by backfill workers instead.
+    #[instrument(skip(self), fields(cursor = ?self.inner))]
+    pub async fn skip_to(&self, height: u64) -> Result<()> {
+        let mut inner = self.inner.write().await;
+        if height <= inner.height {
+            return Ok(());
+        }
+        self.save(height).await?;
+        inner.height = height;
+        inner.last_saved_at = Instant::now();
+        info!(height, "Skipped cursor ahead");
+        Ok(())
+    }
+
+    async fn save(&self, height: u64) -> Result<()> {
+        let model = cursor::ActiveModel {
+            id: ActiveValue::NotSet,
+            domain: ActiveValue::Set(self.domain as i32),
+            time_created: ActiveValue::NotSet,
+            height: ActiveValue::Set(height as i64),
+        };
+        debug!(?model, "Inserting cursor");
+        Insert::one(model).exec(&self.db).await?;
+        Ok(())
+    }
 }
 
 impl ScraperDb {
@@ -0,0 +1,86 @@
+use eyre::Result;
+use itertools::Itertools;
+use sea_orm::{prelude::*, ActiveValue::*, Insert};
+use tracing::{instrument, trace};
+
+use migration::OnConflict;
+
+use crate::chain_scraper::DeliveryIsm;
+use crate::conversions::{address_to_bytes, h256_to_bytes};
+use crate::date_time;
+use crate::db::ScraperDb;
+
+use super::generated::{delivery_ism, delivery_signature};
+
+impl ScraperDb {
+    /// Store the ISMs messages were delivered with and the validator
+    /// signatures they were verified with (or update existing ones).
+    #[instrument(skip_all)]
+    pub async fn store_delivery_isms(&self, domain: u32, isms: &[DeliveryIsm]) -> Result<()> {
+        if isms.is_empty() {
+            return Ok(());
+        }
+        let models = isms
+            .iter()
+            .map(|delivery| delivery_ism::ActiveModel {
+                id: NotSet,
+                time_created: Set(date_time::now()),
+                msg_id: Unchanged(h256_to_bytes(&delivery.message_id)),
+                domain: Unchanged(domain as i32),
+                ism: Set(address_to_bytes(&delivery.ism)),
+                module_type: Set(delivery.module_type.map(|t| t as i16)),
+                metadata: Set(delivery.metadata.clone()),
+            })
+            .collect_vec();
+        trace!(?models, "Writing delivery ISMs to database");
+
+        Insert::many(models)
+            .on_conflict(
+                OnConflict::columns([delivery_ism::Column::MsgId])
+                    .update_columns([
+                        delivery_ism::Column::TimeCreated,
+                        delivery_ism::Column::Ism,
+                        delivery_ism::Column::ModuleType,
+                        delivery_ism::Column::Metadata,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.0)
+            .await?;
+
+        let signatures = isms
+            .iter()
+            .flat_map(|delivery| {
+                delivery.signatures.iter().map(|(validator, signature)| {
+                    delivery_signature::ActiveModel {
+                        id: NotSet,
+                        time_created: Set(date_time::now()),
+                        msg_id: Unchanged(h256_to_bytes(&delivery.message_id)),
+                        validator: Unchanged(validator.as_bytes().to_vec()),
+                        signature: Set(signature.to_vec()),
+                    }
+                })
+            })
+            .collect_vec();
+        if signatures.is_empty() {
+            return Ok(());
+        }
+        trace!(?signatures, "Writing delivery signatures to database");
+
+        Insert::many(signatures)
+            .on_conflict(
+                OnConflict::columns([
+                    delivery_signature::Column::MsgId,
+                    delivery_signature::Column::Validator,
+                ])
+                .update_columns([
+                    delivery_signature::Column::TimeCreated,
+                    delivery_signature::Column::Signature,
+                ])
+                .to_owned(),
+            )
+            .exec(&self.0)
+            .await?;
+        Ok(())
+    }
+}
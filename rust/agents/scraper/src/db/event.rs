@@ -0,0 +1,127 @@
+use eyre::Result;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+use tracing::instrument;
+
+use crate::db::ScraperDb;
+
+/// A dispatch, gas payment or delivery of a message, with the route of the
+/// message if it's known
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct MessageEventRecord {
+    pub id: i64,
+    pub msg_id: Vec<u8>,
+    /// The domain the event happened on
+    pub domain: i32,
+    pub origin: Option<i32>,
+    pub destination: Option<i32>,
+    pub nonce: Option<i32>,
+    pub sender: Option<Vec<u8>>,
+    pub recipient: Option<Vec<u8>>,
+    pub payment: Option<BigDecimal>,
+    pub gas_amount: Option<BigDecimal>,
+    pub tx_hash: Vec<u8>,
+    pub block_height: i64,
+    pub block_timestamp: TimeDateTime,
+}
+
+impl ScraperDb {
+    /// Get up to `limit` canonical dispatches after the one with `after_id`
+    /// that were stored before `stored_before`, in the order they were stored.
+    #[instrument(skip(self))]
+    pub async fn retrieve_dispatch_events(
+        &self,
+        after_id: i64,
+        stored_before: TimeDateTime,
+        limit: u64,
+    ) -> Result<Vec<MessageEventRecord>> {
+        let statement = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                "msg"."id", "msg"."msg_id", "msg"."origin" AS "domain", "msg"."origin",
+                "msg"."destination", "msg"."nonce", "msg"."sender", "msg"."recipient",
+                NULL::numeric AS "payment", NULL::numeric AS "gas_amount",
+                "tx"."hash" AS "tx_hash", "block"."height" AS "block_height",
+                "block"."timestamp" AS "block_timestamp"
+            FROM "message" AS "msg"
+                JOIN "transaction" AS "tx" ON "tx"."id" = "msg"."origin_tx_id"
+                JOIN "block" ON "block"."id" = "tx"."block_id"
+            WHERE "msg"."id" > $1 AND "msg"."time_created" < $2 AND "msg"."time_orphaned" IS NULL
+            ORDER BY "msg"."id"
+            LIMIT $3
+            "#,
+            [after_id.into(), stored_before.into(), (limit as i64).into()],
+        );
+        Ok(MessageEventRecord::find_by_statement(statement)
+            .all(&self.0)
+            .await?)
+    }
+
+    /// Get up to `limit` canonical gas payments after the one with
+    /// `after_id` that were stored before `stored_before`, in the order they
+    /// were stored.
+    #[instrument(skip(self))]
+    pub async fn retrieve_gas_payment_events(
+        &self,
+        after_id: i64,
+        stored_before: TimeDateTime,
+        limit: u64,
+    ) -> Result<Vec<MessageEventRecord>> {
+        let statement = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                "gp"."id", "gp"."msg_id", "gp"."domain", "msg"."origin", "msg"."destination",
+                "msg"."nonce", "msg"."sender", "msg"."recipient", "gp"."payment",
+                "gp"."gas_amount", "tx"."hash" AS "tx_hash", "block"."height" AS "block_height",
+                "block"."timestamp" AS "block_timestamp"
+            FROM "gas_payment" AS "gp"
+                JOIN "transaction" AS "tx" ON "tx"."id" = "gp"."tx_id"
+                JOIN "block" ON "block"."id" = "tx"."block_id"
+                LEFT JOIN "message" AS "msg"
+                    ON "msg"."msg_id" = "gp"."msg_id" AND "msg"."time_orphaned" IS NULL
+            WHERE "gp"."id" > $1 AND "gp"."time_created" < $2 AND "gp"."time_orphaned" IS NULL
+            ORDER BY "gp"."id"
+            LIMIT $3
+            "#,
+            [after_id.into(), stored_before.into(), (limit as i64).into()],
+        );
+        Ok(MessageEventRecord::find_by_statement(statement)
+            .all(&self.0)
+            .await?)
+    }
+
+    /// Get up to `limit` canonical deliveries after the one with `after_id`
+    /// that were stored before `stored_before`, in the order they were stored.
+    #[instrument(skip(self))]
+    pub async fn retrieve_delivery_events(
+        &self,
+        after_id: i64,
+        stored_before: TimeDateTime,
+        limit: u64,
+    ) -> Result<Vec<MessageEventRecord>> {
+        let statement = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                "dm"."id", "dm"."msg_id", "dm"."domain", "msg"."origin", "msg"."destination",
+                "msg"."nonce", "msg"."sender", "msg"."recipient",
+                NULL::numeric AS "payment", NULL::numeric AS "gas_amount",
+                "tx"."hash" AS "tx_hash", "block"."height" AS "block_height",
+                "block"."timestamp" AS "block_timestamp"
+            FROM "delivered_message" AS "dm"
+                JOIN "transaction" AS "tx" ON "tx"."id" = "dm"."destination_tx_id"
+                JOIN "block" ON "block"."id" = "tx"."block_id"
+                LEFT JOIN "message" AS "msg"
+                    ON "msg"."msg_id" = "dm"."msg_id" AND "msg"."time_orphaned" IS NULL
+            WHERE "dm"."id" > $1 AND "dm"."time_created" < $2 AND "dm"."time_orphaned" IS NULL
+            ORDER BY "dm"."id"
+            LIMIT $3
+            "#,
+            [after_id.into(), stored_before.into(), (limit as i64).into()],
+        );
+        Ok(MessageEventRecord::find_by_statement(statement)
+            .all(&self.0)
+            .await?)
+    }
+}
@@ -0,0 +1,182 @@
+use eyre::{Context, Result};
+use sea_orm::{prelude::*, ActiveValue::*, DbBackend, FromQueryResult, Insert, Statement};
+use tracing::instrument;
+
+use migration::OnConflict;
+
+use crate::{date_time, db::ScraperDb};
+
+use super::generated::export_cursor;
+
+/// A dispatched message as it's exported
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct MessageExport {
+    pub id: i64,
+    pub msg_id: Vec<u8>,
+    pub origin: i32,
+    pub destination: i32,
+    pub nonce: i32,
+    pub sender: Vec<u8>,
+    pub recipient: Vec<u8>,
+    pub msg_body: Option<Vec<u8>>,
+    pub origin_mailbox: Vec<u8>,
+    pub tx_hash: Vec<u8>,
+    pub block_height: i64,
+    pub block_timestamp: TimeDateTime,
+}
+
+/// A message delivery as it's exported
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct DeliveryExport {
+    pub id: i64,
+    pub msg_id: Vec<u8>,
+    pub domain: i32,
+    pub destination_mailbox: Vec<u8>,
+    pub tx_hash: Vec<u8>,
+    pub block_height: i64,
+    pub block_timestamp: TimeDateTime,
+}
+
+/// A gas payment as it's exported
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct GasPaymentExport {
+    pub id: i64,
+    pub msg_id: Vec<u8>,
+    pub domain: i32,
+    pub payment: BigDecimal,
+    pub gas_amount: BigDecimal,
+    pub log_index: i64,
+    pub tx_hash: Vec<u8>,
+    pub block_height: i64,
+    pub block_timestamp: TimeDateTime,
+}
+
+impl ScraperDb {
+    /// Get the ID of the last row of `dataset` that was exported, or 0 if
+    /// none was.
+    #[instrument(skip(self))]
+    pub async fn retrieve_exported_id(&self, dataset: &str) -> Result<i64> {
+        Ok(export_cursor::Entity::find()
+            .filter(export_cursor::Column::Dataset.eq(dataset))
+            .one(&self.0)
+            .await?
+            .map(|cursor| cursor.last_id)
+            .unwrap_or(0))
+    }
+
+    /// Record that `dataset` was exported up to the row with `last_id`.
+    #[instrument(skip(self))]
+    pub async fn store_exported_id(&self, dataset: &str, last_id: i64) -> Result<()> {
+        let model = export_cursor::ActiveModel {
+            id: NotSet,
+            time_updated: Set(date_time::now()),
+            dataset: Set(dataset.to_owned()),
+            last_id: Set(last_id),
+        };
+        Insert::one(model)
+            .on_conflict(
+                OnConflict::column(export_cursor::Column::Dataset)
+                    .update_columns([
+                        export_cursor::Column::TimeUpdated,
+                        export_cursor::Column::LastId,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.0)
+            .await
+            .context("When storing export cursor")?;
+        Ok(())
+    }
+
+    /// Get up to `limit` canonical messages after the one with `after_id`
+    /// that were stored before `stored_before`, in the order they were stored.
+    #[instrument(skip(self))]
+    pub async fn retrieve_message_exports(
+        &self,
+        after_id: i64,
+        stored_before: TimeDateTime,
+        limit: u64,
+    ) -> Result<Vec<MessageExport>> {
+        let statement = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                "msg"."id", "msg"."msg_id", "msg"."origin", "msg"."destination", "msg"."nonce",
+                "msg"."sender", "msg"."recipient", "msg"."msg_body", "msg"."origin_mailbox",
+                "tx"."hash" AS "tx_hash", "block"."height" AS "block_height",
+                "block"."timestamp" AS "block_timestamp"
+            FROM "message" AS "msg"
+                JOIN "transaction" AS "tx" ON "tx"."id" = "msg"."origin_tx_id"
+                JOIN "block" ON "block"."id" = "tx"."block_id"
+            WHERE "msg"."id" > $1 AND "msg"."time_created" < $2 AND "msg"."time_orphaned" IS NULL
+            ORDER BY "msg"."id"
+            LIMIT $3
+            "#,
+            [after_id.into(), stored_before.into(), (limit as i64).into()],
+        );
+        Ok(MessageExport::find_by_statement(statement)
+            .all(&self.0)
+            .await?)
+    }
+
+    /// Get up to `limit` canonical deliveries after the one with `after_id`
+    /// that were stored before `stored_before`, in the order they were stored.
+    #[instrument(skip(self))]
+    pub async fn retrieve_delivery_exports(
+        &self,
+        after_id: i64,
+        stored_before: TimeDateTime,
+        limit: u64,
+    ) -> Result<Vec<DeliveryExport>> {
+        let statement = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                "dm"."id", "dm"."msg_id", "dm"."domain", "dm"."destination_mailbox",
+                "tx"."hash" AS "tx_hash", "block"."height" AS "block_height",
+                "block"."timestamp" AS "block_timestamp"
+            FROM "delivered_message" AS "dm"
+                JOIN "transaction" AS "tx" ON "tx"."id" = "dm"."destination_tx_id"
+                JOIN "block" ON "block"."id" = "tx"."block_id"
+            WHERE "dm"."id" > $1 AND "dm"."time_created" < $2 AND "dm"."time_orphaned" IS NULL
+            ORDER BY "dm"."id"
+            LIMIT $3
+            "#,
+            [after_id.into(), stored_before.into(), (limit as i64).into()],
+        );
+        Ok(DeliveryExport::find_by_statement(statement)
+            .all(&self.0)
+            .await?)
+    }
+
+    /// Get up to `limit` canonical gas payments after the one with
+    /// `after_id` that were stored before `stored_before`, in the order they
+    /// were stored.
+    #[instrument(skip(self))]
+    pub async fn retrieve_gas_payment_exports(
+        &self,
+        after_id: i64,
+        stored_before: TimeDateTime,
+        limit: u64,
+    ) -> Result<Vec<GasPaymentExport>> {
+        let statement = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                "gp"."id", "gp"."msg_id", "gp"."domain", "gp"."payment", "gp"."gas_amount",
+                "gp"."log_index", "tx"."hash" AS "tx_hash", "block"."height" AS "block_height",
+                "block"."timestamp" AS "block_timestamp"
+            FROM "gas_payment" AS "gp"
+                JOIN "transaction" AS "tx" ON "tx"."id" = "gp"."tx_id"
+                JOIN "block" ON "block"."id" = "tx"."block_id"
+            WHERE "gp"."id" > $1 AND "gp"."time_created" < $2 AND "gp"."time_orphaned" IS NULL
+            ORDER BY "gp"."id"
+            LIMIT $3
+            "#,
+            [after_id.into(), stored_before.into(), (limit as i64).into()],
+        );
+        Ok(GasPaymentExport::find_by_statement(statement)
+            .all(&self.0)
+            .await?)
+    }
+}
@@ -19,6 +19,7 @@ pub struct Model {
     pub hash: Vec<u8>,
     pub height: i64,
     pub timestamp: TimeDateTime,
+    pub time_orphaned: Option<TimeDateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -29,6 +30,7 @@ pub enum Column {
     Hash,
     Height,
     Timestamp,
+    TimeOrphaned,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -59,6 +61,7 @@ impl ColumnTrait for Column {
             Self::Hash => ColumnType::Binary(BlobSize::Blob(None)).def().unique(),
             Self::Height => ColumnType::BigInteger.def(),
             Self::Timestamp => ColumnType::DateTime.def(),
+            Self::TimeOrphaned => ColumnType::DateTime.def().null(),
         }
     }
 }
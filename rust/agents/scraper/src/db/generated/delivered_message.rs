@@ -19,6 +19,7 @@ pub struct Model {
     pub domain: i32,
     pub destination_mailbox: Vec<u8>,
     pub destination_tx_id: i64,
+    pub time_orphaned: Option<TimeDateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -29,6 +30,7 @@ pub enum Column {
     Domain,
     DestinationMailbox,
     DestinationTxId,
+    TimeOrphaned,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -45,6 +47,8 @@ impl PrimaryKeyTrait for PrimaryKey {
 
 #[derive(Copy, Clone, Debug, EnumIter)]
 pub enum Relation {
+    DeliveryIsm,
+    DeliverySignature,
     Domain,
     Transaction,
 }
@@ -59,6 +63,7 @@ impl ColumnTrait for Column {
             Self::Domain => ColumnType::Integer.def(),
             Self::DestinationMailbox => ColumnType::Binary(BlobSize::Blob(None)).def(),
             Self::DestinationTxId => ColumnType::BigInteger.def(),
+            Self::TimeOrphaned => ColumnType::DateTime.def().null(),
         }
     }
 }
@@ -66,6 +71,8 @@ impl ColumnTrait for Column {
 impl RelationTrait for Relation {
     fn def(&self) -> RelationDef {
         match self {
+            Self::DeliveryIsm => Entity::has_many(super::delivery_ism::Entity).into(),
+            Self::DeliverySignature => Entity::has_many(super::delivery_signature::Entity).into(),
             Self::Domain => Entity::belongs_to(super::domain::Entity)
                 .from(Column::Domain)
                 .to(super::domain::Column::Id)
@@ -78,6 +85,18 @@ impl RelationTrait for Relation {
     }
 }
 
+impl Related<super::delivery_ism::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DeliveryIsm.def()
+    }
+}
+
+impl Related<super::delivery_signature::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DeliverySignature.def()
+    }
+}
+
 impl Related<super::domain::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Domain.def()
@@ -49,9 +49,11 @@ impl PrimaryKeyTrait for PrimaryKey {
 
 #[derive(Copy, Clone, Debug, EnumIter)]
 pub enum Relation {
+    BackfillShard,
     Block,
     Cursor,
     DeliveredMessage,
+    DeliveryIsm,
     GasPayment,
     Message,
 }
@@ -75,15 +77,23 @@ impl ColumnTrait for Column {
 impl RelationTrait for Relation {
     fn def(&self) -> RelationDef {
         match self {
+            Self::BackfillShard => Entity::has_many(super::backfill_shard::Entity).into(),
             Self::Block => Entity::has_many(super::block::Entity).into(),
             Self::Cursor => Entity::has_many(super::cursor::Entity).into(),
             Self::DeliveredMessage => Entity::has_many(super::delivered_message::Entity).into(),
+            Self::DeliveryIsm => Entity::has_many(super::delivery_ism::Entity).into(),
             Self::GasPayment => Entity::has_many(super::gas_payment::Entity).into(),
             Self::Message => Entity::has_many(super::message::Entity).into(),
         }
     }
 }
 
+impl Related<super::backfill_shard::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BackfillShard.def()
+    }
+}
+
 impl Related<super::block::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Block.def()
@@ -102,6 +112,12 @@ impl Related<super::delivered_message::Entity> for Entity {
     }
 }
 
+impl Related<super::delivery_ism::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DeliveryIsm.def()
+    }
+}
+
 impl Related<super::gas_payment::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::GasPayment.def()
@@ -24,6 +24,7 @@ pub struct Model {
     pub msg_body: Option<Vec<u8>>,
     pub origin_mailbox: Vec<u8>,
     pub origin_tx_id: i64,
+    pub time_orphaned: Option<TimeDateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -39,6 +40,7 @@ pub enum Column {
     MsgBody,
     OriginMailbox,
     OriginTxId,
+    TimeOrphaned,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -74,6 +76,7 @@ impl ColumnTrait for Column {
             Self::MsgBody => ColumnType::Binary(BlobSize::Blob(None)).def().null(),
             Self::OriginMailbox => ColumnType::Binary(BlobSize::Blob(None)).def(),
             Self::OriginTxId => ColumnType::BigInteger.def(),
+            Self::TimeOrphaned => ColumnType::DateTime.def().null(),
         }
     }
 }
@@ -2,10 +2,14 @@
 
 pub mod prelude;
 
+pub mod backfill_shard;
 pub mod block;
 pub mod cursor;
 pub mod delivered_message;
+pub mod delivery_ism;
+pub mod delivery_signature;
 pub mod domain;
+pub mod export_cursor;
 pub mod gas_payment;
 pub mod message;
 pub mod transaction;
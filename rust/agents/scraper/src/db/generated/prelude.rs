@@ -1,8 +1,10 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.3
 
 pub use super::{
-    block::Entity as Block, cursor::Entity as Cursor,
-    delivered_message::Entity as DeliveredMessage, domain::Entity as Domain,
+    backfill_shard::Entity as BackfillShard, block::Entity as Block, cursor::Entity as Cursor,
+    delivered_message::Entity as DeliveredMessage, delivery_ism::Entity as DeliveryIsm,
+    delivery_signature::Entity as DeliverySignature, domain::Entity as Domain,
+    export_cursor::Entity as ExportCursor,
     gas_payment::Entity as GasPayment, message::Entity as Message,
     transaction::Entity as Transaction,
 };
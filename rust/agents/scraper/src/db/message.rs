@@ -43,6 +43,7 @@ impl ScraperDb {
         let last_nonce = message::Entity::find()
             .filter(message::Column::Origin.eq(origin_domain))
             .filter(message::Column::OriginMailbox.eq(address_to_bytes(origin_mailbox)))
+            .filter(message::Column::TimeOrphaned.is_null())
             .select_only()
             .column_as(message::Column::Nonce.max(), QueryAs::Nonce)
             .into_values::<i32, QueryAs>()
@@ -74,6 +75,7 @@ impl ScraperDb {
             .filter(message::Column::Origin.eq(origin_domain))
             .filter(message::Column::OriginMailbox.eq(address_to_bytes(origin_mailbox)))
             .filter(message::Column::Nonce.eq(nonce))
+            .filter(message::Column::TimeOrphaned.is_null())
             .one(&self.0)
             .await?
         {
@@ -109,6 +111,7 @@ impl ScraperDb {
             .filter(message::Column::Origin.eq(origin_domain))
             .filter(message::Column::OriginMailbox.eq(address_to_bytes(origin_mailbox)))
             .filter(message::Column::Nonce.eq(nonce))
+            .filter(message::Column::TimeOrphaned.is_null())
             .select_only()
             .column_as(message::Column::OriginTxId.max(), QueryAs::Nonce)
             .group_by(message::Column::Origin)
@@ -150,6 +153,7 @@ impl ScraperDb {
                 domain: Unchanged(domain as i32),
                 destination_mailbox: Unchanged(destination_mailbox.clone()),
                 destination_tx_id: Set(delivery.txn_id),
+                time_orphaned: Set(None),
             })
             .collect_vec();
 
@@ -162,6 +166,7 @@ impl ScraperDb {
                     .update_columns([
                         delivered_message::Column::TimeCreated,
                         delivered_message::Column::DestinationTxId,
+                        delivered_message::Column::TimeOrphaned,
                     ])
                     .to_owned(),
             )
@@ -217,6 +222,7 @@ impl ScraperDb {
                 }),
                 origin_mailbox: Unchanged(origin_mailbox.clone()),
                 origin_tx_id: Set(storable.txn_id),
+                time_orphaned: Set(None),
             })
             .collect_vec();
 
@@ -230,13 +236,16 @@ impl ScraperDb {
                     message::Column::Origin,
                     message::Column::Nonce,
                 ])
+                // After a reorg, the nonce may be reused by another message
                 .update_columns([
                     message::Column::TimeCreated,
+                    message::Column::MsgId,
                     message::Column::Destination,
                     message::Column::Sender,
                     message::Column::Recipient,
                     message::Column::MsgBody,
                     message::Column::OriginTxId,
+                    message::Column::TimeOrphaned,
                 ])
                 .to_owned(),
             )
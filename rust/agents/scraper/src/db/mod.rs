@@ -1,8 +1,12 @@
+pub use backfill_shard::*;
 pub use block::*;
 pub use block_cursor::BlockCursor;
+pub use event::*;
+pub use export::*;
 use eyre::Result;
 pub use message::*;
 pub use payment::*;
+pub use query::*;
 use sea_orm::{Database, DbConn};
 use tracing::instrument;
 pub use txn::*;
@@ -11,10 +15,15 @@ pub use txn::*;
 mod generated;
 
 // These modules implement additional functionality for the ScraperDb
+mod backfill_shard;
 mod block;
 mod block_cursor;
+mod delivery_ism;
+mod event;
+mod export;
 mod message;
 mod payment;
+mod query;
 mod txn;
 
 /// Database interface to the message explorer database for the scraper. This is
@@ -38,6 +38,7 @@ impl ScraperDb {
                 gas_amount: Set(u256_to_decimal(storable.payment.gas_amount)),
                 tx_id: Unchanged(storable.txn_id),
                 log_index: Unchanged(storable.meta.log_index.as_u64() as i64),
+                time_orphaned: Set(None),
             })
             .collect_vec();
 
@@ -56,6 +57,7 @@ impl ScraperDb {
                     gas_payment::Column::TimeCreated,
                     gas_payment::Column::Payment,
                     gas_payment::Column::GasAmount,
+                    gas_payment::Column::TimeOrphaned,
                 ])
                 .to_owned(),
             )
@@ -0,0 +1,151 @@
+use eyre::Result;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+use tracing::instrument;
+
+use hyperlane_core::H256;
+
+use crate::conversions::{address_to_bytes, h256_to_bytes};
+use crate::db::ScraperDb;
+
+/// A dispatched message as read from the `message_view`, with its delivery
+/// status and gas payments
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct MessageRecord {
+    pub msg_id: Vec<u8>,
+    pub nonce: i32,
+    pub origin_domain_id: i32,
+    pub destination_domain_id: i32,
+    pub sender: Vec<u8>,
+    pub recipient: Vec<u8>,
+    pub message_body: Option<Vec<u8>>,
+    pub origin_tx_hash: Option<Vec<u8>>,
+    pub send_occurred_at: Option<TimeDateTime>,
+    pub is_delivered: bool,
+    pub destination_tx_hash: Option<Vec<u8>>,
+    pub delivery_occurred_at: Option<TimeDateTime>,
+    pub num_payments: i64,
+    pub total_payment: BigDecimal,
+    pub total_gas_amount: BigDecimal,
+}
+
+/// A single gas payment for a message
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct GasPaymentRecord {
+    pub domain: i32,
+    pub payment: BigDecimal,
+    pub gas_amount: BigDecimal,
+    pub tx_hash: Vec<u8>,
+    pub log_index: i64,
+    pub occurred_at: TimeDateTime,
+}
+
+/// Statistics of the messages sent from an origin to a destination
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct RouteStatsRecord {
+    pub origin_domain_id: i32,
+    pub destination_domain_id: i32,
+    pub dispatched: i64,
+    pub delivered: i64,
+    pub total_payment: BigDecimal,
+    pub avg_delivery_latency_seconds: Option<f64>,
+}
+
+/// Which messages to look up. Unset fields match all messages.
+#[derive(Debug, Clone, Default)]
+pub struct MessageFilter {
+    pub msg_id: Option<H256>,
+    pub sender: Option<H256>,
+    pub recipient: Option<H256>,
+}
+
+impl ScraperDb {
+    /// Get the latest messages matching the filter, newest first.
+    #[instrument(skip(self))]
+    pub async fn retrieve_messages(
+        &self,
+        filter: &MessageFilter,
+        limit: u64,
+    ) -> Result<Vec<MessageRecord>> {
+        let statement = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                "msg_id", "nonce", "origin_domain_id", "destination_domain_id",
+                "sender", "recipient", "message_body", "origin_tx_hash", "send_occurred_at",
+                "is_delivered", "destination_tx_hash", "delivery_occurred_at",
+                "num_payments", "total_payment", "total_gas_amount"
+            FROM "message_view"
+            WHERE ($1::bytea IS NULL OR "msg_id" = $1)
+                AND ($2::bytea IS NULL OR "sender" = $2)
+                AND ($3::bytea IS NULL OR "recipient" = $3)
+            ORDER BY "id" DESC
+            LIMIT $4
+            "#,
+            [
+                filter.msg_id.as_ref().map(h256_to_bytes).into(),
+                filter.sender.as_ref().map(address_to_bytes).into(),
+                filter.recipient.as_ref().map(address_to_bytes).into(),
+                (limit as i64).into(),
+            ],
+        );
+        Ok(MessageRecord::find_by_statement(statement)
+            .all(&self.0)
+            .await?)
+    }
+
+    /// Get the gas payments made for a message, oldest first.
+    #[instrument(skip(self))]
+    pub async fn retrieve_gas_payments(&self, msg_id: &H256) -> Result<Vec<GasPaymentRecord>> {
+        let statement = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                "gp"."domain", "gp"."payment", "gp"."gas_amount", "tx"."hash" AS "tx_hash",
+                "gp"."log_index", "block"."timestamp" AS "occurred_at"
+            FROM "gas_payment" AS "gp"
+                JOIN "transaction" AS "tx" ON "tx"."id" = "gp"."tx_id"
+                JOIN "block" ON "block"."id" = "tx"."block_id"
+            WHERE "gp"."msg_id" = $1 AND "gp"."time_orphaned" IS NULL
+            ORDER BY "block"."timestamp", "gp"."log_index"
+            "#,
+            [h256_to_bytes(msg_id).into()],
+        );
+        Ok(GasPaymentRecord::find_by_statement(statement)
+            .all(&self.0)
+            .await?)
+    }
+
+    /// Get the statistics of each route, optionally only from `origin` or
+    /// to `destination`.
+    #[instrument(skip(self))]
+    pub async fn retrieve_route_stats(
+        &self,
+        origin: Option<u32>,
+        destination: Option<u32>,
+    ) -> Result<Vec<RouteStatsRecord>> {
+        let statement = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                "origin_domain_id",
+                "destination_domain_id",
+                COUNT(*) AS "dispatched",
+                COUNT(*) FILTER (WHERE "is_delivered") AS "delivered",
+                SUM("total_payment") AS "total_payment",
+                EXTRACT(EPOCH FROM AVG("delivery_latency"))::float8 AS "avg_delivery_latency_seconds"
+            FROM "message_view"
+            WHERE ($1::integer IS NULL OR "origin_domain_id" = $1)
+                AND ($2::integer IS NULL OR "destination_domain_id" = $2)
+            GROUP BY "origin_domain_id", "destination_domain_id"
+            ORDER BY "origin_domain_id", "destination_domain_id"
+            "#,
+            [
+                origin.map(|origin| origin as i32).into(),
+                destination.map(|destination| destination as i32).into(),
+            ],
+        );
+        Ok(RouteStatsRecord::find_by_statement(statement)
+            .all(&self.0)
+            .await?)
+    }
+}
@@ -4,12 +4,12 @@ use derive_more::Deref;
 use eyre::{eyre, Context, Result};
 use hyperlane_core::{TxnInfo, H256};
 use sea_orm::{
-    prelude::*, sea_query::OnConflict, ActiveValue::*, DeriveColumn, EnumIter, Insert, NotSet,
-    QuerySelect,
+    prelude::*, sea_query::OnConflict, ActiveValue::*, DeriveColumn, EnumIter, Insert, JoinType,
+    NotSet, QuerySelect, RelationTrait,
 };
 use tracing::{debug, instrument, trace};
 
-use super::generated::transaction;
+use super::generated::{block, transaction};
 use crate::{
     conversions::{address_to_bytes, h256_to_bytes, u256_to_decimal},
     date_time,
@@ -40,7 +40,7 @@ impl ScraperDb {
     }
 
     /// Lookup transactions and find their ids. Any transactions which are not
-    /// found be excluded from the hashmap.
+    /// found, or only in orphaned blocks, be excluded from the hashmap.
     pub async fn get_txn_ids(
         &self,
         hashes: impl Iterator<Item = &H256>,
@@ -54,6 +54,8 @@ impl ScraperDb {
         // check database to see which txns we already know and fetch their IDs
         let txns = transaction::Entity::find()
             .filter(transaction::Column::Hash.is_in(hashes.map(h256_to_bytes)))
+            .join(JoinType::InnerJoin, transaction::Relation::Block.def())
+            .filter(block::Column::TimeOrphaned.is_null())
             .select_only()
             .column_as(transaction::Column::Id, QueryAs::Id)
             .column_as(transaction::Column::Hash, QueryAs::Hash)
@@ -70,6 +72,8 @@ impl ScraperDb {
     }
 
     /// Store a new transaction into the database (or update an existing one).
+    /// Transactions of orphaned blocks that are included again are moved to
+    /// their new block.
     #[instrument(skip_all)]
     pub async fn store_txns(&self, txns: impl Iterator<Item = StorableTxn>) -> Result<()> {
         let models = txns
@@ -107,7 +111,13 @@ impl ScraperDb {
         match Insert::many(models)
             .on_conflict(
                 OnConflict::column(transaction::Column::Hash)
-                    .do_nothing()
+                    .update_columns([
+                        transaction::Column::TimeCreated,
+                        transaction::Column::BlockId,
+                        transaction::Column::GasUsed,
+                        transaction::Column::EffectiveGasPrice,
+                        transaction::Column::CumulativeGasUsed,
+                    ])
                     .to_owned(),
             )
             .exec(&self.0)
@@ -0,0 +1,445 @@
+//! Exports the scraped data as partitioned Parquet files to object storage,
+//! so that data warehouses can ingest it without access to the database.
+//!
+//! Each dataset is exported incrementally, in the order its rows were stored,
+//! once the rows are older than `delay`. By then, rows of blocks that were
+//! reorged are orphaned and left out. The files are partitioned by domain and
+//! the date of the block, e.g.
+//! `messages/domain=1/date=2024-01-31/1000-1999.parquet`. How far each dataset
+//! was exported is stored in the database, after the batch was written, so
+//! that rows are exported at least once.
+
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use arrow_array::{
+    ArrayRef, BinaryArray, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray,
+    UInt32Array,
+};
+use eyre::Result;
+use hyperlane_base::{settings::CheckpointSyncerConf, CoreMetrics, ObjectWriter};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use prometheus::IntCounterVec;
+use sea_orm::prelude::{BigDecimal, TimeDateTime};
+use time::Date;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::{
+    date_time,
+    db::{DeliveryExport, GasPaymentExport, MessageExport, ScraperDb},
+};
+
+/// Settings for exporting the scraped data to object storage
+#[derive(Debug, Clone)]
+pub struct ExportConf {
+    /// Where the files are written to
+    pub location: CheckpointSyncerConf,
+    /// How often new rows are exported
+    pub interval: Duration,
+    /// How long after being stored rows are exported, which should exceed
+    /// how long reorgs take to be detected
+    pub delay: Duration,
+    /// The maximum number of rows exported at once
+    pub batch_size: u32,
+}
+
+/// The datasets that are exported, each to its own folder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dataset {
+    Messages,
+    Deliveries,
+    GasPayments,
+}
+
+impl Dataset {
+    const ALL: [Dataset; 3] = [Self::Messages, Self::Deliveries, Self::GasPayments];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Messages => "messages",
+            Self::Deliveries => "deliveries",
+            Self::GasPayments => "gas_payments",
+        }
+    }
+}
+
+/// A row of a dataset
+trait ExportRow: Sized {
+    fn id(&self) -> i64;
+    /// The domain the row is partitioned by
+    fn domain(&self) -> i32;
+    fn block_timestamp(&self) -> TimeDateTime;
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch>;
+}
+
+/// Periodically exports the new rows of each dataset
+#[derive(Debug)]
+pub struct Exporter {
+    db: ScraperDb,
+    writer: Box<dyn ObjectWriter>,
+    interval: Duration,
+    delay: time::Duration,
+    batch_size: u32,
+    exported_rows: IntCounterVec,
+}
+
+impl Exporter {
+    pub async fn new(conf: &ExportConf, db: ScraperDb, metrics: &CoreMetrics) -> Result<Self> {
+        Ok(Self {
+            db,
+            writer: conf.location.build_object_writer().await?,
+            interval: conf.interval,
+            delay: conf.delay.try_into()?,
+            batch_size: conf.batch_size,
+            exported_rows: metrics.new_int_counter(
+                "scraper_exported_rows",
+                "Rows exported to object storage",
+                &["dataset"],
+            )?,
+        })
+    }
+
+    /// Exports the new rows of each dataset every `interval`, forever
+    pub async fn run(self) {
+        loop {
+            for dataset in Dataset::ALL {
+                if let Err(err) = self.export(dataset).await {
+                    warn!(?err, dataset = dataset.name(), "Failed to export dataset");
+                }
+            }
+            sleep(self.interval).await;
+        }
+    }
+
+    /// Exports batches of the dataset until no rows are left that are old
+    /// enough
+    async fn export(&self, dataset: Dataset) -> Result<()> {
+        loop {
+            let after_id = self.db.retrieve_exported_id(dataset.name()).await?;
+            let stored_before = date_time::now() - self.delay;
+            let limit = self.batch_size as u64;
+            let exported = match dataset {
+                Dataset::Messages => {
+                    let rows = self
+                        .db
+                        .retrieve_message_exports(after_id, stored_before, limit)
+                        .await?;
+                    self.export_batch(dataset, rows).await?
+                }
+                Dataset::Deliveries => {
+                    let rows = self
+                        .db
+                        .retrieve_delivery_exports(after_id, stored_before, limit)
+                        .await?;
+                    self.export_batch(dataset, rows).await?
+                }
+                Dataset::GasPayments => {
+                    let rows = self
+                        .db
+                        .retrieve_gas_payment_exports(after_id, stored_before, limit)
+                        .await?;
+                    self.export_batch(dataset, rows).await?
+                }
+            };
+            let Some((last_id, count)) = exported else {
+                return Ok(());
+            };
+            self.db.store_exported_id(dataset.name(), last_id).await?;
+            info!(dataset = dataset.name(), count, last_id, "Exported rows");
+            if count < self.batch_size as usize {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Writes a file for each partition of the rows, returning the ID of the
+    /// last row and how many were written, if any
+    async fn export_batch<R: ExportRow>(
+        &self,
+        dataset: Dataset,
+        rows: Vec<R>,
+    ) -> Result<Option<(i64, usize)>> {
+        let Some(last_id) = rows.last().map(ExportRow::id) else {
+            return Ok(None);
+        };
+        let count = rows.len();
+        for ((domain, date), rows) in partition(rows) {
+            let key = partition_key(
+                dataset,
+                domain,
+                date,
+                rows[0].id(),
+                rows[rows.len() - 1].id(),
+            );
+            let data = to_parquet(&R::record_batch(&rows)?)?;
+            debug!(key, rows = rows.len(), bytes = data.len(), "Writing export");
+            self.writer.write_object(&key, data).await?;
+            self.exported_rows
+                .with_label_values(&[dataset.name()])
+                .inc_by(rows.len() as u64);
+        }
+        Ok(Some((last_id, count)))
+    }
+}
+
+/// Groups the rows by domain and the date of their block, keeping their order
+fn partition<R: ExportRow>(rows: Vec<R>) -> BTreeMap<(i32, Date), Vec<R>> {
+    let mut partitions = BTreeMap::<_, Vec<R>>::new();
+    for row in rows {
+        partitions
+            .entry((row.domain(), row.block_timestamp().date()))
+            .or_default()
+            .push(row);
+    }
+    partitions
+}
+
+/// The key of the file with the rows from `first_id` to `last_id` of a
+/// partition, with Hive-style partition folders
+fn partition_key(dataset: Dataset, domain: i32, date: Date, first_id: i64, last_id: i64) -> String {
+    format!(
+        "{}/domain={domain}/date={date}/{first_id}-{last_id}.parquet",
+        dataset.name()
+    )
+}
+
+fn to_parquet(batch: &RecordBatch) -> Result<Vec<u8>> {
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut data = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut data, batch.schema(), Some(props))?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(data)
+}
+
+fn int64(values: impl Iterator<Item = i64>) -> ArrayRef {
+    Arc::new(Int64Array::from_iter_values(values))
+}
+
+/// Domains, nonces and other `u32`s, which are stored as `i32`s
+fn uint32(values: impl Iterator<Item = i32>) -> ArrayRef {
+    Arc::new(UInt32Array::from_iter_values(values.map(|v| v as u32)))
+}
+
+fn binary<'a>(values: impl Iterator<Item = &'a Vec<u8>>) -> ArrayRef {
+    Arc::new(BinaryArray::from_iter_values(values))
+}
+
+/// Decimals, which can exceed the precision of Parquet decimals
+fn decimal_strings<'a>(values: impl Iterator<Item = &'a BigDecimal>) -> ArrayRef {
+    Arc::new(StringArray::from_iter_values(values.map(|v| v.to_string())))
+}
+
+fn timestamps(values: impl Iterator<Item = TimeDateTime>) -> ArrayRef {
+    Arc::new(
+        TimestampMicrosecondArray::from_iter_values(
+            values.map(|ts| (ts.assume_utc().unix_timestamp_nanos() / 1_000) as i64),
+        )
+        .with_timezone("UTC"),
+    )
+}
+
+impl ExportRow for MessageExport {
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn domain(&self) -> i32 {
+        self.origin
+    }
+
+    fn block_timestamp(&self) -> TimeDateTime {
+        self.block_timestamp
+    }
+
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        let body: ArrayRef = Arc::new(BinaryArray::from_iter(
+            rows.iter().map(|r| r.msg_body.as_deref()),
+        ));
+        Ok(RecordBatch::try_from_iter_with_nullable([
+            ("id", int64(rows.iter().map(|r| r.id)), false),
+            ("msg_id", binary(rows.iter().map(|r| &r.msg_id)), false),
+            ("origin", uint32(rows.iter().map(|r| r.origin)), false),
+            (
+                "destination",
+                uint32(rows.iter().map(|r| r.destination)),
+                false,
+            ),
+            ("nonce", uint32(rows.iter().map(|r| r.nonce)), false),
+            ("sender", binary(rows.iter().map(|r| &r.sender)), false),
+            (
+                "recipient",
+                binary(rows.iter().map(|r| &r.recipient)),
+                false,
+            ),
+            ("body", body, true),
+            (
+                "origin_mailbox",
+                binary(rows.iter().map(|r| &r.origin_mailbox)),
+                false,
+            ),
+            ("tx_hash", binary(rows.iter().map(|r| &r.tx_hash)), false),
+            (
+                "block_height",
+                int64(rows.iter().map(|r| r.block_height)),
+                false,
+            ),
+            (
+                "block_timestamp",
+                timestamps(rows.iter().map(|r| r.block_timestamp)),
+                false,
+            ),
+        ])?)
+    }
+}
+
+impl ExportRow for DeliveryExport {
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn domain(&self) -> i32 {
+        self.domain
+    }
+
+    fn block_timestamp(&self) -> TimeDateTime {
+        self.block_timestamp
+    }
+
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_from_iter_with_nullable([
+            ("id", int64(rows.iter().map(|r| r.id)), false),
+            ("msg_id", binary(rows.iter().map(|r| &r.msg_id)), false),
+            ("domain", uint32(rows.iter().map(|r| r.domain)), false),
+            (
+                "destination_mailbox",
+                binary(rows.iter().map(|r| &r.destination_mailbox)),
+                false,
+            ),
+            ("tx_hash", binary(rows.iter().map(|r| &r.tx_hash)), false),
+            (
+                "block_height",
+                int64(rows.iter().map(|r| r.block_height)),
+                false,
+            ),
+            (
+                "block_timestamp",
+                timestamps(rows.iter().map(|r| r.block_timestamp)),
+                false,
+            ),
+        ])?)
+    }
+}
+
+impl ExportRow for GasPaymentExport {
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn domain(&self) -> i32 {
+        self.domain
+    }
+
+    fn block_timestamp(&self) -> TimeDateTime {
+        self.block_timestamp
+    }
+
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_from_iter_with_nullable([
+            ("id", int64(rows.iter().map(|r| r.id)), false),
+            ("msg_id", binary(rows.iter().map(|r| &r.msg_id)), false),
+            ("domain", uint32(rows.iter().map(|r| r.domain)), false),
+            (
+                "payment",
+                decimal_strings(rows.iter().map(|r| &r.payment)),
+                false,
+            ),
+            (
+                "gas_amount",
+                decimal_strings(rows.iter().map(|r| &r.gas_amount)),
+                false,
+            ),
+            ("log_index", int64(rows.iter().map(|r| r.log_index)), false),
+            ("tx_hash", binary(rows.iter().map(|r| &r.tx_hash)), false),
+            (
+                "block_height",
+                int64(rows.iter().map(|r| r.block_height)),
+                false,
+            ),
+            (
+                "block_timestamp",
+                timestamps(rows.iter().map(|r| r.block_timestamp)),
+                false,
+            ),
+        ])?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delivery(id: i64, domain: i32, timestamp: u64) -> DeliveryExport {
+        DeliveryExport {
+            id,
+            msg_id: vec![id as u8; 32],
+            domain,
+            destination_mailbox: vec![0; 32],
+            tx_hash: vec![1; 32],
+            block_height: id,
+            block_timestamp: date_time::from_unix_timestamp_s(timestamp),
+        }
+    }
+
+    #[test]
+    fn test_partition() {
+        const DAY: u64 = 24 * 60 * 60;
+        let rows = vec![
+            delivery(1, 1, DAY),
+            delivery(2, 2, DAY),
+            delivery(3, 1, DAY + 1),
+            delivery(4, 1, 2 * DAY),
+        ];
+        let partitions = partition(rows)
+            .into_iter()
+            .map(|((domain, date), rows)| {
+                let key = partition_key(
+                    Dataset::Deliveries,
+                    domain,
+                    date,
+                    rows[0].id,
+                    rows[rows.len() - 1].id,
+                );
+                (key, rows.len())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            partitions,
+            vec![
+                (
+                    "deliveries/domain=1/date=1970-01-02/1-3.parquet".to_owned(),
+                    2
+                ),
+                (
+                    "deliveries/domain=1/date=1970-01-03/4-4.parquet".to_owned(),
+                    1
+                ),
+                (
+                    "deliveries/domain=2/date=1970-01-02/2-2.parquet".to_owned(),
+                    1
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_parquet() {
+        let batch = DeliveryExport::record_batch(&[delivery(1, 1, 0), delivery(2, 1, 0)]).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.num_columns(), 7);
+        let data = to_parquet(&batch).unwrap();
+        assert_eq!(&data[..4], b"PAR1");
+    }
+}
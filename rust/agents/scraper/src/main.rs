@@ -20,9 +20,14 @@ use hyperlane_base::agent_main;
 mod db;
 
 mod agent;
+mod backfill;
 mod chain_scraper;
 mod conversions;
 mod date_time;
+mod export;
+mod notify;
+mod reorg;
+mod server;
 mod settings;
 
 #[tokio::main(flavor = "current_thread")]
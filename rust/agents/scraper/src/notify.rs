@@ -0,0 +1,438 @@
+//! Publishes the lifecycle events of messages, i.e. their dispatch, gas
+//! payments and delivery, to webhooks, Kafka or NATS, so that apps can be
+//! notified without polling the scraper.
+//!
+//! Like the export, each sink follows the stored rows of each kind of event
+//! with a cursor in the database, so that events are published at least once,
+//! even across restarts. Events in blocks that were orphaned before they were
+//! published are left out.
+
+use std::{fmt::Debug, str::FromStr, time::Duration};
+
+use async_trait::async_trait;
+use ethers::utils::hex;
+use eyre::{bail, eyre, Result};
+use hyperlane_base::{settings::matching_list::MatchingList, CoreMetrics};
+use hyperlane_core::H256;
+use prometheus::IntCounterVec;
+use reqwest::Client;
+use serde::Serialize;
+use tokio::time::sleep;
+use tracing::{debug, warn};
+use url::Url;
+
+use crate::{
+    conversions::bytes_to_address,
+    date_time,
+    db::{MessageEventRecord, ScraperDb},
+};
+
+/// Rows are only published once they're this old, so that rows that were
+/// committed after rows with higher IDs aren't skipped
+const STORED_DELAY: time::Duration = time::Duration::seconds(5);
+/// The maximum number of events read at once
+const BATCH_SIZE: u64 = 1_000;
+/// The timeout for requests to webhooks
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Settings for publishing the lifecycle events of messages
+#[derive(Debug, Clone)]
+pub struct NotificationsConf {
+    /// How often new events are looked for
+    pub poll_interval: Duration,
+    pub sinks: Vec<SinkConf>,
+}
+
+/// Where events are published to, and which
+#[derive(Debug, Clone)]
+pub struct SinkConf {
+    /// Unique name of the sink, which its progress is stored under
+    pub name: String,
+    pub kind: SinkKind,
+    /// The kinds of events that are published
+    pub events: Vec<EventKind>,
+    /// Only events of matching messages are published. Events of messages
+    /// whose route is unknown, since their origin isn't scraped, are only
+    /// published if the list is empty.
+    pub matching_list: MatchingList,
+}
+
+/// The kind of sink events are published to
+#[derive(Debug, Clone)]
+pub enum SinkKind {
+    /// Each event is POSTed to the URL as JSON
+    Webhook { url: Url },
+    /// Each event is produced to the topic, keyed by the message ID. Requires
+    /// the `kafka` feature.
+    Kafka { brokers: String, topic: String },
+    /// Each event is published to `<subject>.<event>`. Requires the `nats`
+    /// feature.
+    Nats { url: String, subject: String },
+}
+
+/// A step in the lifecycle of a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Dispatched,
+    GasPaid,
+    Delivered,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 3] = [Self::Dispatched, Self::GasPaid, Self::Delivered];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Dispatched => "dispatched",
+            Self::GasPaid => "gas_paid",
+            Self::Delivered => "delivered",
+        }
+    }
+}
+
+impl FromStr for EventKind {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| eyre!("Unknown event `{s}`"))
+    }
+}
+
+/// An event as it's published
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MessageEvent {
+    pub event: EventKind,
+    pub msg_id: H256,
+    /// The domain the event happened on
+    pub domain: u32,
+    pub origin_domain: Option<u32>,
+    pub destination_domain: Option<u32>,
+    pub nonce: Option<u32>,
+    pub sender: Option<H256>,
+    pub recipient: Option<H256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_amount: Option<String>,
+    pub tx_hash: String,
+    pub block_height: u64,
+    /// Unix timestamp of the block the event happened in
+    pub occurred_at: i64,
+}
+
+impl MessageEvent {
+    fn from_record(event: EventKind, record: MessageEventRecord) -> Result<Self> {
+        Ok(Self {
+            event,
+            msg_id: H256::from_slice(&record.msg_id),
+            domain: record.domain as u32,
+            origin_domain: record.origin.map(|v| v as u32),
+            destination_domain: record.destination.map(|v| v as u32),
+            nonce: record.nonce.map(|v| v as u32),
+            sender: record.sender.map(bytes_to_address).transpose()?,
+            recipient: record.recipient.map(bytes_to_address).transpose()?,
+            payment: record.payment.map(|v| v.to_string()),
+            gas_amount: record.gas_amount.map(|v| v.to_string()),
+            tx_hash: format!("0x{}", hex::encode(&record.tx_hash)),
+            block_height: record.block_height as u64,
+            occurred_at: date_time::to_unix_timestamp_s(record.block_timestamp),
+        })
+    }
+
+    fn matches(&self, list: &MatchingList) -> bool {
+        match (
+            self.origin_domain,
+            &self.sender,
+            self.destination_domain,
+            &self.recipient,
+        ) {
+            (Some(origin), Some(sender), Some(destination), Some(recipient)) => {
+                list.route_matches(origin, sender, destination, recipient, true)
+            }
+            _ => list.is_empty(),
+        }
+    }
+}
+
+/// Somewhere events can be published to
+#[async_trait]
+trait EventSink: Debug + Send + Sync {
+    async fn publish(&self, event: &MessageEvent) -> Result<()>;
+}
+
+#[derive(Debug)]
+struct WebhookSink {
+    client: Client,
+    url: Url,
+}
+
+#[async_trait]
+impl EventSink for WebhookSink {
+    async fn publish(&self, event: &MessageEvent) -> Result<()> {
+        self.client
+            .post(self.url.clone())
+            .json(event)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "kafka")]
+struct KafkaSink {
+    producer: rdkafka::producer::FutureProducer,
+    topic: String,
+}
+
+#[cfg(feature = "kafka")]
+impl Debug for KafkaSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaSink")
+            .field("topic", &self.topic)
+            .finish()
+    }
+}
+
+#[cfg(feature = "kafka")]
+#[async_trait]
+impl EventSink for KafkaSink {
+    async fn publish(&self, event: &MessageEvent) -> Result<()> {
+        let payload = serde_json::to_vec(event)?;
+        let record = rdkafka::producer::FutureRecord::to(&self.topic)
+            .key(event.msg_id.as_bytes())
+            .payload(&payload);
+        self.producer
+            .send(record, Duration::ZERO)
+            .await
+            .map_err(|(err, _)| err)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "nats")]
+#[derive(Debug)]
+struct NatsSink {
+    client: async_nats::Client,
+    subject: String,
+}
+
+#[cfg(feature = "nats")]
+#[async_trait]
+impl EventSink for NatsSink {
+    async fn publish(&self, event: &MessageEvent) -> Result<()> {
+        let subject = format!("{}.{}", self.subject, event.event.name());
+        self.client
+            .publish(subject, serde_json::to_vec(event)?.into())
+            .await?;
+        self.client.flush().await?;
+        Ok(())
+    }
+}
+
+impl SinkKind {
+    async fn build(&self) -> Result<Box<dyn EventSink>> {
+        Ok(match self {
+            SinkKind::Webhook { url } => Box::new(WebhookSink {
+                client: Client::builder().timeout(WEBHOOK_TIMEOUT).build()?,
+                url: url.clone(),
+            }),
+            #[cfg(feature = "kafka")]
+            SinkKind::Kafka { brokers, topic } => Box::new(KafkaSink {
+                producer: rdkafka::ClientConfig::new()
+                    .set("bootstrap.servers", brokers)
+                    .set("message.timeout.ms", "10000")
+                    .create()?,
+                topic: topic.clone(),
+            }),
+            #[cfg(not(feature = "kafka"))]
+            SinkKind::Kafka { .. } => bail!("Kafka sinks require the `kafka` feature"),
+            #[cfg(feature = "nats")]
+            SinkKind::Nats { url, subject } => Box::new(NatsSink {
+                client: async_nats::connect(url.as_str()).await?,
+                subject: subject.clone(),
+            }),
+            #[cfg(not(feature = "nats"))]
+            SinkKind::Nats { .. } => bail!("NATS sinks require the `nats` feature"),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Sink {
+    name: String,
+    events: Vec<EventKind>,
+    matching_list: MatchingList,
+    inner: Box<dyn EventSink>,
+}
+
+/// Periodically publishes the new events to each sink
+#[derive(Debug)]
+pub struct Notifier {
+    db: ScraperDb,
+    poll_interval: Duration,
+    sinks: Vec<Sink>,
+    published_events: IntCounterVec,
+}
+
+impl Notifier {
+    pub async fn new(
+        conf: &NotificationsConf,
+        db: ScraperDb,
+        metrics: &CoreMetrics,
+    ) -> Result<Self> {
+        let mut sinks = Vec::with_capacity(conf.sinks.len());
+        for sink in &conf.sinks {
+            if sinks.iter().any(|s: &Sink| s.name == sink.name) {
+                bail!("Duplicate notification sink `{}`", sink.name);
+            }
+            sinks.push(Sink {
+                name: sink.name.clone(),
+                events: sink.events.clone(),
+                matching_list: sink.matching_list.clone(),
+                inner: sink.kind.build().await?,
+            });
+        }
+        Ok(Self {
+            db,
+            poll_interval: conf.poll_interval,
+            sinks,
+            published_events: metrics.new_int_counter(
+                "scraper_published_events",
+                "Message lifecycle events published to notification sinks",
+                &["sink", "event"],
+            )?,
+        })
+    }
+
+    /// Publishes the new events to each sink every `poll_interval`, forever
+    pub async fn run(self) {
+        loop {
+            for sink in &self.sinks {
+                for &event in &sink.events {
+                    if let Err(err) = self.notify(sink, event).await {
+                        warn!(
+                            ?err,
+                            sink = sink.name,
+                            event = event.name(),
+                            "Failed to publish events"
+                        );
+                    }
+                }
+            }
+            sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Publishes batches of events to the sink until none are left
+    async fn notify(&self, sink: &Sink, event: EventKind) -> Result<()> {
+        let cursor = format!("notifications/{}/{}", sink.name, event.name());
+        let published = self
+            .published_events
+            .with_label_values(&[&sink.name, event.name()]);
+        loop {
+            let after_id = self.db.retrieve_exported_id(&cursor).await?;
+            let stored_before = date_time::now() - STORED_DELAY;
+            let records = match event {
+                EventKind::Dispatched => {
+                    self.db
+                        .retrieve_dispatch_events(after_id, stored_before, BATCH_SIZE)
+                        .await?
+                }
+                EventKind::GasPaid => {
+                    self.db
+                        .retrieve_gas_payment_events(after_id, stored_before, BATCH_SIZE)
+                        .await?
+                }
+                EventKind::Delivered => {
+                    self.db
+                        .retrieve_delivery_events(after_id, stored_before, BATCH_SIZE)
+                        .await?
+                }
+            };
+            let Some(last_id) = records.last().map(|record| record.id) else {
+                return Ok(());
+            };
+            let count = records.len();
+            for record in records {
+                let message_event = MessageEvent::from_record(event, record)?;
+                if !message_event.matches(&sink.matching_list) {
+                    continue;
+                }
+                sink.inner.publish(&message_event).await?;
+                published.inc();
+            }
+            self.db.store_exported_id(&cursor, last_id).await?;
+            debug!(
+                sink = sink.name,
+                event = event.name(),
+                last_id,
+                "Published events"
+            );
+            if (count as u64) < BATCH_SIZE {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(origin: Option<i32>) -> MessageEventRecord {
+        MessageEventRecord {
+            id: 1,
+            msg_id: vec![1; 32],
+            domain: 2,
+            origin,
+            destination: origin.map(|_| 2),
+            nonce: origin.map(|_| 7),
+            sender: origin.map(|_| vec![3; 20]),
+            recipient: origin.map(|_| vec![4; 32]),
+            payment: None,
+            gas_amount: None,
+            tx_hash: vec![0xab; 32],
+            block_height: 100,
+            block_timestamp: date_time::from_unix_timestamp_s(1_700_000_000),
+        }
+    }
+
+    #[test]
+    fn test_event_from_record() {
+        let event = MessageEvent::from_record(EventKind::Delivered, record(Some(1))).unwrap();
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "delivered");
+        assert_eq!(json["origin_domain"], 1);
+        assert_eq!(json["occurred_at"], 1_700_000_000);
+        assert_eq!(json["tx_hash"], format!("0x{}", "ab".repeat(32)));
+        // Addresses are padded like in the query API
+        assert_eq!(
+            event.sender,
+            Some(H256::from_slice(&[[0; 12], [3; 20]].concat()))
+        );
+        assert!(json.get("payment").is_none());
+    }
+
+    #[test]
+    fn test_event_matches() {
+        let list: MatchingList = serde_json::from_str(r#"[{"origindomain": 1}]"#).unwrap();
+        let known = MessageEvent::from_record(EventKind::Delivered, record(Some(1))).unwrap();
+        let other = MessageEvent::from_record(EventKind::Delivered, record(Some(5))).unwrap();
+        let unknown = MessageEvent::from_record(EventKind::Delivered, record(None)).unwrap();
+        assert!(known.matches(&list));
+        assert!(!other.matches(&list));
+        assert!(!unknown.matches(&list));
+        assert!(unknown.matches(&MatchingList::default()));
+    }
+
+    #[test]
+    fn test_parse_event_kind() {
+        assert_eq!("gas_paid".parse::<EventKind>().unwrap(), EventKind::GasPaid);
+        assert!("paid".parse::<EventKind>().is_err());
+    }
+}
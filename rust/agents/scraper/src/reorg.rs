@@ -0,0 +1,255 @@
+//! Detects reorgs of the blocks the scraper already stored.
+//!
+//! The most recently scraped blocks are periodically scraped again. When the
+//! logs of a height now come from another block than the stored one, or no
+//! longer exist, the stored block was replaced on the canonical chain. It's
+//! marked as orphaned along with the dispatches, deliveries and gas payments in
+//! it, and the logs of the canonical blocks are stored in their place.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    ops::RangeInclusive,
+    sync::Arc,
+    time::Duration,
+};
+
+use eyre::{bail, Result};
+use hyperlane_base::{settings::ChainConf, CoreMetrics};
+use hyperlane_core::{
+    Delivery, HyperlaneLogStore, HyperlaneMessage, IndexMode, Indexed, Indexer,
+    InterchainGasPayment, LogMeta, SequenceAwareIndexer, H256,
+};
+use prometheus::{IntCounter, IntCounterVec};
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::{
+    chain_scraper::HyperlaneSqlDb,
+    db::{ScraperDb, StoredBlock},
+};
+
+/// Settings for detecting reorgs of the scraped blocks
+#[derive(Debug, Clone)]
+pub struct ReorgCheckConf {
+    /// How many of the most recently scraped blocks are checked. Zero
+    /// disables the check.
+    pub window: u32,
+    /// How often the blocks are checked
+    pub interval: Duration,
+}
+
+impl Default for ReorgCheckConf {
+    fn default() -> Self {
+        Self {
+            window: 128,
+            interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Reorgs detected across all chains
+#[derive(Debug, Clone)]
+pub struct ReorgMetrics {
+    orphaned_blocks: IntCounterVec,
+}
+
+impl ReorgMetrics {
+    pub fn new(metrics: &CoreMetrics) -> Result<Self> {
+        Ok(Self {
+            orphaned_blocks: metrics.new_int_counter(
+                "scraper_orphaned_blocks",
+                "Scraped blocks that were replaced by a reorg",
+                &["chain"],
+            )?,
+        })
+    }
+}
+
+/// Checks the recently scraped blocks of a single chain for reorgs
+#[derive(Debug)]
+pub struct ReorgDetector {
+    window: u32,
+    interval: Duration,
+    db: ScraperDb,
+    sql_db: HyperlaneSqlDb,
+    message_indexer: Arc<dyn SequenceAwareIndexer<HyperlaneMessage>>,
+    delivery_indexer: Arc<dyn SequenceAwareIndexer<Delivery>>,
+    gas_payment_indexer: Option<Arc<dyn SequenceAwareIndexer<InterchainGasPayment>>>,
+    orphaned_blocks: IntCounter,
+}
+
+/// The logs the scraper stores, emitted in a range of blocks
+#[derive(Debug, Default)]
+struct RangeLogs {
+    messages: Vec<(Indexed<HyperlaneMessage>, LogMeta)>,
+    deliveries: Vec<(Indexed<Delivery>, LogMeta)>,
+    gas_payments: Vec<(Indexed<InterchainGasPayment>, LogMeta)>,
+}
+
+impl RangeLogs {
+    /// The hash of the block each log was emitted in, by height
+    fn block_hashes(&self) -> HashMap<u64, H256> {
+        let metas = self
+            .messages
+            .iter()
+            .map(|(_, meta)| meta)
+            .chain(self.deliveries.iter().map(|(_, meta)| meta))
+            .chain(self.gas_payments.iter().map(|(_, meta)| meta));
+        metas
+            .map(|meta| (meta.block_number, meta.block_hash))
+            .collect()
+    }
+}
+
+impl ReorgDetector {
+    pub async fn new(
+        conf: &ReorgCheckConf,
+        chain_conf: &ChainConf,
+        db: ScraperDb,
+        sql_db: HyperlaneSqlDb,
+        metrics: &CoreMetrics,
+        reorg_metrics: &ReorgMetrics,
+    ) -> Result<Self> {
+        let chain = sql_db.domain().name();
+        if conf.window == 0 {
+            bail!("The reorg check window is zero");
+        }
+        // The scraped blocks are checked by querying their logs again, which
+        // chains indexed by sequence can't be queried by
+        if !matches!(chain_conf.index.mode, IndexMode::Block) {
+            bail!("Reorgs are only detected for chains indexed by block");
+        }
+        let message_indexer = chain_conf.build_message_indexer(metrics).await?.into();
+        let delivery_indexer = chain_conf.build_delivery_indexer(metrics).await?.into();
+        // Like for the live indexers, not all chains have an interchain gas
+        // paymaster indexer
+        let gas_payment_indexer = match chain_conf
+            .build_interchain_gas_payment_indexer(metrics)
+            .await
+        {
+            Ok(indexer) => Some(indexer.into()),
+            Err(err) => {
+                warn!(?err, chain, "Not checking gas payments for reorgs");
+                None
+            }
+        };
+        Ok(Self {
+            window: conf.window,
+            interval: conf.interval,
+            orphaned_blocks: reorg_metrics.orphaned_blocks.with_label_values(&[chain]),
+            db,
+            sql_db,
+            message_indexer,
+            delivery_indexer,
+            gas_payment_indexer,
+        })
+    }
+
+    /// Checks the most recently scraped blocks every `interval`, forever
+    pub async fn run(self) {
+        loop {
+            sleep(self.interval).await;
+            if let Err(err) = self.check().await {
+                warn!(?err, "Failed to check for reorgs");
+            }
+        }
+    }
+
+    async fn check(&self) -> Result<()> {
+        let to = self.sql_db.cursor_height().await;
+        let range = to.saturating_sub(self.window as u64 - 1)..=to;
+        let stored = self
+            .db
+            .retrieve_canonical_blocks(self.sql_db.domain().id(), range.clone())
+            .await?;
+        if stored.is_empty() {
+            return Ok(());
+        }
+        let logs = self.fetch_logs(*range.start() as u32..=to as u32).await?;
+        let orphaned = orphaned_blocks(&stored, &logs.block_hashes());
+        if orphaned.is_empty() {
+            debug!(?range, blocks = stored.len(), "No reorg detected");
+            return Ok(());
+        }
+
+        warn!(
+            ?range,
+            ?orphaned,
+            "Detected reorg, re-scraping orphaned blocks"
+        );
+        let ids = orphaned.iter().map(|block| block.id).collect::<Vec<_>>();
+        self.db.orphan_blocks(&ids).await?;
+        self.orphaned_blocks.inc_by(ids.len() as u64);
+        // Storing the logs is idempotent, so the ones that survived the reorg
+        // are only stored again
+        self.sql_db.store_logs(&logs.messages).await?;
+        self.sql_db.store_logs(&logs.deliveries).await?;
+        self.sql_db.store_logs(&logs.gas_payments).await?;
+        info!(?range, orphaned = ids.len(), "Re-scraped reorged blocks");
+        Ok(())
+    }
+
+    async fn fetch_logs(&self, range: RangeInclusive<u32>) -> Result<RangeLogs> {
+        let mut logs = RangeLogs {
+            messages: fetch_logs(&*self.message_indexer, range.clone()).await?,
+            deliveries: fetch_logs(&*self.delivery_indexer, range.clone()).await?,
+            ..Default::default()
+        };
+        if let Some(indexer) = &self.gas_payment_indexer {
+            logs.gas_payments = fetch_logs(&**indexer, range).await?;
+        }
+        Ok(logs)
+    }
+}
+
+async fn fetch_logs<T, I>(
+    indexer: &I,
+    range: RangeInclusive<u32>,
+) -> Result<Vec<(Indexed<T>, LogMeta)>>
+where
+    T: Hash + Eq,
+    I: Indexer<T> + ?Sized,
+{
+    let logs = indexer.fetch_logs_in_range(range).await?;
+    Ok(Vec::from_iter(HashSet::<_>::from_iter(logs)))
+}
+
+/// The stored blocks that aren't on the canonical chain anymore, given the
+/// canonical block hashes of the heights that have logs. Blocks are only
+/// stored for heights with logs, so a stored block whose height has none
+/// anymore was replaced too.
+fn orphaned_blocks<'a>(
+    stored: &'a [StoredBlock],
+    canonical: &HashMap<u64, H256>,
+) -> Vec<&'a StoredBlock> {
+    stored
+        .iter()
+        .filter(|block| canonical.get(&block.height) != Some(&block.hash))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orphaned_blocks() {
+        let block = |id, hash, height| StoredBlock {
+            id,
+            hash: H256::repeat_byte(hash),
+            height,
+        };
+        let stored = vec![block(1, 1, 10), block(2, 2, 11), block(3, 3, 12)];
+        let canonical = HashMap::from([
+            (10, H256::repeat_byte(1)),
+            (11, H256::repeat_byte(4)),
+            (13, H256::repeat_byte(5)),
+        ]);
+        assert_eq!(
+            orphaned_blocks(&stored, &canonical),
+            vec![&stored[1], &stored[2]]
+        );
+        assert!(orphaned_blocks(&stored[..1], &canonical).is_empty());
+    }
+}
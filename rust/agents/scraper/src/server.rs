@@ -0,0 +1,331 @@
+use std::str::FromStr;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing, Json, Router,
+};
+use derive_new::new;
+use ethers::utils::hex;
+use serde::{Deserialize, Serialize};
+
+use hyperlane_core::{H160, H256};
+
+use crate::conversions::bytes_to_address;
+use crate::date_time;
+use crate::db::{GasPaymentRecord, MessageFilter, MessageRecord, RouteStatsRecord, ScraperDb};
+
+const QUERY_API_BASE: &str = "/api";
+/// How many messages are returned when no limit is requested
+const DEFAULT_MESSAGES_LIMIT: u64 = 100;
+/// The most messages returned by a single request
+const MAX_MESSAGES_LIMIT: u64 = 1_000;
+
+type ApiError = (StatusCode, String);
+
+/// Returns a vector of scraper-specific endpoint routes to be served.
+pub fn routes(db: ScraperDb) -> Vec<(&'static str, Router)> {
+    vec![QueryApi::new(db).get_route()]
+}
+
+/// A dispatched message with its delivery status and gas payments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageResponse {
+    pub id: H256,
+    pub nonce: u32,
+    pub origin_domain: u32,
+    pub destination_domain: u32,
+    pub sender: H256,
+    pub recipient: H256,
+    pub body: Option<String>,
+    pub origin_tx_hash: Option<String>,
+    /// Unix timestamp of the block the message was dispatched in
+    pub dispatched_at: Option<i64>,
+    pub delivered: bool,
+    pub destination_tx_hash: Option<String>,
+    /// Unix timestamp of the block the message was delivered in
+    pub delivered_at: Option<i64>,
+    pub num_payments: u64,
+    pub total_payment: String,
+    pub total_gas_amount: String,
+}
+
+/// A single gas payment for a message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GasPaymentResponse {
+    pub domain: u32,
+    pub payment: String,
+    pub gas_amount: String,
+    pub tx_hash: String,
+    pub log_index: u64,
+    pub paid_at: i64,
+}
+
+/// Statistics of the messages sent from an origin to a destination
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteStatsResponse {
+    pub origin_domain: u32,
+    pub destination_domain: u32,
+    pub dispatched: u64,
+    pub delivered: u64,
+    pub pending: u64,
+    pub total_payment: String,
+    pub avg_delivery_latency_seconds: Option<f64>,
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn internal_error(err: eyre::Report) -> ApiError {
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+}
+
+fn parse_h256(value: &str, what: &str) -> Result<H256, ApiError> {
+    H256::from_str(value).map_err(|err| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Failed to parse {what}: {err}"),
+        )
+    })
+}
+
+/// Parses a 20 or 32 byte address
+fn parse_address(value: &str, what: &str) -> Result<H256, ApiError> {
+    match H160::from_str(value) {
+        Ok(address) => Ok(address.into()),
+        Err(_) => parse_h256(value, what),
+    }
+}
+
+impl TryFrom<MessageRecord> for MessageResponse {
+    type Error = eyre::Report;
+
+    fn try_from(record: MessageRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: H256::from_slice(&record.msg_id),
+            nonce: record.nonce as u32,
+            origin_domain: record.origin_domain_id as u32,
+            destination_domain: record.destination_domain_id as u32,
+            sender: bytes_to_address(record.sender)?,
+            recipient: bytes_to_address(record.recipient)?,
+            body: record.message_body.as_deref().map(hex_string),
+            origin_tx_hash: record.origin_tx_hash.as_deref().map(hex_string),
+            dispatched_at: record.send_occurred_at.map(date_time::to_unix_timestamp_s),
+            delivered: record.is_delivered,
+            destination_tx_hash: record.destination_tx_hash.as_deref().map(hex_string),
+            delivered_at: record
+                .delivery_occurred_at
+                .map(date_time::to_unix_timestamp_s),
+            num_payments: record.num_payments as u64,
+            total_payment: record.total_payment.to_string(),
+            total_gas_amount: record.total_gas_amount.to_string(),
+        })
+    }
+}
+
+impl From<GasPaymentRecord> for GasPaymentResponse {
+    fn from(record: GasPaymentRecord) -> Self {
+        Self {
+            domain: record.domain as u32,
+            payment: record.payment.to_string(),
+            gas_amount: record.gas_amount.to_string(),
+            tx_hash: hex_string(&record.tx_hash),
+            log_index: record.log_index as u64,
+            paid_at: date_time::to_unix_timestamp_s(record.occurred_at),
+        }
+    }
+}
+
+impl From<RouteStatsRecord> for RouteStatsResponse {
+    fn from(record: RouteStatsRecord) -> Self {
+        Self {
+            origin_domain: record.origin_domain_id as u32,
+            destination_domain: record.destination_domain_id as u32,
+            dispatched: record.dispatched as u64,
+            delivered: record.delivered as u64,
+            pending: record.dispatched.saturating_sub(record.delivered) as u64,
+            total_payment: record.total_payment.to_string(),
+            avg_delivery_latency_seconds: record.avg_delivery_latency_seconds,
+        }
+    }
+}
+
+/// Serves what the scraper indexed, so that explorers and dashboards don't
+/// have to query its database directly.
+///
+/// Routes:
+///  - `GET /messages?msg_id=&sender=&recipient=&limit=` - the latest matching messages
+///  - `GET /messages/:msg_id` - a message by its id
+///  - `GET /messages/:msg_id/gas_payments` - the gas payments made for a message
+///  - `GET /routes?origin=&destination=` - statistics of each route
+#[derive(new, Clone)]
+pub struct QueryApi {
+    db: ScraperDb,
+}
+
+#[derive(Deserialize)]
+struct RawMessagesRequest {
+    msg_id: Option<String>,
+    sender: Option<String>,
+    recipient: Option<String>,
+    limit: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RawRouteStatsRequest {
+    origin: Option<u32>,
+    destination: Option<u32>,
+}
+
+async fn messages(
+    State(db): State<ScraperDb>,
+    Query(request): Query<RawMessagesRequest>,
+) -> Result<Json<Vec<MessageResponse>>, ApiError> {
+    let filter = MessageFilter {
+        msg_id: request
+            .msg_id
+            .map(|msg_id| parse_h256(&msg_id, "message id"))
+            .transpose()?,
+        sender: request
+            .sender
+            .map(|sender| parse_address(&sender, "sender"))
+            .transpose()?,
+        recipient: request
+            .recipient
+            .map(|recipient| parse_address(&recipient, "recipient"))
+            .transpose()?,
+    };
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_MESSAGES_LIMIT)
+        .min(MAX_MESSAGES_LIMIT);
+    let records = db
+        .retrieve_messages(&filter, limit)
+        .await
+        .map_err(internal_error)?;
+    let messages = records
+        .into_iter()
+        .map(MessageResponse::try_from)
+        .collect::<Result<_, _>>()
+        .map_err(internal_error)?;
+    Ok(Json(messages))
+}
+
+async fn message(
+    State(db): State<ScraperDb>,
+    Path(msg_id): Path<String>,
+) -> Result<Json<MessageResponse>, ApiError> {
+    let filter = MessageFilter {
+        msg_id: Some(parse_h256(&msg_id, "message id")?),
+        ..Default::default()
+    };
+    let record = db
+        .retrieve_messages(&filter, 1)
+        .await
+        .map_err(internal_error)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "No message with this id".to_owned()))?;
+    Ok(Json(record.try_into().map_err(internal_error)?))
+}
+
+async fn gas_payments(
+    State(db): State<ScraperDb>,
+    Path(msg_id): Path<String>,
+) -> Result<Json<Vec<GasPaymentResponse>>, ApiError> {
+    let msg_id = parse_h256(&msg_id, "message id")?;
+    let payments = db
+        .retrieve_gas_payments(&msg_id)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(payments.into_iter().map(Into::into).collect()))
+}
+
+async fn route_stats(
+    State(db): State<ScraperDb>,
+    Query(request): Query<RawRouteStatsRequest>,
+) -> Result<Json<Vec<RouteStatsResponse>>, ApiError> {
+    let stats = db
+        .retrieve_route_stats(request.origin, request.destination)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(stats.into_iter().map(Into::into).collect()))
+}
+
+impl QueryApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/messages", routing::get(messages))
+            .route("/messages/:msg_id", routing::get(message))
+            .route("/messages/:msg_id/gas_payments", routing::get(gas_payments))
+            .route("/routes", routing::get(route_stats))
+            .with_state(self.db.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (QUERY_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::prelude::BigDecimal;
+
+    use super::*;
+
+    #[test]
+    fn test_route_stats_response() {
+        let response = RouteStatsResponse::from(RouteStatsRecord {
+            origin_domain_id: 1,
+            destination_domain_id: 2,
+            dispatched: 10,
+            delivered: 7,
+            total_payment: BigDecimal::from(1_000),
+            avg_delivery_latency_seconds: Some(12.5),
+        });
+        assert_eq!(response.pending, 3);
+        assert_eq!(response.total_payment, "1000");
+    }
+
+    #[test]
+    fn test_message_response_pads_addresses() {
+        let record = MessageRecord {
+            msg_id: vec![1; 32],
+            nonce: 5,
+            origin_domain_id: 1,
+            destination_domain_id: 2,
+            sender: vec![2; 20],
+            recipient: vec![3; 32],
+            message_body: Some(vec![0xab]),
+            origin_tx_hash: Some(vec![4; 32]),
+            send_occurred_at: Some(date_time::from_unix_timestamp_s(1_000)),
+            is_delivered: false,
+            destination_tx_hash: None,
+            delivery_occurred_at: None,
+            num_payments: 0,
+            total_payment: BigDecimal::from(0),
+            total_gas_amount: BigDecimal::from(0),
+        };
+        let response = MessageResponse::try_from(record).unwrap();
+        assert_eq!(response.id, H256::repeat_byte(1));
+        assert_eq!(&response.sender.as_bytes()[..12], &[0; 12]);
+        assert_eq!(response.recipient, H256::repeat_byte(3));
+        assert_eq!(response.body.as_deref(), Some("0xab"));
+        assert_eq!(response.dispatched_at, Some(1_000));
+    }
+
+    #[test]
+    fn test_parse_address() {
+        let address = "0x0202020202020202020202020202020202020202";
+        assert_eq!(
+            parse_address(address, "sender").unwrap(),
+            H256::from(H160::repeat_byte(2))
+        );
+        assert_eq!(
+            parse_address(&format!("{:?}", H256::repeat_byte(3)), "sender").unwrap(),
+            H256::repeat_byte(3)
+        );
+        assert!(parse_address("0x1234", "sender").is_err());
+    }
+}
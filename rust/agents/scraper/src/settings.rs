@@ -4,21 +4,28 @@
 //! and validations it defines are not applied here, we should mirror them.
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
-use std::{collections::HashSet, default::Default};
+use std::{collections::HashSet, default::Default, num::NonZeroU32, str::FromStr, time::Duration};
 
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
-use eyre::Context;
+use eyre::{eyre, Context};
 use hyperlane_base::{
     impl_loadable_from_settings,
     settings::{
-        parser::{RawAgentConf, ValueParser},
-        Settings,
+        parser::{parse_matching_list, RawAgentConf, ValueParser},
+        CheckpointSyncerConf, Settings,
     },
 };
 use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain};
 use serde::Deserialize;
 use serde_json::Value;
 
+use crate::{
+    backfill::BackfillConf,
+    export::ExportConf,
+    notify::{EventKind, NotificationsConf, SinkConf, SinkKind},
+    reorg::ReorgCheckConf,
+};
+
 /// Settings for `Scraper`
 #[derive(Debug, AsRef, AsMut, Deref, DerefMut)]
 pub struct ScraperSettings {
@@ -30,6 +37,14 @@ pub struct ScraperSettings {
 
     pub db: String,
     pub chains_to_scrape: Vec<HyperlaneDomain>,
+    /// Backfill the history of the scraped chains with parallel workers
+    pub backfill: Option<BackfillConf>,
+    /// Detect reorgs of the recently scraped blocks
+    pub reorg_check: ReorgCheckConf,
+    /// Export the scraped data to object storage as Parquet files
+    pub export: Option<ExportConf>,
+    /// Publish the lifecycle events of messages to webhooks, Kafka or NATS
+    pub notifications: Option<NotificationsConf>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -83,12 +98,239 @@ impl FromRawConf<RawScraperSettings> for ScraperSettings {
             Default::default()
         };
 
+        let backfill = p
+            .chain(&mut err)
+            .get_opt_key("backfill")
+            .and_then(parse_backfill)
+            .end();
+
+        let reorg_check = p
+            .chain(&mut err)
+            .get_opt_key("reorgCheck")
+            .and_then(parse_reorg_check)
+            .unwrap_or_default();
+
+        let export = p
+            .chain(&mut err)
+            .get_opt_key("export")
+            .and_then(parse_export)
+            .end();
+
+        let notifications = p
+            .chain(&mut err)
+            .get_opt_key("notifications")
+            .and_then(parse_notifications)
+            .end();
+
         cfg_unwrap_all!(&p.cwp, err: [base, db]);
 
         err.into_result(Self {
             base,
             db,
             chains_to_scrape,
+            backfill,
+            reorg_check,
+            export,
+            notifications,
         })
     }
 }
+
+/// Expects ScraperAgentConfig.backfill
+fn parse_backfill(backfill: ValueParser) -> ConfigResult<BackfillConf> {
+    let mut err = ConfigParsingError::default();
+    let workers = backfill
+        .chain(&mut err)
+        .get_opt_key("workers")
+        .parse_u32()
+        .unwrap_or(4);
+    let shard_size = backfill
+        .chain(&mut err)
+        .get_opt_key("shardSize")
+        .parse_u32()
+        .unwrap_or(100_000);
+
+    let workers = NonZeroU32::new(workers)
+        .ok_or_else(|| eyre!("Expected backfill.workers to be positive"))
+        .take_err(&mut err, || &backfill.cwp + "workers");
+    let shard_size = NonZeroU32::new(shard_size)
+        .ok_or_else(|| eyre!("Expected backfill.shardSize to be positive"))
+        .take_err(&mut err, || &backfill.cwp + "shard_size");
+
+    cfg_unwrap_all!(&backfill.cwp, err: [workers, shard_size]);
+    err.into_result(BackfillConf {
+        workers: workers.get(),
+        shard_size: shard_size.get(),
+    })
+}
+
+/// Expects ScraperAgentConfig.reorgCheck
+fn parse_reorg_check(reorg_check: ValueParser) -> ConfigResult<ReorgCheckConf> {
+    let mut err = ConfigParsingError::default();
+    let default = ReorgCheckConf::default();
+    let window = reorg_check
+        .chain(&mut err)
+        .get_opt_key("window")
+        .parse_u32()
+        .unwrap_or(default.window);
+    let interval = reorg_check
+        .chain(&mut err)
+        .get_opt_key("intervalSecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(default.interval);
+
+    err.into_result(ReorgCheckConf { window, interval })
+}
+
+/// Expects ScraperAgentConfig.export
+fn parse_export(export: ValueParser) -> ConfigResult<ExportConf> {
+    let mut err = ConfigParsingError::default();
+    let location = export
+        .chain(&mut err)
+        .get_key("location")
+        .parse_string()
+        .end()
+        .and_then(|location| {
+            CheckpointSyncerConf::from_str(location).take_err(&mut err, || &export.cwp + "location")
+        });
+    let interval = export
+        .chain(&mut err)
+        .get_opt_key("intervalSecs")
+        .parse_u64()
+        .unwrap_or(600);
+    let delay = export
+        .chain(&mut err)
+        .get_opt_key("delaySecs")
+        .parse_u64()
+        .unwrap_or(3600);
+    let batch_size = export
+        .chain(&mut err)
+        .get_opt_key("batchSize")
+        .parse_u32()
+        .unwrap_or(100_000);
+
+    let batch_size = NonZeroU32::new(batch_size)
+        .ok_or_else(|| eyre!("Expected export.batchSize to be positive"))
+        .take_err(&mut err, || &export.cwp + "batch_size");
+
+    cfg_unwrap_all!(&export.cwp, err: [location, batch_size]);
+    err.into_result(ExportConf {
+        location,
+        interval: Duration::from_secs(interval),
+        delay: Duration::from_secs(delay),
+        batch_size: batch_size.get(),
+    })
+}
+
+/// Expects ScraperAgentConfig.notifications
+fn parse_notifications(notifications: ValueParser) -> ConfigResult<NotificationsConf> {
+    let mut err = ConfigParsingError::default();
+    let poll_interval = notifications
+        .chain(&mut err)
+        .get_opt_key("pollIntervalSecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(5));
+    let sinks = notifications
+        .chain(&mut err)
+        .get_key("sinks")
+        .into_array_iter()
+        .map(|sinks| {
+            sinks
+                .filter_map(|sink| parse_sink(sink).take_config_err(&mut err))
+                .collect::<Vec<_>>()
+        });
+
+    cfg_unwrap_all!(&notifications.cwp, err: [sinks]);
+    err.into_result(NotificationsConf {
+        poll_interval,
+        sinks,
+    })
+}
+
+/// Expects ScraperAgentConfig.notifications.sinks[]
+fn parse_sink(sink: ValueParser) -> ConfigResult<SinkConf> {
+    let mut err = ConfigParsingError::default();
+    let name = sink
+        .chain(&mut err)
+        .get_key("name")
+        .parse_string()
+        .end()
+        .map(|v| v.to_owned());
+    let sink_type = sink.chain(&mut err).get_key("type").parse_string().end();
+    let kind = match sink_type {
+        Some("webhook") => sink
+            .chain(&mut err)
+            .get_key("url")
+            .parse_from_str("Expected a webhook url")
+            .end()
+            .map(|url| SinkKind::Webhook { url }),
+        Some("kafka") => {
+            let brokers = sink
+                .chain(&mut err)
+                .get_key("brokers")
+                .parse_string()
+                .end()
+                .map(|v| v.to_owned());
+            let topic = sink
+                .chain(&mut err)
+                .get_key("topic")
+                .parse_string()
+                .end()
+                .map(|v| v.to_owned());
+            brokers
+                .zip(topic)
+                .map(|(brokers, topic)| SinkKind::Kafka { brokers, topic })
+        }
+        Some("nats") => {
+            let url = sink
+                .chain(&mut err)
+                .get_key("url")
+                .parse_string()
+                .end()
+                .map(|v| v.to_owned());
+            let subject = sink
+                .chain(&mut err)
+                .get_key("subject")
+                .parse_string()
+                .end()
+                .map(|v| v.to_owned());
+            url.zip(subject)
+                .map(|(url, subject)| SinkKind::Nats { url, subject })
+        }
+        Some(t) => Err(eyre!("Unknown notification sink type `{t}`"))
+            .take_err(&mut err, || &sink.cwp + "type"),
+        None => None,
+    };
+    let events = sink
+        .chain(&mut err)
+        .get_opt_key("events")
+        .into_array_iter()
+        .map(|events| {
+            events
+                .filter_map(|event| {
+                    event
+                        .parse_string()
+                        .take_config_err(&mut err)
+                        .and_then(|s| {
+                            EventKind::from_str(s).take_err(&mut err, || event.cwp.clone())
+                        })
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_else(|| EventKind::ALL.to_vec());
+    let matching_list = sink
+        .chain(&mut err)
+        .get_opt_key("matchingList")
+        .and_then(parse_matching_list)
+        .unwrap_or_default();
+
+    cfg_unwrap_all!(&sink.cwp, err: [name, kind]);
+    err.into_result(SinkConf {
+        name,
+        kind,
+        events,
+        matching_list,
+    })
+}
@@ -0,0 +1,303 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use async_trait::async_trait;
+use eyre::{eyre, Result};
+use futures_util::future::join_all;
+use hyperlane_base::{CheckpointSyncer, CoreMetrics};
+use hyperlane_core::{HyperlaneDomain, SignedAnnouncement, SignedCheckpointWithMessageId};
+use prometheus::{IntCounter, IntGauge};
+use tracing::warn;
+
+/// The most checkpoints that are kept to be written again to a backend
+/// that failed to store them. Older ones are dropped, since relayers mostly
+/// need recent checkpoints.
+const MAX_MISSED_CHECKPOINTS: usize = 1_000;
+
+/// One of the checkpoint syncers that checkpoints are written to
+struct Backend {
+    syncer: Arc<dyn CheckpointSyncer>,
+    /// The checkpoints the backend failed to store, by index
+    missed: Mutex<BTreeMap<u32, SignedCheckpointWithMessageId>>,
+    /// The latest index written to the backend
+    latest_index: AtomicU32,
+    lag: IntGauge,
+    failures: IntCounter,
+}
+
+impl Backend {
+    fn location(&self) -> String {
+        self.syncer.announcement_location()
+    }
+
+    /// Write `signed_checkpoint`, after the checkpoints the backend failed
+    /// to store before
+    async fn write_checkpoint(&self, signed_checkpoint: &SignedCheckpointWithMessageId) -> bool {
+        let missed = {
+            let mut missed = self
+                .missed
+                .lock()
+                .expect("missed checkpoints lock poisoned");
+            missed.insert(signed_checkpoint.value.index, signed_checkpoint.clone());
+            while missed.len() > MAX_MISSED_CHECKPOINTS {
+                missed.pop_first();
+            }
+            missed.values().cloned().collect::<Vec<_>>()
+        };
+        for checkpoint in missed {
+            if let Err(err) = self.syncer.write_checkpoint(&checkpoint).await {
+                warn!(
+                    ?err,
+                    location = self.location(),
+                    index = checkpoint.value.index,
+                    "Failed to write checkpoint to checkpoint syncer"
+                );
+                self.failures.inc();
+                return false;
+            }
+            self.missed
+                .lock()
+                .expect("missed checkpoints lock poisoned")
+                .remove(&checkpoint.value.index);
+        }
+        true
+    }
+}
+
+/// Writes each signed checkpoint to several checkpoint syncers at once, so
+/// that a validator stays visible to relayers while one of them is down.
+/// Writes succeed as long as one of the syncers succeeds. Checkpoints that
+/// a syncer failed to store are written to it again along with the next
+/// ones.
+pub(crate) struct FanoutCheckpointSyncer {
+    /// The primary syncer first
+    backends: Vec<Backend>,
+    /// The highest index written to any of the syncers
+    latest_index: AtomicU32,
+}
+
+impl Debug for FanoutCheckpointSyncer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.backends.iter().map(|backend| &backend.syncer))
+            .finish()
+    }
+}
+
+impl FanoutCheckpointSyncer {
+    pub(crate) fn new(
+        syncers: Vec<Arc<dyn CheckpointSyncer>>,
+        metrics: &CoreMetrics,
+        origin: &HyperlaneDomain,
+    ) -> Result<Self> {
+        let lag = metrics.new_int_gauge(
+            "checkpoint_syncer_lag",
+            "How many checkpoints the latest index written to a checkpoint syncer is behind the latest signed one",
+            &["chain", "location"],
+        )?;
+        let failures = metrics.new_int_counter(
+            "checkpoint_syncer_write_failures",
+            "Writes of checkpoints, latest indices and announcements to a checkpoint syncer that failed",
+            &["chain", "location"],
+        )?;
+        let backends = syncers
+            .into_iter()
+            .map(|syncer| {
+                let location = syncer.announcement_location();
+                let labels = [origin.name(), location.as_str()];
+                Backend {
+                    lag: lag.with_label_values(&labels),
+                    failures: failures.with_label_values(&labels),
+                    syncer,
+                    missed: Default::default(),
+                    latest_index: Default::default(),
+                }
+            })
+            .collect();
+        Ok(Self {
+            backends,
+            latest_index: Default::default(),
+        })
+    }
+
+    /// The syncers, the primary one first
+    pub(crate) fn syncers(&self) -> impl DoubleEndedIterator<Item = &Arc<dyn CheckpointSyncer>> {
+        self.backends.iter().map(|backend| &backend.syncer)
+    }
+
+    fn update_lag(&self) {
+        let latest_index = self.latest_index.load(Ordering::Relaxed);
+        for backend in &self.backends {
+            let lag = latest_index.saturating_sub(backend.latest_index.load(Ordering::Relaxed));
+            backend.lag.set(lag as i64);
+        }
+    }
+
+    /// Ok if any of the syncers succeeded
+    fn any_succeeded(results: Vec<Result<()>>, action: &str) -> Result<()> {
+        if results.iter().any(Result::is_ok) {
+            Ok(())
+        } else {
+            Err(eyre!("Failed to {action} to any checkpoint syncer"))
+        }
+    }
+}
+
+#[async_trait]
+impl CheckpointSyncer for FanoutCheckpointSyncer {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let results = join_all(
+            self.backends
+                .iter()
+                .map(|backend| backend.syncer.latest_index()),
+        )
+        .await;
+        let mut latest_index = None;
+        let mut first_err = None;
+        for result in results {
+            match result {
+                Ok(index) => latest_index = latest_index.max(index),
+                Err(err) => {
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+        match (latest_index, first_err) {
+            (None, Some(err)) => Err(err),
+            (latest_index, _) => Ok(latest_index),
+        }
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        let results = join_all(self.backends.iter().map(|backend| async move {
+            backend
+                .syncer
+                .write_latest_index(index)
+                .await
+                .map(|()| backend.latest_index.store(index, Ordering::Relaxed))
+                .map_err(|err| {
+                    warn!(
+                        ?err,
+                        location = backend.location(),
+                        index,
+                        "Failed to write latest index to checkpoint syncer"
+                    );
+                    backend.failures.inc();
+                    err
+                })
+        }))
+        .await;
+        self.latest_index.fetch_max(index, Ordering::Relaxed);
+        self.update_lag();
+        Self::any_succeeded(results, "write latest index")
+    }
+
+    async fn update_latest_index(&self, index: u32) -> Result<()> {
+        let results = join_all(self.backends.iter().map(|backend| async move {
+            backend
+                .syncer
+                .update_latest_index(index)
+                .await
+                .map(|()| backend.latest_index.fetch_max(index, Ordering::Relaxed))
+                .map(|_| ())
+                .map_err(|err| {
+                    warn!(
+                        ?err,
+                        location = backend.location(),
+                        index,
+                        "Failed to update latest index of checkpoint syncer"
+                    );
+                    backend.failures.inc();
+                    err
+                })
+        }))
+        .await;
+        self.latest_index.fetch_max(index, Ordering::Relaxed);
+        self.update_lag();
+        Self::any_succeeded(results, "update latest index")
+    }
+
+    /// The checkpoint, if all of the syncers stored it. Otherwise it's
+    /// signed and written again.
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        let results = join_all(
+            self.backends
+                .iter()
+                .map(|backend| backend.syncer.fetch_checkpoint(index)),
+        )
+        .await;
+        let mut checkpoint = None;
+        for result in results {
+            match result {
+                Ok(Some(fetched)) => {
+                    checkpoint.get_or_insert(fetched);
+                }
+                Ok(None) | Err(_) => return Ok(None),
+            }
+        }
+        Ok(checkpoint)
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        let written = join_all(
+            self.backends
+                .iter()
+                .map(|backend| backend.write_checkpoint(signed_checkpoint)),
+        )
+        .await;
+        if written.contains(&true) {
+            Ok(())
+        } else {
+            Err(eyre!("Failed to write checkpoint to any checkpoint syncer"))
+        }
+    }
+
+    /// Deletes the checkpoint from all of the syncers, failing if any of
+    /// them failed, so that it's deleted again
+    async fn delete_checkpoint(&self, index: u32) -> Result<()> {
+        let results = join_all(
+            self.backends
+                .iter()
+                .map(|backend| backend.syncer.delete_checkpoint(index)),
+        )
+        .await;
+        results.into_iter().collect()
+    }
+
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        let results = join_all(self.backends.iter().map(|backend| async move {
+            backend
+                .syncer
+                .write_announcement(signed_announcement)
+                .await
+                .map_err(|err| {
+                    warn!(
+                        ?err,
+                        location = backend.location(),
+                        "Failed to write announcement to checkpoint syncer"
+                    );
+                    backend.failures.inc();
+                    err
+                })
+        }))
+        .await;
+        Self::any_succeeded(results, "write announcement")
+    }
+
+    /// The location of the primary syncer
+    fn announcement_location(&self) -> String {
+        self.backends
+            .first()
+            .map(Backend::location)
+            .unwrap_or_default()
+    }
+}
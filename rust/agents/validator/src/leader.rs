@@ -0,0 +1,377 @@
+//! Leader election between replicas of a validator that share its key, so
+//! that the validator stays up while one of them is down. Only the leader
+//! signs and publishes checkpoints.
+//!
+//! The leader is elected through a Kubernetes `Lease`, which the replicas
+//! take over once its holder stopped renewing it for the lease duration.
+//! Updates of the lease are compare-and-swap through its resource version,
+//! so at most one replica holds it at a time. A leader that fails to renew
+//! the lease steps down well before the lease expires.
+
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+use eyre::{eyre, Result};
+use hyperlane_base::CoreMetrics;
+use hyperlane_core::HyperlaneDomain;
+use prometheus::IntGauge;
+use reqwest::{Certificate, Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use time::{format_description, OffsetDateTime};
+use tokio::{sync::watch, time::sleep};
+use tracing::{info, warn};
+
+/// Where the service account of the pod is mounted
+const SERVICE_ACCOUNT_DIR: &str = "/var/run/secrets/kubernetes.io/serviceaccount";
+
+/// The timeout for requests to the API server
+const KUBERNETES_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Settings for electing the replica that signs checkpoints
+#[derive(Debug, Clone)]
+pub struct LeaderElectionConf {
+    /// Name of the `Lease` the replicas compete for
+    pub lease_name: String,
+    /// Namespace of the lease - defaults to the namespace of the pod
+    pub namespace: Option<String>,
+    /// Identity of this replica - defaults to the name of the pod
+    pub identity: Option<String>,
+    /// How long the lease is held without being renewed
+    pub lease_duration: Duration,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LeaseSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    holder_identity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lease_duration_seconds: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    acquire_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    renew_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lease_transitions: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LeaseMetadata {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resource_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Lease {
+    api_version: String,
+    kind: String,
+    metadata: LeaseMetadata,
+    spec: LeaseSpec,
+}
+
+/// Whether this replica is the leader, for the tasks that sign checkpoints
+#[derive(Debug, Clone)]
+pub(crate) struct LeaderHandle {
+    /// None if the validator has no replicas, so it's always the leader
+    is_leader: Option<watch::Receiver<bool>>,
+}
+
+impl LeaderHandle {
+    /// The handle of a validator without replicas
+    pub(crate) fn always_leader() -> Self {
+        Self { is_leader: None }
+    }
+
+    pub(crate) fn is_leader(&self) -> bool {
+        self.is_leader
+            .as_ref()
+            .map_or(true, |is_leader| *is_leader.borrow())
+    }
+
+    /// Waits until this replica is elected leader
+    pub(crate) async fn wait_until_leader(&self) {
+        let Some(is_leader) = &self.is_leader else {
+            return;
+        };
+        let mut is_leader = is_leader.clone();
+        if !*is_leader.borrow_and_update() {
+            info!("Waiting to be elected leader before signing checkpoints");
+        }
+        while !*is_leader.borrow_and_update() {
+            if is_leader.changed().await.is_err() {
+                // The elector stopped, so this replica never becomes leader
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+}
+
+/// Competes for the lease on behalf of this replica
+#[derive(Debug)]
+pub(crate) struct LeaderElector {
+    client: Client,
+    lease_url: String,
+    lease_name: String,
+    identity: String,
+    lease_duration: Duration,
+    /// The lease as last seen and when it was first seen like that. Holders
+    /// of the lease are timed out by the local clock, so clock skew between
+    /// replicas doesn't matter.
+    observed: Option<(LeaseSpec, Instant)>,
+    /// When the last successful acquisition or renewal was started
+    renewed_at: Option<Instant>,
+    is_leader: watch::Sender<bool>,
+    is_leader_gauge: IntGauge,
+}
+
+impl LeaderElector {
+    pub(crate) fn new(
+        conf: &LeaderElectionConf,
+        metrics: &CoreMetrics,
+        origin: &HyperlaneDomain,
+    ) -> Result<(Self, LeaderHandle)> {
+        let host = std::env::var("KUBERNETES_SERVICE_HOST")
+            .map_err(|_| eyre!("Leader election requires running in a Kubernetes pod"))?;
+        let port = std::env::var("KUBERNETES_SERVICE_PORT").unwrap_or_else(|_| "443".into());
+        let namespace = match &conf.namespace {
+            Some(namespace) => namespace.clone(),
+            None => fs::read_to_string(format!("{SERVICE_ACCOUNT_DIR}/namespace"))?
+                .trim()
+                .to_owned(),
+        };
+        let identity = match &conf.identity {
+            Some(identity) => identity.clone(),
+            None => std::env::var("HOSTNAME")
+                .map_err(|_| eyre!("Expected a leader election identity or HOSTNAME"))?,
+        };
+        let ca = Certificate::from_pem(&fs::read(format!("{SERVICE_ACCOUNT_DIR}/ca.crt"))?)?;
+        let client = Client::builder()
+            .add_root_certificate(ca)
+            .timeout(KUBERNETES_REQUEST_TIMEOUT)
+            .build()?;
+        let is_leader_gauge = metrics
+            .new_int_gauge(
+                "validator_is_leader",
+                "Whether this replica of the validator is the elected leader that signs checkpoints",
+                &["chain"],
+            )?
+            .with_label_values(&[origin.name()]);
+        let (is_leader, receiver) = watch::channel(false);
+        let elector = Self {
+            client,
+            lease_url: format!(
+                "https://{host}:{port}/apis/coordination.k8s.io/v1/namespaces/{namespace}/leases"
+            ),
+            lease_name: conf.lease_name.clone(),
+            identity,
+            lease_duration: conf.lease_duration,
+            observed: None,
+            renewed_at: None,
+            is_leader,
+            is_leader_gauge,
+        };
+        Ok((
+            elector,
+            LeaderHandle {
+                is_leader: Some(receiver),
+            },
+        ))
+    }
+
+    /// Leaders step down once they failed to renew the lease for this long,
+    /// which leaves a margin before other replicas take it over
+    fn renew_deadline(&self) -> Duration {
+        self.lease_duration * 2 / 3
+    }
+
+    fn retry_period(&self) -> Duration {
+        self.lease_duration / 5
+    }
+
+    /// Competes for the lease forever
+    pub(crate) async fn run(mut self) {
+        info!(
+            identity = self.identity,
+            lease = self.lease_name,
+            "Starting leader election"
+        );
+        loop {
+            let started_at = Instant::now();
+            match self.try_acquire_or_renew(started_at).await {
+                Ok(true) => self.renewed_at = Some(started_at),
+                Ok(false) => self.renewed_at = None,
+                Err(err) => warn!(
+                    ?err,
+                    lease = self.lease_name,
+                    "Failed to acquire or renew lease"
+                ),
+            }
+            let is_leader = self.renewed_at.map_or(false, |renewed_at| {
+                renewed_at.elapsed() < self.renew_deadline()
+            });
+            if is_leader != *self.is_leader.borrow() {
+                if is_leader {
+                    info!(
+                        identity = self.identity,
+                        "Elected leader, signing checkpoints"
+                    );
+                } else {
+                    warn!(
+                        identity = self.identity,
+                        "Lost leadership, no longer signing checkpoints"
+                    );
+                }
+                self.is_leader.send_replace(is_leader);
+            }
+            self.is_leader_gauge.set(is_leader as i64);
+            sleep(self.retry_period()).await;
+        }
+    }
+
+    /// Acquires the lease if it's free or expired, or renews it if this
+    /// replica holds it. Returns whether this replica holds it.
+    async fn try_acquire_or_renew(&mut self, now: Instant) -> Result<bool> {
+        let Some(mut lease) = self.get_lease().await? else {
+            let lease = Lease {
+                api_version: "coordination.k8s.io/v1".into(),
+                kind: "Lease".into(),
+                metadata: LeaseMetadata {
+                    name: self.lease_name.clone(),
+                    resource_version: None,
+                },
+                spec: self.acquired_spec(&LeaseSpec::default())?,
+            };
+            return self
+                .write_lease(self.client.post(&self.lease_url), &lease)
+                .await;
+        };
+
+        if self.observed.as_ref().map(|(spec, _)| spec) != Some(&lease.spec) {
+            self.observed = Some((lease.spec.clone(), now));
+        }
+        let held_by_self = lease.spec.holder_identity.as_deref() == Some(self.identity.as_str());
+        let is_free = lease
+            .spec
+            .holder_identity
+            .as_deref()
+            .map_or(true, str::is_empty);
+        let expired = self.observed.as_ref().map_or(true, |(spec, observed_at)| {
+            let duration = spec
+                .lease_duration_seconds
+                .map_or(self.lease_duration, Duration::from_secs);
+            observed_at.elapsed() >= duration
+        });
+        if !held_by_self && !is_free && !expired {
+            return Ok(false);
+        }
+
+        lease.spec = if held_by_self {
+            LeaseSpec {
+                renew_time: Some(Self::micro_time()?),
+                lease_duration_seconds: Some(self.lease_duration.as_secs()),
+                ..lease.spec
+            }
+        } else {
+            info!(
+                previous_holder = ?lease.spec.holder_identity,
+                "Taking over lease"
+            );
+            self.acquired_spec(&lease.spec)?
+        };
+        let url = format!("{}/{}", self.lease_url, self.lease_name);
+        self.write_lease(self.client.put(url), &lease).await
+    }
+
+    /// The spec of the lease once this replica acquired it
+    fn acquired_spec(&self, previous: &LeaseSpec) -> Result<LeaseSpec> {
+        let now = Self::micro_time()?;
+        Ok(LeaseSpec {
+            holder_identity: Some(self.identity.clone()),
+            lease_duration_seconds: Some(self.lease_duration.as_secs()),
+            acquire_time: Some(now.clone()),
+            renew_time: Some(now),
+            lease_transitions: Some(previous.lease_transitions.map_or(0, |n| n + 1)),
+        })
+    }
+
+    /// The current time, formatted as a Kubernetes `MicroTime`
+    fn micro_time() -> Result<String> {
+        let format = format_description::parse(
+            "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:6]Z",
+        )?;
+        Ok(OffsetDateTime::now_utc().format(&format)?)
+    }
+
+    fn token() -> Result<String> {
+        // The token is rotated, so it's read for each request
+        Ok(fs::read_to_string(format!("{SERVICE_ACCOUNT_DIR}/token"))?
+            .trim()
+            .to_owned())
+    }
+
+    async fn get_lease(&self) -> Result<Option<Lease>> {
+        let response = self
+            .client
+            .get(format!("{}/{}", self.lease_url, self.lease_name))
+            .bearer_auth(Self::token()?)
+            .send()
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(response.error_for_status()?.json().await?))
+    }
+
+    /// Creates or updates the lease. Returns false if another replica
+    /// changed it in the meantime.
+    async fn write_lease(&self, request: reqwest::RequestBuilder, lease: &Lease) -> Result<bool> {
+        let response = request
+            .bearer_auth(Self::token()?)
+            .json(lease)
+            .send()
+            .await?;
+        if response.status() == StatusCode::CONFLICT {
+            return Ok(false);
+        }
+        response.error_for_status()?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lease_serialization() {
+        let lease: Lease = serde_json::from_str(
+            r#"{
+                "apiVersion": "coordination.k8s.io/v1",
+                "kind": "Lease",
+                "metadata": {"name": "validator", "namespace": "hyperlane", "resourceVersion": "42"},
+                "spec": {"holderIdentity": "validator-0", "leaseDurationSeconds": 15, "leaseTransitions": 3}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(lease.metadata.resource_version.as_deref(), Some("42"));
+        assert_eq!(lease.spec.holder_identity.as_deref(), Some("validator-0"));
+        assert_eq!(lease.spec.lease_transitions, Some(3));
+
+        let json = serde_json::to_value(&lease).unwrap();
+        assert_eq!(json["metadata"]["resourceVersion"], "42");
+        assert!(json["spec"].get("renewTime").is_none());
+    }
+
+    #[test]
+    fn test_micro_time() {
+        let time = LeaderElector::micro_time().unwrap();
+        // e.g. 2024-01-01T00:00:00.000000Z
+        assert_eq!(time.len(), 27);
+        assert!(time.ends_with('Z'));
+    }
+}
@@ -9,6 +9,11 @@ use hyperlane_base::agent_main;
 
 use crate::validator::Validator;
 
+mod fanout;
+mod leader;
+mod retention;
+mod safety;
+mod self_check;
 mod server;
 mod settings;
 mod submit;
@@ -0,0 +1,220 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use eyre::{eyre, Result};
+use hyperlane_base::{
+    db::HyperlaneRocksDB, settings::CheckpointSyncerConf, CheckpointSyncer, CoreMetrics,
+};
+use hyperlane_core::{HyperlaneDomain, SignedCheckpointWithMessageId};
+use prometheus::{IntCounter, IntGauge};
+use reqwest::{Client, Url};
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::{fanout::FanoutCheckpointSyncer, leader::LeaderHandle};
+
+/// How often old checkpoints are pruned
+const PRUNE_INTERVAL: Duration = Duration::from_secs(600);
+
+/// The most checkpoints pruned at once, so that a validator that enables
+/// retention with a large backlog catches up gradually
+const MAX_PRUNED_PER_RUN: u32 = 1_000;
+
+/// The timeout for requests to the delivery hint endpoint
+const DELIVERY_HINT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Settings for pruning old checkpoints from the checkpoint syncers
+#[derive(Debug, Clone)]
+pub struct CheckpointRetentionConf {
+    /// How many checkpoints behind the latest one are retained
+    pub keep_latest: u32,
+    /// Where pruned checkpoints are archived before they're deleted, e.g. a
+    /// cold folder of the bucket. Without it, they're only deleted.
+    pub archive: Option<CheckpointSyncerConf>,
+    /// An endpoint returning the lowest index of the origin's merkle tree
+    /// whose message wasn't delivered yet as a JSON number, e.g. derived from
+    /// the scraper. Checkpoints from that index on are always retained.
+    pub delivery_hint_url: Option<Url>,
+}
+
+/// Periodically archives and deletes the checkpoints that are more than
+/// `keep_latest` indices behind the latest one, so that the storage of the
+/// checkpoint syncers doesn't grow unboundedly. The index up to which
+/// checkpoints were pruned is persisted, so that they aren't signed again
+/// while backfilling.
+pub(crate) struct CheckpointRetention {
+    keep_latest: u32,
+    checkpoint_syncer: Arc<FanoutCheckpointSyncer>,
+    archive: Option<Box<dyn CheckpointSyncer>>,
+    delivery_hint_url: Option<Url>,
+    client: Client,
+    db: HyperlaneRocksDB,
+    leader: LeaderHandle,
+    archived: IntCounter,
+    deleted: IntCounter,
+    lowest_retained_index: IntGauge,
+}
+
+impl CheckpointRetention {
+    pub(crate) async fn new(
+        conf: &CheckpointRetentionConf,
+        checkpoint_syncer: Arc<FanoutCheckpointSyncer>,
+        db: HyperlaneRocksDB,
+        leader: LeaderHandle,
+        metrics: &CoreMetrics,
+        origin: &HyperlaneDomain,
+    ) -> Result<Self> {
+        let archive = match &conf.archive {
+            Some(archive) => Some(archive.build(None).await?),
+            None => None,
+        };
+        let pruned = metrics.new_int_counter(
+            "validator_checkpoints_pruned",
+            "Checkpoints pruned from the checkpoint syncers, by whether they were archived or deleted",
+            &["chain", "action"],
+        )?;
+        let lowest_retained_index = metrics
+            .new_int_gauge(
+                "validator_lowest_retained_checkpoint_index",
+                "The index of the lowest checkpoint that wasn't pruned from the checkpoint syncers",
+                &["chain"],
+            )?
+            .with_label_values(&[origin.name()]);
+        Ok(Self {
+            keep_latest: conf.keep_latest,
+            checkpoint_syncer,
+            archive,
+            delivery_hint_url: conf.delivery_hint_url.clone(),
+            client: Client::builder()
+                .timeout(DELIVERY_HINT_REQUEST_TIMEOUT)
+                .build()?,
+            db,
+            leader,
+            archived: pruned.with_label_values(&[origin.name(), "archived"]),
+            deleted: pruned.with_label_values(&[origin.name(), "deleted"]),
+            lowest_retained_index,
+        })
+    }
+
+    pub(crate) async fn run(self) {
+        loop {
+            sleep(PRUNE_INTERVAL).await;
+            // Only the replica that signs checkpoints prunes them
+            if !self.leader.is_leader() {
+                continue;
+            }
+            if let Err(err) = self.prune().await {
+                warn!(?err, "Failed to prune checkpoints");
+            }
+        }
+    }
+
+    async fn prune(&self) -> Result<()> {
+        let Some(latest_index) = self.checkpoint_syncer.latest_index().await? else {
+            return Ok(());
+        };
+        let lowest_undelivered_index = match &self.delivery_hint_url {
+            // If the hint can't be fetched, nothing is pruned rather than
+            // checkpoints that relayers may still need
+            Some(url) => Some(self.lowest_undelivered_index(url).await?),
+            None => None,
+        };
+        let lowest_retained_index = self
+            .db
+            .retrieve_lowest_retained_checkpoint_index()?
+            .unwrap_or_default();
+        let range = prune_range(
+            lowest_retained_index,
+            latest_index,
+            self.keep_latest,
+            lowest_undelivered_index,
+        );
+        if range.is_empty() {
+            self.lowest_retained_index.set(lowest_retained_index as i64);
+            return Ok(());
+        }
+        debug!(?range, latest_index, "Pruning checkpoints");
+
+        for index in range.clone() {
+            if let Some(archive) = &self.archive {
+                if let Some(checkpoint) = self.fetch_checkpoint(index).await? {
+                    archive.write_checkpoint(&checkpoint).await?;
+                    self.archived.inc();
+                }
+            }
+            self.checkpoint_syncer.delete_checkpoint(index).await?;
+            self.deleted.inc();
+            self.db.store_lowest_retained_checkpoint_index(index + 1)?;
+            self.lowest_retained_index.set(index as i64 + 1);
+        }
+        if let Some(archive) = &self.archive {
+            archive.update_latest_index(range.end - 1).await?;
+        }
+        info!(?range, "Pruned checkpoints");
+        Ok(())
+    }
+
+    /// The checkpoint from any of the syncers, since they may have missed it
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        for syncer in self.checkpoint_syncer.syncers() {
+            match syncer.fetch_checkpoint(index).await {
+                Ok(Some(checkpoint)) => return Ok(Some(checkpoint)),
+                Ok(None) => {}
+                Err(err) => {
+                    warn!(
+                        ?err,
+                        location = syncer.announcement_location(),
+                        index,
+                        "Failed to fetch checkpoint to archive"
+                    );
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    async fn lowest_undelivered_index(&self, url: &Url) -> Result<u32> {
+        self.client
+            .get(url.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(|err| eyre!("Expected the lowest undelivered index from {url}: {err}"))
+    }
+}
+
+/// The indices of the checkpoints to prune, from the lowest retained one up
+/// to `keep_latest` behind the latest one, but not past the lowest
+/// undelivered one
+fn prune_range(
+    lowest_retained_index: u32,
+    latest_index: u32,
+    keep_latest: u32,
+    lowest_undelivered_index: Option<u32>,
+) -> Range<u32> {
+    let mut end = latest_index.saturating_sub(keep_latest);
+    if let Some(lowest_undelivered_index) = lowest_undelivered_index {
+        end = end.min(lowest_undelivered_index);
+    }
+    let end = end.min(lowest_retained_index.saturating_add(MAX_PRUNED_PER_RUN));
+    lowest_retained_index..end.max(lowest_retained_index)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_prune_range() {
+        // Keeps the latest checkpoints
+        assert_eq!(prune_range(0, 100, 10, None), 0..90);
+        assert_eq!(prune_range(90, 100, 10, None), 90..90);
+        assert_eq!(prune_range(0, 5, 10, None), 0..0);
+        // Keeps the undelivered checkpoints
+        assert_eq!(prune_range(0, 100, 10, Some(50)), 0..50);
+        assert_eq!(prune_range(60, 100, 10, Some(50)), 60..60);
+        // Bounded per run
+        assert_eq!(prune_range(0, 5_000, 10, None), 0..MAX_PRUNED_PER_RUN);
+    }
+}
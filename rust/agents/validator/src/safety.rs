@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use hyperlane_base::{
+    db::{DbError, HyperlaneRocksDB},
+    CoreMetrics,
+};
+use hyperlane_core::{CheckpointWithMessageId, HyperlaneDomain, H256};
+use prometheus::IntCounter;
+use tokio::sync::Mutex;
+use tracing::{error, info};
+
+/// Why a checkpoint wasn't allowed to be signed
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum DoubleSignError {
+    /// A different root was signed at the index before
+    #[error("Refusing to sign root {root:?} at index {index}, root {signed_root:?} was signed at it before")]
+    ConflictingRoot {
+        index: u32,
+        root: H256,
+        signed_root: H256,
+    },
+    /// The signed roots couldn't be read or stored
+    #[error(transparent)]
+    Db(#[from] DbError),
+}
+
+/// Keeps the validator from signing two different roots at the same index,
+/// e.g. after a reorg or when an RPC serves a different fork, which relayers
+/// and watchers would treat as fraud. The root signed at each index is
+/// persisted before signing, so that this also holds across restarts.
+#[derive(Clone, Debug)]
+pub(crate) struct DoubleSignGuard {
+    db: HyperlaneRocksDB,
+    /// Serializes the checks of the backfill and tip submitters
+    lock: Arc<Mutex<()>>,
+    refusals: IntCounter,
+}
+
+impl DoubleSignGuard {
+    pub(crate) fn new(
+        db: HyperlaneRocksDB,
+        metrics: &CoreMetrics,
+        origin: &HyperlaneDomain,
+    ) -> eyre::Result<Self> {
+        let refusals = metrics
+            .new_int_counter(
+                "validator_double_sign_refusals",
+                "Checkpoints the validator refused to sign because it signed a different root at their index before",
+                &["chain"],
+            )?
+            .with_label_values(&[origin.name()]);
+        if let Some(index) = db.retrieve_highest_signed_checkpoint_index()? {
+            info!(index, "Resuming from highest signed checkpoint index");
+        }
+        Ok(Self {
+            db,
+            lock: Default::default(),
+            refusals,
+        })
+    }
+
+    /// Records that `checkpoint` is about to be signed, unless a different
+    /// root was signed at its index before, in which case it must not be
+    /// signed.
+    pub(crate) async fn check_and_record(
+        &self,
+        checkpoint: &CheckpointWithMessageId,
+    ) -> Result<(), DoubleSignError> {
+        let _guard = self.lock.lock().await;
+        let index = checkpoint.index;
+        if let Some(signed_root) = self.db.retrieve_signed_checkpoint_root_by_index(&index)? {
+            if signed_root != checkpoint.root {
+                return Err(self.refuse(checkpoint, signed_root));
+            }
+            return Ok(());
+        }
+        self.db
+            .store_signed_checkpoint_root_by_index(&index, &checkpoint.root)?;
+        self.db.try_update_highest_signed_checkpoint_index(index)?;
+        Ok(())
+    }
+
+    /// Refuses to sign `checkpoint` because `signed_root` was signed at its
+    /// index before. Signed checkpoints already written to the checkpoint
+    /// syncer are checked with this as well, since they may predate the
+    /// database.
+    pub(crate) fn refuse(
+        &self,
+        checkpoint: &CheckpointWithMessageId,
+        signed_root: H256,
+    ) -> DoubleSignError {
+        self.refusals.inc();
+        error!(
+            index = checkpoint.index,
+            root = ?checkpoint.root,
+            ?signed_root,
+            "CRITICAL: refusing to sign a checkpoint that conflicts with one signed before. \
+             The origin may have reorged past its finality or an RPC may be serving a different \
+             fork; the validator won't sign past this index until this is investigated"
+        );
+        DoubleSignError::ConflictingRoot {
+            index: checkpoint.index,
+            root: checkpoint.root,
+            signed_root,
+        }
+    }
+}
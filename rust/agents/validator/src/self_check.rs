@@ -0,0 +1,108 @@
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use eyre::{bail, eyre, Result};
+use hyperlane_base::{settings::CheckpointSyncerConf, CoreMetrics};
+use hyperlane_core::{HyperlaneDomain, ValidatorAnnounce, H160};
+use prometheus::IntGaugeVec;
+use tokio::time::sleep;
+use tracing::{debug, error};
+
+/// How often the announced locations are checked
+const SELF_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Periodically reads the validator's latest checkpoint from each of its
+/// announced storage locations the way relayers do, to catch locations
+/// relayers can't read from, e.g. because of a bucket policy, CORS or a
+/// wrong path. Otherwise the validator silently doesn't count towards
+/// quorums.
+#[derive(Debug)]
+pub(crate) struct SelfCheck {
+    validator: H160,
+    /// The announcement locations of the validator's checkpoint syncers
+    locations: Vec<String>,
+    validator_announce: Arc<dyn ValidatorAnnounce>,
+    origin: HyperlaneDomain,
+    readable: IntGaugeVec,
+}
+
+impl SelfCheck {
+    pub(crate) fn new(
+        validator: H160,
+        locations: Vec<String>,
+        validator_announce: Arc<dyn ValidatorAnnounce>,
+        origin: HyperlaneDomain,
+        metrics: &CoreMetrics,
+    ) -> Result<Self> {
+        let readable = metrics.new_int_gauge(
+            "validator_announced_location_readable",
+            "Whether the latest checkpoint of the validator could be read from an announced storage location like relayers do",
+            &["chain", "location"],
+        )?;
+        Ok(Self {
+            validator,
+            locations,
+            validator_announce,
+            origin,
+            readable,
+        })
+    }
+
+    pub(crate) async fn run(self) {
+        loop {
+            // The first check waits for the first checkpoints to be written
+            sleep(SELF_CHECK_INTERVAL).await;
+            for location in &self.locations {
+                let readable = match self.check(location).await {
+                    Ok(index) => {
+                        debug!(
+                            location,
+                            index, "Read latest checkpoint from announced location"
+                        );
+                        true
+                    }
+                    Err(err) => {
+                        error!(
+                            ?err,
+                            location,
+                            "Relayers can't read the validator's checkpoints from its announced storage location. Check the bucket policy, CORS and path of the location"
+                        );
+                        false
+                    }
+                };
+                self.readable
+                    .with_label_values(&[self.origin.name(), location])
+                    .set(readable as i64);
+            }
+        }
+    }
+
+    /// Reads the latest checkpoint from the location, returning its index
+    async fn check(&self, location: &str) -> Result<u32> {
+        let announced = self
+            .validator_announce
+            .get_announced_storage_locations(&[self.validator.into()])
+            .await?;
+        if !announced
+            .first()
+            .map_or(false, |locations| locations.iter().any(|l| l == location))
+        {
+            bail!("Location isn't announced");
+        }
+        // Built from the announced location, like relayers do
+        let syncer = CheckpointSyncerConf::from_str(location)?
+            .build(None)
+            .await?;
+        let index = syncer
+            .latest_index()
+            .await?
+            .ok_or_else(|| eyre!("No latest checkpoint index at location"))?;
+        let checkpoint = syncer
+            .fetch_checkpoint(index)
+            .await?
+            .ok_or_else(|| eyre!("Latest checkpoint {index} not found at location"))?;
+        checkpoint.verify(self.validator).map_err(|err| {
+            eyre!("Latest checkpoint {index} isn't signed by the validator: {err}")
+        })?;
+        Ok(index)
+    }
+}
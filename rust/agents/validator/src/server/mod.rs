@@ -4,16 +4,22 @@ use std::{sync::Arc, vec};
 use axum::Router;
 pub use eigen_node::EigenNodeApi;
 
-use hyperlane_base::CoreMetrics;
+use hyperlane_base::{CoreMetrics, DbSnapshotApi, LogFilterApi};
 use hyperlane_core::HyperlaneDomain;
 
 /// Returns a vector of validator-specific endpoint routes to be served.
 /// Can be extended with additional routes and feature flags to enable/disable individually.
+/// The admin APIs are only served if an api key is configured.
 pub fn routes(
     origin_chain: HyperlaneDomain,
     metrics: Arc<CoreMetrics>,
+    db_snapshot_api: Option<DbSnapshotApi>,
+    log_filter_api: Option<LogFilterApi>,
 ) -> Vec<(&'static str, Router)> {
     let eigen_node_api = EigenNodeApi::new(origin_chain, metrics);
 
-    vec![eigen_node_api.get_route()]
+    let mut routes = vec![eigen_node_api.get_route()];
+    routes.extend(db_snapshot_api.map(|api| api.get_route()));
+    routes.extend(log_filter_api.map(|api| api.get_route()));
+    routes
 }
@@ -4,21 +4,30 @@
 //! and validations it defines are not applied here, we should mirror them.
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
-use std::{collections::HashSet, path::PathBuf, time::Duration};
+use std::{
+    collections::HashSet, env, num::NonZeroU32, path::PathBuf, str::FromStr, time::Duration,
+};
 
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
 use eyre::{eyre, Context};
 use hyperlane_base::{
+    db::{DbBackend, IntegrityCheckConf, RocksDbTuning},
     impl_loadable_from_settings,
     settings::{
-        parser::{RawAgentConf, RawAgentSignerConf, ValueParser},
-        CheckpointSyncerConf, Settings, SignerConf,
+        ipfs_gateway,
+        parser::{parse_db_integrity_check, RawAgentConf, RawAgentSignerConf, ValueParser},
+        s3_region, CheckpointSyncerConf, Settings, SignerConf,
     },
+    AzureBlobAuth, IpfsWriter, AZURE_STORAGE_SAS_TOKEN, GCS_SERVICE_ACCOUNT_KEY, GCS_USER_SECRET,
 };
 use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneDomainProtocol};
 use serde::Deserialize;
 use serde_json::Value;
 
+use crate::{
+    leader::LeaderElectionConf, retention::CheckpointRetentionConf, submit::CheckpointSamplingConf,
+};
+
 /// Settings for `Validator`
 #[derive(Debug, AsRef, AsMut, Deref, DerefMut)]
 pub struct ValidatorSettings {
@@ -30,16 +39,42 @@ pub struct ValidatorSettings {
 
     /// Database path
     pub db: PathBuf,
+    /// The key-value store the database is kept in
+    pub db_backend: DbBackend,
+    /// How RocksDB trades memory for throughput
+    pub db_tuning: RocksDbTuning,
+    /// A snapshot file to restore into the database if it's new
+    pub db_restore_from: Option<PathBuf>,
+    /// Where the snapshots taken through the admin API are written to
+    pub db_snapshot_location: Option<CheckpointSyncerConf>,
+    /// If set, the stored sequences are verified against the chain on startup
+    pub db_integrity_check: Option<IntegrityCheckConf>,
+    /// If set, the validator serves the database snapshot API, which requires
+    /// this key as a bearer token
+    pub admin_api_key: Option<String>,
     /// Chain to validate messages on
     pub origin_chain: HyperlaneDomain,
     /// The validator attestation signer
     pub validator: SignerConf,
-    /// The checkpoint syncer configuration
-    pub checkpoint_syncer: CheckpointSyncerConf,
-    /// The reorg_period in blocks
-    pub reorg_period: u64,
+    /// The checkpoint syncers each signed checkpoint is written to, the
+    /// primary one first
+    pub checkpoint_syncers: Vec<CheckpointSyncerConf>,
     /// How frequently to check for new checkpoints
     pub interval: Duration,
+    /// Which checkpoints are signed, if not all of them. Only allowed for
+    /// validators of merkle root multisig ISMs.
+    pub checkpoint_sampling: Option<CheckpointSamplingConf>,
+    /// Whether checkpoints are only signed once they're behind the origin's
+    /// finalized tag, on top of its finality policy
+    pub reorg_safety: bool,
+    /// Whether the validator announces its storage locations itself when
+    /// they aren't announced yet, e.g. after they changed
+    pub auto_announce: bool,
+    /// Election of the replica that signs checkpoints, if the validator
+    /// runs several replicas
+    pub leader_election: Option<LeaderElectionConf>,
+    /// Pruning of old checkpoints from the checkpoint syncers
+    pub checkpoint_retention: Option<CheckpointRetentionConf>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -101,11 +136,71 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
                     .join(format!("validator_db_{}", origin_chain_name.unwrap_or("")))
             });
 
+        let db_backend = p
+            .chain(&mut err)
+            .get_opt_key("dbBackend")
+            .parse_from_str("Expected a database backend")
+            .unwrap_or_default();
+
+        let db_tuning = p
+            .chain(&mut err)
+            .get_opt_key("dbTuning")
+            .parse_from_str("Expected a RocksDB tuning")
+            .unwrap_or_default();
+
+        let db_restore_from = p
+            .chain(&mut err)
+            .get_opt_key("dbRestoreFrom")
+            .parse_from_str("Expected a database snapshot path")
+            .end();
+
+        let db_snapshot_location = p
+            .chain(&mut err)
+            .get_opt_key("dbSnapshotLocation")
+            .parse_string()
+            .end()
+            .and_then(|location| {
+                CheckpointSyncerConf::from_str(location)
+                    .take_err(&mut err, || &p.cwp + "db_snapshot_location")
+            });
+
+        let db_integrity_check = p
+            .chain(&mut err)
+            .get_opt_key("dbIntegrityCheck")
+            .and_then(parse_db_integrity_check)
+            .end();
+
+        let admin_api_key = p
+            .chain(&mut err)
+            .get_opt_key("adminApiKey")
+            .parse_string()
+            .end()
+            .map(|v| v.to_owned());
+
         let checkpoint_syncer = p
             .chain(&mut err)
-            .get_key("checkpointSyncer")
+            .get_opt_key("checkpointSyncer")
             .and_then(parse_checkpoint_syncer)
             .end();
+        // Additional syncers that checkpoints are also written to
+        let additional_checkpoint_syncers = p
+            .chain(&mut err)
+            .get_opt_key("checkpointSyncers")
+            .into_array_iter()
+            .map(|syncers| {
+                syncers
+                    .filter_map(|syncer| parse_checkpoint_syncer(syncer).take_config_err(&mut err))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let checkpoint_syncers = checkpoint_syncer
+            .into_iter()
+            .chain(additional_checkpoint_syncers)
+            .collect::<Vec<_>>();
+        if checkpoint_syncers.is_empty() {
+            Err::<(), _>(eyre!("Expected checkpointSyncer or checkpointSyncers"))
+                .take_err(&mut err, || cwp + "checkpoint_syncer");
+        }
 
         let interval = p
             .chain(&mut err)
@@ -114,22 +209,82 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             .map(Duration::from_secs)
             .unwrap_or(Duration::from_secs(5));
 
-        cfg_unwrap_all!(cwp, err: [origin_chain_name]);
+        let sign_every_nth_index = p
+            .chain(&mut err)
+            .get_opt_key("signEveryNthIndex")
+            .parse_u32()
+            .unwrap_or(1);
+        let sign_every_nth_index = NonZeroU32::new(sign_every_nth_index)
+            .ok_or_else(|| eyre!("Expected signEveryNthIndex to be positive"))
+            .take_err(&mut err, || cwp + "sign_every_nth_index");
 
-        let reorg_period = p
+        let latest_signing_interval = p
             .chain(&mut err)
-            .get_key("chains")
-            .get_key(origin_chain_name)
-            .get_opt_key("blocks")
-            .get_opt_key("reorgPeriod")
+            .get_opt_key("latestSigningInterval")
             .parse_u64()
-            .unwrap_or(1);
+            .map(Duration::from_secs)
+            .end();
+
+        let merkle_root_multisig_only = p
+            .chain(&mut err)
+            .get_opt_key("merkleRootMultisigOnly")
+            .parse_bool()
+            .unwrap_or(false);
 
-        cfg_unwrap_all!(cwp, err: [base, origin_chain, validator, checkpoint_syncer]);
+        // Message id multisig ISMs need a checkpoint at the index of each
+        // message, so skipping any is opt-in
+        let checkpoint_sampling = match sign_every_nth_index {
+            Some(every_nth_index)
+                if every_nth_index.get() > 1 || latest_signing_interval.is_some() =>
+            {
+                if !merkle_root_multisig_only {
+                    Err::<(), _>(eyre!(
+                        "signEveryNthIndex and latestSigningInterval leave checkpoints unsigned, which message id multisig ISMs need. Set merkleRootMultisigOnly if only merkle root multisig ISMs use this validator"
+                    ))
+                    .take_err(&mut err, || cwp + "merkle_root_multisig_only");
+                }
+                Some(CheckpointSamplingConf {
+                    every_nth_index,
+                    latest_signing_interval,
+                })
+            }
+            _ => None,
+        };
+
+        let reorg_safety = p
+            .chain(&mut err)
+            .get_opt_key("reorgSafety")
+            .parse_bool()
+            .unwrap_or(false);
+
+        let auto_announce = p
+            .chain(&mut err)
+            .get_opt_key("autoAnnounce")
+            .parse_bool()
+            .unwrap_or(true);
+
+        let leader_election = p
+            .chain(&mut err)
+            .get_opt_key("leaderElection")
+            .and_then(parse_leader_election)
+            .end();
+
+        let checkpoint_retention = p
+            .chain(&mut err)
+            .get_opt_key("checkpointRetention")
+            .and_then(parse_checkpoint_retention)
+            .end();
+
+        cfg_unwrap_all!(cwp, err: [origin_chain_name]);
+
+        cfg_unwrap_all!(cwp, err: [base, origin_chain, validator]);
 
         let mut base: Settings = base;
         // If the origin chain is an EVM chain, then we can use the validator as the signer if needed.
-        if origin_chain.domain_protocol() == HyperlaneDomainProtocol::Ethereum {
+        // Remote signers only sign messages, not transactions.
+        if origin_chain.domain_protocol() == HyperlaneDomainProtocol::Ethereum
+            && !matches!(validator, SignerConf::Remote { .. })
+        {
             if let Some(origin) = base.chains.get_mut(origin_chain.name()) {
                 origin.signer.get_or_insert_with(|| validator.clone());
             }
@@ -138,15 +293,89 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
         err.into_result(Self {
             base,
             db,
+            db_backend,
+            db_tuning,
+            db_restore_from,
+            db_snapshot_location,
+            db_integrity_check,
+            admin_api_key,
             origin_chain,
             validator,
-            checkpoint_syncer,
-            reorg_period,
+            checkpoint_syncers,
             interval,
+            checkpoint_sampling,
+            reorg_safety,
+            auto_announce,
+            leader_election,
+            checkpoint_retention,
         })
     }
 }
 
+/// Expects ValidatorAgentConfig.checkpointRetention
+fn parse_checkpoint_retention(retention: ValueParser) -> ConfigResult<CheckpointRetentionConf> {
+    let mut err = ConfigParsingError::default();
+    let keep_latest = retention
+        .chain(&mut err)
+        .get_key("keepLatest")
+        .parse_u32()
+        .end();
+    let archive = retention
+        .chain(&mut err)
+        .get_opt_key("archive")
+        .and_then(parse_checkpoint_syncer)
+        .end();
+    let delivery_hint_url = retention
+        .chain(&mut err)
+        .get_opt_key("deliveryHintUrl")
+        .parse_from_str("Expected delivery hint url")
+        .end();
+
+    cfg_unwrap_all!(&retention.cwp, err: [keep_latest]);
+    err.into_result(CheckpointRetentionConf {
+        keep_latest,
+        archive,
+        delivery_hint_url,
+    })
+}
+
+/// Expects ValidatorAgentConfig.leaderElection
+fn parse_leader_election(election: ValueParser) -> ConfigResult<LeaderElectionConf> {
+    let mut err = ConfigParsingError::default();
+    let lease_name = election
+        .chain(&mut err)
+        .get_key("leaseName")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    let namespace = election
+        .chain(&mut err)
+        .get_opt_key("namespace")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    let identity = election
+        .chain(&mut err)
+        .get_opt_key("identity")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    let lease_duration = election
+        .chain(&mut err)
+        .get_opt_key("leaseDuration")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(15));
+
+    cfg_unwrap_all!(&election.cwp, err: [lease_name]);
+    err.into_result(LeaderElectionConf {
+        lease_name,
+        namespace,
+        identity,
+        lease_duration,
+    })
+}
+
 /// Expects ValidatorAgentConfig.checkpointSyncer
 fn parse_checkpoint_syncer(syncer: ValueParser) -> ConfigResult<CheckpointSyncerConf> {
     let mut err = ConfigParsingError::default();
@@ -169,11 +398,19 @@ fn parse_checkpoint_syncer(syncer: ValueParser) -> ConfigResult<CheckpointSyncer
                 .parse_string()
                 .end()
                 .map(str::to_owned);
+            let endpoint = syncer
+                .chain(&mut err)
+                .get_opt_key("endpoint")
+                .parse_string()
+                .end();
             let region = syncer
                 .chain(&mut err)
                 .get_key("region")
-                .parse_from_str("Expected aws region")
-                .end();
+                .parse_string()
+                .end()
+                .and_then(|region| {
+                    s3_region(region, endpoint).take_err(&mut err, || &syncer.cwp + "region")
+                });
             let folder = syncer
                 .chain(&mut err)
                 .get_opt_key("folder")
@@ -188,6 +425,133 @@ fn parse_checkpoint_syncer(syncer: ValueParser) -> ConfigResult<CheckpointSyncer
                 folder,
             })
         }
+        Some("gcs") => {
+            let bucket = syncer
+                .chain(&mut err)
+                .get_key("bucket")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let folder = syncer
+                .chain(&mut err)
+                .get_opt_key("folder")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let service_account_key = syncer
+                .chain(&mut err)
+                .get_opt_key("serviceAccountKey")
+                .parse_string()
+                .end()
+                .map(str::to_owned)
+                .or_else(|| env::var(GCS_SERVICE_ACCOUNT_KEY).ok());
+            let user_secrets = syncer
+                .chain(&mut err)
+                .get_opt_key("userSecrets")
+                .parse_string()
+                .end()
+                .map(str::to_owned)
+                .or_else(|| env::var(GCS_USER_SECRET).ok());
+
+            cfg_unwrap_all!(&syncer.cwp, err: [bucket]);
+            err.into_result(CheckpointSyncerConf::Gcs {
+                bucket,
+                folder,
+                service_account_key,
+                user_secrets,
+            })
+        }
+        Some("azure") => {
+            let account = syncer
+                .chain(&mut err)
+                .get_key("account")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let container = syncer
+                .chain(&mut err)
+                .get_key("container")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let folder = syncer
+                .chain(&mut err)
+                .get_opt_key("folder")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let sas_token = syncer
+                .chain(&mut err)
+                .get_opt_key("sasToken")
+                .parse_string()
+                .end()
+                .map(str::to_owned)
+                .or_else(|| env::var(AZURE_STORAGE_SAS_TOKEN).ok());
+            let managed_identity = syncer
+                .chain(&mut err)
+                .get_opt_key("managedIdentity")
+                .parse_bool()
+                .unwrap_or(false);
+            let managed_identity_client_id = syncer
+                .chain(&mut err)
+                .get_opt_key("managedIdentityClientId")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let auth = match sas_token {
+                Some(sas) => AzureBlobAuth::Sas(sas),
+                None if managed_identity || managed_identity_client_id.is_some() => {
+                    AzureBlobAuth::ManagedIdentity {
+                        client_id: managed_identity_client_id,
+                    }
+                }
+                None => AzureBlobAuth::Anonymous,
+            };
+
+            cfg_unwrap_all!(&syncer.cwp, err: [account, container]);
+            err.into_result(CheckpointSyncerConf::Azure {
+                account,
+                container,
+                folder,
+                auth,
+            })
+        }
+        Some("ipfs") => {
+            let api_url = syncer
+                .chain(&mut err)
+                .get_key("apiUrl")
+                .parse_from_str("Expected IPFS RPC API url")
+                .end();
+            let key = syncer
+                .chain(&mut err)
+                .get_opt_key("key")
+                .parse_string()
+                .unwrap_or("self")
+                .to_owned();
+            let folder = syncer
+                .chain(&mut err)
+                .get_opt_key("folder")
+                .parse_string()
+                .unwrap_or("hyperlane-checkpoints")
+                .to_owned();
+            let gateway = syncer
+                .chain(&mut err)
+                .get_opt_key("gateway")
+                .parse_from_str("Expected IPFS gateway url")
+                .end()
+                .or_else(|| ipfs_gateway().take_err(&mut err, || &syncer.cwp + "gateway"));
+
+            cfg_unwrap_all!(&syncer.cwp, err: [api_url, gateway]);
+            err.into_result(CheckpointSyncerConf::Ipfs {
+                ipns_name: None,
+                gateway,
+                writer: Some(IpfsWriter {
+                    api_url,
+                    key,
+                    folder,
+                }),
+            })
+        }
         Some(_) => {
             Err(eyre!("Unknown checkpoint syncer type")).into_config_result(|| &syncer.cwp + "type")
         }
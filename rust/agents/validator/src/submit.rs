@@ -1,49 +1,177 @@
-use std::num::NonZeroU64;
-use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::num::{NonZeroU32, NonZeroU64};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::vec;
 
+use eyre::Result;
+use futures_util::future::try_join_all;
 use hyperlane_core::rpc_clients::call_and_retry_indefinitely;
-use hyperlane_core::{ChainCommunicationError, ChainResult, MerkleTreeHook};
-use prometheus::IntGauge;
+use hyperlane_core::{
+    BlockTag, ChainCommunicationError, ChainResult, Finality, FinalityPolicy, FinalitySource,
+    MerkleTreeHook,
+};
+use prometheus::{Histogram, IntCounter, IntGauge};
 use tokio::time::sleep;
 use tracing::{debug, error, info};
 
-use hyperlane_base::{db::HyperlaneRocksDB, CheckpointSyncer, CoreMetrics};
+use hyperlane_base::{db::HyperlaneRocksDB, settings::ChainConf, CheckpointSyncer, CoreMetrics};
 use hyperlane_core::{
     accumulator::incremental::IncrementalMerkle, Checkpoint, CheckpointWithMessageId,
     HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneSignerExt,
 };
 use hyperlane_ethereum::SingletonSignerHandle;
 
+use crate::{leader::LeaderHandle, safety::DoubleSignGuard};
+
+/// How many checkpoints are signed and written concurrently
+const SIGNING_BATCH_SIZE: usize = 20;
+
+/// Signing only some checkpoints, for high-traffic origins. Relayers need a
+/// checkpoint at the exact index of a message to prove it with a message id
+/// multisig ISM, so this is only valid for validators whose signatures are
+/// only used by merkle root multisig ISMs, which can prove a message with
+/// any later checkpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointSamplingConf {
+    /// Checkpoints at multiples of this index are signed
+    pub every_nth_index: NonZeroU32,
+    /// If set, the latest checkpoint is signed at most this often, instead
+    /// of every interval. It's left unsigned until then, unless its index is
+    /// a multiple of `every_nth_index`.
+    pub latest_signing_interval: Option<Duration>,
+}
+
+impl CheckpointSamplingConf {
+    /// The checkpoints at multiples of `every_nth_index`, along with the last
+    /// one if `sign_latest`
+    fn sample(
+        &self,
+        checkpoints: Vec<CheckpointWithMessageId>,
+        sign_latest: bool,
+    ) -> Vec<CheckpointWithMessageId> {
+        let last = checkpoints.len().saturating_sub(1);
+        checkpoints
+            .into_iter()
+            .enumerate()
+            .filter(|(i, checkpoint)| {
+                checkpoint.index % self.every_nth_index == 0 || (sign_latest && *i == last)
+            })
+            .map(|(_, checkpoint)| checkpoint)
+            .collect()
+    }
+}
+
+/// The finality policy of the origin chain, along with the source it's
+/// evaluated against. Sources are only needed by policies other than block
+/// counts, which are a fixed lag, and by the reorg-safety mode.
+#[derive(Clone, Debug)]
+pub(crate) struct OriginFinality {
+    policy: Finality,
+    source: Option<Arc<dyn FinalitySource>>,
+    /// Whether only checkpoints behind the chain's finalized tag are signed,
+    /// on top of the policy
+    reorg_safety: bool,
+}
+
+impl OriginFinality {
+    pub(crate) async fn new(
+        origin_chain_conf: &ChainConf,
+        metrics: &CoreMetrics,
+        reorg_safety: bool,
+    ) -> Result<Self> {
+        let source = match origin_chain_conf.finality {
+            Finality::Blocks(_) if !reorg_safety => None,
+            _ => Some(
+                origin_chain_conf
+                    .build_finality_source(metrics)
+                    .await?
+                    .into(),
+            ),
+        };
+        Ok(Self {
+            policy: origin_chain_conf.finality.clone(),
+            source,
+            reorg_safety,
+        })
+    }
+
+    /// How far behind the tip the origin is queried, so that only final
+    /// checkpoints are signed
+    pub(crate) async fn lag(&self) -> ChainResult<Option<NonZeroU64>> {
+        let lag = match (&self.policy, &self.source) {
+            (Finality::Blocks(blocks), _) => NonZeroU64::new((*blocks).into()),
+            (policy, Some(source)) => policy.lag(source.as_ref()).await?,
+            (_, None) => unreachable!("sources are built for policies other than block counts"),
+        };
+        match &self.source {
+            Some(source) if self.reorg_safety => {
+                // Chains without a finalized tag are left to the policy
+                let finalized_lag = match source.tagged_block_number(BlockTag::Finalized).await? {
+                    Some(finalized) => {
+                        let latest = source.latest_block_number().await?;
+                        NonZeroU64::new(latest.saturating_sub(finalized).into())
+                    }
+                    None => None,
+                };
+                Ok(lag.max(finalized_lag))
+            }
+            _ => Ok(lag),
+        }
+    }
+
+    /// Whether checkpoints are only signed once they're behind the finalized
+    /// tag
+    pub(crate) fn reorg_safety(&self) -> bool {
+        self.reorg_safety
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct ValidatorSubmitter {
     interval: Duration,
-    reorg_period: Option<NonZeroU64>,
+    /// Which checkpoints are signed, if not all of them
+    sampling: Option<CheckpointSamplingConf>,
+    /// When the latest checkpoint was last signed, if sampling defers it
+    latest_signed_at: Arc<Mutex<Option<Instant>>>,
+    finality: OriginFinality,
     signer: SingletonSignerHandle,
     merkle_tree_hook: Arc<dyn MerkleTreeHook>,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    /// Source of the timestamps of the blocks of insertions, on chains that
+    /// support it
+    block_timestamps: Option<Arc<dyn FinalitySource>>,
     message_db: HyperlaneRocksDB,
+    double_sign_guard: DoubleSignGuard,
+    leader: LeaderHandle,
     metrics: ValidatorSubmitterMetrics,
 }
 
 impl ValidatorSubmitter {
     pub(crate) fn new(
         interval: Duration,
-        reorg_period: u64,
+        sampling: Option<CheckpointSamplingConf>,
+        finality: OriginFinality,
         merkle_tree_hook: Arc<dyn MerkleTreeHook>,
         signer: SingletonSignerHandle,
         checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+        block_timestamps: Option<Arc<dyn FinalitySource>>,
         message_db: HyperlaneRocksDB,
+        double_sign_guard: DoubleSignGuard,
+        leader: LeaderHandle,
         metrics: ValidatorSubmitterMetrics,
     ) -> Self {
         Self {
-            reorg_period: NonZeroU64::new(reorg_period),
+            finality,
             interval,
+            sampling,
+            latest_signed_at: Default::default(),
             merkle_tree_hook,
             signer,
             checkpoint_syncer,
+            block_timestamps,
             message_db,
+            double_sign_guard,
+            leader,
             metrics,
         }
     }
@@ -65,6 +193,7 @@ impl ValidatorSubmitter {
             let target_checkpoint = target_checkpoint;
             let self_clone = self.clone();
             Box::pin(async move {
+                self_clone.leader.wait_until_leader().await;
                 self_clone
                     .submit_checkpoints_until_correctness_checkpoint(&mut tree, &target_checkpoint)
                     .await?;
@@ -99,10 +228,17 @@ impl ValidatorSubmitter {
         };
 
         loop {
-            // Lag by reorg period because this is our correctness checkpoint.
+            // Only the leader of the replicas signs checkpoints
+            self.leader.wait_until_leader().await;
+
+            // Lag by the finality of the origin because this is our correctness checkpoint.
             let latest_checkpoint = call_and_retry_indefinitely(|| {
                 let merkle_tree_hook = self.merkle_tree_hook.clone();
-                Box::pin(async move { merkle_tree_hook.latest_checkpoint(self.reorg_period).await })
+                let finality = self.finality.clone();
+                Box::pin(async move {
+                    let lag = finality.lag().await?;
+                    merkle_tree_hook.latest_checkpoint(lag).await
+                })
             })
             .await;
 
@@ -110,6 +246,11 @@ impl ValidatorSubmitter {
                 .latest_checkpoint_observed
                 .set(latest_checkpoint.index as i64);
 
+            if self.finality.reorg_safety() {
+                self.update_unsigned_due_to_finality(&latest_checkpoint)
+                    .await;
+            }
+
             if should_log_checkpoint_info() {
                 info!(
                     ?latest_checkpoint,
@@ -133,6 +274,7 @@ impl ValidatorSubmitter {
                 continue;
             }
 
+            let count_before = tree.count();
             tree = call_and_retry_indefinitely(|| {
                 let mut tree = tree;
                 let self_clone = self.clone();
@@ -151,11 +293,59 @@ impl ValidatorSubmitter {
             self.metrics
                 .latest_checkpoint_processed
                 .set(latest_checkpoint.index as i64);
+            if tree.count() > count_before {
+                self.observe_publication_latency(latest_checkpoint.index)
+                    .await;
+            }
 
             sleep(self.interval).await;
         }
     }
 
+    /// Observes how long after the block of the insertion at `index` its
+    /// signed checkpoint was written. Only the latest checkpoint of each pass
+    /// is observed, which bounds the latency of the others.
+    async fn observe_publication_latency(&self, index: u32) {
+        let Some(block_timestamps) = &self.block_timestamps else {
+            return;
+        };
+        let timestamp = match self
+            .message_db
+            .retrieve_merkle_tree_insertion_block_number_by_leaf_index(&index)
+        {
+            Ok(Some(block_number)) => block_timestamps.block_timestamp(block_number as u32).await,
+            Ok(None) => return,
+            Err(err) => Err(ChainCommunicationError::from_other(err)),
+        };
+        match timestamp {
+            Ok(timestamp) => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                self.metrics
+                    .publication_latency
+                    .observe(now.saturating_sub(timestamp) as f64);
+            }
+            Err(err) => debug!(
+                ?err,
+                index, "Failed to get the block timestamp of insertion"
+            ),
+        }
+    }
+
+    /// Sets how many checkpoints past `latest_checkpoint` are left unsigned
+    /// because they aren't final yet
+    async fn update_unsigned_due_to_finality(&self, latest_checkpoint: &Checkpoint) {
+        match self.merkle_tree_hook.latest_checkpoint(None).await {
+            Ok(tip_checkpoint) => self
+                .metrics
+                .unsigned_due_to_finality
+                .set(tip_checkpoint.index.saturating_sub(latest_checkpoint.index) as i64),
+            Err(err) => debug!(?err, "Failed to get the latest checkpoint at the tip"),
+        }
+    }
+
     /// Submits signed checkpoints relating to the given tree until the correctness checkpoint (inclusive).
     /// Only submits the signed checkpoints once the correctness checkpoint is reached.
     async fn submit_checkpoints_until_correctness_checkpoint(
@@ -243,50 +433,163 @@ impl ValidatorSubmitter {
                 index = checkpoint.index,
                 "Signed all queued checkpoints until index"
             );
+        } else if self.latest_signing_due() {
+            self.sign_deferred_latest_checkpoint(checkpoint).await?;
         }
 
         Ok(())
     }
 
+    /// Whether the latest checkpoint should be signed, rather than deferred
+    /// until the latest signing interval elapsed
+    fn latest_signing_due(&self) -> bool {
+        let Some(interval) = self
+            .sampling
+            .and_then(|sampling| sampling.latest_signing_interval)
+        else {
+            return true;
+        };
+        self.latest_signed_at
+            .lock()
+            .expect("poisoned lock")
+            .map_or(true, |signed_at| signed_at.elapsed() >= interval)
+    }
+
+    /// Signs `checkpoint`, the latest one, if signing it was deferred and no
+    /// checkpoints were queued since
+    async fn sign_deferred_latest_checkpoint(&self, checkpoint: Checkpoint) -> ChainResult<()> {
+        if self
+            .sampling
+            .and_then(|sampling| sampling.latest_signing_interval)
+            .is_none()
+        {
+            return Ok(());
+        }
+        let latest_index = self
+            .checkpoint_syncer
+            .latest_index()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        if latest_index >= Some(checkpoint.index) {
+            *self.latest_signed_at.lock().expect("poisoned lock") = Some(Instant::now());
+            return Ok(());
+        }
+        let Some(insertion) = self
+            .message_db
+            .retrieve_merkle_tree_insertion_by_leaf_index(&checkpoint.index)?
+        else {
+            return Ok(());
+        };
+        debug!(
+            index = checkpoint.index,
+            "Signing deferred latest checkpoint"
+        );
+        self.sign_and_submit_checkpoints(vec![CheckpointWithMessageId {
+            checkpoint,
+            message_id: insertion.message_id(),
+        }])
+        .await
+    }
+
     /// Signs and submits any previously unsubmitted checkpoints.
     async fn sign_and_submit_checkpoints(
         &self,
         checkpoints: Vec<CheckpointWithMessageId>,
     ) -> ChainResult<()> {
         let last_checkpoint = checkpoints.as_slice()[checkpoints.len() - 1];
+        let checkpoints = match &self.sampling {
+            Some(sampling) => sampling.sample(checkpoints, self.latest_signing_due()),
+            None => checkpoints,
+        };
+        // Relayers fetch the checkpoint at the latest index, so it has to be
+        // signed
+        let Some(last_signed) = checkpoints.last().map(|checkpoint| checkpoint.index) else {
+            return Ok(());
+        };
 
-        for queued_checkpoint in checkpoints {
-            let existing = self
-                .checkpoint_syncer
-                .fetch_checkpoint(queued_checkpoint.index)
-                .await?;
-            if existing.is_some() {
-                debug!(
-                    index = queued_checkpoint.index,
-                    "Checkpoint already submitted"
-                );
-                continue;
-            }
-            let signed_checkpoint = self.signer.sign(queued_checkpoint).await?;
-            self.checkpoint_syncer
-                .write_checkpoint(&signed_checkpoint)
-                .await?;
-            debug!(
-                index = queued_checkpoint.index,
-                "Signed and submitted checkpoint"
-            );
+        // Checkpoints are signed and written in batches, pausing between
+        // them to avoid rate limiting
+        for batch in checkpoints.chunks(SIGNING_BATCH_SIZE) {
+            try_join_all(
+                batch
+                    .iter()
+                    .map(|checkpoint| self.sign_and_submit_checkpoint(*checkpoint)),
+            )
+            .await?;
 
             // TODO: move these into S3 implementations
-            // small sleep before signing next checkpoint to avoid rate limiting
             sleep(Duration::from_millis(100)).await;
         }
 
+        if last_signed == last_checkpoint.index {
+            *self.latest_signed_at.lock().expect("poisoned lock") = Some(Instant::now());
+        }
         self.checkpoint_syncer
-            .update_latest_index(last_checkpoint.index)
+            .update_latest_index(last_signed)
             .await?;
 
         Ok(())
     }
+
+    /// Signs and submits the checkpoint, unless it was submitted before
+    async fn sign_and_submit_checkpoint(
+        &self,
+        queued_checkpoint: CheckpointWithMessageId,
+    ) -> ChainResult<()> {
+        if !self.leader.is_leader() {
+            return Err(ChainCommunicationError::from_other_str(
+                "Lost leadership, not signing checkpoints",
+            ));
+        }
+        // Pruned checkpoints aren't signed again, e.g. while backfilling
+        if self
+            .message_db
+            .retrieve_lowest_retained_checkpoint_index()?
+            .map_or(false, |lowest_retained| {
+                queued_checkpoint.index < lowest_retained
+            })
+        {
+            return Ok(());
+        }
+        let existing = self
+            .checkpoint_syncer
+            .fetch_checkpoint(queued_checkpoint.index)
+            .await?;
+        if let Some(existing) = existing {
+            if existing.value.root != queued_checkpoint.root {
+                return Err(ChainCommunicationError::from_other(
+                    self.double_sign_guard
+                        .refuse(&queued_checkpoint, existing.value.root),
+                ));
+            }
+            debug!(
+                index = queued_checkpoint.index,
+                "Checkpoint already submitted"
+            );
+            return Ok(());
+        }
+        // Never sign a root that conflicts with one signed before
+        self.double_sign_guard
+            .check_and_record(&queued_checkpoint)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let signing_start = Instant::now();
+        let signed_checkpoint = self.signer.sign(queued_checkpoint).await.map_err(|err| {
+            self.metrics.signing_failures.inc();
+            err
+        })?;
+        self.metrics
+            .signing_duration
+            .observe(signing_start.elapsed().as_secs_f64());
+        self.checkpoint_syncer
+            .write_checkpoint(&signed_checkpoint)
+            .await?;
+        debug!(
+            index = queued_checkpoint.index,
+            "Signed and submitted checkpoint"
+        );
+        Ok(())
+    }
 }
 
 /// Returns whether the tree exceeds the checkpoint.
@@ -300,18 +603,98 @@ fn tree_exceeds_checkpoint(checkpoint: &Checkpoint, tree: &IncrementalMerkle) ->
 pub(crate) struct ValidatorSubmitterMetrics {
     latest_checkpoint_observed: IntGauge,
     latest_checkpoint_processed: IntGauge,
+    unsigned_due_to_finality: IntGauge,
+    signing_duration: Histogram,
+    signing_failures: IntCounter,
+    publication_latency: Histogram,
 }
 
 impl ValidatorSubmitterMetrics {
-    pub fn new(metrics: &CoreMetrics, mailbox_chain: &HyperlaneDomain) -> Self {
+    pub fn new(metrics: &CoreMetrics, mailbox_chain: &HyperlaneDomain) -> Result<Self> {
         let chain_name = mailbox_chain.name();
-        Self {
+        Ok(Self {
             latest_checkpoint_observed: metrics
                 .latest_checkpoint()
                 .with_label_values(&["validator_observed", chain_name]),
             latest_checkpoint_processed: metrics
                 .latest_checkpoint()
                 .with_label_values(&["validator_processed", chain_name]),
+            unsigned_due_to_finality: metrics
+                .new_int_gauge(
+                    "validator_checkpoints_unsigned_due_to_finality",
+                    "Checkpoints at the tip of the origin that are left unsigned until they're final, in reorg-safety mode",
+                    &["chain"],
+                )?
+                .with_label_values(&[chain_name]),
+            signing_duration: metrics
+                .new_histogram(
+                    "validator_checkpoint_signing_duration_seconds",
+                    "How long signing a checkpoint took, including retries of remote signers",
+                    &["chain"],
+                    vec![0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1., 2.5, 5., 10., 30.],
+                )?
+                .with_label_values(&[chain_name]),
+            signing_failures: metrics
+                .new_int_counter(
+                    "validator_checkpoint_signing_failures",
+                    "Checkpoints that failed to be signed, e.g. while a remote signer is unavailable",
+                    &["chain"],
+                )?
+                .with_label_values(&[chain_name]),
+            publication_latency: metrics
+                .new_histogram(
+                    "validator_checkpoint_publication_latency_seconds",
+                    "Time from the block of a merkle tree insertion to its signed checkpoint being written to storage",
+                    &["chain"],
+                    vec![5., 10., 30., 60., 120., 300., 600., 1200., 1800., 3600., 7200.],
+                )?
+                .with_label_values(&[chain_name]),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::H256;
+
+    use super::*;
+
+    fn checkpoint(index: u32) -> CheckpointWithMessageId {
+        CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: H256::zero(),
+                mailbox_domain: 1,
+                root: H256::from_low_u64_be(index.into()),
+                index,
+            },
+            message_id: H256::from_low_u64_be(index.into()),
         }
     }
+
+    fn sampled_indices(
+        indices: std::ops::RangeInclusive<u32>,
+        every_nth_index: u32,
+        sign_latest: bool,
+    ) -> Vec<u32> {
+        let sampling = CheckpointSamplingConf {
+            every_nth_index: NonZeroU32::new(every_nth_index).unwrap(),
+            latest_signing_interval: None,
+        };
+        sampling
+            .sample(indices.map(checkpoint).collect(), sign_latest)
+            .into_iter()
+            .map(|checkpoint| checkpoint.index)
+            .collect()
+    }
+
+    #[test]
+    fn test_sampled_checkpoints() {
+        assert_eq!(sampled_indices(3..=7, 1, true), vec![3, 4, 5, 6, 7]);
+        assert_eq!(sampled_indices(3..=12, 5, true), vec![5, 10, 12]);
+        assert_eq!(sampled_indices(3..=10, 5, true), vec![5, 10]);
+        assert_eq!(sampled_indices(6..=6, 5, true), vec![6]);
+        // The latest checkpoint can be deferred
+        assert_eq!(sampled_indices(3..=12, 5, false), vec![5, 10]);
+        assert!(sampled_indices(6..=6, 5, false).is_empty());
+    }
 }
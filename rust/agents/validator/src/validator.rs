@@ -1,4 +1,4 @@
-use std::{num::NonZeroU64, sync::Arc, time::Duration};
+use std::{sync::Arc, time::Duration};
 
 use crate::server as validator_server;
 use async_trait::async_trait;
@@ -7,26 +7,34 @@ use eyre::Result;
 
 use futures_util::future::try_join_all;
 use tokio::{task::JoinHandle, time::sleep};
-use tracing::{error, info, info_span, instrument::Instrumented, warn, Instrument};
+use tracing::{debug, error, info, info_span, instrument::Instrumented, warn, Instrument};
 
 use hyperlane_base::{
     db::{HyperlaneRocksDB, DB},
-    metrics::AgentMetrics,
-    settings::ChainConf,
-    BaseAgent, ChainMetrics, CheckpointSyncer, ContractSyncMetrics, ContractSyncer, CoreMetrics,
-    HyperlaneAgentCore, MetricsUpdater, SequencedDataContractSync,
+    metrics::{AgentMetrics, BalanceMonitor, DbMetricsUpdater},
+    settings::{ChainConf, ConfigReloader},
+    AgentHealth, BaseAgent, ChainMetrics, CheckpointSyncer, ContractSyncMetrics, ContractSyncer,
+    CoreMetrics, DbSnapshotApi, HyperlaneAgentCore, LogFilterApi, MetricsUpdater, ObjectWriter,
+    SequencedDataContractSync,
 };
 
 use hyperlane_core::{
-    Announcement, ChainResult, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneSigner,
-    HyperlaneSignerExt, Mailbox, MerkleTreeHook, MerkleTreeInsertion, TxOutcome, ValidatorAnnounce,
-    H256, U256,
+    Announcement, ChainResult, FinalitySource, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneSigner, HyperlaneSignerExt, Mailbox, MerkleTreeHook, MerkleTreeInsertion, TxOutcome,
+    ValidatorAnnounce, H256, U256,
 };
 use hyperlane_ethereum::{SingletonSigner, SingletonSignerHandle};
 
 use crate::{
+    fanout::FanoutCheckpointSyncer,
+    leader::{LeaderElector, LeaderHandle},
+    retention::{CheckpointRetention, CheckpointRetentionConf},
+    safety::DoubleSignGuard,
+    self_check::SelfCheck,
     settings::ValidatorSettings,
-    submit::{ValidatorSubmitter, ValidatorSubmitterMetrics},
+    submit::{
+        CheckpointSamplingConf, OriginFinality, ValidatorSubmitter, ValidatorSubmitterMetrics,
+    },
 };
 
 /// A validator agent
@@ -44,9 +52,20 @@ pub struct Validator {
     signer: SingletonSignerHandle,
     // temporary holder until `run` is called
     signer_instance: Option<Box<SingletonSigner>>,
-    reorg_period: u64,
+    origin_finality: OriginFinality,
+    block_timestamps: Option<Arc<dyn FinalitySource>>,
     interval: Duration,
-    checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    checkpoint_sampling: Option<CheckpointSamplingConf>,
+    auto_announce: bool,
+    checkpoint_syncer: Arc<FanoutCheckpointSyncer>,
+    leader: LeaderHandle,
+    // temporary holder until `run` is called
+    leader_elector: Option<LeaderElector>,
+    checkpoint_retention: Option<CheckpointRetentionConf>,
+    /// Serves database snapshots, if an admin api key is configured
+    db_snapshot_api: Option<DbSnapshotApi>,
+    /// Changes the log filter, if an admin api key is configured
+    log_filter_api: Option<LogFilterApi>,
     core_metrics: Arc<CoreMetrics>,
     agent_metrics: AgentMetrics,
     chain_metrics: ChainMetrics,
@@ -68,14 +87,58 @@ impl BaseAgent for Validator {
     where
         Self: Sized,
     {
-        let db = DB::from_path(&settings.db)?;
+        let db = DB::open(
+            settings.db_backend,
+            settings.db_tuning,
+            &settings.db,
+            settings.db_restore_from.as_deref(),
+        )?;
+        AgentHealth::global().pass("db");
+        let db_snapshot_api = match &settings.admin_api_key {
+            Some(api_key) => {
+                let writer: Option<Arc<dyn ObjectWriter>> = match &settings.db_snapshot_location {
+                    Some(location) => Some(location.build_object_writer().await?.into()),
+                    None => None,
+                };
+                Some(DbSnapshotApi::new(
+                    api_key.clone(),
+                    Self::AGENT_NAME,
+                    db.clone(),
+                    writer,
+                ))
+            }
+            None => None,
+        };
+        let log_filter_api = settings
+            .admin_api_key
+            .clone()
+            .and_then(LogFilterApi::global);
         let msg_db = HyperlaneRocksDB::new(&settings.origin_chain, db);
 
         // Intentionally using hyperlane_ethereum for the validator's signer
         let (signer_instance, signer) = SingletonSigner::new(settings.validator.build().await?);
 
         let core = settings.build_hyperlane_core(metrics.clone());
-        let checkpoint_syncer = settings.checkpoint_syncer.build(None).await?.into();
+        let checkpoint_syncers = try_join_all(
+            settings
+                .checkpoint_syncers
+                .iter()
+                .map(|conf| async move { conf.build(None).await.map(Arc::from) }),
+        )
+        .await?;
+        let checkpoint_syncer = Arc::new(FanoutCheckpointSyncer::new(
+            checkpoint_syncers,
+            &metrics,
+            &settings.origin_chain,
+        )?);
+
+        let (leader_elector, leader) = match &settings.leader_election {
+            Some(conf) => {
+                let (elector, leader) = LeaderElector::new(conf, &metrics, &settings.origin_chain)?;
+                (Some(elector), leader)
+            }
+            None => (None, LeaderHandle::always_leader()),
+        };
 
         let mailbox = settings
             .build_mailbox(&settings.origin_chain, &metrics)
@@ -94,9 +157,33 @@ impl BaseAgent for Validator {
             .chain_setup(&settings.origin_chain)
             .unwrap()
             .clone();
+        let origin_finality =
+            OriginFinality::new(&origin_chain_conf, &metrics, settings.reorg_safety).await?;
+        // Only used for metrics, so chains without it are fine
+        let block_timestamps = match origin_chain_conf.build_finality_source(&metrics).await {
+            Ok(source) => Some(source.into()),
+            Err(err) => {
+                debug!(?err, "Not measuring checkpoint publication latency");
+                None
+            }
+        };
 
         let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&metrics));
 
+        // Verified before the contract sync builds its cursor, which then
+        // indexes the invalidated insertions again
+        if let Some(conf) = &settings.db_integrity_check {
+            let insertions = origin_chain_conf
+                .build_merkle_tree_hook_indexer(&metrics)
+                .await?;
+            if let Err(err) = msg_db
+                .verify_against_chain(conf, None, Some(insertions.as_ref()))
+                .await
+            {
+                warn!(?err, "Failed to verify the database against the chain");
+            }
+        }
+
         let merkle_tree_hook_sync = settings
             .sequenced_contract_sync::<MerkleTreeInsertion, _>(
                 &settings.origin_chain,
@@ -117,22 +204,38 @@ impl BaseAgent for Validator {
             validator_announce: validator_announce.into(),
             signer,
             signer_instance: Some(Box::new(signer_instance)),
-            reorg_period: settings.reorg_period,
+            origin_finality,
+            block_timestamps,
             interval: settings.interval,
+            checkpoint_sampling: settings.checkpoint_sampling,
+            auto_announce: settings.auto_announce,
             checkpoint_syncer,
+            leader,
+            leader_elector,
+            checkpoint_retention: settings.checkpoint_retention,
+            db_snapshot_api,
+            log_filter_api,
             agent_metrics,
             chain_metrics,
             core_metrics: metrics,
         })
     }
 
+    fn preflight_chains(settings: &Self::Settings) -> Vec<HyperlaneDomain> {
+        vec![settings.origin_chain.clone()]
+    }
+
     #[allow(clippy::async_yields_async)]
     async fn run(mut self) {
         let mut tasks = vec![];
 
         // run server
-        let custom_routes =
-            validator_server::routes(self.origin_chain.clone(), self.core.metrics.clone());
+        let custom_routes = validator_server::routes(
+            self.origin_chain.clone(),
+            self.core.metrics.clone(),
+            self.db_snapshot_api.clone(),
+            self.log_filter_api.clone(),
+        );
         let server = self
             .core
             .settings
@@ -144,6 +247,10 @@ impl BaseAgent for Validator {
         .instrument(info_span!("Validator server"));
         tasks.push(server_task);
 
+        let config_reloader = ConfigReloader::new(self.core.settings.config_watch_interval)
+            .expect("Failed to start config reloader");
+        tasks.push(config_reloader.spawn::<ValidatorSettings>(&self.core.settings));
+
         if let Some(signer_instance) = self.signer_instance.take() {
             tasks.push(
                 tokio::spawn(async move {
@@ -153,10 +260,32 @@ impl BaseAgent for Validator {
             );
         }
 
+        if let Some(leader_elector) = self.leader_elector.take() {
+            tasks.push(
+                tokio::spawn(async move {
+                    leader_elector.run().await;
+                })
+                .instrument(info_span!("LeaderElector")),
+            );
+        }
+
+        let balance_monitor = BalanceMonitor::new(
+            &self.core.settings,
+            &self.core_metrics,
+            self.agent_metrics.clone(),
+            Self::AGENT_NAME.to_string(),
+        )
+        .await;
+        tasks.push(balance_monitor.spawn());
+
+        match DbMetricsUpdater::new(self.db.as_ref().clone(), &self.core_metrics) {
+            Ok(db_metrics_updater) => tasks.push(db_metrics_updater.spawn()),
+            Err(err) => warn!(?err, "Not reporting database metrics"),
+        }
+
         let metrics_updater = MetricsUpdater::new(
             &self.origin_chain_conf,
             self.core_metrics.clone(),
-            self.agent_metrics.clone(),
             self.chain_metrics.clone(),
             Self::AGENT_NAME.to_string(),
         )
@@ -172,12 +301,14 @@ impl BaseAgent for Validator {
         // announce the validator after spawning the signer task
         self.announce().await.expect("Failed to announce validator");
 
-        let reorg_period = NonZeroU64::new(self.reorg_period);
-
         // Ensure that the merkle tree hook has count > 0 before we begin indexing
         // messages or submitting checkpoints.
         loop {
-            match self.merkle_tree_hook.count(reorg_period).await {
+            let count = match self.origin_finality.lag().await {
+                Ok(lag) => self.merkle_tree_hook.count(lag).await,
+                Err(err) => Err(err),
+            };
+            match count {
                 Ok(0) => {
                     info!("Waiting for first message in merkle tree hook");
                     sleep(self.interval).await;
@@ -187,6 +318,10 @@ impl BaseAgent for Validator {
                     for checkpoint_sync_task in self.run_checkpoint_submitters().await {
                         tasks.push(checkpoint_sync_task);
                     }
+                    tasks.push(self.run_self_check());
+                    if let Some(retention_task) = self.run_checkpoint_retention().await {
+                        tasks.push(retention_task);
+                    }
                     break;
                 }
                 _ => {
@@ -219,20 +354,32 @@ impl Validator {
     }
 
     async fn run_checkpoint_submitters(&self) -> Vec<Instrumented<JoinHandle<()>>> {
+        let double_sign_guard =
+            DoubleSignGuard::new(self.db.clone(), &self.core.metrics, &self.origin_chain)
+                .expect("failed to create double sign guard");
         let submitter = ValidatorSubmitter::new(
             self.interval,
-            self.reorg_period,
+            self.checkpoint_sampling,
+            self.origin_finality.clone(),
             self.merkle_tree_hook.clone(),
             self.signer.clone(),
             self.checkpoint_syncer.clone(),
+            self.block_timestamps.clone(),
             self.db.clone(),
-            ValidatorSubmitterMetrics::new(&self.core.metrics, &self.origin_chain),
+            double_sign_guard,
+            self.leader.clone(),
+            ValidatorSubmitterMetrics::new(&self.core.metrics, &self.origin_chain)
+                .expect("failed to register validator submitter metrics"),
         );
 
-        let reorg_period = NonZeroU64::new(self.reorg_period);
+        let lag = self
+            .origin_finality
+            .lag()
+            .await
+            .expect("failed to get the finality lag");
         let tip_tree = self
             .merkle_tree_hook
-            .tree(reorg_period)
+            .tree(lag)
             .await
             .expect("failed to get merkle tree");
         // This function is only called after we have already checked that the
@@ -261,6 +408,41 @@ impl Validator {
         tasks
     }
 
+    fn run_self_check(&self) -> Instrumented<JoinHandle<()>> {
+        let locations = self
+            .checkpoint_syncer
+            .syncers()
+            .map(|syncer| syncer.announcement_location())
+            .collect();
+        let self_check = SelfCheck::new(
+            self.signer.eth_address(),
+            locations,
+            self.validator_announce.clone(),
+            self.origin_chain.clone(),
+            &self.core.metrics,
+        )
+        .expect("failed to create self check");
+        tokio::spawn(async move { self_check.run().await }).instrument(info_span!("SelfCheck"))
+    }
+
+    async fn run_checkpoint_retention(&self) -> Option<Instrumented<JoinHandle<()>>> {
+        let conf = self.checkpoint_retention.as_ref()?;
+        let retention = CheckpointRetention::new(
+            conf,
+            self.checkpoint_syncer.clone(),
+            self.db.clone(),
+            self.leader.clone(),
+            &self.core.metrics,
+            &self.origin_chain,
+        )
+        .await
+        .expect("failed to create checkpoint retention");
+        Some(
+            tokio::spawn(async move { retention.run().await })
+                .instrument(info_span!("CheckpointRetention")),
+        )
+    }
+
     fn log_on_announce_failure(result: ChainResult<TxOutcome>, chain_signer: &String) {
         match result {
             Ok(outcome) => {
@@ -291,8 +473,27 @@ impl Validator {
     }
 
     async fn announce(&self) -> Result<()> {
+        // The primary location is announced last, so that relayers, which
+        // prefer the most recently announced location, read from it
+        let mut syncers = self.checkpoint_syncer.syncers();
+        let primary = syncers.next().expect("validators have a checkpoint syncer");
+        for checkpoint_syncer in syncers.rev() {
+            // Additional syncers that are down don't keep the validator from
+            // signing checkpoints
+            if let Err(err) = self.announce_location(checkpoint_syncer.as_ref()).await {
+                warn!(
+                    ?err,
+                    location = checkpoint_syncer.announcement_location(),
+                    "Failed to announce additional checkpoint syncer"
+                );
+            }
+        }
+        self.announce_location(primary.as_ref()).await
+    }
+
+    async fn announce_location(&self, checkpoint_syncer: &dyn CheckpointSyncer) -> Result<()> {
         let address = self.signer.eth_address();
-        let announcement_location = self.checkpoint_syncer.announcement_location();
+        let announcement_location = checkpoint_syncer.announcement_location();
 
         // Sign and post the validator announcement
         let announcement = Announcement {
@@ -302,7 +503,7 @@ impl Validator {
             storage_location: announcement_location.clone(),
         };
         let signed_announcement = self.signer.sign(announcement.clone()).await?;
-        self.checkpoint_syncer
+        checkpoint_syncer
             .write_announcement(&signed_announcement)
             .await?;
 
@@ -331,11 +532,24 @@ impl Validator {
                     announced_locations=?locations,
                     "Validator has not announced signature storage location"
                 );
+                if !locations.is_empty() {
+                    info!(
+                        announced_locations=?locations,
+                        ?announcement_location,
+                        "Signature storage location changed since the validator last announced"
+                    );
+                }
 
-                if let Some(chain_signer) = self.core.settings.chains[self.origin_chain.name()]
+                let chain_signer = self.core.settings.chains[self.origin_chain.name()]
                     .chain_signer()
-                    .await?
-                {
+                    .await?;
+                if !self.auto_announce {
+                    warn!(
+                        ?announcement_location,
+                        signed_announcement = %serde_json::to_string(&signed_announcement)?,
+                        "Automatic announcements are disabled; submit the signed announcement to the ValidatorAnnounce contract of the origin chain"
+                    );
+                } else if let Some(chain_signer) = chain_signer {
                     let chain_signer = chain_signer.address_string();
                     info!(eth_validator_address=?announcement.validator, ?chain_signer, "Attempting self announce");
                     let balance_delta = self
@@ -0,0 +1,51 @@
+//! The ARC-4 encoding of application call arguments, and the ARC-28 events
+//! logged by applications.
+
+use sha2::{Digest, Sha512_256};
+
+/// The first 4 bytes of the SHA-512/256 hash of a method or event signature,
+/// e.g. `process(byte[],byte[])void`
+pub(crate) fn selector(signature: &str) -> [u8; 4] {
+    let hash = Sha512_256::digest(signature.as_bytes());
+    let mut selector = [0; 4];
+    selector.copy_from_slice(&hash[..4]);
+    selector
+}
+
+/// Encodes a `byte[]` argument, prefixed with its 16 bit length
+pub(crate) fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = (bytes.len() as u16).to_be_bytes().to_vec();
+    encoded.extend_from_slice(bytes);
+    encoded
+}
+
+/// The arguments of the event with `signature` if `log` is one, i.e. the log
+/// without its selector
+pub(crate) fn event_args<'a>(log: &'a [u8], signature: &str) -> Option<&'a [u8]> {
+    log.strip_prefix(&selector(signature))
+}
+
+/// Decodes an event whose only argument is a `byte[]`, which is encoded after
+/// its offset in the arguments tuple
+pub(crate) fn decode_single_bytes(args: &[u8]) -> Option<&[u8]> {
+    let (offset, _) = read_u16(args)?;
+    let (len, tail) = read_u16(args.get(offset as usize..)?)?;
+    tail.get(..len as usize)
+}
+
+fn read_u16(bytes: &[u8]) -> Option<(u16, &[u8])> {
+    let value = bytes.get(..2)?;
+    Some((u16::from_be_bytes([value[0], value[1]]), &bytes[2..]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_single_bytes() {
+        let args = [&[0, 2][..], &encode_bytes(b"message")].concat();
+        assert_eq!(decode_single_bytes(&args), Some(&b"message"[..]));
+        assert_eq!(decode_single_bytes(&args[..6]), None);
+    }
+}
@@ -0,0 +1,177 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hyperlane_core::ChainResult;
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use url::Url;
+
+use crate::{
+    types::{
+        Account, Application, BoxValue, IndexerHealth, PendingTransaction, SimulationResult,
+        SubmittedTransaction, TransactionParams, TransactionsPage,
+    },
+    HyperlaneAlgorandError,
+};
+
+/// The maximum number of transactions returned by the indexer per page
+pub(crate) const TRANSACTIONS_PAGE_SIZE: u32 = 1000;
+
+/// A client of the REST APIs of an algod node and of an indexer
+#[derive(Debug, Clone)]
+pub(crate) struct AlgorandClient {
+    http: Client,
+    url: Url,
+    indexer_url: Url,
+    api_token: Option<String>,
+}
+
+impl AlgorandClient {
+    pub(crate) fn new(url: Url, indexer_url: Url, api_token: Option<String>) -> Self {
+        Self {
+            http: Client::new(),
+            url,
+            indexer_url,
+            api_token,
+        }
+    }
+
+    fn node(&self, method: Method, path: &str) -> RequestBuilder {
+        let url = format!("{}{path}", self.url.as_str().trim_end_matches('/'));
+        let request = self.http.request(method, url);
+        match &self.api_token {
+            Some(token) => request.header("X-Algo-API-Token", token),
+            None => request,
+        }
+    }
+
+    fn indexer(&self, path: &str) -> RequestBuilder {
+        let url = format!("{}{path}", self.indexer_url.as_str().trim_end_matches('/'));
+        let request = self.http.get(url);
+        match &self.api_token {
+            Some(token) => request.header("X-Indexer-API-Token", token),
+            None => request,
+        }
+    }
+
+    /// Sends `request`, turning error responses into API errors
+    async fn send<T: DeserializeOwned>(
+        request: RequestBuilder,
+    ) -> Result<T, HyperlaneAlgorandError> {
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response.json().await?);
+        }
+        let body: Value = response.json().await.unwrap_or_default();
+        Err(HyperlaneAlgorandError::ApiError {
+            status: status.as_u16(),
+            message: body["message"].as_str().unwrap_or_default().to_owned(),
+        })
+    }
+
+    /// The parameters of transactions suggested by the node
+    pub(crate) async fn params(&self) -> ChainResult<TransactionParams> {
+        Ok(Self::send(self.node(Method::GET, "/v2/transactions/params")).await?)
+    }
+
+    /// The application `app_id`, unless it doesn't exist
+    pub(crate) async fn application(&self, app_id: u64) -> ChainResult<Option<Application>> {
+        let request = self.node(Method::GET, &format!("/v2/applications/{app_id}"));
+        match Self::send(request).await {
+            Ok(application) => Ok(Some(application)),
+            Err(HyperlaneAlgorandError::ApiError { status, .. })
+                if status == StatusCode::NOT_FOUND.as_u16() =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// The value of the box `name` of the application `app_id`, unless it
+    /// doesn't exist
+    pub(crate) async fn application_box(
+        &self,
+        app_id: u64,
+        name: &[u8],
+    ) -> ChainResult<Option<Vec<u8>>> {
+        let request = self
+            .node(Method::GET, &format!("/v2/applications/{app_id}/box"))
+            .query(&[("name", format!("b64:{}", BASE64.encode(name)))]);
+        match Self::send::<BoxValue>(request).await {
+            Ok(value) => Ok(Some(
+                BASE64
+                    .decode(value.value)
+                    .map_err(HyperlaneAlgorandError::from)?,
+            )),
+            Err(HyperlaneAlgorandError::ApiError { status, .. })
+                if status == StatusCode::NOT_FOUND.as_u16() =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub(crate) async fn account(&self, address: &str) -> ChainResult<Account> {
+        let request = self
+            .node(Method::GET, &format!("/v2/accounts/{address}"))
+            .query(&[("exclude", "all")]);
+        Ok(Self::send(request).await?)
+    }
+
+    /// Submits the concatenated msgpack of signed transactions of a group
+    pub(crate) async fn send_transactions(&self, group: Vec<u8>) -> ChainResult<String> {
+        let request = self
+            .node(Method::POST, "/v2/transactions")
+            .header("Content-Type", "application/x-binary")
+            .body(group);
+        let submitted: SubmittedTransaction = Self::send(request).await?;
+        Ok(submitted.tx_id)
+    }
+
+    pub(crate) async fn pending_transaction(&self, id: &str) -> ChainResult<PendingTransaction> {
+        let request = self
+            .node(Method::GET, &format!("/v2/transactions/pending/{id}"))
+            .query(&[("format", "json")]);
+        Ok(Self::send(request).await?)
+    }
+
+    /// Simulates the msgpack encoded simulation request `request`
+    pub(crate) async fn simulate(&self, request: Vec<u8>) -> ChainResult<SimulationResult> {
+        let request = self
+            .node(Method::POST, "/v2/transactions/simulate")
+            .query(&[("format", "json")])
+            .header("Content-Type", "application/msgpack")
+            .body(request);
+        Ok(Self::send(request).await?)
+    }
+
+    /// The last round processed by the indexer
+    pub(crate) async fn indexer_round(&self) -> ChainResult<u64> {
+        let health: IndexerHealth = Self::send(self.indexer("/health")).await?;
+        Ok(health.round)
+    }
+
+    /// A page of the transactions calling the application `app_id`, directly
+    /// or through inner transactions, committed between `min_round` and
+    /// `max_round` inclusive
+    pub(crate) async fn application_transactions(
+        &self,
+        app_id: u64,
+        min_round: u64,
+        max_round: u64,
+        next: Option<&str>,
+    ) -> ChainResult<TransactionsPage> {
+        let mut request = self.indexer("/v2/transactions").query(&[
+            ("application-id", app_id.to_string()),
+            ("min-round", min_round.to_string()),
+            ("max-round", max_round.to_string()),
+            ("limit", TRANSACTIONS_PAGE_SIZE.to_string()),
+        ]);
+        if let Some(next) = next {
+            request = request.query(&[("next", next)]);
+        }
+        Ok(Self::send(request).await?)
+    }
+}
@@ -0,0 +1,38 @@
+use hyperlane_core::ChainCommunicationError;
+
+/// Errors from the crates specific to the hyperlane-algorand
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneAlgorandError {
+    /// Reqwest error
+    #[error("{0}")]
+    ReqwestError(#[from] reqwest::Error),
+    /// serde_json error
+    #[error("{0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+    /// Base64 decoding error
+    #[error("{0}")]
+    Base64Error(#[from] base64::DecodeError),
+    /// Error returned by the node or the indexer
+    #[error("Algorand API error {status}: {message}")]
+    ApiError {
+        /// The HTTP status of the response
+        status: u16,
+        /// The error message
+        message: String,
+    },
+    /// The simulation of a transaction group failed
+    #[error("Simulation failed: {0}")]
+    SimulationError(String),
+    /// A response that doesn't have the expected shape
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+}
+
+impl From<HyperlaneAlgorandError> for ChainCommunicationError {
+    fn from(value: HyperlaneAlgorandError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
@@ -0,0 +1,18 @@
+//! Implementation of hyperlane for Algorand, whose mailbox is an application
+//! called through application call transactions.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+mod abi;
+mod client;
+mod error;
+mod mailbox;
+mod msgpack;
+mod provider;
+mod signers;
+mod trait_builder;
+mod transaction;
+mod types;
+
+pub use self::{error::*, mailbox::*, provider::*, signers::*, trait_builder::*};
@@ -0,0 +1,291 @@
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use tracing::{instrument, warn};
+
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, Decode,
+    FixedPointNumber, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, RawHyperlaneMessage,
+    SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, U256,
+};
+
+use crate::{
+    abi::{decode_single_bytes, encode_bytes, event_args, selector},
+    provider::{application_id, ApplicationCall},
+    AlgorandProvider, ConnectionConf, HyperlaneAlgorandError, Signer,
+};
+
+/// The ARC-4 signature of the method processing messages
+const PROCESS_METHOD: &str = "process(byte[],byte[])void";
+/// The ARC-28 signatures of the events logged by the mailbox, whose
+/// arguments are respectively the encoded message and the message id
+const DISPATCH_EVENT: &str = "Dispatch(byte[])";
+const PROCESS_EVENT: &str = "Process(byte[32])";
+/// The keys of the global state of the mailbox holding the number of
+/// dispatched messages and the id of the default ISM application
+const NONCE_KEY: &str = "nonce";
+const DEFAULT_ISM_KEY: &str = "default_ism";
+/// The key of the global state of recipients holding the id of their ISM
+/// application, the default ISM being used if unset or 0
+const RECIPIENT_ISM_KEY: &str = "ism";
+/// The inner transactions of `process`, which calls the ISM to verify the
+/// message and the recipient to handle it
+const PROCESS_INNER_TRANSACTIONS: u64 = 2;
+
+/// A reference to a Mailbox application on some Algorand chain
+#[derive(Debug)]
+pub struct AlgorandMailbox {
+    address: H256,
+    app_id: u64,
+    provider: AlgorandProvider,
+}
+
+impl AlgorandMailbox {
+    /// Create a new Algorand mailbox
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            app_id: application_id(&locator.address)?,
+            provider: AlgorandProvider::new(locator.domain.clone(), conf, signer),
+        })
+    }
+
+    /// The ARC-4 encoded arguments of `process`, starting with its selector
+    fn process_args(message: &HyperlaneMessage, metadata: &[u8]) -> Vec<Vec<u8>> {
+        vec![
+            selector(PROCESS_METHOD).to_vec(),
+            encode_bytes(metadata),
+            encode_bytes(&RawHyperlaneMessage::from(message)),
+        ]
+    }
+
+    /// The call of `process`, referencing the applications and the box it
+    /// accesses
+    async fn process_call(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<ApplicationCall> {
+        let recipient = application_id(&message.recipient)?;
+        let ism = application_id(&self.recipient_ism(message.recipient).await?)?;
+        Ok(ApplicationCall {
+            app_id: self.app_id,
+            args: Self::process_args(message, metadata),
+            foreign_apps: vec![recipient, ism],
+            boxes: vec![message.id().as_bytes().to_vec()],
+            inner_transactions: PROCESS_INNER_TRANSACTIONS,
+        })
+    }
+}
+
+impl HyperlaneContract for AlgorandMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for AlgorandMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for AlgorandMailbox {
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        assert!(
+            lag.is_none(),
+            "Algorand does not support querying point-in-time"
+        );
+        let nonce = self
+            .provider
+            .global_uint(self.app_id, NONCE_KEY)
+            .await?
+            .unwrap_or_default();
+        Ok(nonce
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?)
+    }
+
+    /// Delivered messages are stored in boxes named after their id
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        self.provider.box_exists(self.app_id, id.as_bytes()).await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        let ism = self
+            .provider
+            .global_uint(self.app_id, DEFAULT_ISM_KEY)
+            .await?
+            .ok_or_else(|| {
+                HyperlaneAlgorandError::UnexpectedResponse("mailbox has no default ISM".into())
+            })?;
+        Ok(H256::from_low_u64_be(ism))
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let ism = self
+            .provider
+            .global_uint(application_id(&recipient)?, RECIPIENT_ISM_KEY)
+            .await?;
+        match ism {
+            Some(ism) if ism != 0 => Ok(H256::from_low_u64_be(ism)),
+            _ => self.default_ism().await,
+        }
+    }
+
+    /// Algorand has no gas, so the limit is ignored and the fees of all the
+    /// transactions of the group are paid by the signer
+    #[instrument(err, ret, skip(self))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        _tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let call = self.process_call(message, metadata).await?;
+        let outcome = self.provider.submit(&call).await?;
+        Ok(TxOutcome {
+            transaction_id: outcome.id.into(),
+            executed: outcome.committed,
+            gas_used: call.fee_units().into(),
+            gas_price: FixedPointNumber::from(outcome.unit_fee),
+        })
+    }
+
+    /// The cost of processing is the minimum fee of each transaction of the
+    /// group, including the inner ones
+    #[instrument(err, ret, skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        let call = self.process_call(message, metadata).await?;
+        let unit_fee = self.provider.simulate(&call).await?;
+        Ok(TxCostEstimate {
+            gas_limit: call.fee_units().into(),
+            gas_price: FixedPointNumber::from(unit_fee),
+            l2_gas_limit: None,
+            resources: vec![],
+        })
+    }
+
+    /// The concatenated application arguments of `process`
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        Self::process_args(message, metadata).concat()
+    }
+}
+
+/// Struct that retrieves dispatched messages and processed message ids from
+/// the ARC-28 events logged by a Mailbox application on some Algorand chain
+#[derive(Debug)]
+pub struct AlgorandMailboxIndexer {
+    mailbox: AlgorandMailbox,
+}
+
+impl AlgorandMailboxIndexer {
+    /// Create a new Algorand mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            mailbox: AlgorandMailbox::new(conf, locator, None)?,
+        })
+    }
+
+    /// The arguments of the `event` events logged in the rounds in `range`
+    async fn events_in_range(
+        &self,
+        event: &str,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Vec<u8>, LogMeta)>> {
+        let logs = self
+            .mailbox
+            .provider
+            .logs_in_range(self.mailbox.app_id, range)
+            .await?;
+        Ok(logs
+            .into_iter()
+            .filter_map(|(log, meta)| Some((event_args(&log, event)?.to_vec(), meta)))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for AlgorandMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        let events = self.events_in_range(DISPATCH_EVENT, range).await?;
+        let mut messages = vec![];
+        for (args, meta) in events {
+            let Some(message) = decode_single_bytes(&args) else {
+                warn!(?meta, args = %bytes_to_hex(&args), "Malformed dispatch event, skipping");
+                continue;
+            };
+            let message = HyperlaneMessage::read_from(&mut &message[..])
+                .map_err(ChainCommunicationError::from_other)?;
+            messages.push((message.into(), meta));
+        }
+        Ok(messages)
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.indexed_round().await
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for AlgorandMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        let events = self.events_in_range(PROCESS_EVENT, range).await?;
+        let mut ids = vec![];
+        for (args, meta) in events {
+            if args.len() != H256::len_bytes() {
+                warn!(?meta, args = %bytes_to_hex(&args), "Malformed process event, skipping");
+                continue;
+            }
+            ids.push((Indexed::new(H256::from_slice(&args)), meta));
+        }
+        Ok(ids)
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.indexed_round().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for AlgorandMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
+        let count = Mailbox::count(&self.mailbox, None).await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for AlgorandMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<H256>::get_finalized_block_number(self).await?;
+        // No sequence for message deliveries.
+        Ok((None, tip))
+    }
+}
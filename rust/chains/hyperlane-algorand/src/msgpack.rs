@@ -0,0 +1,138 @@
+//! A minimal encoder of the canonical msgpack that Algorand transactions are
+//! hashed, signed and submitted in. Maps are sorted by key and fields with
+//! zero values are omitted.
+
+use std::collections::BTreeMap;
+
+/// The fields of a map, which are sorted by key
+pub(crate) type Map = BTreeMap<&'static str, Value>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Value {
+    Bool(bool),
+    Uint(u64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Array(Vec<Value>),
+    Map(Map),
+}
+
+impl Value {
+    /// Whether the value is the zero value of its type, which is omitted from
+    /// maps
+    fn is_empty(&self) -> bool {
+        match self {
+            Value::Bool(value) => !value,
+            Value::Uint(value) => *value == 0,
+            Value::Str(value) => value.is_empty(),
+            Value::Bytes(value) => value.is_empty(),
+            Value::Array(values) => values.is_empty(),
+            Value::Map(fields) => fields.values().all(Value::is_empty),
+        }
+    }
+
+    /// The canonical encoding of the value
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        self.encode_to(&mut buf);
+        buf
+    }
+
+    fn encode_to(&self, buf: &mut Vec<u8>) {
+        match self {
+            Value::Bool(value) => buf.push(if *value { 0xc3 } else { 0xc2 }),
+            Value::Uint(value) => encode_uint(*value, buf),
+            Value::Str(value) => {
+                encode_len(value.len(), Some((0xa0, 31)), Some(0xd9), 0xda, 0xdb, buf);
+                buf.extend_from_slice(value.as_bytes());
+            }
+            Value::Bytes(value) => {
+                encode_len(value.len(), None, Some(0xc4), 0xc5, 0xc6, buf);
+                buf.extend_from_slice(value);
+            }
+            Value::Array(values) => {
+                encode_len(values.len(), Some((0x90, 15)), None, 0xdc, 0xdd, buf);
+                for value in values {
+                    value.encode_to(buf);
+                }
+            }
+            Value::Map(fields) => {
+                let fields: Vec<_> = fields.iter().filter(|(_, v)| !v.is_empty()).collect();
+                encode_len(fields.len(), Some((0x80, 15)), None, 0xde, 0xdf, buf);
+                for (key, value) in fields {
+                    Value::Str((*key).to_owned()).encode_to(buf);
+                    value.encode_to(buf);
+                }
+            }
+        }
+    }
+}
+
+fn encode_uint(value: u64, buf: &mut Vec<u8>) {
+    if value < 0x80 {
+        buf.push(value as u8);
+    } else if value <= u8::MAX.into() {
+        buf.push(0xcc);
+        buf.push(value as u8);
+    } else if value <= u16::MAX.into() {
+        buf.push(0xcd);
+        buf.extend_from_slice(&(value as u16).to_be_bytes());
+    } else if value <= u32::MAX.into() {
+        buf.push(0xce);
+        buf.extend_from_slice(&(value as u32).to_be_bytes());
+    } else {
+        buf.push(0xcf);
+        buf.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+/// Encodes the length of a string, bin, array or map with the first format
+/// that fits it, among the fixed format packing lengths up to some maximum
+/// in its first byte and the formats followed by an 8, 16 or 32 bit length.
+/// Not all types have a fixed or an 8 bit format.
+fn encode_len(
+    len: usize,
+    fixed: Option<(u8, usize)>,
+    len8: Option<u8>,
+    len16: u8,
+    len32: u8,
+    buf: &mut Vec<u8>,
+) {
+    if let Some((fixed, _)) = fixed.filter(|(_, max)| len <= *max) {
+        buf.push(fixed | len as u8);
+    } else if let Some(len8) = len8.filter(|_| len <= u8::MAX.into()) {
+        buf.push(len8);
+        buf.push(len as u8);
+    } else if len <= u16::MAX.into() {
+        buf.push(len16);
+        buf.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        buf.push(len32);
+        buf.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Map, Value};
+
+    #[test]
+    fn test_encode_canonical_map() {
+        let map = Map::from([
+            ("type", Value::Str("pay".into())),
+            ("amt", Value::Uint(1000)),
+            ("fee", Value::Uint(0)),
+            ("rcv", Value::Bytes(vec![1, 2])),
+        ]);
+        assert_eq!(
+            Value::Map(map).encode(),
+            [
+                &[0x83][..],
+                &[0xa3, b'a', b'm', b't', 0xcd, 0x03, 0xe8],
+                &[0xa3, b'r', b'c', b'v', 0xc4, 0x02, 1, 2],
+                &[0xa4, b't', b'y', b'p', b'e', 0xa3, b'p', b'a', b'y'],
+            ]
+            .concat()
+        );
+    }
+}
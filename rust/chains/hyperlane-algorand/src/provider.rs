@@ -0,0 +1,369 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use data_encoding::BASE32_NOPAD;
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, LogMeta, TxnInfo, H256, U256,
+};
+use tracing::{debug, warn};
+
+use crate::{
+    client::{AlgorandClient, TRANSACTIONS_PAGE_SIZE},
+    msgpack::{Map, Value},
+    transaction::{assign_group, Transaction, TransactionKind},
+    types::{IndexedTransaction, TealValue, TransactionParams},
+    ConnectionConf, HyperlaneAlgorandError, Signer,
+};
+
+/// The type of global state values holding an uint, rather than bytes
+const TEAL_UINT: u8 = 2;
+/// How many rounds after the current one transactions are valid for, which
+/// is the maximum
+const VALIDITY_ROUNDS: u64 = 1000;
+/// How often a submitted group is polled for its outcome
+const TRANSACTION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How many times a group is polled before giving up. Rounds last about 3
+/// seconds.
+const TRANSACTION_POLL_ATTEMPTS: usize = 30;
+
+/// The application id an address refers to, which fits in its last 8 bytes
+pub(crate) fn application_id(address: &H256) -> ChainResult<u64> {
+    let (high, app_id) = address.as_bytes().split_at(24);
+    if high.iter().any(|b| *b != 0) {
+        return Err(ChainCommunicationError::ParseError {
+            msg: format!("{address:?} is not an Algorand application id"),
+        });
+    }
+    let app_id: [u8; 8] = app_id.try_into().expect("8 bytes");
+    Ok(u64::from_be_bytes(app_id))
+}
+
+/// Appends the logs of the calls of `transaction` and of its inner
+/// transactions to the application `app_id`
+fn application_logs<'a>(transaction: &'a IndexedTransaction, app_id: u64, logs: &mut Vec<&'a str>) {
+    if transaction.application_id() == Some(app_id) {
+        logs.extend(transaction.logs.iter().map(String::as_str));
+    }
+    for inner in &transaction.inner_txns {
+        application_logs(inner, app_id, logs);
+    }
+}
+
+/// A call to an application, which is sent in a group along with a payment
+/// paying the fees of the group
+#[derive(Debug, Clone)]
+pub(crate) struct ApplicationCall {
+    pub app_id: u64,
+    pub args: Vec<Vec<u8>>,
+    pub foreign_apps: Vec<u64>,
+    pub boxes: Vec<Vec<u8>>,
+    /// How many inner transactions the call makes, whose fees are pooled
+    /// with the ones of the group
+    pub inner_transactions: u64,
+}
+
+impl ApplicationCall {
+    /// How many minimum fees the group pays, one per transaction
+    pub(crate) fn fee_units(&self) -> u64 {
+        2 + self.inner_transactions
+    }
+}
+
+/// The outcome of a submitted application call
+#[derive(Debug)]
+pub(crate) struct CallOutcome {
+    /// The id of the application call transaction
+    pub id: H256,
+    /// Whether the group was committed
+    pub committed: bool,
+    /// The fee paid per transaction, in microAlgos
+    pub unit_fee: u64,
+}
+
+/// A wrapper around an algod node and an indexer to get generic blockchain
+/// information, read the state of applications and call them.
+#[derive(Debug, Clone)]
+pub struct AlgorandProvider {
+    domain: HyperlaneDomain,
+    client: AlgorandClient,
+    signer: Option<Signer>,
+}
+
+impl AlgorandProvider {
+    /// Create a new Algorand provider
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf, signer: Option<Signer>) -> Self {
+        Self {
+            domain,
+            client: AlgorandClient::new(
+                conf.url.clone(),
+                conf.indexer_url.clone(),
+                conf.api_token.clone(),
+            ),
+            signer,
+        }
+    }
+
+    fn signer(&self) -> ChainResult<&Signer> {
+        self.signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)
+    }
+
+    /// The last round processed by the indexer. Rounds are final as soon as
+    /// they are committed.
+    pub(crate) async fn indexed_round(&self) -> ChainResult<u32> {
+        let round = self.client.indexer_round().await?;
+        Ok(round
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?)
+    }
+
+    /// The global state of the application `app_id`, by key
+    async fn global_state(&self, app_id: u64) -> ChainResult<HashMap<Vec<u8>, TealValue>> {
+        let application = self.client.application(app_id).await?.ok_or_else(|| {
+            HyperlaneAlgorandError::UnexpectedResponse(format!(
+                "application {app_id} doesn't exist"
+            ))
+        })?;
+        application
+            .params
+            .global_state
+            .into_iter()
+            .map(|entry| {
+                let key = BASE64
+                    .decode(&entry.key)
+                    .map_err(HyperlaneAlgorandError::from)?;
+                Ok((key, entry.value))
+            })
+            .collect()
+    }
+
+    /// The uint stored at `key` in the global state of the application
+    /// `app_id`, if any
+    pub(crate) async fn global_uint(&self, app_id: u64, key: &str) -> ChainResult<Option<u64>> {
+        let state = self.global_state(app_id).await?;
+        Ok(state
+            .get(key.as_bytes())
+            .filter(|value| value.kind == TEAL_UINT)
+            .map(|value| value.uint))
+    }
+
+    /// Whether the application `app_id` has a box named `name`
+    pub(crate) async fn box_exists(&self, app_id: u64, name: &[u8]) -> ChainResult<bool> {
+        Ok(self.client.application_box(app_id, name).await?.is_some())
+    }
+
+    /// The group of `call` and of the payment of its fees, which are pooled
+    async fn group(
+        &self,
+        call: &ApplicationCall,
+    ) -> ChainResult<(Vec<Transaction>, TransactionParams)> {
+        let sender = self.signer()?.public_key();
+        let params = self.client.params().await?;
+        let genesis_hash: [u8; 32] = BASE64
+            .decode(&params.genesis_hash)
+            .map_err(HyperlaneAlgorandError::from)?
+            .try_into()
+            .map_err(|_| {
+                HyperlaneAlgorandError::UnexpectedResponse("genesis hash is not 32 bytes".into())
+            })?;
+        let transaction = |fee, kind| Transaction {
+            sender,
+            fee,
+            first_valid: params.last_round,
+            last_valid: params.last_round + VALIDITY_ROUNDS,
+            genesis_id: params.genesis_id.clone(),
+            genesis_hash,
+            group: None,
+            kind,
+        };
+        let mut group = vec![
+            transaction(
+                params.min_fee * call.fee_units(),
+                TransactionKind::Payment {
+                    receiver: sender,
+                    amount: 0,
+                },
+            ),
+            transaction(
+                0,
+                TransactionKind::ApplicationCall {
+                    app_id: call.app_id,
+                    args: call.args.clone(),
+                    foreign_apps: call.foreign_apps.clone(),
+                    boxes: call.boxes.clone(),
+                },
+            ),
+        ];
+        assign_group(&mut group);
+        Ok((group, params))
+    }
+
+    /// Simulates `call`, failing if it would be rejected, and returns the
+    /// fee that would be paid per transaction
+    pub(crate) async fn simulate(&self, call: &ApplicationCall) -> ChainResult<u64> {
+        let (group, params) = self.group(call).await?;
+        let txns = group.iter().map(|txn| txn.signed(None)).collect();
+        let request = Value::Map(Map::from([
+            ("allow-empty-signatures", Value::Bool(true)),
+            (
+                "txn-groups",
+                Value::Array(vec![Value::Map(Map::from([("txns", Value::Array(txns))]))]),
+            ),
+        ]));
+        let result = self.client.simulate(request.encode()).await?;
+        if let Some(message) = result
+            .txn_groups
+            .into_iter()
+            .find_map(|group| group.failure_message)
+        {
+            return Err(HyperlaneAlgorandError::SimulationError(message).into());
+        }
+        Ok(params.min_fee)
+    }
+
+    /// Signs and submits `call` in a group, and waits for it to be committed
+    pub(crate) async fn submit(&self, call: &ApplicationCall) -> ChainResult<CallOutcome> {
+        let signer = self.signer()?;
+        let (group, params) = self.group(call).await?;
+        let signed = group
+            .iter()
+            .flat_map(|txn| txn.signed(Some(signer.sign(&txn.bytes_to_sign()))).encode())
+            .collect();
+        let call_txn = group.last().expect("group has an application call");
+        let id = call_txn.id();
+        self.client.send_transactions(signed).await?;
+        debug!(%id, app_id = call.app_id, "Sent Algorand transaction group");
+
+        for _ in 0..TRANSACTION_POLL_ATTEMPTS {
+            tokio::time::sleep(TRANSACTION_POLL_INTERVAL).await;
+            let pending = self.client.pending_transaction(&id).await?;
+            let committed = pending.confirmed_round.is_some();
+            if committed || !pending.pool_error.is_empty() {
+                if !committed {
+                    warn!(%id, error = %pending.pool_error, "Algorand transaction group dropped");
+                }
+                return Ok(CallOutcome {
+                    id: H256(call_txn.raw_id()),
+                    committed,
+                    unit_fee: params.min_fee,
+                });
+            }
+        }
+        Err(ChainCommunicationError::TransactionTimeout())
+    }
+
+    /// The logs of the application `app_id` in the rounds in `range`, along
+    /// with their metadata. Only committed transactions are indexed, which
+    /// all succeeded.
+    pub(crate) async fn logs_in_range(
+        &self,
+        app_id: u64,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Vec<u8>, LogMeta)>> {
+        let address = H256::from_low_u64_be(app_id);
+        let mut seen = HashSet::new();
+        let mut logs = vec![];
+        let mut next = None;
+        loop {
+            let page = self
+                .client
+                .application_transactions(
+                    app_id,
+                    (*range.start()).into(),
+                    (*range.end()).into(),
+                    next.as_deref(),
+                )
+                .await?;
+            let count = page.transactions.len();
+            for transaction in &page.transactions {
+                // Transactions calling the application more than once are
+                // only returned once
+                let Some(id) = &transaction.id else {
+                    continue;
+                };
+                if !seen.insert(id.clone()) {
+                    continue;
+                }
+                let transaction_id = BASE32_NOPAD
+                    .decode(id.as_bytes())
+                    .ok()
+                    .filter(|id| id.len() == H256::len_bytes())
+                    .map(|id| H256::from_slice(&id))
+                    .ok_or_else(|| {
+                        HyperlaneAlgorandError::UnexpectedResponse(format!(
+                            "invalid transaction id `{id}`"
+                        ))
+                    })?;
+                let mut transaction_logs = vec![];
+                application_logs(transaction, app_id, &mut transaction_logs);
+                for (log_index, log) in transaction_logs.into_iter().enumerate() {
+                    let log = BASE64.decode(log).map_err(HyperlaneAlgorandError::from)?;
+                    logs.push((
+                        log,
+                        LogMeta {
+                            address,
+                            block_number: transaction.confirmed_round,
+                            // Transactions don't include the hash of their
+                            // block
+                            block_hash: H256::zero(),
+                            transaction_id: transaction_id.into(),
+                            transaction_index: transaction.intra_round_offset,
+                            log_index: log_index.into(),
+                        },
+                    ));
+                }
+            }
+            next = page.next_token;
+            if count < TRANSACTIONS_PAGE_SIZE as usize || next.is_none() {
+                return Ok(logs);
+            }
+        }
+    }
+}
+
+impl HyperlaneChain for AlgorandProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for AlgorandProvider {
+    async fn get_block_by_hash(&self, _hash: &H256) -> ChainResult<BlockInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "Algorand does not support fetching blocks by hash yet",
+        ))
+    }
+
+    async fn get_txn_by_hash(&self, _hash: &H256) -> ChainResult<TxnInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "Algorand does not support fetching transactions yet",
+        ))
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        // Accounts are addressed by their public key
+        let Ok(app_id) = application_id(address) else {
+            return Ok(false);
+        };
+        Ok(self.client.application(app_id).await?.is_some())
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        let account = self.client.account(&address).await?;
+        Ok(account.amount.into())
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        Ok(None)
+    }
+}
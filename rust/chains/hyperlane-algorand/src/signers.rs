@@ -0,0 +1,44 @@
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer as _};
+use hyperlane_core::{ChainCommunicationError, ChainResult, HyperlaneDomainProtocol, H256};
+
+/// An ed25519 key signing transactions for an Algorand account
+#[derive(Debug)]
+pub struct Signer {
+    keypair: Keypair,
+}
+
+impl Signer {
+    /// Create a new Algorand signer from an ed25519 private key
+    pub fn new(private_key: &H256) -> ChainResult<Self> {
+        let secret = SecretKey::from_bytes(private_key.as_bytes())
+            .map_err(ChainCommunicationError::from_other)?;
+        let public = PublicKey::from(&secret);
+        Ok(Self {
+            keypair: Keypair { secret, public },
+        })
+    }
+
+    /// The public key of the account, which is its address
+    pub fn public_key(&self) -> [u8; 32] {
+        self.keypair.public.to_bytes()
+    }
+
+    /// Signs the prefixed encoding of a transaction
+    pub(crate) fn sign(&self, bytes: &[u8]) -> [u8; 64] {
+        self.keypair.sign(bytes).to_bytes()
+    }
+
+    /// The base32 address of the account
+    pub fn address_string(&self) -> String {
+        HyperlaneDomainProtocol::Algorand.fmt_address(H256(self.public_key()))
+    }
+}
+
+impl Clone for Signer {
+    fn clone(&self) -> Self {
+        Self {
+            keypair: Keypair::from_bytes(&self.keypair.to_bytes())
+                .expect("keypair bytes are valid"),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use hyperlane_core::config::OperationBatchConfig;
+use url::Url;
+
+/// Algorand connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Fully qualified url of an algod node, e.g.
+    /// `https://testnet-api.algonode.cloud`
+    pub url: Url,
+    /// Fully qualified url of an indexer, which the logs of applications are
+    /// scraped from, e.g. `https://testnet-idx.algonode.cloud`
+    pub indexer_url: Url,
+    /// The token sent to the node and the indexer, if they require one
+    pub api_token: Option<String>,
+    /// Operation batching configuration
+    pub operation_batch: OperationBatchConfig,
+}
+
+impl ConnectionConf {
+    /// Create a new connection configuration
+    pub fn new(
+        url: Url,
+        indexer_url: Url,
+        api_token: Option<String>,
+        operation_batch: OperationBatchConfig,
+    ) -> Self {
+        Self {
+            url,
+            indexer_url,
+            api_token,
+            operation_batch,
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Payment and application call transactions, and the atomic groups they are
+//! submitted in.
+
+use data_encoding::BASE32_NOPAD;
+use sha2::{Digest, Sha512_256};
+
+use crate::msgpack::{Map, Value};
+
+/// The prefix of encoded transactions when they are hashed and signed
+const TRANSACTION_PREFIX: &[u8] = b"TX";
+/// The prefix of the encoded list of transaction ids a group id is the hash of
+const GROUP_PREFIX: &[u8] = b"TG";
+
+#[derive(Debug, Clone)]
+pub(crate) enum TransactionKind {
+    Payment {
+        receiver: [u8; 32],
+        /// In microAlgos
+        amount: u64,
+    },
+    ApplicationCall {
+        app_id: u64,
+        args: Vec<Vec<u8>>,
+        /// The applications called by the application
+        foreign_apps: Vec<u64>,
+        /// The names of the boxes of the application accessed by the call
+        boxes: Vec<Vec<u8>>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Transaction {
+    pub sender: [u8; 32],
+    /// In microAlgos
+    pub fee: u64,
+    pub first_valid: u64,
+    pub last_valid: u64,
+    pub genesis_id: String,
+    pub genesis_hash: [u8; 32],
+    /// The id of the group the transaction is part of, if any
+    pub group: Option<[u8; 32]>,
+    pub kind: TransactionKind,
+}
+
+impl Transaction {
+    fn to_value(&self) -> Value {
+        let mut fields = Map::from([
+            ("snd", Value::Bytes(self.sender.to_vec())),
+            ("fee", Value::Uint(self.fee)),
+            ("fv", Value::Uint(self.first_valid)),
+            ("lv", Value::Uint(self.last_valid)),
+            ("gen", Value::Str(self.genesis_id.clone())),
+            ("gh", Value::Bytes(self.genesis_hash.to_vec())),
+            (
+                "grp",
+                Value::Bytes(self.group.map(|g| g.to_vec()).unwrap_or_default()),
+            ),
+        ]);
+        match &self.kind {
+            TransactionKind::Payment { receiver, amount } => {
+                fields.insert("type", Value::Str("pay".into()));
+                fields.insert("rcv", Value::Bytes(receiver.to_vec()));
+                fields.insert("amt", Value::Uint(*amount));
+            }
+            TransactionKind::ApplicationCall {
+                app_id,
+                args,
+                foreign_apps,
+                boxes,
+            } => {
+                fields.insert("type", Value::Str("appl".into()));
+                fields.insert("apid", Value::Uint(*app_id));
+                fields.insert(
+                    "apaa",
+                    Value::Array(args.iter().cloned().map(Value::Bytes).collect()),
+                );
+                fields.insert(
+                    "apfa",
+                    Value::Array(foreign_apps.iter().copied().map(Value::Uint).collect()),
+                );
+                // Index 0 refers to the called application
+                let boxes = boxes
+                    .iter()
+                    .map(|name| Value::Map(Map::from([("n", Value::Bytes(name.clone()))])))
+                    .collect();
+                fields.insert("apbx", Value::Array(boxes));
+            }
+        }
+        Value::Map(fields)
+    }
+
+    /// The bytes that are hashed into the id of the transaction and signed
+    pub(crate) fn bytes_to_sign(&self) -> Vec<u8> {
+        [TRANSACTION_PREFIX, &self.to_value().encode()].concat()
+    }
+
+    pub(crate) fn raw_id(&self) -> [u8; 32] {
+        Sha512_256::digest(self.bytes_to_sign()).into()
+    }
+
+    /// The base32 id of the transaction, as used by the APIs
+    pub(crate) fn id(&self) -> String {
+        BASE32_NOPAD.encode(&self.raw_id())
+    }
+
+    /// The msgpack of the transaction along with its signature. Simulations
+    /// accept transactions without a signature.
+    pub(crate) fn signed(&self, signature: Option<[u8; 64]>) -> Value {
+        Value::Map(Map::from([
+            (
+                "sig",
+                Value::Bytes(signature.map(|s| s.to_vec()).unwrap_or_default()),
+            ),
+            ("txn", self.to_value()),
+        ]))
+    }
+}
+
+/// Makes the transactions an atomic group, which is committed only if all of
+/// its transactions succeed. The id of the group is the hash of the ids of
+/// its transactions.
+pub(crate) fn assign_group(transactions: &mut [Transaction]) {
+    let ids = transactions
+        .iter()
+        .map(|transaction| Value::Bytes(transaction.raw_id().to_vec()))
+        .collect();
+    let list = Value::Map(Map::from([("txlist", Value::Array(ids))]));
+    let group: [u8; 32] = Sha512_256::digest([GROUP_PREFIX, &list.encode()].concat()).into();
+    for transaction in transactions {
+        transaction.group = Some(group);
+    }
+}
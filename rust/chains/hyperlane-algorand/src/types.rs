@@ -0,0 +1,154 @@
+//! The JSON types returned by the algod and indexer APIs.
+
+use serde::Deserialize;
+
+/// The response of `GET /v2/transactions/params`
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct TransactionParams {
+    /// The suggested fee per byte, which is 0 unless the network is congested
+    pub fee: u64,
+    /// The minimum fee of a transaction, in microAlgos
+    pub min_fee: u64,
+    pub last_round: u64,
+    pub genesis_id: String,
+    /// Base64 encoded
+    pub genesis_hash: String,
+}
+
+/// A value of the global state of an application
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct TealValue {
+    /// 1 for bytes, 2 for uint
+    #[serde(rename = "type")]
+    pub kind: u8,
+    /// Base64 encoded
+    #[serde(default)]
+    pub bytes: String,
+    #[serde(default)]
+    pub uint: u64,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct TealKeyValue {
+    /// Base64 encoded
+    pub key: String,
+    pub value: TealValue,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct ApplicationParams {
+    #[serde(default)]
+    pub global_state: Vec<TealKeyValue>,
+}
+
+/// The response of `GET /v2/applications/{id}`
+#[derive(Deserialize, Debug)]
+pub(crate) struct Application {
+    pub params: ApplicationParams,
+}
+
+/// The response of `GET /v2/applications/{id}/box`
+#[derive(Deserialize, Debug)]
+pub(crate) struct BoxValue {
+    /// Base64 encoded
+    pub value: String,
+}
+
+/// The response of `GET /v2/accounts/{address}`
+#[derive(Deserialize, Debug)]
+pub(crate) struct Account {
+    /// The balance, in microAlgos
+    pub amount: u64,
+}
+
+/// The response of `POST /v2/transactions`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SubmittedTransaction {
+    /// The id of the first transaction of the group
+    pub tx_id: String,
+}
+
+/// The response of `GET /v2/transactions/pending/{txid}`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct PendingTransaction {
+    /// The round the transaction was committed in, if it was
+    pub confirmed_round: Option<u64>,
+    /// Why the transaction was removed from the pool, if it was
+    #[serde(default)]
+    pub pool_error: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct SimulatedGroup {
+    /// Why the group would be rejected, if it would be
+    pub failure_message: Option<String>,
+}
+
+/// The response of `POST /v2/transactions/simulate`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct SimulationResult {
+    pub txn_groups: Vec<SimulatedGroup>,
+}
+
+/// The response of `GET /health` of the indexer
+#[derive(Deserialize, Debug)]
+pub(crate) struct IndexerHealth {
+    /// The last round the indexer has processed
+    pub round: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct ApplicationTransaction {
+    pub application_id: u64,
+}
+
+/// A transaction returned by the indexer, along with its inner transactions
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct IndexedTransaction {
+    /// Only set for top level transactions
+    pub id: Option<String>,
+    #[serde(default)]
+    pub confirmed_round: u64,
+    /// The index of the transaction in its round
+    #[serde(default)]
+    pub intra_round_offset: u64,
+    pub application_transaction: Option<ApplicationTransaction>,
+    /// The id of the application created by the transaction, if any
+    pub created_application_index: Option<u64>,
+    /// Base64 encoded
+    #[serde(default)]
+    pub logs: Vec<String>,
+    #[serde(default)]
+    pub inner_txns: Vec<IndexedTransaction>,
+}
+
+impl IndexedTransaction {
+    /// The id of the application called by the transaction, if it's an
+    /// application call
+    pub(crate) fn application_id(&self) -> Option<u64> {
+        let app_id = self.application_transaction.as_ref()?.application_id;
+        // Creations call the application with id 0
+        Some(if app_id == 0 {
+            self.created_application_index?
+        } else {
+            app_id
+        })
+    }
+}
+
+/// The response of `GET /v2/transactions` of the indexer
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct TransactionsPage {
+    pub transactions: Vec<IndexedTransaction>,
+    /// The token of the next page, if there may be one
+    pub next_token: Option<String>,
+}
@@ -0,0 +1,236 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+use reqwest::{header::CONTENT_TYPE, Client, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use tracing::debug;
+use url::Url;
+
+use crate::{
+    types::{
+        AccountInfo, BlockInfo, EntryFunction, Event, LedgerInfo, RawTransaction,
+        SignedTransaction, Transaction, TransactionAuthenticator, TransactionPayload,
+    },
+    HyperlaneAptosError, Signer,
+};
+
+/// The content type of BCS encoded signed transactions
+const SIGNED_TRANSACTION_CONTENT_TYPE: &str = "application/x.aptos.signed_transaction+bcs";
+/// How long a submitted transaction stays valid
+const TRANSACTION_EXPIRATION: Duration = Duration::from_secs(60);
+/// The max gas amount of simulated transactions, which the simulation
+/// replaces with its own estimate
+const SIMULATION_MAX_GAS_AMOUNT: u64 = 2_000_000;
+/// How often the status of a submitted transaction is polled
+const TRANSACTION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How many times the status of a submitted transaction is polled before
+/// giving up
+const TRANSACTION_POLL_ATTEMPTS: usize = 60;
+
+/// A client of the REST API of an Aptos fullnode
+#[derive(Debug, Clone)]
+pub(crate) struct AptosClient {
+    http: Client,
+    url: Url,
+}
+
+impl AptosClient {
+    pub(crate) fn new(url: Url) -> Self {
+        Self {
+            http: Client::new(),
+            url,
+        }
+    }
+
+    fn endpoint(&self, path: &str) -> ChainResult<Url> {
+        self.url
+            .join(&format!("v1/{path}"))
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, HyperlaneAptosError> {
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body: Value = response.json().await.unwrap_or_default();
+            return Err(HyperlaneAptosError::ApiError {
+                status: status.as_u16(),
+                message: body["message"].as_str().unwrap_or_default().to_owned(),
+            });
+        }
+        Ok(response.json().await?)
+    }
+
+    pub(crate) async fn get<T: DeserializeOwned>(&self, path: &str) -> ChainResult<T> {
+        Ok(self.send(self.http.get(self.endpoint(path)?)).await?)
+    }
+
+    /// Like [`Self::get`], but `None` if the API doesn't know about `path`
+    pub(crate) async fn get_opt<T: DeserializeOwned>(&self, path: &str) -> ChainResult<Option<T>> {
+        match self.send(self.http.get(self.endpoint(path)?)).await {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if err.is_not_found() => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub(crate) async fn ledger_info(&self) -> ChainResult<LedgerInfo> {
+        self.get("").await
+    }
+
+    pub(crate) async fn block_by_version(&self, version: u64) -> ChainResult<BlockInfo> {
+        self.get(&format!("blocks/by_version/{version}")).await
+    }
+
+    pub(crate) async fn transaction_by_version(&self, version: u64) -> ChainResult<Transaction> {
+        self.get(&format!("transactions/by_version/{version}"))
+            .await
+    }
+
+    /// The data of the `resource` of `address`
+    pub(crate) async fn resource(&self, address: &H256, resource: &str) -> ChainResult<Value> {
+        let mut resource: Value = self
+            .get(&format!("accounts/{address:?}/resource/{resource}"))
+            .await?;
+        Ok(resource["data"].take())
+    }
+
+    /// The events emitted to the event handle in `field` of the `resource` of
+    /// `address`, starting at sequence number `start`
+    pub(crate) async fn events(
+        &self,
+        address: &H256,
+        resource: &str,
+        field: &str,
+        start: u64,
+        limit: u64,
+    ) -> ChainResult<Vec<Event>> {
+        self.get(&format!(
+            "accounts/{address:?}/events/{resource}/{field}?start={start}&limit={limit}"
+        ))
+        .await
+    }
+
+    /// Calls the view function `function`, e.g. `0x1::coin::balance`
+    pub(crate) async fn view(
+        &self,
+        function: &str,
+        arguments: Vec<Value>,
+    ) -> ChainResult<Vec<Value>> {
+        let request = self.http.post(self.endpoint("view")?).json(&json!({
+            "function": function,
+            "type_arguments": [],
+            "arguments": arguments,
+        }));
+        Ok(self.send(request).await?)
+    }
+
+    async fn raw_transaction(
+        &self,
+        signer: &Signer,
+        payload: EntryFunction,
+        max_gas_amount: u64,
+        gas_unit_price: u64,
+    ) -> ChainResult<RawTransaction> {
+        let chain_id = self.ledger_info().await?.chain_id;
+        let account: AccountInfo = self.get(&format!("accounts/{:?}", signer.address)).await?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(RawTransaction {
+            sender: signer.address.into(),
+            sequence_number: account.sequence_number,
+            payload: TransactionPayload::EntryFunction(payload),
+            max_gas_amount,
+            gas_unit_price,
+            expiration_timestamp_secs: (now + TRANSACTION_EXPIRATION).as_secs(),
+            chain_id,
+        })
+    }
+
+    async fn post_transaction<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        transaction: &SignedTransaction,
+    ) -> ChainResult<T> {
+        let body = bcs::to_bytes(transaction).map_err(HyperlaneAptosError::from)?;
+        let request = self
+            .http
+            .post(self.endpoint(path)?)
+            .header(CONTENT_TYPE, SIGNED_TRANSACTION_CONTENT_TYPE)
+            .body(body);
+        Ok(self.send(request).await?)
+    }
+
+    /// Simulates `payload` sent by `signer`, letting the node estimate the
+    /// gas unit price and max gas amount. Simulations must not be signed.
+    pub(crate) async fn simulate(
+        &self,
+        signer: &Signer,
+        payload: EntryFunction,
+    ) -> ChainResult<Transaction> {
+        let raw_txn = self
+            .raw_transaction(signer, payload, SIMULATION_MAX_GAS_AMOUNT, 0)
+            .await?;
+        let transaction = SignedTransaction {
+            raw_txn,
+            authenticator: TransactionAuthenticator::Ed25519 {
+                public_key: signer.public_key().to_vec(),
+                signature: vec![0; 64],
+            },
+        };
+        let mut simulated: Vec<Transaction> = self
+            .post_transaction(
+                "transactions/simulate?estimate_gas_unit_price=true&estimate_max_gas_amount=true",
+                &transaction,
+            )
+            .await?;
+        simulated.pop().ok_or_else(|| {
+            HyperlaneAptosError::UnexpectedResponse("empty simulation result".into()).into()
+        })
+    }
+
+    /// Signs and submits `payload` from `signer`'s account, and waits for it
+    /// to be committed.
+    pub(crate) async fn submit(
+        &self,
+        signer: &Signer,
+        payload: EntryFunction,
+        max_gas_amount: u64,
+        gas_unit_price: u64,
+    ) -> ChainResult<Transaction> {
+        let raw_txn = self
+            .raw_transaction(signer, payload, max_gas_amount, gas_unit_price)
+            .await?;
+        let signing_message = raw_txn
+            .signing_message()
+            .map_err(HyperlaneAptosError::from)?;
+        let transaction = SignedTransaction {
+            raw_txn,
+            authenticator: TransactionAuthenticator::Ed25519 {
+                public_key: signer.public_key().to_vec(),
+                signature: signer.sign(&signing_message).to_vec(),
+            },
+        };
+        let pending: Transaction = self.post_transaction("transactions", &transaction).await?;
+        debug!(hash=?pending.hash, "Submitted Aptos transaction");
+        self.wait_for_transaction(&pending.hash).await
+    }
+
+    async fn wait_for_transaction(&self, hash: &H256) -> ChainResult<Transaction> {
+        for _ in 0..TRANSACTION_POLL_ATTEMPTS {
+            let transaction: Option<Transaction> = self
+                .get_opt(&format!("transactions/by_hash/{hash:?}"))
+                .await?;
+            match transaction {
+                Some(transaction) if !transaction.is_pending() => return Ok(transaction),
+                _ => tokio::time::sleep(TRANSACTION_POLL_INTERVAL).await,
+            }
+        }
+        Err(ChainCommunicationError::TransactionTimeout())
+    }
+}
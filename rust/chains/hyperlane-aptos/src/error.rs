@@ -0,0 +1,42 @@
+use hyperlane_core::ChainCommunicationError;
+
+/// Errors from the crates specific to the hyperlane-aptos
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneAptosError {
+    /// Reqwest error
+    #[error("{0}")]
+    ReqwestError(#[from] reqwest::Error),
+    /// serde_json error
+    #[error("{0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+    /// BCS serialization error
+    #[error("{0}")]
+    BcsError(#[from] bcs::Error),
+    /// Error returned by the REST API
+    #[error("Aptos API error {status}: {message}")]
+    ApiError {
+        /// The HTTP status code
+        status: u16,
+        /// The error message
+        message: String,
+    },
+    /// A response that doesn't have the expected shape
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+}
+
+impl HyperlaneAptosError {
+    /// Whether the API reported that the requested resource doesn't exist
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::ApiError { status: 404, .. })
+    }
+}
+
+impl From<HyperlaneAptosError> for ChainCommunicationError {
+    fn from(value: HyperlaneAptosError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
@@ -0,0 +1,384 @@
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tracing::{instrument, warn};
+
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, Decode,
+    FixedPointNumber, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, RawHyperlaneMessage,
+    SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, U256,
+};
+
+use crate::{
+    types::{EntryFunction, ModuleId, Transaction},
+    AptosProvider, ConnectionConf, HyperlaneAptosError, Signer,
+};
+
+/// The name of the mailbox module
+const MAILBOX_MODULE: &str = "mailbox";
+/// The resource of the mailbox module holding its event handles
+const MAILBOX_STATE: &str = "MailboxState";
+/// The event handle of dispatched messages
+const DISPATCH_EVENTS: &str = "dispatch_events";
+/// The event handle of processed message ids
+const PROCESS_EVENTS: &str = "process_events";
+/// The simulated gas of a transaction is multiplied by this to get its max
+/// gas amount, as the simulation and the execution can differ slightly.
+const GAS_LIMIT_MULTIPLIER_NUMERATOR: u64 = 3;
+const GAS_LIMIT_MULTIPLIER_DENOMINATOR: u64 = 2;
+
+/// A reference to a Mailbox module on some Aptos chain
+#[derive(Debug)]
+pub struct AptosMailbox {
+    address: H256,
+    provider: AptosProvider,
+}
+
+impl AptosMailbox {
+    /// Create a new Aptos mailbox
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: AptosProvider::new(locator.domain.clone(), conf, signer),
+        })
+    }
+
+    /// The fully qualified name of `function` of the mailbox module
+    fn function(&self, function: &str) -> String {
+        format!("{:?}::{MAILBOX_MODULE}::{function}", self.address)
+    }
+
+    /// The fully qualified name of the mailbox state resource
+    fn state_resource(&self) -> String {
+        format!("{:?}::{MAILBOX_MODULE}::{MAILBOX_STATE}", self.address)
+    }
+
+    async fn view(&self, function: &str, arguments: Vec<Value>) -> ChainResult<Value> {
+        let mut values = self
+            .provider
+            .client()
+            .view(&self.function(function), arguments)
+            .await?;
+        values.pop().ok_or_else(|| {
+            HyperlaneAptosError::UnexpectedResponse(format!("{function} returned nothing")).into()
+        })
+    }
+
+    /// The number of events emitted to the event handle in `field` of the
+    /// mailbox state
+    async fn event_count(&self, field: &str) -> ChainResult<u32> {
+        let state = self
+            .provider
+            .client()
+            .resource(&self.address, &self.state_resource())
+            .await?;
+        parse_u64(&state[field]["counter"])?
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    /// The events emitted to the event handle in `field` of the mailbox
+    /// state whose sequence numbers are in `range`, along with their metadata
+    async fn events_in_range(
+        &self,
+        field: &str,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Value, LogMeta)>> {
+        let start = u64::from(*range.start());
+        let limit = u64::from(*range.end()) + 1 - start;
+        let events = self
+            .provider
+            .client()
+            .events(&self.address, &self.state_resource(), field, start, limit)
+            .await?;
+        let mut logs = Vec::with_capacity(events.len());
+        for event in events {
+            let meta = self.provider.event_meta(self.address, &event).await?;
+            logs.push((event.data, meta));
+        }
+        Ok(logs)
+    }
+
+    /// The entry function call processing `message`. The mailbox verifies the
+    /// message and hands it to the recipient module.
+    fn process_payload(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<EntryFunction> {
+        let message = RawHyperlaneMessage::from(message);
+        Ok(EntryFunction {
+            module: ModuleId {
+                address: self.address.into(),
+                name: MAILBOX_MODULE.into(),
+            },
+            function: "process".into(),
+            ty_args: vec![],
+            args: vec![
+                bcs::to_bytes(metadata).map_err(HyperlaneAptosError::from)?,
+                bcs::to_bytes(&message).map_err(HyperlaneAptosError::from)?,
+            ],
+        })
+    }
+
+    async fn simulate_process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Transaction> {
+        let payload = self.process_payload(message, metadata)?;
+        let simulation = self
+            .provider
+            .client()
+            .simulate(self.provider.signer()?, payload)
+            .await?;
+        if simulation.success != Some(true) {
+            return Err(ChainCommunicationError::CustomError(format!(
+                "Simulation of process failed: {}",
+                simulation.vm_status.unwrap_or_default()
+            )));
+        }
+        Ok(simulation)
+    }
+}
+
+fn parse_u64(value: &Value) -> ChainResult<u64> {
+    value
+        .as_str()
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| {
+            HyperlaneAptosError::UnexpectedResponse(format!("{value} is not a u64")).into()
+        })
+}
+
+/// A gas field of a committed or simulated transaction, which are always set
+fn gas_field(value: Option<u64>, name: &str) -> ChainResult<u64> {
+    value.ok_or_else(|| {
+        HyperlaneAptosError::UnexpectedResponse(format!("transaction has no {name}")).into()
+    })
+}
+
+fn parse_h256(value: &Value) -> ChainResult<H256> {
+    value
+        .as_str()
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| {
+            HyperlaneAptosError::UnexpectedResponse(format!("{value} is not an address")).into()
+        })
+}
+
+fn parse_bytes(value: &Value) -> ChainResult<Vec<u8>> {
+    value
+        .as_str()
+        .and_then(|value| hex::decode(value.trim_start_matches("0x")).ok())
+        .ok_or_else(|| {
+            HyperlaneAptosError::UnexpectedResponse(format!("{value} is not hex")).into()
+        })
+}
+
+impl HyperlaneContract for AptosMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for AptosMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for AptosMailbox {
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        assert!(
+            lag.is_none(),
+            "Aptos does not support querying point-in-time"
+        );
+        self.event_count(DISPATCH_EVENTS).await
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let delivered = self
+            .view("delivered", vec![json!(format!("{id:?}"))])
+            .await?;
+        delivered.as_bool().ok_or_else(|| {
+            HyperlaneAptosError::UnexpectedResponse(format!("{delivered} is not a bool")).into()
+        })
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        parse_h256(&self.view("get_default_ism", vec![]).await?)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        parse_h256(
+            &self
+                .view("get_recipient_ism", vec![json!(format!("{recipient:?}"))])
+                .await?,
+        )
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let simulation = self.simulate_process(message, metadata).await?;
+        let gas_unit_price = gas_field(simulation.gas_unit_price, "gas_unit_price")?;
+        let max_gas_amount = match tx_gas_limit {
+            Some(gas_limit) => gas_limit.as_u64(),
+            None => {
+                gas_field(simulation.gas_used, "gas_used")? * GAS_LIMIT_MULTIPLIER_NUMERATOR
+                    / GAS_LIMIT_MULTIPLIER_DENOMINATOR
+            }
+        };
+
+        let payload = self.process_payload(message, metadata)?;
+        let transaction = self
+            .provider
+            .client()
+            .submit(
+                self.provider.signer()?,
+                payload,
+                max_gas_amount,
+                gas_unit_price,
+            )
+            .await?;
+        if transaction.success != Some(true) {
+            warn!(hash=?transaction.hash, vm_status=?transaction.vm_status, "Aptos transaction failed");
+        }
+
+        Ok(TxOutcome {
+            transaction_id: transaction.hash.into(),
+            executed: transaction.success == Some(true),
+            gas_used: gas_field(transaction.gas_used, "gas_used")?.into(),
+            gas_price: FixedPointNumber::from(gas_unit_price),
+        })
+    }
+
+    #[instrument(err, ret, skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        let simulation = self.simulate_process(message, metadata).await?;
+        Ok(TxCostEstimate {
+            gas_limit: gas_field(simulation.gas_used, "gas_used")?.into(),
+            gas_price: FixedPointNumber::from(gas_field(
+                simulation.gas_unit_price,
+                "gas_unit_price",
+            )?),
+            l2_gas_limit: None,
+            resources: vec![],
+        })
+    }
+
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        let payload = self
+            .process_payload(message, metadata)
+            .expect("BCS encoding of the process arguments can't fail");
+        bcs::to_bytes(&payload).expect("BCS encoding of an entry function can't fail")
+    }
+}
+
+/// Struct that retrieves dispatched messages and processed message ids from
+/// the event handles of a Mailbox module on some Aptos chain
+#[derive(Debug)]
+pub struct AptosMailboxIndexer {
+    mailbox: AptosMailbox,
+}
+
+impl AptosMailboxIndexer {
+    /// Create a new Aptos mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            mailbox: AptosMailbox::new(conf, locator, None)?,
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for AptosMailboxIndexer {
+    /// Fetches the messages with the nonces in `range`, which are the
+    /// sequence numbers of their dispatch events
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        let events = self.mailbox.events_in_range(DISPATCH_EVENTS, range).await?;
+        events
+            .into_iter()
+            .map(|(data, meta)| {
+                let message =
+                    HyperlaneMessage::read_from(&mut parse_bytes(&data["message"])?.as_slice())
+                        .map_err(ChainCommunicationError::from_other)?;
+                Ok((message.into(), meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.latest_block_height().await
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for AptosMailboxIndexer {
+    /// Fetches the ids of the messages processed in the order of `range`,
+    /// which are the sequence numbers of their process events
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        let events = self.mailbox.events_in_range(PROCESS_EVENTS, range).await?;
+        events
+            .into_iter()
+            .map(|(data, meta)| {
+                let message_id = H256::from_slice(&parse_bytes(&data["message_id"])?);
+                let sequence = meta.log_index.as_u32();
+                Ok((Indexed::new(message_id).with_sequence(sequence), meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.latest_block_height().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for AptosMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
+        let count = self.mailbox.event_count(DISPATCH_EVENTS).await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for AptosMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<H256>::get_finalized_block_number(self).await?;
+        let count = self.mailbox.event_count(PROCESS_EVENTS).await?;
+        Ok((Some(count), tip))
+    }
+}
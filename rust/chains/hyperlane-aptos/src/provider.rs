@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, LogMeta, TxnInfo, H256, U256,
+};
+
+use crate::{client::AptosClient, types::Event, ConnectionConf, HyperlaneAptosError, Signer};
+
+/// The resource holding the balance of the native coin
+const APTOS_COIN_STORE: &str = "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>";
+
+/// A wrapper around the REST API of an Aptos fullnode to get generic
+/// blockchain information, call view functions and submit transactions.
+#[derive(Debug, Clone)]
+pub struct AptosProvider {
+    domain: HyperlaneDomain,
+    client: AptosClient,
+    signer: Option<Signer>,
+}
+
+impl AptosProvider {
+    /// Create a new Aptos provider
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf, signer: Option<Signer>) -> Self {
+        Self {
+            domain,
+            client: AptosClient::new(conf.url.clone()),
+            signer,
+        }
+    }
+
+    pub(crate) fn client(&self) -> &AptosClient {
+        &self.client
+    }
+
+    pub(crate) fn signer(&self) -> ChainResult<&Signer> {
+        self.signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)
+    }
+
+    /// The height of the latest block. Aptos blocks are final once committed.
+    pub(crate) async fn latest_block_height(&self) -> ChainResult<u32> {
+        let ledger_info = self.client.ledger_info().await?;
+        ledger_info
+            .block_height
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    /// The metadata of an event emitted by the contract at `address`
+    pub(crate) async fn event_meta(&self, address: H256, event: &Event) -> ChainResult<LogMeta> {
+        let block = self.client.block_by_version(event.version).await?;
+        let transaction = self.client.transaction_by_version(event.version).await?;
+        Ok(LogMeta {
+            address,
+            block_number: block.block_height,
+            block_hash: block.block_hash,
+            transaction_id: transaction.hash.into(),
+            transaction_index: event.version - block.first_version,
+            // Events of a handle are identified by their sequence number
+            log_index: event.sequence_number.into(),
+        })
+    }
+}
+
+impl HyperlaneChain for AptosProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for AptosProvider {
+    async fn get_block_by_hash(&self, _hash: &H256) -> ChainResult<BlockInfo> {
+        // The REST API only looks up blocks by height or version
+        Err(ChainCommunicationError::from_other_str(
+            "Aptos blocks can't be looked up by hash",
+        ))
+    }
+
+    async fn get_txn_by_hash(&self, _hash: &H256) -> ChainResult<TxnInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "Aptos does not support fetching transactions yet",
+        ))
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        // Any account can publish modules, so contracts are accounts with
+        // modules
+        let modules: Option<Vec<serde_json::Value>> = self
+            .client
+            .get_opt(&format!("accounts/{address:?}/modules?limit=1"))
+            .await?;
+        Ok(modules.map_or(false, |modules| !modules.is_empty()))
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        let address = address
+            .parse::<H256>()
+            .map_err(ChainCommunicationError::from_other)?;
+        let coin_store = self.client.resource(&address, APTOS_COIN_STORE).await?;
+        let balance = coin_store["coin"]["value"].as_str().ok_or_else(|| {
+            HyperlaneAptosError::UnexpectedResponse("coin store has no balance".into())
+        })?;
+        U256::from_dec_str(balance).map_err(ChainCommunicationError::from_other)
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        Ok(None)
+    }
+}
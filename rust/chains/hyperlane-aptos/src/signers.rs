@@ -0,0 +1,63 @@
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer as _};
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+use sha3::{Digest, Sha3_256};
+
+/// The scheme identifier of single ed25519 key accounts, which is appended to
+/// the public key to derive their authentication key.
+const ED25519_SCHEME: u8 = 0;
+
+/// An ed25519 key signing for an Aptos account. The account's address is the
+/// authentication key of the key, so accounts whose key was rotated are not
+/// supported.
+#[derive(Debug)]
+pub struct Signer {
+    keypair: Keypair,
+    /// The address of the account
+    pub address: H256,
+}
+
+impl Signer {
+    /// Create a new Aptos signer from an ed25519 private key
+    pub fn new(private_key: &H256) -> ChainResult<Self> {
+        let secret = SecretKey::from_bytes(private_key.as_bytes())
+            .map_err(ChainCommunicationError::from_other)?;
+        let public = PublicKey::from(&secret);
+        let address = authentication_key(&public);
+        Ok(Self {
+            keypair: Keypair { secret, public },
+            address,
+        })
+    }
+
+    /// The public key of the signer
+    pub(crate) fn public_key(&self) -> [u8; 32] {
+        self.keypair.public.to_bytes()
+    }
+
+    /// Signs `message` with the private key
+    pub(crate) fn sign(&self, message: &[u8]) -> [u8; 64] {
+        self.keypair.sign(message).to_bytes()
+    }
+
+    /// The address of the account, 0x-prefixed
+    pub fn address_string(&self) -> String {
+        format!("{:?}", self.address)
+    }
+}
+
+impl Clone for Signer {
+    fn clone(&self) -> Self {
+        Self {
+            keypair: Keypair::from_bytes(&self.keypair.to_bytes())
+                .expect("keypair bytes are valid"),
+            address: self.address,
+        }
+    }
+}
+
+fn authentication_key(public: &PublicKey) -> H256 {
+    let mut hasher = Sha3_256::new();
+    hasher.update(public.as_bytes());
+    hasher.update([ED25519_SCHEME]);
+    H256::from_slice(&hasher.finalize())
+}
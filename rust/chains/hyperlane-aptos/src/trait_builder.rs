@@ -0,0 +1,22 @@
+use hyperlane_core::config::OperationBatchConfig;
+use url::Url;
+
+/// Aptos connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Fully qualified url of the REST API of a fullnode, e.g.
+    /// `https://fullnode.mainnet.aptoslabs.com`
+    pub url: Url,
+    /// Operation batching configuration
+    pub operation_batch: OperationBatchConfig,
+}
+
+impl ConnectionConf {
+    /// Create a new connection configuration
+    pub fn new(url: Url, operation_batch: OperationBatchConfig) -> Self {
+        Self {
+            url,
+            operation_batch,
+        }
+    }
+}
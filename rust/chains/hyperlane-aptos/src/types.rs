@@ -0,0 +1,190 @@
+//! The BCS encoded transaction types submitted to Aptos, and the JSON types
+//! returned by its REST API.
+
+use hyperlane_core::H256;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+
+/// The salt hashed into the prefix of the signing message of a raw
+/// transaction.
+const RAW_TRANSACTION_SALT: &[u8] = b"APTOS::RawTransaction";
+
+/// A 32 byte account address, BCS encoded without a length prefix.
+#[derive(Serialize, Clone, Copy, Debug)]
+pub(crate) struct AccountAddress(pub [u8; 32]);
+
+impl From<H256> for AccountAddress {
+    fn from(value: H256) -> Self {
+        Self(value.0)
+    }
+}
+
+/// A Move module, e.g. `0x1::coin`
+#[derive(Serialize, Clone, Debug)]
+pub(crate) struct ModuleId {
+    pub address: AccountAddress,
+    pub name: String,
+}
+
+/// Type arguments of an entry function. None of the functions called by the
+/// agents are generic.
+#[derive(Serialize, Clone, Debug)]
+pub(crate) enum TypeTag {}
+
+/// A call to a public entry function of a module
+#[derive(Serialize, Clone, Debug)]
+pub(crate) struct EntryFunction {
+    pub module: ModuleId,
+    pub function: String,
+    pub ty_args: Vec<TypeTag>,
+    /// The BCS encoded arguments
+    pub args: Vec<Vec<u8>>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub(crate) enum TransactionPayload {
+    // Only listed so that entry functions get their variant index
+    #[allow(dead_code)]
+    Script,
+    #[allow(dead_code)]
+    ModuleBundle,
+    EntryFunction(EntryFunction),
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub(crate) struct RawTransaction {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub payload: TransactionPayload,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
+    pub expiration_timestamp_secs: u64,
+    pub chain_id: u8,
+}
+
+impl RawTransaction {
+    /// The message signed by the sender: the hash of the salt followed by
+    /// the BCS encoded transaction.
+    pub(crate) fn signing_message(&self) -> Result<Vec<u8>, bcs::Error> {
+        let mut message = Sha3_256::digest(RAW_TRANSACTION_SALT).to_vec();
+        message.extend(bcs::to_bytes(self)?);
+        Ok(message)
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub(crate) enum TransactionAuthenticator {
+    Ed25519 {
+        public_key: Vec<u8>,
+        signature: Vec<u8>,
+    },
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub(crate) struct SignedTransaction {
+    pub raw_txn: RawTransaction,
+    pub authenticator: TransactionAuthenticator,
+}
+
+/// Deserializes a u64 that the API encodes as a string
+fn u64_from_str<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+}
+
+/// Deserializes an optional u64 that the API encodes as a string
+fn opt_u64_from_str<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// The `GET /v1` ledger info
+#[derive(Deserialize, Debug)]
+pub(crate) struct LedgerInfo {
+    pub chain_id: u8,
+    #[serde(deserialize_with = "u64_from_str")]
+    pub block_height: u64,
+}
+
+/// The `GET /v1/accounts/{address}` account info
+#[derive(Deserialize, Debug)]
+pub(crate) struct AccountInfo {
+    #[serde(deserialize_with = "u64_from_str")]
+    pub sequence_number: u64,
+}
+
+/// The `GET /v1/blocks/by_version/{version}` block info
+#[derive(Deserialize, Debug)]
+pub(crate) struct BlockInfo {
+    #[serde(deserialize_with = "u64_from_str")]
+    pub block_height: u64,
+    pub block_hash: H256,
+    #[serde(deserialize_with = "u64_from_str")]
+    pub first_version: u64,
+}
+
+/// An event emitted to an event handle
+#[derive(Deserialize, Debug)]
+pub(crate) struct Event {
+    /// The version of the transaction that emitted the event
+    #[serde(deserialize_with = "u64_from_str")]
+    pub version: u64,
+    #[serde(deserialize_with = "u64_from_str")]
+    pub sequence_number: u64,
+    pub data: serde_json::Value,
+}
+
+/// The fields of committed, pending and simulated transactions that the
+/// agents use
+#[derive(Deserialize, Debug)]
+pub(crate) struct Transaction {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub hash: H256,
+    /// Unset for pending transactions
+    #[serde(default)]
+    pub success: Option<bool>,
+    #[serde(default)]
+    pub vm_status: Option<String>,
+    #[serde(default, deserialize_with = "opt_u64_from_str")]
+    pub gas_used: Option<u64>,
+    #[serde(default, deserialize_with = "opt_u64_from_str")]
+    pub gas_unit_price: Option<u64>,
+}
+
+impl Transaction {
+    pub(crate) fn is_pending(&self) -> bool {
+        self.kind == "pending_transaction"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_entry_function_payload_encoding() {
+        let payload = TransactionPayload::EntryFunction(EntryFunction {
+            module: ModuleId {
+                address: AccountAddress([1; 32]),
+                name: "mailbox".into(),
+            },
+            function: "process".into(),
+            ty_args: vec![],
+            args: vec![bcs::to_bytes(&vec![0xabu8]).unwrap()],
+        });
+        let mut expected = vec![2];
+        expected.extend([1; 32]);
+        expected.push(7);
+        expected.extend(b"mailbox");
+        expected.push(7);
+        expected.extend(b"process");
+        // No type arguments, one argument of 2 bytes: the vector's length
+        // and its single byte
+        expected.extend([0, 1, 2, 1, 0xab]);
+        assert_eq!(bcs::to_bytes(&payload).unwrap(), expected);
+    }
+}
@@ -1,11 +1,17 @@
 use std::str::FromStr;
 
 use crate::{
+    address::CosmosAddress,
     grpc::WasmProvider,
     payloads::{
+        aggregate_ism::{
+            ModulesAndThresholdRequest, ModulesAndThresholdRequestInner,
+            ModulesAndThresholdResponse,
+        },
         ism_routes::QueryIsmGeneralRequest,
         multisig_ism::{VerifyInfoRequest, VerifyInfoRequestInner, VerifyInfoResponse},
     },
+    utils::is_unknown_query_error,
     ConnectionConf, CosmosProvider, Signer,
 };
 use async_trait::async_trait;
@@ -13,7 +19,7 @@ use hyperlane_core::{
     AggregationIsm, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
     HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, RawHyperlaneMessage, H160, H256,
 };
-use tracing::instrument;
+use tracing::{debug, instrument};
 
 /// A reference to an AggregationIsm contract on some Cosmos chain
 #[derive(Debug)]
@@ -61,13 +67,37 @@ impl HyperlaneChain for CosmosAggregationIsm {
     }
 }
 
-#[async_trait]
-impl AggregationIsm for CosmosAggregationIsm {
-    #[instrument(err)]
-    async fn modules_and_threshold(
+impl CosmosAggregationIsm {
+    /// Queries the `modules_and_threshold` endpoint of the ISM interface.
+    async fn query_modules_and_threshold(
         &self,
         message: &HyperlaneMessage,
     ) -> ChainResult<(Vec<H256>, u8)> {
+        let payload = ModulesAndThresholdRequest {
+            modules_and_threshold: ModulesAndThresholdRequestInner {
+                message: hex::encode(RawHyperlaneMessage::from(message)),
+            },
+        };
+
+        let data = self
+            .provider
+            .grpc()
+            .wasm_query(QueryIsmGeneralRequest { ism: payload }, None)
+            .await?;
+        let response: ModulesAndThresholdResponse = serde_json::from_slice(&data)?;
+
+        let modules: ChainResult<Vec<H256>> = response
+            .modules
+            .iter()
+            .map(|module| parse_module(module))
+            .collect();
+
+        Ok((modules?, response.threshold))
+    }
+
+    /// Queries the legacy `verify_info` endpoint, for contracts that predate
+    /// `modules_and_threshold`.
+    async fn query_verify_info(&self, message: &HyperlaneMessage) -> ChainResult<(Vec<H256>, u8)> {
         let payload = VerifyInfoRequest {
             verify_info: VerifyInfoRequestInner {
                 message: hex::encode(RawHyperlaneMessage::from(message)),
@@ -85,18 +115,90 @@ impl AggregationIsm for CosmosAggregationIsm {
         let modules: ChainResult<Vec<H256>> = response
             .validators
             .iter()
-            .map(|module| {
-                // The returned values are Bech32-decoded Cosmos addresses.
-                // Since they are not EOAs but rather contracts, they can be 32 bytes long and
-                // need to be parsed directly as an `H256`.
-                if let Ok(res) = H256::from_str(module) {
-                    return Ok(res);
-                }
-                // If the address is not 32 bytes long, it is a 20-byte address
-                H160::from_str(module).map(H256::from).map_err(Into::into)
-            })
+            .map(|module| parse_module(module))
             .collect();
 
         Ok((modules?, response.threshold))
     }
 }
+
+/// Parses the address of a module, which is either a Bech32 contract address
+/// or its hex-encoded bytes.
+fn parse_module(module: &str) -> ChainResult<H256> {
+    if let Ok(address) = CosmosAddress::from_str(module) {
+        return Ok(address.digest());
+    }
+    // Since they are not EOAs but rather contracts, they can be 32 bytes long and
+    // need to be parsed directly as an `H256`.
+    if let Ok(res) = H256::from_str(module) {
+        return Ok(res);
+    }
+    // If the address is not 32 bytes long, it is a 20-byte address
+    H160::from_str(module).map(H256::from).map_err(Into::into)
+}
+
+#[async_trait]
+impl AggregationIsm for CosmosAggregationIsm {
+    #[instrument(err)]
+    async fn modules_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        match self.query_modules_and_threshold(message).await {
+            Err(err) if is_unknown_query_error(&err) => {
+                debug!(error=?err, "ISM doesn't support modules_and_threshold, falling back to verify_info");
+                self.query_verify_info(message).await
+            }
+            res => res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_module() {
+        let expected =
+            H256::from_str("0d8a53233a016a05f234d1cd105c3a3884c7ecde176b85bde7b423a05cd45b21")
+                .unwrap();
+        assert_eq!(
+            parse_module("dual1pk99xge6q94qtu3568x3qhp68zzv0mx7za4ct008ks36qhx5tvss3qawfh")
+                .unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_module("0d8a53233a016a05f234d1cd105c3a3884c7ecde176b85bde7b423a05cd45b21")
+                .unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_module("1cdcf6568b3e80b52f2806e01b89a34dc90ae616").unwrap(),
+            H256::from(H160::from_str("1cdcf6568b3e80b52f2806e01b89a34dc90ae616").unwrap())
+        );
+        assert!(parse_module("not a module").is_err());
+    }
+
+    #[test]
+    fn test_modules_and_threshold_payloads() {
+        let payload = QueryIsmGeneralRequest {
+            ism: ModulesAndThresholdRequest {
+                modules_and_threshold: ModulesAndThresholdRequestInner {
+                    message: "0102".to_owned(),
+                },
+            },
+        };
+        assert_eq!(
+            serde_json::to_string(&payload).unwrap(),
+            r#"{"ism":{"modules_and_threshold":{"message":"0102"}}}"#
+        );
+
+        let response: ModulesAndThresholdResponse = serde_json::from_str(
+            r#"{"threshold":2,"modules":["dual1pk99xge6q94qtu3568x3qhp68zzv0mx7za4ct008ks36qhx5tvss3qawfh"]}"#,
+        )
+        .unwrap();
+        assert_eq!(response.threshold, 2);
+        assert_eq!(response.modules.len(), 1);
+    }
+}
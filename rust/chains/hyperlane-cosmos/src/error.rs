@@ -44,6 +44,9 @@ pub enum HyperlaneCosmosError {
     /// Protobuf error
     #[error("{0}")]
     Protobuf(#[from] protobuf::ProtobufError),
+    /// Elliptic curve signature error
+    #[error("{0}")]
+    EcdsaError(#[from] k256::ecdsa::Error),
     /// Fallback providers failed
     #[error("Fallback providers failed. (Errors: {0:?})")]
     FallbackProvidersFailed(Vec<HyperlaneCosmosError>),
@@ -1,6 +1,5 @@
 use async_trait::async_trait;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-use futures::future;
 use hyperlane_core::{
     ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
     HyperlaneDomain, HyperlaneProvider, Indexed, Indexer, InterchainGasPaymaster,
@@ -9,13 +8,16 @@ use hyperlane_core::{
 use once_cell::sync::Lazy;
 use std::ops::RangeInclusive;
 use tendermint::abci::EventAttribute;
-use tracing::{instrument, warn};
+use tracing::instrument;
 
 use crate::{
+    grpc::WasmProvider,
+    native,
+    payloads::igp,
     rpc::{CosmosWasmIndexer, ParsedEvent, WasmIndexer},
     signers::Signer,
     utils::{CONTRACT_ADDRESS_ATTRIBUTE_KEY, CONTRACT_ADDRESS_ATTRIBUTE_KEY_BASE64},
-    ConnectionConf, CosmosProvider, HyperlaneCosmosError,
+    ConnectionConf, CosmosModuleType, CosmosProvider, HyperlaneCosmosError,
 };
 
 /// A reference to a InterchainGasPaymaster contract on some Cosmos chain
@@ -42,7 +44,30 @@ impl HyperlaneChain for CosmosInterchainGasPaymaster {
     }
 }
 
-impl InterchainGasPaymaster for CosmosInterchainGasPaymaster {}
+#[async_trait]
+impl InterchainGasPaymaster for CosmosInterchainGasPaymaster {
+    #[instrument(err, skip(self))]
+    async fn quote_gas_payment(
+        &self,
+        destination_domain: u32,
+        gas_amount: U256,
+    ) -> ChainResult<U256> {
+        let payload = igp::IgpGenericRequest {
+            igp: igp::QuoteGasPaymentRequest {
+                quote_gas_payment: igp::QuoteGasPaymentRequestInner {
+                    dest_domain: destination_domain,
+                    gas_amount: gas_amount.to_string(),
+                },
+            },
+        };
+
+        let data = self.provider.grpc().wasm_query(payload, None).await?;
+        let response: igp::QuoteGasPaymentResponse = serde_json::from_slice(&data)?;
+
+        // U256's from_str assumes a radix of 16, so we explicitly use from_dec_str.
+        Ok(U256::from_dec_str(&response.gas_needed)?)
+    }
+}
 
 impl CosmosInterchainGasPaymaster {
     /// create new Cosmos InterchainGasPaymaster agent
@@ -84,10 +109,13 @@ const DESTINATION_ATTRIBUTE_KEY: &str = "dest_domain";
 static DESTINATION_ATTRIBUTE_KEY_BASE64: Lazy<String> =
     Lazy::new(|| BASE64.encode(DESTINATION_ATTRIBUTE_KEY));
 
+const NATIVE_DESTINATION_ATTRIBUTE_KEY: &str = "destination";
+
 /// A reference to a InterchainGasPaymasterIndexer contract on some Cosmos chain
 #[derive(Debug, Clone)]
 pub struct CosmosInterchainGasPaymasterIndexer {
     indexer: Box<CosmosWasmIndexer>,
+    native_module: bool,
 }
 
 impl CosmosInterchainGasPaymasterIndexer {
@@ -100,15 +128,27 @@ impl CosmosInterchainGasPaymasterIndexer {
         locator: ContractLocator,
         reorg_period: u32,
     ) -> ChainResult<Self> {
-        let indexer = CosmosWasmIndexer::new(
-            conf,
-            locator,
-            Self::INTERCHAIN_GAS_PAYMENT_EVENT_TYPE.into(),
-            reorg_period,
-        )?;
+        let native_module = conf.get_module_type() == CosmosModuleType::Native;
+        let indexer = if native_module {
+            CosmosWasmIndexer::new_native(
+                conf,
+                locator,
+                native::GAS_PAYMENT_EVENT_TYPE,
+                native::GAS_PAYMENT_IGP_ATTRIBUTE_KEY,
+                reorg_period,
+            )?
+        } else {
+            CosmosWasmIndexer::new(
+                conf,
+                locator,
+                Self::INTERCHAIN_GAS_PAYMENT_EVENT_TYPE.into(),
+                reorg_period,
+            )?
+        };
 
         Ok(Self {
             indexer: Box::new(indexer),
+            native_module,
         })
     }
 
@@ -198,6 +238,47 @@ impl CosmosInterchainGasPaymasterIndexer {
 
         Ok(ParsedEvent::new(contract_address, gas_payment.try_into()?))
     }
+
+    /// Parses the typed gas payment event of the native module, whose
+    /// attribute values are JSON encoded.
+    #[instrument(err)]
+    fn native_interchain_gas_payment_parser(
+        attrs: &Vec<EventAttribute>,
+    ) -> ChainResult<ParsedEvent<InterchainGasPayment>> {
+        let mut igp_id: Option<String> = None;
+        let mut gas_payment = IncompleteInterchainGasPayment::default();
+
+        for attr in attrs {
+            match attr.key.as_str() {
+                native::GAS_PAYMENT_IGP_ATTRIBUTE_KEY => {
+                    // Kept JSON encoded, as that is how the indexer matches it
+                    igp_id = Some(attr.value.clone());
+                }
+                MESSAGE_ID_ATTRIBUTE_KEY => {
+                    let value = native::decode_event_string(&attr.value)?;
+                    gas_payment.message_id = Some(native::parse_id(&value)?);
+                }
+                PAYMENT_ATTRIBUTE_KEY => {
+                    let value = native::decode_event_string(&attr.value)?;
+                    gas_payment.payment = Some(U256::from_dec_str(&value)?);
+                }
+                GAS_AMOUNT_ATTRIBUTE_KEY => {
+                    let value = native::decode_event_string(&attr.value)?;
+                    gas_payment.gas_amount = Some(U256::from_dec_str(&value)?);
+                }
+                // The native module names the destination without the `dest_` prefix
+                NATIVE_DESTINATION_ATTRIBUTE_KEY => {
+                    gas_payment.destination = Some(attr.value.parse::<u32>()?);
+                }
+                _ => {}
+            }
+        }
+
+        let igp_id =
+            igp_id.ok_or_else(|| ChainCommunicationError::from_other_str("missing igp_id"))?;
+
+        Ok(ParsedEvent::new(igp_id, gas_payment.try_into()?))
+    }
 }
 
 #[async_trait]
@@ -206,39 +287,17 @@ impl Indexer<InterchainGasPayment> for CosmosInterchainGasPaymasterIndexer {
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
-        let logs_futures: Vec<_> = range
-            .map(|block_number| {
-                let self_clone = self.clone();
-                tokio::spawn(async move {
-                    let logs = self_clone
-                        .indexer
-                        .get_logs_in_block(
-                            block_number,
-                            Self::interchain_gas_payment_parser,
-                            "InterchainGasPaymentCursor",
-                        )
-                        .await;
-                    (logs, block_number)
-                })
-            })
-            .collect();
-
-        // TODO: this can be refactored when we rework indexing, to be part of the block-by-block indexing
-        let result = future::join_all(logs_futures)
-            .await
-            .into_iter()
-            .flatten()
-            .map(|(logs, block_number)| {
-                if let Err(err) = &logs {
-                    warn!(?err, ?block_number, "Failed to fetch logs for block");
-                }
-                logs
-            })
-            // Propagate errors from any of the queries. This will cause the entire range to be retried,
-            // including successful ones, but we don't have a way to handle partial failures in a range for now.
-            .collect::<Result<Vec<_>, _>>()?
+        let parser: for<'a> fn(&'a Vec<EventAttribute>) -> ChainResult<ParsedEvent<_>> =
+            if self.native_module {
+                Self::native_interchain_gas_payment_parser
+            } else {
+                Self::interchain_gas_payment_parser
+            };
+        let result = self
+            .indexer
+            .get_logs_in_range(range, parser, "InterchainGasPaymentCursor")
+            .await?
             .into_iter()
-            .flatten()
             .map(|(log, meta)| (Indexed::new(log), meta))
             .collect();
 
@@ -339,4 +398,51 @@ mod tests {
 
         assert_parsed_event(&base64_attrs);
     }
+
+    #[test]
+    fn test_native_interchain_gas_payment_parser() {
+        let igp_id = "\"0x726f757465725f706f73745f6469737061746368000000040000000000000000\"";
+        let attrs = event_attributes_from_str(
+            r#"[{"key":"message_id","value":"\"0x5dcf6120f8adf4f267eb1a122a85c42eae257fbc872671e93929fbf63daed19b\"","index":true},{"key":"destination","value":"169","index":true},{"key":"gas_amount","value":"\"25000\"","index":true},{"key":"payment","value":"\"2\"","index":true},{"key":"igp_id","value":"\"0x726f757465725f706f73745f6469737061746368000000040000000000000000\"","index":true}]"#,
+        );
+        let parsed_event =
+            CosmosInterchainGasPaymasterIndexer::native_interchain_gas_payment_parser(&attrs)
+                .unwrap();
+
+        assert_eq!(
+            parsed_event,
+            ParsedEvent::new(
+                igp_id.into(),
+                InterchainGasPayment {
+                    message_id: H256::from_str(
+                        "5dcf6120f8adf4f267eb1a122a85c42eae257fbc872671e93929fbf63daed19b",
+                    )
+                    .unwrap(),
+                    payment: U256::from(2),
+                    gas_amount: U256::from(25000),
+                    destination: 169,
+                },
+            )
+        );
+    }
+
+    #[test]
+    fn test_quote_gas_payment_payload() {
+        let payload = igp::IgpGenericRequest {
+            igp: igp::QuoteGasPaymentRequest {
+                quote_gas_payment: igp::QuoteGasPaymentRequestInner {
+                    dest_domain: 169,
+                    gas_amount: U256::from(25000).to_string(),
+                },
+            },
+        };
+        assert_eq!(
+            serde_json::to_string(&payload).unwrap(),
+            r#"{"igp":{"quote_gas_payment":{"dest_domain":169,"gas_amount":"25000"}}}"#
+        );
+
+        let response: igp::QuoteGasPaymentResponse =
+            serde_json::from_str(r#"{"gas_needed":"2"}"#).unwrap();
+        assert_eq!(U256::from_dec_str(&response.gas_needed).unwrap(), 2.into());
+    }
 }
@@ -13,6 +13,7 @@ mod libs;
 mod mailbox;
 mod merkle_tree_hook;
 mod multisig_ism;
+mod native;
 mod payloads;
 mod providers;
 mod routing_ism;
@@ -26,5 +27,5 @@ mod validator_announce;
 pub use self::{
     aggregation_ism::*, error::*, interchain_gas::*, interchain_security_module::*, libs::*,
     mailbox::*, merkle_tree_hook::*, multisig_ism::*, providers::*, routing_ism::*, signers::*,
-    trait_builder::*, trait_builder::*, validator_announce::*, validator_announce::*,
+    trait_builder::*, validator_announce::*,
 };
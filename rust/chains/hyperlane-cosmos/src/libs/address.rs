@@ -5,12 +5,39 @@ use cosmrs::{
     AccountId,
 };
 use derive_new::new;
-use hyperlane_core::{ChainCommunicationError, ChainResult, Error::Overflow, H256};
+use hyperlane_core::{AddressCodec, ChainCommunicationError, ChainResult, Error::Overflow, H256};
+use k256::ecdsa::VerifyingKey;
+use sha3::{Digest, Keccak256};
 use tendermint::account::Id as TendermintAccountId;
 use tendermint::public_key::PublicKey as TendermintPublicKey;
 
 use crate::HyperlaneCosmosError;
 
+/// The scheme used to derive an account address from a secp256k1 public key.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum AccountAddressType {
+    /// Bitcoin style address: RIPEMD160(SHA256(pubkey)). Used by most cosmos-sdk chains.
+    #[default]
+    Bitcoin,
+    /// Ethereum style address: the last 20 bytes of Keccak256(uncompressed pubkey).
+    /// Used by Ethermint-based chains such as Injective.
+    Ethereum,
+}
+
+impl FromStr for AccountAddressType {
+    type Err = ChainCommunicationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "bitcoin" => Ok(Self::Bitcoin),
+            "ethereum" => Ok(Self::Ethereum),
+            _ => Err(ChainCommunicationError::ParseError {
+                msg: format!("Unknown account address type `{s}`"),
+            }),
+        }
+    }
+}
+
 /// Wrapper around the cosmrs AccountId type that abstracts bech32 encoding
 #[derive(new, Debug, Clone)]
 pub struct CosmosAddress {
@@ -21,27 +48,59 @@ pub struct CosmosAddress {
 }
 
 impl CosmosAddress {
-    /// Returns a Bitcoin style address: RIPEMD160(SHA256(pubkey))
-    /// Source: `<https://github.com/cosmos/cosmos-sdk/blob/177e7f45959215b0b4e85babb7c8264eaceae052/crypto/keys/secp256k1/secp256k1.go#L154>`
-    pub fn from_pubkey(pubkey: PublicKey, prefix: &str) -> ChainResult<Self> {
-        // Get the inner type
-        let tendermint_pubkey = TendermintPublicKey::from(pubkey);
-        // Get the RIPEMD160(SHA256(pubkey))
-        let tendermint_id = TendermintAccountId::from(tendermint_pubkey);
+    /// Returns an address derived from the public key using the given `account_address_type`
+    pub fn from_pubkey(
+        pubkey: PublicKey,
+        prefix: &str,
+        account_address_type: AccountAddressType,
+    ) -> ChainResult<Self> {
+        let account_bytes = match account_address_type {
+            AccountAddressType::Bitcoin => Self::bitcoin_style_bytes(pubkey),
+            AccountAddressType::Ethereum => Self::ethereum_style_bytes(pubkey)?,
+        };
         // Bech32 encoding
-        let account_id = AccountId::new(prefix, tendermint_id.as_bytes())
+        let account_id = AccountId::new(prefix, account_bytes.as_slice())
             .map_err(Into::<HyperlaneCosmosError>::into)?;
         // Hex digest
-        let digest = Self::bech32_decode(account_id.clone())?;
+        let codec = AddressCodec::Bech32 {
+            prefix: prefix.to_owned(),
+            len: account_bytes.len(),
+        };
+        let digest = codec.decode(&account_id.to_string())?;
         Ok(CosmosAddress::new(account_id, digest))
     }
 
     /// Creates a wrapper around a cosmrs AccountId from a private key byte array
-    pub fn from_privkey(priv_key: &[u8], prefix: &str) -> ChainResult<Self> {
+    pub fn from_privkey(
+        priv_key: &[u8],
+        prefix: &str,
+        account_address_type: AccountAddressType,
+    ) -> ChainResult<Self> {
         let pubkey = SigningKey::from_slice(priv_key)
             .map_err(Into::<HyperlaneCosmosError>::into)?
             .public_key();
-        Self::from_pubkey(pubkey, prefix)
+        Self::from_pubkey(pubkey, prefix, account_address_type)
+    }
+
+    /// Returns a Bitcoin style address: RIPEMD160(SHA256(pubkey))
+    /// Source: `<https://github.com/cosmos/cosmos-sdk/blob/177e7f45959215b0b4e85babb7c8264eaceae052/crypto/keys/secp256k1/secp256k1.go#L154>`
+    fn bitcoin_style_bytes(pubkey: PublicKey) -> Vec<u8> {
+        // Get the inner type
+        let tendermint_pubkey = TendermintPublicKey::from(pubkey);
+        // Get the RIPEMD160(SHA256(pubkey))
+        let tendermint_id = TendermintAccountId::from(tendermint_pubkey);
+        tendermint_id.as_bytes().to_vec()
+    }
+
+    /// Returns an Ethereum style address: Keccak256(uncompressed pubkey)[12..]
+    /// Source: `<https://github.com/evmos/ethermint/blob/fd8c2d25cf80e7d2d2a142e7b374f979f8f51981/crypto/ethsecp256k1/ethsecp256k1.go#L165>`
+    fn ethereum_style_bytes(pubkey: PublicKey) -> ChainResult<Vec<u8>> {
+        let verifying_key = VerifyingKey::from_sec1_bytes(pubkey.to_bytes().as_slice())
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let uncompressed = verifying_key.to_encoded_point(false);
+        // Skip the 0x04 uncompressed point tag
+        let hash = Keccak256::digest(&uncompressed.as_bytes()[1..]);
+        Ok(hash[12..].to_vec())
     }
 
     /// Creates a wrapper around a cosmrs AccountId from a H256 digest
@@ -69,14 +128,6 @@ impl CosmosAddress {
         Ok(CosmosAddress::new(account_id, digest))
     }
 
-    /// Builds a H256 digest from a cosmos AccountId (Bech32 encoding)
-    fn bech32_decode(account_id: AccountId) -> ChainResult<H256> {
-        // Temporarily set the digest to a default value as a placeholder.
-        // Can't implement H256::try_from for AccountId to avoid this.
-        let cosmos_address = CosmosAddress::new(account_id, Default::default());
-        H256::try_from(&cosmos_address)
-    }
-
     /// String representation of a cosmos AccountId
     pub fn address(&self) -> String {
         self.account_id.to_string()
@@ -88,30 +139,16 @@ impl CosmosAddress {
     }
 }
 
-impl TryFrom<&CosmosAddress> for H256 {
-    type Error = ChainCommunicationError;
-
-    fn try_from(cosmos_address: &CosmosAddress) -> Result<Self, Self::Error> {
-        // `to_bytes()` decodes the Bech32 into a hex, represented as a byte vec
-        let bytes = cosmos_address.account_id.to_bytes();
-        let h256_len = H256::len_bytes();
-        let Some(start_point) = h256_len.checked_sub(bytes.len()) else {
-            // input is too large to fit in a H256
-            return Err(Overflow.into());
-        };
-        let mut empty_hash = H256::default();
-        let result = empty_hash.as_bytes_mut();
-        result[start_point..].copy_from_slice(bytes.as_slice());
-        Ok(H256::from_slice(result))
-    }
-}
-
 impl FromStr for CosmosAddress {
     type Err = ChainCommunicationError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let account_id = AccountId::from_str(s).map_err(Into::<HyperlaneCosmosError>::into)?;
-        let digest = Self::bech32_decode(account_id.clone())?;
+        let codec = AddressCodec::Bech32 {
+            prefix: account_id.prefix().to_owned(),
+            len: account_id.to_bytes().len(),
+        };
+        let digest = codec.decode(s)?;
         Ok(Self::new(account_id, digest))
     }
 }
@@ -138,7 +175,7 @@ pub mod test {
         let hex_key = "0x5486418967eabc770b0fcb995f7ef6d9a72f7fc195531ef76c5109f44f51af26";
         let key = hex_or_base58_to_h256(hex_key).unwrap();
         let prefix = "neutron";
-        let addr = CosmosAddress::from_privkey(key.as_bytes(), prefix)
+        let addr = CosmosAddress::from_privkey(key.as_bytes(), prefix, AccountAddressType::Bitcoin)
             .expect("Cosmos address creation failed");
         assert_eq!(
             addr.address(),
@@ -153,6 +190,20 @@ pub mod test {
         assert_eq!(addr.address(), addr2.address());
     }
 
+    #[test]
+    fn test_ethereum_style_address_from_key() {
+        // Ethereum address of this key is 0x90F8bf6A479f320ead074411a4B0e7944Ea8c9C1
+        let hex_key = "0x4f3edf983ac636a65a842ce7c78d9aa706d3b113bce9c46f30d7d21715b23b1d";
+        let key = hex_or_base58_to_h256(hex_key).unwrap();
+        let addr = CosmosAddress::from_privkey(key.as_bytes(), "inj", AccountAddressType::Ethereum)
+            .expect("Cosmos address creation failed");
+        assert_eq!(
+            addr.digest(),
+            H256::from_str("00000000000000000000000090f8bf6a479f320ead074411a4b0e7944ea8c9c1")
+                .unwrap()
+        );
+    }
+
     #[test]
     fn test_bech32_encode_from_h256() {
         let hex_key = "0x1b16866227825a5166eb44031cdcf6568b3e80b52f2806e01b89a34dc90ae616";
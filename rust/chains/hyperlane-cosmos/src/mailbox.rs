@@ -1,5 +1,4 @@
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-use futures::future;
 use std::{
     fmt::{Debug, Formatter},
     io::Cursor,
@@ -8,15 +7,19 @@ use std::{
     str::FromStr,
 };
 
+use crate::native::{
+    self, MsgProcessMessage, NativeMailbox, QueryDeliveredRequest, QueryDeliveredResponse,
+    QueryMailboxRequest, QueryMailboxResponse, QueryRecipientIsmRequest, QueryRecipientIsmResponse,
+};
 use crate::payloads::mailbox::{
     GeneralMailboxQuery, ProcessMessageRequest, ProcessMessageRequestInner,
 };
 use crate::payloads::{general, mailbox};
 use crate::rpc::{CosmosWasmIndexer, ParsedEvent, WasmIndexer};
-use crate::CosmosProvider;
 use crate::{address::CosmosAddress, types::tx_response_to_outcome};
 use crate::{grpc::WasmProvider, HyperlaneCosmosError};
 use crate::{signers::Signer, utils::get_block_height_for_lag, ConnectionConf};
+use crate::{CosmosModuleType, CosmosProvider};
 use async_trait::async_trait;
 use cosmrs::proto::cosmos::base::abci::v1beta1::TxResponse;
 use once_cell::sync::Lazy;
@@ -73,6 +76,10 @@ impl CosmosMailbox {
     fn contract_address_bytes(&self) -> usize {
         self.config.get_contract_address_bytes()
     }
+
+    fn is_native_module(&self) -> bool {
+        self.config.get_module_type() == CosmosModuleType::Native
+    }
 }
 
 impl HyperlaneContract for CosmosMailbox {
@@ -107,6 +114,9 @@ impl Mailbox for CosmosMailbox {
 
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        if self.is_native_module() {
+            return self.native_delivered(id).await;
+        }
         let id = hex::encode(id);
         let payload = mailbox::DeliveredRequest {
             message_delivered: mailbox::DeliveredRequestInner { id },
@@ -138,6 +148,9 @@ impl Mailbox for CosmosMailbox {
 
     #[instrument(err, ret, skip(self))]
     async fn default_ism(&self) -> ChainResult<H256> {
+        if self.is_native_module() {
+            return native::parse_id(&self.native_mailbox(None).await?.default_ism);
+        }
         let payload = mailbox::DefaultIsmRequest {
             default_ism: general::EmptyStruct {},
         };
@@ -156,6 +169,9 @@ impl Mailbox for CosmosMailbox {
 
     #[instrument(err, ret, skip(self))]
     async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        if self.is_native_module() {
+            return self.native_recipient_ism(recipient).await;
+        }
         let address = CosmosAddress::from_h256(
             recipient,
             &self.bech32_prefix(),
@@ -188,6 +204,11 @@ impl Mailbox for CosmosMailbox {
         metadata: &[u8],
         tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome> {
+        if self.is_native_module() {
+            let msg = self.native_process_msg(message, metadata)?;
+            let response = self.provider.grpc().send(vec![msg], tx_gas_limit).await?;
+            return Ok(tx_response_to_outcome(response)?);
+        }
         let process_message = ProcessMessageRequest {
             process: ProcessMessageRequestInner {
                 message: hex::encode(RawHyperlaneMessage::from(message)),
@@ -210,23 +231,27 @@ impl Mailbox for CosmosMailbox {
         message: &HyperlaneMessage,
         metadata: &[u8],
     ) -> ChainResult<TxCostEstimate> {
-        let process_message = ProcessMessageRequest {
-            process: ProcessMessageRequestInner {
-                message: hex::encode(RawHyperlaneMessage::from(message)),
-                metadata: hex::encode(metadata),
-            },
+        let gas_limit = if self.is_native_module() {
+            let msg = self.native_process_msg(message, metadata)?;
+            self.provider.grpc().estimate_gas(vec![msg]).await?
+        } else {
+            let process_message = ProcessMessageRequest {
+                process: ProcessMessageRequestInner {
+                    message: hex::encode(RawHyperlaneMessage::from(message)),
+                    metadata: hex::encode(metadata),
+                },
+            };
+            self.provider
+                .grpc()
+                .wasm_estimate_gas(process_message)
+                .await?
         };
 
-        let gas_limit = self
-            .provider
-            .grpc()
-            .wasm_estimate_gas(process_message)
-            .await?;
-
         let result = TxCostEstimate {
             gas_limit: gas_limit.into(),
-            gas_price: self.provider.grpc().gas_price(),
+            gas_price: self.provider.grpc().canonical_gas_price(),
             l2_gas_limit: None,
+            resources: vec![],
         };
 
         Ok(result)
@@ -240,6 +265,9 @@ impl Mailbox for CosmosMailbox {
 impl CosmosMailbox {
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn nonce_at_block(&self, block_height: Option<u64>) -> ChainResult<u32> {
+        if self.is_native_module() {
+            return Ok(self.native_mailbox(block_height).await?.message_sent);
+        }
         let payload = mailbox::NonceRequest {
             nonce: general::EmptyStruct {},
         };
@@ -256,6 +284,76 @@ impl CosmosMailbox {
     }
 }
 
+// ------------------ Native module ------------------
+
+impl CosmosMailbox {
+    async fn native_mailbox(&self, block_height: Option<u64>) -> ChainResult<NativeMailbox> {
+        let response: QueryMailboxResponse = self
+            .provider
+            .grpc()
+            .module_query(
+                native::MAILBOX_QUERY_PATH,
+                QueryMailboxRequest {
+                    id: native::format_id(self.address),
+                },
+                block_height,
+            )
+            .await?;
+
+        response
+            .mailbox
+            .ok_or_else(|| ChainCommunicationError::from_other_str("mailbox not present"))
+    }
+
+    async fn native_delivered(&self, id: H256) -> ChainResult<bool> {
+        let response: QueryDeliveredResponse = self
+            .provider
+            .grpc()
+            .module_query(
+                native::DELIVERED_QUERY_PATH,
+                QueryDeliveredRequest {
+                    id: native::format_id(self.address),
+                    message_id: native::format_id(id),
+                },
+                None,
+            )
+            .await?;
+
+        Ok(response.delivered)
+    }
+
+    async fn native_recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let response: QueryRecipientIsmResponse = self
+            .provider
+            .grpc()
+            .module_query(
+                native::RECIPIENT_ISM_QUERY_PATH,
+                QueryRecipientIsmRequest {
+                    recipient: native::format_id(recipient),
+                },
+                None,
+            )
+            .await?;
+
+        native::parse_id(&response.ism_id)
+    }
+
+    fn native_process_msg(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<cosmrs::Any> {
+        let signer = self.provider.grpc().get_signer()?;
+        Ok(MsgProcessMessage {
+            mailbox_id: native::format_id(self.address),
+            relayer: signer.address.clone(),
+            metadata: bytes_to_hex(metadata),
+            message: bytes_to_hex(&RawHyperlaneMessage::from(message)),
+        }
+        .to_any())
+    }
+}
+
 // ------------------ Indexer ------------------
 
 const MESSAGE_ATTRIBUTE_KEY: &str = "message";
@@ -282,12 +380,21 @@ impl CosmosMailboxIndexer {
         reorg_period: u32,
     ) -> ChainResult<Self> {
         let mailbox = CosmosMailbox::new(conf.clone(), locator.clone(), signer.clone())?;
-        let indexer = CosmosWasmIndexer::new(
-            conf,
-            locator,
-            Self::MESSAGE_DISPATCH_EVENT_TYPE.into(),
-            reorg_period,
-        )?;
+        let indexer = match conf.get_module_type() {
+            CosmosModuleType::Wasm => CosmosWasmIndexer::new(
+                conf,
+                locator,
+                Self::MESSAGE_DISPATCH_EVENT_TYPE.into(),
+                reorg_period,
+            )?,
+            CosmosModuleType::Native => CosmosWasmIndexer::new_native(
+                conf,
+                locator,
+                native::DISPATCH_EVENT_TYPE,
+                native::DISPATCH_MAILBOX_ATTRIBUTE_KEY,
+                reorg_period,
+            )?,
+        };
 
         Ok(Self {
             mailbox,
@@ -346,6 +453,38 @@ impl CosmosMailboxIndexer {
 
         Ok(ParsedEvent::new(contract_address, message))
     }
+
+    /// Parses the typed dispatch event of the native module, whose attribute
+    /// values are JSON encoded.
+    #[instrument(err)]
+    fn native_hyperlane_message_parser(
+        attrs: &Vec<EventAttribute>,
+    ) -> ChainResult<ParsedEvent<HyperlaneMessage>> {
+        let mut mailbox_id: Option<String> = None;
+        let mut message: Option<HyperlaneMessage> = None;
+
+        for attr in attrs {
+            match attr.key.as_str() {
+                native::DISPATCH_MAILBOX_ATTRIBUTE_KEY => {
+                    // Kept JSON encoded, as that is how the indexer matches it
+                    mailbox_id = Some(attr.value.clone());
+                }
+                MESSAGE_ATTRIBUTE_KEY => {
+                    let value = native::decode_event_string(&attr.value)?;
+                    let mut reader = Cursor::new(hex::decode(value.trim_start_matches("0x"))?);
+                    message = Some(HyperlaneMessage::read_from(&mut reader)?);
+                }
+                _ => {}
+            }
+        }
+
+        let mailbox_id = mailbox_id
+            .ok_or_else(|| ChainCommunicationError::from_other_str("missing origin_mailbox_id"))?;
+        let message =
+            message.ok_or_else(|| ChainCommunicationError::from_other_str("missing message"))?;
+
+        Ok(ParsedEvent::new(mailbox_id, message))
+    }
 }
 
 #[async_trait]
@@ -354,36 +493,17 @@ impl Indexer<HyperlaneMessage> for CosmosMailboxIndexer {
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
-        let logs_futures: Vec<_> = range
-            .map(|block_number| {
-                let self_clone = self.clone();
-                tokio::spawn(async move {
-                    let logs = self_clone
-                        .indexer
-                        .get_logs_in_block(
-                            block_number,
-                            Self::hyperlane_message_parser,
-                            "HyperlaneMessageCursor",
-                        )
-                        .await;
-                    (logs, block_number)
-                })
-            })
-            .collect();
-
-        // TODO: this can be refactored when we rework indexing, to be part of the block-by-block indexing
-        let result = future::join_all(logs_futures)
-            .await
+        let parser: for<'a> fn(&'a Vec<EventAttribute>) -> ChainResult<ParsedEvent<_>> =
+            if self.mailbox.is_native_module() {
+                Self::native_hyperlane_message_parser
+            } else {
+                Self::hyperlane_message_parser
+            };
+        let result = self
+            .indexer
+            .get_logs_in_range(range, parser, "HyperlaneMessageCursor")
+            .await?
             .into_iter()
-            .flatten()
-            .filter_map(|(logs_res, block_number)| match logs_res {
-                Ok(logs) => Some(logs),
-                Err(err) => {
-                    warn!(?err, ?block_number, "Failed to fetch logs for block");
-                    None
-                }
-            })
-            .flatten()
             .map(|(log, meta)| (log.into(), meta))
             .collect();
 
@@ -466,4 +586,17 @@ mod tests {
         );
         assert_parsed_event(&base64_attrs);
     }
+
+    #[test]
+    fn test_native_hyperlane_message_parser() {
+        let message = HyperlaneMessage::from(hex::decode("03000000006e74726e0000000000000000000000006ba6343a09a60ac048d0e99f50b76fd99eff1063000000a9000000000000000000000000281973b53c9aacec128ac964a6f750fea40912aa48656c6c6f").unwrap());
+        let mailbox_id = "\"0x68797065726c616e650000000000000000000000000000000000000000000000\"";
+
+        let attrs = event_attributes_from_str(
+            r#"[{"key":"origin_mailbox_id","value":"\"0x68797065726c616e650000000000000000000000000000000000000000000000\"","index":true},{"key":"sender","value":"\"0x0000000000000000000000006ba6343a09a60ac048d0e99f50b76fd99eff1063\"","index":true},{"key":"destination","value":"169","index":true},{"key":"message","value":"\"0x03000000006e74726e0000000000000000000000006ba6343a09a60ac048d0e99f50b76fd99eff1063000000a9000000000000000000000000281973b53c9aacec128ac964a6f750fea40912aa48656c6c6f\"","index":true}]"#,
+        );
+        let parsed_event = CosmosMailboxIndexer::native_hyperlane_message_parser(&attrs).unwrap();
+
+        assert_eq!(parsed_event, ParsedEvent::new(mailbox_id.into(), message));
+    }
 }
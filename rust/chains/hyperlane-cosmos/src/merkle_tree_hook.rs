@@ -2,7 +2,6 @@ use std::{fmt::Debug, num::NonZeroU64, ops::RangeInclusive, str::FromStr};
 
 use async_trait::async_trait;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-use futures::future;
 use hyperlane_core::{
     accumulator::incremental::IncrementalMerkle, ChainCommunicationError, ChainResult, Checkpoint,
     ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneProvider,
@@ -10,10 +9,11 @@ use hyperlane_core::{
 };
 use once_cell::sync::Lazy;
 use tendermint::abci::EventAttribute;
-use tracing::{instrument, warn};
+use tracing::instrument;
 
 use crate::{
     grpc::WasmProvider,
+    native::{self, NativeMerkleTree, QueryMerkleTreeHookRequest, QueryMerkleTreeHookResponse},
     payloads::{
         general::{self},
         merkle_tree_hook,
@@ -23,12 +23,14 @@ use crate::{
         get_block_height_for_lag, CONTRACT_ADDRESS_ATTRIBUTE_KEY,
         CONTRACT_ADDRESS_ATTRIBUTE_KEY_BASE64,
     },
-    ConnectionConf, CosmosProvider, HyperlaneCosmosError, Signer,
+    ConnectionConf, CosmosModuleType, CosmosProvider, HyperlaneCosmosError, Signer,
 };
 
 #[derive(Debug, Clone)]
 /// A reference to a MerkleTreeHook contract on some Cosmos chain
 pub struct CosmosMerkleTreeHook {
+    /// Connection configuration
+    config: ConnectionConf,
     /// Domain
     domain: HyperlaneDomain,
     /// Contract address
@@ -52,11 +54,16 @@ impl CosmosMerkleTreeHook {
         )?;
 
         Ok(Self {
+            config: conf,
             domain: locator.domain.clone(),
             address: locator.address,
             provider,
         })
     }
+
+    fn is_native_module(&self) -> bool {
+        self.config.get_module_type() == CosmosModuleType::Native
+    }
 }
 
 impl HyperlaneContract for CosmosMerkleTreeHook {
@@ -86,6 +93,19 @@ impl MerkleTreeHook for CosmosMerkleTreeHook {
 
         let block_height = get_block_height_for_lag(self.provider.grpc(), lag).await?;
 
+        if self.is_native_module() {
+            let tree = self.native_tree(block_height).await?;
+            let branch = tree
+                .leafs
+                .iter()
+                .map(|node| native::parse_hash(node))
+                .collect::<ChainResult<Vec<H256>>>()?;
+            let branch_res: [H256; 32] = branch.try_into().map_err(|_| {
+                ChainCommunicationError::from_other_str("Failed to build merkle branch array")
+            })?;
+            return Ok(IncrementalMerkle::new(branch_res, tree.count as usize));
+        }
+
         let data = self
             .provider
             .grpc()
@@ -131,6 +151,16 @@ impl MerkleTreeHook for CosmosMerkleTreeHook {
 
         let block_height = get_block_height_for_lag(self.provider.grpc(), lag).await?;
 
+        if self.is_native_module() {
+            let tree = self.native_tree(block_height).await?;
+            return Ok(Checkpoint {
+                merkle_tree_hook_address: self.address,
+                mailbox_domain: self.domain.id(),
+                root: native::parse_hash(&tree.root)?,
+                index: tree.count.saturating_sub(1),
+            });
+        }
+
         let data = self
             .provider
             .grpc()
@@ -155,6 +185,10 @@ impl MerkleTreeHook for CosmosMerkleTreeHook {
 impl CosmosMerkleTreeHook {
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn count_at_block(&self, block_height: Option<u64>) -> ChainResult<u32> {
+        if self.is_native_module() {
+            return Ok(self.native_tree(block_height).await?.count);
+        }
+
         let payload = merkle_tree_hook::MerkleTreeCountRequest {
             count: general::EmptyStruct {},
         };
@@ -173,6 +207,25 @@ impl CosmosMerkleTreeHook {
 
         Ok(response.count)
     }
+
+    async fn native_tree(&self, block_height: Option<u64>) -> ChainResult<NativeMerkleTree> {
+        let response: QueryMerkleTreeHookResponse = self
+            .provider
+            .grpc()
+            .module_query(
+                native::MERKLE_TREE_HOOK_QUERY_PATH,
+                QueryMerkleTreeHookRequest {
+                    id: native::format_id(self.address),
+                },
+                block_height,
+            )
+            .await?;
+
+        response
+            .merkle_tree_hook
+            .and_then(|hook| hook.merkle_tree)
+            .ok_or_else(|| ChainCommunicationError::from_other_str("merkle tree hook not present"))
+    }
 }
 
 // ------------------ Indexer ------------------
@@ -205,12 +258,21 @@ impl CosmosMerkleTreeHookIndexer {
         signer: Option<Signer>,
         reorg_period: u32,
     ) -> ChainResult<Self> {
-        let indexer = CosmosWasmIndexer::new(
-            conf.clone(),
-            locator.clone(),
-            Self::MERKLE_TREE_INSERTION_EVENT_TYPE.into(),
-            reorg_period,
-        )?;
+        let indexer = match conf.get_module_type() {
+            CosmosModuleType::Wasm => CosmosWasmIndexer::new(
+                conf.clone(),
+                locator.clone(),
+                Self::MERKLE_TREE_INSERTION_EVENT_TYPE.into(),
+                reorg_period,
+            )?,
+            CosmosModuleType::Native => CosmosWasmIndexer::new_native(
+                conf.clone(),
+                locator.clone(),
+                native::INSERTED_INTO_TREE_EVENT_TYPE,
+                native::INSERTED_INTO_TREE_HOOK_ATTRIBUTE_KEY,
+                reorg_period,
+            )?,
+        };
 
         Ok(Self {
             merkle_tree_hook: CosmosMerkleTreeHook::new(conf, locator, signer)?,
@@ -278,6 +340,39 @@ impl CosmosMerkleTreeHookIndexer {
 
         Ok(ParsedEvent::new(contract_address, insertion.try_into()?))
     }
+
+    /// Parses the typed insertion event of the native module, whose attribute
+    /// values are JSON encoded.
+    #[instrument(err)]
+    fn native_merkle_tree_insertion_parser(
+        attrs: &Vec<EventAttribute>,
+    ) -> ChainResult<ParsedEvent<MerkleTreeInsertion>> {
+        let mut hook_id: Option<String> = None;
+        let mut insertion = IncompleteMerkleTreeInsertion::default();
+
+        for attr in attrs {
+            match attr.key.as_str() {
+                native::INSERTED_INTO_TREE_HOOK_ATTRIBUTE_KEY => {
+                    // Kept JSON encoded, as that is how the indexer matches it
+                    hook_id = Some(attr.value.clone());
+                }
+                MESSAGE_ID_ATTRIBUTE_KEY => {
+                    let value = native::decode_event_string(&attr.value)?;
+                    insertion.message_id = Some(native::parse_id(&value)?);
+                }
+                INDEX_ATTRIBUTE_KEY => {
+                    insertion.leaf_index = Some(attr.value.parse::<u32>()?);
+                }
+                _ => {}
+            }
+        }
+
+        let hook_id = hook_id.ok_or_else(|| {
+            ChainCommunicationError::from_other_str("missing merkle_tree_hook_id")
+        })?;
+
+        Ok(ParsedEvent::new(hook_id, insertion.try_into()?))
+    }
 }
 
 #[async_trait]
@@ -287,36 +382,17 @@ impl Indexer<MerkleTreeInsertion> for CosmosMerkleTreeHookIndexer {
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
-        let logs_futures: Vec<_> = range
-            .map(|block_number| {
-                let self_clone = self.clone();
-                tokio::spawn(async move {
-                    let logs = self_clone
-                        .indexer
-                        .get_logs_in_block(
-                            block_number,
-                            Self::merkle_tree_insertion_parser,
-                            "MerkleTreeInsertionCursor",
-                        )
-                        .await;
-                    (logs, block_number)
-                })
-            })
-            .collect();
-
-        // TODO: this can be refactored when we rework indexing, to be part of the block-by-block indexing
-        let result = future::join_all(logs_futures)
-            .await
+        let parser: for<'a> fn(&'a Vec<EventAttribute>) -> ChainResult<ParsedEvent<_>> =
+            if self.merkle_tree_hook.is_native_module() {
+                Self::native_merkle_tree_insertion_parser
+            } else {
+                Self::merkle_tree_insertion_parser
+            };
+        let result = self
+            .indexer
+            .get_logs_in_range(range, parser, "MerkleTreeInsertionCursor")
+            .await?
             .into_iter()
-            .flatten()
-            .filter_map(|(logs_res, block_number)| match logs_res {
-                Ok(logs) => Some(logs),
-                Err(err) => {
-                    warn!(?err, ?block_number, "Failed to fetch logs for block");
-                    None
-                }
-            })
-            .flatten()
             .map(|(log, meta)| (log.into(), meta))
             .collect();
 
@@ -404,4 +480,28 @@ mod tests {
         );
         assert_parsed_event(&base64_attrs);
     }
+
+    #[test]
+    fn test_native_merkle_tree_insertion_parser() {
+        let hook_id = "\"0x726f757465725f706f73745f6469737061746368000000030000000000000001\"";
+        let attrs = event_attributes_from_str(
+            r#"[{"key":"message_id","value":"\"0xa21078beac8bc19770d532eed0b4ada5ef0b45992cde219979f07e3e49185384\"","index":true},{"key":"index","value":"4","index":true},{"key":"merkle_tree_hook_id","value":"\"0x726f757465725f706f73745f6469737061746368000000030000000000000001\"","index":true}]"#,
+        );
+        let parsed_event =
+            CosmosMerkleTreeHookIndexer::native_merkle_tree_insertion_parser(&attrs).unwrap();
+
+        assert_eq!(
+            parsed_event,
+            ParsedEvent::new(
+                hook_id.into(),
+                MerkleTreeInsertion::new(
+                    4,
+                    H256::from_str(
+                        "a21078beac8bc19770d532eed0b4ada5ef0b45992cde219979f07e3e49185384"
+                    )
+                    .unwrap(),
+                ),
+            )
+        );
+    }
 }
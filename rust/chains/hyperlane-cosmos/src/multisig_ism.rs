@@ -2,15 +2,19 @@ use std::str::FromStr;
 
 use crate::{
     grpc::WasmProvider, payloads::ism_routes::QueryIsmGeneralRequest, signers::Signer,
-    ConnectionConf, CosmosProvider,
+    utils::is_unknown_query_error, ConnectionConf, CosmosProvider,
 };
 use async_trait::async_trait;
 use hyperlane_core::{
     ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
     HyperlaneMessage, HyperlaneProvider, MultisigIsm, RawHyperlaneMessage, H160, H256,
 };
+use tracing::debug;
 
-use crate::payloads::multisig_ism::{self, VerifyInfoRequest, VerifyInfoRequestInner};
+use crate::payloads::multisig_ism::{
+    EnrolledValidatorsRequest, EnrolledValidatorsRequestInner, EnrolledValidatorsResponse,
+    QueryMultisigIsmGeneralRequest, VerifyInfoRequest, VerifyInfoRequestInner, VerifyInfoResponse,
+};
 
 /// A reference to a MultisigIsm contract on some Cosmos chain
 #[derive(Debug)]
@@ -58,13 +62,36 @@ impl HyperlaneChain for CosmosMultisigIsm {
     }
 }
 
-#[async_trait]
-impl MultisigIsm for CosmosMultisigIsm {
-    /// Returns the validator and threshold needed to verify message
-    async fn validators_and_threshold(
+impl CosmosMultisigIsm {
+    /// Queries the validators enrolled for the origin domain of `message`.
+    async fn query_enrolled_validators(
         &self,
         message: &HyperlaneMessage,
     ) -> ChainResult<(Vec<H256>, u8)> {
+        let payload = EnrolledValidatorsRequest {
+            enrolled_validators: EnrolledValidatorsRequestInner {
+                domain: message.origin,
+            },
+        };
+
+        let data = self
+            .provider
+            .grpc()
+            .wasm_query(
+                QueryMultisigIsmGeneralRequest {
+                    multisig_ism: payload,
+                },
+                None,
+            )
+            .await?;
+        let response: EnrolledValidatorsResponse = serde_json::from_slice(&data)?;
+
+        Ok((parse_validators(&response.validators)?, response.threshold))
+    }
+
+    /// Queries the legacy `verify_info` endpoint, for contracts that predate
+    /// `enrolled_validators`.
+    async fn query_verify_info(&self, message: &HyperlaneMessage) -> ChainResult<(Vec<H256>, u8)> {
         let payload = VerifyInfoRequest {
             verify_info: VerifyInfoRequestInner {
                 message: hex::encode(RawHyperlaneMessage::from(message)),
@@ -76,14 +103,63 @@ impl MultisigIsm for CosmosMultisigIsm {
             .grpc()
             .wasm_query(QueryIsmGeneralRequest { ism: payload }, None)
             .await?;
-        let response: multisig_ism::VerifyInfoResponse = serde_json::from_slice(&data)?;
+        let response: VerifyInfoResponse = serde_json::from_slice(&data)?;
+
+        Ok((parse_validators(&response.validators)?, response.threshold))
+    }
+}
 
-        let validators: ChainResult<Vec<H256>> = response
-            .validators
-            .iter()
-            .map(|v| H160::from_str(v).map(H256::from).map_err(Into::into))
-            .collect();
+/// Validators are hex-encoded 20 byte addresses.
+fn parse_validators(validators: &[String]) -> ChainResult<Vec<H256>> {
+    validators
+        .iter()
+        .map(|v| H160::from_str(v).map(H256::from).map_err(Into::into))
+        .collect()
+}
+
+#[async_trait]
+impl MultisigIsm for CosmosMultisigIsm {
+    /// Returns the validator and threshold needed to verify message
+    async fn validators_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        match self.query_enrolled_validators(message).await {
+            Err(err) if is_unknown_query_error(&err) => {
+                debug!(error=?err, "ISM doesn't support enrolled_validators, falling back to verify_info");
+                self.query_verify_info(message).await
+            }
+            res => res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enrolled_validators_payloads() {
+        let payload = QueryMultisigIsmGeneralRequest {
+            multisig_ism: EnrolledValidatorsRequest {
+                enrolled_validators: EnrolledValidatorsRequestInner { domain: 1 },
+            },
+        };
+        assert_eq!(
+            serde_json::to_string(&payload).unwrap(),
+            r#"{"multisig_ism":{"enrolled_validators":{"domain":1}}}"#
+        );
 
-        Ok((validators?, response.threshold))
+        let response: EnrolledValidatorsResponse = serde_json::from_str(
+            r#"{"validators":["90f8bf6a479f320ead074411a4b0e7944ea8c9c1"],"threshold":1}"#,
+        )
+        .unwrap();
+        assert_eq!(response.threshold, 1);
+        assert_eq!(
+            parse_validators(&response.validators).unwrap(),
+            vec![H256::from(
+                H160::from_str("90f8bf6a479f320ead074411a4b0e7944ea8c9c1").unwrap()
+            )]
+        );
     }
 }
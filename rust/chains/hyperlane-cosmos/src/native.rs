@@ -0,0 +1,231 @@
+//! Types of the native Hyperlane Cosmos SDK module (`hyperlane.core.v1`) and
+//! its post dispatch hooks (`hyperlane.core.post_dispatch.v1`).
+//!
+//! Chains that implement Hyperlane as a native module instead of CosmWasm
+//! contracts expose it through the module's own gRPC query and msg services.
+//! Objects such as mailboxes are identified by 32 byte ids, which are passed
+//! around as 0x-prefixed hex strings.
+
+use std::str::FromStr;
+
+use cosmrs::Any;
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+use prost::Message;
+
+/// gRPC method querying a mailbox by id.
+pub(crate) const MAILBOX_QUERY_PATH: &str = "/hyperlane.core.v1.Query/Mailbox";
+/// gRPC method querying whether a message has been delivered.
+pub(crate) const DELIVERED_QUERY_PATH: &str = "/hyperlane.core.v1.Query/Delivered";
+/// gRPC method querying the ISM of a recipient.
+pub(crate) const RECIPIENT_ISM_QUERY_PATH: &str = "/hyperlane.core.v1.Query/RecipientIsm";
+/// Type url of the msg delivering a message to its recipient.
+pub(crate) const MSG_PROCESS_MESSAGE_TYPE_URL: &str = "/hyperlane.core.v1.MsgProcessMessage";
+/// Typed event emitted by the module when a message is dispatched.
+pub(crate) const DISPATCH_EVENT_TYPE: &str = "hyperlane.core.v1.EventDispatch";
+/// Attribute of the dispatch event holding the id of the origin mailbox.
+pub(crate) const DISPATCH_MAILBOX_ATTRIBUTE_KEY: &str = "origin_mailbox_id";
+/// gRPC method querying a merkle tree hook by id.
+pub(crate) const MERKLE_TREE_HOOK_QUERY_PATH: &str =
+    "/hyperlane.core.post_dispatch.v1.Query/MerkleTreeHook";
+/// Typed event emitted by a merkle tree hook when a message id is inserted.
+pub(crate) const INSERTED_INTO_TREE_EVENT_TYPE: &str =
+    "hyperlane.core.post_dispatch.v1.EventInsertedIntoTree";
+/// Attribute of the insertion event holding the id of the merkle tree hook.
+pub(crate) const INSERTED_INTO_TREE_HOOK_ATTRIBUTE_KEY: &str = "merkle_tree_hook_id";
+/// Typed event emitted by an interchain gas paymaster when gas is paid for.
+pub(crate) const GAS_PAYMENT_EVENT_TYPE: &str = "hyperlane.core.post_dispatch.v1.EventGasPayment";
+/// Attribute of the gas payment event holding the id of the paymaster.
+pub(crate) const GAS_PAYMENT_IGP_ATTRIBUTE_KEY: &str = "igp_id";
+
+/// A mailbox of the native module.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct NativeMailbox {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    #[prost(uint32, tag = "3")]
+    pub message_sent: u32,
+    #[prost(uint32, tag = "4")]
+    pub message_received: u32,
+    #[prost(string, tag = "5")]
+    pub default_ism: String,
+    #[prost(string, tag = "6")]
+    pub default_hook: String,
+    #[prost(string, tag = "7")]
+    pub required_hook: String,
+    #[prost(uint32, tag = "8")]
+    pub local_domain: u32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryMailboxRequest {
+    #[prost(string, tag = "1")]
+    pub id: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryMailboxResponse {
+    #[prost(message, optional, tag = "1")]
+    pub mailbox: Option<NativeMailbox>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryDeliveredRequest {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub message_id: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryDeliveredResponse {
+    #[prost(bool, tag = "1")]
+    pub delivered: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryRecipientIsmRequest {
+    #[prost(string, tag = "1")]
+    pub recipient: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryRecipientIsmResponse {
+    #[prost(string, tag = "1")]
+    pub ism_id: String,
+}
+
+/// The incremental merkle tree of a merkle tree hook.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct NativeMerkleTree {
+    /// The branch of the tree, one node per level.
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub leafs: Vec<Vec<u8>>,
+    #[prost(uint32, tag = "2")]
+    pub count: u32,
+    #[prost(bytes = "vec", tag = "3")]
+    pub root: Vec<u8>,
+}
+
+/// A merkle tree hook of the native module.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct NativeMerkleTreeHook {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    #[prost(string, tag = "3")]
+    pub mailbox_id: String,
+    #[prost(message, optional, tag = "4")]
+    pub merkle_tree: Option<NativeMerkleTree>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryMerkleTreeHookRequest {
+    #[prost(string, tag = "1")]
+    pub id: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryMerkleTreeHookResponse {
+    #[prost(message, optional, tag = "1")]
+    pub merkle_tree_hook: Option<NativeMerkleTreeHook>,
+}
+
+/// Delivers `message` to its recipient after verifying `metadata` with the
+/// recipient's ISM. Message and metadata are hex encoded.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct MsgProcessMessage {
+    #[prost(string, tag = "1")]
+    pub mailbox_id: String,
+    #[prost(string, tag = "2")]
+    pub relayer: String,
+    #[prost(string, tag = "3")]
+    pub metadata: String,
+    #[prost(string, tag = "4")]
+    pub message: String,
+}
+
+impl MsgProcessMessage {
+    /// Wrap the msg so it can be included in a transaction.
+    pub(crate) fn to_any(&self) -> Any {
+        Any {
+            type_url: MSG_PROCESS_MESSAGE_TYPE_URL.to_owned(),
+            value: self.encode_to_vec(),
+        }
+    }
+}
+
+/// Format the id of a module object the way the module expects it.
+pub(crate) fn format_id(id: H256) -> String {
+    format!("{id:?}")
+}
+
+/// Parse the id of a module object.
+pub(crate) fn parse_id(id: &str) -> ChainResult<H256> {
+    H256::from_str(id).map_err(|e| ChainCommunicationError::ParseError {
+        msg: format!("Invalid native module id `{id}`: {e}"),
+    })
+}
+
+/// Parse a 32 byte hash, e.g. a node of a merkle tree.
+pub(crate) fn parse_hash(bytes: &[u8]) -> ChainResult<H256> {
+    if bytes.len() != H256::len_bytes() {
+        return Err(ChainCommunicationError::ParseError {
+            msg: format!("Invalid hash of {} bytes", bytes.len()),
+        });
+    }
+    Ok(H256::from_slice(bytes))
+}
+
+/// Attribute values of typed events are JSON encoded, e.g. strings are quoted.
+pub(crate) fn decode_event_string(value: &str) -> ChainResult<String> {
+    Ok(serde_json::from_str(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_roundtrip() {
+        let id = H256::from_low_u64_be(42);
+        let formatted = format_id(id);
+        assert_eq!(
+            formatted,
+            "0x000000000000000000000000000000000000000000000000000000000000002a"
+        );
+        assert_eq!(parse_id(&formatted).unwrap(), id);
+        assert!(parse_id("neutron1xyz").is_err());
+    }
+
+    #[test]
+    fn test_parse_hash() {
+        let hash = H256::from_low_u64_be(42);
+        assert_eq!(parse_hash(hash.as_bytes()).unwrap(), hash);
+        assert!(parse_hash(&[0u8; 20]).is_err());
+    }
+
+    #[test]
+    fn test_process_message_encoding() {
+        let msg = MsgProcessMessage {
+            mailbox_id: "0x01".to_owned(),
+            relayer: "hyp1relayer".to_owned(),
+            metadata: "0x".to_owned(),
+            message: "0x03".to_owned(),
+        };
+        let any = msg.to_any();
+        assert_eq!(any.type_url, MSG_PROCESS_MESSAGE_TYPE_URL);
+        assert_eq!(
+            MsgProcessMessage::decode(any.value.as_slice()).unwrap(),
+            msg
+        );
+    }
+
+    #[test]
+    fn test_decode_event_string() {
+        assert_eq!(decode_event_string("\"0x03\"").unwrap(), "0x03");
+        assert!(decode_event_string("0x03").is_err());
+    }
+}
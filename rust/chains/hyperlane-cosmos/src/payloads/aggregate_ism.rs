@@ -15,3 +15,19 @@ pub struct VerifyRequestInner {
 pub struct VerifyResponse {
     pub verified: bool,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModulesAndThresholdRequest {
+    pub modules_and_threshold: ModulesAndThresholdRequestInner,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModulesAndThresholdRequestInner {
+    pub message: String, // hexbinary
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ModulesAndThresholdResponse {
+    pub threshold: u8,
+    pub modules: Vec<String>, // bech32 contract addresses
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IgpGenericRequest<T> {
+    pub igp: T,
+}
+
+// --------- Requests ---------
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuoteGasPaymentRequest {
+    pub quote_gas_payment: QuoteGasPaymentRequestInner,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuoteGasPaymentRequestInner {
+    pub dest_domain: u32,
+    pub gas_amount: String, // decimal Uint256
+}
+
+// --------- Responses ---------
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QuoteGasPaymentResponse {
+    pub gas_needed: String, // decimal Uint256
+}
@@ -1,5 +1,6 @@
 pub mod aggregate_ism;
 pub mod general;
+pub mod igp;
 pub mod ism_routes;
 pub mod mailbox;
 pub mod merkle_tree_hook;
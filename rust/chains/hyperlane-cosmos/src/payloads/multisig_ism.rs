@@ -15,3 +15,24 @@ pub struct VerifyInfoResponse {
     pub threshold: u8,
     pub validators: Vec<String>,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QueryMultisigIsmGeneralRequest<T> {
+    pub multisig_ism: T,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EnrolledValidatorsRequest {
+    pub enrolled_validators: EnrolledValidatorsRequestInner,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EnrolledValidatorsRequestInner {
+    pub domain: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EnrolledValidatorsResponse {
+    pub validators: Vec<String>, // hexbinary
+    pub threshold: u8,
+}
@@ -10,6 +10,7 @@ use cosmrs::{
                 abci::v1beta1::TxResponse,
                 tendermint::v1beta1::{service_client::ServiceClient, GetLatestBlockRequest},
             },
+            crypto::secp256k1::PubKey as Secp256k1PubKey,
             tx::v1beta1::{
                 service_client::ServiceClient as TxServiceClient, BroadcastMode,
                 BroadcastTxRequest, SimulateRequest, TxRaw,
@@ -21,7 +22,7 @@ use cosmrs::{
         },
         traits::Message,
     },
-    tx::{self, Fee, MessageExt, SignDoc, SignerInfo},
+    tx::{self, Fee, MessageExt, ModeInfo, SignDoc, SignMode, SignerInfo, SignerPublicKey},
     Any, Coin,
 };
 use derive_new::new;
@@ -39,7 +40,10 @@ use tonic::{
 use tracing::{debug, instrument};
 use url::Url;
 
-use crate::{address::CosmosAddress, CosmosAmount};
+use crate::{
+    address::{AccountAddressType, CosmosAddress},
+    CosmosAmount,
+};
 use crate::{rpc_clients::CosmosFallbackProvider, HyperlaneCosmosError};
 use crate::{signers::Signer, ConnectionConf};
 
@@ -49,6 +53,10 @@ const GAS_ESTIMATE_MULTIPLIER: f64 = 1.25;
 /// The number of blocks in the future in which a transaction will
 /// be valid for.
 const TIMEOUT_BLOCKS: u64 = 1000;
+/// The type url of Injective's `eth_secp256k1` public keys.
+const INJECTIVE_PUBLIC_KEY_TYPE_URL: &str = "/injective.crypto.v1beta1.ethsecp256k1.PubKey";
+/// The type url of Ethermint's `eth_secp256k1` public keys.
+const ETHERMINT_PUBLIC_KEY_TYPE_URL: &str = "/ethermint.crypto.v1.ethsecp256k1.PubKey";
 
 #[derive(Debug, Clone, new)]
 struct CosmosChannel {
@@ -135,6 +143,8 @@ pub struct WasmGrpcProvider {
     /// See `<https://docs.rs/tonic/latest/tonic/transport/struct.Channel.html#multiplexing-requests>`
     provider: CosmosFallbackProvider<CosmosChannel>,
     gas_price: CosmosAmount,
+    /// The gas price in the canonical asset, which costs are estimated in
+    canonical_gas_price: FixedPointNumber,
 }
 
 impl WasmGrpcProvider {
@@ -160,6 +170,7 @@ impl WasmGrpcProvider {
         builder = builder.add_providers(channels?);
         let fallback_provider = builder.build();
         let provider = CosmosFallbackProvider::new(fallback_provider);
+        let canonical_gas_price = CosmosAmount::try_from(conf.get_canonical_gas_price())?.amount;
 
         let contract_address = locator
             .map(|l| {
@@ -178,21 +189,58 @@ impl WasmGrpcProvider {
             signer,
             provider,
             gas_price,
+            canonical_gas_price,
         })
     }
 
     /// Gets a signer, or returns an error if one is not available.
-    fn get_signer(&self) -> ChainResult<&Signer> {
+    pub fn get_signer(&self) -> ChainResult<&Signer> {
         self.signer
             .as_ref()
             .ok_or(ChainCommunicationError::SignerUnavailable)
     }
 
-    /// Get the gas price
+    /// Get the gas price in the denom fees are paid in
     pub fn gas_price(&self) -> FixedPointNumber {
         self.gas_price.amount.clone()
     }
 
+    /// Get the gas price in the canonical asset
+    pub fn canonical_gas_price(&self) -> FixedPointNumber {
+        self.canonical_gas_price.clone()
+    }
+
+    /// Whether the signer uses Ethermint-style (`eth_secp256k1`) accounts.
+    fn uses_ethermint_accounts(&self) -> bool {
+        self.signer
+            .as_ref()
+            .map(|signer| signer.account_address_type == AccountAddressType::Ethereum)
+            .unwrap_or_default()
+    }
+
+    /// The public key of the signer as it should be included in the `SignerInfo`.
+    /// Ethermint chains expect `eth_secp256k1` keys, which share the encoding of
+    /// regular secp256k1 keys but use a different type url.
+    fn signer_public_key(&self, signer: &Signer) -> SignerPublicKey {
+        match signer.account_address_type {
+            AccountAddressType::Bitcoin => signer.public_key.into(),
+            AccountAddressType::Ethereum => {
+                let type_url = if self.domain.is_injective() {
+                    INJECTIVE_PUBLIC_KEY_TYPE_URL
+                } else {
+                    ETHERMINT_PUBLIC_KEY_TYPE_URL
+                };
+                SignerPublicKey::Any(Any {
+                    type_url: type_url.to_owned(),
+                    value: Secp256k1PubKey {
+                        key: signer.public_key.to_bytes(),
+                    }
+                    .encode_to_vec(),
+                })
+            }
+        }
+    }
+
     /// Generates an unsigned SignDoc for a transaction and the Coin amount
     /// required to pay for tx fees.
     async fn generate_unsigned_sign_doc_and_fee(
@@ -213,15 +261,19 @@ impl WasmGrpcProvider {
             TryInto::<u32>::try_into(timeout_height)
                 .map_err(ChainCommunicationError::from_other)?,
         );
-        let signer_info = SignerInfo::single_direct(Some(signer.public_key), account_info.sequence);
+        let signer_info = SignerInfo {
+            public_key: Some(self.signer_public_key(signer)),
+            mode_info: ModeInfo::single(SignMode::Direct),
+            sequence: account_info.sequence,
+        };
 
         let amount: u128 = (FixedPointNumber::from(gas_limit) * self.gas_price())
             .ceil_to_integer()
             .try_into()?;
         let fee_coin = Coin::new(
-            // The fee to pay is the gas limit * the gas price
+            // The fee to pay is the gas limit * the gas price, in the configured fee denom
             amount,
-            self.conf.get_canonical_asset().as_str(),
+            self.gas_price.denom.as_str(),
         )
         .map_err(Into::<HyperlaneCosmosError>::into)?;
         let auth_info =
@@ -258,19 +310,25 @@ impl WasmGrpcProvider {
             .await?;
 
         let signer = self.get_signer()?;
-        let tx_signed = sign_doc
-            .sign(&signer.signing_key()?)
+        let sign_doc_bytes = sign_doc
+            .clone()
+            .into_bytes()
             .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let tx_signed = TxRaw {
+            body_bytes: sign_doc.body_bytes,
+            auth_info_bytes: sign_doc.auth_info_bytes,
+            signatures: vec![signer.sign(&sign_doc_bytes)?],
+        };
         Ok((
             tx_signed
                 .to_bytes()
-                .map_err(Into::<HyperlaneCosmosError>::into)?,
+                .map_err(ChainCommunicationError::from_other)?,
             fee,
         ))
     }
 
     /// Estimates gas for a transaction containing `msgs`.
-    async fn estimate_gas(&self, msgs: Vec<cosmrs::Any>) -> ChainResult<u64> {
+    pub async fn estimate_gas(&self, msgs: Vec<cosmrs::Any>) -> ChainResult<u64> {
         // Get a sign doc with 0 gas, because we plan to simulate
         let (sign_doc, _) = self.generate_unsigned_sign_doc_and_fee(msgs, 0).await?;
 
@@ -318,6 +376,63 @@ impl WasmGrpcProvider {
         Ok(gas_estimate)
     }
 
+    /// Signs and broadcasts a transaction containing `msgs`. The gas limit is
+    /// estimated if none is given.
+    #[instrument(skip(self, msgs))]
+    pub async fn send(&self, msgs: Vec<Any>, gas_limit: Option<U256>) -> ChainResult<TxResponse> {
+        let signer = self.get_signer()?;
+        let gas_limit: Option<u64> = gas_limit.and_then(|limit| match limit.try_into() {
+            Ok(limit) => Some(limit),
+            Err(err) => {
+                tracing::warn!(
+                    ?err,
+                    "failed to convert gas_limit to u64, falling back to estimation"
+                );
+                None
+            }
+        });
+        let (tx_bytes, fee) = self.generate_raw_signed_tx_and_fee(msgs, gas_limit).await?;
+
+        // Check if the signer has enough funds to pay for the fee so we can get
+        // a more informative error.
+        let signer_balance = self
+            .get_balance(signer.address.clone(), fee.denom.to_string())
+            .await?;
+        let fee_amount: U256 = fee.amount.into();
+        if signer_balance < fee_amount {
+            return Err(ChainCommunicationError::InsufficientFunds {
+                required: fee_amount,
+                available: signer_balance,
+            });
+        }
+
+        let tx_res = self
+            .provider
+            .call(move |provider| {
+                let tx_bytes = tx_bytes.clone();
+                let future = async move {
+                    let mut client = TxServiceClient::new(provider.channel.clone());
+                    // We often use U256s to represent gas limits, but Cosmos expects u64s. Try to convert,
+                    // and if it fails, just fallback to None which will result in gas estimation.
+                    let tx_req = BroadcastTxRequest {
+                        tx_bytes,
+                        mode: BroadcastMode::Sync as i32,
+                    };
+                    client
+                        .broadcast_tx(tx_req)
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?
+                        .into_inner()
+                        .tx_response
+                        .ok_or_else(|| ChainCommunicationError::from_other_str("Empty tx_response"))
+                };
+                Box::pin(future)
+            })
+            .await?;
+        debug!(tx_result=?tx_res, domain=?self.domain, "Transaction sent");
+        Ok(tx_res)
+    }
+
     /// Fetches balance for a given `address` and `denom`
     pub async fn get_balance(&self, address: String, denom: String) -> ChainResult<U256> {
         let response = self
@@ -349,10 +464,10 @@ impl WasmGrpcProvider {
 
     /// Queries an account.
     pub async fn account_query(&self, account: String) -> ChainResult<BaseAccount> {
-        // Injective is a special case where their account query requires
-        // the use of different protobuf types.
-        if self.domain.is_injective() {
-            return self.account_query_injective(account).await;
+        // Injective and other Ethermint-based chains are a special case where
+        // their account query requires the use of different protobuf types.
+        if self.domain.is_injective() || self.uses_ethermint_accounts() {
+            return self.account_query_ethermint(account).await;
         }
 
         let response = self
@@ -384,8 +499,8 @@ impl WasmGrpcProvider {
         Ok(account)
     }
 
-    /// Injective-specific logic for querying an account.
-    async fn account_query_injective(&self, account: String) -> ChainResult<BaseAccount> {
+    /// Logic for querying an Ethermint `EthAccount`, e.g. on Injective.
+    async fn account_query_ethermint(&self, account: String) -> ChainResult<BaseAccount> {
         let response = self
             .provider
             .call(move |provider| {
@@ -450,6 +565,59 @@ impl WasmGrpcProvider {
             sequence: base_account.sequence,
         })
     }
+
+    /// Queries a gRPC service that has no generated client, e.g. the query
+    /// service of a native Hyperlane module. `path` is the full method path,
+    /// e.g. `/hyperlane.core.v1.Query/Mailbox`.
+    pub async fn module_query<Req, Res>(
+        &self,
+        path: &'static str,
+        request: Req,
+        block_height: Option<u64>,
+    ) -> ChainResult<Res>
+    where
+        Req: Message + Clone + Send + Sync + 'static,
+        Res: Message + Default + Send + 'static,
+    {
+        let (service, method) = path
+            .trim_start_matches('/')
+            .split_once('/')
+            .ok_or_else(|| ChainCommunicationError::from_other_str("invalid gRPC method path"))?;
+        let response = self
+            .provider
+            .call(move |provider| {
+                let request = request.clone();
+                let future = async move {
+                    let mut grpc_client = tonic::client::Grpc::new(provider.channel.clone());
+                    grpc_client
+                        .ready()
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?;
+
+                    let mut req = tonic::Request::new(request);
+                    req.extensions_mut()
+                        .insert(GrpcMethod::new(service, method));
+                    if let Some(block_height) = block_height {
+                        req.metadata_mut()
+                            .insert("x-cosmos-block-height", block_height.into());
+                    }
+                    let response: tonic::Response<Res> = grpc_client
+                        .unary(
+                            req,
+                            http::uri::PathAndQuery::from_static(path),
+                            tonic::codec::ProstCodec::default(),
+                        )
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?;
+
+                    Ok(response.into_inner())
+                };
+                Box::pin(future)
+            })
+            .await?;
+
+        Ok(response)
+    }
 }
 
 #[async_trait]
@@ -551,54 +719,7 @@ impl WasmProvider for WasmGrpcProvider {
         }
         .to_any()
         .map_err(ChainCommunicationError::from_other)?];
-        let gas_limit: Option<u64> = gas_limit.and_then(|limit| match limit.try_into() {
-            Ok(limit) => Some(limit),
-            Err(err) => {
-                tracing::warn!(
-                    ?err,
-                    "failed to convert gas_limit to u64, falling back to estimation"
-                );
-                None
-            }
-        });
-        let (tx_bytes, fee) = self.generate_raw_signed_tx_and_fee(msgs, gas_limit).await?;
-
-        // Check if the signer has enough funds to pay for the fee so we can get
-        // a more informative error.
-        let signer_balance = self
-            .get_balance(signer.address.clone(), fee.denom.to_string())
-            .await?;
-        let fee_amount: U256 = fee.amount.into();
-        if signer_balance < fee_amount {
-            return Err(ChainCommunicationError::InsufficientFunds {
-                required: fee_amount,
-                available: signer_balance,
-            });
-        }
-
-        let tx_res = self
-            .provider
-            .call(move |provider| {
-                let tx_bytes = tx_bytes.clone();
-                let future = async move {
-                    let mut client = TxServiceClient::new(provider.channel.clone());
-                    // We often use U256s to represent gas limits, but Cosmos expects u64s. Try to convert,
-                    // and if it fails, just fallback to None which will result in gas estimation.
-                    let tx_req = BroadcastTxRequest {
-                        tx_bytes,
-                        mode: BroadcastMode::Sync as i32,
-                    };
-                    client
-                        .broadcast_tx(tx_req)
-                        .await
-                        .map_err(Into::<HyperlaneCosmosError>::into)?
-                        .into_inner()
-                        .tx_response
-                        .ok_or_else(|| ChainCommunicationError::from_other_str("Empty tx_response"))
-                };
-                Box::pin(future)
-            })
-            .await?;
+        let tx_res = self.send(msgs, gas_limit).await?;
         debug!(tx_result=?tx_res, domain=?self.domain, ?payload, "Wasm transaction sent");
         Ok(tx_res)
     }
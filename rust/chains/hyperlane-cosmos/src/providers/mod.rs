@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use hyperlane_core::{
-    BlockInfo, ChainInfo, ChainResult, ContractLocator, HyperlaneChain, HyperlaneDomain,
-    HyperlaneProvider, TxnInfo, H256, U256,
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneDomain, HyperlaneProvider, NativeDenom, TxnInfo, H256, U256,
 };
 use tendermint_rpc::{client::CompatMode, HttpClient};
 
@@ -14,6 +14,17 @@ pub mod grpc;
 /// cosmos rpc provider
 pub mod rpc;
 
+/// The decimals of `denom`, going by the SI prefix of its name, e.g. 6 for
+/// `untrn` and 18 for `aevmos`
+fn denom_decimals(denom: &str) -> Option<u8> {
+    match denom.chars().next()? {
+        'u' => Some(6),
+        'n' => Some(9),
+        'a' => Some(18),
+        _ => None,
+    }
+}
+
 /// Abstraction over a connection to a Cosmos chain
 #[derive(Debug, Clone)]
 pub struct CosmosProvider {
@@ -31,7 +42,10 @@ impl CosmosProvider {
         locator: Option<ContractLocator>,
         signer: Option<Signer>,
     ) -> ChainResult<Self> {
-        let gas_price = CosmosAmount::try_from(conf.get_minimum_gas_price().clone())?;
+        let gas_price = CosmosAmount::try_from(
+            conf.get_fee_gas_price()
+                .map_err(ChainCommunicationError::from_other)?,
+        )?;
         let grpc_client = WasmGrpcProvider::new(
             domain.clone(),
             conf.clone(),
@@ -100,7 +114,31 @@ impl HyperlaneProvider for CosmosProvider {
             .await?)
     }
 
+    fn native_denom(&self) -> NativeDenom {
+        let default = NativeDenom::for_protocol(self.domain.domain_protocol());
+        NativeDenom {
+            decimals: denom_decimals(&self.canonical_asset).unwrap_or(default.decimals),
+            denom: Some(self.canonical_asset.clone()),
+        }
+    }
+
     async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_denom_decimals() {
+        assert_eq!(denom_decimals("untrn"), Some(6));
+        assert_eq!(denom_decimals("aevmos"), Some(18));
+        assert_eq!(
+            denom_decimals("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"),
+            None
+        );
+        assert_eq!(denom_decimals(""), None);
+    }
+}
@@ -1,19 +1,27 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
 use async_trait::async_trait;
 use cosmrs::rpc::client::Client;
+use futures::{stream, StreamExt, TryStreamExt};
 use hyperlane_core::rpc_clients::call_with_retry;
 use hyperlane_core::{ChainCommunicationError, ChainResult, ContractLocator, LogMeta, H256, U256};
 use sha256::digest;
-use std::fmt::Debug;
 use tendermint::abci::{Event, EventAttribute};
 use tendermint::hash::Algorithm;
 use tendermint::Hash;
 use tendermint_rpc::endpoint::block::Response as BlockResponse;
 use tendermint_rpc::endpoint::block_results::Response as BlockResultsResponse;
-use tendermint_rpc::HttpClient;
-use tracing::{debug, instrument, trace};
+use tendermint_rpc::endpoint::tx_search::Response as TxSearchResponse;
+use tendermint_rpc::query::Query;
+use tendermint_rpc::{HttpClient, Order};
+use tracing::{debug, instrument, trace, warn};
 
 use crate::address::CosmosAddress;
-use crate::{ConnectionConf, CosmosProvider, HyperlaneCosmosError};
+use crate::native;
+use crate::utils::CONTRACT_ADDRESS_ATTRIBUTE_KEY;
+use crate::{ConnectionConf, CosmosModuleType, CosmosProvider, HyperlaneCosmosError};
 
 #[async_trait]
 /// Trait for wasm indexer. Use rpc provider
@@ -30,6 +38,17 @@ pub trait WasmIndexer: Send + Sync {
     ) -> ChainResult<Vec<(T, LogMeta)>>
     where
         T: Send + Sync + PartialEq + Debug + 'static;
+
+    /// Get logs for all blocks in the given range using the given parser,
+    /// ordered by (block number, transaction index, log index).
+    async fn get_logs_in_range<T>(
+        &self,
+        range: RangeInclusive<u32>,
+        parser: for<'a> fn(&'a Vec<EventAttribute>) -> ChainResult<ParsedEvent<T>>,
+        cursor_label: &'static str,
+    ) -> ChainResult<Vec<(T, LogMeta)>>
+    where
+        T: Send + Sync + PartialEq + Debug + 'static;
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -60,11 +79,22 @@ pub struct CosmosWasmIndexer {
     provider: CosmosProvider,
     contract_address: CosmosAddress,
     target_event_kind: String,
+    /// The event attribute identifying the emitter of an event and its value
+    /// for the indexed contract.
+    address_attribute: (String, String),
     reorg_period: u32,
 }
 
 impl CosmosWasmIndexer {
     const WASM_TYPE: &str = "wasm";
+    /// The maximum page size accepted by the `tx_search` RPC endpoint.
+    const TX_SEARCH_PAGE_SIZE: u8 = 100;
+    /// The maximum number of `tx_search` pages fetched for a single range. Ranges
+    /// with more matching txs are indexed block by block instead, so that a busy
+    /// range neither hammers the RPC nor gets truncated.
+    const TX_SEARCH_MAX_PAGES: u32 = 20;
+    /// The maximum number of concurrent block requests.
+    const MAX_CONCURRENT_BLOCK_REQUESTS: usize = 20;
 
     /// create new Cosmwasm RPC Provider
     pub fn new(
@@ -72,6 +102,44 @@ impl CosmosWasmIndexer {
         locator: ContractLocator,
         event_type: String,
         reorg_period: u32,
+    ) -> ChainResult<Self> {
+        if conf.get_module_type() != CosmosModuleType::Wasm {
+            return Err(ChainCommunicationError::from_other_str(
+                "CosmWasm events can only be indexed on chains using CosmWasm contracts",
+            ));
+        }
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            Some(locator.clone()),
+            None,
+        )?;
+        let contract_address = CosmosAddress::from_h256(
+            locator.address,
+            conf.get_bech32_prefix().as_str(),
+            conf.get_contract_address_bytes(),
+        )?;
+        Ok(Self {
+            provider,
+            address_attribute: (
+                CONTRACT_ADDRESS_ATTRIBUTE_KEY.to_owned(),
+                contract_address.address(),
+            ),
+            contract_address,
+            target_event_kind: format!("{}-{}", Self::WASM_TYPE, event_type),
+            reorg_period,
+        })
+    }
+
+    /// Create an indexer for the typed `event_type` events of a native
+    /// Hyperlane module object, which is identified by the JSON encoded id in
+    /// the event's `address_attribute_key` attribute.
+    pub fn new_native(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        event_type: &str,
+        address_attribute_key: &str,
+        reorg_period: u32,
     ) -> ChainResult<Self> {
         let provider = CosmosProvider::new(
             locator.domain.clone(),
@@ -86,7 +154,11 @@ impl CosmosWasmIndexer {
                 conf.get_bech32_prefix().as_str(),
                 conf.get_contract_address_bytes(),
             )?,
-            target_event_kind: format!("{}-{}", Self::WASM_TYPE, event_type),
+            target_event_kind: event_type.to_owned(),
+            address_attribute: (
+                address_attribute_key.to_owned(),
+                serde_json::to_string(&native::format_id(locator.address))?,
+            ),
             reorg_period,
         })
     }
@@ -108,6 +180,23 @@ impl CosmosWasmIndexer {
             .map_err(Into::<HyperlaneCosmosError>::into)?)
     }
 
+    async fn tx_search(
+        client: HttpClient,
+        query: Query,
+        page: u32,
+    ) -> ChainResult<TxSearchResponse> {
+        Ok(client
+            .tx_search(
+                query,
+                false,
+                page,
+                Self::TX_SEARCH_PAGE_SIZE,
+                Order::Ascending,
+            )
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?)
+    }
+
     async fn get_latest_block(client: HttpClient) -> ChainResult<BlockResponse> {
         Ok(client
             .latest_block()
@@ -158,7 +247,14 @@ impl CosmosWasmIndexer {
                     debug!(?tx_hash, "Not indexing failed transaction");
                     return None;
                 }
-                Some(self.handle_tx(block.clone(), tx.events, *tx_hash, idx, parser))
+                Some(self.handle_tx(
+                    block.block.header.height.into(),
+                    H256::from_slice(block.block_id.hash.as_bytes()),
+                    tx.events,
+                    *tx_hash,
+                    idx,
+                    parser,
+                ))
             })
             .flatten()
             .collect()
@@ -168,7 +264,8 @@ impl CosmosWasmIndexer {
     // made by the contract we are indexing.
     fn handle_tx<T>(
         &self,
-        block: BlockResponse,
+        block_number: u64,
+        block_hash: H256,
         tx_events: Vec<Event>,
         tx_hash: H256,
         transaction_index: usize,
@@ -194,15 +291,15 @@ impl CosmosWasmIndexer {
                     // in the event matches the contract address we are indexing.
                     // Otherwise, we might index events from other contracts that happen
                     // to have the same target event name.
-                    if parsed_event.contract_address != self.contract_address.address() {
+                    if parsed_event.contract_address != self.address_attribute.1 {
                         trace!(tx_hash=?tx_hash, log_idx, ?event, "Event contract address does not match indexer contract address");
                         return None;
                     }
 
                     Some((parsed_event.event, LogMeta {
                         address: self.contract_address.digest(),
-                        block_number: block.block.header.height.into(),
-                        block_hash: H256::from_slice(block.block_id.hash.as_bytes()),
+                        block_number,
+                        block_hash,
                         transaction_id: H256::from_slice(tx_hash.as_bytes()).into(),
                         transaction_index: transaction_index as u64,
                         log_index: U256::from(log_idx),
@@ -212,6 +309,89 @@ impl CosmosWasmIndexer {
     }
 }
 
+impl CosmosWasmIndexer {
+    /// Find the txs in `range` that emitted a target event of the indexed contract,
+    /// paging through `tx_search`. Returns `None` if there are more matching txs
+    /// than `TX_SEARCH_MAX_PAGES` pages can hold, and an error if the node can't
+    /// search txs at all, e.g. because its tx indexer is disabled.
+    async fn search_txs(
+        &self,
+        range: &RangeInclusive<u32>,
+    ) -> ChainResult<Option<Vec<tendermint_rpc::endpoint::tx::Response>>> {
+        let client = self.provider.rpc().clone();
+        let query = Query::gte("tx.height", u64::from(*range.start()))
+            .and_lte("tx.height", u64::from(*range.end()))
+            .and_eq(
+                format!("{}.{}", self.target_event_kind, self.address_attribute.0),
+                self.address_attribute.1.clone(),
+            );
+
+        let mut txs = vec![];
+        let mut page = 1;
+        loop {
+            let response =
+                call_with_retry(|| Box::pin(Self::tx_search(client.clone(), query.clone(), page)))
+                    .await?;
+            let total_count = response.total_count as usize;
+            let max_count = Self::TX_SEARCH_MAX_PAGES as usize * Self::TX_SEARCH_PAGE_SIZE as usize;
+            if total_count > max_count {
+                return Ok(None);
+            }
+            let page_len = response.txs.len();
+            txs.extend(response.txs);
+            if txs.len() >= total_count || page_len == 0 {
+                // Never silently drop txs, e.g. if the node prunes results mid-pagination
+                if txs.len() != total_count {
+                    return Err(ChainCommunicationError::from_other_str(
+                        "tx_search returned a different number of txs than it reported",
+                    ));
+                }
+                return Ok(Some(txs));
+            }
+            page += 1;
+        }
+    }
+
+    async fn get_block_hashes(&self, heights: BTreeSet<u64>) -> ChainResult<HashMap<u64, H256>> {
+        let client = self.provider.rpc().clone();
+        stream::iter(heights)
+            .map(|height| {
+                let client = client.clone();
+                async move {
+                    let block_number =
+                        u32::try_from(height).map_err(ChainCommunicationError::from_other)?;
+                    let block =
+                        call_with_retry(|| Box::pin(Self::get_block(client.clone(), block_number)))
+                            .await?;
+                    Ok::<_, ChainCommunicationError>((
+                        height,
+                        H256::from_slice(block.block_id.hash.as_bytes()),
+                    ))
+                }
+            })
+            .buffer_unordered(Self::MAX_CONCURRENT_BLOCK_REQUESTS)
+            .try_collect()
+            .await
+    }
+
+    async fn get_logs_by_block<T>(
+        &self,
+        range: RangeInclusive<u32>,
+        parser: for<'a> fn(&'a Vec<EventAttribute>) -> ChainResult<ParsedEvent<T>>,
+        cursor_label: &'static str,
+    ) -> ChainResult<Vec<(T, LogMeta)>>
+    where
+        T: Send + Sync + PartialEq + Debug + 'static,
+    {
+        let logs: Vec<Vec<(T, LogMeta)>> = stream::iter(range)
+            .map(|block_number| self.get_logs_in_block(block_number, parser, cursor_label))
+            .buffered(Self::MAX_CONCURRENT_BLOCK_REQUESTS)
+            .try_collect()
+            .await?;
+        Ok(logs.into_iter().flatten().collect())
+    }
+}
+
 #[async_trait]
 impl WasmIndexer for CosmosWasmIndexer {
     #[instrument(err, skip(self))]
@@ -249,4 +429,69 @@ impl WasmIndexer for CosmosWasmIndexer {
 
         Ok(self.handle_txs(block?, block_results?, parser, cursor_label))
     }
+
+    #[instrument(err, skip(self, parser))]
+    async fn get_logs_in_range<T>(
+        &self,
+        range: RangeInclusive<u32>,
+        parser: for<'a> fn(&'a Vec<EventAttribute>) -> ChainResult<ParsedEvent<T>>,
+        cursor_label: &'static str,
+    ) -> ChainResult<Vec<(T, LogMeta)>>
+    where
+        T: Send + Sync + PartialEq + Debug + 'static,
+    {
+        let txs = match self.search_txs(&range).await {
+            Ok(Some(txs)) => txs,
+            Ok(None) => {
+                warn!(
+                    ?range,
+                    cursor_label, "Too many matching txs to page through, indexing block by block"
+                );
+                return self.get_logs_by_block(range, parser, cursor_label).await;
+            }
+            // E.g. nodes with the tx indexer disabled reject `tx_search` requests
+            Err(err) => {
+                warn!(
+                    ?range,
+                    cursor_label,
+                    ?err,
+                    "Failed to search for matching txs, indexing block by block"
+                );
+                return self.get_logs_by_block(range, parser, cursor_label).await;
+            }
+        };
+
+        let successful_txs: Vec<_> = txs
+            .into_iter()
+            .filter(|tx| {
+                if tx.tx_result.code.is_err() {
+                    debug!(tx_hash=?tx.hash, "Not indexing failed transaction");
+                    return false;
+                }
+                true
+            })
+            .collect();
+        let block_hashes = self
+            .get_block_hashes(successful_txs.iter().map(|tx| tx.height.value()).collect())
+            .await?;
+
+        let mut logs = successful_txs
+            .into_iter()
+            .flat_map(|tx| {
+                let block_number = tx.height.value();
+                self.handle_tx(
+                    block_number,
+                    block_hashes[&block_number],
+                    tx.tx_result.events,
+                    H256::from_slice(tx.hash.as_bytes()),
+                    tx.index as usize,
+                    parser,
+                )
+                .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        // `tx_search` orders by height and tx index already, but don't rely on the node for it
+        logs.sort_by_key(|(_, meta)| (meta.block_number, meta.transaction_index, meta.log_index));
+        Ok(logs)
+    }
 }
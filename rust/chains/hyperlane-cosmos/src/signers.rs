@@ -1,7 +1,12 @@
 use cosmrs::crypto::{secp256k1::SigningKey, PublicKey};
 use hyperlane_core::ChainResult;
+use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature};
+use sha3::{Digest, Keccak256};
 
-use crate::{address::CosmosAddress, HyperlaneCosmosError};
+use crate::{
+    address::{AccountAddressType, CosmosAddress},
+    HyperlaneCosmosError,
+};
 
 #[derive(Clone, Debug)]
 /// Signer for cosmos chain
@@ -13,6 +18,8 @@ pub struct Signer {
     pub address: String,
     /// address prefix
     pub prefix: String,
+    /// The scheme used to derive the address and sign transactions
+    pub account_address_type: AccountAddressType,
     private_key: Vec<u8>,
 }
 
@@ -22,8 +29,14 @@ impl Signer {
     /// # Arguments
     /// * `private_key` - private key for signer
     /// * `prefix` - prefix for signer address
-    pub fn new(private_key: Vec<u8>, prefix: String) -> ChainResult<Self> {
-        let address = CosmosAddress::from_privkey(&private_key, &prefix)?.address();
+    /// * `account_address_type` - the scheme used to derive the signer address
+    pub fn new(
+        private_key: Vec<u8>,
+        prefix: String,
+        account_address_type: AccountAddressType,
+    ) -> ChainResult<Self> {
+        let address =
+            CosmosAddress::from_privkey(&private_key, &prefix, account_address_type)?.address();
         let signing_key = Self::build_signing_key(&private_key)?;
         let public_key = signing_key.public_key();
         Ok(Self {
@@ -31,6 +44,7 @@ impl Signer {
             private_key,
             address,
             prefix,
+            account_address_type,
         })
     }
 
@@ -40,6 +54,30 @@ impl Signer {
         Self::build_signing_key(&self.private_key)
     }
 
+    /// Signs the provided sign doc bytes, returning the signature in the
+    /// `r || s` form expected by the chain.
+    ///
+    /// Cosmos-sdk chains sign the SHA256 digest of the bytes, while Ethermint
+    /// chains (`eth_secp256k1` keys) sign the Keccak256 digest.
+    pub fn sign(&self, sign_doc_bytes: &[u8]) -> ChainResult<Vec<u8>> {
+        match self.account_address_type {
+            AccountAddressType::Bitcoin => Ok(self
+                .signing_key()?
+                .sign(sign_doc_bytes)
+                .map_err(Into::<HyperlaneCosmosError>::into)?
+                .to_vec()),
+            AccountAddressType::Ethereum => {
+                let signing_key = k256::ecdsa::SigningKey::from_slice(&self.private_key)
+                    .map_err(Into::<HyperlaneCosmosError>::into)?;
+                let digest = Keccak256::digest(sign_doc_bytes);
+                let signature: Signature = signing_key
+                    .sign_prehash(digest.as_slice())
+                    .map_err(Into::<HyperlaneCosmosError>::into)?;
+                Ok(signature.to_vec())
+            }
+        }
+    }
+
     fn build_signing_key(private_key: &Vec<u8>) -> ChainResult<SigningKey> {
         Ok(SigningKey::from_slice(private_key.as_slice())
             .map_err(Into::<HyperlaneCosmosError>::into)?)
@@ -21,12 +21,45 @@ pub struct ConnectionConf {
     /// minimum price set by the validator.
     /// More details here: https://docs.cosmos.network/main/learn/beginner/gas-fees#antehandler
     gas_price: RawCosmosAmount,
+    /// Gas prices for denoms other than the one in `gas_price`, e.g. IBC vouchers on chains
+    /// that allow paying fees in alternative denoms through a fee market or fee abstraction.
+    additional_gas_prices: Vec<RawCosmosAmount>,
+    /// The denom transaction fees are paid in. Defaults to the canonical asset.
+    fee_denom: Option<String>,
     /// The number of bytes used to represent a contract address.
     /// Cosmos address lengths are sometimes less than 32 bytes, so this helps to serialize it in
     /// bech32 with the appropriate length.
     contract_address_bytes: usize,
     /// Operation batching configuration
     pub operation_batch: OperationBatchConfig,
+    /// Whether Hyperlane is deployed as CosmWasm contracts or as a native module
+    module_type: CosmosModuleType,
+}
+
+/// How Hyperlane is deployed on a Cosmos chain.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CosmosModuleType {
+    /// Hyperlane contracts are CosmWasm contracts, queried and executed
+    /// through the `cosmwasm.wasm.v1` services. Contract addresses are bech32.
+    #[default]
+    Wasm,
+    /// Hyperlane is a native Cosmos SDK module with its own query and msg
+    /// services. Contract addresses are the 32 byte ids of the module's objects.
+    Native,
+}
+
+impl FromStr for CosmosModuleType {
+    type Err = ChainCommunicationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "wasm" | "cosmwasm" => Ok(Self::Wasm),
+            "native" => Ok(Self::Native),
+            _ => Err(ChainCommunicationError::ParseError {
+                msg: format!("Unknown cosmos module type `{s}`"),
+            }),
+        }
+    }
 }
 
 /// Untyped cosmos amount
@@ -75,6 +108,9 @@ pub enum ConnectionConfError {
     /// Invalid `url` for connection configuration
     #[error("Invalid `url` for connection configuration: `{0}` ({1})")]
     InvalidConnectionUrl(String, url::ParseError),
+    /// No gas price is configured for the denom fees are paid in
+    #[error("No gas price configured for fee denom `{0}`")]
+    MissingFeeDenomGasPrice(String),
 }
 
 impl ConnectionConf {
@@ -108,11 +144,54 @@ impl ConnectionConf {
         self.gas_price.clone()
     }
 
+    /// Get the gas prices configured for denoms other than the minimum gas price's
+    pub fn get_additional_gas_prices(&self) -> Vec<RawCosmosAmount> {
+        self.additional_gas_prices.clone()
+    }
+
+    /// Get the gas price in the denom transaction fees are paid in.
+    ///
+    /// Without an explicit fee denom, fees are paid in the canonical asset at the
+    /// minimum gas price. Otherwise the fee denom must have a configured gas price,
+    /// unless it is the canonical asset.
+    pub fn get_fee_gas_price(&self) -> Result<RawCosmosAmount, ConnectionConfError> {
+        let fee_denom = self.fee_denom.as_ref().unwrap_or(&self.canonical_asset);
+        self.gas_price_in(fee_denom)
+            .ok_or_else(|| ConnectionConfError::MissingFeeDenomGasPrice(fee_denom.clone()))
+    }
+
+    /// Get the gas price in the canonical asset.
+    ///
+    /// Cost estimates are priced in the canonical asset even if fees are paid in
+    /// another denom, since that's what gas payments, profitability and the gas
+    /// payment enforcement policies are denominated in. The configured gas prices
+    /// of both denoms are taken to be worth the same.
+    pub fn get_canonical_gas_price(&self) -> RawCosmosAmount {
+        self.gas_price_in(&self.canonical_asset)
+            .expect("the canonical asset always has a gas price")
+    }
+
+    fn gas_price_in(&self, denom: &str) -> Option<RawCosmosAmount> {
+        std::iter::once(&self.gas_price)
+            .chain(self.additional_gas_prices.iter())
+            .find(|price| price.denom == denom)
+            .cloned()
+            .or_else(|| {
+                (denom == self.canonical_asset)
+                    .then(|| RawCosmosAmount::new(denom.to_owned(), self.gas_price.amount.clone()))
+            })
+    }
+
     /// Get the number of bytes used to represent a contract address
     pub fn get_contract_address_bytes(&self) -> usize {
         self.contract_address_bytes
     }
 
+    /// Get whether Hyperlane is deployed as CosmWasm contracts or as a native module
+    pub fn get_module_type(&self) -> CosmosModuleType {
+        self.module_type
+    }
+
     /// Create a new connection configuration
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -122,8 +201,11 @@ impl ConnectionConf {
         bech32_prefix: String,
         canonical_asset: String,
         minimum_gas_price: RawCosmosAmount,
+        additional_gas_prices: Vec<RawCosmosAmount>,
+        fee_denom: Option<String>,
         contract_address_bytes: usize,
         operation_batch: OperationBatchConfig,
+        module_type: CosmosModuleType,
     ) -> Self {
         Self {
             grpc_urls,
@@ -132,8 +214,94 @@ impl ConnectionConf {
             bech32_prefix,
             canonical_asset,
             gas_price: minimum_gas_price,
+            additional_gas_prices,
+            fee_denom,
             contract_address_bytes,
             operation_batch,
+            module_type,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conf(
+        additional_gas_prices: Vec<RawCosmosAmount>,
+        fee_denom: Option<&str>,
+    ) -> ConnectionConf {
+        ConnectionConf::new(
+            vec![],
+            "http://localhost:26657".to_owned(),
+            "neutron-1".to_owned(),
+            "neutron".to_owned(),
+            "untrn".to_owned(),
+            RawCosmosAmount::new("untrn".to_owned(), "0.0053".to_owned()),
+            additional_gas_prices,
+            fee_denom.map(str::to_owned),
+            32,
+            OperationBatchConfig::default(),
+            CosmosModuleType::default(),
+        )
+    }
+
+    #[test]
+    fn test_fee_gas_price_defaults_to_canonical_asset() {
+        let price = conf(vec![], None).get_fee_gas_price().unwrap();
+        assert_eq!(price.denom, "untrn");
+        assert_eq!(price.amount, "0.0053");
+    }
+
+    #[test]
+    fn test_fee_gas_price_uses_additional_denom() {
+        let ibc_denom = "ibc/B559A80D62249C8AA07A380E2A2BEA6E5CA9A6F079C912C3A9E9B494105E4F81";
+        let price = conf(
+            vec![RawCosmosAmount::new(
+                ibc_denom.to_owned(),
+                "0.0025".to_owned(),
+            )],
+            Some(ibc_denom),
+        )
+        .get_fee_gas_price()
+        .unwrap();
+        assert_eq!(price.denom, ibc_denom);
+        assert_eq!(price.amount, "0.0025");
+    }
+
+    #[test]
+    fn test_canonical_gas_price_ignores_fee_denom() {
+        let ibc_denom = "ibc/B559A80D62249C8AA07A380E2A2BEA6E5CA9A6F079C912C3A9E9B494105E4F81";
+        let price = conf(
+            vec![RawCosmosAmount::new(
+                ibc_denom.to_owned(),
+                "0.0025".to_owned(),
+            )],
+            Some(ibc_denom),
+        )
+        .get_canonical_gas_price();
+        assert_eq!(price.denom, "untrn");
+        assert_eq!(price.amount, "0.0053");
+    }
+
+    #[test]
+    fn test_fee_gas_price_missing_denom() {
+        assert!(matches!(
+            conf(vec![], Some("uatom")).get_fee_gas_price(),
+            Err(ConnectionConfError::MissingFeeDenomGasPrice(denom)) if denom == "uatom"
+        ));
+    }
+
+    #[test]
+    fn test_parse_module_type() {
+        assert_eq!(
+            "native".parse::<CosmosModuleType>().unwrap(),
+            CosmosModuleType::Native
+        );
+        assert_eq!(
+            "CosmWasm".parse::<CosmosModuleType>().unwrap(),
+            CosmosModuleType::Wasm
+        );
+        assert!("evm".parse::<CosmosModuleType>().is_err());
+    }
+}
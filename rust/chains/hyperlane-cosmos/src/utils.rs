@@ -1,7 +1,7 @@
 use std::num::NonZeroU64;
 
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-use hyperlane_core::ChainResult;
+use hyperlane_core::{ChainCommunicationError, ChainResult};
 use once_cell::sync::Lazy;
 
 use crate::grpc::{WasmGrpcProvider, WasmProvider};
@@ -31,6 +31,16 @@ pub(crate) async fn get_block_height_for_lag(
     Ok(block_height)
 }
 
+/// Whether `err` means the queried contract doesn't know the query, e.g.
+/// because it predates it. CosmWasm rejects unknown query variants when
+/// deserializing the query msg.
+pub(crate) fn is_unknown_query_error(err: &ChainCommunicationError) -> bool {
+    // Matched on the debug representation, which includes the nested errors
+    // of e.g. the fallback provider
+    let err = format!("{err:?}");
+    err.contains("unknown variant") || err.contains("method not found")
+}
+
 #[cfg(test)]
 /// Helper function to create a Vec<EventAttribute> from a JSON string -
 /// crate::payloads::general::EventAttribute has a Deserialize impl while
@@ -44,3 +54,20 @@ pub(crate) fn event_attributes_from_str(
         .map(|attr| attr.into())
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_unknown_query_error() {
+        let unknown = ChainCommunicationError::from_other(tonic::Status::invalid_argument(
+            "Error parsing into type hpl_interface::ism::multisig::QueryMsg: unknown variant `enrolled_validators`, expected `ism` or `multisig_ism`: query wasm contract failed",
+        ));
+        assert!(is_unknown_query_error(&unknown));
+
+        let unavailable =
+            ChainCommunicationError::from_other(tonic::Status::unavailable("connection refused"));
+        assert!(!is_unknown_query_error(&unavailable));
+    }
+}
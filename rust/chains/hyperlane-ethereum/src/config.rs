@@ -35,6 +35,11 @@ pub struct ConnectionConf {
     pub transaction_overrides: TransactionOverrides,
     /// Operation batching configuration
     pub operation_batch: OperationBatchConfig,
+    /// How the chain deviates from Ethereum
+    pub quirks: ChainQuirks,
+    /// Url of a light client RPC, such as Helios, to verify the responses of
+    /// the RPC connection against. If unset, they are trusted.
+    pub light_client_url: Option<Url>,
 }
 
 /// Ethereum transaction overrides.
@@ -51,3 +56,29 @@ pub struct TransactionOverrides {
     /// Max priority fee per gas to use for EIP-1559 transactions.
     pub max_priority_fee_per_gas: Option<U256>,
 }
+
+/// The capabilities and quirks of a chain that is almost, but not quite,
+/// equivalent to Ethereum, which toggle how transactions are built and how
+/// logs are indexed. The defaults match Ethereum.
+#[derive(Debug, Clone)]
+pub struct ChainQuirks {
+    /// Whether the chain supports EIP-1559 transactions.
+    /// If false, legacy transactions are always used.
+    pub supports_eip1559: bool,
+    /// The maximum number of topics `eth_getLogs` filters can have.
+    /// Logs of filters with more topics are filtered locally instead.
+    pub max_log_topics: Option<usize>,
+    /// The maximum number of blocks a single `eth_getLogs` call can span.
+    /// Larger ranges are split into several calls.
+    pub max_log_block_range: Option<u32>,
+}
+
+impl Default for ChainQuirks {
+    fn default() -> Self {
+        Self {
+            supports_eip1559: true,
+            max_log_topics: None,
+            max_log_block_range: None,
+        }
+    }
+}
@@ -0,0 +1,334 @@
+#![allow(clippy::enum_variant_names)]
+#![allow(missing_docs)]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::providers::Middleware;
+use num_traits::cast::FromPrimitive;
+use tracing::{instrument, warn};
+
+use hyperlane_core::{
+    ChainResult, ContractLocator, HookType, HyperlaneAbi, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, PausableHook, PostDispatchHook,
+    ProtocolFeeHook, RawHyperlaneMessage, H256, U256,
+};
+
+use crate::interfaces::i_post_dispatch_hook::{
+    IPostDispatchHook as EthereumPostDispatchHookInternal, IPOSTDISPATCHHOOK_ABI,
+};
+use crate::interfaces::pausable_hook::{
+    PausableHook as EthereumPausableHookInternal, PAUSABLEHOOK_ABI,
+};
+use crate::interfaces::protocol_fee::{
+    ProtocolFee as EthereumProtocolFeeHookInternal, PROTOCOLFEE_ABI,
+};
+use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+
+pub struct PostDispatchHookBuilder {}
+
+#[async_trait]
+impl BuildableWithProvider for PostDispatchHookBuilder {
+    type Output = Box<dyn PostDispatchHook>;
+
+    async fn build_with_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        _conn: &ConnectionConf,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        Box::new(EthereumPostDispatchHook::new(Arc::new(provider), locator))
+    }
+}
+
+/// A reference to a post-dispatch hook contract on some Ethereum chain
+#[derive(Debug)]
+pub struct EthereumPostDispatchHook<M>
+where
+    M: Middleware,
+{
+    contract: Arc<EthereumPostDispatchHookInternal<M>>,
+    domain: HyperlaneDomain,
+}
+
+impl<M> EthereumPostDispatchHook<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a reference to a post-dispatch hook at a specific Ethereum
+    /// address on some chain
+    pub fn new(provider: Arc<M>, locator: &ContractLocator) -> Self {
+        Self {
+            contract: Arc::new(EthereumPostDispatchHookInternal::new(
+                locator.address,
+                provider,
+            )),
+            domain: locator.domain.clone(),
+        }
+    }
+}
+
+impl<M> HyperlaneChain for EthereumPostDispatchHook<M>
+where
+    M: Middleware + 'static,
+{
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(EthereumProvider::new(
+            self.contract.client(),
+            self.domain.clone(),
+        ))
+    }
+}
+
+impl<M> HyperlaneContract for EthereumPostDispatchHook<M>
+where
+    M: Middleware + 'static,
+{
+    fn address(&self) -> H256 {
+        self.contract.address().into()
+    }
+}
+
+#[async_trait]
+impl<M> PostDispatchHook for EthereumPostDispatchHook<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(err)]
+    async fn hook_type(&self) -> ChainResult<HookType> {
+        let hook = self.contract.hook_type().call().await?;
+        if let Some(hook_type) = HookType::from_u8(hook) {
+            Ok(hook_type)
+        } else {
+            warn!(%hook, "Unknown hook type");
+            Ok(HookType::Unused)
+        }
+    }
+
+    #[instrument(err, skip(metadata))]
+    async fn supports_metadata(&self, metadata: &[u8]) -> ChainResult<bool> {
+        let supported = self
+            .contract
+            .supports_metadata(metadata.to_vec().into())
+            .call()
+            .await?;
+        Ok(supported)
+    }
+
+    #[instrument(err, skip(metadata))]
+    async fn quote_dispatch(
+        &self,
+        metadata: &[u8],
+        message: &HyperlaneMessage,
+    ) -> ChainResult<U256> {
+        let quote = self
+            .contract
+            .quote_dispatch(
+                metadata.to_vec().into(),
+                RawHyperlaneMessage::from(message).to_vec().into(),
+            )
+            .call()
+            .await?;
+        Ok(quote.into())
+    }
+}
+
+pub struct EthereumPostDispatchHookAbi;
+
+impl HyperlaneAbi for EthereumPostDispatchHookAbi {
+    const SELECTOR_SIZE_BYTES: usize = 4;
+
+    fn fn_map() -> HashMap<Vec<u8>, &'static str> {
+        crate::extract_fn_map(&IPOSTDISPATCHHOOK_ABI)
+    }
+}
+
+pub struct PausableHookBuilder {}
+
+#[async_trait]
+impl BuildableWithProvider for PausableHookBuilder {
+    type Output = Box<dyn PausableHook>;
+
+    async fn build_with_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        _conn: &ConnectionConf,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        Box::new(EthereumPausableHook::new(Arc::new(provider), locator))
+    }
+}
+
+/// A reference to a PausableHook contract on some Ethereum chain
+#[derive(Debug)]
+pub struct EthereumPausableHook<M>
+where
+    M: Middleware,
+{
+    contract: Arc<EthereumPausableHookInternal<M>>,
+    domain: HyperlaneDomain,
+}
+
+impl<M> EthereumPausableHook<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a reference to a PausableHook at a specific Ethereum address on
+    /// some chain
+    pub fn new(provider: Arc<M>, locator: &ContractLocator) -> Self {
+        Self {
+            contract: Arc::new(EthereumPausableHookInternal::new(locator.address, provider)),
+            domain: locator.domain.clone(),
+        }
+    }
+}
+
+impl<M> HyperlaneChain for EthereumPausableHook<M>
+where
+    M: Middleware + 'static,
+{
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(EthereumProvider::new(
+            self.contract.client(),
+            self.domain.clone(),
+        ))
+    }
+}
+
+impl<M> HyperlaneContract for EthereumPausableHook<M>
+where
+    M: Middleware + 'static,
+{
+    fn address(&self) -> H256 {
+        self.contract.address().into()
+    }
+}
+
+#[async_trait]
+impl<M> PausableHook for EthereumPausableHook<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(err)]
+    async fn paused(&self) -> ChainResult<bool> {
+        Ok(self.contract.paused().call().await?)
+    }
+}
+
+pub struct EthereumPausableHookAbi;
+
+impl HyperlaneAbi for EthereumPausableHookAbi {
+    const SELECTOR_SIZE_BYTES: usize = 4;
+
+    fn fn_map() -> HashMap<Vec<u8>, &'static str> {
+        crate::extract_fn_map(&PAUSABLEHOOK_ABI)
+    }
+}
+
+pub struct ProtocolFeeHookBuilder {}
+
+#[async_trait]
+impl BuildableWithProvider for ProtocolFeeHookBuilder {
+    type Output = Box<dyn ProtocolFeeHook>;
+
+    async fn build_with_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        _conn: &ConnectionConf,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        Box::new(EthereumProtocolFeeHook::new(Arc::new(provider), locator))
+    }
+}
+
+/// A reference to a ProtocolFee hook contract on some Ethereum chain
+#[derive(Debug)]
+pub struct EthereumProtocolFeeHook<M>
+where
+    M: Middleware,
+{
+    contract: Arc<EthereumProtocolFeeHookInternal<M>>,
+    domain: HyperlaneDomain,
+}
+
+impl<M> EthereumProtocolFeeHook<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a reference to a ProtocolFee hook at a specific Ethereum address
+    /// on some chain
+    pub fn new(provider: Arc<M>, locator: &ContractLocator) -> Self {
+        Self {
+            contract: Arc::new(EthereumProtocolFeeHookInternal::new(
+                locator.address,
+                provider,
+            )),
+            domain: locator.domain.clone(),
+        }
+    }
+}
+
+impl<M> HyperlaneChain for EthereumProtocolFeeHook<M>
+where
+    M: Middleware + 'static,
+{
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(EthereumProvider::new(
+            self.contract.client(),
+            self.domain.clone(),
+        ))
+    }
+}
+
+impl<M> HyperlaneContract for EthereumProtocolFeeHook<M>
+where
+    M: Middleware + 'static,
+{
+    fn address(&self) -> H256 {
+        self.contract.address().into()
+    }
+}
+
+#[async_trait]
+impl<M> ProtocolFeeHook for EthereumProtocolFeeHook<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(err)]
+    async fn protocol_fee(&self) -> ChainResult<U256> {
+        Ok(self.contract.protocol_fee().call().await?.into())
+    }
+
+    #[instrument(err)]
+    async fn max_protocol_fee(&self) -> ChainResult<U256> {
+        Ok(self.contract.max_protocol_fee().call().await?.into())
+    }
+
+    #[instrument(err)]
+    async fn beneficiary(&self) -> ChainResult<H256> {
+        Ok(self.contract.beneficiary().call().await?.into())
+    }
+}
+
+pub struct EthereumProtocolFeeHookAbi;
+
+impl HyperlaneAbi for EthereumProtocolFeeHookAbi {
+    const SELECTOR_SIZE_BYTES: usize = 4;
+
+    fn fn_map() -> HashMap<Vec<u8>, &'static str> {
+        crate::extract_fn_map(&PROTOCOLFEE_ABI)
+    }
+}
@@ -8,18 +8,20 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use ethers::prelude::Middleware;
 use hyperlane_core::{
-    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneAbi, HyperlaneChain,
+    ChainResult, ContractLocator, Finality, FinalityPolicy, HyperlaneAbi, HyperlaneChain,
     HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer,
     InterchainGasPaymaster, InterchainGasPayment, LogMeta, SequenceAwareIndexer, H160, H256, H512,
+    U256,
 };
 use tracing::instrument;
 
-use super::utils::fetch_raw_logs_and_log_meta;
+use super::utils::{fetch_raw_logs_and_log_meta, query_logs_in_range};
+use crate::interfaces::i_gas_oracle::IGasOracle;
 use crate::interfaces::i_interchain_gas_paymaster::{
     GasPaymentFilter, IInterchainGasPaymaster as EthereumInterchainGasPaymasterInternal,
     IINTERCHAINGASPAYMASTER_ABI,
 };
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+use crate::{BuildableWithProvider, ChainQuirks, ConnectionConf, EthereumProvider};
 
 impl<M> Display for EthereumInterchainGasPaymasterInternal<M>
 where
@@ -32,7 +34,7 @@ where
 
 pub struct InterchainGasPaymasterIndexerBuilder {
     pub mailbox_address: H160,
-    pub reorg_period: u32,
+    pub finality: Finality,
 }
 
 #[async_trait]
@@ -42,13 +44,14 @@ impl BuildableWithProvider for InterchainGasPaymasterIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
         Box::new(EthereumInterchainGasPaymasterIndexer::new(
             Arc::new(provider),
             locator,
-            self.reorg_period,
+            self.finality.clone(),
+            conn.quirks.clone(),
         ))
     }
 }
@@ -61,7 +64,9 @@ where
 {
     contract: Arc<EthereumInterchainGasPaymasterInternal<M>>,
     provider: Arc<M>,
-    reorg_period: u32,
+    finality: Finality,
+    finality_source: EthereumProvider<M>,
+    quirks: ChainQuirks,
 }
 
 impl<M> EthereumInterchainGasPaymasterIndexer<M>
@@ -69,14 +74,21 @@ where
     M: Middleware + 'static,
 {
     /// Create new EthereumInterchainGasPaymasterIndexer
-    pub fn new(provider: Arc<M>, locator: &ContractLocator, reorg_period: u32) -> Self {
+    pub fn new(
+        provider: Arc<M>,
+        locator: &ContractLocator,
+        finality: Finality,
+        quirks: ChainQuirks,
+    ) -> Self {
         Self {
             contract: Arc::new(EthereumInterchainGasPaymasterInternal::new(
                 locator.address,
                 provider.clone(),
             )),
+            finality_source: EthereumProvider::new(provider.clone(), locator.domain.clone()),
             provider,
-            reorg_period,
+            finality,
+            quirks,
         }
     }
 }
@@ -92,13 +104,13 @@ where
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
-        let events = self
-            .contract
-            .gas_payment_filter()
-            .from_block(*range.start())
-            .to_block(*range.end())
-            .query_with_meta()
-            .await?;
+        let events = query_logs_in_range(
+            self.contract.gas_payment_filter(),
+            &*self.provider,
+            range,
+            &self.quirks,
+        )
+        .await?;
 
         Ok(events
             .into_iter()
@@ -110,7 +122,7 @@ where
                         payment: log.payment.into(),
                         gas_amount: log.gas_amount.into(),
                     }),
-                    log_meta.into(),
+                    log_meta,
                 )
             })
             .collect())
@@ -118,13 +130,9 @@ where
 
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        Ok(self
-            .provider
-            .get_block_number()
+        self.finality
+            .finalized_block_number(&self.finality_source)
             .await
-            .map_err(ChainCommunicationError::from_other)?
-            .as_u32()
-            .saturating_sub(self.reorg_period))
     }
 
     async fn fetch_logs_by_tx_hash(
@@ -243,7 +251,35 @@ where
 }
 
 #[async_trait]
-impl<M> InterchainGasPaymaster for EthereumInterchainGasPaymaster<M> where M: Middleware + 'static {}
+impl<M> InterchainGasPaymaster for EthereumInterchainGasPaymaster<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(skip(self))]
+    async fn quote_gas_payment(
+        &self,
+        destination_domain: u32,
+        gas_amount: U256,
+    ) -> ChainResult<U256> {
+        let quote = self
+            .contract
+            .quote_gas_payment(destination_domain, gas_amount.into())
+            .call()
+            .await?;
+        Ok(quote.into())
+    }
+
+    #[instrument(skip(self))]
+    async fn token_exchange_rate(&self, destination_domain: u32) -> ChainResult<U256> {
+        // The IGP is the gas oracle of its remote domains
+        let oracle = IGasOracle::new(self.contract.address(), self.contract.client());
+        let (token_exchange_rate, _gas_price) = oracle
+            .get_exchange_rate_and_gas_price(destination_domain)
+            .call()
+            .await?;
+        Ok(token_exchange_rate.into())
+    }
+}
 
 pub struct EthereumInterchainGasPaymasterAbi;
 
@@ -1,7 +1,7 @@
 #![allow(clippy::enum_variant_names)]
 #![allow(missing_docs)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroU64;
 use std::ops::RangeInclusive;
 use std::sync::Arc;
@@ -9,29 +9,41 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use ethers::abi::{AbiEncode, Detokenize};
 use ethers::prelude::Middleware;
-use ethers_contract::builders::ContractCall;
+use ethers_contract::{builders::ContractCall, EthEvent};
+use ethers_core::types::BlockNumber;
 use futures_util::future::join_all;
 use hyperlane_core::H512;
-use tracing::instrument;
+use tracing::{debug, instrument};
 
 use hyperlane_core::{
-    utils::bytes_to_hex, BatchItem, ChainCommunicationError, ChainResult, ContractLocator,
-    HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProtocolError, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox,
-    RawHyperlaneMessage, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H160, H256, U256,
+    utils::bytes_to_hex, BatchItem, BatchResult, ChainCommunicationError, ChainResult,
+    ContractLocator, Delivery, DeliveryStatus, Finality, FinalityPolicy, HyperlaneAbi,
+    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProtocolError,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, MempoolCongestion, ProcessRevert,
+    ProcessSimulation, RawHyperlaneMessage, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H160,
+    H256, U256,
 };
 
 use crate::error::HyperlaneEthereumError;
 use crate::interfaces::arbitrum_node_interface::ArbitrumNodeInterface;
+use crate::interfaces::i_interchain_security_module::IInterchainSecurityModule;
 use crate::interfaces::i_mailbox::{
-    IMailbox as EthereumMailboxInternal, ProcessCall, IMAILBOX_ABI,
+    IMailbox as EthereumMailboxInternal, ProcessCall, ProcessIdFilter, IMAILBOX_ABI,
 };
-use crate::interfaces::mailbox::DispatchFilter;
+use crate::interfaces::mailbox::{DispatchFilter, Mailbox as MailboxContract};
 use crate::tx::{call_with_lag, fill_tx_gas_params, report_tx};
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider, TransactionOverrides};
+use crate::{
+    BuildableWithProvider, ChainQuirks, ConnectionConf, EthereumProvider, TransactionOverrides,
+};
 
 use super::multicall::{self, build_multicall};
-use super::utils::fetch_raw_logs_and_log_meta;
+use super::utils::{fetch_raw_logs_and_log_meta, query_logs_in_range};
+
+/// The revert reason of the mailbox for messages that were delivered
+const ALREADY_DELIVERED_REASON: &str = "Mailbox: already delivered";
+
+/// The number of recent blocks the base fee slope is calculated over.
+const BASE_FEE_HISTORY_BLOCKS: u64 = 10;
 
 impl<M> std::fmt::Display for EthereumMailboxInternal<M>
 where
@@ -43,7 +55,7 @@ where
 }
 
 pub struct SequenceIndexerBuilder {
-    pub reorg_period: u32,
+    pub finality: Finality,
 }
 
 #[async_trait]
@@ -53,19 +65,20 @@ impl BuildableWithProvider for SequenceIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
         Box::new(EthereumMailboxIndexer::new(
             Arc::new(provider),
             locator,
-            self.reorg_period,
+            self.finality.clone(),
+            conn.quirks.clone(),
         ))
     }
 }
 
 pub struct DeliveryIndexerBuilder {
-    pub reorg_period: u32,
+    pub finality: Finality,
 }
 
 #[async_trait]
@@ -75,13 +88,14 @@ impl BuildableWithProvider for DeliveryIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
         Box::new(EthereumMailboxIndexer::new(
             Arc::new(provider),
             locator,
-            self.reorg_period,
+            self.finality.clone(),
+            conn.quirks.clone(),
         ))
     }
 }
@@ -94,7 +108,9 @@ where
 {
     contract: Arc<EthereumMailboxInternal<M>>,
     provider: Arc<M>,
-    reorg_period: u32,
+    finality: Finality,
+    finality_source: EthereumProvider<M>,
+    quirks: ChainQuirks,
 }
 
 impl<M> EthereumMailboxIndexer<M>
@@ -102,27 +118,30 @@ where
     M: Middleware + 'static,
 {
     /// Create new EthereumMailboxIndexer
-    pub fn new(provider: Arc<M>, locator: &ContractLocator, reorg_period: u32) -> Self {
+    pub fn new(
+        provider: Arc<M>,
+        locator: &ContractLocator,
+        finality: Finality,
+        quirks: ChainQuirks,
+    ) -> Self {
         let contract = Arc::new(EthereumMailboxInternal::new(
             locator.address,
             provider.clone(),
         ));
         Self {
             contract,
+            finality_source: EthereumProvider::new(provider.clone(), locator.domain.clone()),
             provider,
-            reorg_period,
+            finality,
+            quirks,
         }
     }
 
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        Ok(self
-            .provider
-            .get_block_number()
+        self.finality
+            .finalized_block_number(&self.finality_source)
             .await
-            .map_err(ChainCommunicationError::from_other)?
-            .as_u32()
-            .saturating_sub(self.reorg_period))
     }
 }
 
@@ -141,21 +160,16 @@ where
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
-        let mut events: Vec<(Indexed<HyperlaneMessage>, LogMeta)> = self
-            .contract
-            .dispatch_filter()
-            .from_block(*range.start())
-            .to_block(*range.end())
-            .query_with_meta()
-            .await?
-            .into_iter()
-            .map(|(event, meta)| {
-                (
-                    HyperlaneMessage::from(event.message.to_vec()).into(),
-                    meta.into(),
-                )
-            })
-            .collect();
+        let mut events: Vec<(Indexed<HyperlaneMessage>, LogMeta)> = query_logs_in_range(
+            self.contract.dispatch_filter(),
+            &*self.provider,
+            range,
+            &self.quirks,
+        )
+        .await?
+        .into_iter()
+        .map(|(event, meta)| (HyperlaneMessage::from(event.message.to_vec()).into(), meta))
+        .collect();
 
         events.sort_by(|a, b| a.0.inner().nonce.cmp(&b.0.inner().nonce));
         Ok(events)
@@ -211,16 +225,16 @@ where
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
-        Ok(self
-            .contract
-            .process_id_filter()
-            .from_block(*range.start())
-            .to_block(*range.end())
-            .query_with_meta()
-            .await?
-            .into_iter()
-            .map(|(event, meta)| (Indexed::new(H256::from(event.message_id)), meta.into()))
-            .collect())
+        Ok(query_logs_in_range(
+            self.contract.process_id_filter(),
+            &*self.provider,
+            range,
+            &self.quirks,
+        )
+        .await?
+        .into_iter()
+        .map(|(event, meta)| (Indexed::new(H256::from(event.message_id)), meta))
+        .collect())
     }
 }
 
@@ -296,6 +310,29 @@ where
         }
     }
 
+    /// The delivery of the delivered message `id`, along with the
+    /// transaction that delivered it if its event can be found
+    async fn delivery(&self, id: H256) -> ChainResult<Delivery> {
+        let mailbox = MailboxContract::new(self.contract.address(), self.provider.clone());
+        let processor = mailbox.processor(id.into()).call().await?;
+        let block_number = mailbox.processed_at(id.into()).call().await?;
+        let block = u32::try_from(block_number).map_err(ChainCommunicationError::from_other)?;
+        let events = query_logs_in_range(
+            mailbox
+                .process_id_filter()
+                .topic1(ethers_core::types::H256::from(id.to_fixed_bytes())),
+            &*self.provider,
+            block..=block,
+            &self.conn.quirks,
+        )
+        .await?;
+        Ok(Delivery {
+            block_number,
+            processor: processor.into(),
+            transaction_id: events.first().map(|(_, meta)| meta.transaction_id),
+        })
+    }
+
     /// Returns a ContractCall that processes the provided message.
     /// If the provided tx_gas_limit is None, gas estimation occurs.
     async fn process_contract_call(
@@ -326,7 +363,7 @@ where
                 .or(tx_gas_estimate),
             ..self.conn.transaction_overrides.clone()
         };
-        fill_tx_gas_params(tx, self.provider.clone(), &tx_overrides).await
+        fill_tx_gas_params(tx, self.provider.clone(), &tx_overrides, &self.conn.quirks).await
     }
 }
 
@@ -372,6 +409,40 @@ where
         Ok(self.contract.delivered(id.into()).call().await?)
     }
 
+    /// Messages for other domains, to recipients that aren't contracts, or
+    /// whose recipient ISM can't be looked up can't be delivered
+    #[instrument(skip(self), fields(msg=%message))]
+    async fn delivery_status(&self, message: &HyperlaneMessage) -> ChainResult<DeliveryStatus> {
+        let id = message.id();
+        if self.contract.delivered(id.into()).call().await? {
+            return Ok(DeliveryStatus::Delivered(Some(self.delivery(id).await?)));
+        }
+        if message.destination != self.domain.id() {
+            return Ok(DeliveryStatus::Undeliverable {
+                reason: format!("message is for domain {}", message.destination),
+            });
+        }
+        if !self.provider().is_contract(&message.recipient).await? {
+            return Ok(DeliveryStatus::Undeliverable {
+                reason: "recipient is not a contract".to_owned(),
+            });
+        }
+        match self
+            .contract
+            .recipient_ism(message.recipient.into())
+            .call()
+            .await
+        {
+            Ok(_) => Ok(DeliveryStatus::Pending),
+            Err(err) if err.is_revert() => Ok(DeliveryStatus::Undeliverable {
+                reason: err
+                    .decode_revert::<String>()
+                    .unwrap_or_else(|| "recipient ISM lookup reverts".to_owned()),
+            }),
+            Err(err) => Err(err.into()),
+        }
+    }
+
     #[instrument(skip(self))]
     async fn default_ism(&self) -> ChainResult<H256> {
         Ok(self.contract.default_ism().call().await?.into())
@@ -405,7 +476,7 @@ where
     async fn process_batch(
         &self,
         messages: &[BatchItem<HyperlaneMessage>],
-    ) -> ChainResult<TxOutcome> {
+    ) -> ChainResult<BatchResult> {
         let mut multicall = build_multicall(self.provider.clone(), &self.conn, self.domain.clone())
             .await
             .map_err(|e| HyperlaneEthereumError::MulticallError(e.to_string()))?;
@@ -429,7 +500,65 @@ where
         let call = self.add_gas_overrides(batch_call, None).await?;
 
         let receipt = report_tx(call).await?;
-        Ok(receipt.into())
+        // Calls are allowed to fail, so the messages that were processed are
+        // the ones the mailbox emitted a `ProcessId` event for
+        let processed = receipt
+            .logs
+            .iter()
+            .filter(|log| {
+                log.address == self.contract.address()
+                    && log.topics.first() == Some(&ProcessIdFilter::signature())
+            })
+            .filter_map(|log| log.topics.get(1))
+            .map(|id| H256::from(id.to_fixed_bytes()))
+            .collect::<HashSet<_>>();
+        let executed = messages
+            .iter()
+            .map(|item| processed.contains(&item.data.id()))
+            .collect::<Vec<_>>();
+        Ok(BatchResult::new(receipt.into(), messages, executed))
+    }
+
+    /// Reverts are attributed to the ISM unless verifying the metadata with
+    /// it directly succeeds, since ISMs may revert rather than return false
+    #[instrument(skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn simulate_process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<ProcessSimulation> {
+        let raw_message = RawHyperlaneMessage::from(message).to_vec();
+        let call = self
+            .contract
+            .process(metadata.to_vec().into(), raw_message.clone().into());
+        let reason = match call.call().await {
+            Ok(()) => return Ok(ProcessSimulation::Succeeded),
+            Err(err) if err.is_revert() => err.decode_revert::<String>().unwrap_or_default(),
+            Err(err) => return Err(err.into()),
+        };
+        if reason == ALREADY_DELIVERED_REASON {
+            return Ok(ProcessSimulation::Reverted(ProcessRevert::AlreadyDelivered));
+        }
+
+        let ism_address = self
+            .contract
+            .recipient_ism(message.recipient.into())
+            .call()
+            .await?;
+        let ism = IInterchainSecurityModule::new(ism_address, self.provider.clone());
+        let revert = match ism
+            .verify(metadata.to_vec().into(), raw_message.into())
+            .call()
+            .await
+        {
+            Ok(true) => ProcessRevert::RecipientReverted { reason },
+            Ok(false) => ProcessRevert::IsmVerificationFailed { reason },
+            Err(err) if err.is_revert() => ProcessRevert::IsmVerificationFailed {
+                reason: err.decode_revert::<String>().unwrap_or(reason),
+            },
+            Err(err) => return Err(err.into()),
+        };
+        Ok(ProcessSimulation::Reverted(revert))
     }
 
     #[instrument(skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
@@ -477,6 +606,48 @@ where
             gas_limit: gas_limit.into(),
             gas_price: gas_price.try_into()?,
             l2_gas_limit: l2_gas_limit.map(|v| v.into()),
+            resources: vec![],
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn mempool_congestion(&self) -> ChainResult<MempoolCongestion> {
+        let pending_txs = match self.provider.default_sender() {
+            Some(sender) => {
+                let (pending, latest) = futures_util::try_join!(
+                    self.provider
+                        .get_transaction_count(sender, Some(BlockNumber::Pending.into())),
+                    self.provider
+                        .get_transaction_count(sender, Some(BlockNumber::Latest.into())),
+                )
+                .map_err(ChainCommunicationError::from_other)?;
+                Some(pending.saturating_sub(latest).low_u64())
+            }
+            None => None,
+        };
+        // Chains without EIP-1559 don't support `eth_feeHistory`
+        let base_fee_slope = match self
+            .provider
+            .fee_history(BASE_FEE_HISTORY_BLOCKS, BlockNumber::Latest, &[])
+            .await
+        {
+            Ok(history) => MempoolCongestion::base_fee_slope(
+                &history
+                    .base_fee_per_gas
+                    .into_iter()
+                    .map(Into::into)
+                    .collect::<Vec<_>>(),
+            ),
+            Err(err) => {
+                debug!(error=?err, "Failed to get fee history");
+                None
+            }
+        };
+
+        Ok(MempoolCongestion {
+            pending_txs,
+            base_fee_slope,
+            expired_txs: None,
         })
     }
 
@@ -506,7 +677,7 @@ mod test {
 
     use ethers::{
         providers::{MockProvider, Provider},
-        types::{Block, Transaction, U256 as EthersU256},
+        types::{transaction::eip2718::TypedTransaction, Block, Transaction, U256 as EthersU256},
     };
 
     use hyperlane_core::{
@@ -514,7 +685,7 @@ mod test {
         TxCostEstimate, H160, H256, U256,
     };
 
-    use crate::{contracts::EthereumMailbox, ConnectionConf, RpcConnectionConf};
+    use crate::{contracts::EthereumMailbox, ChainQuirks, ConnectionConf, RpcConnectionConf};
 
     /// An amount of gas to add to the estimated gas
     const GAS_ESTIMATE_BUFFER: u32 = 75_000;
@@ -529,6 +700,8 @@ mod test {
             },
             transaction_overrides: Default::default(),
             operation_batch: Default::default(),
+            quirks: Default::default(),
+            light_client_url: None,
         };
 
         let mailbox = EthereumMailbox::new(
@@ -588,7 +761,45 @@ mod test {
                 gas_limit: estimated_gas_limit,
                 gas_price: gas_price.try_into().unwrap(),
                 l2_gas_limit: Some(l2_gas_limit),
+                resources: vec![],
+            },
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_contract_call_is_legacy_without_eip1559() {
+        let mock_provider = Arc::new(MockProvider::new());
+        let provider = Arc::new(Provider::new(mock_provider.clone()));
+        let connection_conf = ConnectionConf {
+            rpc_connection: RpcConnectionConf::Http {
+                url: "http://127.0.0.1:8545".parse().unwrap(),
+            },
+            transaction_overrides: Default::default(),
+            operation_batch: Default::default(),
+            quirks: ChainQuirks {
+                supports_eip1559: false,
+                ..Default::default()
+            },
+            light_client_url: None,
+        };
+
+        let mailbox = EthereumMailbox::new(
+            provider.clone(),
+            &connection_conf,
+            &ContractLocator {
+                domain: &HyperlaneDomain::new_test_domain("test"),
+                address: H256::default(),
             },
         );
+
+        // The gas limit is given, so no RPCs are made
+        let gas_limit = U256::from(1000000u32);
+        let call = mailbox
+            .process_contract_call(&HyperlaneMessage::default(), &[], Some(gas_limit))
+            .await
+            .unwrap();
+
+        assert!(matches!(call.tx, TypedTransaction::Legacy(_)));
+        assert_eq!(call.tx.gas(), Some(&gas_limit.into()));
     }
 }
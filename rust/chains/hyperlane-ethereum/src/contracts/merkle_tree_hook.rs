@@ -9,7 +9,7 @@ use hyperlane_core::accumulator::incremental::IncrementalMerkle;
 use tracing::instrument;
 
 use hyperlane_core::{
-    ChainCommunicationError, ChainResult, Checkpoint, ContractLocator, HyperlaneChain,
+    ChainResult, Checkpoint, ContractLocator, Finality, FinalityPolicy, HyperlaneChain,
     HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer, LogMeta,
     MerkleTreeHook, MerkleTreeInsertion, SequenceAwareIndexer, H256, H512,
 };
@@ -18,9 +18,9 @@ use crate::interfaces::merkle_tree_hook::{
     InsertedIntoTreeFilter, MerkleTreeHook as MerkleTreeHookContract, Tree,
 };
 use crate::tx::call_with_lag;
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+use crate::{BuildableWithProvider, ChainQuirks, ConnectionConf, EthereumProvider};
 
-use super::utils::fetch_raw_logs_and_log_meta;
+use super::utils::{fetch_raw_logs_and_log_meta, query_logs_in_range};
 
 // We don't need the reverse of this impl, so it's ok to disable the clippy lint
 #[allow(clippy::from_over_into)]
@@ -56,7 +56,7 @@ impl BuildableWithProvider for MerkleTreeHookBuilder {
 }
 
 pub struct MerkleTreeHookIndexerBuilder {
-    pub reorg_period: u32,
+    pub finality: Finality,
 }
 
 #[async_trait]
@@ -66,13 +66,14 @@ impl BuildableWithProvider for MerkleTreeHookIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
         Box::new(EthereumMerkleTreeHookIndexer::new(
             Arc::new(provider),
             locator,
-            self.reorg_period,
+            self.finality.clone(),
+            conn.quirks.clone(),
         ))
     }
 }
@@ -85,7 +86,9 @@ where
 {
     contract: Arc<MerkleTreeHookContract<M>>,
     provider: Arc<M>,
-    reorg_period: u32,
+    finality: Finality,
+    finality_source: EthereumProvider<M>,
+    quirks: ChainQuirks,
 }
 
 impl<M> EthereumMerkleTreeHookIndexer<M>
@@ -93,14 +96,21 @@ where
     M: Middleware + 'static,
 {
     /// Create new EthereumMerkleTreeHookIndexer
-    pub fn new(provider: Arc<M>, locator: &ContractLocator, reorg_period: u32) -> Self {
+    pub fn new(
+        provider: Arc<M>,
+        locator: &ContractLocator,
+        finality: Finality,
+        quirks: ChainQuirks,
+    ) -> Self {
         Self {
             contract: Arc::new(MerkleTreeHookContract::new(
                 locator.address,
                 provider.clone(),
             )),
+            finality_source: EthereumProvider::new(provider.clone(), locator.domain.clone()),
             provider,
-            reorg_period,
+            finality,
+            quirks,
         }
     }
 }
@@ -116,20 +126,20 @@ where
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
-        let events = self
-            .contract
-            .inserted_into_tree_filter()
-            .from_block(*range.start())
-            .to_block(*range.end())
-            .query_with_meta()
-            .await?;
+        let events = query_logs_in_range(
+            self.contract.inserted_into_tree_filter(),
+            &*self.provider,
+            range,
+            &self.quirks,
+        )
+        .await?;
 
         let logs = events
             .into_iter()
             .map(|(log, log_meta)| {
                 (
                     MerkleTreeInsertion::new(log.index, H256::from(log.message_id)).into(),
-                    log_meta.into(),
+                    log_meta,
                 )
             })
             .collect();
@@ -138,13 +148,9 @@ where
 
     #[instrument(level = "debug", err, skip(self))]
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        Ok(self
-            .provider
-            .get_block_number()
+        self.finality
+            .finalized_block_number(&self.finality_source)
             .await
-            .map_err(ChainCommunicationError::from_other)?
-            .as_u32()
-            .saturating_sub(self.reorg_period))
     }
 
     async fn fetch_logs_by_tx_hash(
@@ -1,5 +1,6 @@
-pub use {interchain_gas::*, mailbox::*, merkle_tree_hook::*, validator_announce::*};
+pub use {hooks::*, interchain_gas::*, mailbox::*, merkle_tree_hook::*, validator_announce::*};
 
+mod hooks;
 mod interchain_gas;
 mod mailbox;
 mod merkle_tree_hook;
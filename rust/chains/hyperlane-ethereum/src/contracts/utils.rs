@@ -1,14 +1,17 @@
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use ethers::{
     abi::RawLog,
     providers::Middleware,
-    types::{H160 as EthersH160, H256 as EthersH256},
+    types::{Topic, ValueOrArray, H160 as EthersH160, H256 as EthersH256},
 };
-use ethers_contract::{ContractError, EthEvent, LogMeta as EthersLogMeta};
+use ethers_contract::{ContractError, EthEvent, EthLogDecode, Event, LogMeta as EthersLogMeta};
 use hyperlane_core::{ChainResult, LogMeta, H512};
 use tracing::warn;
 
+use crate::ChainQuirks;
+
 pub async fn fetch_raw_logs_and_log_meta<T: EthEvent, M>(
     tx_hash: H512,
     provider: Arc<M>,
@@ -46,3 +49,140 @@ where
         .collect();
     Ok(logs)
 }
+
+/// Splits `range` into consecutive ranges spanning at most `max_len` blocks
+fn split_range(range: RangeInclusive<u32>, max_len: Option<u32>) -> Vec<RangeInclusive<u32>> {
+    let Some(max_len) = max_len.filter(|len| *len > 0) else {
+        return vec![range];
+    };
+    let mut ranges = vec![];
+    let mut start = *range.start();
+    while start <= *range.end() {
+        let end = start.saturating_add(max_len - 1).min(*range.end());
+        ranges.push(start..=end);
+        match end.checked_add(1) {
+            Some(next) => start = next,
+            None => break,
+        }
+    }
+    ranges
+}
+
+/// Whether a log with `log_topic` at some position matches the `topic` a
+/// filter has at that position, like a node would match it: a topic matches
+/// any of the values it lists, and no topic or a null value matches any.
+fn topic_matches(topic: &Option<Topic>, log_topic: Option<&EthersH256>) -> bool {
+    let values = match topic {
+        None => return true,
+        Some(ValueOrArray::Value(value)) => std::slice::from_ref(value),
+        Some(ValueOrArray::Array(values)) => values.as_slice(),
+    };
+    if values.is_empty() || values.contains(&None) {
+        return true;
+    }
+    log_topic.map_or(false, |log_topic| values.contains(&Some(*log_topic)))
+}
+
+/// Fetches the logs of `event` in the blocks in `range`, working around the
+/// limits the chain puts on `eth_getLogs`: the range is split if it spans too
+/// many blocks, and logs are filtered by topic locally if the filter has too
+/// many topics.
+pub(crate) async fn query_logs_in_range<B, M, D>(
+    event: Event<B, M, D>,
+    provider: &M,
+    range: RangeInclusive<u32>,
+    quirks: &ChainQuirks,
+) -> ChainResult<Vec<(D, LogMeta)>>
+where
+    M: Middleware + 'static,
+    D: EthLogDecode,
+{
+    let mut filter = event.filter;
+    let topics = filter.topics.clone();
+    let filter_topics = quirks
+        .max_log_topics
+        .map_or(true, |max| topics.iter().flatten().count() <= max);
+    if !filter_topics {
+        filter.topics = Default::default();
+    }
+
+    let mut logs = vec![];
+    for range in split_range(range, quirks.max_log_block_range) {
+        let filter = filter
+            .clone()
+            .from_block(*range.start())
+            .to_block(*range.end());
+        let range_logs = provider
+            .get_logs(&filter)
+            .await
+            .map_err(ContractError::<M>::MiddlewareError)?;
+        for log in range_logs {
+            if !filter_topics
+                && !topics
+                    .iter()
+                    .enumerate()
+                    .all(|(i, topic)| topic_matches(topic, log.topics.get(i)))
+            {
+                continue;
+            }
+            let raw_log = RawLog {
+                topics: log.topics.clone(),
+                data: log.data.to_vec(),
+            };
+            match D::decode_log(&raw_log) {
+                Ok(decoded) => {
+                    let log_meta: EthersLogMeta = (&log).into();
+                    logs.push((decoded, log_meta.into()));
+                }
+                Err(err) => return Err(ContractError::<M>::DecodingError(err).into()),
+            }
+        }
+    }
+    Ok(logs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_range() {
+        assert_eq!(split_range(1..=10, None), vec![1..=10]);
+        assert_eq!(split_range(1..=10, Some(0)), vec![1..=10]);
+        assert_eq!(split_range(1..=10, Some(4)), vec![1..=4, 5..=8, 9..=10]);
+        assert_eq!(split_range(1..=8, Some(4)), vec![1..=4, 5..=8]);
+        assert_eq!(split_range(3..=3, Some(4)), vec![3..=3]);
+        assert_eq!(
+            split_range(u32::MAX - 2..=u32::MAX, Some(2)),
+            vec![u32::MAX - 2..=u32::MAX - 1, u32::MAX..=u32::MAX]
+        );
+    }
+
+    #[test]
+    fn test_topic_matches() {
+        let a = EthersH256::repeat_byte(0xaa);
+        let b = EthersH256::repeat_byte(0xbb);
+        let c = EthersH256::repeat_byte(0xcc);
+
+        assert!(topic_matches(&None, None));
+        assert!(topic_matches(&None, Some(&a)));
+        assert!(topic_matches(&Some(ValueOrArray::Value(None)), Some(&a)));
+        assert!(topic_matches(&Some(ValueOrArray::Value(Some(a))), Some(&a)));
+        assert!(!topic_matches(
+            &Some(ValueOrArray::Value(Some(a))),
+            Some(&b)
+        ));
+        // A log without a topic at the position doesn't match a value
+        assert!(!topic_matches(&Some(ValueOrArray::Value(Some(a))), None));
+
+        let either = Some(ValueOrArray::Array(vec![Some(a), Some(b)]));
+        assert!(topic_matches(&either, Some(&a)));
+        assert!(topic_matches(&either, Some(&b)));
+        assert!(!topic_matches(&either, Some(&c)));
+        assert!(topic_matches(&Some(ValueOrArray::Array(vec![])), Some(&c)));
+        assert!(topic_matches(
+            &Some(ValueOrArray::Array(vec![Some(a), None])),
+            Some(&c)
+        ));
+    }
+}
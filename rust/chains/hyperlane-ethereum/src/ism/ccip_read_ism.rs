@@ -89,12 +89,34 @@ where
     M: Middleware + 'static,
 {
     #[instrument(err)]
-    async fn get_offchain_verify_info(&self, message: Vec<u8>) -> ChainResult<()> {
-        self.contract
+    async fn get_offchain_verify_info(
+        &self,
+        message: Vec<u8>,
+    ) -> ChainResult<Option<hyperlane_core::OffchainLookup>> {
+        match self
+            .contract
             .get_offchain_verify_info(message.into())
             .call()
-            .await?;
-        Ok(())
+            .await
+        {
+            Ok(()) => Ok(None),
+            Err(err) => match err.decode_revert::<OffchainLookup>() {
+                Some(lookup) => Ok(Some(lookup.into())),
+                None => Err(err.into()),
+            },
+        }
+    }
+}
+
+impl From<OffchainLookup> for hyperlane_core::OffchainLookup {
+    fn from(lookup: OffchainLookup) -> Self {
+        Self {
+            sender: lookup.sender.into(),
+            urls: lookup.urls,
+            call_data: lookup.call_data.to_vec(),
+            callback_function: lookup.callback_function,
+            extra_data: lookup.extra_data.to_vec(),
+        }
     }
 }
 
@@ -1,10 +1,11 @@
 pub use {
     aggregation_ism::*, ccip_read_ism::*, interchain_security_module::*, multisig_ism::*,
-    routing_ism::*,
+    optimistic_ism::*, routing_ism::*,
 };
 
 mod aggregation_ism;
 mod ccip_read_ism;
 mod interchain_security_module;
 mod multisig_ism;
+mod optimistic_ism;
 mod routing_ism;
@@ -1,12 +1,16 @@
 use ethers::providers::HttpClientError;
 use tracing::{info, trace, warn};
 
-pub use self::{fallback::*, provider::*, retrying::*, trait_builder::*};
+pub use self::{
+    fallback::*, provider::*, reloadable::*, retrying::*, trait_builder::*, verifying::*,
+};
 
 mod fallback;
 mod provider;
+mod reloadable;
 mod retrying;
 mod trait_builder;
+mod verifying;
 
 enum CategorizedResponse<R> {
     IsOk(R),
@@ -12,8 +12,9 @@ use tokio::time::sleep;
 use tracing::instrument;
 
 use hyperlane_core::{
-    BlockInfo, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
-    HyperlaneDomain, HyperlaneProvider, HyperlaneProviderError, TxnInfo, TxnReceiptInfo, H256,
+    BlockInfo, BlockTag, ChainCommunicationError, ChainResult, ContractLocator, FinalitySource,
+    HyperlaneChain, HyperlaneDomain, HyperlaneProvider, HyperlaneProviderError, TxnInfo,
+    TxnReceiptInfo, H256,
 };
 
 use crate::{BuildableWithProvider, ConnectionConf};
@@ -90,6 +91,7 @@ where
             nonce: txn.nonce.as_u64(),
             sender: txn.from.into(),
             recipient: txn.to.map(Into::into),
+            raw_input_data: Some(txn.input.to_vec()),
             receipt,
         })
     }
@@ -143,6 +145,16 @@ where
         );
         Ok(Some(chain_metrics))
     }
+
+    #[instrument(err, skip(self))]
+    async fn get_chain_id(&self) -> ChainResult<Option<u64>> {
+        let chain_id = self
+            .provider
+            .get_chainid()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(Some(chain_id.as_u64()))
+    }
 }
 
 impl<M> EthereumProvider<M>
@@ -164,6 +176,52 @@ where
     }
 }
 
+#[async_trait]
+impl<M> FinalitySource for EthereumProvider<M>
+where
+    M: Middleware + 'static,
+{
+    async fn latest_block_number(&self) -> ChainResult<u32> {
+        Ok(self
+            .provider
+            .get_block_number()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .as_u32())
+    }
+
+    async fn block_timestamp(&self, number: u32) -> ChainResult<u64> {
+        let block = self
+            .provider
+            .get_block(u64::from(number))
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .ok_or_else(|| ChainCommunicationError::from_other_str("block not found"))?;
+        Ok(block.timestamp.as_u64())
+    }
+
+    async fn tagged_block_number(&self, tag: BlockTag) -> ChainResult<Option<u32>> {
+        let tag = match tag {
+            BlockTag::Safe => BlockNumber::Safe,
+            BlockTag::Finalized => BlockNumber::Finalized,
+        };
+        let block = self
+            .provider
+            .get_block(tag)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(block
+            .and_then(|block| block.number)
+            .map(|number| number.as_u32()))
+    }
+
+    /// The nodes of rollups derive the `finalized` tag from the finality of
+    /// the L1 blocks their batches were posted in
+    async fn l1_settled_block_number(&self) -> ChainResult<Option<u32>> {
+        self.tagged_block_number(BlockTag::Finalized).await
+    }
+}
+
 /// Builder for hyperlane providers.
 pub struct HyperlaneProviderBuilder {}
 
@@ -184,6 +242,26 @@ impl BuildableWithProvider for HyperlaneProviderBuilder {
     }
 }
 
+/// Builder for the sources finality policies are evaluated against.
+pub struct FinalitySourceBuilder {}
+
+#[async_trait]
+impl BuildableWithProvider for FinalitySourceBuilder {
+    type Output = Box<dyn FinalitySource>;
+
+    async fn build_with_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        _conn: &ConnectionConf,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        Box::new(EthereumProvider::new(
+            Arc::new(provider),
+            locator.domain.clone(),
+        ))
+    }
+}
+
 /// Call a get function that returns a Result<Option<T>> and retry if the inner
 /// option is None. This can happen because the provider has not discovered the
 /// object we are looking for yet.
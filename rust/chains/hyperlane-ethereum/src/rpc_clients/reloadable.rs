@@ -0,0 +1,116 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, Mutex, OnceLock, RwLock},
+};
+
+use async_trait::async_trait;
+use ethers::providers::{Http, HttpClientError, JsonRpcClient};
+use reqwest::{Client, Url};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// The URLs of the HTTP RPCs of every chain, by chain name and position in
+/// its config, shared by every provider built for them
+static RPC_URLS: OnceLock<Mutex<HashMap<(String, usize), Arc<RwLock<Url>>>>> = OnceLock::new();
+
+fn rpc_urls() -> &'static Mutex<HashMap<(String, usize), Arc<RwLock<Url>>>> {
+    RPC_URLS.get_or_init(Default::default)
+}
+
+/// An HTTP JSON-RPC client whose URL can be replaced while the agent is
+/// running, e.g. to move off a failing RPC provider without a restart. The
+/// node labels of the RPC metrics keep the URL the client was built with.
+#[derive(Debug, Clone)]
+pub struct ReloadableHttp {
+    url: Arc<RwLock<Url>>,
+    client: Client,
+}
+
+impl ReloadableHttp {
+    /// Creates a client for the RPC at `index` in the config of `chain`,
+    /// which every client of that RPC shares the URL of
+    pub fn new(chain: &str, index: usize, url: Url, client: Client) -> Self {
+        let shared = rpc_urls()
+            .lock()
+            .expect("poisoned lock")
+            .entry((chain.to_owned(), index))
+            .or_insert_with(|| Arc::new(RwLock::new(url.clone())))
+            .clone();
+        *shared.write().expect("poisoned lock") = url;
+        Self {
+            url: shared,
+            client,
+        }
+    }
+
+    /// The URL requests are currently sent to
+    pub fn url(&self) -> Url {
+        self.url.read().expect("poisoned lock").clone()
+    }
+}
+
+/// Replaces the URLs of the HTTP RPCs of `chain`, in the order of its config.
+/// Returns false without replacing any if the number of RPCs changed, since
+/// that changes how the providers are built.
+pub fn reload_rpc_urls(chain: &str, urls: &[Url]) -> bool {
+    let rpc_urls = rpc_urls().lock().expect("poisoned lock");
+    let registered = rpc_urls.keys().filter(|(name, _)| name == chain).count();
+    if registered == 0 {
+        // No providers were built for the chain
+        return true;
+    }
+    if registered != urls.len() {
+        return false;
+    }
+    for (index, url) in urls.iter().enumerate() {
+        if let Some(shared) = rpc_urls.get(&(chain.to_owned(), index)) {
+            *shared.write().expect("poisoned lock") = url.clone();
+        }
+    }
+    true
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl JsonRpcClient for ReloadableHttp {
+    type Error = HttpClientError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        // Cheap, the connection pool is shared through the client
+        let http = Http::new_with_client(self.url(), self.client.clone());
+        http.request(method, params).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn reloads_urls_of_every_client() {
+        let chain = "reloadable-test";
+        let first = ReloadableHttp::new(chain, 0, url("http://a.test"), Client::new());
+        let second = ReloadableHttp::new(chain, 1, url("http://b.test"), Client::new());
+        let first_again = ReloadableHttp::new(chain, 0, url("http://a.test"), Client::new());
+
+        assert!(reload_rpc_urls(
+            chain,
+            &[url("http://c.test"), url("http://d.test")]
+        ));
+        assert_eq!(first.url(), url("http://c.test"));
+        assert_eq!(first_again.url(), url("http://c.test"));
+        assert_eq!(second.url(), url("http://d.test"));
+
+        // Changing the number of RPCs isn't reloaded
+        assert!(!reload_rpc_urls(chain, &[url("http://e.test")]));
+        assert_eq!(first.url(), url("http://c.test"));
+    }
+}
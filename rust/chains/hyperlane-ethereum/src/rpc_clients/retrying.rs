@@ -1,8 +1,8 @@
 use std::{fmt::Debug, str::FromStr, time::Duration};
 
-use crate::rpc_clients::{categorize_client_response, CategorizedResponse};
+use crate::rpc_clients::{categorize_client_response, CategorizedResponse, ReloadableHttp};
 use async_trait::async_trait;
-use ethers::providers::{Http, JsonRpcClient, ProviderError};
+use ethers::providers::{JsonRpcClient, ProviderError};
 use ethers_prometheus::json_rpc_client::{
     PrometheusJsonRpcClient, PrometheusJsonRpcClientConfigExt,
 };
@@ -165,8 +165,8 @@ where
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
-impl JsonRpcClient for RetryingProvider<PrometheusJsonRpcClient<Http>> {
-    type Error = RetryingProviderError<PrometheusJsonRpcClient<Http>>;
+impl JsonRpcClient for RetryingProvider<PrometheusJsonRpcClient<ReloadableHttp>> {
+    type Error = RetryingProviderError<PrometheusJsonRpcClient<ReloadableHttp>>;
 
     #[instrument(skip(self), fields(provider_host = %self.inner.node_host(), chain_name = %self.inner.chain_name()))]
     async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
@@ -11,6 +11,7 @@ use ethers::prelude::{
     SignerMiddleware, WeightedProvider, Ws, WsClientError,
 };
 use hyperlane_core::rpc_clients::FallbackProvider;
+use prometheus::IntCounterVec;
 use reqwest::{Client, Url};
 use thiserror::Error;
 
@@ -24,7 +25,10 @@ use hyperlane_core::{
 };
 
 use crate::signer::Signers;
-use crate::{ConnectionConf, EthereumFallbackProvider, RetryingProvider, RpcConnectionConf};
+use crate::{
+    ConnectionConf, EthereumFallbackProvider, ReloadableHttp, RetryingProvider, RpcConnectionConf,
+    VerifyingProvider,
+};
 
 // This should be whatever the prometheus scrape interval is
 const HTTP_CLIENT_TIMEOUT: Duration = Duration::from_secs(60);
@@ -62,6 +66,7 @@ pub trait BuildableWithProvider {
         signer: Option<Signers>,
         rpc_metrics: Option<JsonRpcClientMetrics>,
         middleware_metrics: Option<(MiddlewareMetrics, PrometheusMiddlewareConf)>,
+        verification_failures: Option<IntCounterVec>,
     ) -> ChainResult<Self::Output> {
         Ok(match &conn.rpc_connection {
             RpcConnectionConf::HttpQuorum { urls } => {
@@ -70,8 +75,13 @@ pub trait BuildableWithProvider {
                     .timeout(HTTP_CLIENT_TIMEOUT)
                     .build()
                     .map_err(EthereumProviderConnectionError::from)?;
-                for url in urls {
-                    let http_provider = Http::new_with_client(url.clone(), http_client.clone());
+                for (index, url) in urls.iter().enumerate() {
+                    let http_provider = ReloadableHttp::new(
+                        locator.domain.name(),
+                        index,
+                        url.clone(),
+                        http_client.clone(),
+                    );
                     // Wrap the inner providers as RetryingProviders rather than the QuorumProvider.
                     // We've observed issues where the QuorumProvider will first get the latest
                     // block number and then submit an RPC at that block height,
@@ -93,7 +103,14 @@ pub trait BuildableWithProvider {
                     builder = builder.add_provider(weighted_provider);
                 }
                 let quorum_provider = builder.build();
-                self.build(quorum_provider, conn, locator, signer).await?
+                self.build_verified(
+                    quorum_provider,
+                    conn,
+                    locator,
+                    signer,
+                    verification_failures,
+                )
+                .await?
             }
             RpcConnectionConf::HttpFallback { urls } => {
                 let mut builder = FallbackProvider::builder();
@@ -101,8 +118,13 @@ pub trait BuildableWithProvider {
                     .timeout(HTTP_CLIENT_TIMEOUT)
                     .build()
                     .map_err(EthereumProviderConnectionError::from)?;
-                for url in urls {
-                    let http_provider = Http::new_with_client(url.clone(), http_client.clone());
+                for (index, url) in urls.iter().enumerate() {
+                    let http_provider = ReloadableHttp::new(
+                        locator.domain.name(),
+                        index,
+                        url.clone(),
+                        http_client.clone(),
+                    );
                     let metrics_provider = self.wrap_rpc_with_metrics(
                         http_provider,
                         url.clone(),
@@ -114,17 +136,24 @@ pub trait BuildableWithProvider {
                 let fallback_provider = builder.build();
                 let ethereum_fallback_provider = EthereumFallbackProvider::<
                     _,
-                    JsonRpcBlockGetter<PrometheusJsonRpcClient<Http>>,
+                    JsonRpcBlockGetter<PrometheusJsonRpcClient<ReloadableHttp>>,
                 >::new(fallback_provider);
-                self.build(ethereum_fallback_provider, conn, locator, signer)
-                    .await?
+                self.build_verified(
+                    ethereum_fallback_provider,
+                    conn,
+                    locator,
+                    signer,
+                    verification_failures,
+                )
+                .await?
             }
             RpcConnectionConf::Http { url } => {
                 let http_client = Client::builder()
                     .timeout(HTTP_CLIENT_TIMEOUT)
                     .build()
                     .map_err(EthereumProviderConnectionError::from)?;
-                let http_provider = Http::new_with_client(url.clone(), http_client);
+                let http_provider =
+                    ReloadableHttp::new(locator.domain.name(), 0, url.clone(), http_client);
                 let metrics_provider = self.wrap_rpc_with_metrics(
                     http_provider,
                     url.clone(),
@@ -132,14 +161,21 @@ pub trait BuildableWithProvider {
                     &middleware_metrics,
                 );
                 let retrying_http_provider = RetryingProvider::new(metrics_provider, None, None);
-                self.build(retrying_http_provider, conn, locator, signer)
-                    .await?
+                self.build_verified(
+                    retrying_http_provider,
+                    conn,
+                    locator,
+                    signer,
+                    verification_failures,
+                )
+                .await?
             }
             RpcConnectionConf::Ws { url } => {
                 let ws = Ws::connect(url)
                     .await
                     .map_err(EthereumProviderConnectionError::from)?;
-                self.build(ws, conn, locator, signer).await?
+                self.build_verified(ws, conn, locator, signer, verification_failures)
+                    .await?
             }
         })
     }
@@ -180,6 +216,36 @@ pub trait BuildableWithProvider {
         )
     }
 
+    /// Wrap the client with a verifying provider if a light client is
+    /// configured, and then create the provider.
+    async fn build_verified<P>(
+        &self,
+        client: P,
+        conn: &ConnectionConf,
+        locator: &ContractLocator,
+        signer: Option<Signers>,
+        verification_failures: Option<IntCounterVec>,
+    ) -> ChainResult<Self::Output>
+    where
+        P: JsonRpcClient + 'static,
+    {
+        let Some(url) = &conn.light_client_url else {
+            return self.build(client, conn, locator, signer).await;
+        };
+        let http_client = Client::builder()
+            .timeout(HTTP_CLIENT_TIMEOUT)
+            .build()
+            .map_err(EthereumProviderConnectionError::from)?;
+        let light_client = Http::new_with_client(url.clone(), http_client);
+        let verifying_provider = VerifyingProvider::new(
+            client,
+            light_client,
+            locator.domain.name().to_owned(),
+            verification_failures,
+        );
+        self.build(verifying_provider, conn, locator, signer).await
+    }
+
     /// Create the provider, applying any middlewares (e.g. gas oracle, signer) as needed,
     /// and then create the associated trait.
     async fn build<P>(
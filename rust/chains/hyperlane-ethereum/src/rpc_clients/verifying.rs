@@ -0,0 +1,304 @@
+//! A wrapper around a JsonRpcClient that verifies the responses of an
+//! untrusted RPC against a consensus light client, such as Helios, which
+//! checks execution data against the beacon chain.
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use ethers::providers::{Http, JsonRpcClient, ProviderError};
+use ethers::types::{H256, U64};
+use prometheus::IntCounterVec;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use tracing::{instrument, warn};
+
+const BLOCK_NUMBER_METHOD: &str = "eth_blockNumber";
+const BLOCK_BY_NUMBER_METHOD: &str = "eth_getBlockByNumber";
+/// Methods whose response only depends on the state of the chain, which are
+/// verified by making the same request to the light client
+const STATE_METHODS: &[&str] = &[
+    "eth_call",
+    "eth_chainId",
+    "eth_getBalance",
+    "eth_getCode",
+    "eth_getStorageAt",
+    "eth_getTransactionCount",
+];
+/// Methods returning blocks, whose hashes are verified
+const BLOCK_METHODS: &[&str] = &["eth_getBlockByHash", BLOCK_BY_NUMBER_METHOD];
+/// Methods returning data included in blocks, whose block hashes are
+/// verified
+const INCLUDED_METHODS: &[&str] = &[
+    "eth_getLogs",
+    "eth_getTransactionByHash",
+    "eth_getTransactionReceipt",
+];
+
+/// Errors when verifying a response against the light client
+#[derive(Error, Debug)]
+pub enum VerificationError {
+    /// The RPC and the light client responded differently
+    #[error("Response to `{0}` doesn't match the light client's")]
+    ResponseMismatch(String),
+    /// A block in the response isn't the one the light client knows
+    #[error("Block {number} has hash {actual:?} but the light client's is {expected:?}")]
+    BlockHashMismatch {
+        /// The number of the block
+        number: U64,
+        /// The hash of the block according to the light client
+        expected: H256,
+        /// The hash of the block according to the RPC
+        actual: H256,
+    },
+    /// The response has several blocks with the same number
+    #[error("Block {0} has several hashes in the response")]
+    ConflictingBlockHashes(U64),
+    /// The light client doesn't know a block in the response, e.g. because
+    /// it's ahead of its head
+    #[error("Block {0} is unknown to the light client")]
+    UnknownBlock(U64),
+    /// The response couldn't be parsed
+    #[error("Malformed response: {0}")]
+    MalformedResponse(#[from] serde_json::Error),
+    /// The light client request failed
+    #[error(transparent)]
+    LightClient(ProviderError),
+}
+
+impl From<VerificationError> for ProviderError {
+    fn from(src: VerificationError) -> Self {
+        ProviderError::JsonRpcClientError(Box::new(src))
+    }
+}
+
+/// An ethers-rs JsonRpcClient wrapper that verifies the responses of the
+/// security relevant methods against a light client. State queries are made
+/// to both and compared, the blocks of block, log and receipt responses are
+/// checked against the light client's, and the latest block number is capped
+/// by the light client's head. Other methods are passed through.
+#[derive(Debug)]
+pub struct VerifyingProvider<P> {
+    inner: P,
+    light_client: Http,
+    chain: String,
+    verification_failures: Option<IntCounterVec>,
+}
+
+impl<P> VerifyingProvider<P> {
+    /// Wrap `inner`, verifying its responses against `light_client`
+    pub fn new(
+        inner: P,
+        light_client: Http,
+        chain: String,
+        verification_failures: Option<IntCounterVec>,
+    ) -> Self {
+        Self {
+            inner,
+            light_client,
+            chain,
+            verification_failures,
+        }
+    }
+
+    /// The inner RpcClient implementation
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    async fn light_client_request(
+        &self,
+        method: &str,
+        params: &Value,
+    ) -> Result<Value, VerificationError> {
+        request_value(&self.light_client, method, params)
+            .await
+            .map_err(VerificationError::LightClient)
+    }
+
+    /// The lower of the RPC's and the light client's latest block numbers,
+    /// so that later queries are about blocks the light client knows
+    async fn cap_block_number(&self, response: Value) -> Result<Value, VerificationError> {
+        let number: U64 = serde_json::from_value(response)?;
+        let head: U64 = serde_json::from_value(
+            self.light_client_request(BLOCK_NUMBER_METHOD, &Value::Null)
+                .await?,
+        )?;
+        Ok(serde_json::to_value(number.min(head))?)
+    }
+
+    async fn verify_state(
+        &self,
+        method: &str,
+        params: &Value,
+        response: Value,
+    ) -> Result<Value, VerificationError> {
+        if self.light_client_request(method, params).await? != response {
+            return Err(VerificationError::ResponseMismatch(method.to_owned()));
+        }
+        Ok(response)
+    }
+
+    async fn verify_blocks(
+        &self,
+        response: Value,
+        number_key: &str,
+        hash_key: &str,
+    ) -> Result<Value, VerificationError> {
+        for (number, actual) in included_blocks(&response, number_key, hash_key)? {
+            let block = self
+                .light_client_request(BLOCK_BY_NUMBER_METHOD, &json!([number, false]))
+                .await?;
+            let expected: H256 = match block.get("hash") {
+                Some(hash) if !hash.is_null() => serde_json::from_value(hash.clone())?,
+                _ => return Err(VerificationError::UnknownBlock(number)),
+            };
+            if expected != actual {
+                return Err(VerificationError::BlockHashMismatch {
+                    number,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(response)
+    }
+
+    async fn verify(
+        &self,
+        method: &str,
+        params: &Value,
+        response: Value,
+    ) -> Result<Value, VerificationError> {
+        match method {
+            BLOCK_NUMBER_METHOD => self.cap_block_number(response).await,
+            _ if STATE_METHODS.contains(&method) => {
+                self.verify_state(method, params, response).await
+            }
+            _ if BLOCK_METHODS.contains(&method) => {
+                self.verify_blocks(response, "number", "hash").await
+            }
+            _ if INCLUDED_METHODS.contains(&method) => {
+                self.verify_blocks(response, "blockNumber", "blockHash")
+                    .await
+            }
+            _ => Ok(response),
+        }
+    }
+}
+
+/// Make a request whose params and response are untyped
+async fn request_value<C: JsonRpcClient>(
+    client: &C,
+    method: &str,
+    params: &Value,
+) -> Result<Value, ProviderError> {
+    match params {
+        Value::Null => client.request(method, ()).await,
+        _ => client.request(method, params).await,
+    }
+    .map_err(Into::into)
+}
+
+/// The numbers and hashes of the blocks of the objects in `response`, which
+/// is an object or a list of them. Objects that aren't in a block yet are
+/// skipped.
+fn included_blocks(
+    response: &Value,
+    number_key: &str,
+    hash_key: &str,
+) -> Result<BTreeMap<U64, H256>, VerificationError> {
+    let objects = match response {
+        Value::Array(objects) => objects.iter().collect(),
+        object => vec![object],
+    };
+    let mut blocks = BTreeMap::new();
+    for object in objects {
+        let (Some(number), Some(hash)) = (object.get(number_key), object.get(hash_key)) else {
+            continue;
+        };
+        if number.is_null() || hash.is_null() {
+            continue;
+        }
+        let number: U64 = serde_json::from_value(number.clone())?;
+        let hash: H256 = serde_json::from_value(hash.clone())?;
+        if blocks
+            .insert(number, hash)
+            .is_some_and(|other| other != hash)
+        {
+            return Err(VerificationError::ConflictingBlockHashes(number));
+        }
+    }
+    Ok(blocks)
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<P> JsonRpcClient for VerifyingProvider<P>
+where
+    P: JsonRpcClient,
+{
+    type Error = ProviderError;
+
+    #[instrument(skip(self), fields(chain = %self.chain))]
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        let params = serde_json::to_value(params)?;
+        let response = request_value(&self.inner, method, &params).await?;
+        match self.verify(method, &params, response).await {
+            Ok(response) => Ok(serde_json::from_value(response)?),
+            Err(VerificationError::LightClient(err)) => Err(err),
+            Err(err) => {
+                warn!(error = %err, "RPC response failed light client verification");
+                if let Some(failures) = &self.verification_failures {
+                    failures.with_label_values(&[&self.chain, method]).inc();
+                }
+                Err(err.into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> H256 {
+        H256::repeat_byte(byte)
+    }
+
+    #[test]
+    fn test_included_blocks() {
+        let logs = json!([
+            { "blockNumber": "0x1", "blockHash": hash(1) },
+            { "blockNumber": "0x1", "blockHash": hash(1) },
+            { "blockNumber": "0x2", "blockHash": hash(2) },
+        ]);
+        let blocks = included_blocks(&logs, "blockNumber", "blockHash").unwrap();
+        assert_eq!(
+            blocks.into_iter().collect::<Vec<_>>(),
+            vec![(U64::from(1), hash(1)), (U64::from(2), hash(2))]
+        );
+
+        let pending = json!({ "blockNumber": null, "blockHash": null });
+        assert!(included_blocks(&pending, "blockNumber", "blockHash")
+            .unwrap()
+            .is_empty());
+        assert!(included_blocks(&Value::Null, "number", "hash")
+            .unwrap()
+            .is_empty());
+
+        let conflicting = json!([
+            { "number": "0x1", "hash": hash(1) },
+            { "number": "0x1", "hash": hash(2) },
+        ]);
+        assert!(matches!(
+            included_blocks(&conflicting, "number", "hash"),
+            Err(VerificationError::ConflictingBlockHashes(_))
+        ));
+    }
+}
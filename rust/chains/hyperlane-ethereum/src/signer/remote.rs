@@ -0,0 +1,255 @@
+use std::{fmt, time::Duration};
+
+use async_trait::async_trait;
+use ethers::prelude::{Address, Signature};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::utils::hex;
+use ethers_signers::Signer;
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use tokio::time::sleep;
+use tracing::warn;
+use url::Url;
+
+/// The timeout for requests to the signing service
+const REMOTE_SIGNER_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many times a request is retried while the signing service is
+/// unavailable
+const REMOTE_SIGNER_RETRIES: u32 = 3;
+
+/// The backoff before the first retry, doubled for each one after it
+const REMOTE_SIGNER_BACKOFF: Duration = Duration::from_millis(500);
+
+/// A signer that delegates signing to an external signing service with a
+/// Web3Signer-compatible JSON-RPC API, so that the agent never holds the key.
+/// Only messages can be signed, which covers checkpoints and announcements.
+#[derive(Clone)]
+pub struct RemoteSigner {
+    client: Client,
+    url: Url,
+    address: Address,
+    chain_id: u64,
+}
+
+impl fmt::Debug for RemoteSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteSigner")
+            .field("url", &self.url.as_str())
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+/// An error incurred by the remote signer
+#[derive(Debug, Error)]
+pub enum RemoteSignerError {
+    /// The signing service couldn't be reached or failed
+    #[error("Request to remote signer failed: {0}")]
+    Request(#[from] reqwest::Error),
+    /// The signing service returned a JSON-RPC error
+    #[error("Remote signer returned error {code}: {message}")]
+    Rpc {
+        /// The JSON-RPC error code
+        code: i64,
+        /// The JSON-RPC error message
+        message: String,
+    },
+    /// The signing service returned something unexpected
+    #[error("Unexpected response from remote signer: {0}")]
+    UnexpectedResponse(String),
+    /// The signature wasn't made by the key of the signer
+    #[error("Remote signer signed with {signer:?} instead of {expected:?}")]
+    WrongSigner {
+        /// The address of the signer
+        expected: Address,
+        /// The address recovered from the signature
+        signer: Address,
+    },
+    /// The operation isn't supported by remote signers
+    #[error("Remote signers can't sign {0}")]
+    Unsupported(&'static str),
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+impl RemoteSigner {
+    /// Connects to the signing service at `url`. Without an `address`, the
+    /// service has to hold a single key, which is signed with.
+    pub async fn new(url: Url, address: Option<Address>) -> Result<Self, RemoteSignerError> {
+        let client = Client::builder()
+            .timeout(REMOTE_SIGNER_REQUEST_TIMEOUT)
+            .build()?;
+        let mut signer = Self {
+            client,
+            url,
+            address: address.unwrap_or_default(),
+            chain_id: 1,
+        };
+        if address.is_none() {
+            let accounts: Vec<Address> = signer.request("eth_accounts", json!([])).await?;
+            signer.address = match accounts.as_slice() {
+                [address] => *address,
+                _ => {
+                    return Err(RemoteSignerError::UnexpectedResponse(format!(
+                        "expected a single account, got {}; set the address of the key",
+                        accounts.len()
+                    )))
+                }
+            };
+        }
+        Ok(signer)
+    }
+
+    /// Makes a JSON-RPC request, retrying with backoff while the service is
+    /// unavailable
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, RemoteSignerError> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut backoff = REMOTE_SIGNER_BACKOFF;
+        let mut retries = 0;
+        let response = loop {
+            let result = self
+                .client
+                .post(self.url.clone())
+                .json(&body)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            match result {
+                Ok(response) => break response,
+                Err(err) if retries < REMOTE_SIGNER_RETRIES && Self::is_unavailable(&err) => {
+                    warn!(?err, method, retries, "Remote signer unavailable, retrying");
+                    sleep(backoff).await;
+                    backoff *= 2;
+                    retries += 1;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        };
+        let response: RpcResponse<T> = response.json().await?;
+        match (response.result, response.error) {
+            (_, Some(RpcError { code, message })) => Err(RemoteSignerError::Rpc { code, message }),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(RemoteSignerError::UnexpectedResponse(
+                "no result".to_owned(),
+            )),
+        }
+    }
+
+    /// Whether the request may succeed when retried
+    fn is_unavailable(err: &reqwest::Error) -> bool {
+        err.is_connect()
+            || err.is_timeout()
+            || err
+                .status()
+                .map_or(false, |status| status.is_server_error())
+    }
+
+    /// Parses a signature returned by the service
+    fn parse_signature(signature: &str) -> Result<Signature, RemoteSignerError> {
+        let bytes = hex::decode(signature.trim_start_matches("0x"))
+            .map_err(|_| RemoteSignerError::UnexpectedResponse(signature.to_owned()))?;
+        Signature::try_from(bytes.as_slice())
+            .map_err(|_| RemoteSignerError::UnexpectedResponse(signature.to_owned()))
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    type Error = RemoteSignerError;
+
+    /// Signs the message with the EIP-191 prefix, like local wallets
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        let message = message.as_ref();
+        let data = format!("0x{}", hex::encode(message));
+        let signature: String = self
+            .request("eth_sign", json!([self.address, data]))
+            .await?;
+        let signature = Self::parse_signature(&signature)?;
+        // Guards against a service signing with another key
+        let signer = signature
+            .recover(message)
+            .map_err(|err| RemoteSignerError::UnexpectedResponse(err.to_string()))?;
+        if signer != self.address {
+            return Err(RemoteSignerError::WrongSigner {
+                expected: self.address,
+                signer,
+            });
+        }
+        Ok(signature)
+    }
+
+    async fn sign_transaction(&self, _tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        Err(RemoteSignerError::Unsupported("transactions"))
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        _payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        Err(RemoteSignerError::Unsupported("typed data"))
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::signers::LocalWallet;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_signature() {
+        let t = async {
+            let wallet: LocalWallet =
+                "1111111111111111111111111111111111111111111111111111111111111111"
+                    .parse()
+                    .unwrap();
+            let message = &[1u8; 32][..];
+            let signature = wallet.sign_message(message).await.unwrap();
+            let parsed =
+                RemoteSigner::parse_signature(&format!("0x{}", hex::encode(signature.to_vec())))
+                    .unwrap();
+            assert_eq!(parsed, signature);
+            assert_eq!(parsed.recover(message).unwrap(), wallet.address());
+            assert!(RemoteSigner::parse_signature("0x1234").is_err());
+        };
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(t)
+    }
+}
@@ -19,7 +19,7 @@ use ethers_core::{
 use hyperlane_core::{utils::bytes_to_hex, ChainCommunicationError, ChainResult, H256, U256};
 use tracing::{error, info};
 
-use crate::{Middleware, TransactionOverrides};
+use crate::{ChainQuirks, Middleware, TransactionOverrides};
 
 /// An amount of gas to add to the estimated gas
 pub const GAS_ESTIMATE_BUFFER: u32 = 75_000;
@@ -87,6 +87,7 @@ pub(crate) async fn fill_tx_gas_params<M, D>(
     tx: ContractCall<M, D>,
     provider: Arc<M>,
     transaction_overrides: &TransactionOverrides,
+    quirks: &ChainQuirks,
 ) -> ChainResult<ContractCall<M, D>>
 where
     M: Middleware + 'static,
@@ -106,6 +107,11 @@ where
         return Ok(tx.gas_price(gas_price).gas(gas_limit));
     }
 
+    if !quirks.supports_eip1559 {
+        // Contract calls are EIP-1559 transactions by default
+        return Ok(tx.legacy().gas(gas_limit));
+    }
+
     let Ok((base_fee, max_fee, max_priority_fee)) = estimate_eip1559_fees(provider, None).await
     else {
         // Is not EIP 1559 chain
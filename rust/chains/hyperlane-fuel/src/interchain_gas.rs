@@ -1,29 +1,53 @@
 use std::ops::RangeInclusive;
 
 use async_trait::async_trait;
+use fuels::prelude::ContractId;
+use tracing::warn;
 
 use hyperlane_core::{
-    ChainResult, HyperlaneChain, HyperlaneContract, Indexed, Indexer, InterchainGasPaymaster,
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, Indexed, Indexer,
+    InterchainGasPaymaster, SequenceAwareIndexer,
 };
 use hyperlane_core::{HyperlaneDomain, HyperlaneProvider, InterchainGasPayment, LogMeta, H256};
 
+use crate::{conversions::*, ConnectionConf, FuelProvider};
+
+/// The id the IGP logs gas payments with, `gaspaymt` in ASCII.
+const GAS_PAYMENT_LOG_ID: u64 = 0x6761737061796d74;
+/// The size of an encoded gas payment: the message id, followed by the
+/// destination domain, gas amount and payment, each padded to a word.
+const GAS_PAYMENT_SIZE: usize = 32 + 3 * 8;
+
 /// A reference to an IGP contract on some Fuel chain
 #[derive(Debug)]
-pub struct FuelInterchainGasPaymaster {}
+pub struct FuelInterchainGasPaymaster {
+    address: H256,
+    provider: FuelProvider,
+}
+
+impl FuelInterchainGasPaymaster {
+    /// Create a new fuel IGP
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: FuelProvider::new(locator.domain.clone(), conf)?,
+        })
+    }
+}
 
 impl HyperlaneContract for FuelInterchainGasPaymaster {
     fn address(&self) -> H256 {
-        todo!()
+        self.address
     }
 }
 
 impl HyperlaneChain for FuelInterchainGasPaymaster {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        self.provider.domain()
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        self.provider.provider()
     }
 }
 
@@ -31,7 +55,20 @@ impl InterchainGasPaymaster for FuelInterchainGasPaymaster {}
 
 /// Struct that retrieves event data for a Fuel IGP contract
 #[derive(Debug)]
-pub struct FuelInterchainGasPaymasterIndexer {}
+pub struct FuelInterchainGasPaymasterIndexer {
+    contract_id: ContractId,
+    provider: FuelProvider,
+}
+
+impl FuelInterchainGasPaymasterIndexer {
+    /// Create a new fuel IGP indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            contract_id: ContractId::from_h256(&locator.address),
+            provider: FuelProvider::new(locator.domain.clone(), conf)?,
+        })
+    }
+}
 
 #[async_trait]
 impl Indexer<InterchainGasPayment> for FuelInterchainGasPaymasterIndexer {
@@ -39,10 +76,74 @@ impl Indexer<InterchainGasPayment> for FuelInterchainGasPaymasterIndexer {
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
-        todo!()
+        let logs = self
+            .provider
+            .log_data_in_range(&self.contract_id, GAS_PAYMENT_LOG_ID, range)
+            .await?;
+        Ok(logs
+            .into_iter()
+            .filter_map(|(data, meta)| match decode_gas_payment(&data) {
+                Some(payment) => Some((Indexed::new(payment), meta)),
+                None => {
+                    warn!(?meta, "Could not decode gas payment log, skipping");
+                    None
+                }
+            })
+            .collect())
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        todo!()
+        self.provider.latest_block_height().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<InterchainGasPayment> for FuelInterchainGasPaymasterIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.get_finalized_block_number().await?;
+        // No sequence for gas payments.
+        Ok((None, tip))
+    }
+}
+
+/// Decodes a gas payment as logged by the IGP, with the big endian words of
+/// the Fuel ABI.
+fn decode_gas_payment(data: &[u8]) -> Option<InterchainGasPayment> {
+    if data.len() != GAS_PAYMENT_SIZE {
+        return None;
+    }
+    let (message_id, words) = data.split_at(32);
+    let word = |i: usize| -> u64 {
+        let bytes: [u8; 8] = words[i * 8..(i + 1) * 8].try_into().unwrap();
+        u64::from_be_bytes(bytes)
+    };
+    Some(InterchainGasPayment {
+        message_id: H256::from_slice(message_id),
+        destination: word(0).try_into().ok()?,
+        gas_amount: word(1).into(),
+        payment: word(2).into(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::U256;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_gas_payment() {
+        let mut data = H256::repeat_byte(1).as_bytes().to_vec();
+        data.extend_from_slice(&42u64.to_be_bytes());
+        data.extend_from_slice(&100_000u64.to_be_bytes());
+        data.extend_from_slice(&7u64.to_be_bytes());
+
+        let payment = decode_gas_payment(&data).unwrap();
+        assert_eq!(payment.message_id, H256::repeat_byte(1));
+        assert_eq!(payment.destination, 42);
+        assert_eq!(payment.gas_amount, U256::from(100_000));
+        assert_eq!(payment.payment, U256::from(7));
+
+        assert!(decode_gas_payment(&data[1..]).is_none());
     }
 }
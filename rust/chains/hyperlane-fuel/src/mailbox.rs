@@ -1,27 +1,44 @@
-use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use std::num::NonZeroU64;
 use std::ops::RangeInclusive;
 
 use async_trait::async_trait;
-use fuels::prelude::{Bech32ContractId, WalletUnlocked};
+use fuels::{
+    prelude::{Bech32ContractId, ContractId, TxParameters, WalletUnlocked},
+    tx::Receipt,
+    types::{errors::Error as FuelError, transaction::Transaction, Bits256},
+};
 use hyperlane_core::Indexed;
-use tracing::instrument;
+use tracing::{instrument, warn};
 
 use hyperlane_core::{
-    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneAbi,
-    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider,
-    Indexer, LogMeta, Mailbox, TxCostEstimate, TxOutcome, H256, U256,
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, Decode,
+    FixedPointNumber, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
+    HyperlaneProvider, Indexer, LogMeta, Mailbox, SequenceAwareIndexer, TxCostEstimate, TxOutcome,
+    H256, H512, U256,
 };
 
 use crate::{
-    contracts::mailbox::Mailbox as FuelMailboxInner, conversions::*, make_provider, ConnectionConf,
+    contracts::{
+        mailbox::{Mailbox as FuelMailboxInner, Message as FuelMessage},
+        message_recipient::MessageRecipient,
+    },
+    conversions::*,
+    ConnectionConf, FuelProvider,
 };
 
+/// The id the mailbox logs the encoded message of a dispatch with, `dispatch`
+/// in ASCII.
+const DISPATCHED_MESSAGE_LOG_ID: u64 = 0x6469737061746368;
+/// The id the mailbox logs the id of a processed message with, `process` in
+/// ASCII.
+const PROCESSED_MESSAGE_ID_LOG_ID: u64 = 0x70726f63657373;
+
 /// A reference to a Mailbox contract on some Fuel chain
 pub struct FuelMailbox {
     contract: FuelMailboxInner,
     domain: HyperlaneDomain,
+    provider: FuelProvider,
 }
 
 impl FuelMailbox {
@@ -31,15 +48,48 @@ impl FuelMailbox {
         locator: ContractLocator,
         mut wallet: WalletUnlocked,
     ) -> ChainResult<Self> {
-        let provider = make_provider(conf)?;
-        wallet.set_provider(provider);
+        let provider = FuelProvider::new(locator.domain.clone(), conf)?;
+        wallet.set_provider(provider.fuel_provider().clone());
         let address = Bech32ContractId::from_h256(&locator.address);
 
         Ok(FuelMailbox {
             contract: FuelMailboxInner::new(address, wallet),
             domain: locator.domain.clone(),
+            provider,
         })
     }
+
+    /// The call to process `message`. The contracts it calls into have to be
+    /// declared upfront, which are the recipient and its ISM.
+    async fn process_call(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<fuels::programs::contract::ContractCallHandler<()>> {
+        let ism = self.recipient_ism(message.recipient).await?;
+        Ok(self
+            .contract
+            .methods()
+            .process(metadata.to_vec(), FuelMessage::from(message))
+            .set_contract_ids(&[
+                Bech32ContractId::from_h256(&message.recipient),
+                Bech32ContractId::from_h256(&ism),
+            ]))
+    }
+}
+
+impl From<&HyperlaneMessage> for FuelMessage {
+    fn from(message: &HyperlaneMessage) -> Self {
+        Self {
+            version: message.version,
+            nonce: message.nonce,
+            origin: message.origin,
+            sender: Bits256::from_h256(&message.sender),
+            destination: message.destination,
+            recipient: Bits256::from_h256(&message.recipient),
+            body: message.body.clone(),
+        }
+    }
 }
 
 impl HyperlaneContract for FuelMailbox {
@@ -54,7 +104,7 @@ impl HyperlaneChain for FuelMailbox {
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        self.provider.provider()
     }
 }
 
@@ -83,17 +133,44 @@ impl Mailbox for FuelMailbox {
 
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn delivered(&self, id: H256) -> ChainResult<bool> {
-        todo!()
+        self.contract
+            .methods()
+            .delivered(Bits256::from_h256(&id))
+            .simulate()
+            .await
+            .map(|r| r.value)
+            .map_err(ChainCommunicationError::from_other)
     }
 
     #[instrument(err, ret, skip(self))]
     async fn default_ism(&self) -> ChainResult<H256> {
-        todo!()
+        self.contract
+            .methods()
+            .get_default_ism()
+            .simulate()
+            .await
+            .map(|r| r.value.into_h256())
+            .map_err(ChainCommunicationError::from_other)
     }
 
     #[instrument(err, ret, skip(self))]
     async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
-        todo!()
+        let recipient = MessageRecipient::new(
+            Bech32ContractId::from_h256(&recipient),
+            self.contract.wallet(),
+        );
+        match recipient
+            .methods()
+            .interchain_security_module()
+            .simulate()
+            .await
+        {
+            Ok(response) if !response.value.into_h256().is_zero() => Ok(response.value.into_h256()),
+            // Like on other chains, recipients without an ISM of their own, or
+            // that don't specify one at all, use the default ISM
+            Ok(_) | Err(FuelError::RevertTransactionError { .. }) => self.default_ism().await,
+            Err(err) => Err(ChainCommunicationError::from_other(err)),
+        }
     }
 
     #[instrument(err, ret, skip(self))]
@@ -103,7 +180,53 @@ impl Mailbox for FuelMailbox {
         metadata: &[u8],
         tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome> {
-        todo!()
+        let mut call = self.process_call(message, metadata).await?;
+        if let Some(gas_limit) = tx_gas_limit {
+            match u64::try_from(gas_limit) {
+                Ok(gas_limit) => {
+                    call = call.tx_params(TxParameters::default().set_gas_limit(gas_limit));
+                }
+                Err(err) => {
+                    warn!(
+                        ?gas_limit,
+                        err, "Gas limit doesn't fit in a u64, using the default"
+                    );
+                }
+            }
+        }
+        // Build the transaction ourselves rather than using `call`, which
+        // doesn't expose the id of the transaction it submits
+        let tx = call
+            .build_tx()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let transaction_id: H512 = H256::from(<[u8; 32]>::from(tx.id())).into();
+
+        let receipts = match self.provider.fuel_provider().send_transaction(&tx).await {
+            Ok(receipts) => receipts,
+            Err(FuelError::RevertTransactionError {
+                reason, receipts, ..
+            }) => {
+                warn!(?transaction_id, %reason, "Process transaction reverted");
+                return Ok(TxOutcome {
+                    transaction_id,
+                    executed: false,
+                    gas_used: gas_used(&receipts).into(),
+                    gas_price: FixedPointNumber::zero(),
+                });
+            }
+            Err(err) => return Err(ChainCommunicationError::from_other(err)),
+        };
+        let response = call
+            .get_response(receipts)
+            .map_err(ChainCommunicationError::from_other)?;
+
+        Ok(TxOutcome {
+            transaction_id,
+            executed: true,
+            gas_used: response.gas_used.into(),
+            gas_price: FixedPointNumber::zero(),
+        })
     }
 
     #[instrument(err, ret, skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
@@ -112,17 +235,63 @@ impl Mailbox for FuelMailbox {
         message: &HyperlaneMessage,
         metadata: &[u8],
     ) -> ChainResult<TxCostEstimate> {
-        todo!()
+        let response = self
+            .process_call(message, metadata)
+            .await?
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+
+        Ok(TxCostEstimate {
+            gas_limit: response.gas_used.into(),
+            gas_price: FixedPointNumber::zero(),
+            l2_gas_limit: None,
+            resources: vec![],
+        })
     }
 
     fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
-        todo!()
+        // The selector of the `process` function followed by its encoded
+        // arguments, as the call script passes them to the mailbox
+        let call = self
+            .contract
+            .methods()
+            .process(metadata.to_vec(), FuelMessage::from(message))
+            .contract_call;
+        [call.encoded_selector.to_vec(), call.encoded_args.resolve(0)].concat()
     }
 }
 
 /// Struct that retrieves event data for a Fuel Mailbox contract
 #[derive(Debug)]
-pub struct FuelMailboxIndexer {}
+pub struct FuelMailboxIndexer {
+    /// The mailbox to query the message count of. Even read-only calls are
+    /// funded transactions on Fuel, so this requires a wallet.
+    mailbox: Option<FuelMailbox>,
+    contract_id: ContractId,
+    provider: FuelProvider,
+}
+
+impl FuelMailboxIndexer {
+    /// Create a new fuel mailbox indexer. Without a wallet, only deliveries
+    /// can be indexed.
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        wallet: Option<WalletUnlocked>,
+    ) -> ChainResult<Self> {
+        let contract_id = ContractId::from_h256(&locator.address);
+        let provider = FuelProvider::new(locator.domain.clone(), conf)?;
+        let mailbox = wallet
+            .map(|wallet| FuelMailbox::new(conf, locator, wallet))
+            .transpose()?;
+        Ok(Self {
+            mailbox,
+            contract_id,
+            provider,
+        })
+    }
+}
 
 #[async_trait]
 impl Indexer<HyperlaneMessage> for FuelMailboxIndexer {
@@ -130,11 +299,21 @@ impl Indexer<HyperlaneMessage> for FuelMailboxIndexer {
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
-        todo!()
+        let logs = self
+            .provider
+            .log_data_in_range(&self.contract_id, DISPATCHED_MESSAGE_LOG_ID, range)
+            .await?;
+        logs.into_iter()
+            .map(|(data, meta)| {
+                let message = HyperlaneMessage::read_from(&mut data.as_slice())
+                    .map_err(ChainCommunicationError::from_other)?;
+                Ok((message.into(), meta))
+            })
+            .collect()
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        todo!()
+        self.provider.latest_block_height().await
     }
 }
 
@@ -144,21 +323,60 @@ impl Indexer<H256> for FuelMailboxIndexer {
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
-        todo!()
+        let logs = self
+            .provider
+            .log_data_in_range(&self.contract_id, PROCESSED_MESSAGE_ID_LOG_ID, range)
+            .await?;
+        Ok(logs
+            .into_iter()
+            .filter_map(|(data, meta)| match <[u8; 32]>::try_from(data.as_slice()) {
+                Ok(id) => Some((Indexed::new(H256::from(id)), meta)),
+                Err(_) => {
+                    warn!(?meta, "Processed message log is not a message id, skipping");
+                    None
+                }
+            })
+            .collect())
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        todo!()
+        self.provider.latest_block_height().await
     }
 }
 
-struct FuelMailboxAbi;
-
-impl HyperlaneAbi for FuelMailboxAbi {
-    const SELECTOR_SIZE_BYTES: usize = 8;
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for FuelMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
+        // TODO: need to make sure the call and tip are at the same height?
+        let mailbox = self.mailbox.as_ref().ok_or_else(|| {
+            ChainCommunicationError::from_other_str(
+                "Fuel requires a signer to query the message count of the mailbox",
+            )
+        })?;
+        let count = Mailbox::count(mailbox, None).await?;
+        Ok((Some(count), tip))
+    }
+}
 
-    fn fn_map() -> HashMap<Vec<u8>, &'static str> {
-        // Can't support this without Fuels exporting it in the generated code
-        todo!()
+#[async_trait]
+impl SequenceAwareIndexer<H256> for FuelMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<H256>::get_finalized_block_number(self).await?;
+        // No sequence for message deliveries.
+        Ok((None, tip))
     }
 }
+
+/// The gas used by a transaction, as reported by the result receipt of its
+/// script.
+fn gas_used(receipts: &[Receipt]) -> u64 {
+    receipts
+        .iter()
+        .rev()
+        .find_map(|receipt| match receipt {
+            Receipt::ScriptResult { gas_used, .. } => Some(*gas_used),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
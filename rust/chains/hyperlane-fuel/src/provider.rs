@@ -1,40 +1,166 @@
+use std::{ops::RangeInclusive, str::FromStr};
+
 use async_trait::async_trait;
+use fuels::{
+    client::FuelClient,
+    prelude::{Bech32Address, ContractId, Provider, BASE_ASSET_ID},
+};
 
 use hyperlane_core::{
-    BlockInfo, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain, HyperlaneProvider, TxnInfo,
-    H256, U256,
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, LogMeta, TxnInfo, H256, U256,
 };
 
+use crate::{conversions::*, make_provider, ConnectionConf};
+
 /// A wrapper around a fuel provider to get generic blockchain information.
-#[derive(Debug)]
-pub struct FuelProvider {}
+#[derive(Debug, Clone)]
+pub struct FuelProvider {
+    domain: HyperlaneDomain,
+    provider: Provider,
+}
+
+impl FuelProvider {
+    /// Create a new fuel provider
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf) -> ChainResult<Self> {
+        Ok(Self {
+            domain,
+            provider: make_provider(conf)?,
+        })
+    }
+
+    /// The underlying fuels provider, e.g. to connect a wallet to
+    pub fn fuel_provider(&self) -> &Provider {
+        &self.provider
+    }
+
+    fn client(&self) -> &FuelClient {
+        &self.provider.client
+    }
+
+    /// The height of the latest block. Fuel blocks are final once produced.
+    pub async fn latest_block_height(&self) -> ChainResult<u32> {
+        let chain_info = self
+            .client()
+            .chain_info()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        u64::from(chain_info.latest_block.header.height)
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    /// Fetches the data of the `LogData` receipts that `contract` emitted with
+    /// `log_id` in the blocks of `range`, along with their metadata.
+    pub(crate) async fn log_data_in_range(
+        &self,
+        contract: &ContractId,
+        log_id: u64,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Vec<u8>, LogMeta)>> {
+        let mut logs = vec![];
+        for height in range {
+            let block = self
+                .client()
+                .block_by_height(height.into())
+                .await
+                .map_err(ChainCommunicationError::from_other)?
+                .ok_or_else(|| {
+                    ChainCommunicationError::CustomError(format!("Block {height} not found"))
+                })?;
+            let block_hash = parse_h256(&block.id.to_string())?;
+
+            let mut log_index = 0u64;
+            for (transaction_index, transaction) in block.transactions.iter().enumerate() {
+                let transaction_id = transaction.id.to_string();
+                let receipts = self
+                    .client()
+                    .receipts(&transaction_id)
+                    .await
+                    .map_err(ChainCommunicationError::from_other)?;
+                for receipt in receipts {
+                    let matches = receipt.id() == Some(contract) && receipt.rb() == Some(log_id);
+                    if let (true, Some(data)) = (matches, receipt.data()) {
+                        logs.push((
+                            data.to_vec(),
+                            LogMeta {
+                                address: contract.into_h256(),
+                                block_number: height.into(),
+                                block_hash,
+                                transaction_id: parse_h256(&transaction_id)?.into(),
+                                transaction_index: transaction_index as u64,
+                                log_index: log_index.into(),
+                            },
+                        ));
+                    }
+                    log_index += 1;
+                }
+            }
+        }
+        Ok(logs)
+    }
+}
+
+fn parse_h256(hex: &str) -> ChainResult<H256> {
+    H256::from_str(hex).map_err(ChainCommunicationError::from_other)
+}
 
 impl HyperlaneChain for FuelProvider {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        &self.domain
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.clone())
     }
 }
 
 #[async_trait]
 impl HyperlaneProvider for FuelProvider {
     async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
-        todo!()
+        let block = self
+            .client()
+            .block(&format!("{hash:?}"))
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .ok_or_else(|| {
+                ChainCommunicationError::CustomError(format!("Block {hash:?} not found"))
+            })?;
+        Ok(BlockInfo {
+            hash: *hash,
+            timestamp: block
+                .header
+                .time
+                .0
+                .to_unix()
+                .try_into()
+                .map_err(ChainCommunicationError::from_other)?,
+            number: block.header.height.into(),
+        })
     }
 
     async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
-        todo!()
+        Err(ChainCommunicationError::from_other_str(
+            "Fuel does not support fetching transactions yet",
+        ))
     }
 
     async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
-        todo!()
+        self.client()
+            .contract(&format!("{address:?}"))
+            .await
+            .map(|contract| contract.is_some())
+            .map_err(ChainCommunicationError::from_other)
     }
 
     async fn get_balance(&self, address: String) -> ChainResult<U256> {
-        todo!()
+        let address =
+            Bech32Address::from_str(&address).map_err(ChainCommunicationError::from_other)?;
+        self.provider
+            .get_asset_balance(&address, BASE_ASSET_ID)
+            .await
+            .map(Into::into)
+            .map_err(ChainCommunicationError::from_other)
     }
 
     async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
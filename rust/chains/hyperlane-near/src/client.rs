@@ -0,0 +1,224 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hyperlane_core::{ChainResult, H256};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{
+    types::{
+        AccessKey, Account, Block, CallResult, Chunk, ExecutionOutcomeWithId,
+        FinalExecutionOutcome, GasPrice, LightClientProof,
+    },
+    HyperlaneNearError,
+};
+
+/// The cause of errors about blocks that don't exist, e.g. at skipped heights
+const UNKNOWN_BLOCK: &str = "UNKNOWN_BLOCK";
+/// The cause of errors about accounts that don't exist
+const UNKNOWN_ACCOUNT: &str = "UNKNOWN_ACCOUNT";
+
+/// Decodes a base58 block hash, transaction hash or receipt id
+pub(crate) fn decode_hash(hash: &str) -> Result<H256, HyperlaneNearError> {
+    let bytes = bs58::decode(hash).into_vec()?;
+    if bytes.len() != H256::len_bytes() {
+        return Err(HyperlaneNearError::UnexpectedResponse(format!(
+            "hash `{hash}` is not 32 bytes"
+        )));
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+/// A client of the JSON-RPC API of a NEAR node
+#[derive(Debug, Clone)]
+pub(crate) struct NearClient {
+    http: Client,
+    url: Url,
+}
+
+impl NearClient {
+    pub(crate) fn new(url: Url) -> Self {
+        Self {
+            http: Client::new(),
+            url,
+        }
+    }
+
+    async fn rpc<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, HyperlaneNearError> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": "hyperlane",
+            "method": method,
+            "params": params,
+        });
+        let mut response: Value = self
+            .http
+            .post(self.url.clone())
+            .json(&request)
+            .send()
+            .await?
+            .json()
+            .await?;
+        if let Some(error) = response.get("error") {
+            let name = error["cause"]["name"]
+                .as_str()
+                .or_else(|| error["name"].as_str())
+                .unwrap_or_default();
+            let message = match &error["data"] {
+                Value::String(data) => data.clone(),
+                Value::Null => error["message"].as_str().unwrap_or_default().to_owned(),
+                data => data.to_string(),
+            };
+            return Err(HyperlaneNearError::RpcError {
+                name: name.to_owned(),
+                message,
+            });
+        }
+        Ok(serde_json::from_value(response["result"].take())?)
+    }
+
+    /// The latest final block
+    pub(crate) async fn final_block(&self) -> ChainResult<Block> {
+        Ok(self.rpc("block", json!({ "finality": "final" })).await?)
+    }
+
+    /// The block at `height`, unless no block was produced at that height
+    pub(crate) async fn block(&self, height: u64) -> ChainResult<Option<Block>> {
+        match self.rpc("block", json!({ "block_id": height })).await {
+            Ok(block) => Ok(Some(block)),
+            Err(HyperlaneNearError::RpcError { name, .. }) if name == UNKNOWN_BLOCK => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub(crate) async fn chunk(&self, chunk_hash: &str) -> ChainResult<Chunk> {
+        Ok(self.rpc("chunk", json!({ "chunk_id": chunk_hash })).await?)
+    }
+
+    /// The JSON value returned by the view function `method` of the contract
+    /// at `account` when called with `args`, at the latest final block
+    pub(crate) async fn call_function<T: DeserializeOwned>(
+        &self,
+        account: &str,
+        method: &str,
+        args: &Value,
+    ) -> ChainResult<T> {
+        let result: CallResult = self
+            .rpc(
+                "query",
+                json!({
+                    "request_type": "call_function",
+                    "finality": "final",
+                    "account_id": account,
+                    "method_name": method,
+                    "args_base64": BASE64.encode(args.to_string()),
+                }),
+            )
+            .await?;
+        Ok(serde_json::from_slice(&result.result).map_err(HyperlaneNearError::from)?)
+    }
+
+    /// The access key `public_key` of `account`, along with the hash of the
+    /// block it was queried at
+    pub(crate) async fn access_key(
+        &self,
+        account: &str,
+        public_key: &str,
+    ) -> ChainResult<AccessKey> {
+        Ok(self
+            .rpc(
+                "query",
+                json!({
+                    "request_type": "view_access_key",
+                    "finality": "optimistic",
+                    "account_id": account,
+                    "public_key": public_key,
+                }),
+            )
+            .await?)
+    }
+
+    /// The state of `account`, if it exists
+    pub(crate) async fn account(&self, account: &str) -> ChainResult<Option<Account>> {
+        let result = self
+            .rpc(
+                "query",
+                json!({
+                    "request_type": "view_account",
+                    "finality": "final",
+                    "account_id": account,
+                }),
+            )
+            .await;
+        match result {
+            Ok(account) => Ok(Some(account)),
+            Err(HyperlaneNearError::RpcError { name, .. }) if name == UNKNOWN_ACCOUNT => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// The price of gas in the latest block, in yoctoNEAR
+    pub(crate) async fn gas_price(&self) -> ChainResult<u128> {
+        let price: GasPrice = self.rpc("gas_price", json!([null])).await?;
+        Ok(price.gas_price)
+    }
+
+    /// Submits a borsh encoded signed transaction, and waits for it and all
+    /// its receipts to be executed
+    pub(crate) async fn broadcast_tx_commit(
+        &self,
+        transaction: &[u8],
+    ) -> ChainResult<FinalExecutionOutcome> {
+        Ok(self
+            .rpc("broadcast_tx_commit", json!([BASE64.encode(transaction)]))
+            .await?)
+    }
+
+    /// The outcome of converting the transaction `hash` sent by `sender` into
+    /// a receipt, provable at the block `head`
+    pub(crate) async fn transaction_outcome(
+        &self,
+        hash: &str,
+        sender: &str,
+        head: &str,
+    ) -> ChainResult<ExecutionOutcomeWithId> {
+        let proof: LightClientProof = self
+            .rpc(
+                "EXPERIMENTAL_light_client_proof",
+                json!({
+                    "type": "transaction",
+                    "transaction_hash": hash,
+                    "sender_id": sender,
+                    "light_client_head": head,
+                }),
+            )
+            .await?;
+        Ok(proof.outcome_proof)
+    }
+
+    /// The outcome of executing the receipt `receipt_id` by `receiver`,
+    /// provable at the block `head`
+    pub(crate) async fn receipt_outcome(
+        &self,
+        receipt_id: &str,
+        receiver: &str,
+        head: &str,
+    ) -> ChainResult<ExecutionOutcomeWithId> {
+        let proof: LightClientProof = self
+            .rpc(
+                "EXPERIMENTAL_light_client_proof",
+                json!({
+                    "type": "receipt",
+                    "receipt_id": receipt_id,
+                    "receiver_id": receiver,
+                    "light_client_head": head,
+                }),
+            )
+            .await?;
+        Ok(proof.outcome_proof)
+    }
+}
@@ -0,0 +1,38 @@
+use hyperlane_core::ChainCommunicationError;
+
+/// Errors from the crates specific to the hyperlane-near
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneNearError {
+    /// Reqwest error
+    #[error("{0}")]
+    ReqwestError(#[from] reqwest::Error),
+    /// serde_json error
+    #[error("{0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+    /// Borsh serialization error
+    #[error("{0}")]
+    BorshError(#[from] std::io::Error),
+    /// Base58 decoding error
+    #[error("{0}")]
+    Base58Error(#[from] bs58::decode::Error),
+    /// Error returned by the NEAR RPC node
+    #[error("NEAR RPC error {name}: {message}")]
+    RpcError {
+        /// The name of the cause of the error, e.g. `UNKNOWN_BLOCK`
+        name: String,
+        /// The error message
+        message: String,
+    },
+    /// A response that doesn't have the expected shape
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+}
+
+impl From<HyperlaneNearError> for ChainCommunicationError {
+    fn from(value: HyperlaneNearError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
@@ -0,0 +1,14 @@
+//! Implementation of hyperlane for NEAR.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+mod client;
+mod error;
+mod mailbox;
+mod provider;
+mod signers;
+mod trait_builder;
+mod types;
+
+pub use self::{error::*, mailbox::*, provider::*, signers::*, trait_builder::*};
@@ -0,0 +1,328 @@
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{instrument, warn};
+
+use hyperlane_core::{
+    utils::{bytes_to_hex, hex_or_base58_to_h256},
+    ChainCommunicationError, ChainResult, ContractLocator, Decode, FixedPointNumber,
+    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneMessage,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, RawHyperlaneMessage,
+    SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, U256,
+};
+
+use crate::{ConnectionConf, HyperlaneNearError, NearProvider, Signer};
+
+/// The gas attached to `process` calls when no limit is set, which is the
+/// maximum a transaction can attach. Unused gas is refunded.
+const DEFAULT_PROCESS_GAS: u64 = 300_000_000_000_000;
+/// The prefix of logs holding NEP-297 events
+const EVENT_LOG_PREFIX: &str = "EVENT_JSON:";
+/// The standard of the events emitted by the mailbox
+const EVENT_STANDARD: &str = "hyperlane";
+
+/// A NEP-297 event, e.g.
+/// `{"standard":"hyperlane","version":"1.0.0","event":"dispatch","data":{...}}`
+#[derive(Deserialize, Debug)]
+struct Event {
+    standard: String,
+    event: String,
+    data: Value,
+}
+
+/// The data of the hyperlane event `name` in `log`, if it is one
+fn event_data(log: &str, name: &str) -> Option<Value> {
+    let event: Event = serde_json::from_str(log.strip_prefix(EVENT_LOG_PREFIX)?).ok()?;
+    (event.standard == EVENT_STANDARD && event.event == name).then_some(event.data)
+}
+
+/// A reference to a Mailbox contract on some NEAR chain
+#[derive(Debug)]
+pub struct NearMailbox {
+    address: H256,
+    account: String,
+    provider: NearProvider,
+}
+
+impl NearMailbox {
+    /// Create a new NEAR mailbox
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            account: HyperlaneDomainProtocol::Near.fmt_address(locator.address),
+            provider: NearProvider::new(locator.domain.clone(), conf, signer),
+        })
+    }
+
+    /// The JSON arguments of `process`
+    fn process_args(message: &HyperlaneMessage, metadata: &[u8]) -> Value {
+        json!({
+            "metadata": bytes_to_hex(metadata),
+            "message": bytes_to_hex(&RawHyperlaneMessage::from(message)),
+        })
+    }
+
+    /// The account id returned by a view function, as an address
+    async fn view_account(&self, method: &str, args: Value) -> ChainResult<H256> {
+        let account: String = self.provider.view(&self.account, method, args).await?;
+        HyperlaneDomainProtocol::Near.parse_address(&account)
+    }
+}
+
+impl HyperlaneContract for NearMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for NearMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for NearMailbox {
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        assert!(
+            lag.is_none(),
+            "NEAR does not support querying point-in-time"
+        );
+        self.provider.view(&self.account, "nonce", json!({})).await
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        self.provider
+            .view(
+                &self.account,
+                "delivered",
+                json!({ "id": bytes_to_hex(id.as_bytes()) }),
+            )
+            .await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        self.view_account("default_ism", json!({})).await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let recipient = HyperlaneDomainProtocol::Near.fmt_address(recipient);
+        self.view_account("recipient_ism", json!({ "recipient": recipient }))
+            .await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let gas = tx_gas_limit.map_or(DEFAULT_PROCESS_GAS, |limit| limit.as_u64());
+        let (hash, outcome) = self
+            .provider
+            .function_call(
+                &self.account,
+                "process",
+                &Self::process_args(message, metadata),
+                gas,
+            )
+            .await?;
+        let executed = outcome.succeeded();
+        if !executed {
+            warn!(?hash, status = %outcome.status, "NEAR transaction failed");
+        }
+        Ok(TxOutcome {
+            transaction_id: hash.into(),
+            executed,
+            gas_used: outcome.gas_burnt().into(),
+            gas_price: FixedPointNumber::from(self.provider.gas_price().await?),
+        })
+    }
+
+    #[instrument(err, ret, skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn process_estimate_costs(
+        &self,
+        _message: &HyperlaneMessage,
+        _metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        // Function calls can't be simulated, so the attached gas is used as
+        // an upper bound
+        Ok(TxCostEstimate {
+            gas_limit: DEFAULT_PROCESS_GAS.into(),
+            gas_price: FixedPointNumber::from(self.provider.gas_price().await?),
+            l2_gas_limit: None,
+            resources: vec![],
+        })
+    }
+
+    /// The JSON encoded arguments of `process`
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        Self::process_args(message, metadata)
+            .to_string()
+            .into_bytes()
+    }
+}
+
+/// Struct that retrieves dispatched messages and processed message ids from
+/// the NEP-297 events logged by a Mailbox contract on some NEAR chain
+#[derive(Debug)]
+pub struct NearMailboxIndexer {
+    mailbox: NearMailbox,
+}
+
+impl NearMailboxIndexer {
+    /// Create a new NEAR mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            mailbox: NearMailbox::new(conf, locator, None)?,
+        })
+    }
+
+    /// The hex encoded `field` of the data of the `name` events logged in
+    /// the blocks in `range`
+    async fn events_in_range(
+        &self,
+        name: &str,
+        field: &str,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(String, LogMeta)>> {
+        let logs = self
+            .mailbox
+            .provider
+            .logs_in_range(&self.mailbox.account, range)
+            .await?;
+        Ok(logs
+            .into_iter()
+            .filter_map(|(log, meta)| {
+                let data = event_data(&log, name)?;
+                match data[field].as_str() {
+                    Some(value) => Some((value.to_owned(), meta)),
+                    None => {
+                        warn!(?meta, %log, "Event is missing `{field}`, skipping");
+                        None
+                    }
+                }
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for NearMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        let events = self.events_in_range("dispatch", "message", range).await?;
+        events
+            .into_iter()
+            .map(|(message, meta)| {
+                let message = hex::decode(message.trim_start_matches("0x"))
+                    .map_err(ChainCommunicationError::from_other)?;
+                let message = HyperlaneMessage::read_from(&mut message.as_slice())
+                    .map_err(ChainCommunicationError::from_other)?;
+                Ok((message.into(), meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.final_block_height().await
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for NearMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        let events = self.events_in_range("process", "id", range).await?;
+        events
+            .into_iter()
+            .map(|(id, meta)| {
+                let id = hex_or_base58_to_h256(&id).map_err(|e| {
+                    HyperlaneNearError::UnexpectedResponse(format!("invalid message id: {e}"))
+                })?;
+                Ok((Indexed::new(id), meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.final_block_height().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for NearMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
+        let count = Mailbox::count(&self.mailbox, None).await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for NearMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<H256>::get_finalized_block_number(self).await?;
+        // No sequence for message deliveries.
+        Ok((None, tip))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::config::OperationBatchConfig;
+    use url::Url;
+
+    use super::*;
+
+    #[test]
+    fn test_process_calldata() {
+        let domain = HyperlaneDomain::new_test_domain("test");
+        let conf = ConnectionConf::new(
+            Url::parse("https://rpc.testnet.near.org").unwrap(),
+            OperationBatchConfig::default(),
+        );
+        let locator = ContractLocator {
+            domain: &domain,
+            address: H256::zero(),
+        };
+        let mailbox = NearMailbox::new(&conf, locator, None).unwrap();
+        let message = HyperlaneMessage::default();
+
+        assert_eq!(
+            String::from_utf8(mailbox.process_calldata(&message, &[0xaa])).unwrap(),
+            format!(
+                r#"{{"message":"0x{}","metadata":"0xaa"}}"#,
+                hex::encode(RawHyperlaneMessage::from(&message))
+            )
+        );
+    }
+
+    #[test]
+    fn test_event_data() {
+        let log = r#"EVENT_JSON:{"standard":"hyperlane","version":"1.0.0","event":"process","data":{"id":"0x01"}}"#;
+        assert_eq!(event_data(log, "process"), Some(json!({ "id": "0x01" })));
+        assert_eq!(event_data(log, "dispatch"), None);
+        assert_eq!(event_data(&log[1..], "process"), None);
+    }
+}
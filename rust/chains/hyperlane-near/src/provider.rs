@@ -0,0 +1,295 @@
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use borsh::BorshSerialize;
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneDomainProtocol, HyperlaneProvider, LogMeta, TxnInfo, H256, U256,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+use crate::{
+    client::{decode_hash, NearClient},
+    types::{
+        Action, FinalExecutionOutcome, FunctionCallAction, PublicKey, Signature, SignedTransaction,
+        Transaction, EMPTY_CODE_HASH,
+    },
+    ConnectionConf, HyperlaneNearError, Signer,
+};
+
+/// A receipt executed by an account, found in the chunks of a block
+struct IncludedReceipt {
+    receipt_id: String,
+    height: u64,
+    block_hash: H256,
+    index: u64,
+}
+
+/// A wrapper around a NEAR RPC node to get generic blockchain information,
+/// call view functions and send function call transactions.
+#[derive(Debug, Clone)]
+pub struct NearProvider {
+    domain: HyperlaneDomain,
+    client: NearClient,
+    signer: Option<Signer>,
+}
+
+impl NearProvider {
+    /// Create a new NEAR provider
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf, signer: Option<Signer>) -> Self {
+        Self {
+            domain,
+            client: NearClient::new(conf.url.clone()),
+            signer,
+        }
+    }
+
+    fn signer(&self) -> ChainResult<&Signer> {
+        self.signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)
+    }
+
+    /// The height of the latest final block
+    pub(crate) async fn final_block_height(&self) -> ChainResult<u32> {
+        let height = self.client.final_block().await?.header.height;
+        Ok(height
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?)
+    }
+
+    /// The price of gas, in yoctoNEAR
+    pub(crate) async fn gas_price(&self) -> ChainResult<u128> {
+        self.client.gas_price().await
+    }
+
+    /// The JSON value returned by the view function `method` of the contract
+    /// at `account`
+    pub(crate) async fn view<T: DeserializeOwned>(
+        &self,
+        account: &str,
+        method: &str,
+        args: Value,
+    ) -> ChainResult<T> {
+        self.client.call_function(account, method, &args).await
+    }
+
+    /// Calls `method` of the contract at `receiver` with the JSON `args` from
+    /// the signer's account, attaching `gas`, and waits for the transaction
+    /// and its receipts to be executed
+    pub(crate) async fn function_call(
+        &self,
+        receiver: &str,
+        method: &str,
+        args: &Value,
+        gas: u64,
+    ) -> ChainResult<(H256, FinalExecutionOutcome)> {
+        let signer = self.signer()?;
+        let access_key = self
+            .client
+            .access_key(&signer.account_id, &signer.public_key_string())
+            .await?;
+        let transaction = Transaction {
+            signer_id: signer.account_id.clone(),
+            public_key: PublicKey::Ed25519(signer.public_key()),
+            nonce: access_key.nonce + 1,
+            receiver_id: receiver.to_owned(),
+            block_hash: decode_hash(&access_key.block_hash)?.0,
+            actions: vec![Action::FunctionCall(FunctionCallAction {
+                method_name: method.to_owned(),
+                args: args.to_string().into_bytes(),
+                gas,
+                deposit: 0,
+            })],
+        };
+        let hash = H256(
+            Sha256::digest(transaction.try_to_vec().map_err(HyperlaneNearError::from)?).into(),
+        );
+        let signed = SignedTransaction {
+            signature: Signature::Ed25519(signer.sign(&hash)),
+            transaction,
+        };
+        debug!(?hash, receiver, method, "Sending NEAR transaction");
+        let outcome = self
+            .client
+            .broadcast_tx_commit(&signed.try_to_vec().map_err(HyperlaneNearError::from)?)
+            .await?;
+        Ok((hash, outcome))
+    }
+
+    /// The receipts executed by `account` in the blocks in `range`. Receipts
+    /// from other shards are listed in the chunks they are applied in, while
+    /// receipts of transactions from the same shard are applied along with
+    /// the transaction.
+    async fn receipts_in_range(
+        &self,
+        account: &str,
+        range: RangeInclusive<u32>,
+        head: &str,
+    ) -> ChainResult<Vec<IncludedReceipt>> {
+        let mut receipts = vec![];
+        for height in range.map(u64::from) {
+            // Heights can be skipped
+            let Some(block) = self.client.block(height).await? else {
+                continue;
+            };
+            let block_hash = decode_hash(&block.header.hash)?;
+            let mut receipt_ids = vec![];
+            for chunk in block
+                .chunks
+                .iter()
+                .filter(|chunk| chunk.height_included == height)
+            {
+                let chunk = self.client.chunk(&chunk.chunk_hash).await?;
+                receipt_ids.extend(
+                    chunk
+                        .receipts
+                        .into_iter()
+                        .filter(|receipt| {
+                            receipt.receiver_id == account
+                                && receipt.receipt.get("Action").is_some()
+                        })
+                        .map(|receipt| receipt.receipt_id),
+                );
+                for transaction in chunk
+                    .transactions
+                    .iter()
+                    .filter(|transaction| transaction.receiver_id == account)
+                {
+                    let outcome = self
+                        .client
+                        .transaction_outcome(&transaction.hash, &transaction.signer_id, head)
+                        .await?;
+                    let Some(receipt_id) = outcome.outcome.receipt_ids.into_iter().next() else {
+                        continue;
+                    };
+                    let receipt = self
+                        .client
+                        .receipt_outcome(&receipt_id, account, head)
+                        .await?;
+                    // Receipts applied in later blocks come from another
+                    // shard, and are listed in the chunk they're applied in
+                    if receipt.block_hash == outcome.block_hash {
+                        receipt_ids.push(receipt_id);
+                    }
+                }
+            }
+            receipts.extend(
+                receipt_ids
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, receipt_id)| IncludedReceipt {
+                        receipt_id,
+                        height,
+                        block_hash,
+                        index: index as u64,
+                    }),
+            );
+        }
+        Ok(receipts)
+    }
+
+    /// The logs of the successful receipts executed by the contract at
+    /// `account` in the blocks in `range`, along with their metadata
+    pub(crate) async fn logs_in_range(
+        &self,
+        account: &str,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(String, LogMeta)>> {
+        // Outcomes are fetched through light client proofs, which need a
+        // final block after them
+        let head = self.client.final_block().await?.header.hash;
+        let address = HyperlaneDomainProtocol::Near.parse_address(account)?;
+
+        let mut seen = HashSet::new();
+        let mut logs = vec![];
+        for receipt in self.receipts_in_range(account, range, &head).await? {
+            if !seen.insert(receipt.receipt_id.clone()) {
+                continue;
+            }
+            let outcome = self
+                .client
+                .receipt_outcome(&receipt.receipt_id, account, &head)
+                .await?;
+            if !outcome.outcome.succeeded() {
+                continue;
+            }
+            let receipt_id = decode_hash(&receipt.receipt_id)?;
+            logs.extend(
+                outcome
+                    .outcome
+                    .logs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(log_index, log)| {
+                        (
+                            log,
+                            LogMeta {
+                                address,
+                                block_number: receipt.height,
+                                block_hash: receipt.block_hash,
+                                // Logs are emitted by receipts rather than
+                                // transactions
+                                transaction_id: receipt_id.into(),
+                                transaction_index: receipt.index,
+                                log_index: log_index.into(),
+                            },
+                        )
+                    }),
+            );
+        }
+        Ok(logs)
+    }
+}
+
+impl HyperlaneChain for NearProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for NearProvider {
+    async fn get_block_by_hash(&self, _hash: &H256) -> ChainResult<BlockInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "NEAR does not support fetching blocks by hash yet",
+        ))
+    }
+
+    async fn get_txn_by_hash(&self, _hash: &H256) -> ChainResult<TxnInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "NEAR does not support fetching transactions yet",
+        ))
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        let account = HyperlaneDomainProtocol::Near.fmt_address(*address);
+        Ok(self
+            .client
+            .account(&account)
+            .await?
+            .map_or(false, |account| account.code_hash != EMPTY_CODE_HASH))
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        let balance = self
+            .client
+            .account(&address)
+            .await?
+            .map(|account| account.amount)
+            .unwrap_or_default();
+        Ok(balance.into())
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,54 @@
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer as _};
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+
+/// A full access key of a NEAR account, signing its transactions
+#[derive(Debug)]
+pub struct Signer {
+    keypair: Keypair,
+    /// The id of the account the key belongs to
+    pub account_id: String,
+}
+
+impl Signer {
+    /// Create a new NEAR signer from an ed25519 private key and the id of its
+    /// account. Without an account id, the implicit account of the key is
+    /// used.
+    pub fn new(private_key: &H256, account_id: Option<String>) -> ChainResult<Self> {
+        let secret = SecretKey::from_bytes(private_key.as_bytes())
+            .map_err(ChainCommunicationError::from_other)?;
+        let public = PublicKey::from(&secret);
+        Ok(Self {
+            account_id: account_id.unwrap_or_else(|| hex::encode(public.as_bytes())),
+            keypair: Keypair { secret, public },
+        })
+    }
+
+    pub(crate) fn public_key(&self) -> [u8; 32] {
+        self.keypair.public.to_bytes()
+    }
+
+    /// The public key in the format used by the RPC API, e.g. `ed25519:...`
+    pub(crate) fn public_key_string(&self) -> String {
+        format!("ed25519:{}", bs58::encode(self.public_key()).into_string())
+    }
+
+    /// Signs the hash of a transaction
+    pub(crate) fn sign(&self, hash: &H256) -> [u8; 64] {
+        self.keypair.sign(hash.as_bytes()).to_bytes()
+    }
+
+    /// The id of the account
+    pub fn address_string(&self) -> String {
+        self.account_id.clone()
+    }
+}
+
+impl Clone for Signer {
+    fn clone(&self) -> Self {
+        Self {
+            keypair: Keypair::from_bytes(&self.keypair.to_bytes())
+                .expect("keypair bytes are valid"),
+            account_id: self.account_id.clone(),
+        }
+    }
+}
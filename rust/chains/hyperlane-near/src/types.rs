@@ -0,0 +1,241 @@
+//! The borsh encoded transactions submitted to NEAR, and the JSON types
+//! returned by its RPC API.
+
+use borsh::BorshSerialize;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The code hash of accounts without a contract
+pub(crate) const EMPTY_CODE_HASH: &str = "11111111111111111111111111111111";
+
+#[derive(BorshSerialize, Clone, Debug)]
+pub(crate) enum PublicKey {
+    Ed25519([u8; 32]),
+}
+
+#[derive(BorshSerialize, Clone, Debug)]
+pub(crate) enum Signature {
+    Ed25519([u8; 64]),
+}
+
+#[derive(BorshSerialize, Clone, Debug)]
+pub(crate) struct FunctionCallAction {
+    pub method_name: String,
+    /// The JSON encoded arguments
+    pub args: Vec<u8>,
+    pub gas: u64,
+    /// The yoctoNEAR attached to the call
+    pub deposit: u128,
+}
+
+#[derive(BorshSerialize, Clone, Debug)]
+pub(crate) enum Action {
+    // Only listed so that function calls get their variant index
+    #[allow(dead_code)]
+    CreateAccount,
+    #[allow(dead_code)]
+    DeployContract(Vec<u8>),
+    FunctionCall(FunctionCallAction),
+}
+
+#[derive(BorshSerialize, Clone, Debug)]
+pub(crate) struct Transaction {
+    pub signer_id: String,
+    pub public_key: PublicKey,
+    /// The nonce of the access key, which must be greater than the previous
+    /// one
+    pub nonce: u64,
+    pub receiver_id: String,
+    /// The hash of a recent block, after which the transaction expires
+    pub block_hash: [u8; 32],
+    pub actions: Vec<Action>,
+}
+
+#[derive(BorshSerialize, Clone, Debug)]
+pub(crate) struct SignedTransaction {
+    pub transaction: Transaction,
+    pub signature: Signature,
+}
+
+/// Deserializes a u128 that the API encodes as a string
+fn u128_from_str<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct BlockHeader {
+    pub height: u64,
+    /// Base58 encoded
+    pub hash: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ChunkHeader {
+    /// Base58 encoded
+    pub chunk_hash: String,
+    /// The height of the block the chunk was produced for. Shards without a
+    /// new chunk repeat their previous one.
+    pub height_included: u64,
+}
+
+/// The response of `block`
+#[derive(Deserialize, Debug)]
+pub(crate) struct Block {
+    pub header: BlockHeader,
+    pub chunks: Vec<ChunkHeader>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ChunkTransaction {
+    /// Base58 encoded
+    pub hash: String,
+    pub signer_id: String,
+    pub receiver_id: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ChunkReceipt {
+    /// Base58 encoded
+    pub receipt_id: String,
+    pub receiver_id: String,
+    /// Either `{"Action": ...}` or `{"Data": ...}`
+    pub receipt: Value,
+}
+
+/// The response of `chunk`
+#[derive(Deserialize, Debug)]
+pub(crate) struct Chunk {
+    pub transactions: Vec<ChunkTransaction>,
+    /// The receipts from other shards applied in the chunk
+    pub receipts: Vec<ChunkReceipt>,
+}
+
+/// The response of a `call_function` query
+#[derive(Deserialize, Debug)]
+pub(crate) struct CallResult {
+    /// The JSON encoded return value
+    pub result: Vec<u8>,
+}
+
+/// The response of a `view_access_key` query
+#[derive(Deserialize, Debug)]
+pub(crate) struct AccessKey {
+    pub nonce: u64,
+    /// The hash of the block the query was made at, base58 encoded
+    pub block_hash: String,
+}
+
+/// The response of a `view_account` query
+#[derive(Deserialize, Debug)]
+pub(crate) struct Account {
+    /// The balance, in yoctoNEAR
+    #[serde(deserialize_with = "u128_from_str")]
+    pub amount: u128,
+    /// Base58 encoded
+    pub code_hash: String,
+}
+
+/// The response of `gas_price`
+#[derive(Deserialize, Debug)]
+pub(crate) struct GasPrice {
+    /// The price of gas, in yoctoNEAR
+    #[serde(deserialize_with = "u128_from_str")]
+    pub gas_price: u128,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ExecutionOutcome {
+    pub logs: Vec<String>,
+    pub receipt_ids: Vec<String>,
+    pub gas_burnt: u64,
+    /// E.g. `{"SuccessValue": ""}` or `{"Failure": ...}`
+    pub status: Value,
+}
+
+impl ExecutionOutcome {
+    pub(crate) fn succeeded(&self) -> bool {
+        self.status.get("SuccessValue").is_some() || self.status.get("SuccessReceiptId").is_some()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ExecutionOutcomeWithId {
+    /// The hash of the block the outcome was executed in, base58 encoded
+    pub block_hash: String,
+    pub outcome: ExecutionOutcome,
+}
+
+/// The response of `broadcast_tx_commit`
+#[derive(Deserialize, Debug)]
+pub(crate) struct FinalExecutionOutcome {
+    /// E.g. `{"SuccessValue": ""}` or `{"Failure": ...}`
+    pub status: Value,
+    pub transaction_outcome: ExecutionOutcomeWithId,
+    pub receipts_outcome: Vec<ExecutionOutcomeWithId>,
+}
+
+impl FinalExecutionOutcome {
+    pub(crate) fn succeeded(&self) -> bool {
+        self.status.get("SuccessValue").is_some()
+    }
+
+    /// The gas burnt by the transaction and all its receipts
+    pub(crate) fn gas_burnt(&self) -> u64 {
+        self.receipts_outcome
+            .iter()
+            .map(|receipt| receipt.outcome.gas_burnt)
+            .sum::<u64>()
+            + self.transaction_outcome.outcome.gas_burnt
+    }
+}
+
+/// The response of `EXPERIMENTAL_light_client_proof`
+#[derive(Deserialize, Debug)]
+pub(crate) struct LightClientProof {
+    pub outcome_proof: ExecutionOutcomeWithId,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_signed_transaction_borsh() {
+        let transaction = Transaction {
+            signer_id: "a.near".to_owned(),
+            public_key: PublicKey::Ed25519([1; 32]),
+            nonce: 7,
+            receiver_id: "b.near".to_owned(),
+            block_hash: [2; 32],
+            actions: vec![Action::FunctionCall(FunctionCallAction {
+                method_name: "process".to_owned(),
+                args: b"{}".to_vec(),
+                gas: 300_000_000_000_000,
+                deposit: 0,
+            })],
+        };
+        let signed = SignedTransaction {
+            transaction,
+            signature: Signature::Ed25519([3; 64]),
+        };
+
+        // Strings and vectors are prefixed with their u32 length, enums with
+        // their u8 variant index and integers are little endian
+        let expected = [
+            format!("06000000{}", hex::encode("a.near")),
+            format!("00{}", "01".repeat(32)),
+            "0700000000000000".to_owned(),
+            format!("06000000{}", hex::encode("b.near")),
+            "02".repeat(32),
+            "01000000".to_owned(),
+            format!("0207000000{}", hex::encode("process")),
+            "020000007b7d".to_owned(),
+            "00c06e31d9100100".to_owned(),
+            "00".repeat(16),
+            format!("00{}", "03".repeat(64)),
+        ]
+        .concat();
+        assert_eq!(hex::encode(signed.try_to_vec().unwrap()), expected);
+    }
+}
@@ -0,0 +1,127 @@
+//! Tracking of submitted transactions until they reach the configured
+//! commitment, or are dropped because their blockhash expired first.
+
+use std::time::Duration;
+
+use hyperlane_core::{ChainCommunicationError, ChainResult};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, hash::Hash, signature::Signature,
+    transaction::TransactionError,
+};
+use solana_transaction_status::TransactionStatus;
+
+use crate::RpcClientWithDebug;
+
+/// How often the status of a transaction is polled.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The final state of a submitted transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Confirmation {
+    /// The transaction reached the commitment, with the error it failed with
+    /// if any.
+    Landed(Option<TransactionError>),
+    /// The blockhash of the transaction expired before it landed, so it can
+    /// never land. It's safe to re-sign it with a new blockhash.
+    Dropped,
+}
+
+/// Polls the status of the transaction with `signature` until it reaches
+/// `commitment`, or until `recent_blockhash` expired without the transaction
+/// having been processed.
+pub(crate) async fn track_confirmation(
+    rpc: &RpcClientWithDebug,
+    signature: &Signature,
+    recent_blockhash: &Hash,
+    commitment: CommitmentConfig,
+) -> ChainResult<Confirmation> {
+    loop {
+        let status = signature_status(rpc, signature).await?;
+        if let Some(confirmation) = confirmation(status.as_ref(), commitment) {
+            return Ok(confirmation);
+        }
+        if status.is_none()
+            && !rpc
+                .is_blockhash_valid(recent_blockhash, CommitmentConfig::processed())
+                .await
+                .map_err(ChainCommunicationError::from_other)?
+        {
+            // The transaction may have been processed since its status was
+            // polled, right before the blockhash expired.
+            let status = signature_status(rpc, signature).await?;
+            if status.is_none() {
+                return Ok(Confirmation::Dropped);
+            }
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+async fn signature_status(
+    rpc: &RpcClientWithDebug,
+    signature: &Signature,
+) -> ChainResult<Option<TransactionStatus>> {
+    Ok(rpc
+        .get_signature_statuses(&[*signature])
+        .await
+        .map_err(ChainCommunicationError::from_other)?
+        .value
+        .pop()
+        .flatten())
+}
+
+/// The confirmation of a transaction with `status`, or `None` if it hasn't
+/// reached `commitment` yet.
+fn confirmation(
+    status: Option<&TransactionStatus>,
+    commitment: CommitmentConfig,
+) -> Option<Confirmation> {
+    status
+        .filter(|status| status.satisfies_commitment(commitment))
+        .map(|status| Confirmation::Landed(status.err.clone()))
+}
+
+#[cfg(test)]
+mod test {
+    use solana_transaction_status::TransactionConfirmationStatus;
+
+    use super::*;
+
+    fn status(confirmation_status: TransactionConfirmationStatus) -> TransactionStatus {
+        TransactionStatus {
+            slot: 1,
+            confirmations: None,
+            status: Ok(()),
+            err: None,
+            confirmation_status: Some(confirmation_status),
+        }
+    }
+
+    #[test]
+    fn test_confirmation() {
+        let confirmed = status(TransactionConfirmationStatus::Confirmed);
+        assert_eq!(confirmation(None, CommitmentConfig::processed()), None);
+        assert_eq!(
+            confirmation(Some(&confirmed), CommitmentConfig::processed()),
+            Some(Confirmation::Landed(None))
+        );
+        assert_eq!(
+            confirmation(Some(&confirmed), CommitmentConfig::confirmed()),
+            Some(Confirmation::Landed(None))
+        );
+        assert_eq!(
+            confirmation(Some(&confirmed), CommitmentConfig::finalized()),
+            None
+        );
+
+        let failed = TransactionStatus {
+            status: Err(TransactionError::AccountInUse),
+            err: Some(TransactionError::AccountInUse),
+            ..status(TransactionConfirmationStatus::Finalized)
+        };
+        assert_eq!(
+            confirmation(Some(&failed), CommitmentConfig::finalized()),
+            Some(Confirmation::Landed(Some(TransactionError::AccountInUse)))
+        );
+    }
+}
@@ -2,10 +2,10 @@ use async_trait::async_trait;
 use hyperlane_core::{
     config::StrOrIntParseError, ChainCommunicationError, ChainResult, ContractLocator,
     HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer,
-    InterchainGasPaymaster, InterchainGasPayment, LogMeta, SequenceAwareIndexer, H256, H512,
+    InterchainGasPaymaster, InterchainGasPayment, LogMeta, SequenceAwareIndexer, H256, H512, U256,
 };
 use hyperlane_sealevel_igp::{
-    accounts::{GasPaymentAccount, ProgramDataAccount},
+    accounts::{GasPaymentAccount, IgpAccount, OverheadIgpAccount, ProgramDataAccount},
     igp_gas_payment_pda_seeds, igp_program_data_pda_seeds,
 };
 use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
@@ -13,13 +13,18 @@ use solana_client::{
     rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
     rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
 };
-use std::ops::RangeInclusive;
+use std::{
+    ops::RangeInclusive,
+    sync::{Arc, OnceLock},
+};
 use tracing::{info, instrument};
+use url::Url;
 
 use crate::{
-    client::RpcClientWithDebug, utils::get_finalized_block_number, ConnectionConf, SealevelProvider,
+    client::RpcClientWithDebug, subscription::AccountSubscription,
+    utils::get_finalized_block_number, ConnectionConf, SealevelProvider,
 };
-use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
 
 use derive_new::new;
 
@@ -76,6 +81,20 @@ impl SealevelInterchainGasPaymaster {
             })?;
         Ok(account.owner)
     }
+
+    async fn fetch_account_data(&self, pubkey: &Pubkey) -> ChainResult<Vec<u8>> {
+        let account = self
+            .provider
+            .rpc()
+            .get_account_with_commitment(pubkey, CommitmentConfig::finalized())
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str("Could not find IGP account for pubkey")
+            })?;
+        Ok(account.data)
+    }
 }
 
 impl HyperlaneContract for SealevelInterchainGasPaymaster {
@@ -94,13 +113,53 @@ impl HyperlaneChain for SealevelInterchainGasPaymaster {
     }
 }
 
-impl InterchainGasPaymaster for SealevelInterchainGasPaymaster {}
+#[async_trait]
+impl InterchainGasPaymaster for SealevelInterchainGasPaymaster {
+    /// Quotes with the gas oracles of the IGP account, adding the gas
+    /// overhead if the account is an overhead IGP
+    #[instrument(err, skip(self))]
+    async fn quote_gas_payment(
+        &self,
+        destination_domain: u32,
+        gas_amount: U256,
+    ) -> ChainResult<U256> {
+        if gas_amount > U256::from(u64::MAX) {
+            return Err(ChainCommunicationError::from_other_str(
+                "gas amount doesn't fit into a u64",
+            ));
+        }
+        let igp_account = Pubkey::from(<[u8; 32]>::from(self.igp_account));
+        let data = self.fetch_account_data(&igp_account).await?;
+        let quote = match IgpAccount::fetch(&mut data.as_ref()) {
+            Ok(igp) => igp
+                .into_inner()
+                .quote_gas_payment(destination_domain, gas_amount.as_u64()),
+            Err(_) => {
+                let overhead_igp = OverheadIgpAccount::fetch(&mut data.as_ref())
+                    .map_err(ChainCommunicationError::from_other)?
+                    .into_inner();
+                let inner_data = self.fetch_account_data(&overhead_igp.inner).await?;
+                let inner_igp = IgpAccount::fetch(&mut inner_data.as_ref())
+                    .map_err(ChainCommunicationError::from_other)?
+                    .into_inner();
+                overhead_igp.quote_gas_payment(destination_domain, gas_amount.as_u64(), &inner_igp)
+            }
+        };
+        quote
+            .map(U256::from)
+            .map_err(ChainCommunicationError::from_other)
+    }
+}
 
 /// Struct that retrieves event data for a Sealevel IGP contract
 #[derive(Debug)]
 pub struct SealevelInterchainGasPaymasterIndexer {
     rpc_client: RpcClientWithDebug,
     igp: SealevelInterchainGasPaymaster,
+    ws_url: Option<Url>,
+    indexing_commitment: CommitmentConfig,
+    /// Streams gas payments, started on the first fetch
+    gas_payments: OnceLock<AccountSubscription<SealevelGasPayment>>,
 }
 
 /// IGP payment data on Sealevel
@@ -124,7 +183,32 @@ impl SealevelInterchainGasPaymasterIndexer {
         );
 
         let igp = SealevelInterchainGasPaymaster::new(conf, &igp_account_locator).await?;
-        Ok(Self { rpc_client, igp })
+        Ok(Self {
+            rpc_client,
+            igp,
+            ws_url: conf.ws_url.clone(),
+            indexing_commitment: conf.indexing_commitment,
+            gas_payments: OnceLock::new(),
+        })
+    }
+
+    /// The subscription to gas payment accounts, if a websocket url is
+    /// configured.
+    fn gas_payments(&self) -> Option<&AccountSubscription<SealevelGasPayment>> {
+        let ws_url = self.ws_url.as_ref()?;
+        Some(self.gas_payments.get_or_init(|| {
+            let program_id = self.igp.program_id;
+            AccountSubscription::spawn(
+                ws_url.clone(),
+                program_id,
+                self.indexing_commitment,
+                hyperlane_sealevel_igp::accounts::GAS_PAYMENT_DISCRIMINATOR,
+                Arc::new(move |pubkey, account| {
+                    let payment = gas_payment(&program_id, pubkey, account)?;
+                    Ok(payment.and_then(|payment| Some((payment.payment.sequence?, payment))))
+                }),
+            )
+        }))
     }
 
     #[instrument(err, skip(self))]
@@ -160,7 +244,7 @@ impl SealevelInterchainGasPaymasterIndexer {
                     offset: UNIQUE_GAS_PAYMENT_PUBKEY_OFFSET,
                     length: 32, // the length of the `unique_gas_payment_pubkey` field
                 }),
-                commitment: Some(CommitmentConfig::finalized()),
+                commitment: Some(self.indexing_commitment),
                 min_context_slot: None,
             },
             with_context: Some(false),
@@ -202,45 +286,65 @@ impl SealevelInterchainGasPaymasterIndexer {
         // Now that we have the valid gas payment PDA pubkey, we can get the full account data.
         let account = self
             .rpc_client
-            .get_account_with_commitment(&valid_payment_pda_pubkey, CommitmentConfig::finalized())
+            .get_account_with_commitment(&valid_payment_pda_pubkey, self.indexing_commitment)
             .await
             .map_err(ChainCommunicationError::from_other)?
             .value
             .ok_or_else(|| {
                 ChainCommunicationError::from_other_str("Could not find account data")
             })?;
-        let gas_payment_account = GasPaymentAccount::fetch(&mut account.data.as_ref())
-            .map_err(ChainCommunicationError::from_other)?
-            .into_inner();
-
-        tracing::debug!(gas_payment_account=?gas_payment_account, "Found gas payment account");
-
-        let igp_payment = InterchainGasPayment {
-            message_id: gas_payment_account.message_id,
-            destination: gas_payment_account.destination_domain,
-            payment: gas_payment_account.payment.into(),
-            gas_amount: gas_payment_account.gas_amount.into(),
-        };
+        gas_payment(&self.igp.program_id, &valid_payment_pda_pubkey, &account)?
+            .ok_or_else(|| ChainCommunicationError::from_other_str("Invalid gas payment PDA"))
+    }
+}
 
-        Ok(SealevelGasPayment::new(
-            Indexed::new(igp_payment).with_sequence(
-                sequence_number
-                    .try_into()
-                    .map_err(StrOrIntParseError::from)?,
-            ),
-            LogMeta {
-                address: self.igp.program_id.to_bytes().into(),
-                block_number: gas_payment_account.slot,
-                // TODO: get these when building out scraper support.
-                // It's inconvenient to get these :|
-                block_hash: H256::zero(),
-                transaction_id: H512::zero(),
-                transaction_index: 0,
-                log_index: sequence_number.into(),
-            },
-            H256::from(gas_payment_account.igp.to_bytes()),
-        ))
+/// Decodes the gas payment stored in `account`, or `None` if `pubkey` is
+/// not a gas payment PDA of the IGP program.
+fn gas_payment(
+    program_id: &Pubkey,
+    pubkey: &Pubkey,
+    account: &Account,
+) -> ChainResult<Option<SealevelGasPayment>> {
+    let gas_payment_account = GasPaymentAccount::fetch(&mut account.data.as_ref())
+        .map_err(ChainCommunicationError::from_other)?
+        .into_inner();
+    let expected_pubkey = Pubkey::try_find_program_address(
+        igp_gas_payment_pda_seeds!(gas_payment_account.unique_gas_payment_pubkey),
+        program_id,
+    )
+    .map(|(expected_pubkey, _bump)| expected_pubkey);
+    if expected_pubkey != Some(*pubkey) {
+        return Ok(None);
     }
+
+    tracing::debug!(gas_payment_account=?gas_payment_account, "Found gas payment account");
+
+    let sequence_number = gas_payment_account.sequence_number;
+    let igp_payment = InterchainGasPayment {
+        message_id: gas_payment_account.message_id,
+        destination: gas_payment_account.destination_domain,
+        payment: gas_payment_account.payment.into(),
+        gas_amount: gas_payment_account.gas_amount.into(),
+    };
+
+    Ok(Some(SealevelGasPayment::new(
+        Indexed::new(igp_payment).with_sequence(
+            sequence_number
+                .try_into()
+                .map_err(StrOrIntParseError::from)?,
+        ),
+        LogMeta {
+            address: program_id.to_bytes().into(),
+            block_number: gas_payment_account.slot,
+            // TODO: get these when building out scraper support.
+            // It's inconvenient to get these :|
+            block_hash: H256::zero(),
+            transaction_id: H512::zero(),
+            transaction_index: 0,
+            log_index: sequence_number.into(),
+        },
+        H256::from(gas_payment_account.igp.to_bytes()),
+    )))
 }
 
 #[async_trait]
@@ -257,8 +361,15 @@ impl Indexer<InterchainGasPayment> for SealevelInterchainGasPaymasterIndexer {
 
         let payments_capacity = range.end().saturating_sub(*range.start());
         let mut payments = Vec::with_capacity(payments_capacity as usize);
+        let gas_payments = self.gas_payments();
         for nonce in range {
-            if let Ok(sealevel_payment) = self.get_payment_with_sequence(nonce.into()).await {
+            // Payments the subscription missed are looked up over RPC
+            let streamed = gas_payments.and_then(|streamed| streamed.take(nonce));
+            let sealevel_payment = match streamed {
+                Some(payment) => Ok(payment),
+                None => self.get_payment_with_sequence(nonce.into()).await,
+            };
+            if let Ok(sealevel_payment) = sealevel_payment {
                 let igp_account_filter = self.igp.igp_account;
                 if igp_account_filter == sealevel_payment.igp_account_pubkey {
                     payments.push((sealevel_payment.payment, sealevel_payment.log_meta));
@@ -272,7 +383,7 @@ impl Indexer<InterchainGasPayment> for SealevelInterchainGasPaymasterIndexer {
 
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        get_finalized_block_number(&self.rpc_client).await
+        get_finalized_block_number(&self.rpc_client, self.indexing_commitment).await
     }
 }
 
@@ -282,7 +393,7 @@ impl SequenceAwareIndexer<InterchainGasPayment> for SealevelInterchainGasPaymast
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
         let program_data_account = self
             .rpc_client
-            .get_account_with_commitment(&self.igp.data_pda_pubkey, CommitmentConfig::finalized())
+            .get_account_with_commitment(&self.igp.data_pda_pubkey, self.indexing_commitment)
             .await
             .map_err(ChainCommunicationError::from_other)?
             .value
@@ -296,7 +407,7 @@ impl SequenceAwareIndexer<InterchainGasPayment> for SealevelInterchainGasPaymast
             .payment_count
             .try_into()
             .map_err(StrOrIntParseError::from)?;
-        let tip = get_finalized_block_number(&self.rpc_client).await?;
+        let tip = get_finalized_block_number(&self.rpc_client, self.indexing_commitment).await?;
         Ok((Some(payment_count), tip))
     }
 }
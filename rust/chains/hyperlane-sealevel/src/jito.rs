@@ -0,0 +1,156 @@
+//! Submission of transactions as bundles to a Jito block engine, which
+//! forwards them to the current leader if it runs the Jito validator client.
+
+use std::{str::FromStr, time::SystemTime};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hyperlane_core::{ChainCommunicationError, ChainResult};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, system_instruction, transaction::VersionedTransaction,
+};
+use url::Url;
+
+use crate::JitoConf;
+
+/// A client for the bundles JSON-RPC API of a Jito block engine.
+#[derive(Debug, Clone)]
+pub(crate) struct JitoClient {
+    http: reqwest::Client,
+    url: Url,
+    tip_lamports: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+impl JitoClient {
+    pub(crate) fn new(conf: &JitoConf) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url: conf.url.clone(),
+            tip_lamports: conf.tip_lamports,
+        }
+    }
+
+    /// An instruction transferring the tip from `payer` to one of the tip
+    /// accounts of the block engine. Bundles are only considered by the
+    /// block engine if one of their transactions pays a tip.
+    pub(crate) async fn tip_instruction(&self, payer: &Pubkey) -> ChainResult<Instruction> {
+        let tip_accounts: Vec<String> = self.request("getTipAccounts", json!([])).await?;
+        // Spread the tips over the accounts, as every bundle writes to the
+        // account it tips, to reduce contention between bundles.
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|elapsed| elapsed.subsec_nanos() as usize)
+            .unwrap_or_default();
+        let tip_account = tip_accounts
+            .get(nanos % tip_accounts.len().max(1))
+            .ok_or_else(|| ChainCommunicationError::from_other_str("No Jito tip accounts"))
+            .and_then(|account| {
+                Pubkey::from_str(account).map_err(ChainCommunicationError::from_other)
+            })?;
+        Ok(system_instruction::transfer(
+            payer,
+            &tip_account,
+            self.tip_lamports,
+        ))
+    }
+
+    /// Submits the signed `txns` as a bundle, which lands atomically and in
+    /// order. Returns the id of the bundle.
+    pub(crate) async fn send_bundle(&self, txns: &[VersionedTransaction]) -> ChainResult<String> {
+        self.request("sendBundle", bundle_params(txns)?).await
+    }
+
+    async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> ChainResult<T> {
+        let response: JsonRpcResponse<T> = self
+            .http
+            .post(self.url.clone())
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .json()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        into_result(method, response)
+    }
+}
+
+fn bundle_params(txns: &[VersionedTransaction]) -> ChainResult<Value> {
+    let encoded = txns
+        .iter()
+        .map(|txn| {
+            bincode::serialize(txn)
+                .map(|bytes| BASE64.encode(bytes))
+                .map_err(ChainCommunicationError::from_other)
+        })
+        .collect::<ChainResult<Vec<_>>>()?;
+    Ok(json!([encoded, { "encoding": "base64" }]))
+}
+
+fn into_result<T>(method: &str, response: JsonRpcResponse<T>) -> ChainResult<T> {
+    match response {
+        JsonRpcResponse {
+            error: Some(JsonRpcError { code, message }),
+            ..
+        } => Err(ChainCommunicationError::CustomError(format!(
+            "Jito `{method}` request failed with code {code}: {message}"
+        ))),
+        JsonRpcResponse {
+            result: Some(result),
+            ..
+        } => Ok(result),
+        _ => Err(ChainCommunicationError::CustomError(format!(
+            "Jito `{method}` response has no result"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use solana_sdk::transaction::Transaction;
+
+    use super::*;
+
+    #[test]
+    fn test_bundle_params() {
+        let txn = VersionedTransaction::from(Transaction::default());
+        let params = bundle_params(&[txn.clone()]).unwrap();
+        assert_eq!(params[1], json!({ "encoding": "base64" }));
+
+        let encoded = params[0][0].as_str().unwrap();
+        let decoded: VersionedTransaction =
+            bincode::deserialize(&BASE64.decode(encoded).unwrap()).unwrap();
+        assert_eq!(decoded, txn);
+    }
+
+    #[test]
+    fn test_into_result() {
+        let ok: JsonRpcResponse<String> =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","result":"bundle-id","id":1}"#).unwrap();
+        assert_eq!(into_result("sendBundle", ok).unwrap(), "bundle-id");
+
+        let err: JsonRpcResponse<String> = serde_json::from_str(
+            r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"bundle contains an already processed transaction"},"id":1}"#,
+        )
+        .unwrap();
+        assert!(into_result("sendBundle", err).is_err());
+    }
+}
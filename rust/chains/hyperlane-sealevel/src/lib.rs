@@ -18,12 +18,17 @@ pub use validator_announce::*;
 mod error;
 mod interchain_gas;
 mod interchain_security_module;
+mod jito;
+mod lookup_table;
 mod mailbox;
 mod merkle_tree_hook;
 mod multisig_ism;
+mod priority_fee;
 mod provider;
+mod subscription;
 mod trait_builder;
 mod utils;
 
 mod client;
+mod confirmation;
 mod validator_announce;
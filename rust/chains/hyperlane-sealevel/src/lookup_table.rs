@@ -0,0 +1,118 @@
+//! Address lookup tables, which let v0 transactions reference accounts by a
+//! one byte index instead of their 32 byte address. This is what makes room
+//! for process transactions whose ISM or recipient require many accounts.
+
+use hyperlane_core::{ChainCommunicationError, ChainResult};
+use solana_address_lookup_table_program::state::AddressLookupTable;
+use solana_sdk::{
+    address_lookup_table_account::AddressLookupTableAccount,
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use crate::RpcClientWithDebug;
+
+/// Fetches the lookup tables at `addresses`. Tables that don't exist are an
+/// error, as they are most likely a misconfiguration.
+pub(crate) async fn fetch_lookup_tables(
+    rpc: &RpcClientWithDebug,
+    addresses: &[Pubkey],
+    commitment: CommitmentConfig,
+) -> ChainResult<Vec<AddressLookupTableAccount>> {
+    if addresses.is_empty() {
+        return Ok(vec![]);
+    }
+    let accounts = rpc
+        .get_multiple_accounts_with_commitment(addresses, commitment)
+        .await
+        .map_err(ChainCommunicationError::from_other)?
+        .value;
+    addresses
+        .iter()
+        .zip(accounts)
+        .map(|(key, account)| {
+            let account = account.ok_or_else(|| {
+                ChainCommunicationError::CustomError(format!(
+                    "Address lookup table {key} does not exist"
+                ))
+            })?;
+            let table = AddressLookupTable::deserialize(&account.data)
+                .map_err(ChainCommunicationError::from_other)?;
+            Ok(AddressLookupTableAccount {
+                key: *key,
+                addresses: table.addresses.to_vec(),
+            })
+        })
+        .collect()
+}
+
+/// Compiles `instructions` into a message paid for by `payer`. Without lookup
+/// tables this is a legacy message, so that chains without v0 transaction
+/// support keep working.
+pub(crate) fn compile_message(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> ChainResult<VersionedMessage> {
+    if lookup_tables.is_empty() {
+        return Ok(VersionedMessage::Legacy(Message::new_with_blockhash(
+            instructions,
+            Some(payer),
+            &recent_blockhash,
+        )));
+    }
+    v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)
+        .map(VersionedMessage::V0)
+        .map_err(ChainCommunicationError::from_other)
+}
+
+/// A transaction of `message` with placeholder signatures, for simulations
+/// that don't verify signatures.
+pub(crate) fn unsigned_transaction(message: VersionedMessage) -> VersionedTransaction {
+    VersionedTransaction {
+        signatures: vec![Signature::default(); message.header().num_required_signatures as usize],
+        message,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use solana_sdk::instruction::AccountMeta;
+
+    use super::*;
+
+    #[test]
+    fn test_compile_message() {
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let accounts: Vec<Pubkey> = (0..40).map(|_| Pubkey::new_unique()).collect();
+        let instruction = Instruction {
+            program_id: program,
+            data: vec![],
+            accounts: accounts
+                .iter()
+                .map(|account| AccountMeta::new_readonly(*account, false))
+                .collect(),
+        };
+
+        let legacy = compile_message(&payer, &[instruction.clone()], &[], Hash::default()).unwrap();
+        assert!(matches!(legacy, VersionedMessage::Legacy(_)));
+        assert_eq!(legacy.static_account_keys().len(), 42);
+
+        let lookup_table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: accounts,
+        };
+        let v0 = compile_message(&payer, &[instruction], &[lookup_table], Hash::default()).unwrap();
+        assert!(matches!(v0, VersionedMessage::V0(_)));
+        // Only the payer and the invoked program can't be looked up
+        assert_eq!(v0.static_account_keys().len(), 2);
+        assert_eq!(unsigned_transaction(v0).signatures.len(), 1);
+    }
+}
@@ -1,6 +1,15 @@
 #![allow(warnings)] // FIXME remove
 
-use std::{collections::HashMap, num::NonZeroU64, ops::RangeInclusive, str::FromStr as _};
+use std::{
+    collections::HashMap,
+    num::NonZeroU64,
+    ops::RangeInclusive,
+    str::FromStr as _,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, OnceLock,
+    },
+};
 
 use async_trait::async_trait;
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -8,11 +17,11 @@ use jsonrpc_core::futures_util::TryFutureExt;
 use tracing::{debug, info, instrument, warn};
 
 use hyperlane_core::{
-    accumulator::incremental::IncrementalMerkle, BatchItem, ChainCommunicationError, ChainResult,
-    Checkpoint, ContractLocator, Decode as _, Encode as _, FixedPointNumber, HyperlaneAbi,
-    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider,
-    Indexed, Indexer, LogMeta, Mailbox, MerkleTreeHook, SequenceAwareIndexer, TxCostEstimate,
-    TxOutcome, H256, H512, U256,
+    accumulator::incremental::IncrementalMerkle, BatchItem, BatchResult, ChainCommunicationError,
+    ChainResult, Checkpoint, ContractLocator, Decode as _, Encode as _, FixedPointNumber,
+    HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, MempoolCongestion, MerkleTreeHook,
+    SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, H512, U256,
 };
 use hyperlane_sealevel_interchain_security_module_interface::{
     InterchainSecurityModuleInstruction, VerifyInstruction,
@@ -29,22 +38,24 @@ use hyperlane_sealevel_message_recipient_interface::{
 use serializable_account_meta::SimulationReturnData;
 use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
 use solana_client::{
+    client_error::ClientError,
     nonblocking::rpc_client::RpcClient,
     rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig},
     rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
 };
 use solana_sdk::{
     account::Account,
+    address_lookup_table_account::AddressLookupTableAccount,
     commitment_config::CommitmentConfig,
-    compute_budget::ComputeBudgetInstruction,
     hash::Hash,
     instruction::AccountMeta,
     instruction::Instruction,
     message::Message,
+    packet::PACKET_DATA_SIZE,
     pubkey::Pubkey,
     signature::Signature,
     signer::{keypair::Keypair, Signer as _},
-    transaction::{Transaction, VersionedTransaction},
+    transaction::{Transaction, TransactionError, VersionedTransaction},
 };
 use solana_transaction_status::{
     EncodedConfirmedBlock, EncodedTransaction, EncodedTransactionWithStatusMeta,
@@ -52,19 +63,25 @@ use solana_transaction_status::{
     UiTransaction, UiTransactionReturnData, UiTransactionStatusMeta,
 };
 
+use url::Url;
+
 use crate::RpcClientWithDebug;
 use crate::{
+    confirmation::{track_confirmation, Confirmation},
+    jito::JitoClient,
+    lookup_table::{compile_message, fetch_lookup_tables},
+    priority_fee::{estimate_compute_budget, with_compute_budget},
+    subscription::AccountSubscription,
     utils::{get_account_metas, get_finalized_block_number, simulate_instruction},
-    ConnectionConf, SealevelProvider,
+    ConnectionConf, PriorityFeeStrategy, SealevelProvider,
 };
 
 const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+/// The max number of times a process transaction is re-signed with a new
+/// blockhash after it was dropped.
+const MAX_RESUBMISSIONS: usize = 3;
 const SPL_NOOP: &str = "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV";
 
-// The max amount of compute units for a transaction.
-// TODO: consider a more sane value and/or use IGP gas payments instead.
-const PROCESS_COMPUTE_UNITS: u32 = 1_400_000;
-
 /// A reference to a Mailbox contract on some Sealevel chain
 pub struct SealevelMailbox {
     pub(crate) program_id: Pubkey,
@@ -72,6 +89,17 @@ pub struct SealevelMailbox {
     pub(crate) outbox: (Pubkey, u8),
     pub(crate) provider: SealevelProvider,
     payer: Option<Keypair>,
+    priority_fee: PriorityFeeStrategy,
+    jito: Option<JitoClient>,
+    /// Address lookup tables to compile process transactions against
+    lookup_tables: Vec<Pubkey>,
+    /// The commitment process transactions are confirmed at
+    submission_commitment: CommitmentConfig,
+    /// The commitment dispatched messages are indexed at
+    pub(crate) indexing_commitment: CommitmentConfig,
+    /// Process transactions whose blockhash expired before they were
+    /// included, since congestion was last checked.
+    expired_txs: Arc<AtomicU64>,
 }
 
 impl SealevelMailbox {
@@ -98,6 +126,16 @@ impl SealevelMailbox {
             outbox,
             provider,
             payer,
+            priority_fee: conf.priority_fee,
+            jito: conf.jito.as_ref().map(JitoClient::new),
+            lookup_tables: conf
+                .lookup_tables
+                .iter()
+                .map(|address| Pubkey::from(<[u8; 32]>::from(*address)))
+                .collect(),
+            submission_commitment: conf.submission_commitment,
+            indexing_commitment: conf.indexing_commitment,
+            expired_txs: Default::default(),
         })
     }
 
@@ -253,6 +291,255 @@ impl SealevelMailbox {
 
         self.get_account_metas(instruction).await
     }
+
+    /// Builds the `InboxProcess` instruction for `message`, along with all
+    /// the accounts required by the recipient and its ISM.
+    async fn process_instruction(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        payer: &Keypair,
+    ) -> ChainResult<Instruction> {
+        let recipient: Pubkey = message.recipient.0.into();
+        let mut encoded_message = vec![];
+        message.write_to(&mut encoded_message).unwrap();
+
+        let (process_authority_key, _process_authority_bump) = Pubkey::try_find_program_address(
+            mailbox_process_authority_pda_seeds!(&recipient),
+            &self.program_id,
+        )
+        .ok_or_else(|| {
+            ChainCommunicationError::from_other_str(
+                "Could not find program address for process authority",
+            )
+        })?;
+        let (processed_message_account_key, _processed_message_account_bump) =
+            Pubkey::try_find_program_address(
+                mailbox_processed_message_pda_seeds!(message.id()),
+                &self.program_id,
+            )
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "Could not find program address for processed message account",
+                )
+            })?;
+
+        // Get the account metas required for the recipient.InterchainSecurityModule instruction.
+        let ism_getter_account_metas = self.get_ism_getter_account_metas(recipient).await?;
+
+        // Get the recipient ISM.
+        let ism = self
+            .get_recipient_ism(recipient, ism_getter_account_metas.clone())
+            .await?;
+
+        let ixn =
+            hyperlane_sealevel_mailbox::instruction::Instruction::InboxProcess(InboxProcess {
+                metadata: metadata.to_vec(),
+                message: encoded_message.clone(),
+            });
+        let ixn_data = ixn
+            .into_instruction_data()
+            .map_err(ChainCommunicationError::from_other)?;
+
+        // Craft the accounts for the transaction.
+        let mut accounts: Vec<AccountMeta> = vec![
+            AccountMeta::new_readonly(payer.pubkey(), true),
+            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM).unwrap(), false),
+            AccountMeta::new(self.inbox.0, false),
+            AccountMeta::new_readonly(process_authority_key, false),
+            AccountMeta::new(processed_message_account_key, false),
+        ];
+        accounts.extend(ism_getter_account_metas);
+        accounts.extend([
+            AccountMeta::new_readonly(Pubkey::from_str(SPL_NOOP).unwrap(), false),
+            AccountMeta::new_readonly(ism, false),
+        ]);
+
+        // Get the account metas required for the ISM.Verify instruction.
+        let ism_verify_account_metas = self
+            .get_ism_verify_account_metas(ism, metadata.into(), encoded_message)
+            .await?;
+        accounts.extend(ism_verify_account_metas);
+
+        // The recipient.
+        accounts.extend([AccountMeta::new_readonly(recipient, false)]);
+
+        // Get account metas required for the Handle instruction
+        let handle_account_metas = self.get_handle_account_metas(message).await?;
+        accounts.extend(handle_account_metas);
+
+        Ok(Instruction {
+            program_id: self.program_id,
+            data: ixn_data,
+            accounts,
+        })
+    }
+
+    /// Signs and sends a transaction of `instructions`, compiled against the
+    /// configured address lookup tables, and tracks it until it reaches the
+    /// submission commitment. If the transaction is dropped because its
+    /// blockhash expired, it's re-signed with a new blockhash and resubmitted.
+    async fn send_instructions(
+        &self,
+        instructions: Vec<Instruction>,
+        payer: &Keypair,
+    ) -> ChainResult<TxOutcome> {
+        let commitment = self.submission_commitment;
+        let lookup_tables =
+            fetch_lookup_tables(self.rpc(), &self.lookup_tables, commitment).await?;
+        // Only the first attempt is sent as a Jito bundle, as a dropped bundle
+        // most likely means the leaders don't run the Jito validator client.
+        let mut jito = self.jito.as_ref();
+
+        for attempt in 0..=MAX_RESUBMISSIONS {
+            let (txn, recent_blockhash, tipped_jito) = self
+                .sign_transaction(instructions.clone(), payer, &lookup_tables, jito)
+                .await?;
+            let signature = txn.signatures[0];
+            tracing::info!(
+                ?txn,
+                attempt,
+                "Created sealevel transaction to process message"
+            );
+
+            let sent = match tipped_jito {
+                Some(jito) => match jito.send_bundle(&[txn.clone()]).await {
+                    Ok(bundle_id) => {
+                        info!(?signature, bundle_id, "Sent Jito bundle");
+                        Ok(())
+                    }
+                    Err(err) => {
+                        // The tip is paid all the same, but as the signature
+                        // is the same, the transaction can't land twice.
+                        warn!(error=?err, ?signature, "Failed to send Jito bundle, sending transaction to the RPC");
+                        self.send_transaction(&txn, commitment).await
+                    }
+                },
+                None => self.send_transaction(&txn, commitment).await,
+            };
+            match sent {
+                Ok(()) => {}
+                Err(err) if is_blockhash_expiry(&err) => {
+                    self.expired_txs.fetch_add(1, Ordering::Relaxed);
+                    warn!(
+                        ?signature,
+                        attempt,
+                        "Blockhash of process transaction expired before it was sent, resubmitting"
+                    );
+                    jito = None;
+                    continue;
+                }
+                Err(err) => return Err(ChainCommunicationError::from_other(err)),
+            }
+            tracing::info!(?signature, "Sealevel transaction sent");
+
+            match track_confirmation(self.rpc(), &signature, &recent_blockhash, commitment).await? {
+                Confirmation::Landed(err) => {
+                    if let Some(err) = &err {
+                        warn!(?signature, error=?err, "Process transaction failed");
+                    }
+                    return Ok(TxOutcome {
+                        transaction_id: signature.into(),
+                        executed: err.is_none(),
+                        // TODO use correct data upon integrating IGP support
+                        gas_price: U256::zero().try_into()?,
+                        gas_used: U256::zero(),
+                    });
+                }
+                Confirmation::Dropped => {
+                    self.expired_txs.fetch_add(1, Ordering::Relaxed);
+                    warn!(
+                        ?signature,
+                        attempt,
+                        "Process transaction was dropped, resubmitting with a new blockhash"
+                    );
+                    jito = None;
+                }
+            }
+        }
+        Err(ChainCommunicationError::CustomError(format!(
+            "Process transaction was dropped {} times",
+            MAX_RESUBMISSIONS + 1
+        )))
+    }
+
+    /// Signs a transaction of `instructions` with the latest blockhash, which
+    /// is returned along with it. If `jito` is set, the transaction pays a tip,
+    /// and the client is returned if the tip could be added.
+    async fn sign_transaction<'j>(
+        &self,
+        instructions: Vec<Instruction>,
+        payer: &Keypair,
+        lookup_tables: &[AddressLookupTableAccount],
+        jito: Option<&'j JitoClient>,
+    ) -> ChainResult<(VersionedTransaction, Hash, Option<&'j JitoClient>)> {
+        let commitment = self.submission_commitment;
+        let (recent_blockhash, _) = self
+            .rpc()
+            .get_latest_blockhash_with_commitment(commitment)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let mut instructions = with_compute_budget(
+            self.rpc(),
+            &self.priority_fee,
+            instructions,
+            &payer.pubkey(),
+            lookup_tables,
+            &recent_blockhash,
+            commitment,
+        )
+        .await;
+        // The tip is added after the compute budget is estimated, so that the
+        // contended tip accounts don't influence the priority fee. The margin
+        // of the compute unit limit covers the transfer.
+        let jito = match jito {
+            Some(jito) => match jito.tip_instruction(&payer.pubkey()).await {
+                Ok(tip) => {
+                    instructions.push(tip);
+                    Some(jito)
+                }
+                Err(err) => {
+                    warn!(error=?err, "Failed to create Jito tip, sending transaction to the RPC");
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let message = compile_message(
+            &payer.pubkey(),
+            &instructions,
+            lookup_tables,
+            recent_blockhash,
+        )?;
+        let txn = VersionedTransaction::try_new(message, &[payer])
+            .map_err(ChainCommunicationError::from_other)?;
+        let txn_size =
+            bincode::serialized_size(&txn).map_err(ChainCommunicationError::from_other)?;
+        if txn_size > PACKET_DATA_SIZE as u64 {
+            return Err(ChainCommunicationError::CustomError(format!(
+                "Transaction of {txn_size} bytes exceeds the max of {PACKET_DATA_SIZE} bytes"
+            )));
+        }
+        Ok((txn, recent_blockhash, jito))
+    }
+
+    async fn send_transaction(
+        &self,
+        txn: &VersionedTransaction,
+        commitment: CommitmentConfig,
+    ) -> Result<(), ClientError> {
+        self.rpc()
+            .send_transaction_with_config(
+                txn,
+                RpcSendTransactionConfig {
+                    preflight_commitment: Some(commitment.commitment),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map(|_| ())
+    }
 }
 
 impl HyperlaneContract for SealevelMailbox {
@@ -344,149 +631,75 @@ impl Mailbox for SealevelMailbox {
         metadata: &[u8],
         _tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome> {
-        let recipient: Pubkey = message.recipient.0.into();
-        let mut encoded_message = vec![];
-        message.write_to(&mut encoded_message).unwrap();
-
         let payer = self
             .payer
             .as_ref()
             .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?;
+        let instruction = self.process_instruction(message, metadata, payer).await?;
+        self.send_instructions(vec![instruction], payer).await
+    }
 
-        let mut instructions = Vec::with_capacity(2);
-        // Set the compute unit limit.
-        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
-            PROCESS_COMPUTE_UNITS,
-        ));
-
-        // "processed" level commitment does not guarantee finality.
-        // roughly 5% of blocks end up on a dropped fork.
-        // However we don't want this function to be a bottleneck and there already
-        // is retry logic in the agents.
-        let commitment = CommitmentConfig::processed();
-
-        let (process_authority_key, _process_authority_bump) = Pubkey::try_find_program_address(
-            mailbox_process_authority_pda_seeds!(&recipient),
-            &self.program_id,
-        )
-        .ok_or_else(|| {
-            ChainCommunicationError::from_other_str(
-                "Could not find program address for process authority",
-            )
-        })?;
-        let (processed_message_account_key, _processed_message_account_bump) =
-            Pubkey::try_find_program_address(
-                mailbox_processed_message_pda_seeds!(message.id()),
-                &self.program_id,
-            )
-            .ok_or_else(|| {
-                ChainCommunicationError::from_other_str(
-                    "Could not find program address for processed message account",
-                )
-            })?;
-
-        // Get the account metas required for the recipient.InterchainSecurityModule instruction.
-        let ism_getter_account_metas = self.get_ism_getter_account_metas(recipient).await?;
-
-        // Get the recipient ISM.
-        let ism = self
-            .get_recipient_ism(recipient, ism_getter_account_metas.clone())
-            .await?;
-
-        let ixn =
-            hyperlane_sealevel_mailbox::instruction::Instruction::InboxProcess(InboxProcess {
-                metadata: metadata.to_vec(),
-                message: encoded_message.clone(),
-            });
-        let ixn_data = ixn
-            .into_instruction_data()
-            .map_err(ChainCommunicationError::from_other)?;
-
-        // Craft the accounts for the transaction.
-        let mut accounts: Vec<AccountMeta> = vec![
-            AccountMeta::new_readonly(payer.pubkey(), true),
-            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM).unwrap(), false),
-            AccountMeta::new(self.inbox.0, false),
-            AccountMeta::new_readonly(process_authority_key, false),
-            AccountMeta::new(processed_message_account_key, false),
-        ];
-        accounts.extend(ism_getter_account_metas);
-        accounts.extend([
-            AccountMeta::new_readonly(Pubkey::from_str(SPL_NOOP).unwrap(), false),
-            AccountMeta::new_readonly(ism, false),
-        ]);
-
-        // Get the account metas required for the ISM.Verify instruction.
-        let ism_verify_account_metas = self
-            .get_ism_verify_account_metas(ism, metadata.into(), encoded_message)
-            .await?;
-        accounts.extend(ism_verify_account_metas);
-
-        // The recipient.
-        accounts.extend([AccountMeta::new_readonly(recipient, false)]);
-
-        // Get account metas required for the Handle instruction
-        let handle_account_metas = self.get_handle_account_metas(message).await?;
-        accounts.extend(handle_account_metas);
+    /// Processes all messages of the batch in a single transaction, which is
+    /// only likely to fit if address lookup tables are configured.
+    #[instrument(err, ret, skip(self))]
+    async fn process_batch(
+        &self,
+        messages: &[BatchItem<HyperlaneMessage>],
+    ) -> ChainResult<BatchResult> {
+        let payer = self
+            .payer
+            .as_ref()
+            .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?;
+        let mut instructions = Vec::with_capacity(messages.len());
+        for item in messages {
+            instructions.push(
+                self.process_instruction(&item.data, &item.submission_data.metadata, payer)
+                    .await?,
+            );
+        }
+        let outcome = self.send_instructions(instructions, payer).await?;
+        // Transactions are atomic, so either all messages were processed or
+        // none were
+        let executed = vec![outcome.executed; messages.len()];
+        Ok(BatchResult::new(outcome, messages, executed))
+    }
 
-        let inbox_instruction = Instruction {
-            program_id: self.program_id,
-            data: ixn_data,
-            accounts,
-        };
-        instructions.push(inbox_instruction);
+    #[instrument(err, ret, skip(self))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        let payer = self
+            .payer
+            .as_ref()
+            .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?;
+        let instruction = self.process_instruction(message, metadata, payer).await?;
+        let commitment = self.submission_commitment;
+        let lookup_tables =
+            fetch_lookup_tables(self.rpc(), &self.lookup_tables, commitment).await?;
         let (recent_blockhash, _) = self
             .rpc()
             .get_latest_blockhash_with_commitment(commitment)
             .await
             .map_err(ChainCommunicationError::from_other)?;
-
-        let txn = Transaction::new_signed_with_payer(
-            &instructions,
-            Some(&payer.pubkey()),
-            &[payer],
-            recent_blockhash,
-        );
-
-        tracing::info!(?txn, "Created sealevel transaction to process message");
-
-        let signature = self
-            .rpc()
-            .send_and_confirm_transaction(&txn)
-            .await
-            .map_err(ChainCommunicationError::from_other)?;
-
-        tracing::info!(?txn, ?signature, "Sealevel transaction sent");
-
-        let executed = self
-            .rpc()
-            .confirm_transaction_with_commitment(&signature, commitment)
-            .await
-            .map_err(|err| warn!("Failed to confirm inbox process transaction: {}", err))
-            .map(|ctx| ctx.value)
-            .unwrap_or(false);
-        let txid = signature.into();
-
-        Ok(TxOutcome {
-            transaction_id: txid,
-            executed,
-            // TODO use correct data upon integrating IGP support
-            gas_price: U256::zero().try_into()?,
-            gas_used: U256::zero(),
-        })
+        let budget = estimate_compute_budget(
+            self.rpc(),
+            &self.priority_fee,
+            &[instruction],
+            &payer.pubkey(),
+            &lookup_tables,
+            &recent_blockhash,
+            commitment,
+        )
+        .await;
+        Ok(budget.cost_estimate())
     }
 
-    #[instrument(err, ret, skip(self))]
-    async fn process_estimate_costs(
-        &self,
-        _message: &HyperlaneMessage,
-        _metadata: &[u8],
-    ) -> ChainResult<TxCostEstimate> {
-        // TODO use correct data upon integrating IGP support
-        Ok(TxCostEstimate {
-            gas_limit: U256::zero(),
-            gas_price: FixedPointNumber::zero(),
-            l2_gas_limit: None,
+    async fn mempool_congestion(&self) -> ChainResult<MempoolCongestion> {
+        Ok(MempoolCongestion {
+            expired_txs: Some(self.expired_txs.swap(0, Ordering::Relaxed)),
+            ..Default::default()
         })
     }
 
@@ -495,11 +708,23 @@ impl Mailbox for SealevelMailbox {
     }
 }
 
+/// Whether a transaction was rejected because its recent blockhash expired
+/// before it was sent, which is how an overloaded RPC shows.
+fn is_blockhash_expiry(err: &ClientError) -> bool {
+    matches!(
+        err.get_transaction_error(),
+        Some(TransactionError::BlockhashNotFound)
+    )
+}
+
 /// Struct that retrieves event data for a Sealevel Mailbox contract
 #[derive(Debug)]
 pub struct SealevelMailboxIndexer {
     mailbox: SealevelMailbox,
     program_id: Pubkey,
+    ws_url: Option<Url>,
+    /// Streams dispatched messages, started on the first fetch
+    dispatched_messages: OnceLock<AccountSubscription<(Indexed<HyperlaneMessage>, LogMeta)>>,
 }
 
 impl SealevelMailboxIndexer {
@@ -509,6 +734,8 @@ impl SealevelMailboxIndexer {
         Ok(Self {
             program_id,
             mailbox,
+            ws_url: conf.ws_url.clone(),
+            dispatched_messages: OnceLock::new(),
         })
     }
 
@@ -516,10 +743,32 @@ impl SealevelMailboxIndexer {
         &self.mailbox.rpc()
     }
 
+    /// The subscription to dispatched message accounts, if a websocket url
+    /// is configured.
+    fn dispatched_messages(
+        &self,
+    ) -> Option<&AccountSubscription<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        let ws_url = self.ws_url.as_ref()?;
+        Some(self.dispatched_messages.get_or_init(|| {
+            let program_id = self.program_id;
+            AccountSubscription::spawn(
+                ws_url.clone(),
+                program_id,
+                self.mailbox.indexing_commitment,
+                hyperlane_sealevel_mailbox::accounts::DISPATCHED_MESSAGE_DISCRIMINATOR,
+                Arc::new(move |pubkey, account| {
+                    let dispatched_message = dispatched_message(&program_id, pubkey, account)?;
+                    Ok(dispatched_message
+                        .map(|(message, meta)| (message.inner().nonce, (message, meta))))
+                }),
+            )
+        }))
+    }
+
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
         let height = self
             .rpc()
-            .get_block_height()
+            .get_block_height_with_commitment(self.mailbox.indexing_commitment)
             .await
             .map_err(ChainCommunicationError::from_other)?
             .try_into()
@@ -558,7 +807,7 @@ impl SealevelMailboxIndexer {
                     offset: 1 + 8 + 4 + 8, // the offset to get the `unique_message_pubkey` field
                     length: 32,            // the length of the `unique_message_pubkey` field
                 }),
-                commitment: Some(CommitmentConfig::finalized()),
+                commitment: Some(self.mailbox.indexing_commitment),
                 min_context_slot: None,
             },
             with_context: Some(false),
@@ -602,7 +851,7 @@ impl SealevelMailboxIndexer {
             .rpc()
             .get_account_with_commitment(
                 &valid_message_storage_pda_pubkey,
-                CommitmentConfig::finalized(),
+                self.mailbox.indexing_commitment,
             )
             .await
             .map_err(ChainCommunicationError::from_other)?
@@ -610,36 +859,64 @@ impl SealevelMailboxIndexer {
             .ok_or_else(|| {
                 ChainCommunicationError::from_other_str("Could not find account data")
             })?;
-        let dispatched_message_account =
-            DispatchedMessageAccount::fetch(&mut account.data.as_ref())
-                .map_err(ChainCommunicationError::from_other)?
-                .into_inner();
-        let hyperlane_message =
-            HyperlaneMessage::read_from(&mut &dispatched_message_account.encoded_message[..])?;
-
-        Ok((
-            hyperlane_message.into(),
-            LogMeta {
-                address: self.mailbox.program_id.to_bytes().into(),
-                block_number: dispatched_message_account.slot,
-                // TODO: get these when building out scraper support.
-                // It's inconvenient to get these :|
-                block_hash: H256::zero(),
-                transaction_id: H512::zero(),
-                transaction_index: 0,
-                log_index: U256::zero(),
-            },
-        ))
+        dispatched_message(
+            &self.mailbox.program_id,
+            &valid_message_storage_pda_pubkey,
+            &account,
+        )?
+        .ok_or_else(|| ChainCommunicationError::from_other_str("Invalid message storage PDA"))
     }
 }
 
+/// Decodes the dispatched message stored in `account`, or `None` if
+/// `pubkey` is not a message storage PDA of the mailbox.
+fn dispatched_message(
+    program_id: &Pubkey,
+    pubkey: &Pubkey,
+    account: &Account,
+) -> ChainResult<Option<(Indexed<HyperlaneMessage>, LogMeta)>> {
+    let dispatched_message_account = DispatchedMessageAccount::fetch(&mut account.data.as_ref())
+        .map_err(ChainCommunicationError::from_other)?
+        .into_inner();
+    let expected_pubkey = Pubkey::try_find_program_address(
+        mailbox_dispatched_message_pda_seeds!(dispatched_message_account.unique_message_pubkey),
+        program_id,
+    )
+    .map(|(expected_pubkey, _bump)| expected_pubkey);
+    if expected_pubkey != Some(*pubkey) {
+        return Ok(None);
+    }
+    let hyperlane_message =
+        HyperlaneMessage::read_from(&mut &dispatched_message_account.encoded_message[..])?;
+
+    Ok(Some((
+        hyperlane_message.into(),
+        LogMeta {
+            address: program_id.to_bytes().into(),
+            block_number: dispatched_message_account.slot,
+            // TODO: get these when building out scraper support.
+            // It's inconvenient to get these :|
+            block_hash: H256::zero(),
+            transaction_id: H512::zero(),
+            transaction_index: 0,
+            log_index: U256::zero(),
+        },
+    )))
+}
+
 #[async_trait]
 impl SequenceAwareIndexer<HyperlaneMessage> for SealevelMailboxIndexer {
     #[instrument(err, skip(self))]
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
         let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
         // TODO: need to make sure the call and tip are at the same height?
-        let count = Mailbox::count(&self.mailbox, None).await?;
+        let count = self
+            .mailbox
+            .tree_with_commitment(self.mailbox.indexing_commitment)
+            .await?
+            .count()
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
         Ok((Some(count), tip))
     }
 }
@@ -657,14 +934,20 @@ impl Indexer<HyperlaneMessage> for SealevelMailboxIndexer {
 
         let message_capacity = range.end().saturating_sub(*range.start());
         let mut messages = Vec::with_capacity(message_capacity as usize);
+        let dispatched_messages = self.dispatched_messages();
         for nonce in range {
-            messages.push(self.get_message_with_nonce(nonce).await?);
+            // Messages the subscription missed are looked up over RPC
+            let streamed = dispatched_messages.and_then(|streamed| streamed.take(nonce));
+            messages.push(match streamed {
+                Some(message) => message,
+                None => self.get_message_with_nonce(nonce).await?,
+            });
         }
         Ok(messages)
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        get_finalized_block_number(&self.rpc()).await
+        get_finalized_block_number(&self.rpc(), self.mailbox.indexing_commitment).await
     }
 }
 
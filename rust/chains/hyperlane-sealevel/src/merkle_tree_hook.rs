@@ -13,18 +13,15 @@ use tracing::instrument;
 
 use crate::{SealevelMailbox, SealevelMailboxIndexer};
 
-#[async_trait]
-impl MerkleTreeHook for SealevelMailbox {
-    #[instrument(err, ret, skip(self))]
-    async fn tree(&self, lag: Option<NonZeroU64>) -> ChainResult<IncrementalMerkle> {
-        assert!(
-            lag.is_none(),
-            "Sealevel does not support querying point-in-time"
-        );
-
+impl SealevelMailbox {
+    /// The merkle tree of the outbox, as of `commitment`.
+    pub(crate) async fn tree_with_commitment(
+        &self,
+        commitment: CommitmentConfig,
+    ) -> ChainResult<IncrementalMerkle> {
         let outbox_account = self
             .rpc()
-            .get_account_with_commitment(&self.outbox.0, CommitmentConfig::finalized())
+            .get_account_with_commitment(&self.outbox.0, commitment)
             .await
             .map_err(ChainCommunicationError::from_other)?
             .value
@@ -37,6 +34,21 @@ impl MerkleTreeHook for SealevelMailbox {
 
         Ok(outbox.tree)
     }
+}
+
+#[async_trait]
+impl MerkleTreeHook for SealevelMailbox {
+    #[instrument(err, ret, skip(self))]
+    async fn tree(&self, lag: Option<NonZeroU64>) -> ChainResult<IncrementalMerkle> {
+        assert!(
+            lag.is_none(),
+            "Sealevel does not support querying point-in-time"
+        );
+
+        // Checkpoints are only ever signed for finalized trees
+        self.tree_with_commitment(CommitmentConfig::finalized())
+            .await
+    }
 
     #[instrument(err, ret, skip(self))]
     async fn latest_checkpoint(&self, lag: Option<NonZeroU64>) -> ChainResult<Checkpoint> {
@@ -0,0 +1,291 @@
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, FixedPointNumber, ResourceCost, TxCostEstimate,
+    TxResource,
+};
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::{
+    address_lookup_table_account::AddressLookupTableAccount, commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction, hash::Hash, instruction::Instruction, pubkey::Pubkey,
+};
+use tracing::{debug, warn};
+
+use crate::{
+    lookup_table::{compile_message, unsigned_transaction},
+    PriorityFeeStrategy, RpcClientWithDebug,
+};
+
+/// The max amount of compute units for a transaction.
+const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+/// Headroom added to the simulated compute units, in percent, as account
+/// state can change between simulation and execution.
+const COMPUTE_UNITS_MARGIN_PERCENT: u64 = 10;
+/// Compute units consumed by the compute budget instructions themselves.
+const COMPUTE_BUDGET_INSTRUCTIONS_UNITS: u64 = 300;
+/// The base fee of a transaction, charged per signature.
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+/// Compute unit prices are denominated in micro-lamports.
+const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+
+/// The compute budget of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ComputeBudget {
+    /// The max compute units the transaction can consume.
+    pub compute_unit_limit: u32,
+    /// The price of compute units, in micro-lamports, or `None` to not pay a
+    /// priority fee.
+    pub compute_unit_price: Option<u64>,
+}
+
+impl ComputeBudget {
+    /// The cost of a transaction with a single signature and this budget.
+    /// The priority fee is charged for the compute unit limit, whether or
+    /// not the units are consumed.
+    pub(crate) fn cost_estimate(&self) -> TxCostEstimate {
+        let compute_unit_price = FixedPointNumber::from(self.compute_unit_price.unwrap_or(0))
+            / MICRO_LAMPORTS_PER_LAMPORT;
+        TxCostEstimate {
+            gas_limit: self.compute_unit_limit.into(),
+            gas_price: compute_unit_price.clone(),
+            l2_gas_limit: None,
+            resources: vec![
+                ResourceCost::new(
+                    TxResource::ComputeUnits,
+                    self.compute_unit_limit.into(),
+                    compute_unit_price,
+                ),
+                ResourceCost::new(TxResource::Signatures, 1, LAMPORTS_PER_SIGNATURE),
+            ],
+        }
+    }
+}
+
+/// Prepends the compute budget instructions to `instructions`, with the
+/// budget estimated by `estimate_compute_budget`.
+pub(crate) async fn with_compute_budget(
+    rpc: &RpcClientWithDebug,
+    strategy: &PriorityFeeStrategy,
+    instructions: Vec<Instruction>,
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: &Hash,
+    commitment: CommitmentConfig,
+) -> Vec<Instruction> {
+    let ComputeBudget {
+        compute_unit_limit,
+        compute_unit_price,
+    } = estimate_compute_budget(
+        rpc,
+        strategy,
+        &instructions,
+        payer,
+        lookup_tables,
+        recent_blockhash,
+        commitment,
+    )
+    .await;
+
+    let mut budgeted = Vec::with_capacity(instructions.len() + 2);
+    budgeted.push(ComputeBudgetInstruction::set_compute_unit_limit(
+        compute_unit_limit,
+    ));
+    if let Some(price) = compute_unit_price {
+        budgeted.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+    budgeted.extend(instructions);
+    budgeted
+}
+
+/// The compute budget of a transaction of `instructions`. The compute unit
+/// limit is estimated by simulating the transaction, falling back to the max
+/// if the simulation fails, and the compute unit price is chosen according to
+/// `strategy`.
+pub(crate) async fn estimate_compute_budget(
+    rpc: &RpcClientWithDebug,
+    strategy: &PriorityFeeStrategy,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: &Hash,
+    commitment: CommitmentConfig,
+) -> ComputeBudget {
+    let compute_unit_limit = match estimate_compute_units(
+        rpc,
+        instructions,
+        payer,
+        lookup_tables,
+        recent_blockhash,
+        commitment,
+    )
+    .await
+    {
+        Ok(units) => units,
+        Err(err) => {
+            warn!(error=?err, "Failed to estimate compute units, using the max");
+            MAX_COMPUTE_UNITS
+        }
+    };
+    let compute_unit_price = match compute_unit_price(rpc, strategy, instructions).await {
+        Ok(price) => price,
+        Err(err) => {
+            warn!(error=?err, "Failed to get recent prioritization fees, not paying a priority fee");
+            None
+        }
+    };
+    debug!(
+        compute_unit_limit,
+        ?compute_unit_price,
+        "Estimated transaction compute budget"
+    );
+    ComputeBudget {
+        compute_unit_limit,
+        compute_unit_price,
+    }
+}
+
+/// Simulates `instructions` with the max compute unit limit and returns the
+/// units they consumed plus some margin.
+async fn estimate_compute_units(
+    rpc: &RpcClientWithDebug,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: &Hash,
+    commitment: CommitmentConfig,
+) -> ChainResult<u32> {
+    let mut simulated = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+        MAX_COMPUTE_UNITS,
+    )];
+    simulated.extend_from_slice(instructions);
+    let txn = unsigned_transaction(compile_message(
+        payer,
+        &simulated,
+        lookup_tables,
+        *recent_blockhash,
+    )?);
+    let result = rpc
+        .simulate_transaction_with_config(
+            &txn,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(commitment),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(ChainCommunicationError::from_other)?
+        .value;
+    if let Some(err) = result.err {
+        return Err(ChainCommunicationError::from_other(err));
+    }
+    let units_consumed = result.units_consumed.ok_or_else(|| {
+        ChainCommunicationError::from_other_str("Simulation did not report consumed compute units")
+    })?;
+    Ok(compute_unit_limit(units_consumed))
+}
+
+fn compute_unit_limit(units_consumed: u64) -> u32 {
+    let units = (units_consumed + COMPUTE_BUDGET_INSTRUCTIONS_UNITS)
+        * (100 + COMPUTE_UNITS_MARGIN_PERCENT)
+        / 100;
+    units.min(MAX_COMPUTE_UNITS as u64) as u32
+}
+
+/// The compute unit price to pay, in micro-lamports, or `None` to not pay a
+/// priority fee.
+async fn compute_unit_price(
+    rpc: &RpcClientWithDebug,
+    strategy: &PriorityFeeStrategy,
+    instructions: &[Instruction],
+) -> ChainResult<Option<u64>> {
+    match *strategy {
+        PriorityFeeStrategy::None => Ok(None),
+        PriorityFeeStrategy::Constant(price) => Ok(Some(price)),
+        PriorityFeeStrategy::Percentile {
+            percentile,
+            min,
+            max,
+        } => {
+            // Fees are local to the accounts that are written to, so only the
+            // fees of transactions contending for the same accounts matter.
+            let mut writable_accounts: Vec<Pubkey> = instructions
+                .iter()
+                .flat_map(|ixn| ixn.accounts.iter())
+                .filter(|meta| meta.is_writable)
+                .map(|meta| meta.pubkey)
+                .collect();
+            writable_accounts.sort();
+            writable_accounts.dedup();
+
+            let fees: Vec<u64> = rpc
+                .get_recent_prioritization_fees(&writable_accounts)
+                .await
+                .map_err(ChainCommunicationError::from_other)?
+                .into_iter()
+                .map(|fee| fee.prioritization_fee)
+                .collect();
+            // Unlike `clamp`, doesn't panic if `min` is greater than `max`
+            Ok(Some(fee_percentile(fees, percentile).max(min).min(max)))
+        }
+    }
+}
+
+/// Nearest-rank percentile of `fees`, 0 if there are none.
+fn fee_percentile(mut fees: Vec<u64>, percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+    fees.sort_unstable();
+    let rank = (fees.len() * percentile.min(100) as usize + 99) / 100;
+    fees[rank.saturating_sub(1)]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fee_percentile() {
+        let fees = vec![50, 0, 10, 40, 20, 30, 0, 0, 60, 100];
+        assert_eq!(fee_percentile(fees.clone(), 0), 0);
+        assert_eq!(fee_percentile(fees.clone(), 50), 20);
+        assert_eq!(fee_percentile(fees.clone(), 75), 50);
+        assert_eq!(fee_percentile(fees.clone(), 100), 100);
+        assert_eq!(fee_percentile(fees, 200), 100);
+        assert_eq!(fee_percentile(vec![], 50), 0);
+    }
+
+    #[test]
+    fn test_compute_unit_limit() {
+        assert_eq!(compute_unit_limit(100_000), 110_330);
+        assert_eq!(compute_unit_limit(1_300_000), MAX_COMPUTE_UNITS);
+    }
+
+    #[test]
+    fn test_cost_estimate() {
+        let budget = ComputeBudget {
+            compute_unit_limit: 200_000,
+            compute_unit_price: Some(1_000),
+        };
+        let estimate = budget.cost_estimate();
+        // 200 lamports of priority fee and the 5000 lamports base fee
+        assert_eq!(
+            estimate.total_cost().unwrap(),
+            FixedPointNumber::from(5_200)
+        );
+        assert_eq!(
+            estimate.enforceable_gas_limit().unwrap(),
+            5_200_000u64.into()
+        );
+
+        // Without a priority fee, the cost can't be expressed in compute units
+        let budget = ComputeBudget {
+            compute_unit_price: None,
+            ..budget
+        };
+        assert_eq!(
+            budget.cost_estimate().enforceable_gas_limit().unwrap(),
+            200_000u64.into()
+        );
+    }
+}
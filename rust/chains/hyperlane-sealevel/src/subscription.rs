@@ -0,0 +1,217 @@
+//! Streaming of program accounts over a websocket `programSubscribe`
+//! subscription, so that indexers learn about new accounts as soon as they
+//! reach the indexing commitment instead of looking every one of them up
+//! over RPC.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use futures_util::StreamExt;
+use hyperlane_core::{ChainCommunicationError, ChainResult};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::pubsub_client::PubsubClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+use url::Url;
+
+/// How long to wait before resubscribing after the subscription failed.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+/// The max number of accounts held until the indexer asks for them. The
+/// accounts with the lowest sequences are dropped first, as the indexer has
+/// most likely moved past them already.
+const MAX_CACHED_ACCOUNTS: usize = 10_000;
+
+/// Decodes an account streamed by the subscription into its sequence and
+/// value, or `None` if it's not an account the subscriber cares about.
+pub(crate) type AccountDecoder<V> =
+    Arc<dyn Fn(&Pubkey, &Account) -> ChainResult<Option<(u32, V)>> + Send + Sync>;
+
+/// A subscription to the accounts of a program with a given discriminator.
+/// The decoded accounts are cached by sequence until they are taken.
+#[derive(Debug)]
+pub(crate) struct AccountSubscription<V> {
+    cache: Arc<Mutex<SequenceCache<V>>>,
+    task: JoinHandle<()>,
+}
+
+impl<V: Send + 'static> AccountSubscription<V> {
+    /// Subscribes to the accounts of `program_id` whose data starts with
+    /// `discriminator`, after the one byte initialized flag, once they reach
+    /// `commitment`.
+    pub(crate) fn spawn(
+        ws_url: Url,
+        program_id: Pubkey,
+        commitment: CommitmentConfig,
+        discriminator: &[u8],
+        decode: AccountDecoder<V>,
+    ) -> Self {
+        let cache = Arc::new(Mutex::new(SequenceCache::new(MAX_CACHED_ACCOUNTS)));
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp {
+                offset: 1,
+                bytes: MemcmpEncodedBytes::Base64(BASE64.encode(discriminator)),
+                encoding: None,
+            })]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: None,
+                commitment: Some(commitment),
+                min_context_slot: None,
+            },
+            with_context: Some(false),
+        };
+        let task = tokio::spawn(subscribe(ws_url, program_id, config, decode, cache.clone()));
+        Self { cache, task }
+    }
+
+    /// Takes the value with `sequence` if it was streamed.
+    pub(crate) fn take(&self, sequence: u32) -> Option<V> {
+        self.cache
+            .lock()
+            .expect("subscription cache lock poisoned")
+            .take(sequence)
+    }
+}
+
+impl<V> Drop for AccountSubscription<V> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn subscribe<V>(
+    ws_url: Url,
+    program_id: Pubkey,
+    config: RpcProgramAccountsConfig,
+    decode: AccountDecoder<V>,
+    cache: Arc<Mutex<SequenceCache<V>>>,
+) {
+    loop {
+        match stream_accounts(&ws_url, &program_id, &config, &decode, &cache).await {
+            Ok(()) => warn!(%program_id, "Program account subscription closed, resubscribing"),
+            Err(err) => {
+                warn!(%program_id, error=?err, "Program account subscription failed, resubscribing")
+            }
+        }
+        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+    }
+}
+
+async fn stream_accounts<V>(
+    ws_url: &Url,
+    program_id: &Pubkey,
+    config: &RpcProgramAccountsConfig,
+    decode: &AccountDecoder<V>,
+    cache: &Mutex<SequenceCache<V>>,
+) -> ChainResult<()> {
+    let client = PubsubClient::new(ws_url.as_str())
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    let (mut accounts, unsubscribe) = client
+        .program_subscribe(program_id, Some(config.clone()))
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    info!(%program_id, "Subscribed to program accounts");
+
+    while let Some(response) = accounts.next().await {
+        let keyed_account = response.value;
+        let decoded = keyed_account
+            .pubkey
+            .parse::<Pubkey>()
+            .map_err(ChainCommunicationError::from_other)
+            .and_then(|pubkey| {
+                let account = keyed_account.account.decode::<Account>().ok_or_else(|| {
+                    ChainCommunicationError::from_other_str("Could not decode account data")
+                })?;
+                decode(&pubkey, &account)
+            });
+        match decoded {
+            Ok(Some((sequence, value))) => {
+                debug!(%program_id, sequence, "Streamed program account");
+                let gap = cache
+                    .lock()
+                    .expect("subscription cache lock poisoned")
+                    .insert(sequence, value);
+                if let Some(missing) = gap {
+                    // The indexer backfills these over RPC when it gets to them
+                    info!(%program_id, ?missing, "Subscription skipped sequences");
+                }
+            }
+            Ok(None) => {}
+            Err(err) => {
+                warn!(%program_id, pubkey=%keyed_account.pubkey, error=?err, "Failed to decode streamed account")
+            }
+        }
+    }
+    unsubscribe().await;
+    Ok(())
+}
+
+/// Values by sequence, holding at most `capacity` of them.
+#[derive(Debug)]
+struct SequenceCache<V> {
+    values: BTreeMap<u32, V>,
+    capacity: usize,
+    /// The highest sequence inserted so far
+    highest: Option<u32>,
+}
+
+impl<V> SequenceCache<V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            values: BTreeMap::new(),
+            capacity,
+            highest: None,
+        }
+    }
+
+    /// Inserts `value` and returns the range of sequences that were skipped
+    /// since the highest one inserted, if any.
+    fn insert(&mut self, sequence: u32, value: V) -> Option<std::ops::Range<u32>> {
+        self.values.insert(sequence, value);
+        while self.values.len() > self.capacity {
+            self.values.pop_first();
+        }
+        let gap = self
+            .highest
+            .map(|highest| highest.saturating_add(1)..sequence)
+            .filter(|gap| !gap.is_empty());
+        self.highest = self.highest.max(Some(sequence));
+        gap
+    }
+
+    fn take(&mut self, sequence: u32) -> Option<V> {
+        self.values.remove(&sequence)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sequence_cache() {
+        let mut cache = SequenceCache::new(3);
+        assert_eq!(cache.insert(0, "a"), None);
+        assert_eq!(cache.insert(1, "b"), None);
+        // 2 and 3 were skipped
+        assert_eq!(cache.insert(4, "e"), Some(2..4));
+        // Late arrivals don't count as gaps
+        assert_eq!(cache.insert(2, "c"), None);
+        // The lowest sequence was dropped to stay within capacity
+        assert_eq!(cache.take(0), None);
+        assert_eq!(cache.take(1), Some("b"));
+        assert_eq!(cache.take(1), None);
+        assert_eq!(cache.take(2), Some("c"));
+        assert_eq!(cache.take(4), Some("e"));
+    }
+}
@@ -1,4 +1,5 @@
-use hyperlane_core::{config::OperationBatchConfig, ChainCommunicationError};
+use hyperlane_core::{config::OperationBatchConfig, ChainCommunicationError, H256};
+use solana_sdk::commitment_config::CommitmentConfig;
 use url::Url;
 
 /// Sealevel connection configuration
@@ -6,8 +7,55 @@ use url::Url;
 pub struct ConnectionConf {
     /// Fully qualified string to connect to
     pub url: Url,
+    /// Websocket url to stream dispatched messages and gas payments from,
+    /// instead of only polling for them
+    pub ws_url: Option<Url>,
     /// Operation batching configuration
     pub operation_batch: OperationBatchConfig,
+    /// How the priority fee of transactions is chosen
+    pub priority_fee: PriorityFeeStrategy,
+    /// Submit process transactions as Jito bundles, if configured
+    pub jito: Option<JitoConf>,
+    /// Address lookup tables to compile process transactions against. If
+    /// any are configured, v0 transactions are sent instead of legacy ones.
+    pub lookup_tables: Vec<H256>,
+    /// The commitment process transactions must reach before they count as
+    /// landed. Transactions whose blockhash expires first are resubmitted.
+    pub submission_commitment: CommitmentConfig,
+    /// The commitment dispatched messages and gas payments must reach before
+    /// they are indexed
+    pub indexing_commitment: CommitmentConfig,
+}
+
+/// Jito block engine configuration
+#[derive(Debug, Clone)]
+pub struct JitoConf {
+    /// The bundles endpoint of the block engine, e.g.
+    /// `https://mainnet.block-engine.jito.wtf/api/v1/bundles`
+    pub url: Url,
+    /// The tip paid to the Jito validators for each bundle, in lamports
+    pub tip_lamports: u64,
+}
+
+/// How the compute unit price, i.e. the priority fee, of transactions is chosen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PriorityFeeStrategy {
+    /// Don't pay a priority fee
+    #[default]
+    None,
+    /// Pay a constant compute unit price, in micro-lamports
+    Constant(u64),
+    /// Pay a percentile of the compute unit prices recently paid by
+    /// transactions writing to the same accounts, clamped to `[min, max]`
+    /// micro-lamports
+    Percentile {
+        /// The percentile, between 0 and 100
+        percentile: u8,
+        /// The minimum compute unit price
+        min: u64,
+        /// The maximum compute unit price
+        max: u64,
+    },
 }
 
 /// An error type when parsing a connection configuration.
@@ -81,9 +81,12 @@ pub async fn get_account_metas(
     Ok(account_metas)
 }
 
-pub async fn get_finalized_block_number(rpc_client: &RpcClientWithDebug) -> ChainResult<u32> {
+pub async fn get_finalized_block_number(
+    rpc_client: &RpcClientWithDebug,
+    commitment: CommitmentConfig,
+) -> ChainResult<u32> {
     let height = rpc_client
-        .get_block_height()
+        .get_block_height_with_commitment(commitment)
         .await
         .map_err(ChainCommunicationError::from_other)?
         .try_into()
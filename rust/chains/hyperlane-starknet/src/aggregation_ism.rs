@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use starknet::macros::selector;
+use tracing::instrument;
+
+use hyperlane_core::{
+    AggregationIsm, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, H256,
+};
+
+use crate::{
+    conversions::{encode_message, FeltReader},
+    ConnectionConf, StarknetProvider,
+};
+
+/// A reference to a AggregationIsm contract on some Starknet chain
+#[derive(Debug)]
+pub struct StarknetAggregationIsm {
+    address: H256,
+    provider: StarknetProvider,
+}
+
+impl StarknetAggregationIsm {
+    /// Create a new Starknet aggregation ISM
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: StarknetProvider::new(locator.domain.clone(), conf, None),
+        })
+    }
+}
+
+impl HyperlaneContract for StarknetAggregationIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetAggregationIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl AggregationIsm for StarknetAggregationIsm {
+    #[instrument(err, skip(self))]
+    async fn modules_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        let result = self
+            .provider
+            .call(
+                &self.address,
+                selector!("modules_and_threshold"),
+                encode_message(message),
+            )
+            .await?;
+        let mut reader = FeltReader::new(&result);
+        let modules = (0..reader.array_len()?)
+            .map(|_| reader.address())
+            .collect::<ChainResult<Vec<_>>>()?;
+        Ok((modules, reader.u8()?))
+    }
+}
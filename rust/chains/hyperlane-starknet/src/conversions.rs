@@ -0,0 +1,242 @@
+//! Conversions between Hyperlane types and their Cairo serialization, which
+//! is a sequence of field elements.
+
+use hyperlane_core::{ChainResult, HyperlaneMessage, H160, H256, U256};
+use starknet::core::types::FieldElement;
+
+use crate::HyperlaneStarknetError;
+
+/// The number of bytes in a word of a Cairo `Bytes` value
+const BYTES_WORD_SIZE: usize = 16;
+/// The max number of bytes a short string felt holds
+const SHORT_STRING_SIZE: usize = 31;
+
+/// Converts a 32 byte value into a field element. Fails for values beyond the
+/// field's prime, which can't be Starknet addresses.
+pub(crate) fn h256_to_felt(value: &H256) -> ChainResult<FieldElement> {
+    Ok(FieldElement::from_bytes_be(&value.0).map_err(HyperlaneStarknetError::from)?)
+}
+
+/// Converts a field element into its 32 byte big endian representation
+pub(crate) fn felt_to_h256(value: &FieldElement) -> H256 {
+    H256::from(value.to_bytes_be())
+}
+
+/// The low and high 128 bits of a `u256`, which is how Cairo serializes it
+pub(crate) fn h256_to_u256_felts(value: &H256) -> [FieldElement; 2] {
+    let (high, low) = value.0.split_at(16);
+    [word_to_felt(low), word_to_felt(high)]
+}
+
+fn word_to_felt(word: &[u8]) -> FieldElement {
+    let mut bytes = [0u8; 32];
+    bytes[32 - word.len()..].copy_from_slice(word);
+    FieldElement::from_bytes_be(&bytes).expect("values of at most 31 bytes fit in a felt")
+}
+
+fn felt_to_u128(value: &FieldElement) -> ChainResult<u128> {
+    let bytes = value.to_bytes_be();
+    let (high, low) = bytes.split_at(16);
+    if high.iter().any(|byte| *byte != 0) {
+        return Err(HyperlaneStarknetError::UnexpectedCallResult("felt exceeds 128 bits").into());
+    }
+    Ok(u128::from_be_bytes(low.try_into().unwrap()))
+}
+
+/// Serializes `bytes` as a Cairo `Bytes` value: the number of bytes, followed
+/// by the array of 16 byte words. A trailing partial word holds its bytes in
+/// its lowest positions.
+pub(crate) fn encode_bytes(bytes: &[u8]) -> Vec<FieldElement> {
+    let words: Vec<FieldElement> = bytes.chunks(BYTES_WORD_SIZE).map(word_to_felt).collect();
+    let mut encoded = Vec::with_capacity(words.len() + 2);
+    encoded.push(FieldElement::from(bytes.len() as u64));
+    encoded.push(FieldElement::from(words.len() as u64));
+    encoded.extend(words);
+    encoded
+}
+
+/// Serializes `message` as a Cairo `Message` struct
+pub(crate) fn encode_message(message: &HyperlaneMessage) -> Vec<FieldElement> {
+    let mut encoded = vec![
+        FieldElement::from(message.version),
+        FieldElement::from(message.nonce),
+        FieldElement::from(message.origin),
+    ];
+    encoded.extend(h256_to_u256_felts(&message.sender));
+    encoded.push(FieldElement::from(message.destination));
+    encoded.extend(h256_to_u256_felts(&message.recipient));
+    encoded.extend(encode_bytes(&message.body));
+    encoded
+}
+
+/// Serializes `value` as an array of short strings, each holding up to 31
+/// bytes.
+pub(crate) fn encode_short_strings(value: &str) -> Vec<FieldElement> {
+    let chunks: Vec<FieldElement> = value
+        .as_bytes()
+        .chunks(SHORT_STRING_SIZE)
+        .map(word_to_felt)
+        .collect();
+    let mut encoded = Vec::with_capacity(chunks.len() + 1);
+    encoded.push(FieldElement::from(chunks.len() as u64));
+    encoded.extend(chunks);
+    encoded
+}
+
+/// Reads Cairo-serialized values off a call result or event data, in order.
+pub(crate) struct FeltReader<'a> {
+    felts: &'a [FieldElement],
+}
+
+impl<'a> FeltReader<'a> {
+    pub(crate) fn new(felts: &'a [FieldElement]) -> Self {
+        Self { felts }
+    }
+
+    pub(crate) fn felt(&mut self) -> ChainResult<FieldElement> {
+        let (first, rest) =
+            self.felts
+                .split_first()
+                .ok_or(HyperlaneStarknetError::UnexpectedCallResult(
+                    "not enough felts",
+                ))?;
+        self.felts = rest;
+        Ok(*first)
+    }
+
+    pub(crate) fn u128(&mut self) -> ChainResult<u128> {
+        felt_to_u128(&self.felt()?)
+    }
+
+    pub(crate) fn u32(&mut self) -> ChainResult<u32> {
+        u32::try_from(self.u128()?)
+            .map_err(|_| HyperlaneStarknetError::UnexpectedCallResult("felt exceeds u32").into())
+    }
+
+    pub(crate) fn u8(&mut self) -> ChainResult<u8> {
+        u8::try_from(self.u128()?)
+            .map_err(|_| HyperlaneStarknetError::UnexpectedCallResult("felt exceeds u8").into())
+    }
+
+    pub(crate) fn bool(&mut self) -> ChainResult<bool> {
+        Ok(self.felt()? != FieldElement::ZERO)
+    }
+
+    /// A `u256`, serialized as its low and high halves
+    pub(crate) fn u256(&mut self) -> ChainResult<U256> {
+        let low = self.u128()?;
+        let high = self.u128()?;
+        Ok((U256::from(high) << 128) + U256::from(low))
+    }
+
+    pub(crate) fn h256(&mut self) -> ChainResult<H256> {
+        let mut bytes = [0u8; 32];
+        self.u256()?.to_big_endian(&mut bytes);
+        Ok(H256::from(bytes))
+    }
+
+    /// A `ContractAddress`, which fits in a single felt
+    pub(crate) fn address(&mut self) -> ChainResult<H256> {
+        Ok(felt_to_h256(&self.felt()?))
+    }
+
+    pub(crate) fn eth_address(&mut self) -> ChainResult<H256> {
+        let address = felt_to_h256(&self.felt()?);
+        Ok(H160::from(address).into())
+    }
+
+    pub(crate) fn array_len(&mut self) -> ChainResult<usize> {
+        Ok(self.u32()? as usize)
+    }
+
+    /// A Cairo `Bytes` value, see [`encode_bytes`]
+    pub(crate) fn bytes(&mut self) -> ChainResult<Vec<u8>> {
+        let size = self.array_len()?;
+        let words = self.array_len()?;
+        let mut bytes = Vec::with_capacity(words * BYTES_WORD_SIZE);
+        for i in 0..words {
+            let word = self.u128()?.to_be_bytes();
+            let word_size = (size - i * BYTES_WORD_SIZE).min(BYTES_WORD_SIZE);
+            bytes.extend_from_slice(&word[BYTES_WORD_SIZE - word_size..]);
+        }
+        if bytes.len() != size {
+            return Err(HyperlaneStarknetError::UnexpectedCallResult(
+                "bytes size doesn't match its words",
+            )
+            .into());
+        }
+        Ok(bytes)
+    }
+
+    /// A Cairo `Message` struct, see [`encode_message`]
+    pub(crate) fn message(&mut self) -> ChainResult<HyperlaneMessage> {
+        Ok(HyperlaneMessage {
+            version: self.u8()?,
+            nonce: self.u32()?,
+            origin: self.u32()?,
+            sender: self.h256()?,
+            destination: self.u32()?,
+            recipient: self.h256()?,
+            body: self.bytes()?,
+        })
+    }
+
+    /// An array of short strings, see [`encode_short_strings`]
+    pub(crate) fn short_strings(&mut self) -> ChainResult<String> {
+        let len = self.array_len()?;
+        let mut bytes = vec![];
+        for _ in 0..len {
+            let felt = self.felt()?.to_bytes_be();
+            let start = felt.iter().position(|byte| *byte != 0).unwrap_or(32);
+            bytes.extend_from_slice(&felt[start..]);
+        }
+        String::from_utf8(bytes).map_err(|_| {
+            HyperlaneStarknetError::UnexpectedCallResult("short strings are not utf-8").into()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_message_roundtrip() {
+        for body in [vec![], vec![1u8; 16], (0u8..=40).collect::<Vec<_>>()] {
+            let message = HyperlaneMessage {
+                version: 3,
+                nonce: 7,
+                origin: 1000,
+                sender: H256::repeat_byte(0xab),
+                destination: 2000,
+                recipient: H256::repeat_byte(0xcd),
+                body,
+            };
+            let encoded = encode_message(&message);
+            let mut reader = FeltReader::new(&encoded);
+            assert_eq!(reader.message().unwrap(), message);
+            assert!(reader.felt().is_err());
+        }
+    }
+
+    #[test]
+    fn test_partial_bytes_word_is_right_aligned() {
+        let encoded = encode_bytes(&[0x12, 0x34]);
+        assert_eq!(
+            encoded,
+            vec![
+                FieldElement::from(2u64),
+                FieldElement::from(1u64),
+                FieldElement::from(0x1234u64)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_short_strings_roundtrip() {
+        let location = "s3://hyperlane-validator-signatures-starknet/us-east-1";
+        let encoded = encode_short_strings(location);
+        assert_eq!(encoded.len(), 3);
+        assert_eq!(FeltReader::new(&encoded).short_strings().unwrap(), location);
+    }
+}
@@ -0,0 +1,28 @@
+use hyperlane_core::ChainCommunicationError;
+use starknet::{core::types::FromByteArrayError, providers::ProviderError};
+
+/// Errors from the crates specific to the hyperlane-starknet
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneStarknetError {
+    /// Starknet RPC provider error
+    #[error(transparent)]
+    ProviderError(#[from] ProviderError),
+    /// A 32 byte value that doesn't fit in a field element
+    #[error("{0}")]
+    FeltOverflow(#[from] FromByteArrayError),
+    /// Error sending or estimating a transaction from the account
+    #[error("Account error: {0}")]
+    AccountError(String),
+    /// A contract call returned data of an unexpected shape
+    #[error("Unexpected call result: {0}")]
+    UnexpectedCallResult(&'static str),
+}
+
+impl From<HyperlaneStarknetError> for ChainCommunicationError {
+    fn from(value: HyperlaneStarknetError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
@@ -0,0 +1,105 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use starknet::macros::selector;
+
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneProvider, Indexed, Indexer, InterchainGasPaymaster, InterchainGasPayment, LogMeta,
+    SequenceAwareIndexer, H256,
+};
+
+use crate::{conversions::FeltReader, ConnectionConf, StarknetProvider};
+
+/// A reference to an IGP contract on some Starknet chain
+#[derive(Debug)]
+pub struct StarknetInterchainGasPaymaster {
+    address: H256,
+    provider: StarknetProvider,
+}
+
+impl StarknetInterchainGasPaymaster {
+    /// Create a new Starknet IGP
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: StarknetProvider::new(locator.domain.clone(), conf, None),
+        })
+    }
+}
+
+impl HyperlaneContract for StarknetInterchainGasPaymaster {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetInterchainGasPaymaster {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+impl InterchainGasPaymaster for StarknetInterchainGasPaymaster {}
+
+/// Struct that retrieves event data for a Starknet IGP contract
+#[derive(Debug)]
+pub struct StarknetInterchainGasPaymasterIndexer {
+    igp: StarknetInterchainGasPaymaster,
+}
+
+impl StarknetInterchainGasPaymasterIndexer {
+    /// Create a new Starknet IGP indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            igp: StarknetInterchainGasPaymaster::new(conf, locator)?,
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<InterchainGasPayment> for StarknetInterchainGasPaymasterIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
+        let events = self
+            .igp
+            .provider
+            .events_in_range(&self.igp.address, selector!("GasPayment"), range)
+            .await?;
+        // The message id and destination domain are indexed as keys, the gas
+        // amount and payment are the event's data
+        events
+            .into_iter()
+            .map(|(event, meta)| {
+                let mut keys = FeltReader::new(&event.keys[1..]);
+                let mut data = FeltReader::new(&event.data);
+                let payment = InterchainGasPayment {
+                    message_id: keys.h256()?,
+                    destination: keys.u32()?,
+                    gas_amount: data.u256()?,
+                    payment: data.u256()?,
+                };
+                Ok((Indexed::new(payment), meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.igp.provider.latest_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<InterchainGasPayment> for StarknetInterchainGasPaymasterIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.get_finalized_block_number().await?;
+        // No sequence for gas payments.
+        Ok((None, tip))
+    }
+}
@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use num_traits::cast::FromPrimitive;
+use starknet::macros::selector;
+use tracing::{instrument, warn};
+
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, InterchainSecurityModule, ModuleType, H256, U256,
+};
+
+use crate::{
+    conversions::{encode_bytes, encode_message, FeltReader},
+    ConnectionConf, StarknetProvider,
+};
+
+/// A reference to an ISM contract on some Starknet chain
+#[derive(Debug)]
+pub struct StarknetInterchainSecurityModule {
+    address: H256,
+    provider: StarknetProvider,
+}
+
+impl StarknetInterchainSecurityModule {
+    /// Create a new Starknet ISM
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: StarknetProvider::new(locator.domain.clone(), conf, None),
+        })
+    }
+}
+
+impl HyperlaneContract for StarknetInterchainSecurityModule {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetInterchainSecurityModule {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl InterchainSecurityModule for StarknetInterchainSecurityModule {
+    #[instrument(err, ret, skip(self))]
+    async fn module_type(&self) -> ChainResult<ModuleType> {
+        let result = self
+            .provider
+            .call(&self.address, selector!("module_type"), vec![])
+            .await?;
+        // A Cairo enum, whose variants are in the order of `ModuleType`
+        let variant = FeltReader::new(&result).u8()?;
+        if let Some(module_type) = ModuleType::from_u8(variant) {
+            Ok(module_type)
+        } else {
+            warn!(%variant, "Unknown module type");
+            Ok(ModuleType::Unused)
+        }
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn dry_run_verify(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        let mut calldata = encode_bytes(metadata);
+        calldata.extend(encode_message(message));
+        let result = self
+            .provider
+            .call(&self.address, selector!("verify"), calldata)
+            .await?;
+        // Starknet calls don't report the gas they used, so a dummy gas value
+        // is used, as for other chains that can't simulate `verify`.
+        let dummy_gas_value = U256::one();
+        Ok(FeltReader::new(&result).bool()?.then_some(dummy_gas_value))
+    }
+}
@@ -0,0 +1,24 @@
+//! Implementation of hyperlane for Starknet.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+mod aggregation_ism;
+mod conversions;
+mod error;
+mod interchain_gas;
+mod interchain_security_module;
+mod mailbox;
+mod merkle_tree_hook;
+mod multisig_ism;
+mod provider;
+mod routing_ism;
+mod signers;
+mod trait_builder;
+mod validator_announce;
+
+pub use self::{
+    aggregation_ism::*, error::*, interchain_gas::*, interchain_security_module::*, mailbox::*,
+    merkle_tree_hook::*, multisig_ism::*, provider::*, routing_ism::*, signers::*,
+    trait_builder::*, validator_announce::*,
+};
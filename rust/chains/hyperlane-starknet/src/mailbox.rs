@@ -0,0 +1,244 @@
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use starknet::{core::types::FieldElement, macros::selector};
+use tracing::{instrument, warn};
+
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox,
+    SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, U256,
+};
+
+use crate::{
+    conversions::{encode_bytes, encode_message, h256_to_u256_felts, FeltReader},
+    ConnectionConf, Signer, StarknetProvider,
+};
+
+/// A reference to a Mailbox contract on some Starknet chain
+#[derive(Debug)]
+pub struct StarknetMailbox {
+    address: H256,
+    provider: StarknetProvider,
+}
+
+impl StarknetMailbox {
+    /// Create a new Starknet mailbox
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: StarknetProvider::new(locator.domain.clone(), conf, signer),
+        })
+    }
+
+    /// The calldata of `process`: the metadata bytes followed by the message
+    fn process_args(message: &HyperlaneMessage, metadata: &[u8]) -> Vec<FieldElement> {
+        let mut calldata = encode_bytes(metadata);
+        calldata.extend(encode_message(message));
+        calldata
+    }
+}
+
+impl HyperlaneContract for StarknetMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for StarknetMailbox {
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        assert!(
+            lag.is_none(),
+            "Starknet does not support querying point-in-time"
+        );
+        let result = self
+            .provider
+            .call(&self.address, selector!("nonce"), vec![])
+            .await?;
+        FeltReader::new(&result).u32()
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let result = self
+            .provider
+            .call(
+                &self.address,
+                selector!("delivered"),
+                h256_to_u256_felts(&id).to_vec(),
+            )
+            .await?;
+        FeltReader::new(&result).bool()
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        let result = self
+            .provider
+            .call(&self.address, selector!("get_default_ism"), vec![])
+            .await?;
+        FeltReader::new(&result).address()
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let result = self
+            .provider
+            .call(
+                &self.address,
+                selector!("recipient_ism"),
+                h256_to_u256_felts(&recipient).to_vec(),
+            )
+            .await?;
+        FeltReader::new(&result).address()
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        _tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        // Starknet transactions are bounded by their max fee, which the
+        // account estimates, rather than by a gas limit
+        self.provider
+            .invoke(
+                &self.address,
+                selector!("process"),
+                Self::process_args(message, metadata),
+            )
+            .await
+    }
+
+    #[instrument(err, ret, skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        self.provider
+            .estimate_invoke(
+                &self.address,
+                selector!("process"),
+                Self::process_args(message, metadata),
+            )
+            .await
+    }
+
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        Self::process_args(message, metadata)
+            .iter()
+            .flat_map(FieldElement::to_bytes_be)
+            .collect()
+    }
+}
+
+/// Struct that retrieves event data for a Starknet Mailbox contract
+#[derive(Debug)]
+pub struct StarknetMailboxIndexer {
+    mailbox: StarknetMailbox,
+}
+
+impl StarknetMailboxIndexer {
+    /// Create a new Starknet mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            mailbox: StarknetMailbox::new(conf, locator, None)?,
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for StarknetMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        let events = self
+            .mailbox
+            .provider
+            .events_in_range(&self.mailbox.address, selector!("Dispatch"), range)
+            .await?;
+        // The sender, destination and recipient are indexed as keys, the
+        // whole message is the event's data
+        events
+            .into_iter()
+            .map(|(event, meta)| {
+                let message = FeltReader::new(&event.data).message()?;
+                Ok((message.into(), meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.latest_block_number().await
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for StarknetMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        let events = self
+            .mailbox
+            .provider
+            .events_in_range(&self.mailbox.address, selector!("ProcessId"), range)
+            .await?;
+        // The message id is the only key after the event selector
+        Ok(events
+            .into_iter()
+            .filter_map(
+                |(event, meta)| match FeltReader::new(&event.keys[1..]).h256() {
+                    Ok(id) => Some((Indexed::new(id), meta)),
+                    Err(_) => {
+                        warn!(?meta, "ProcessId event has no message id, skipping");
+                        None
+                    }
+                },
+            )
+            .collect())
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.latest_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for StarknetMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
+        // TODO: need to make sure the call and tip are at the same height?
+        let count = Mailbox::count(&self.mailbox, None).await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for StarknetMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<H256>::get_finalized_block_number(self).await?;
+        // No sequence for message deliveries.
+        Ok((None, tip))
+    }
+}
@@ -0,0 +1,161 @@
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use starknet::macros::selector;
+use tracing::instrument;
+
+use hyperlane_core::{
+    accumulator::{incremental::IncrementalMerkle, TREE_DEPTH},
+    ChainResult, Checkpoint, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, MerkleTreeHook, MerkleTreeInsertion,
+    SequenceAwareIndexer, H256,
+};
+
+use crate::{conversions::FeltReader, ConnectionConf, HyperlaneStarknetError, StarknetProvider};
+
+/// A reference to a MerkleTreeHook contract on some Starknet chain
+#[derive(Debug)]
+pub struct StarknetMerkleTreeHook {
+    address: H256,
+    provider: StarknetProvider,
+}
+
+impl StarknetMerkleTreeHook {
+    /// Create a new Starknet merkle tree hook
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: StarknetProvider::new(locator.domain.clone(), conf, None),
+        })
+    }
+}
+
+impl HyperlaneContract for StarknetMerkleTreeHook {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetMerkleTreeHook {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl MerkleTreeHook for StarknetMerkleTreeHook {
+    #[instrument(err, ret, skip(self))]
+    async fn tree(&self, lag: Option<NonZeroU64>) -> ChainResult<IncrementalMerkle> {
+        assert!(
+            lag.is_none(),
+            "Starknet does not support querying point-in-time"
+        );
+        let result = self
+            .provider
+            .call(&self.address, selector!("tree"), vec![])
+            .await?;
+        // The branch, as an array of u256, followed by the count
+        let mut reader = FeltReader::new(&result);
+        let branch = (0..reader.array_len()?)
+            .map(|_| reader.h256())
+            .collect::<ChainResult<Vec<_>>>()?;
+        let branch: [H256; TREE_DEPTH] = branch.try_into().map_err(|_| {
+            HyperlaneStarknetError::UnexpectedCallResult("merkle branch has the wrong depth")
+        })?;
+        Ok(IncrementalMerkle::new(branch, reader.u32()? as usize))
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        assert!(
+            lag.is_none(),
+            "Starknet does not support querying point-in-time"
+        );
+        let result = self
+            .provider
+            .call(&self.address, selector!("count"), vec![])
+            .await?;
+        FeltReader::new(&result).u32()
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn latest_checkpoint(&self, lag: Option<NonZeroU64>) -> ChainResult<Checkpoint> {
+        assert!(
+            lag.is_none(),
+            "Starknet does not support querying point-in-time"
+        );
+        let result = self
+            .provider
+            .call(&self.address, selector!("latest_checkpoint"), vec![])
+            .await?;
+        let mut reader = FeltReader::new(&result);
+        Ok(Checkpoint {
+            merkle_tree_hook_address: self.address,
+            mailbox_domain: self.domain().id(),
+            root: reader.h256()?,
+            index: reader.u32()?,
+        })
+    }
+}
+
+/// Struct that retrieves event data for a Starknet MerkleTreeHook contract
+#[derive(Debug)]
+pub struct StarknetMerkleTreeHookIndexer {
+    merkle_tree_hook: StarknetMerkleTreeHook,
+}
+
+impl StarknetMerkleTreeHookIndexer {
+    /// Create a new Starknet merkle tree hook indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            merkle_tree_hook: StarknetMerkleTreeHook::new(conf, locator)?,
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<MerkleTreeInsertion> for StarknetMerkleTreeHookIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        let events = self
+            .merkle_tree_hook
+            .provider
+            .events_in_range(
+                &self.merkle_tree_hook.address,
+                selector!("InsertedIntoTree"),
+                range,
+            )
+            .await?;
+        // The event's data is the message id followed by the leaf index
+        events
+            .into_iter()
+            .map(|(event, meta)| {
+                let mut reader = FeltReader::new(&event.data);
+                let message_id = reader.h256()?;
+                let insertion = MerkleTreeInsertion::new(reader.u32()?, message_id);
+                Ok((insertion.into(), meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.merkle_tree_hook.provider.latest_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<MerkleTreeInsertion> for StarknetMerkleTreeHookIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.get_finalized_block_number().await?;
+        // TODO: need to make sure the call and tip are at the same height?
+        let count = MerkleTreeHook::count(&self.merkle_tree_hook, None).await?;
+        Ok((Some(count), tip))
+    }
+}
@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use starknet::macros::selector;
+use tracing::instrument;
+
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, MultisigIsm, H256,
+};
+
+use crate::{
+    conversions::{encode_message, FeltReader},
+    ConnectionConf, StarknetProvider,
+};
+
+/// A reference to a MultisigIsm contract on some Starknet chain
+#[derive(Debug)]
+pub struct StarknetMultisigIsm {
+    address: H256,
+    provider: StarknetProvider,
+}
+
+impl StarknetMultisigIsm {
+    /// Create a new Starknet multisig ISM
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: StarknetProvider::new(locator.domain.clone(), conf, None),
+        })
+    }
+}
+
+impl HyperlaneContract for StarknetMultisigIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetMultisigIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl MultisigIsm for StarknetMultisigIsm {
+    #[instrument(err, skip(self))]
+    async fn validators_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        let result = self
+            .provider
+            .call(
+                &self.address,
+                selector!("validators_and_threshold"),
+                encode_message(message),
+            )
+            .await?;
+        // The validators are Ethereum addresses, followed by the threshold
+        let mut reader = FeltReader::new(&result);
+        let validators = (0..reader.array_len()?)
+            .map(|_| reader.eth_address())
+            .collect::<ChainResult<Vec<_>>>()?;
+        Ok((validators, reader.u8()?))
+    }
+}
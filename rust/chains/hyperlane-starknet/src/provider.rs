@@ -0,0 +1,315 @@
+use std::{collections::HashMap, ops::RangeInclusive, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, FixedPointNumber, HyperlaneChain,
+    HyperlaneDomain, HyperlaneProvider, LogMeta, TxCostEstimate, TxOutcome, TxnInfo, H256, U256,
+};
+use starknet::{
+    accounts::{Account, Call, ExecutionEncoding, SingleOwnerAccount},
+    core::types::{
+        BlockId, BlockTag, EmittedEvent, EventFilter, ExecutionResult, FieldElement, FunctionCall,
+        MaybePendingBlockWithTxHashes, MaybePendingTransactionReceipt, StarknetError,
+    },
+    macros::{felt, selector},
+    providers::{
+        jsonrpc::{HttpTransport, JsonRpcClient},
+        Provider, ProviderError,
+    },
+    signers::LocalWallet,
+};
+use tracing::{debug, instrument, warn};
+
+use crate::{
+    conversions::{felt_to_h256, h256_to_felt, FeltReader},
+    ConnectionConf, HyperlaneStarknetError, Signer,
+};
+
+/// The ETH fee token, which has the same address on every Starknet network
+const ETH_FEE_TOKEN: FieldElement =
+    felt!("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7");
+/// The max number of events fetched per `starknet_getEvents` page
+const EVENTS_CHUNK_SIZE: u64 = 100;
+/// How often the receipt of a sent transaction is polled
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How many times the receipt of a sent transaction is polled before giving up
+const RECEIPT_POLL_ATTEMPTS: usize = 60;
+
+type StarknetAccount = SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>;
+
+/// A wrapper around a Starknet JSON-RPC provider to get generic blockchain
+/// information, call contracts and send transactions.
+#[derive(Debug, Clone)]
+pub struct StarknetProvider {
+    domain: HyperlaneDomain,
+    conf: ConnectionConf,
+    rpc: Arc<JsonRpcClient<HttpTransport>>,
+    signer: Option<Signer>,
+}
+
+impl StarknetProvider {
+    /// Create a new Starknet provider
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf, signer: Option<Signer>) -> Self {
+        Self {
+            domain,
+            conf: conf.clone(),
+            rpc: Arc::new(JsonRpcClient::new(HttpTransport::new(conf.url.clone()))),
+            signer,
+        }
+    }
+
+    /// Calls the view function `selector` of `contract`, at the latest block
+    pub(crate) async fn call(
+        &self,
+        contract: &H256,
+        selector: FieldElement,
+        calldata: Vec<FieldElement>,
+    ) -> ChainResult<Vec<FieldElement>> {
+        let request = FunctionCall {
+            contract_address: h256_to_felt(contract)?,
+            entry_point_selector: selector,
+            calldata,
+        };
+        Ok(self
+            .rpc
+            .call(request, BlockId::Tag(BlockTag::Latest))
+            .await
+            .map_err(HyperlaneStarknetError::from)?)
+    }
+
+    /// The latest block number
+    pub(crate) async fn latest_block_number(&self) -> ChainResult<u32> {
+        let block_number = self
+            .rpc
+            .block_number()
+            .await
+            .map_err(HyperlaneStarknetError::from)?;
+        block_number
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    async fn account(&self) -> ChainResult<StarknetAccount> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)?;
+        let chain_id = self
+            .rpc
+            .chain_id()
+            .await
+            .map_err(HyperlaneStarknetError::from)?;
+        let mut account = SingleOwnerAccount::new(
+            JsonRpcClient::new(HttpTransport::new(self.conf.url.clone())),
+            signer.wallet(),
+            signer.address,
+            chain_id,
+            ExecutionEncoding::New,
+        );
+        // So that the nonce accounts for transactions that are still pending
+        account.set_block_id(BlockId::Tag(BlockTag::Pending));
+        Ok(account)
+    }
+
+    /// Sends a transaction invoking `selector` of `contract` from the signer's
+    /// account, and waits for it to be accepted.
+    #[instrument(err, skip(self, calldata))]
+    pub(crate) async fn invoke(
+        &self,
+        contract: &H256,
+        selector: FieldElement,
+        calldata: Vec<FieldElement>,
+    ) -> ChainResult<TxOutcome> {
+        let call = Call {
+            to: h256_to_felt(contract)?,
+            selector,
+            calldata,
+        };
+        let sent = self
+            .account()
+            .await?
+            .execute(vec![call])
+            .send()
+            .await
+            .map_err(|err| HyperlaneStarknetError::AccountError(err.to_string()))?;
+        debug!(transaction_hash=?sent.transaction_hash, "Sent Starknet transaction");
+
+        let receipt = self.wait_for_receipt(sent.transaction_hash).await?;
+        let executed = match receipt.execution_result() {
+            ExecutionResult::Succeeded => true,
+            ExecutionResult::Reverted { reason } => {
+                warn!(transaction_hash=?sent.transaction_hash, reason, "Starknet transaction reverted");
+                false
+            }
+        };
+        Ok(TxOutcome {
+            transaction_id: felt_to_h256(&sent.transaction_hash).into(),
+            executed,
+            // Starknet receipts only report the fee that was paid
+            gas_used: U256::from_big_endian(&receipt.actual_fee().amount.to_bytes_be()),
+            gas_price: FixedPointNumber::from(1),
+        })
+    }
+
+    /// Estimates the cost of invoking `selector` of `contract` from the
+    /// signer's account.
+    pub(crate) async fn estimate_invoke(
+        &self,
+        contract: &H256,
+        selector: FieldElement,
+        calldata: Vec<FieldElement>,
+    ) -> ChainResult<TxCostEstimate> {
+        let call = Call {
+            to: h256_to_felt(contract)?,
+            selector,
+            calldata,
+        };
+        let estimate = self
+            .account()
+            .await?
+            .execute(vec![call])
+            .estimate_fee()
+            .await
+            .map_err(|err| HyperlaneStarknetError::AccountError(err.to_string()))?;
+        Ok(TxCostEstimate {
+            gas_limit: U256::from_big_endian(&estimate.gas_consumed.to_bytes_be()),
+            gas_price: U256::from_big_endian(&estimate.gas_price.to_bytes_be()).try_into()?,
+            l2_gas_limit: None,
+            resources: vec![],
+        })
+    }
+
+    async fn wait_for_receipt(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> ChainResult<starknet::core::types::TransactionReceipt> {
+        for _ in 0..RECEIPT_POLL_ATTEMPTS {
+            match self.rpc.get_transaction_receipt(transaction_hash).await {
+                Ok(MaybePendingTransactionReceipt::Receipt(receipt)) => return Ok(receipt),
+                Ok(MaybePendingTransactionReceipt::PendingReceipt(_))
+                | Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => {}
+                Err(err) => return Err(HyperlaneStarknetError::from(err).into()),
+            }
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+        Err(ChainCommunicationError::TransactionTimeout())
+    }
+
+    /// Fetches the events of `contract` whose first key is `key`, i.e. the
+    /// events named by `key`, emitted in the blocks of `range`.
+    pub(crate) async fn events_in_range(
+        &self,
+        contract: &H256,
+        key: FieldElement,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(EmittedEvent, LogMeta)>> {
+        let filter = EventFilter {
+            from_block: Some(BlockId::Number((*range.start()).into())),
+            to_block: Some(BlockId::Number((*range.end()).into())),
+            address: Some(h256_to_felt(contract)?),
+            keys: Some(vec![vec![key]]),
+        };
+        let mut events = vec![];
+        let mut continuation_token = None;
+        loop {
+            let page = self
+                .rpc
+                .get_events(filter.clone(), continuation_token, EVENTS_CHUNK_SIZE)
+                .await
+                .map_err(HyperlaneStarknetError::from)?;
+            events.extend(page.events);
+            continuation_token = page.continuation_token;
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        // Events are returned in order, so their position among the
+        // contract's events of a block identifies them.
+        let mut log_indices = HashMap::<u64, u64>::new();
+        Ok(events
+            .into_iter()
+            .filter_map(|event| {
+                // Pending events aren't in a block yet
+                let (block_number, block_hash) = (event.block_number?, event.block_hash?);
+                let log_index = log_indices.entry(block_number).or_default();
+                let meta = LogMeta {
+                    address: *contract,
+                    block_number,
+                    block_hash: felt_to_h256(&block_hash),
+                    transaction_id: felt_to_h256(&event.transaction_hash).into(),
+                    transaction_index: 0,
+                    log_index: (*log_index).into(),
+                };
+                *log_index += 1;
+                Some((event, meta))
+            })
+            .collect())
+    }
+}
+
+impl HyperlaneChain for StarknetProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for StarknetProvider {
+    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
+        let block = self
+            .rpc
+            .get_block_with_tx_hashes(BlockId::Hash(h256_to_felt(hash)?))
+            .await
+            .map_err(HyperlaneStarknetError::from)?;
+        match block {
+            MaybePendingBlockWithTxHashes::Block(block) => Ok(BlockInfo {
+                hash: *hash,
+                timestamp: block.timestamp,
+                number: block.block_number,
+            }),
+            MaybePendingBlockWithTxHashes::PendingBlock(_) => Err(
+                ChainCommunicationError::from_other_str("Block is still pending"),
+            ),
+        }
+    }
+
+    async fn get_txn_by_hash(&self, _hash: &H256) -> ChainResult<TxnInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "Starknet does not support fetching transactions yet",
+        ))
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        match self
+            .rpc
+            .get_class_hash_at(BlockId::Tag(BlockTag::Latest), h256_to_felt(address)?)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Ok(false),
+            Err(err) => Err(HyperlaneStarknetError::from(err).into()),
+        }
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        let address =
+            FieldElement::from_hex_be(&address).map_err(ChainCommunicationError::from_other)?;
+        let balance = self
+            .call(
+                &felt_to_h256(&ETH_FEE_TOKEN),
+                selector!("balanceOf"),
+                vec![address],
+            )
+            .await?;
+        FeltReader::new(&balance).u256()
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        Ok(None)
+    }
+}
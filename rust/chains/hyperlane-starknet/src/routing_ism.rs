@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use starknet::macros::selector;
+use tracing::instrument;
+
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, RoutingIsm, H256,
+};
+
+use crate::{
+    conversions::{encode_message, FeltReader},
+    ConnectionConf, StarknetProvider,
+};
+
+/// A reference to a RoutingIsm contract on some Starknet chain
+#[derive(Debug)]
+pub struct StarknetRoutingIsm {
+    address: H256,
+    provider: StarknetProvider,
+}
+
+impl StarknetRoutingIsm {
+    /// Create a new Starknet routing ISM
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: StarknetProvider::new(locator.domain.clone(), conf, None),
+        })
+    }
+}
+
+impl HyperlaneContract for StarknetRoutingIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetRoutingIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl RoutingIsm for StarknetRoutingIsm {
+    #[instrument(err, skip(self))]
+    async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
+        let result = self
+            .provider
+            .call(&self.address, selector!("route"), encode_message(message))
+            .await?;
+        FeltReader::new(&result).address()
+    }
+}
@@ -0,0 +1,37 @@
+use hyperlane_core::{ChainResult, H256};
+use starknet::{
+    core::types::FieldElement,
+    signers::{LocalWallet, SigningKey},
+};
+
+use crate::conversions::{felt_to_h256, h256_to_felt};
+
+/// A Starknet account contract and the key that signs for it. Unlike on most
+/// chains, the account address can't be derived from the key.
+#[derive(Clone, Debug)]
+pub struct Signer {
+    /// The address of the account contract
+    pub address: FieldElement,
+    key: FieldElement,
+}
+
+impl Signer {
+    /// Create a new Starknet signer from the private key and the address of
+    /// the account contract it signs for
+    pub fn new(key: &H256, address: &H256) -> ChainResult<Self> {
+        Ok(Self {
+            address: h256_to_felt(address)?,
+            key: h256_to_felt(key)?,
+        })
+    }
+
+    /// The wallet signing transactions of the account
+    pub(crate) fn wallet(&self) -> LocalWallet {
+        LocalWallet::from(SigningKey::from_secret_scalar(self.key))
+    }
+
+    /// The address of the account contract, 0x-prefixed
+    pub fn address_string(&self) -> String {
+        format!("{:?}", felt_to_h256(&self.address))
+    }
+}
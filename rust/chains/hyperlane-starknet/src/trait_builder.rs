@@ -0,0 +1,21 @@
+use hyperlane_core::config::OperationBatchConfig;
+use url::Url;
+
+/// Starknet connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Fully qualified url of the JSON-RPC endpoint
+    pub url: Url,
+    /// Operation batching configuration
+    pub operation_batch: OperationBatchConfig,
+}
+
+impl ConnectionConf {
+    /// Create a new connection configuration
+    pub fn new(url: Url, operation_batch: OperationBatchConfig) -> Self {
+        Self {
+            url,
+            operation_batch,
+        }
+    }
+}
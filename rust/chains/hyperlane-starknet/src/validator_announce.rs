@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use starknet::{core::types::FieldElement, macros::selector};
+use tracing::instrument;
+
+use hyperlane_core::{
+    Announcement, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneProvider, SignedType, TxOutcome, ValidatorAnnounce, H160, H256, U256,
+};
+
+use crate::{
+    conversions::{encode_bytes, encode_short_strings, h256_to_felt, FeltReader},
+    ConnectionConf, Signer, StarknetProvider,
+};
+
+/// A reference to a ValidatorAnnounce contract on some Starknet chain
+#[derive(Debug)]
+pub struct StarknetValidatorAnnounce {
+    address: H256,
+    provider: StarknetProvider,
+}
+
+impl StarknetValidatorAnnounce {
+    /// Create a new Starknet validator announce
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: StarknetProvider::new(locator.domain.clone(), conf, signer),
+        })
+    }
+}
+
+impl HyperlaneContract for StarknetValidatorAnnounce {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StarknetValidatorAnnounce {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl ValidatorAnnounce for StarknetValidatorAnnounce {
+    async fn get_announced_storage_locations(
+        &self,
+        validators: &[H256],
+    ) -> ChainResult<Vec<Vec<String>>> {
+        // The validators are passed as a span of Ethereum addresses
+        let mut calldata = vec![FieldElement::from(validators.len() as u64)];
+        for validator in validators {
+            calldata.push(h256_to_felt(&H160::from(*validator).into())?);
+        }
+        let result = self
+            .provider
+            .call(
+                &self.address,
+                selector!("get_announced_storage_locations"),
+                calldata,
+            )
+            .await?;
+        // A span of storage locations per validator, each of them an array of
+        // short strings
+        let mut reader = FeltReader::new(&result);
+        (0..reader.array_len()?)
+            .map(|_| {
+                (0..reader.array_len()?)
+                    .map(|_| reader.short_strings())
+                    .collect::<ChainResult<Vec<_>>>()
+            })
+            .collect()
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome> {
+        let mut calldata = vec![h256_to_felt(&announcement.value.validator.into())?];
+        calldata.extend(encode_short_strings(&announcement.value.storage_location));
+        calldata.extend(encode_bytes(&announcement.signature.to_vec()));
+        self.provider
+            .invoke(&self.address, selector!("announce"), calldata)
+            .await
+    }
+
+    async fn announce_tokens_needed(&self, announcement: SignedType<Announcement>) -> Option<U256> {
+        // TODO: check user balance. For now, just try announcing and
+        // allow the announce attempt to fail if there are not enough tokens.
+        Some(0u64.into())
+    }
+}
@@ -0,0 +1,153 @@
+use hyperlane_core::{ChainResult, H256};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use stellar_xdr::curr::{
+    LedgerEntryData, LedgerKey, Limits, ReadXdr, TransactionEnvelope, WriteXdr,
+};
+use url::Url;
+
+use crate::{
+    types::{
+        EventPage, LatestLedger, LedgerEntries, Network, SendResult, Simulation, TransactionStatus,
+    },
+    HyperlaneStellarError,
+};
+
+/// The number of events fetched per request
+pub(crate) const EVENTS_PAGE_SIZE: usize = 100;
+
+/// A client of the JSON-RPC API of a Soroban RPC server
+#[derive(Debug, Clone)]
+pub(crate) struct SorobanClient {
+    http: Client,
+    url: Url,
+}
+
+impl SorobanClient {
+    pub(crate) fn new(url: Url) -> Self {
+        Self {
+            http: Client::new(),
+            url,
+        }
+    }
+
+    async fn rpc<T: DeserializeOwned>(&self, method: &str, params: Value) -> ChainResult<T> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let mut response: Value = self
+            .http
+            .post(self.url.clone())
+            .json(&request)
+            .send()
+            .await
+            .map_err(HyperlaneStellarError::from)?
+            .json()
+            .await
+            .map_err(HyperlaneStellarError::from)?;
+        if let Some(error) = response.get("error") {
+            return Err(HyperlaneStellarError::RpcError {
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or_default().to_owned(),
+            }
+            .into());
+        }
+        Ok(serde_json::from_value(response["result"].take())
+            .map_err(HyperlaneStellarError::from)?)
+    }
+
+    /// The sequence number of the latest ledger. Ledgers are final once
+    /// closed.
+    pub(crate) async fn latest_ledger(&self) -> ChainResult<u32> {
+        let ledger: LatestLedger = self.rpc("getLatestLedger", json!({})).await?;
+        Ok(ledger.sequence)
+    }
+
+    /// The passphrase of the network the server is connected to
+    pub(crate) async fn network_passphrase(&self) -> ChainResult<String> {
+        let network: Network = self.rpc("getNetwork", json!({})).await?;
+        Ok(network.passphrase)
+    }
+
+    /// The data of the ledger entry at `key`, if it exists
+    pub(crate) async fn ledger_entry(
+        &self,
+        key: &LedgerKey,
+    ) -> ChainResult<Option<LedgerEntryData>> {
+        let key = key
+            .to_xdr_base64(Limits::none())
+            .map_err(HyperlaneStellarError::from)?;
+        let response: LedgerEntries = self
+            .rpc("getLedgerEntries", json!({ "keys": [key] }))
+            .await?;
+        response
+            .entries
+            .first()
+            .map(|entry| {
+                LedgerEntryData::from_xdr_base64(&entry.xdr, Limits::none())
+                    .map_err(|e| HyperlaneStellarError::from(e).into())
+            })
+            .transpose()
+    }
+
+    /// The events emitted by `contract` in the ledgers from `start` to `end`
+    /// with `topic` as their first topic, after the event at `cursor` if set
+    pub(crate) async fn events(
+        &self,
+        contract: &str,
+        topic: &str,
+        start: u32,
+        end: u32,
+        cursor: Option<&str>,
+    ) -> ChainResult<EventPage> {
+        let mut params = json!({
+            "endLedger": end + 1,
+            "filters": [{
+                "type": "contract",
+                "contractIds": [contract],
+                "topics": [[topic, "**"]],
+            }],
+            "pagination": { "limit": EVENTS_PAGE_SIZE },
+        });
+        // The start ledger can't be set along with a cursor
+        match cursor {
+            Some(cursor) => params["pagination"]["cursor"] = cursor.into(),
+            None => params["startLedger"] = start.into(),
+        }
+        self.rpc("getEvents", params).await
+    }
+
+    /// Simulates `transaction`, to get its result, footprint and resource fee
+    pub(crate) async fn simulate(
+        &self,
+        transaction: &TransactionEnvelope,
+    ) -> ChainResult<Simulation> {
+        let transaction = transaction
+            .to_xdr_base64(Limits::none())
+            .map_err(HyperlaneStellarError::from)?;
+        self.rpc("simulateTransaction", json!({ "transaction": transaction }))
+            .await
+    }
+
+    /// Submits a signed transaction
+    pub(crate) async fn send(&self, transaction: &TransactionEnvelope) -> ChainResult<SendResult> {
+        let transaction = transaction
+            .to_xdr_base64(Limits::none())
+            .map_err(HyperlaneStellarError::from)?;
+        self.rpc("sendTransaction", json!({ "transaction": transaction }))
+            .await
+    }
+
+    /// The status of the transaction with `hash`
+    pub(crate) async fn transaction(&self, hash: &H256) -> ChainResult<TransactionStatus> {
+        self.rpc(
+            "getTransaction",
+            json!({ "hash": hex::encode(hash.as_bytes()) }),
+        )
+        .await
+    }
+}
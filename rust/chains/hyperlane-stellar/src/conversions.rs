@@ -0,0 +1,116 @@
+//! Conversions between hyperlane types and Soroban values.
+
+use hyperlane_core::H256;
+use stellar_xdr::curr::{AccountId, Hash, PublicKey, ScAddress, ScBytes, ScSymbol, ScVal, Uint256};
+
+use crate::HyperlaneStellarError;
+
+/// The address of the contract with id `contract`
+pub(crate) fn contract_address(contract: &H256) -> ScAddress {
+    ScAddress::Contract(Hash(contract.0))
+}
+
+/// The address of the account with the ed25519 public key `account`
+pub(crate) fn account_id(account: &H256) -> AccountId {
+    AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(account.0)))
+}
+
+pub(crate) fn symbol(symbol: &str) -> Result<ScVal, HyperlaneStellarError> {
+    Ok(ScVal::Symbol(ScSymbol(symbol.try_into()?)))
+}
+
+pub(crate) fn bytes(bytes: &[u8]) -> Result<ScVal, HyperlaneStellarError> {
+    Ok(ScVal::Bytes(ScBytes(bytes.to_vec().try_into()?)))
+}
+
+fn unexpected(expected: &str, value: &ScVal) -> HyperlaneStellarError {
+    HyperlaneStellarError::UnexpectedResponse(format!("expected {expected}, got {value:?}"))
+}
+
+pub(crate) fn to_u32(value: &ScVal) -> Result<u32, HyperlaneStellarError> {
+    match value {
+        ScVal::U32(value) => Ok(*value),
+        _ => Err(unexpected("a u32", value)),
+    }
+}
+
+pub(crate) fn to_bool(value: &ScVal) -> Result<bool, HyperlaneStellarError> {
+    match value {
+        ScVal::Bool(value) => Ok(*value),
+        _ => Err(unexpected("a bool", value)),
+    }
+}
+
+pub(crate) fn to_bytes(value: &ScVal) -> Result<Vec<u8>, HyperlaneStellarError> {
+    match value {
+        ScVal::Bytes(bytes) => Ok(bytes.to_vec()),
+        _ => Err(unexpected("bytes", value)),
+    }
+}
+
+/// The contract id or account public key of an address, or a `BytesN<32>`
+pub(crate) fn to_h256(value: &ScVal) -> Result<H256, HyperlaneStellarError> {
+    match value {
+        ScVal::Address(ScAddress::Contract(Hash(id))) => Ok(H256(*id)),
+        ScVal::Address(ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(
+            Uint256(key),
+        )))) => Ok(H256(*key)),
+        ScVal::Bytes(bytes) if bytes.len() == H256::len_bytes() => Ok(H256::from_slice(bytes)),
+        _ => Err(unexpected("an address", value)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use stellar_xdr::curr::{Limits, ReadXdr, WriteXdr};
+
+    use super::*;
+
+    /// Checks that `value` is XDR encoded as `expected`, and decodes back to
+    /// itself
+    fn assert_xdr(value: &ScVal, expected: &str) {
+        let encoded = value.to_xdr(Limits::none()).unwrap();
+        assert_eq!(hex::encode(&encoded), expected);
+        assert_eq!(&ScVal::from_xdr(encoded, Limits::none()).unwrap(), value);
+    }
+
+    #[test]
+    fn test_scval_xdr() {
+        assert_xdr(&ScVal::U32(7), "0000000300000007");
+        assert_xdr(&ScVal::Bool(true), "0000000000000001");
+        // Opaque data is padded to a multiple of 4 bytes
+        assert_xdr(&bytes(&[1, 2, 3]).unwrap(), "0000000d0000000301020300");
+        assert_xdr(
+            &symbol("process").unwrap(),
+            "0000000f0000000770726f6365737300",
+        );
+        let id = H256::repeat_byte(0xab);
+        assert_xdr(
+            &ScVal::Address(contract_address(&id)),
+            &format!("0000001200000001{}", hex::encode(id)),
+        );
+        assert_xdr(
+            &ScVal::Address(ScAddress::Account(account_id(&id))),
+            &format!("000000120000000000000000{}", hex::encode(id)),
+        );
+    }
+
+    #[test]
+    fn test_scval_conversions() {
+        let id = H256::repeat_byte(0xab);
+        assert_eq!(to_h256(&ScVal::Address(contract_address(&id))).unwrap(), id);
+        assert_eq!(
+            to_h256(&ScVal::Address(ScAddress::Account(account_id(&id)))).unwrap(),
+            id
+        );
+        assert_eq!(to_h256(&bytes(id.as_bytes()).unwrap()).unwrap(), id);
+        assert!(to_h256(&bytes(&[1, 2, 3]).unwrap()).is_err());
+        assert_eq!(
+            to_bytes(&bytes(&[1, 2, 3]).unwrap()).unwrap(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(to_u32(&ScVal::U32(7)).unwrap(), 7);
+        assert!(to_u32(&ScVal::Bool(true)).is_err());
+        assert!(to_bool(&ScVal::Bool(true)).unwrap());
+    }
+}
@@ -0,0 +1,46 @@
+use hyperlane_core::ChainCommunicationError;
+
+/// Errors from the crates specific to the hyperlane-stellar
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneStellarError {
+    /// Reqwest error
+    #[error("{0}")]
+    ReqwestError(#[from] reqwest::Error),
+    /// serde_json error
+    #[error("{0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+    /// XDR encoding or decoding error
+    #[error("{0}")]
+    XdrError(#[from] stellar_xdr::curr::Error),
+    /// Error returned by the Soroban RPC server
+    #[error("Soroban RPC error {code}: {message}")]
+    RpcError {
+        /// The JSON-RPC error code
+        code: i64,
+        /// The error message
+        message: String,
+    },
+    /// The simulation of a contract invocation failed
+    #[error("Simulation failed: {0}")]
+    SimulationError(String),
+    /// A transaction was rejected by the RPC server
+    #[error("Transaction rejected with status {status}: {result:?}")]
+    TransactionRejected {
+        /// The status of the submission
+        status: String,
+        /// The base64 XDR encoded result, if any
+        result: Option<String>,
+    },
+    /// A response that doesn't have the expected shape
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+}
+
+impl From<HyperlaneStellarError> for ChainCommunicationError {
+    fn from(value: HyperlaneStellarError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
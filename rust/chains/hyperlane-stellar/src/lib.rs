@@ -0,0 +1,16 @@
+//! Implementation of hyperlane for Stellar, whose smart contracts run on
+//! Soroban.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+mod client;
+mod conversions;
+mod error;
+mod mailbox;
+mod provider;
+mod signers;
+mod trait_builder;
+mod types;
+
+pub use self::{error::*, mailbox::*, provider::*, signers::*, trait_builder::*};
@@ -0,0 +1,300 @@
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use stellar_xdr::curr::{Limits, ScVal, ScVec, WriteXdr};
+use tracing::{instrument, warn};
+
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, Decode,
+    FixedPointNumber, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, RawHyperlaneMessage,
+    SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, U256,
+};
+
+use crate::{
+    conversions::{bytes, contract_address, to_bool, to_bytes, to_h256, to_u32},
+    types::SUCCESS,
+    ConnectionConf, Signer, StellarProvider,
+};
+
+/// The topics of the events emitted by the mailbox, whose data is
+/// respectively the encoded message and the message id
+const DISPATCH_TOPIC: &str = "dispatch";
+const PROCESS_TOPIC: &str = "process";
+
+/// A reference to a Mailbox contract on some Stellar chain
+#[derive(Debug)]
+pub struct StellarMailbox {
+    address: H256,
+    provider: StellarProvider,
+}
+
+impl StellarMailbox {
+    /// Create a new Stellar mailbox
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: StellarProvider::new(locator.domain.clone(), conf, signer),
+        })
+    }
+
+    /// The arguments of the `process` function
+    fn process_args(message: &HyperlaneMessage, metadata: &[u8]) -> ChainResult<Vec<ScVal>> {
+        Ok(vec![
+            bytes(metadata)?,
+            bytes(&RawHyperlaneMessage::from(message))?,
+        ])
+    }
+}
+
+impl HyperlaneContract for StellarMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for StellarMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for StellarMailbox {
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        assert!(
+            lag.is_none(),
+            "Stellar does not support querying point-in-time"
+        );
+        let nonce = self.provider.read(&self.address, "nonce", vec![]).await?;
+        Ok(to_u32(&nonce)?)
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let delivered = self
+            .provider
+            .read(&self.address, "delivered", vec![bytes(id.as_bytes())?])
+            .await?;
+        Ok(to_bool(&delivered)?)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        let ism = self
+            .provider
+            .read(&self.address, "default_ism", vec![])
+            .await?;
+        Ok(to_h256(&ism)?)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let ism = self
+            .provider
+            .read(
+                &self.address,
+                "recipient_ism",
+                vec![ScVal::Address(contract_address(&recipient))],
+            )
+            .await?;
+        Ok(to_h256(&ism)?)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        _tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let (hash, status) = self
+            .provider
+            .invoke(
+                &self.address,
+                "process",
+                Self::process_args(message, metadata)?,
+            )
+            .await?;
+        let executed = status.status == SUCCESS;
+        if !executed {
+            warn!(?hash, status = %status.status, "Stellar transaction failed");
+        }
+        Ok(TxOutcome {
+            transaction_id: hash.into(),
+            executed,
+            gas_used: status.fee_charged()?.into(),
+            gas_price: FixedPointNumber::from(1u64),
+        })
+    }
+
+    #[instrument(err, ret, skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        let fee = self
+            .provider
+            .estimate_fee(
+                &self.address,
+                "process",
+                Self::process_args(message, metadata)?,
+            )
+            .await?;
+        // The inclusion and resource fees are charged in stroops, so costs
+        // are reported at a gas price of 1
+        Ok(TxCostEstimate {
+            gas_limit: fee.into(),
+            gas_price: FixedPointNumber::from(1u64),
+            l2_gas_limit: None,
+            resources: vec![],
+        })
+    }
+
+    /// The XDR encoded arguments of `process`
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        let args: ScVec = Self::process_args(message, metadata)
+            .expect("arguments are valid")
+            .try_into()
+            .expect("arguments fit in a vector");
+        args.to_xdr(Limits::none())
+            .expect("arguments are encodable")
+    }
+}
+
+/// Struct that retrieves dispatched messages and processed message ids from
+/// the events of a Mailbox contract on some Stellar chain
+#[derive(Debug)]
+pub struct StellarMailboxIndexer {
+    mailbox: StellarMailbox,
+}
+
+impl StellarMailboxIndexer {
+    /// Create a new Stellar mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            mailbox: StellarMailbox::new(conf, locator, None)?,
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for StellarMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        let events = self
+            .mailbox
+            .provider
+            .events_in_range(&self.mailbox.address, DISPATCH_TOPIC, range)
+            .await?;
+        events
+            .into_iter()
+            .map(|(value, meta)| {
+                let message = to_bytes(&value)?;
+                let message = HyperlaneMessage::read_from(&mut message.as_slice())
+                    .map_err(ChainCommunicationError::from_other)?;
+                Ok((message.into(), meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.latest_ledger().await
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for StellarMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        let events = self
+            .mailbox
+            .provider
+            .events_in_range(&self.mailbox.address, PROCESS_TOPIC, range)
+            .await?;
+        Ok(events
+            .into_iter()
+            .filter_map(|(value, meta)| match to_h256(&value) {
+                Ok(id) => Some((Indexed::new(id), meta)),
+                Err(_) => {
+                    warn!(?meta, "Process event has no valid message id, skipping");
+                    None
+                }
+            })
+            .collect())
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.latest_ledger().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for StellarMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
+        let count = Mailbox::count(&self.mailbox, None).await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for StellarMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<H256>::get_finalized_block_number(self).await?;
+        // No sequence for message deliveries.
+        Ok((None, tip))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::config::OperationBatchConfig;
+    use url::Url;
+
+    use super::*;
+
+    #[test]
+    fn test_process_calldata() {
+        let domain = HyperlaneDomain::new_test_domain("test");
+        let conf = ConnectionConf::new(
+            Url::parse("https://soroban-testnet.stellar.org").unwrap(),
+            None,
+            OperationBatchConfig::default(),
+        );
+        let locator = ContractLocator {
+            domain: &domain,
+            address: H256::zero(),
+        };
+        let mailbox = StellarMailbox::new(&conf, locator, None).unwrap();
+        let message = HyperlaneMessage::default();
+
+        // A vector of the two byte arguments, each padded to a multiple of 4
+        // bytes
+        let raw_message = RawHyperlaneMessage::from(&message);
+        assert_eq!(
+            hex::encode(mailbox.process_calldata(&message, &[0xaa])),
+            format!(
+                "00000002{}{}{}000000",
+                "0000000d00000001aa000000",
+                "0000000d0000004d",
+                hex::encode(raw_message)
+            )
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneDomainProtocol, HyperlaneProvider, LogMeta, TxnInfo, H256, U256,
+};
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{
+    AccountEntry, ContractDataDurability, Hash, HostFunction, InvokeContractArgs,
+    InvokeHostFunctionOp, LedgerEntryData, LedgerKey, LedgerKeyAccount, LedgerKeyContractData,
+    Limits, Memo, MuxedAccount, Operation, OperationBody, Preconditions, ReadXdr, ScSymbol, ScVal,
+    SequenceNumber, SorobanAuthorizationEntry, SorobanTransactionData, Transaction,
+    TransactionEnvelope, TransactionExt, TransactionSignaturePayload,
+    TransactionSignaturePayloadTaggedTransaction, TransactionV1Envelope, Uint256, WriteXdr,
+};
+use tracing::debug;
+
+use crate::{
+    client::{SorobanClient, EVENTS_PAGE_SIZE},
+    conversions::{account_id, contract_address, symbol},
+    types::{Simulation, TransactionStatus, NOT_FOUND, PENDING},
+    ConnectionConf, HyperlaneStellarError, Signer,
+};
+
+/// The fee, in stroops, bid to include an operation in a ledger
+const BASE_FEE: u32 = 100;
+/// How often a submitted transaction is polled for its outcome
+const TRANSACTION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How many times a transaction is polled before giving up. Ledgers close
+/// about every 5 seconds.
+const TRANSACTION_POLL_ATTEMPTS: usize = 30;
+
+/// The index of the transaction in its ledger and of the event in the
+/// transaction, from the id of an event. The TOID of the operation packs the
+/// ledger sequence, the application order of the transaction and the index
+/// of the operation in 32, 20 and 12 bits.
+fn event_indices(id: &str) -> Option<(u64, u64)> {
+    let (toid, index) = id.split_once('-')?;
+    let toid: u64 = toid.parse().ok()?;
+    Some(((toid >> 12) & 0xfffff, index.parse().ok()?))
+}
+
+fn transaction(
+    source: &H256,
+    seq_num: i64,
+    fee: u32,
+    operation: Operation,
+    ext: TransactionExt,
+) -> Result<Transaction, HyperlaneStellarError> {
+    Ok(Transaction {
+        source_account: MuxedAccount::Ed25519(Uint256(source.0)),
+        fee,
+        seq_num: SequenceNumber(seq_num),
+        cond: Preconditions::None,
+        memo: Memo::None,
+        operations: vec![operation].try_into()?,
+        ext,
+    })
+}
+
+fn invocation(
+    contract: &H256,
+    function: &str,
+    args: Vec<ScVal>,
+    auth: Vec<SorobanAuthorizationEntry>,
+) -> Result<Operation, HyperlaneStellarError> {
+    Ok(Operation {
+        source_account: None,
+        body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+            host_function: HostFunction::InvokeContract(InvokeContractArgs {
+                contract_address: contract_address(contract),
+                function_name: ScSymbol(function.try_into()?),
+                args: args.try_into()?,
+            }),
+            auth: auth.try_into()?,
+        }),
+    })
+}
+
+/// A wrapper around a Soroban RPC server to get generic blockchain
+/// information, simulate contract invocations and send transactions.
+#[derive(Debug, Clone)]
+pub struct StellarProvider {
+    domain: HyperlaneDomain,
+    client: SorobanClient,
+    network_passphrase: Option<String>,
+    signer: Option<Signer>,
+}
+
+impl StellarProvider {
+    /// Create a new Stellar provider
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf, signer: Option<Signer>) -> Self {
+        Self {
+            domain,
+            client: SorobanClient::new(conf.url.clone()),
+            network_passphrase: conf.network_passphrase.clone(),
+            signer,
+        }
+    }
+
+    fn signer(&self) -> ChainResult<&Signer> {
+        self.signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)
+    }
+
+    /// The sequence number of the latest ledger, which indexing uses as the
+    /// block number
+    pub(crate) async fn latest_ledger(&self) -> ChainResult<u32> {
+        self.client.latest_ledger().await
+    }
+
+    async fn account(&self, account: &H256) -> ChainResult<Option<AccountEntry>> {
+        let key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: account_id(account),
+        });
+        match self.client.ledger_entry(&key).await? {
+            Some(LedgerEntryData::Account(entry)) => Ok(Some(entry)),
+            Some(entry) => Err(HyperlaneStellarError::UnexpectedResponse(format!(
+                "expected an account entry, got {entry:?}"
+            ))
+            .into()),
+            None => Ok(None),
+        }
+    }
+
+    /// The hash of `transaction`, which is what gets signed
+    async fn transaction_hash(&self, transaction: &Transaction) -> ChainResult<H256> {
+        let passphrase = match &self.network_passphrase {
+            Some(passphrase) => passphrase.clone(),
+            None => self.client.network_passphrase().await?,
+        };
+        let payload = TransactionSignaturePayload {
+            network_id: Hash(Sha256::digest(passphrase.as_bytes()).into()),
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(
+                transaction.clone(),
+            ),
+        };
+        let payload = payload
+            .to_xdr(Limits::none())
+            .map_err(HyperlaneStellarError::from)?;
+        Ok(H256(Sha256::digest(payload).into()))
+    }
+
+    async fn simulate(&self, transaction: Transaction) -> ChainResult<Simulation> {
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: transaction,
+            signatures: Default::default(),
+        });
+        let simulation = self.client.simulate(&envelope).await?;
+        match simulation.error {
+            Some(error) => Err(HyperlaneStellarError::SimulationError(error).into()),
+            None => Ok(simulation),
+        }
+    }
+
+    /// The value returned by `function` of `contract` when called with
+    /// `args`, without sending a transaction
+    pub(crate) async fn read(
+        &self,
+        contract: &H256,
+        function: &str,
+        args: Vec<ScVal>,
+    ) -> ChainResult<ScVal> {
+        // Simulations don't check the source account, so any key works
+        let source = self
+            .signer
+            .as_ref()
+            .map(Signer::public_key)
+            .unwrap_or_default();
+        let operation = invocation(contract, function, args, vec![])?;
+        let transaction = transaction(&source, 0, BASE_FEE, operation, TransactionExt::V0)?;
+        let simulation = self.simulate(transaction).await?;
+        let result = simulation.results.first().ok_or_else(|| {
+            HyperlaneStellarError::UnexpectedResponse(format!("`{function}` returned no result"))
+        })?;
+        Ok(ScVal::from_xdr_base64(&result.xdr, Limits::none())
+            .map_err(HyperlaneStellarError::from)?)
+    }
+
+    /// The fee, in stroops, paid by the signer to call `function` of
+    /// `contract` with `args`
+    pub(crate) async fn estimate_fee(
+        &self,
+        contract: &H256,
+        function: &str,
+        args: Vec<ScVal>,
+    ) -> ChainResult<u64> {
+        let source = self.signer()?.public_key();
+        let operation = invocation(contract, function, args, vec![])?;
+        let transaction = transaction(&source, 0, BASE_FEE, operation, TransactionExt::V0)?;
+        let simulation = self.simulate(transaction).await?;
+        Ok(u64::from(BASE_FEE) + simulation.min_resource_fee)
+    }
+
+    /// Calls `function` of `contract` with `args` from the signer's account,
+    /// and waits for the transaction to be included in a ledger
+    pub(crate) async fn invoke(
+        &self,
+        contract: &H256,
+        function: &str,
+        args: Vec<ScVal>,
+    ) -> ChainResult<(H256, TransactionStatus)> {
+        let signer = self.signer()?;
+        let source = signer.public_key();
+        let account = self.account(&source).await?.ok_or_else(|| {
+            HyperlaneStellarError::UnexpectedResponse(format!(
+                "account {} does not exist",
+                signer.address_string()
+            ))
+        })?;
+        let seq_num = account.seq_num.0 + 1;
+
+        // The simulation finds the footprint, resources and authorizations
+        // that the transaction must declare
+        let operation = invocation(contract, function, args.clone(), vec![])?;
+        let simulation = self
+            .simulate(transaction(
+                &source,
+                seq_num,
+                BASE_FEE,
+                operation,
+                TransactionExt::V0,
+            )?)
+            .await?;
+        let data = simulation
+            .transaction_data
+            .as_deref()
+            .map(|data| SorobanTransactionData::from_xdr_base64(data, Limits::none()))
+            .transpose()
+            .map_err(HyperlaneStellarError::from)?
+            .ok_or_else(|| {
+                HyperlaneStellarError::UnexpectedResponse(format!(
+                    "simulation of `{function}` has no transaction data"
+                ))
+            })?;
+        let auth = simulation
+            .results
+            .first()
+            .map(|result| result.auth.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| SorobanAuthorizationEntry::from_xdr_base64(entry, Limits::none()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(HyperlaneStellarError::from)?;
+        let fee = (u64::from(BASE_FEE) + simulation.min_resource_fee)
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+        let transaction = transaction(
+            &source,
+            seq_num,
+            fee,
+            invocation(contract, function, args, auth)?,
+            TransactionExt::V1(data),
+        )?;
+
+        let hash = self.transaction_hash(&transaction).await?;
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: transaction,
+            signatures: vec![signer.sign(&hash)?]
+                .try_into()
+                .map_err(HyperlaneStellarError::from)?,
+        });
+        let result = self.client.send(&envelope).await?;
+        if result.status != PENDING {
+            return Err(HyperlaneStellarError::TransactionRejected {
+                status: result.status,
+                result: result.error_result_xdr,
+            }
+            .into());
+        }
+        debug!(?hash, function, "Sent Stellar transaction");
+
+        for _ in 0..TRANSACTION_POLL_ATTEMPTS {
+            tokio::time::sleep(TRANSACTION_POLL_INTERVAL).await;
+            let status = self.client.transaction(&hash).await?;
+            if status.status != NOT_FOUND {
+                return Ok((hash, status));
+            }
+        }
+        Err(ChainCommunicationError::TransactionTimeout())
+    }
+
+    /// The data of the events with `topic` as their first topic emitted by
+    /// `contract` in the ledgers in `range`, along with their metadata
+    pub(crate) async fn events_in_range(
+        &self,
+        contract: &H256,
+        topic: &str,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(ScVal, LogMeta)>> {
+        let contract_id = stellar_strkey::Contract(contract.0).to_string();
+        let topic = symbol(topic)?
+            .to_xdr_base64(Limits::none())
+            .map_err(HyperlaneStellarError::from)?;
+
+        let mut events = vec![];
+        let mut cursor = None;
+        loop {
+            let page = self
+                .client
+                .events(
+                    &contract_id,
+                    &topic,
+                    *range.start(),
+                    *range.end(),
+                    cursor.as_deref(),
+                )
+                .await?;
+            let count = page.events.len();
+            cursor = page.events.last().map(|event| event.paging_token.clone());
+            for event in page.events {
+                // Events of failed invocations are only diagnostics
+                if !range.contains(&event.ledger) || !event.in_successful_contract_call {
+                    continue;
+                }
+                let (transaction_index, log_index) = event_indices(&event.id).ok_or_else(|| {
+                    HyperlaneStellarError::UnexpectedResponse(format!(
+                        "invalid event id `{}`",
+                        event.id
+                    ))
+                })?;
+                let transaction_id = event
+                    .tx_hash
+                    .parse::<H256>()
+                    .map_err(ChainCommunicationError::from_other)?;
+                let value = ScVal::from_xdr_base64(&event.value, Limits::none())
+                    .map_err(HyperlaneStellarError::from)?;
+                events.push((
+                    value,
+                    LogMeta {
+                        address: *contract,
+                        block_number: event.ledger.into(),
+                        // Events don't include the hash of their ledger
+                        block_hash: H256::zero(),
+                        transaction_id: transaction_id.into(),
+                        transaction_index,
+                        log_index: log_index.into(),
+                    },
+                ));
+            }
+            if count < EVENTS_PAGE_SIZE {
+                return Ok(events);
+            }
+        }
+    }
+}
+
+impl HyperlaneChain for StellarProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for StellarProvider {
+    async fn get_block_by_hash(&self, _hash: &H256) -> ChainResult<BlockInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "Stellar does not support fetching blocks by hash yet",
+        ))
+    }
+
+    async fn get_txn_by_hash(&self, _hash: &H256) -> ChainResult<TxnInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "Stellar does not support fetching transactions yet",
+        ))
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        // Deployed contracts have an instance entry
+        let key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: contract_address(address),
+            key: ScVal::LedgerKeyContractInstance,
+            durability: ContractDataDurability::Persistent,
+        });
+        Ok(self.client.ledger_entry(&key).await?.is_some())
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        let address = HyperlaneDomainProtocol::Stellar.parse_address(&address)?;
+        let balance = self
+            .account(&address)
+            .await?
+            .map(|account| account.balance.unsigned_abs())
+            .unwrap_or_default();
+        Ok(balance.into())
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        Ok(None)
+    }
+}
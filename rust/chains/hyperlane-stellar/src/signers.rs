@@ -0,0 +1,55 @@
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer as _};
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+use stellar_xdr::curr::{DecoratedSignature, Signature, SignatureHint};
+
+use crate::HyperlaneStellarError;
+
+/// An ed25519 key signing transactions for a Stellar account
+#[derive(Debug)]
+pub struct Signer {
+    keypair: Keypair,
+}
+
+impl Signer {
+    /// Create a new Stellar signer from an ed25519 private key
+    pub fn new(private_key: &H256) -> ChainResult<Self> {
+        let secret = SecretKey::from_bytes(private_key.as_bytes())
+            .map_err(ChainCommunicationError::from_other)?;
+        let public = PublicKey::from(&secret);
+        Ok(Self {
+            keypair: Keypair { secret, public },
+        })
+    }
+
+    /// The public key of the account, which is its id
+    pub fn public_key(&self) -> H256 {
+        H256(self.keypair.public.to_bytes())
+    }
+
+    /// Signs the hash of a transaction. The hint is the last 4 bytes of the
+    /// public key, which identifies the signer of the account.
+    pub(crate) fn sign(&self, hash: &H256) -> Result<DecoratedSignature, HyperlaneStellarError> {
+        let public_key = self.keypair.public.to_bytes();
+        let mut hint = [0; 4];
+        hint.copy_from_slice(&public_key[28..]);
+        let signature = self.keypair.sign(hash.as_bytes());
+        Ok(DecoratedSignature {
+            hint: SignatureHint(hint),
+            signature: Signature(signature.to_bytes().to_vec().try_into()?),
+        })
+    }
+
+    /// The `G...` strkey address of the account
+    pub fn address_string(&self) -> String {
+        stellar_strkey::ed25519::PublicKey(self.keypair.public.to_bytes()).to_string()
+    }
+}
+
+impl Clone for Signer {
+    fn clone(&self) -> Self {
+        Self {
+            keypair: Keypair::from_bytes(&self.keypair.to_bytes())
+                .expect("keypair bytes are valid"),
+        }
+    }
+}
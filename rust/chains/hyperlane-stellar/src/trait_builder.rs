@@ -0,0 +1,30 @@
+use hyperlane_core::config::OperationBatchConfig;
+use url::Url;
+
+/// Stellar connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Fully qualified url of a Soroban RPC server, e.g.
+    /// `https://soroban-testnet.stellar.org`
+    pub url: Url,
+    /// The passphrase of the network that transactions are signed for. It is
+    /// queried from the RPC server when not set.
+    pub network_passphrase: Option<String>,
+    /// Operation batching configuration
+    pub operation_batch: OperationBatchConfig,
+}
+
+impl ConnectionConf {
+    /// Create a new connection configuration
+    pub fn new(
+        url: Url,
+        network_passphrase: Option<String>,
+        operation_batch: OperationBatchConfig,
+    ) -> Self {
+        Self {
+            url,
+            network_passphrase,
+            operation_batch,
+        }
+    }
+}
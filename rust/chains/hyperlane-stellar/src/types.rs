@@ -0,0 +1,126 @@
+//! The JSON types returned by the Soroban RPC API. XDR values are base64
+//! encoded.
+
+use serde::Deserialize;
+use stellar_xdr::curr::{Limits, ReadXdr, TransactionResult};
+
+use crate::HyperlaneStellarError;
+
+/// The status of transactions that were applied successfully
+pub(crate) const SUCCESS: &str = "SUCCESS";
+/// The status of transactions that aren't in a closed ledger yet
+pub(crate) const NOT_FOUND: &str = "NOT_FOUND";
+/// The status of transactions accepted by the RPC server
+pub(crate) const PENDING: &str = "PENDING";
+
+/// Deserializes a u64 that the API encodes as a string
+fn u64_from_str<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+}
+
+/// The response of `getLatestLedger`
+#[derive(Deserialize, Debug)]
+pub(crate) struct LatestLedger {
+    pub sequence: u32,
+}
+
+/// The response of `getNetwork`
+#[derive(Deserialize, Debug)]
+pub(crate) struct Network {
+    pub passphrase: String,
+}
+
+/// The response of `getLedgerEntries`, which only includes the entries that
+/// exist
+#[derive(Deserialize, Debug)]
+pub(crate) struct LedgerEntries {
+    #[serde(default)]
+    pub entries: Vec<LedgerEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct LedgerEntry {
+    /// The XDR encoded `LedgerEntryData`
+    pub xdr: String,
+}
+
+/// The response of `getEvents`
+#[derive(Deserialize, Debug)]
+pub(crate) struct EventPage {
+    #[serde(default)]
+    pub events: Vec<Event>,
+}
+
+/// An event emitted by a contract
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Event {
+    pub ledger: u32,
+    /// The id of the operation that emitted the event, as a TOID, and the
+    /// index of the event in the operation, e.g. `0000000012884905984-0000000001`
+    pub id: String,
+    pub paging_token: String,
+    /// The XDR encoded `ScVal` topics
+    pub topic: Vec<String>,
+    /// The XDR encoded `ScVal` data
+    pub value: String,
+    /// Hex encoded
+    pub tx_hash: String,
+    pub in_successful_contract_call: bool,
+}
+
+/// The response of `simulateTransaction`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Simulation {
+    pub error: Option<String>,
+    /// The XDR encoded `SorobanTransactionData`, with the footprint and
+    /// resources of the invocation
+    pub transaction_data: Option<String>,
+    /// The fee, in stroops, charged for the resources of the invocation
+    #[serde(default, deserialize_with = "u64_from_str")]
+    pub min_resource_fee: u64,
+    #[serde(default)]
+    pub results: Vec<SimulationResult>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct SimulationResult {
+    /// The XDR encoded `SorobanAuthorizationEntry`s the invocation requires
+    #[serde(default)]
+    pub auth: Vec<String>,
+    /// The XDR encoded `ScVal` returned by the invocation
+    pub xdr: String,
+}
+
+/// The response of `sendTransaction`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SendResult {
+    /// One of `PENDING`, `DUPLICATE`, `TRY_AGAIN_LATER` or `ERROR`
+    pub status: String,
+    /// The XDR encoded `TransactionResult` of rejected transactions
+    pub error_result_xdr: Option<String>,
+}
+
+/// The response of `getTransaction`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TransactionStatus {
+    /// One of `SUCCESS`, `NOT_FOUND` or `FAILED`
+    pub status: String,
+    /// The XDR encoded `TransactionResult`
+    pub result_xdr: Option<String>,
+}
+
+impl TransactionStatus {
+    /// The fee charged for the transaction, in stroops
+    pub(crate) fn fee_charged(&self) -> Result<u64, HyperlaneStellarError> {
+        let Some(result) = &self.result_xdr else {
+            return Ok(0);
+        };
+        let result = TransactionResult::from_xdr_base64(result, Limits::none())?;
+        Ok(result.fee_charged.unsigned_abs())
+    }
+}
@@ -0,0 +1,30 @@
+use hyperlane_core::ChainCommunicationError;
+
+/// Errors from the crates specific to the hyperlane-substrate
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneSubstrateError {
+    /// subxt error
+    #[error("{0}")]
+    SubxtError(#[from] subxt::Error),
+    /// SCALE decoding error
+    #[error("{0}")]
+    CodecError(#[from] subxt::ext::codec::Error),
+    /// Error decoding a value using the type information of the metadata
+    #[error("{0}")]
+    DecodeError(#[from] subxt::ext::scale_decode::Error),
+    /// Key derivation error
+    #[error("{0}")]
+    SignerError(String),
+    /// A block the node doesn't know about
+    #[error("Block {0} not found")]
+    MissingBlock(u32),
+}
+
+impl From<HyperlaneSubstrateError> for ChainCommunicationError {
+    fn from(value: HyperlaneSubstrateError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
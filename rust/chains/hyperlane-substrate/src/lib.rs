@@ -0,0 +1,13 @@
+//! Implementation of hyperlane for Substrate chains, such as Polkadot
+//! parachains, running the Hyperlane pallet.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+mod error;
+mod mailbox;
+mod provider;
+mod signers;
+mod trait_builder;
+
+pub use self::{error::*, mailbox::*, provider::*, signers::*, trait_builder::*};
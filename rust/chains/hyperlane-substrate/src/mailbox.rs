@@ -0,0 +1,347 @@
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use subxt::{dynamic::Value, ext::codec::Decode as _};
+use tracing::{instrument, warn};
+
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, Decode,
+    FixedPointNumber, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, RawHyperlaneMessage,
+    SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, U256,
+};
+
+use crate::{ConnectionConf, HyperlaneSubstrateError, Signer, SubstrateProvider};
+
+/// The storage entries of the Hyperlane pallet
+const NONCE_ENTRY: &str = "Nonce";
+const DELIVERED_ENTRY: &str = "Delivered";
+const DEFAULT_ISM_ENTRY: &str = "DefaultIsm";
+const RECIPIENT_ISM_ENTRY: &str = "RecipientIsm";
+/// The events of the Hyperlane pallet, whose first field is respectively the
+/// encoded message and the message id
+const DISPATCH_EVENT: &str = "Dispatch";
+const PROCESS_EVENT: &str = "Process";
+
+/// A reference to the Mailbox of the Hyperlane pallet on some Substrate
+/// chain. Pallets have no address, so the configured one is only used to
+/// identify the mailbox.
+#[derive(Debug)]
+pub struct SubstrateMailbox {
+    address: H256,
+    provider: SubstrateProvider,
+}
+
+impl SubstrateMailbox {
+    /// Create a new Substrate mailbox
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: SubstrateProvider::new(locator.domain.clone(), conf, signer),
+        })
+    }
+
+    /// The arguments of the `process` call
+    fn process_args(message: &HyperlaneMessage, metadata: &[u8]) -> Vec<Value> {
+        vec![
+            Value::from_bytes(metadata),
+            Value::from_bytes(RawHyperlaneMessage::from(message)),
+        ]
+    }
+}
+
+impl HyperlaneContract for SubstrateMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for SubstrateMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for SubstrateMailbox {
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        assert!(
+            lag.is_none(),
+            "Substrate does not support querying point-in-time"
+        );
+        let nonce = self.provider.storage(NONCE_ENTRY, vec![]).await?;
+        Ok(nonce.unwrap_or_default())
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let delivered = self
+            .provider
+            .storage(DELIVERED_ENTRY, vec![Value::from_bytes(id.as_bytes())])
+            .await?;
+        Ok(delivered.unwrap_or_default())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        let ism: Option<[u8; 32]> = self.provider.storage(DEFAULT_ISM_ENTRY, vec![]).await?;
+        Ok(ism.unwrap_or_default().into())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let ism: Option<[u8; 32]> = self
+            .provider
+            .storage(
+                RECIPIENT_ISM_ENTRY,
+                vec![Value::from_bytes(recipient.as_bytes())],
+            )
+            .await?;
+        match ism {
+            Some(ism) => Ok(ism.into()),
+            None => self.default_ism().await,
+        }
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        _tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let call = self
+            .provider
+            .call("process", Self::process_args(message, metadata));
+        let extrinsic = self.provider.sign(&call).await?;
+        let transaction_id = H256(extrinsic.hash().0);
+        // Weights are charged upfront, so the estimate is the fee paid
+        let fee = extrinsic
+            .partial_fee_estimate()
+            .await
+            .map_err(HyperlaneSubstrateError::from)?;
+
+        let progress = extrinsic
+            .submit_and_watch()
+            .await
+            .map_err(HyperlaneSubstrateError::from)?;
+        let executed = match progress.wait_for_finalized_success().await {
+            Ok(_) => true,
+            Err(subxt::Error::Runtime(err)) => {
+                warn!(?transaction_id, ?err, "Substrate extrinsic failed");
+                false
+            }
+            Err(err) => return Err(HyperlaneSubstrateError::from(err).into()),
+        };
+
+        Ok(TxOutcome {
+            transaction_id: transaction_id.into(),
+            executed,
+            gas_used: fee.into(),
+            gas_price: FixedPointNumber::from(1u64),
+        })
+    }
+
+    #[instrument(err, ret, skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        let call = self
+            .provider
+            .call("process", Self::process_args(message, metadata));
+        let fee = self
+            .provider
+            .sign(&call)
+            .await?
+            .partial_fee_estimate()
+            .await
+            .map_err(HyperlaneSubstrateError::from)?;
+        // Fees are computed from the weight and length of extrinsics, so
+        // costs are in the smallest unit of the native token at a gas price
+        // of 1
+        Ok(TxCostEstimate {
+            gas_limit: fee.into(),
+            gas_price: FixedPointNumber::from(1u64),
+            l2_gas_limit: None,
+            resources: vec![],
+        })
+    }
+
+    /// The SCALE encoded arguments of `process`, as the index of the call
+    /// depends on the runtime
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        use subxt::ext::codec::Encode;
+
+        (metadata, RawHyperlaneMessage::from(message)).encode()
+    }
+}
+
+/// Struct that retrieves dispatched messages and processed message ids from
+/// the events of the Hyperlane pallet on some Substrate chain
+#[derive(Debug)]
+pub struct SubstrateMailboxIndexer {
+    mailbox: SubstrateMailbox,
+}
+
+impl SubstrateMailboxIndexer {
+    /// Create a new Substrate mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            mailbox: SubstrateMailbox::new(conf, locator, None)?,
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for SubstrateMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        let events = self
+            .mailbox
+            .provider
+            .events_in_range(self.mailbox.address, DISPATCH_EVENT, range)
+            .await?;
+        events
+            .into_iter()
+            .map(|event| {
+                let message = Vec::<u8>::decode(&mut event.fields.as_slice())
+                    .map_err(HyperlaneSubstrateError::from)?;
+                let message = HyperlaneMessage::read_from(&mut message.as_slice())
+                    .map_err(ChainCommunicationError::from_other)?;
+                Ok((message.into(), event.meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for SubstrateMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        let events = self
+            .mailbox
+            .provider
+            .events_in_range(self.mailbox.address, PROCESS_EVENT, range)
+            .await?;
+        Ok(events
+            .into_iter()
+            .filter_map(
+                |event| match <[u8; 32]>::decode(&mut event.fields.as_slice()) {
+                    Ok(id) => Some((Indexed::new(H256(id)), event.meta)),
+                    Err(_) => {
+                        warn!(meta=?event.meta, "Process event has no valid message id, skipping");
+                        None
+                    }
+                },
+            )
+            .collect())
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for SubstrateMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
+        let count = Mailbox::count(&self.mailbox, None).await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for SubstrateMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<H256>::get_finalized_block_number(self).await?;
+        // No sequence for message deliveries.
+        Ok((None, tip))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use hyperlane_core::config::OperationBatchConfig;
+    use url::Url;
+
+    use super::*;
+
+    #[test]
+    fn test_process_calldata() {
+        let domain = HyperlaneDomain::new_test_domain("test");
+        let conf = ConnectionConf::new(
+            Url::parse("ws://localhost:9944").unwrap(),
+            None,
+            None,
+            OperationBatchConfig::default(),
+        );
+        let locator = ContractLocator {
+            domain: &domain,
+            address: H256::zero(),
+        };
+        let mailbox = SubstrateMailbox::new(&conf, locator, None).unwrap();
+        let message = HyperlaneMessage {
+            body: vec![0xaa, 0xbb, 0xcc],
+            ..Default::default()
+        };
+
+        // Both arguments are byte vectors, prefixed with their compact
+        // encoded length: 3 in single byte mode and 80 in two byte mode
+        assert_eq!(
+            mailbox.process_calldata(&message, &[1, 2, 3]),
+            [
+                vec![0x0c, 1, 2, 3],
+                vec![0x41, 0x01],
+                RawHyperlaneMessage::from(&message),
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn test_storage_root_keys() {
+        // The root key of an entry is the twox128 hash of the pallet name
+        // followed by the one of the entry name
+        let root = |pallet: &str, entry: &str| {
+            H256::from_slice(&subxt::dynamic::storage(pallet, entry, vec![]).to_root_bytes())
+        };
+        assert_eq!(
+            root("System", "Account"),
+            H256::from_str("26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9")
+                .unwrap()
+        );
+        assert_eq!(
+            root("Mailbox", NONCE_ENTRY),
+            H256::from_str("fade8a86028f74937a49e1a9f91f61cc718368a0ace36e2b1b8b6dbd7f8093c0")
+                .unwrap()
+        );
+        assert_eq!(
+            root("Mailbox", DELIVERED_ENTRY),
+            H256::from_str("fade8a86028f74937a49e1a9f91f61cc64757c2c47f9ac7068448d64162d9f66")
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneDomainProtocol, HyperlaneProvider, LogMeta, TxnInfo, H256, H512, U256,
+};
+use subxt::{
+    backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+    config::polkadot::PolkadotExtrinsicParamsBuilder,
+    dynamic::Value,
+    ext::scale_decode::DecodeAsType,
+    tx::{SubmittableExtrinsic, TxPayload},
+    OnlineClient, PolkadotConfig,
+};
+use tokio::sync::OnceCell;
+use tracing::warn;
+
+use crate::{ConnectionConf, HyperlaneSubstrateError, Signer};
+
+/// The pallet and storage entry of account balances
+const SYSTEM_PALLET: &str = "System";
+const ACCOUNT_ENTRY: &str = "Account";
+
+/// The clients of a node, connected on first use
+#[derive(Clone)]
+struct Clients {
+    api: OnlineClient<PolkadotConfig>,
+    rpc: LegacyRpcMethods<PolkadotConfig>,
+}
+
+/// The data of an event emitted by the Hyperlane pallet, along with its
+/// metadata
+pub(crate) struct PalletEvent {
+    /// The SCALE encoded fields of the event
+    pub fields: Vec<u8>,
+    pub meta: LogMeta,
+}
+
+/// A wrapper around a Substrate node to get generic blockchain information,
+/// query the Hyperlane pallet and submit its extrinsics.
+#[derive(Clone)]
+pub struct SubstrateProvider {
+    domain: HyperlaneDomain,
+    conf: ConnectionConf,
+    signer: Option<Signer>,
+    clients: Arc<OnceCell<Clients>>,
+}
+
+impl SubstrateProvider {
+    /// Create a new Substrate provider
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf, signer: Option<Signer>) -> Self {
+        Self {
+            domain,
+            conf: conf.clone(),
+            signer,
+            clients: Default::default(),
+        }
+    }
+
+    /// Connects to the node on first use. The runtime metadata is kept up to
+    /// date in the background, so that extrinsics and storage queries keep
+    /// working across runtime upgrades.
+    async fn clients(&self) -> ChainResult<&Clients> {
+        self.clients
+            .get_or_try_init(|| async {
+                let rpc = RpcClient::from_url(self.conf.url.as_str())
+                    .await
+                    .map_err(HyperlaneSubstrateError::from)?;
+                let api = OnlineClient::<PolkadotConfig>::from_rpc_client(rpc.clone())
+                    .await
+                    .map_err(HyperlaneSubstrateError::from)?;
+
+                let updater = api.updater();
+                let domain = self.domain.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = updater.perform_runtime_updates().await {
+                        warn!(?err, %domain, "Substrate runtime updates stopped");
+                    }
+                });
+
+                Ok(Clients {
+                    api,
+                    rpc: LegacyRpcMethods::new(rpc),
+                })
+            })
+            .await
+    }
+
+    fn signer(&self) -> ChainResult<&Signer> {
+        self.signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)
+    }
+
+    /// The number of the latest finalized block
+    pub(crate) async fn finalized_block_number(&self) -> ChainResult<u32> {
+        let rpc = &self.clients().await?.rpc;
+        let hash = rpc
+            .chain_get_finalized_head()
+            .await
+            .map_err(HyperlaneSubstrateError::from)?;
+        let header = rpc
+            .chain_get_header(Some(hash))
+            .await
+            .map_err(HyperlaneSubstrateError::from)?
+            .ok_or_else(|| {
+                ChainCommunicationError::CustomError(format!(
+                    "Finalized block {hash:?} has no header"
+                ))
+            })?;
+        Ok(header.number)
+    }
+
+    /// The value of the storage entry `entry` of the Hyperlane pallet at
+    /// `keys`, if it is set, in the latest block
+    pub(crate) async fn storage<T: DecodeAsType>(
+        &self,
+        entry: &str,
+        keys: Vec<Value>,
+    ) -> ChainResult<Option<T>> {
+        self.fetch(&self.conf.pallet, entry, keys).await
+    }
+
+    async fn fetch<T: DecodeAsType>(
+        &self,
+        pallet: &str,
+        entry: &str,
+        keys: Vec<Value>,
+    ) -> ChainResult<Option<T>> {
+        let address = subxt::dynamic::storage(pallet, entry, keys);
+        let value = self
+            .clients()
+            .await?
+            .api
+            .storage()
+            .at_latest()
+            .await
+            .map_err(HyperlaneSubstrateError::from)?
+            .fetch(&address)
+            .await
+            .map_err(HyperlaneSubstrateError::from)?;
+        Ok(value
+            .map(|value| value.as_type::<T>())
+            .transpose()
+            .map_err(HyperlaneSubstrateError::from)?)
+    }
+
+    /// A call to `function` of the Hyperlane pallet
+    pub(crate) fn call(&self, function: &str, args: Vec<Value>) -> impl TxPayload {
+        subxt::dynamic::tx(self.conf.pallet.as_str(), function, args)
+    }
+
+    /// Signs `call` as an extrinsic that is only valid for the configured
+    /// number of blocks after the latest one
+    pub(crate) async fn sign(
+        &self,
+        call: &impl TxPayload,
+    ) -> ChainResult<SubmittableExtrinsic<PolkadotConfig, OnlineClient<PolkadotConfig>>> {
+        let signer = self.signer()?;
+        let api = &self.clients().await?.api;
+        let latest = api
+            .blocks()
+            .at_latest()
+            .await
+            .map_err(HyperlaneSubstrateError::from)?;
+        let params = PolkadotExtrinsicParamsBuilder::new()
+            .mortal(latest.header(), self.conf.mortality_period)
+            .build();
+        Ok(api
+            .tx()
+            .create_signed(call, signer, params)
+            .await
+            .map_err(HyperlaneSubstrateError::from)?)
+    }
+
+    /// The events named `name` emitted by the Hyperlane pallet in the blocks
+    /// in `range`, attributed to the contract at `address`
+    pub(crate) async fn events_in_range(
+        &self,
+        address: H256,
+        name: &str,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<PalletEvent>> {
+        let clients = self.clients().await?;
+        let mut events = vec![];
+        for number in range {
+            let hash = clients
+                .rpc
+                .chain_get_block_hash(Some(number.into()))
+                .await
+                .map_err(HyperlaneSubstrateError::from)?
+                .ok_or(HyperlaneSubstrateError::MissingBlock(number))?;
+            let block = clients
+                .api
+                .blocks()
+                .at(hash)
+                .await
+                .map_err(HyperlaneSubstrateError::from)?;
+            let extrinsics = block
+                .extrinsics()
+                .await
+                .map_err(HyperlaneSubstrateError::from)?;
+            for extrinsic in extrinsics.iter() {
+                let extrinsic = extrinsic.map_err(HyperlaneSubstrateError::from)?;
+                let extrinsic_events = extrinsic
+                    .events()
+                    .await
+                    .map_err(HyperlaneSubstrateError::from)?;
+                let transaction_id: H512 = H256(extrinsic_events.extrinsic_hash().0).into();
+                for event in extrinsic_events.iter() {
+                    let event = event.map_err(HyperlaneSubstrateError::from)?;
+                    if event.pallet_name() != self.conf.pallet || event.variant_name() != name {
+                        continue;
+                    }
+                    events.push(PalletEvent {
+                        fields: event.field_bytes().to_vec(),
+                        meta: LogMeta {
+                            address,
+                            block_number: number.into(),
+                            block_hash: H256(hash.0),
+                            transaction_id,
+                            transaction_index: extrinsic.index().into(),
+                            log_index: event.index().into(),
+                        },
+                    });
+                }
+            }
+        }
+        Ok(events)
+    }
+}
+
+impl std::fmt::Debug for SubstrateProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubstrateProvider")
+            .field("domain", &self.domain)
+            .field("conf", &self.conf)
+            .field("signer", &self.signer)
+            .finish()
+    }
+}
+
+impl HyperlaneChain for SubstrateProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for SubstrateProvider {
+    async fn get_block_by_hash(&self, _hash: &H256) -> ChainResult<BlockInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "Substrate does not support fetching blocks by hash yet",
+        ))
+    }
+
+    async fn get_txn_by_hash(&self, _hash: &H256) -> ChainResult<TxnInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "Substrate does not support fetching transactions yet",
+        ))
+    }
+
+    async fn is_contract(&self, _address: &H256) -> ChainResult<bool> {
+        // The Hyperlane contracts are pallets, which don't have addresses
+        Ok(true)
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        /// The fields of `frame_system::AccountInfo` up to the free balance
+        #[derive(subxt::ext::scale_decode::DecodeAsType)]
+        #[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+        struct AccountInfo {
+            data: AccountData,
+        }
+        #[derive(subxt::ext::scale_decode::DecodeAsType)]
+        #[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+        struct AccountData {
+            free: u128,
+        }
+
+        let account = HyperlaneDomainProtocol::Substrate.parse_address(&address)?;
+        let account = Value::from_bytes(account.as_bytes());
+        let info: Option<AccountInfo> = self
+            .fetch(SYSTEM_PALLET, ACCOUNT_ENTRY, vec![account])
+            .await?;
+        Ok(info.map_or(0, |info| info.data.free).into())
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        Ok(None)
+    }
+}
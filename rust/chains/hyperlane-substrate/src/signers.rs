@@ -0,0 +1,101 @@
+use std::fmt::{Debug, Formatter};
+use std::str::FromStr;
+
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+use subxt::{
+    utils::{AccountId32, MultiAddress, MultiSignature},
+    PolkadotConfig,
+};
+use subxt_signer::{ecdsa, sr25519};
+
+use crate::HyperlaneSubstrateError;
+
+/// The signature scheme of a Substrate account key.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SignatureScheme {
+    /// Schnorrkel signatures, the default for Substrate accounts
+    #[default]
+    Sr25519,
+    /// secp256k1 signatures, whose account ids are the blake2 hash of the
+    /// compressed public key
+    Ecdsa,
+}
+
+impl FromStr for SignatureScheme {
+    type Err = ChainCommunicationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sr25519" => Ok(Self::Sr25519),
+            "ecdsa" => Ok(Self::Ecdsa),
+            _ => Err(ChainCommunicationError::ParseError {
+                msg: format!("Unknown signature scheme `{s}`"),
+            }),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Keypair {
+    Sr25519(sr25519::Keypair),
+    Ecdsa(ecdsa::Keypair),
+}
+
+/// A key signing extrinsics for a Substrate account
+#[derive(Clone)]
+pub struct Signer {
+    keypair: Keypair,
+}
+
+impl Signer {
+    /// Create a new Substrate signer from the seed of a key using `scheme`
+    pub fn new(seed: &H256, scheme: SignatureScheme) -> ChainResult<Self> {
+        let keypair = match scheme {
+            SignatureScheme::Sr25519 => sr25519::Keypair::from_seed(seed.0)
+                .map(Keypair::Sr25519)
+                .map_err(|e| e.to_string()),
+            SignatureScheme::Ecdsa => ecdsa::Keypair::from_seed(seed.0)
+                .map(Keypair::Ecdsa)
+                .map_err(|e| e.to_string()),
+        }
+        .map_err(HyperlaneSubstrateError::SignerError)?;
+        Ok(Self { keypair })
+    }
+
+    /// The SS58 address of the account, with the generic Substrate prefix
+    pub fn address_string(&self) -> String {
+        <Self as subxt::tx::Signer<PolkadotConfig>>::account_id(self).to_string()
+    }
+}
+
+impl subxt::tx::Signer<PolkadotConfig> for Signer {
+    fn account_id(&self) -> AccountId32 {
+        match &self.keypair {
+            Keypair::Sr25519(keypair) => subxt::tx::Signer::<PolkadotConfig>::account_id(keypair),
+            Keypair::Ecdsa(keypair) => subxt::tx::Signer::<PolkadotConfig>::account_id(keypair),
+        }
+    }
+
+    fn address(&self) -> MultiAddress<AccountId32, ()> {
+        self.account_id().into()
+    }
+
+    fn sign(&self, signer_payload: &[u8]) -> MultiSignature {
+        match &self.keypair {
+            Keypair::Sr25519(keypair) => {
+                subxt::tx::Signer::<PolkadotConfig>::sign(keypair, signer_payload)
+            }
+            Keypair::Ecdsa(keypair) => {
+                subxt::tx::Signer::<PolkadotConfig>::sign(keypair, signer_payload)
+            }
+        }
+    }
+}
+
+impl Debug for Signer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signer")
+            .field("address", &self.address_string())
+            .finish()
+    }
+}
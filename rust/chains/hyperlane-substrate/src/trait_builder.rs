@@ -0,0 +1,38 @@
+use hyperlane_core::config::OperationBatchConfig;
+use url::Url;
+
+/// The name the Hyperlane pallet is usually included in runtimes with
+const DEFAULT_PALLET: &str = "Mailbox";
+/// The number of blocks extrinsics stay valid for when not configured
+const DEFAULT_MORTALITY_PERIOD: u64 = 64;
+
+/// Substrate connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Fully qualified websocket url of a node
+    pub url: Url,
+    /// The name of the Hyperlane pallet in the runtime
+    pub pallet: String,
+    /// The number of blocks `process` extrinsics stay valid for
+    pub mortality_period: u64,
+    /// Operation batching configuration
+    pub operation_batch: OperationBatchConfig,
+}
+
+impl ConnectionConf {
+    /// Create a new connection configuration, with the default pallet name
+    /// and mortality period unless they are given
+    pub fn new(
+        url: Url,
+        pallet: Option<String>,
+        mortality_period: Option<u64>,
+        operation_batch: OperationBatchConfig,
+    ) -> Self {
+        Self {
+            url,
+            pallet: pallet.unwrap_or_else(|| DEFAULT_PALLET.to_owned()),
+            mortality_period: mortality_period.unwrap_or(DEFAULT_MORTALITY_PERIOD),
+            operation_batch,
+        }
+    }
+}
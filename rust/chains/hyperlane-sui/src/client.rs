@@ -0,0 +1,258 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hyperlane_core::{ChainResult, H256};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{
+    types::{
+        Coin, DevInspectResponse, EventNode, EventPage, ObjectData, ObjectRef, TransactionKind,
+        TransactionResponse,
+    },
+    HyperlaneSuiError,
+};
+
+/// The type of the native coin
+const SUI_COIN_TYPE: &str = "0x2::sui::SUI";
+/// The maximum number of gas coins fetched to pay for a transaction
+const MAX_GAS_COINS: u64 = 50;
+/// The number of events fetched per GraphQL query
+const EVENTS_PAGE_SIZE: u64 = 50;
+
+const EVENTS_QUERY: &str = r#"
+query Events($eventType: String!, $after: UInt53, $before: UInt53, $cursor: String, $first: Int) {
+  events(
+    first: $first
+    after: $cursor
+    filter: { eventType: $eventType, afterCheckpoint: $after, beforeCheckpoint: $before }
+  ) {
+    pageInfo { hasNextPage endCursor }
+    nodes {
+      contents { json }
+      transactionBlock { digest effects { checkpoint { sequenceNumber digest } } }
+    }
+  }
+}
+"#;
+
+/// Decodes a base58 transaction, checkpoint or object digest
+pub(crate) fn decode_digest(digest: &str) -> Result<H256, HyperlaneSuiError> {
+    let bytes = bs58::decode(digest).into_vec()?;
+    if bytes.len() != H256::len_bytes() {
+        return Err(HyperlaneSuiError::UnexpectedResponse(format!(
+            "digest `{digest}` is not 32 bytes"
+        )));
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+/// A client of the JSON-RPC and GraphQL APIs of a Sui fullnode
+#[derive(Debug, Clone)]
+pub(crate) struct SuiClient {
+    http: Client,
+    url: Url,
+    graphql_url: Url,
+}
+
+impl SuiClient {
+    pub(crate) fn new(url: Url, graphql_url: Url) -> Self {
+        Self {
+            http: Client::new(),
+            url,
+            graphql_url,
+        }
+    }
+
+    async fn rpc<T: DeserializeOwned>(&self, method: &str, params: Value) -> ChainResult<T> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let mut response: Value = self
+            .http
+            .post(self.url.clone())
+            .json(&request)
+            .send()
+            .await
+            .map_err(HyperlaneSuiError::from)?
+            .json()
+            .await
+            .map_err(HyperlaneSuiError::from)?;
+        if let Some(error) = response.get("error") {
+            return Err(HyperlaneSuiError::RpcError {
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or_default().to_owned(),
+            }
+            .into());
+        }
+        Ok(serde_json::from_value(response["result"].take()).map_err(HyperlaneSuiError::from)?)
+    }
+
+    async fn graphql<T: DeserializeOwned>(&self, query: &str, variables: Value) -> ChainResult<T> {
+        let mut response: Value = self
+            .http
+            .post(self.graphql_url.clone())
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .map_err(HyperlaneSuiError::from)?
+            .json()
+            .await
+            .map_err(HyperlaneSuiError::from)?;
+        if let Some(errors) = response.get("errors") {
+            return Err(HyperlaneSuiError::GraphQlError(errors.to_string()).into());
+        }
+        Ok(serde_json::from_value(response["data"].take()).map_err(HyperlaneSuiError::from)?)
+    }
+
+    /// The sequence number of the latest executed checkpoint. Checkpoints
+    /// are final once executed.
+    pub(crate) async fn latest_checkpoint(&self) -> ChainResult<u64> {
+        let checkpoint: String = self
+            .rpc("sui_getLatestCheckpointSequenceNumber", json!([]))
+            .await?;
+        parse_u64(&checkpoint)
+    }
+
+    /// The reference gas price of the current epoch, in MIST
+    pub(crate) async fn reference_gas_price(&self) -> ChainResult<u64> {
+        let price: String = self.rpc("suix_getReferenceGasPrice", json!([])).await?;
+        parse_u64(&price)
+    }
+
+    /// The type and owner of the object `id`, or `None` if it doesn't exist
+    pub(crate) async fn object(&self, id: &H256) -> ChainResult<Option<ObjectData>> {
+        let mut response: Value = self
+            .rpc(
+                "sui_getObject",
+                json!([format!("{id:?}"), { "showType": true, "showOwner": true }]),
+            )
+            .await?;
+        Ok(match response.get_mut("data") {
+            Some(data) => {
+                Some(serde_json::from_value(data.take()).map_err(HyperlaneSuiError::from)?)
+            }
+            None => None,
+        })
+    }
+
+    /// The SUI balance of `owner`, in MIST
+    pub(crate) async fn balance(&self, owner: &H256) -> ChainResult<u128> {
+        let balance: Value = self
+            .rpc(
+                "suix_getBalance",
+                json!([format!("{owner:?}"), SUI_COIN_TYPE]),
+            )
+            .await?;
+        balance["totalBalance"]
+            .as_str()
+            .and_then(|balance| balance.parse().ok())
+            .ok_or_else(|| {
+                HyperlaneSuiError::UnexpectedResponse(format!("invalid balance {balance}")).into()
+            })
+    }
+
+    /// References to the SUI coins of `owner`, to pay for gas with
+    pub(crate) async fn gas_coins(&self, owner: &H256) -> ChainResult<Vec<ObjectRef>> {
+        let mut page: Value = self
+            .rpc(
+                "suix_getCoins",
+                json!([format!("{owner:?}"), SUI_COIN_TYPE, null, MAX_GAS_COINS]),
+            )
+            .await?;
+        let coins: Vec<Coin> =
+            serde_json::from_value(page["data"].take()).map_err(HyperlaneSuiError::from)?;
+        coins
+            .into_iter()
+            .map(|coin| {
+                Ok(ObjectRef {
+                    object_id: coin.coin_object_id.into(),
+                    version: coin.version,
+                    digest: decode_digest(&coin.digest)?.as_bytes().to_vec(),
+                })
+            })
+            .collect()
+    }
+
+    /// Executes `kind` without committing it, e.g. to call read-only
+    /// functions
+    pub(crate) async fn dev_inspect(
+        &self,
+        sender: &H256,
+        kind: &TransactionKind,
+    ) -> ChainResult<DevInspectResponse> {
+        let kind = bcs::to_bytes(kind).map_err(HyperlaneSuiError::from)?;
+        self.rpc(
+            "sui_devInspectTransactionBlock",
+            json!([format!("{sender:?}"), BASE64.encode(kind)]),
+        )
+        .await
+    }
+
+    /// Simulates the BCS encoded transaction data
+    pub(crate) async fn dry_run(&self, tx_bytes: &[u8]) -> ChainResult<TransactionResponse> {
+        self.rpc(
+            "sui_dryRunTransactionBlock",
+            json!([BASE64.encode(tx_bytes)]),
+        )
+        .await
+    }
+
+    /// Executes the BCS encoded transaction data and waits for its effects
+    pub(crate) async fn execute(
+        &self,
+        tx_bytes: &[u8],
+        signatures: Vec<String>,
+    ) -> ChainResult<TransactionResponse> {
+        self.rpc(
+            "sui_executeTransactionBlock",
+            json!([
+                BASE64.encode(tx_bytes),
+                signatures,
+                { "showEffects": true },
+                "WaitForLocalExecution",
+            ]),
+        )
+        .await
+    }
+
+    /// The events of `event_type`, e.g. `{package}::mailbox::DispatchEvent`,
+    /// emitted in the checkpoints `from..=to`
+    pub(crate) async fn events(
+        &self,
+        event_type: &str,
+        from: u64,
+        to: u64,
+    ) -> ChainResult<Vec<EventNode>> {
+        let mut events = vec![];
+        let mut cursor: Option<String> = None;
+        loop {
+            // The checkpoint filters are exclusive
+            let variables = json!({
+                "eventType": event_type,
+                "after": from.checked_sub(1),
+                "before": to + 1,
+                "cursor": cursor,
+                "first": EVENTS_PAGE_SIZE,
+            });
+            let mut data: Value = self.graphql(EVENTS_QUERY, variables).await?;
+            let page: EventPage =
+                serde_json::from_value(data["events"].take()).map_err(HyperlaneSuiError::from)?;
+            events.extend(page.nodes);
+            if !page.page_info.has_next_page {
+                break;
+            }
+            cursor = page.page_info.end_cursor;
+        }
+        Ok(events)
+    }
+}
+
+fn parse_u64(value: &str) -> ChainResult<u64> {
+    value
+        .parse()
+        .map_err(|_| HyperlaneSuiError::UnexpectedResponse(format!("{value} is not a u64")).into())
+}
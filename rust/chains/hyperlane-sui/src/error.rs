@@ -0,0 +1,44 @@
+use hyperlane_core::ChainCommunicationError;
+
+/// Errors from the crates specific to the hyperlane-sui
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneSuiError {
+    /// Reqwest error
+    #[error("{0}")]
+    ReqwestError(#[from] reqwest::Error),
+    /// serde_json error
+    #[error("{0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+    /// BCS serialization error
+    #[error("{0}")]
+    BcsError(#[from] bcs::Error),
+    /// Base64 decoding error
+    #[error("{0}")]
+    Base64Error(#[from] base64::DecodeError),
+    /// Base58 decoding error
+    #[error("{0}")]
+    Base58Error(#[from] bs58::decode::Error),
+    /// Error returned by the JSON-RPC API
+    #[error("Sui RPC error {code}: {message}")]
+    RpcError {
+        /// The JSON-RPC error code
+        code: i64,
+        /// The error message
+        message: String,
+    },
+    /// Errors returned by the GraphQL API
+    #[error("Sui GraphQL errors: {0}")]
+    GraphQlError(String),
+    /// A response that doesn't have the expected shape
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+}
+
+impl From<HyperlaneSuiError> for ChainCommunicationError {
+    fn from(value: HyperlaneSuiError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
@@ -0,0 +1,391 @@
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::sync::OnceCell;
+use tracing::{instrument, warn};
+
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, Decode,
+    FixedPointNumber, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneDomainProtocol,
+    HyperlaneMessage, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, RawHyperlaneMessage,
+    ResourceCost, SequenceAwareIndexer, TxCostEstimate, TxOutcome, TxResource, H256, U256,
+};
+
+use crate::{
+    client::decode_digest,
+    types::{CallArg, ObjectArg, ProgrammableTransaction, TransactionEffects, TransactionKind},
+    ConnectionConf, HyperlaneSuiError, Signer, SuiProvider,
+};
+
+/// The name of the mailbox module
+const MAILBOX_MODULE: &str = "mailbox";
+/// The dry run gas budget of a transaction is multiplied by this to get its
+/// budget, as the dry run and the execution can differ slightly.
+const GAS_BUDGET_MULTIPLIER_NUMERATOR: u64 = 3;
+const GAS_BUDGET_MULTIPLIER_DENOMINATOR: u64 = 2;
+
+/// The package and sharing version of the shared `Mailbox` object
+#[derive(Debug, Clone, Copy)]
+struct MailboxObject {
+    package: H256,
+    initial_shared_version: u64,
+}
+
+/// A reference to the shared `Mailbox` object of a mailbox package on some
+/// Sui chain. The object is what the agents are configured with, its package
+/// is looked up on first use.
+#[derive(Debug)]
+pub struct SuiMailbox {
+    object_id: H256,
+    object: OnceCell<MailboxObject>,
+    provider: SuiProvider,
+}
+
+impl SuiMailbox {
+    /// Create a new Sui mailbox
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        Ok(Self {
+            object_id: locator.address,
+            object: OnceCell::new(),
+            provider: SuiProvider::new(locator.domain.clone(), conf, signer),
+        })
+    }
+
+    async fn object(&self) -> ChainResult<MailboxObject> {
+        self.object
+            .get_or_try_init(|| async {
+                let object = self
+                    .provider
+                    .client()
+                    .object(&self.object_id)
+                    .await?
+                    .ok_or_else(|| {
+                        ChainCommunicationError::CustomError(format!(
+                            "Mailbox object {:?} doesn't exist",
+                            self.object_id
+                        ))
+                    })?;
+                let initial_shared_version = object.initial_shared_version().ok_or_else(|| {
+                    HyperlaneSuiError::UnexpectedResponse("mailbox is not shared".into())
+                })?;
+                // The type is `{package}::mailbox::Mailbox`
+                let object_type = object.object_type.unwrap_or_default();
+                let package = object_type.split("::").next().unwrap_or_default();
+                Ok(MailboxObject {
+                    package: HyperlaneDomainProtocol::Sui.parse_address(package)?,
+                    initial_shared_version,
+                })
+            })
+            .await
+            .copied()
+    }
+
+    /// A programmable transaction block calling `function` of the mailbox
+    /// module with the mailbox object followed by `args`
+    async fn move_call(
+        &self,
+        function: &str,
+        mutable: bool,
+        args: Vec<CallArg>,
+    ) -> ChainResult<TransactionKind> {
+        let object = self.object().await?;
+        let mut inputs = vec![CallArg::Object(ObjectArg::SharedObject {
+            id: self.object_id.into(),
+            initial_shared_version: object.initial_shared_version,
+            mutable,
+        })];
+        inputs.extend(args);
+        Ok(TransactionKind::ProgrammableTransaction(
+            ProgrammableTransaction::move_call(object.package, MAILBOX_MODULE, function, inputs),
+        ))
+    }
+
+    /// Calls the read-only `function`, returning its single BCS encoded
+    /// return value
+    async fn view<T: DeserializeOwned>(
+        &self,
+        function: &str,
+        args: Vec<CallArg>,
+    ) -> ChainResult<T> {
+        let kind = self.move_call(function, false, args).await?;
+        let value = self.provider.view(&kind).await?.pop().ok_or_else(|| {
+            HyperlaneSuiError::UnexpectedResponse(format!("{function} returned nothing"))
+        })?;
+        Ok(bcs::from_bytes(&value).map_err(HyperlaneSuiError::from)?)
+    }
+
+    /// The type of the events named `name` emitted by the mailbox module
+    async fn event_type(&self, name: &str) -> ChainResult<String> {
+        let package = self.object().await?.package;
+        Ok(format!("{package:?}::{MAILBOX_MODULE}::{name}"))
+    }
+
+    /// The inputs of `process` after the mailbox object
+    fn process_args(message: &HyperlaneMessage, metadata: &[u8]) -> ChainResult<Vec<CallArg>> {
+        let message = RawHyperlaneMessage::from(message);
+        Ok(vec![
+            CallArg::Pure(bcs::to_bytes(metadata).map_err(HyperlaneSuiError::from)?),
+            CallArg::Pure(bcs::to_bytes(&message).map_err(HyperlaneSuiError::from)?),
+        ])
+    }
+
+    async fn dry_run_process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        gas_price: u64,
+    ) -> ChainResult<TransactionEffects> {
+        let kind = self
+            .move_call("process", true, Self::process_args(message, metadata)?)
+            .await?;
+        let effects = self.provider.dry_run(kind, gas_price).await?;
+        if !effects.is_success() {
+            return Err(ChainCommunicationError::CustomError(format!(
+                "Dry run of process failed: {}",
+                effects.status.error.unwrap_or_default()
+            )));
+        }
+        Ok(effects)
+    }
+}
+
+/// Parses a `vector<u8>` event field, which is either a JSON array of bytes
+/// or base64 encoded
+fn parse_bytes(value: &Value) -> ChainResult<Vec<u8>> {
+    let bytes = match value {
+        Value::String(encoded) => BASE64.decode(encoded).ok(),
+        Value::Array(bytes) => bytes
+            .iter()
+            .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+            .collect(),
+        _ => None,
+    };
+    bytes.ok_or_else(|| {
+        HyperlaneSuiError::UnexpectedResponse(format!("{value} is not a vector<u8>")).into()
+    })
+}
+
+impl HyperlaneContract for SuiMailbox {
+    fn address(&self) -> H256 {
+        self.object_id
+    }
+}
+
+impl HyperlaneChain for SuiMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for SuiMailbox {
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        assert!(lag.is_none(), "Sui does not support querying point-in-time");
+        self.view("nonce", vec![]).await
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let id = bcs::to_bytes(id.as_bytes()).map_err(HyperlaneSuiError::from)?;
+        self.view("delivered", vec![CallArg::Pure(id)]).await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        let ism: [u8; 32] = self.view("default_ism", vec![]).await?;
+        Ok(ism.into())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        // Addresses are BCS encoded without a length prefix
+        let recipient = bcs::to_bytes(&recipient.0).map_err(HyperlaneSuiError::from)?;
+        let ism: [u8; 32] = self
+            .view("recipient_ism", vec![CallArg::Pure(recipient)])
+            .await?;
+        Ok(ism.into())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let gas_price = self.provider.gas_price().await?;
+        let gas_budget = match tx_gas_limit {
+            Some(gas_limit) => gas_limit.as_u64() * gas_price,
+            None => {
+                self.dry_run_process(message, metadata, gas_price)
+                    .await?
+                    .gas_used
+                    .budget()
+                    * GAS_BUDGET_MULTIPLIER_NUMERATOR
+                    / GAS_BUDGET_MULTIPLIER_DENOMINATOR
+            }
+        };
+
+        let kind = self
+            .move_call("process", true, Self::process_args(message, metadata)?)
+            .await?;
+        let response = self.provider.send(kind, gas_price, gas_budget).await?;
+        let digest = response.digest.ok_or_else(|| {
+            HyperlaneSuiError::UnexpectedResponse("executed transaction has no digest".into())
+        })?;
+        let executed = response.effects.is_success();
+        if !executed {
+            warn!(%digest, error=?response.effects.status.error, "Sui transaction failed");
+        }
+
+        Ok(TxOutcome {
+            transaction_id: decode_digest(&digest)?.into(),
+            executed,
+            gas_used: (response.effects.gas_used.net_cost() / gas_price).into(),
+            gas_price: FixedPointNumber::from(gas_price),
+        })
+    }
+
+    #[instrument(err, ret, skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        let gas_price = self.provider.gas_price().await?;
+        let effects = self.dry_run_process(message, metadata, gas_price).await?;
+        let gas_used = &effects.gas_used;
+        // The budget must cover storage before it's rebated, while the rebate
+        // lowers what the transaction actually costs
+        Ok(TxCostEstimate {
+            gas_limit: (gas_used.budget() / gas_price).into(),
+            gas_price: FixedPointNumber::from(gas_price),
+            l2_gas_limit: None,
+            resources: vec![
+                ResourceCost::new(
+                    TxResource::Computation,
+                    gas_used.computation_cost / gas_price,
+                    gas_price,
+                ),
+                ResourceCost::new(TxResource::Storage, gas_used.storage_cost, 1u64),
+                ResourceCost::new(TxResource::StorageRebate, gas_used.storage_rebate, 1u64),
+            ],
+        })
+    }
+
+    /// The BCS encoded arguments of `process` after the mailbox object, as
+    /// the package of the mailbox is only known once it has been looked up
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        let args = Self::process_args(message, metadata)
+            .expect("BCS encoding of the process arguments can't fail");
+        bcs::to_bytes(&args).expect("BCS encoding of call arguments can't fail")
+    }
+}
+
+/// Struct that retrieves dispatched messages and processed message ids from
+/// the events of a Mailbox package on some Sui chain, by checkpoint
+#[derive(Debug)]
+pub struct SuiMailboxIndexer {
+    mailbox: SuiMailbox,
+}
+
+impl SuiMailboxIndexer {
+    /// Create a new Sui mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            mailbox: SuiMailbox::new(conf, locator, None)?,
+        })
+    }
+
+    async fn events_in_range(
+        &self,
+        name: &str,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Value, LogMeta)>> {
+        let event_type = self.mailbox.event_type(name).await?;
+        self.mailbox
+            .provider
+            .events_in_range(self.mailbox.object_id, &event_type, range)
+            .await
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for SuiMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        let events = self.events_in_range("DispatchEvent", range).await?;
+        events
+            .into_iter()
+            .map(|(event, meta)| {
+                let message = parse_bytes(&event["message"])?;
+                let message = HyperlaneMessage::read_from(&mut message.as_slice())
+                    .map_err(ChainCommunicationError::from_other)?;
+                Ok((message.into(), meta))
+            })
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.latest_checkpoint().await
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for SuiMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        let events = self.events_in_range("ProcessEvent", range).await?;
+        Ok(events
+            .into_iter()
+            .filter_map(|(event, meta)| match parse_bytes(&event["message_id"]) {
+                Ok(id) if id.len() == H256::len_bytes() => {
+                    Some((Indexed::new(H256::from_slice(&id)), meta))
+                }
+                _ => {
+                    warn!(?meta, "ProcessEvent has no valid message id, skipping");
+                    None
+                }
+            })
+            .collect())
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.latest_checkpoint().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for SuiMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
+        let count = Mailbox::count(&self.mailbox, None).await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for SuiMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<H256>::get_finalized_block_number(self).await?;
+        // No sequence for message deliveries.
+        Ok((None, tip))
+    }
+}
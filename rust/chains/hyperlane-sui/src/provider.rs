@@ -0,0 +1,224 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, LogMeta, TxnInfo, H256, H512, U256,
+};
+use serde_json::Value;
+
+use crate::{
+    client::{decode_digest, SuiClient},
+    types::{
+        GasData, SuiAddress, TransactionData, TransactionDataV1, TransactionEffects,
+        TransactionExpiration, TransactionKind, TransactionResponse,
+    },
+    ConnectionConf, HyperlaneSuiError, Signer,
+};
+
+/// The gas budget of dry runs, in MIST, which is the maximum budget allowed
+/// by the protocol
+const DRY_RUN_GAS_BUDGET: u64 = 50_000_000_000;
+
+/// A wrapper around the JSON-RPC and GraphQL APIs of a Sui fullnode to get
+/// generic blockchain information, inspect objects and submit transactions.
+#[derive(Debug, Clone)]
+pub struct SuiProvider {
+    domain: HyperlaneDomain,
+    client: SuiClient,
+    signer: Option<Signer>,
+}
+
+impl SuiProvider {
+    /// Create a new Sui provider
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf, signer: Option<Signer>) -> Self {
+        Self {
+            domain,
+            client: SuiClient::new(conf.url.clone(), conf.graphql_url.clone()),
+            signer,
+        }
+    }
+
+    pub(crate) fn client(&self) -> &SuiClient {
+        &self.client
+    }
+
+    fn signer(&self) -> ChainResult<&Signer> {
+        self.signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)
+    }
+
+    /// The latest executed checkpoint, which checkpoint-based indexing uses
+    /// as its block number
+    pub(crate) async fn latest_checkpoint(&self) -> ChainResult<u32> {
+        self.client
+            .latest_checkpoint()
+            .await?
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    /// Calls the read-only Move call in `kind`, returning the BCS encoded
+    /// values it returned
+    pub(crate) async fn view(&self, kind: &TransactionKind) -> ChainResult<Vec<Vec<u8>>> {
+        // Anyone can inspect a transaction, the sender doesn't need to exist
+        let sender = self.signer.as_ref().map(|s| s.address).unwrap_or_default();
+        let response = self.client.dev_inspect(&sender, kind).await?;
+        if let Some(error) = response.error {
+            return Err(ChainCommunicationError::CustomError(error));
+        }
+        let result = response.results.into_iter().last().ok_or_else(|| {
+            HyperlaneSuiError::UnexpectedResponse("dev inspect returned no results".into())
+        })?;
+        Ok(result
+            .return_values
+            .into_iter()
+            .map(|(bytes, _)| bytes)
+            .collect())
+    }
+
+    /// The BCS encoded transaction data of `kind`. The gas is paid by the
+    /// signer's sponsor if it has one.
+    async fn transaction_data(
+        &self,
+        kind: TransactionKind,
+        gas_price: u64,
+        gas_budget: u64,
+    ) -> ChainResult<Vec<u8>> {
+        let signer = self.signer()?;
+        let gas_owner = signer.gas_owner();
+        let payment = self.client.gas_coins(&gas_owner.address).await?;
+        let data = TransactionData::V1(TransactionDataV1 {
+            kind,
+            sender: signer.address.into(),
+            gas_data: GasData {
+                payment,
+                owner: SuiAddress::from(gas_owner.address),
+                price: gas_price,
+                budget: gas_budget,
+            },
+            expiration: TransactionExpiration::None,
+        });
+        Ok(bcs::to_bytes(&data).map_err(HyperlaneSuiError::from)?)
+    }
+
+    /// The price of gas paid by transactions, in MIST
+    pub(crate) async fn gas_price(&self) -> ChainResult<u64> {
+        self.client.reference_gas_price().await
+    }
+
+    /// Simulates `kind` sent by the signer
+    pub(crate) async fn dry_run(
+        &self,
+        kind: TransactionKind,
+        gas_price: u64,
+    ) -> ChainResult<TransactionEffects> {
+        let tx_bytes = self
+            .transaction_data(kind, gas_price, DRY_RUN_GAS_BUDGET)
+            .await?;
+        Ok(self.client.dry_run(&tx_bytes).await?.effects)
+    }
+
+    /// Signs `kind` by the signer, and by its sponsor if it has one, then
+    /// executes it and waits for its effects
+    pub(crate) async fn send(
+        &self,
+        kind: TransactionKind,
+        gas_price: u64,
+        gas_budget: u64,
+    ) -> ChainResult<TransactionResponse> {
+        let tx_bytes = self.transaction_data(kind, gas_price, gas_budget).await?;
+        let signer = self.signer()?;
+        let mut signatures = vec![signer.sign_transaction(&tx_bytes)];
+        let gas_owner = signer.gas_owner();
+        if gas_owner.address != signer.address {
+            signatures.push(gas_owner.sign_transaction(&tx_bytes));
+        }
+        self.client.execute(&tx_bytes, signatures).await
+    }
+
+    /// The JSON contents of the events of `event_type` emitted in the
+    /// checkpoints in `range`, along with their metadata
+    pub(crate) async fn events_in_range(
+        &self,
+        address: H256,
+        event_type: &str,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Value, LogMeta)>> {
+        let events = self
+            .client
+            .events(event_type, (*range.start()).into(), (*range.end()).into())
+            .await?;
+        let mut logs: Vec<(Value, LogMeta)> = Vec::with_capacity(events.len());
+        for event in events {
+            let transaction_id = H512::from(decode_digest(&event.transaction_block.digest)?);
+            let checkpoint = event.transaction_block.effects.checkpoint;
+            // Events are returned in the order they were emitted, so their
+            // position in their transaction is the number of events before
+            // them with the same transaction
+            let log_index = logs
+                .iter()
+                .rev()
+                .take_while(|(_, meta)| meta.transaction_id == transaction_id)
+                .count();
+            logs.push((
+                event.contents.json,
+                LogMeta {
+                    address,
+                    block_number: checkpoint.sequence_number,
+                    block_hash: decode_digest(&checkpoint.digest)?,
+                    transaction_id,
+                    // Checkpoints don't expose the position of their
+                    // transactions
+                    transaction_index: 0,
+                    log_index: log_index.into(),
+                },
+            ));
+        }
+        Ok(logs)
+    }
+}
+
+impl HyperlaneChain for SuiProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for SuiProvider {
+    async fn get_block_by_hash(&self, _hash: &H256) -> ChainResult<BlockInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "Sui does not support fetching blocks by hash yet",
+        ))
+    }
+
+    async fn get_txn_by_hash(&self, _hash: &H256) -> ChainResult<TxnInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "Sui does not support fetching transactions yet",
+        ))
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        let object = self.client.object(address).await?;
+        Ok(object.map_or(false, |object| {
+            object.object_type.as_deref() == Some("package")
+        }))
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        let address = address
+            .parse::<H256>()
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(self.client.balance(&address).await?.into())
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,84 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer as _};
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+
+/// The flag of the ed25519 signature scheme, which prefixes public keys when
+/// deriving addresses and serialized signatures.
+const ED25519_FLAG: u8 = 0;
+/// The intent of transaction data signed by a Sui wallet: the transaction
+/// data scope, intent version 0 and the Sui app id.
+const TRANSACTION_INTENT: [u8; 3] = [0, 0, 0];
+
+type Blake2b256 = Blake2b<U32>;
+
+/// An ed25519 key signing for a Sui account, optionally along with the key of
+/// a sponsor account that pays for the gas of its transactions.
+#[derive(Debug)]
+pub struct Signer {
+    keypair: Keypair,
+    /// The address of the account
+    pub address: H256,
+    sponsor: Option<Box<Signer>>,
+}
+
+impl Signer {
+    /// Create a new Sui signer from an ed25519 private key, and optionally
+    /// the private key of the sponsor paying for its gas
+    pub fn new(private_key: &H256, sponsor_key: Option<&H256>) -> ChainResult<Self> {
+        let secret = SecretKey::from_bytes(private_key.as_bytes())
+            .map_err(ChainCommunicationError::from_other)?;
+        let public = PublicKey::from(&secret);
+        let sponsor = sponsor_key
+            .map(|key| Self::new(key, None).map(Box::new))
+            .transpose()?;
+        Ok(Self {
+            address: address(&public),
+            keypair: Keypair { secret, public },
+            sponsor,
+        })
+    }
+
+    /// The signer paying for gas, which is the signer itself unless its
+    /// transactions are sponsored
+    pub(crate) fn gas_owner(&self) -> &Signer {
+        self.sponsor.as_deref().unwrap_or(self)
+    }
+
+    /// Signs BCS encoded transaction data, returning the base64 serialized
+    /// signature expected by `sui_executeTransactionBlock`
+    pub(crate) fn sign_transaction(&self, tx_bytes: &[u8]) -> String {
+        let mut hasher = Blake2b256::new();
+        hasher.update(TRANSACTION_INTENT);
+        hasher.update(tx_bytes);
+        let signature = self.keypair.sign(&hasher.finalize());
+
+        let mut serialized = vec![ED25519_FLAG];
+        serialized.extend(signature.to_bytes());
+        serialized.extend(self.keypair.public.to_bytes());
+        BASE64.encode(serialized)
+    }
+
+    /// The address of the account, 0x-prefixed
+    pub fn address_string(&self) -> String {
+        format!("{:?}", self.address)
+    }
+}
+
+impl Clone for Signer {
+    fn clone(&self) -> Self {
+        Self {
+            keypair: Keypair::from_bytes(&self.keypair.to_bytes())
+                .expect("keypair bytes are valid"),
+            address: self.address,
+            sponsor: self.sponsor.clone(),
+        }
+    }
+}
+
+fn address(public: &PublicKey) -> H256 {
+    let mut hasher = Blake2b256::new();
+    hasher.update([ED25519_FLAG]);
+    hasher.update(public.as_bytes());
+    H256::from_slice(&hasher.finalize())
+}
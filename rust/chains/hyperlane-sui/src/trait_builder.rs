@@ -0,0 +1,26 @@
+use hyperlane_core::config::OperationBatchConfig;
+use url::Url;
+
+/// Sui connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Fully qualified url of the JSON-RPC API of a fullnode, e.g.
+    /// `https://fullnode.mainnet.sui.io`
+    pub url: Url,
+    /// Fully qualified url of the GraphQL API used to index events by
+    /// checkpoint, e.g. `https://sui-mainnet.mystenlabs.com/graphql`
+    pub graphql_url: Url,
+    /// Operation batching configuration
+    pub operation_batch: OperationBatchConfig,
+}
+
+impl ConnectionConf {
+    /// Create a new connection configuration
+    pub fn new(url: Url, graphql_url: Url, operation_batch: OperationBatchConfig) -> Self {
+        Self {
+            url,
+            graphql_url,
+            operation_batch,
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! The BCS encoded programmable transaction blocks submitted to Sui, and the
+//! JSON types returned by its JSON-RPC API.
+
+use hyperlane_core::H256;
+use serde::{Deserialize, Serialize};
+
+/// A 32 byte address of an account, object or package
+#[derive(Serialize, Clone, Copy, Debug)]
+pub(crate) struct SuiAddress(pub [u8; 32]);
+
+impl From<H256> for SuiAddress {
+    fn from(value: H256) -> Self {
+        Self(value.0)
+    }
+}
+
+/// A reference to a specific version of an owned object
+#[derive(Serialize, Clone, Debug)]
+pub(crate) struct ObjectRef {
+    pub object_id: SuiAddress,
+    pub version: u64,
+    /// The 32 byte digest, BCS encoded with its length
+    pub digest: Vec<u8>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub(crate) enum ObjectArg {
+    #[allow(dead_code)]
+    ImmOrOwnedObject(ObjectRef),
+    SharedObject {
+        id: SuiAddress,
+        initial_shared_version: u64,
+        mutable: bool,
+    },
+}
+
+/// An input of a programmable transaction block
+#[derive(Serialize, Clone, Debug)]
+pub(crate) enum CallArg {
+    /// A BCS encoded pure value
+    Pure(Vec<u8>),
+    Object(ObjectArg),
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub(crate) enum Argument {
+    // Only listed so that inputs get their variant index
+    #[allow(dead_code)]
+    GasCoin,
+    Input(u16),
+}
+
+/// Type arguments of a Move call. None of the functions called by the agents
+/// are generic.
+#[derive(Serialize, Clone, Debug)]
+pub(crate) enum TypeTag {}
+
+#[derive(Serialize, Clone, Debug)]
+pub(crate) struct ProgrammableMoveCall {
+    pub package: SuiAddress,
+    pub module: String,
+    pub function: String,
+    pub type_arguments: Vec<TypeTag>,
+    pub arguments: Vec<Argument>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub(crate) enum Command {
+    MoveCall(Box<ProgrammableMoveCall>),
+}
+
+/// A programmable transaction block
+#[derive(Serialize, Clone, Debug)]
+pub(crate) struct ProgrammableTransaction {
+    pub inputs: Vec<CallArg>,
+    pub commands: Vec<Command>,
+}
+
+impl ProgrammableTransaction {
+    /// A block with a single call of `package::module::function`, taking the
+    /// `inputs` as its arguments in order
+    pub(crate) fn move_call(
+        package: H256,
+        module: &str,
+        function: &str,
+        inputs: Vec<CallArg>,
+    ) -> Self {
+        let arguments = (0..inputs.len() as u16).map(Argument::Input).collect();
+        Self {
+            inputs,
+            commands: vec![Command::MoveCall(Box::new(ProgrammableMoveCall {
+                package: package.into(),
+                module: module.into(),
+                function: function.into(),
+                type_arguments: vec![],
+                arguments,
+            }))],
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub(crate) enum TransactionKind {
+    ProgrammableTransaction(ProgrammableTransaction),
+}
+
+/// The coins paying for gas. The owner differs from the sender for sponsored
+/// transactions.
+#[derive(Serialize, Clone, Debug)]
+pub(crate) struct GasData {
+    pub payment: Vec<ObjectRef>,
+    pub owner: SuiAddress,
+    pub price: u64,
+    pub budget: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub(crate) enum TransactionExpiration {
+    None,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub(crate) struct TransactionDataV1 {
+    pub kind: TransactionKind,
+    pub sender: SuiAddress,
+    pub gas_data: GasData,
+    pub expiration: TransactionExpiration,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub(crate) enum TransactionData {
+    V1(TransactionDataV1),
+}
+
+/// Deserializes a u64 that the API encodes as a string
+fn u64_from_str<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+}
+
+/// A coin returned by `suix_getCoins`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Coin {
+    pub coin_object_id: H256,
+    #[serde(deserialize_with = "u64_from_str")]
+    pub version: u64,
+    /// Base58 encoded
+    pub digest: String,
+}
+
+/// The data of an object returned by `sui_getObject`
+#[derive(Deserialize, Debug)]
+pub(crate) struct ObjectData {
+    /// The type of the object, e.g. `0x2::coin::Coin<0x2::sui::SUI>`, or
+    /// `package` for packages
+    #[serde(rename = "type")]
+    pub object_type: Option<String>,
+    /// E.g. `{"Shared": {"initial_shared_version": 3}}` for shared objects
+    pub owner: Option<serde_json::Value>,
+}
+
+impl ObjectData {
+    /// The version at which the object was shared, if it is shared
+    pub(crate) fn initial_shared_version(&self) -> Option<u64> {
+        self.owner.as_ref()?["Shared"]["initial_shared_version"].as_u64()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GasCostSummary {
+    #[serde(deserialize_with = "u64_from_str")]
+    pub computation_cost: u64,
+    #[serde(deserialize_with = "u64_from_str")]
+    pub storage_cost: u64,
+    #[serde(deserialize_with = "u64_from_str")]
+    pub storage_rebate: u64,
+}
+
+impl GasCostSummary {
+    /// The gas budget, in MIST, needed to execute the transaction. Storage
+    /// rebates are only paid out after execution.
+    pub(crate) fn budget(&self) -> u64 {
+        self.computation_cost + self.storage_cost
+    }
+
+    /// The gas paid by the transaction, in MIST
+    pub(crate) fn net_cost(&self) -> u64 {
+        (self.computation_cost + self.storage_cost).saturating_sub(self.storage_rebate)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ExecutionStatus {
+    pub status: String,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TransactionEffects {
+    pub status: ExecutionStatus,
+    pub gas_used: GasCostSummary,
+}
+
+impl TransactionEffects {
+    pub(crate) fn is_success(&self) -> bool {
+        self.status.status == "success"
+    }
+}
+
+/// The response of `sui_executeTransactionBlock` and
+/// `sui_dryRunTransactionBlock`
+#[derive(Deserialize, Debug)]
+pub(crate) struct TransactionResponse {
+    /// Unset for dry runs
+    pub digest: Option<String>,
+    pub effects: TransactionEffects,
+}
+
+/// The result of a single command of `sui_devInspectTransactionBlock`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DevInspectResult {
+    /// The BCS encoded return values along with their types
+    #[serde(default)]
+    pub return_values: Vec<(Vec<u8>, String)>,
+}
+
+/// The response of `sui_devInspectTransactionBlock`
+#[derive(Deserialize, Debug)]
+pub(crate) struct DevInspectResponse {
+    #[serde(default)]
+    pub results: Vec<DevInspectResult>,
+    pub error: Option<String>,
+}
+
+/// A page of events returned by the GraphQL API
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EventPage {
+    pub page_info: PageInfo,
+    pub nodes: Vec<EventNode>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EventNode {
+    pub contents: MoveValue,
+    pub transaction_block: TransactionBlock,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct MoveValue {
+    pub json: serde_json::Value,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct TransactionBlock {
+    /// Base58 encoded
+    pub digest: String,
+    pub effects: TransactionBlockEffects,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct TransactionBlockEffects {
+    pub checkpoint: Checkpoint,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Checkpoint {
+    pub sequence_number: u64,
+    /// Base58 encoded
+    pub digest: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_move_call_encoding() {
+        let transaction =
+            TransactionKind::ProgrammableTransaction(ProgrammableTransaction::move_call(
+                H256::repeat_byte(1),
+                "mailbox",
+                "process",
+                vec![
+                    CallArg::Object(ObjectArg::SharedObject {
+                        id: SuiAddress([2; 32]),
+                        initial_shared_version: 3,
+                        mutable: true,
+                    }),
+                    CallArg::Pure(vec![0xab]),
+                ],
+            ));
+        // Programmable transaction, two inputs: a mutable shared object and
+        // a pure value
+        let mut expected = vec![0, 2, 1, 1];
+        expected.extend([2; 32]);
+        expected.extend(3u64.to_le_bytes());
+        expected.extend([1, 0, 1, 0xab]);
+        // One move call without type arguments, taking both inputs
+        expected.extend([1, 0]);
+        expected.extend([1; 32]);
+        expected.push(7);
+        expected.extend(b"mailbox");
+        expected.push(7);
+        expected.extend(b"process");
+        expected.extend([0, 2, 1, 0, 0, 1, 1, 0]);
+        assert_eq!(bcs::to_bytes(&transaction).unwrap(), expected);
+    }
+}
@@ -0,0 +1,447 @@
+//! A minimal implementation of TON cells and of their serialization into bags
+//! of cells (BOC), covering the ordinary cells of the mailbox and wallet
+//! messages.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use hyperlane_core::H256;
+use sha2::{Digest, Sha256};
+
+use crate::HyperlaneTonError;
+
+type CellResult<T> = Result<T, HyperlaneTonError>;
+
+/// The magic prefix of a serialized bag of cells
+const BOC_MAGIC: [u8; 4] = [0xb5, 0xee, 0x9c, 0x72];
+/// The maximum number of data bits of a cell
+const MAX_BITS: usize = 1023;
+/// The maximum number of references of a cell
+const MAX_REFS: usize = 4;
+/// The number of bytes stored per cell of snake encoded data
+const SNAKE_BYTES_PER_CELL: usize = 127;
+
+fn invalid(reason: impl Into<String>) -> HyperlaneTonError {
+    HyperlaneTonError::InvalidCell(reason.into())
+}
+
+/// An ordinary cell: up to 1023 bits of data and up to 4 references to other
+/// cells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Cell {
+    /// The data bits, most significant bit first. The bits of the last byte
+    /// after `bit_len` are zero.
+    data: Vec<u8>,
+    bit_len: usize,
+    refs: Vec<Arc<Cell>>,
+}
+
+impl Cell {
+    fn descriptors(&self) -> [u8; 2] {
+        let d1 = self.refs.len() as u8;
+        let d2 = (self.bit_len / 8 + (self.bit_len + 7) / 8) as u8;
+        [d1, d2]
+    }
+
+    /// The data, with a completion tag appended if it doesn't fill its last
+    /// byte
+    fn padded_data(&self) -> Vec<u8> {
+        let mut data = self.data.clone();
+        if self.bit_len % 8 != 0 {
+            let last = data.len() - 1;
+            data[last] |= 0x80 >> (self.bit_len % 8);
+        }
+        data
+    }
+
+    fn depth(&self) -> u16 {
+        self.refs
+            .iter()
+            .map(|cell| cell.depth() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The representation hash of the cell, which is what gets signed
+    pub(crate) fn hash(&self) -> H256 {
+        let mut hasher = Sha256::new();
+        hasher.update(self.descriptors());
+        hasher.update(self.padded_data());
+        for cell in &self.refs {
+            hasher.update(cell.depth().to_be_bytes());
+        }
+        for cell in &self.refs {
+            hasher.update(cell.hash().as_bytes());
+        }
+        H256::from_slice(&hasher.finalize())
+    }
+
+    pub(crate) fn parser(&self) -> CellParser<'_> {
+        CellParser {
+            cell: self,
+            bit: 0,
+            next_ref: 0,
+        }
+    }
+
+    /// Serializes the cell and the cells it references into a bag of cells
+    pub(crate) fn to_boc(&self) -> Vec<u8> {
+        // Cells must be listed before the cells they reference
+        fn visit<'a>(cell: &'a Cell, seen: &mut HashMap<H256, usize>, order: &mut Vec<&'a Cell>) {
+            let hash = cell.hash();
+            if seen.contains_key(&hash) {
+                return;
+            }
+            for child in &cell.refs {
+                visit(child, seen, order);
+            }
+            seen.insert(hash, 0);
+            order.push(cell);
+        }
+        let mut indexes = HashMap::new();
+        let mut order = vec![];
+        visit(self, &mut indexes, &mut order);
+        order.reverse();
+        for (index, cell) in order.iter().enumerate() {
+            indexes.insert(cell.hash(), index);
+        }
+
+        let size_bytes = byte_len(order.len() as u64);
+        let mut cells = vec![];
+        for cell in &order {
+            cells.extend(cell.descriptors());
+            cells.extend(cell.padded_data());
+            for child in &cell.refs {
+                write_uint(&mut cells, indexes[&child.hash()] as u64, size_bytes);
+            }
+        }
+        let offset_bytes = byte_len(cells.len() as u64);
+
+        let mut boc = BOC_MAGIC.to_vec();
+        // No index, no checksum and no cache bits
+        boc.push(size_bytes as u8);
+        boc.push(offset_bytes as u8);
+        write_uint(&mut boc, order.len() as u64, size_bytes);
+        // One root, no absent cells
+        write_uint(&mut boc, 1, size_bytes);
+        write_uint(&mut boc, 0, size_bytes);
+        write_uint(&mut boc, cells.len() as u64, offset_bytes);
+        // The root is the first cell
+        write_uint(&mut boc, 0, size_bytes);
+        boc.extend(cells);
+        boc
+    }
+
+    /// Deserializes the first root of a bag of cells
+    pub(crate) fn from_boc(boc: &[u8]) -> CellResult<Self> {
+        let mut reader = ByteReader { bytes: boc, pos: 0 };
+        if reader.take(4)? != BOC_MAGIC {
+            return Err(invalid("not a bag of cells"));
+        }
+        let flags = reader.take(1)?[0];
+        let has_index = flags & 0x80 != 0;
+        let size_bytes = (flags & 0x07) as usize;
+        let offset_bytes = reader.take(1)?[0] as usize;
+        let cell_count = reader.uint(size_bytes)?;
+        let root_count = reader.uint(size_bytes)?;
+        let _absent = reader.uint(size_bytes)?;
+        let _total_size = reader.uint(offset_bytes)?;
+        if root_count == 0 {
+            return Err(invalid("bag of cells has no root"));
+        }
+        let root = reader.uint(size_bytes)?;
+        reader.take((root_count - 1) * size_bytes)?;
+        if has_index {
+            reader.take(cell_count * offset_bytes)?;
+        }
+
+        let mut raw_cells = Vec::with_capacity(cell_count);
+        for _ in 0..cell_count {
+            let [d1, d2] = [reader.take(1)?[0], reader.take(1)?[0]];
+            if d1 & 0x08 != 0 {
+                return Err(invalid("exotic cells are not supported"));
+            }
+            let mut data = reader.take((d2 as usize + 1) / 2)?.to_vec();
+            let mut bit_len = data.len() * 8;
+            if d2 % 2 == 1 {
+                // Strip the completion tag
+                let last = data
+                    .last_mut()
+                    .ok_or_else(|| invalid("missing completion tag"))?;
+                if *last == 0 {
+                    return Err(invalid("missing completion tag"));
+                }
+                let tag = last.trailing_zeros() as usize;
+                *last &= !(1 << tag);
+                bit_len -= tag + 1;
+            }
+            let refs = (0..d1 & 0x07)
+                .map(|_| reader.uint(size_bytes))
+                .collect::<CellResult<Vec<_>>>()?;
+            raw_cells.push((data, bit_len, refs));
+        }
+
+        // References always point to later cells, so build from the end
+        let mut cells: Vec<Option<Arc<Cell>>> = vec![None; cell_count];
+        for (index, (data, bit_len, refs)) in raw_cells.into_iter().enumerate().rev() {
+            let refs = refs
+                .into_iter()
+                .map(|child| match cells.get(child) {
+                    Some(Some(cell)) if child > index => Ok(cell.clone()),
+                    _ => Err(invalid(format!(
+                        "invalid reference {child} of cell {index}"
+                    ))),
+                })
+                .collect::<CellResult<Vec<_>>>()?;
+            cells[index] = Some(Arc::new(Cell {
+                data,
+                bit_len,
+                refs,
+            }));
+        }
+        cells
+            .get(root)
+            .cloned()
+            .flatten()
+            .map(|cell| (*cell).clone())
+            .ok_or_else(|| invalid("invalid root"))
+    }
+}
+
+/// Builds a cell bit by bit
+#[derive(Debug, Default)]
+pub(crate) struct CellBuilder {
+    data: Vec<u8>,
+    bit_len: usize,
+    refs: Vec<Arc<Cell>>,
+}
+
+impl CellBuilder {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn store_bit(&mut self, bit: bool) -> CellResult<&mut Self> {
+        if self.bit_len == MAX_BITS {
+            return Err(invalid("cell overflow"));
+        }
+        if self.bit_len % 8 == 0 {
+            self.data.push(0);
+        }
+        if bit {
+            self.data[self.bit_len / 8] |= 0x80 >> (self.bit_len % 8);
+        }
+        self.bit_len += 1;
+        Ok(self)
+    }
+
+    /// Stores the `bits` least significant bits of `value`
+    pub(crate) fn store_uint(&mut self, bits: usize, value: u64) -> CellResult<&mut Self> {
+        if bits < 64 && value >> bits != 0 {
+            return Err(invalid(format!("{value} doesn't fit in {bits} bits")));
+        }
+        for bit in (0..bits).rev() {
+            self.store_bit((value >> bit) & 1 == 1)?;
+        }
+        Ok(self)
+    }
+
+    pub(crate) fn store_bytes(&mut self, bytes: &[u8]) -> CellResult<&mut Self> {
+        for byte in bytes {
+            self.store_uint(8, (*byte).into())?;
+        }
+        Ok(self)
+    }
+
+    /// Stores a standard internal address (`addr_std`) without anycast
+    pub(crate) fn store_address(&mut self, workchain: i8, account: &H256) -> CellResult<&mut Self> {
+        self.store_uint(2, 0b10)?
+            .store_bit(false)?
+            .store_uint(8, workchain as u8 as u64)?
+            .store_bytes(account.as_bytes())
+    }
+
+    /// Stores an amount of nanotons as `VarUInteger 16`
+    pub(crate) fn store_coins(&mut self, amount: u128) -> CellResult<&mut Self> {
+        let len = (128 - amount.leading_zeros() as usize + 7) / 8;
+        self.store_uint(4, len as u64)?
+            .store_bytes(&amount.to_be_bytes()[16 - len..])
+    }
+
+    pub(crate) fn store_ref(&mut self, cell: Cell) -> CellResult<&mut Self> {
+        if self.refs.len() == MAX_REFS {
+            return Err(invalid("too many references"));
+        }
+        self.refs.push(Arc::new(cell));
+        Ok(self)
+    }
+
+    /// Appends the data and references of `cell`
+    pub(crate) fn store_cell(&mut self, cell: &Cell) -> CellResult<&mut Self> {
+        let mut parser = cell.parser();
+        while parser.remaining_bits() > 0 {
+            self.store_bit(parser.load_bit()?)?;
+        }
+        for child in &cell.refs {
+            self.store_ref((**child).clone())?;
+        }
+        Ok(self)
+    }
+
+    pub(crate) fn build(&mut self) -> Cell {
+        let builder = std::mem::take(self);
+        Cell {
+            data: builder.data,
+            bit_len: builder.bit_len,
+            refs: builder.refs,
+        }
+    }
+}
+
+/// Reads the data and references of a cell in order
+#[derive(Debug)]
+pub(crate) struct CellParser<'a> {
+    cell: &'a Cell,
+    bit: usize,
+    next_ref: usize,
+}
+
+impl<'a> CellParser<'a> {
+    pub(crate) fn remaining_bits(&self) -> usize {
+        self.cell.bit_len - self.bit
+    }
+
+    pub(crate) fn load_bit(&mut self) -> CellResult<bool> {
+        if self.remaining_bits() == 0 {
+            return Err(invalid("cell underflow"));
+        }
+        let bit = self.cell.data[self.bit / 8] & (0x80 >> (self.bit % 8)) != 0;
+        self.bit += 1;
+        Ok(bit)
+    }
+
+    pub(crate) fn load_uint(&mut self, bits: usize) -> CellResult<u64> {
+        let mut value = 0;
+        for _ in 0..bits {
+            value = (value << 1) | u64::from(self.load_bit()?);
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn load_bytes(&mut self, len: usize) -> CellResult<Vec<u8>> {
+        (0..len).map(|_| Ok(self.load_uint(8)? as u8)).collect()
+    }
+
+    pub(crate) fn load_h256(&mut self) -> CellResult<H256> {
+        Ok(H256::from_slice(&self.load_bytes(H256::len_bytes())?))
+    }
+
+    pub(crate) fn load_ref(&mut self) -> CellResult<&'a Cell> {
+        let cell = self
+            .cell
+            .refs
+            .get(self.next_ref)
+            .ok_or_else(|| invalid("no more references"))?;
+        self.next_ref += 1;
+        Ok(cell)
+    }
+}
+
+/// Stores `bytes` in a chain of cells, each referencing the next one
+pub(crate) fn snake(bytes: &[u8]) -> CellResult<Cell> {
+    let mut chunks = bytes.chunks(SNAKE_BYTES_PER_CELL).rev();
+    let mut cell = CellBuilder::new()
+        .store_bytes(chunks.next().unwrap_or_default())?
+        .build();
+    for chunk in chunks {
+        cell = CellBuilder::new()
+            .store_bytes(chunk)?
+            .store_ref(cell)?
+            .build();
+    }
+    Ok(cell)
+}
+
+/// Reads bytes stored in a chain of cells by [`snake`]
+pub(crate) fn read_snake(cell: &Cell) -> CellResult<Vec<u8>> {
+    let mut bytes = vec![];
+    let mut cell = cell;
+    loop {
+        let mut parser = cell.parser();
+        bytes.extend(parser.load_bytes(parser.remaining_bits() / 8)?);
+        match parser.load_ref() {
+            Ok(next) => cell = next,
+            Err(_) => return Ok(bytes),
+        }
+    }
+}
+
+/// The number of bytes needed to represent `value`, at least one
+fn byte_len(value: u64) -> usize {
+    ((64 - value.leading_zeros() as usize + 7) / 8).max(1)
+}
+
+fn write_uint(bytes: &mut Vec<u8>, value: u64, len: usize) {
+    bytes.extend(&value.to_be_bytes()[8 - len..]);
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> CellResult<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| invalid("bag of cells is truncated"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn uint(&mut self, len: usize) -> CellResult<usize> {
+        if len > 8 {
+            return Err(invalid("integer too large"));
+        }
+        Ok(self
+            .take(len)?
+            .iter()
+            .fold(0, |value, byte| (value << 8) | *byte as usize))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_empty_cell_hash() {
+        let cell = CellBuilder::new().build();
+        assert_eq!(
+            format!("{:x}", cell.hash()),
+            "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7"
+        );
+    }
+
+    #[test]
+    fn test_boc_roundtrip() {
+        let bytes: Vec<u8> = (0..=255).cycle().take(300).collect();
+        let cell = CellBuilder::new()
+            .store_uint(3, 0b101)
+            .unwrap()
+            .store_coins(1_000_000_000)
+            .unwrap()
+            .store_ref(snake(&bytes).unwrap())
+            .unwrap()
+            .build();
+
+        let decoded = Cell::from_boc(&cell.to_boc()).unwrap();
+        assert_eq!(decoded, cell);
+        assert_eq!(decoded.hash(), cell.hash());
+
+        let mut parser = decoded.parser();
+        assert_eq!(parser.load_uint(3).unwrap(), 0b101);
+        assert_eq!(read_snake(parser.load_ref().unwrap()).unwrap(), bytes);
+    }
+}
@@ -0,0 +1,246 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hyperlane_core::{ChainResult, HyperlaneDomainProtocol, H256, U256};
+use reqwest::{Client, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{
+    cell::Cell,
+    types::{
+        Account, Block, Blocks, FeeEstimate, GetMethodResult, MasterchainInfo, Transaction,
+        Transactions,
+    },
+    HyperlaneTonError,
+};
+
+/// The header carrying the toncenter API key
+const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Decodes a base64 transaction or block hash
+pub(crate) fn decode_hash(hash: &str) -> Result<H256, HyperlaneTonError> {
+    let bytes = BASE64.decode(hash)?;
+    if bytes.len() != H256::len_bytes() {
+        return Err(HyperlaneTonError::UnexpectedResponse(format!(
+            "hash `{hash}` is not 32 bytes"
+        )));
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+/// Decodes a base64 bag of cells
+pub(crate) fn decode_boc(boc: &str) -> Result<Cell, HyperlaneTonError> {
+    Cell::from_boc(&BASE64.decode(boc)?)
+}
+
+/// A get method argument or return value
+pub(crate) fn num(value: U256) -> Value {
+    json!({ "type": "num", "value": format!("{value:#x}") })
+}
+
+/// A client of the toncenter v3 API
+#[derive(Debug, Clone)]
+pub(crate) struct TonClient {
+    http: Client,
+    url: Url,
+    api_key: Option<String>,
+}
+
+impl TonClient {
+    pub(crate) fn new(url: Url, api_key: Option<String>) -> Self {
+        Self {
+            http: Client::new(),
+            url,
+            api_key,
+        }
+    }
+
+    fn endpoint(&self, path: &str) -> Result<Url, HyperlaneTonError> {
+        Ok(self.url.join(path)?)
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        mut request: RequestBuilder,
+    ) -> Result<T, HyperlaneTonError> {
+        if let Some(api_key) = &self.api_key {
+            request = request.header(API_KEY_HEADER, api_key);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body: Value = response.json().await.unwrap_or_default();
+            return Err(HyperlaneTonError::ApiError {
+                status: status.as_u16(),
+                message: body["error"].as_str().unwrap_or_default().to_owned(),
+            });
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> ChainResult<T> {
+        let request = self.http.get(self.endpoint(path)?).query(query);
+        Ok(self.send(request).await?)
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str, body: Value) -> ChainResult<T> {
+        let request = self.http.post(self.endpoint(path)?).json(&body);
+        Ok(self.send(request).await?)
+    }
+
+    /// The latest masterchain block
+    pub(crate) async fn latest_block(&self) -> ChainResult<Block> {
+        let info: MasterchainInfo = self.get("masterchainInfo", &[]).await?;
+        Ok(info.last)
+    }
+
+    /// The masterchain block `seqno`
+    pub(crate) async fn block(&self, seqno: u32) -> ChainResult<Block> {
+        let blocks: Blocks = self
+            .get(
+                "blocks",
+                &[
+                    ("workchain", "-1".into()),
+                    ("seqno", seqno.to_string()),
+                    ("limit", "1".into()),
+                ],
+            )
+            .await?;
+        blocks.blocks.into_iter().next().ok_or_else(|| {
+            HyperlaneTonError::UnexpectedResponse(format!("masterchain block {seqno} not found"))
+                .into()
+        })
+    }
+
+    /// The state of `account`
+    pub(crate) async fn account(&self, account: &H256) -> ChainResult<Account> {
+        self.get(
+            "account",
+            &[(
+                "address",
+                HyperlaneDomainProtocol::Ton.fmt_address(*account),
+            )],
+        )
+        .await
+    }
+
+    /// The transactions of `account` between the unix times `start` and
+    /// `end`, oldest first
+    pub(crate) async fn transactions(
+        &self,
+        account: &H256,
+        start: u64,
+        end: u64,
+        offset: usize,
+        limit: usize,
+    ) -> ChainResult<Vec<Transaction>> {
+        let transactions: Transactions = self
+            .get(
+                "transactions",
+                &[
+                    (
+                        "account",
+                        HyperlaneDomainProtocol::Ton.fmt_address(*account),
+                    ),
+                    ("start_utime", start.to_string()),
+                    ("end_utime", end.to_string()),
+                    ("offset", offset.to_string()),
+                    ("limit", limit.to_string()),
+                    ("sort", "asc".into()),
+                ],
+            )
+            .await?;
+        Ok(transactions.transactions)
+    }
+
+    /// The latest transaction of `account`
+    pub(crate) async fn last_transaction(
+        &self,
+        account: &H256,
+    ) -> ChainResult<Option<Transaction>> {
+        let transactions: Transactions = self
+            .get(
+                "transactions",
+                &[
+                    (
+                        "account",
+                        HyperlaneDomainProtocol::Ton.fmt_address(*account),
+                    ),
+                    ("limit", "1".into()),
+                    ("sort", "desc".into()),
+                ],
+            )
+            .await?;
+        Ok(transactions.transactions.into_iter().next())
+    }
+
+    /// Runs the get method `method` of the contract at `address`, returning
+    /// its stack
+    pub(crate) async fn run_get_method(
+        &self,
+        address: &H256,
+        method: &str,
+        stack: Vec<Value>,
+    ) -> Result<Vec<Value>, HyperlaneTonError> {
+        let body = json!({
+            "address": HyperlaneDomainProtocol::Ton.fmt_address(*address),
+            "method": method,
+            "stack": stack,
+        });
+        let request = self.http.post(self.endpoint("runGetMethod")?).json(&body);
+        let result: GetMethodResult = self.send(request).await?;
+        if result.exit_code != 0 {
+            return Err(HyperlaneTonError::GetMethodError {
+                method: method.into(),
+                exit_code: result.exit_code,
+            });
+        }
+        Ok(result
+            .stack
+            .into_iter()
+            .filter(|entry| entry.kind == "num")
+            .map(|entry| entry.value)
+            .collect())
+    }
+
+    /// The fees paid by `address` to process the external message with
+    /// `body`
+    pub(crate) async fn estimate_fee(&self, address: &H256, body: &Cell) -> ChainResult<u64> {
+        let estimate: FeeEstimate = self
+            .post(
+                "estimateFee",
+                json!({
+                    "address": HyperlaneDomainProtocol::Ton.fmt_address(*address),
+                    "body": BASE64.encode(body.to_boc()),
+                    "ignore_chksig": true,
+                }),
+            )
+            .await?;
+        Ok(estimate.source_fees.total())
+    }
+
+    /// Sends an external message
+    pub(crate) async fn send_message(&self, message: &Cell) -> ChainResult<()> {
+        let _: Value = self
+            .post("message", json!({ "boc": BASE64.encode(message.to_boc()) }))
+            .await?;
+        Ok(())
+    }
+}
+
+/// Parses a `num` stack entry
+pub(crate) fn parse_num(value: &Value) -> ChainResult<U256> {
+    let value = value.as_str().unwrap_or_default();
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).ok(),
+        None => U256::from_dec_str(value).ok(),
+    };
+    parsed.ok_or_else(|| {
+        HyperlaneTonError::UnexpectedResponse(format!("`{value}` is not an unsigned integer"))
+            .into()
+    })
+}
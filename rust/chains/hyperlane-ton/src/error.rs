@@ -0,0 +1,50 @@
+use hyperlane_core::ChainCommunicationError;
+
+/// Errors from the crates specific to the hyperlane-ton
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneTonError {
+    /// Reqwest error
+    #[error("{0}")]
+    ReqwestError(#[from] reqwest::Error),
+    /// serde_json error
+    #[error("{0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+    /// Url parsing error
+    #[error("{0}")]
+    UrlError(#[from] url::ParseError),
+    /// Base64 decoding error
+    #[error("{0}")]
+    Base64Error(#[from] base64::DecodeError),
+    /// Error returned by the toncenter API
+    #[error("toncenter API error {status}: {message}")]
+    ApiError {
+        /// The HTTP status code
+        status: u16,
+        /// The error message
+        message: String,
+    },
+    /// A get method exited with a non-zero code
+    #[error("Get method `{method}` exited with code {exit_code}")]
+    GetMethodError {
+        /// The name of the get method
+        method: String,
+        /// The exit code of the TVM
+        exit_code: i64,
+    },
+    /// A bag of cells that can't be deserialized, or a cell that doesn't
+    /// have the expected layout
+    #[error("Invalid cell: {0}")]
+    InvalidCell(String),
+    /// A response that doesn't have the expected shape
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+}
+
+impl From<HyperlaneTonError> for ChainCommunicationError {
+    fn from(value: HyperlaneTonError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
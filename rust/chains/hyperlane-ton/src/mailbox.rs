@@ -0,0 +1,328 @@
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, Decode,
+    FixedPointNumber, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, RawHyperlaneMessage,
+    SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, U256,
+};
+
+use crate::{
+    cell::{read_snake, snake, Cell, CellBuilder},
+    client::{decode_hash, num, parse_num},
+    ConnectionConf, HyperlaneTonError, Signer, TonProvider,
+};
+
+/// The op of the internal message processing a message
+const OP_PROCESS: u64 = 0x3a1c_29e4;
+/// The op of the event emitted when a message is dispatched
+const EVENT_DISPATCH: u64 = 0x2c3f_67a1;
+/// The op of the event emitted when a message is processed
+const EVENT_PROCESS: u64 = 0x5e0b_d8c2;
+/// The nanotons attached to `process` messages when no gas limit is given.
+/// What isn't spent by the mailbox and the recipient is bounced back.
+const PROCESS_VALUE: u128 = 100_000_000;
+/// How often the mailbox is polled for the delivery of a processed message
+const DELIVERY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How many times the mailbox is polled before the message is considered
+/// not delivered
+const DELIVERY_POLL_ATTEMPTS: usize = 15;
+
+/// A reference to a Mailbox contract on some TON chain
+#[derive(Debug)]
+pub struct TonMailbox {
+    address: H256,
+    provider: TonProvider,
+}
+
+impl TonMailbox {
+    /// Create a new TON mailbox
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        Ok(Self {
+            address: locator.address,
+            provider: TonProvider::new(locator.domain.clone(), conf, signer),
+        })
+    }
+
+    /// Runs the get method `method` of the mailbox, returning the first
+    /// entry of its stack
+    async fn get(&self, method: &str, stack: Vec<Value>) -> ChainResult<Value> {
+        let stack = self
+            .provider
+            .client()
+            .run_get_method(&self.address, method, stack)
+            .await?;
+        stack.into_iter().next().ok_or_else(|| {
+            HyperlaneTonError::UnexpectedResponse(format!("{method} returned nothing")).into()
+        })
+    }
+
+    /// The body of the internal message processing `message`
+    fn process_body(message: &HyperlaneMessage, metadata: &[u8]) -> ChainResult<Cell> {
+        let message = RawHyperlaneMessage::from(message);
+        Ok(CellBuilder::new()
+            .store_uint(32, OP_PROCESS)?
+            // query_id
+            .store_uint(64, 0)?
+            .store_ref(snake(metadata)?)?
+            .store_ref(snake(&message)?)?
+            .build())
+    }
+}
+
+/// Whether a boolean returned by a get method is true. TVM booleans are `-1`
+/// when true and `0` when false.
+fn is_true(value: &Value) -> bool {
+    !matches!(value.as_str(), Some("0") | Some("0x0") | None)
+}
+
+impl HyperlaneContract for TonMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for TonMailbox {
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        assert!(lag.is_none(), "TON does not support querying point-in-time");
+        let nonce = parse_num(&self.get("get_nonce", vec![]).await?)?;
+        Ok(nonce.as_u32())
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let delivered = self
+            .get(
+                "get_delivered",
+                vec![num(U256::from_big_endian(id.as_bytes()))],
+            )
+            .await?;
+        Ok(is_true(&delivered))
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        let ism = parse_num(&self.get("get_default_ism", vec![]).await?)?;
+        Ok(ism.into())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        // Recipients without a `get_ism` get method, or returning the zero
+        // address, use the default ISM
+        let ism = match self
+            .provider
+            .client()
+            .run_get_method(&recipient, "get_ism", vec![])
+            .await
+        {
+            Ok(stack) => stack
+                .first()
+                .map(parse_num)
+                .transpose()?
+                .unwrap_or_default(),
+            Err(HyperlaneTonError::GetMethodError { .. }) => U256::zero(),
+            Err(err) => return Err(err.into()),
+        };
+        if ism.is_zero() {
+            return self.default_ism().await;
+        }
+        Ok(ism.into())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let value = tx_gas_limit.map_or(PROCESS_VALUE, |gas_limit| gas_limit.as_u128());
+        let body = Self::process_body(message, metadata)?;
+        let transaction = self.provider.send(&self.address, value, body).await?;
+        let transaction_id = decode_hash(&transaction.hash)?;
+
+        // The wallet's transaction only sends the internal message, which the
+        // mailbox processes in a later transaction
+        let id = message.id();
+        let mut executed = false;
+        for _ in 0..DELIVERY_POLL_ATTEMPTS {
+            if self.delivered(id).await? {
+                executed = true;
+                break;
+            }
+            tokio::time::sleep(DELIVERY_POLL_INTERVAL).await;
+        }
+        if !executed {
+            warn!(?id, %transaction.hash, "TON message was not delivered");
+        }
+
+        Ok(TxOutcome {
+            transaction_id: transaction_id.into(),
+            executed,
+            // An upper bound, as the unspent value is bounced back later
+            gas_used: value.into(),
+            gas_price: FixedPointNumber::from(1u64),
+        })
+    }
+
+    #[instrument(err, ret, skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        let body = Self::process_body(message, metadata)?;
+        let fee = self
+            .provider
+            .estimate_send(&self.address, PROCESS_VALUE, body)
+            .await?;
+        // TON has no gas limit for a chain of messages, so costs are in
+        // nanotons at a gas price of 1
+        Ok(TxCostEstimate {
+            gas_limit: (PROCESS_VALUE + u128::from(fee)).into(),
+            gas_price: FixedPointNumber::from(1u64),
+            l2_gas_limit: None,
+            resources: vec![],
+        })
+    }
+
+    /// The bag of cells of the internal message body
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        Self::process_body(message, metadata)
+            .expect("the process body fits in cells")
+            .to_boc()
+    }
+}
+
+/// Struct that retrieves dispatched messages and processed message ids from
+/// the external out messages of a Mailbox contract on some TON chain, by
+/// masterchain block
+#[derive(Debug)]
+pub struct TonMailboxIndexer {
+    mailbox: TonMailbox,
+}
+
+impl TonMailboxIndexer {
+    /// Create a new TON mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            mailbox: TonMailbox::new(conf, locator, None)?,
+        })
+    }
+
+    /// The events with the op `op` in `range`, with their op loaded
+    async fn events_in_range(
+        &self,
+        op: u64,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Cell, LogMeta)>> {
+        let events = self
+            .mailbox
+            .provider
+            .events_in_range(&self.mailbox.address, range)
+            .await?;
+        Ok(events
+            .into_iter()
+            .filter(|(body, _)| body.parser().load_uint(32).ok() == Some(op))
+            .collect())
+    }
+}
+
+/// Reads the dispatched message of a `Dispatch` event
+fn parse_dispatch(body: &Cell) -> ChainResult<HyperlaneMessage> {
+    let mut parser = body.parser();
+    parser.load_uint(32)?;
+    let message = read_snake(parser.load_ref()?)?;
+    HyperlaneMessage::read_from(&mut message.as_slice())
+        .map_err(ChainCommunicationError::from_other)
+}
+
+/// Reads the message id of a `Process` event
+fn parse_process(body: &Cell) -> ChainResult<H256> {
+    let mut parser = body.parser();
+    parser.load_uint(32)?;
+    Ok(parser.load_h256()?)
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for TonMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        let events = self.events_in_range(EVENT_DISPATCH, range).await?;
+        events
+            .into_iter()
+            .map(|(body, meta)| Ok((parse_dispatch(&body)?.into(), meta)))
+            .collect()
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.latest_block_number().await
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for TonMailboxIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        let events = self.events_in_range(EVENT_PROCESS, range).await?;
+        Ok(events
+            .into_iter()
+            .filter_map(|(body, meta)| match parse_process(&body) {
+                Ok(id) => Some((Indexed::new(id), meta)),
+                Err(_) => {
+                    warn!(?meta, "Process event has no valid message id, skipping");
+                    None
+                }
+            })
+            .collect())
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.mailbox.provider.latest_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for TonMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
+        let count = Mailbox::count(&self.mailbox, None).await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for TonMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<H256>::get_finalized_block_number(self).await?;
+        // No sequence for message deliveries.
+        Ok((None, tip))
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneDomainProtocol, HyperlaneProvider, LogMeta, TxnInfo, H256, U256,
+};
+use tracing::debug;
+
+use crate::{
+    cell::Cell,
+    client::{decode_boc, decode_hash, parse_num, TonClient},
+    types::Transaction,
+    ConnectionConf, HyperlaneTonError, Signer,
+};
+
+/// How long a wallet message stays valid
+const MESSAGE_TTL: Duration = Duration::from_secs(60);
+/// How often the seqno of the wallet is polled after sending a message
+const SEQNO_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How many times the seqno of the wallet is polled before giving up
+const SEQNO_POLL_ATTEMPTS: usize = 30;
+/// The number of transactions fetched per request when indexing
+const TRANSACTIONS_PAGE_SIZE: usize = 256;
+
+/// A wrapper around the toncenter API to get generic blockchain information,
+/// run get methods and send messages from a wallet.
+#[derive(Debug, Clone)]
+pub struct TonProvider {
+    domain: HyperlaneDomain,
+    client: TonClient,
+    signer: Option<Signer>,
+}
+
+impl TonProvider {
+    /// Create a new TON provider
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf, signer: Option<Signer>) -> Self {
+        Self {
+            domain,
+            client: TonClient::new(conf.url.clone(), conf.api_key.clone()),
+            signer,
+        }
+    }
+
+    pub(crate) fn client(&self) -> &TonClient {
+        &self.client
+    }
+
+    fn signer(&self) -> ChainResult<&Signer> {
+        self.signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)
+    }
+
+    /// The seqno of the latest masterchain block, which indexing uses as the
+    /// block number. Masterchain blocks are final once committed.
+    pub(crate) async fn latest_block_number(&self) -> ChainResult<u32> {
+        Ok(self.client.latest_block().await?.seqno)
+    }
+
+    async fn wallet_seqno(&self, wallet: &H256) -> ChainResult<u32> {
+        let stack = self.client.run_get_method(wallet, "seqno", vec![]).await?;
+        let seqno = stack
+            .first()
+            .map(parse_num)
+            .transpose()?
+            .unwrap_or_default();
+        Ok(seqno.as_u32())
+    }
+
+    /// The fees paid by the signer's wallet to send `body` to `destination`
+    pub(crate) async fn estimate_send(
+        &self,
+        destination: &H256,
+        value: u128,
+        body: Cell,
+    ) -> ChainResult<u64> {
+        let signer = self.signer()?;
+        let message = signer.wallet_message(0, 0, destination, value, body)?;
+        // The fees only depend on the body of the external message
+        let body = message.parser().load_ref()?.clone();
+        self.client.estimate_fee(&signer.address, &body).await
+    }
+
+    /// Sends `value` nanotons along with `body` to `destination` from the
+    /// signer's wallet, and waits for the wallet to process the message.
+    /// Returns the transaction of the wallet, the transaction of the
+    /// destination happens asynchronously.
+    pub(crate) async fn send(
+        &self,
+        destination: &H256,
+        value: u128,
+        body: Cell,
+    ) -> ChainResult<Transaction> {
+        let signer = self.signer()?;
+        let seqno = self.wallet_seqno(&signer.address).await?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(ChainCommunicationError::from_other)?;
+        let valid_until = (now + MESSAGE_TTL).as_secs() as u32;
+        let message = signer.wallet_message(seqno, valid_until, destination, value, body)?;
+        self.client.send_message(&message).await?;
+        debug!(seqno, wallet=%signer.address_string(), "Sent TON wallet message");
+
+        for _ in 0..SEQNO_POLL_ATTEMPTS {
+            tokio::time::sleep(SEQNO_POLL_INTERVAL).await;
+            if self.wallet_seqno(&signer.address).await? > seqno {
+                return self
+                    .client
+                    .last_transaction(&signer.address)
+                    .await?
+                    .ok_or_else(|| {
+                        HyperlaneTonError::UnexpectedResponse("wallet has no transactions".into())
+                            .into()
+                    });
+            }
+        }
+        Err(ChainCommunicationError::TransactionTimeout())
+    }
+
+    /// The bodies of the external out messages, which contracts use as
+    /// events, sent by the contract at `address` in the masterchain blocks in
+    /// `range`, along with their metadata
+    pub(crate) async fn events_in_range(
+        &self,
+        address: &H256,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Cell, LogMeta)>> {
+        let start = self.client.block(*range.start()).await?;
+        let end = self.client.block(*range.end()).await?;
+        let mut block_hashes = HashMap::from([
+            (start.seqno, decode_hash(&start.root_hash)?),
+            (end.seqno, decode_hash(&end.root_hash)?),
+        ]);
+
+        let mut events = vec![];
+        let mut offset = 0;
+        loop {
+            let transactions = self
+                .client
+                .transactions(
+                    address,
+                    start.gen_utime,
+                    end.gen_utime,
+                    offset,
+                    TRANSACTIONS_PAGE_SIZE,
+                )
+                .await?;
+            let count = transactions.len();
+            for transaction in transactions {
+                // Blocks generated in the same second as the range's ends can
+                // be outside of it
+                let Some(seqno) = transaction
+                    .mc_block_seqno
+                    .filter(|seqno| range.contains(seqno))
+                else {
+                    continue;
+                };
+                let block_hash = match block_hashes.get(&seqno) {
+                    Some(hash) => *hash,
+                    None => {
+                        let hash = decode_hash(&self.client.block(seqno).await?.root_hash)?;
+                        block_hashes.insert(seqno, hash);
+                        hash
+                    }
+                };
+                let transaction_id = decode_hash(&transaction.hash)?;
+                for (log_index, message) in transaction.out_msgs.iter().enumerate() {
+                    let Some(content) = message
+                        .message_content
+                        .as_ref()
+                        .filter(|_| message.destination.is_none())
+                    else {
+                        continue;
+                    };
+                    let body = decode_boc(&content.body)?;
+                    events.push((
+                        body,
+                        LogMeta {
+                            address: *address,
+                            block_number: seqno.into(),
+                            block_hash,
+                            transaction_id: transaction_id.into(),
+                            // Logical times order the transactions of an account
+                            transaction_index: transaction.lt,
+                            log_index: log_index.into(),
+                        },
+                    ));
+                }
+            }
+            if count < TRANSACTIONS_PAGE_SIZE {
+                return Ok(events);
+            }
+            offset += count;
+        }
+    }
+}
+
+impl HyperlaneChain for TonProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for TonProvider {
+    async fn get_block_by_hash(&self, _hash: &H256) -> ChainResult<BlockInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "TON does not support fetching blocks by hash yet",
+        ))
+    }
+
+    async fn get_txn_by_hash(&self, _hash: &H256) -> ChainResult<TxnInfo> {
+        Err(ChainCommunicationError::from_other_str(
+            "TON does not support fetching transactions yet",
+        ))
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        // Contracts are accounts with code, which have transactions
+        Ok(self.client.last_transaction(address).await?.is_some())
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        let address = HyperlaneDomainProtocol::Ton.parse_address(&address)?;
+        Ok(self.client.account(&address).await?.balance.into())
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,113 @@
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer as _};
+use hyperlane_core::{ChainCommunicationError, ChainResult, HyperlaneDomainProtocol, H256};
+
+use crate::{
+    cell::{Cell, CellBuilder},
+    HyperlaneTonError,
+};
+
+/// The subwallet id of wallets deployed to the basechain with the default
+/// settings
+const DEFAULT_SUBWALLET_ID: u32 = 698983191;
+/// The op of wallet v4 messages sending internal messages
+const WALLET_OP_SEND: u64 = 0;
+/// Pay the forwarding fees separately from the value, and ignore errors
+const SEND_MODE: u64 = 3;
+/// The workchain of the wallet and of the contracts it sends messages to
+pub(crate) const BASECHAIN: i8 = 0;
+
+/// An ed25519 key controlling a v4 wallet on the basechain. Wallet addresses
+/// depend on their code, so the address is configured along with the key.
+#[derive(Debug)]
+pub struct Signer {
+    keypair: Keypair,
+    /// The account id of the wallet
+    pub address: H256,
+}
+
+impl Signer {
+    /// Create a new TON signer from an ed25519 private key and the address of
+    /// the wallet it controls
+    pub fn new(private_key: &H256, address: H256) -> ChainResult<Self> {
+        let secret = SecretKey::from_bytes(private_key.as_bytes())
+            .map_err(ChainCommunicationError::from_other)?;
+        let public = PublicKey::from(&secret);
+        Ok(Self {
+            keypair: Keypair { secret, public },
+            address,
+        })
+    }
+
+    /// The external message making the wallet send `value` nanotons along
+    /// with `body` to the contract at `destination`
+    pub(crate) fn wallet_message(
+        &self,
+        seqno: u32,
+        valid_until: u32,
+        destination: &H256,
+        value: u128,
+        body: Cell,
+    ) -> Result<Cell, HyperlaneTonError> {
+        let internal = CellBuilder::new()
+            // int_msg_info$0, IHR disabled, bounce, not bounced, no source
+            .store_uint(1, 0)?
+            .store_bit(true)?
+            .store_bit(true)?
+            .store_bit(false)?
+            .store_uint(2, 0)?
+            .store_address(BASECHAIN, destination)?
+            .store_coins(value)?
+            // No extra currencies, the fees and times are set by the validators
+            .store_bit(false)?
+            .store_coins(0)?
+            .store_coins(0)?
+            .store_uint(64, 0)?
+            .store_uint(32, 0)?
+            // No state init, body in a reference
+            .store_bit(false)?
+            .store_bit(true)?
+            .store_ref(body)?
+            .build();
+
+        let signing_message = CellBuilder::new()
+            .store_uint(32, DEFAULT_SUBWALLET_ID.into())?
+            .store_uint(32, valid_until.into())?
+            .store_uint(32, seqno.into())?
+            .store_uint(8, WALLET_OP_SEND)?
+            .store_uint(8, SEND_MODE)?
+            .store_ref(internal)?
+            .build();
+        let signature = self.keypair.sign(signing_message.hash().as_bytes());
+        let body = CellBuilder::new()
+            .store_bytes(&signature.to_bytes())?
+            .store_cell(&signing_message)?
+            .build();
+
+        CellBuilder::new()
+            // ext_in_msg_info$10, no source, no import fee
+            .store_uint(2, 0b10)?
+            .store_uint(2, 0)?
+            .store_address(BASECHAIN, &self.address)?
+            .store_coins(0)?
+            // No state init, body in a reference
+            .store_bit(false)?
+            .store_bit(true)?
+            .store_ref(body)
+            .map(|builder| builder.build())
+    }
+
+    /// The address of the wallet, in raw form
+    pub fn address_string(&self) -> String {
+        HyperlaneDomainProtocol::Ton.fmt_address(self.address)
+    }
+}
+
+impl Clone for Signer {
+    fn clone(&self) -> Self {
+        Self {
+            keypair: Keypair::from_bytes(&self.keypair.to_bytes())
+                .expect("keypair bytes are valid"),
+            address: self.address,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use hyperlane_core::config::OperationBatchConfig;
+use url::Url;
+
+/// TON connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Fully qualified url of a toncenter v3 API, which indexes the
+    /// blockchain from lite-servers, e.g. `https://toncenter.com/api/v3/`
+    pub url: Url,
+    /// The toncenter API key, without which requests are rate limited
+    pub api_key: Option<String>,
+    /// Operation batching configuration
+    pub operation_batch: OperationBatchConfig,
+}
+
+impl ConnectionConf {
+    /// Create a new connection configuration
+    pub fn new(url: Url, api_key: Option<String>, operation_batch: OperationBatchConfig) -> Self {
+        Self {
+            url,
+            api_key,
+            operation_batch,
+        }
+    }
+}
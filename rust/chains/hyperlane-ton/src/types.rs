@@ -0,0 +1,106 @@
+//! The JSON types returned by the toncenter v3 API.
+
+use serde::Deserialize;
+
+/// Deserializes a u64 that the API encodes as a string
+fn u64_from_str<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+}
+
+/// A masterchain block, of which the others are shard blocks
+#[derive(Deserialize, Debug)]
+pub(crate) struct Block {
+    pub seqno: u32,
+    /// Base64 encoded
+    pub root_hash: String,
+    #[serde(deserialize_with = "u64_from_str")]
+    pub gen_utime: u64,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct Blocks {
+    pub blocks: Vec<Block>,
+}
+
+/// The `GET /masterchainInfo` response
+#[derive(Deserialize, Debug)]
+pub(crate) struct MasterchainInfo {
+    pub last: Block,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct MessageContent {
+    /// The base64 encoded bag of cells of the body
+    pub body: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct Message {
+    /// Unset for external out messages, which contracts use as events
+    pub destination: Option<String>,
+    pub message_content: Option<MessageContent>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct Transaction {
+    /// Base64 encoded
+    pub hash: String,
+    /// The logical time of the transaction
+    #[serde(deserialize_with = "u64_from_str")]
+    pub lt: u64,
+    /// The masterchain block that committed the shard block of the
+    /// transaction
+    pub mc_block_seqno: Option<u32>,
+    #[serde(default)]
+    pub out_msgs: Vec<Message>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct Transactions {
+    pub transactions: Vec<Transaction>,
+}
+
+/// An entry of the TVM stack passed to and returned by get methods
+#[derive(Deserialize, Debug)]
+pub(crate) struct StackEntry {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: serde_json::Value,
+}
+
+/// The `POST /runGetMethod` response
+#[derive(Deserialize, Debug)]
+pub(crate) struct GetMethodResult {
+    pub exit_code: i64,
+    pub stack: Vec<StackEntry>,
+}
+
+/// The fees, in nanotons, paid by the account receiving an external message
+#[derive(Deserialize, Debug)]
+pub(crate) struct Fees {
+    pub in_fwd_fee: u64,
+    pub storage_fee: u64,
+    pub gas_fee: u64,
+    pub fwd_fee: u64,
+}
+
+impl Fees {
+    pub(crate) fn total(&self) -> u64 {
+        self.in_fwd_fee + self.storage_fee + self.gas_fee + self.fwd_fee
+    }
+}
+
+/// The `POST /estimateFee` response
+#[derive(Deserialize, Debug)]
+pub(crate) struct FeeEstimate {
+    pub source_fees: Fees,
+}
+
+/// The `GET /account` response
+#[derive(Deserialize, Debug)]
+pub(crate) struct Account {
+    /// The balance in nanotons
+    #[serde(deserialize_with = "u64_from_str")]
+    pub balance: u64,
+}
@@ -0,0 +1,166 @@
+use hyperlane_core::{ChainResult, H160, H256};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{
+    transaction::tron_address,
+    types::{CallResult, ChainParameters, TransactionInfo, TriggerResponse, UnsignedTransaction},
+    HyperlaneTronError,
+};
+
+/// The header carrying the TronGrid API key
+const API_KEY_HEADER: &str = "TRON-PRO-API-KEY";
+/// The chain parameter holding the price of energy, in sun
+const ENERGY_FEE_PARAMETER: &str = "getEnergyFee";
+/// The chain parameter holding the price of bandwidth, in sun per byte
+const BANDWIDTH_FEE_PARAMETER: &str = "getTransactionFee";
+
+/// The hex form of addresses expected by the API, prefixed with the `0x41`
+/// version byte
+fn hex_address(address: &H160) -> String {
+    hex::encode(tron_address(address))
+}
+
+/// A client of the HTTP API of a Tron full node
+#[derive(Debug, Clone)]
+pub(crate) struct TronClient {
+    http: Client,
+    url: Url,
+    api_key: Option<String>,
+}
+
+impl TronClient {
+    pub(crate) fn new(url: Url, api_key: Option<String>) -> Self {
+        Self {
+            http: Client::new(),
+            url,
+            api_key,
+        }
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str, body: Value) -> ChainResult<T> {
+        let url = self.url.join(path).map_err(HyperlaneTronError::from)?;
+        let mut request = self.http.post(url).json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.header(API_KEY_HEADER, api_key);
+        }
+        let response = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(HyperlaneTronError::from)?;
+        Ok(response.json().await.map_err(HyperlaneTronError::from)?)
+    }
+
+    /// Builds an unsigned transaction calling `contract` with `data`, which
+    /// burns at most `fee_limit` sun for energy
+    pub(crate) async fn trigger_smart_contract(
+        &self,
+        owner: &H160,
+        contract: &H160,
+        data: &[u8],
+        fee_limit: u64,
+    ) -> ChainResult<UnsignedTransaction> {
+        let response: TriggerResponse = self
+            .post(
+                "wallet/triggersmartcontract",
+                json!({
+                    "owner_address": hex_address(owner),
+                    "contract_address": hex_address(contract),
+                    "data": hex::encode(data),
+                    "fee_limit": fee_limit,
+                    "call_value": 0,
+                }),
+            )
+            .await?;
+        response.result.into_result()?;
+        response.transaction.ok_or_else(|| {
+            HyperlaneTronError::UnexpectedResponse("no transaction was built".into()).into()
+        })
+    }
+
+    /// The energy used by calling `contract` with `data`, without sending a
+    /// transaction
+    pub(crate) async fn estimate_energy(
+        &self,
+        owner: &H160,
+        contract: &H160,
+        data: &[u8],
+    ) -> ChainResult<u64> {
+        let response: TriggerResponse = self
+            .post(
+                "wallet/triggerconstantcontract",
+                json!({
+                    "owner_address": hex_address(owner),
+                    "contract_address": hex_address(contract),
+                    "data": hex::encode(data),
+                }),
+            )
+            .await?;
+        response.result.into_result()?;
+        Ok(response.energy_used)
+    }
+
+    /// Broadcasts a signed transaction, given as its protobuf encoding
+    pub(crate) async fn broadcast_hex(&self, transaction: &[u8]) -> ChainResult<()> {
+        let result: CallResult = self
+            .post(
+                "wallet/broadcasthex",
+                json!({ "transaction": hex::encode(transaction) }),
+            )
+            .await?;
+        Ok(result.into_result()?)
+    }
+
+    /// The outcome of the transaction `id`, once it is included in a block
+    pub(crate) async fn transaction_info(&self, id: &H256) -> ChainResult<Option<TransactionInfo>> {
+        let info: TransactionInfo = self
+            .post(
+                "wallet/gettransactioninfobyid",
+                json!({ "value": hex::encode(id.as_bytes()) }),
+            )
+            .await?;
+        Ok(info.id.is_some().then_some(info))
+    }
+
+    /// The price of energy, in sun
+    pub(crate) async fn energy_price(&self) -> ChainResult<u64> {
+        self.chain_parameter(ENERGY_FEE_PARAMETER).await
+    }
+
+    /// The price of bandwidth, in sun per byte
+    pub(crate) async fn bandwidth_price(&self) -> ChainResult<u64> {
+        self.chain_parameter(BANDWIDTH_FEE_PARAMETER).await
+    }
+
+    async fn chain_parameter(&self, key: &str) -> ChainResult<u64> {
+        let parameters: ChainParameters = self.post("wallet/getchainparameters", json!({})).await?;
+        parameters
+            .chain_parameter
+            .into_iter()
+            .find(|parameter| parameter.key == key)
+            .and_then(|parameter| parameter.value)
+            .ok_or_else(|| {
+                HyperlaneTronError::UnexpectedResponse(format!("chain parameter {key} is not set"))
+                    .into()
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_hex_address() {
+        let address = H160::from_str("a614f803b6fd780986a42c78ec9c7f77e6ded13c").unwrap();
+        assert_eq!(
+            hex_address(&address),
+            "41a614f803b6fd780986a42c78ec9c7f77e6ded13c"
+        );
+    }
+}
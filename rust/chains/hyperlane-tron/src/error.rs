@@ -0,0 +1,41 @@
+use hyperlane_core::ChainCommunicationError;
+
+/// Errors from the crates specific to the hyperlane-tron
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneTronError {
+    /// Reqwest error
+    #[error("{0}")]
+    ReqwestError(#[from] reqwest::Error),
+    /// serde_json error
+    #[error("{0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+    /// Url parsing error
+    #[error("{0}")]
+    UrlError(#[from] url::ParseError),
+    /// Error returned by the HTTP API
+    #[error("Tron API error {code}: {message}")]
+    ApiError {
+        /// The error code, e.g. `CONTRACT_VALIDATE_ERROR`
+        code: String,
+        /// The error message
+        message: String,
+    },
+    /// A response that doesn't have the expected shape
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+    /// Protobuf decoding error
+    #[error("{0}")]
+    ProtobufError(#[from] prost::DecodeError),
+    /// A transaction built by the node that isn't the requested one
+    #[error("Transaction doesn't match the requested call: {0}")]
+    TransactionMismatch(String),
+}
+
+impl From<HyperlaneTronError> for ChainCommunicationError {
+    fn from(value: HyperlaneTronError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
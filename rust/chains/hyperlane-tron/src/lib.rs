@@ -0,0 +1,20 @@
+//! Implementation of hyperlane for Tron.
+//!
+//! Tron runs the EVM, so the Ethereum contract bindings are reused over the
+//! Ethereum compatible JSON-RPC API of its nodes for reads and indexing.
+//! Transactions are built with the Tron HTTP API, verified, signed and
+//! broadcast.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+mod client;
+mod error;
+mod mailbox;
+mod provider;
+mod signers;
+mod trait_builder;
+mod transaction;
+mod types;
+
+pub use self::{error::*, mailbox::*, provider::*, signers::*, trait_builder::*};
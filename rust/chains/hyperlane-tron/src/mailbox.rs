@@ -0,0 +1,215 @@
+use std::num::NonZeroU64;
+
+use async_trait::async_trait;
+use ethers::providers::{Http, Provider};
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainResult, ContractLocator, FixedPointNumber, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Mailbox, ResourceCost,
+    TxCostEstimate, TxOutcome, TxResource, H160, H256, U256,
+};
+use hyperlane_ethereum::EthereumMailbox;
+use tracing::{instrument, warn};
+
+use crate::{ConnectionConf, Signer, TronProvider};
+
+/// The receipt result of successful contract calls
+const SUCCESS: &str = "SUCCESS";
+/// The bandwidth used by a contract call besides its calldata: the rest of
+/// the raw transaction, its signature and the space reserved for its result
+const TRANSACTION_OVERHEAD_BYTES: u64 = 300;
+
+/// A reference to a Mailbox contract on some Tron chain. Reads go through the
+/// Ethereum bindings, while `process` transactions are sent with the Tron
+/// HTTP API.
+#[derive(Debug)]
+pub struct TronMailbox {
+    mailbox: EthereumMailbox<Provider<Http>>,
+    provider: TronProvider,
+}
+
+impl TronMailbox {
+    /// Create a new Tron mailbox
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        Ok(Self {
+            mailbox: EthereumMailbox::new(conf.rpc_provider()?, &conf.ethereum_conf()?, &locator),
+            provider: TronProvider::new(locator.domain.clone(), conf, signer)?,
+        })
+    }
+
+    fn contract(&self) -> H160 {
+        self.mailbox.address().into()
+    }
+}
+
+impl HyperlaneContract for TronMailbox {
+    fn address(&self) -> H256 {
+        self.mailbox.address()
+    }
+}
+
+impl HyperlaneChain for TronMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.mailbox.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl Mailbox for TronMailbox {
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        self.mailbox.count(lag).await
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        self.mailbox.delivered(id).await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        self.mailbox.default_ism().await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        self.mailbox.recipient_ism(recipient).await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let data = self.process_calldata(message, metadata);
+        let energy_limit = tx_gas_limit.and_then(|gas_limit| match u64::try_from(gas_limit) {
+            Ok(gas_limit) => Some(gas_limit),
+            Err(err) => {
+                warn!(
+                    ?gas_limit,
+                    err, "Gas limit doesn't fit in a u64, estimating it"
+                );
+                None
+            }
+        });
+        let energy_limit = match energy_limit {
+            Some(energy_limit) => energy_limit,
+            None => {
+                self.provider
+                    .estimate_energy(&self.contract(), &data)
+                    .await?
+            }
+        };
+        let energy_price = self.provider.energy_price().await?;
+        let (id, info) = self
+            .provider
+            .send(&self.contract(), &data, energy_limit, energy_price)
+            .await?;
+
+        let executed = info.receipt.result.as_deref() == Some(SUCCESS);
+        if !executed {
+            warn!(?id, result=?info.receipt.result, "Tron transaction failed");
+        }
+        Ok(TxOutcome {
+            transaction_id: id.into(),
+            executed,
+            gas_used: info.receipt.energy_usage_total.into(),
+            gas_price: FixedPointNumber::from(energy_price),
+        })
+    }
+
+    #[instrument(err, ret, skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        let data = self.process_calldata(message, metadata);
+        let energy = self
+            .provider
+            .estimate_energy(&self.contract(), &data)
+            .await?;
+        let energy_price = self.provider.energy_price().await?;
+        // Bandwidth is often covered by the daily free allowance or staked
+        // TRX, in which case this overestimates the cost
+        let bandwidth = data.len() as u64 + TRANSACTION_OVERHEAD_BYTES;
+        Ok(TxCostEstimate {
+            gas_limit: energy.into(),
+            gas_price: FixedPointNumber::from(energy_price),
+            l2_gas_limit: None,
+            resources: vec![
+                ResourceCost::new(TxResource::Energy, energy, energy_price),
+                ResourceCost::new(
+                    TxResource::Bandwidth,
+                    bandwidth,
+                    self.provider.bandwidth_price().await?,
+                ),
+            ],
+        })
+    }
+
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        self.mailbox.process_calldata(message, metadata)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{config::OperationBatchConfig, RawHyperlaneMessage};
+    use url::Url;
+
+    use super::*;
+
+    #[test]
+    fn test_process_calldata() {
+        let domain = HyperlaneDomain::new_test_domain("test");
+        let conf = ConnectionConf::new(
+            Url::parse("https://api.trongrid.io/").unwrap(),
+            None,
+            OperationBatchConfig::default(),
+        );
+        let locator = ContractLocator {
+            domain: &domain,
+            address: H256::zero(),
+        };
+        let mailbox = TronMailbox::new(&conf, locator, None).unwrap();
+        let message = HyperlaneMessage::default();
+
+        // The ABI encoding of `process(bytes,bytes)`: the selector, the
+        // offsets of both arguments, then each length followed by the data
+        // right padded to a word
+        let calldata = mailbox.process_calldata(&message, &[0xaa]);
+        let raw_message = RawHyperlaneMessage::from(&message);
+        let word = |value: u8| {
+            let mut word = [0u8; 32];
+            word[31] = value;
+            word.to_vec()
+        };
+        let mut metadata = vec![0u8; 32];
+        metadata[0] = 0xaa;
+        let mut padded_message = raw_message.clone();
+        padded_message.resize(96, 0);
+        assert_eq!(
+            calldata,
+            [
+                vec![0x7c, 0x39, 0xd1, 0x30],
+                word(0x40),
+                word(0x80),
+                word(1),
+                metadata,
+                word(raw_message.len() as u8),
+                padded_message,
+            ]
+            .concat()
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ethers::providers::{Http, Provider};
+use hyperlane_core::{
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneDomainProtocol, HyperlaneProvider, TxnInfo, H160, H256, U256,
+};
+use hyperlane_ethereum::EthereumProvider;
+use prost::Message;
+use tracing::debug;
+
+use crate::{
+    client::TronClient,
+    transaction::{transaction_id, verify_transaction, ContractCall, SignedTransaction},
+    types::TransactionInfo,
+    ConnectionConf, HyperlaneTronError, Signer,
+};
+
+/// How often a broadcast transaction is polled for its outcome
+const TRANSACTION_POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// How many times a transaction is polled before giving up. Blocks are
+/// produced every 3 seconds.
+const TRANSACTION_POLL_ATTEMPTS: usize = 20;
+
+/// A wrapper around a Tron full node, whose Ethereum compatible JSON-RPC API
+/// is used for generic blockchain information and its HTTP API to send
+/// transactions.
+#[derive(Debug, Clone)]
+pub struct TronProvider {
+    rpc: EthereumProvider<Provider<Http>>,
+    client: TronClient,
+    signer: Option<Signer>,
+}
+
+impl TronProvider {
+    /// Create a new Tron provider
+    pub fn new(
+        domain: HyperlaneDomain,
+        conf: &ConnectionConf,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        Ok(Self {
+            rpc: EthereumProvider::new(conf.rpc_provider()?, domain),
+            client: TronClient::new(conf.url.clone(), conf.api_key.clone()),
+            signer,
+        })
+    }
+
+    fn signer(&self) -> ChainResult<&Signer> {
+        self.signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)
+    }
+
+    /// The price of energy, in sun
+    pub(crate) async fn energy_price(&self) -> ChainResult<u64> {
+        self.client.energy_price().await
+    }
+
+    /// The price of bandwidth, in sun per byte
+    pub(crate) async fn bandwidth_price(&self) -> ChainResult<u64> {
+        self.client.bandwidth_price().await
+    }
+
+    /// The energy used by calling `contract` with `data` from the signer's
+    /// account
+    pub(crate) async fn estimate_energy(&self, contract: &H160, data: &[u8]) -> ChainResult<u64> {
+        let signer = self.signer()?;
+        self.client
+            .estimate_energy(&signer.address(), contract, data)
+            .await
+    }
+
+    /// Calls `contract` with `data` from the signer's account, using at most
+    /// `energy_limit` energy at `energy_price` sun, and waits for the
+    /// transaction to be included in a block
+    pub(crate) async fn send(
+        &self,
+        contract: &H160,
+        data: &[u8],
+        energy_limit: u64,
+        energy_price: u64,
+    ) -> ChainResult<(H256, TransactionInfo)> {
+        let signer = self.signer()?;
+        let call = ContractCall {
+            owner: &signer.address(),
+            contract,
+            data,
+            fee_limit: energy_limit.saturating_mul(energy_price),
+        };
+        let transaction = self
+            .client
+            .trigger_smart_contract(call.owner, call.contract, call.data, call.fee_limit)
+            .await?;
+
+        // The node builds the transaction, so check that it is the requested
+        // call before signing its id
+        let raw_data = hex::decode(&transaction.raw_data_hex).map_err(|_| {
+            HyperlaneTronError::UnexpectedResponse(format!(
+                "invalid raw data {}",
+                transaction.raw_data_hex
+            ))
+        })?;
+        verify_transaction(&raw_data, &call)?;
+        let id = transaction_id(&raw_data);
+        if transaction.tx_id.parse::<H256>().ok() != Some(id) {
+            return Err(HyperlaneTronError::TransactionMismatch(format!(
+                "id {} isn't the hash of the raw data",
+                transaction.tx_id
+            ))
+            .into());
+        }
+
+        let signed = SignedTransaction {
+            raw_data,
+            signature: vec![signer.sign_transaction(&id)?],
+        };
+        self.client.broadcast_hex(&signed.encode_to_vec()).await?;
+        debug!(?id, "Broadcast Tron transaction");
+
+        for _ in 0..TRANSACTION_POLL_ATTEMPTS {
+            tokio::time::sleep(TRANSACTION_POLL_INTERVAL).await;
+            if let Some(info) = self.client.transaction_info(&id).await? {
+                return Ok((id, info));
+            }
+        }
+        Err(ChainCommunicationError::TransactionTimeout())
+    }
+}
+
+impl HyperlaneChain for TronProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.rpc.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for TronProvider {
+    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
+        self.rpc.get_block_by_hash(hash).await
+    }
+
+    async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
+        self.rpc.get_txn_by_hash(hash).await
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        self.rpc.is_contract(address).await
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        // The JSON-RPC API expects hex addresses
+        let address = HyperlaneDomainProtocol::Tron.parse_address(&address)?;
+        self.rpc
+            .get_balance(format!("{:?}", H160::from(address)))
+            .await
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        self.rpc.get_chain_metrics().await
+    }
+}
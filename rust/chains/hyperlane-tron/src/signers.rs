@@ -0,0 +1,38 @@
+use ethers::signers::{LocalWallet, Signer as _};
+use hyperlane_core::{ChainCommunicationError, ChainResult, HyperlaneDomainProtocol, H160, H256};
+
+/// A secp256k1 key signing for a Tron account. Tron derives account
+/// addresses like Ethereum, so they are the same for a key.
+#[derive(Debug, Clone)]
+pub struct Signer {
+    wallet: LocalWallet,
+}
+
+impl Signer {
+    /// Create a new Tron signer from a secp256k1 private key
+    pub fn new(private_key: &H256) -> ChainResult<Self> {
+        let wallet = LocalWallet::from_bytes(private_key.as_bytes())
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(Self { wallet })
+    }
+
+    /// The address of the account, without its `0x41` version byte
+    pub fn address(&self) -> H160 {
+        self.wallet.address().into()
+    }
+
+    /// Signs the id of a transaction, returning the signature expected in
+    /// its `signature` field
+    pub(crate) fn sign_transaction(&self, id: &H256) -> ChainResult<Vec<u8>> {
+        let signature = self
+            .wallet
+            .sign_hash(id.0.into())
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(signature.to_vec())
+    }
+
+    /// The base58 address of the account
+    pub fn address_string(&self) -> String {
+        HyperlaneDomainProtocol::Tron.fmt_address(self.address().into())
+    }
+}
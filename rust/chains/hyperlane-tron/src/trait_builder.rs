@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use ethers::providers::{Http, Provider};
+use hyperlane_core::{config::OperationBatchConfig, ChainResult};
+use hyperlane_ethereum::{self as h_eth, RpcConnectionConf};
+use url::Url;
+
+use crate::HyperlaneTronError;
+
+/// The path of the Ethereum compatible JSON-RPC API of full nodes
+const JSON_RPC_PATH: &str = "jsonrpc";
+
+/// Tron connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Fully qualified url of the HTTP API of a full node, e.g.
+    /// `https://api.trongrid.io/`
+    pub url: Url,
+    /// The TronGrid API key, without which requests are rate limited
+    pub api_key: Option<String>,
+    /// Operation batching configuration
+    pub operation_batch: OperationBatchConfig,
+}
+
+impl ConnectionConf {
+    /// Create a new connection configuration
+    pub fn new(url: Url, api_key: Option<String>, operation_batch: OperationBatchConfig) -> Self {
+        Self {
+            url,
+            api_key,
+            operation_batch,
+        }
+    }
+
+    /// The url of the Ethereum compatible JSON-RPC API of the node
+    pub fn json_rpc_url(&self) -> ChainResult<Url> {
+        Ok(self
+            .url
+            .join(JSON_RPC_PATH)
+            .map_err(HyperlaneTronError::from)?)
+    }
+
+    /// An ethers provider for the JSON-RPC API of the node, which the
+    /// Ethereum contract bindings can be used with for reads and indexing
+    pub fn rpc_provider(&self) -> ChainResult<Arc<Provider<Http>>> {
+        Ok(Arc::new(Provider::new(Http::new(self.json_rpc_url()?))))
+    }
+
+    /// The configuration of the JSON-RPC API of the node, to build Ethereum
+    /// contract bindings with
+    pub fn ethereum_conf(&self) -> ChainResult<h_eth::ConnectionConf> {
+        Ok(h_eth::ConnectionConf {
+            rpc_connection: RpcConnectionConf::Http {
+                url: self.json_rpc_url()?,
+            },
+            transaction_overrides: Default::default(),
+            operation_batch: self.operation_batch.clone(),
+            quirks: Default::default(),
+            light_client_url: None,
+        })
+    }
+}
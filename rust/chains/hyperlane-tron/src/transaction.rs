@@ -0,0 +1,205 @@
+//! Protobuf types of Tron transactions, to verify the transactions the HTTP
+//! API builds before signing them.
+//!
+//! Only the fields that are checked are declared, the others are skipped
+//! when decoding.
+
+use hyperlane_core::{H160, H256};
+use prost::Message;
+use sha2::{Digest, Sha256};
+
+use crate::HyperlaneTronError;
+
+/// The version byte prefixing account addresses
+const ADDRESS_PREFIX: u8 = 0x41;
+/// The contract type of smart contract calls
+const TRIGGER_SMART_CONTRACT_TYPE: i32 = 31;
+/// Type url of the parameter of smart contract calls
+const TRIGGER_SMART_CONTRACT_TYPE_URL: &str = "type.googleapis.com/protocol.TriggerSmartContract";
+
+/// The raw data of a transaction, which its id is the hash of.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct TransactionRaw {
+    #[prost(message, repeated, tag = "11")]
+    pub contract: Vec<Contract>,
+    #[prost(int64, tag = "18")]
+    pub fee_limit: i64,
+}
+
+/// A contract of a transaction, i.e. the action it performs.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct Contract {
+    #[prost(int32, tag = "1")]
+    pub r#type: i32,
+    #[prost(message, optional, tag = "2")]
+    pub parameter: Option<Any>,
+}
+
+/// A `google.protobuf.Any`.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct Any {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+/// The parameter of smart contract calls.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct TriggerSmartContract {
+    #[prost(bytes = "vec", tag = "1")]
+    pub owner_address: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub contract_address: Vec<u8>,
+    #[prost(int64, tag = "3")]
+    pub call_value: i64,
+    #[prost(bytes = "vec", tag = "4")]
+    pub data: Vec<u8>,
+    #[prost(int64, tag = "5")]
+    pub call_token_value: i64,
+    #[prost(int64, tag = "6")]
+    pub token_id: i64,
+}
+
+/// A signed transaction. The raw data is kept encoded, so that exactly the
+/// signed bytes are broadcast.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct SignedTransaction {
+    #[prost(bytes = "vec", tag = "1")]
+    pub raw_data: Vec<u8>,
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub signature: Vec<Vec<u8>>,
+}
+
+/// A call of `contract` with `data` from the `owner` account, without
+/// transferring any TRX, which burns at most `fee_limit` sun for energy.
+#[derive(Debug)]
+pub(crate) struct ContractCall<'a> {
+    pub owner: &'a H160,
+    pub contract: &'a H160,
+    pub data: &'a [u8],
+    pub fee_limit: u64,
+}
+
+/// An account address, prefixed with its version byte
+pub(crate) fn tron_address(address: &H160) -> Vec<u8> {
+    [&[ADDRESS_PREFIX], address.as_bytes()].concat()
+}
+
+/// The id of a transaction, the sha256 hash of its raw data
+pub(crate) fn transaction_id(raw_data: &[u8]) -> H256 {
+    H256(Sha256::digest(raw_data).into())
+}
+
+/// Checks that `raw_data` is the raw data of a transaction that only
+/// performs `call`.
+pub(crate) fn verify_transaction(
+    raw_data: &[u8],
+    call: &ContractCall,
+) -> Result<(), HyperlaneTronError> {
+    let mismatch = |field: &str| HyperlaneTronError::TransactionMismatch(format!("wrong {field}"));
+
+    let raw = TransactionRaw::decode(raw_data)?;
+    let [contract] = raw.contract.as_slice() else {
+        return Err(HyperlaneTronError::TransactionMismatch(format!(
+            "{} contracts instead of one",
+            raw.contract.len()
+        )));
+    };
+    if contract.r#type != TRIGGER_SMART_CONTRACT_TYPE {
+        return Err(mismatch("contract type"));
+    }
+    let parameter = contract
+        .parameter
+        .as_ref()
+        .filter(|parameter| parameter.type_url == TRIGGER_SMART_CONTRACT_TYPE_URL)
+        .ok_or_else(|| mismatch("contract parameter"))?;
+    let trigger = TriggerSmartContract::decode(parameter.value.as_slice())?;
+
+    if trigger.owner_address != tron_address(call.owner) {
+        return Err(mismatch("owner address"));
+    }
+    if trigger.contract_address != tron_address(call.contract) {
+        return Err(mismatch("contract address"));
+    }
+    if trigger.call_value != 0 || trigger.call_token_value != 0 {
+        return Err(mismatch("call value"));
+    }
+    if trigger.data != call.data {
+        return Err(mismatch("data"));
+    }
+    if u64::try_from(raw.fee_limit).ok() != Some(call.fee_limit) {
+        return Err(mismatch("fee limit"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn raw_data(trigger: &TriggerSmartContract, fee_limit: i64) -> Vec<u8> {
+        TransactionRaw {
+            contract: vec![Contract {
+                r#type: TRIGGER_SMART_CONTRACT_TYPE,
+                parameter: Some(Any {
+                    type_url: TRIGGER_SMART_CONTRACT_TYPE_URL.to_owned(),
+                    value: trigger.encode_to_vec(),
+                }),
+            }],
+            fee_limit,
+        }
+        .encode_to_vec()
+    }
+
+    #[test]
+    fn test_transaction_id() {
+        // The sha256 hash of the empty string
+        assert_eq!(
+            transaction_id(&[]),
+            H256::from_str("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_verify_transaction() {
+        let owner = H160::repeat_byte(0x11);
+        let contract = H160::repeat_byte(0x22);
+        let call = ContractCall {
+            owner: &owner,
+            contract: &contract,
+            data: &[0x7c, 0x39, 0xd1, 0x30],
+            fee_limit: 1_000_000,
+        };
+        let trigger = TriggerSmartContract {
+            owner_address: tron_address(&owner),
+            contract_address: tron_address(&contract),
+            data: call.data.to_vec(),
+            ..Default::default()
+        };
+        assert!(verify_transaction(&raw_data(&trigger, 1_000_000), &call).is_ok());
+
+        let tampered = [
+            TriggerSmartContract {
+                contract_address: tron_address(&owner),
+                ..trigger.clone()
+            },
+            TriggerSmartContract {
+                call_value: 1,
+                ..trigger.clone()
+            },
+            TriggerSmartContract {
+                data: vec![],
+                ..trigger.clone()
+            },
+        ];
+        for trigger in tampered {
+            assert!(verify_transaction(&raw_data(&trigger, 1_000_000), &call).is_err());
+        }
+        assert!(verify_transaction(&raw_data(&trigger, 2_000_000), &call).is_err());
+        assert!(verify_transaction(&TransactionRaw::default().encode_to_vec(), &call).is_err());
+    }
+}
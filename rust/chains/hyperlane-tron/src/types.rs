@@ -0,0 +1,86 @@
+//! The JSON types returned by the Tron HTTP API.
+
+use serde::Deserialize;
+
+use crate::HyperlaneTronError;
+
+/// The status of a call, which the API reports with a success status code.
+/// Also the `POST /wallet/broadcasthex` response.
+#[derive(Deserialize, Debug, Default)]
+pub(crate) struct CallResult {
+    #[serde(default)]
+    pub result: bool,
+    pub code: Option<String>,
+    /// Hex encoded
+    pub message: Option<String>,
+}
+
+impl CallResult {
+    pub(crate) fn into_result(self) -> Result<(), HyperlaneTronError> {
+        if self.result {
+            return Ok(());
+        }
+        let message = self.message.unwrap_or_default();
+        let message = hex::decode(&message)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .unwrap_or(message);
+        Err(HyperlaneTronError::ApiError {
+            code: self.code.unwrap_or_default(),
+            message,
+        })
+    }
+}
+
+/// The `POST /wallet/triggersmartcontract` and
+/// `POST /wallet/triggerconstantcontract` response
+#[derive(Deserialize, Debug)]
+pub(crate) struct TriggerResponse {
+    #[serde(default)]
+    pub result: CallResult,
+    /// The unsigned transaction, which is verified before it is signed
+    pub transaction: Option<UnsignedTransaction>,
+    /// The energy a constant call used
+    #[serde(default)]
+    pub energy_used: u64,
+}
+
+/// A transaction built by the node
+#[derive(Deserialize, Debug)]
+pub(crate) struct UnsignedTransaction {
+    /// The hex encoded id, the sha256 hash of the raw data
+    #[serde(rename = "txID")]
+    pub tx_id: String,
+    /// The hex encoded protobuf of the raw data
+    pub raw_data_hex: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub(crate) struct Receipt {
+    #[serde(default)]
+    pub energy_usage_total: u64,
+    /// `SUCCESS` for successful contract calls, e.g. `REVERT` otherwise
+    pub result: Option<String>,
+}
+
+/// The `POST /wallet/gettransactioninfobyid` response, which is empty until
+/// the transaction is included in a block
+#[derive(Deserialize, Debug)]
+pub(crate) struct TransactionInfo {
+    pub id: Option<String>,
+    #[serde(default)]
+    pub receipt: Receipt,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ChainParameter {
+    pub key: String,
+    pub value: Option<u64>,
+}
+
+/// The `GET /wallet/getchainparameters` response
+#[derive(Deserialize, Debug)]
+pub(crate) struct ChainParameters {
+    #[serde(rename = "chainParameter")]
+    pub chain_parameter: Vec<ChainParameter>,
+}
@@ -2,14 +2,14 @@ use std::{env, fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
 use eyre::Result;
-use hyperlane_core::config::*;
+use hyperlane_core::{config::*, HyperlaneDomain};
 use tracing::info;
 
 use crate::{
     create_chain_metrics,
-    metrics::{create_agent_metrics, AgentMetrics, CoreMetrics},
+    metrics::{create_agent_metrics, AgentMetrics, CoreMetrics, OtlpMetricsExporter},
     settings::Settings,
-    ChainMetrics,
+    AgentHealth, ChainMetrics, HealthServer,
 };
 
 /// Properties shared across all hyperlane agents
@@ -49,6 +49,10 @@ pub trait BaseAgent: Send + Sync + Debug {
     where
         Self: Sized;
 
+    /// The chains the agent connects to, which are checked on startup before
+    /// the agent is instantiated
+    fn preflight_chains(settings: &Self::Settings) -> Vec<HyperlaneDomain>;
+
     /// Start running this agent.
     #[allow(clippy::async_yields_async)]
     async fn run(self);
@@ -75,10 +79,36 @@ pub async fn agent_main<A: BaseAgent>() -> Result<()> {
     let settings = A::Settings::load()?;
     let core_settings: &Settings = settings.as_ref();
 
+    let health = AgentHealth::global();
+    health.pass("config");
+    // Agents open their database when they're instantiated
+    health.expect("db");
+    for chain in A::preflight_chains(&settings) {
+        health.expect(format!("rpc:{}", chain.name()));
+    }
+    if let Some(port) = core_settings.health_port {
+        // Runs for as long as the agent
+        HealthServer::new(port, health).run();
+    }
+
     let metrics = settings.as_ref().metrics(A::AGENT_NAME)?;
-    let tokio_server = core_settings.tracing.start_tracing(&metrics)?;
+    let otlp = core_settings
+        .otlp
+        .as_ref()
+        .map(|conf| conf.exporter(A::AGENT_NAME, &A::preflight_chains(&settings)))
+        .transpose()?;
+    let tokio_server = core_settings
+        .tracing
+        .start_tracing(&metrics, otlp.as_ref())?;
+    if let Some(exporter) = otlp.filter(|exporter| exporter.exports_metrics()) {
+        // Runs for as long as the agent
+        OtlpMetricsExporter::new(exporter, metrics.clone()).spawn();
+    }
     let agent_metrics = create_agent_metrics(&metrics)?;
     let chain_metrics = create_chain_metrics(&metrics)?;
+    core_settings
+        .preflight_check(&A::preflight_chains(&settings), &metrics)
+        .await?;
     let agent = A::from_settings(
         settings,
         metrics.clone(),
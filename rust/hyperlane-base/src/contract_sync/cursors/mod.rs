@@ -1,9 +1,7 @@
-pub(crate) mod sequence_aware;
-
+pub(crate) use hyperlane_core::cursors::ForwardBackwardSequenceAwareSyncCursor;
 use hyperlane_core::{
     Delivery, HyperlaneDomainProtocol, HyperlaneMessage, InterchainGasPayment, MerkleTreeInsertion,
 };
-pub(crate) use sequence_aware::ForwardBackwardSequenceAwareSyncCursor;
 
 pub(crate) mod rate_limited;
 pub(crate) use rate_limited::RateLimitedContractSyncCursor;
@@ -31,9 +29,18 @@ impl Indexable for HyperlaneMessage {
     fn indexing_cursor(domain: HyperlaneDomainProtocol) -> CursorType {
         match domain {
             HyperlaneDomainProtocol::Ethereum => CursorType::SequenceAware,
-            HyperlaneDomainProtocol::Fuel => todo!(),
+            HyperlaneDomainProtocol::Fuel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Cosmos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Starknet => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Aptos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Sui => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Ton => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Substrate => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Tron => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Stellar => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Near => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Algorand => CursorType::SequenceAware,
         }
     }
 
@@ -47,9 +54,18 @@ impl Indexable for InterchainGasPayment {
     fn indexing_cursor(domain: HyperlaneDomainProtocol) -> CursorType {
         match domain {
             HyperlaneDomainProtocol::Ethereum => CursorType::RateLimited,
-            HyperlaneDomainProtocol::Fuel => todo!(),
+            HyperlaneDomainProtocol::Fuel => CursorType::RateLimited,
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Cosmos => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Starknet => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Aptos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Sui => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Ton => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Substrate => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Tron => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Stellar => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Near => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Algorand => CursorType::RateLimited,
         }
     }
 }
@@ -58,9 +74,18 @@ impl Indexable for MerkleTreeInsertion {
     fn indexing_cursor(domain: HyperlaneDomainProtocol) -> CursorType {
         match domain {
             HyperlaneDomainProtocol::Ethereum => CursorType::SequenceAware,
-            HyperlaneDomainProtocol::Fuel => todo!(),
+            HyperlaneDomainProtocol::Fuel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Cosmos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Starknet => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Aptos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Sui => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Ton => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Substrate => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Tron => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Stellar => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Near => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Algorand => CursorType::SequenceAware,
         }
     }
 }
@@ -69,9 +94,18 @@ impl Indexable for Delivery {
     fn indexing_cursor(domain: HyperlaneDomainProtocol) -> CursorType {
         match domain {
             HyperlaneDomainProtocol::Ethereum => CursorType::RateLimited,
-            HyperlaneDomainProtocol::Fuel => todo!(),
+            HyperlaneDomainProtocol::Fuel => CursorType::RateLimited,
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Cosmos => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Starknet => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Aptos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Sui => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Ton => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Substrate => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Tron => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Stellar => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Near => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Algorand => CursorType::RateLimited,
         }
     }
 }
@@ -10,6 +10,7 @@ use derive_new::new;
 use eyre::Result;
 use hyperlane_core::{
     ContractSyncCursor, CursorAction, HyperlaneWatermarkedLogStore, Indexed, Indexer, LogMeta,
+    ReorgAwareCursor,
 };
 
 use crate::contract_sync::eta_calculator::SyncerEtaCalculator;
@@ -64,6 +65,21 @@ impl SyncState {
             }
         }
     }
+
+    /// Makes the blocks from `block` onward be indexed again
+    fn rewind_to(&mut self, block: u32) {
+        match self.direction {
+            SyncDirection::Forward => {
+                self.next_block = u32::min(self.next_block, u32::max(block, self.start_block));
+            }
+            SyncDirection::Backward => {
+                // Only the blocks up to the start block are indexed
+                if block <= self.start_block {
+                    self.next_block = self.start_block;
+                }
+            }
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -216,6 +232,12 @@ where
     }
 }
 
+impl<T> ReorgAwareCursor for RateLimitedContractSyncCursor<T> {
+    fn invalidate_range(&mut self, range: RangeInclusive<u32>) {
+        self.sync_state.rewind_to(*range.start());
+    }
+}
+
 impl<T> Debug for RateLimitedContractSyncCursor<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RateLimitedContractSyncCursor")
@@ -333,6 +355,20 @@ pub(crate) mod test {
         assert!(matches!(action_3, CursorAction::Query(_expected_range)));
     }
 
+    #[tokio::test]
+    async fn test_rewinds_for_reorgs() {
+        let mut cursor = mock_rate_limited_cursor(None).await;
+        cursor.update(vec![], 0..=10).await.unwrap();
+        cursor.update(vec![], 11..=20).await.unwrap();
+
+        cursor.rewind_to_height(15);
+        assert_eq!(cursor.sync_state.next_block, 15);
+
+        // Blocks that weren't indexed yet being reorged doesn't move the cursor
+        cursor.rewind_to_height(30);
+        assert_eq!(cursor.sync_state.next_block, 15);
+    }
+
     #[tokio::test]
     async fn test_next_action_sleeps_if_tip_is_not_updated() {
         let chain_tips = vec![10];
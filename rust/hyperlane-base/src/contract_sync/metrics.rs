@@ -20,6 +20,13 @@ pub struct ContractSyncMetrics {
 
     /// See `last_known_message_nonce` in CoreMetrics.
     pub message_nonce: IntGaugeVec,
+
+    /// Sequences the cursors skipped that weren't backfilled yet
+    ///
+    /// Labels:
+    /// - `data_type`: the data the indexer is recording. E.g. `messages` or `gas_payments`.
+    /// - `chain`: Chain the indexer is collecting data from.
+    pub sequence_gaps: IntGaugeVec,
 }
 
 impl ContractSyncMetrics {
@@ -43,10 +50,19 @@ impl ContractSyncMetrics {
 
         let message_nonce = metrics.last_known_message_nonce();
 
+        let sequence_gaps = metrics
+            .new_int_gauge(
+                "contract_sync_sequence_gaps",
+                "Number of skipped sequences that weren't backfilled yet",
+                &["data_type", "chain"],
+            )
+            .expect("failed to register sequence_gaps metric");
+
         ContractSyncMetrics {
             indexed_height,
             stored_events,
             message_nonce,
+            sequence_gaps,
         }
     }
 }
@@ -1,14 +1,26 @@
 use std::{
-    collections::HashSet, fmt::Debug, hash::Hash, marker::PhantomData, sync::Arc, time::Duration,
+    collections::HashSet,
+    fmt::Debug,
+    hash::Hash,
+    marker::PhantomData,
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
 use axum::async_trait;
 use cursors::*;
 use derive_new::new;
+use futures_util::StreamExt;
 use hyperlane_core::{
-    utils::fmt_sync_time, ContractSyncCursor, CursorAction, HyperlaneDomain, HyperlaneLogStore,
+    cursors::{SequenceGap, SequenceGaps},
+    utils::fmt_sync_time,
+    ContractSyncCursor, CursorAction, HyperlaneDomain, HyperlaneLogStore,
     HyperlaneSequenceAwareIndexerStore, HyperlaneWatermarkedLogStore, Indexer,
-    SequenceAwareIndexer,
+    SequenceAwareIndexer, StreamingIndexer,
 };
 use hyperlane_core::{Indexed, LogMeta, H512};
 pub use metrics::ContractSyncMetrics;
@@ -18,7 +30,7 @@ use tokio::sync::broadcast::{Receiver as BroadcastReceiver, Sender as BroadcastS
 use tokio::time::sleep;
 use tracing::{debug, info, instrument, trace, warn};
 
-use crate::settings::IndexSettings;
+use crate::{settings::IndexSettings, AgentHealth};
 
 pub(crate) mod cursors;
 mod eta_calculator;
@@ -28,6 +40,13 @@ use cursors::ForwardBackwardSequenceAwareSyncCursor;
 
 const SLEEP_DURATION: Duration = Duration::from_secs(5);
 
+/// How long to wait between attempts to backfill the skipped sequences
+const BACKFILL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long an iteration of the sync loop may take before the agent is
+/// reported unhealthy, long enough for slow RPCs and their retries
+const SYNC_WATCHDOG_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
 /// Entity that drives the syncing of an agent's db with on-chain data.
 /// Extracts chain-specific data (emitted checkpoints, messages, etc) from an
 /// `indexer` and fills the agent's db with this data.
@@ -36,6 +55,20 @@ pub struct ContractSync<T: Indexable, D: HyperlaneLogStore<T>, I: Indexer<T>> {
     domain: HyperlaneDomain,
     db: D,
     indexer: I,
+    /// The indexer to backfill skipped sequences with, if it differs from
+    /// `indexer`
+    backfill_indexer: Option<I>,
+    /// How many blocks behind the tip ranges have to be to be queried with
+    /// `backfill_indexer` instead of `indexer`
+    archive_lag: Option<u32>,
+    /// The last known tip, to tell how far behind the queried ranges are
+    archive_tip: AtomicU32,
+    /// Whether the cursor caught up with the tip and stopped using
+    /// `backfill_indexer`
+    caught_up: AtomicBool,
+    /// The sequences the cursor skipped, which are backfilled in the
+    /// background
+    gaps: Arc<SequenceGaps>,
     metrics: ContractSyncMetrics,
     broadcast_sender: Option<BroadcastSender<H512>>,
     _phantom: PhantomData<T>,
@@ -48,11 +81,30 @@ impl<T: Indexable, D: HyperlaneLogStore<T>, I: Indexer<T>> ContractSync<T, D, I>
             domain,
             db,
             indexer,
+            backfill_indexer: None,
+            archive_lag: None,
+            archive_tip: AtomicU32::new(0),
+            caught_up: AtomicBool::new(false),
+            gaps: Default::default(),
             metrics,
             broadcast_sender: T::broadcast_channel_size().map(BroadcastSender::new),
             _phantom: PhantomData,
         }
     }
+
+    /// Backfill the skipped sequences with `indexer`, e.g. one connected to
+    /// archival RPCs
+    pub fn with_backfill_indexer(mut self, indexer: I) -> Self {
+        self.backfill_indexer = Some(indexer);
+        self
+    }
+
+    /// Query the ranges that are more than `lag` blocks behind the tip with
+    /// the backfill indexer, until the cursor catches up
+    pub fn with_archive_switchover(mut self, lag: u32) -> Self {
+        self.archive_lag = Some(lag);
+        self
+    }
 }
 
 impl<T, D, I> ContractSync<T, D, I>
@@ -82,8 +134,11 @@ where
             .metrics
             .stored_events
             .with_label_values(&[label, chain_name]);
+        let watchdog =
+            AgentHealth::global().watchdog(format!("{label}:{chain_name}"), SYNC_WATCHDOG_TIMEOUT);
 
         loop {
+            watchdog.feed();
             if let Some(rx) = opts.tx_id_receiver.as_mut() {
                 self.fetch_logs_from_receiver(rx, &stored_logs_metric).await;
             }
@@ -154,7 +209,8 @@ where
             CursorAction::Query(range) => loop {
                 debug!(?range, "Looking for events in index range");
 
-                let logs = match self.indexer.fetch_logs_in_range(range.clone()).await {
+                let indexer = self.range_indexer(&range).await;
+                let logs = match indexer.fetch_logs_in_range(range.clone()).await {
                     Ok(logs) => logs,
                     Err(err) => {
                         warn!(?err, ?range, "Error fetching logs in range");
@@ -193,6 +249,42 @@ where
         sleep(sleep_duration).await
     }
 
+    /// The indexer to query `range` with, which is the backfill indexer while
+    /// the range is far behind the tip
+    async fn range_indexer(&self, range: &RangeInclusive<u32>) -> &I {
+        let (Some(lag), Some(archive)) = (self.archive_lag, &self.backfill_indexer) else {
+            return &self.indexer;
+        };
+        if self.caught_up.load(Ordering::Relaxed) {
+            return &self.indexer;
+        }
+        let behind = |tip: u32| range.end().saturating_add(lag) < tip;
+        if behind(self.archive_tip.load(Ordering::Relaxed)) {
+            return archive;
+        }
+        // The tip only grows, so it's only fetched again once the cursor
+        // caught up with the last known one
+        let tip = match self.indexer.get_finalized_block_number().await {
+            Ok(tip) => tip,
+            Err(err) => {
+                warn!(?err, "Error fetching tip to choose indexer");
+                return &self.indexer;
+            }
+        };
+        self.archive_tip.store(tip, Ordering::Relaxed);
+        if behind(tip) {
+            return archive;
+        }
+        // A cursor that follows the tip doesn't fall behind by `lag` blocks
+        // again, so the tip isn't fetched anymore
+        self.caught_up.store(true, Ordering::Relaxed);
+        info!(
+            tip,
+            lag, "Caught up with the tip, switching from the archival indexer"
+        );
+        &self.indexer
+    }
+
     async fn dedupe_and_store_logs(
         &self,
         logs: Vec<(Indexed<T>, LogMeta)>,
@@ -221,6 +313,131 @@ where
         stored_logs_metric.inc_by(stored as u64);
         logs
     }
+
+    /// Periodically index the ranges of the sequences the cursor skipped,
+    /// until the skipped sequences are found
+    #[instrument(name = "ContractSyncBackfill", fields(domain=self.domain().name()), skip(self))]
+    pub async fn backfill_gaps(&self, label: &'static str) {
+        let chain_name = self.domain.as_ref();
+        let stored_logs_metric = self
+            .metrics
+            .stored_events
+            .with_label_values(&[label, chain_name]);
+        let gaps_metric = self
+            .metrics
+            .sequence_gaps
+            .with_label_values(&[label, chain_name]);
+        let indexer = self.backfill_indexer.as_ref().unwrap_or(&self.indexer);
+
+        loop {
+            let gaps = self.gaps.pending();
+            gaps_metric.set(gaps.len() as i64);
+            for (sequence, gap) in gaps {
+                if self
+                    .backfill_gap(indexer, sequence, gap.clone(), &stored_logs_metric)
+                    .await
+                {
+                    info!(sequence, "Backfilled skipped sequence");
+                    self.gaps.resolve(sequence);
+                } else {
+                    warn!(sequence, range = ?gap.range, "Skipped sequence is still missing");
+                }
+            }
+            gaps_metric.set(self.gaps.pending().len() as i64);
+            sleep(BACKFILL_INTERVAL).await;
+        }
+    }
+
+    /// Index the range of `gap` in chunks, until the log with `sequence` is
+    /// found. Returns whether it was found.
+    async fn backfill_gap(
+        &self,
+        indexer: &I,
+        sequence: u32,
+        gap: SequenceGap,
+        stored_logs_metric: &GenericCounter<AtomicU64>,
+    ) -> bool {
+        let chunk_size = gap.chunk_size.max(1);
+        let mut from = *gap.range.start();
+        while from <= *gap.range.end() {
+            let range = from..=from.saturating_add(chunk_size - 1).min(*gap.range.end());
+            let logs = match indexer.fetch_logs_in_range(range.clone()).await {
+                Ok(logs) => logs,
+                Err(err) => {
+                    warn!(?err, sequence, ?range, "Error backfilling skipped sequence");
+                    return false;
+                }
+            };
+            let logs = self.dedupe_and_store_logs(logs, stored_logs_metric).await;
+            if logs.iter().any(|(log, _)| log.sequence == Some(sequence)) {
+                return true;
+            }
+            let Some(next) = range.end().checked_add(1) else {
+                break;
+            };
+            from = next;
+        }
+        false
+    }
+}
+
+impl<T, D, I> ContractSync<T, D, I>
+where
+    T: Indexable + Debug + Send + Sync + Clone + Eq + Hash + 'static,
+    D: HyperlaneLogStore<T>,
+    I: Indexer<T> + StreamingIndexer<T> + 'static,
+{
+    /// Sync the logs pushed by the chain from block `from` onwards and write
+    /// them to the LogStore. The stream is resumed from the block of the
+    /// last stored log whenever it ends or fails, which may stream some logs
+    /// again.
+    #[instrument(name = "StreamingContractSync", fields(domain=self.domain().name()), skip(self))]
+    pub async fn sync_stream(&self, label: &'static str, mut from: u32) {
+        let chain_name = self.domain.as_ref();
+        let indexed_height_metric = self
+            .metrics
+            .indexed_height
+            .with_label_values(&[label, chain_name]);
+        let stored_logs_metric = self
+            .metrics
+            .stored_events
+            .with_label_values(&[label, chain_name]);
+
+        loop {
+            let mut stream = match self.indexer.stream_logs(from).await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    warn!(?err, from, "Error subscribing to logs");
+                    sleep(SLEEP_DURATION).await;
+                    continue;
+                }
+            };
+            while let Some(log) = stream.next().await {
+                let (log, meta) = match log {
+                    Ok(log) => log,
+                    Err(err) => {
+                        warn!(?err, from, "Error streaming logs");
+                        break;
+                    }
+                };
+                let block_number = meta.block_number;
+                let logs = self
+                    .dedupe_and_store_logs(vec![(log, meta)], &stored_logs_metric)
+                    .await;
+                if let Some(tx) = self.broadcast_sender.as_ref() {
+                    logs.iter().for_each(|(_, meta)| {
+                        if let Err(err) = tx.send(meta.transaction_id) {
+                            trace!(?err, "Error sending txid to receiver");
+                        }
+                    });
+                }
+                from = block_number.try_into().unwrap_or(from);
+                indexed_height_metric.set(from as i64);
+            }
+            debug!(from, "Log stream ended, resuming it");
+            sleep(SLEEP_DURATION).await;
+        }
+    }
 }
 
 /// A ContractSync for syncing events using a SequenceAwareIndexer
@@ -239,6 +456,16 @@ pub trait ContractSyncer<T>: Send + Sync {
     /// Returns a new cursor to be used for syncing events from the indexer
     async fn cursor(&self, index_settings: IndexSettings) -> Box<dyn ContractSyncCursor<T>>;
 
+    /// Returns a new cursor that skips the sequences it can't index, if
+    /// `skip_gaps_after_rewinds` is set. The skipped sequences are only
+    /// indexed if `backfill_gaps` runs alongside the sync.
+    async fn cursor_skipping_gaps(
+        &self,
+        index_settings: IndexSettings,
+    ) -> Box<dyn ContractSyncCursor<T>> {
+        self.cursor(index_settings).await
+    }
+
     /// Syncs events from the indexer using the provided cursor
     async fn sync(&self, label: &'static str, opts: SyncOptions<T>);
 
@@ -247,6 +474,15 @@ pub trait ContractSyncer<T>: Send + Sync {
 
     /// If this syncer is also a broadcaster, return the channel to receive txids
     fn get_broadcaster(&self) -> Option<BroadcastSender<H512>>;
+
+    /// Backfills the sequences that the cursor skipped. Doesn't return for
+    /// syncers whose cursors skip sequences.
+    async fn backfill_gaps(&self, _label: &'static str) {}
+
+    /// The sequences that the cursor skipped and that weren't backfilled yet
+    fn sequence_gaps(&self) -> Option<Arc<SequenceGaps>> {
+        None
+    }
 }
 
 #[derive(new)]
@@ -278,8 +514,7 @@ where
         let watermark = self.db.retrieve_high_watermark().await.unwrap();
         let index_settings = IndexSettings {
             from: watermark.unwrap_or(index_settings.from),
-            chunk_size: index_settings.chunk_size,
-            mode: index_settings.mode,
+            ..index_settings
         };
         Box::new(
             RateLimitedContractSyncCursor::new(
@@ -313,6 +548,25 @@ pub type SequenceAwareLogStore<T> = Arc<dyn HyperlaneSequenceAwareIndexerStore<T
 pub type SequencedDataContractSync<T> =
     SequenceAwareContractSync<T, Arc<dyn HyperlaneSequenceAwareIndexerStore<T>>>;
 
+impl<T> SequencedDataContractSync<T>
+where
+    T: Indexable + Send + Sync + Debug + Clone + Eq + Hash + 'static,
+{
+    async fn sequence_aware_cursor(
+        &self,
+        index_settings: &IndexSettings,
+    ) -> ForwardBackwardSequenceAwareSyncCursor<T> {
+        ForwardBackwardSequenceAwareSyncCursor::new(
+            self.indexer.clone(),
+            Arc::new(self.db.clone()),
+            index_settings.chunk_size,
+            index_settings.mode,
+        )
+        .await
+        .unwrap()
+    }
+}
+
 #[async_trait]
 impl<T> ContractSyncer<T> for SequencedDataContractSync<T>
 where
@@ -320,22 +574,32 @@ where
 {
     /// Returns a new cursor to be used for syncing dispatched messages from the indexer
     async fn cursor(&self, index_settings: IndexSettings) -> Box<dyn ContractSyncCursor<T>> {
-        Box::new(
-            ForwardBackwardSequenceAwareSyncCursor::new(
-                self.indexer.clone(),
-                Arc::new(self.db.clone()),
-                index_settings.chunk_size,
-                index_settings.mode,
-            )
-            .await
-            .unwrap(),
-        )
+        Box::new(self.sequence_aware_cursor(&index_settings).await)
+    }
+
+    async fn cursor_skipping_gaps(
+        &self,
+        index_settings: IndexSettings,
+    ) -> Box<dyn ContractSyncCursor<T>> {
+        let cursor = self.sequence_aware_cursor(&index_settings).await;
+        match index_settings.skip_gaps_after_rewinds {
+            Some(max_rewinds) => Box::new(cursor.with_gap_skipping(max_rewinds, self.gaps.clone())),
+            None => Box::new(cursor),
+        }
     }
 
     async fn sync(&self, label: &'static str, opts: SyncOptions<T>) {
         ContractSync::sync(self, label, opts).await;
     }
 
+    async fn backfill_gaps(&self, label: &'static str) {
+        ContractSync::backfill_gaps(self, label).await
+    }
+
+    fn sequence_gaps(&self) -> Option<Arc<SequenceGaps>> {
+        Some(self.gaps.clone())
+    }
+
     fn domain(&self) -> &HyperlaneDomain {
         ContractSync::domain(self)
     }
@@ -1,2 +1,4 @@
 pub use rocks::*;
+pub use store::*;
 mod rocks;
+mod store;
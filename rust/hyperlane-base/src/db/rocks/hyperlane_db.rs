@@ -1,19 +1,20 @@
 use async_trait::async_trait;
 use eyre::{bail, Result};
 use paste::paste;
-use tracing::{debug, instrument, trace};
+use tracing::{debug, info_span, instrument, trace};
 
 use hyperlane_core::{
     GasPaymentKey, HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage,
     HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore, Indexed,
     InterchainGasExpenditure, InterchainGasPayment, InterchainGasPaymentMeta, LogMeta,
-    MerkleTreeInsertion, H256,
+    MerkleTreeInsertion, MessageProfit, PendingMessageState, SignedDeliveryReceipt, H256,
 };
 
 use super::{
     storage_types::{InterchainGasExpenditureData, InterchainGasPaymentData},
     DbError, TypedDB, DB,
 };
+use crate::settings::link_to_message;
 
 // these keys MUST not be given multiple uses in case multiple agents are
 // started with the same database and domain.
@@ -34,6 +35,13 @@ const MERKLE_LEAF_INDEX_BY_MESSAGE_ID: &str = "merkle_leaf_index_by_message_id_"
 const MERKLE_TREE_INSERTION_BLOCK_NUMBER_BY_LEAF_INDEX: &str =
     "merkle_tree_insertion_block_number_by_leaf_index_";
 const LATEST_INDEXED_GAS_PAYMENT_BLOCK: &str = "latest_indexed_gas_payment_block";
+const DELIVERY_RECEIPT_BY_MESSAGE_ID: &str = "delivery_receipt_by_message_id_";
+const DEAD_LETTERED_BY_MESSAGE_ID: &str = "dead_lettered_by_message_id_";
+const MESSAGE_PROFIT_BY_NONCE: &str = "message_profit_by_nonce_";
+const PENDING_MESSAGE_STATE_BY_MESSAGE_ID: &str = "pending_message_state_by_message_id_";
+const SIGNED_CHECKPOINT_ROOT_BY_INDEX: &str = "signed_checkpoint_root_by_index_";
+const HIGHEST_SIGNED_CHECKPOINT_INDEX: &str = "highest_signed_checkpoint_index_";
+const LOWEST_RETAINED_CHECKPOINT_INDEX: &str = "lowest_retained_checkpoint_index_";
 
 /// Rocks DB result type
 pub type DbResult<T> = std::result::Result<T, DbError>;
@@ -90,6 +98,17 @@ impl HyperlaneRocksDB {
         }
 
         let id = message.id();
+        // The first span in the trace of the message
+        let span = info_span!(
+            "index_message",
+            id = ?id,
+            origin = message.origin,
+            destination = message.destination,
+            nonce = message.nonce,
+            block = dispatched_block_number
+        );
+        link_to_message(&span, id);
+        let _entered = span.enter();
         debug!(msg=?message,  "Storing new message in db",);
 
         // - `id` --> `message`
@@ -112,6 +131,30 @@ impl HyperlaneRocksDB {
         }
     }
 
+    /// Delete a message and what's stored about it by its nonce, so that
+    /// it's indexed and processed again
+    pub fn invalidate_message(&self, nonce: u32) -> DbResult<()> {
+        // The id may be what's corrupted
+        if let Ok(Some(id)) = self.retrieve_message_id_by_nonce(&nonce) {
+            self.delete_keyed(MESSAGE, &id)?;
+        }
+        self.delete_keyed(MESSAGE_ID, &nonce)?;
+        self.delete_keyed(MESSAGE_DISPATCHED_BLOCK_NUMBER, &nonce)?;
+        self.delete_keyed(NONCE_PROCESSED, &nonce)?;
+        Ok(())
+    }
+
+    /// Set the nonce of the highest message we're aware of, e.g. after
+    /// invalidating the ones above it. `None` if there's no message.
+    pub fn reset_highest_seen_message_nonce(&self, nonce: Option<u32>) -> DbResult<()> {
+        match nonce {
+            Some(nonce) => {
+                self.store_highest_seen_message_nonce_number(&Default::default(), &nonce)
+            }
+            None => self.delete_keyed(HIGHEST_SEEN_MESSAGE_NONCE, &bool::default()),
+        }
+    }
+
     /// Update the nonce of the highest processed message we're aware of
     pub fn try_update_max_seen_message_nonce(&self, nonce: u32) -> DbResult<()> {
         let current_max = self
@@ -128,6 +171,32 @@ impl HyperlaneRocksDB {
         self.retrieve_highest_seen_message_nonce_number(&Default::default())
     }
 
+    /// Update the index of the highest checkpoint the validator signed
+    pub fn try_update_highest_signed_checkpoint_index(&self, index: u32) -> DbResult<()> {
+        let current_max = self.retrieve_highest_signed_checkpoint_index()?;
+        if current_max.map_or(true, |current_max| index > current_max) {
+            self.store_highest_signed_checkpoint_index_number(&Default::default(), &index)?;
+        }
+        Ok(())
+    }
+
+    /// Retrieve the index of the highest checkpoint the validator signed
+    pub fn retrieve_highest_signed_checkpoint_index(&self) -> DbResult<Option<u32>> {
+        self.retrieve_highest_signed_checkpoint_index_number(&Default::default())
+    }
+
+    /// Store the index of the lowest checkpoint that wasn't pruned from the
+    /// validator's checkpoint syncers
+    pub fn store_lowest_retained_checkpoint_index(&self, index: u32) -> DbResult<()> {
+        self.store_lowest_retained_checkpoint_index_number(&Default::default(), &index)
+    }
+
+    /// Retrieve the index of the lowest checkpoint that wasn't pruned from
+    /// the validator's checkpoint syncers
+    pub fn retrieve_lowest_retained_checkpoint_index(&self) -> DbResult<Option<u32>> {
+        self.retrieve_lowest_retained_checkpoint_index_number(&Default::default())
+    }
+
     /// If the provided gas payment, identified by its metadata, has not been
     /// processed, processes the gas payment and records it as processed.
     /// Returns whether the gas payment was processed for the first time.
@@ -218,6 +287,28 @@ impl HyperlaneRocksDB {
         Ok(true)
     }
 
+    /// Delete a merkle tree insertion by its leaf index, so that it's indexed
+    /// again
+    pub fn invalidate_merkle_tree_insertion(&self, leaf_index: u32) -> DbResult<()> {
+        if let Ok(Some(insertion)) = self.retrieve_merkle_tree_insertion_by_leaf_index(&leaf_index)
+        {
+            // The message may have been inserted again at another leaf
+            if let Ok(Some(index)) =
+                self.retrieve_merkle_leaf_index_by_message_id(&insertion.message_id())
+            {
+                if index == leaf_index {
+                    self.delete_keyed(MERKLE_LEAF_INDEX_BY_MESSAGE_ID, &insertion.message_id())?;
+                }
+            }
+        }
+        self.delete_keyed(MERKLE_TREE_INSERTION, &leaf_index)?;
+        self.delete_keyed(
+            MERKLE_TREE_INSERTION_BLOCK_NUMBER_BY_LEAF_INDEX,
+            &leaf_index,
+        )?;
+        Ok(())
+    }
+
     /// Processes the gas expenditure and store the total expenditure for the
     /// message.
     pub fn process_gas_expenditure(&self, expenditure: InterchainGasExpenditure) -> DbResult<()> {
@@ -532,6 +623,56 @@ make_store_and_retrieve!(
     u32,
     u64
 );
+make_store_and_retrieve!(
+    pub,
+    delivery_receipt_by_message_id,
+    DELIVERY_RECEIPT_BY_MESSAGE_ID,
+    H256,
+    SignedDeliveryReceipt
+);
+make_store_and_retrieve!(
+    pub,
+    dead_lettered_by_message_id,
+    DEAD_LETTERED_BY_MESSAGE_ID,
+    H256,
+    bool
+);
+make_store_and_retrieve!(
+    pub,
+    message_profit_by_nonce,
+    MESSAGE_PROFIT_BY_NONCE,
+    u32,
+    MessageProfit
+);
+make_store_and_retrieve!(
+    pub,
+    pending_message_state_by_message_id,
+    PENDING_MESSAGE_STATE_BY_MESSAGE_ID,
+    H256,
+    PendingMessageState
+);
 // There's no unit struct Encode/Decode impl, so just use `bool`, have visibility be private (by omitting the first argument), and wrap
 // with a function that always uses the `Default::default()` key
 make_store_and_retrieve!(, highest_seen_message_nonce_number, HIGHEST_SEEN_MESSAGE_NONCE, bool, u32);
+make_store_and_retrieve!(
+    pub,
+    signed_checkpoint_root_by_index,
+    SIGNED_CHECKPOINT_ROOT_BY_INDEX,
+    u32,
+    H256
+);
+// Like the highest seen message nonce, wrapped with functions that always use the `Default::default()` key
+make_store_and_retrieve!(
+    ,
+    highest_signed_checkpoint_index_number,
+    HIGHEST_SIGNED_CHECKPOINT_INDEX,
+    bool,
+    u32
+);
+make_store_and_retrieve!(
+    ,
+    lowest_retained_checkpoint_index_number,
+    LOWEST_RETAINED_CHECKPOINT_INDEX,
+    bool,
+    u32
+);
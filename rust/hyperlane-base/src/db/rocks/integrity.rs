@@ -0,0 +1,327 @@
+use eyre::{eyre, Result};
+use hyperlane_core::{HyperlaneMessage, MerkleTreeInsertion, SequenceAwareIndexer};
+use tracing::{info, warn};
+
+use super::{DbError, DbResult, HyperlaneRocksDB};
+
+/// How many of the most recent sequences are verified by default
+pub const DEFAULT_INTEGRITY_CHECK_DEPTH: u32 = 10_000;
+
+/// Config of the integrity check of the database on startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegrityCheckConf {
+    /// How many sequences below the on-chain count are verified. The ones at
+    /// or above it are always checked.
+    pub depth: u32,
+    /// Whether invalid entries are deleted, so that the contract syncs index
+    /// them again, rather than only reported
+    pub repair: bool,
+}
+
+impl Default for IntegrityCheckConf {
+    fn default() -> Self {
+        Self {
+            depth: DEFAULT_INTEGRITY_CHECK_DEPTH,
+            repair: false,
+        }
+    }
+}
+
+/// The invalid sequences an integrity check found for one kind of log
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Sequences at or above the on-chain count, e.g. indexed from another
+    /// deployment or from blocks that were reorged out
+    pub stale: Vec<u32>,
+    /// Sequences whose entries don't decode or don't match the sequence
+    /// they're stored under
+    pub corrupted: Vec<u32>,
+    /// Whether the highest seen sequence is at or above the on-chain count
+    pub stale_highest_seen: bool,
+}
+
+impl IntegrityReport {
+    /// Whether nothing invalid was found
+    pub fn is_clean(&self) -> bool {
+        self.stale.is_empty() && self.corrupted.is_empty() && !self.stale_highest_seen
+    }
+
+    /// Every invalid sequence
+    pub fn invalid(&self) -> impl Iterator<Item = u32> + '_ {
+        self.stale.iter().chain(&self.corrupted).copied()
+    }
+}
+
+/// Whether a retrieved entry is intact: `None` if it's missing, `Some(false)`
+/// if it doesn't decode or `valid` rejects it
+fn intact<T>(
+    retrieved: DbResult<Option<T>>,
+    valid: impl FnOnce(T) -> bool,
+) -> DbResult<Option<bool>> {
+    match retrieved {
+        Ok(None) => Ok(None),
+        Ok(Some(value)) => Ok(Some(valid(value))),
+        Err(DbError::HyperlaneError(_)) => Ok(Some(false)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn onchain_count<T>(indexer: &dyn SequenceAwareIndexer<T>, logs: &str) -> Result<u32> {
+    let (count, _) = indexer.latest_sequence_count_and_tip().await?;
+    // Comparing against a count of 0 would invalidate everything
+    count.ok_or_else(|| eyre!("Failed to query the on-chain count of {logs}"))
+}
+
+impl HyperlaneRocksDB {
+    /// Cross-checks the stored messages against the on-chain message count,
+    /// verifying the `depth` most recent ones and any above the count
+    pub fn check_messages(&self, onchain_count: u32, depth: u32) -> DbResult<IntegrityReport> {
+        let mut report = IntegrityReport::default();
+        let highest_seen = match self.retrieve_highest_seen_message_nonce() {
+            Ok(highest_seen) => highest_seen,
+            Err(DbError::HyperlaneError(_)) => {
+                report.stale_highest_seen = true;
+                None
+            }
+            Err(err) => return Err(err),
+        };
+        let Some(highest_seen) = highest_seen else {
+            return Ok(report);
+        };
+        report.stale_highest_seen |= highest_seen >= onchain_count;
+
+        for nonce in onchain_count..=highest_seen {
+            if intact(self.retrieve_message_id_by_nonce(&nonce), |_| true)?.is_some() {
+                report.stale.push(nonce);
+            }
+        }
+
+        let end = onchain_count.min(highest_seen.saturating_add(1));
+        for nonce in onchain_count.saturating_sub(depth)..end {
+            let id = match self.retrieve_message_id_by_nonce(&nonce) {
+                Ok(Some(id)) => id,
+                // Not indexed yet
+                Ok(None) => continue,
+                Err(DbError::HyperlaneError(_)) => {
+                    report.corrupted.push(nonce);
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            let valid = intact(self.retrieve_message_by_nonce(nonce), |message| {
+                message.nonce == nonce && message.id() == id && message.origin == self.domain().id()
+            })?;
+            // Also invalid if the id is stored without the message it identifies
+            if valid != Some(true) {
+                report.corrupted.push(nonce);
+            }
+        }
+        Ok(report)
+    }
+
+    /// Invalidates the messages in `report`, so that they're indexed again
+    pub fn repair_messages(&self, report: &IntegrityReport, onchain_count: u32) -> DbResult<()> {
+        for nonce in report.invalid() {
+            self.invalidate_message(nonce)?;
+        }
+        if report.stale_highest_seen {
+            self.reset_highest_seen_message_nonce(onchain_count.checked_sub(1))?;
+        }
+        Ok(())
+    }
+
+    /// Cross-checks the stored merkle tree insertions against the on-chain
+    /// tree size, verifying the `depth` most recent ones and any above it
+    pub fn check_merkle_tree_insertions(
+        &self,
+        onchain_count: u32,
+        depth: u32,
+    ) -> DbResult<IntegrityReport> {
+        let mut report = IntegrityReport::default();
+        // Insertions are indexed without gaps, so the stale ones end at the
+        // first missing leaf
+        let mut leaf_index = onchain_count;
+        while intact(
+            self.retrieve_merkle_tree_insertion_by_leaf_index(&leaf_index),
+            |_| true,
+        )?
+        .is_some()
+        {
+            report.stale.push(leaf_index);
+            leaf_index += 1;
+        }
+
+        for leaf_index in onchain_count.saturating_sub(depth)..onchain_count {
+            let valid = intact(
+                self.retrieve_merkle_tree_insertion_by_leaf_index(&leaf_index),
+                |insertion| insertion.index() == leaf_index,
+            )?;
+            if valid == Some(false) {
+                report.corrupted.push(leaf_index);
+            }
+        }
+        Ok(report)
+    }
+
+    /// Invalidates the merkle tree insertions in `report`, so that they're
+    /// indexed again
+    pub fn repair_merkle_tree_insertions(&self, report: &IntegrityReport) -> DbResult<()> {
+        for leaf_index in report.invalid() {
+            self.invalidate_merkle_tree_insertion(leaf_index)?;
+        }
+        Ok(())
+    }
+
+    /// Checks the stored messages and merkle tree insertions against the
+    /// on-chain counts the indexers report and, if `conf.repair` is set,
+    /// invalidates the invalid ones. Must run before the contract syncs
+    /// start, whose cursors then index the invalidated ranges again.
+    pub async fn verify_against_chain(
+        &self,
+        conf: &IntegrityCheckConf,
+        messages: Option<&dyn SequenceAwareIndexer<HyperlaneMessage>>,
+        insertions: Option<&dyn SequenceAwareIndexer<MerkleTreeInsertion>>,
+    ) -> Result<()> {
+        if let Some(indexer) = messages {
+            let onchain_count = onchain_count(indexer, "messages").await?;
+            let report = self.check_messages(onchain_count, conf.depth)?;
+            self.log_report("messages", onchain_count, &report, conf.repair);
+            if conf.repair && !report.is_clean() {
+                self.repair_messages(&report, onchain_count)?;
+            }
+        }
+        if let Some(indexer) = insertions {
+            let onchain_count = onchain_count(indexer, "merkle tree insertions").await?;
+            let report = self.check_merkle_tree_insertions(onchain_count, conf.depth)?;
+            self.log_report(
+                "merkle tree insertions",
+                onchain_count,
+                &report,
+                conf.repair,
+            );
+            if conf.repair && !report.is_clean() {
+                self.repair_merkle_tree_insertions(&report)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn log_report(&self, logs: &str, onchain_count: u32, report: &IntegrityReport, repair: bool) {
+        let domain = self.domain().name();
+        if report.is_clean() {
+            info!(
+                domain,
+                logs, onchain_count, "Database integrity check passed"
+            );
+        } else if repair {
+            warn!(
+                domain,
+                logs,
+                onchain_count,
+                ?report,
+                "Database integrity check failed, invalidating the affected sequences so that they're indexed again"
+            );
+        } else {
+            warn!(
+                domain,
+                logs,
+                onchain_count,
+                ?report,
+                "Database integrity check failed, enable `dbIntegrityCheck.repair` to index the affected sequences again"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{KnownHyperlaneDomain, H256};
+
+    use super::*;
+    use crate::db::DB;
+
+    fn message(db: &HyperlaneRocksDB, nonce: u32) -> HyperlaneMessage {
+        HyperlaneMessage {
+            nonce,
+            origin: db.domain().id(),
+            ..Default::default()
+        }
+    }
+
+    fn db_with_messages(count: u32) -> HyperlaneRocksDB {
+        let db = HyperlaneRocksDB::new(&KnownHyperlaneDomain::Test1.into(), DB::in_memory());
+        for nonce in 0..count {
+            db.store_message(&message(&db, nonce), 100 + nonce as u64)
+                .unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn passes_intact_messages() {
+        let db = db_with_messages(5);
+        assert!(db.check_messages(5, 10).unwrap().is_clean());
+        // Messages that aren't indexed yet are fine
+        assert!(db.check_messages(8, 10).unwrap().is_clean());
+    }
+
+    #[test]
+    fn repairs_stale_and_corrupted_messages() {
+        let db = db_with_messages(5);
+        db.store_message_id_by_nonce(&1, &H256::repeat_byte(1))
+            .unwrap();
+
+        let report = db.check_messages(3, 10).unwrap();
+        assert_eq!(report.stale, vec![3, 4]);
+        assert_eq!(report.corrupted, vec![1]);
+        assert!(report.stale_highest_seen);
+
+        db.repair_messages(&report, 3).unwrap();
+        assert!(db.check_messages(3, 10).unwrap().is_clean());
+        assert_eq!(db.retrieve_message_by_nonce(1).unwrap(), None);
+        assert_eq!(db.retrieve_message_by_nonce(4).unwrap(), None);
+        assert_eq!(
+            db.retrieve_message_by_nonce(2).unwrap(),
+            Some(message(&db, 2))
+        );
+        assert_eq!(db.retrieve_highest_seen_message_nonce().unwrap(), Some(2));
+
+        // The invalidated messages are stored again when they're re-indexed
+        assert!(db.store_message(&message(&db, 1), 101).unwrap());
+    }
+
+    #[test]
+    fn only_verifies_recent_messages() {
+        let db = db_with_messages(5);
+        db.store_message_id_by_nonce(&0, &H256::repeat_byte(1))
+            .unwrap();
+        assert!(db.check_messages(5, 2).unwrap().is_clean());
+        assert_eq!(db.check_messages(5, 5).unwrap().corrupted, vec![0]);
+    }
+
+    #[test]
+    fn repairs_stale_merkle_tree_insertions() {
+        let db = db_with_messages(0);
+        for index in 0..4 {
+            let insertion = MerkleTreeInsertion::new(index, H256::repeat_byte(index as u8));
+            db.process_tree_insertion(&insertion, 100).unwrap();
+        }
+        db.store_merkle_tree_insertion_by_leaf_index(
+            &1,
+            &MerkleTreeInsertion::new(7, H256::zero()),
+        )
+        .unwrap();
+
+        let report = db.check_merkle_tree_insertions(3, 10).unwrap();
+        assert_eq!(report.stale, vec![3]);
+        assert_eq!(report.corrupted, vec![1]);
+
+        db.repair_merkle_tree_insertions(&report).unwrap();
+        assert!(db.check_merkle_tree_insertions(3, 10).unwrap().is_clean());
+        assert_eq!(
+            db.retrieve_merkle_leaf_index_by_message_id(&H256::repeat_byte(3))
+                .unwrap(),
+            None
+        );
+    }
+}
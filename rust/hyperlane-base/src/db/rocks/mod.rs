@@ -2,17 +2,29 @@ use std::path::PathBuf;
 use std::{io, path::Path, sync::Arc};
 
 use hyperlane_core::{ChainCommunicationError, HyperlaneProtocolError};
+#[cfg(feature = "rocksdb")]
 use rocksdb::{Options, DB as Rocks};
 use tracing::info;
 
+use crate::db::{DbBackend, KeyValueStore, MemoryStore, RocksDbTuning};
+
 pub use hyperlane_db::*;
+pub use integrity::*;
+pub use schema::*;
 pub use typed_db::*;
 
 /// Shared functionality surrounding use of rocksdb
+#[cfg(feature = "rocksdb")]
 pub mod iterator;
 
 /// DB operations tied to specific Mailbox
 mod hyperlane_db;
+/// Verification of the stored sequences against the chain
+mod integrity;
+/// Versioning and migrations of the stored entries
+mod schema;
+/// Snapshots to move the stored entries between hosts
+mod snapshot;
 /// Type-specific db operations
 mod typed_db;
 
@@ -25,31 +37,77 @@ pub mod test_utils;
 
 #[derive(Debug, Clone)]
 /// A KV Store
-pub struct DB(Arc<Rocks>);
+pub struct DB(Arc<dyn KeyValueStore>);
 
+#[cfg(feature = "rocksdb")]
 impl From<Rocks> for DB {
     fn from(rocks: Rocks) -> Self {
         Self(Arc::new(rocks))
     }
 }
 
+#[cfg(feature = "sled")]
+impl From<sled::Db> for DB {
+    fn from(sled: sled::Db) -> Self {
+        Self(Arc::new(sled))
+    }
+}
+
+impl From<MemoryStore> for DB {
+    fn from(memory: MemoryStore) -> Self {
+        Self(Arc::new(memory))
+    }
+}
+
 /// DB Error type
 #[derive(thiserror::Error, Debug)]
 pub enum DbError {
     /// Rocks DB Error
+    #[cfg(feature = "rocksdb")]
     #[error("{0}")]
     RockError(#[from] rocksdb::Error),
+    /// sled Error
+    #[cfg(feature = "sled")]
+    #[error("{0}")]
+    SledError(#[from] sled::Error),
     #[error("Failed to open {path}, canonicalized as {canonicalized}: {source}")]
     /// Error opening the database
     OpeningError {
-        /// Rocksdb error during opening
+        /// Store error during opening
         #[source]
-        source: rocksdb::Error,
+        source: Box<DbError>,
         /// Raw database path provided
         path: PathBuf,
         /// Parsed path used
         canonicalized: PathBuf,
     },
+    /// The configured backend isn't one of the supported ones
+    #[error("Unknown database backend `{0}`, expected `rocksdb`, `sled` or `memory`")]
+    UnknownBackend(String),
+    /// The configured RocksDB tuning isn't one of the supported ones
+    #[error("Unknown RocksDB tuning `{0}`, expected `default`, `throughput` or `lowMemory`")]
+    UnknownTuning(String),
+    /// The configured backend wasn't built into this binary
+    #[error("The {0:?} database backend isn't built in, enable its feature")]
+    BackendNotBuilt(DbBackend),
+    /// The database was written by a newer build, which may have changed how
+    /// entries are encoded
+    #[error("The database has schema version {stored}, but this build only supports up to {supported}. Downgrading isn't supported: upgrade the agent, or start from an empty database")]
+    SchemaDowngrade {
+        /// The schema version of the database
+        stored: u32,
+        /// The newest schema version this build supports
+        supported: u32,
+    },
+    /// The snapshot to restore couldn't be read
+    #[error("Invalid database snapshot: {0}")]
+    InvalidSnapshot(String),
+    /// A snapshot couldn't be written or read
+    #[error("Failed to write or read database snapshot; {0}")]
+    SnapshotIo(#[source] io::Error),
+    /// The stored schema version isn't a big-endian u32
+    #[error("Invalid schema version {0:?}")]
+    InvalidSchemaVersion(Vec<u8>),
     /// Could not parse the provided database path string
     #[error("Invalid database path supplied {1:?}; {0}")]
     InvalidDbPath(#[source] io::Error, String),
@@ -67,33 +125,63 @@ impl From<DbError> for ChainCommunicationError {
 type Result<T> = std::result::Result<T, DbError>;
 
 impl DB {
-    /// Opens db at `db_path` and creates if missing
+    /// Opens a `backend` db at `db_path` and creates it if missing, then
+    /// migrates it to the current schema. The path is ignored by the
+    /// in-memory backend. If the db is new and `restore_from` is set, the
+    /// snapshot file there is restored into it first. `tuning` only applies
+    /// to the RocksDB backend.
     #[tracing::instrument(err)]
-    pub fn from_path(db_path: &Path) -> Result<DB> {
-        let path = {
-            let mut path = db_path
-                .parent()
-                .unwrap_or(Path::new("."))
-                .canonicalize()
-                .map_err(|e| DbError::InvalidDbPath(e, db_path.to_string_lossy().into()))?;
-            if let Some(file_name) = db_path.file_name() {
-                path.push(file_name);
+    pub fn open(
+        backend: DbBackend,
+        tuning: RocksDbTuning,
+        db_path: &Path,
+        restore_from: Option<&Path>,
+    ) -> Result<DB> {
+        let db = match backend {
+            #[cfg(feature = "rocksdb")]
+            DbBackend::RocksDb => Self::from_path(db_path, tuning),
+            #[cfg(feature = "sled")]
+            DbBackend::Sled => {
+                let path = canonicalize(db_path)?;
+                log_opening(&path);
+                sled::open(&path)
+                    .map_err(|e| DbError::OpeningError {
+                        source: Box::new(e.into()),
+                        path: db_path.into(),
+                        canonicalized: path,
+                    })
+                    .map(Into::into)
             }
-            path
-        };
-
-        if path.is_dir() {
-            info!(path=%path.to_string_lossy(), "Opening existing db")
-        } else {
-            info!(path=%path.to_string_lossy(), "Creating db")
+            DbBackend::Memory => Ok(Self::in_memory()),
+            #[allow(unreachable_patterns)]
+            backend => Err(DbError::BackendNotBuilt(backend)),
+        }?;
+        if let Some(snapshot) = restore_from {
+            db.restore_new_from_file(snapshot)?;
         }
+        db.migrate()?;
+        Ok(db)
+    }
+
+    /// Creates an empty db that only lives as long as the process
+    pub fn in_memory() -> DB {
+        MemoryStore::default().into()
+    }
+
+    /// Opens a RocksDB db at `db_path` and creates it if missing
+    #[cfg(feature = "rocksdb")]
+    #[tracing::instrument(err)]
+    pub fn from_path(db_path: &Path, tuning: RocksDbTuning) -> Result<DB> {
+        let path = canonicalize(db_path)?;
+        log_opening(&path);
 
         let mut opts = Options::default();
         opts.create_if_missing(true);
+        tuning.apply(&mut opts);
 
         Rocks::open(&opts, &path)
             .map_err(|e| DbError::OpeningError {
-                source: e,
+                source: Box::new(e.into()),
                 path: db_path.into(),
                 canonicalized: path,
             })
@@ -102,11 +190,50 @@ impl DB {
 
     /// Store a value in the DB
     pub fn store(&self, key: &[u8], value: &[u8]) -> Result<()> {
-        Ok(self.0.put(key, value)?)
+        self.0.store(key, value)
+    }
+
+    /// Statistics of the store's internals by name, for the metrics
+    pub fn stats(&self) -> Vec<(&'static str, u64)> {
+        self.0.stats()
     }
 
     /// Retrieve a value from the DB
     pub fn retrieve(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
-        Ok(self.0.get(key)?)
+        self.0.retrieve(key)
+    }
+
+    /// Delete a value from the DB
+    pub fn delete(&self, key: &[u8]) -> Result<()> {
+        self.0.delete(key)
+    }
+
+    /// Call `visit` with every entry in the DB, e.g. to re-encode them in a
+    /// schema migration
+    pub fn visit_all(&self, mut visit: impl FnMut(&[u8], &[u8]) -> Result<()>) -> Result<()> {
+        self.0.visit_all(&mut visit)
+    }
+}
+
+#[cfg(any(feature = "rocksdb", feature = "sled"))]
+/// Resolves the parent directory of `db_path`, which has to exist
+fn canonicalize(db_path: &Path) -> Result<PathBuf> {
+    let mut path = db_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .canonicalize()
+        .map_err(|e| DbError::InvalidDbPath(e, db_path.to_string_lossy().into()))?;
+    if let Some(file_name) = db_path.file_name() {
+        path.push(file_name);
+    }
+    Ok(path)
+}
+
+#[cfg(any(feature = "rocksdb", feature = "sled"))]
+fn log_opening(path: &Path) {
+    if path.is_dir() {
+        info!(path=%path.to_string_lossy(), "Opening existing db")
+    } else {
+        info!(path=%path.to_string_lossy(), "Creating db")
     }
 }
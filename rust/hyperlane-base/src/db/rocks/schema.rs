@@ -0,0 +1,140 @@
+use tracing::info;
+
+use crate::db::{DbError, DB};
+
+type Result<T> = std::result::Result<T, DbError>;
+
+/// The key the schema version is stored under. Unlike the other keys, it
+/// isn't prefixed with a domain.
+const SCHEMA_VERSION_KEY: &[u8] = b"__schema_version";
+
+/// An upgrade of the encoding of the stored keys or values
+#[derive(Debug)]
+pub struct Migration {
+    /// The schema version the database has after the migration
+    pub version: u32,
+    /// What the migration changes, for the logs
+    pub description: &'static str,
+    /// Rewrites the affected entries. Must be idempotent, since a migration
+    /// that's interrupted is run again on the next start.
+    pub migrate: fn(&DB) -> Result<()>,
+}
+
+/// The migrations to the current schema, in order. Databases created before
+/// the schema was versioned are at version 0.
+///
+/// Changing the encoding of stored keys or values requires adding a migration
+/// here, rather than a new key prefix that leaves the old entries behind.
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "Start tracking the schema version",
+    migrate: |_| Ok(()),
+}];
+
+/// The schema version this build reads and writes
+pub fn current_schema_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+impl DB {
+    /// The schema version of the stored entries
+    pub fn schema_version(&self) -> Result<u32> {
+        let Some(bytes) = self.retrieve(SCHEMA_VERSION_KEY)? else {
+            return Ok(0);
+        };
+        let bytes: [u8; 4] = bytes.try_into().map_err(DbError::InvalidSchemaVersion)?;
+        Ok(u32::from_be_bytes(bytes))
+    }
+
+    /// Whether an agent already ran with the DB, which then has a schema
+    /// version
+    pub fn is_initialized(&self) -> Result<bool> {
+        Ok(self.retrieve(SCHEMA_VERSION_KEY)?.is_some())
+    }
+
+    /// Upgrades the stored entries to the current schema. Refuses to open
+    /// databases that were written by a newer build, whose entries this one
+    /// may misread.
+    pub fn migrate(&self) -> Result<()> {
+        self.migrate_with(MIGRATIONS)
+    }
+
+    fn migrate_with(&self, migrations: &[Migration]) -> Result<()> {
+        let stored = self.schema_version()?;
+        let supported = migrations.last().map_or(0, |m| m.version);
+        if stored > supported {
+            return Err(DbError::SchemaDowngrade { stored, supported });
+        }
+        for migration in migrations.iter().filter(|m| m.version > stored) {
+            info!(
+                version = migration.version,
+                description = migration.description,
+                "Migrating database"
+            );
+            (migration.migrate)(self)?;
+            // The version is stored after each migration, so that a restart
+            // resumes from the first one that didn't complete
+            self.store(SCHEMA_VERSION_KEY, &migration.version.to_be_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rename_keys(db: &DB) -> Result<()> {
+        let mut renamed = vec![];
+        db.visit_all(|key, value| {
+            if let Some(rest) = key.strip_prefix(b"old_") {
+                renamed.push((key.to_vec(), [&b"new_"[..], rest].concat(), value.to_vec()));
+            }
+            Ok(())
+        })?;
+        for (old, new, value) in renamed {
+            db.store(&new, &value)?;
+            db.delete(&old)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn migrates_from_stored_version() {
+        let migrations = [
+            Migration {
+                version: 1,
+                description: "baseline",
+                migrate: |_| Ok(()),
+            },
+            Migration {
+                version: 2,
+                description: "rename keys",
+                migrate: rename_keys,
+            },
+        ];
+        let db = DB::in_memory();
+        db.store(b"old_key", b"value").unwrap();
+        assert_eq!(db.schema_version().unwrap(), 0);
+
+        db.migrate_with(&migrations[..1]).unwrap();
+        assert_eq!(db.schema_version().unwrap(), 1);
+        assert_eq!(db.retrieve(b"old_key").unwrap(), Some(b"value".to_vec()));
+
+        db.migrate_with(&migrations).unwrap();
+        assert_eq!(db.schema_version().unwrap(), 2);
+        assert_eq!(db.retrieve(b"old_key").unwrap(), None);
+        assert_eq!(db.retrieve(b"new_key").unwrap(), Some(b"value".to_vec()));
+    }
+
+    #[test]
+    fn refuses_downgrade() {
+        let db = DB::in_memory();
+        db.store(
+            SCHEMA_VERSION_KEY,
+            &(current_schema_version() + 1).to_be_bytes(),
+        )
+        .unwrap();
+        assert!(matches!(db.migrate(), Err(DbError::SchemaDowngrade { .. })));
+    }
+}
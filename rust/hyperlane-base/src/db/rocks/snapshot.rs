@@ -0,0 +1,158 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use tracing::{info, warn};
+
+use crate::db::{DbError, DB};
+
+type Result<T> = std::result::Result<T, DbError>;
+
+/// Identifies snapshot files and the version of their format
+const SNAPSHOT_MAGIC: &[u8] = b"HYPERLANE_DB_SNAPSHOT_V1";
+
+impl DB {
+    /// Writes every entry of the DB to `writer`, so that it can be restored
+    /// on another host. Entries are written as they're iterated over, so the
+    /// DB is never held in memory. Snapshots don't depend on the backend,
+    /// e.g. a RocksDB db can be restored into a sled one.
+    ///
+    /// RocksDB iterates over an implicit snapshot of the db, so its snapshots
+    /// are consistent even while the agent keeps writing.
+    pub fn write_snapshot(&self, writer: impl Write) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        writer
+            .write_all(SNAPSHOT_MAGIC)
+            .map_err(DbError::SnapshotIo)?;
+        self.visit_all(|key, value| {
+            write_chunk(&mut writer, key)?;
+            write_chunk(&mut writer, value)
+        })?;
+        writer.flush().map_err(DbError::SnapshotIo)
+    }
+
+    /// Stores every entry of the snapshot read from `reader`, returning how
+    /// many there were
+    pub fn restore_snapshot(&self, reader: impl Read) -> Result<usize> {
+        let mut reader = BufReader::new(reader);
+        let mut magic = [0; SNAPSHOT_MAGIC.len()];
+        match reader.read_exact(&mut magic) {
+            Ok(()) if magic == SNAPSHOT_MAGIC => {}
+            Ok(()) => return Err(DbError::InvalidSnapshot("unknown format".to_owned())),
+            Err(err) => return Err(snapshot_read_error(err)),
+        }
+        let mut entries = 0;
+        while let Some(key) = read_chunk(&mut reader)? {
+            let value = read_chunk(&mut reader)?.ok_or_else(truncated)?;
+            self.store(&key, &value)?;
+            entries += 1;
+        }
+        Ok(entries)
+    }
+
+    /// Restores the snapshot file at `path` if the DB is new. Restoring into
+    /// a DB an agent already ran with would mix the two states.
+    pub fn restore_new_from_file(&self, path: &Path) -> Result<()> {
+        if self.is_initialized()? {
+            warn!(
+                snapshot = %path.display(),
+                "Not restoring snapshot into a database that's already in use"
+            );
+            return Ok(());
+        }
+        let snapshot = File::open(path)
+            .map_err(|e| DbError::InvalidDbPath(e, path.to_string_lossy().into()))?;
+        let entries = self.restore_snapshot(snapshot)?;
+        info!(snapshot = %path.display(), entries, "Restored database snapshot");
+        Ok(())
+    }
+}
+
+fn truncated() -> DbError {
+    DbError::InvalidSnapshot("truncated".to_owned())
+}
+
+fn snapshot_read_error(err: io::Error) -> DbError {
+    match err.kind() {
+        io::ErrorKind::UnexpectedEof => truncated(),
+        _ => DbError::SnapshotIo(err),
+    }
+}
+
+fn write_chunk(writer: &mut impl Write, chunk: &[u8]) -> Result<()> {
+    writer
+        .write_all(&(chunk.len() as u64).to_be_bytes())
+        .and_then(|()| writer.write_all(chunk))
+        .map_err(DbError::SnapshotIo)
+}
+
+/// Reads the next chunk, or `None` at the end of the snapshot
+fn read_chunk(reader: &mut impl BufRead) -> Result<Option<Vec<u8>>> {
+    if reader.fill_buf().map_err(DbError::SnapshotIo)?.is_empty() {
+        return Ok(None);
+    }
+    let mut len = [0; 8];
+    reader.read_exact(&mut len).map_err(snapshot_read_error)?;
+    let len = u64::from_be_bytes(len);
+    // Not allocated upfront, the length may be corrupted
+    let mut chunk = vec![];
+    reader
+        .take(len)
+        .read_to_end(&mut chunk)
+        .map_err(DbError::SnapshotIo)?;
+    if (chunk.len() as u64) < len {
+        return Err(truncated());
+    }
+    Ok(Some(chunk))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn snapshot(db: &DB) -> Vec<u8> {
+        let mut snapshot = vec![];
+        db.write_snapshot(&mut snapshot).unwrap();
+        snapshot
+    }
+
+    #[test]
+    fn restores_snapshot() {
+        let db = DB::in_memory();
+        db.store(b"key", b"value").unwrap();
+        db.store(b"empty", b"").unwrap();
+        db.migrate().unwrap();
+        let snapshot = snapshot(&db);
+
+        let restored = DB::in_memory();
+        assert_eq!(restored.restore_snapshot(snapshot.as_slice()).unwrap(), 3);
+        assert_eq!(restored.retrieve(b"key").unwrap(), Some(b"value".to_vec()));
+        assert_eq!(restored.retrieve(b"empty").unwrap(), Some(vec![]));
+        assert_eq!(
+            restored.schema_version().unwrap(),
+            db.schema_version().unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_snapshots() {
+        let db = DB::in_memory();
+        db.store(b"key", b"value").unwrap();
+        let snapshot = snapshot(&db);
+
+        let restored = DB::in_memory();
+        assert!(restored
+            .restore_snapshot(b"not a snapshot".as_slice())
+            .is_err());
+        assert!(restored.restore_snapshot(&b"HYPER"[..]).is_err());
+        for len in [
+            snapshot.len() - 1,
+            snapshot.len() - 6,
+            SNAPSHOT_MAGIC.len() + 4,
+        ] {
+            assert!(restored.restore_snapshot(&snapshot[..len]).is_err());
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::{collections::BTreeMap, fmt::Debug, str::FromStr, sync::RwLock};
+
+use crate::db::DbError;
+
+type Result<T> = std::result::Result<T, DbError>;
+
+/// A key-value store the agents persist their state in
+pub trait KeyValueStore: Debug + Send + Sync {
+    /// Store a value under `key`, replacing any previous one
+    fn store(&self, key: &[u8], value: &[u8]) -> Result<()>;
+
+    /// Retrieve the value stored under `key`
+    fn retrieve(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Delete the value stored under `key`, if any
+    fn delete(&self, key: &[u8]) -> Result<()>;
+
+    /// Call `visit` with every entry in the store, in key order. `visit` may
+    /// store and delete entries, but whether it's called with the ones it
+    /// stores is unspecified.
+    fn visit_all(&self, visit: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>) -> Result<()>;
+
+    /// Statistics of the store's internals by name, for the metrics
+    fn stats(&self) -> Vec<(&'static str, u64)> {
+        vec![]
+    }
+}
+
+/// The key-value stores that can be selected in the agent config
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DbBackend {
+    /// RocksDB, persisted at the database path
+    #[default]
+    RocksDb,
+    /// sled, persisted at the database path. Unlike RocksDB, it's pure Rust,
+    /// so it builds on targets without a C++ toolchain.
+    Sled,
+    /// Kept in memory and lost on restart, e.g. for tests
+    Memory,
+}
+
+impl FromStr for DbBackend {
+    type Err = DbError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "rocksdb" => Ok(Self::RocksDb),
+            "sled" => Ok(Self::Sled),
+            "memory" => Ok(Self::Memory),
+            _ => Err(DbError::UnknownBackend(s.to_owned())),
+        }
+    }
+}
+
+/// How RocksDB trades memory for throughput
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RocksDbTuning {
+    /// RocksDB's defaults
+    #[default]
+    Default,
+    /// Larger memtables and block cache and more background jobs, for
+    /// relayers of many chains on hosts with memory to spare
+    Throughput,
+    /// Small memtables and block cache that also holds the index and filter
+    /// blocks, so that memory stays bounded as the database grows
+    LowMemory,
+}
+
+impl FromStr for RocksDbTuning {
+    type Err = DbError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "default" => Ok(Self::Default),
+            "throughput" => Ok(Self::Throughput),
+            "lowMemory" => Ok(Self::LowMemory),
+            _ => Err(DbError::UnknownTuning(s.to_owned())),
+        }
+    }
+}
+
+#[cfg(feature = "rocksdb")]
+impl RocksDbTuning {
+    /// Applies the profile to the options the db is opened with
+    pub(crate) fn apply(&self, opts: &mut rocksdb::Options) {
+        const MIB: usize = 1024 * 1024;
+        let mut table_opts = rocksdb::BlockBasedOptions::default();
+        match self {
+            Self::Default => return,
+            Self::Throughput => {
+                let cpus = std::thread::available_parallelism().map_or(4, |n| n.get()) as i32;
+                opts.increase_parallelism(cpus);
+                opts.set_max_background_jobs(cpus.max(4));
+                opts.optimize_level_style_compaction(512 * MIB);
+                opts.set_write_buffer_size(128 * MIB);
+                opts.set_max_write_buffer_number(4);
+                table_opts.set_block_cache(&rocksdb::Cache::new_lru_cache(512 * MIB));
+                table_opts.set_bloom_filter(10.0, false);
+            }
+            Self::LowMemory => {
+                opts.set_max_background_jobs(2);
+                opts.set_write_buffer_size(16 * MIB);
+                opts.set_max_write_buffer_number(2);
+                opts.set_max_open_files(256);
+                table_opts.set_block_cache(&rocksdb::Cache::new_lru_cache(32 * MIB));
+                table_opts.set_cache_index_and_filter_blocks(true);
+                table_opts.set_pin_l0_filter_and_index_blocks_in_cache(true);
+            }
+        }
+        opts.set_block_based_table_factory(&table_opts);
+    }
+}
+
+#[cfg(feature = "rocksdb")]
+impl KeyValueStore for rocksdb::DB {
+    fn store(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        Ok(self.put(key, value)?)
+    }
+
+    fn retrieve(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.get(key)?)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        Ok(rocksdb::DB::delete(self, key)?)
+    }
+
+    fn visit_all(&self, visit: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>) -> Result<()> {
+        for entry in self.iterator(rocksdb::IteratorMode::Start) {
+            let (key, value) = entry?;
+            visit(&key, &value)?;
+        }
+        Ok(())
+    }
+
+    fn stats(&self) -> Vec<(&'static str, u64)> {
+        let property = |name: &str| self.property_int_value(name).ok().flatten().unwrap_or(0);
+        let sst_files = (0..7)
+            .map(|level| property(&format!("rocksdb.num-files-at-level{level}")))
+            .sum();
+        vec![
+            ("sst_files", sst_files),
+            ("sst_files_bytes", property("rocksdb.total-sst-files-size")),
+            (
+                "compaction_pending_bytes",
+                property("rocksdb.estimate-pending-compaction-bytes"),
+            ),
+            (
+                "running_compactions",
+                property("rocksdb.num-running-compactions"),
+            ),
+            ("write_stopped", property("rocksdb.is-write-stopped")),
+            (
+                "delayed_write_rate",
+                property("rocksdb.actual-delayed-write-rate"),
+            ),
+            (
+                "memtables_bytes",
+                property("rocksdb.cur-size-all-mem-tables"),
+            ),
+            ("block_cache_bytes", property("rocksdb.block-cache-usage")),
+            ("estimated_keys", property("rocksdb.estimate-num-keys")),
+        ]
+    }
+}
+
+#[cfg(feature = "sled")]
+impl KeyValueStore for sled::Db {
+    fn store(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.insert(key, value)?;
+        Ok(())
+    }
+
+    fn retrieve(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.get(key)?.map(|value| value.to_vec()))
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        sled::Tree::remove(self, key)?;
+        Ok(())
+    }
+
+    fn visit_all(&self, visit: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>) -> Result<()> {
+        for entry in self.iter() {
+            let (key, value) = entry?;
+            visit(&key, &value)?;
+        }
+        Ok(())
+    }
+}
+
+/// A store that only lives as long as the process
+#[derive(Debug, Default)]
+pub struct MemoryStore(RwLock<BTreeMap<Vec<u8>, Vec<u8>>>);
+
+impl KeyValueStore for MemoryStore {
+    fn store(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.0
+            .write()
+            .expect("poisoned lock")
+            .insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn retrieve(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.0.read().expect("poisoned lock").get(key).cloned())
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        self.0.write().expect("poisoned lock").remove(key);
+        Ok(())
+    }
+
+    fn visit_all(&self, visit: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>) -> Result<()> {
+        // The entries are copied so that `visit` can write to the store
+        let entries = self.0.read().expect("poisoned lock").clone();
+        for (key, value) in entries {
+            visit(&key, &value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn memory_store_stores_and_retrieves() {
+        let store = MemoryStore::default();
+        assert_eq!(store.retrieve(b"key").unwrap(), None);
+        store.store(b"key", b"value").unwrap();
+        store.store(b"key", b"other").unwrap();
+        assert_eq!(store.retrieve(b"key").unwrap(), Some(b"other".to_vec()));
+    }
+
+    #[test]
+    fn parses_backends() {
+        assert_eq!("rocksdb".parse::<DbBackend>().unwrap(), DbBackend::RocksDb);
+        assert_eq!("sled".parse::<DbBackend>().unwrap(), DbBackend::Sled);
+        assert_eq!("memory".parse::<DbBackend>().unwrap(), DbBackend::Memory);
+        assert!("redis".parse::<DbBackend>().is_err());
+    }
+
+    #[test]
+    fn parses_tunings() {
+        assert_eq!(
+            "lowMemory".parse::<RocksDbTuning>().unwrap(),
+            RocksDbTuning::LowMemory
+        );
+        assert_eq!(
+            "throughput".parse::<RocksDbTuning>().unwrap(),
+            RocksDbTuning::Throughput
+        );
+        assert!("fast".parse::<RocksDbTuning>().is_err());
+    }
+}
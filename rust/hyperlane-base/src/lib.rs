@@ -22,6 +22,9 @@ pub use server::*;
 mod contract_sync;
 pub use contract_sync::*;
 
+mod supervisor;
+pub use supervisor::*;
+
 mod traits;
 pub use traits::*;
 
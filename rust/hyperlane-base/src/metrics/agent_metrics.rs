@@ -8,6 +8,7 @@ use eyre::Result;
 use hyperlane_core::metrics::agent::decimals_by_protocol;
 use hyperlane_core::metrics::agent::u256_as_scaled_f64;
 use hyperlane_core::metrics::agent::METRICS_SCRAPE_INTERVAL;
+use hyperlane_core::BalanceProvider;
 use hyperlane_core::HyperlaneDomain;
 use hyperlane_core::HyperlaneProvider;
 use maplit::hashmap;
@@ -17,7 +18,7 @@ use tokio::{task::JoinHandle, time::MissedTickBehavior};
 use tracing::info_span;
 use tracing::{debug, instrument::Instrumented, trace, warn, Instrument};
 
-use crate::settings::ChainConf;
+use crate::settings::{ChainConf, Settings};
 use crate::CoreMetrics;
 
 /// Expected label names for the `wallet_balance` metric.
@@ -115,9 +116,128 @@ pub struct AgentMetricsConf {
     pub name: String,
 }
 
+/// A signer whose balance is monitored
+#[derive(Debug)]
+struct MonitoredSigner {
+    domain: HyperlaneDomain,
+    address: String,
+    provider: Box<dyn HyperlaneProvider>,
+    min_balance: Option<f64>,
+}
+
+/// Periodically reports the balances of the signers of an agent on every
+/// configured chain, warning about the ones below their configured minimum
+pub struct BalanceMonitor {
+    agent_metrics: AgentMetrics,
+    agent_name: String,
+    signers: Vec<MonitoredSigner>,
+}
+
+impl BalanceMonitor {
+    /// Creates a monitor of the signers configured in `settings`. Chains
+    /// without a signer, or whose provider can't be built, are skipped.
+    pub async fn new(
+        settings: &Settings,
+        core_metrics: &CoreMetrics,
+        agent_metrics: AgentMetrics,
+        agent_name: String,
+    ) -> Self {
+        let mut signers = vec![];
+        for chain_conf in settings.chains.values() {
+            let chain = chain_conf.domain.name();
+            let signer = match chain_conf.chain_signer().await {
+                Ok(Some(signer)) => signer,
+                Ok(None) => continue,
+                Err(err) => {
+                    warn!(
+                        chain,
+                        ?err,
+                        "Failed to build signer, not monitoring its balance"
+                    );
+                    continue;
+                }
+            };
+            let provider = match chain_conf.build_provider(core_metrics).await {
+                Ok(provider) => provider,
+                Err(err) => {
+                    warn!(
+                        chain,
+                        ?err,
+                        "Failed to build provider, not monitoring the signer balance"
+                    );
+                    continue;
+                }
+            };
+            signers.push(MonitoredSigner {
+                domain: chain_conf.domain.clone(),
+                address: signer.address_string(),
+                provider,
+                min_balance: chain_conf.min_signer_balance,
+            });
+        }
+        Self {
+            agent_metrics,
+            agent_name,
+            signers,
+        }
+    }
+
+    async fn update_balances(&self) {
+        let Some(wallet_balance) = &self.agent_metrics.wallet_balance else {
+            return;
+        };
+        for signer in &self.signers {
+            let chain = signer.domain.name();
+            let address = signer.address.as_str();
+            let balance = match signer.provider.native_balance(signer.address.clone()).await {
+                Ok(balance) => balance,
+                Err(err) => {
+                    warn!(chain, address, ?err, "Failed to fetch signer balance");
+                    continue;
+                }
+            };
+            let amount = balance.as_f64();
+            let symbol = balance.denom.denom.as_deref().unwrap_or("Native");
+            trace!(chain, address, balance = amount, symbol, "Signer balance");
+            wallet_balance
+                .with(&hashmap! {
+                    "chain" => chain,
+                    "wallet_address" => address,
+                    "wallet_name" => self.agent_name.as_str(),
+                    "token_address" => "none",
+                    "token_symbol" => symbol,
+                    "token_name" => "Native"
+                })
+                .set(amount);
+            if let Some(min_balance) = signer.min_balance.filter(|min| amount < *min) {
+                warn!(
+                    chain,
+                    address,
+                    balance = amount,
+                    min_balance,
+                    symbol,
+                    "Signer balance is below the minimum and needs to be topped up"
+                );
+            }
+        }
+    }
+
+    /// Spawns a tokio task to periodically update the balances
+    pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(METRICS_SCRAPE_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                self.update_balances().await;
+                interval.tick().await;
+            }
+        })
+        .instrument(info_span!("BalanceMonitor"))
+    }
+}
+
 /// Utility struct to update various metrics using a standalone tokio task
 pub struct MetricsUpdater {
-    agent_metrics: AgentMetrics,
     chain_metrics: ChainMetrics,
     conf: AgentMetricsConf,
     provider: Box<dyn HyperlaneProvider>,
@@ -128,7 +248,6 @@ impl MetricsUpdater {
     pub async fn new(
         chain_conf: &ChainConf,
         core_metrics: Arc<CoreMetrics>,
-        agent_metrics: AgentMetrics,
         chain_metrics: ChainMetrics,
         agent_name: String,
     ) -> Result<Self> {
@@ -136,42 +255,12 @@ impl MetricsUpdater {
         let provider = chain_conf.build_provider(&core_metrics).await?;
 
         Ok(Self {
-            agent_metrics,
             chain_metrics,
             conf: agent_metrics_conf,
             provider,
         })
     }
 
-    async fn update_agent_metrics(&self) {
-        let Some(wallet_addr) = self.conf.address.clone() else {
-            return;
-        };
-        let wallet_name = self.conf.name.clone();
-        let Some(wallet_balance_metric) = self.agent_metrics.wallet_balance.clone() else {
-            return;
-        };
-        let chain = self.conf.domain.name();
-
-        match self.provider.get_balance(wallet_addr.clone()).await {
-            Ok(balance) => {
-                let balance = u256_as_scaled_f64(balance, self.conf.domain.domain_protocol());
-                trace!("Wallet {wallet_name} ({wallet_addr}) on chain {chain} balance is {balance} of the native currency");
-                wallet_balance_metric
-                .with(&hashmap! {
-                    "chain" => chain,
-                    "wallet_address" => wallet_addr.as_str(),
-                    "wallet_name" => wallet_name.as_str(),
-                    "token_address" => "none",
-                    // Note: Whatever this `chain`'s native currency is
-                    "token_symbol" => "Native",
-                    "token_name" => "Native"
-                }).set(balance)
-            },
-            Err(e) => warn!("Metric update failed for wallet {wallet_name} ({wallet_addr}) on chain {chain} balance for native currency; {e}")
-        }
-    }
-
     async fn update_block_details(&self) {
         let block_height = self.chain_metrics.block_height.clone();
         let gas_price = self.chain_metrics.gas_price.clone();
@@ -214,7 +303,6 @@ impl MetricsUpdater {
         let mut interval = tokio::time::interval(period);
         interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
         loop {
-            self.update_agent_metrics().await;
             self.update_block_details().await;
             interval.tick().await;
         }
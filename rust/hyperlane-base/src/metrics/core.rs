@@ -39,11 +39,25 @@ pub struct CoreMetrics {
     last_known_message_nonce: IntGaugeVec,
     submitter_queue_length: IntGaugeVec,
 
+    circuit_breaker_state: IntGaugeVec,
+    circuit_breaker_trips: IntCounterVec,
+
     operations_processed_count: IntCounterVec,
     messages_processed_count: IntCounterVec,
 
     latest_checkpoint: IntGaugeVec,
 
+    light_client_verification_failures: IntCounterVec,
+
+    messages_in_fraud_window: IntGaugeVec,
+
+    dead_lettered_messages: IntGaugeVec,
+
+    avoided_failed_transactions: IntCounterVec,
+
+    message_gas_payments: CounterVec,
+    message_gas_costs: CounterVec,
+
     /// Set of metrics that tightly wrap the JsonRpcClient for use with the
     /// quorum provider.
     json_rpc_client_metrics: OnceLock<JsonRpcClientMetrics>,
@@ -136,6 +150,26 @@ impl CoreMetrics {
             registry
         )?;
 
+        let circuit_breaker_state = register_int_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("circuit_breaker_state"),
+                "State of the circuit breaker of the submissions to a chain, 0 if closed, 1 if half-open and 2 if open",
+                const_labels_ref
+            ),
+            &["remote"],
+            registry
+        )?;
+
+        let circuit_breaker_trips = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("circuit_breaker_trips"),
+                "Number of times submissions to a chain were paused because too many of them failed",
+                const_labels_ref
+            ),
+            &["remote"],
+            registry
+        )?;
+
         let latest_checkpoint = register_int_gauge_vec_with_registry!(
             opts!(
                 namespaced!("latest_checkpoint"),
@@ -166,6 +200,66 @@ impl CoreMetrics {
             registry
         )?;
 
+        let light_client_verification_failures = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("light_client_verification_failures"),
+                "Number of RPC responses that failed verification against a light client",
+                const_labels_ref
+            ),
+            &["chain", "method"],
+            registry
+        )?;
+
+        let messages_in_fraud_window = register_int_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("messages_in_fraud_window"),
+                "Number of messages pre-verified by an optimistic ISM waiting for its fraud window to elapse",
+                const_labels_ref
+            ),
+            &["origin", "remote"],
+            registry
+        )?;
+
+        let dead_lettered_messages = register_int_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("dead_lettered_messages"),
+                "Number of messages parked as undeliverable until they are manually retried",
+                const_labels_ref
+            ),
+            &["origin", "remote"],
+            registry
+        )?;
+
+        let avoided_failed_transactions = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("avoided_failed_transactions"),
+                "Number of process transactions that weren't sent because simulating them reverted",
+                const_labels_ref
+            ),
+            &["origin", "remote", "reason"],
+            registry
+        )?;
+
+        let message_gas_payments = register_counter_vec_with_registry!(
+            opts!(
+                namespaced!("message_gas_payments"),
+                "Gas payments received for processed messages whose cost is known, in the smallest unit of the origin native token",
+                const_labels_ref
+            ),
+            &["origin", "remote"],
+            registry
+        )?;
+
+        let message_gas_costs = register_counter_vec_with_registry!(
+            opts!(
+                namespaced!("message_gas_costs"),
+                "Cost of processing messages, converted with the gas oracle exchange rate to the smallest unit of the origin native token",
+                const_labels_ref
+            ),
+            &["origin", "remote"],
+            registry
+        )?;
+
         Ok(Self {
             agent_name: for_agent.into(),
             registry,
@@ -179,11 +273,25 @@ impl CoreMetrics {
 
             submitter_queue_length,
 
+            circuit_breaker_state,
+            circuit_breaker_trips,
+
             operations_processed_count,
             messages_processed_count,
 
             latest_checkpoint,
 
+            light_client_verification_failures,
+
+            messages_in_fraud_window,
+
+            dead_lettered_messages,
+
+            avoided_failed_transactions,
+
+            message_gas_payments,
+            message_gas_costs,
+
             json_rpc_client_metrics: OnceLock::new(),
             provider_metrics: OnceLock::new(),
 
@@ -327,6 +435,24 @@ impl CoreMetrics {
         self.submitter_queue_length.clone()
     }
 
+    /// The state of the circuit breaker of the submissions to a chain: 0 if
+    /// closed, 1 if half-open and probing, 2 if open and paused.
+    ///
+    /// Labels:
+    /// - `remote`: Remote chain the submissions are for.
+    pub fn circuit_breaker_state(&self) -> IntGaugeVec {
+        self.circuit_breaker_state.clone()
+    }
+
+    /// The number of times submissions to a chain were paused because too
+    /// many of them failed.
+    ///
+    /// Labels:
+    /// - `remote`: Remote chain the submissions are for.
+    pub fn circuit_breaker_trips(&self) -> IntCounterVec {
+        self.circuit_breaker_trips.clone()
+    }
+
     /// The number of operations successfully submitted by this process during
     /// its lifetime.
     ///
@@ -354,6 +480,69 @@ impl CoreMetrics {
         self.operations_processed_count.clone()
     }
 
+    /// The number of RPC responses that didn't match what a light client
+    /// verified, or that it couldn't verify, on chains configured with one.
+    ///
+    /// Labels:
+    /// - `chain`: Chain the RPC responses are from.
+    /// - `method`: The RPC method of the request.
+    pub fn light_client_verification_failures(&self) -> IntCounterVec {
+        self.light_client_verification_failures.clone()
+    }
+
+    /// The number of messages pre-verified by an optimistic ISM that are
+    /// waiting for its fraud window to elapse before they can be delivered.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain of the messages.
+    /// - `remote`: Destination chain of the messages.
+    pub fn messages_in_fraud_window(&self) -> IntGaugeVec {
+        self.messages_in_fraud_window.clone()
+    }
+
+    /// The number of messages the relayer parked as undeliverable, which
+    /// aren't attempted again until they are manually retried.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain of the messages.
+    /// - `remote`: Destination chain of the messages.
+    pub fn dead_lettered_messages(&self) -> IntGaugeVec {
+        self.dead_lettered_messages.clone()
+    }
+
+    /// The number of process transactions that the relayer didn't send
+    /// because simulating them reverted.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain of the messages.
+    /// - `remote`: Destination chain of the messages.
+    /// - `reason`: Why the simulation reverted, one of `already_delivered`,
+    ///   `ism_verification_failed` or `recipient_reverted`.
+    pub fn avoided_failed_transactions(&self) -> IntCounterVec {
+        self.avoided_failed_transactions.clone()
+    }
+
+    /// The gas payments received for the messages processed by the relayer,
+    /// counting only messages whose cost could be converted to the origin
+    /// native token. Compare with `message_gas_costs` for profitability.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain of the messages.
+    /// - `remote`: Destination chain of the messages.
+    pub fn message_gas_payments(&self) -> CounterVec {
+        self.message_gas_payments.clone()
+    }
+
+    /// The cost of processing messages on their destination, converted to
+    /// the origin native token with the exchange rate of the gas oracle.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain of the messages.
+    /// - `remote`: Destination chain of the messages.
+    pub fn message_gas_costs(&self) -> CounterVec {
+        self.message_gas_costs.clone()
+    }
+
     /// The number of messages successfully submitted by this process during its
     /// lifetime.
     ///
@@ -413,6 +602,11 @@ impl CoreMetrics {
         Ok(out_buf)
     }
 
+    /// Gather the current values of all metrics, e.g. to push them
+    pub(crate) fn gather_families(&self) -> Vec<prometheus::proto::MetricFamily> {
+        self.registry.gather()
+    }
+
     /// Get the name of this agent, e.g. "relayer"
     pub fn agent_name(&self) -> &str {
         &self.agent_name
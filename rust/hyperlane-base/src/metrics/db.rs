@@ -0,0 +1,48 @@
+//! Metrics of the agent database
+
+use eyre::Result;
+use hyperlane_core::metrics::agent::METRICS_SCRAPE_INTERVAL;
+use prometheus::IntGaugeVec;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{info_span, instrument::Instrumented, Instrument};
+
+use crate::{db::DB, CoreMetrics};
+
+/// Periodically reports the statistics of the database's store, e.g. the
+/// number of SST files and pending compaction bytes of RocksDB
+#[derive(Debug)]
+pub struct DbMetricsUpdater {
+    db: DB,
+    stats: IntGaugeVec,
+}
+
+impl DbMetricsUpdater {
+    /// Creates a new instance of the `DbMetricsUpdater`
+    pub fn new(db: DB, core_metrics: &CoreMetrics) -> Result<Self> {
+        let stats = core_metrics.new_int_gauge(
+            "db_stats",
+            "Statistics of the internals of the agent database, e.g. `sst_files` or `compaction_pending_bytes` for RocksDB",
+            &["stat"],
+        )?;
+        Ok(Self { db, stats })
+    }
+
+    fn update(&self) {
+        for (stat, value) in self.db.stats() {
+            self.stats.with_label_values(&[stat]).set(value as i64);
+        }
+    }
+
+    /// Spawns a task that updates the metrics on an interval
+    pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(METRICS_SCRAPE_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                self.update();
+                interval.tick().await;
+            }
+        })
+        .instrument(info_span!("DbMetricsUpdater"))
+    }
+}
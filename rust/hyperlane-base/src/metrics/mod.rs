@@ -8,7 +8,11 @@ pub const NAMESPACE: &str = "hyperlane";
 mod core;
 
 mod agent_metrics;
+mod db;
 mod json_rpc_client;
+mod otlp;
 mod provider;
 
 pub use self::agent_metrics::*;
+pub use self::db::*;
+pub use self::otlp::*;
@@ -0,0 +1,250 @@
+//! Export of the metrics to an OpenTelemetry collector
+
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use prometheus::proto::{Metric, MetricFamily, MetricType};
+use serde_json::{json, Value as JsonValue};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::{settings::OtlpExporter, CoreMetrics};
+
+/// OTLP `AGGREGATION_TEMPORALITY_CUMULATIVE`, which matches how prometheus
+/// counters and histograms accumulate since the agent started
+const CUMULATIVE: u8 = 2;
+
+/// Periodically pushes every metric of the prometheus registry to the
+/// collector, converted to OTLP metrics. The scrape endpoint keeps serving
+/// them as well.
+#[derive(Debug)]
+pub struct OtlpMetricsExporter {
+    exporter: OtlpExporter,
+    metrics: Arc<CoreMetrics>,
+    start_time: SystemTime,
+}
+
+impl OtlpMetricsExporter {
+    /// Creates a new instance of the `OtlpMetricsExporter`
+    pub fn new(exporter: OtlpExporter, metrics: Arc<CoreMetrics>) -> Self {
+        Self {
+            exporter,
+            metrics,
+            start_time: SystemTime::now(),
+        }
+    }
+
+    async fn export(&self) {
+        let body = export_request(
+            self.exporter.resource_json(),
+            &self.metrics.gather_families(),
+            self.start_time,
+            SystemTime::now(),
+        );
+        if let Err(err) = self.exporter.export("metrics", &body).await {
+            warn!(?err, "Failed to export metrics");
+        }
+    }
+
+    /// Spawns a task that exports the metrics on an interval
+    pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.exporter.metrics_interval());
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                self.export().await;
+            }
+        })
+        .instrument(info_span!("OtlpMetricsExporter"))
+    }
+}
+
+/// The body of an OTLP/HTTP export request with `families`
+fn export_request(
+    resource: JsonValue,
+    families: &[MetricFamily],
+    start_time: SystemTime,
+    time: SystemTime,
+) -> JsonValue {
+    let times = (unix_nanos(start_time), unix_nanos(time));
+    json!({
+        "resourceMetrics": [{
+            "resource": resource,
+            "scopeMetrics": [{
+                "scope": { "name": "hyperlane-agent" },
+                "metrics": families
+                    .iter()
+                    .filter_map(|family| metric_json(family, &times))
+                    .collect::<Vec<_>>(),
+            }],
+        }],
+    })
+}
+
+/// Converts a prometheus metric family. Summaries aren't converted, the
+/// agents don't use them.
+fn metric_json(family: &MetricFamily, (start, time): &(String, String)) -> Option<JsonValue> {
+    let metrics = family.get_metric();
+    let (kind, data) = match family.get_field_type() {
+        MetricType::COUNTER => (
+            "sum",
+            json!({
+                "aggregationTemporality": CUMULATIVE,
+                "isMonotonic": true,
+                "dataPoints": metrics
+                    .iter()
+                    .map(|metric| json!({
+                        "attributes": attributes(metric),
+                        "startTimeUnixNano": start,
+                        "timeUnixNano": time,
+                        "asDouble": metric.get_counter().get_value(),
+                    }))
+                    .collect::<Vec<_>>(),
+            }),
+        ),
+        MetricType::GAUGE | MetricType::UNTYPED => (
+            "gauge",
+            json!({
+                "dataPoints": metrics
+                    .iter()
+                    .map(|metric| json!({
+                        "attributes": attributes(metric),
+                        "timeUnixNano": time,
+                        "asDouble": if metric.has_gauge() {
+                            metric.get_gauge().get_value()
+                        } else {
+                            metric.get_untyped().get_value()
+                        },
+                    }))
+                    .collect::<Vec<_>>(),
+            }),
+        ),
+        MetricType::HISTOGRAM => (
+            "histogram",
+            json!({
+                "aggregationTemporality": CUMULATIVE,
+                "dataPoints": metrics
+                    .iter()
+                    .map(|metric| histogram_point(metric, start, time))
+                    .collect::<Vec<_>>(),
+            }),
+        ),
+        MetricType::SUMMARY => return None,
+    };
+    Some(json!({
+        "name": family.get_name(),
+        "description": family.get_help(),
+        kind: data,
+    }))
+}
+
+/// Prometheus buckets count the observations up to their bound, including
+/// the ones of the lower buckets, while OTLP counts them per bucket, with an
+/// implicit last bucket up to infinity
+fn histogram_point(metric: &Metric, start: &str, time: &str) -> JsonValue {
+    let histogram = metric.get_histogram();
+    let mut bounds = vec![];
+    let mut counts = vec![];
+    let mut below = 0;
+    for bucket in histogram.get_bucket() {
+        if bucket.get_upper_bound().is_infinite() {
+            continue;
+        }
+        bounds.push(bucket.get_upper_bound());
+        counts.push(bucket.get_cumulative_count().saturating_sub(below));
+        below = bucket.get_cumulative_count();
+    }
+    counts.push(histogram.get_sample_count().saturating_sub(below));
+    json!({
+        "attributes": attributes(metric),
+        "startTimeUnixNano": start,
+        "timeUnixNano": time,
+        // 64 bit integers are encoded as strings
+        "count": histogram.get_sample_count().to_string(),
+        "sum": histogram.get_sample_sum(),
+        "bucketCounts": counts.iter().map(u64::to_string).collect::<Vec<_>>(),
+        "explicitBounds": bounds,
+    })
+}
+
+fn attributes(metric: &Metric) -> Vec<JsonValue> {
+    metric
+        .get_label()
+        .iter()
+        .map(|label| {
+            json!({
+                "key": label.get_name(),
+                "value": { "stringValue": label.get_value() },
+            })
+        })
+        .collect()
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use prometheus::{histogram_opts, opts, HistogramVec, IntCounterVec, Registry};
+
+    use super::*;
+
+    fn families() -> Vec<MetricFamily> {
+        let registry = Registry::new();
+        let counter =
+            IntCounterVec::new(opts!("messages", "Messages processed"), &["chain"]).unwrap();
+        let histogram = HistogramVec::new(
+            histogram_opts!("latency", "Latency", vec![1.0, 10.0]),
+            &["chain"],
+        )
+        .unwrap();
+        registry.register(Box::new(counter.clone())).unwrap();
+        registry.register(Box::new(histogram.clone())).unwrap();
+        counter.with_label_values(&["test1"]).inc_by(3);
+        for latency in [0.5, 2.0, 3.0, 20.0] {
+            histogram.with_label_values(&["test1"]).observe(latency);
+        }
+        registry.gather()
+    }
+
+    #[test]
+    fn converts_counters() {
+        let times = ("1".to_owned(), "2".to_owned());
+        let families = families();
+        let family = families
+            .iter()
+            .find(|family| family.get_name() == "messages")
+            .unwrap();
+        let metric = metric_json(family, &times).unwrap();
+        assert_eq!(metric["description"], "Messages processed");
+        assert_eq!(metric["sum"]["isMonotonic"], true);
+        let point = &metric["sum"]["dataPoints"][0];
+        assert_eq!(point["asDouble"], 3.0);
+        assert_eq!(
+            point["attributes"][0],
+            json!({ "key": "chain", "value": { "stringValue": "test1" } })
+        );
+    }
+
+    #[test]
+    fn converts_histogram_buckets() {
+        let times = ("1".to_owned(), "2".to_owned());
+        let families = families();
+        let family = families
+            .iter()
+            .find(|family| family.get_name() == "latency")
+            .unwrap();
+        let point = &metric_json(family, &times).unwrap()["histogram"]["dataPoints"][0];
+        assert_eq!(point["count"], "4");
+        assert_eq!(point["sum"], 25.5);
+        assert_eq!(point["explicitBounds"], json!([1.0, 10.0]));
+        assert_eq!(point["bucketCounts"], json!(["1", "2", "1"]));
+    }
+}
@@ -0,0 +1,43 @@
+use axum::http::{header::AUTHORIZATION, HeaderMap, StatusCode};
+use subtle::ConstantTimeEq;
+
+/// Check that `headers` authorize the request with `api_key` as a bearer
+/// token. The token is compared in constant time, so that response times
+/// don't leak how much of it matches.
+pub fn authorize(api_key: &str, headers: &HeaderMap) -> Result<(), (StatusCode, String)> {
+    let authorized = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| token.as_bytes().ct_eq(api_key.as_bytes()).into());
+    if authorized {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid api key".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn headers(authorization: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static(authorization));
+        headers
+    }
+
+    #[test]
+    fn test_authorize() {
+        assert!(authorize("key", &headers("Bearer key")).is_ok());
+        assert!(authorize("key", &headers("Bearer kez")).is_err());
+        assert!(authorize("key", &headers("Bearer key2")).is_err());
+        assert!(authorize("key", &headers("key")).is_err());
+        assert!(authorize("key", &HeaderMap::new()).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use std::{
+    io::{Seek, SeekFrom},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    body::StreamBody,
+    extract::State,
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE},
+        HeaderMap, StatusCode,
+    },
+    response::IntoResponse,
+    routing, Router,
+};
+use derive_new::new;
+use tokio::fs::File;
+use tracing::info;
+
+use super::authorize;
+use crate::{db::DB, types::utils::file_chunks, ObjectWriter};
+
+const DB_SNAPSHOT_API_BASE: &str = "/admin/db_snapshot";
+
+/// Routes for taking snapshots of the agent database, to move its state to
+/// another host without re-indexing. A snapshot is restored by pointing the
+/// `dbRestoreFrom` setting of a new agent at it. Requests must carry the api
+/// key as a bearer token.
+///
+/// - `GET /admin/db_snapshot` - download a snapshot
+/// - `POST /admin/db_snapshot` - write a snapshot to the configured object
+///   storage, returning its key
+///
+/// Snapshots are staged in a file in the temp directory, which needs room
+/// for them, and streamed from there.
+#[derive(new, Clone, Debug)]
+pub struct DbSnapshotApi {
+    api_key: String,
+    agent: &'static str,
+    db: DB,
+    writer: Option<Arc<dyn ObjectWriter>>,
+}
+
+/// Writes a snapshot of `db` to a temp file that's deleted once closed,
+/// returning the file, rewound, and its size
+async fn take_snapshot(db: DB) -> Result<(File, u64), (StatusCode, String)> {
+    // Serializing the whole db blocks for a while
+    tokio::task::spawn_blocking(move || -> eyre::Result<_> {
+        let mut file = tempfile::tempfile()?;
+        db.write_snapshot(&mut file)?;
+        let size = file.seek(SeekFrom::End(0))?;
+        file.rewind()?;
+        Ok((File::from_std(file), size))
+    })
+    .await
+    .map_err(|err| err.to_string())
+    .and_then(|snapshot| snapshot.map_err(|err| err.to_string()))
+    .map_err(|err| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to take snapshot: {err}"),
+        )
+    })
+}
+
+fn snapshot_name(agent: &str) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    format!("{agent}-{now}.snapshot")
+}
+
+async fn download_snapshot(
+    State(api): State<DbSnapshotApi>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(&api.api_key, &headers)?;
+    let (snapshot, size) = take_snapshot(api.db).await?;
+    let disposition = format!("attachment; filename=\"{}\"", snapshot_name(api.agent));
+    Ok((
+        [
+            (CONTENT_TYPE, "application/octet-stream".to_owned()),
+            (CONTENT_LENGTH, size.to_string()),
+            (CONTENT_DISPOSITION, disposition),
+        ],
+        StreamBody::new(file_chunks(snapshot)),
+    ))
+}
+
+async fn upload_snapshot(
+    State(api): State<DbSnapshotApi>,
+    headers: HeaderMap,
+) -> Result<String, (StatusCode, String)> {
+    authorize(&api.api_key, &headers)?;
+    let writer = api.writer.ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            "No snapshot location is configured".to_string(),
+        )
+    })?;
+    let (snapshot, size) = take_snapshot(api.db).await?;
+    let key = format!("db_snapshots/{}", snapshot_name(api.agent));
+    writer
+        .write_object_from_file(&key, snapshot, size)
+        .await
+        .map_err(|err| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to write snapshot: {err}"),
+            )
+        })?;
+    info!(key, size, "Wrote database snapshot");
+    Ok(key)
+}
+
+impl DbSnapshotApi {
+    fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(download_snapshot).post(upload_snapshot))
+            .with_state(self.clone())
+    }
+
+    /// The base path and router serving the snapshot API
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (DB_SNAPSHOT_API_BASE, self.router())
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use axum::{extract::State, http::StatusCode, routing::get, Router};
+use tokio::task::JoinHandle;
+
+static HEALTH: OnceLock<AgentHealth> = OnceLock::new();
+
+/// The readiness checks and liveness watchdogs of the agent, which are
+/// served by the `HealthServer`
+///
+/// - Readiness: the agent is ready once every expected check passed, i.e.
+///   its config loaded, its database opened and an RPC of each of its chains
+///   responded
+/// - Liveness: the agent is healthy as long as every watchdog was fed within
+///   its timeout, e.g. by each contract sync loop
+#[derive(Debug, Default)]
+pub struct AgentHealth {
+    checks: Mutex<BTreeMap<String, bool>>,
+    watchdogs: Mutex<BTreeMap<String, Arc<WatchdogState>>>,
+}
+
+#[derive(Debug)]
+struct WatchdogState {
+    timeout: Duration,
+    last_fed: Mutex<Instant>,
+}
+
+/// A liveness watchdog, which fails the health check if it isn't fed within
+/// its timeout
+#[derive(Debug, Clone)]
+pub struct Watchdog(Arc<WatchdogState>);
+
+impl Watchdog {
+    /// Records that the watched loop made progress
+    pub fn feed(&self) {
+        *self.0.last_fed.lock().expect("poisoned lock") = Instant::now();
+    }
+
+    /// Whether the watchdog wasn't fed within its timeout
+    pub fn is_starving(&self) -> bool {
+        self.0.last_fed.lock().expect("poisoned lock").elapsed() > self.0.timeout
+    }
+}
+
+impl AgentHealth {
+    /// The health of this process' agent
+    pub fn global() -> &'static AgentHealth {
+        HEALTH.get_or_init(Default::default)
+    }
+
+    /// Registers a readiness check that has to pass before the agent is
+    /// ready. Checks that already passed stay passed.
+    pub fn expect(&self, check: impl Into<String>) {
+        self.checks
+            .lock()
+            .expect("poisoned lock")
+            .entry(check.into())
+            .or_insert(false);
+    }
+
+    /// Records that a readiness check passed
+    pub fn pass(&self, check: impl Into<String>) {
+        self.checks
+            .lock()
+            .expect("poisoned lock")
+            .insert(check.into(), true);
+    }
+
+    /// The watchdog registered under `name`, which is registered with
+    /// `timeout` if it isn't yet. It's fed either way, so that both a
+    /// supervisor and the loop it watches can get it.
+    pub fn watchdog(&self, name: impl Into<String>, timeout: Duration) -> Watchdog {
+        let watchdog = Watchdog(
+            self.watchdogs
+                .lock()
+                .expect("poisoned lock")
+                .entry(name.into())
+                .or_insert_with(|| {
+                    Arc::new(WatchdogState {
+                        timeout,
+                        last_fed: Mutex::new(Instant::now()),
+                    })
+                })
+                .clone(),
+        );
+        watchdog.feed();
+        watchdog
+    }
+
+    /// Unregisters the watchdog under `name`, e.g. when the loop it watches
+    /// is stopped on purpose
+    pub fn remove_watchdog(&self, name: &str) {
+        self.watchdogs.lock().expect("poisoned lock").remove(name);
+    }
+
+    /// Whether the agent is ready, with the status of every check
+    pub fn readiness(&self) -> (bool, Vec<(String, bool)>) {
+        let checks = self.checks.lock().expect("poisoned lock");
+        // Not ready before the agent registered what it waits for
+        let ready = !checks.is_empty() && checks.values().all(|passed| *passed);
+        let statuses = checks
+            .iter()
+            .map(|(check, passed)| (check.clone(), *passed))
+            .collect();
+        (ready, statuses)
+    }
+
+    /// Whether the agent is healthy, with the status of every watchdog
+    pub fn liveness(&self) -> (bool, Vec<(String, bool)>) {
+        let watchdogs = self.watchdogs.lock().expect("poisoned lock");
+        let statuses: Vec<_> = watchdogs
+            .iter()
+            .map(|(name, state)| (name.clone(), !Watchdog(state.clone()).is_starving()))
+            .collect();
+        let healthy = statuses.iter().all(|(_, alive)| *alive);
+        (healthy, statuses)
+    }
+}
+
+/// A server for the probes of orchestrators like Kubernetes, on its own port
+/// so that probing it doesn't depend on the metrics
+///
+/// - `GET /healthz` - 200 if every liveness watchdog was fed in time, 503
+///   otherwise
+/// - `GET /readyz` - 200 once every readiness check passed, 503 before
+///
+/// Both list the status of every watchdog or check in the response body.
+#[derive(Debug)]
+pub struct HealthServer {
+    listen_port: u16,
+    health: &'static AgentHealth,
+}
+
+impl HealthServer {
+    /// Creates a new instance of the `HealthServer`
+    pub fn new(listen_port: u16, health: &'static AgentHealth) -> Self {
+        Self {
+            listen_port,
+            health,
+        }
+    }
+
+    fn router(&self) -> Router {
+        Router::new()
+            .route("/healthz", get(healthz))
+            .route("/readyz", get(readyz))
+            .with_state(self.health)
+    }
+
+    /// Run the HTTP server
+    pub fn run(self) -> JoinHandle<()> {
+        let port = self.listen_port;
+        tracing::info!(port, "starting health server on 0.0.0.0");
+        let app = self.router();
+        tokio::spawn(async move {
+            let addr = SocketAddr::from(([0, 0, 0, 0], port));
+            axum::Server::bind(&addr)
+                .serve(app.into_make_service())
+                .await
+                .expect("Failed to start health server");
+        })
+    }
+}
+
+fn probe_response(ok: bool, statuses: Vec<(String, bool)>) -> (StatusCode, String) {
+    let status = if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let body = statuses
+        .iter()
+        .map(|(name, ok)| format!("[{}] {name}\n", if *ok { "+" } else { "-" }))
+        .collect();
+    (status, body)
+}
+
+async fn healthz(State(health): State<&'static AgentHealth>) -> (StatusCode, String) {
+    let (healthy, statuses) = health.liveness();
+    probe_response(healthy, statuses)
+}
+
+async fn readyz(State(health): State<&'static AgentHealth>) -> (StatusCode, String) {
+    let (ready, statuses) = health.readiness();
+    probe_response(ready, statuses)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ready_once_every_check_passed() {
+        let health = AgentHealth::default();
+        assert!(!health.readiness().0);
+
+        health.expect("config");
+        health.expect("rpc:test1");
+        health.pass("config");
+        let (ready, statuses) = health.readiness();
+        assert!(!ready);
+        assert_eq!(
+            statuses,
+            vec![("config".to_owned(), true), ("rpc:test1".to_owned(), false)]
+        );
+
+        health.pass("rpc:test1");
+        // Expecting a passed check again doesn't reset it
+        health.expect("rpc:test1");
+        assert!(health.readiness().0);
+    }
+
+    #[test]
+    fn unhealthy_when_a_watchdog_starves() {
+        let health = AgentHealth::default();
+        assert!(health.liveness().0);
+
+        let fed = health.watchdog("fed", Duration::from_secs(60));
+        let starving = health.watchdog("starving", Duration::from_millis(50));
+        std::thread::sleep(Duration::from_millis(60));
+        fed.feed();
+        let (healthy, statuses) = health.liveness();
+        assert!(!healthy);
+        assert_eq!(
+            statuses,
+            vec![("fed".to_owned(), true), ("starving".to_owned(), false)]
+        );
+
+        // Getting the watchdog again feeds the same one
+        health.watchdog("starving", Duration::from_secs(60));
+        assert!(!starving.is_starving());
+        assert!(health.liveness().0);
+
+        health.remove_watchdog("fed");
+        assert_eq!(health.liveness().1.len(), 1);
+    }
+}
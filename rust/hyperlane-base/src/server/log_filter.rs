@@ -0,0 +1,88 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing, Router,
+};
+use derive_new::new;
+
+use super::authorize;
+use crate::settings::LogFilterHandle;
+
+const LOG_FILTER_API_BASE: &str = "/admin/log_filter";
+
+/// Routes for inspecting and changing the log filter at runtime. Requests
+/// must carry the api key as a bearer token.
+///
+/// - `GET /admin/log_filter` - the currently applied overrides
+/// - `POST /admin/log_filter` - apply the comma-separated `target=level`
+///   directives in the request body on top of the configured filter,
+///   e.g. `relayer::msg::op_submitter=debug`
+/// - `DELETE /admin/log_filter` - go back to the configured filter
+#[derive(new, Clone, Debug)]
+pub struct LogFilterApi {
+    api_key: String,
+    filter: &'static LogFilterHandle,
+}
+
+async fn get_filter(
+    State(api): State<LogFilterApi>,
+    headers: HeaderMap,
+) -> Result<String, (StatusCode, String)> {
+    authorize(&api.api_key, &headers)?;
+    Ok(api
+        .filter
+        .overrides()
+        .unwrap_or_else(|| "No log filter overrides applied".to_owned()))
+}
+
+async fn set_filter(
+    State(api): State<LogFilterApi>,
+    headers: HeaderMap,
+    directives: String,
+) -> Result<String, (StatusCode, String)> {
+    authorize(&api.api_key, &headers)?;
+    match api.filter.set_overrides(directives.trim()) {
+        Ok(()) => Ok("Applied log filter overrides".to_owned()),
+        Err(err) => Err((
+            StatusCode::BAD_REQUEST,
+            format!("Failed to apply log filter overrides: {err}"),
+        )),
+    }
+}
+
+async fn reset_filter(
+    State(api): State<LogFilterApi>,
+    headers: HeaderMap,
+) -> Result<String, (StatusCode, String)> {
+    authorize(&api.api_key, &headers)?;
+    match api.filter.reset() {
+        Ok(()) => Ok("Reset log filter".to_owned()),
+        Err(err) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to reset log filter: {err}"),
+        )),
+    }
+}
+
+impl LogFilterApi {
+    /// The API changing the global log filter, if one is installed
+    pub fn global(api_key: String) -> Option<Self> {
+        LogFilterHandle::global().map(|filter| Self::new(api_key, filter))
+    }
+
+    fn router(&self) -> Router {
+        Router::new()
+            .route(
+                "/",
+                routing::get(get_filter)
+                    .post(set_filter)
+                    .delete(reset_filter),
+            )
+            .with_state(self.clone())
+    }
+
+    /// The base path and router serving the log filter API
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (LOG_FILTER_API_BASE, self.router())
+    }
+}
@@ -1,2 +1,14 @@
+mod auth;
+pub use auth::authorize;
+
 mod base_server;
 pub use base_server::Server;
+
+mod db_snapshot;
+pub use db_snapshot::DbSnapshotApi;
+
+mod health;
+pub use health::{AgentHealth, HealthServer, Watchdog};
+
+mod log_filter;
+pub use log_filter::LogFilterApi;
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fmt::Debug, hash::Hash, sync::Arc};
+use std::{collections::HashMap, fmt::Debug, hash::Hash, sync::Arc, time::Duration};
 
 use eyre::{eyre, Context, Result};
 use futures_util::future::try_join_all;
@@ -10,7 +10,7 @@ use hyperlane_core::{
 
 use crate::{
     cursors::{CursorType, Indexable},
-    settings::{chains::ChainConf, trace::TracingConfig},
+    settings::{chains::ChainConf, trace::TracingConfig, OtlpConf},
     ContractSync, ContractSyncMetrics, ContractSyncer, CoreMetrics, HyperlaneAgentCore,
     SequenceAwareLogStore, SequencedDataContractSync, Server, WatermarkContractSync,
     WatermarkLogStore,
@@ -48,8 +48,15 @@ pub struct Settings {
     pub chains: HashMap<String, ChainConf>,
     /// Port to listen for prometheus scrape requests
     pub metrics_port: u16,
+    /// Port to serve the `/healthz` and `/readyz` probes on, if any
+    pub health_port: Option<u16>,
     /// The tracing configuration
     pub tracing: TracingConfig,
+    /// Export of traces and metrics to an OpenTelemetry collector, if any
+    pub otlp: Option<OtlpConf>,
+    /// How often the config files are checked for changes to reload, if at
+    /// all. The config is reloaded on SIGHUP either way.
+    pub config_watch_interval: Option<Duration>,
 }
 
 impl Settings {
@@ -103,13 +110,16 @@ impl Settings {
         Ok(Arc::new(Server::new(self.metrics_port, core_metrics)))
     }
 
-    /// Private to preserve linearity of AgentCore::from_settings -- creating an
-    /// agent consumes the settings.
-    fn clone(&self) -> Self {
+    /// Not public to preserve linearity of AgentCore::from_settings -- creating
+    /// an agent consumes the settings.
+    pub(crate) fn clone(&self) -> Self {
         Self {
             chains: self.chains.clone(),
             metrics_port: self.metrics_port,
+            health_port: self.health_port,
             tracing: self.tracing.clone(),
+            otlp: self.otlp.clone(),
+            config_watch_interval: self.config_watch_interval,
         }
     }
 }
@@ -167,12 +177,21 @@ impl Settings {
         let setup = self.chain_setup(domain)?;
         // Currently, all indexers are of the `SequenceIndexer` type
         let indexer = SequenceIndexer::<T>::try_from_with_metrics(setup, metrics).await?;
-        Ok(Arc::new(ContractSync::new(
+        let mut sync = ContractSync::new(
             domain.clone(),
             db.clone() as SequenceAwareLogStore<_>,
             indexer,
             sync_metrics.clone(),
-        )))
+        );
+        if let Some(archive_setup) = setup.archive_setup() {
+            let backfill_indexer =
+                SequenceIndexer::<T>::try_from_with_metrics(&archive_setup, metrics).await?;
+            sync = sync.with_backfill_indexer(backfill_indexer);
+            if let Some(lag) = setup.index.archive_switchover_blocks {
+                sync = sync.with_archive_switchover(lag);
+            }
+        }
+        Ok(Arc::new(sync))
     }
 
     /// Build a contract sync for type `T` using log store `D`
@@ -191,12 +210,24 @@ impl Settings {
         let setup = self.chain_setup(domain)?;
         // Currently, all indexers are of the `SequenceIndexer` type
         let indexer = SequenceIndexer::<T>::try_from_with_metrics(setup, metrics).await?;
-        Ok(Arc::new(ContractSync::new(
+        let mut sync = ContractSync::new(
             domain.clone(),
             db.clone() as WatermarkLogStore<_>,
             indexer,
             sync_metrics.clone(),
-        )))
+        );
+        // Watermark cursors don't skip sequences, so the archival RPCs are
+        // only used to catch up with the tip
+        if let (Some(archive_setup), Some(lag)) =
+            (setup.archive_setup(), setup.index.archive_switchover_blocks)
+        {
+            let archive_indexer =
+                SequenceIndexer::<T>::try_from_with_metrics(&archive_setup, metrics).await?;
+            sync = sync
+                .with_backfill_indexer(archive_indexer)
+                .with_archive_switchover(lag);
+        }
+        Ok(Arc::new(sync))
     }
 
     /// Build multiple contract syncs.
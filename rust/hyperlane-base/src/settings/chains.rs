@@ -1,25 +1,62 @@
 use axum::async_trait;
 use ethers::prelude::Selector;
-use h_cosmos::CosmosProvider;
-use std::{collections::HashMap, sync::Arc};
-
-use eyre::{eyre, Context, Result};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+#[cfg(any(
+    feature = "fuel",
+    feature = "sealevel",
+    feature = "cosmos",
+    feature = "starknet",
+    feature = "aptos",
+    feature = "sui",
+    feature = "ton",
+    feature = "substrate",
+    feature = "tron",
+    feature = "stellar",
+    feature = "near",
+    feature = "algorand"
+))]
+use eyre::eyre;
+use eyre::{Context, Result};
 
 use ethers_prometheus::middleware::{ChainInfo, ContractInfo, PrometheusMiddlewareConf};
+#[cfg(feature = "algorand")]
+use hyperlane_algorand as h_algorand;
+#[cfg(feature = "aptos")]
+use hyperlane_aptos as h_aptos;
 use hyperlane_core::{
-    config::OperationBatchConfig, AggregationIsm, CcipReadIsm, ContractLocator, HyperlaneAbi,
-    HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneMessage, HyperlaneProvider, IndexMode,
-    InterchainGasPaymaster, InterchainGasPayment, InterchainSecurityModule, Mailbox,
-    MerkleTreeHook, MerkleTreeInsertion, MultisigIsm, RoutingIsm, SequenceAwareIndexer,
-    ValidatorAnnounce, H256,
+    config::OperationBatchConfig, AggregationIsm, CcipReadIsm, ContractLocator, Finality,
+    FinalitySource, HyperlaneAbi, HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneMessage,
+    HyperlaneProvider, IndexMode, InterchainGasPaymaster, InterchainGasPayment,
+    InterchainSecurityModule, Mailbox, MerkleTreeHook, MerkleTreeInsertion, MultisigIsm,
+    OptimisticIsm, PausableHook, PostDispatchHook, ProtocolFeeHook, RoutingIsm,
+    SequenceAwareIndexer, ValidatorAnnounce, H256, U256,
 };
+#[cfg(feature = "cosmos")]
 use hyperlane_cosmos as h_cosmos;
 use hyperlane_ethereum::{
     self as h_eth, BuildableWithProvider, EthereumInterchainGasPaymasterAbi, EthereumMailboxAbi,
     EthereumValidatorAnnounceAbi,
 };
+#[cfg(feature = "fuel")]
 use hyperlane_fuel as h_fuel;
+#[cfg(feature = "near")]
+use hyperlane_near as h_near;
+#[cfg(feature = "sealevel")]
 use hyperlane_sealevel as h_sealevel;
+#[cfg(feature = "starknet")]
+use hyperlane_starknet as h_starknet;
+#[cfg(feature = "stellar")]
+use hyperlane_stellar as h_stellar;
+#[cfg(feature = "substrate")]
+use hyperlane_substrate as h_substrate;
+#[cfg(feature = "sui")]
+use hyperlane_sui as h_sui;
+#[cfg(feature = "ton")]
+use hyperlane_ton as h_ton;
+#[cfg(feature = "tron")]
+use hyperlane_tron as h_tron;
+use url::Url;
 
 use crate::{
     metrics::AgentMetricsConf,
@@ -42,10 +79,15 @@ pub trait TryFromWithMetrics<T>: Sized {
 pub struct ChainConf {
     /// The domain
     pub domain: HyperlaneDomain,
+    /// The id the chain identifies itself with, if it's numeric
+    pub chain_id: Option<u64>,
     /// Signer configuration for this chain
     pub signer: Option<SignerConf>,
     /// The reorg period of the chain, i.e. the number of blocks until finality
     pub reorg_period: u32,
+    /// How blocks that can no longer be reorged are determined, which
+    /// defaults to the reorg period
+    pub finality: Finality,
     /// Addresses of contracts on the chain
     pub addresses: CoreContractAddresses,
     /// The chain connection details
@@ -56,6 +98,15 @@ pub struct ChainConf {
     pub metrics_conf: PrometheusMiddlewareConf,
     /// Settings for event indexing
     pub index: IndexSettings,
+    /// The balance of the signer, in whole native tokens, below which
+    /// agents warn that it needs to be topped up
+    pub min_signer_balance: Option<f64>,
+    /// How operations to the chain are retried, if not with the default
+    /// schedule of the agent
+    pub retry_policy: Option<RetryPolicyConf>,
+    /// How the estimated gas limits of operations to the chain are adjusted,
+    /// if at all
+    pub gas_limit_policy: Option<GasLimitPolicyConf>,
 }
 
 /// A sequence-aware indexer for messages
@@ -108,11 +159,41 @@ pub enum ChainConnectionConf {
     /// Ethereum configuration
     Ethereum(h_eth::ConnectionConf),
     /// Fuel configuration
+    #[cfg(feature = "fuel")]
     Fuel(h_fuel::ConnectionConf),
     /// Sealevel configuration.
+    #[cfg(feature = "sealevel")]
     Sealevel(h_sealevel::ConnectionConf),
     /// Cosmos configuration.
+    #[cfg(feature = "cosmos")]
     Cosmos(h_cosmos::ConnectionConf),
+    /// Starknet configuration.
+    #[cfg(feature = "starknet")]
+    Starknet(h_starknet::ConnectionConf),
+    /// Aptos configuration.
+    #[cfg(feature = "aptos")]
+    Aptos(h_aptos::ConnectionConf),
+    /// Sui configuration.
+    #[cfg(feature = "sui")]
+    Sui(h_sui::ConnectionConf),
+    /// TON configuration.
+    #[cfg(feature = "ton")]
+    Ton(h_ton::ConnectionConf),
+    /// Substrate configuration.
+    #[cfg(feature = "substrate")]
+    Substrate(h_substrate::ConnectionConf),
+    /// Tron configuration.
+    #[cfg(feature = "tron")]
+    Tron(h_tron::ConnectionConf),
+    /// Stellar configuration.
+    #[cfg(feature = "stellar")]
+    Stellar(h_stellar::ConnectionConf),
+    /// NEAR configuration.
+    #[cfg(feature = "near")]
+    Near(h_near::ConnectionConf),
+    /// Algorand configuration.
+    #[cfg(feature = "algorand")]
+    Algorand(h_algorand::ConnectionConf),
 }
 
 impl ChainConnectionConf {
@@ -120,9 +201,30 @@ impl ChainConnectionConf {
     pub fn protocol(&self) -> HyperlaneDomainProtocol {
         match self {
             Self::Ethereum(_) => HyperlaneDomainProtocol::Ethereum,
+            #[cfg(feature = "fuel")]
             Self::Fuel(_) => HyperlaneDomainProtocol::Fuel,
+            #[cfg(feature = "sealevel")]
             Self::Sealevel(_) => HyperlaneDomainProtocol::Sealevel,
+            #[cfg(feature = "cosmos")]
             Self::Cosmos(_) => HyperlaneDomainProtocol::Cosmos,
+            #[cfg(feature = "starknet")]
+            Self::Starknet(_) => HyperlaneDomainProtocol::Starknet,
+            #[cfg(feature = "aptos")]
+            Self::Aptos(_) => HyperlaneDomainProtocol::Aptos,
+            #[cfg(feature = "sui")]
+            Self::Sui(_) => HyperlaneDomainProtocol::Sui,
+            #[cfg(feature = "ton")]
+            Self::Ton(_) => HyperlaneDomainProtocol::Ton,
+            #[cfg(feature = "substrate")]
+            Self::Substrate(_) => HyperlaneDomainProtocol::Substrate,
+            #[cfg(feature = "tron")]
+            Self::Tron(_) => HyperlaneDomainProtocol::Tron,
+            #[cfg(feature = "stellar")]
+            Self::Stellar(_) => HyperlaneDomainProtocol::Stellar,
+            #[cfg(feature = "near")]
+            Self::Near(_) => HyperlaneDomainProtocol::Near,
+            #[cfg(feature = "algorand")]
+            Self::Algorand(_) => HyperlaneDomainProtocol::Algorand,
         }
     }
 
@@ -130,9 +232,30 @@ impl ChainConnectionConf {
     pub fn operation_batch_config(&self) -> Option<&OperationBatchConfig> {
         match self {
             Self::Ethereum(conf) => Some(&conf.operation_batch),
+            #[cfg(feature = "cosmos")]
             Self::Cosmos(conf) => Some(&conf.operation_batch),
+            #[cfg(feature = "sealevel")]
             Self::Sealevel(conf) => Some(&conf.operation_batch),
-            _ => None,
+            #[cfg(feature = "fuel")]
+            Self::Fuel(_) => None,
+            #[cfg(feature = "starknet")]
+            Self::Starknet(conf) => Some(&conf.operation_batch),
+            #[cfg(feature = "aptos")]
+            Self::Aptos(conf) => Some(&conf.operation_batch),
+            #[cfg(feature = "sui")]
+            Self::Sui(conf) => Some(&conf.operation_batch),
+            #[cfg(feature = "ton")]
+            Self::Ton(conf) => Some(&conf.operation_batch),
+            #[cfg(feature = "substrate")]
+            Self::Substrate(conf) => Some(&conf.operation_batch),
+            #[cfg(feature = "tron")]
+            Self::Tron(conf) => Some(&conf.operation_batch),
+            #[cfg(feature = "stellar")]
+            Self::Stellar(conf) => Some(&conf.operation_batch),
+            #[cfg(feature = "near")]
+            Self::Near(conf) => Some(&conf.operation_batch),
+            #[cfg(feature = "algorand")]
+            Self::Algorand(conf) => Some(&conf.operation_batch),
         }
     }
 }
@@ -159,6 +282,106 @@ pub struct IndexSettings {
     pub chunk_size: u32,
     /// The indexing mode.
     pub mode: IndexMode,
+    /// After how many consecutive rewinds on the same missing sequence the
+    /// relayer's sequence-aware cursors skip it and carry on. The skipped
+    /// range is backfilled in the background. If unset, or for other agents,
+    /// cursors rewind until the sequence is indexed.
+    pub skip_gaps_after_rewinds: Option<u32>,
+    /// Urls of archival RPCs to backfill skipped ranges from. If empty, they
+    /// are backfilled from the regular RPCs.
+    pub archive_rpc_urls: Vec<Url>,
+    /// How many blocks behind the tip the indexed ranges have to be to be
+    /// queried from the archival RPCs. If unset, ranges are only queried from
+    /// them to backfill skipped sequences.
+    pub archive_switchover_blocks: Option<u32>,
+}
+
+/// Exponential backoff of operations to a chain. Slow-finality chains
+/// usually need longer delays than fast L2s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicyConf {
+    /// The delay after the first failed attempt, which doubles after every
+    /// further one
+    pub base_delay: Duration,
+    /// The longest delay between attempts
+    pub max_delay: Duration,
+    /// The fraction by which delays are randomly lengthened or shortened, so
+    /// that operations that failed together aren't all retried together
+    pub jitter: f64,
+    /// Operations are given up on after this many failed attempts
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for RetryPolicyConf {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(60 * 60 * 3),
+            jitter: 0.,
+            max_attempts: None,
+        }
+    }
+}
+
+impl RetryPolicyConf {
+    /// The delay before the next attempt of an operation that failed
+    /// `num_retries` times. `jitter_seed` spreads the delays of different
+    /// operations, e.g. it can be derived from their id.
+    pub fn backoff(&self, num_retries: u32, jitter_seed: u64) -> Option<Duration> {
+        if num_retries < 1 {
+            return None;
+        }
+        let factor = 2u32.checked_pow(num_retries - 1).unwrap_or(u32::MAX);
+        let delay = self
+            .base_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+        if self.jitter <= 0. {
+            return Some(delay);
+        }
+        // A splitmix64 step, so that consecutive retries of the same
+        // operation get unrelated jitter
+        let mut x = jitter_seed ^ (num_retries as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        x ^= x >> 31;
+        let unit = x as f64 / u64::MAX as f64;
+        let scale = 1. + self.jitter.min(1.) * (2. * unit - 1.);
+        Some(delay.mul_f64(scale))
+    }
+}
+
+/// Adjusts the estimated gas limits of operations, e.g. for recipients whose
+/// gas usage `eth_estimateGas` underestimates on some chains.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GasLimitPolicyConf {
+    /// The factor the estimated gas limit is multiplied by
+    pub multiplier: Option<f64>,
+    /// The lowest gas limit operations are submitted with
+    pub floor: Option<U256>,
+    /// The highest gas limit operations are submitted with
+    pub cap: Option<U256>,
+}
+
+impl GasLimitPolicyConf {
+    /// The gas limit to submit an operation with, given its estimate. The
+    /// multiplier is applied first, then the floor and the cap.
+    pub fn apply(&self, estimate: U256) -> U256 {
+        let mut gas_limit = match self.multiplier {
+            Some(multiplier) => {
+                estimate.saturating_mul(U256::from((multiplier * 10_000.) as u64)) / 10_000
+            }
+            None => estimate,
+        };
+        if let Some(floor) = self.floor {
+            gas_limit = gas_limit.max(floor);
+        }
+        if let Some(cap) = self.cap {
+            gas_limit = gas_limit.min(cap);
+        }
+        gas_limit
+    }
 }
 
 impl ChainConf {
@@ -167,6 +390,26 @@ impl ChainConf {
         self.index.clone()
     }
 
+    /// The settings to backfill skipped ranges with, which connect to the
+    /// archival RPCs instead of the regular ones. Only supported for EVM
+    /// chains.
+    pub fn archive_setup(&self) -> Option<ChainConf> {
+        if self.index.archive_rpc_urls.is_empty() {
+            return None;
+        }
+        let ChainConnectionConf::Ethereum(conf) = &self.connection else {
+            return None;
+        };
+        let mut setup = self.clone();
+        setup.connection = ChainConnectionConf::Ethereum(h_eth::ConnectionConf {
+            rpc_connection: h_eth::RpcConnectionConf::HttpFallback {
+                urls: self.index.archive_rpc_urls.clone(),
+            },
+            ..conf.clone()
+        });
+        Some(setup)
+    }
+
     /// Try to convert the chain settings into an HyperlaneProvider.
     pub async fn build_provider(
         &self,
@@ -179,13 +422,19 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::HyperlaneProviderBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(conf) => Ok(Box::new(h_fuel::FuelProvider::new(
+                locator.domain.clone(),
+                conf,
+            )?) as Box<dyn HyperlaneProvider>),
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(conf) => Ok(Box::new(h_sealevel::SealevelProvider::new(
                 locator.domain.clone(),
                 conf,
             )) as Box<dyn HyperlaneProvider>),
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
-                let provider = CosmosProvider::new(
+                let provider = h_cosmos::CosmosProvider::new(
                     locator.domain.clone(),
                     conf.clone(),
                     Some(locator.clone()),
@@ -193,6 +442,90 @@ impl ChainConf {
                 )?;
                 Ok(Box::new(provider) as Box<dyn HyperlaneProvider>)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => Ok(Box::new(h_starknet::StarknetProvider::new(
+                locator.domain.clone(),
+                conf,
+                None,
+            )) as Box<dyn HyperlaneProvider>),
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(conf) => Ok(Box::new(h_aptos::AptosProvider::new(
+                locator.domain.clone(),
+                conf,
+                None,
+            )) as Box<dyn HyperlaneProvider>),
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(conf) => Ok(Box::new(h_sui::SuiProvider::new(
+                locator.domain.clone(),
+                conf,
+                None,
+            )) as Box<dyn HyperlaneProvider>),
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(conf) => Ok(Box::new(h_ton::TonProvider::new(
+                locator.domain.clone(),
+                conf,
+                None,
+            )) as Box<dyn HyperlaneProvider>),
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(conf) => Ok(Box::new(
+                h_substrate::SubstrateProvider::new(locator.domain.clone(), conf, None),
+            ) as Box<dyn HyperlaneProvider>),
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => Ok(Box::new(h_tron::TronProvider::new(
+                locator.domain.clone(),
+                conf,
+                None,
+            )?) as Box<dyn HyperlaneProvider>),
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(conf) => Ok(Box::new(h_stellar::StellarProvider::new(
+                locator.domain.clone(),
+                conf,
+                None,
+            )) as Box<dyn HyperlaneProvider>),
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(conf) => Ok(Box::new(h_near::NearProvider::new(
+                locator.domain.clone(),
+                conf,
+                None,
+            )) as Box<dyn HyperlaneProvider>),
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(conf) => Ok(Box::new(h_algorand::AlgorandProvider::new(
+                locator.domain.clone(),
+                conf,
+                None,
+            )) as Box<dyn HyperlaneProvider>),
+        }
+        .context(ctx)
+    }
+
+    /// Try to build the source the finality policy of the chain is evaluated
+    /// against. Only EVM chains support policies other than block counts.
+    pub async fn build_finality_source(
+        &self,
+        metrics: &CoreMetrics,
+    ) -> Result<Box<dyn FinalitySource>> {
+        let ctx = "Building finality source";
+        let locator = self.locator(H256::zero());
+        match &self.connection {
+            ChainConnectionConf::Ethereum(conf) => {
+                self.build_ethereum(conf, &locator, metrics, h_eth::FinalitySourceBuilder {})
+                    .await
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::FinalitySourceBuilder {},
+                )
+                .await
+            }
+            #[allow(unreachable_patterns)]
+            conn => Err(eyre!(
+                "{} does not support finality policies other than block counts",
+                conn.protocol()
+            )),
         }
         .context(ctx)
     }
@@ -207,24 +540,90 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::MailboxBuilder {})
                     .await
             }
+            #[cfg(feature = "fuel")]
             ChainConnectionConf::Fuel(conf) => {
                 let wallet = self.fuel_signer().await.context(ctx)?;
                 hyperlane_fuel::FuelMailbox::new(conf, locator, wallet)
                     .map(|m| Box::new(m) as Box<dyn Mailbox>)
                     .map_err(Into::into)
             }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(conf) => {
                 let keypair = self.sealevel_signer().await.context(ctx)?;
                 h_sealevel::SealevelMailbox::new(conf, locator, keypair)
                     .map(|m| Box::new(m) as Box<dyn Mailbox>)
                     .map_err(Into::into)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 h_cosmos::CosmosMailbox::new(conf.clone(), locator.clone(), signer.clone())
                     .map(|m| Box::new(m) as Box<dyn Mailbox>)
                     .map_err(Into::into)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => {
+                let signer = self.starknet_signer().await.context(ctx)?;
+                h_starknet::StarknetMailbox::new(conf, locator, signer)
+                    .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    .map_err(Into::into)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(conf) => {
+                let signer = self.aptos_signer().await.context(ctx)?;
+                h_aptos::AptosMailbox::new(conf, locator, signer)
+                    .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    .map_err(Into::into)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(conf) => {
+                let signer = self.sui_signer().await.context(ctx)?;
+                h_sui::SuiMailbox::new(conf, locator, signer)
+                    .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    .map_err(Into::into)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(conf) => {
+                let signer = self.ton_signer().await.context(ctx)?;
+                h_ton::TonMailbox::new(conf, locator, signer)
+                    .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    .map_err(Into::into)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(conf) => {
+                let signer = self.substrate_signer().await.context(ctx)?;
+                h_substrate::SubstrateMailbox::new(conf, locator, signer)
+                    .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    .map_err(Into::into)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                let signer = self.tron_signer().await.context(ctx)?;
+                h_tron::TronMailbox::new(conf, locator, signer)
+                    .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    .map_err(Into::into)
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(conf) => {
+                let signer = self.stellar_signer().await.context(ctx)?;
+                h_stellar::StellarMailbox::new(conf, locator, signer)
+                    .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    .map_err(Into::into)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(conf) => {
+                let signer = self.near_signer().await.context(ctx)?;
+                h_near::NearMailbox::new(conf, locator, signer)
+                    .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    .map_err(Into::into)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(conf) => {
+                let signer = self.algorand_signer().await.context(ctx)?;
+                h_algorand::AlgorandMailbox::new(conf, locator, signer)
+                    .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    .map_err(Into::into)
+            }
         }
         .context(ctx)
     }
@@ -242,14 +641,17 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::MerkleTreeHookBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_conf) => {
-                todo!("Fuel does not support merkle tree hooks yet")
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support merkle tree hooks yet")).context(ctx)
             }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(conf) => {
                 h_sealevel::SealevelMailbox::new(conf, locator, None)
                     .map(|m| Box::new(m) as Box<dyn MerkleTreeHook>)
                     .map_err(Into::into)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let hook =
@@ -257,6 +659,49 @@ impl ChainConf {
 
                 Ok(Box::new(hook) as Box<dyn MerkleTreeHook>)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => {
+                let hook = h_starknet::StarknetMerkleTreeHook::new(conf, locator)?;
+                Ok(Box::new(hook) as Box<dyn MerkleTreeHook>)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support merkle tree hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => {
+                Err(eyre!("Sui does not support merkle tree hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support merkle tree hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => {
+                Err(eyre!("Substrate does not support merkle tree hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::MerkleTreeHookBuilder {},
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => {
+                Err(eyre!("Stellar does not support merkle tree hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support merkle tree hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => {
+                Err(eyre!("Algorand does not support merkle tree hooks yet")).context(ctx)
+            }
         }
         .context(ctx)
     }
@@ -276,16 +721,28 @@ impl ChainConf {
                     &locator,
                     metrics,
                     h_eth::SequenceIndexerBuilder {
-                        reorg_period: self.reorg_period,
+                        finality: self.finality.clone(),
                     },
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(conf) => {
+                // Querying the message count needs a wallet to fund the dry-run
+                let wallet = self.fuel_signer().await.context(ctx)?;
+                let indexer = Box::new(h_fuel::FuelMailboxIndexer::new(
+                    conf,
+                    locator,
+                    Some(wallet),
+                )?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(h_sealevel::SealevelMailboxIndexer::new(conf, locator)?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let indexer = Box::new(h_cosmos::CosmosMailboxIndexer::new(
@@ -296,6 +753,58 @@ impl ChainConf {
                 )?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => {
+                let indexer = Box::new(h_starknet::StarknetMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(conf) => {
+                let indexer = Box::new(h_aptos::AptosMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(conf) => {
+                let indexer = Box::new(h_sui::SuiMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(conf) => {
+                let indexer = Box::new(h_ton::TonMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(conf) => {
+                let indexer = Box::new(h_substrate::SubstrateMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::SequenceIndexerBuilder {
+                        finality: self.finality.clone(),
+                    },
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(conf) => {
+                let indexer = Box::new(h_stellar::StellarMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(conf) => {
+                let indexer = Box::new(h_near::NearMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(conf) => {
+                let indexer = Box::new(h_algorand::AlgorandMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
         }
         .context(ctx)
     }
@@ -315,16 +824,23 @@ impl ChainConf {
                     &locator,
                     metrics,
                     h_eth::DeliveryIndexerBuilder {
-                        reorg_period: self.reorg_period,
+                        finality: self.finality.clone(),
                     },
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(conf) => {
+                let wallet = self.signer().await.context(ctx)?;
+                let indexer = Box::new(h_fuel::FuelMailboxIndexer::new(conf, locator, wallet)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(h_sealevel::SealevelMailboxIndexer::new(conf, locator)?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let indexer = Box::new(h_cosmos::CosmosMailboxIndexer::new(
@@ -335,6 +851,58 @@ impl ChainConf {
                 )?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => {
+                let indexer = Box::new(h_starknet::StarknetMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(conf) => {
+                let indexer = Box::new(h_aptos::AptosMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(conf) => {
+                let indexer = Box::new(h_sui::SuiMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(conf) => {
+                let indexer = Box::new(h_ton::TonMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(conf) => {
+                let indexer = Box::new(h_substrate::SubstrateMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::DeliveryIndexerBuilder {
+                        finality: self.finality.clone(),
+                    },
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(conf) => {
+                let indexer = Box::new(h_stellar::StellarMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(conf) => {
+                let indexer = Box::new(h_near::NearMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(conf) => {
+                let indexer = Box::new(h_algorand::AlgorandMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
         }
         .context(ctx)
     }
@@ -358,13 +926,19 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(conf) => {
+                let paymaster = Box::new(h_fuel::FuelInterchainGasPaymaster::new(conf, locator)?);
+                Ok(paymaster as Box<dyn InterchainGasPaymaster>)
+            }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(conf) => {
                 let paymaster = Box::new(
                     h_sealevel::SealevelInterchainGasPaymaster::new(conf, &locator).await?,
                 );
                 Ok(paymaster as Box<dyn InterchainGasPaymaster>)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let paymaster = Box::new(h_cosmos::CosmosInterchainGasPaymaster::new(
@@ -374,6 +948,55 @@ impl ChainConf {
                 )?);
                 Ok(paymaster as Box<dyn InterchainGasPaymaster>)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => {
+                let paymaster = Box::new(h_starknet::StarknetInterchainGasPaymaster::new(
+                    conf, locator,
+                )?);
+                Ok(paymaster as Box<dyn InterchainGasPaymaster>)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => Err(eyre!(
+                "Aptos does not support interchain gas paymasters yet"
+            ))
+            .context(ctx),
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => {
+                Err(eyre!("Sui does not support interchain gas paymasters yet")).context(ctx)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support interchain gas paymasters yet")).context(ctx)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => Err(eyre!(
+                "Substrate does not support interchain gas paymasters yet"
+            ))
+            .context(ctx),
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::InterchainGasPaymasterBuilder {},
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => Err(eyre!(
+                "Stellar does not support interchain gas paymasters yet"
+            ))
+            .context(ctx),
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support interchain gas paymasters yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => Err(eyre!(
+                "Algorand does not support interchain gas paymasters yet"
+            ))
+            .context(ctx),
         }
         .context(ctx)
     }
@@ -394,18 +1017,26 @@ impl ChainConf {
                     metrics,
                     h_eth::InterchainGasPaymasterIndexerBuilder {
                         mailbox_address: self.addresses.mailbox.into(),
-                        reorg_period: self.reorg_period,
+                        finality: self.finality.clone(),
                     },
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(conf) => {
+                let indexer = Box::new(h_fuel::FuelInterchainGasPaymasterIndexer::new(
+                    conf, locator,
+                )?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<InterchainGasPayment>>)
+            }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(
                     h_sealevel::SealevelInterchainGasPaymasterIndexer::new(conf, locator).await?,
                 );
                 Ok(indexer as Box<dyn SequenceAwareIndexer<InterchainGasPayment>>)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
                 let indexer = Box::new(h_cosmos::CosmosInterchainGasPaymasterIndexer::new(
                     conf.clone(),
@@ -414,6 +1045,61 @@ impl ChainConf {
                 )?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<InterchainGasPayment>>)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => {
+                let indexer = Box::new(h_starknet::StarknetInterchainGasPaymasterIndexer::new(
+                    conf, locator,
+                )?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<InterchainGasPayment>>)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => Err(eyre!(
+                "Aptos does not support interchain gas payment indexing yet"
+            ))
+            .context(ctx),
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => Err(eyre!(
+                "Sui does not support interchain gas payment indexing yet"
+            ))
+            .context(ctx),
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => Err(eyre!(
+                "TON does not support interchain gas payment indexing yet"
+            ))
+            .context(ctx),
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => Err(eyre!(
+                "Substrate does not support interchain gas payment indexing yet"
+            ))
+            .context(ctx),
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::InterchainGasPaymasterIndexerBuilder {
+                        mailbox_address: self.addresses.mailbox.into(),
+                        finality: self.finality.clone(),
+                    },
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => Err(eyre!(
+                "Stellar does not support interchain gas payment indexing yet"
+            ))
+            .context(ctx),
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => Err(eyre!(
+                "NEAR does not support interchain gas payment indexing yet"
+            ))
+            .context(ctx),
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => Err(eyre!(
+                "Algorand does not support interchain gas payment indexing yet"
+            ))
+            .context(ctx),
         }
         .context(ctx)
     }
@@ -433,12 +1119,16 @@ impl ChainConf {
                     &locator,
                     metrics,
                     h_eth::MerkleTreeHookIndexerBuilder {
-                        reorg_period: self.reorg_period,
+                        finality: self.finality.clone(),
                     },
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support merkle tree hook indexer yet")).context(ctx)
+            }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(conf) => {
                 let mailbox_indexer =
                     Box::new(h_sealevel::SealevelMailboxIndexer::new(conf, locator)?);
@@ -447,6 +1137,7 @@ impl ChainConf {
                 ));
                 Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let indexer = Box::new(h_cosmos::CosmosMerkleTreeHookIndexer::new(
@@ -458,6 +1149,57 @@ impl ChainConf {
                 )?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => {
+                let indexer = Box::new(h_starknet::StarknetMerkleTreeHookIndexer::new(
+                    conf, locator,
+                )?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => Err(eyre!(
+                "Aptos does not support merkle tree hook indexing yet"
+            ))
+            .context(ctx),
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => {
+                Err(eyre!("Sui does not support merkle tree hook indexing yet")).context(ctx)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support merkle tree hook indexing yet")).context(ctx)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => Err(eyre!(
+                "Substrate does not support merkle tree hook indexing yet"
+            ))
+            .context(ctx),
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::MerkleTreeHookIndexerBuilder {
+                        finality: self.finality.clone(),
+                    },
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => Err(eyre!(
+                "Stellar does not support merkle tree hook indexing yet"
+            ))
+            .context(ctx),
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support merkle tree hook indexing yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => Err(eyre!(
+                "Algorand does not support merkle tree hook indexing yet"
+            ))
+            .context(ctx),
         }
         .context(ctx)
     }
@@ -474,11 +1216,16 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::ValidatorAnnounceBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support validator announce yet")).context(ctx)
+            }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(conf) => {
                 let va = Box::new(h_sealevel::SealevelValidatorAnnounce::new(conf, locator));
                 Ok(va as Box<dyn ValidatorAnnounce>)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let va = Box::new(h_cosmos::CosmosValidatorAnnounce::new(
@@ -489,8 +1236,48 @@ impl ChainConf {
 
                 Ok(va as Box<dyn ValidatorAnnounce>)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => {
+                let signer = self.starknet_signer().await.context(ctx)?;
+                let va = Box::new(h_starknet::StarknetValidatorAnnounce::new(
+                    conf, locator, signer,
+                )?);
+                Ok(va as Box<dyn ValidatorAnnounce>)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support validator announce yet")).context(ctx)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => {
+                Err(eyre!("Sui does not support validator announce yet")).context(ctx)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support validator announce yet")).context(ctx)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => {
+                Err(eyre!("Substrate does not support validator announce yet")).context(ctx)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(_) => {
+                Err(eyre!("Tron does not support validator announce yet")).context(ctx)
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => {
+                Err(eyre!("Stellar does not support validator announce yet")).context(ctx)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support validator announce yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => {
+                Err(eyre!("Algorand does not support validator announce yet")).context(ctx)
+            }
         }
-        .context("Building ValidatorAnnounce")
+        .context(ctx)
     }
 
     /// Try to convert the chain setting into an InterchainSecurityModule
@@ -513,7 +1300,11 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support ISM yet")).context(ctx)
+            }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(conf) => {
                 let keypair = self.sealevel_signer().await.context(ctx)?;
                 let ism = Box::new(h_sealevel::SealevelInterchainSecurityModule::new(
@@ -521,6 +1312,7 @@ impl ChainConf {
                 ));
                 Ok(ism as Box<dyn InterchainSecurityModule>)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let ism = Box::new(h_cosmos::CosmosInterchainSecurityModule::new(
@@ -528,6 +1320,47 @@ impl ChainConf {
                 )?);
                 Ok(ism as Box<dyn InterchainSecurityModule>)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => {
+                let ism = Box::new(h_starknet::StarknetInterchainSecurityModule::new(
+                    conf, locator,
+                )?);
+                Ok(ism as Box<dyn InterchainSecurityModule>)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => Err(eyre!("Sui does not support ISMs yet")).context(ctx),
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => Err(eyre!("TON does not support ISMs yet")).context(ctx),
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => {
+                Err(eyre!("Substrate does not support ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::InterchainSecurityModuleBuilder {},
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => {
+                Err(eyre!("Stellar does not support ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => {
+                Err(eyre!("Algorand does not support ISMs yet")).context(ctx)
+            }
         }
         .context(ctx)
     }
@@ -547,12 +1380,17 @@ impl ChainConf {
                     .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support multisig ISM yet")).context(ctx)
+            }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(conf) => {
                 let keypair = self.sealevel_signer().await.context(ctx)?;
                 let ism = Box::new(h_sealevel::SealevelMultisigIsm::new(conf, locator, keypair));
                 Ok(ism as Box<dyn MultisigIsm>)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let ism = Box::new(h_cosmos::CosmosMultisigIsm::new(
@@ -562,6 +1400,49 @@ impl ChainConf {
                 )?);
                 Ok(ism as Box<dyn MultisigIsm>)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => {
+                let ism = Box::new(h_starknet::StarknetMultisigIsm::new(conf, locator)?);
+                Ok(ism as Box<dyn MultisigIsm>)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support multisig ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => {
+                Err(eyre!("Sui does not support multisig ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support multisig ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => {
+                Err(eyre!("Substrate does not support multisig ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::MultisigIsmBuilder {},
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => {
+                Err(eyre!("Stellar does not support multisig ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support multisig ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => {
+                Err(eyre!("Algorand does not support multisig ISMs yet")).context(ctx)
+            }
         }
         .context(ctx)
     }
@@ -583,10 +1464,15 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::RoutingIsmBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support routing ISM yet")).context(ctx)
+            }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(_) => {
                 Err(eyre!("Sealevel does not support routing ISM yet")).context(ctx)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let ism = Box::new(h_cosmos::CosmosRoutingIsm::new(
@@ -596,6 +1482,49 @@ impl ChainConf {
                 )?);
                 Ok(ism as Box<dyn RoutingIsm>)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => {
+                let ism = Box::new(h_starknet::StarknetRoutingIsm::new(conf, locator)?);
+                Ok(ism as Box<dyn RoutingIsm>)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support routing ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => {
+                Err(eyre!("Sui does not support routing ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support routing ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => {
+                Err(eyre!("Substrate does not support routing ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::RoutingIsmBuilder {},
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => {
+                Err(eyre!("Stellar does not support routing ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support routing ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => {
+                Err(eyre!("Algorand does not support routing ISMs yet")).context(ctx)
+            }
         }
         .context(ctx)
     }
@@ -617,10 +1546,15 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::AggregationIsmBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support aggregation ISM yet")).context(ctx)
+            }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(_) => {
                 Err(eyre!("Sealevel does not support aggregation ISM yet")).context(ctx)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let ism = Box::new(h_cosmos::CosmosAggregationIsm::new(
@@ -631,6 +1565,49 @@ impl ChainConf {
 
                 Ok(ism as Box<dyn AggregationIsm>)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(conf) => {
+                let ism = Box::new(h_starknet::StarknetAggregationIsm::new(conf, locator)?);
+                Ok(ism as Box<dyn AggregationIsm>)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support aggregation ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => {
+                Err(eyre!("Sui does not support aggregation ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support aggregation ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => {
+                Err(eyre!("Substrate does not support aggregation ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::AggregationIsmBuilder {},
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => {
+                Err(eyre!("Stellar does not support aggregation ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support aggregation ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => {
+                Err(eyre!("Algorand does not support aggregation ISMs yet")).context(ctx)
+            }
         }
         .context(ctx)
     }
@@ -652,13 +1629,360 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::CcipReadIsmBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support CCIP read ISM yet")).context(ctx)
+            }
+            #[cfg(feature = "sealevel")]
             ChainConnectionConf::Sealevel(_) => {
                 Err(eyre!("Sealevel does not support CCIP read ISM yet")).context(ctx)
             }
+            #[cfg(feature = "cosmos")]
             ChainConnectionConf::Cosmos(_) => {
                 Err(eyre!("Cosmos does not support CCIP read ISM yet")).context(ctx)
             }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(_) => {
+                Err(eyre!("Starknet does not support CCIP read ISM yet")).context(ctx)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support CCIP read ISM yet")).context(ctx)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => {
+                Err(eyre!("Sui does not support CCIP read ISM yet")).context(ctx)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support CCIP read ISM yet")).context(ctx)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => {
+                Err(eyre!("Substrate does not support CCIP read ISM yet")).context(ctx)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::CcipReadIsmBuilder {},
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => {
+                Err(eyre!("Stellar does not support CCIP read ISM yet")).context(ctx)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support CCIP read ISM yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => {
+                Err(eyre!("Algorand does not support CCIP read ISM yet")).context(ctx)
+            }
+        }
+        .context(ctx)
+    }
+
+    /// Try to convert the chain setting into an Optimistic Ism contract
+    pub async fn build_optimistic_ism(
+        &self,
+        address: H256,
+        metrics: &CoreMetrics,
+    ) -> Result<Box<dyn OptimisticIsm>> {
+        let ctx = "Building Optimistic ISM";
+        let locator = ContractLocator {
+            domain: &self.domain,
+            address,
+        };
+
+        match &self.connection {
+            ChainConnectionConf::Ethereum(conf) => {
+                self.build_ethereum(conf, &locator, metrics, h_eth::OptimisticIsmBuilder {})
+                    .await
+            }
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support optimistic ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "sealevel")]
+            ChainConnectionConf::Sealevel(_) => {
+                Err(eyre!("Sealevel does not support optimistic ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "cosmos")]
+            ChainConnectionConf::Cosmos(_) => {
+                Err(eyre!("Cosmos does not support optimistic ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(_) => {
+                Err(eyre!("Starknet does not support optimistic ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support optimistic ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => {
+                Err(eyre!("Sui does not support optimistic ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support optimistic ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => {
+                Err(eyre!("Substrate does not support optimistic ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::OptimisticIsmBuilder {},
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => {
+                Err(eyre!("Stellar does not support optimistic ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support optimistic ISMs yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => {
+                Err(eyre!("Algorand does not support optimistic ISMs yet")).context(ctx)
+            }
+        }
+        .context(ctx)
+    }
+
+    /// Try to convert the chain setting into a post-dispatch hook contract
+    pub async fn build_post_dispatch_hook(
+        &self,
+        address: H256,
+        metrics: &CoreMetrics,
+    ) -> Result<Box<dyn PostDispatchHook>> {
+        let ctx = "Building post-dispatch hook";
+        let locator = ContractLocator {
+            domain: &self.domain,
+            address,
+        };
+
+        match &self.connection {
+            ChainConnectionConf::Ethereum(conf) => {
+                self.build_ethereum(conf, &locator, metrics, h_eth::PostDispatchHookBuilder {})
+                    .await
+            }
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support post-dispatch hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "sealevel")]
+            ChainConnectionConf::Sealevel(_) => {
+                Err(eyre!("Sealevel does not support post-dispatch hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "cosmos")]
+            ChainConnectionConf::Cosmos(_) => {
+                Err(eyre!("Cosmos does not support post-dispatch hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(_) => {
+                Err(eyre!("Starknet does not support post-dispatch hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support post-dispatch hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => {
+                Err(eyre!("Sui does not support post-dispatch hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support post-dispatch hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => {
+                Err(eyre!("Substrate does not support post-dispatch hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::PostDispatchHookBuilder {},
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => {
+                Err(eyre!("Stellar does not support post-dispatch hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support post-dispatch hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => {
+                Err(eyre!("Algorand does not support post-dispatch hooks yet")).context(ctx)
+            }
+        }
+        .context(ctx)
+    }
+
+    /// Try to convert the chain setting into a PausableHook contract
+    pub async fn build_pausable_hook(
+        &self,
+        address: H256,
+        metrics: &CoreMetrics,
+    ) -> Result<Box<dyn PausableHook>> {
+        let ctx = "Building pausable hook";
+        let locator = ContractLocator {
+            domain: &self.domain,
+            address,
+        };
+
+        match &self.connection {
+            ChainConnectionConf::Ethereum(conf) => {
+                self.build_ethereum(conf, &locator, metrics, h_eth::PausableHookBuilder {})
+                    .await
+            }
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support pausable hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "sealevel")]
+            ChainConnectionConf::Sealevel(_) => {
+                Err(eyre!("Sealevel does not support pausable hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "cosmos")]
+            ChainConnectionConf::Cosmos(_) => {
+                Err(eyre!("Cosmos does not support pausable hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(_) => {
+                Err(eyre!("Starknet does not support pausable hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support pausable hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => {
+                Err(eyre!("Sui does not support pausable hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support pausable hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => {
+                Err(eyre!("Substrate does not support pausable hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::PausableHookBuilder {},
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => {
+                Err(eyre!("Stellar does not support pausable hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support pausable hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => {
+                Err(eyre!("Algorand does not support pausable hooks yet")).context(ctx)
+            }
+        }
+        .context(ctx)
+    }
+
+    /// Try to convert the chain setting into a ProtocolFee hook contract
+    pub async fn build_protocol_fee_hook(
+        &self,
+        address: H256,
+        metrics: &CoreMetrics,
+    ) -> Result<Box<dyn ProtocolFeeHook>> {
+        let ctx = "Building protocol fee hook";
+        let locator = ContractLocator {
+            domain: &self.domain,
+            address,
+        };
+
+        match &self.connection {
+            ChainConnectionConf::Ethereum(conf) => {
+                self.build_ethereum(conf, &locator, metrics, h_eth::ProtocolFeeHookBuilder {})
+                    .await
+            }
+            #[cfg(feature = "fuel")]
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support protocol fee hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "sealevel")]
+            ChainConnectionConf::Sealevel(_) => {
+                Err(eyre!("Sealevel does not support protocol fee hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "cosmos")]
+            ChainConnectionConf::Cosmos(_) => {
+                Err(eyre!("Cosmos does not support protocol fee hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "starknet")]
+            ChainConnectionConf::Starknet(_) => {
+                Err(eyre!("Starknet does not support protocol fee hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "aptos")]
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support protocol fee hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "sui")]
+            ChainConnectionConf::Sui(_) => {
+                Err(eyre!("Sui does not support protocol fee hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "ton")]
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support protocol fee hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "substrate")]
+            ChainConnectionConf::Substrate(_) => {
+                Err(eyre!("Substrate does not support protocol fee hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "tron")]
+            ChainConnectionConf::Tron(conf) => {
+                self.build_ethereum(
+                    &conf.ethereum_conf()?,
+                    &locator,
+                    metrics,
+                    h_eth::ProtocolFeeHookBuilder {},
+                )
+                .await
+            }
+            #[cfg(feature = "stellar")]
+            ChainConnectionConf::Stellar(_) => {
+                Err(eyre!("Stellar does not support protocol fee hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "near")]
+            ChainConnectionConf::Near(_) => {
+                Err(eyre!("NEAR does not support protocol fee hooks yet")).context(ctx)
+            }
+            #[cfg(feature = "algorand")]
+            ChainConnectionConf::Algorand(_) => {
+                Err(eyre!("Algorand does not support protocol fee hooks yet")).context(ctx)
+            }
         }
         .context(ctx)
     }
@@ -676,13 +2000,42 @@ impl ChainConf {
         if let Some(conf) = &self.signer {
             let chain_signer: Box<dyn ChainSigner> = match &self.connection {
                 ChainConnectionConf::Ethereum(_) => Box::new(conf.build::<h_eth::Signers>().await?),
+                #[cfg(feature = "fuel")]
                 ChainConnectionConf::Fuel(_) => {
                     Box::new(conf.build::<fuels::prelude::WalletUnlocked>().await?)
                 }
+                #[cfg(feature = "sealevel")]
                 ChainConnectionConf::Sealevel(_) => {
                     Box::new(conf.build::<h_sealevel::Keypair>().await?)
                 }
+                #[cfg(feature = "cosmos")]
                 ChainConnectionConf::Cosmos(_) => Box::new(conf.build::<h_cosmos::Signer>().await?),
+                #[cfg(feature = "starknet")]
+                ChainConnectionConf::Starknet(_) => {
+                    Box::new(conf.build::<h_starknet::Signer>().await?)
+                }
+                #[cfg(feature = "aptos")]
+                ChainConnectionConf::Aptos(_) => Box::new(conf.build::<h_aptos::Signer>().await?),
+                #[cfg(feature = "sui")]
+                ChainConnectionConf::Sui(_) => Box::new(conf.build::<h_sui::Signer>().await?),
+                #[cfg(feature = "ton")]
+                ChainConnectionConf::Ton(_) => Box::new(conf.build::<h_ton::Signer>().await?),
+                #[cfg(feature = "substrate")]
+                ChainConnectionConf::Substrate(_) => {
+                    Box::new(conf.build::<h_substrate::Signer>().await?)
+                }
+                #[cfg(feature = "tron")]
+                ChainConnectionConf::Tron(_) => Box::new(conf.build::<h_tron::Signer>().await?),
+                #[cfg(feature = "stellar")]
+                ChainConnectionConf::Stellar(_) => {
+                    Box::new(conf.build::<h_stellar::Signer>().await?)
+                }
+                #[cfg(feature = "near")]
+                ChainConnectionConf::Near(_) => Box::new(conf.build::<h_near::Signer>().await?),
+                #[cfg(feature = "algorand")]
+                ChainConnectionConf::Algorand(_) => {
+                    Box::new(conf.build::<h_algorand::Signer>().await?)
+                }
             };
             Ok(Some(chain_signer))
         } else {
@@ -694,20 +2047,68 @@ impl ChainConf {
         self.signer().await
     }
 
+    #[cfg(feature = "fuel")]
     async fn fuel_signer(&self) -> Result<fuels::prelude::WalletUnlocked> {
         self.signer().await.and_then(|opt| {
             opt.ok_or_else(|| eyre!("Fuel requires a signer to construct contract instances"))
         })
     }
 
+    #[cfg(feature = "sealevel")]
     async fn sealevel_signer(&self) -> Result<Option<h_sealevel::Keypair>> {
         self.signer().await
     }
 
+    #[cfg(feature = "cosmos")]
     async fn cosmos_signer(&self) -> Result<Option<h_cosmos::Signer>> {
         self.signer().await
     }
 
+    #[cfg(feature = "starknet")]
+    async fn starknet_signer(&self) -> Result<Option<h_starknet::Signer>> {
+        self.signer().await
+    }
+
+    #[cfg(feature = "aptos")]
+    async fn aptos_signer(&self) -> Result<Option<h_aptos::Signer>> {
+        self.signer().await
+    }
+
+    #[cfg(feature = "sui")]
+    async fn sui_signer(&self) -> Result<Option<h_sui::Signer>> {
+        self.signer().await
+    }
+
+    #[cfg(feature = "ton")]
+    async fn ton_signer(&self) -> Result<Option<h_ton::Signer>> {
+        self.signer().await
+    }
+
+    #[cfg(feature = "substrate")]
+    async fn substrate_signer(&self) -> Result<Option<h_substrate::Signer>> {
+        self.signer().await
+    }
+
+    #[cfg(feature = "tron")]
+    async fn tron_signer(&self) -> Result<Option<h_tron::Signer>> {
+        self.signer().await
+    }
+
+    #[cfg(feature = "stellar")]
+    async fn stellar_signer(&self) -> Result<Option<h_stellar::Signer>> {
+        self.signer().await
+    }
+
+    #[cfg(feature = "near")]
+    async fn near_signer(&self) -> Result<Option<h_near::Signer>> {
+        self.signer().await
+    }
+
+    #[cfg(feature = "algorand")]
+    async fn algorand_signer(&self) -> Result<Option<h_algorand::Signer>> {
+        self.signer().await
+    }
+
     /// Try to build an agent metrics configuration from the chain config
     pub async fn agent_metrics_conf(&self, agent_name: String) -> Result<AgentMetricsConf> {
         let chain_signer_address = self.chain_signer().await?.map(|s| s.address_string());
@@ -786,9 +2187,70 @@ impl ChainConf {
         let metrics_conf = self.metrics_conf();
         let rpc_metrics = Some(metrics.json_rpc_client_metrics());
         let middleware_metrics = Some((metrics.provider_metrics(), metrics_conf));
+        let verification_failures = Some(metrics.light_client_verification_failures());
         let res = builder
-            .build_with_connection_conf(conf, locator, signer, rpc_metrics, middleware_metrics)
+            .build_with_connection_conf(
+                conf,
+                locator,
+                signer,
+                rpc_metrics,
+                middleware_metrics,
+                verification_failures,
+            )
             .await;
         Ok(res?)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        let policy = RetryPolicyConf {
+            base_delay: Duration::from_secs(5),
+            max_delay: Duration::from_secs(60),
+            jitter: 0.,
+            max_attempts: None,
+        };
+        let delays: Vec<_> = (0..7).map(|n| policy.backoff(n, 0)).collect();
+        assert_eq!(
+            delays,
+            [
+                None,
+                Some(5),
+                Some(10),
+                Some(20),
+                Some(40),
+                Some(60),
+                Some(60)
+            ]
+            .map(|secs| secs.map(Duration::from_secs))
+        );
+        assert_eq!(policy.backoff(u32::MAX, 0), Some(Duration::from_secs(60)));
+
+        let jittered = RetryPolicyConf {
+            jitter: 0.5,
+            ..policy
+        };
+        for seed in 0..100 {
+            let delay = jittered.backoff(3, seed).unwrap();
+            assert!(delay >= Duration::from_secs(10) && delay <= Duration::from_secs(30));
+        }
+        assert_ne!(jittered.backoff(3, 1), jittered.backoff(3, 2));
+    }
+
+    #[test]
+    fn test_gas_limit_policy() {
+        let policy = GasLimitPolicyConf {
+            multiplier: Some(1.5),
+            floor: Some(100_000.into()),
+            cap: Some(1_000_000.into()),
+        };
+        assert_eq!(policy.apply(200_000.into()), 300_000.into());
+        assert_eq!(policy.apply(10_000.into()), 100_000.into());
+        assert_eq!(policy.apply(800_000.into()), 1_000_000.into());
+        assert_eq!(GasLimitPolicyConf::default().apply(123.into()), 123.into());
+    }
+}
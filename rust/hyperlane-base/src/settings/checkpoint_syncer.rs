@@ -1,12 +1,15 @@
 use crate::{
-    CheckpointSyncer, GcsStorageClientBuilder, LocalStorage, S3Storage, GCS_SERVICE_ACCOUNT_KEY,
-    GCS_USER_SECRET,
+    types::ANNOUNCEMENT_KEY, AzureBlobAuth, AzureBlobStorage, CheckpointSyncer,
+    GcsStorageClientBuilder, IpfsStorage, IpfsWriter, LocalStorage, ObjectWriter, S3Storage,
+    AZURE_STORAGE_SAS_TOKEN, DEFAULT_IPFS_GATEWAY, GCS_SERVICE_ACCOUNT_KEY, GCS_USER_SECRET,
+    IPFS_GATEWAY_URL,
 };
 use core::str::FromStr;
 use eyre::{eyre, Context, Report, Result};
 use prometheus::IntGauge;
 use rusoto_core::Region;
 use std::{env, path::PathBuf};
+use url::Url;
 use ya_gcp::{AuthFlow, ServiceAccountAuth};
 
 /// Checkpoint Syncer types
@@ -23,7 +26,8 @@ pub enum CheckpointSyncerConf {
         bucket: String,
         /// Folder name inside bucket - defaults to the root of the bucket
         folder: Option<String>,
-        /// S3 Region
+        /// S3 Region, or a custom region with the endpoint of an
+        /// S3-compatible store like R2 or MinIO
         region: Region,
     },
     /// A checkpoint syncer on Google Cloud Storage
@@ -38,6 +42,33 @@ pub enum CheckpointSyncerConf {
         /// `gcloud auth application-default login`
         user_secrets: Option<String>,
     },
+    /// A checkpoint syncer on Azure Blob Storage
+    Azure {
+        /// Storage account name
+        account: String,
+        /// Container name
+        container: String,
+        /// Folder name inside container - defaults to the root of the container
+        folder: Option<String>,
+        /// How requests are authorized. Anonymous access is read only.
+        auth: AzureBlobAuth,
+    },
+    /// An experimental checkpoint syncer on IPFS
+    Ipfs {
+        /// The IPNS name the checkpoints are published under. Looked up
+        /// from the key of the writer if not set.
+        ipns_name: Option<String>,
+        /// The gateway checkpoints are read through
+        gateway: Url,
+        /// The node checkpoints are written through, for validators
+        writer: Option<IpfsWriter>,
+    },
+}
+
+/// The gateway set through the env, or the default one
+pub fn ipfs_gateway() -> Result<Url> {
+    let gateway = env::var(IPFS_GATEWAY_URL).unwrap_or_else(|_| DEFAULT_IPFS_GATEWAY.to_owned());
+    Url::parse(&gateway).context("Invalid IPFS gateway")
 }
 
 impl FromStr for CheckpointSyncerConf {
@@ -50,19 +81,36 @@ impl FromStr for CheckpointSyncerConf {
             })?;
 
         match prefix {
-            "s3" => {
+            "s3" | "s3+http" | "s3+https" => {
+                // S3-compatible stores announce the host of their endpoint before the bucket
+                let (endpoint, suffix) = match prefix.strip_prefix("s3+") {
+                    Some(scheme) => {
+                        let (host, suffix) = suffix.split_once('/').ok_or_else(|| {
+                            eyre!("Error parsing storage location; could not split endpoint and bucket ({suffix})")
+                        })?;
+                        (Some(format!("{scheme}://{host}")), suffix)
+                    }
+                    None => (None, suffix),
+                };
                 let url_components = suffix.split('/').collect::<Vec<&str>>();
                 let (bucket, region, folder): (&str, &str, Option<String>) = match url_components.len() {
                     2 => Ok((url_components[0], url_components[1], None)),
                     3 .. => Ok((url_components[0], url_components[1], Some(url_components[2..].join("/")))),
                     _ => Err(eyre!("Error parsing storage location; could not split bucket, region and folder ({suffix})"))
                 }?;
+                let region = match endpoint {
+                    Some(endpoint) => Region::Custom {
+                        name: region.into(),
+                        endpoint,
+                    },
+                    None => region
+                        .parse()
+                        .context("Invalid region when parsing storage location")?,
+                };
                 Ok(CheckpointSyncerConf::S3 {
                     bucket: bucket.into(),
                     folder,
-                    region: region
-                        .parse()
-                        .context("Invalid region when parsing storage location")?,
+                    region,
                 })
             }
             "file" => Ok(CheckpointSyncerConf::LocalStorage {
@@ -73,29 +121,140 @@ impl FromStr for CheckpointSyncerConf {
             "gs" => {
                 let service_account_key = env::var(GCS_SERVICE_ACCOUNT_KEY).ok();
                 let user_secrets = env::var(GCS_USER_SECRET).ok();
-                if let Some(ind) = suffix.find('/') {
-                    let (bucket, folder) = suffix.split_at(ind);
-                    Ok(Self::Gcs {
-                        bucket: bucket.into(),
-                        folder: Some(folder.into()),
-                        service_account_key,
-                        user_secrets,
-                    })
-                } else {
-                    Ok(Self::Gcs {
-                        bucket: suffix.into(),
-                        folder: None,
-                        service_account_key,
-                        user_secrets,
-                    })
+                let (bucket, folder) = match suffix.split_once('/') {
+                    Some((bucket, folder)) => (bucket, folder),
+                    None => (suffix, ""),
+                };
+                // Validators used to announce the location of the announcement object
+                // rather than the folder it's in
+                let folder = folder.trim_end_matches(ANNOUNCEMENT_KEY).trim_matches('/');
+                if bucket.is_empty() {
+                    return Err(eyre!(
+                        "Error parsing storage location; missing bucket ({suffix})"
+                    ));
+                }
+                Ok(Self::Gcs {
+                    bucket: bucket.into(),
+                    folder: (!folder.is_empty()).then(|| folder.into()),
+                    service_account_key,
+                    user_secrets,
+                })
+            }
+            // like for google cloud, a SAS token can only be passed through env variables
+            "azure" => {
+                let url_components = suffix.splitn(3, '/').collect::<Vec<&str>>();
+                let (account, container, folder) = match url_components[..] {
+                    [account, container] => Ok((account, container, None)),
+                    [account, container, folder] => Ok((account, container, Some(folder.to_owned()))),
+                    _ => Err(eyre!("Error parsing storage location; could not split account, container and folder ({suffix})")),
+                }?;
+                if account.is_empty() || container.is_empty() {
+                    return Err(eyre!(
+                        "Error parsing storage location; missing account or container ({suffix})"
+                    ));
                 }
+                let auth = match env::var(AZURE_STORAGE_SAS_TOKEN) {
+                    Ok(sas) => AzureBlobAuth::Sas(sas),
+                    Err(_) => AzureBlobAuth::Anonymous,
+                };
+                Ok(Self::Azure {
+                    account: account.into(),
+                    container: container.into(),
+                    folder,
+                    auth,
+                })
+            }
+            // the gateway can only be set through env variables
+            "ipfs" => {
+                if suffix.is_empty() || suffix.contains('/') {
+                    return Err(eyre!(
+                        "Error parsing storage location; expected an IPNS name ({suffix})"
+                    ));
+                }
+                Ok(Self::Ipfs {
+                    ipns_name: Some(suffix.into()),
+                    gateway: ipfs_gateway()?,
+                    writer: None,
+                })
             }
             _ => Err(eyre!("Unknown storage location prefix `{prefix}`")),
         }
     }
 }
 
+/// The region of an S3 checkpoint syncer. Stores like R2 and MinIO are
+/// addressed through a custom region with their `endpoint`, and accept any
+/// region name.
+pub fn s3_region(name: &str, endpoint: Option<&str>) -> Result<Region> {
+    match endpoint {
+        Some(endpoint) => {
+            let endpoint = Url::parse(endpoint).context("Invalid S3-compatible endpoint")?;
+            Ok(Region::Custom {
+                name: name.to_owned(),
+                endpoint: endpoint.as_str().trim_end_matches('/').to_owned(),
+            })
+        }
+        None => name.parse().context("Invalid AWS region"),
+    }
+}
+
 impl CheckpointSyncerConf {
+    /// Turn conf into a writer of arbitrary objects to the same storage
+    /// location. IPFS isn't supported, as its objects are content-addressed.
+    pub async fn build_object_writer(&self) -> Result<Box<dyn ObjectWriter>> {
+        Ok(match self {
+            CheckpointSyncerConf::LocalStorage { path } => {
+                Box::new(LocalStorage::new(path.clone(), None)?)
+            }
+            CheckpointSyncerConf::S3 {
+                bucket,
+                folder,
+                region,
+            } => Box::new(S3Storage::new(
+                bucket.clone(),
+                folder.clone(),
+                region.clone(),
+                None,
+            )),
+            CheckpointSyncerConf::Gcs {
+                bucket,
+                folder,
+                service_account_key,
+                user_secrets,
+            } => {
+                let auth = if let Some(path) = service_account_key {
+                    AuthFlow::ServiceAccount(ServiceAccountAuth::Path(path.into()))
+                } else if let Some(path) = user_secrets {
+                    AuthFlow::UserAccount(path.into())
+                } else {
+                    return Err(eyre!(
+                        "Writing to GCS requires a service account key or user secrets"
+                    ));
+                };
+                Box::new(
+                    GcsStorageClientBuilder::new(auth)
+                        .build(bucket, folder.to_owned())
+                        .await?,
+                )
+            }
+            CheckpointSyncerConf::Azure {
+                account,
+                container,
+                folder,
+                auth,
+            } => Box::new(AzureBlobStorage::new(
+                account.clone(),
+                container.clone(),
+                folder.clone(),
+                auth.clone(),
+                None,
+            )),
+            CheckpointSyncerConf::Ipfs { .. } => {
+                return Err(eyre!("Arbitrary objects can't be written to IPFS"))
+            }
+        })
+    }
+
     /// Turn conf info a Checkpoint Syncer
     pub async fn build(
         &self,
@@ -133,9 +292,148 @@ impl CheckpointSyncerConf {
                 Box::new(
                     GcsStorageClientBuilder::new(auth)
                         .build(bucket, folder.to_owned())
-                        .await?,
+                        .await?
+                        .with_latest_index_gauge(latest_index_gauge),
                 )
             }
+            CheckpointSyncerConf::Azure {
+                account,
+                container,
+                folder,
+                auth,
+            } => Box::new(AzureBlobStorage::new(
+                account.clone(),
+                container.clone(),
+                folder.clone(),
+                auth.clone(),
+                latest_index_gauge,
+            )),
+            CheckpointSyncerConf::Ipfs {
+                ipns_name,
+                gateway,
+                writer,
+            } => match (writer, ipns_name) {
+                (Some(writer), _) => Box::new(
+                    IpfsStorage::writer(writer.clone(), gateway.clone(), latest_index_gauge)
+                        .await?,
+                ),
+                (None, Some(ipns_name)) => Box::new(IpfsStorage::reader(
+                    ipns_name.clone(),
+                    gateway.clone(),
+                    latest_index_gauge,
+                )),
+                (None, None) => {
+                    return Err(eyre!(
+                        "IPFS checkpoint syncer needs an IPNS name or the RPC API of a node"
+                    ))
+                }
+            },
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn gcs_location(location: &str) -> (String, Option<String>) {
+        match location.parse().unwrap() {
+            CheckpointSyncerConf::Gcs { bucket, folder, .. } => (bucket, folder),
+            conf => panic!("Expected a GCS checkpoint syncer, got {conf:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parses_gcs_locations() {
+        assert_eq!(gcs_location("gs://bucket"), ("bucket".to_owned(), None));
+        assert_eq!(
+            gcs_location("gs://bucket/validators/ethereum"),
+            ("bucket".to_owned(), Some("validators/ethereum".to_owned()))
+        );
+        // Locations announced before they pointed to the folder
+        assert_eq!(
+            gcs_location("gs://bucket/gcsAnnouncementKey"),
+            ("bucket".to_owned(), None)
+        );
+        assert_eq!(
+            gcs_location("gs://bucket/validators/gcsAnnouncementKey"),
+            ("bucket".to_owned(), Some("validators".to_owned()))
+        );
+        assert!("gs:///folder".parse::<CheckpointSyncerConf>().is_err());
+    }
+
+    #[test]
+    fn test_parses_s3_compatible_locations() {
+        let location = "s3+https://account.r2.cloudflarestorage.com/bucket/auto/validators";
+        let conf: CheckpointSyncerConf = location.parse().unwrap();
+        let CheckpointSyncerConf::S3 {
+            bucket,
+            folder,
+            region,
+        } = conf
+        else {
+            panic!("Expected an S3 checkpoint syncer, got {conf:?}");
+        };
+        assert_eq!(bucket, "bucket");
+        assert_eq!(folder.as_deref(), Some("validators"));
+        assert_eq!(
+            region,
+            Region::Custom {
+                name: "auto".to_owned(),
+                endpoint: "https://account.r2.cloudflarestorage.com".to_owned(),
+            }
+        );
+        // The location is announced the way it's parsed
+        let storage = S3Storage::new(bucket, folder, region, None);
+        assert_eq!(storage.announcement_location(), location);
+
+        assert!("s3+http://localhost:9000"
+            .parse::<CheckpointSyncerConf>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parses_ipfs_locations() {
+        match "ipfs://k51qzi5uqu5dlvj2baxnqndepeb86cbk3ng7n3i46uzyxzyqj2xjonzllnv0v8"
+            .parse()
+            .unwrap()
+        {
+            CheckpointSyncerConf::Ipfs {
+                ipns_name, writer, ..
+            } => {
+                assert_eq!(
+                    ipns_name.as_deref(),
+                    Some("k51qzi5uqu5dlvj2baxnqndepeb86cbk3ng7n3i46uzyxzyqj2xjonzllnv0v8")
+                );
+                assert!(writer.is_none());
+            }
+            conf => panic!("Expected an IPFS checkpoint syncer, got {conf:?}"),
+        }
+        assert!("ipfs://".parse::<CheckpointSyncerConf>().is_err());
+        assert!("ipfs://name/folder"
+            .parse::<CheckpointSyncerConf>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parses_azure_locations() {
+        match "azure://account/container/validators/ethereum"
+            .parse()
+            .unwrap()
+        {
+            CheckpointSyncerConf::Azure {
+                account,
+                container,
+                folder,
+                ..
+            } => {
+                assert_eq!(account, "account");
+                assert_eq!(container, "container");
+                assert_eq!(folder.as_deref(), Some("validators/ethereum"));
+            }
+            conf => panic!("Expected an Azure checkpoint syncer, got {conf:?}"),
+        }
+        assert!("azure://account".parse::<CheckpointSyncerConf>().is_err());
+        assert!("azure://account/".parse::<CheckpointSyncerConf>().is_err());
+    }
+}
@@ -16,6 +16,29 @@ mod arguments;
 mod case_adapter;
 mod environment;
 
+/// The config files settings are loaded from, i.e. `./config/*.json` and the
+/// ones in `CONFIG_FILES`, skipping any that don't exist.
+pub fn config_file_paths() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = PathBuf::from("./config")
+        .read_dir()
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension() == Some("json".as_ref()))
+        .collect();
+    paths.sort();
+    if let Ok(config_files) = env::var("CONFIG_FILES") {
+        paths.extend(
+            config_files
+                .split(',')
+                .map(PathBuf::from)
+                .filter(|path| path.is_file()),
+        );
+    }
+    paths
+}
+
 /// Deserialize a settings object from the configs.
 pub fn load_settings<T, R>() -> ConfigResult<R>
 where
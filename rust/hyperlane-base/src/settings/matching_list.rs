@@ -272,6 +272,31 @@ impl MatchingList {
         self.matches(msg.into(), default)
     }
 
+    /// Check if a message on the given route matches any of the rules, for
+    /// when only the route of the message is known.
+    /// - `default`: What to return if the matching list is empty.
+    pub fn route_matches(
+        &self,
+        origin: u32,
+        sender: &H256,
+        destination: u32,
+        recipient: &H256,
+        default: bool,
+    ) -> bool {
+        let info = MatchInfo {
+            src_domain: origin,
+            src_addr: sender,
+            dst_domain: destination,
+            dst_addr: recipient,
+        };
+        self.matches(info, default)
+    }
+
+    /// Whether the list has no rules
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
     /// Check if a message matches any of the rules.
     /// - `default`: What to return if the matching list is empty.
     fn matches(&self, info: MatchInfo, default: bool) -> bool {
@@ -448,6 +473,18 @@ mod test {
         assert!(!MatchingList(None).matches(info, false));
     }
 
+    #[test]
+    fn matches_routes() {
+        let list: MatchingList =
+            serde_json::from_str(r#"[{"origindomain": 1, "destinationdomain": [2, 3]}]"#).unwrap();
+        let addr = H256::repeat_byte(1);
+        assert!(list.route_matches(1, &addr, 2, &addr, false));
+        assert!(list.route_matches(1, &addr, 3, &addr, false));
+        assert!(!list.route_matches(2, &addr, 3, &addr, true));
+        assert!(!list.is_empty());
+        assert!(MatchingList(None).is_empty());
+    }
+
     #[test]
     fn supports_base58() {
         serde_json::from_str::<MatchingList>(
@@ -465,8 +502,7 @@ mod test {
         // Test parsing into a Value and then into MatchingList, which is the path used
         // by the agent config parser.
         let val: serde_json::Value = serde_json::from_str(json_str).unwrap();
-        let value_parser =
-            hyperlane_base::settings::parser::ValueParser::new(Default::default(), &val);
-        crate::settings::parse_matching_list(value_parser).unwrap();
+        let value_parser = crate::settings::parser::ValueParser::new(Default::default(), &val);
+        crate::settings::parser::parse_matching_list(value_parser).unwrap();
     }
 }
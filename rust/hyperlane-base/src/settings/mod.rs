@@ -65,17 +65,40 @@
 pub use base::*;
 pub use chains::*;
 pub use checkpoint_syncer::*;
+pub use otlp::*;
 /// Export this so they don't need to import paste.
 #[doc(hidden)]
 pub use paste;
+pub use reload::*;
 pub use signers::*;
 pub use trace::*;
 
 mod envs {
+    #[cfg(feature = "algorand")]
+    pub use hyperlane_algorand as h_algorand;
+    #[cfg(feature = "aptos")]
+    pub use hyperlane_aptos as h_aptos;
+    #[cfg(feature = "cosmos")]
     pub use hyperlane_cosmos as h_cosmos;
     pub use hyperlane_ethereum as h_eth;
+    #[cfg(feature = "fuel")]
     pub use hyperlane_fuel as h_fuel;
+    #[cfg(feature = "near")]
+    pub use hyperlane_near as h_near;
+    #[cfg(feature = "sealevel")]
     pub use hyperlane_sealevel as h_sealevel;
+    #[cfg(feature = "starknet")]
+    pub use hyperlane_starknet as h_starknet;
+    #[cfg(feature = "stellar")]
+    pub use hyperlane_stellar as h_stellar;
+    #[cfg(feature = "substrate")]
+    pub use hyperlane_substrate as h_substrate;
+    #[cfg(feature = "sui")]
+    pub use hyperlane_sui as h_sui;
+    #[cfg(feature = "ton")]
+    pub use hyperlane_ton as h_ton;
+    #[cfg(feature = "tron")]
+    pub use hyperlane_tron as h_tron;
 }
 
 /// AWS Credentials provider.
@@ -84,6 +107,13 @@ mod base;
 /// Chain configuration
 mod chains;
 pub mod loader;
+pub mod matching_list;
+/// Export of traces and metrics to OpenTelemetry collectors
+mod otlp;
+/// Checks of the configured chains on agent startup
+mod preflight;
+/// Reloading of the config while agents are running
+mod reload;
 /// Signer configuration
 mod signers;
 /// Tracing subscriber management
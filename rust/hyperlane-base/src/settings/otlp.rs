@@ -0,0 +1,211 @@
+use std::{collections::HashMap, time::Duration};
+
+use eyre::{Context, Result};
+use hyperlane_core::HyperlaneDomain;
+use itertools::Itertools;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde_json::{json, Value as JsonValue};
+use url::Url;
+
+/// How often metrics are exported by default
+pub const DEFAULT_OTLP_METRICS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long an export request may take, so that a hanging collector doesn't
+/// hold up the exports that follow
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Export of traces and metrics to an OpenTelemetry collector, e.g. one
+/// forwarding them to Tempo, Jaeger or Datadog
+#[derive(Debug, Clone)]
+pub struct OtlpConf {
+    /// The OTLP/HTTP endpoint of the collector, without the `v1/traces` or
+    /// `v1/metrics` path
+    pub endpoint: Url,
+    /// Headers sent with every export request, e.g. an api key
+    pub headers: HashMap<String, String>,
+    /// Whether spans are exported
+    pub traces: bool,
+    /// Whether the metrics are exported
+    pub metrics: bool,
+    /// How often the metrics are exported
+    pub metrics_interval: Duration,
+    /// Attributes of the agent added to everything it exports, on top of the
+    /// agent type and chains
+    pub resource_attributes: HashMap<String, String>,
+}
+
+impl OtlpConf {
+    /// Only exports traces, as configured by `log.otlpEndpoint`
+    pub fn traces_only(endpoint: Url) -> Self {
+        Self {
+            endpoint,
+            headers: HashMap::new(),
+            traces: true,
+            metrics: false,
+            metrics_interval: DEFAULT_OTLP_METRICS_INTERVAL,
+            resource_attributes: HashMap::new(),
+        }
+    }
+
+    /// The exporter of the `agent` running on `chains`
+    pub fn exporter(&self, agent: &str, chains: &[HyperlaneDomain]) -> Result<OtlpExporter> {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| -> Result<_> {
+                Ok((
+                    HeaderName::try_from(name.as_str())?,
+                    HeaderValue::try_from(value.as_str())?,
+                ))
+            })
+            .collect::<Result<HeaderMap>>()
+            .context("Invalid OTLP header")?;
+
+        let chain_names = chains.iter().map(|chain| chain.name()).sorted().join(",");
+        let mut resource = vec![
+            ("service.name".to_owned(), agent.to_owned()),
+            (
+                "service.version".to_owned(),
+                env!("CARGO_PKG_VERSION").to_owned(),
+            ),
+            ("hyperlane.agent".to_owned(), agent.to_owned()),
+            ("hyperlane.chains".to_owned(), chain_names),
+        ];
+        // Agents on a single chain, like validators, can be filtered by it
+        if let [chain] = chains {
+            resource.push(("hyperlane.chain".to_owned(), chain.name().to_owned()));
+            resource.push(("hyperlane.domain".to_owned(), chain.id().to_string()));
+        }
+        for (key, value) in self.resource_attributes.iter().sorted() {
+            resource.retain(|(existing, _)| existing != key);
+            resource.push((key.clone(), value.clone()));
+        }
+
+        Ok(OtlpExporter {
+            endpoint: self.endpoint.clone(),
+            headers,
+            client: reqwest::Client::builder()
+                .timeout(EXPORT_TIMEOUT)
+                .build()
+                .context("Failed to build OTLP client")?,
+            resource,
+            traces: self.traces,
+            metrics: self.metrics,
+            metrics_interval: self.metrics_interval,
+        })
+    }
+}
+
+/// Sends OTLP/HTTP export requests in the JSON encoding, attributed to the
+/// agent
+#[derive(Debug, Clone)]
+pub struct OtlpExporter {
+    endpoint: Url,
+    headers: HeaderMap,
+    client: reqwest::Client,
+    resource: Vec<(String, String)>,
+    traces: bool,
+    metrics: bool,
+    metrics_interval: Duration,
+}
+
+impl OtlpExporter {
+    /// Whether spans are exported
+    pub fn exports_traces(&self) -> bool {
+        self.traces
+    }
+
+    /// Whether the metrics are exported
+    pub fn exports_metrics(&self) -> bool {
+        self.metrics
+    }
+
+    /// How often the metrics are exported
+    pub fn metrics_interval(&self) -> Duration {
+        self.metrics_interval
+    }
+
+    /// The resource everything is exported with
+    pub(crate) fn resource_json(&self) -> JsonValue {
+        json!({
+            "attributes": self
+                .resource
+                .iter()
+                .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// Posts `body` to the collector's endpoint for `signal`, i.e. `traces`
+    /// or `metrics`
+    pub(crate) async fn export(&self, signal: &str, body: &JsonValue) -> Result<()> {
+        let url = self
+            .endpoint
+            .join(&format!("v1/{signal}"))
+            .context("Invalid OTLP endpoint")?;
+        self.client
+            .post(url)
+            .headers(self.headers.clone())
+            .json(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::KnownHyperlaneDomain;
+
+    use super::*;
+
+    fn attribute<'a>(resource: &'a JsonValue, key: &str) -> Option<&'a JsonValue> {
+        resource["attributes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|attribute| attribute["key"] == key)
+            .map(|attribute| &attribute["value"]["stringValue"])
+    }
+
+    #[test]
+    fn resource_has_agent_and_chains() {
+        let mut conf = OtlpConf::traces_only("http://localhost:4318".parse().unwrap());
+        conf.resource_attributes
+            .insert("deployment.environment".to_owned(), "testnet".to_owned());
+        conf.resource_attributes
+            .insert("hyperlane.agent".to_owned(), "relayer-2".to_owned());
+
+        let chains = [
+            HyperlaneDomain::from(KnownHyperlaneDomain::Test2),
+            HyperlaneDomain::from(KnownHyperlaneDomain::Test1),
+        ];
+        let resource = conf.exporter("relayer", &chains).unwrap().resource_json();
+        assert_eq!(attribute(&resource, "service.name").unwrap(), "relayer");
+        assert_eq!(
+            attribute(&resource, "hyperlane.chains").unwrap(),
+            "test1,test2"
+        );
+        assert_eq!(attribute(&resource, "hyperlane.chain"), None);
+        assert_eq!(
+            attribute(&resource, "deployment.environment").unwrap(),
+            "testnet"
+        );
+        // Configured attributes take precedence
+        assert_eq!(
+            attribute(&resource, "hyperlane.agent").unwrap(),
+            "relayer-2"
+        );
+
+        let resource = conf
+            .exporter(
+                "validator",
+                &[HyperlaneDomain::from(KnownHyperlaneDomain::Test1)],
+            )
+            .unwrap()
+            .resource_json();
+        assert_eq!(attribute(&resource, "hyperlane.chain").unwrap(), "test1");
+        assert_eq!(attribute(&resource, "hyperlane.domain").unwrap(), "13371");
+    }
+}
@@ -1,5 +1,7 @@
 use eyre::eyre;
-use h_eth::TransactionOverrides;
+use h_eth::{ChainQuirks, TransactionOverrides};
+#[cfg(any(feature = "cosmos", feature = "sealevel"))]
+use hyperlane_core::config::ConfigResultExt;
 use hyperlane_core::config::{ConfigErrResultExt, OperationBatchConfig};
 use hyperlane_core::{config::ConfigParsingError, HyperlaneDomainProtocol};
 use url::Url;
@@ -7,7 +9,9 @@ use url::Url;
 use crate::settings::envs::*;
 use crate::settings::ChainConnectionConf;
 
-use super::{parse_base_and_override_urls, parse_cosmos_gas_price, ValueParser};
+#[cfg(feature = "cosmos")]
+use super::parse_cosmos_gas_price;
+use super::{parse_base_and_override_urls, ValueParser};
 
 pub fn build_ethereum_connection_conf(
     rpcs: &[Url],
@@ -65,13 +69,49 @@ pub fn build_ethereum_connection_conf(
         })
         .unwrap_or_default();
 
+    let quirks = chain
+        .get_opt_key("quirks")
+        .take_err(err, || &chain.cwp + "quirks")
+        .flatten()
+        .map(|value_parser| {
+            let default = ChainQuirks::default();
+            ChainQuirks {
+                supports_eip1559: value_parser
+                    .chain(err)
+                    .get_opt_key("supportsEip1559")
+                    .parse_bool()
+                    .unwrap_or(default.supports_eip1559),
+                max_log_topics: value_parser
+                    .chain(err)
+                    .get_opt_key("maxLogTopics")
+                    .parse_u32()
+                    .end()
+                    .map(|max| max as usize),
+                max_log_block_range: value_parser
+                    .chain(err)
+                    .get_opt_key("maxLogBlockRange")
+                    .parse_u32()
+                    .end(),
+            }
+        })
+        .unwrap_or_default();
+
+    let light_client_url = chain
+        .chain(err)
+        .get_opt_key("lightClientUrl")
+        .parse_from_str("Invalid light client url")
+        .end();
+
     Some(ChainConnectionConf::Ethereum(h_eth::ConnectionConf {
         rpc_connection: rpc_connection_conf?,
         transaction_overrides,
         operation_batch,
+        quirks,
+        light_client_url,
     }))
 }
 
+#[cfg(feature = "cosmos")]
 pub fn build_cosmos_connection_conf(
     rpcs: &[Url],
     chain: &ValueParser,
@@ -128,29 +168,336 @@ pub fn build_cosmos_connection_conf(
         .and_then(parse_cosmos_gas_price)
         .end();
 
+    let additional_gas_prices = chain
+        .chain(err)
+        .get_opt_key("gasPrices")
+        .into_array_iter()
+        .map(|prices| {
+            prices
+                .filter_map(|price| parse_cosmos_gas_price(price).take_config_err(err))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let fee_denom = chain
+        .chain(err)
+        .get_opt_key("feeDenom")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+
     let contract_address_bytes = chain
         .chain(err)
         .get_opt_key("contractAddressBytes")
         .parse_u64()
         .end();
 
+    let module_type = chain
+        .chain(err)
+        .get_opt_key("moduleType")
+        .parse_from_str("Expected cosmos module type")
+        .unwrap_or_default();
+
     if !local_err.is_ok() {
         err.merge(local_err);
         None
     } else {
-        Some(ChainConnectionConf::Cosmos(h_cosmos::ConnectionConf::new(
+        let conf = h_cosmos::ConnectionConf::new(
             grpcs,
             rpcs.first().unwrap().to_string(),
             chain_id.unwrap().to_string(),
             prefix.unwrap().to_string(),
             canonical_asset.unwrap(),
             gas_price.unwrap(),
+            additional_gas_prices,
+            fee_denom,
             contract_address_bytes.unwrap().try_into().unwrap(),
             operation_batch,
-        )))
+            module_type,
+        );
+        if let Err(e) = conf.get_fee_gas_price() {
+            err.push(&chain.cwp + "fee_denom", e.into());
+            return None;
+        }
+        Some(ChainConnectionConf::Cosmos(conf))
     }
 }
 
+#[cfg(feature = "sealevel")]
+fn build_sealevel_connection_conf(
+    rpcs: &[Url],
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+    operation_batch: OperationBatchConfig,
+) -> Option<ChainConnectionConf> {
+    let url = rpcs.iter().next()?;
+    let ws_url = chain
+        .chain(err)
+        .get_opt_key("wsUrl")
+        .parse_from_str("Invalid websocket url")
+        .end();
+    let priority_fee = chain
+        .get_opt_key("priorityFee")
+        .take_err(err, || &chain.cwp + "priority_fee")
+        .flatten()
+        .and_then(|value_parser| parse_sealevel_priority_fee(&value_parser, err))
+        .unwrap_or_default();
+    let jito = chain
+        .get_opt_key("jito")
+        .take_err(err, || &chain.cwp + "jito")
+        .flatten()
+        .and_then(|value_parser| parse_sealevel_jito(&value_parser, err));
+    let lookup_tables = chain
+        .chain(err)
+        .get_opt_key("lookupTables")
+        .into_array_iter()
+        .map(|tables| {
+            tables
+                .filter_map(|table| table.parse_address_hash().take_config_err(err))
+                .collect()
+        })
+        .unwrap_or_default();
+    let submission_commitment =
+        parse_sealevel_commitment(chain, "submission_commitment", "processed", err);
+    let indexing_commitment =
+        parse_sealevel_commitment(chain, "indexing_commitment", "finalized", err);
+
+    Some(ChainConnectionConf::Sealevel(h_sealevel::ConnectionConf {
+        url: url.clone(),
+        ws_url,
+        operation_batch,
+        priority_fee,
+        jito,
+        lookup_tables,
+        submission_commitment: submission_commitment?,
+        indexing_commitment: indexing_commitment?,
+    }))
+}
+
+#[cfg(feature = "sealevel")]
+fn parse_sealevel_commitment(
+    chain: &ValueParser,
+    key: &str,
+    default: &str,
+    err: &mut ConfigParsingError,
+) -> Option<solana_sdk::commitment_config::CommitmentConfig> {
+    use solana_sdk::commitment_config::CommitmentConfig;
+
+    let commitment = chain
+        .chain(err)
+        .get_opt_key(key)
+        .parse_string()
+        .unwrap_or(default);
+    match commitment {
+        "processed" => Some(CommitmentConfig::processed()),
+        "confirmed" => Some(CommitmentConfig::confirmed()),
+        "finalized" => Some(CommitmentConfig::finalized()),
+        commitment => {
+            Err(eyre!("unknown commitment `{commitment}`")).take_err(err, || &chain.cwp + key)
+        }
+    }
+}
+
+#[cfg(feature = "sealevel")]
+fn parse_sealevel_jito(
+    value_parser: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<h_sealevel::JitoConf> {
+    let url = value_parser
+        .chain(err)
+        .get_key("url")
+        .parse_from_str("Invalid Jito block engine url")
+        .end();
+    let tip_lamports = value_parser
+        .chain(err)
+        .get_key("tipLamports")
+        .parse_u64()
+        .end();
+    Some(h_sealevel::JitoConf {
+        url: url?,
+        tip_lamports: tip_lamports?,
+    })
+}
+
+#[cfg(feature = "sealevel")]
+fn parse_sealevel_priority_fee(
+    value_parser: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<h_sealevel::PriorityFeeStrategy> {
+    let strategy = value_parser
+        .chain(err)
+        .get_opt_key("strategy")
+        .parse_string()
+        .unwrap_or("none");
+    match strategy {
+        "none" => Some(h_sealevel::PriorityFeeStrategy::None),
+        "constant" => value_parser
+            .chain(err)
+            .get_key("microLamports")
+            .parse_u64()
+            .end()
+            .map(h_sealevel::PriorityFeeStrategy::Constant),
+        "percentile" => {
+            let percentile = value_parser
+                .chain(err)
+                .get_key("percentile")
+                .parse_u16()
+                .end()
+                .and_then(|percentile| match percentile {
+                    0..=100 => Some(percentile as u8),
+                    _ => Err(eyre!("percentile must be between 0 and 100"))
+                        .take_err(err, || &value_parser.cwp + "percentile"),
+                });
+            let min = value_parser
+                .chain(err)
+                .get_opt_key("min")
+                .parse_u64()
+                .unwrap_or(0);
+            let max = value_parser.chain(err).get_key("max").parse_u64().end()?;
+            if min > max {
+                return Err(eyre!("min must not be greater than max"))
+                    .take_err(err, || &value_parser.cwp + "min");
+            }
+            Some(h_sealevel::PriorityFeeStrategy::Percentile {
+                percentile: percentile?,
+                min,
+                max,
+            })
+        }
+        ty => Err(eyre!("unknown priority fee strategy `{ty}`"))
+            .take_err(err, || &value_parser.cwp + "strategy"),
+    }
+}
+
+#[cfg(feature = "sui")]
+fn build_sui_connection_conf(
+    rpcs: &[Url],
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+    operation_batch: OperationBatchConfig,
+) -> Option<ChainConnectionConf> {
+    let url = rpcs.iter().next()?;
+    let graphql_url = chain
+        .chain(err)
+        .get_key("graphqlUrl")
+        .parse_from_str("Invalid GraphQL url")
+        .end()?;
+    Some(ChainConnectionConf::Sui(h_sui::ConnectionConf::new(
+        url.clone(),
+        graphql_url,
+        operation_batch,
+    )))
+}
+
+#[cfg(feature = "ton")]
+fn build_ton_connection_conf(
+    rpcs: &[Url],
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+    operation_batch: OperationBatchConfig,
+) -> Option<ChainConnectionConf> {
+    let url = rpcs.iter().next()?;
+    let api_key = chain
+        .chain(err)
+        .get_opt_key("apiKey")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    Some(ChainConnectionConf::Ton(h_ton::ConnectionConf::new(
+        url.clone(),
+        api_key,
+        operation_batch,
+    )))
+}
+
+#[cfg(feature = "substrate")]
+fn build_substrate_connection_conf(
+    rpcs: &[Url],
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+    operation_batch: OperationBatchConfig,
+) -> Option<ChainConnectionConf> {
+    let url = rpcs.iter().next()?;
+    let pallet = chain
+        .chain(err)
+        .get_opt_key("palletName")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    let mortality_period = chain
+        .chain(err)
+        .get_opt_key("mortalityPeriod")
+        .parse_u64()
+        .end();
+    Some(ChainConnectionConf::Substrate(
+        h_substrate::ConnectionConf::new(url.clone(), pallet, mortality_period, operation_batch),
+    ))
+}
+
+#[cfg(feature = "tron")]
+fn build_tron_connection_conf(
+    rpcs: &[Url],
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+    operation_batch: OperationBatchConfig,
+) -> Option<ChainConnectionConf> {
+    let url = rpcs.iter().next()?;
+    let api_key = chain
+        .chain(err)
+        .get_opt_key("apiKey")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    Some(ChainConnectionConf::Tron(h_tron::ConnectionConf::new(
+        url.clone(),
+        api_key,
+        operation_batch,
+    )))
+}
+
+#[cfg(feature = "stellar")]
+fn build_stellar_connection_conf(
+    rpcs: &[Url],
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+    operation_batch: OperationBatchConfig,
+) -> Option<ChainConnectionConf> {
+    let url = rpcs.iter().next()?;
+    let network_passphrase = chain
+        .chain(err)
+        .get_opt_key("networkPassphrase")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    Some(ChainConnectionConf::Stellar(
+        h_stellar::ConnectionConf::new(url.clone(), network_passphrase, operation_batch),
+    ))
+}
+
+#[cfg(feature = "algorand")]
+fn build_algorand_connection_conf(
+    rpcs: &[Url],
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+    operation_batch: OperationBatchConfig,
+) -> Option<ChainConnectionConf> {
+    let url = rpcs.iter().next()?;
+    let indexer_url = chain
+        .chain(err)
+        .get_key("indexerUrl")
+        .parse_from_str("Invalid indexer url")
+        .end()?;
+    let api_token = chain
+        .chain(err)
+        .get_opt_key("apiToken")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    Some(ChainConnectionConf::Algorand(
+        h_algorand::ConnectionConf::new(url.clone(), indexer_url, api_token, operation_batch),
+    ))
+}
+
 pub fn build_connection_conf(
     domain_protocol: HyperlaneDomainProtocol,
     rpcs: &[Url],
@@ -167,18 +514,59 @@ pub fn build_connection_conf(
             default_rpc_consensus_type,
             operation_batch,
         ),
+        #[cfg(feature = "fuel")]
         HyperlaneDomainProtocol::Fuel => rpcs
             .iter()
             .next()
             .map(|url| ChainConnectionConf::Fuel(h_fuel::ConnectionConf { url: url.clone() })),
-        HyperlaneDomainProtocol::Sealevel => rpcs.iter().next().map(|url| {
-            ChainConnectionConf::Sealevel(h_sealevel::ConnectionConf {
-                url: url.clone(),
-                operation_batch,
-            })
-        }),
+        #[cfg(feature = "sealevel")]
+        HyperlaneDomainProtocol::Sealevel => {
+            build_sealevel_connection_conf(rpcs, chain, err, operation_batch)
+        }
+        #[cfg(feature = "cosmos")]
         HyperlaneDomainProtocol::Cosmos => {
             build_cosmos_connection_conf(rpcs, chain, err, operation_batch)
         }
+        #[cfg(feature = "starknet")]
+        HyperlaneDomainProtocol::Starknet => rpcs.iter().next().map(|url| {
+            ChainConnectionConf::Starknet(h_starknet::ConnectionConf::new(
+                url.clone(),
+                operation_batch,
+            ))
+        }),
+        #[cfg(feature = "aptos")]
+        HyperlaneDomainProtocol::Aptos => rpcs.iter().next().map(|url| {
+            ChainConnectionConf::Aptos(h_aptos::ConnectionConf::new(url.clone(), operation_batch))
+        }),
+        #[cfg(feature = "sui")]
+        HyperlaneDomainProtocol::Sui => build_sui_connection_conf(rpcs, chain, err, operation_batch),
+        #[cfg(feature = "ton")]
+        HyperlaneDomainProtocol::Ton => build_ton_connection_conf(rpcs, chain, err, operation_batch),
+        #[cfg(feature = "substrate")]
+        HyperlaneDomainProtocol::Substrate => {
+            build_substrate_connection_conf(rpcs, chain, err, operation_batch)
+        }
+        #[cfg(feature = "tron")]
+        HyperlaneDomainProtocol::Tron => {
+            build_tron_connection_conf(rpcs, chain, err, operation_batch)
+        }
+        #[cfg(feature = "stellar")]
+        HyperlaneDomainProtocol::Stellar => {
+            build_stellar_connection_conf(rpcs, chain, err, operation_batch)
+        }
+        #[cfg(feature = "near")]
+        HyperlaneDomainProtocol::Near => rpcs.iter().next().map(|url| {
+            ChainConnectionConf::Near(h_near::ConnectionConf::new(url.clone(), operation_batch))
+        }),
+        #[cfg(feature = "algorand")]
+        HyperlaneDomainProtocol::Algorand => {
+            build_algorand_connection_conf(rpcs, chain, err, operation_batch)
+        }
+        // Only reachable in agents built without support for some protocols
+        #[allow(unreachable_patterns)]
+        protocol => Err(eyre!(
+            "{protocol} chains are not supported by this build, it must be built with the `{protocol}` feature"
+        ))
+        .take_err(err, || &chain.cwp + "protocol"),
     }
 }
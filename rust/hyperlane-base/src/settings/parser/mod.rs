@@ -7,13 +7,16 @@
 use std::{
     collections::{HashMap, HashSet},
     default::Default,
+    path::PathBuf,
+    time::Duration,
 };
 
 use convert_case::{Case, Casing};
 use eyre::{eyre, Context};
+#[cfg(feature = "cosmos")]
 use h_cosmos::RawCosmosAmount;
 use hyperlane_core::{
-    cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneDomainProtocol,
+    cfg_unwrap_all, config::*, BlockTag, Finality, HyperlaneDomain, HyperlaneDomainProtocol,
     HyperlaneDomainTechnicalStack, IndexMode,
 };
 use itertools::Itertools;
@@ -23,9 +26,15 @@ use url::Url;
 
 pub use self::json_value_parser::ValueParser;
 pub use super::envs::*;
-use crate::settings::{
-    chains::IndexSettings, parser::connection_parser::build_connection_conf, trace::TracingConfig,
-    ChainConf, CoreContractAddresses, Settings, SignerConf,
+use crate::{
+    db::{IntegrityCheckConf, DEFAULT_INTEGRITY_CHECK_DEPTH},
+    settings::{
+        chains::IndexSettings, matching_list::MatchingList,
+        parser::connection_parser::build_connection_conf, trace::TracingConfig, ChainConf,
+        CoreContractAddresses, GasLimitPolicyConf, OtlpConf, RetryPolicyConf, Settings, SignerConf,
+        DEFAULT_OTLP_METRICS_INTERVAL,
+    },
+    SupervisorConf,
 };
 
 mod connection_parser;
@@ -52,6 +61,20 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
             .parse_u16()
             .unwrap_or(9090);
 
+        let health_port = p
+            .chain(&mut err)
+            .get_opt_key("healthPort")
+            .parse_u16()
+            .end();
+
+        let config_watch_interval = p
+            .chain(&mut err)
+            .get_opt_key("configWatchIntervalSecs")
+            .parse_u64()
+            .end()
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs);
+
         let fmt = p
             .chain(&mut err)
             .get_opt_key("log")
@@ -66,6 +89,29 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
             .parse_value("Invalid log level")
             .unwrap_or_default();
 
+        let filter_file = p
+            .chain(&mut err)
+            .get_opt_key("log")
+            .get_opt_key("filterFile")
+            .parse_string()
+            .end()
+            .map(PathBuf::from);
+
+        // `log.otlpEndpoint` only exports traces, and is superseded by `otlp`
+        let otlp = p
+            .chain(&mut err)
+            .get_opt_key("otlp")
+            .and_then(parse_otlp)
+            .end()
+            .or_else(|| {
+                p.chain(&mut err)
+                    .get_opt_key("log")
+                    .get_opt_key("otlpEndpoint")
+                    .parse_from_str("Invalid OTLP endpoint")
+                    .end()
+                    .map(OtlpConf::traces_only)
+            });
+
         let raw_chains: Vec<(String, ValueParser)> = if let Some(filter) = filter {
             p.chain(&mut err)
                 .get_opt_key("chains")
@@ -109,7 +155,14 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
         err.into_result(Self {
             chains,
             metrics_port,
-            tracing: TracingConfig { fmt, level },
+            health_port,
+            tracing: TracingConfig {
+                fmt,
+                level,
+                filter_file,
+            },
+            otlp,
+            config_watch_interval,
         })
     }
 }
@@ -135,6 +188,14 @@ fn parse_chain(
         .get_key("reorgPeriod")
         .parse_u32()
         .unwrap_or(1);
+    let finality = parse_finality(&chain, reorg_period, &mut err);
+
+    // The chain ids of some protocols aren't numbers, and aren't checked
+    let chain_id = chain
+        .get_opt_key("chainId")
+        .ok()
+        .flatten()
+        .and_then(|value_parser| value_parser.parse_u64().ok());
 
     let rpcs = parse_base_and_override_urls(&chain, "rpcUrls", "customRpcUrls", "http", &mut err);
 
@@ -161,10 +222,31 @@ fn parse_chain(
                 .and_then(|d| match d.domain_protocol() {
                     HyperlaneDomainProtocol::Ethereum => Some(IndexMode::Block),
                     HyperlaneDomainProtocol::Sealevel => Some(IndexMode::Sequence),
+                    HyperlaneDomainProtocol::Aptos => Some(IndexMode::Sequence),
                     _ => None,
                 })
                 .unwrap_or_default()
         });
+    let skip_gaps_after_rewinds = chain
+        .chain(&mut err)
+        .get_opt_key("index")
+        .get_opt_key("skipGapsAfterRewinds")
+        .parse_u32()
+        .end();
+    if skip_gaps_after_rewinds.is_some_and(|rewinds| rewinds < 2) {
+        err.push(
+            &chain.cwp + "index.skipGapsAfterRewinds",
+            eyre!("gaps can only be skipped after at least 2 rewinds"),
+        );
+    }
+    let archive_rpc_urls =
+        parse_custom_urls(&chain, "archiveRpcUrls", &mut err).unwrap_or_default();
+    let archive_switchover_blocks = chain
+        .chain(&mut err)
+        .get_opt_key("index")
+        .get_opt_key("archiveSwitchoverBlocks")
+        .parse_u32()
+        .end();
 
     let mailbox = chain
         .chain(&mut err)
@@ -199,7 +281,35 @@ fn parse_chain(
         .parse_u32()
         .unwrap_or(1);
 
+    let batch_window = chain
+        .chain(&mut err)
+        .get_opt_key("batchWindowMs")
+        .parse_u64()
+        .map(Duration::from_millis)
+        .end();
+
+    let min_signer_balance = chain
+        .chain(&mut err)
+        .get_opt_key("minSignerBalance")
+        .parse_f64()
+        .end();
+
+    let retry_policy = parse_retry_policy(&chain, &mut err);
+
+    let gas_limit_policy = parse_gas_limit_policy(&chain, &mut err);
+
     cfg_unwrap_all!(&chain.cwp, err: [domain]);
+    if !matches!(finality, Finality::Blocks(_))
+        && !matches!(
+            domain.domain_protocol(),
+            HyperlaneDomainProtocol::Ethereum | HyperlaneDomainProtocol::Tron
+        )
+    {
+        err.push(
+            &chain.cwp + "finality",
+            eyre!("only EVM chains support finality policies other than block counts"),
+        );
+    }
     let connection = build_connection_conf(
         domain.domain_protocol(),
         &rpcs,
@@ -209,14 +319,17 @@ fn parse_chain(
         OperationBatchConfig {
             batch_contract_address,
             max_batch_size,
+            batch_window,
         },
     );
 
     cfg_unwrap_all!(&chain.cwp, err: [connection, mailbox, interchain_gas_paymaster, validator_announce, merkle_tree_hook]);
     err.into_result(ChainConf {
         domain,
+        chain_id,
         signer,
         reorg_period,
+        finality,
         addresses: CoreContractAddresses {
             mailbox,
             interchain_gas_paymaster,
@@ -229,10 +342,153 @@ fn parse_chain(
             from,
             chunk_size,
             mode,
+            skip_gaps_after_rewinds,
+            archive_rpc_urls,
+            archive_switchover_blocks,
         },
+        min_signer_balance,
+        retry_policy,
+        gas_limit_policy,
     })
 }
 
+/// Expects a `gasLimitPolicy` key on `value`, e.g. on ChainMetadata or on
+/// the app contexts of the relayer
+pub fn parse_gas_limit_policy(
+    value: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<GasLimitPolicyConf> {
+    let policy = value
+        .get_opt_key("gasLimitPolicy")
+        .take_err(err, || &value.cwp + "gasLimitPolicy")
+        .flatten()?;
+    let multiplier = policy
+        .chain(err)
+        .get_opt_key("multiplier")
+        .parse_f64()
+        .end();
+    let floor = policy.chain(err).get_opt_key("floor").parse_u256().end();
+    let cap = policy.chain(err).get_opt_key("cap").parse_u256().end();
+    if multiplier.is_some_and(|multiplier| multiplier <= 0.) {
+        err.push(
+            &policy.cwp + "multiplier",
+            eyre!("multiplier must be positive"),
+        );
+    }
+    if let (Some(floor), Some(cap)) = (floor, cap) {
+        if cap < floor {
+            err.push(&policy.cwp + "cap", eyre!("cap must be at least the floor"));
+        }
+    }
+    Some(GasLimitPolicyConf {
+        multiplier,
+        floor,
+        cap,
+    })
+}
+
+/// Expects ChainMetadata.retryPolicy, whose unset fields default to the
+/// default policy
+fn parse_retry_policy(
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<RetryPolicyConf> {
+    let policy = chain
+        .get_opt_key("retryPolicy")
+        .take_err(err, || &chain.cwp + "retryPolicy")
+        .flatten()?;
+    let default = RetryPolicyConf::default();
+    let base_delay = policy
+        .chain(err)
+        .get_opt_key("baseDelaySecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(default.base_delay);
+    let max_delay = policy
+        .chain(err)
+        .get_opt_key("maxDelaySecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(default.max_delay);
+    let jitter = policy
+        .chain(err)
+        .get_opt_key("jitter")
+        .parse_f64()
+        .unwrap_or(default.jitter);
+    let max_attempts = policy
+        .chain(err)
+        .get_opt_key("maxAttempts")
+        .parse_u32()
+        .end();
+    if !(0. ..=1.).contains(&jitter) {
+        err.push(
+            &policy.cwp + "jitter",
+            eyre!("jitter must be a fraction between 0 and 1"),
+        );
+    }
+    if max_delay < base_delay {
+        err.push(
+            &policy.cwp + "maxDelaySecs",
+            eyre!("max delay must be at least the base delay"),
+        );
+    }
+    Some(RetryPolicyConf {
+        base_delay,
+        max_delay,
+        jitter,
+        max_attempts,
+    })
+}
+
+/// Expects ChainMetadata.finality, defaulting to the reorg period
+fn parse_finality(
+    chain: &ValueParser,
+    reorg_period: u32,
+    err: &mut ConfigParsingError,
+) -> Finality {
+    let Some(finality) = chain
+        .get_opt_key("finality")
+        .take_err(err, || &chain.cwp + "finality")
+        .flatten()
+    else {
+        return Finality::Blocks(reorg_period);
+    };
+    let parsed = match finality.chain(err).get_key("type").parse_string().end() {
+        Some("blocks") => Some(Finality::Blocks(
+            finality
+                .chain(err)
+                .get_opt_key("blocks")
+                .parse_u32()
+                .unwrap_or(reorg_period),
+        )),
+        Some("time") => finality
+            .chain(err)
+            .get_key("seconds")
+            .parse_u64()
+            .map(|seconds| Finality::Time(Duration::from_secs(seconds)))
+            .end(),
+        Some("tag") => match finality.chain(err).get_key("tag").parse_string().end() {
+            Some("safe") => Some(Finality::Tag(BlockTag::Safe)),
+            Some("finalized") => Some(Finality::Tag(BlockTag::Finalized)),
+            Some(tag) => {
+                err.push(&finality.cwp + "tag", eyre!("unknown block tag `{tag}`"));
+                None
+            }
+            None => None,
+        },
+        Some("l1Settlement") => Some(Finality::L1Settlement),
+        Some(ty) => {
+            err.push(
+                &finality.cwp + "type",
+                eyre!("unknown finality type `{ty}`"),
+            );
+            None
+        }
+        None => None,
+    };
+    parsed.unwrap_or(Finality::Blocks(reorg_period))
+}
+
 /// Expects ChainMetadata
 fn parse_domain(chain: ValueParser, name: &str) -> ConfigResult<HyperlaneDomain> {
     let mut err = ConfigParsingError::default();
@@ -318,6 +574,20 @@ fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
                 .unwrap_or_default();
             err.into_result(SignerConf::Aws { id, region })
         }};
+        (remote) => {{
+            let url = signer
+                .chain(&mut err)
+                .get_key("url")
+                .parse_from_str("Expected remote signer url")
+                .end();
+            let address = signer
+                .chain(&mut err)
+                .get_opt_key("address")
+                .parse_from_str("Expected address of the remote signer key")
+                .end();
+            cfg_unwrap_all!(&signer.cwp, err: [url]);
+            err.into_result(SignerConf::Remote { url, address })
+        }};
         (cosmosKey) => {{
             let key = signer
                 .chain(&mut err)
@@ -329,17 +599,118 @@ fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
                 .get_key("prefix")
                 .parse_string()
                 .unwrap_or_default();
+            let account_address_type = signer
+                .chain(&mut err)
+                .get_opt_key("accountAddressType")
+                .parse_from_str("Expected account address type")
+                .unwrap_or_default();
             err.into_result(SignerConf::CosmosKey {
                 key,
                 prefix: prefix.to_string(),
+                account_address_type,
             })
         }};
+        (starknetKey) => {{
+            let key = signer
+                .chain(&mut err)
+                .get_key("key")
+                .parse_private_key()
+                .unwrap_or_default();
+            let address = signer
+                .chain(&mut err)
+                .get_key("address")
+                .parse_string()
+                .end();
+            // Account addresses are felts, often written without their
+            // leading zeros
+            let address = address
+                .and_then(|address| {
+                    HyperlaneDomainProtocol::Starknet
+                        .parse_address(address)
+                        .take_err(&mut err, || &signer.cwp + "address")
+                })
+                .unwrap_or_default();
+            err.into_result(SignerConf::StarknetKey { key, address })
+        }};
+        (suiKey) => {{
+            let key = signer
+                .chain(&mut err)
+                .get_key("key")
+                .parse_private_key()
+                .unwrap_or_default();
+            let sponsor_key = signer
+                .chain(&mut err)
+                .get_opt_key("sponsorKey")
+                .parse_private_key()
+                .end();
+            err.into_result(SignerConf::SuiKey { key, sponsor_key })
+        }};
+        (tonKey) => {{
+            let key = signer
+                .chain(&mut err)
+                .get_key("key")
+                .parse_private_key()
+                .unwrap_or_default();
+            let address = signer
+                .chain(&mut err)
+                .get_key("address")
+                .parse_string()
+                .end();
+            // Wallet addresses are usually written in their user-friendly form
+            let address = address
+                .and_then(|address| {
+                    HyperlaneDomainProtocol::Ton
+                        .parse_address(address)
+                        .take_err(&mut err, || &signer.cwp + "address")
+                })
+                .unwrap_or_default();
+            err.into_result(SignerConf::TonKey { key, address })
+        }};
+        (substrateKey) => {{
+            let key = signer
+                .chain(&mut err)
+                .get_key("key")
+                .parse_private_key()
+                .unwrap_or_default();
+            let scheme = signer
+                .chain(&mut err)
+                .get_opt_key("scheme")
+                .parse_from_str("Expected signature scheme")
+                .unwrap_or_default();
+            err.into_result(SignerConf::SubstrateKey { key, scheme })
+        }};
+        (nearKey) => {{
+            let key = signer
+                .chain(&mut err)
+                .get_key("key")
+                .parse_private_key()
+                .unwrap_or_default();
+            let account_id = signer
+                .chain(&mut err)
+                .get_opt_key("accountId")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            err.into_result(SignerConf::NearKey { key, account_id })
+        }};
     }
 
     match signer_type {
         Some("hexKey") => parse_signer!(hexKey),
         Some("aws") => parse_signer!(aws),
+        Some("remote") => parse_signer!(remote),
+        #[cfg(feature = "cosmos")]
         Some("cosmosKey") => parse_signer!(cosmosKey),
+        #[cfg(feature = "starknet")]
+        Some("starknetKey") => parse_signer!(starknetKey),
+        #[cfg(feature = "sui")]
+        Some("suiKey") => parse_signer!(suiKey),
+        #[cfg(feature = "ton")]
+        Some("tonKey") => parse_signer!(tonKey),
+        #[cfg(feature = "substrate")]
+        Some("substrateKey") => parse_signer!(substrateKey),
+        #[cfg(feature = "near")]
+        Some("nearKey") => parse_signer!(nearKey),
         Some(t) => {
             Err(eyre!("Unknown signer type `{t}`")).into_config_result(|| &signer.cwp + "type")
         }
@@ -385,7 +756,173 @@ pub fn recase_json_value(mut val: Value, case: Case) -> Value {
     val
 }
 
+/// Expects a JSON array, or a string of one whose keys are re-cased to flat
+/// case.
+pub fn parse_json_array(p: ValueParser) -> Option<(ConfigPath, Value)> {
+    let mut err = ConfigParsingError::default();
+
+    match p {
+        ValueParser {
+            val: Value::String(array_str),
+            cwp,
+        } => serde_json::from_str::<Value>(array_str)
+            .context("Expected JSON string")
+            .take_err(&mut err, || cwp.clone())
+            .map(|v| (cwp, recase_json_value(v, Case::Flat))),
+        ValueParser {
+            val: value @ Value::Array(_),
+            cwp,
+        } => Some((cwp, value.clone())),
+        _ => Err(eyre!("Expected JSON array or stringified JSON"))
+            .take_err(&mut err, || p.cwp.clone()),
+    }
+}
+
+/// Expects a matching list, as a JSON array or a string of one. A missing
+/// list is empty, see [`MatchingList::msg_matches`].
+pub fn parse_matching_list(p: ValueParser) -> ConfigResult<MatchingList> {
+    let mut err = ConfigParsingError::default();
+
+    let raw_list = parse_json_array(p.clone()).map(|(_, v)| v);
+    let Some(raw_list) = raw_list else {
+        return err.into_result(MatchingList::default());
+    };
+    let p = ValueParser::new(p.cwp.clone(), &raw_list);
+    let ml = p
+        .parse_value::<MatchingList>("Expected matching list")
+        .take_config_err(&mut err)
+        .unwrap_or_default();
+
+    err.into_result(ml)
+}
+
+/// Expects AgentConfig.otlp
+fn parse_otlp(otlp: ValueParser) -> ConfigResult<OtlpConf> {
+    let mut err = ConfigParsingError::default();
+
+    let endpoint = otlp
+        .chain(&mut err)
+        .get_key("endpoint")
+        .parse_from_str("Invalid OTLP endpoint")
+        .end();
+    let headers = parse_string_map(&otlp, "headers", &mut err);
+    let traces = otlp
+        .chain(&mut err)
+        .get_opt_key("traces")
+        .parse_bool()
+        .unwrap_or(true);
+    let metrics = otlp
+        .chain(&mut err)
+        .get_opt_key("metrics")
+        .parse_bool()
+        .unwrap_or(true);
+    let metrics_interval = otlp
+        .chain(&mut err)
+        .get_opt_key("metricsIntervalSecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_OTLP_METRICS_INTERVAL);
+    let resource_attributes = parse_string_map(&otlp, "resourceAttributes", &mut err);
+
+    cfg_unwrap_all!(&otlp.cwp, err: [endpoint]);
+    err.into_result(OtlpConf {
+        endpoint,
+        headers,
+        traces,
+        metrics,
+        metrics_interval,
+        resource_attributes,
+    })
+}
+
+/// Expects an optional object of strings under `key`
+fn parse_string_map(
+    p: &ValueParser,
+    key: &str,
+    err: &mut ConfigParsingError,
+) -> HashMap<String, String> {
+    let entries: Vec<_> = p
+        .chain(err)
+        .get_opt_key(key)
+        .into_obj_iter()
+        .map(|entries| entries.collect())
+        .unwrap_or_default();
+    entries
+        .into_iter()
+        .filter_map(|(name, value)| {
+            value
+                .chain(err)
+                .parse_string()
+                .end()
+                .map(|value| (name, value.to_owned()))
+        })
+        .collect()
+}
+
+/// Expects the `dbIntegrityCheck` agent config
+pub fn parse_db_integrity_check(check: ValueParser) -> ConfigResult<IntegrityCheckConf> {
+    let mut err = ConfigParsingError::default();
+
+    let depth = check
+        .chain(&mut err)
+        .get_opt_key("depth")
+        .parse_u32()
+        .unwrap_or(DEFAULT_INTEGRITY_CHECK_DEPTH);
+    let repair = check
+        .chain(&mut err)
+        .get_opt_key("repair")
+        .parse_bool()
+        .unwrap_or(false);
+
+    err.into_result(IntegrityCheckConf { depth, repair })
+}
+
+/// Expects the `syncSupervisor` agent config, whose unset fields default to
+/// the default config
+pub fn parse_supervisor(supervisor: ValueParser) -> ConfigResult<SupervisorConf> {
+    let mut err = ConfigParsingError::default();
+    let default = SupervisorConf::default();
+
+    let stall_timeout = supervisor
+        .chain(&mut err)
+        .get_opt_key("stallTimeoutSecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(default.stall_timeout);
+    let min_backoff = supervisor
+        .chain(&mut err)
+        .get_opt_key("minBackoffSecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(default.min_backoff);
+    let max_backoff = supervisor
+        .chain(&mut err)
+        .get_opt_key("maxBackoffSecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(default.max_backoff);
+    if stall_timeout.is_zero() {
+        err.push(
+            &supervisor.cwp + "stallTimeoutSecs",
+            eyre!("stall timeout must be positive"),
+        );
+    }
+    if max_backoff < min_backoff {
+        err.push(
+            &supervisor.cwp + "maxBackoffSecs",
+            eyre!("max backoff must be at least the min backoff"),
+        );
+    }
+
+    err.into_result(SupervisorConf {
+        stall_timeout,
+        min_backoff,
+        max_backoff,
+    })
+}
+
 /// Expects AgentSigner.
+#[cfg(feature = "cosmos")]
 fn parse_cosmos_gas_price(gas_price: ValueParser) -> ConfigResult<RawCosmosAmount> {
     let mut err = ConfigParsingError::default();
 
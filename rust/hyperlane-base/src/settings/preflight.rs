@@ -0,0 +1,114 @@
+use eyre::{eyre, Context, Result};
+use futures_util::future::join_all;
+use hyperlane_core::{HyperlaneDomain, HyperlaneProvider, H256};
+use tracing::info;
+
+use crate::{
+    settings::{chains::ChainConf, Settings},
+    AgentHealth, CoreMetrics,
+};
+
+impl ChainConf {
+    /// Check that the RPC of the chain responds, that it serves the chain
+    /// the domain is for, and that the core contracts are deployed at the
+    /// configured addresses, so misconfigurations fail on startup with an
+    /// actionable error rather than later with a cryptic one.
+    pub async fn preflight_check(&self, metrics: &CoreMetrics) -> Result<()> {
+        let provider = self.build_provider(metrics).await?;
+
+        if let Some(expected) = self.chain_id {
+            let chain_id = provider.get_chain_id().await.with_context(|| {
+                format!("The RPC of {} doesn't respond, check its url", self.domain)
+            })?;
+            if let Some(actual) = chain_id.filter(|actual| *actual != expected) {
+                return Err(eyre!(
+                    "The RPC of {} serves chain {actual} but chain {expected} is configured, check its url",
+                    self.domain
+                ));
+            }
+        }
+
+        self.check_contract(provider.as_ref(), "mailbox", self.addresses.mailbox)
+            .await?;
+        self.check_contract(
+            provider.as_ref(),
+            "interchainGasPaymaster",
+            self.addresses.interchain_gas_paymaster,
+        )
+        .await?;
+        self.check_contract(
+            provider.as_ref(),
+            "validatorAnnounce",
+            self.addresses.validator_announce,
+        )
+        .await?;
+
+        // Calling the mailbox checks the address is a mailbox rather than
+        // some other contract
+        let mailbox = self.build_mailbox(metrics).await?;
+        mailbox.default_ism().await.with_context(|| {
+            format!(
+                "The mailbox of {} at {:?} doesn't implement the Mailbox interface, check the `mailbox` address",
+                self.domain, self.addresses.mailbox
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Check a contract is deployed at `address`, which is configured by
+    /// `key`. Unset addresses aren't checked.
+    async fn check_contract(
+        &self,
+        provider: &dyn HyperlaneProvider,
+        key: &str,
+        address: H256,
+    ) -> Result<()> {
+        if address.is_zero() {
+            return Ok(());
+        }
+        let deployed = provider.is_contract(&address).await.with_context(|| {
+            format!("The RPC of {} doesn't respond, check its url", self.domain)
+        })?;
+        if !deployed {
+            return Err(eyre!(
+                "No contract is deployed at {address:?} on {}, check the `{key}` address",
+                self.domain
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Settings {
+    /// Run the preflight checks of all of `domains` concurrently, reporting
+    /// all the chains that failed them at once.
+    pub async fn preflight_check(
+        &self,
+        domains: &[HyperlaneDomain],
+        metrics: &CoreMetrics,
+    ) -> Result<()> {
+        let results = join_all(domains.iter().map(|domain| async move {
+            let checked = self.chain_setup(domain)?.preflight_check(metrics).await;
+            if checked.is_ok() {
+                // The checks include RPC round trips
+                AgentHealth::global().pass(format!("rpc:{}", domain.name()));
+            }
+            checked
+        }))
+        .await;
+        let errors = domains
+            .iter()
+            .zip(results)
+            .filter_map(|(domain, result)| result.err().map(|err| format!("{domain}: {err:#}")))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(eyre!(
+                "Preflight checks failed for {} chain(s):\n{}",
+                errors.len(),
+                errors.join("\n")
+            ));
+        }
+        info!(chains = domains.len(), "Preflight checks passed");
+        Ok(())
+    }
+}
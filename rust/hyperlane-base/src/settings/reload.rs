@@ -0,0 +1,363 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use eyre::Result;
+use hyperlane_ethereum::{reload_rpc_urls, RpcConnectionConf};
+use tokio::{
+    task::JoinHandle,
+    time::{interval_at, Instant, Interval},
+};
+use tracing::{info, info_span, instrument::Instrumented, warn, Instrument};
+use url::Url;
+
+use crate::{
+    settings::{loader::config_file_paths, ChainConf, ChainConnectionConf, LogFilterHandle},
+    LoadableFromSettings, Settings,
+};
+
+/// Reloads the config when the process receives SIGHUP or, if configured,
+/// when a config file changes, so that settings that don't change the
+/// structure of the agent can be changed without restarting it
+#[derive(Debug)]
+pub struct ConfigReloader {
+    hangup: Hangup,
+    watch: Option<Interval>,
+    modified: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl ConfigReloader {
+    /// Creates a new instance of the `ConfigReloader`, which checks the
+    /// config files for changes every `watch_interval`, if set
+    pub fn new(watch_interval: Option<Duration>) -> Result<Self> {
+        Ok(Self {
+            hangup: Hangup::new()?,
+            watch: watch_interval.map(|period| interval_at(Instant::now() + period, period)),
+            modified: modified_times(),
+        })
+    }
+
+    /// Waits until the config should be reloaded, and then loads it. Configs
+    /// that fail to load are logged and skipped, keeping the current one.
+    pub async fn reload<S: LoadableFromSettings>(&mut self) -> S {
+        loop {
+            let trigger = self.changed().await;
+            match S::load() {
+                Ok(settings) => {
+                    info!(trigger, "Reloaded config");
+                    return settings;
+                }
+                Err(err) => warn!(
+                    trigger,
+                    ?err,
+                    "Failed to reload config, keeping the current one"
+                ),
+            }
+        }
+    }
+
+    /// Spawns a task applying the reloadable settings of `settings` whenever
+    /// the config is reloaded, for agents without settings of their own to
+    /// reload. The task applies them to its own copy of `settings`, which only
+    /// tracks what was applied, so only settings that take effect globally,
+    /// see [Settings::apply_reloaded], are reloaded.
+    pub fn spawn<S: LoadableFromSettings + Send + 'static>(
+        mut self,
+        settings: &Settings,
+    ) -> Instrumented<JoinHandle<()>> {
+        let mut settings = settings.clone();
+        tokio::spawn(async move {
+            loop {
+                let reloaded: S = self.reload().await;
+                settings.apply_reloaded(reloaded.as_ref());
+            }
+        })
+        .instrument(info_span!("ConfigReloader"))
+    }
+
+    /// Waits for SIGHUP or a change of the config files, returning which
+    async fn changed(&mut self) -> &'static str {
+        loop {
+            tokio::select! {
+                _ = self.hangup.recv() => return "SIGHUP",
+                _ = next_tick(&mut self.watch) => {
+                    let modified = modified_times();
+                    if modified != self.modified {
+                        self.modified = modified;
+                        return "config file changed";
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Settings {
+    /// Applies the settings of `reloaded` that can change while the agent is
+    /// running for every agent: the log level and the HTTP RPC URLs of EVM
+    /// chains, which are both applied globally. Structural changes, like
+    /// chains that were added or removed, are logged and ignored, since they
+    /// require building the agent again. Other chain settings, like contract
+    /// addresses, signers or retry policies, are left to the agents that
+    /// reload them, or only applied on restart.
+    pub fn apply_reloaded(&mut self, reloaded: &Settings) {
+        for change in structural_changes(&self.chains, &reloaded.chains) {
+            warn!(
+                change,
+                "Ignoring structural config change, restart the agent to apply it"
+            );
+        }
+
+        if reloaded.tracing.level != self.tracing.level {
+            if let Some(filter) = LogFilterHandle::global() {
+                match filter.set_configured(reloaded.tracing.targets()) {
+                    Ok(()) => info!(level = ?reloaded.tracing.level, "Reloaded log level"),
+                    Err(err) => warn!(?err, "Failed to reload log level"),
+                }
+            }
+            self.tracing.level = reloaded.tracing.level;
+        }
+
+        for (name, chain) in self.chains.iter_mut() {
+            let Some(reloaded) = reloaded.chains.get(name) else {
+                continue;
+            };
+            if chain.domain != reloaded.domain {
+                continue;
+            }
+            let (ChainConnectionConf::Ethereum(conn), ChainConnectionConf::Ethereum(reloaded)) =
+                (&mut chain.connection, &reloaded.connection)
+            else {
+                continue;
+            };
+            let (kind, urls) = rpc_urls(&conn.rpc_connection);
+            let (reloaded_kind, reloaded_urls) = rpc_urls(&reloaded.rpc_connection);
+            // Websocket providers are connected once, so their URL can't be reloaded
+            if kind == "ws" || kind != reloaded_kind || urls == reloaded_urls {
+                continue;
+            }
+            if reload_rpc_urls(name, &reloaded_urls) {
+                info!(chain = name.as_str(), "Reloaded RPC URLs");
+                conn.rpc_connection = reloaded.rpc_connection.clone();
+            } else {
+                warn!(
+                    chain = name.as_str(),
+                    "Number of RPCs changed, restart the agent to apply it"
+                );
+            }
+        }
+    }
+}
+
+/// The changes from `applied` to `reloaded` chains that can't be applied
+/// while the agent is running
+fn structural_changes(
+    applied: &HashMap<String, ChainConf>,
+    reloaded: &HashMap<String, ChainConf>,
+) -> Vec<String> {
+    let mut changes = vec![];
+    for name in reloaded.keys().filter(|name| !applied.contains_key(*name)) {
+        changes.push(format!("chain {name} was added"));
+    }
+    for (name, chain) in applied {
+        let Some(reloaded) = reloaded.get(name) else {
+            changes.push(format!("chain {name} was removed"));
+            continue;
+        };
+        if chain.domain != reloaded.domain {
+            changes.push(format!("domain of chain {name} changed"));
+        } else if chain.connection.protocol() != reloaded.connection.protocol() {
+            changes.push(format!("protocol of chain {name} changed"));
+        } else if let (
+            ChainConnectionConf::Ethereum(conn),
+            ChainConnectionConf::Ethereum(reloaded),
+        ) = (&chain.connection, &reloaded.connection)
+        {
+            let (kind, urls) = rpc_urls(&conn.rpc_connection);
+            let (reloaded_kind, reloaded_urls) = rpc_urls(&reloaded.rpc_connection);
+            if kind != reloaded_kind || (kind == "ws" && urls != reloaded_urls) {
+                changes.push(format!("RPC connection of chain {name} changed"));
+            }
+        }
+    }
+    changes.sort();
+    changes
+}
+
+/// How the RPCs of an EVM chain are connected to, and their URLs
+fn rpc_urls(rpc_connection: &RpcConnectionConf) -> (&'static str, Vec<Url>) {
+    match rpc_connection {
+        RpcConnectionConf::HttpQuorum { urls } => ("httpQuorum", urls.clone()),
+        RpcConnectionConf::HttpFallback { urls } => ("httpFallback", urls.clone()),
+        RpcConnectionConf::Http { url } => ("http", vec![url.clone()]),
+        RpcConnectionConf::Ws { url } => ("ws", vec![url.clone()]),
+    }
+}
+
+/// When each config file was last modified, if it can be read
+fn modified_times() -> Vec<(PathBuf, Option<SystemTime>)> {
+    config_file_paths()
+        .into_iter()
+        .map(|path| {
+            let modified = path.metadata().and_then(|meta| meta.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
+
+/// Waits for the next tick of `interval`, or forever if there is none
+async fn next_tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => futures_util::future::pending().await,
+    }
+}
+
+/// SIGHUP, which other platforms than unix don't have
+#[cfg(unix)]
+#[derive(Debug)]
+struct Hangup(tokio::signal::unix::Signal);
+
+#[cfg(unix)]
+impl Hangup {
+    fn new() -> Result<Self> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        Ok(Self(signal(SignalKind::hangup())?))
+    }
+
+    async fn recv(&mut self) {
+        if self.0.recv().await.is_none() {
+            futures_util::future::pending().await
+        }
+    }
+}
+
+#[cfg(not(unix))]
+#[derive(Debug)]
+struct Hangup;
+
+#[cfg(not(unix))]
+impl Hangup {
+    fn new() -> Result<Self> {
+        Ok(Self)
+    }
+
+    async fn recv(&mut self) {
+        futures_util::future::pending().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{HyperlaneDomain, KnownHyperlaneDomain};
+
+    use super::*;
+    use crate::settings::RetryPolicyConf;
+
+    fn url(s: &str) -> Url {
+        s.parse().unwrap()
+    }
+
+    fn chain(domain: KnownHyperlaneDomain, rpc_connection: RpcConnectionConf) -> ChainConf {
+        ChainConf {
+            domain: HyperlaneDomain::from(domain),
+            chain_id: None,
+            signer: Default::default(),
+            reorg_period: Default::default(),
+            finality: Default::default(),
+            addresses: Default::default(),
+            connection: ChainConnectionConf::Ethereum(hyperlane_ethereum::ConnectionConf {
+                rpc_connection,
+                transaction_overrides: Default::default(),
+                operation_batch: Default::default(),
+                quirks: Default::default(),
+                light_client_url: None,
+            }),
+            metrics_conf: Default::default(),
+            index: Default::default(),
+            min_signer_balance: None,
+            retry_policy: Default::default(),
+            gas_limit_policy: None,
+        }
+    }
+
+    fn settings(chains: Vec<ChainConf>) -> Settings {
+        Settings {
+            chains: chains
+                .into_iter()
+                .map(|chain| (chain.domain.name().to_owned(), chain))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn applies_reloadable_settings_only() {
+        let mut applied = settings(vec![
+            chain(
+                KnownHyperlaneDomain::Test1,
+                RpcConnectionConf::HttpFallback {
+                    urls: vec![url("http://a.test"), url("http://b.test")],
+                },
+            ),
+            chain(
+                KnownHyperlaneDomain::Test2,
+                RpcConnectionConf::Ws {
+                    url: url("ws://a.test"),
+                },
+            ),
+        ]);
+        let mut test1 = chain(
+            KnownHyperlaneDomain::Test1,
+            RpcConnectionConf::HttpFallback {
+                urls: vec![url("http://c.test"), url("http://d.test")],
+            },
+        );
+        test1.retry_policy = Some(RetryPolicyConf::default());
+        let reloaded = settings(vec![
+            test1,
+            chain(
+                KnownHyperlaneDomain::Test2,
+                RpcConnectionConf::Ws {
+                    url: url("ws://b.test"),
+                },
+            ),
+            chain(
+                KnownHyperlaneDomain::Test3,
+                RpcConnectionConf::Http {
+                    url: url("http://a.test"),
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            structural_changes(&applied.chains, &reloaded.chains),
+            vec![
+                "RPC connection of chain test2 changed".to_owned(),
+                "chain test3 was added".to_owned(),
+            ]
+        );
+
+        applied.apply_reloaded(&reloaded);
+        assert_eq!(applied.chains.len(), 2);
+        let test1 = &applied.chains["test1"];
+        // Retry policies are reloaded by the agents using them
+        assert!(test1.retry_policy.is_none());
+        let ChainConnectionConf::Ethereum(conn) = &test1.connection else {
+            panic!("Not an EVM chain");
+        };
+        assert_eq!(
+            rpc_urls(&conn.rpc_connection).1,
+            vec![url("http://c.test"), url("http://d.test")]
+        );
+        let ChainConnectionConf::Ethereum(conn) = &applied.chains["test2"].connection else {
+            panic!("Not an EVM chain");
+        };
+        assert_eq!(rpc_urls(&conn.rpc_connection).1, vec![url("ws://a.test")]);
+    }
+}
@@ -1,13 +1,20 @@
 use async_trait::async_trait;
+#[cfg(feature = "sealevel")]
 use ed25519_dalek::SecretKey;
 use ethers::prelude::{AwsSigner, LocalWallet};
 use ethers::utils::hex::ToHex;
 use eyre::{bail, Context, Report};
-use hyperlane_core::H256;
+use hyperlane_core::{H160, H256};
+#[cfg(feature = "cosmos")]
+use hyperlane_cosmos::address::AccountAddressType;
+#[cfg(feature = "sealevel")]
 use hyperlane_sealevel::Keypair;
+#[cfg(feature = "substrate")]
+use hyperlane_substrate::SignatureScheme;
 use rusoto_core::Region;
 use rusoto_kms::KmsClient;
 use tracing::instrument;
+use url::Url;
 
 use super::aws_credentials::AwsChainCredentialsProvider;
 use crate::types::utils;
@@ -28,12 +35,65 @@ pub enum SignerConf {
         /// The AWS region
         region: Region,
     },
+    /// A Web3Signer-compatible signing service, which holds the key so the
+    /// agent never does. Only signs messages, e.g. checkpoints.
+    Remote {
+        /// The JSON-RPC endpoint of the service
+        url: Url,
+        /// The address of the key - required if the service holds several
+        address: Option<H160>,
+    },
     /// Cosmos Specific key
+    #[cfg(feature = "cosmos")]
     CosmosKey {
         /// Private key value
         key: H256,
         /// Prefix for cosmos address
         prefix: String,
+        /// The scheme used to derive the account address, e.g. `ethereum`
+        /// for Ethermint-based chains such as Injective
+        account_address_type: AccountAddressType,
+    },
+    /// Starknet specific key, along with the account contract it signs for
+    #[cfg(feature = "starknet")]
+    StarknetKey {
+        /// Private key value
+        key: H256,
+        /// Address of the account contract
+        address: H256,
+    },
+    /// Sui specific key, along with the key of the sponsor paying for the gas
+    /// of its transactions
+    #[cfg(feature = "sui")]
+    SuiKey {
+        /// Private key value
+        key: H256,
+        /// Private key of the gas sponsor, if transactions are sponsored
+        sponsor_key: Option<H256>,
+    },
+    /// TON specific key, along with the wallet contract it controls
+    #[cfg(feature = "ton")]
+    TonKey {
+        /// Private key value
+        key: H256,
+        /// Address of the wallet contract
+        address: H256,
+    },
+    /// Substrate specific key
+    #[cfg(feature = "substrate")]
+    SubstrateKey {
+        /// Seed of the key
+        key: H256,
+        /// The signature scheme of the key, e.g. `sr25519`
+        scheme: SignatureScheme,
+    },
+    /// NEAR specific key, along with the account it belongs to
+    #[cfg(feature = "near")]
+    NearKey {
+        /// Private key value
+        key: H256,
+        /// Id of the account, the implicit account of the key if unset
+        account_id: Option<String>,
     },
     /// Assume node will sign on RPC calls
     #[default]
@@ -83,9 +143,27 @@ impl BuildableWithSignerConf for hyperlane_ethereum::Signers {
                 let signer = AwsSigner::new(client, id, 0).await?;
                 hyperlane_ethereum::Signers::Aws(signer)
             }
+            SignerConf::Remote { url, address } => hyperlane_ethereum::Signers::Remote(
+                hyperlane_ethereum::RemoteSigner::new(url.clone(), *address).await?,
+            ),
+            #[cfg(feature = "cosmos")]
             SignerConf::CosmosKey { .. } => {
                 bail!("cosmosKey signer is not supported by Ethereum")
             }
+            #[cfg(feature = "starknet")]
+            SignerConf::StarknetKey { .. } => {
+                bail!("starknetKey signer is not supported by Ethereum")
+            }
+            #[cfg(feature = "sui")]
+            SignerConf::SuiKey { .. } => bail!("suiKey signer is not supported by Ethereum"),
+            #[cfg(feature = "ton")]
+            SignerConf::TonKey { .. } => bail!("tonKey signer is not supported by Ethereum"),
+            #[cfg(feature = "substrate")]
+            SignerConf::SubstrateKey { .. } => {
+                bail!("substrateKey signer is not supported by Ethereum")
+            }
+            #[cfg(feature = "near")]
+            SignerConf::NearKey { .. } => bail!("nearKey signer is not supported by Ethereum"),
             SignerConf::Node => bail!("Node signer"),
         })
     }
@@ -97,6 +175,7 @@ impl ChainSigner for hyperlane_ethereum::Signers {
     }
 }
 
+#[cfg(feature = "fuel")]
 #[async_trait]
 impl BuildableWithSignerConf for fuels::prelude::WalletUnlocked {
     async fn build(conf: &SignerConf) -> Result<Self, Report> {
@@ -112,12 +191,14 @@ impl BuildableWithSignerConf for fuels::prelude::WalletUnlocked {
     }
 }
 
+#[cfg(feature = "fuel")]
 impl ChainSigner for fuels::prelude::WalletUnlocked {
     fn address_string(&self) -> String {
         self.address().to_string()
     }
 }
 
+#[cfg(feature = "sealevel")]
 #[async_trait]
 impl BuildableWithSignerConf for Keypair {
     async fn build(conf: &SignerConf) -> Result<Self, Report> {
@@ -134,19 +215,27 @@ impl BuildableWithSignerConf for Keypair {
     }
 }
 
+#[cfg(feature = "sealevel")]
 impl ChainSigner for Keypair {
     fn address_string(&self) -> String {
         solana_sdk::signer::Signer::pubkey(self).to_string()
     }
 }
 
+#[cfg(feature = "cosmos")]
 #[async_trait]
 impl BuildableWithSignerConf for hyperlane_cosmos::Signer {
     async fn build(conf: &SignerConf) -> Result<Self, Report> {
-        if let SignerConf::CosmosKey { key, prefix } = conf {
+        if let SignerConf::CosmosKey {
+            key,
+            prefix,
+            account_address_type,
+        } = conf
+        {
             Ok(hyperlane_cosmos::Signer::new(
                 key.as_bytes().to_vec(),
                 prefix.clone(),
+                *account_address_type,
             )?)
         } else {
             bail!(format!("{conf:?} key is not supported by cosmos"));
@@ -154,8 +243,189 @@ impl BuildableWithSignerConf for hyperlane_cosmos::Signer {
     }
 }
 
+#[cfg(feature = "cosmos")]
 impl ChainSigner for hyperlane_cosmos::Signer {
     fn address_string(&self) -> String {
         self.address.clone()
     }
 }
+
+#[cfg(feature = "starknet")]
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_starknet::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        if let SignerConf::StarknetKey { key, address } = conf {
+            Ok(hyperlane_starknet::Signer::new(key, address)?)
+        } else {
+            bail!(format!("{conf:?} key is not supported by starknet"));
+        }
+    }
+}
+
+#[cfg(feature = "starknet")]
+impl ChainSigner for hyperlane_starknet::Signer {
+    fn address_string(&self) -> String {
+        self.address_string()
+    }
+}
+
+#[cfg(feature = "aptos")]
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_aptos::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        if let SignerConf::HexKey { key } = conf {
+            Ok(hyperlane_aptos::Signer::new(key)?)
+        } else {
+            bail!(format!("{conf:?} key is not supported by aptos"));
+        }
+    }
+}
+
+#[cfg(feature = "aptos")]
+impl ChainSigner for hyperlane_aptos::Signer {
+    fn address_string(&self) -> String {
+        self.address_string()
+    }
+}
+
+#[cfg(feature = "sui")]
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_sui::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        match conf {
+            SignerConf::HexKey { key } => Ok(hyperlane_sui::Signer::new(key, None)?),
+            SignerConf::SuiKey { key, sponsor_key } => {
+                Ok(hyperlane_sui::Signer::new(key, sponsor_key.as_ref())?)
+            }
+            _ => bail!(format!("{conf:?} key is not supported by sui")),
+        }
+    }
+}
+
+#[cfg(feature = "sui")]
+impl ChainSigner for hyperlane_sui::Signer {
+    fn address_string(&self) -> String {
+        self.address_string()
+    }
+}
+
+#[cfg(feature = "ton")]
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_ton::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        if let SignerConf::TonKey { key, address } = conf {
+            Ok(hyperlane_ton::Signer::new(key, *address)?)
+        } else {
+            bail!(format!("{conf:?} key is not supported by ton"));
+        }
+    }
+}
+
+#[cfg(feature = "ton")]
+impl ChainSigner for hyperlane_ton::Signer {
+    fn address_string(&self) -> String {
+        self.address_string()
+    }
+}
+
+#[cfg(feature = "substrate")]
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_substrate::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        match conf {
+            SignerConf::HexKey { key } => Ok(hyperlane_substrate::Signer::new(
+                key,
+                SignatureScheme::default(),
+            )?),
+            SignerConf::SubstrateKey { key, scheme } => {
+                Ok(hyperlane_substrate::Signer::new(key, *scheme)?)
+            }
+            _ => bail!(format!("{conf:?} key is not supported by substrate")),
+        }
+    }
+}
+
+#[cfg(feature = "substrate")]
+impl ChainSigner for hyperlane_substrate::Signer {
+    fn address_string(&self) -> String {
+        self.address_string()
+    }
+}
+
+#[cfg(feature = "tron")]
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_tron::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        if let SignerConf::HexKey { key } = conf {
+            Ok(hyperlane_tron::Signer::new(key)?)
+        } else {
+            bail!(format!("{conf:?} key is not supported by tron"));
+        }
+    }
+}
+
+#[cfg(feature = "tron")]
+impl ChainSigner for hyperlane_tron::Signer {
+    fn address_string(&self) -> String {
+        self.address_string()
+    }
+}
+
+#[cfg(feature = "stellar")]
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_stellar::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        if let SignerConf::HexKey { key } = conf {
+            Ok(hyperlane_stellar::Signer::new(key)?)
+        } else {
+            bail!(format!("{conf:?} key is not supported by stellar"));
+        }
+    }
+}
+
+#[cfg(feature = "stellar")]
+impl ChainSigner for hyperlane_stellar::Signer {
+    fn address_string(&self) -> String {
+        self.address_string()
+    }
+}
+
+#[cfg(feature = "near")]
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_near::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        match conf {
+            SignerConf::HexKey { key } => Ok(hyperlane_near::Signer::new(key, None)?),
+            SignerConf::NearKey { key, account_id } => {
+                Ok(hyperlane_near::Signer::new(key, account_id.clone())?)
+            }
+            _ => bail!(format!("{conf:?} key is not supported by near")),
+        }
+    }
+}
+
+#[cfg(feature = "near")]
+impl ChainSigner for hyperlane_near::Signer {
+    fn address_string(&self) -> String {
+        self.address_string()
+    }
+}
+
+#[cfg(feature = "algorand")]
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_algorand::Signer {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        if let SignerConf::HexKey { key } = conf {
+            Ok(hyperlane_algorand::Signer::new(key)?)
+        } else {
+            bail!(format!("{conf:?} key is not supported by algorand"));
+        }
+    }
+}
+
+#[cfg(feature = "algorand")]
+impl ChainSigner for hyperlane_algorand::Signer {
+    fn address_string(&self) -> String {
+        self.address_string()
+    }
+}
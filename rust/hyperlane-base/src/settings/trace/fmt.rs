@@ -16,6 +16,9 @@ use tracing_subscriber::{
 pub enum Style {
     /// JSON
     Json,
+    /// JSON with consistent fields for the agent and the message an event is
+    /// about, see [`StructuredJsonLayer`](super::StructuredJsonLayer)
+    StructuredJson,
     /// Compact
     Compact,
     /// Shows everything
@@ -64,6 +67,8 @@ impl<S> From<Style> for LogOutputLayer<S> {
             Style::Pretty => Self::Pretty(fmt::layer().pretty()),
             Style::Compact => Self::Compact(fmt::layer().compact()),
             Style::Json => Self::Json(fmt::layer().json()),
+            // Needs the agent name, so it's built by `start_tracing`
+            Style::StructuredJson => Self::Json(fmt::layer().json()),
         }
     }
 }
@@ -235,6 +240,12 @@ mod test {
             Style::Json
         );
 
+        let case = r#"{"style": "structuredJson"}"#;
+        assert_eq!(
+            serde_json::from_str::<TestStyle>(case).unwrap().style,
+            Style::StructuredJson
+        );
+
         let case = r#"{"style": "toast"}"#;
         assert_eq!(
             serde_json::from_str::<TestStyle>(case).unwrap().style,
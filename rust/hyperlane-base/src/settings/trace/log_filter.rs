@@ -0,0 +1,190 @@
+use std::{
+    fmt::{Debug, Formatter},
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+use eyre::{eyre, Result};
+use tracing::{info, warn};
+use tracing_subscriber::{filter::Targets, layer::Layered, reload, Registry};
+
+/// The subscriber the reloadable filter layer is applied on top of.
+pub(crate) type FilterSubscriber = Layered<console_subscriber::ConsoleLayer, Registry>;
+
+static LOG_FILTER: OnceLock<LogFilterHandle> = OnceLock::new();
+
+/// Handle to the filter deciding which targets get logged at which level.
+///
+/// Overrides are applied on top of the filter built from the agent config, so
+/// operators can e.g. turn on debug logs for `relayer::msg::op_submitter`
+/// during an incident without restarting the agent and losing queue state.
+pub struct LogFilterHandle {
+    configured: Mutex<Targets>,
+    overrides: Mutex<Option<String>>,
+    handle: reload::Handle<Targets, FilterSubscriber>,
+}
+
+impl Debug for LogFilterHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogFilterHandle")
+            .field("configured", &self.configured)
+            .field("overrides", &self.overrides)
+            .finish()
+    }
+}
+
+impl LogFilterHandle {
+    /// The filter handle of the globally installed tracing subscriber, if
+    /// tracing has been started.
+    pub fn global() -> Option<&'static LogFilterHandle> {
+        LOG_FILTER.get()
+    }
+
+    pub(crate) fn install(configured: Targets, handle: reload::Handle<Targets, FilterSubscriber>) {
+        let filter = Self {
+            configured: Mutex::new(configured),
+            overrides: Mutex::new(None),
+            handle,
+        };
+        if LOG_FILTER.set(filter).is_err() {
+            warn!("Log filter handle was already installed, ignoring");
+        }
+    }
+
+    /// The currently applied override directives, if any.
+    pub fn overrides(&self) -> Option<String> {
+        self.overrides.lock().unwrap().clone()
+    }
+
+    /// Apply comma-separated `target=level` directives on top of the
+    /// configured filter, replacing any previously applied overrides.
+    pub fn set_overrides(&self, directives: &str) -> Result<()> {
+        let targets = apply_overrides(&self.configured.lock().unwrap(), directives)?;
+        self.handle
+            .reload(targets)
+            .map_err(|e| eyre!("Failed to reload log filter: {e}"))?;
+        *self.overrides.lock().unwrap() = Some(directives.to_owned());
+        info!(directives, "Applied log filter overrides");
+        Ok(())
+    }
+
+    /// Drop all overrides and go back to the filter from the agent config.
+    pub fn reset(&self) -> Result<()> {
+        self.handle
+            .reload(self.configured.lock().unwrap().clone())
+            .map_err(|e| eyre!("Failed to reload log filter: {e}"))?;
+        *self.overrides.lock().unwrap() = None;
+        info!("Reset log filter to configured defaults");
+        Ok(())
+    }
+
+    /// Replace the filter from the agent config, e.g. when its log level was
+    /// reloaded. Applied overrides stay applied on top of it.
+    pub fn set_configured(&self, configured: Targets) -> Result<()> {
+        let overrides = self.overrides();
+        let targets = match &overrides {
+            Some(directives) => apply_overrides(&configured, directives)?,
+            None => configured.clone(),
+        };
+        self.handle
+            .reload(targets)
+            .map_err(|e| eyre!("Failed to reload log filter: {e}"))?;
+        *self.configured.lock().unwrap() = configured;
+        Ok(())
+    }
+
+    /// Read override directives from `path`. An empty file resets the filter.
+    pub fn reload_from_file(&self, path: &Path) -> Result<()> {
+        let contents = std::fs::read_to_string(path)?;
+        let directives = contents
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join(",");
+        if directives.is_empty() {
+            self.reset()
+        } else {
+            self.set_overrides(&directives)
+        }
+    }
+
+    /// Re-read `path` every time the process receives SIGUSR2.
+    #[cfg(unix)]
+    pub(crate) fn reload_on_sigusr2(&'static self, path: PathBuf) -> Result<()> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sigusr2 = signal(SignalKind::user_defined2())?;
+        tokio::spawn(async move {
+            while sigusr2.recv().await.is_some() {
+                info!(path = %path.display(), "Received SIGUSR2, reloading log filter");
+                if let Err(err) = self.reload_from_file(&path) {
+                    warn!(?err, path = %path.display(), "Failed to reload log filter");
+                }
+            }
+        });
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn reload_on_sigusr2(&'static self, _path: PathBuf) -> Result<()> {
+        Err(eyre!(
+            "Reloading the log filter on SIGUSR2 is only supported on unix"
+        ))
+    }
+}
+
+/// Layer the directives over the configured targets. Targets named in the
+/// directives replace their configured level and a bare level replaces the
+/// default.
+fn apply_overrides(configured: &Targets, directives: &str) -> Result<Targets> {
+    let overrides: Targets = directives.parse()?;
+    let mut targets = configured.clone().with_targets(
+        overrides
+            .iter()
+            .map(|(target, level)| (target.to_owned(), level)),
+    );
+    if let Some(level) = overrides.default_level() {
+        targets = targets.with_default(level);
+    }
+    Ok(targets)
+}
+
+#[cfg(test)]
+mod test {
+    use tracing::Level;
+
+    use super::*;
+
+    #[test]
+    fn overrides_replace_configured_levels() {
+        let configured = Targets::new()
+            .with_default(Level::INFO)
+            .with_target("hyper", Level::INFO);
+
+        let targets =
+            apply_overrides(&configured, "relayer::msg::op_submitter=debug,hyper=warn").unwrap();
+
+        assert!(targets.would_enable("relayer::msg::op_submitter", &Level::DEBUG));
+        assert!(!targets.would_enable("relayer::msg::processor", &Level::DEBUG));
+        assert!(targets.would_enable("relayer::msg::processor", &Level::INFO));
+        assert!(!targets.would_enable("hyper", &Level::INFO));
+    }
+
+    #[test]
+    fn bare_level_overrides_default() {
+        let configured = Targets::new()
+            .with_default(Level::INFO)
+            .with_target("hyper", Level::INFO);
+
+        let targets = apply_overrides(&configured, "debug").unwrap();
+
+        assert!(targets.would_enable("relayer", &Level::DEBUG));
+        assert!(!targets.would_enable("hyper", &Level::DEBUG));
+    }
+
+    #[test]
+    fn invalid_directives_are_rejected() {
+        assert!(apply_overrides(&Targets::new(), "relayer=notalevel").is_err());
+    }
+}
@@ -1,17 +1,29 @@
+use std::path::PathBuf;
+
 use eyre::Result;
+pub use log_filter::LogFilterHandle;
+pub use otlp::link_to_message;
 pub use span_metrics::TimeSpanLifetime;
+pub use structured::StructuredJsonLayer;
 use tracing_subscriber::{
     filter::{LevelFilter, Targets},
     prelude::*,
+    reload,
 };
 
 use self::fmt::LogOutputLayer;
-use crate::{settings::trace::fmt::Style, CoreMetrics};
+use crate::{
+    settings::{trace::fmt::Style, OtlpExporter},
+    CoreMetrics,
+};
 
 /// Configure a `tracing_subscriber::fmt` Layer outputting to stdout
 pub mod fmt;
 
+mod log_filter;
+mod otlp;
 mod span_metrics;
+mod structured;
 
 /// Logging level. A "higher level" means more will be logged.
 #[derive(Default, Debug, Clone, Copy, serde::Deserialize, PartialOrd, Ord, PartialEq, Eq)]
@@ -55,12 +67,57 @@ pub struct TracingConfig {
     pub(crate) fmt: Style,
     #[serde(default)]
     pub(crate) level: Level,
+    /// File of log filter overrides that is re-read on SIGUSR2
+    #[serde(default)]
+    pub(crate) filter_file: Option<PathBuf>,
 }
 
 impl TracingConfig {
     /// Attempt to instantiate and register a tracing subscriber setup from
-    /// settings.
-    pub fn start_tracing(&self, metrics: &CoreMetrics) -> Result<console_subscriber::Server> {
+    /// settings. Spans are exported with `otlp` if it exports traces.
+    pub fn start_tracing(
+        &self,
+        metrics: &CoreMetrics,
+        otlp: Option<&OtlpExporter>,
+    ) -> Result<console_subscriber::Server> {
+        let target_layer = self.targets();
+        let (fmt_layer, structured_layer) = match self.fmt {
+            Style::StructuredJson => (None, Some(StructuredJsonLayer::new(metrics.agent_name()))),
+            style => (Some(LogOutputLayer::from(style)), None),
+        };
+        let err_layer = tracing_error::ErrorLayer::default();
+
+        // The target filter is reloadable so that it can be changed at runtime,
+        // see `LogFilterHandle`
+        let configured_targets = target_layer.clone();
+        let (target_layer, filter_handle) = reload::Layer::new(target_layer);
+
+        let (tokio_layer, tokio_server) = console_subscriber::ConsoleLayer::new();
+        let otlp_layer = otlp
+            .filter(|exporter| exporter.exports_traces())
+            .map(|exporter| otlp::otlp_layer(exporter, metrics))
+            .transpose()?;
+        let subscriber = tracing_subscriber::Registry::default()
+            .with(tokio_layer)
+            .with(target_layer)
+            .with(otlp_layer)
+            .with(TimeSpanLifetime::new(metrics))
+            .with(fmt_layer)
+            .with(structured_layer)
+            .with(err_layer);
+
+        subscriber.try_init()?;
+
+        LogFilterHandle::install(configured_targets, filter_handle);
+        if let (Some(path), Some(filter)) = (&self.filter_file, LogFilterHandle::global()) {
+            filter.reload_on_sigusr2(path.clone())?;
+        }
+        Ok(tokio_server)
+    }
+
+    /// The filter of which targets get logged at which level, before any
+    /// overrides of the `LogFilterHandle`
+    pub(crate) fn targets(&self) -> Targets {
         let mut target_layer = Targets::new().with_default(self.level);
 
         if self.level < Level::DependencyTrace {
@@ -83,18 +140,6 @@ impl TracingConfig {
             // only show sqlx query logs at trace level
             target_layer = target_layer.with_target("sqlx::query", Level::Warn);
         }
-        let fmt_layer: LogOutputLayer<_> = self.fmt.into();
-        let err_layer = tracing_error::ErrorLayer::default();
-
-        let (tokio_layer, tokio_server) = console_subscriber::ConsoleLayer::new();
-        let subscriber = tracing_subscriber::Registry::default()
-            .with(tokio_layer)
-            .with(target_layer)
-            .with(TimeSpanLifetime::new(metrics))
-            .with(fmt_layer)
-            .with(err_layer);
-
-        subscriber.try_init()?;
-        Ok(tokio_server)
+        target_layer
     }
 }
@@ -0,0 +1,229 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use eyre::Result;
+use hyperlane_core::H256;
+use opentelemetry::{
+    sdk::{
+        export::trace::{ExportResult, SpanData, SpanExporter},
+        trace::{self as sdk_trace, TracerProvider},
+    },
+    trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState, TracerProvider as _,
+    },
+    Context, Value,
+};
+use prometheus::IntCounter;
+use serde_json::{json, Value as JsonValue};
+use tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
+use tracing::{warn, Span};
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::{settings::OtlpExporter, CoreMetrics};
+
+/// How often the finished spans are sent to the collector
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The most spans sent to the collector in one request
+const MAX_EXPORT_BATCH: usize = 512;
+
+/// The most finished spans waiting to be sent. Spans finished while it's
+/// full, e.g. because the collector is slow or down, are dropped.
+const MAX_QUEUED_SPANS: usize = 16 * MAX_EXPORT_BATCH;
+
+/// Make `span` part of the trace of the message with `message_id`, e.g. the
+/// spans indexing, preparing, submitting and confirming it. The trace id is
+/// derived from the message id, so that spans recorded by different tasks,
+/// agents or relayer runs end up in the same trace, and the trace of any
+/// message can be looked up by its id.
+///
+/// Does nothing unless traces are exported.
+pub fn link_to_message(span: &Span, message_id: H256) {
+    let bytes = message_id.to_fixed_bytes();
+    let mut trace_id = [0; 16];
+    trace_id.copy_from_slice(&bytes[..16]);
+    let mut span_id = [0; 8];
+    span_id.copy_from_slice(&bytes[16..24]);
+    let parent = SpanContext::new(
+        TraceId::from_bytes(trace_id),
+        SpanId::from_bytes(span_id),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    );
+    span.set_parent(Context::new().with_remote_span_context(parent));
+}
+
+/// A layer exporting spans with `exporter`. Spans are sent in the
+/// background, so this has to be called from within a tokio runtime.
+pub(crate) fn otlp_layer<S>(
+    exporter: &OtlpExporter,
+    metrics: &CoreMetrics,
+) -> Result<OpenTelemetryLayer<S, sdk_trace::Tracer>>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    let dropped = metrics
+        .new_int_counter(
+            "otlp_dropped_spans",
+            "Finished spans dropped instead of being exported because too many were waiting to be sent",
+            &[],
+        )?
+        .with_label_values(&[]);
+    let (sender, receiver) = mpsc::channel(MAX_QUEUED_SPANS);
+    tokio::spawn(send_spans(exporter.clone(), receiver));
+    // The resource is added by the exporter
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(OtlpJsonExporter { sender, dropped })
+        .build();
+    let tracer = provider.tracer("hyperlane-agent");
+    // The tracer only holds a weak reference to its provider
+    opentelemetry::global::set_tracer_provider(provider);
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Hands the finished spans over to `send_spans`, since the span processor
+/// exports them outside of the tokio runtime
+#[derive(Debug)]
+struct OtlpJsonExporter {
+    sender: Sender<SpanData>,
+    dropped: IntCounter,
+}
+
+#[async_trait]
+impl SpanExporter for OtlpJsonExporter {
+    async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
+        for span in batch {
+            match self.sender.try_send(span) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => self.dropped.inc(),
+                // The receiver only stops once the runtime shuts down
+                Err(TrySendError::Closed(_)) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Send the finished spans to the collector in batches
+async fn send_spans(exporter: OtlpExporter, mut spans: Receiver<SpanData>) {
+    let mut batch = Vec::new();
+    loop {
+        let deadline = tokio::time::sleep(EXPORT_INTERVAL);
+        tokio::pin!(deadline);
+        while batch.len() < MAX_EXPORT_BATCH {
+            tokio::select! {
+                span = spans.recv() => match span {
+                    Some(span) => batch.push(span),
+                    None => return,
+                },
+                _ = &mut deadline => break,
+            }
+        }
+        if batch.is_empty() {
+            continue;
+        }
+        let body = export_request(exporter.resource_json(), &batch);
+        batch.clear();
+        if let Err(err) = exporter.export("traces", &body).await {
+            warn!(?err, "Failed to export spans");
+        }
+    }
+}
+
+/// The body of an OTLP/HTTP export request with `spans`
+fn export_request(resource: JsonValue, spans: &[SpanData]) -> JsonValue {
+    json!({
+        "resourceSpans": [{
+            "resource": resource,
+            "scopeSpans": [{
+                "scope": { "name": "hyperlane-agent" },
+                "spans": spans.iter().map(span_json).collect::<Vec<_>>(),
+            }],
+        }],
+    })
+}
+
+fn span_json(span: &SpanData) -> JsonValue {
+    let parent_span_id = if span.parent_span_id == SpanId::INVALID {
+        String::new()
+    } else {
+        format!("{:016x}", span.parent_span_id)
+    };
+    json!({
+        "traceId": format!("{:032x}", span.span_context.trace_id()),
+        "spanId": format!("{:016x}", span.span_context.span_id()),
+        "parentSpanId": parent_span_id,
+        "name": span.name,
+        // SPAN_KIND_INTERNAL
+        "kind": 1,
+        "startTimeUnixNano": unix_nanos(span.start_time),
+        "endTimeUnixNano": unix_nanos(span.end_time),
+        "attributes": span
+            .attributes
+            .iter()
+            .map(|(key, value)| attribute(key.as_str(), value))
+            .collect::<Vec<_>>(),
+        "events": span
+            .events
+            .iter()
+            .map(|event| json!({
+                "timeUnixNano": unix_nanos(event.timestamp),
+                "name": event.name,
+                "attributes": event
+                    .attributes
+                    .iter()
+                    .map(|kv| attribute(kv.key.as_str(), &kv.value))
+                    .collect::<Vec<_>>(),
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn attribute(key: &str, value: &Value) -> JsonValue {
+    let value = match value {
+        Value::Bool(value) => json!({ "boolValue": value }),
+        // 64 bit integers are encoded as strings
+        Value::I64(value) => json!({ "intValue": value.to_string() }),
+        Value::F64(value) => json!({ "doubleValue": value }),
+        value => json!({ "stringValue": value.as_str() }),
+    };
+    json!({ "key": key, "value": value })
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_attribute_encoding() {
+        assert_eq!(
+            attribute("nonce", &Value::I64(42)),
+            json!({ "key": "nonce", "value": { "intValue": "42" } })
+        );
+        assert_eq!(
+            attribute("id", &Value::from("0x01".to_owned())),
+            json!({ "key": "id", "value": { "stringValue": "0x01" } })
+        );
+        assert_eq!(
+            attribute("submitted", &Value::Bool(true)),
+            json!({ "key": "submitted", "value": { "boolValue": true } })
+        );
+    }
+
+    #[test]
+    fn test_unix_nanos() {
+        assert_eq!(
+            unix_nanos(UNIX_EPOCH + Duration::from_millis(1_500)),
+            "1500000000"
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    fmt::Debug,
+    io::{self, Stdout, Write},
+};
+
+use serde_json::{json, Map, Value as JsonValue};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Subscriber,
+};
+use tracing_subscriber::{
+    fmt::{
+        format::Writer,
+        time::{FormatTime, SystemTime},
+        MakeWriter,
+    },
+    layer::Context,
+    registry::LookupSpan,
+    Layer,
+};
+
+/// The fields every structured log line has at the top level, if the event or
+/// one of its spans recorded them, and the names they're recorded under
+const LIFTED_FIELDS: &[(&str, &[&str])] = &[
+    ("origin_domain", &["origin_domain", "origin"]),
+    ("destination_domain", &["destination_domain", "destination"]),
+    ("message_id", &["message_id", "msg_id", "id"]),
+    ("sequence", &["sequence", "nonce"]),
+];
+
+/// Writes every event as a line of JSON with consistent fields, so that log
+/// pipelines like Loki or Elastic can index them without parsing messages:
+///
+/// - `timestamp`, `level`, `target` and `message`
+/// - `agent`, e.g. `relayer`
+/// - `origin_domain`, `destination_domain`, `message_id` and `sequence`, when
+///   the event or one of its spans recorded them, e.g. as `origin` or `nonce`.
+///   Domains are ids or chain names, whichever was recorded.
+/// - `fields`: the other fields of the event
+/// - `spans`: the names of the spans the event is in, outermost first
+#[derive(Debug)]
+pub struct StructuredJsonLayer<W = fn() -> Stdout> {
+    agent: String,
+    make_writer: W,
+}
+
+impl StructuredJsonLayer {
+    /// A layer writing the events of `agent` to stdout
+    pub fn new(agent: &str) -> Self {
+        Self {
+            agent: agent.to_owned(),
+            make_writer: io::stdout,
+        }
+    }
+}
+
+impl<W> StructuredJsonLayer<W> {
+    /// Writes the lines to `make_writer` instead
+    pub fn with_writer<W2>(self, make_writer: W2) -> StructuredJsonLayer<W2> {
+        StructuredJsonLayer {
+            agent: self.agent,
+            make_writer,
+        }
+    }
+
+    fn line(
+        &self,
+        event: &Event<'_>,
+        mut fields: Map<String, JsonValue>,
+        span_fields: Map<String, JsonValue>,
+        spans: Vec<&str>,
+    ) -> JsonValue {
+        let mut timestamp = String::new();
+        let _ = SystemTime.format_time(&mut Writer::new(&mut timestamp));
+        let metadata = event.metadata();
+        let message = fields.remove("message").unwrap_or_default();
+
+        let mut line = json!({
+            "timestamp": timestamp,
+            "level": metadata.level().as_str(),
+            "target": metadata.target(),
+            "agent": self.agent,
+        });
+        let line_fields = line.as_object_mut().expect("line is an object");
+        for (lifted, names) in LIFTED_FIELDS {
+            // The event's own fields take precedence over the spans'
+            let value = [&fields, &span_fields].into_iter().find_map(|recorded| {
+                names
+                    .iter()
+                    .filter_map(|name| recorded.get(*name))
+                    .find(|value| *lifted != "message_id" || is_message_id(value))
+            });
+            if let Some(value) = value {
+                line_fields.insert((*lifted).to_owned(), value.clone());
+            }
+        }
+        line_fields.insert("message".to_owned(), message);
+        line_fields.insert("fields".to_owned(), JsonValue::Object(fields));
+        line_fields.insert("spans".to_owned(), json!(spans));
+        line
+    }
+}
+
+/// Whether `value` is a 32 byte hex id, since `id` is a common field name
+fn is_message_id(value: &JsonValue) -> bool {
+    value.as_str().map_or(false, |id| {
+        id.len() == 66 && id.starts_with("0x") && id[2..].chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+/// The fields a span recorded, stored in its extensions
+#[derive(Debug, Default)]
+struct SpanFields(Map<String, JsonValue>);
+
+struct JsonVisitor<'a>(&'a mut Map<String, JsonValue>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_owned(), json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_owned(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_owned(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_owned(), json!(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().to_owned(), json!(format!("{value:?}")));
+    }
+}
+
+impl<S, W> Layer<S> for StructuredJsonLayer<W>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + 'static,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = Map::new();
+        attrs.record(&mut JsonVisitor(&mut fields));
+        span.extensions_mut().insert(SpanFields(fields));
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        if let Some(SpanFields(fields)) = span.extensions_mut().get_mut::<SpanFields>() {
+            values.record(&mut JsonVisitor(fields));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = Map::new();
+        event.record(&mut JsonVisitor(&mut fields));
+
+        // Inner spans' fields override the outer ones'
+        let mut span_fields = Map::new();
+        let mut spans = vec![];
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                spans.push(span.name());
+                if let Some(SpanFields(recorded)) = span.extensions().get::<SpanFields>() {
+                    span_fields.extend(recorded.clone());
+                }
+            }
+        }
+
+        let line = self.line(event, fields, span_fields, spans);
+        // Written at once, so that lines of concurrent events don't interleave
+        let mut bytes = line.to_string().into_bytes();
+        bytes.push(b'\n');
+        // Like the fmt layers, logging doesn't fail the agent
+        let _ = self.make_writer.make_writer().write_all(&bytes);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::{info, info_span};
+    use tracing_subscriber::prelude::*;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Lines(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Lines {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'w> MakeWriter<'w> for Lines {
+        type Writer = Self;
+
+        fn make_writer(&'w self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn lifts_message_fields_from_spans() {
+        let lines = Lines::default();
+        let subscriber = tracing_subscriber::registry()
+            .with(StructuredJsonLayer::new("relayer").with_writer(lines.clone()));
+        let id = format!("0x{}", "ab".repeat(32));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _processor = info_span!("MessageProcessor", origin = "test1").entered();
+            let _message =
+                info_span!("message", id = %id, destination = 13372, nonce = 7).entered();
+            info!(gas_limit = 100, "Submitting message");
+            info!(origin = 13371, id = 5, "Overridden origin");
+        });
+
+        let output = String::from_utf8(lines.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<JsonValue> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        let line = &lines[0];
+        assert_eq!(line["agent"], "relayer");
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["message"], "Submitting message");
+        assert_eq!(line["origin_domain"], "test1");
+        assert_eq!(line["destination_domain"], 13372);
+        assert_eq!(line["message_id"], id.as_str());
+        assert_eq!(line["sequence"], 7);
+        assert_eq!(line["fields"], json!({ "gas_limit": 100 }));
+        assert_eq!(line["spans"], json!(["MessageProcessor", "message"]));
+
+        let line = &lines[1];
+        assert_eq!(line["origin_domain"], 13371);
+        // Not an id of a message, so the span's is used
+        assert_eq!(line["message_id"], id.as_str());
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use eyre::Result;
+use hyperlane_core::HyperlaneDomain;
+use prometheus::IntCounterVec;
+use tokio::{task::JoinHandle, time::sleep};
+use tracing::{warn, Instrument};
+
+use crate::{AgentHealth, CoreMetrics, Watchdog};
+
+/// The longest a stalled task goes unnoticed past its stall timeout
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Config of the `TaskSupervisor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SupervisorConf {
+    /// How long a task may go without feeding its watchdog before it's
+    /// restarted
+    pub stall_timeout: Duration,
+    /// How long to wait before the first restart of a task
+    pub min_backoff: Duration,
+    /// The longest wait between restarts, which doubles with every restart
+    /// of a task that failed again soon after it started
+    pub max_backoff: Duration,
+}
+
+impl Default for SupervisorConf {
+    fn default() -> Self {
+        Self {
+            stall_timeout: Duration::from_secs(10 * 60),
+            min_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// Restarts tasks that panicked, returned or stopped feeding their watchdog,
+/// e.g. a contract sync stuck on an RPC that never responds, so that the rest
+/// of the agent doesn't keep running with a stale chain
+#[derive(Debug, Clone)]
+pub struct TaskSupervisor {
+    conf: SupervisorConf,
+    restarts: IntCounterVec,
+}
+
+/// Aborts the supervised task and unregisters its watchdog when the
+/// supervisor is dropped, e.g. because it was aborted itself
+struct Supervised {
+    watchdog_name: String,
+    task: Option<JoinHandle<()>>,
+}
+
+impl Drop for Supervised {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        AgentHealth::global().remove_watchdog(&self.watchdog_name);
+    }
+}
+
+impl TaskSupervisor {
+    /// Creates a new instance of the `TaskSupervisor`
+    pub fn new(conf: SupervisorConf, core_metrics: &CoreMetrics) -> Result<Self> {
+        let restarts = core_metrics.new_int_counter(
+            "task_restarts",
+            "Restarts of supervised tasks, by the reason they were restarted for: `stalled`, `panicked` or `exited`",
+            &["task", "chain", "reason"],
+        )?;
+        Ok(Self { conf, restarts })
+    }
+
+    /// Runs the task `start` returns on `domain` until it fails, and then
+    /// starts it again after a backoff, forever. The task is watched through
+    /// the watchdog `{task}:{domain}`, which contract syncs feed under their
+    /// label.
+    pub fn supervise<F, Fut>(
+        &self,
+        task: &'static str,
+        domain: &HyperlaneDomain,
+        mut start: F,
+    ) -> impl Future<Output = ()> + Send + 'static
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let this = self.clone();
+        let chain = domain.name().to_owned();
+        async move {
+            let watchdog_name = format!("{task}:{chain}");
+            let watchdog = AgentHealth::global().watchdog(&watchdog_name, this.conf.stall_timeout);
+            let mut supervised = Supervised {
+                watchdog_name,
+                task: None,
+            };
+            let mut backoff = this.conf.min_backoff;
+            loop {
+                watchdog.feed();
+                let started = Instant::now();
+                let handle = supervised
+                    .task
+                    .insert(tokio::spawn(start().in_current_span()));
+                let reason = this.wait(handle, &watchdog).await;
+                if let Some(handle) = supervised.task.take() {
+                    handle.abort();
+                }
+
+                // Tasks that ran for a while failed for a new reason
+                if started.elapsed() > this.conf.max_backoff {
+                    backoff = this.conf.min_backoff;
+                }
+                this.restarts
+                    .with_label_values(&[task, &chain, reason])
+                    .inc();
+                warn!(
+                    task,
+                    chain = chain.as_str(),
+                    reason,
+                    ?backoff,
+                    "Restarting supervised task"
+                );
+                sleep(backoff).await;
+                backoff = (backoff * 2).min(this.conf.max_backoff);
+            }
+        }
+    }
+
+    /// Waits until `task` fails, returning why
+    async fn wait(&self, task: &mut JoinHandle<()>, watchdog: &Watchdog) -> &'static str {
+        let mut checks =
+            tokio::time::interval((self.conf.stall_timeout / 4).min(MAX_CHECK_INTERVAL));
+        loop {
+            tokio::select! {
+                result = &mut *task => {
+                    return match result {
+                        Err(err) if err.is_panic() => "panicked",
+                        _ => "exited",
+                    };
+                }
+                _ = checks.tick() => {
+                    if watchdog.is_starving() {
+                        return "stalled";
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    use hyperlane_core::KnownHyperlaneDomain;
+    use prometheus::Registry;
+
+    use super::*;
+
+    fn supervisor() -> TaskSupervisor {
+        let metrics = CoreMetrics::new("test", 9090, Registry::new()).unwrap();
+        let conf = SupervisorConf {
+            stall_timeout: Duration::from_millis(100),
+            min_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(20),
+        };
+        TaskSupervisor::new(conf, &metrics).unwrap()
+    }
+
+    fn restarts(supervisor: &TaskSupervisor, task: &str, reason: &str) -> u64 {
+        supervisor
+            .restarts
+            .with_label_values(&[task, "test1", reason])
+            .get()
+    }
+
+    #[tokio::test]
+    async fn restarts_stalled_and_panicked_tasks() {
+        let supervisor = supervisor();
+        let domain = HyperlaneDomain::from(KnownHyperlaneDomain::Test1);
+        let starts = Arc::new(AtomicU32::new(0));
+
+        let stalling = {
+            let starts = starts.clone();
+            supervisor.supervise("stalling", &domain, move || {
+                starts.fetch_add(1, Ordering::SeqCst);
+                futures_util::future::pending()
+            })
+        };
+        let panicking = supervisor.supervise("panicking", &domain, || async {
+            panic!("Task failed");
+        });
+        let feeding = supervisor.supervise("feeding", &domain, || async {
+            let watchdog = AgentHealth::global().watchdog("feeding:test1", Duration::ZERO);
+            loop {
+                watchdog.feed();
+                sleep(Duration::from_millis(10)).await;
+            }
+        });
+        let supervised = tokio::spawn(async move {
+            tokio::join!(stalling, panicking, feeding);
+        });
+
+        sleep(Duration::from_millis(500)).await;
+        assert!(starts.load(Ordering::SeqCst) >= 2);
+        assert!(restarts(&supervisor, "stalling", "stalled") >= 1);
+        assert!(restarts(&supervisor, "panicking", "panicked") >= 2);
+        assert_eq!(restarts(&supervisor, "feeding", "stalled"), 0);
+
+        // Aborting the supervisors stops their tasks
+        supervised.abort();
+        let _ = supervised.await;
+        let starts_after_abort = starts.load(Ordering::SeqCst);
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(starts.load(Ordering::SeqCst), starts_after_abort);
+        assert!(AgentHealth::global().liveness().1.iter().all(|(name, _)| ![
+            "stalling",
+            "panicking",
+            "feeding"
+        ]
+        .iter()
+        .any(|task| name.starts_with(task))));
+    }
+}
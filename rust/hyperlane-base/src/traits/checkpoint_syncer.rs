@@ -1,7 +1,7 @@
 use std::fmt::Debug;
 
 use async_trait::async_trait;
-use eyre::Result;
+use eyre::{bail, Result};
 
 use hyperlane_core::{SignedAnnouncement, SignedCheckpointWithMessageId};
 
@@ -20,13 +20,24 @@ pub trait CheckpointSyncer: Debug + Send + Sync {
         }
         Ok(())
     }
-    /// Attempt to fetch the signed (checkpoint, messageId) tuple at this index
+    /// Attempt to fetch the signed (checkpoint, messageId) tuple at this index.
+    /// Checkpoints written by older validators, under legacy names or with
+    /// legacy field names, are read as well.
     async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>>;
     /// Write the signed (checkpoint, messageId) tuple to this syncer
     async fn write_checkpoint(
         &self,
         signed_checkpoint: &SignedCheckpointWithMessageId,
     ) -> Result<()>;
+    /// Delete the signed (checkpoint, messageId) tuple at this index, e.g.
+    /// once it's past the retention of the syncer. Deleting a checkpoint
+    /// that doesn't exist succeeds.
+    async fn delete_checkpoint(&self, index: u32) -> Result<()> {
+        bail!(
+            "Deleting checkpoints isn't supported by {}",
+            self.announcement_location()
+        )
+    }
     /// Write the signed announcement to this syncer
     async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()>;
     /// Return the announcement storage location for this syncer
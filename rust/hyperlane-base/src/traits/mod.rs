@@ -1,3 +1,5 @@
 mod checkpoint_syncer;
+mod object_writer;
 
 pub use checkpoint_syncer::*;
+pub use object_writer::*;
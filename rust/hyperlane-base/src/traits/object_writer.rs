@@ -0,0 +1,23 @@
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use eyre::Result;
+use tokio::{fs::File, io::AsyncReadExt};
+
+/// A generic trait to write arbitrary objects offchain, to the same storage
+/// locations checkpoints are written to
+#[async_trait]
+pub trait ObjectWriter: Debug + Send + Sync {
+    /// Write `data` to the object with `key`, replacing it if it exists.
+    /// Keys may contain `/` to nest objects in folders.
+    async fn write_object(&self, key: &str, data: Vec<u8>) -> Result<()>;
+
+    /// Write the `size` bytes of `file` to the object with `key`, like
+    /// `write_object`. Storages that can't stream the file from disk read
+    /// it into memory.
+    async fn write_object_from_file(&self, key: &str, mut file: File, size: u64) -> Result<()> {
+        let mut data = Vec::with_capacity(size as usize);
+        file.read_to_end(&mut data).await?;
+        self.write_object(key, data).await
+    }
+}
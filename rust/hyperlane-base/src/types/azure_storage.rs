@@ -0,0 +1,323 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use derive_new::new;
+use eyre::{bail, Result};
+use hyperlane_core::{SignedAnnouncement, SignedCheckpointWithMessageId};
+use prometheus::IntGauge;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use url::Url;
+
+use crate::{CheckpointSyncer, ObjectWriter};
+
+/// SAS token granting access to the container, used by checkpoint syncers
+/// parsed from a storage location
+pub const AZURE_STORAGE_SAS_TOKEN: &str = "AZURE_STORAGE_SAS_TOKEN";
+
+/// The timeout for requests to the Blob service
+const AZURE_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The version of the Blob service REST API, which has to be at least
+/// 2017-11-09 for OAuth tokens to be accepted
+const AZURE_STORAGE_API_VERSION: &str = "2021-08-06";
+
+/// The instance metadata service that hands out managed identity tokens
+const MANAGED_IDENTITY_TOKEN_URL: &str =
+    "http://169.254.169.254/metadata/identity/oauth2/token?api-version=2018-02-01&resource=https://storage.azure.com/";
+
+/// Managed identity tokens are refreshed this long before they expire
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(300);
+
+/// How requests to the Blob service are authorized
+#[derive(Clone, PartialEq, Eq)]
+pub enum AzureBlobAuth {
+    /// Anonymous access to a public container, which is read only
+    Anonymous,
+    /// A shared access signature, appended to the query of each request
+    Sas(String),
+    /// Tokens of the managed identity of the VM or pod the agent runs on.
+    /// The client id picks a user-assigned identity.
+    ManagedIdentity {
+        /// Client id of a user-assigned identity
+        client_id: Option<String>,
+    },
+}
+
+impl fmt::Debug for AzureBlobAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // intentionally leaves out the SAS token
+        match self {
+            Self::Anonymous => write!(f, "Anonymous"),
+            Self::Sas(_) => write!(f, "Sas"),
+            Self::ManagedIdentity { client_id } => f
+                .debug_struct("ManagedIdentity")
+                .field("client_id", client_id)
+                .finish(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ManagedIdentityToken {
+    access_token: String,
+    /// Seconds since the unix epoch
+    expires_on: String,
+}
+
+#[derive(Clone, new)]
+/// Type for reading/writing to a container in Azure Blob Storage
+pub struct AzureBlobStorage {
+    /// The storage account
+    account: String,
+    /// The container in the storage account
+    container: String,
+    /// A folder inside the container - defaults to the root of the container
+    folder: Option<String>,
+    /// How requests are authorized
+    auth: AzureBlobAuth,
+    /// The latest seen signed checkpoint index.
+    latest_index: Option<IntGauge>,
+    #[new(value = "Client::builder().timeout(AZURE_REQUEST_TIMEOUT).build().unwrap()")]
+    client: Client,
+    /// The managed identity token and when it's refreshed
+    #[new(default)]
+    token: Arc<Mutex<Option<(String, Instant)>>>,
+}
+
+impl fmt::Debug for AzureBlobStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AzureBlobStorage")
+            .field("account", &self.account)
+            .field("container", &self.container)
+            .field("folder", &self.folder)
+            .field("auth", &self.auth)
+            .finish()
+    }
+}
+
+impl AzureBlobStorage {
+    fn blob_url(&self, key: &str) -> Result<Url> {
+        let mut url = Url::parse(&format!(
+            "https://{}.blob.core.windows.net/{}/",
+            self.account, self.container
+        ))?;
+        url = match self.folder.as_deref() {
+            None | Some("") => url.join(key)?,
+            Some(folder) => url.join(&format!("{}/{}", folder.trim_matches('/'), key))?,
+        };
+        if let AzureBlobAuth::Sas(sas) = &self.auth {
+            url.set_query(Some(sas.trim_start_matches('?')));
+        }
+        Ok(url)
+    }
+
+    /// Authorize the request to the Blob service
+    async fn authorize(&self, request: RequestBuilder) -> Result<RequestBuilder> {
+        let request = request.header("x-ms-version", AZURE_STORAGE_API_VERSION);
+        Ok(match &self.auth {
+            // The SAS is part of the url
+            AzureBlobAuth::Anonymous | AzureBlobAuth::Sas(_) => request,
+            AzureBlobAuth::ManagedIdentity { client_id } => {
+                request.bearer_auth(self.managed_identity_token(client_id.as_deref()).await?)
+            }
+        })
+    }
+
+    /// Gets a managed identity token, fetching a new one if the cached one
+    /// is about to expire
+    async fn managed_identity_token(&self, client_id: Option<&str>) -> Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some((access_token, refresh_at)) = token.as_ref() {
+            if Instant::now() < *refresh_at {
+                return Ok(access_token.clone());
+            }
+        }
+        let mut url = Url::parse(MANAGED_IDENTITY_TOKEN_URL)?;
+        if let Some(client_id) = client_id {
+            url.query_pairs_mut().append_pair("client_id", client_id);
+        }
+        let response: ManagedIdentityToken = self
+            .client
+            .get(url)
+            .header("Metadata", "true")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let expires_in = response
+            .expires_on
+            .parse::<u64>()?
+            .saturating_sub(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs());
+        let refresh_at =
+            Instant::now() + Duration::from_secs(expires_in).saturating_sub(TOKEN_REFRESH_MARGIN);
+        *token = Some((response.access_token.clone(), refresh_at));
+        Ok(response.access_token)
+    }
+
+    async fn write_to_container(&self, key: &str, body: String) -> Result<()> {
+        self.put_blob(key, body.into_bytes(), "application/json")
+            .await
+    }
+
+    async fn put_blob(&self, key: &str, body: Vec<u8>, content_type: &str) -> Result<()> {
+        if self.auth == AzureBlobAuth::Anonymous {
+            bail!("Writing to Azure Blob Storage requires a SAS token or a managed identity");
+        }
+        let request = self
+            .client
+            .put(self.blob_url(key)?)
+            .header("x-ms-blob-type", "BlockBlob")
+            .header("Content-Type", content_type)
+            .body(body);
+        self.authorize(request)
+            .await?
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    async fn delete_from_container(&self, key: &str) -> Result<()> {
+        if self.auth == AzureBlobAuth::Anonymous {
+            bail!("Deleting from Azure Blob Storage requires a SAS token or a managed identity");
+        }
+        let request = self.client.delete(self.blob_url(key)?);
+        let response = self.authorize(request).await?.send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        response.error_for_status()?;
+        Ok(())
+    }
+
+    async fn read_from_container(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let request = self.client.get(self.blob_url(key)?);
+        let response = self.authorize(request).await?.send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(response.error_for_status()?.bytes().await?.to_vec()))
+    }
+
+    fn checkpoint_key(index: u32) -> String {
+        format!("checkpoint_{index}_with_id.json")
+    }
+
+    fn latest_index_key() -> &'static str {
+        "checkpoint_latest_index.json"
+    }
+
+    fn announcement_key() -> &'static str {
+        "announcement.json"
+    }
+}
+
+#[async_trait]
+impl ObjectWriter for AzureBlobStorage {
+    async fn write_object(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        self.put_blob(key, data, "application/octet-stream").await
+    }
+}
+
+#[async_trait]
+impl CheckpointSyncer for AzureBlobStorage {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let latest_index = self
+            .read_from_container(Self::latest_index_key())
+            .await?
+            .map(|data| serde_json::from_slice::<u32>(&data))
+            .transpose()?;
+        if let (Some(latest_index), Some(gauge)) = (latest_index, &self.latest_index) {
+            gauge.set(latest_index as i64);
+        }
+        Ok(latest_index)
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        self.write_to_container(Self::latest_index_key(), serde_json::to_string(&index)?)
+            .await
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        self.read_from_container(&Self::checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        self.write_to_container(
+            &Self::checkpoint_key(signed_checkpoint.value.index),
+            serde_json::to_string_pretty(signed_checkpoint)?,
+        )
+        .await
+    }
+
+    async fn delete_checkpoint(&self, index: u32) -> Result<()> {
+        self.delete_from_container(&Self::checkpoint_key(index))
+            .await
+    }
+
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        self.write_to_container(
+            Self::announcement_key(),
+            serde_json::to_string_pretty(signed_announcement)?,
+        )
+        .await
+    }
+
+    fn announcement_location(&self) -> String {
+        match self.folder.as_deref() {
+            None | Some("") => format!("azure://{}/{}", self.account, self.container),
+            Some(folder) => format!("azure://{}/{}/{}", self.account, self.container, folder),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_blob_url() {
+        let storage = AzureBlobStorage::new(
+            "account".to_owned(),
+            "container".to_owned(),
+            Some("validators/ethereum".to_owned()),
+            AzureBlobAuth::Sas("?sv=2021-08-06&sig=abc".to_owned()),
+            None,
+        );
+        assert_eq!(
+            storage.blob_url("checkpoint_1_with_id.json").unwrap().as_str(),
+            "https://account.blob.core.windows.net/container/validators/ethereum/checkpoint_1_with_id.json?sv=2021-08-06&sig=abc"
+        );
+        assert_eq!(
+            storage.announcement_location(),
+            "azure://account/container/validators/ethereum"
+        );
+
+        let storage = AzureBlobStorage::new(
+            "account".to_owned(),
+            "container".to_owned(),
+            None,
+            AzureBlobAuth::Anonymous,
+            None,
+        );
+        assert_eq!(
+            storage.blob_url("announcement.json").unwrap().as_str(),
+            "https://account.blob.core.windows.net/container/announcement.json"
+        );
+        assert_eq!(storage.announcement_location(), "azure://account/container");
+    }
+}
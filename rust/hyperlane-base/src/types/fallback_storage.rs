@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+use derive_new::new;
+use eyre::{bail, Result};
+use futures_util::future::join_all;
+use hyperlane_core::{SignedAnnouncement, SignedCheckpointWithMessageId};
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::CheckpointSyncer;
+
+/// Reads the checkpoints of a validator that writes them to several
+/// locations, so that they're still read while one of the locations is
+/// down. Read only.
+#[derive(Debug, new)]
+pub struct FallbackCheckpointSyncer {
+    /// The syncers of the announced locations, the most recently announced
+    /// one first
+    syncers: Vec<Arc<dyn CheckpointSyncer>>,
+}
+
+#[async_trait]
+impl CheckpointSyncer for FallbackCheckpointSyncer {
+    /// The highest latest index of any of the locations
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let results = join_all(self.syncers.iter().map(|syncer| syncer.latest_index())).await;
+        let mut latest_index = None;
+        let mut first_err = None;
+        for (syncer, result) in self.syncers.iter().zip(results) {
+            match result {
+                Ok(index) => latest_index = latest_index.max(index),
+                Err(err) => {
+                    debug!(?err, ?syncer, "Failed to get latest index from location");
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+        match (latest_index, first_err) {
+            (None, Some(err)) => Err(err),
+            (latest_index, _) => Ok(latest_index),
+        }
+    }
+
+    async fn write_latest_index(&self, _index: u32) -> Result<()> {
+        bail!("Fallback checkpoint syncers are read only")
+    }
+
+    /// The checkpoint from the first location that has it
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        let mut first_err = None;
+        let mut answered = false;
+        for syncer in &self.syncers {
+            match syncer.fetch_checkpoint(index).await {
+                Ok(Some(checkpoint)) => return Ok(Some(checkpoint)),
+                Ok(None) => answered = true,
+                Err(err) => {
+                    debug!(
+                        ?err,
+                        ?syncer,
+                        index,
+                        "Failed to fetch checkpoint from location"
+                    );
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+        // Only fails if none of the locations could be read
+        match first_err {
+            Some(err) if !answered => Err(err),
+            _ => Ok(None),
+        }
+    }
+
+    async fn write_checkpoint(
+        &self,
+        _signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        bail!("Fallback checkpoint syncers are read only")
+    }
+
+    async fn write_announcement(&self, _signed_announcement: &SignedAnnouncement) -> Result<()> {
+        bail!("Fallback checkpoint syncers are read only")
+    }
+
+    fn announcement_location(&self) -> String {
+        self.syncers
+            .first()
+            .map(|syncer| syncer.announcement_location())
+            .unwrap_or_default()
+    }
+}
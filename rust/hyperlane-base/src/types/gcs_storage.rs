@@ -1,13 +1,21 @@
-use crate::CheckpointSyncer;
+use crate::{CheckpointSyncer, ObjectWriter};
 use async_trait::async_trait;
 use derive_new::new;
 use eyre::{bail, Result};
 use hyperlane_core::{SignedAnnouncement, SignedCheckpointWithMessageId};
+use prometheus::IntGauge;
 use std::fmt;
-use ya_gcp::{storage::StorageClient, AuthFlow, ClientBuilder, ClientBuilderConfig};
+use ya_gcp::{
+    storage::{
+        api::{error::HttpStatusError, http::StatusCode, Error},
+        ObjectError, StorageClient,
+    },
+    AuthFlow, ClientBuilder, ClientBuilderConfig,
+};
 
 const LATEST_INDEX_KEY: &str = "gcsLatestIndexKey";
-const ANNOUNCEMENT_KEY: &str = "gcsAnnouncementKey";
+/// Name of the object the signed announcement is stored in
+pub(crate) const ANNOUNCEMENT_KEY: &str = "gcsAnnouncementKey";
 /// Path to GCS users_secret file
 pub const GCS_USER_SECRET: &str = "GCS_USER_SECRET";
 /// Path to GCS Service account key
@@ -72,35 +80,95 @@ pub struct GcsStorageClient {
     inner: StorageClient,
     // bucket name of this client's storage
     bucket: String,
+    // folder inside the bucket that the objects are stored in, if any
+    folder: Option<String>,
+    // the latest seen signed checkpoint index
+    latest_index: Option<IntGauge>,
 }
 
 impl GcsStorageClientBuilder {
     /// Instantiates `ya_gcp:StorageClient` based on provided auth method
     /// # Param
-    /// * `baucket_name` - String name of target bucket to work with, will be used by all store and get ops
+    /// * `bucket_name` - String name of target bucket to work with, will be used by all store and get ops
+    /// * `folder` - Folder inside the bucket to prefix the object names with
     pub async fn build(
         self,
         bucket_name: impl Into<String>,
         folder: Option<String>,
     ) -> Result<GcsStorageClient> {
+        let bucket = bucket_name.into();
+        if bucket.contains('/') {
+            bail!("Bucket name `{bucket}` must not contain `/`, use a folder instead");
+        }
         let inner = ClientBuilder::new(ClientBuilderConfig::new().auth_flow(self.auth))
             .await?
             .build_storage_client();
-        let bucket = if let Some(folder) = folder {
-            format! {"{}/{}", bucket_name.into(), folder}
-        } else {
-            bucket_name.into()
-        };
+        let folder = folder
+            .map(|folder| folder.trim_matches('/').to_owned())
+            .filter(|folder| !folder.is_empty());
 
-        Ok(GcsStorageClient { inner, bucket })
+        Ok(GcsStorageClient {
+            inner,
+            bucket,
+            folder,
+            latest_index: None,
+        })
     }
 }
 
 impl GcsStorageClient {
+    /// Report the latest index read from the bucket with `gauge`
+    pub fn with_latest_index_gauge(mut self, gauge: Option<IntGauge>) -> Self {
+        self.latest_index = gauge;
+        self
+    }
+
     // convenience formatter
     fn get_checkpoint_key(index: u32) -> String {
         format!("checkpoint_{index}_with_id.json")
     }
+
+    // name of the object with `key` inside the folder
+    fn object_name(&self, key: &str) -> String {
+        match &self.folder {
+            Some(folder) => format!("{folder}/{key}"),
+            None => key.to_owned(),
+        }
+    }
+
+    /// Read the object with `key`, or `None` if it doesn't exist
+    async fn read_object(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match self
+            .inner
+            .get_object(&self.bucket, self.object_name(key))
+            .await
+        {
+            Ok(data) => Ok(Some(data.to_vec())),
+            // never written before to this bucket
+            Err(ObjectError::InvalidName(_))
+            | Err(ObjectError::Failure(Error::HttpStatus(HttpStatusError(
+                StatusCode::NOT_FOUND,
+            )))) => Ok(None),
+            Err(e) => bail!(e),
+        }
+    }
+
+    /// Delete the object with `key`, which succeeds if it doesn't exist
+    async fn delete_object(&self, key: &str) -> Result<()> {
+        match self
+            .inner
+            .delete_object(&self.bucket, self.object_name(key))
+            .await
+        {
+            Ok(_) => Ok(()),
+            // already deleted
+            Err(ObjectError::Failure(Error::HttpStatus(HttpStatusError(
+                StatusCode::NOT_FOUND,
+            )))) => Ok(()),
+            Err(e) => bail!(e),
+        }
+    }
+
     // #test only method[s]
     #[cfg(test)]
     pub(crate) async fn get_by_path(&self, path: impl AsRef<str>) -> Result<()> {
@@ -112,33 +180,44 @@ impl GcsStorageClient {
 // required by `CheckpointSyncer`
 impl fmt::Debug for GcsStorageClient {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("S3Storage")
+        f.debug_struct("GcsStorageClient")
             .field("bucket", &self.bucket)
+            .field("folder", &self.folder)
             .finish()
     }
 }
 
+// The client only uploads bodies that are in memory, so files are read into
+// memory by the default `write_object_from_file`
+#[async_trait]
+impl ObjectWriter for GcsStorageClient {
+    async fn write_object(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        self.inner
+            .insert_object(&self.bucket, self.object_name(key), data)
+            .await?;
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl CheckpointSyncer for GcsStorageClient {
     /// Read the highest index of this Syncer
     async fn latest_index(&self) -> Result<Option<u32>> {
-        match self.inner.get_object(&self.bucket, LATEST_INDEX_KEY).await {
-            Ok(data) => Ok(Some(serde_json::from_slice(data.as_ref())?)),
-            Err(e) => match e {
-                // never written before to this bucket
-                ya_gcp::storage::ObjectError::InvalidName(_) => Ok(None),
-                _ => bail!(e),
-            },
+        let latest_index = self
+            .read_object(LATEST_INDEX_KEY)
+            .await?
+            .map(|data| serde_json::from_slice::<u32>(&data))
+            .transpose()?;
+        if let (Some(latest_index), Some(gauge)) = (latest_index, &self.latest_index) {
+            gauge.set(latest_index as i64);
         }
+        Ok(latest_index)
     }
 
     /// Writes the highest index of this Syncer
     async fn write_latest_index(&self, index: u32) -> Result<()> {
-        let d = serde_json::to_vec(&index)?;
-        self.inner
-            .insert_object(&self.bucket, LATEST_INDEX_KEY, d)
-            .await?;
-        Ok(())
+        self.write_object(LATEST_INDEX_KEY, serde_json::to_vec(&index)?)
+            .await
     }
 
     /// Update the latest index of this syncer if necessary
@@ -152,11 +231,11 @@ impl CheckpointSyncer for GcsStorageClient {
 
     /// Attempt to fetch the signed (checkpoint, messageId) tuple at this index
     async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
-        let res = self
-            .inner
-            .get_object(&self.bucket, GcsStorageClient::get_checkpoint_key(index))
-            .await?;
-        Ok(Some(serde_json::from_slice(res.as_ref())?))
+        self.read_object(&GcsStorageClient::get_checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
     }
 
     /// Write the signed (checkpoint, messageId) tuple to this syncer
@@ -164,31 +243,32 @@ impl CheckpointSyncer for GcsStorageClient {
         &self,
         signed_checkpoint: &SignedCheckpointWithMessageId,
     ) -> Result<()> {
-        self.inner
-            .insert_object(
-                &self.bucket,
-                GcsStorageClient::get_checkpoint_key(signed_checkpoint.value.index),
-                serde_json::to_vec(signed_checkpoint)?,
-            )
-            .await?;
-        Ok(())
+        self.write_object(
+            &GcsStorageClient::get_checkpoint_key(signed_checkpoint.value.index),
+            serde_json::to_vec(signed_checkpoint)?,
+        )
+        .await
+    }
+
+    /// Delete the signed (checkpoint, messageId) tuple at this index
+    async fn delete_checkpoint(&self, index: u32) -> Result<()> {
+        self.delete_object(&GcsStorageClient::get_checkpoint_key(index))
+            .await
     }
 
     /// Write the signed announcement to this syncer
     async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
-        self.inner
-            .insert_object(
-                &self.bucket,
-                ANNOUNCEMENT_KEY,
-                serde_json::to_string(signed_announcement)?,
-            )
-            .await?;
-        Ok(())
+        self.write_object(ANNOUNCEMENT_KEY, serde_json::to_vec(signed_announcement)?)
+            .await
     }
 
-    /// Return the announcement storage location for this syncer
+    /// Return the announcement storage location for this syncer, which is
+    /// parsed back into a `CheckpointSyncerConf::Gcs` by relayers
     fn announcement_location(&self) -> String {
-        format!("gs://{}/{}", &self.bucket, ANNOUNCEMENT_KEY)
+        match &self.folder {
+            Some(folder) => format!("gs://{}/{}", self.bucket, folder),
+            None => format!("gs://{}", self.bucket),
+        }
     }
 }
 
@@ -0,0 +1,353 @@
+use std::{fmt, time::Duration};
+
+use async_trait::async_trait;
+use eyre::{bail, eyre, Result};
+use hyperlane_core::{SignedAnnouncement, SignedCheckpointWithMessageId};
+use prometheus::IntGauge;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use tracing::debug;
+use url::Url;
+
+use crate::CheckpointSyncer;
+
+/// Gateway that checkpoint syncers parsed from a storage location read
+/// through, unless it's set through the env
+pub const IPFS_GATEWAY_URL: &str = "IPFS_GATEWAY_URL";
+
+/// The gateway checkpoints are read through by default
+pub const DEFAULT_IPFS_GATEWAY: &str = "https://ipfs.io";
+
+/// The timeout for requests to gateways and the RPC API. Publishing to IPNS
+/// can take a while.
+const IPFS_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Separates the parts of the multipart bodies sent to the RPC API
+const MULTIPART_BOUNDARY: &str = "hyperlane-checkpoint-boundary";
+
+/// The RPC API of the IPFS node a validator writes its checkpoints through
+#[derive(Debug, Clone)]
+pub struct IpfsWriter {
+    /// The RPC API, e.g. `http://127.0.0.1:5001`
+    pub api_url: Url,
+    /// Name of the key of the node the checkpoints are published with
+    pub key: String,
+    /// The directory of the node's mutable file system the checkpoints are
+    /// written to
+    pub folder: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct KeyList {
+    keys: Vec<Key>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Key {
+    name: String,
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FileStat {
+    hash: String,
+}
+
+/// Type for reading/writing checkpoints to IPFS. Experimental.
+///
+/// The checkpoints are written to a directory of the validator's IPFS node,
+/// which is published under the IPNS name of the node's key whenever the
+/// latest index or the announcement is written. Relayers read the
+/// checkpoints through any gateway, so they remain available for as long as
+/// some node pins them, even if the validator's node goes away.
+#[derive(Clone)]
+pub struct IpfsStorage {
+    /// The IPNS name the checkpoints are published under
+    ipns_name: String,
+    /// The gateway checkpoints are read through
+    gateway: Url,
+    /// The node checkpoints are written through, for validators
+    writer: Option<IpfsWriter>,
+    client: Client,
+    /// The latest seen signed checkpoint index.
+    latest_index: Option<IntGauge>,
+}
+
+impl fmt::Debug for IpfsStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IpfsStorage")
+            .field("ipns_name", &self.ipns_name)
+            .field("gateway", &self.gateway.as_str())
+            .field("writer", &self.writer)
+            .finish()
+    }
+}
+
+impl IpfsStorage {
+    /// Read the checkpoints published under `ipns_name` through `gateway`
+    pub fn reader(ipns_name: String, gateway: Url, latest_index: Option<IntGauge>) -> Self {
+        Self {
+            ipns_name,
+            gateway,
+            writer: None,
+            client: Self::client(),
+            latest_index,
+        }
+    }
+
+    /// Write checkpoints through the node of `writer`, publishing them
+    /// under the IPNS name of its key
+    pub async fn writer(
+        writer: IpfsWriter,
+        gateway: Url,
+        latest_index: Option<IntGauge>,
+    ) -> Result<Self> {
+        let client = Self::client();
+        let keys: KeyList = client
+            .post(writer.api_url.join("api/v0/key/list")?)
+            .query(&[("l", "true")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let ipns_name = keys
+            .keys
+            .into_iter()
+            .find(|key| key.name == writer.key)
+            .map(|key| key.id)
+            .ok_or_else(|| eyre!("IPFS node has no key named `{}`", writer.key))?;
+        Ok(Self {
+            ipns_name,
+            gateway,
+            writer: Some(writer),
+            client,
+            latest_index,
+        })
+    }
+
+    fn client() -> Client {
+        Client::builder()
+            .timeout(IPFS_REQUEST_TIMEOUT)
+            .build()
+            .unwrap()
+    }
+
+    fn writer_conf(&self) -> Result<&IpfsWriter> {
+        self.writer
+            .as_ref()
+            .ok_or_else(|| eyre!("Writing to IPFS requires the RPC API of a node"))
+    }
+
+    fn gateway_url(&self, key: &str) -> Result<Url> {
+        Ok(self
+            .gateway
+            .join(&format!("ipns/{}/{}", self.ipns_name, key))?)
+    }
+
+    fn folder_path(writer: &IpfsWriter) -> String {
+        format!("/{}", writer.folder.trim_matches('/'))
+    }
+
+    async fn read_from_gateway(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let response = self.client.get(self.gateway_url(key)?).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(response.error_for_status()?.bytes().await?.to_vec()))
+    }
+
+    /// Write `data` to the node's directory, which has to be published to
+    /// be visible to relayers
+    async fn write_to_node(&self, key: &str, data: String) -> Result<()> {
+        let writer = self.writer_conf()?;
+        let path = format!("{}/{}", Self::folder_path(writer), key);
+        let body = format!(
+            "--{MULTIPART_BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{key}\"\r\nContent-Type: application/octet-stream\r\n\r\n{data}\r\n--{MULTIPART_BOUNDARY}--\r\n"
+        );
+        self.client
+            .post(writer.api_url.join("api/v0/files/write")?)
+            .query(&[
+                ("arg", path.as_str()),
+                ("create", "true"),
+                ("parents", "true"),
+                ("truncate", "true"),
+            ])
+            .header(
+                "Content-Type",
+                format!("multipart/form-data; boundary={MULTIPART_BOUNDARY}"),
+            )
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Remove `key` from the node's directory. Like writes, this is visible
+    /// to relayers once the directory is published again.
+    async fn remove_from_node(&self, key: &str) -> Result<()> {
+        let writer = self.writer_conf()?;
+        let path = format!("{}/{}", Self::folder_path(writer), key);
+        let response = self
+            .client
+            .post(writer.api_url.join("api/v0/files/rm")?)
+            .query(&[("arg", path.as_str()), ("force", "true")])
+            .send()
+            .await?;
+        match response.error_for_status() {
+            Ok(_) => Ok(()),
+            // Doesn't exist
+            Err(err) if err.status() == Some(StatusCode::INTERNAL_SERVER_ERROR) => Ok(()),
+            Err(err) => bail!(err),
+        }
+    }
+
+    /// Publish the current content of the node's directory under the IPNS
+    /// name, which also pins it on the node
+    async fn publish(&self) -> Result<()> {
+        let writer = self.writer_conf()?;
+        let stat: FileStat = self
+            .client
+            .post(writer.api_url.join("api/v0/files/stat")?)
+            .query(&[("arg", Self::folder_path(writer))])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let content = format!("/ipfs/{}", stat.hash);
+        self.client
+            .post(writer.api_url.join("api/v0/pin/add")?)
+            .query(&[("arg", content.as_str())])
+            .send()
+            .await?
+            .error_for_status()?;
+        self.client
+            .post(writer.api_url.join("api/v0/name/publish")?)
+            .query(&[("arg", content.as_str()), ("key", writer.key.as_str())])
+            .send()
+            .await?
+            .error_for_status()?;
+        debug!(%content, ipns_name=%self.ipns_name, "Published checkpoints to IPNS");
+        Ok(())
+    }
+
+    fn checkpoint_key(index: u32) -> String {
+        format!("checkpoint_{index}_with_id.json")
+    }
+
+    fn latest_index_key() -> &'static str {
+        "checkpoint_latest_index.json"
+    }
+
+    fn announcement_key() -> &'static str {
+        "announcement.json"
+    }
+}
+
+#[async_trait]
+impl CheckpointSyncer for IpfsStorage {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let latest_index = self
+            .read_from_gateway(Self::latest_index_key())
+            .await?
+            .map(|data| serde_json::from_slice::<u32>(&data))
+            .transpose()?;
+        if let (Some(latest_index), Some(gauge)) = (latest_index, &self.latest_index) {
+            gauge.set(latest_index as i64);
+        }
+        Ok(latest_index)
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        self.write_to_node(Self::latest_index_key(), serde_json::to_string(&index)?)
+            .await?;
+        // The checkpoints up to the index become visible at once
+        self.publish().await
+    }
+
+    async fn update_latest_index(&self, index: u32) -> Result<()> {
+        // The published index lags behind the node's while IPNS records
+        // propagate, so the node's own copy is compared against
+        let writer = self.writer_conf()?;
+        let path = format!("{}/{}", Self::folder_path(writer), Self::latest_index_key());
+        let response = self
+            .client
+            .post(writer.api_url.join("api/v0/files/read")?)
+            .query(&[("arg", path.as_str())])
+            .send()
+            .await?;
+        let current = match response.error_for_status() {
+            Ok(response) => serde_json::from_slice::<u32>(&response.bytes().await?)?,
+            // Never written before
+            Err(err) if err.status() == Some(StatusCode::INTERNAL_SERVER_ERROR) => 0,
+            Err(err) => bail!(err),
+        };
+        if index > current {
+            self.write_latest_index(index).await?;
+        }
+        Ok(())
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        self.read_from_gateway(&Self::checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        self.write_to_node(
+            &Self::checkpoint_key(signed_checkpoint.value.index),
+            serde_json::to_string_pretty(signed_checkpoint)?,
+        )
+        .await
+    }
+
+    async fn delete_checkpoint(&self, index: u32) -> Result<()> {
+        self.remove_from_node(&Self::checkpoint_key(index)).await
+    }
+
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        self.write_to_node(
+            Self::announcement_key(),
+            serde_json::to_string_pretty(signed_announcement)?,
+        )
+        .await?;
+        self.publish().await
+    }
+
+    fn announcement_location(&self) -> String {
+        format!("ipfs://{}", self.ipns_name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gateway_url() {
+        let storage = IpfsStorage::reader(
+            "k51qzi5uqu5dlvj2baxnqndepeb86cbk3ng7n3i46uzyxzyqj2xjonzllnv0v8".to_owned(),
+            DEFAULT_IPFS_GATEWAY.parse().unwrap(),
+            None,
+        );
+        assert_eq!(
+            storage.gateway_url("checkpoint_1_with_id.json").unwrap().as_str(),
+            "https://ipfs.io/ipns/k51qzi5uqu5dlvj2baxnqndepeb86cbk3ng7n3i46uzyxzyqj2xjonzllnv0v8/checkpoint_1_with_id.json"
+        );
+        assert_eq!(
+            storage.announcement_location(),
+            "ipfs://k51qzi5uqu5dlvj2baxnqndepeb86cbk3ng7n3i46uzyxzyqj2xjonzllnv0v8"
+        );
+    }
+}
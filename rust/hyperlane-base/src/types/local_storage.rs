@@ -4,8 +4,9 @@ use async_trait::async_trait;
 use eyre::{Context, Result};
 use hyperlane_core::{SignedAnnouncement, SignedCheckpointWithMessageId};
 use prometheus::IntGauge;
+use tokio::fs::File;
 
-use crate::traits::CheckpointSyncer;
+use crate::traits::{CheckpointSyncer, ObjectWriter};
 
 #[derive(Debug, Clone)]
 /// Type for reading/write to LocalStorage
@@ -30,7 +31,13 @@ impl LocalStorage {
     }
 
     fn checkpoint_file_path(&self, index: u32) -> PathBuf {
-        self.path.join(format!("{}_with_id.json", index))
+        self.path.join(format!("checkpoint_{index}_with_id.json"))
+    }
+
+    /// Where checkpoints used to be written, before the file names were
+    /// aligned with the keys of the other checkpoint syncers
+    fn legacy_checkpoint_file_path(&self, index: u32) -> PathBuf {
+        self.path.join(format!("{index}_with_id.json"))
     }
 
     fn latest_index_file_path(&self) -> PathBuf {
@@ -42,6 +49,41 @@ impl LocalStorage {
     }
 }
 
+impl LocalStorage {
+    /// The path of the object with `key`, creating its parent directory
+    async fn object_path(&self, key: &str) -> Result<PathBuf> {
+        let path = self.path.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Creating directory {parent:?}"))?;
+        }
+        Ok(path)
+    }
+}
+
+#[async_trait]
+impl ObjectWriter for LocalStorage {
+    async fn write_object(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        let path = self.object_path(key).await?;
+        tokio::fs::write(&path, data)
+            .await
+            .with_context(|| format!("Writing object to {path:?}"))?;
+        Ok(())
+    }
+
+    async fn write_object_from_file(&self, key: &str, mut file: File, _size: u64) -> Result<()> {
+        let path = self.object_path(key).await?;
+        let mut object = File::create(&path)
+            .await
+            .with_context(|| format!("Creating object {path:?}"))?;
+        tokio::io::copy(&mut file, &mut object)
+            .await
+            .with_context(|| format!("Writing object to {path:?}"))?;
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl CheckpointSyncer for LocalStorage {
     async fn latest_index(&self) -> Result<Option<u32>> {
@@ -71,8 +113,12 @@ impl CheckpointSyncer for LocalStorage {
     }
 
     async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
-        let Ok(data) = tokio::fs::read(self.checkpoint_file_path(index)).await else {
-            return Ok(None);
+        let data = match tokio::fs::read(self.checkpoint_file_path(index)).await {
+            Ok(data) => data,
+            Err(_) => match tokio::fs::read(self.legacy_checkpoint_file_path(index)).await {
+                Ok(data) => data,
+                Err(_) => return Ok(None),
+            },
         };
         let checkpoint = serde_json::from_slice(&data)?;
         Ok(Some(checkpoint))
@@ -91,6 +137,22 @@ impl CheckpointSyncer for LocalStorage {
         Ok(())
     }
 
+    async fn delete_checkpoint(&self, index: u32) -> Result<()> {
+        for path in [
+            self.checkpoint_file_path(index),
+            self.legacy_checkpoint_file_path(index),
+        ] {
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    return Err(err).with_context(|| format!("Deleting checkpoint at {path:?}"))
+                }
+            }
+        }
+        Ok(())
+    }
+
     async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
         let serialized_announcement = serde_json::to_string_pretty(signed_announcement)?;
         let path = self.announcement_file_path();
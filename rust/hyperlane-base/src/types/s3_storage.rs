@@ -8,13 +8,17 @@ use hyperlane_core::{SignedAnnouncement, SignedCheckpointWithMessageId};
 use prometheus::IntGauge;
 use rusoto_core::{
     credential::{Anonymous, AwsCredentials, StaticProvider},
-    Region, RusotoError,
+    ByteStream, Region, RusotoError,
 };
-use rusoto_s3::{GetObjectError, GetObjectRequest, PutObjectRequest, S3Client, S3};
-use tokio::time::timeout;
+use rusoto_s3::{
+    DeleteObjectRequest, GetObjectError, GetObjectRequest, PutObjectRequest, S3Client, S3,
+};
+use tokio::{fs::File, time::timeout};
 
 use crate::types::utils;
-use crate::{settings::aws_credentials::AwsChainCredentialsProvider, CheckpointSyncer};
+use crate::{
+    settings::aws_credentials::AwsChainCredentialsProvider, CheckpointSyncer, ObjectWriter,
+};
 
 /// The timeout for S3 requests. Rusoto doesn't offer timeout configuration
 /// out of the box, so S3 requests must be wrapped with a timeout.
@@ -52,11 +56,16 @@ impl fmt::Debug for S3Storage {
 
 impl S3Storage {
     async fn write_to_bucket(&self, key: String, body: &str) -> Result<()> {
+        self.put_to_bucket(key, Vec::from(body), "application/json")
+            .await
+    }
+
+    async fn put_to_bucket(&self, key: String, body: Vec<u8>, content_type: &str) -> Result<()> {
         let req = PutObjectRequest {
             key: self.get_composite_key(key),
             bucket: self.bucket.clone(),
-            body: Some(Vec::from(body).into()),
-            content_type: Some("application/json".to_owned()),
+            body: Some(body.into()),
+            content_type: Some(content_type.to_owned()),
             ..Default::default()
         };
         timeout(
@@ -67,6 +76,21 @@ impl S3Storage {
         Ok(())
     }
 
+    async fn delete_from_bucket(&self, key: String) -> Result<()> {
+        let req = DeleteObjectRequest {
+            key: self.get_composite_key(key),
+            bucket: self.bucket.clone(),
+            ..Default::default()
+        };
+        // Deleting a key that doesn't exist succeeds as well
+        timeout(
+            Duration::from_secs(S3_REQUEST_TIMEOUT_SECONDS),
+            self.authenticated_client().delete_object(req),
+        )
+        .await??;
+        Ok(())
+    }
+
     /// Uses an anonymous client. This should only be used for publicly accessible buckets.
     async fn anonymously_read_from_bucket(&self, key: String) -> Result<Option<Vec<u8>>> {
         let req = GetObjectRequest {
@@ -141,6 +165,32 @@ impl S3Storage {
     }
 }
 
+#[async_trait]
+impl ObjectWriter for S3Storage {
+    async fn write_object(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        self.put_to_bucket(key.to_owned(), data, "application/octet-stream")
+            .await
+    }
+
+    /// Streams the file, without the timeout of the other requests, as large
+    /// files take a while to upload
+    async fn write_object_from_file(&self, key: &str, file: File, size: u64) -> Result<()> {
+        let req = PutObjectRequest {
+            key: self.get_composite_key(key.to_owned()),
+            bucket: self.bucket.clone(),
+            body: Some(ByteStream::new_with_size(
+                utils::file_chunks(file),
+                size.try_into()?,
+            )),
+            content_length: Some(size.try_into()?),
+            content_type: Some("application/octet-stream".to_owned()),
+            ..Default::default()
+        };
+        self.authenticated_client().put_object(req).await?;
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl CheckpointSyncer for S3Storage {
     async fn latest_index(&self) -> Result<Option<u32>> {
@@ -188,6 +238,11 @@ impl CheckpointSyncer for S3Storage {
         Ok(())
     }
 
+    async fn delete_checkpoint(&self, index: u32) -> Result<()> {
+        self.delete_from_bucket(S3Storage::checkpoint_key(index))
+            .await
+    }
+
     async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
         let serialized_announcement = serde_json::to_string_pretty(signed_announcement)?;
         self.write_to_bucket(S3Storage::announcement_key(), &serialized_announcement)
@@ -196,11 +251,22 @@ impl CheckpointSyncer for S3Storage {
     }
 
     fn announcement_location(&self) -> String {
-        match self.folder.as_deref() {
-            None | Some("") => format!("s3://{}/{}", self.bucket, self.region.name()),
-            Some(folder_str) => {
-                format!("s3://{}/{}/{}", self.bucket, self.region.name(), folder_str)
+        let location = match &self.region {
+            // S3-compatible stores are announced with their endpoint, e.g.
+            // `s3+https://<account>.r2.cloudflarestorage.com/<bucket>/auto`
+            Region::Custom { name, endpoint } => {
+                let (scheme, host) = endpoint.split_once("://").unwrap_or(("https", endpoint));
+                format!(
+                    "s3+{scheme}://{}/{}/{name}",
+                    host.trim_end_matches('/'),
+                    self.bucket
+                )
             }
+            region => format!("s3://{}/{}", self.bucket, region.name()),
+        };
+        match self.folder.as_deref() {
+            None | Some("") => location,
+            Some(folder_str) => format!("{location}/{folder_str}"),
         }
     }
 }
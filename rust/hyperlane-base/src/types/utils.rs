@@ -1,7 +1,10 @@
-use std::time::Duration;
+use std::{io, time::Duration};
 
+use bytes::Bytes;
 use eyre::Result;
+use futures_util::{stream, Stream};
 use rusoto_core::{HttpClient, HttpConfig};
+use tokio::{fs::File, io::AsyncReadExt};
 
 /// See https://github.com/hyperium/hyper/issues/2136#issuecomment-589488526
 pub const HYPER_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(15);
@@ -13,3 +16,20 @@ pub fn http_client_with_timeout() -> Result<HttpClient> {
     config.pool_idle_timeout(HYPER_POOL_IDLE_TIMEOUT);
     Ok(HttpClient::new_with_config(config)?)
 }
+
+/// Size of the chunks files are streamed in
+const FILE_CHUNK_SIZE: usize = 1 << 16;
+
+/// Streams the rest of `file` in chunks, so that it doesn't have to fit in
+/// memory
+pub fn file_chunks(file: File) -> impl Stream<Item = io::Result<Bytes>> + Send + Sync + 'static {
+    stream::try_unfold(file, |mut file| async move {
+        let mut chunk = vec![0; FILE_CHUNK_SIZE];
+        let read = file.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        chunk.truncate(read);
+        Ok(Some((Bytes::from(chunk), file)))
+    })
+}
@@ -0,0 +1,21 @@
+//! Deserializes arbitrary bytes as a signed checkpoint, as validators' checkpoint
+//! syncers do with objects fetched from S3 / GCS / local storage.
+
+#![no_main]
+
+use hyperlane_core::{Signable, SignedCheckpointWithMessageId};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(signed) = serde_json::from_slice::<SignedCheckpointWithMessageId>(data) else {
+        return;
+    };
+    let _ = signed.value.signing_hash();
+    let _ = signed.value.eth_signed_message_hash();
+
+    // Whatever was accepted must survive a roundtrip through our own serialization
+    let serialized = serde_json::to_vec(&signed).expect("serialization cannot fail");
+    let reparsed = serde_json::from_slice::<SignedCheckpointWithMessageId>(&serialized)
+        .expect("own serialization must deserialize");
+    assert_eq!(reparsed, signed);
+});
@@ -0,0 +1,17 @@
+//! Decodes arbitrary bytes as a `HyperlaneMessage`, as the relayer and scraper
+//! do with dispatched messages read from chain.
+
+#![no_main]
+
+use hyperlane_core::{Decode, Encode, HyperlaneMessage};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(message) = HyperlaneMessage::read_from(&mut &data[..]) else {
+        return;
+    };
+    // The encoding is canonical, so anything that decodes must re-encode to the input
+    assert_eq!(message.to_vec(), data);
+    assert_eq!(HyperlaneMessage::from(&data.to_vec()), message);
+    let _ = message.id();
+});
@@ -0,0 +1,15 @@
+//! Structured fuzzing of the `HyperlaneMessage` codec: any well-formed message
+//! must survive an encode / decode roundtrip with a stable id.
+
+#![no_main]
+
+use hyperlane_core::{Decode, Encode, HyperlaneMessage};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|message: HyperlaneMessage| {
+    let encoded = message.to_vec();
+    let decoded = HyperlaneMessage::read_from(&mut encoded.as_slice())
+        .expect("a well-formed message must decode");
+    assert_eq!(decoded, message);
+    assert_eq!(decoded.id(), message.id());
+});
@@ -0,0 +1,61 @@
+//! Parses arbitrary bytes with the sealevel message id multisig ISM metadata parser, i.e.
+//! `merkle_tree_hook (32) || root (32) || index (4) || signatures (65 * n)`, and builds the
+//! multisig checkpoint the relayer would have signed over from the result.
+
+#![no_main]
+
+use hyperlane_core::{
+    Checkpoint, CheckpointWithMessageId, Decode, Encode, MultisigSignedCheckpoint, Signature,
+    SignedCheckpointWithMessageId, H256,
+};
+use hyperlane_sealevel_multisig_ism_message_id::metadata::MultisigIsmMessageIdMetadata;
+use libfuzzer_sys::fuzz_target;
+
+const SIGNATURES_OFFSET: usize = 68;
+const SIGNATURE_LEN: usize = 65;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(metadata) = MultisigIsmMessageIdMetadata::try_from(data.to_vec()) else {
+        return;
+    };
+    assert_eq!(
+        metadata.validator_signatures.len(),
+        (data.len() - SIGNATURES_OFFSET) / SIGNATURE_LEN
+    );
+
+    // Recovery ids are normalized while parsing, so the encoding only has to be stable from here on.
+    let encoded = metadata.to_vec();
+    assert_eq!(encoded.len(), data.len());
+    let reparsed =
+        MultisigIsmMessageIdMetadata::try_from(encoded).expect("re-encoded metadata parses");
+    assert_eq!(
+        reparsed.origin_merkle_tree_hook,
+        metadata.origin_merkle_tree_hook
+    );
+    assert_eq!(reparsed.merkle_root, metadata.merkle_root);
+    assert_eq!(reparsed.merkle_index, metadata.merkle_index);
+    assert_eq!(reparsed.validator_signatures, metadata.validator_signatures);
+
+    let checkpoint = CheckpointWithMessageId {
+        checkpoint: Checkpoint {
+            merkle_tree_hook_address: metadata.origin_merkle_tree_hook,
+            mailbox_domain: 0,
+            root: metadata.merkle_root,
+            index: metadata.merkle_index,
+        },
+        message_id: H256::zero(),
+    };
+    let mut signed_checkpoints: Vec<SignedCheckpointWithMessageId> = metadata
+        .validator_signatures
+        .iter()
+        .map(|signature| SignedCheckpointWithMessageId {
+            value: checkpoint,
+            signature: Signature::read_from(&mut &signature.as_fixed_bytes()[..])
+                .expect("65 bytes make a signature"),
+        })
+        .collect();
+    let expected_signatures = signed_checkpoints.len();
+    let multisig = MultisigSignedCheckpoint::try_from(&mut signed_checkpoints)
+        .expect("parsed metadata has at least one signature");
+    assert_eq!(multisig.signatures.len(), expected_signatures);
+});
@@ -7,6 +7,8 @@ use crate::H256;
 pub mod incremental;
 /// A full incremental merkle. Suitable for running off-chain.
 pub mod merkle;
+/// A merkle tree producing proofs of its leaves against its past roots.
+pub mod prover;
 /// Utilities for manipulating proofs to reflect sparse merkle trees.
 pub mod sparse;
 
@@ -1,13 +1,12 @@
-//! Prover process: generate proofs in the tree.
-//!
-//! Struct responsible for syncing Prover
+use tracing::instrument;
 
-use hyperlane_core::accumulator::{
-    merkle::{merkle_root_from_branch, MerkleTree, MerkleTreeError, Proof},
-    TREE_DEPTH,
+use crate::{
+    accumulator::{
+        merkle::{MerkleTree, MerkleTreeError, Proof},
+        TREE_DEPTH,
+    },
+    H256,
 };
-use hyperlane_core::H256;
-use tracing::{error, instrument};
 
 /// A depth-32 sparse Merkle tree capable of producing proofs for arbitrary
 /// elements.
@@ -34,9 +33,16 @@ pub enum ProverError {
     /// Bubbled up from underlying
     #[error(transparent)]
     MerkleTreeError(#[from] MerkleTreeError),
+    /// Requested proof of a leaf inserted after the checkpoint
+    #[error("Requested proof of leaf {leaf_index} against the earlier checkpoint {root_index}")]
+    LeafAfterRoot {
+        /// The index of the leaf
+        leaf_index: usize,
+        /// The index of the checkpoint
+        root_index: usize,
+    },
     /// Failed proof verification
     #[error("Proof verification failed. Root is {expected}, produced is {actual}")]
-    #[allow(dead_code)]
     VerificationFailed {
         /// The expected root (this tree's current root)
         expected: H256,
@@ -75,7 +81,9 @@ impl Prover {
         self.count
     }
 
-    /// Create a proof of a leaf in this tree.
+    /// Create a proof of the leaf at `leaf_index` against the root of the
+    /// tree when the checkpoint `root_index` was its latest leaf, which is
+    /// what validators signed.
     #[instrument(err, skip(self), fields(prover_msg_count=self.count()))]
     pub fn prove_against_previous(
         &self,
@@ -92,14 +100,23 @@ impl Prover {
                 count,
             });
         }
+        if leaf_index > root_index {
+            return Err(ProverError::LeafAfterRoot {
+                leaf_index,
+                root_index,
+            });
+        }
         Ok(self.tree.prove_against_previous(leaf_index, root_index))
     }
 
     /// Verify a proof against this tree's root.
-    #[allow(dead_code)]
     pub fn verify(&self, proof: &Proof) -> Result<(), ProverError> {
-        let actual = merkle_root_from_branch(proof.leaf, &proof.path, TREE_DEPTH, proof.index);
-        let expected = self.root();
+        Self::verify_against(proof, self.root())
+    }
+
+    /// Verify a proof against the root `expected` of some checkpoint.
+    pub fn verify_against(proof: &Proof, expected: H256) -> Result<(), ProverError> {
+        let actual = proof.root();
         if expected == actual {
             Ok(())
         } else {
@@ -139,11 +156,11 @@ impl std::iter::Extend<H256> for Prover {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "ethers"))]
 mod test {
-    use ethers::utils::hash_message;
+    use ethers_core::utils::hash_message;
 
-    use hyperlane_core::test_utils;
+    use crate::{test_utils, Decode, Encode};
 
     use super::*;
 
@@ -174,7 +191,40 @@ mod test {
 
                 // check that the tree can verify the proof for this leaf
                 tree.verify(&proof).unwrap();
+
+                // check that the proof survives encoding
+                let decoded = Proof::read_from(&mut proof.to_vec().as_slice()).unwrap();
+                assert_eq!(decoded, proof);
+            }
+        }
+    }
+
+    #[test]
+    fn it_proves_against_previous_checkpoints() {
+        let leaves: Vec<H256> = (0..20).map(H256::from_low_u64_be).collect();
+        let mut tree = Prover::default();
+        let mut roots = vec![];
+        for leaf in &leaves {
+            roots.push(tree.ingest(*leaf).unwrap());
+        }
+
+        for (root_index, root) in roots.iter().enumerate() {
+            for leaf_index in 0..=root_index {
+                let proof = tree.prove_against_previous(leaf_index, root_index).unwrap();
+                assert_eq!(proof.leaf, leaves[leaf_index]);
+                Prover::verify_against(&proof, *root).unwrap();
+                if root_index + 1 < roots.len() {
+                    assert!(Prover::verify_against(&proof, roots[root_index + 1]).is_err());
+                }
             }
         }
+        assert!(matches!(
+            tree.prove_against_previous(3, 2),
+            Err(ProverError::LeafAfterRoot { .. })
+        ));
+        assert!(matches!(
+            tree.prove_against_previous(0, 20),
+            Err(ProverError::ZeroProof { .. })
+        ));
     }
 }
@@ -11,7 +11,10 @@ use num_traits::FromPrimitive;
 #[cfg(feature = "strum")]
 use strum::{EnumIter, EnumString, IntoStaticStr};
 
-use crate::{utils::many_to_one, HyperlaneProtocolError, IndexMode, H160, H256};
+use crate::{
+    utils::many_to_one, AddressCodec, ChainCommunicationError, ChainResult, HyperlaneProtocolError,
+    IndexMode, H160, H256,
+};
 
 #[derive(Debug, Clone)]
 pub struct Address(pub bytes::Bytes);
@@ -186,6 +189,24 @@ pub enum HyperlaneDomainProtocol {
     Sealevel,
     /// A Cosmos-based chain type which uses hyperlane-cosmos.
     Cosmos,
+    /// A Starknet-based chain type which uses hyperlane-starknet.
+    Starknet,
+    /// An Aptos-based chain type which uses hyperlane-aptos.
+    Aptos,
+    /// A Sui-based chain type which uses hyperlane-sui.
+    Sui,
+    /// A TON-based chain type which uses hyperlane-ton.
+    Ton,
+    /// A Substrate-based chain type which uses hyperlane-substrate.
+    Substrate,
+    /// A Tron-based chain type which uses hyperlane-tron.
+    Tron,
+    /// A Stellar-based chain type which uses hyperlane-stellar.
+    Stellar,
+    /// A NEAR-based chain type which uses hyperlane-near.
+    Near,
+    /// An Algorand-based chain type which uses hyperlane-algorand.
+    Algorand,
 }
 
 impl HyperlaneDomainProtocol {
@@ -196,8 +217,228 @@ impl HyperlaneDomainProtocol {
             Fuel => format!("{:?}", addr),
             Sealevel => format!("{:?}", addr),
             Cosmos => format!("{:?}", addr),
+            Starknet => format!("{:?}", addr),
+            Aptos => format!("{:?}", addr),
+            Sui => format!("{:?}", addr),
+            // Raw addresses on the basechain
+            Ton => format!("0:{}", hex::encode(addr)),
+            Substrate => format!("{:?}", addr),
+            Tron => {
+                let mut bytes = vec![TRON_ADDRESS_PREFIX];
+                bytes.extend_from_slice(H160::from(addr).as_bytes());
+                bs58::encode(bytes).with_check().into_string()
+            }
+            // Accounts and contracts share the address space, contracts are
+            // the more common
+            Stellar => stellar_strkey::Contract(addr.0).to_string(),
+            Near => fmt_near_address(addr),
+            Algorand => fmt_algorand_address(addr),
         }
     }
+
+    /// Parse an address in the native format of the protocol into the H256
+    /// representation used by Hyperlane, e.g. a bech32 contract address for
+    /// Cosmos, a base58 program id for Sealevel or an address without its
+    /// leading zeros for Starknet, Aptos and Sui, or a raw or user-friendly
+    /// address for TON, whose workchain is dropped, an SS58 address for
+    /// Substrate, a base58 address for Tron, a `G...` account or `C...`
+    /// contract strkey for Stellar, an account id for NEAR or a base32
+    /// account address or decimal application id for Algorand. A 0x-prefixed
+    /// H256 is accepted for all protocols.
+    pub fn parse_address(&self, address: &str) -> ChainResult<H256> {
+        use HyperlaneDomainProtocol::*;
+
+        if address.starts_with("0x") && address.len() == 66 {
+            return Ok(address.parse()?);
+        }
+        let codec = match self {
+            Ethereum => return Ok(address.parse::<H160>()?.into()),
+            Fuel => return Ok(address.parse()?),
+            Sealevel => AddressCodec::Base58,
+            // Any prefix and length is accepted when decoding
+            Cosmos => AddressCodec::Bech32 {
+                prefix: String::new(),
+                len: H256::len_bytes(),
+            },
+            Starknet => AddressCodec::HexFelt,
+            Aptos | Sui => AddressCodec::Hex,
+            // Any network prefix is accepted when decoding
+            Substrate => AddressCodec::Ss58 { prefix: 0 },
+            Ton => return left_pad(address, parse_ton_address(address)?),
+            Tron => return left_pad(address, parse_tron_address(address)?),
+            Stellar => return left_pad(address, parse_stellar_address(address)?),
+            Near => return left_pad(address, parse_near_address(address)?),
+            Algorand => return left_pad(address, parse_algorand_address(address)?),
+        };
+        Ok(codec.decode(address)?)
+    }
+}
+
+/// Left pads the bytes parsed from `address`, matching how shorter addresses
+/// are represented on chain
+fn left_pad(address: &str, bytes: Vec<u8>) -> ChainResult<H256> {
+    if bytes.len() > H256::len_bytes() {
+        return Err(ChainCommunicationError::ParseError {
+            msg: format!("Address `{address}` is longer than 32 bytes"),
+        });
+    }
+    let mut padded = H256::zero();
+    padded[H256::len_bytes() - bytes.len()..].copy_from_slice(&bytes);
+    Ok(padded)
+}
+
+/// Parses the account id of a raw (`workchain:hex`) or user-friendly (base64
+/// of the flags, workchain, account id and checksum) TON address
+fn parse_ton_address(address: &str) -> ChainResult<Vec<u8>> {
+    use base64::{
+        engine::general_purpose::{STANDARD, URL_SAFE},
+        Engine,
+    };
+
+    let invalid = |e: &dyn std::fmt::Display| ChainCommunicationError::ParseError {
+        msg: format!("Invalid TON address `{address}`: {e}"),
+    };
+    if let Some((_, account)) = address.split_once(':') {
+        return hex::decode(account).map_err(|e| invalid(&e));
+    }
+    let engine = if address.contains(['-', '_']) {
+        URL_SAFE
+    } else {
+        STANDARD
+    };
+    let bytes = engine.decode(address).map_err(|e| invalid(&e))?;
+    if bytes.len() != 36 {
+        return Err(invalid(&"user-friendly addresses are 36 bytes"));
+    }
+    Ok(bytes[2..34].to_vec())
+}
+
+/// The version byte of Tron addresses, before the 20 byte account id
+const TRON_ADDRESS_PREFIX: u8 = 0x41;
+
+/// Parses the account id of a base58check Tron address, or of its hex form
+/// with or without the version byte
+fn parse_tron_address(address: &str) -> ChainResult<Vec<u8>> {
+    let invalid = |e: &dyn std::fmt::Display| ChainCommunicationError::ParseError {
+        msg: format!("Invalid Tron address `{address}`: {e}"),
+    };
+    let bytes = match hex::decode(address.strip_prefix("0x").unwrap_or(address)) {
+        Ok(bytes) if bytes.len() == H160::len_bytes() => return Ok(bytes),
+        Ok(bytes) => bytes,
+        Err(_) => bs58::decode(address)
+            .with_check(Some(TRON_ADDRESS_PREFIX))
+            .into_vec()
+            .map_err(|e| invalid(&e))?,
+    };
+    match bytes.split_first() {
+        Some((&TRON_ADDRESS_PREFIX, account)) if account.len() == H160::len_bytes() => {
+            Ok(account.to_vec())
+        }
+        _ => Err(invalid(&"expected a version byte and 20 bytes")),
+    }
+}
+
+/// Parses the public key of a `G...` account or the id of a `C...` contract
+/// strkey
+fn parse_stellar_address(address: &str) -> ChainResult<Vec<u8>> {
+    use stellar_strkey::Strkey;
+
+    let invalid = |e: &dyn std::fmt::Display| ChainCommunicationError::ParseError {
+        msg: format!("Invalid Stellar address `{address}`: {e}"),
+    };
+    match Strkey::from_string(address).map_err(|e| invalid(&e))? {
+        Strkey::PublicKeyEd25519(key) => Ok(key.0.to_vec()),
+        Strkey::Contract(contract) => Ok(contract.0.to_vec()),
+        _ => Err(invalid(&"not an account or contract")),
+    }
+}
+
+/// Whether `byte` can be part of a named NEAR account id
+fn is_near_account_char(byte: u8) -> bool {
+    byte.is_ascii_lowercase() || byte.is_ascii_digit() || matches!(byte, b'-' | b'_' | b'.')
+}
+
+/// Formats a NEAR account id. Named accounts are represented by their left
+/// padded ASCII id, and implicit accounts by their 32 byte public key.
+fn fmt_near_address(addr: H256) -> String {
+    let bytes = addr.as_bytes();
+    let name = &bytes[bytes.iter().take_while(|b| **b == 0).count()..];
+    if !name.is_empty() && name.iter().all(|b| is_near_account_char(*b)) {
+        String::from_utf8_lossy(name).into_owned()
+    } else {
+        hex::encode(addr)
+    }
+}
+
+/// Parses an implicit NEAR account id, i.e. a hex public key, or a named
+/// account id of up to 32 characters
+fn parse_near_address(address: &str) -> ChainResult<Vec<u8>> {
+    let invalid = |e: &dyn std::fmt::Display| ChainCommunicationError::ParseError {
+        msg: format!("Invalid NEAR account id `{address}`: {e}"),
+    };
+    if address.len() == 2 * H256::len_bytes() {
+        if let Ok(key) = hex::decode(address) {
+            return Ok(key);
+        }
+    }
+    if address.len() > H256::len_bytes() {
+        return Err(invalid(
+            &"named accounts can't be longer than 32 characters",
+        ));
+    }
+    if address.is_empty() || !address.bytes().all(is_near_account_char) {
+        return Err(invalid(&"invalid characters"));
+    }
+    Ok(address.as_bytes().to_vec())
+}
+
+/// The checksum of an Algorand address, the last 4 bytes of the SHA-512/256
+/// hash of its public key
+fn algorand_checksum(public_key: &[u8]) -> [u8; 4] {
+    use sha2::{Digest, Sha512_256};
+
+    let hash = Sha512_256::digest(public_key);
+    let mut checksum = [0; 4];
+    checksum.copy_from_slice(&hash[28..]);
+    checksum
+}
+
+/// Formats an Algorand address. Application ids fit in the last 8 bytes and
+/// are formatted in decimal, while accounts are the base32 of their public
+/// key and checksum.
+fn fmt_algorand_address(addr: H256) -> String {
+    let (high, app_id) = addr.as_bytes().split_at(24);
+    if high.iter().all(|b| *b == 0) {
+        let app_id: [u8; 8] = app_id.try_into().expect("8 bytes");
+        return u64::from_be_bytes(app_id).to_string();
+    }
+    let mut bytes = addr.as_bytes().to_vec();
+    bytes.extend_from_slice(&algorand_checksum(addr.as_bytes()));
+    data_encoding::BASE32_NOPAD.encode(&bytes)
+}
+
+/// Parses a decimal Algorand application id, or the public key of a base32
+/// account address
+fn parse_algorand_address(address: &str) -> ChainResult<Vec<u8>> {
+    let invalid = |e: &dyn std::fmt::Display| ChainCommunicationError::ParseError {
+        msg: format!("Invalid Algorand address `{address}`: {e}"),
+    };
+    if let Ok(app_id) = address.parse::<u64>() {
+        return Ok(app_id.to_be_bytes().to_vec());
+    }
+    let bytes = data_encoding::BASE32_NOPAD
+        .decode(address.as_bytes())
+        .map_err(|e| invalid(&e))?;
+    if bytes.len() != H256::len_bytes() + 4 {
+        return Err(invalid(
+            &"expected a 32 byte public key and a 4 byte checksum",
+        ));
+    }
+    let (public_key, checksum) = bytes.split_at(H256::len_bytes());
+    if checksum != algorand_checksum(public_key) {
+        return Err(invalid(&"checksum mismatch"));
+    }
+    Ok(public_key.to_vec())
 }
 
 /// Hyperlane domain technical stack types.
@@ -446,8 +687,8 @@ impl HyperlaneDomain {
         use HyperlaneDomainProtocol::*;
         let protocol = self.domain_protocol();
         many_to_one!(match protocol {
-            IndexMode::Block: [Ethereum, Cosmos],
-            IndexMode::Sequence : [Sealevel, Fuel],
+            IndexMode::Block: [Ethereum, Cosmos, Fuel, Starknet, Sui, Ton, Substrate, Tron, Stellar, Near, Algorand],
+            IndexMode::Sequence : [Sealevel, Aptos],
         })
     }
 }
@@ -510,4 +751,139 @@ mod tests {
         );
         assert!("foo".parse::<KnownHyperlaneDomain>().is_err());
     }
+
+    #[test]
+    fn test_parse_address() {
+        use bech32::ToBase32;
+
+        use crate::{HyperlaneDomainProtocol, H256};
+
+        let address = H256::repeat_byte(0x42);
+        let account = [0x42u8; 20];
+        let mut padded_account = H256::zero();
+        padded_account[12..].copy_from_slice(&account);
+
+        assert_eq!(
+            HyperlaneDomainProtocol::Ethereum
+                .parse_address("0x4242424242424242424242424242424242424242")
+                .unwrap(),
+            padded_account
+        );
+        assert_eq!(
+            HyperlaneDomainProtocol::Sealevel
+                .parse_address(&bs58::encode(address).into_string())
+                .unwrap(),
+            address
+        );
+        let bech32_contract =
+            bech32::encode("neutron", address.to_base32(), bech32::Variant::Bech32).unwrap();
+        assert_eq!(
+            HyperlaneDomainProtocol::Cosmos
+                .parse_address(&bech32_contract)
+                .unwrap(),
+            address
+        );
+        let bech32_account =
+            bech32::encode("neutron", account.to_base32(), bech32::Variant::Bech32).unwrap();
+        assert_eq!(
+            HyperlaneDomainProtocol::Cosmos
+                .parse_address(&bech32_account)
+                .unwrap(),
+            padded_account
+        );
+        assert_eq!(
+            HyperlaneDomainProtocol::Cosmos
+                .parse_address(&format!("{address:?}"))
+                .unwrap(),
+            address
+        );
+        assert!(HyperlaneDomainProtocol::Cosmos
+            .parse_address("neutron1invalid")
+            .is_err());
+        let mut starknet_address = H256::zero();
+        starknet_address[29..].copy_from_slice(&[0x04, 0x9d, 0x36]);
+        assert_eq!(
+            HyperlaneDomainProtocol::Starknet
+                .parse_address("0x49d36")
+                .unwrap(),
+            starknet_address
+        );
+        let mut aptos_address = H256::zero();
+        aptos_address[31] = 0x1;
+        assert_eq!(
+            HyperlaneDomainProtocol::Aptos.parse_address("0x1").unwrap(),
+            aptos_address
+        );
+        let ton_address = HyperlaneDomainProtocol::Ton.fmt_address(address);
+        assert_eq!(
+            HyperlaneDomainProtocol::Ton
+                .parse_address(&ton_address)
+                .unwrap(),
+            address
+        );
+        assert_eq!(
+            HyperlaneDomainProtocol::Substrate
+                .parse_address("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")
+                .unwrap(),
+            "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+                .parse::<H256>()
+                .unwrap()
+        );
+        let tron_address = HyperlaneDomainProtocol::Tron.fmt_address(padded_account);
+        assert!(tron_address.starts_with('T'));
+        assert_eq!(
+            HyperlaneDomainProtocol::Tron
+                .parse_address(&tron_address)
+                .unwrap(),
+            padded_account
+        );
+
+        assert_eq!(
+            HyperlaneDomainProtocol::Stellar
+                .parse_address("GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ")
+                .unwrap(),
+            "0x3f0c34bf93ad0d9971d04ccc90f705511c838aad9734a4a2fb0d7a03fc7fe89a"
+                .parse::<H256>()
+                .unwrap()
+        );
+        let contract = HyperlaneDomainProtocol::Stellar.fmt_address(padded_account);
+        assert!(contract.starts_with('C'));
+        assert_eq!(
+            HyperlaneDomainProtocol::Stellar
+                .parse_address(&contract)
+                .unwrap(),
+            padded_account
+        );
+
+        let near = HyperlaneDomainProtocol::Near;
+        let mailbox = near.parse_address("mailbox.hyperlane.near").unwrap();
+        assert_eq!(&mailbox[10..], b"mailbox.hyperlane.near");
+        assert_eq!(near.fmt_address(mailbox), "mailbox.hyperlane.near");
+        let implicit = "98793cd91a3f870fb126f66285808c7e094afcfc4eda8a970f6648cdf0dbd6de";
+        assert_eq!(
+            near.fmt_address(near.parse_address(implicit).unwrap()),
+            implicit
+        );
+        assert!(near.parse_address("Not.A.Near.Account").is_err());
+
+        let algorand = HyperlaneDomainProtocol::Algorand;
+        let account = "H4GDJP4TVUGZS4OQJTGJB5YFKEOIHCVNS42KJIX3BV5AH7D75CNJANYAO4";
+        assert_eq!(
+            algorand.parse_address(account).unwrap(),
+            "0x3f0c34bf93ad0d9971d04ccc90f705511c838aad9734a4a2fb0d7a03fc7fe89a"
+                .parse::<H256>()
+                .unwrap()
+        );
+        assert_eq!(
+            algorand.fmt_address(algorand.parse_address(account).unwrap()),
+            account
+        );
+        let app = algorand.parse_address("1284326447").unwrap();
+        assert_eq!(app, H256::from_low_u64_be(1284326447));
+        assert_eq!(algorand.fmt_address(app), "1284326447");
+        // Wrong checksum
+        assert!(algorand
+            .parse_address("H4GDJP4TVUGZS4OQJTGJB5YFKEOIHCVNS42KJIX3BV5AH7D75CNJANYAO5")
+            .is_err());
+    }
 }
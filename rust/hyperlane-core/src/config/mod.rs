@@ -3,7 +3,10 @@
 //! it but is more forgiving for the deserialization, and then to implement
 //! `FromRawConf` which will allow for better error messages.
 
-use std::fmt::{Debug, Display, Formatter};
+use std::{
+    fmt::{Debug, Display, Formatter},
+    time::Duration,
+};
 
 pub use config_path::ConfigPath;
 use eyre::Report;
@@ -29,6 +32,9 @@ pub struct OperationBatchConfig {
     pub batch_contract_address: Option<H256>,
     /// Batch size
     pub max_batch_size: u32,
+    /// How long to wait for a full batch once an operation is ready to be
+    /// submitted. Operations are submitted as soon as they are ready if unset.
+    pub batch_window: Option<Duration>,
 }
 
 /// A trait that allows for constructing `Self` from a raw config type.
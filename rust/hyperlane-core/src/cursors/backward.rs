@@ -4,20 +4,21 @@ use std::{collections::HashSet, fmt::Debug, ops::RangeInclusive, sync::Arc, time
 
 use async_trait::async_trait;
 use eyre::Result;
-use hyperlane_core::{
-    indexed_to_sequence_indexed_array, ContractSyncCursor, CursorAction,
-    HyperlaneSequenceAwareIndexerStoreReader, IndexMode, Indexed, LogMeta, SequenceIndexed,
-};
 use itertools::Itertools;
 use tokio::time::sleep;
 use tracing::{debug, instrument, warn};
 
 use super::{LastIndexedSnapshot, TargetSnapshot};
+use crate::{
+    indexed_to_sequence_indexed_array, ContractSyncCursor, CursorAction,
+    HyperlaneSequenceAwareIndexerStoreReader, IndexMode, Indexed, LogMeta, ReorgAwareCursor,
+    SequenceIndexed,
+};
 
 const MAX_BACKWARD_SYNC_BLOCKING_TIME: Duration = Duration::from_secs(5);
 
 /// A sequence-aware cursor that syncs backward until there are no earlier logs to index.
-pub(crate) struct BackwardSequenceAwareSyncCursor<T> {
+pub struct BackwardSequenceAwareSyncCursor<T> {
     /// The max chunk size to query for logs.
     /// If in sequence mode, this is the max number of sequences to query.
     /// If in block mode, this is the max number of blocks to query.
@@ -31,6 +32,9 @@ pub(crate) struct BackwardSequenceAwareSyncCursor<T> {
     /// if the last indexed snapshot was sequence 100, this would be sequence 99.
     /// A None value indicates we're fully synced.
     current_indexing_snapshot: Option<TargetSnapshot>,
+    /// The snapshot the cursor started from, which it rewinds to when logs it
+    /// indexed are reorged.
+    start_snapshot: LastIndexedSnapshot,
     /// The mode of indexing to use.
     index_mode: IndexMode,
 }
@@ -47,6 +51,8 @@ impl<T> Debug for BackwardSequenceAwareSyncCursor<T> {
 }
 
 impl<T: Debug> BackwardSequenceAwareSyncCursor<T> {
+    /// Creates a cursor indexing backward from the sequence preceding
+    /// `current_sequence_count`, which is expected at or before `start_block`.
     #[instrument(
         skip(db),
         fields(chunk_size, next_sequence, start_block, index_mode),
@@ -71,6 +77,7 @@ impl<T: Debug> BackwardSequenceAwareSyncCursor<T> {
             chunk_size,
             db,
             current_indexing_snapshot: last_indexed_snapshot.previous_target(),
+            start_snapshot: last_indexed_snapshot.clone(),
             last_indexed_snapshot,
             index_mode,
         }
@@ -327,6 +334,29 @@ impl<T: Debug> BackwardSequenceAwareSyncCursor<T> {
     }
 }
 
+impl<T> ReorgAwareCursor for BackwardSequenceAwareSyncCursor<T> {
+    /// The cursor only indexes blocks up to the one it started from. If any
+    /// of them were reorged, it rewinds to where it started and fast forwards
+    /// through the logs still in the store.
+    fn invalidate_range(&mut self, range: RangeInclusive<u32>) {
+        let reorg_start = *range.start();
+        if reorg_start > self.start_snapshot.at_block {
+            return;
+        }
+        warn!(
+            ?range,
+            current_indexing_snapshot=?self.current_indexing_snapshot,
+            last_indexed_snapshot=?self.last_indexed_snapshot,
+            "Blocks were reorged, rewinding",
+        );
+        self.last_indexed_snapshot = LastIndexedSnapshot {
+            sequence: self.start_snapshot.sequence,
+            at_block: reorg_start,
+        };
+        self.rewind();
+    }
+}
+
 #[async_trait]
 impl<T: Send + Sync + Clone + Debug + 'static> ContractSyncCursor<T>
     for BackwardSequenceAwareSyncCursor<T>
@@ -777,6 +807,42 @@ mod test {
             // We're fully synced, so expect no range
             assert_eq!(cursor.get_next_range().await.unwrap(), None);
         }
+
+        #[tracing_test::traced_test]
+        #[tokio::test]
+        async fn test_rewinds_for_reorgs() {
+            let mut cursor = get_cursor().await;
+
+            let range = cursor.get_next_range().await.unwrap().unwrap();
+            cursor
+                .update(
+                    vec![
+                        (MockSequencedData::new(97).into(), log_meta_with_block(970)),
+                        (MockSequencedData::new(98).into(), log_meta_with_block(980)),
+                        (MockSequencedData::new(99).into(), log_meta_with_block(990)),
+                    ],
+                    range,
+                )
+                .await
+                .unwrap();
+
+            // Reorgs of blocks after the start block don't concern the cursor.
+            cursor.rewind_to_height(INITIAL_START_BLOCK + 1);
+            assert_eq!(
+                cursor.current_indexing_snapshot,
+                Some(TargetSnapshot {
+                    sequence: 96,
+                    at_block: 900,
+                })
+            );
+
+            // Otherwise the cursor rewinds to where it started, fast forwarding through the logs
+            // left in the store, which don't include the reorged sequences 97 to 99.
+            cursor.rewind_to_height(975);
+            let range = cursor.get_next_range().await.unwrap().unwrap();
+            assert_eq!(range, 900..=1000);
+            assert_eq!(cursor.last_indexed_snapshot, INITIAL_LAST_INDEXED_SNAPSHOT);
+        }
     }
 
     mod sequence_range {
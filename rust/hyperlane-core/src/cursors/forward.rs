@@ -7,18 +7,18 @@ use std::{
 
 use async_trait::async_trait;
 use eyre::Result;
-use hyperlane_core::{
-    indexed_to_sequence_indexed_array, ContractSyncCursor, CursorAction,
-    HyperlaneSequenceAwareIndexerStoreReader, IndexMode, Indexed, LogMeta, SequenceAwareIndexer,
-    SequenceIndexed,
-};
 use itertools::Itertools;
 use tracing::{debug, instrument, warn};
 
-use super::{LastIndexedSnapshot, TargetSnapshot};
+use super::{LastIndexedSnapshot, SequenceGap, SequenceGaps, TargetSnapshot};
+use crate::{
+    indexed_to_sequence_indexed_array, ContractSyncCursor, CursorAction,
+    HyperlaneSequenceAwareIndexerStoreReader, IndexMode, Indexed, LogMeta, ReorgAwareCursor,
+    SequenceAwareIndexer, SequenceIndexed,
+};
 
 /// A sequence-aware cursor that syncs forwards in perpetuity.
-pub(crate) struct ForwardSequenceAwareSyncCursor<T> {
+pub struct ForwardSequenceAwareSyncCursor<T> {
     /// The max chunk size to query for logs.
     /// If in sequence mode, this is the max number of sequences to query.
     /// If in block mode, this is the max number of blocks to query.
@@ -35,10 +35,20 @@ pub(crate) struct ForwardSequenceAwareSyncCursor<T> {
     /// The current snapshot we're indexing. As this is a forward cursor,
     /// if the last indexed snapshot was sequence 100, this would be sequence 101.
     current_indexing_snapshot: TargetSnapshot,
+    /// The snapshot the cursor started from, which it rewinds to when logs it
+    /// indexed are reorged.
+    start_snapshot: LastIndexedSnapshot,
     /// The target snapshot to index towards.
     target_snapshot: Option<TargetSnapshot>,
     /// The mode of indexing.
     index_mode: IndexMode,
+    /// How many times in a row the cursor rewinds on the same sequence
+    /// before skipping it, if it skips any.
+    skip_gaps_after_rewinds: Option<u32>,
+    /// The sequence the cursor last rewound on, and how many times in a row.
+    stalled_rewinds: Option<(u32, u32)>,
+    /// The sequences the cursor skipped.
+    gaps: Arc<SequenceGaps>,
 }
 
 impl<T> Debug for ForwardSequenceAwareSyncCursor<T> {
@@ -49,11 +59,15 @@ impl<T> Debug for ForwardSequenceAwareSyncCursor<T> {
             .field("current_indexing_snapshot", &self.current_indexing_snapshot)
             .field("target_snapshot", &self.target_snapshot)
             .field("index_mode", &self.index_mode)
+            .field("skip_gaps_after_rewinds", &self.skip_gaps_after_rewinds)
+            .field("stalled_rewinds", &self.stalled_rewinds)
             .finish()
     }
 }
 
 impl<T: Debug> ForwardSequenceAwareSyncCursor<T> {
+    /// Creates a cursor indexing forward from `next_sequence`, which is
+    /// expected at or after `start_block`.
     #[instrument(
         skip(db, latest_sequence_querier),
         fields(chunk_size, next_sequence, start_block, index_mode),
@@ -78,16 +92,30 @@ impl<T: Debug> ForwardSequenceAwareSyncCursor<T> {
             chunk_size,
             latest_sequence_querier,
             db,
-            last_indexed_snapshot,
             current_indexing_snapshot: TargetSnapshot {
                 sequence: next_sequence,
                 at_block: start_block,
             },
             target_snapshot: None,
+            start_snapshot: last_indexed_snapshot.clone(),
+            last_indexed_snapshot,
             index_mode,
+            skip_gaps_after_rewinds: None,
+            stalled_rewinds: None,
+            gaps: Default::default(),
         }
     }
 
+    /// Skips a sequence once the cursor rewound on it `max_rewinds` times in
+    /// a row, recording it in `gaps` to be backfilled, rather than stalling
+    /// on a log the RPC can't return, e.g. because it pruned it.
+    pub fn with_gap_skipping(mut self, max_rewinds: u32, gaps: Arc<SequenceGaps>) -> Self {
+        // A single rewind doesn't tell whether the sequence was indexed since
+        self.skip_gaps_after_rewinds = Some(max_rewinds.max(2));
+        self.gaps = gaps;
+        self
+    }
+
     /// Gets the next range of logs to index.
     /// If there are no logs to index, returns `None`.
     /// If there are logs to index, returns the range of logs, either by sequence or block number
@@ -302,7 +330,7 @@ impl<T: Debug> ForwardSequenceAwareSyncCursor<T> {
                 target_snapshot=?self.target_snapshot,
                 "Reached the target block number but not the target sequence, rewinding to last snapshot",
             );
-            self.rewind();
+            self.rewind_stalled();
             return Ok(());
         }
 
@@ -394,7 +422,56 @@ impl<T: Debug> ForwardSequenceAwareSyncCursor<T> {
             "Log sequences don't exactly match the expected sequence range, rewinding to last indexed snapshot",
         );
         // If there are any missing sequences, rewind to index immediately after the last snapshot.
+        self.rewind_stalled();
+    }
+
+    /// Rewinds the cursor because the sequence after the last indexed snapshot is missing,
+    /// skipping it once the cursor rewound on it too many times in a row.
+    fn rewind_stalled(&mut self) {
+        self.rewind();
+        let sequence = self.current_indexing_snapshot.sequence;
+        let rewinds = match self.stalled_rewinds {
+            Some((stalled_sequence, rewinds)) if stalled_sequence == sequence => rewinds + 1,
+            _ => 1,
+        };
+        self.stalled_rewinds = Some((sequence, rewinds));
+        if self
+            .skip_gaps_after_rewinds
+            .map_or(true, |max_rewinds| rewinds < max_rewinds)
+        {
+            return;
+        }
+
+        let range = match &self.index_mode {
+            IndexMode::Block => {
+                self.last_indexed_snapshot.at_block
+                    ..=self
+                        .target_snapshot
+                        .as_ref()
+                        .map_or(self.last_indexed_snapshot.at_block, |target| {
+                            target.at_block
+                        })
+            }
+            IndexMode::Sequence => sequence..=sequence,
+        };
+        warn!(
+            sequence,
+            rewinds,
+            ?range,
+            last_indexed_snapshot=?self.last_indexed_snapshot,
+            "Skipping sequence whose log repeatedly couldn't be indexed, recording it as a gap to backfill",
+        );
+        self.gaps.record(
+            sequence,
+            SequenceGap {
+                range,
+                chunk_size: self.chunk_size,
+            },
+        );
+        // Treat the sequence as indexed, so that the cursor moves on to the next one
+        self.last_indexed_snapshot.sequence = Some(sequence);
         self.rewind();
+        self.stalled_rewinds = None;
     }
 
     // Rewinds the cursor to target immediately after the last indexed snapshot.
@@ -403,6 +480,36 @@ impl<T: Debug> ForwardSequenceAwareSyncCursor<T> {
     }
 }
 
+impl<T> ReorgAwareCursor for ForwardSequenceAwareSyncCursor<T> {
+    /// Reorged blocks after the last indexed log are queried again. If logs
+    /// the cursor indexed may have been reorged, which sequences they were
+    /// isn't known, so the cursor rewinds to where it started and fast
+    /// forwards through the logs still in the store.
+    fn invalidate_range(&mut self, range: RangeInclusive<u32>) {
+        let reorg_start = *range.start();
+        if reorg_start > self.current_indexing_snapshot.at_block {
+            // The reorged blocks weren't queried yet
+            return;
+        }
+        warn!(
+            ?range,
+            current_indexing_snapshot=?self.current_indexing_snapshot,
+            last_indexed_snapshot=?self.last_indexed_snapshot,
+            "Blocks were reorged, rewinding",
+        );
+        if reorg_start > self.last_indexed_snapshot.at_block {
+            self.current_indexing_snapshot.at_block = reorg_start;
+        } else {
+            self.last_indexed_snapshot = LastIndexedSnapshot {
+                sequence: self.start_snapshot.sequence,
+                at_block: u32::min(self.start_snapshot.at_block, reorg_start),
+            };
+            self.rewind();
+        }
+        self.target_snapshot = None;
+    }
+}
+
 #[async_trait]
 impl<T: Send + Sync + Clone + Debug + 'static> ContractSyncCursor<T>
     for ForwardSequenceAwareSyncCursor<T>
@@ -467,8 +574,8 @@ impl<T: Send + Sync + Clone + Debug + 'static> ContractSyncCursor<T>
 
 #[cfg(test)]
 pub(crate) mod test {
+    use crate::{ChainResult, HyperlaneLogStore, Indexed, Indexer, Sequenced};
     use derive_new::new;
-    use hyperlane_core::{ChainResult, HyperlaneLogStore, Indexed, Indexer, Sequenced};
 
     use super::*;
 
@@ -1001,6 +1108,59 @@ pub(crate) mod test {
                 }
             );
         }
+
+        /// Tests rewinding when blocks are reorged.
+        #[tracing_test::traced_test]
+        #[tokio::test]
+        async fn test_rewinds_for_reorgs() {
+            let mut cursor = get_cursor().await;
+
+            // Synced up to the latest sequence, the cursor is at the tip.
+            let range = cursor.get_next_range().await.unwrap();
+            assert_eq!(range, None);
+
+            // Blocks that weren't queried yet being reorged doesn't move the cursor.
+            cursor.rewind_to_height(101);
+            assert_eq!(
+                cursor.current_indexing_snapshot,
+                TargetSnapshot {
+                    sequence: 5,
+                    at_block: 100,
+                }
+            );
+
+            // Reorgs after the last indexed log only make the reorged blocks be queried again.
+            cursor.rewind_to_height(95);
+            assert_eq!(
+                cursor.current_indexing_snapshot,
+                TargetSnapshot {
+                    sequence: 5,
+                    at_block: 95,
+                }
+            );
+            assert_eq!(cursor.last_indexed_snapshot, INITIAL_LAST_INDEXED_SNAPSHOT);
+
+            // Reorgs of indexed logs rewind the cursor to where it started, fast forwarding
+            // through the logs left in the store, which no longer include the reorged sequence 4.
+            cursor.db = Arc::new(MockHyperlaneSequenceAwareIndexerStore {
+                logs: vec![
+                    (MockSequencedData::new(0), log_meta_with_block(50)),
+                    (MockSequencedData::new(1), log_meta_with_block(60)),
+                    (MockSequencedData::new(2), log_meta_with_block(70)),
+                    (MockSequencedData::new(3), log_meta_with_block(80)),
+                ],
+            });
+            cursor.rewind_to_height(85);
+            let range = cursor.get_next_range().await.unwrap();
+            assert_eq!(range, Some(80..=100));
+            assert_eq!(
+                cursor.last_indexed_snapshot,
+                LastIndexedSnapshot {
+                    sequence: Some(3),
+                    at_block: 80,
+                }
+            );
+        }
     }
 
     mod sequence_range {
@@ -1223,5 +1383,50 @@ pub(crate) mod test {
             )
             .await;
         }
+
+        /// Tests skipping a sequence whose log is persistently missing
+        #[tracing_test::traced_test]
+        #[tokio::test]
+        async fn test_skips_persistent_gaps() {
+            let gaps = Arc::new(SequenceGaps::default());
+            let mut cursor = get_cursor().await.with_gap_skipping(2, gaps.clone());
+
+            cursor.latest_sequence_querier = Arc::new(MockLatestSequenceQuerier {
+                latest_sequence_count: Some(8),
+                tip: 120,
+            });
+
+            // Sequence 5 is missing
+            let logs = vec![
+                (MockSequencedData::new(6).into(), log_meta_with_block(100)),
+                (MockSequencedData::new(7).into(), log_meta_with_block(105)),
+            ];
+            let range = cursor.get_next_range().await.unwrap().unwrap();
+            assert_eq!(range, 5..=7);
+            cursor.update(logs.clone(), range).await.unwrap();
+
+            // A single rewind doesn't skip the sequence
+            assert_eq!(cursor.current_indexing_snapshot.sequence, 5);
+            assert!(gaps.pending().is_empty());
+
+            let range = cursor.get_next_range().await.unwrap().unwrap();
+            assert_eq!(range, 5..=7);
+            cursor.update(logs, range).await.unwrap();
+
+            // The second one does, and the cursor moves on
+            assert_eq!(
+                gaps.pending(),
+                vec![(
+                    5,
+                    SequenceGap {
+                        range: 5..=5,
+                        chunk_size: CHUNK_SIZE,
+                    }
+                )]
+            );
+            assert_eq!(cursor.last_indexed_snapshot.sequence, Some(5));
+            let range = cursor.get_next_range().await.unwrap().unwrap();
+            assert_eq!(range, 6..=7);
+        }
     }
 }
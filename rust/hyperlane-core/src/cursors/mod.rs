@@ -1,18 +1,25 @@
-use std::{fmt::Debug, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    ops::RangeInclusive,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use eyre::Result;
-use hyperlane_core::{
+
+use crate::{
     ChainCommunicationError, ContractSyncCursor, CursorAction,
-    HyperlaneSequenceAwareIndexerStoreReader, IndexMode, Indexed, LogMeta, SequenceAwareIndexer,
+    HyperlaneSequenceAwareIndexerStoreReader, IndexMode, Indexed, LogMeta, ReorgAwareCursor,
+    SequenceAwareIndexer,
 };
-use std::ops::RangeInclusive;
 
 mod backward;
 mod forward;
 
-pub(crate) use backward::BackwardSequenceAwareSyncCursor;
-pub(crate) use forward::ForwardSequenceAwareSyncCursor;
+pub use backward::BackwardSequenceAwareSyncCursor;
+pub use forward::ForwardSequenceAwareSyncCursor;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct LastIndexedSnapshot {
@@ -48,22 +55,78 @@ impl LastIndexedSnapshot {
     }
 }
 
+/// A sequence that a forward cursor skipped because its log couldn't be
+/// fetched, and where to look for it again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceGap {
+    /// The range the log is expected in, of blocks or of sequences depending
+    /// on the index mode
+    pub range: RangeInclusive<u32>,
+    /// The largest range to query at once
+    pub chunk_size: u32,
+}
+
+/// The sequences skipped by a forward cursor that weren't backfilled yet.
+/// They're shared with the task backfilling them and with the consumers of
+/// the indexed logs, which shouldn't wait for them either.
+#[derive(Debug, Default)]
+pub struct SequenceGaps(Mutex<BTreeMap<u32, SequenceGap>>);
+
+impl SequenceGaps {
+    /// Record that `sequence` was skipped
+    pub fn record(&self, sequence: u32, gap: SequenceGap) {
+        self.0
+            .lock()
+            .expect("sequence gaps lock poisoned")
+            .insert(sequence, gap);
+    }
+
+    /// Forget `sequence`, e.g. once its log was backfilled
+    pub fn resolve(&self, sequence: u32) {
+        self.0
+            .lock()
+            .expect("sequence gaps lock poisoned")
+            .remove(&sequence);
+    }
+
+    /// Whether `sequence` was skipped and not backfilled yet
+    pub fn contains(&self, sequence: u32) -> bool {
+        self.0
+            .lock()
+            .expect("sequence gaps lock poisoned")
+            .contains_key(&sequence)
+    }
+
+    /// The gaps that weren't backfilled yet, lowest sequence first
+    pub fn pending(&self) -> Vec<(u32, SequenceGap)> {
+        self.0
+            .lock()
+            .expect("sequence gaps lock poisoned")
+            .iter()
+            .map(|(sequence, gap)| (*sequence, gap.clone()))
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct TargetSnapshot {
     pub sequence: u32,
     pub at_block: u32,
 }
 
+/// The direction a [ForwardBackwardSequenceAwareSyncCursor] last synced in
 #[derive(Debug)]
 pub enum SyncDirection {
+    /// Syncing newer logs
     Forward,
+    /// Syncing older logs
     Backward,
 }
 
 /// A cursor that prefers to sync forward, but will sync backward if there is nothing to
 /// sync forward.
 #[derive(Debug)]
-pub(crate) struct ForwardBackwardSequenceAwareSyncCursor<T> {
+pub struct ForwardBackwardSequenceAwareSyncCursor<T> {
     forward: ForwardSequenceAwareSyncCursor<T>,
     backward: BackwardSequenceAwareSyncCursor<T>,
     last_direction: SyncDirection,
@@ -99,6 +162,13 @@ impl<T: Debug> ForwardBackwardSequenceAwareSyncCursor<T> {
             last_direction: SyncDirection::Forward,
         })
     }
+
+    /// Skip the sequences the forward cursor keeps rewinding on, recording
+    /// them in `gaps`. See [ForwardSequenceAwareSyncCursor::with_gap_skipping].
+    pub fn with_gap_skipping(mut self, max_rewinds: u32, gaps: Arc<SequenceGaps>) -> Self {
+        self.forward = self.forward.with_gap_skipping(max_rewinds, gaps);
+        self
+    }
 }
 
 #[async_trait]
@@ -137,3 +207,10 @@ impl<T: Send + Sync + Clone + Debug + 'static> ContractSyncCursor<T>
         }
     }
 }
+
+impl<T> ReorgAwareCursor for ForwardBackwardSequenceAwareSyncCursor<T> {
+    fn invalidate_range(&mut self, range: RangeInclusive<u32>) {
+        self.forward.invalidate_range(range.clone());
+        self.backward.invalidate_range(range);
+    }
+}
@@ -11,7 +11,7 @@ use crate::rpc_clients::RpcClientError;
 use std::string::FromUtf8Error;
 
 use crate::HyperlaneProviderError;
-use crate::{Error as PrimitiveTypeError, HyperlaneSignerError, H256, U256};
+use crate::{AddressCodecError, Error as PrimitiveTypeError, HyperlaneSignerError, H256, U256};
 
 /// The result of interacting with a chain.
 pub type ChainResult<T> = Result<T, ChainCommunicationError>;
@@ -157,6 +157,9 @@ pub enum ChainCommunicationError {
     /// Hyperlane signer error
     #[error("{0}")]
     HyperlaneSignerError(#[from] HyperlaneSignerError),
+    /// Address conversion error
+    #[error(transparent)]
+    AddressCodecError(#[from] AddressCodecError),
 }
 
 impl ChainCommunicationError {
@@ -33,6 +33,10 @@ pub mod metrics;
 /// Core hyperlane system data structures
 mod types;
 
+/// Sequence-aware cursors governing the indexing of sequenced events
+#[cfg(feature = "async")]
+pub mod cursors;
+
 mod chain;
 mod error;
 
@@ -3,12 +3,33 @@ use std::fmt::Debug;
 use async_trait::async_trait;
 use auto_impl::auto_impl;
 
-use crate::{ChainResult, HyperlaneContract};
+use crate::{ChainResult, HyperlaneContract, H256};
+
+/// The data of an ERC-3668 `OffchainLookup` revert, which tells where to
+/// fetch the metadata of a message from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffchainLookup {
+    /// The contract that reverted, which must be the ISM
+    pub sender: H256,
+    /// The gateway urls to query, in order of preference
+    pub urls: Vec<String>,
+    /// The data to send to the gateways
+    pub call_data: Vec<u8>,
+    /// The selector of the function to call with the response of a gateway
+    pub callback_function: [u8; 4],
+    /// The data to pass to the callback along with the response
+    pub extra_data: Vec<u8>,
+}
 
 /// Interface for the CcipReadIsm chain contract
 #[async_trait]
 #[auto_impl(&, Box, Arc)]
 pub trait CcipReadIsm: HyperlaneContract + Send + Sync + Debug {
-    /// Reverts with a custom error specifying how to query for offchain information
-    async fn get_offchain_verify_info(&self, message: Vec<u8>) -> ChainResult<()>;
+    /// The offchain lookup the ISM reverts with to specify how to query for
+    /// the offchain information verifying `message`, or None if it doesn't
+    /// revert
+    async fn get_offchain_verify_info(
+        &self,
+        message: Vec<u8>,
+    ) -> ChainResult<Option<OffchainLookup>>;
 }
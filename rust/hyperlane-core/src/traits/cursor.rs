@@ -13,7 +13,7 @@ use crate::{Indexed, LogMeta};
 /// A cursor governs event indexing for a contract.
 #[async_trait]
 #[auto_impl(Box)]
-pub trait ContractSyncCursor<T>: Debug + Send + Sync + 'static {
+pub trait ContractSyncCursor<T>: ReorgAwareCursor + Debug + Send + Sync + 'static {
     /// The next block range that should be queried.
     /// This method should be tolerant to being called multiple times in a row
     /// without any updates in between.
@@ -35,6 +35,25 @@ pub trait ContractSyncCursor<T>: Debug + Send + Sync + 'static {
     ) -> Result<()>;
 }
 
+/// Hooks for cursors to handle reorgs of the chain they index, so that every
+/// cursor gets the same semantics.
+///
+/// Logs of the invalidated blocks are expected to have been removed from the
+/// store the cursor reads beforehand, otherwise the cursor fast forwards
+/// through them again.
+#[auto_impl(&mut, Box)]
+pub trait ReorgAwareCursor {
+    /// Forgets what was indexed in the blocks of `range`, which were reorged,
+    /// so that they're indexed again.
+    fn invalidate_range(&mut self, range: RangeInclusive<u32>);
+
+    /// Rewinds the cursor to index again from `height`, the first block that
+    /// was reorged.
+    fn rewind_to_height(&mut self, height: u32) {
+        self.invalidate_range(height..=u32::MAX)
+    }
+}
+
 /// The action that should be taken by the contract sync loop
 pub enum CursorAction {
     /// Direct the contract_sync task to query a block range (inclusive)
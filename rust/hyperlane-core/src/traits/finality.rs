@@ -0,0 +1,222 @@
+use std::fmt::Debug;
+use std::num::NonZeroU64;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+
+use crate::{ChainCommunicationError, ChainResult};
+
+/// A block tag some chains maintain for blocks that are unlikely or unable
+/// to be reorged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockTag {
+    /// The latest block that is unlikely to be reorged
+    Safe,
+    /// The latest block that can no longer be reorged
+    Finalized,
+}
+
+/// The chain state finality policies are evaluated against
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait FinalitySource: Send + Sync + Debug {
+    /// The number of the latest block
+    async fn latest_block_number(&self) -> ChainResult<u32>;
+
+    /// The timestamp of the block `number`, in seconds
+    async fn block_timestamp(&self, number: u32) -> ChainResult<u64>;
+
+    /// The number of the latest block with `tag`, if the chain maintains it
+    async fn tagged_block_number(&self, tag: BlockTag) -> ChainResult<Option<u32>>;
+
+    /// The number of the latest block whose data was settled on the L1, if
+    /// the chain is a rollup
+    async fn l1_settled_block_number(&self) -> ChainResult<Option<u32>> {
+        Ok(None)
+    }
+}
+
+/// Decides which blocks of a chain can no longer be reorged, which bounds
+/// what indexers index and what validators sign
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait FinalityPolicy: Send + Sync + Debug {
+    /// The number of the latest final block
+    async fn finalized_block_number(&self, source: &dyn FinalitySource) -> ChainResult<u32>;
+
+    /// How many blocks behind the latest one the latest final block is, to
+    /// query state at it through APIs taking a lag. The latest block may
+    /// move between the two queries, so this is an approximation.
+    async fn lag(&self, source: &dyn FinalitySource) -> ChainResult<Option<NonZeroU64>> {
+        let finalized = self.finalized_block_number(source).await?;
+        let latest = source.latest_block_number().await?;
+        Ok(NonZeroU64::new(latest.saturating_sub(finalized).into()))
+    }
+}
+
+/// The finality policies that can be configured for a chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finality {
+    /// Blocks are final once this many blocks were built on top of them
+    Blocks(u32),
+    /// Blocks are final once they are this old
+    Time(Duration),
+    /// Blocks are final up to the latest block with the tag
+    Tag(BlockTag),
+    /// Blocks are final once their data was settled on the L1
+    L1Settlement,
+}
+
+impl Default for Finality {
+    fn default() -> Self {
+        Self::Blocks(0)
+    }
+}
+
+impl Finality {
+    /// The latest block at least `age` older than the latest one. It's found
+    /// by searching backwards exponentially and then by bisection, which
+    /// takes a number of queries logarithmic in the number of blocks built
+    /// during `age`.
+    async fn block_older_than(source: &dyn FinalitySource, age: Duration) -> ChainResult<u32> {
+        let latest = source.latest_block_number().await?;
+        if age.is_zero() {
+            return Ok(latest);
+        }
+        let target = source
+            .block_timestamp(latest)
+            .await?
+            .saturating_sub(age.as_secs());
+
+        // The block `newer` is always more recent than the target
+        let mut newer = latest;
+        let mut step = 1u32;
+        let mut older = loop {
+            let candidate = latest.saturating_sub(step);
+            if source.block_timestamp(candidate).await? <= target {
+                break candidate;
+            }
+            if candidate == 0 {
+                return Ok(0);
+            }
+            newer = candidate;
+            step = step.saturating_mul(2);
+        };
+        while newer - older > 1 {
+            let middle = older + (newer - older) / 2;
+            if source.block_timestamp(middle).await? <= target {
+                older = middle;
+            } else {
+                newer = middle;
+            }
+        }
+        Ok(older)
+    }
+}
+
+#[async_trait]
+impl FinalityPolicy for Finality {
+    async fn finalized_block_number(&self, source: &dyn FinalitySource) -> ChainResult<u32> {
+        match self {
+            Self::Blocks(blocks) => Ok(source.latest_block_number().await?.saturating_sub(*blocks)),
+            Self::Time(age) => Self::block_older_than(source, *age).await,
+            Self::Tag(tag) => source.tagged_block_number(*tag).await?.ok_or_else(|| {
+                ChainCommunicationError::from_other_str("chain has no block with the finality tag")
+            }),
+            Self::L1Settlement => source.l1_settled_block_number().await?.ok_or_else(|| {
+                ChainCommunicationError::from_other_str("chain has no block settled on the L1")
+            }),
+        }
+    }
+
+    /// Block counts are a fixed lag, which doesn't need querying the chain
+    async fn lag(&self, source: &dyn FinalitySource) -> ChainResult<Option<NonZeroU64>> {
+        match self {
+            Self::Blocks(blocks) => Ok(NonZeroU64::new((*blocks).into())),
+            _ => {
+                let finalized = self.finalized_block_number(source).await?;
+                let latest = source.latest_block_number().await?;
+                Ok(NonZeroU64::new(latest.saturating_sub(finalized).into()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::future::Future;
+
+    use super::*;
+
+    /// A chain of 1000 blocks built every 2 seconds
+    #[derive(Debug)]
+    struct MockSource {
+        finalized: Option<u32>,
+    }
+
+    #[async_trait]
+    impl FinalitySource for MockSource {
+        async fn latest_block_number(&self) -> ChainResult<u32> {
+            Ok(999)
+        }
+
+        async fn block_timestamp(&self, number: u32) -> ChainResult<u64> {
+            Ok(1_700_000_000 + 2 * u64::from(number))
+        }
+
+        async fn tagged_block_number(&self, tag: BlockTag) -> ChainResult<Option<u32>> {
+            Ok(self.finalized.filter(|_| tag == BlockTag::Finalized))
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn finalized(finality: Finality, source: &MockSource) -> Option<u32> {
+        block_on(finality.finalized_block_number(source)).ok()
+    }
+
+    #[test]
+    fn test_finalized_block_number() {
+        let source = MockSource {
+            finalized: Some(900),
+        };
+        assert_eq!(finalized(Finality::Blocks(20), &source), Some(979));
+        assert_eq!(finalized(Finality::Blocks(2000), &source), Some(0));
+        assert_eq!(
+            finalized(Finality::Tag(BlockTag::Finalized), &source),
+            Some(900)
+        );
+        assert_eq!(finalized(Finality::Tag(BlockTag::Safe), &source), None);
+        assert_eq!(finalized(Finality::L1Settlement, &source), None);
+    }
+
+    #[test]
+    fn test_time_finality() {
+        let source = MockSource { finalized: None };
+        let time = |secs| finalized(Finality::Time(Duration::from_secs(secs)), &source);
+        assert_eq!(time(0), Some(999));
+        assert_eq!(time(1), Some(998));
+        assert_eq!(time(2), Some(998));
+        assert_eq!(time(3), Some(997));
+        assert_eq!(time(600), Some(699));
+        assert_eq!(time(1998), Some(0));
+        assert_eq!(time(5000), Some(0));
+    }
+
+    #[test]
+    fn test_lag() {
+        let source = MockSource {
+            finalized: Some(900),
+        };
+        let lag = |finality: Finality| block_on(finality.lag(&source)).unwrap();
+        assert_eq!(lag(Finality::Blocks(0)), None);
+        assert_eq!(lag(Finality::Blocks(20)), NonZeroU64::new(20));
+        assert_eq!(lag(Finality::Tag(BlockTag::Finalized)), NonZeroU64::new(99));
+    }
+}
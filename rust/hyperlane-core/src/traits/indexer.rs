@@ -9,6 +9,8 @@ use std::ops::RangeInclusive;
 
 use async_trait::async_trait;
 use auto_impl::auto_impl;
+#[cfg(feature = "async")]
+use futures::stream::BoxStream;
 use serde::Deserialize;
 
 use crate::{ChainResult, Indexed, LogMeta, H512};
@@ -46,6 +48,25 @@ pub trait Indexer<T: Sized>: Send + Sync + Debug {
     }
 }
 
+/// A stream of logs pushed by a chain
+#[cfg(feature = "async")]
+pub type LogStream<T> = BoxStream<'static, ChainResult<(Indexed<T>, LogMeta)>>;
+
+/// Interface for an indexer of chains that push logs as they are emitted,
+/// e.g. over a websocket subscription, rather than being polled for the logs
+/// of ranges of blocks.
+#[cfg(feature = "async")]
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait StreamingIndexer<T: Sized>: Send + Sync + Debug {
+    /// Stream the logs emitted from block `from` onwards, in the order they
+    /// were emitted. Logs of blocks already built are streamed before the
+    /// ones of new blocks. The stream ends or yields an error when the
+    /// subscription is lost, after which it can be resumed from the block of
+    /// the last streamed log.
+    async fn stream_logs(&self, from: u32) -> ChainResult<LogStream<T>>;
+}
+
 /// Interface for indexing data in sequence.
 /// SequenceAwareIndexer is an umbrella trait for all indexers types (sequence-aware and rate-limited).
 /// The rate-limited indexer doesn't need `SequenceAwareIndexer`, so impls of `SequenceAwareIndexer` just return nullish values.
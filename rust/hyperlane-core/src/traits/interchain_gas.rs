@@ -3,10 +3,32 @@ use std::fmt::Debug;
 use async_trait::async_trait;
 use auto_impl::auto_impl;
 
-use crate::HyperlaneContract;
+use crate::{ChainCommunicationError, ChainResult, HyperlaneContract, U256};
 
 /// Interface for the InterchainGasPaymaster chain contract.
 /// Allows abstraction over different chains.
 #[async_trait]
 #[auto_impl(&, Box, Arc)]
-pub trait InterchainGasPaymaster: HyperlaneContract + Send + Sync + Debug {}
+pub trait InterchainGasPaymaster: HyperlaneContract + Send + Sync + Debug {
+    /// Quote the payment, in the native token of this chain, for
+    /// `gas_amount` of gas on the `destination_domain`, as determined by the
+    /// gas oracle of the destination
+    async fn quote_gas_payment(
+        &self,
+        _destination_domain: u32,
+        _gas_amount: U256,
+    ) -> ChainResult<U256> {
+        Err(ChainCommunicationError::from_other_str(
+            "quoting gas payments is not supported",
+        ))
+    }
+
+    /// The exchange rate of the native token of the `destination_domain`
+    /// quoted in the native token of this chain by the gas oracle, scaled
+    /// by `TOKEN_EXCHANGE_RATE_SCALE`
+    async fn token_exchange_rate(&self, _destination_domain: u32) -> ChainResult<U256> {
+        Err(ChainCommunicationError::from_other_str(
+            "fetching token exchange rates is not supported",
+        ))
+    }
+}
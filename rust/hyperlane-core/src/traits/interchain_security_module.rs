@@ -41,6 +41,8 @@ pub enum ModuleType {
     Null,
     /// Ccip Read ISM (accepts offchain signature information)
     CcipRead,
+    /// Optimistic ISM (accepts pre-verified messages after a fraud window)
+    Optimistic,
 }
 
 /// Interface for the InterchainSecurityModule chain contract. Allows abstraction over
@@ -4,8 +4,9 @@ use std::num::NonZeroU64;
 use async_trait::async_trait;
 
 use crate::{
-    traits::TxOutcome, utils::domain_hash, BatchItem, ChainCommunicationError, ChainResult,
-    HyperlaneContract, HyperlaneMessage, TxCostEstimate, H256, U256,
+    traits::TxOutcome, utils::domain_hash, BatchItem, BatchResult, ChainCommunicationError,
+    ChainResult, DeliveryStatus, HyperlaneContract, HyperlaneMessage, MempoolCongestion,
+    ProcessSimulation, TxCostEstimate, H256, U256,
 };
 
 /// Interface for the Mailbox chain contract. Allows abstraction over different
@@ -26,6 +27,21 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
     /// Fetch the status of a message
     async fn delivered(&self, id: H256) -> ChainResult<bool>;
 
+    /// Fetch the delivery status of a message, which distinguishes messages
+    /// that aren't delivered yet from ones that never will be. Messages to
+    /// recipients that aren't contracts can't be delivered.
+    async fn delivery_status(&self, message: &HyperlaneMessage) -> ChainResult<DeliveryStatus> {
+        if self.delivered(message.id()).await? {
+            return Ok(DeliveryStatus::Delivered(None));
+        }
+        if !self.provider().is_contract(&message.recipient).await? {
+            return Ok(DeliveryStatus::Undeliverable {
+                reason: "recipient is not a contract".to_owned(),
+            });
+        }
+        Ok(DeliveryStatus::Pending)
+    }
+
     /// Fetch the current default interchain security module value
     async fn default_ism(&self) -> ChainResult<H256>;
 
@@ -40,11 +56,12 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
         tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome>;
 
-    /// Process a message with a proof against the provided signed checkpoint
+    /// Process a batch of messages in a single transaction, reporting the
+    /// outcome of each of them
     async fn process_batch(
         &self,
         _messages: &[BatchItem<HyperlaneMessage>],
-    ) -> ChainResult<TxOutcome> {
+    ) -> ChainResult<BatchResult> {
         // Batching is not supported by default
         Err(ChainCommunicationError::BatchingFailed)
     }
@@ -56,6 +73,24 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
         metadata: &[u8],
     ) -> ChainResult<TxCostEstimate>;
 
+    /// Simulate processing a message without sending a transaction,
+    /// returning why it would revert, if it would. Chains that can't
+    /// simulate report it as unsupported.
+    async fn simulate_process(
+        &self,
+        _message: &HyperlaneMessage,
+        _metadata: &[u8],
+    ) -> ChainResult<ProcessSimulation> {
+        Ok(ProcessSimulation::Unsupported)
+    }
+
+    /// Signals about how congested the mempool is for transactions sent by
+    /// the signer of this mailbox. Chains without congestion signals report
+    /// none.
+    async fn mempool_congestion(&self) -> ChainResult<MempoolCongestion> {
+        Ok(MempoolCongestion::default())
+    }
+
     /// Get the calldata for a transaction to process a message with a proof
     /// against the provided signed checkpoint
     fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8>;
@@ -4,13 +4,16 @@ pub use cursor::*;
 pub use db::*;
 pub use deployed::*;
 pub use encode::*;
+pub use finality::*;
 pub use indexer::*;
 pub use interchain_gas::*;
 pub use interchain_security_module::*;
 pub use mailbox::*;
 pub use merkle_tree_hook::*;
 pub use multisig_ism::*;
+pub use optimistic_ism::*;
 pub use pending_operation::*;
+pub use post_dispatch_hook::*;
 pub use provider::*;
 pub use routing_ism::*;
 pub use signing::*;
@@ -24,13 +27,16 @@ mod cursor;
 mod db;
 mod deployed;
 mod encode;
+mod finality;
 mod indexer;
 mod interchain_gas;
 mod interchain_security_module;
 mod mailbox;
 mod merkle_tree_hook;
 mod multisig_ism;
+mod optimistic_ism;
 mod pending_operation;
+mod post_dispatch_hook;
 mod provider;
 mod routing_ism;
 mod signing;
@@ -0,0 +1,43 @@
+use std::fmt::Debug;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+
+use crate::{
+    ChainResult, HyperlaneContract, HyperlaneMessage, TxCostEstimate, TxOutcome, H256, U256,
+};
+
+/// Interface for the OptimisticIsm chain contract, which verifies messages
+/// that were pre-verified by a submodule once a fraud window elapsed without
+/// the submodule being marked as fraudulent
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait OptimisticIsm: HyperlaneContract + Send + Sync + Debug {
+    /// The address of the ISM that pre-verifies `message`
+    async fn submodule(&self, message: &HyperlaneMessage) -> ChainResult<H256>;
+
+    /// How long pre-verified messages wait before they can be delivered
+    async fn fraud_window(&self) -> ChainResult<Duration>;
+
+    /// The timestamp `message` was pre-verified at, in seconds, if it was
+    async fn pre_verified_at(&self, message: &HyperlaneMessage) -> ChainResult<Option<u64>>;
+
+    /// Pre-verify `message` with the `metadata` of its submodule, which
+    /// starts its fraud window. If the provided tx_gas_limit is None, gas
+    /// estimation occurs.
+    async fn pre_verify(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome>;
+
+    /// Estimate transaction costs to pre-verify `message` with the
+    /// `metadata` of its submodule
+    async fn pre_verify_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate>;
+}
@@ -46,6 +46,14 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     /// operations when neither of them have a `next_attempt_after`
     fn priority(&self) -> u32;
 
+    /// The rank of this operation under the prioritization policy of the
+    /// queue, where a lower rank is submitted first. Ranks only order the
+    /// operations that are due to be attempted, and all operations rank the
+    /// same by default.
+    fn rank(&self) -> u64 {
+        0
+    }
+
     /// The domain this originates from.
     fn origin_domain_id(&self) -> u32;
 
@@ -67,6 +75,14 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     /// submit call.
     async fn prepare(&mut self) -> PendingOperationResult;
 
+    /// Renew the lease this operation holds on being submitted by this
+    /// process, right before it's submitted. Returns false if the lease was
+    /// lost, in which case the operation is prepared again instead. By
+    /// default operations hold no lease.
+    async fn renew_lease(&mut self) -> bool {
+        true
+    }
+
     /// Submit this operation to the blockchain
     async fn submit(&mut self);
 
@@ -76,6 +92,16 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     /// Get the estimated the cost of the `submit` call
     fn get_tx_cost_estimate(&self) -> Option<U256>;
 
+    /// Discard the data prepared for the submission, so that the operation
+    /// is prepared and estimated again before it is submitted. Its next
+    /// submission no longer counts as a replacement.
+    fn discard_preparation(&mut self);
+
+    /// Whether a transaction for this operation has been submitted before.
+    /// Submitting it again replaces one of our own transactions, which
+    /// reverted, got dropped or is stuck.
+    fn has_been_submitted(&self) -> bool;
+
     /// This will be called after the operation has been submitted and is
     /// responsible for checking if the operation has reached a point at
     /// which we consider it safe from reorgs.
@@ -101,6 +127,20 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     /// retried immediately.
     fn reset_attempts(&mut self);
 
+    /// The number of times this operation has been retried.
+    fn num_retries(&self) -> u32;
+
+    /// The reason the last attempt of this operation failed, if any.
+    fn last_error(&self) -> Option<String> {
+        None
+    }
+
+    /// Whether this operation was parked as undeliverable. Parked operations
+    /// aren't attempted again until their attempts are reset.
+    fn is_dead_lettered(&self) -> bool {
+        false
+    }
+
     /// Set the number of times this operation has been retried.
     #[cfg(any(test, feature = "test-utils"))]
     fn set_retries(&mut self, retries: u32);
@@ -199,7 +239,8 @@ pub enum PendingOperationResult {
     Confirm,
 }
 
-/// create a `op_try!` macro for the `on_retry` handler.
+/// create a `op_try!` macro for the `on_retry` handler, which is called with
+/// a description of the error.
 #[macro_export]
 macro_rules! make_op_try {
     ($on_retry:expr) => {
@@ -212,7 +253,7 @@ macro_rules! make_op_try {
                                     Err(e) => {
                                         error!(error=?e, concat!("Critical error when ", $ctx));
                                         #[allow(clippy::redundant_closure_call)]
-                                        return $on_retry();
+                                        return $on_retry(format!(concat!("Critical error when ", $ctx, ": {:?}"), e));
                                     }
                                 }
                             };
@@ -222,7 +263,7 @@ macro_rules! make_op_try {
                                     Err(e) => {
                                         warn!(error=?e, concat!("Error when ", $ctx));
                                         #[allow(clippy::redundant_closure_call)]
-                                        return $on_retry();
+                                        return $on_retry(format!(concat!("Error when ", $ctx, ": {:?}"), e));
                                     }
                                 }
                             };
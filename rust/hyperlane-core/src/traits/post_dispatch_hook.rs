@@ -0,0 +1,80 @@
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use num_derive::FromPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::{ChainResult, HyperlaneContract, HyperlaneMessage, H256, U256};
+
+/// Enumeration of all known post-dispatch hook types
+#[derive(FromPrimitive, Clone, Debug, Default, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strum", derive(strum::Display))]
+pub enum HookType {
+    /// INVALID hook
+    #[default]
+    Unused,
+    /// Routing hook (defers to another hook)
+    Routing,
+    /// Aggregation hook (calls multiple hooks)
+    Aggregation,
+    /// Merkle tree hook (inserts messages into the merkle tree)
+    MerkleTree,
+    /// Interchain gas paymaster (charges for gas on the destination)
+    InterchainGasPaymaster,
+    /// Routing hook falling back to a default hook
+    FallbackRouting,
+    /// Hook sending message ids to a native bridge ISM
+    IdAuthIsm,
+    /// Hook that can be paused
+    Pausable,
+    /// Protocol fee hook (charges a fixed fee)
+    ProtocolFee,
+    /// LayerZero v1 hook
+    LayerZeroV1,
+    /// Hook rate limiting dispatches
+    RateLimited,
+}
+
+/// Interface for the post-dispatch hook chain contracts the mailbox calls
+/// after dispatching messages. Allows abstraction over different chains.
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait PostDispatchHook: HyperlaneContract + Send + Sync + Debug {
+    /// The type of the hook
+    async fn hook_type(&self) -> ChainResult<HookType>;
+
+    /// Whether the hook supports `metadata`
+    async fn supports_metadata(&self, metadata: &[u8]) -> ChainResult<bool>;
+
+    /// The payment the hook requires to dispatch `message` with `metadata`
+    async fn quote_dispatch(
+        &self,
+        metadata: &[u8],
+        message: &HyperlaneMessage,
+    ) -> ChainResult<U256>;
+}
+
+/// Interface for the PausableHook chain contract, which makes dispatches
+/// revert while it's paused
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait PausableHook: HyperlaneContract + Send + Sync + Debug {
+    /// Whether the hook is paused
+    async fn paused(&self) -> ChainResult<bool>;
+}
+
+/// Interface for the ProtocolFee chain contract, which charges a fixed fee
+/// per dispatch
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait ProtocolFeeHook: HyperlaneContract + Send + Sync + Debug {
+    /// The fee charged per dispatch
+    async fn protocol_fee(&self) -> ChainResult<U256>;
+
+    /// The highest fee that can be set
+    async fn max_protocol_fee(&self) -> ChainResult<U256>;
+
+    /// The account the fees are collected to
+    async fn beneficiary(&self) -> ChainResult<H256>;
+}
@@ -4,7 +4,9 @@ use async_trait::async_trait;
 use auto_impl::auto_impl;
 use thiserror::Error;
 
-use crate::{BlockInfo, ChainInfo, ChainResult, HyperlaneChain, TxnInfo, H256, U256};
+use crate::{
+    Balance, BlockInfo, ChainInfo, ChainResult, HyperlaneChain, NativeDenom, TxnInfo, H256, U256,
+};
 
 /// Interface for a provider. Allows abstraction over different provider types
 /// for different chains.
@@ -28,8 +30,40 @@ pub trait HyperlaneProvider: HyperlaneChain + Send + Sync + Debug {
     /// Fetch the balance of the wallet address associated with the chain provider.
     async fn get_balance(&self, address: String) -> ChainResult<U256>;
 
+    /// The denomination balances are returned in by `get_balance`
+    fn native_denom(&self) -> NativeDenom {
+        NativeDenom::for_protocol(self.domain().domain_protocol())
+    }
+
     /// Fetch metrics related to this chain
     async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>>;
+
+    /// Fetch the id of the chain the provider is connected to, if the chain
+    /// identifies itself with a numeric id
+    async fn get_chain_id(&self) -> ChainResult<Option<u64>> {
+        Ok(None)
+    }
+}
+
+/// Capability of providers to look up balances of accounts in the native
+/// token, denominated so that they can be compared across chains
+#[async_trait]
+pub trait BalanceProvider: Send + Sync + Debug {
+    /// Fetch the balance of `address` in the native token
+    async fn native_balance(&self, address: String) -> ChainResult<Balance>;
+}
+
+#[async_trait]
+impl<P> BalanceProvider for P
+where
+    P: HyperlaneProvider + ?Sized,
+{
+    async fn native_balance(&self, address: String) -> ChainResult<Balance> {
+        Ok(Balance {
+            amount: self.get_balance(address).await?,
+            denom: self.native_denom(),
+        })
+    }
 }
 
 /// Errors when querying for provider information.
@@ -7,7 +7,7 @@ use serde::{
 use std::fmt::{Debug, Formatter};
 
 use crate::utils::bytes_to_hex;
-use crate::{Signature, H160, H256};
+use crate::{Decode, Encode, HyperlaneProtocolError, Signature, H160, H256};
 
 /// An error incurred by a signer
 #[derive(thiserror::Error, Debug)]
@@ -104,6 +104,31 @@ impl<T: Signable + Serialize> Serialize for SignedType<T> {
     }
 }
 
+impl<T: Signable + Encode> Encode for SignedType<T> {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        let mut written = 0;
+        written += self.value.write_to(writer)?;
+        written += self.signature.write_to(writer)?;
+        Ok(written)
+    }
+}
+
+impl<T: Signable + Decode> Decode for SignedType<T> {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+        Self: Sized,
+    {
+        Ok(Self {
+            value: T::read_from(reader)?,
+            signature: Signature::read_from(reader)?,
+        })
+    }
+}
+
 impl<T: Signable> SignedType<T> {
     /// Recover the Ethereum address of the signer
     #[cfg(feature = "ethers")]
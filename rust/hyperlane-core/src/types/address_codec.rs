@@ -0,0 +1,309 @@
+use bech32::{FromBase32, ToBase32, Variant};
+use blake2::{Blake2b512, Digest};
+
+use crate::H256;
+
+/// The prime of the Starknet field, which addresses are elements of
+const STARKNET_FIELD_PRIME: H256 = H256([
+    0x08, 0, 0, 0, 0, 0, 0, 0x11, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0x01,
+]);
+
+/// The prefix hashed with SS58 payloads to compute their checksum
+const SS58_CHECKSUM_PREFIX: &[u8] = b"SS58PRE";
+/// The length of the checksum of SS58 addresses
+const SS58_CHECKSUM_LEN: usize = 2;
+
+/// Errors converting between H256 and chain-native addresses
+#[derive(Debug, thiserror::Error)]
+pub enum AddressCodecError {
+    /// The address isn't validly encoded
+    #[error("Invalid {format} address `{address}`: {reason}")]
+    InvalidEncoding {
+        /// The native format
+        format: &'static str,
+        /// The address that failed to decode
+        address: String,
+        /// Why it failed to decode
+        reason: String,
+    },
+    /// The address is longer than 32 bytes
+    #[error("Address `{0}` is longer than 32 bytes")]
+    TooLong(String),
+    /// The address has more bytes than the native format holds
+    #[error("Address {address:?} doesn't fit in {len} bytes")]
+    DoesNotFit {
+        /// The address that failed to encode
+        address: H256,
+        /// The length of the native format
+        len: usize,
+    },
+    /// The address is beyond the Starknet field prime
+    #[error("Address {0:?} isn't a Starknet field element")]
+    NotAFelt(H256),
+    /// The SS58 network prefix can't be encoded
+    #[error("SS58 prefix {0} is reserved")]
+    ReservedSs58Prefix(u16),
+}
+
+/// The chain-native representations of addresses, which are converted to and
+/// from the H256 representation used by Hyperlane. Native addresses shorter
+/// than 32 bytes are left padded, matching how they are represented on chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressCodec {
+    /// 0x-prefixed hex, e.g. Aptos and Sui addresses, whose leading zeros
+    /// may be omitted when decoding
+    Hex,
+    /// 0x-prefixed hex of a Starknet field element, without leading zeros
+    HexFelt,
+    /// Base58 of the 32 bytes, e.g. Sealevel program ids
+    Base58,
+    /// Bech32 of the last `len` bytes, e.g. 20 byte accounts and 32 byte
+    /// contracts on Cosmos. Any prefix and length is accepted when decoding.
+    Bech32 {
+        /// The human readable prefix of the chain
+        prefix: String,
+        /// The number of bytes of the addresses
+        len: usize,
+    },
+    /// SS58, e.g. Substrate accounts. Any prefix is accepted when decoding.
+    Ss58 {
+        /// The network prefix of the chain
+        prefix: u16,
+    },
+}
+
+impl AddressCodec {
+    /// The name of the format, used in errors
+    fn format(&self) -> &'static str {
+        match self {
+            Self::Hex => "hex",
+            Self::HexFelt => "Starknet",
+            Self::Base58 => "base58",
+            Self::Bech32 { .. } => "bech32",
+            Self::Ss58 { .. } => "SS58",
+        }
+    }
+
+    /// Encodes `address` in the native format
+    pub fn encode(&self, address: H256) -> Result<String, AddressCodecError> {
+        match self {
+            Self::Hex => Ok(format!("{address:?}")),
+            Self::HexFelt => {
+                if address >= STARKNET_FIELD_PRIME {
+                    return Err(AddressCodecError::NotAFelt(address));
+                }
+                let digits = hex::encode(address);
+                let digits = digits.trim_start_matches('0');
+                Ok(format!(
+                    "0x{}",
+                    if digits.is_empty() { "0" } else { digits }
+                ))
+            }
+            Self::Base58 => Ok(bs58::encode(address).into_string()),
+            Self::Bech32 { prefix, len } => {
+                let bytes = truncate(&address, *len)?;
+                bech32::encode(prefix, bytes.to_base32(), Variant::Bech32).map_err(|e| {
+                    AddressCodecError::InvalidEncoding {
+                        format: self.format(),
+                        address: format!("{address:?}"),
+                        reason: e.to_string(),
+                    }
+                })
+            }
+            Self::Ss58 { prefix } => {
+                let mut bytes = ss58_prefix_bytes(*prefix)?;
+                bytes.extend_from_slice(address.as_bytes());
+                let checksum = ss58_checksum(&bytes);
+                bytes.extend_from_slice(&checksum);
+                Ok(bs58::encode(bytes).into_string())
+            }
+        }
+    }
+
+    /// Decodes `address` from the native format
+    pub fn decode(&self, address: &str) -> Result<H256, AddressCodecError> {
+        let invalid = |reason: &dyn std::fmt::Display| AddressCodecError::InvalidEncoding {
+            format: self.format(),
+            address: address.to_owned(),
+            reason: reason.to_string(),
+        };
+        let bytes = match self {
+            Self::Hex | Self::HexFelt => {
+                let digits = address.strip_prefix("0x").unwrap_or(address);
+                let digits = if digits.len() % 2 == 1 {
+                    format!("0{digits}")
+                } else {
+                    digits.to_owned()
+                };
+                hex::decode(digits).map_err(|e| invalid(&e))?
+            }
+            Self::Base58 => bs58::decode(address).into_vec().map_err(|e| invalid(&e))?,
+            Self::Bech32 { .. } => {
+                let (_, data, _) = bech32::decode(address).map_err(|e| invalid(&e))?;
+                Vec::<u8>::from_base32(&data).map_err(|e| invalid(&e))?
+            }
+            Self::Ss58 { .. } => {
+                let bytes = bs58::decode(address).into_vec().map_err(|e| invalid(&e))?;
+                // Prefixes above 63 are encoded in two bytes, flagged by the first one
+                let prefix_len = match bytes.first() {
+                    Some(0..=63) => 1,
+                    Some(64..=127) => 2,
+                    _ => return Err(invalid(&"reserved prefix")),
+                };
+                if bytes.len() != prefix_len + H256::len_bytes() + SS58_CHECKSUM_LEN {
+                    return Err(invalid(&format!("unexpected length {}", bytes.len())));
+                }
+                let (payload, checksum) = bytes.split_at(bytes.len() - SS58_CHECKSUM_LEN);
+                if checksum != ss58_checksum(payload) {
+                    return Err(invalid(&"checksum mismatch"));
+                }
+                payload[prefix_len..].to_vec()
+            }
+        };
+        if bytes.len() > H256::len_bytes() {
+            return Err(AddressCodecError::TooLong(address.to_owned()));
+        }
+        let mut padded = H256::zero();
+        padded[H256::len_bytes() - bytes.len()..].copy_from_slice(&bytes);
+        if *self == Self::HexFelt && padded >= STARKNET_FIELD_PRIME {
+            return Err(AddressCodecError::NotAFelt(padded));
+        }
+        Ok(padded)
+    }
+}
+
+/// The last `len` bytes of `address`, which must all be preceded by zeros
+fn truncate(address: &H256, len: usize) -> Result<&[u8], AddressCodecError> {
+    let start = H256::len_bytes()
+        .checked_sub(len)
+        .ok_or(AddressCodecError::DoesNotFit {
+            address: *address,
+            len,
+        })?;
+    let (padding, bytes) = address.as_bytes().split_at(start);
+    if padding.iter().any(|b| *b != 0) {
+        return Err(AddressCodecError::DoesNotFit {
+            address: *address,
+            len,
+        });
+    }
+    Ok(bytes)
+}
+
+/// Encodes an SS58 network prefix in one or two bytes
+fn ss58_prefix_bytes(prefix: u16) -> Result<Vec<u8>, AddressCodecError> {
+    match prefix {
+        0..=63 => Ok(vec![prefix as u8]),
+        64..=16_383 => {
+            let first = ((prefix & 0b0000_0000_1111_1100) as u8) >> 2;
+            let second = ((prefix >> 8) as u8) | (((prefix & 0b0000_0000_0000_0011) as u8) << 6);
+            Ok(vec![first | 0b0100_0000, second])
+        }
+        _ => Err(AddressCodecError::ReservedSs58Prefix(prefix)),
+    }
+}
+
+/// The checksum of an SS58 payload, the first bytes of its Blake2b-512 hash
+fn ss58_checksum(payload: &[u8]) -> [u8; SS58_CHECKSUM_LEN] {
+    let hash = Blake2b512::new()
+        .chain_update(SS58_CHECKSUM_PREFIX)
+        .chain_update(payload)
+        .finalize();
+    let mut checksum = [0; SS58_CHECKSUM_LEN];
+    checksum.copy_from_slice(&hash[..SS58_CHECKSUM_LEN]);
+    checksum
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip(codec: AddressCodec, address: H256) -> String {
+        let encoded = codec.encode(address).unwrap();
+        assert_eq!(codec.decode(&encoded).unwrap(), address);
+        encoded
+    }
+
+    #[test]
+    fn test_hex() {
+        let address = H256::repeat_byte(0x42);
+        round_trip(AddressCodec::Hex, address);
+        assert_eq!(
+            AddressCodec::Hex.decode("0x1").unwrap(),
+            H256::from_low_u64_be(1)
+        );
+        assert!(AddressCodec::Hex.decode("0xnothex").is_err());
+    }
+
+    #[test]
+    fn test_hex_felt() {
+        let address = H256::from_low_u64_be(0x49d36);
+        assert_eq!(round_trip(AddressCodec::HexFelt, address), "0x49d36");
+        assert_eq!(round_trip(AddressCodec::HexFelt, H256::zero()), "0x0");
+        assert!(matches!(
+            AddressCodec::HexFelt.encode(STARKNET_FIELD_PRIME),
+            Err(AddressCodecError::NotAFelt(_))
+        ));
+        assert!(matches!(
+            AddressCodec::HexFelt.decode(&format!("{:?}", H256::repeat_byte(0xff))),
+            Err(AddressCodecError::NotAFelt(_))
+        ));
+    }
+
+    #[test]
+    fn test_base58() {
+        round_trip(AddressCodec::Base58, H256::repeat_byte(0x42));
+        assert!(matches!(
+            AddressCodec::Base58.decode(&bs58::encode([0x42; 33]).into_string()),
+            Err(AddressCodecError::TooLong(_))
+        ));
+    }
+
+    #[test]
+    fn test_bech32() {
+        let contract: H256 = "0x1b16866227825a5166eb44031cdcf6568b3e80b52f2806e01b89a34dc90ae616"
+            .parse()
+            .unwrap();
+        let codec = AddressCodec::Bech32 {
+            prefix: "dual".to_owned(),
+            len: 32,
+        };
+        assert_eq!(
+            round_trip(codec, contract),
+            "dual1rvtgvc38sfd9zehtgsp3eh8k269naq949u5qdcqm3x35mjg2uctqfdn3yq"
+        );
+
+        let codec = AddressCodec::Bech32 {
+            prefix: "neutron".to_owned(),
+            len: 20,
+        };
+        round_trip(codec.clone(), H256::from_low_u64_be(42));
+        assert!(matches!(
+            codec.encode(contract),
+            Err(AddressCodecError::DoesNotFit { len: 20, .. })
+        ));
+        assert!(codec.decode("neutron1invalid").is_err());
+    }
+
+    #[test]
+    fn test_ss58() {
+        let alice: H256 = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            round_trip(AddressCodec::Ss58 { prefix: 42 }, alice),
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+        );
+        // Prefixes above 63 take two bytes
+        round_trip(AddressCodec::Ss58 { prefix: 1_000 }, alice);
+        assert!(matches!(
+            AddressCodec::Ss58 { prefix: 16_384 }.encode(alice),
+            Err(AddressCodecError::ReservedSs58Prefix(16_384))
+        ));
+        // A different last character breaks the checksum
+        assert!(AddressCodec::Ss58 { prefix: 42 }
+            .decode("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ")
+            .is_err());
+    }
+}
@@ -1,10 +1,14 @@
 use async_trait::async_trait;
+use borsh::BorshSerialize;
 use serde::{Deserialize, Serialize};
 use sha3::{digest::Update, Digest, Keccak256};
 use std::fmt::{Debug, Formatter};
 
 use crate::utils::{fmt_address_for_domain, fmt_domain};
-use crate::{utils::announcement_domain_hash, Signable, SignedType, H160, H256};
+use crate::{
+    utils::announcement_domain_hash, ChainCommunicationError, ChainResult, HyperlaneDomainProtocol,
+    Signable, SignedType, H160, H256,
+};
 
 /// An Hyperlane checkpoint
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -50,3 +54,118 @@ impl Signable for Announcement {
 
 /// An announcement that has been signed.
 pub type SignedAnnouncement = SignedType<Announcement>;
+
+/// Discriminator of the `Announce` variant of the Sealevel validator announce
+/// program's instruction enum.
+const SEALEVEL_ANNOUNCE_INSTRUCTION_DISCRIMINATOR: u8 = 1;
+
+/// Mirrors `AnnounceInstruction` of the Sealevel validator announce program.
+#[derive(BorshSerialize)]
+struct SealevelAnnounceInstruction {
+    validator: H160,
+    storage_location: String,
+    signature: Vec<u8>,
+}
+
+impl SignedAnnouncement {
+    /// The payload that submits this announcement to the validator announce
+    /// contract of a chain using `protocol`:
+    /// - Ethereum: calldata for `announce(address,string,bytes)`
+    /// - Cosmos: the JSON `announce` execute message of the CosmWasm contract
+    /// - Sealevel: the borsh-encoded `Announce` instruction data
+    ///
+    /// The signed digest is the same for every protocol, only the encoding of
+    /// the mailbox address in the announcement and the submitted payload differ.
+    pub fn announce_payload(&self, protocol: HyperlaneDomainProtocol) -> ChainResult<Vec<u8>> {
+        let signature = self.signature.to_vec();
+        match protocol {
+            #[cfg(feature = "ethers")]
+            HyperlaneDomainProtocol::Ethereum => {
+                use ethers_core::abi::{encode, Token};
+
+                let mut calldata =
+                    ethers_core::utils::id("announce(address,string,bytes)").to_vec();
+                calldata.extend(encode(&[
+                    Token::Address(self.value.validator.into()),
+                    Token::String(self.value.storage_location.clone()),
+                    Token::Bytes(signature),
+                ]));
+                Ok(calldata)
+            }
+            HyperlaneDomainProtocol::Cosmos => Ok(serde_json::to_vec(&serde_json::json!({
+                "announce": {
+                    "validator": hex::encode(self.value.validator),
+                    "storage_location": self.value.storage_location,
+                    "signature": hex::encode(signature),
+                }
+            }))?),
+            HyperlaneDomainProtocol::Sealevel => {
+                let mut data = vec![SEALEVEL_ANNOUNCE_INSTRUCTION_DISCRIMINATOR];
+                SealevelAnnounceInstruction {
+                    validator: self.value.validator,
+                    storage_location: self.value.storage_location.clone(),
+                    signature,
+                }
+                .serialize(&mut data)
+                .map_err(ChainCommunicationError::from_other)?;
+                Ok(data)
+            }
+            _ => Err(ChainCommunicationError::CustomError(format!(
+                "Announcement payloads are not supported for {protocol:?}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Signature;
+
+    use super::*;
+
+    fn signed_announcement() -> SignedAnnouncement {
+        SignedAnnouncement {
+            value: Announcement {
+                validator: H160::repeat_byte(0xab),
+                mailbox_address: H256::repeat_byte(0x01),
+                mailbox_domain: 1399811149,
+                storage_location: "s3://bucket/region".to_owned(),
+            },
+            signature: Signature {
+                r: 1.into(),
+                s: 2.into(),
+                v: 27,
+            },
+        }
+    }
+
+    #[test]
+    fn cosmos_announce_payload() {
+        let payload = signed_announcement()
+            .announce_payload(HyperlaneDomainProtocol::Cosmos)
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(
+            json["announce"]["validator"],
+            "abababababababababababababababababababab"
+        );
+        assert_eq!(json["announce"]["storage_location"], "s3://bucket/region");
+        assert_eq!(json["announce"]["signature"].as_str().unwrap().len(), 130);
+    }
+
+    #[test]
+    fn sealevel_announce_payload() {
+        let payload = signed_announcement()
+            .announce_payload(HyperlaneDomainProtocol::Sealevel)
+            .unwrap();
+        let storage_location = b"s3://bucket/region";
+
+        let mut expected = vec![1u8];
+        expected.extend([0xab; 20]);
+        expected.extend((storage_location.len() as u32).to_le_bytes());
+        expected.extend(storage_location);
+        expected.extend(65u32.to_le_bytes());
+        expected.extend(signed_announcement().signature.to_vec());
+        assert_eq!(payload, expected);
+    }
+}
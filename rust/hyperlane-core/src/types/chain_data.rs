@@ -1,6 +1,6 @@
 use derive_new::new;
 
-use crate::{H256, U256};
+use crate::{metrics::agent::decimals_by_protocol, HyperlaneDomainProtocol, H256, U256};
 
 /// Info about a given block in the chain.
 #[derive(Debug, Clone, Default)]
@@ -23,6 +23,43 @@ pub struct ChainInfo {
     pub min_gas_price: Option<U256>,
 }
 
+/// The denomination of the native token of a chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeDenom {
+    /// The name of the lowest denomination, e.g. `untrn`, on chains that
+    /// name it
+    pub denom: Option<String>,
+    /// The number of decimals of the lowest denomination
+    pub decimals: u8,
+}
+
+impl NativeDenom {
+    /// The denomination most chains of `protocol` use
+    pub fn for_protocol(protocol: HyperlaneDomainProtocol) -> Self {
+        Self {
+            denom: None,
+            decimals: decimals_by_protocol(protocol),
+        }
+    }
+}
+
+/// A balance of the native token of a chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Balance {
+    /// The balance, in the lowest denomination
+    pub amount: U256,
+    /// The denomination of the native token
+    pub denom: NativeDenom,
+}
+
+impl Balance {
+    /// The balance in whole tokens
+    #[cfg(feature = "float")]
+    pub fn as_f64(&self) -> f64 {
+        self.amount.to_f64_lossy() / 10f64.powi(self.denom.decimals.into())
+    }
+}
+
 /// Information about a given transaction in the chain.
 #[derive(Debug, Clone)]
 pub struct TxnInfo {
@@ -46,6 +83,8 @@ pub struct TxnInfo {
     pub sender: H256,
     /// Address of the receiver or contract that was interacted with
     pub recipient: Option<H256>,
+    /// Input data of the transaction, if the chain exposes it.
+    pub raw_input_data: Option<Vec<u8>>,
     /// If the txn has been processed, we can also report some additional
     /// information.
     pub receipt: Option<TxnReceiptInfo>,
@@ -64,3 +103,51 @@ pub struct TxnReceiptInfo {
     /// only be determined post-execution
     pub effective_gas_price: Option<U256>,
 }
+
+/// Signals about how saturated a chain's mempool is for the transactions we
+/// submit. Signals a chain cannot provide are `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MempoolCongestion {
+    /// Number of transactions sent from our account that are not yet included
+    /// in a block
+    pub pending_txs: Option<u64>,
+    /// Average relative change of the base fee per block over recent blocks,
+    /// e.g. `0.125` if it rose by 12.5% per block
+    pub base_fee_slope: Option<f64>,
+    /// Number of our transactions that expired before being included since
+    /// the last time congestion was checked, e.g. because their recent
+    /// blockhash expired on Solana
+    pub expired_txs: Option<u64>,
+}
+
+impl MempoolCongestion {
+    /// Average relative change per block of the given base fees, which are
+    /// ordered from oldest to newest.
+    pub fn base_fee_slope(base_fees: &[U256]) -> Option<f64> {
+        let (first, last) = (base_fees.first()?, base_fees.last()?);
+        let first = u128::try_from(*first).ok()? as f64;
+        let last = u128::try_from(*last).ok()? as f64;
+        if base_fees.len() < 2 || first == 0. {
+            return None;
+        }
+        Some((last / first).powf(1. / (base_fees.len() - 1) as f64) - 1.)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_base_fee_slope() {
+        let fees = |fees: &[u64]| fees.iter().map(|f| U256::from(*f)).collect::<Vec<_>>();
+
+        let slope = MempoolCongestion::base_fee_slope(&fees(&[64, 72, 81])).unwrap();
+        assert!((slope - 0.125).abs() < 1e-9);
+        let slope = MempoolCongestion::base_fee_slope(&fees(&[100, 100, 100])).unwrap();
+        assert_eq!(slope, 0.);
+        assert!(MempoolCongestion::base_fee_slope(&fees(&[100, 50])).unwrap() < 0.);
+        assert_eq!(MempoolCongestion::base_fee_slope(&fees(&[100])), None);
+        assert_eq!(MempoolCongestion::base_fee_slope(&fees(&[0, 100])), None);
+    }
+}
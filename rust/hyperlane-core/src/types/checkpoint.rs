@@ -9,7 +9,9 @@ use crate::{utils::domain_hash, Signable, Signature, SignedType, H256};
 /// An Hyperlane checkpoint
 #[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 pub struct Checkpoint {
-    /// The merkle tree hook address
+    /// The merkle tree hook address. Checkpoints of validators predating
+    /// merkle tree hooks name it after the mailbox.
+    #[serde(alias = "mailbox_address")]
     pub merkle_tree_hook_address: H256,
     /// The mailbox / merkle tree hook domain
     pub mailbox_domain: u32,
@@ -98,3 +100,38 @@ impl TryFrom<&mut Vec<SignedCheckpointWithMessageId>> for MultisigSignedCheckpoi
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_legacy_checkpoint_with_message_id() {
+        let legacy = r#"{
+            "checkpoint": {
+                "mailbox_address": "0x000000000000000000000000c005dc82818d67af737725bd4bf75435d065d239",
+                "mailbox_domain": 1,
+                "root": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                "index": 42
+            },
+            "message_id": "0x0000000000000000000000000000000000000000000000000000000000000002"
+        }"#;
+        let checkpoint: CheckpointWithMessageId = serde_json::from_str(legacy).unwrap();
+        assert_eq!(
+            checkpoint.merkle_tree_hook_address,
+            "0x000000000000000000000000c005dc82818d67af737725bd4bf75435d065d239"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(checkpoint.index, 42);
+        assert_eq!(checkpoint.message_id, H256::from_low_u64_be(2));
+
+        // Checkpoints are written with the current field names
+        let serialized = serde_json::to_string(&checkpoint).unwrap();
+        assert!(serialized.contains("merkle_tree_hook_address"));
+        assert_eq!(
+            serde_json::from_str::<CheckpointWithMessageId>(&serialized).unwrap(),
+            checkpoint
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use sha3::{digest::Update, Digest, Keccak256};
+
+use crate::{Decode, Encode, HyperlaneProtocolError, Signable, SignedType, H160, H256, H512};
+
+/// Prefix of the signed hash, so that a receipt signature can't be replayed
+/// as a signature over some other Hyperlane type.
+const DELIVERY_RECEIPT_DOMAIN: &[u8] = b"HYPERLANE_DELIVERY_RECEIPT";
+
+/// A receipt attesting that a relayer delivered a message
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryReceipt {
+    /// The id of the delivered message
+    pub message_id: H256,
+    /// The domain the message was delivered to
+    pub destination_domain: u32,
+    /// The hash of the transaction that delivered the message
+    pub transaction_id: H512,
+    /// When the delivery was confirmed, in seconds since the unix epoch
+    pub timestamp: u64,
+    /// The address the relayer signs receipts with
+    pub relayer: H160,
+}
+
+impl Signable for DeliveryReceipt {
+    fn signing_hash(&self) -> H256 {
+        // sign:
+        // domain || message_id || destination_domain || transaction_id || timestamp || relayer
+        H256::from_slice(
+            Keccak256::new()
+                .chain(DELIVERY_RECEIPT_DOMAIN)
+                .chain(self.message_id)
+                .chain(self.destination_domain.to_be_bytes())
+                .chain(self.transaction_id)
+                .chain(self.timestamp.to_be_bytes())
+                .chain(self.relayer)
+                .finalize()
+                .as_slice(),
+        )
+    }
+}
+
+/// A delivery receipt signed by the relayer
+pub type SignedDeliveryReceipt = SignedType<DeliveryReceipt>;
+
+impl Encode for DeliveryReceipt {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        let mut written = 0;
+        written += self.message_id.write_to(writer)?;
+        written += self.destination_domain.write_to(writer)?;
+        written += self.transaction_id.write_to(writer)?;
+        written += self.timestamp.write_to(writer)?;
+        written += self.relayer.write_to(writer)?;
+        Ok(written)
+    }
+}
+
+impl Decode for DeliveryReceipt {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+        Self: Sized,
+    {
+        Ok(Self {
+            message_id: H256::read_from(reader)?,
+            destination_domain: u32::read_from(reader)?,
+            transaction_id: H512::read_from(reader)?,
+            timestamp: u64::read_from(reader)?,
+            relayer: H160::read_from(reader)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn receipt() -> DeliveryReceipt {
+        DeliveryReceipt {
+            message_id: H256::repeat_byte(1),
+            destination_domain: 42,
+            transaction_id: H512::repeat_byte(2),
+            timestamp: 1_700_000_000,
+            relayer: H160::repeat_byte(3),
+        }
+    }
+
+    #[test]
+    fn test_encoding_roundtrip() {
+        let receipt = receipt();
+        let encoded = receipt.to_vec();
+        assert_eq!(encoded.len(), 32 + 4 + 64 + 8 + 20);
+        assert_eq!(
+            DeliveryReceipt::read_from(&mut encoded.as_slice()).unwrap(),
+            receipt
+        );
+    }
+
+    #[test]
+    fn test_signing_hash_commits_to_every_field() {
+        let receipt = receipt();
+        let hash = receipt.signing_hash();
+        for changed in [
+            DeliveryReceipt {
+                message_id: H256::zero(),
+                ..receipt
+            },
+            DeliveryReceipt {
+                destination_domain: 43,
+                ..receipt
+            },
+            DeliveryReceipt {
+                transaction_id: H512::zero(),
+                ..receipt
+            },
+            DeliveryReceipt {
+                timestamp: 0,
+                ..receipt
+            },
+            DeliveryReceipt {
+                relayer: H160::zero(),
+                ..receipt
+            },
+        ] {
+            assert_ne!(changed.signing_hash(), hash);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{H256, H512};
+
+/// Where and by whom a message was delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Delivery {
+    /// The number of the block the message was delivered in
+    pub block_number: u64,
+    /// The address of the account that delivered the message
+    pub processor: H256,
+    /// The transaction that delivered the message, if it could be found
+    pub transaction_id: Option<H512>,
+}
+
+/// The delivery status of a message on its destination chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// The message was delivered, along with the details of the delivery if
+    /// the chain provides them
+    Delivered(Option<Delivery>),
+    /// The message wasn't delivered yet, but can be
+    Pending,
+    /// The message can never be delivered
+    Undeliverable {
+        /// Why the message can't be delivered, e.g. the decoded reason of a
+        /// revert
+        reason: String,
+    },
+}
+
+impl DeliveryStatus {
+    /// Whether the message was delivered
+    pub fn is_delivered(&self) -> bool {
+        matches!(self, Self::Delivered(_))
+    }
+
+    /// Whether the status can no longer change
+    pub fn is_final(&self) -> bool {
+        !matches!(self, Self::Pending)
+    }
+}
+
+/// The result of simulating the processing of a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessSimulation {
+    /// The chain can't simulate processing messages
+    Unsupported,
+    /// Processing the message would succeed
+    Succeeded,
+    /// Processing the message would revert
+    Reverted(ProcessRevert),
+}
+
+/// Why simulating the processing of a message reverted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessRevert {
+    /// The message was delivered in the meantime
+    AlreadyDelivered,
+    /// The ISM of the recipient rejected the metadata
+    IsmVerificationFailed {
+        /// The decoded reason of the revert, if any
+        reason: String,
+    },
+    /// The recipient reverted while handling the message
+    RecipientReverted {
+        /// The decoded reason of the revert, if any
+        reason: String,
+    },
+}
@@ -2,7 +2,7 @@ use sha3::{digest::Update, Digest, Keccak256};
 use std::fmt::{Debug, Display, Formatter};
 
 use crate::utils::{fmt_address_for_domain, fmt_domain};
-use crate::{Decode, Encode, HyperlaneProtocolError, H256};
+use crate::{Decode, Encode, HyperlaneProtocolError, H256, LATEST_MESSAGE_VERSION};
 
 const HYPERLANE_MESSAGE_PREFIX_LEN: usize = 77;
 
@@ -42,8 +42,7 @@ pub struct HyperlaneMessage {
 impl Default for HyperlaneMessage {
     fn default() -> Self {
         Self {
-            // Use version 3 now that Hyperlane V3 is the default
-            version: 3,
+            version: LATEST_MESSAGE_VERSION,
             nonce: 0,
             origin: 0,
             sender: H256::zero(),
@@ -54,6 +53,21 @@ impl Default for HyperlaneMessage {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for HyperlaneMessage {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            version: u.arbitrary()?,
+            nonce: u.arbitrary()?,
+            origin: u.arbitrary()?,
+            sender: H256(u.arbitrary()?),
+            destination: u.arbitrary()?,
+            recipient: H256(u.arbitrary()?),
+            body: u.arbitrary()?,
+        })
+    }
+}
+
 impl Debug for HyperlaneMessage {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -169,3 +183,42 @@ impl HyperlaneMessage {
         H256::from_slice(Keccak256::new().chain(self.to_vec()).finalize().as_slice())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn test_message() -> HyperlaneMessage {
+        HyperlaneMessage {
+            version: 3,
+            nonce: 42,
+            origin: 1,
+            sender: H256::repeat_byte(0xaa),
+            destination: 2,
+            recipient: H256::repeat_byte(0xbb),
+            body: vec![1, 2, 3, 4],
+        }
+    }
+
+    #[test]
+    fn encode_decode_roundtrip() {
+        let message = test_message();
+        let encoded = message.to_vec();
+        assert_eq!(
+            encoded.len(),
+            HYPERLANE_MESSAGE_PREFIX_LEN + message.body.len()
+        );
+
+        let decoded = HyperlaneMessage::read_from(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, message);
+        assert_eq!(HyperlaneMessage::from(&encoded), message);
+    }
+
+    #[test]
+    fn decode_truncated_message_errors() {
+        let encoded = test_message().to_vec();
+        for len in 0..HYPERLANE_MESSAGE_PREFIX_LEN {
+            assert!(HyperlaneMessage::read_from(&mut &encoded[..len]).is_err());
+        }
+    }
+}
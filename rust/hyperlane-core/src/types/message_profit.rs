@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Decode, Encode, HyperlaneProtocolError, H256, U256};
+
+/// The scale of the token exchange rates of gas oracles, i.e. a rate of
+/// 1e10 is one origin token per destination token
+pub const TOKEN_EXCHANGE_RATE_SCALE: u64 = 10_000_000_000;
+
+/// How the gas payment for a processed message compares with what the
+/// relayer spent delivering it
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MessageProfit {
+    /// The id of the message
+    pub message_id: H256,
+    /// The nonce of the message
+    pub nonce: u32,
+    /// The domain the message was sent from
+    pub origin_domain: u32,
+    /// The domain the message was delivered to
+    pub destination_domain: u32,
+    /// The gas payment received on the origin, in origin native tokens
+    pub payment: U256,
+    /// The gas spent on the destination
+    pub gas_used: U256,
+    /// The destination native tokens spent
+    pub tokens_used: U256,
+    /// The exchange rate of destination native tokens quoted in origin ones
+    /// by the gas oracle, scaled by `TOKEN_EXCHANGE_RATE_SCALE`. Zero if it
+    /// couldn't be fetched.
+    pub token_exchange_rate: U256,
+    /// When the delivery was confirmed, in seconds since the unix epoch
+    pub timestamp: u64,
+}
+
+impl MessageProfit {
+    /// The destination tokens spent in origin native tokens, if the exchange
+    /// rate is known
+    pub fn cost(&self) -> Option<U256> {
+        if self.token_exchange_rate.is_zero() {
+            return None;
+        }
+        Some(
+            self.tokens_used.saturating_mul(self.token_exchange_rate)
+                / U256::from(TOKEN_EXCHANGE_RATE_SCALE),
+        )
+    }
+}
+
+impl Encode for MessageProfit {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        let mut written = 0;
+        written += self.message_id.write_to(writer)?;
+        written += self.nonce.write_to(writer)?;
+        written += self.origin_domain.write_to(writer)?;
+        written += self.destination_domain.write_to(writer)?;
+        written += self.payment.write_to(writer)?;
+        written += self.gas_used.write_to(writer)?;
+        written += self.tokens_used.write_to(writer)?;
+        written += self.token_exchange_rate.write_to(writer)?;
+        written += self.timestamp.write_to(writer)?;
+        Ok(written)
+    }
+}
+
+impl Decode for MessageProfit {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+        Self: Sized,
+    {
+        Ok(Self {
+            message_id: H256::read_from(reader)?,
+            nonce: u32::read_from(reader)?,
+            origin_domain: u32::read_from(reader)?,
+            destination_domain: u32::read_from(reader)?,
+            payment: U256::read_from(reader)?,
+            gas_used: U256::read_from(reader)?,
+            tokens_used: U256::read_from(reader)?,
+            token_exchange_rate: U256::read_from(reader)?,
+            timestamp: u64::read_from(reader)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encoding_roundtrip() {
+        let profit = MessageProfit {
+            message_id: H256::repeat_byte(1),
+            nonce: 7,
+            origin_domain: 1,
+            destination_domain: 42,
+            payment: 1_000.into(),
+            gas_used: 200.into(),
+            tokens_used: 400.into(),
+            token_exchange_rate: 2.into(),
+            timestamp: 1_700_000_000,
+        };
+        let encoded = profit.to_vec();
+        assert_eq!(
+            MessageProfit::read_from(&mut encoded.as_slice()).unwrap(),
+            profit
+        );
+    }
+
+    #[test]
+    fn test_cost() {
+        let profit = MessageProfit {
+            tokens_used: 400.into(),
+            ..Default::default()
+        };
+        assert_eq!(profit.cost(), None);
+        // Destination tokens worth half an origin token each
+        let profit = MessageProfit {
+            token_exchange_rate: (TOKEN_EXCHANGE_RATE_SCALE / 2).into(),
+            ..profit
+        };
+        assert_eq!(profit.cost(), Some(200.into()));
+    }
+}
@@ -6,26 +6,38 @@ use std::ops::Add;
 pub use self::primitive_types::*;
 #[cfg(feature = "ethers")]
 pub use ::primitive_types as ethers_core_types;
+pub use address_codec::*;
 pub use announcement::*;
 pub use chain_data::*;
 pub use checkpoint::*;
+pub use delivery_receipt::*;
+pub use delivery_status::*;
 pub use indexing::*;
 pub use log_metadata::*;
 pub use merkle_tree::*;
 pub use message::*;
+pub use message_profit::*;
+pub use pending_message_state::*;
 pub use transaction::*;
+pub use versioned_message::*;
 
-use crate::{Decode, Encode, HyperlaneProtocolError};
+use crate::{ChainResult, Decode, Encode, HyperlaneProtocolError};
 
+mod address_codec;
 mod announcement;
 mod chain_data;
 mod checkpoint;
+mod delivery_receipt;
+mod delivery_status;
 mod indexing;
 mod log_metadata;
 mod merkle_tree;
 mod message;
+mod message_profit;
+mod pending_message_state;
 mod serialize;
 mod transaction;
+mod versioned_message;
 
 /// Unified 32-byte identifier with convenience tooling for handling
 /// 20-byte ids (e.g ethereum addresses)
@@ -87,6 +99,32 @@ impl From<Signature> for Vec<u8> {
     }
 }
 
+impl Encode for Signature {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        writer.write_all(&<[u8; 65]>::from(self))?;
+        Ok(65)
+    }
+}
+
+impl Decode for Signature {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        let mut buf = [0u8; 65];
+        reader.read_exact(&mut buf)?;
+        Ok(Self {
+            r: U256::from_big_endian(&buf[0..32]),
+            s: U256::from_big_endian(&buf[32..64]),
+            v: buf[64] as u64,
+        })
+    }
+}
+
 #[cfg(feature = "ethers")]
 impl From<ethers_core::types::Signature> for Signature {
     fn from(value: ethers_core::types::Signature) -> Self {
@@ -231,6 +269,62 @@ impl From<&LogMeta> for InterchainGasPaymentMeta {
     }
 }
 
+/// A resource consumed by transactions on chains whose fees aren't a single
+/// gas amount times a gas price.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxResource {
+    /// Solana compute units, priced by the priority fee
+    ComputeUnits,
+    /// Signatures, e.g. the base fee of Solana transactions
+    Signatures,
+    /// Sui computation units
+    Computation,
+    /// Sui storage of the objects written by the transaction
+    Storage,
+    /// Sui storage rebates for the objects deleted or rewritten by the
+    /// transaction, which are refunded
+    StorageRebate,
+    /// Tron energy, consumed by contract execution
+    Energy,
+    /// Tron bandwidth, consumed by the bytes of the transaction
+    Bandwidth,
+}
+
+impl TxResource {
+    /// Whether the resource is refunded rather than charged for
+    pub fn is_refund(&self) -> bool {
+        matches!(self, Self::StorageRebate)
+    }
+}
+
+/// The amount of a resource consumed by a transaction and its price
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResourceCost {
+    /// The resource
+    pub resource: TxResource,
+    /// The amount of the resource
+    pub amount: u64,
+    /// The price of a unit of the resource, in the smallest unit of the
+    /// native token
+    pub price: FixedPointNumber,
+}
+
+impl ResourceCost {
+    /// Create a new resource cost
+    pub fn new(resource: TxResource, amount: u64, price: impl Into<FixedPointNumber>) -> Self {
+        Self {
+            resource,
+            amount,
+            price: price.into(),
+        }
+    }
+
+    /// The cost of the resource, in the smallest unit of the native token
+    pub fn cost(&self) -> FixedPointNumber {
+        self.price.clone() * self.amount
+    }
+}
+
 /// A cost estimate for a transaction.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct TxCostEstimate {
@@ -245,11 +339,44 @@ pub struct TxCostEstimate {
     /// is used to cover L1 and L2 costs. For details:
     /// `<https://medium.com/offchainlabs/understanding-arbitrum-2-dimensional-fees-fd1d582596c9>`
     pub l2_gas_limit: Option<U256>,
+    /// The resources the transaction consumes, on chains with
+    /// multi-dimensional fees, e.g. Solana compute units and signatures, Sui
+    /// computation and storage or Tron energy and bandwidth. `gas_limit` is
+    /// then the limit the transaction is submitted with, which may be above
+    /// its actual cost. Empty if the cost is `gas_limit` times `gas_price`.
+    pub resources: Vec<ResourceCost>,
 }
 
 impl TxCostEstimate {
-    /// The gas limit to be used by gas enforcement policies.
-    pub fn enforceable_gas_limit(&self) -> U256 {
-        self.l2_gas_limit.unwrap_or(self.gas_limit)
+    /// The estimated cost of the transaction, in the smallest unit of the
+    /// native token
+    pub fn total_cost(&self) -> ChainResult<FixedPointNumber> {
+        if self.resources.is_empty() {
+            return Ok(self.gas_price.clone() * FixedPointNumber::try_from(self.gas_limit)?);
+        }
+        let (charged, refunded) = self.resources.iter().fold(
+            (FixedPointNumber::zero(), FixedPointNumber::zero()),
+            |(charged, refunded), resource| {
+                if resource.resource.is_refund() {
+                    (charged, refunded + resource.cost())
+                } else {
+                    (charged + resource.cost(), refunded)
+                }
+            },
+        );
+        Ok((charged - refunded).max(FixedPointNumber::zero()))
+    }
+
+    /// The gas limit to be used by gas enforcement policies. If the
+    /// transaction consumes several resources, this is their total cost
+    /// expressed in gas at `gas_price`, so that payments are compared to
+    /// what the transaction actually costs.
+    pub fn enforceable_gas_limit(&self) -> ChainResult<U256> {
+        if self.resources.is_empty() || self.gas_price == FixedPointNumber::zero() {
+            return Ok(self.l2_gas_limit.unwrap_or(self.gas_limit));
+        }
+        (self.total_cost()? / self.gas_price.clone())
+            .ceil_to_integer()
+            .try_into()
     }
 }
@@ -0,0 +1,104 @@
+use crate::{Decode, Encode, HyperlaneProtocolError, MessageSubmissionData, U256};
+
+/// The state of a message in the relayer's queues that is persisted, so that
+/// it's restored with its backoff schedule and prepared submission after a
+/// restart
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PendingMessageState {
+    /// How often the message was retried
+    pub num_retries: u32,
+    /// When the message is next attempted, in seconds since the unix epoch.
+    /// Zero if it can be attempted right away.
+    pub next_attempt_at: u64,
+    /// When the relayer first saw the message, in milliseconds since the
+    /// unix epoch
+    pub first_seen: u64,
+    /// Whether the message is known to be delivered, possibly by another
+    /// relayer, and awaits confirmation
+    pub submitted: bool,
+    /// The metadata and gas limit the message was last prepared with
+    pub submission_data: Option<MessageSubmissionData>,
+}
+
+impl Encode for PendingMessageState {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        let mut written = 0;
+        written += self.num_retries.write_to(writer)?;
+        written += self.next_attempt_at.write_to(writer)?;
+        written += self.first_seen.write_to(writer)?;
+        written += self.submitted.write_to(writer)?;
+        written += self.submission_data.is_some().write_to(writer)?;
+        if let Some(data) = &self.submission_data {
+            written += (data.metadata.len() as u32).write_to(writer)?;
+            writer.write_all(&data.metadata)?;
+            written += data.metadata.len();
+            written += data.gas_limit.write_to(writer)?;
+        }
+        Ok(written)
+    }
+}
+
+impl Decode for PendingMessageState {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+        Self: Sized,
+    {
+        let num_retries = u32::read_from(reader)?;
+        let next_attempt_at = u64::read_from(reader)?;
+        let first_seen = u64::read_from(reader)?;
+        let submitted = bool::read_from(reader)?;
+        let submission_data = if bool::read_from(reader)? {
+            let mut metadata = vec![0; u32::read_from(reader)? as usize];
+            reader.read_exact(&mut metadata)?;
+            Some(MessageSubmissionData {
+                metadata,
+                gas_limit: U256::read_from(reader)?,
+            })
+        } else {
+            None
+        };
+        Ok(Self {
+            num_retries,
+            next_attempt_at,
+            first_seen,
+            submitted,
+            submission_data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encoding_roundtrip() {
+        let mut state = PendingMessageState {
+            num_retries: 3,
+            next_attempt_at: 1_700_000_010,
+            first_seen: 1_700_000_000_000,
+            submitted: false,
+            submission_data: None,
+        };
+        let encoded = state.to_vec();
+        assert_eq!(
+            PendingMessageState::read_from(&mut encoded.as_slice()).unwrap(),
+            state
+        );
+
+        state.submitted = true;
+        state.submission_data = Some(MessageSubmissionData {
+            metadata: vec![1, 2, 3],
+            gas_limit: 100_000.into(),
+        });
+        let encoded = state.to_vec();
+        assert_eq!(
+            PendingMessageState::read_from(&mut encoded.as_slice()).unwrap(),
+            state
+        );
+    }
+}
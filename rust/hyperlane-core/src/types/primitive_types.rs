@@ -4,7 +4,7 @@
 #![allow(clippy::reversed_empty_ranges)]
 
 use std::{
-    ops::{Div, Mul},
+    ops::{Add, Div, Mul, Sub},
     str::FromStr,
 };
 
@@ -413,6 +413,30 @@ where
     }
 }
 
+impl<T> Add<T> for FixedPointNumber
+where
+    T: Into<FixedPointNumber>,
+{
+    type Output = FixedPointNumber;
+
+    fn add(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl<T> Sub<T> for FixedPointNumber
+where
+    T: Into<FixedPointNumber>,
+{
+    type Output = FixedPointNumber;
+
+    fn sub(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        Self(self.0 - rhs.0)
+    }
+}
+
 impl<T> Mul<T> for FixedPointNumber
 where
     T: Into<FixedPointNumber>,
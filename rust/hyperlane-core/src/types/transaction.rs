@@ -1,10 +1,10 @@
 use std::sync::Arc;
 
-use crate::{ChainResult, Mailbox, U256};
+use crate::{ChainResult, Mailbox, TxOutcome, U256};
 use derive_new::new;
 
 /// State for the next submission attempt generated by a prepare call.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MessageSubmissionData {
     /// Transaction metadata - currently only applies to Messages, so this field can be made optional or generic if other
     /// operations are submitted in the future.
@@ -33,3 +33,65 @@ pub trait TryBatchAs<T> {
         Err(crate::ChainCommunicationError::BatchingFailed)
     }
 }
+
+/// The outcome of an item of a batch
+#[derive(Clone, Debug)]
+pub struct BatchItemOutcome {
+    /// Whether the item was executed. Items can revert without reverting the
+    /// rest of the batch.
+    pub executed: bool,
+    /// The share of the gas used by the batch attributed to the item
+    pub gas_used: U256,
+}
+
+/// The outcome of a batch submitted in a single transaction
+#[derive(Clone, Debug)]
+pub struct BatchResult {
+    /// The outcome of the transaction
+    pub outcome: TxOutcome,
+    /// The outcomes of the items, in the order they were batched
+    pub items: Vec<BatchItemOutcome>,
+}
+
+impl BatchResult {
+    /// Attribute the gas used by `outcome` to `items` in proportion to their
+    /// gas limits, or evenly if they have none. `executed` tells whether
+    /// each item was executed.
+    pub fn new<T>(
+        outcome: TxOutcome,
+        items: &[BatchItem<T>],
+        executed: impl IntoIterator<Item = bool>,
+    ) -> Self {
+        let total_gas_limit = items.iter().fold(U256::zero(), |acc, item| {
+            acc.saturating_add(item.submission_data.gas_limit)
+        });
+        let outcomes = items
+            .iter()
+            .zip(executed)
+            .map(|(item, executed)| {
+                let gas_used = if total_gas_limit.is_zero() {
+                    outcome.gas_used / items.len()
+                } else {
+                    outcome
+                        .gas_used
+                        .saturating_mul(item.submission_data.gas_limit)
+                        / total_gas_limit
+                };
+                BatchItemOutcome { executed, gas_used }
+            })
+            .collect();
+        Self {
+            outcome,
+            items: outcomes,
+        }
+    }
+
+    /// The outcome of the item at `index`, as if it was submitted on its own
+    pub fn item_outcome(&self, index: usize) -> Option<TxOutcome> {
+        self.items.get(index).map(|item| TxOutcome {
+            executed: self.outcome.executed && item.executed,
+            gas_used: item.gas_used,
+            ..self.outcome.clone()
+        })
+    }
+}
@@ -0,0 +1,171 @@
+use sha3::{digest::Update, Digest, Keccak256};
+
+use crate::{Decode, Encode, HyperlaneMessage, HyperlaneProtocolError, H256};
+
+/// The version of the messages sent by the current Mailbox contracts
+pub const LATEST_MESSAGE_VERSION: u8 = 3;
+
+/// The layouts messages of the known versions are encoded with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageLayout {
+    /// version (1), nonce (4), origin (4), sender (32), destination (4),
+    /// recipient (32) and body (0+), which all versions up to
+    /// [LATEST_MESSAGE_VERSION] use
+    V3,
+}
+
+impl MessageLayout {
+    /// The layout of the messages of `version`, if it is known
+    pub fn of_version(version: u8) -> Option<Self> {
+        match version {
+            0..=LATEST_MESSAGE_VERSION => Some(Self::V3),
+            _ => None,
+        }
+    }
+}
+
+/// A message of a version unknown to this agent, kept as encoded so that it
+/// can still be relayed as is
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnknownMessage {
+    /// The version of the message, its first byte
+    pub version: u8,
+    /// The whole encoded message, including the version
+    pub bytes: Vec<u8>,
+}
+
+/// A message of any version. Messages of known versions are parsed, while
+/// the ones of newer versions are passed through, so that protocol upgrades
+/// don't require upgrading agents in lockstep.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VersionedMessage {
+    /// A message whose layout is known
+    Known(HyperlaneMessage),
+    /// A message of an unknown version
+    Unknown(UnknownMessage),
+}
+
+impl VersionedMessage {
+    /// The version of the message
+    pub fn version(&self) -> u8 {
+        match self {
+            Self::Known(message) => message.version,
+            Self::Unknown(message) => message.version,
+        }
+    }
+
+    /// The id of the message, which is the hash of its encoding whatever its
+    /// version
+    pub fn id(&self) -> H256 {
+        match self {
+            Self::Known(message) => message.id(),
+            Self::Unknown(message) => {
+                H256::from_slice(Keccak256::new().chain(&message.bytes).finalize().as_slice())
+            }
+        }
+    }
+
+    /// The parsed message, if its version is known
+    pub fn known(&self) -> Option<&HyperlaneMessage> {
+        match self {
+            Self::Known(message) => Some(message),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    /// Parse `bytes` with the layout of their version, or keep them as they
+    /// are if the version is unknown
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, HyperlaneProtocolError> {
+        let Some(&version) = bytes.first() else {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        };
+        match MessageLayout::of_version(version) {
+            Some(MessageLayout::V3) => Ok(Self::Known(HyperlaneMessage::read_from(
+                &mut bytes.as_slice(),
+            )?)),
+            None => Ok(Self::Unknown(UnknownMessage { version, bytes })),
+        }
+    }
+}
+
+impl From<HyperlaneMessage> for VersionedMessage {
+    fn from(message: HyperlaneMessage) -> Self {
+        Self::Known(message)
+    }
+}
+
+impl Encode for VersionedMessage {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        match self {
+            Self::Known(message) => message.write_to(writer),
+            Self::Unknown(message) => {
+                writer.write_all(&message.bytes)?;
+                Ok(message.bytes.len())
+            }
+        }
+    }
+}
+
+impl Decode for VersionedMessage {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+    {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn test_message(version: u8) -> HyperlaneMessage {
+        HyperlaneMessage {
+            version,
+            nonce: 42,
+            origin: 1,
+            sender: H256::repeat_byte(0xaa),
+            destination: 2,
+            recipient: H256::repeat_byte(0xbb),
+            body: vec![1, 2, 3, 4],
+        }
+    }
+
+    #[test]
+    fn known_versions_are_parsed() {
+        for version in [0, LATEST_MESSAGE_VERSION] {
+            let message = test_message(version);
+            let encoded = message.to_vec();
+            let decoded = VersionedMessage::read_from(&mut encoded.as_slice()).unwrap();
+            assert_eq!(decoded.known(), Some(&message));
+            assert_eq!(decoded.id(), message.id());
+            assert_eq!(decoded.to_vec(), encoded);
+        }
+    }
+
+    #[test]
+    fn unknown_versions_are_passed_through() {
+        // A newer layout, which may be shorter than the known one
+        let encoded = vec![LATEST_MESSAGE_VERSION + 1, 0xde, 0xad];
+        let decoded = VersionedMessage::read_from(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded.version(), LATEST_MESSAGE_VERSION + 1);
+        assert_eq!(decoded.known(), None);
+        assert_eq!(decoded.to_vec(), encoded);
+        assert_eq!(
+            decoded.id(),
+            H256::from_slice(Keccak256::new().chain(&encoded).finalize().as_slice())
+        );
+    }
+
+    #[test]
+    fn malformed_messages_error() {
+        assert!(VersionedMessage::from_bytes(vec![]).is_err());
+        let encoded = test_message(LATEST_MESSAGE_VERSION).to_vec();
+        assert!(VersionedMessage::from_bytes(encoded[..10].to_vec()).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use solana_address_lookup_table_program::{
+    instruction::{create_lookup_table, extend_lookup_table},
+    state::AddressLookupTable,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, system_program};
+
+use hyperlane_sealevel_mailbox::{mailbox_inbox_pda_seeds, spl_noop};
+
+use crate::{Context, LookupTableCmd, LookupTableSubCmd};
+
+/// The max number of addresses added per extend transaction, so that the
+/// transaction stays within the size limit.
+const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+pub(crate) fn process_lookup_table_cmd(ctx: Context, cmd: LookupTableCmd) {
+    match cmd.cmd {
+        LookupTableSubCmd::Create(create) => {
+            // The table address is derived from a recent slot, which must
+            // still be in the slot hashes sysvar.
+            let recent_slot = ctx
+                .client
+                .get_slot_with_commitment(CommitmentConfig::finalized())
+                .unwrap();
+            let (create_instruction, lookup_table) =
+                create_lookup_table(ctx.payer_pubkey, ctx.payer_pubkey, recent_slot);
+            ctx.new_txn()
+                .add_with_description(
+                    create_instruction,
+                    format!("Create address lookup table {}", lookup_table),
+                )
+                .send_with_payer();
+
+            let mut addresses = create.addresses;
+            if let Some(mailbox_id) = create.mailbox_id {
+                addresses.extend(mailbox_process_accounts(&mailbox_id));
+            }
+            extend(&ctx, lookup_table, addresses);
+            println!("Address lookup table: {}", lookup_table);
+        }
+        LookupTableSubCmd::Extend(ext) => {
+            extend(&ctx, ext.lookup_table, ext.addresses);
+        }
+        LookupTableSubCmd::Query(query) => {
+            let account = ctx.client.get_account(&query.lookup_table).unwrap();
+            let table = AddressLookupTable::deserialize(&account.data).unwrap();
+            println!("Authority: {:?}", table.meta.authority);
+            println!("Addresses:");
+            for (index, address) in table.addresses.iter().enumerate() {
+                println!("\t{}: {}", index, address);
+            }
+        }
+    }
+}
+
+fn extend(ctx: &Context, lookup_table: Pubkey, addresses: Vec<Pubkey>) {
+    for chunk in addresses.chunks(MAX_ADDRESSES_PER_EXTEND) {
+        let extend_instruction = extend_lookup_table(
+            lookup_table,
+            ctx.payer_pubkey,
+            Some(ctx.payer_pubkey),
+            chunk.to_vec(),
+        );
+        ctx.new_txn()
+            .add_with_description(
+                extend_instruction,
+                format!(
+                    "Add {} addresses to address lookup table {}",
+                    chunk.len(),
+                    lookup_table
+                ),
+            )
+            .send_with_payer();
+    }
+}
+
+/// The accounts of the mailbox that every process transaction includes.
+fn mailbox_process_accounts(mailbox_id: &Pubkey) -> Vec<Pubkey> {
+    let (inbox_account, _inbox_bump) =
+        Pubkey::find_program_address(mailbox_inbox_pda_seeds!(), mailbox_id);
+    vec![
+        *mailbox_id,
+        inbox_account,
+        system_program::id(),
+        spl_noop::id(),
+    ]
+}
@@ -64,6 +64,7 @@ mod context;
 mod r#core;
 mod helloworld;
 mod igp;
+mod lookup_table;
 mod multisig_ism;
 mod router;
 mod serde;
@@ -71,6 +72,7 @@ mod warp_route;
 
 use crate::helloworld::process_helloworld_cmd;
 use crate::igp::process_igp_cmd;
+use crate::lookup_table::process_lookup_table_cmd;
 use crate::multisig_ism::process_multisig_ism_message_id_cmd;
 use crate::warp_route::process_warp_route_cmd;
 pub(crate) use crate::{context::*, core::*};
@@ -111,6 +113,7 @@ enum HyperlaneSealevelCmd {
     MultisigIsmMessageId(MultisigIsmMessageIdCmd),
     WarpRoute(WarpRouteCmd),
     HelloWorld(HelloWorldCmd),
+    LookupTable(LookupTableCmd),
 }
 
 #[derive(Args)]
@@ -677,6 +680,42 @@ pub(crate) struct HelloWorldQuery {
     program_id: Pubkey,
 }
 
+#[derive(Args)]
+pub(crate) struct LookupTableCmd {
+    #[command(subcommand)]
+    cmd: LookupTableSubCmd,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum LookupTableSubCmd {
+    Create(LookupTableCreate),
+    Extend(LookupTableExtend),
+    Query(LookupTableQuery),
+}
+
+#[derive(Args)]
+pub(crate) struct LookupTableCreate {
+    /// Also add the mailbox accounts included in every process transaction.
+    #[arg(long)]
+    mailbox_id: Option<Pubkey>,
+    #[arg(long, value_delimiter = ',')]
+    addresses: Vec<Pubkey>,
+}
+
+#[derive(Args)]
+pub(crate) struct LookupTableExtend {
+    #[arg(long)]
+    lookup_table: Pubkey,
+    #[arg(long, value_delimiter = ',')]
+    addresses: Vec<Pubkey>,
+}
+
+#[derive(Args)]
+pub(crate) struct LookupTableQuery {
+    #[arg(long)]
+    lookup_table: Pubkey,
+}
+
 fn main() {
     pretty_env_logger::init();
 
@@ -752,6 +791,7 @@ fn main() {
         HyperlaneSealevelCmd::WarpRoute(cmd) => process_warp_route_cmd(ctx, cmd),
         HyperlaneSealevelCmd::HelloWorld(cmd) => process_helloworld_cmd(ctx, cmd),
         HyperlaneSealevelCmd::Igp(cmd) => process_igp_cmd(ctx, cmd),
+        HyperlaneSealevelCmd::LookupTable(cmd) => process_lookup_table_cmd(ctx, cmd),
     }
 }
 
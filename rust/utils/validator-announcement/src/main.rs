@@ -0,0 +1,154 @@
+//! Construct and verify validator announcements for any protocol.
+//!
+//! Validators make their checkpoints discoverable by submitting a signed
+//! `Announcement` of their storage location to the validator announce contract
+//! of the origin chain. The signed digest is the same for every protocol, but
+//! the mailbox address has to be converted to its 32 byte representation and
+//! the contract call is encoded differently per protocol. This tool takes care
+//! of both so announcements for non-EVM chains can be produced by hand.
+//!
+//! Usage:
+//! - `digest` prints the digest to sign, for use with external signers
+//! - `sign` signs an announcement with a local key
+//! - `verify` checks the signature of an `announcement.json` written by a
+//!   validator and prints the payload that submits it on chain
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+use ethers::signers::LocalWallet;
+use eyre::{eyre, Result};
+use hyperlane_core::{
+    Announcement, HyperlaneDomainProtocol, HyperlaneSigner, HyperlaneSignerExt, Signable,
+    SignedAnnouncement, H160,
+};
+use hyperlane_ethereum::Signers;
+
+#[derive(Parser)]
+#[command(about = "Construct and verify validator announcements")]
+struct Cli {
+    #[command(subcommand)]
+    cmd: Cmd,
+}
+
+#[derive(Subcommand)]
+enum Cmd {
+    /// Print the digest a validator has to sign
+    Digest(AnnouncementArgs),
+    /// Sign an announcement with a local key
+    Sign(SignArgs),
+    /// Verify a signed announcement and print its on-chain payload
+    Verify(VerifyArgs),
+}
+
+#[derive(Args)]
+struct AnnouncementArgs {
+    /// Protocol of the chain the announcement is made on
+    #[arg(long)]
+    protocol: HyperlaneDomainProtocol,
+    /// Domain of the mailbox the validator signs checkpoints for
+    #[arg(long)]
+    mailbox_domain: u32,
+    /// Mailbox address, in the protocol's native format or as a 0x-prefixed H256
+    #[arg(long)]
+    mailbox: String,
+    /// Location of the validator's signed checkpoints, e.g. `s3://bucket/region`
+    #[arg(long)]
+    storage_location: String,
+}
+
+#[derive(Args)]
+struct SignArgs {
+    #[command(flatten)]
+    announcement: AnnouncementArgs,
+    /// Hex encoded private key of the validator
+    #[arg(long)]
+    key: String,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// Protocol of the chain the announcement is made on
+    #[arg(long)]
+    protocol: HyperlaneDomainProtocol,
+    /// Path to the signed announcement, as written by the validator
+    #[arg(long)]
+    announcement: PathBuf,
+}
+
+impl AnnouncementArgs {
+    fn announcement(&self, validator: H160) -> Result<Announcement> {
+        Ok(Announcement {
+            validator,
+            mailbox_address: self.protocol.parse_address(&self.mailbox)?,
+            mailbox_domain: self.mailbox_domain,
+            storage_location: self.storage_location.clone(),
+        })
+    }
+}
+
+fn fmt_payload(protocol: HyperlaneDomainProtocol, payload: Vec<u8>) -> Result<String> {
+    Ok(match protocol {
+        HyperlaneDomainProtocol::Cosmos => String::from_utf8(payload)?,
+        _ => format!("0x{}", hex::encode(payload)),
+    })
+}
+
+fn digest(args: AnnouncementArgs) -> Result<()> {
+    // The validator address is not part of the signed digest
+    let announcement = args.announcement(Default::default())?;
+    println!("mailbox_address: {:?}", announcement.mailbox_address);
+    println!("signing_hash: {:?}", announcement.signing_hash());
+    println!(
+        "eth_signed_message_hash: {:?}",
+        announcement.eth_signed_message_hash()
+    );
+    Ok(())
+}
+
+async fn sign(args: SignArgs) -> Result<()> {
+    let key = args.key.strip_prefix("0x").unwrap_or(&args.key);
+    let signer: Signers = key.parse::<LocalWallet>()?.into();
+    let announcement = args.announcement.announcement(signer.eth_address())?;
+    let signed = signer.sign(announcement).await?;
+
+    println!("{}", serde_json::to_string_pretty(&signed)?);
+    println!(
+        "payload: {}",
+        fmt_payload(
+            args.announcement.protocol,
+            signed.announce_payload(args.announcement.protocol)?
+        )?
+    );
+    Ok(())
+}
+
+fn verify(args: VerifyArgs) -> Result<()> {
+    let signed: SignedAnnouncement = serde_json::from_slice(&std::fs::read(&args.announcement)?)?;
+    let signer = signed.recover()?;
+    if signer != signed.value.validator {
+        return Err(eyre!(
+            "Announcement is signed by {signer:?}, not by validator {:?}",
+            signed.value.validator
+        ));
+    }
+    println!("{:?}", signed.value);
+    println!("signature valid, signed by {signer:?}");
+    println!(
+        "payload: {}",
+        fmt_payload(args.protocol, signed.announce_payload(args.protocol)?)?
+    );
+    Ok(())
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    match Cli::parse().cmd {
+        Cmd::Digest(args) => digest(args),
+        Cmd::Sign(args) => sign(args).await,
+        Cmd::Verify(args) => verify(args),
+    }
+}
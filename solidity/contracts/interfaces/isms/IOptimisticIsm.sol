@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity >=0.8.0;
+
+import {IInterchainSecurityModule} from "../IInterchainSecurityModule.sol";
+
+interface IOptimisticIsm is IInterchainSecurityModule {
+    /**
+     * @notice Pre-verifies a message with the metadata of its submodule,
+     * starting its fraud window
+     * @param _metadata Metadata verified by the submodule
+     * @param _message Formatted Hyperlane message (see Message.sol).
+     * @return True if the message was pre-verified
+     */
+    function preVerify(
+        bytes calldata _metadata,
+        bytes calldata _message
+    ) external returns (bool);
+
+    /**
+     * @notice Marks a submodule as fraudulent, so that the messages it
+     * pre-verified can't be verified
+     * @param _submodule The submodule to mark as fraudulent
+     */
+    function markFraudulent(address _submodule) external;
+
+    /**
+     * @notice Returns the ISM that pre-verifies a message
+     * @param _message Formatted Hyperlane message (see Message.sol).
+     * @return The submodule pre-verifying the message
+     */
+    function submodule(
+        bytes calldata _message
+    ) external view returns (IInterchainSecurityModule);
+
+    /**
+     * @notice Returns how long pre-verified messages wait before they can
+     * be verified, in seconds
+     */
+    function fraudWindow() external view returns (uint256);
+
+    /**
+     * @notice Returns when a message was pre-verified
+     * @param _id The id of the message
+     * @return The timestamp of the pre-verification, or 0 if the message
+     * wasn't pre-verified
+     */
+    function preVerifiedAt(bytes32 _id) external view returns (uint256);
+}
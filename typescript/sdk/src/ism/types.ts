@@ -23,6 +23,7 @@ export enum ModuleType {
   MESSAGE_ID_MULTISIG,
   NULL,
   CCIP_READ,
+  OPTIMISTIC,
 }
 
 // this enum can be adjusted as per deployments necessary
@@ -34,6 +34,7 @@ export enum AgentLogLevel {
 
 export enum AgentLogFormat {
   Json = 'json',
+  StructuredJson = 'structuredJson',
   Compact = 'compact',
   Full = 'full',
   Pretty = 'pretty',
@@ -48,7 +49,13 @@ export enum AgentSignerKeyType {
   Aws = 'aws',
   Hex = 'hexKey',
   Node = 'node',
+  Remote = 'remote',
   Cosmos = 'cosmosKey',
+  Starknet = 'starknetKey',
+  Sui = 'suiKey',
+  Ton = 'tonKey',
+  Substrate = 'substrateKey',
+  Near = 'nearKey',
 }
 
 const AgentSignerHexKeySchema = z
@@ -66,13 +73,80 @@ const AgentSignerAwsKeySchema = z
   .describe(
     'An AWS signer. Note that AWS credentials must be inserted into the env separately.',
   );
+const AgentSignerRemoteSchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Remote),
+    url: z
+      .string()
+      .url()
+      .describe('The JSON-RPC endpoint of the Web3Signer-compatible service'),
+    address: ZHash.optional().describe(
+      'The address of the key. Required if the service holds several keys.',
+    ),
+  })
+  .describe(
+    'A remote signing service holding the key, which only signs messages such as checkpoints.',
+  );
 const AgentSignerCosmosKeySchema = z
   .object({
     type: z.literal(AgentSignerKeyType.Cosmos),
     prefix: z.string().describe('The bech32 prefix for the cosmos address'),
     key: ZHash,
+    accountAddressType: z
+      .enum(['bitcoin', 'ethereum'])
+      .optional()
+      .describe(
+        'How the account address is derived from the key. Use `ethereum` for Ethermint-based chains like Injective. Defaults to `bitcoin`.',
+      ),
   })
   .describe('Cosmos key');
+const AgentSignerStarknetKeySchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Starknet),
+    key: ZHash,
+    address: z
+      .string()
+      .describe('The address of the account contract the key signs for'),
+  })
+  .describe('Starknet key');
+const AgentSignerSuiKeySchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Sui),
+    key: ZHash,
+    sponsorKey: ZHash.optional().describe(
+      'The key of the account sponsoring the gas of transactions',
+    ),
+  })
+  .describe('Sui key');
+const AgentSignerTonKeySchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Ton),
+    key: ZHash,
+    address: z.string().describe('The address of the wallet the key controls'),
+  })
+  .describe('TON key');
+const AgentSignerSubstrateKeySchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Substrate),
+    key: ZHash,
+    scheme: z
+      .enum(['sr25519', 'ecdsa'])
+      .optional()
+      .describe('The signature scheme of the key. Defaults to `sr25519`.'),
+  })
+  .describe('Substrate key');
+const AgentSignerNearKeySchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Near),
+    key: ZHash,
+    accountId: z
+      .string()
+      .optional()
+      .describe(
+        'The account the key belongs to. Defaults to the implicit account of the key.',
+      ),
+  })
+  .describe('NEAR key');
 const AgentSignerNodeSchema = z
   .object({
     type: z.literal(AgentSignerKeyType.Node),
@@ -82,16 +156,42 @@ const AgentSignerNodeSchema = z
 const AgentSignerSchema = z.union([
   AgentSignerHexKeySchema,
   AgentSignerAwsKeySchema,
+  AgentSignerRemoteSchema,
   AgentSignerCosmosKeySchema,
+  AgentSignerStarknetKeySchema,
+  AgentSignerSuiKeySchema,
+  AgentSignerTonKeySchema,
+  AgentSignerSubstrateKeySchema,
+  AgentSignerNearKeySchema,
   AgentSignerNodeSchema,
 ]);
 
 export type AgentSignerHexKey = z.infer<typeof AgentSignerHexKeySchema>;
 export type AgentSignerAwsKey = z.infer<typeof AgentSignerAwsKeySchema>;
+export type AgentSignerRemote = z.infer<typeof AgentSignerRemoteSchema>;
 export type AgentSignerCosmosKey = z.infer<typeof AgentSignerNodeSchema>;
+export type AgentSignerStarknetKey = z.infer<
+  typeof AgentSignerStarknetKeySchema
+>;
+export type AgentSignerSuiKey = z.infer<typeof AgentSignerSuiKeySchema>;
+export type AgentSignerTonKey = z.infer<typeof AgentSignerTonKeySchema>;
+export type AgentSignerSubstrateKey = z.infer<
+  typeof AgentSignerSubstrateKeySchema
+>;
+export type AgentSignerNearKey = z.infer<typeof AgentSignerNearKeySchema>;
 export type AgentSignerNode = z.infer<typeof AgentSignerNodeSchema>;
 export type AgentSigner = z.infer<typeof AgentSignerSchema>;
 
+const AgentCosmosGasPriceSchema = z.object({
+  denom: z
+    .string()
+    .describe('The coin denom, usually in "micro" form, e.g. untrn'),
+  amount: z
+    .string()
+    .regex(/^(\d*[.])?\d+$/)
+    .describe('The gas price, in denom, to pay for each unit of gas'),
+});
+
 // Additional chain metadata for Cosmos chains required by the agents.
 const AgentCosmosChainMetadataSchema = z.object({
   canonicalAsset: z
@@ -99,27 +199,127 @@ const AgentCosmosChainMetadataSchema = z.object({
     .describe(
       'The name of the canonical asset for this chain, usually in "micro" form, e.g. untrn',
     ),
-  gasPrice: z.object({
-    denom: z
-      .string()
-      .describe('The coin denom, usually in "micro" form, e.g. untrn'),
-    amount: z
-      .string()
-      .regex(/^(\d*[.])?\d+$/)
-      .describe('The gas price, in denom, to pay for each unit of gas'),
-  }),
+  gasPrice: AgentCosmosGasPriceSchema,
+  gasPrices: z
+    .array(AgentCosmosGasPriceSchema)
+    .optional()
+    .describe(
+      'Gas prices for alternative denoms fees can be paid in, e.g. IBC vouchers (ibc/...) on chains with fee abstraction',
+    ),
+  feeDenom: z
+    .string()
+    .optional()
+    .describe(
+      'The denom to pay transaction fees in. Must be the canonical asset or have a gas price in gasPrice or gasPrices. Defaults to the canonical asset. Costs are still estimated at the gas price of the canonical asset, so the gas prices of both denoms should be worth the same.',
+    ),
   contractAddressBytes: z
     .number()
     .int()
     .positive()
     .lte(32)
     .describe('The number of bytes used to represent a contract address.'),
+  moduleType: z
+    .enum(['wasm', 'native'])
+    .optional()
+    .describe(
+      'Whether Hyperlane is deployed as CosmWasm contracts or as a native Cosmos SDK module. Defaults to wasm.',
+    ),
+});
+
+// Additional chain metadata for Sealevel chains used by the agents.
+const AgentSealevelChainMetadataSchema = z.object({
+  priorityFee: z
+    .object({
+      strategy: z
+        .enum(['none', 'constant', 'percentile'])
+        .describe('How the compute unit price of transactions is chosen.'),
+      microLamports: ZUint.optional().describe(
+        'The compute unit price to pay with the constant strategy.',
+      ),
+      percentile: ZUint.lte(100)
+        .optional()
+        .describe(
+          'With the percentile strategy, the percentile of the compute unit prices recently paid to write to the same accounts to pay.',
+        ),
+      min: ZUint.optional().describe(
+        'The minimum compute unit price to pay with the percentile strategy.',
+      ),
+      max: ZUint.optional().describe(
+        'The maximum compute unit price to pay with the percentile strategy.',
+      ),
+    })
+    .refine(
+      (priorityFee) =>
+        priorityFee.min === undefined ||
+        priorityFee.max === undefined ||
+        priorityFee.min <= priorityFee.max,
+      { message: 'min must not be greater than max', path: ['min'] },
+    )
+    .optional()
+    .describe(
+      'The priority fee to pay for transactions, in micro-lamports per compute unit. Defaults to none.',
+    ),
+  jito: z
+    .object({
+      url: z
+        .string()
+        .url()
+        .describe(
+          'The bundles endpoint of the Jito block engine, e.g. https://mainnet.block-engine.jito.wtf/api/v1/bundles',
+        ),
+      tipLamports: ZUint.describe(
+        'The tip to pay the Jito validators for each bundle, in lamports.',
+      ),
+    })
+    .optional()
+    .describe(
+      'If set, messages are processed by submitting bundles to a Jito block engine instead of the RPC, falling back to the RPC if a bundle does not land.',
+    ),
+  wsUrl: z
+    .string()
+    .url()
+    .optional()
+    .describe(
+      'The websocket url of the RPC. If set, dispatched messages and gas payments are streamed from a subscription, falling back to polling for any the subscription missed.',
+    ),
+  lookupTables: z
+    .array(z.string().min(1))
+    .optional()
+    .describe(
+      'Addresses of address lookup tables to compile process transactions against. If set, v0 transactions are sent instead of legacy ones, which fit more accounts.',
+    ),
+  submissionCommitment: z
+    .enum(['processed', 'confirmed', 'finalized'])
+    .optional()
+    .describe(
+      'The commitment process transactions must reach before they count as landed. Defaults to processed.',
+    ),
+  indexingCommitment: z
+    .enum(['processed', 'confirmed', 'finalized'])
+    .optional()
+    .describe(
+      'The commitment dispatched messages and gas payments must reach before they are indexed. Defaults to finalized.',
+    ),
 });
 
 export type AgentCosmosGasPrice = z.infer<
   typeof AgentCosmosChainMetadataSchema
 >['gasPrice'];
 
+const GasLimitPolicySchema = z.object({
+  multiplier: z
+    .number()
+    .positive()
+    .optional()
+    .describe('The factor the estimated gas limit is multiplied by.'),
+  floor: ZUWei.optional().describe(
+    'The lowest gas limit messages are submitted with.',
+  ),
+  cap: ZUWei.optional().describe(
+    'The highest gas limit messages are submitted with.',
+  ),
+});
+
 export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
   HyperlaneDeploymentArtifactsSchema,
 )
@@ -130,13 +330,69 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
       .describe(
         'Specify a comma separated list of custom RPC URLs to use for this chain. If not specified, the default RPC urls will be used.',
       ),
+    archiveRpcUrls: z
+      .string()
+      .optional()
+      .describe(
+        'A comma separated list of archival RPC URLs to backfill skipped index ranges from. Only supported for EVM chains; if not specified, the regular RPC urls are used.',
+      ),
     rpcConsensusType: z
       .nativeEnum(RpcConsensusType)
       .describe('The consensus type to use when multiple RPCs are configured.')
       .optional(),
+    quirks: z
+      .object({
+        supportsEip1559: z
+          .boolean()
+          .optional()
+          .describe('Whether the chain supports EIP-1559 transactions.'),
+        maxLogTopics: ZUint.optional().describe(
+          'The maximum number of topics eth_getLogs filters can have.',
+        ),
+        maxLogBlockRange: ZNzUint.optional().describe(
+          'The maximum number of blocks a single eth_getLogs call can span.',
+        ),
+      })
+      .optional()
+      .describe(
+        'How an EVM-compatible chain deviates from Ethereum; defaults match Ethereum.',
+      ),
+    lightClientUrl: z
+      .string()
+      .url()
+      .optional()
+      .describe(
+        'The URL of a light client RPC, such as Helios, to verify the responses of EVM RPCs against.',
+      ),
     signer: AgentSignerSchema.optional().describe(
       'The signer to use for this chain',
     ),
+    finality: z
+      .discriminatedUnion('type', [
+        z.object({
+          type: z.literal('blocks'),
+          blocks: ZUint.optional().describe(
+            'The number of blocks built on top of final blocks; defaults to the reorg period.',
+          ),
+        }),
+        z.object({
+          type: z.literal('time'),
+          seconds: ZUint.describe('The age of final blocks, in seconds.'),
+        }),
+        z.object({
+          type: z.literal('tag'),
+          tag: z
+            .enum(['safe', 'finalized'])
+            .describe('The tag of the latest final block.'),
+        }),
+        z.object({
+          type: z.literal('l1Settlement'),
+        }),
+      ])
+      .optional()
+      .describe(
+        'How blocks that can no longer be reorged are determined; defaults to the reorg period. Only EVM chains support policies other than block counts.',
+      ),
     index: z
       .object({
         from: ZUint.optional().describe(
@@ -151,10 +407,49 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
           .describe(
             'The indexing method to use for this chain; will attempt to choose a suitable default if not specified.',
           ),
+        skipGapsAfterRewinds: z
+          .number()
+          .int()
+          .min(2)
+          .optional()
+          .describe(
+            'After how many rewinds on the same missing sequence the relayer skips it and backfills it in the background. If not specified, or for other agents, the indexer rewinds until the sequence is found.',
+          ),
+        archiveSwitchoverBlocks: ZUint.optional().describe(
+          'How many blocks behind the tip the indexed ranges have to be to be queried from the archiveRpcUrls, until the indexer catches up. If not specified, the archival RPCs are only used to backfill skipped ranges.',
+        ),
       })
       .optional(),
+    retryPolicy: z
+      .object({
+        baseDelaySecs: ZUint.optional().describe(
+          'The delay after the first failed attempt, which doubles after every further one.',
+        ),
+        maxDelaySecs: ZUint.optional().describe(
+          'The longest delay between attempts.',
+        ),
+        jitter: z
+          .number()
+          .min(0)
+          .max(1)
+          .optional()
+          .describe(
+            'The fraction by which delays are randomly lengthened or shortened.',
+          ),
+        maxAttempts: ZNzUint.optional().describe(
+          'The number of failed attempts after which messages are parked as undeliverable.',
+        ),
+      })
+      .optional()
+      .describe(
+        'How the relayer retries messages to this chain; defaults to a fixed schedule that backs off up to 3 hours.',
+      ),
+    gasLimitPolicy: GasLimitPolicySchema.optional().describe(
+      'How the relayer adjusts the estimated gas limits of messages to this chain. The multiplier is applied first, then the floor and the cap.',
+    ),
   })
   .merge(AgentCosmosChainMetadataSchema.partial())
+  .merge(AgentSealevelChainMetadataSchema)
   .refine((metadata) => {
     // Make sure that the signer is valid for the protocol
 
@@ -212,6 +507,14 @@ export const AgentConfigSchema = z.object({
     .describe(
       'The port to expose prometheus metrics on. Accessible via `GET /metrics`.',
     ),
+  healthPort: ZNzUint.lte(65535)
+    .optional()
+    .describe(
+      'The port to serve liveness and readiness probes on, separate from the metrics. `GET /healthz` fails once a sync loop stalls, `GET /readyz` succeeds once the config loaded, the database opened and the RPC of every chain responded.',
+    ),
+  configWatchIntervalSecs: ZUint.optional().describe(
+    'How often to check the config files for changes, reloading the config when they change. The config is also reloaded on SIGHUP. Reloading applies the log level and RPC URLs of EVM chains, and for relayers the whitelist, blacklist, gas payment enforcement and retry policies. Changes like adding chains are logged and ignored until the agent restarts.',
+  ),
   chains: z
     .record(AgentChainMetadataSchema)
     .describe('Chain metadata for all chains that the agent will index.')
@@ -239,22 +542,86 @@ export const AgentConfigSchema = z.object({
       format: z
         .nativeEnum(AgentLogFormat)
         .optional()
-        .describe('The format to use for tracing logs.'),
+        .describe(
+          'The format to use for tracing logs. `structuredJson` adds consistent `agent`, `origin_domain`, `destination_domain`, `message_id` and `sequence` fields to every line, for log-based alerting and message tracing.',
+        ),
       level: z
         .nativeEnum(AgentLogLevel)
         .optional()
         .describe("The log level to use for the agent's logs."),
+      filterFile: z
+        .string()
+        .optional()
+        .describe(
+          'Path to a file of per-target log directives (e.g. `relayer::msg::op_submitter=debug`) that is re-read when the agent receives SIGUSR2.',
+        ),
+      otlpEndpoint: z
+        .string()
+        .url()
+        .optional()
+        .describe(
+          'An OTLP/HTTP collector to export traces to. The spans of each message share a trace id derived from the message id. Superseded by `otlp`.',
+        ),
     })
     .optional(),
+  otlp: z
+    .object({
+      endpoint: z
+        .string()
+        .url()
+        .describe(
+          'The OTLP/HTTP endpoint of an OpenTelemetry collector, without the `v1/traces` or `v1/metrics` path.',
+        ),
+      headers: z
+        .record(z.string())
+        .optional()
+        .describe('Headers sent with every export request, e.g. an api key.'),
+      traces: z
+        .boolean()
+        .optional()
+        .describe(
+          'Whether spans are exported. The spans of each message share a trace id derived from the message id. Defaults to true.',
+        ),
+      metrics: z
+        .boolean()
+        .optional()
+        .describe('Whether the metrics are exported. Defaults to true.'),
+      metricsIntervalSecs: ZUint.optional().describe(
+        'How often the metrics are exported. Defaults to 30.',
+      ),
+      resourceAttributes: z
+        .record(z.string())
+        .optional()
+        .describe(
+          'Attributes added to everything the agent exports, on top of `service.name`, `hyperlane.agent` and `hyperlane.chains`.',
+        ),
+    })
+    .optional()
+    .describe(
+      'Export of traces and metrics to an OpenTelemetry collector, e.g. one forwarding them to Tempo, Jaeger or Datadog.',
+    ),
 });
 
 const CommaSeperatedChainList = z.string().regex(/^[a-z0-9]+(,[a-z0-9]+)*$/);
 const CommaSeperatedDomainList = z.string().regex(/^\d+(,\d+)*$/);
 
+const DbIntegrityCheckSchema = z.object({
+  depth: ZUint.optional().describe(
+    'How many of the most recent sequences are verified, besides any above the on-chain count. Defaults to 10000.',
+  ),
+  repair: z
+    .boolean()
+    .optional()
+    .describe(
+      'Whether invalid sequences are deleted so that they are indexed again, rather than only logged.',
+    ),
+});
+
 export enum GasPaymentEnforcementPolicyType {
   None = 'none',
   Minimum = 'minimum',
   OnChainFeeQuoting = 'onChainFeeQuoting',
+  IgpQuote = 'igpQuote',
 }
 
 const GasPaymentEnforcementBaseSchema = z.object({
@@ -277,6 +644,12 @@ const GasPaymentEnforcementSchema = z.union([
       .regex(/^\d+ ?\/ ?[1-9]\d*$/)
       .optional(),
   }),
+  GasPaymentEnforcementBaseSchema.extend({
+    type: z.literal(GasPaymentEnforcementPolicyType.IgpQuote),
+    marginBps: ZUint.optional().describe(
+      'Basis points added to the quote of the origin IGP before comparing it with the payment.',
+    ),
+  }),
 ]);
 export type GasPaymentEnforcement = z.infer<typeof GasPaymentEnforcementSchema>;
 
@@ -287,12 +660,55 @@ const MetricAppContextSchema = z.object({
   ),
 });
 
+const AppContextSchema = MetricAppContextSchema.extend({
+  gasPaymentEnforcement: GasPaymentEnforcementSchema.optional().describe(
+    'The gas payment enforcement policy of the app, which takes precedence over `gasPaymentEnforcement`. Its matching list is ignored.',
+  ),
+  priority: ZUint.optional().describe(
+    'The rank of the app under the `appPriority` queue prioritization when `priorityMatchingLists` is not set; lower ranks are submitted first.',
+  ),
+  maxConcurrency: ZNzUint.optional().describe(
+    'The most messages of the app prepared at once for each destination.',
+  ),
+  gasLimitPolicy: GasLimitPolicySchema.optional().describe(
+    'How the estimated gas limits of the messages of the app are adjusted, which takes precedence over the `gasLimitPolicy` of the destination chain.',
+  ),
+});
+
 export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
   db: z
     .string()
     .min(1)
     .optional()
     .describe('The path to the relayer database.'),
+  dbBackend: z
+    .enum(['rocksdb', 'sled', 'memory'])
+    .optional()
+    .describe(
+      'The key-value store the database is kept in. `memory` is lost on restart. Defaults to rocksdb.',
+    ),
+  dbTuning: z
+    .enum(['default', 'throughput', 'lowMemory'])
+    .optional()
+    .describe(
+      'How RocksDB trades memory for throughput. `lowMemory` bounds the memory of large databases, `throughput` uses more of it for relayers of many chains.',
+    ),
+  dbRestoreFrom: z
+    .string()
+    .min(1)
+    .optional()
+    .describe(
+      'The path of a database snapshot to restore into the database if it is new, e.g. to move the state of an agent to another host.',
+    ),
+  dbSnapshotLocation: z
+    .string()
+    .optional()
+    .describe(
+      'Where the database snapshots taken through the admin API are written, e.g. s3://bucket/region/folder.',
+    ),
+  dbIntegrityCheck: DbIntegrityCheckSchema.optional().describe(
+    'If set, the stored sequences are verified against the on-chain counts on startup.',
+  ),
   relayChains: CommaSeperatedChainList.describe(
     'Comma separated list of chains to relay messages between.',
   ),
@@ -332,6 +748,197 @@ export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
     .describe(
       'A list of app contexts and their matching lists to use for metrics. A message will be classified as the first matching app context.',
     ),
+  appContexts: z
+    .union([z.array(AppContextSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Apps with their own gas payment enforcement, priority and concurrency. They also classify messages for metrics, before `metricAppContexts`.',
+    ),
+  receiptSigner: AgentSignerSchema.optional().describe(
+    'If set, the relayer signs a receipt of every message it delivers, which is served by its API.',
+  ),
+  queuePrioritization: z
+    .enum(['nonce', 'gasPaymentRatio', 'age', 'appPriority'])
+    .optional()
+    .describe(
+      'The order in which messages that are due are submitted. Defaults to `nonce`.',
+    ),
+  priorityMatchingLists: z
+    .union([z.array(MatchingListSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'The matching lists used by the `appPriority` queue prioritization, in order of priority. Messages that match none of them come last. Defaults to the app contexts that have a priority.',
+    ),
+  adminApiKey: z
+    .string()
+    .min(1)
+    .optional()
+    .describe(
+      'If set, the relayer serves an API to inspect, retry, re-estimate and drop pending messages, to replace the whitelist and blacklist, and to change the log filter, which requires this key as a bearer token.',
+    ),
+  deadLetterAfterRetries: z
+    .number()
+    .int()
+    .positive()
+    .optional()
+    .describe(
+      'If set, messages that failed this many times are parked as undeliverable until they are retried through the API.',
+    ),
+  deadLetterRevertReasons: z
+    .string()
+    .optional()
+    .describe(
+      'Comma separated list of errors, such as revert reasons, that park a message as undeliverable as soon as an attempt fails with one of them.',
+    ),
+  deadLetterExpiries: z
+    .array(
+      z.object({
+        matchingList: MatchingListSchema.describe(
+          'The messages the TTL applies to.',
+        ),
+        ttlSecs: ZNzUint.describe(
+          'How long after the relayer first saw a message it is parked as undeliverable once an attempt fails.',
+        ),
+      }),
+    )
+    .optional()
+    .describe(
+      'TTLs after which undelivered messages are parked as undeliverable rather than retried, by the first rule whose matching list matches them.',
+    ),
+  ismCacheTtlSecs: z
+    .number()
+    .int()
+    .positive()
+    .optional()
+    .describe(
+      'If set, the configuration of ISMs, such as validator sets, thresholds and routes, is cached for this many seconds instead of being fetched for every message. It is fetched again once verifying a message fails.',
+    ),
+  maxMetadataTasksPerOrigin: z
+    .number()
+    .int()
+    .positive()
+    .optional()
+    .describe(
+      'If set, the most messages from each origin chain whose metadata is built at once, across all destinations.',
+    ),
+  maxInFlightPerDestination: z
+    .number()
+    .int()
+    .positive()
+    .optional()
+    .describe(
+      'If set, the most messages to each destination chain that were submitted and wait to be confirmed.',
+    ),
+  chainRefreshIntervalSecs: z
+    .number()
+    .int()
+    .positive()
+    .optional()
+    .describe(
+      'If set, the config is reloaded this often to start relaying for the chains added to `relayChains` and stop for the ones removed from it, without restarting the relayer. The queues of added chains are not served by the API until the relayer restarts.',
+    ),
+  syncSupervisor: z
+    .object({
+      stallTimeoutSecs: ZNzUint.optional().describe(
+        'How long a contract sync may go without progress before it is restarted. Defaults to 600.',
+      ),
+      minBackoffSecs: ZUint.optional().describe(
+        'How long to wait before restarting a sync that stalled or panicked. Defaults to 5.',
+      ),
+      maxBackoffSecs: ZUint.optional().describe(
+        'The longest wait between restarts, which doubles while a sync keeps failing. Defaults to 300.',
+      ),
+    })
+    .optional()
+    .describe(
+      'Restarts the contract syncs of origin chains that stall, e.g. on an unresponsive RPC, or panic. Restarts are counted by the `task_restarts` metric.',
+    ),
+  allowedValidators: z
+    .string()
+    .optional()
+    .describe(
+      'Comma separated list of validator addresses. If set, multisig metadata is only built from the checkpoints of these validators, and messages whose ISM cannot reach quorum with them are not relayed.',
+    ),
+  circuitBreakerFailureRate: z
+    .number()
+    .positive()
+    .max(1)
+    .optional()
+    .describe(
+      'If set, submissions to a destination chain are paused once this share of the recent ones failed, and resumed once a probe submission succeeds. The state of each breaker is shown by the admin API.',
+    ),
+  circuitBreakerWindow: z
+    .number()
+    .int()
+    .positive()
+    .optional()
+    .describe(
+      'How many of the most recent submissions the circuit breaker failure rate is computed over. Defaults to 20.',
+    ),
+  circuitBreakerCooldownSecs: z
+    .number()
+    .int()
+    .positive()
+    .optional()
+    .describe(
+      'How long submissions are paused by the circuit breaker before a probe is let through. Defaults to 60.',
+    ),
+  coordinationDatabaseUrl: z
+    .string()
+    .optional()
+    .describe(
+      'The URL of a Postgres database shared by relayer instances running active-active. Instances claim messages in it before preparing them, so that each message is only submitted by one of them.',
+    ),
+  coordinationInstanceId: z
+    .string()
+    .optional()
+    .describe(
+      'Identifies this relayer instance among the ones sharing the coordination database. Required if coordinationDatabaseUrl is set.',
+    ),
+  coordinationLeaseSecs: z
+    .number()
+    .int()
+    .positive()
+    .optional()
+    .describe(
+      'How long a message claim lasts unless it is renewed, after which other instances may claim the message. Defaults to 600.',
+    ),
+  messageHooks: z
+    .array(
+      z.discriminatedUnion('type', [
+        z.object({
+          type: z.literal('deny'),
+          matchingList: MatchingListSchema.describe(
+            'The messages that are parked.',
+          ),
+          reason: z
+            .string()
+            .optional()
+            .describe('Why the messages are parked, e.g. a compliance rule.'),
+        }),
+        z.object({
+          type: z.literal('rateLimit'),
+          matchingList: MatchingListSchema.describe(
+            'The messages that share the limit.',
+          ),
+          maxMessages: ZNzUint.describe(
+            'The most messages prepared within the window.',
+          ),
+          windowSecs: ZNzUint.describe('The length of the sliding window.'),
+        }),
+        z.object({
+          type: z.literal('annotate'),
+          matchingList: MatchingListSchema.describe(
+            'The messages that are annotated.',
+          ),
+          note: z.string().describe('The note logged with the messages.'),
+        }),
+      ]),
+    )
+    .optional()
+    .describe(
+      'Hooks that check messages before their metadata is built, in order, and may annotate, delay or park them.',
+    ),
 });
 
 export type RelayerConfig = z.infer<typeof RelayerAgentConfigSchema>;
@@ -341,46 +948,347 @@ export const ScraperAgentConfigSchema = AgentConfigSchema.extend({
   chainsToScrape: CommaSeperatedChainList.describe(
     'Comma separated list of chain names to scrape',
   ),
+  backfill: z
+    .object({
+      workers: ZNzUint.optional().describe(
+        'How many shards of each chain are scraped at once. Defaults to 4.',
+      ),
+      shardSize: ZNzUint.optional().describe(
+        'How many blocks each shard spans. Defaults to 100000.',
+      ),
+    })
+    .optional()
+    .describe(
+      'Backfills the history of the scraped chains with parallel workers. On the first run for a chain, the blocks up to the tip are split into shards that are scraped concurrently, while new blocks are scraped as usual.',
+    ),
+  reorgCheck: z
+    .object({
+      window: ZUint.optional().describe(
+        'How many of the most recently scraped blocks are checked. 0 disables the check. Defaults to 128.',
+      ),
+      intervalSecs: ZUint.optional().describe(
+        'How often the blocks are checked, in seconds. Defaults to 60.',
+      ),
+    })
+    .optional()
+    .describe(
+      'Periodically scrapes the most recent blocks again to detect reorgs. Orphaned blocks, and the messages, deliveries and gas payments in them, are marked as orphaned and replaced by the canonical ones.',
+    ),
+  export: z
+    .object({
+      location: z
+        .string()
+        .min(1)
+        .describe(
+          'Where the Parquet files are written to, as a storage location like s3://bucket/region/folder, gs://bucket/folder, azure://account/container/folder or file:///path.',
+        ),
+      intervalSecs: ZUint.optional().describe(
+        'How often new rows are exported, in seconds. Defaults to 600.',
+      ),
+      delaySecs: ZUint.optional().describe(
+        'How long after being scraped rows are exported, in seconds, so that reorged rows are left out. Defaults to 3600.',
+      ),
+      batchSize: ZNzUint.optional().describe(
+        'The maximum number of rows exported at once. Defaults to 100000.',
+      ),
+    })
+    .optional()
+    .describe(
+      'Periodically exports the scraped messages, deliveries and gas payments as Parquet files, partitioned by domain and date.',
+    ),
+  notifications: z
+    .object({
+      pollIntervalSecs: ZNzUint.optional().describe(
+        'How often new events are looked for, in seconds. Defaults to 5.',
+      ),
+      sinks: z.array(
+        z.object({
+          name: z
+            .string()
+            .min(1)
+            .describe('Unique name of the sink, which its progress is stored under.'),
+          type: z
+            .enum(['webhook', 'kafka', 'nats'])
+            .describe(
+              'Webhooks receive each event as a JSON POST. Kafka events are keyed by message ID, and NATS events are published to `<subject>.<event>`.',
+            ),
+          url: z
+            .string()
+            .url()
+            .optional()
+            .describe('The URL of the webhook or NATS server.'),
+          brokers: z
+            .string()
+            .optional()
+            .describe('Comma separated list of Kafka brokers.'),
+          topic: z.string().optional().describe('The Kafka topic.'),
+          subject: z.string().optional().describe('The NATS subject prefix.'),
+          events: z
+            .array(z.enum(['dispatched', 'gas_paid', 'delivered']))
+            .optional()
+            .describe('The events that are published. Defaults to all.'),
+          matchingList: MatchingListSchema.optional().describe(
+            'Only events of matching messages are published. Events of messages from chains that are not scraped are only published without a matching list.',
+          ),
+        }),
+      ),
+    })
+    .optional()
+    .describe(
+      'Publishes the dispatch, gas payments and delivery of messages to webhooks, Kafka or NATS.',
+    ),
 });
 
 export type ScraperConfig = z.infer<typeof ScraperAgentConfigSchema>;
 
+const CheckpointSyncerSchema = z.discriminatedUnion('type', [
+  z
+    .object({
+      type: z.literal('localStorage'),
+      path: z.string().min(1).describe('Path to the local storage location'),
+    })
+    .describe('A local checkpoint syncer'),
+  z
+    .object({
+      type: z.literal('s3'),
+      bucket: z.string().min(1),
+      region: z
+        .string()
+        .min(1)
+        .describe(
+          'The AWS region, or any region name accepted by the custom endpoint',
+        ),
+      endpoint: z
+        .string()
+        .url()
+        .optional()
+        .describe(
+          'The endpoint of an S3-compatible store like Cloudflare R2 or MinIO, which is addressed path-style',
+        ),
+      folder: z
+        .string()
+        .min(1)
+        .optional()
+        .describe(
+          'The folder/key-prefix to use, defaults to the root of the bucket',
+        ),
+    })
+    .describe('A checkpoint syncer that uses S3'),
+  z
+    .object({
+      type: z.literal('gcs'),
+      bucket: z.string().min(1),
+      folder: z
+        .string()
+        .min(1)
+        .optional()
+        .describe(
+          'The folder/key-prefix to use, defaults to the root of the bucket',
+        ),
+      serviceAccountKey: z
+        .string()
+        .min(1)
+        .optional()
+        .describe(
+          'Path to the service account key to write with, defaults to the GCS_SERVICE_ACCOUNT_KEY env var',
+        ),
+      userSecrets: z
+        .string()
+        .min(1)
+        .optional()
+        .describe(
+          'Path to user secrets to write with, defaults to the GCS_USER_SECRET env var',
+        ),
+    })
+    .describe('A checkpoint syncer that uses Google Cloud Storage'),
+  z
+    .object({
+      type: z.literal('azure'),
+      account: z.string().min(1).describe('The storage account'),
+      container: z.string().min(1),
+      folder: z
+        .string()
+        .min(1)
+        .optional()
+        .describe(
+          'The folder/key-prefix to use, defaults to the root of the container',
+        ),
+      sasToken: z
+        .string()
+        .min(1)
+        .optional()
+        .describe(
+          'A SAS token to write with, defaults to the AZURE_STORAGE_SAS_TOKEN env var',
+        ),
+      managedIdentity: z
+        .boolean()
+        .optional()
+        .describe(
+          'Whether to write with the managed identity of the VM or pod if no SAS token is set',
+        ),
+      managedIdentityClientId: z
+        .string()
+        .min(1)
+        .optional()
+        .describe('The client id of a user-assigned managed identity'),
+    })
+    .describe('A checkpoint syncer that uses Azure Blob Storage'),
+  z
+    .object({
+      type: z.literal('ipfs'),
+      apiUrl: z
+        .string()
+        .url()
+        .describe('The RPC API of the IPFS node to write checkpoints through'),
+      key: z
+        .string()
+        .min(1)
+        .optional()
+        .describe(
+          'The key of the node to publish checkpoints under with IPNS, defaults to `self`',
+        ),
+      folder: z
+        .string()
+        .min(1)
+        .optional()
+        .describe(
+          "The directory of the node's mutable file system to write checkpoints to",
+        ),
+      gateway: z
+        .string()
+        .url()
+        .optional()
+        .describe(
+          'The gateway to read checkpoints through, defaults to the IPFS_GATEWAY_URL env var or https://ipfs.io',
+        ),
+    })
+    .describe(
+      'An experimental checkpoint syncer that publishes checkpoints to IPFS',
+    ),
+]);
+
 export const ValidatorAgentConfigSchema = AgentConfigSchema.extend({
   db: z
     .string()
     .min(1)
     .optional()
     .describe('The path to the validator database.'),
+  dbBackend: z
+    .enum(['rocksdb', 'sled', 'memory'])
+    .optional()
+    .describe(
+      'The key-value store the database is kept in. `memory` is lost on restart. Defaults to rocksdb.',
+    ),
+  dbTuning: z
+    .enum(['default', 'throughput', 'lowMemory'])
+    .optional()
+    .describe(
+      'How RocksDB trades memory for throughput. `lowMemory` bounds the memory of large databases, `throughput` uses more of it for relayers of many chains.',
+    ),
+  dbRestoreFrom: z
+    .string()
+    .min(1)
+    .optional()
+    .describe(
+      'The path of a database snapshot to restore into the database if it is new, e.g. to move the state of an agent to another host.',
+    ),
+  dbSnapshotLocation: z
+    .string()
+    .optional()
+    .describe(
+      'Where the database snapshots taken through the admin API are written, e.g. s3://bucket/region/folder.',
+    ),
+  dbIntegrityCheck: DbIntegrityCheckSchema.optional().describe(
+    'If set, the stored sequences are verified against the on-chain counts on startup.',
+  ),
+  adminApiKey: z
+    .string()
+    .min(1)
+    .optional()
+    .describe(
+      'If set, the validator serves an API to take snapshots of its database and to change the log filter, which requires this key as a bearer token.',
+    ),
   originChainName: z
     .string()
     .min(1)
     .describe('Name of the chain to validate messages on'),
   validator: AgentSignerSchema.describe('The validator attestation signer'),
-  checkpointSyncer: z.discriminatedUnion('type', [
-    z
-      .object({
-        type: z.literal('localStorage'),
-        path: z.string().min(1).describe('Path to the local storage location'),
-      })
-      .describe('A local checkpoint syncer'),
-    z
-      .object({
-        type: z.literal('s3'),
-        bucket: z.string().min(1),
-        region: z.string().min(1),
-        folder: z
-          .string()
-          .min(1)
-          .optional()
-          .describe(
-            'The folder/key-prefix to use, defaults to the root of the bucket',
-          ),
-      })
-      .describe('A checkpoint syncer that uses S3'),
-  ]),
+  checkpointSyncer: CheckpointSyncerSchema,
+  checkpointSyncers: z
+    .array(CheckpointSyncerSchema)
+    .optional()
+    .describe(
+      'Additional checkpoint syncers that each signed checkpoint is also written to, so that the validator stays visible to relayers while one of them is down.',
+    ),
   interval: ZUint.optional().describe(
     'How long to wait between checking for new checkpoints in seconds.',
   ),
+  signEveryNthIndex: ZNzUint.optional().describe(
+    'Only sign checkpoints at multiples of this index, along with the latest one, for high-traffic origins. Message id multisig ISMs need a checkpoint at the index of each message, so this requires `merkleRootMultisigOnly`. Defaults to 1.',
+  ),
+  latestSigningInterval: ZUint.optional().describe(
+    'Sign the latest checkpoint at most this often in seconds, rather than every interval, leaving it unsigned until then. Requires `merkleRootMultisigOnly`.',
+  ),
+  merkleRootMultisigOnly: z
+    .boolean()
+    .optional()
+    .describe(
+      'Whether the signatures of this validator are only used by merkle root multisig ISMs, which can prove a message with any later checkpoint. Required to leave checkpoints unsigned with `signEveryNthIndex` or `latestSigningInterval`.',
+    ),
+  reorgSafety: z
+    .boolean()
+    .optional()
+    .describe(
+      "Whether to only sign checkpoints behind the origin chain's finalized block tag, on top of its finality policy.",
+    ),
+  autoAnnounce: z
+    .boolean()
+    .optional()
+    .describe(
+      'Whether the validator submits the announcement of its storage locations itself when they are not announced yet. Defaults to true.',
+    ),
+  leaderElection: z
+    .object({
+      leaseName: z
+        .string()
+        .min(1)
+        .describe('The name of the Kubernetes Lease the replicas compete for.'),
+      namespace: z
+        .string()
+        .optional()
+        .describe('The namespace of the lease. Defaults to the pod namespace.'),
+      identity: z
+        .string()
+        .optional()
+        .describe('The identity of this replica. Defaults to the pod name.'),
+      leaseDuration: ZUint.optional().describe(
+        'How long the lease is held without being renewed in seconds.',
+      ),
+    })
+    .optional()
+    .describe(
+      'Elects one of several replicas of the validator to sign checkpoints, for high availability.',
+    ),
+  checkpointRetention: z
+    .object({
+      keepLatest: ZUint.describe(
+        'How many checkpoints behind the latest one are retained.',
+      ),
+      archive: CheckpointSyncerSchema.optional().describe(
+        'Where pruned checkpoints are archived before they are deleted, e.g. a cold folder of the bucket. Without it, they are only deleted.',
+      ),
+      deliveryHintUrl: z
+        .string()
+        .url()
+        .optional()
+        .describe(
+          'An endpoint returning the lowest merkle tree index whose message was not delivered yet as a JSON number. Checkpoints from that index on are always retained.',
+        ),
+    })
+    .optional()
+    .describe(
+      'Prunes old checkpoints from the checkpoint syncers, so that their storage does not grow unboundedly.',
+    ),
 });
 
 export type ValidatorConfig = z.infer<typeof ValidatorAgentConfigSchema>;